@@ -12,8 +12,40 @@ layout(push_constant) uniform PushConstants {
     mat4 projection;
     vec4 sun_direction;   // xyz = direction, w = intensity
     vec4 sun_color;       // xyz = color, w = ambient intensity
+    vec4 wind;            // xy = wind vector (x, z), z = time, w = sway amount
+    vec4 highlight;       // rgb = rim color, a = strength (0 = off)
 } pc;
 
+// Must match MAX_POINT_LIGHTS in infinite-render
+#define MAX_POINT_LIGHTS 8
+
+layout(set = 0, binding = 0) uniform PointLights {
+    uvec4 count;                               // x = active light count
+    vec4 position_radius[MAX_POINT_LIGHTS];    // xyz = position, w = radius
+    vec4 color_intensity[MAX_POINT_LIGHTS];    // rgb = color, a = intensity
+} lights;
+
+layout(set = 0, binding = 1) uniform Fog {
+    vec4 color;    // rgb = color, a = density at the base height
+    vec4 height;   // x = height falloff, y = base height
+    vec4 haze;     // x = haze start, y = haze end distance
+    vec4 camera;   // xyz = camera position
+} fog;
+
+// Share of the surface hidden by exponential height fog, integrated along
+// the view ray, and by the haze closing in towards the view distance
+float fog_amount(vec3 camera_pos, vec3 world_pos) {
+    vec3 ray = world_pos - camera_pos;
+    float dist = length(ray);
+    float falloff = fog.height.x;
+    float climb = falloff * ray.y;
+    float along = abs(climb) > 1e-4 ? (1.0 - exp(-climb)) / climb : 1.0;
+    float at_camera = fog.color.a * exp(-falloff * (camera_pos.y - fog.height.y));
+    float height_fog = 1.0 - exp(-at_camera * along * dist);
+    float haze = smoothstep(fog.haze.x, fog.haze.y, dist);
+    return max(height_fog, haze);
+}
+
 void main() {
     vec3 N = normalize(v_normal);
     vec3 L = normalize(pc.sun_direction.xyz);
@@ -26,13 +58,38 @@ void main() {
     vec3 diffuse = v_color.rgb * NdotL * pc.sun_color.rgb * sun_intensity;
     vec3 ambient = v_color.rgb * ambient_intensity;
 
-    vec3 final_color = ambient + diffuse;
+    // Point lights (smooth quadratic falloff to zero at the radius)
+    vec3 point = vec3(0.0);
+    for (uint i = 0u; i < min(lights.count.x, uint(MAX_POINT_LIGHTS)); i++) {
+        vec3 to_light = lights.position_radius[i].xyz - v_world_pos;
+        float dist = length(to_light);
+        float radius = lights.position_radius[i].w;
+        if (dist >= radius) {
+            continue;
+        }
+        float falloff = 1.0 - dist / radius;
+        float atten = falloff * falloff;
+        float PdotL = max(dot(N, to_light / max(dist, 0.001)), 0.0);
+        point += v_color.rgb * lights.color_intensity[i].rgb
+               * lights.color_intensity[i].a * atten * PdotL;
+    }
+
+    vec3 final_color = ambient + diffuse + point;
+
+    vec3 camera_pos = fog.camera.xyz;
+
+    // Rim light around the edges facing away from the camera (focus outline)
+    if (pc.highlight.a > 0.0) {
+        vec3 V = normalize(camera_pos - v_world_pos);
+        float rim = pow(1.0 - abs(dot(N, V)), 2.0);
+        final_color += pc.highlight.rgb * rim * pc.highlight.a;
+    }
 
-    // Simple fog for distance
-    float dist = length(v_world_pos);
-    float fog = exp(-dist * 0.01);
-    vec3 fog_color = vec3(0.02, 0.02, 0.05);
-    final_color = mix(fog_color, final_color, fog);
+    // Height fog and distance haze, brighter looking towards the sun
+    vec3 view_dir = normalize(v_world_pos - camera_pos);
+    float scatter = pow(max(dot(view_dir, L), 0.0), 8.0) * sun_intensity;
+    vec3 fog_color = fog.color.rgb + pc.sun_color.rgb * scatter * 0.3;
+    final_color = mix(final_color, fog_color, fog_amount(camera_pos, v_world_pos));
 
     f_color = vec4(final_color, v_color.a);
 }
@@ -14,10 +14,29 @@ layout(push_constant) uniform PushConstants {
     mat4 projection;
     vec4 sun_direction;   // xyz = direction, w = intensity
     vec4 sun_color;       // xyz = color, w = ambient intensity
+    vec4 wind;            // xy = wind vector (x, z), z = time, w = sway amount
+    vec4 highlight;       // rgb = rim color, a = strength (0 = off)
 } pc;
 
+// Bend vegetation with the wind. Displacement grows with height above the
+// model origin so roots stay planted; a per-position phase keeps neighbouring
+// plants out of step.
+vec3 wind_sway(vec3 world_pos, float height) {
+    float sway = pc.wind.w;
+    if (sway <= 0.0 || height <= 0.0) {
+        return vec3(0.0);
+    }
+    float time = pc.wind.z;
+    float phase = world_pos.x * 0.35 + world_pos.z * 0.27;
+    float flutter = 0.65 + 0.35 * sin(time * 2.1 + phase)
+                  + 0.1 * sin(time * 5.3 + phase * 2.0);
+    float bend = sway * height * height * flutter;
+    return vec3(pc.wind.x * bend, -0.25 * bend * length(pc.wind.xy), pc.wind.y * bend);
+}
+
 void main() {
     vec4 world_pos = pc.model * vec4(position, 1.0);
+    world_pos.xyz += wind_sway(world_pos.xyz, position.y);
     v_world_pos = world_pos.xyz;
     v_normal = mat3(pc.model) * normal;
     v_color = color;
@@ -0,0 +1,55 @@
+#version 450
+
+layout(location = 0) in vec2 v_local;
+
+layout(location = 0) out vec4 f_color;
+
+layout(push_constant) uniform PushConstants {
+    mat4 model;
+    mat4 view;
+    mat4 projection;
+    vec4 rim_color;        // rgb = swirl/rim color, a = time
+    vec4 preview_zenith;   // rgb = destination sky zenith
+    vec4 preview_horizon;  // rgb = destination sky horizon
+    vec4 preview_ground;   // rgb = destination ground, a = preview strength
+} pc;
+
+// Glimpse of the destination: sky gradient over a gently rolling ground line
+vec3 destination_view(vec2 uv) {
+    float ground_line = -0.15 + 0.06 * sin(uv.x * 4.0 + 1.3) + 0.03 * sin(uv.x * 9.0);
+    if (uv.y < ground_line) {
+        float depth = clamp((ground_line - uv.y) * 1.5, 0.0, 1.0);
+        return mix(pc.preview_ground.rgb * 1.1, pc.preview_ground.rgb * 0.6, depth);
+    }
+    float h = clamp((uv.y - ground_line) / (1.0 - ground_line), 0.0, 1.0);
+    return mix(pc.preview_horizon.rgb, pc.preview_zenith.rgb, h);
+}
+
+void main() {
+    float r = length(v_local);
+    if (r > 1.0) {
+        discard;
+    }
+
+    float time = pc.rim_color.a;
+    float angle = atan(v_local.y, v_local.x);
+
+    // Spiral arms rotating inward
+    float swirl = sin(angle * 5.0 + r * 14.0 - time * 3.0) * 0.5 + 0.5;
+    swirl *= smoothstep(0.0, 0.6, r);
+
+    // Bright rim fading towards the centre
+    float rim = smoothstep(0.75, 0.97, r) * (1.0 - smoothstep(0.97, 1.0, r));
+
+    // Ripple distortion applied to the destination view
+    vec2 ripple = v_local + 0.03 * vec2(sin(r * 20.0 - time * 4.0), cos(r * 18.0 - time * 3.5));
+    vec3 preview = destination_view(ripple);
+
+    float strength = pc.preview_ground.a;
+    vec3 swirl_color = pc.rim_color.rgb * (0.4 + 0.8 * swirl);
+    vec3 inner = mix(swirl_color, preview + swirl_color * 0.25, strength * (1.0 - smoothstep(0.55, 0.9, r)));
+
+    vec3 color = inner + pc.rim_color.rgb * rim * 2.0;
+    float alpha = mix(0.85, 1.0, rim) * (1.0 - smoothstep(0.98, 1.0, r));
+    f_color = vec4(color, alpha);
+}
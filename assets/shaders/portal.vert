@@ -0,0 +1,23 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec4 color;
+
+layout(location = 0) out vec2 v_local;
+
+layout(push_constant) uniform PushConstants {
+    mat4 model;
+    mat4 view;
+    mat4 projection;
+    vec4 rim_color;        // rgb = swirl/rim color, a = time
+    vec4 preview_zenith;   // rgb = destination sky zenith
+    vec4 preview_horizon;  // rgb = destination sky horizon
+    vec4 preview_ground;   // rgb = destination ground, a = preview strength
+} pc;
+
+void main() {
+    // Unit disc: local xy is the radial coordinate (-1..1)
+    v_local = position.xy;
+    gl_Position = pc.projection * pc.view * pc.model * vec4(position, 1.0);
+}
@@ -13,6 +13,68 @@ layout(push_constant) uniform PushConstants {
     vec4 sun_params;      // x = sun size, y = sun glow, z = time_of_day
 } pc;
 
+layout(set = 0, binding = 0) uniform SkyUniforms {
+    vec4 moon_direction;  // xyz = direction, w = phase (0 = new, 0.5 = full)
+    vec4 moon_color;      // rgb = tint, a = disc size
+    vec4 sun_color;       // rgb = tint, a = disc size scale
+    vec4 stars;           // rgb = tint, a = density
+    vec4 clouds;          // x = coverage, y = density, z = darkness, w = time
+    vec4 cloud_color;     // rgb = sunlit tint, a = horizon haze
+    vec4 wind;            // xy = cloud drift per second
+    vec4 alien;           // x = second moon size, y = ring brightness
+} sky;
+
+const float TAU = 6.28318530718;
+
+float hash(vec3 p) {
+    return fract(sin(dot(p, vec3(12.9898, 78.233, 45.164))) * 43758.5453);
+}
+
+float hash2(vec2 p) {
+    return fract(sin(dot(p, vec2(127.1, 311.7))) * 43758.5453);
+}
+
+float value_noise(vec2 p) {
+    vec2 i = floor(p);
+    vec2 f = fract(p);
+    vec2 u = f * f * (3.0 - 2.0 * f);
+    float a = hash2(i);
+    float b = hash2(i + vec2(1.0, 0.0));
+    float c = hash2(i + vec2(0.0, 1.0));
+    float d = hash2(i + vec2(1.0, 1.0));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+float fbm(vec2 p) {
+    float sum = 0.0;
+    float amplitude = 0.5;
+    for (int i = 0; i < 5; i++) {
+        sum += value_noise(p) * amplitude;
+        p = p * 2.03 + vec2(17.1, 9.4);
+        amplitude *= 0.5;
+    }
+    return sum;
+}
+
+// Brightness of a moon disc of `size` at `moon_dir`, lit for `phase`
+// (0 = new, 0.5 = full); with a faint earthshine on the dark side
+float moon_disc(vec3 dir, vec3 moon_dir, float size, float phase) {
+    float radius = sqrt(2.0 * size);
+    vec3 right = normalize(cross(moon_dir, abs(moon_dir.y) < 0.99 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0)));
+    vec3 up = cross(right, moon_dir);
+    vec2 p = vec2(dot(dir, right), dot(dir, up)) / radius;
+    float r2 = dot(p, p);
+    if (dot(dir, moon_dir) < 0.0 || r2 > 1.0) {
+        return 0.0;
+    }
+    vec3 normal = vec3(p, sqrt(1.0 - r2));
+    float angle = phase * TAU;
+    vec3 light = vec3(sin(angle), 0.0, -cos(angle));
+    float lit = smoothstep(-0.05, 0.05, dot(normal, light));
+    float edge = 1.0 - smoothstep(0.9, 1.0, r2);
+    return (0.03 + 0.97 * lit) * edge;
+}
+
 void main() {
     vec3 dir = normalize(v_direction);
 
@@ -22,35 +84,94 @@ void main() {
 
     vec3 sky_color = mix(pc.sky_zenith.rgb, pc.sky_horizon.rgb, horizon_factor);
 
+    float sun_intensity = pc.sun_direction.w;
+    float night = 1.0 - clamp(sun_intensity * 10.0, 0.0, 1.0);
+    float time = sky.clouds.w;
+
     // Sun glow
     vec3 sun_dir = normalize(pc.sun_direction.xyz);
     float sun_dot = dot(dir, sun_dir);
 
-    // Sun disk
-    float sun_size = pc.sun_params.x;
+    // Sun disk, darker towards its rim
+    float sun_size = pc.sun_params.x * sky.sun_color.a;
     float sun_disk = smoothstep(1.0 - sun_size, 1.0 - sun_size * 0.5, sun_dot);
+    float limb = mix(0.75, 1.0, smoothstep(1.0 - sun_size * 0.5, 1.0 - sun_size * 0.1, sun_dot));
 
     // Sun glow halo
     float sun_glow = pc.sun_params.y;
     float glow = pow(max(sun_dot, 0.0), 4.0) * sun_glow;
 
     // Sun color (warmer near horizon)
-    vec3 sun_color = vec3(1.0, 0.95, 0.85);
+    vec3 sun_color = sky.sun_color.rgb;
     if (sun_dir.y < 0.2) {
         float sunset_factor = 1.0 - sun_dir.y / 0.2;
         sun_color = mix(sun_color, vec3(1.0, 0.5, 0.2), sunset_factor * 0.7);
     }
 
     vec3 final_color = sky_color + glow * sun_color * 0.3;
-    final_color = mix(final_color, sun_color, sun_disk);
-
-    // Stars at night (when sun is below horizon)
-    if (pc.sun_direction.w < 0.1) {
-        // Simple pseudo-random stars based on direction
-        float star_seed = fract(sin(dot(floor(dir * 500.0), vec3(12.9898, 78.233, 45.164))) * 43758.5453);
-        float star = step(0.998, star_seed) * (1.0 - pc.sun_direction.w * 10.0);
-        final_color += vec3(star);
+    final_color = mix(final_color, sun_color * limb, sun_disk);
+
+    // Stars at night, twinkling and thinning towards the horizon
+    if (night > 0.0) {
+        vec3 cell = floor(dir * 400.0);
+        float star_seed = hash(cell);
+        float star = step(1.0 - sky.stars.a, star_seed);
+        float twinkle = 0.7 + 0.3 * sin(time * 3.0 + hash(cell + 7.0) * TAU);
+        float tint = hash(cell + 13.0);
+        vec3 star_color = sky.stars.rgb * mix(vec3(1.0, 0.85, 0.7), vec3(0.75, 0.85, 1.0), tint);
+        final_color += star_color * star * twinkle * night * smoothstep(-0.05, 0.2, dir.y);
+    }
+
+    // Planetary ring: a thin band tilted across the sky
+    if (sky.alien.y > 0.0) {
+        vec3 ring_normal = normalize(vec3(0.25, 0.4, 1.0));
+        float band = abs(dot(dir, ring_normal));
+        float bands = 0.6 + 0.4 * sin(dot(dir, cross(ring_normal, vec3(0.0, 1.0, 0.0))) * 120.0);
+        float ring = (1.0 - smoothstep(0.0, 0.03, band)) * bands * sky.alien.y;
+        final_color += sky.stars.rgb * ring * mix(0.15, 0.6, night) * smoothstep(-0.02, 0.05, dir.y);
+    }
+
+    // Moon (and in some eras a second one), blocking the stars behind it
+    vec3 moon_dir = normalize(sky.moon_direction.xyz);
+    float phase = sky.moon_direction.w;
+    if (moon_dir.y > -0.1) {
+        float moon = moon_disc(dir, moon_dir, sky.moon_color.a, phase);
+        float moon_mask = smoothstep(1.0 - sky.moon_color.a, 1.0 - sky.moon_color.a * 0.9, dot(dir, moon_dir));
+        final_color = mix(final_color, sky_color + sky.moon_color.rgb * moon, moon_mask);
+        final_color += sky.moon_color.rgb * pow(max(dot(dir, moon_dir), 0.0), 64.0) * 0.05 * night;
+
+        if (sky.alien.x > 0.0) {
+            vec3 second_dir = normalize(moon_dir + vec3(0.35, 0.18, 0.25));
+            float second = moon_disc(dir, second_dir, sky.alien.x, fract(phase + 0.3));
+            float second_mask = smoothstep(1.0 - sky.alien.x, 1.0 - sky.alien.x * 0.9, dot(dir, second_dir));
+            vec3 second_color = sky.moon_color.rgb * vec3(1.0, 0.8, 0.7);
+            final_color = mix(final_color, sky_color + second_color * second, second_mask);
+        }
+    }
+
+    // Clouds: two layers on a plane above the camera, drifting with the wind
+    if (dir.y > 0.0 && sky.clouds.x > 0.0) {
+        vec2 uv = dir.xz / (dir.y + 0.1);
+        float low = fbm(uv * 1.5 + sky.wind.xy * time);
+        float high = fbm(uv * 0.6 + sky.wind.xy * time * 0.4 + vec2(31.0, 7.0));
+        float threshold = 1.0 - sky.clouds.x;
+        float low_cover = smoothstep(threshold * 0.8, threshold * 0.8 + 0.35, low);
+        float high_cover = smoothstep(threshold * 0.9 + 0.1, threshold * 0.9 + 0.4, high) * 0.5;
+        float cover = clamp(max(low_cover, high_cover) * sky.clouds.y, 0.0, 1.0);
+        cover *= smoothstep(0.0, 0.15, dir.y);
+
+        float moon_light = 0.02 + (1.0 - cos(phase * TAU)) * 0.04;
+        float light = max(sun_intensity, moon_light * night);
+        vec3 lit = sky.cloud_color.rgb * mix(sky_color * 0.5 + 0.5, sun_color, 0.3);
+        vec3 shadowed = mix(lit * 0.8, vec3(0.3, 0.32, 0.36), sky.clouds.z);
+        vec3 cloud = mix(lit, shadowed, low_cover) * light;
+        cloud += sun_color * pow(max(sun_dot, 0.0), 8.0) * 0.3 * sun_intensity * (1.0 - low_cover);
+        final_color = mix(final_color, cloud, cover);
     }
 
+    // Fog on the ground fades the sky into the horizon color
+    float haze = sky.cloud_color.a * (1.0 - smoothstep(0.0, 0.3, dir.y));
+    final_color = mix(final_color, pc.sky_horizon.rgb, haze);
+
     f_color = vec4(final_color, 1.0);
 }
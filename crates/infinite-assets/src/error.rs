@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain};
+
 /// Errors that can occur during asset loading.
 #[derive(Debug, thiserror::Error)]
 pub enum AssetError {
@@ -18,3 +20,25 @@ pub enum AssetError {
     #[error("unsupported image format in '{0}'")]
     UnsupportedFormat(PathBuf),
 }
+
+impl Diagnostic for AssetError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::NotFound(_) => 1,
+            Self::GltfLoadFailed(..) => 2,
+            Self::ImageLoadFailed(..) => 3,
+            Self::Io(..) => 4,
+            Self::UnsupportedFormat(_) => 5,
+        };
+        ErrorCode::new(ErrorDomain::Assets, number)
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::NotFound(_) | Self::Io(..) => Some("Check that the assets folder sits next to the game"),
+            Self::GltfLoadFailed(..) | Self::ImageLoadFailed(..) | Self::UnsupportedFormat(_) => {
+                Some("The file may be damaged; reinstalling restores it")
+            }
+        }
+    }
+}
@@ -34,10 +34,7 @@ impl<T> AssetHandle<T> {
 
 impl<T> Clone for AssetHandle<T> {
     fn clone(&self) -> Self {
-        Self {
-            id: self.id,
-            _marker: PhantomData,
-        }
+        *self
     }
 }
 
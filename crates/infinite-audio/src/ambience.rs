@@ -0,0 +1,421 @@
+//! Layered ambient soundscape.
+//!
+//! Looping beds (wind, birds, insects, rain, hum) are mixed by gains derived
+//! from the current biome, time of day, weather, and how much wildlife and
+//! machinery the era has. Gains move toward their targets over a crossfade
+//! time so changes in conditions blend smoothly. Short spot sounds are
+//! scheduled at random around the listener.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use glam::Vec3;
+use infinite_core::DetRng;
+use rand::RngCore;
+
+/// Broad terrain category around the listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Biome {
+    #[default]
+    Grassland,
+    Forest,
+    Highland,
+}
+
+impl Biome {
+    /// Classify from normalized terrain elevation (0.0 = lowest, 1.0 = highest).
+    pub fn from_elevation(normalized: f32) -> Self {
+        if normalized < 0.35 {
+            Self::Grassland
+        } else if normalized < 0.7 {
+            Self::Forest
+        } else {
+            Self::Highland
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Grassland => "Grassland",
+            Self::Forest => "Forest",
+            Self::Highland => "Highland",
+        }
+    }
+}
+
+/// Part of the day, as far as wildlife is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DayPeriod {
+    Dawn,
+    #[default]
+    Day,
+    Dusk,
+    Night,
+}
+
+impl DayPeriod {
+    /// Classify from the hour of day (0.0–24.0).
+    pub fn from_hours(hours: f32) -> Self {
+        if (5.0..7.0).contains(&hours) {
+            Self::Dawn
+        } else if (7.0..18.0).contains(&hours) {
+            Self::Day
+        } else if (18.0..20.0).contains(&hours) {
+            Self::Dusk
+        } else {
+            Self::Night
+        }
+    }
+}
+
+/// Everything the soundscape needs to know about the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientConditions {
+    pub biome: Biome,
+    pub period: DayPeriod,
+    /// Birds, insects and other wild animals, relative to the present day
+    pub wildlife: f32,
+    /// Machine hum and drones (0.0 = none, 1.0 = a fully automated era)
+    pub machinery: f32,
+    /// Rain intensity (0.0 = dry, 1.0 = storm)
+    pub precipitation: f32,
+    /// Wind strength (0.0–1.0)
+    pub wind: f32,
+}
+
+impl Default for AmbientConditions {
+    fn default() -> Self {
+        Self {
+            biome: Biome::default(),
+            period: DayPeriod::default(),
+            wildlife: 1.0,
+            machinery: 0.0,
+            precipitation: 0.0,
+            wind: 0.0,
+        }
+    }
+}
+
+/// A looping ambient layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmbientBed {
+    Wind,
+    Birds,
+    Insects,
+    Rain,
+    Hum,
+}
+
+impl AmbientBed {
+    pub const ALL: [AmbientBed; 5] = [
+        AmbientBed::Wind,
+        AmbientBed::Birds,
+        AmbientBed::Insects,
+        AmbientBed::Rain,
+        AmbientBed::Hum,
+    ];
+
+    /// Target gain (0.0–1.0) for this bed under the given conditions.
+    pub fn target_gain(&self, c: &AmbientConditions) -> f64 {
+        let dry = 1.0 - c.precipitation.clamp(0.0, 1.0);
+        let gain = match self {
+            AmbientBed::Wind => {
+                let exposure = if c.biome == Biome::Highland { 0.3 } else { 0.0 };
+                0.15 + c.wind * 0.6 + exposure
+            }
+            AmbientBed::Birds => {
+                let time = match c.period {
+                    DayPeriod::Dawn => 1.0,
+                    DayPeriod::Day => 0.7,
+                    DayPeriod::Dusk => 0.3,
+                    DayPeriod::Night => 0.0,
+                };
+                let place = match c.biome {
+                    Biome::Forest => 1.0,
+                    Biome::Grassland => 0.6,
+                    Biome::Highland => 0.2,
+                };
+                time * place * c.wildlife.min(1.0) * dry
+            }
+            AmbientBed::Insects => {
+                let time = match c.period {
+                    DayPeriod::Night => 1.0,
+                    DayPeriod::Dusk => 0.7,
+                    DayPeriod::Day => 0.2,
+                    DayPeriod::Dawn => 0.1,
+                };
+                let place = if c.biome == Biome::Highland { 0.2 } else { 1.0 };
+                time * place * c.wildlife * dry
+            }
+            AmbientBed::Rain => c.precipitation,
+            AmbientBed::Hum => c.machinery * 0.6,
+        };
+        (gain as f64).clamp(0.0, 1.0)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            AmbientBed::Wind => "wind",
+            AmbientBed::Birds => "birds",
+            AmbientBed::Insects => "insects",
+            AmbientBed::Rain => "rain",
+            AmbientBed::Hum => "hum",
+        }
+    }
+}
+
+/// A short one-shot placed somewhere around the listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpotSound {
+    BirdCall,
+    Owl,
+    Thunder,
+    Drone,
+}
+
+impl SpotSound {
+    pub const ALL: [SpotSound; 4] = [
+        SpotSound::BirdCall,
+        SpotSound::Owl,
+        SpotSound::Thunder,
+        SpotSound::Drone,
+    ];
+
+    /// Average occurrences per minute under the given conditions.
+    pub fn rate_per_minute(&self, c: &AmbientConditions) -> f32 {
+        match self {
+            SpotSound::BirdCall => AmbientBed::Birds.target_gain(c) as f32 * 6.0,
+            SpotSound::Owl => {
+                if c.period == DayPeriod::Night && c.biome == Biome::Forest {
+                    2.0 * c.wildlife.min(1.0)
+                } else {
+                    0.0
+                }
+            }
+            SpotSound::Thunder => ((c.precipitation - 0.7) / 0.3).max(0.0) * 3.0,
+            SpotSound::Drone => c.machinery * 1.5,
+        }
+    }
+
+    /// Distance range from the listener (min, max) and height offset.
+    fn placement(&self) -> (f32, f32, f32) {
+        match self {
+            SpotSound::BirdCall => (8.0, 30.0, 6.0),
+            SpotSound::Owl => (10.0, 35.0, 8.0),
+            SpotSound::Thunder => (60.0, 90.0, 40.0),
+            SpotSound::Drone => (15.0, 40.0, 12.0),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SpotSound::BirdCall => "bird_call",
+            SpotSound::Owl => "owl",
+            SpotSound::Thunder => "thunder",
+            SpotSound::Drone => "drone",
+        }
+    }
+}
+
+/// Audio files used by the soundscape.
+#[derive(Debug, Clone)]
+pub struct SoundscapeAssets {
+    pub beds: HashMap<AmbientBed, PathBuf>,
+    /// Variations per spot sound; one is picked at random each time.
+    pub spots: HashMap<SpotSound, Vec<PathBuf>>,
+}
+
+impl Default for SoundscapeAssets {
+    fn default() -> Self {
+        let dir = PathBuf::from("assets/audio/ambience");
+        Self {
+            beds: AmbientBed::ALL
+                .iter()
+                .map(|b| (*b, dir.join(format!("{}.ogg", b.name()))))
+                .collect(),
+            spots: SpotSound::ALL
+                .iter()
+                .map(|s| (*s, vec![dir.join(format!("{}.ogg", s.name()))]))
+                .collect(),
+        }
+    }
+}
+
+/// A spot sound the engine should play this frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotEvent {
+    pub sound: SpotSound,
+    pub position: Vec3,
+    /// Index into the variations list in `SoundscapeAssets::spots`
+    pub variation: u32,
+}
+
+/// Tracks bed gains and spot timers as conditions change.
+pub struct Soundscape {
+    conditions: AmbientConditions,
+    gains: HashMap<AmbientBed, f64>,
+    spot_timers: HashMap<SpotSound, f32>,
+    /// Seconds for a bed to fade fully in or out
+    pub crossfade_time: f32,
+    rng: DetRng,
+}
+
+impl Soundscape {
+    pub fn new(seed: u64) -> Self {
+        let mut scape = Self {
+            conditions: AmbientConditions::default(),
+            gains: AmbientBed::ALL.iter().map(|b| (*b, 0.0)).collect(),
+            spot_timers: HashMap::new(),
+            crossfade_time: 4.0,
+            rng: DetRng::new(seed),
+        };
+        for spot in SpotSound::ALL {
+            let delay = scape.rng.range_f32(2.0, 10.0);
+            scape.spot_timers.insert(spot, delay);
+        }
+        scape
+    }
+
+    /// Update the conditions; gains will crossfade toward the new targets.
+    pub fn set_conditions(&mut self, conditions: AmbientConditions) {
+        self.conditions = conditions;
+    }
+
+    pub fn conditions(&self) -> &AmbientConditions {
+        &self.conditions
+    }
+
+    /// Drop all bed gains to zero so they fade in from silence.
+    pub fn silence(&mut self) {
+        for gain in self.gains.values_mut() {
+            *gain = 0.0;
+        }
+    }
+
+    /// Current (smoothed) gain of a bed.
+    pub fn gain(&self, bed: AmbientBed) -> f64 {
+        self.gains.get(&bed).copied().unwrap_or(0.0)
+    }
+
+    /// Advance crossfades and spot timers. Returns spot sounds to play.
+    pub fn update(&mut self, delta: f32, listener_pos: Vec3) -> Vec<SpotEvent> {
+        let step = if self.crossfade_time > 0.0 {
+            (delta / self.crossfade_time) as f64
+        } else {
+            1.0
+        };
+        for bed in AmbientBed::ALL {
+            let target = bed.target_gain(&self.conditions);
+            let gain = self.gains.entry(bed).or_insert(0.0);
+            *gain = if *gain < target {
+                (*gain + step).min(target)
+            } else {
+                (*gain - step).max(target)
+            };
+        }
+
+        let mut events = Vec::new();
+        for spot in SpotSound::ALL {
+            let rate = spot.rate_per_minute(&self.conditions);
+            if rate <= 0.0 {
+                continue;
+            }
+            let timer = self.spot_timers.entry(spot).or_insert(0.0);
+            *timer -= delta;
+            if *timer > 0.0 {
+                continue;
+            }
+
+            let interval = 60.0 / rate * self.rng.range_f32(0.5, 1.5);
+            self.spot_timers.insert(spot, interval);
+
+            let (min_dist, max_dist, height) = spot.placement();
+            let angle = self.rng.range_f32(0.0, std::f32::consts::TAU);
+            let dist = self.rng.range_f32(min_dist, max_dist);
+            let offset = Vec3::new(angle.cos() * dist, height * self.rng.next_f32(), angle.sin() * dist);
+            events.push(SpotEvent {
+                sound: spot,
+                position: listener_pos + offset,
+                variation: self.rng.next_u32(),
+            });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(period: DayPeriod, machinery: f32) -> AmbientConditions {
+        AmbientConditions {
+            biome: Biome::Forest,
+            period,
+            wildlife: 1.0 - machinery * 0.75,
+            machinery,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn birds_by_day_insects_by_night() {
+        let day = conditions(DayPeriod::Day, 0.0);
+        let night = conditions(DayPeriod::Night, 0.0);
+        assert!(AmbientBed::Birds.target_gain(&day) > AmbientBed::Birds.target_gain(&night));
+        assert!(AmbientBed::Insects.target_gain(&night) > AmbientBed::Insects.target_gain(&day));
+    }
+
+    #[test]
+    fn hum_only_with_machinery() {
+        let present = conditions(DayPeriod::Day, 0.0);
+        let future = conditions(DayPeriod::Day, 1.0);
+        assert_eq!(AmbientBed::Hum.target_gain(&present), 0.0);
+        assert!(AmbientBed::Hum.target_gain(&future) > 0.0);
+    }
+
+    #[test]
+    fn rain_silences_birds() {
+        let mut c = conditions(DayPeriod::Day, 0.0);
+        c.precipitation = 1.0;
+        assert_eq!(AmbientBed::Birds.target_gain(&c), 0.0);
+        assert_eq!(AmbientBed::Rain.target_gain(&c), 1.0);
+    }
+
+    #[test]
+    fn gains_crossfade_over_time() {
+        let mut scape = Soundscape::new(7);
+        scape.set_conditions(conditions(DayPeriod::Day, 1.0));
+        scape.update(1.0, Vec3::ZERO);
+        let partial = scape.gain(AmbientBed::Hum);
+        assert!(partial > 0.0 && partial < 0.6, "should be mid-fade: {}", partial);
+
+        for _ in 0..10 {
+            scape.update(1.0, Vec3::ZERO);
+        }
+        assert!((scape.gain(AmbientBed::Hum) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spots_placed_around_listener() {
+        let mut scape = Soundscape::new(42);
+        scape.set_conditions(conditions(DayPeriod::Dawn, 0.0));
+        let listener = Vec3::new(100.0, 0.0, -50.0);
+        let mut events = Vec::new();
+        for _ in 0..600 {
+            events.extend(scape.update(0.1, listener));
+        }
+        assert!(events.iter().any(|e| e.sound == SpotSound::BirdCall));
+        for e in &events {
+            let (min, max, _) = e.sound.placement();
+            let flat = Vec3::new(e.position.x - listener.x, 0.0, e.position.z - listener.z);
+            assert!(flat.length() >= min - 0.01 && flat.length() <= max + 0.01);
+        }
+    }
+
+    #[test]
+    fn classifiers() {
+        assert_eq!(DayPeriod::from_hours(6.0), DayPeriod::Dawn);
+        assert_eq!(DayPeriod::from_hours(23.0), DayPeriod::Night);
+        assert_eq!(Biome::from_elevation(0.9), Biome::Highland);
+    }
+}
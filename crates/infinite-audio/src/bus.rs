@@ -0,0 +1,120 @@
+use kira::manager::AudioManager;
+use kira::manager::backend::DefaultBackend;
+use kira::track::{TrackBuilder, TrackHandle};
+use kira::tween::Tween;
+use kira::OutputDestination;
+
+use crate::config::AudioConfig;
+use crate::error::AudioError;
+
+/// A mixer bus that sounds are routed through.
+///
+/// Every bus except `Master` is a kira sub-track feeding the main track,
+/// so the master volume scales all the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioBus {
+    Master,
+    Music,
+    Sfx,
+    Ambience,
+    Dialogue,
+}
+
+impl AudioBus {
+    /// All buses, in the order they are shown in the settings menu.
+    pub const ALL: [AudioBus; 5] = [
+        AudioBus::Master,
+        AudioBus::Music,
+        AudioBus::Sfx,
+        AudioBus::Ambience,
+        AudioBus::Dialogue,
+    ];
+
+    /// Display name for UI.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioBus::Master => "Master",
+            AudioBus::Music => "Music",
+            AudioBus::Sfx => "SFX",
+            AudioBus::Ambience => "Ambience",
+            AudioBus::Dialogue => "Dialogue",
+        }
+    }
+}
+
+/// Owns the kira sub-tracks backing each non-master bus.
+pub struct BusMixer {
+    music: TrackHandle,
+    sfx: TrackHandle,
+    ambience: TrackHandle,
+    dialogue: TrackHandle,
+}
+
+impl BusMixer {
+    /// Create the sub-tracks on the given manager.
+    pub fn new(manager: &mut AudioManager<DefaultBackend>) -> Result<Self, AudioError> {
+        let mut add = || {
+            manager
+                .add_sub_track(TrackBuilder::new())
+                .map_err(|e| AudioError::InitFailed(e.to_string()))
+        };
+
+        Ok(Self {
+            music: add()?,
+            sfx: add()?,
+            ambience: add()?,
+            dialogue: add()?,
+        })
+    }
+
+    /// Where sounds on a bus should be routed. `Master` plays straight into
+    /// the main track.
+    pub fn output(&self, bus: AudioBus) -> OutputDestination {
+        match bus {
+            AudioBus::Master => OutputDestination::MAIN_TRACK,
+            AudioBus::Music => (&self.music).into(),
+            AudioBus::Sfx => (&self.sfx).into(),
+            AudioBus::Ambience => (&self.ambience).into(),
+            AudioBus::Dialogue => (&self.dialogue).into(),
+        }
+    }
+
+    /// Set the volume of a single bus.
+    pub fn set_volume(
+        &mut self,
+        manager: &mut AudioManager<DefaultBackend>,
+        bus: AudioBus,
+        volume: f64,
+        tween: Tween,
+    ) {
+        let track = match bus {
+            AudioBus::Master => manager.main_track(),
+            AudioBus::Music => &mut self.music,
+            AudioBus::Sfx => &mut self.sfx,
+            AudioBus::Ambience => &mut self.ambience,
+            AudioBus::Dialogue => &mut self.dialogue,
+        };
+        track.set_volume(volume, tween);
+    }
+
+    /// Push every bus volume from the config. When `muted` is set the master
+    /// bus goes silent while the other buses keep their levels; `music_duck`
+    /// scales the music bus (1.0 = no ducking).
+    pub fn apply(
+        &mut self,
+        manager: &mut AudioManager<DefaultBackend>,
+        config: &AudioConfig,
+        muted: bool,
+        music_duck: f64,
+        tween: Tween,
+    ) {
+        for bus in AudioBus::ALL {
+            let volume = match bus {
+                AudioBus::Master if muted => 0.0,
+                AudioBus::Music => config.bus_volume(bus) * music_duck,
+                _ => config.bus_volume(bus),
+            };
+            self.set_volume(manager, bus, volume, tween);
+        }
+    }
+}
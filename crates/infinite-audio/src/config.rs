@@ -1,5 +1,7 @@
+use crate::bus::AudioBus;
+
 /// Audio volume configuration. Maps to the `AudioSettings` in the game's settings.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AudioConfig {
     /// Master volume multiplier (0.0–1.0).
     pub master_volume: f64,
@@ -9,6 +11,12 @@ pub struct AudioConfig {
     pub sfx_volume: f64,
     /// Voice/dialogue volume multiplier (0.0–1.0).
     pub voice_volume: f64,
+    /// Ambient soundscape volume multiplier (0.0–1.0).
+    pub ambience_volume: f64,
+    /// Silence all output while the game window is unfocused.
+    pub mute_on_unfocus: bool,
+    /// Name of the output device to use. `None` selects the system default.
+    pub output_device: Option<String>,
 }
 
 impl Default for AudioConfig {
@@ -18,6 +26,9 @@ impl Default for AudioConfig {
             music_volume: 0.8,
             sfx_volume: 1.0,
             voice_volume: 1.0,
+            ambience_volume: 0.8,
+            mute_on_unfocus: true,
+            output_device: None,
         }
     }
 }
@@ -37,6 +48,34 @@ impl AudioConfig {
     pub fn effective_voice_volume(&self) -> f64 {
         self.master_volume * self.voice_volume
     }
+
+    /// Effective ambience volume (master * ambience).
+    pub fn effective_ambience_volume(&self) -> f64 {
+        self.master_volume * self.ambience_volume
+    }
+
+    /// Raw (non-multiplied) volume of a single bus.
+    pub fn bus_volume(&self, bus: AudioBus) -> f64 {
+        match bus {
+            AudioBus::Master => self.master_volume,
+            AudioBus::Music => self.music_volume,
+            AudioBus::Sfx => self.sfx_volume,
+            AudioBus::Ambience => self.ambience_volume,
+            AudioBus::Dialogue => self.voice_volume,
+        }
+    }
+
+    /// Set the volume of a single bus, clamped to 0.0–1.0.
+    pub fn set_bus_volume(&mut self, bus: AudioBus, volume: f64) {
+        let volume = volume.clamp(0.0, 1.0);
+        match bus {
+            AudioBus::Master => self.master_volume = volume,
+            AudioBus::Music => self.music_volume = volume,
+            AudioBus::Sfx => self.sfx_volume = volume,
+            AudioBus::Ambience => self.ambience_volume = volume,
+            AudioBus::Dialogue => self.voice_volume = volume,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -50,6 +89,9 @@ mod tests {
         assert_eq!(config.music_volume, 0.8);
         assert_eq!(config.sfx_volume, 1.0);
         assert_eq!(config.voice_volume, 1.0);
+        assert_eq!(config.ambience_volume, 0.8);
+        assert!(config.mute_on_unfocus);
+        assert!(config.output_device.is_none());
     }
 
     #[test]
@@ -59,9 +101,32 @@ mod tests {
             music_volume: 0.8,
             sfx_volume: 1.0,
             voice_volume: 0.6,
+            ambience_volume: 0.4,
+            ..Default::default()
         };
         assert!((config.effective_music_volume() - 0.4).abs() < f64::EPSILON);
         assert!((config.effective_sfx_volume() - 0.5).abs() < f64::EPSILON);
         assert!((config.effective_voice_volume() - 0.3).abs() < f64::EPSILON);
+        assert!((config.effective_ambience_volume() - 0.2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn bus_volume_roundtrip() {
+        let mut config = AudioConfig::default();
+        for (i, bus) in AudioBus::ALL.iter().enumerate() {
+            let volume = i as f64 * 0.2;
+            config.set_bus_volume(*bus, volume);
+            assert_eq!(config.bus_volume(*bus), volume);
+        }
+        assert_eq!(config.bus_volume(AudioBus::Dialogue), config.voice_volume);
+    }
+
+    #[test]
+    fn bus_volume_clamped() {
+        let mut config = AudioConfig::default();
+        config.set_bus_volume(AudioBus::Music, 1.5);
+        assert_eq!(config.music_volume, 1.0);
+        config.set_bus_volume(AudioBus::Sfx, -0.5);
+        assert_eq!(config.sfx_volume, 0.0);
     }
 }
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain, Severity};
+
 /// Errors that can occur in the audio system.
 #[derive(Debug, thiserror::Error)]
 pub enum AudioError {
@@ -9,6 +11,36 @@ pub enum AudioError {
     #[error("failed to load audio file '{0}': {1}")]
     LoadFailed(PathBuf, String),
 
+    #[error("failed to decode audio data: {0}")]
+    DecodeFailed(String),
+
     #[error("audio playback failed: {0}")]
     PlaybackFailed(String),
 }
+
+impl Diagnostic for AudioError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::InitFailed(_) => 1,
+            Self::LoadFailed(..) => 2,
+            Self::DecodeFailed(_) => 3,
+            Self::PlaybackFailed(_) => 4,
+        };
+        ErrorCode::new(ErrorDomain::Audio, number)
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            Self::InitFailed(_) => Severity::Error,
+            // One missing sound isn't worth interrupting the player for
+            Self::LoadFailed(..) | Self::DecodeFailed(_) | Self::PlaybackFailed(_) => Severity::Warning,
+        }
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::InitFailed(_) => Some("The game will run without sound. Pick another output device in Settings > Audio"),
+            _ => None,
+        }
+    }
+}
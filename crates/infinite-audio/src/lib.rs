@@ -1,15 +1,25 @@
 //! Infinite Audio - Audio playback and management using kira
 //!
-//! Provides sound effects, music, and spatial audio for the Infinite engine.
+//! Provides sound effects, music, and spatial audio for the Infinite engine,
+//! mixed through master/music/SFX/ambience/dialogue volume buses.
 
+mod ambience;
+mod bus;
 mod config;
 mod error;
 mod manager;
 mod music;
 mod sfx;
 mod spatial;
+mod voice;
 
+pub use ambience::{
+    AmbientBed, AmbientConditions, Biome, DayPeriod, Soundscape, SoundscapeAssets,
+    SpotEvent, SpotSound,
+};
+pub use bus::AudioBus;
 pub use config::AudioConfig;
 pub use error::AudioError;
 pub use manager::AudioEngine;
 pub use spatial::{compute_spatial, Listener, SpatialParams};
+pub use voice::VoiceLibrary;
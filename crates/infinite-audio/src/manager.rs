@@ -1,61 +1,177 @@
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use cpal::traits::{DeviceTrait, HostTrait};
 use kira::manager::{AudioManager, AudioManagerSettings};
+use kira::manager::backend::cpal::CpalBackendSettings;
 use kira::manager::backend::DefaultBackend;
-use kira::sound::static_sound::StaticSoundHandle;
-use tracing::info;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
+use kira::sound::PlaybackState;
+use kira::tween::Tween;
+use tracing::{info, warn};
 
+use crate::ambience::{AmbientBed, AmbientConditions, Soundscape, SoundscapeAssets};
+use crate::bus::{AudioBus, BusMixer};
 use crate::config::AudioConfig;
 use crate::error::AudioError;
 use crate::music::MusicPlayer;
 use crate::sfx::SfxPlayer;
 use crate::spatial::Listener;
+use crate::voice::VoiceLibrary;
 
 /// The main audio engine. Wraps kira's AudioManager and provides high-level
 /// music, SFX, and spatial audio APIs.
 pub struct AudioEngine {
     manager: AudioManager<DefaultBackend>,
+    buses: BusMixer,
     music: MusicPlayer,
     sfx: SfxPlayer,
     config: AudioConfig,
     listener: Listener,
+    /// Whether the game window currently has focus
+    focused: bool,
+    soundscape: Soundscape,
+    ambience_assets: SoundscapeAssets,
+    /// Playing ambient beds
+    ambience_beds: HashMap<AmbientBed, StaticSoundHandle>,
+    /// Ambience files that failed to load (not retried)
+    missing_ambience: HashSet<PathBuf>,
+    voice_library: VoiceLibrary,
+    /// Currently playing voice line
+    voice: Option<StaticSoundHandle>,
 }
 
+/// Fade used when muting/unmuting on focus changes.
+const FOCUS_FADE: Duration = Duration::from_millis(150);
+
+/// Beds below this gain are stopped rather than kept playing silently.
+const BED_SILENCE_GAIN: f64 = 0.001;
+
+/// Music level (relative to its bus volume) while a voice line plays.
+const VOICE_DUCK_LEVEL: f64 = 0.35;
+
+/// Fade used when ducking music under, or restoring it after, a voice line.
+const DUCK_FADE: Duration = Duration::from_millis(300);
+
+/// Glide used when the playback rate follows a change in time scale.
+const RATE_GLIDE: Duration = Duration::from_millis(80);
+
 impl AudioEngine {
     /// Create a new AudioEngine with the given config.
+    ///
+    /// If `config.output_device` names a device that is not present, the
+    /// system default device is used instead.
     pub fn new(config: AudioConfig) -> Result<Self, AudioError> {
-        let manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())
+        let device = config.output_device.as_deref().and_then(|name| {
+            let device = find_output_device(name);
+            if device.is_none() {
+                warn!("Audio output device '{}' not found, using default", name);
+            }
+            device
+        });
+
+        let settings = AudioManagerSettings {
+            backend_settings: CpalBackendSettings {
+                device,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut manager = AudioManager::<DefaultBackend>::new(settings)
             .map_err(|e| AudioError::InitFailed(e.to_string()))?;
 
+        let mut buses = BusMixer::new(&mut manager)?;
+        buses.apply(&mut manager, &config, false, 1.0, Tween::default());
+
         info!("Audio engine initialized");
 
         Ok(Self {
             manager,
-            music: MusicPlayer::new(config.effective_music_volume()),
-            sfx: SfxPlayer::new(config.effective_sfx_volume()),
+            buses,
+            music: MusicPlayer::new(),
+            sfx: SfxPlayer::new(),
             listener: Listener::default(),
             config,
+            focused: true,
+            soundscape: Soundscape::new(0x5eed_a0d1),
+            ambience_assets: SoundscapeAssets::default(),
+            ambience_beds: HashMap::new(),
+            missing_ambience: HashSet::new(),
+            voice_library: VoiceLibrary::default(),
+            voice: None,
         })
     }
 
+    /// Names of the available audio output devices.
+    pub fn output_device_names() -> Vec<String> {
+        match cpal::default_host().output_devices() {
+            Ok(devices) => devices.filter_map(|d| d.name().ok()).collect(),
+            Err(e) => {
+                warn!("Failed to enumerate audio output devices: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Create an AudioEngine with default configuration.
     pub fn with_default() -> Result<Self, AudioError> {
         Self::new(AudioConfig::default())
     }
 
     /// Apply new volume settings at runtime.
+    ///
+    /// The output device is only read at construction; create a new engine
+    /// to switch devices.
     pub fn update_volumes(&mut self, config: AudioConfig) {
-        self.music.set_volume(config.effective_music_volume());
-        self.sfx.set_volume(config.effective_sfx_volume());
         self.config = config;
+        self.apply_buses(Tween::default());
+    }
+
+    // ---- Buses ----
+
+    /// Set the volume of a single bus at runtime.
+    pub fn set_bus_volume(&mut self, bus: AudioBus, volume: f64) {
+        self.config.set_bus_volume(bus, volume);
+        self.apply_buses(Tween::default());
+    }
+
+    /// Current (raw) volume of a bus.
+    pub fn bus_volume(&self, bus: AudioBus) -> f64 {
+        self.config.bus_volume(bus)
+    }
+
+    /// Notify the engine that the game window gained or lost focus.
+    /// Mutes output while unfocused if `mute_on_unfocus` is enabled.
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.focused == focused {
+            return;
+        }
+        self.focused = focused;
+        self.apply_buses(Tween {
+            duration: FOCUS_FADE,
+            ..Default::default()
+        });
+    }
+
+    /// Whether output is currently muted because the window lost focus.
+    pub fn is_muted(&self) -> bool {
+        self.config.mute_on_unfocus && !self.focused
+    }
+
+    fn apply_buses(&mut self, tween: Tween) {
+        let muted = self.is_muted();
+        let duck = if self.voice.is_some() { VOICE_DUCK_LEVEL } else { 1.0 };
+        self.buses
+            .apply(&mut self.manager, &self.config, muted, duck, tween);
     }
 
     // ---- Music ----
 
     /// Play a music track, looping, with a fade-in.
     pub fn play_music(&mut self, path: &Path, fade_in: Duration) -> Result<(), AudioError> {
-        self.music.play(&mut self.manager, path, fade_in)
+        self.music.play(&mut self.manager, self.buses.output(AudioBus::Music), path, fade_in)
     }
 
     /// Stop the current music with a fade-out.
@@ -65,25 +181,53 @@ impl AudioEngine {
 
     /// Crossfade from the current music track to a new one.
     pub fn crossfade_music(&mut self, path: &Path, duration: Duration) -> Result<(), AudioError> {
-        self.music.crossfade(&mut self.manager, path, duration)
+        self.music.crossfade(&mut self.manager, self.buses.output(AudioBus::Music), path, duration)
     }
 
     // ---- Sound Effects ----
 
     /// Play a one-shot sound effect.
     pub fn play_sfx(&mut self, path: &Path) -> Result<(), AudioError> {
-        self.sfx.play(&mut self.manager, path)
+        self.play_on(AudioBus::Sfx, path)
     }
 
     /// Play a one-shot sound effect at a 3D position.
     pub fn play_sfx_at(&mut self, path: &Path, position: glam::Vec3) -> Result<(), AudioError> {
-        self.sfx
-            .play_at(&mut self.manager, path, &self.listener, position)
+        self.play_on_at(AudioBus::Sfx, path, position)
     }
 
     /// Play a looping sound effect. Returns a handle to stop it later.
     pub fn play_looping(&mut self, path: &Path) -> Result<StaticSoundHandle, AudioError> {
-        self.sfx.play_looping(&mut self.manager, path)
+        self.play_looping_on(AudioBus::Sfx, path, 1.0)
+    }
+
+    /// Play a one-shot sound routed through the given bus.
+    pub fn play_on(&mut self, bus: AudioBus, path: &Path) -> Result<(), AudioError> {
+        let output = self.buses.output(bus);
+        self.sfx.play(&mut self.manager, output, path)
+    }
+
+    /// Play a one-shot sound at a 3D position, routed through the given bus.
+    pub fn play_on_at(
+        &mut self,
+        bus: AudioBus,
+        path: &Path,
+        position: glam::Vec3,
+    ) -> Result<(), AudioError> {
+        let output = self.buses.output(bus);
+        self.sfx
+            .play_at(&mut self.manager, output, path, &self.listener, position)
+    }
+
+    /// Play a looping sound routed through the given bus.
+    pub fn play_looping_on(
+        &mut self,
+        bus: AudioBus,
+        path: &Path,
+        volume: f64,
+    ) -> Result<StaticSoundHandle, AudioError> {
+        let output = self.buses.output(bus);
+        self.sfx.play_looping(&mut self.manager, output, path, volume)
     }
 
     /// Stop a looping sound with a fade-out.
@@ -91,6 +235,165 @@ impl AudioEngine {
         crate::sfx::stop_looping(handle, fade_out);
     }
 
+    // ---- Ambience ----
+
+    /// Set the conditions driving the ambient soundscape. Beds crossfade
+    /// toward the new mix over the next few seconds.
+    pub fn set_ambient_conditions(&mut self, conditions: AmbientConditions) {
+        self.soundscape.set_conditions(conditions);
+    }
+
+    /// Replace the audio files used by the soundscape.
+    pub fn set_soundscape_assets(&mut self, assets: SoundscapeAssets) {
+        self.stop_ambience(Duration::from_millis(500));
+        self.missing_ambience.clear();
+        self.ambience_assets = assets;
+    }
+
+    /// Fade out all ambient beds (e.g. when leaving the world). They fade
+    /// back in on subsequent `update_ambience` calls.
+    pub fn stop_ambience(&mut self, fade_out: Duration) {
+        for (_, mut handle) in self.ambience_beds.drain() {
+            crate::sfx::stop_looping(&mut handle, fade_out);
+        }
+        self.soundscape.silence();
+    }
+
+    /// Advance the soundscape: update bed volumes and play spot sounds around
+    /// the listener. Call once per frame while in-world.
+    pub fn update_ambience(&mut self, delta: f32) {
+        let spots = self.soundscape.update(delta, self.listener.position);
+
+        for bed in AmbientBed::ALL {
+            let gain = self.soundscape.gain(bed);
+            if gain <= BED_SILENCE_GAIN {
+                if let Some(mut handle) = self.ambience_beds.remove(&bed) {
+                    crate::sfx::stop_looping(&mut handle, Duration::from_millis(250));
+                }
+                continue;
+            }
+
+            if let Some(handle) = self.ambience_beds.get_mut(&bed) {
+                handle.set_volume(gain, Tween::default());
+                continue;
+            }
+
+            let Some(path) = self.ambience_assets.beds.get(&bed).cloned() else {
+                continue;
+            };
+            if self.missing_ambience.contains(&path) {
+                continue;
+            }
+            match self.play_looping_on(AudioBus::Ambience, &path, gain) {
+                Ok(handle) => {
+                    self.ambience_beds.insert(bed, handle);
+                }
+                Err(e) => {
+                    warn!("Ambient bed unavailable: {}", e);
+                    self.missing_ambience.insert(path);
+                }
+            }
+        }
+
+        for spot in spots {
+            let Some(variations) = self.ambience_assets.spots.get(&spot.sound) else {
+                continue;
+            };
+            if variations.is_empty() {
+                continue;
+            }
+            let path = variations[spot.variation as usize % variations.len()].clone();
+            if self.missing_ambience.contains(&path) {
+                continue;
+            }
+            if let Err(e) = self.play_on_at(AudioBus::Ambience, &path, spot.position) {
+                warn!("Ambient spot sound unavailable: {}", e);
+                self.missing_ambience.insert(path);
+            }
+        }
+    }
+
+    // ---- Voice ----
+
+    /// Library used to resolve line ids to recordings.
+    pub fn voice_library_mut(&mut self) -> &mut VoiceLibrary {
+        &mut self.voice_library
+    }
+
+    /// Play the pre-recorded voice line for `line_id`. Returns `Ok(false)` if
+    /// no recording exists for it.
+    pub fn play_voice_line(&mut self, line_id: &str) -> Result<bool, AudioError> {
+        let Some(path) = self.voice_library.resolve(line_id) else {
+            return Ok(false);
+        };
+        let data = StaticSoundData::from_file(&path)
+            .map_err(|e| AudioError::LoadFailed(path.clone(), e.to_string()))?;
+        self.start_voice(data)?;
+        Ok(true)
+    }
+
+    /// Play encoded voice audio held in memory (e.g. fetched TTS audio).
+    pub fn play_voice_data(&mut self, bytes: Vec<u8>) -> Result<(), AudioError> {
+        let data = StaticSoundData::from_cursor(Cursor::new(bytes))
+            .map_err(|e| AudioError::DecodeFailed(e.to_string()))?;
+        self.start_voice(data)
+    }
+
+    /// Stop the current voice line and restore music.
+    pub fn stop_voice(&mut self, fade_out: Duration) {
+        if let Some(mut handle) = self.voice.take() {
+            crate::sfx::stop_looping(&mut handle, fade_out);
+            self.apply_buses(Tween {
+                duration: DUCK_FADE,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Whether a voice line is currently playing.
+    pub fn is_voice_playing(&self) -> bool {
+        self.voice.is_some()
+    }
+
+    /// Interrupts any current line, then plays `data` on the dialogue bus
+    /// with music ducked until it finishes.
+    fn start_voice(&mut self, data: StaticSoundData) -> Result<(), AudioError> {
+        if let Some(mut previous) = self.voice.take() {
+            crate::sfx::stop_looping(&mut previous, Duration::from_millis(80));
+        }
+        let settings =
+            StaticSoundSettings::new().output_destination(self.buses.output(AudioBus::Dialogue));
+        let handle = self
+            .manager
+            .play(data.with_settings(settings))
+            .map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
+        self.voice = Some(handle);
+        self.apply_buses(Tween {
+            duration: DUCK_FADE,
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    // ---- Playback rate ----
+
+    /// Speed up or slow down sound effects and ambience (pitch shifts with
+    /// them), e.g. to follow slow motion. Music and dialogue keep playing at
+    /// normal speed.
+    pub fn set_playback_rate(&mut self, rate: f64) {
+        if rate == self.sfx.playback_rate() {
+            return;
+        }
+        let tween = Tween {
+            duration: RATE_GLIDE,
+            ..Default::default()
+        };
+        self.sfx.set_playback_rate(rate, tween);
+        for handle in self.ambience_beds.values_mut() {
+            handle.set_playback_rate(rate, tween);
+        }
+    }
+
     // ---- Spatial ----
 
     /// Update the listener position and orientation for spatial audio.
@@ -102,9 +405,22 @@ impl AudioEngine {
 
     // ---- Per-frame ----
 
-    /// Call each frame to clean up finished sounds.
+    /// Call each frame to clean up finished sounds and release music ducking
+    /// once a voice line ends.
     pub fn update(&mut self) {
         self.sfx.cleanup();
+
+        let voice_done = self
+            .voice
+            .as_ref()
+            .is_some_and(|h| h.state() == PlaybackState::Stopped);
+        if voice_done {
+            self.voice = None;
+            self.apply_buses(Tween {
+                duration: DUCK_FADE,
+                ..Default::default()
+            });
+        }
     }
 
     /// Get a reference to the current audio config.
@@ -112,3 +428,11 @@ impl AudioEngine {
         &self.config
     }
 }
+
+/// Find an output device by name on the default host.
+fn find_output_device(name: &str) -> Option<cpal::Device> {
+    cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+}
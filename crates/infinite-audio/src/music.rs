@@ -5,27 +5,28 @@ use kira::manager::AudioManager;
 use kira::manager::backend::DefaultBackend;
 use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
 use kira::tween::Tween;
+use kira::OutputDestination;
 
 use crate::error::AudioError;
 
 /// Manages background music playback with crossfade support.
+///
+/// Tracks are routed to the music bus, which owns the volume; each track
+/// only fades between silence and full level.
 pub struct MusicPlayer {
     current: Option<StaticSoundHandle>,
-    music_volume: f64,
 }
 
 impl MusicPlayer {
-    pub fn new(music_volume: f64) -> Self {
-        Self {
-            current: None,
-            music_volume,
-        }
+    pub fn new() -> Self {
+        Self { current: None }
     }
 
     /// Start playing a music track, fading in over the given duration.
     pub fn play(
         &mut self,
         manager: &mut AudioManager<DefaultBackend>,
+        output: OutputDestination,
         path: &Path,
         fade_in: Duration,
     ) -> Result<(), AudioError> {
@@ -35,7 +36,8 @@ impl MusicPlayer {
             .map_err(|e| AudioError::LoadFailed(path.to_path_buf(), e.to_string()))?;
         let settings = StaticSoundSettings::new()
             .volume(0.0)
-            .loop_region(..);
+            .loop_region(..)
+            .output_destination(output);
         let data = data.with_settings(settings);
 
         let mut handle = manager
@@ -43,7 +45,7 @@ impl MusicPlayer {
             .map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
 
         handle.set_volume(
-            self.music_volume,
+            1.0,
             Tween {
                 duration: fade_in,
                 ..Default::default()
@@ -69,6 +71,7 @@ impl MusicPlayer {
     pub fn crossfade(
         &mut self,
         manager: &mut AudioManager<DefaultBackend>,
+        output: OutputDestination,
         path: &Path,
         duration: Duration,
     ) -> Result<(), AudioError> {
@@ -85,7 +88,8 @@ impl MusicPlayer {
             .map_err(|e| AudioError::LoadFailed(path.to_path_buf(), e.to_string()))?;
         let settings = StaticSoundSettings::new()
             .volume(0.0)
-            .loop_region(..);
+            .loop_region(..)
+            .output_destination(output);
         let data = data.with_settings(settings);
 
         let mut handle = manager
@@ -93,7 +97,7 @@ impl MusicPlayer {
             .map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
 
         handle.set_volume(
-            self.music_volume,
+            1.0,
             Tween {
                 duration,
                 ..Default::default()
@@ -103,12 +107,10 @@ impl MusicPlayer {
         self.current = Some(handle);
         Ok(())
     }
+}
 
-    /// Update the music volume (applied to the current track immediately).
-    pub fn set_volume(&mut self, volume: f64) {
-        self.music_volume = volume;
-        if let Some(ref mut handle) = self.current {
-            handle.set_volume(volume, Tween::default());
-        }
+impl Default for MusicPlayer {
+    fn default() -> Self {
+        Self::new()
     }
 }
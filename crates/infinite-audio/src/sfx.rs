@@ -6,34 +6,55 @@ use kira::manager::backend::DefaultBackend;
 use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
 use kira::sound::PlaybackState;
 use kira::tween::Tween;
+use kira::OutputDestination;
 
 use crate::error::AudioError;
 use crate::spatial::{self, Listener, SpatialParams};
 
 /// Manages fire-and-forget sound effects with basic caching.
+///
+/// Every play call takes the bus output to route through, so the same player (and
+/// its cache) serves SFX, ambience, and dialogue.
 pub struct SfxPlayer {
     cache: HashMap<PathBuf, StaticSoundData>,
     active: Vec<StaticSoundHandle>,
-    sfx_volume: f64,
+    /// Playback rate for new and playing sounds (1.0 = normal speed and pitch)
+    playback_rate: f64,
 }
 
 impl SfxPlayer {
-    pub fn new(sfx_volume: f64) -> Self {
+    pub fn new() -> Self {
         Self {
             cache: HashMap::new(),
             active: Vec::new(),
-            sfx_volume,
+            playback_rate: 1.0,
         }
     }
 
+    /// Change the speed and pitch of every one-shot, including those
+    /// already playing.
+    pub fn set_playback_rate(&mut self, rate: f64, tween: Tween) {
+        self.playback_rate = rate;
+        for handle in &mut self.active {
+            handle.set_playback_rate(rate, tween);
+        }
+    }
+
+    pub fn playback_rate(&self) -> f64 {
+        self.playback_rate
+    }
+
     /// Play a one-shot sound effect at default volume.
     pub fn play(
         &mut self,
         manager: &mut AudioManager<DefaultBackend>,
+        output: OutputDestination,
         path: &Path,
     ) -> Result<(), AudioError> {
         let data = self.load_or_cache(path)?;
-        let settings = StaticSoundSettings::new().volume(self.sfx_volume);
+        let settings = StaticSoundSettings::new()
+            .playback_rate(self.playback_rate)
+            .output_destination(output);
         let data = data.with_settings(settings);
         let handle = manager.play(data).map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
         self.active.push(handle);
@@ -44,6 +65,7 @@ impl SfxPlayer {
     pub fn play_at(
         &mut self,
         manager: &mut AudioManager<DefaultBackend>,
+        output: OutputDestination,
         path: &Path,
         listener: &Listener,
         position: glam::Vec3,
@@ -51,34 +73,36 @@ impl SfxPlayer {
         let SpatialParams { volume, panning } = spatial::compute_spatial(listener, position);
         let data = self.load_or_cache(path)?;
         let settings = StaticSoundSettings::new()
-            .volume(self.sfx_volume * volume)
-            .panning(panning);
+            .volume(volume)
+            .panning(panning)
+            .playback_rate(self.playback_rate)
+            .output_destination(output);
         let data = data.with_settings(settings);
         let handle = manager.play(data).map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
         self.active.push(handle);
         Ok(())
     }
 
-    /// Play a looping sound effect. Returns the handle so the caller can stop it later.
+    /// Play a looping sound effect at the given starting volume. Returns the
+    /// handle so the caller can stop it later.
     pub fn play_looping(
         &mut self,
         manager: &mut AudioManager<DefaultBackend>,
+        output: OutputDestination,
         path: &Path,
+        volume: f64,
     ) -> Result<StaticSoundHandle, AudioError> {
         let data = self.load_or_cache(path)?;
         let settings = StaticSoundSettings::new()
-            .volume(self.sfx_volume)
-            .loop_region(..);
+            .volume(volume)
+            .loop_region(..)
+            .playback_rate(self.playback_rate)
+            .output_destination(output);
         let data = data.with_settings(settings);
         let handle = manager.play(data).map_err(|e| AudioError::PlaybackFailed(e.to_string()))?;
         Ok(handle)
     }
 
-    /// Update volume on all active sounds and remove finished ones.
-    pub fn set_volume(&mut self, volume: f64) {
-        self.sfx_volume = volume;
-    }
-
     /// Remove handles for sounds that have stopped playing.
     pub fn cleanup(&mut self) {
         self.active.retain(|h| h.state() != PlaybackState::Stopped);
@@ -95,6 +119,12 @@ impl SfxPlayer {
     }
 }
 
+impl Default for SfxPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Stop a looping sound with a fade-out.
 pub fn stop_looping(handle: &mut StaticSoundHandle, fade_out: std::time::Duration) {
     handle.stop(Tween {
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Maps dialogue line ids to pre-recorded voice files.
+///
+/// Explicit mappings take priority; otherwise a line id resolves to
+/// `<root>/<line_id>.ogg` if that file exists.
+#[derive(Debug, Clone)]
+pub struct VoiceLibrary {
+    root: PathBuf,
+    lines: HashMap<String, PathBuf>,
+}
+
+impl VoiceLibrary {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            lines: HashMap::new(),
+        }
+    }
+
+    /// Register an explicit file for a line id.
+    pub fn insert(&mut self, line_id: impl Into<String>, path: impl Into<PathBuf>) {
+        self.lines.insert(line_id.into(), path.into());
+    }
+
+    /// Directory searched for `<line_id>.ogg` files.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Find the recording for a line, if one exists.
+    pub fn resolve(&self, line_id: &str) -> Option<PathBuf> {
+        if let Some(path) = self.lines.get(line_id) {
+            return Some(path.clone());
+        }
+        let path = self.root.join(format!("{}.ogg", line_id));
+        path.exists().then_some(path)
+    }
+}
+
+impl Default for VoiceLibrary {
+    fn default() -> Self {
+        Self::new("assets/audio/voice")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_mapping_wins() {
+        let mut library = VoiceLibrary::new("does/not/exist");
+        library.insert("guard.0", "custom/guard_hello.ogg");
+        assert_eq!(
+            library.resolve("guard.0"),
+            Some(PathBuf::from("custom/guard_hello.ogg"))
+        );
+    }
+
+    #[test]
+    fn missing_line_unresolved() {
+        let library = VoiceLibrary::new("does/not/exist");
+        assert!(library.resolve("villager.2").is_none());
+    }
+}
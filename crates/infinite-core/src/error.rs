@@ -0,0 +1,266 @@
+//! Shared error reporting for every crate
+//!
+//! Each crate keeps its own error enum, and implements [`Diagnostic`] on it
+//! to give every failure a stable code, a severity and, where there's
+//! something the player can do about it, a hint. Errors convert into
+//! [`InfiniteError`], which collects context on its way up ("while loading
+//! chunk (3, -2)") so the final report says both what failed and what the
+//! game was doing at the time. The game logs the full report and shows the
+//! player the short version in an error dialog.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Which part of the engine an error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorDomain {
+    Core,
+    Render,
+    Audio,
+    Assets,
+    World,
+    Net,
+    Integration,
+    Save,
+    Mods,
+}
+
+impl ErrorDomain {
+    /// Prefix of this domain's error codes
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Core => "COR",
+            Self::Render => "REN",
+            Self::Audio => "AUD",
+            Self::Assets => "AST",
+            Self::World => "WLD",
+            Self::Net => "NET",
+            Self::Integration => "INT",
+            Self::Save => "SAV",
+            Self::Mods => "MOD",
+        }
+    }
+}
+
+/// Stable identifier for a kind of failure, e.g. `REN-003`. Codes never get
+/// reused, so they can be looked up from a player's bug report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode {
+    pub domain: ErrorDomain,
+    pub number: u16,
+}
+
+impl ErrorCode {
+    pub const fn new(domain: ErrorDomain, number: u16) -> Self {
+        Self { domain, number }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{:03}", self.domain.prefix(), self.number)
+    }
+}
+
+/// How much of the game a failure takes down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Handled quietly; worth a log line, not the player's attention
+    Warning,
+    /// Something stopped working (no sound, a chunk fell back to flat
+    /// ground) but the game carries on
+    Error,
+    /// The game can't continue
+    Fatal,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+            Self::Fatal => "Fatal",
+        }
+    }
+}
+
+/// Implemented by each crate's error type
+pub trait Diagnostic: StdError + Send + Sync + 'static {
+    fn code(&self) -> ErrorCode;
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// What the player can do about it, if anything
+    fn hint(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// An error from anywhere in the engine, with the context it passed through
+#[derive(Debug)]
+pub struct InfiniteError {
+    code: ErrorCode,
+    severity: Severity,
+    message: String,
+    hint: Option<&'static str>,
+    /// Innermost first
+    context: Vec<String>,
+    source: Option<Box<dyn StdError + Send + Sync>>,
+}
+
+impl InfiniteError {
+    pub fn new(code: ErrorCode, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity,
+            message: message.into(),
+            hint: None,
+            context: Vec::new(),
+            source: None,
+        }
+    }
+
+    pub fn with_hint(mut self, hint: &'static str) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Keep `source` as the underlying cause
+    pub fn with_source(mut self, source: impl StdError + Send + Sync + 'static) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Note what was being done when this failed
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context.push(context.into());
+        self
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        self.hint
+    }
+
+    /// What failed, without context
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// What the game was doing, outermost first
+    pub fn contexts(&self) -> impl Iterator<Item = &str> {
+        self.context.iter().rev().map(String::as_str)
+    }
+
+    /// The whole chain, for logs and the dialog's details: outermost
+    /// context down to the root cause
+    pub fn report(&self) -> String {
+        let mut report = format!("{} [{}]", self.code, self.severity.name());
+        for context in self.contexts() {
+            report.push_str(&format!("\n  {}", context));
+        }
+        report.push_str(&format!("\n  {}", self.message));
+        let mut cause = self.source.as_deref().and_then(StdError::source);
+        while let Some(err) = cause {
+            report.push_str(&format!("\n  caused by: {}", err));
+            cause = err.source();
+        }
+        if let Some(hint) = self.hint {
+            report.push_str(&format!("\n  hint: {}", hint));
+        }
+        report
+    }
+}
+
+impl fmt::Display for InfiniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.contexts().next() {
+            Some(context) => write!(f, "{}: {} ({})", self.code, context, self.message),
+            None => write!(f, "{}: {}", self.code, self.message),
+        }
+    }
+}
+
+impl StdError for InfiniteError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_deref().map(|err| err as &(dyn StdError + 'static))
+    }
+}
+
+impl<E: Diagnostic> From<E> for InfiniteError {
+    fn from(err: E) -> Self {
+        Self {
+            code: err.code(),
+            severity: err.severity(),
+            message: err.to_string(),
+            hint: err.hint(),
+            context: Vec::new(),
+            source: Some(Box::new(err)),
+        }
+    }
+}
+
+/// Context chaining on results whose error converts into `InfiniteError`
+pub trait ResultExt<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, InfiniteError>;
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T, InfiniteError>;
+}
+
+impl<T, E: Into<InfiniteError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, InfiniteError> {
+        self.map_err(|err| err.into().context(context))
+    }
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T, InfiniteError> {
+        self.map_err(|err| err.into().context(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::TimelineError;
+
+    #[test]
+    fn test_codes_are_formatted_with_their_domain() {
+        assert_eq!(ErrorCode::new(ErrorDomain::Render, 3).to_string(), "REN-003");
+        assert_eq!(ErrorCode::new(ErrorDomain::Save, 120).to_string(), "SAV-120");
+    }
+
+    #[test]
+    fn test_context_chains_into_the_report() {
+        let result: Result<(), TimelineError> = Err(TimelineError::YearOutOfRange { year: 9000, min: -5000, max: 3000 });
+        let err = result
+            .context("jumping to 9000 CE")
+            .with_context(|| "travelling through the portal")
+            .unwrap_err();
+
+        assert_eq!(err.code(), ErrorCode::new(ErrorDomain::Core, 1));
+        assert_eq!(err.severity(), Severity::Warning);
+        assert_eq!(
+            err.contexts().collect::<Vec<_>>(),
+            vec!["travelling through the portal", "jumping to 9000 CE"]
+        );
+        assert!(err.to_string().starts_with("COR-001: travelling through the portal"));
+
+        let report = err.report();
+        assert!(report.contains("travelling through the portal\n  jumping to 9000 CE\n  Year 9000"));
+        assert!(report.contains("Year 9000 is out of range"));
+        assert!(err.source().is_some());
+    }
+}
@@ -4,11 +4,24 @@
 //! - Mathematical primitives (re-exported from glam)
 //! - Transform component for entity positioning
 //! - Time system for game time and time travel mechanics
+//! - Deterministic per-system random streams
+//! - Memory accounting against a budget
+//! - Runtime-tunable balance values
 //! - Common error types
 
+pub mod error;
+pub mod memory;
+pub mod rng;
+pub mod scheduler;
 pub mod time;
+pub mod tunables;
 pub mod types;
 
 pub use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
-pub use time::{GameTime, TimeConfig, Timeline};
-pub use types::{Color, EntityId, Transform};
+pub use error::{Diagnostic, ErrorCode, ErrorDomain, InfiniteError, ResultExt, Severity};
+pub use memory::{format_bytes, MemoryBudget, MemoryCategory, MemoryEvent, MemoryPressure, MemoryTracker, MAX_DOWNGRADE};
+pub use rng::{hash_seed, DetRng, RngService, RngSnapshot, RngStream};
+pub use scheduler::{Clock, Scheduler, TimerId, Trigger};
+pub use time::{BranchGraph, BranchId, GameTime, TimeConfig, TimeScaleId, Timeline, TimelineBranch};
+pub use tunables::{LoadReport, Tunable, TunableError, TunableRegistry};
+pub use types::{Color, EntityId, Transform, TransformHistory};
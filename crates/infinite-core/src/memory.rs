@@ -0,0 +1,327 @@
+//! Memory accounting against a budget
+//!
+//! Systems report how many bytes they hold in each [`MemoryCategory`] once
+//! a frame. The tracker totals them against the device (VRAM) and host
+//! budgets, and when either runs hot it steps a downgrade level up so the
+//! game can shed detail (fewer chunks streamed in, simpler NPCs). The level
+//! only steps back down once usage has stayed well under budget for a
+//! while, so it doesn't flap at the threshold.
+
+use serde::{Deserialize, Serialize};
+
+const MIB: u64 = 1024 * 1024;
+
+/// Deepest downgrade the tracker asks for
+pub const MAX_DOWNGRADE: u8 = 3;
+
+/// What a block of memory is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MemoryCategory {
+    /// Vertex and index buffers
+    Meshes,
+    /// Images and render targets
+    Textures,
+    /// UI textures (fonts, icons)
+    Ui,
+    /// Host-visible upload buffers
+    Staging,
+    /// Heightfields and era blend sources of loaded chunks
+    ChunkData,
+    /// NPC instances and their per-NPC bookkeeping
+    NpcState,
+}
+
+impl MemoryCategory {
+    pub const ALL: [MemoryCategory; 6] = [
+        Self::Meshes,
+        Self::Textures,
+        Self::Ui,
+        Self::Staging,
+        Self::ChunkData,
+        Self::NpcState,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Meshes => "Meshes",
+            Self::Textures => "Textures",
+            Self::Ui => "UI",
+            Self::Staging => "Staging",
+            Self::ChunkData => "Chunk data",
+            Self::NpcState => "NPC state",
+        }
+    }
+
+    /// Whether this is a GPU allocation, counted against the device budget
+    pub fn is_device(self) -> bool {
+        matches!(self, Self::Meshes | Self::Textures | Self::Ui | Self::Staging)
+    }
+}
+
+/// How close usage is to the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MemoryPressure {
+    Normal,
+    /// Past the high-water mark; time to shed detail
+    High,
+    /// At or over budget
+    Critical,
+}
+
+impl MemoryPressure {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        }
+    }
+}
+
+/// Memory limits and when to react to them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryBudget {
+    /// VRAM the game should stay under, in MiB
+    pub device_mib: u32,
+    /// System RAM for world and NPC data, in MiB
+    pub host_mib: u32,
+    /// Fraction of a budget at which pressure becomes high
+    pub high_water: f32,
+    /// Fraction of a budget usage has to fall under before detail returns
+    pub low_water: f32,
+    /// Seconds between downgrade steps, and the time under `low_water`
+    /// before a step back up
+    pub settle_seconds: f32,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            device_mib: 1024,
+            host_mib: 512,
+            high_water: 0.85,
+            low_water: 0.6,
+            settle_seconds: 3.0,
+        }
+    }
+}
+
+impl MemoryBudget {
+    pub fn device_bytes(&self) -> u64 {
+        self.device_mib as u64 * MIB
+    }
+
+    pub fn host_bytes(&self) -> u64 {
+        self.host_mib as u64 * MIB
+    }
+}
+
+/// A change to the downgrade level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryEvent {
+    Downgraded { level: u8 },
+    Restored { level: u8 },
+}
+
+/// Per-category usage, checked against a budget
+#[derive(Debug, Clone)]
+pub struct MemoryTracker {
+    pub budget: MemoryBudget,
+    usage: [u64; MemoryCategory::ALL.len()],
+    peak: [u64; MemoryCategory::ALL.len()],
+    level: u8,
+    /// Seconds since the level last went up
+    since_downgrade: f32,
+    /// Seconds usage has been under the low-water mark
+    calm_time: f32,
+}
+
+impl MemoryTracker {
+    pub fn new(budget: MemoryBudget) -> Self {
+        Self {
+            budget,
+            usage: [0; MemoryCategory::ALL.len()],
+            peak: [0; MemoryCategory::ALL.len()],
+            level: 0,
+            since_downgrade: f32::INFINITY,
+            calm_time: 0.0,
+        }
+    }
+
+    /// Record what `category` currently holds
+    pub fn set(&mut self, category: MemoryCategory, bytes: u64) {
+        let index = category as usize;
+        self.usage[index] = bytes;
+        self.peak[index] = self.peak[index].max(bytes);
+    }
+
+    pub fn usage(&self, category: MemoryCategory) -> u64 {
+        self.usage[category as usize]
+    }
+
+    /// Most `category` has held since the tracker was made
+    pub fn peak(&self, category: MemoryCategory) -> u64 {
+        self.peak[category as usize]
+    }
+
+    pub fn device_total(&self) -> u64 {
+        self.total(true)
+    }
+
+    pub fn host_total(&self) -> u64 {
+        self.total(false)
+    }
+
+    /// Device usage as a fraction of its budget
+    pub fn device_fraction(&self) -> f32 {
+        fraction(self.device_total(), self.budget.device_bytes())
+    }
+
+    /// Host usage as a fraction of its budget
+    pub fn host_fraction(&self) -> f32 {
+        fraction(self.host_total(), self.budget.host_bytes())
+    }
+
+    /// The worse of device and host pressure
+    pub fn pressure(&self) -> MemoryPressure {
+        let worst = self.device_fraction().max(self.host_fraction());
+        if worst >= 1.0 {
+            MemoryPressure::Critical
+        } else if worst >= self.budget.high_water {
+            MemoryPressure::High
+        } else {
+            MemoryPressure::Normal
+        }
+    }
+
+    /// Downgrade level, 0 (full detail) to [`MAX_DOWNGRADE`]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Advance by `dt` seconds after this frame's usage has been recorded.
+    /// A downgrade waits for the last one to take effect, unless usage
+    /// is over budget outright.
+    pub fn update(&mut self, dt: f32) -> Option<MemoryEvent> {
+        self.since_downgrade += dt;
+        let worst = self.device_fraction().max(self.host_fraction());
+        let settle = self.budget.settle_seconds;
+
+        if worst < self.budget.low_water {
+            self.calm_time += dt;
+        } else {
+            self.calm_time = 0.0;
+        }
+
+        let pressure = self.pressure();
+        let ready = self.since_downgrade >= settle
+            || (pressure == MemoryPressure::Critical && self.since_downgrade >= settle * 0.25);
+        if pressure >= MemoryPressure::High && ready && self.level < MAX_DOWNGRADE {
+            self.level += 1;
+            self.since_downgrade = 0.0;
+            return Some(MemoryEvent::Downgraded { level: self.level });
+        }
+        if self.level > 0 && self.calm_time >= settle {
+            self.level -= 1;
+            self.calm_time = 0.0;
+            return Some(MemoryEvent::Restored { level: self.level });
+        }
+        None
+    }
+
+    fn total(&self, device: bool) -> u64 {
+        MemoryCategory::ALL
+            .iter()
+            .filter(|category| category.is_device() == device)
+            .map(|&category| self.usage(category))
+            .sum()
+    }
+}
+
+fn fraction(used: u64, budget: u64) -> f32 {
+    if budget == 0 {
+        return 0.0;
+    }
+    used as f32 / budget as f32
+}
+
+/// `bytes` as a short human-readable size, e.g. "12.4 MiB"
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= MIB {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    } else if bytes >= 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> MemoryTracker {
+        MemoryTracker::new(MemoryBudget {
+            device_mib: 100,
+            host_mib: 100,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_categories_total_by_memory_kind() {
+        let mut memory = tracker();
+        memory.set(MemoryCategory::Meshes, 30 * MIB);
+        memory.set(MemoryCategory::Ui, 5 * MIB);
+        memory.set(MemoryCategory::ChunkData, 40 * MIB);
+        memory.set(MemoryCategory::Meshes, 20 * MIB);
+
+        assert_eq!(memory.device_total(), 25 * MIB);
+        assert_eq!(memory.host_total(), 40 * MIB);
+        assert_eq!(memory.peak(MemoryCategory::Meshes), 30 * MIB);
+        assert_eq!(memory.pressure(), MemoryPressure::Normal);
+        assert_eq!(format_bytes(3 * MIB / 2), "1.5 MiB");
+        assert_eq!(format_bytes(512), "512 B");
+    }
+
+    #[test]
+    fn test_pressure_steps_detail_down_then_back_up() {
+        let mut memory = tracker();
+        memory.set(MemoryCategory::Meshes, 90 * MIB);
+        assert_eq!(memory.pressure(), MemoryPressure::High);
+
+        assert_eq!(memory.update(0.1), Some(MemoryEvent::Downgraded { level: 1 }));
+        // The next step waits for the first to take effect
+        assert_eq!(memory.update(1.0), None);
+        assert_eq!(memory.update(2.0), Some(MemoryEvent::Downgraded { level: 2 }));
+
+        // Between the marks: hold where we are
+        memory.set(MemoryCategory::Meshes, 70 * MIB);
+        for _ in 0..10 {
+            assert_eq!(memory.update(1.0), None);
+        }
+
+        memory.set(MemoryCategory::Meshes, 10 * MIB);
+        assert_eq!(memory.update(2.0), None);
+        assert_eq!(memory.update(1.0), Some(MemoryEvent::Restored { level: 1 }));
+        assert_eq!(memory.update(3.0), Some(MemoryEvent::Restored { level: 0 }));
+        assert_eq!(memory.update(3.0), None);
+    }
+
+    #[test]
+    fn test_over_budget_downgrades_faster_and_caps() {
+        let mut memory = tracker();
+        memory.set(MemoryCategory::NpcState, 150 * MIB);
+        assert_eq!(memory.pressure(), MemoryPressure::Critical);
+
+        let mut events = 0;
+        for _ in 0..20 {
+            if memory.update(1.0).is_some() {
+                events += 1;
+            }
+        }
+        assert_eq!(memory.level(), MAX_DOWNGRADE);
+        assert_eq!(events, MAX_DOWNGRADE as usize);
+    }
+}
@@ -0,0 +1,246 @@
+//! Deterministic random numbers, one stream per system
+//!
+//! Everything random in a world should follow from its seed. Rather than
+//! sharing one generator (where an extra loot roll would shift every combat
+//! roll after it), each system draws from its own stream, seeded from the
+//! world seed and the stream's name. Stream states can be snapshotted into
+//! a save and restored, so a reloaded game or a replay rolls the same
+//! numbers the original did. Things fixed in place in the world seed their
+//! own generators with [`hash_seed`] instead, so every crate hashes
+//! positions the same way.
+
+use std::collections::BTreeMap;
+
+use rand::{Error, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Independent random stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RngStream {
+    /// World generation
+    Terrain,
+    /// Drops, chests, shop stock
+    Loot,
+    /// Crits, dodges, procs
+    Combat,
+    /// NPC spawning and behaviour
+    Npc,
+}
+
+impl RngStream {
+    pub const ALL: [RngStream; 4] = [Self::Terrain, Self::Loot, Self::Combat, Self::Npc];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Terrain => "terrain",
+            Self::Loot => "loot",
+            Self::Combat => "combat",
+            Self::Npc => "npc",
+        }
+    }
+
+    /// Seed for this stream in a world with `world_seed`
+    pub fn seed(self, world_seed: u64) -> u64 {
+        // FNV-1a over the name, so adding a stream never reseeds the others
+        let name_hash = self
+            .name()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        mix(world_seed ^ name_hash)
+    }
+}
+
+/// Small, fast generator (SplitMix64) whose whole state is one `u64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetRng {
+    state: u64,
+}
+
+impl DetRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Uniform in `0.0..1.0`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `min..max`
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+}
+
+impl RngCore for DetRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Weyl increment SplitMix64 steps its state by
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// SplitMix64 finalizer: scrambles `z` so that nearby inputs give
+/// unrelated outputs
+pub fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Seed for something fixed in the world (a chunk's shrine, a resource
+/// node, an NPC) that depends only on the world seed and `keys`, in order.
+/// Systems that would otherwise share keys salt `world_seed` first.
+pub fn hash_seed(world_seed: u64, keys: &[u64]) -> u64 {
+    keys.iter()
+        .fold(mix(world_seed), |hash, &key| mix(hash.wrapping_add(GOLDEN_GAMMA) ^ mix(key)))
+}
+
+/// Saved state of every stream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngSnapshot {
+    pub world_seed: u64,
+    pub states: BTreeMap<RngStream, DetRng>,
+}
+
+/// The world's random streams, as an ECS resource
+#[derive(Debug, Clone)]
+pub struct RngService {
+    world_seed: u64,
+    streams: BTreeMap<RngStream, DetRng>,
+}
+
+impl RngService {
+    pub fn new(world_seed: u64) -> Self {
+        let streams = RngStream::ALL
+            .iter()
+            .map(|&stream| (stream, DetRng::new(stream.seed(world_seed))))
+            .collect();
+        Self { world_seed, streams }
+    }
+
+    pub fn world_seed(&self) -> u64 {
+        self.world_seed
+    }
+
+    /// Start every stream over for `world_seed`
+    pub fn reseed(&mut self, world_seed: u64) {
+        *self = Self::new(world_seed);
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut DetRng {
+        self.streams
+            .entry(stream)
+            .or_insert_with(|| DetRng::new(stream.seed(self.world_seed)))
+    }
+
+    /// A generator for one thing within a stream (a chunk, an NPC) that
+    /// depends only on the world seed and `key`. Doesn't advance the stream,
+    /// so the result is the same however much else has been rolled.
+    pub fn fork(&self, stream: RngStream, key: u64) -> DetRng {
+        DetRng::new(mix(stream.seed(self.world_seed) ^ mix(key)))
+    }
+
+    pub fn snapshot(&self) -> RngSnapshot {
+        RngSnapshot {
+            world_seed: self.world_seed,
+            states: self.streams.clone(),
+        }
+    }
+
+    /// Pick up where a snapshot left off. Streams missing from it (saved
+    /// before they existed) start fresh.
+    pub fn restore(&mut self, snapshot: &RngSnapshot) {
+        self.reseed(snapshot.world_seed);
+        for (&stream, &state) in &snapshot.states {
+            self.streams.insert(stream, state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolls(rng: &mut RngService, stream: RngStream) -> Vec<u64> {
+        (0..8).map(|_| rng.stream(stream).next_u64()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_rolls() {
+        let mut a = RngService::new(42);
+        let mut b = RngService::new(42);
+        for stream in RngStream::ALL {
+            assert_eq!(rolls(&mut a, stream), rolls(&mut b, stream));
+        }
+        assert_ne!(rolls(&mut RngService::new(43), RngStream::Loot), rolls(&mut a, RngStream::Loot));
+
+        let value = a.stream(RngStream::Combat).next_f32();
+        assert!((0.0..1.0).contains(&value));
+    }
+
+    #[test]
+    fn test_streams_are_independent() {
+        let mut quiet = RngService::new(7);
+        let mut busy = RngService::new(7);
+        for _ in 0..100 {
+            busy.stream(RngStream::Loot).next_u64();
+        }
+        assert_eq!(rolls(&mut quiet, RngStream::Combat), rolls(&mut busy, RngStream::Combat));
+        assert_ne!(rolls(&mut quiet, RngStream::Loot), rolls(&mut busy, RngStream::Loot));
+
+        // Forks ignore how far the stream has got
+        assert_eq!(quiet.fork(RngStream::Npc, 5), busy.fork(RngStream::Npc, 5));
+        assert_ne!(quiet.fork(RngStream::Npc, 5), quiet.fork(RngStream::Npc, 6));
+    }
+
+    #[test]
+    fn test_hash_seed_depends_on_every_key_and_their_order() {
+        let seed = hash_seed(42, &[3, -7i64 as u64]);
+        assert_eq!(seed, hash_seed(42, &[3, -7i64 as u64]));
+        assert_ne!(seed, hash_seed(43, &[3, -7i64 as u64]));
+        assert_ne!(seed, hash_seed(42, &[3, -6i64 as u64]));
+        assert_ne!(seed, hash_seed(42, &[-7i64 as u64, 3]));
+        assert_ne!(hash_seed(42, &[0]), hash_seed(42, &[0, 0]));
+    }
+
+    #[test]
+    fn test_snapshot_restores_every_stream() {
+        let mut rng = RngService::new(99);
+        rng.stream(RngStream::Combat).next_u64();
+        rng.stream(RngStream::Terrain).next_u64();
+
+        let json = serde_json::to_string(&rng.snapshot()).unwrap();
+        let snapshot: RngSnapshot = serde_json::from_str(&json).unwrap();
+        let expected: Vec<_> = RngStream::ALL.iter().map(|&stream| rolls(&mut rng, stream)).collect();
+
+        let mut restored = RngService::new(0);
+        restored.restore(&snapshot);
+        assert_eq!(restored.world_seed(), 99);
+        let actual: Vec<_> = RngStream::ALL.iter().map(|&stream| rolls(&mut restored, stream)).collect();
+        assert_eq!(actual, expected);
+    }
+}
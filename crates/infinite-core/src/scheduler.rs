@@ -0,0 +1,280 @@
+//! Scheduler for timed gameplay events
+//!
+//! Systems that need "do X in N seconds" or "do X at 18:00" register an event
+//! with the scheduler instead of keeping their own countdown. Timers run on
+//! game time (stops while paused, follows the time scale), real time, or the
+//! in-game time of day. Countdowns are stored as time remaining rather than
+//! absolute timestamps, so pending timers can be saved and picked up again
+//! in a later session.
+
+use serde::{Deserialize, Serialize};
+
+use crate::time::GameTime;
+
+/// Hour advances larger than this within one update are treated as a jump
+/// (time set backwards, loading a save) rather than time passing
+const MAX_HOUR_ADVANCE: f32 = 12.0;
+
+/// Handle to a scheduled timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TimerId(pub u64);
+
+/// Which clock a countdown timer follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Clock {
+    /// Scaled game time; doesn't advance while paused
+    Game,
+    /// Wall-clock time, unaffected by pause and time scale
+    Real,
+}
+
+/// When a timer fires
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Trigger {
+    /// After `remaining` seconds on `clock`, then every `repeat` seconds if set
+    After {
+        clock: Clock,
+        remaining: f32,
+        repeat: Option<f32>,
+    },
+    /// When the time of day passes `hour`, once or every day
+    AtHour { hour: f32, daily: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Timer<E> {
+    id: TimerId,
+    trigger: Trigger,
+    event: E,
+}
+
+/// Pending timers and the events they fire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scheduler<E> {
+    timers: Vec<Timer<E>>,
+    next_id: u64,
+    /// Time of day at the last update, to detect hours being passed
+    #[serde(skip)]
+    last_hour: Option<f32>,
+}
+
+impl<E> Default for Scheduler<E> {
+    fn default() -> Self {
+        Self {
+            timers: Vec::new(),
+            next_id: 0,
+            last_hour: None,
+        }
+    }
+}
+
+impl<E: Clone> Scheduler<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fire `event` once after `seconds` on `clock`
+    pub fn after(&mut self, clock: Clock, seconds: f32, event: E) -> TimerId {
+        self.add(
+            Trigger::After {
+                clock,
+                remaining: seconds,
+                repeat: None,
+            },
+            event,
+        )
+    }
+
+    /// Fire `event` every `interval` seconds on `clock`
+    pub fn every(&mut self, clock: Clock, interval: f32, event: E) -> TimerId {
+        let interval = interval.max(f32::EPSILON);
+        self.add(
+            Trigger::After {
+                clock,
+                remaining: interval,
+                repeat: Some(interval),
+            },
+            event,
+        )
+    }
+
+    /// Fire `event` the next time the time of day passes `hour`, and every
+    /// day after that if `daily`
+    pub fn at_hour(&mut self, hour: f32, daily: bool, event: E) -> TimerId {
+        self.add(
+            Trigger::AtHour {
+                hour: hour.rem_euclid(24.0),
+                daily,
+            },
+            event,
+        )
+    }
+
+    fn add(&mut self, trigger: Trigger, event: E) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer { id, trigger, event });
+        id
+    }
+
+    /// Remove a timer. Returns false if it already fired or doesn't exist.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let before = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != before
+    }
+
+    /// Remove every timer whose event matches `predicate`
+    pub fn cancel_where(&mut self, predicate: impl Fn(&E) -> bool) {
+        self.timers.retain(|timer| !predicate(&timer.event));
+    }
+
+    /// Whether any pending timer's event matches `predicate`
+    pub fn is_scheduled(&self, predicate: impl Fn(&E) -> bool) -> bool {
+        self.timers.iter().any(|timer| predicate(&timer.event))
+    }
+
+    /// The trigger of a pending timer
+    pub fn trigger(&self, id: TimerId) -> Option<Trigger> {
+        self.timers.iter().find(|timer| timer.id == id).map(|timer| timer.trigger)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Advance all timers and return the events that fired, in the order
+    /// they were scheduled. `hour` is the current time of day (0-24).
+    pub fn update(&mut self, time: &GameTime, hour: f32) -> Vec<E> {
+        let hour = hour.rem_euclid(24.0);
+        // Hours passed since the last update, or None on the first update
+        // and after a jump
+        let advance = self
+            .last_hour
+            .map(|last| (hour - last).rem_euclid(24.0))
+            .filter(|advance| *advance > 0.0 && *advance <= MAX_HOUR_ADVANCE);
+        let last_hour = self.last_hour.replace(hour);
+
+        let mut fired = Vec::new();
+        self.timers.retain_mut(|timer| match &mut timer.trigger {
+            Trigger::After {
+                clock,
+                remaining,
+                repeat,
+            } => {
+                *remaining -= match clock {
+                    Clock::Game => time.delta_time,
+                    Clock::Real => time.unscaled_delta_time,
+                };
+                if *remaining > 0.0 {
+                    return true;
+                }
+                fired.push(timer.event.clone());
+                match repeat {
+                    Some(interval) => {
+                        // Keep the cadence, but don't fire a burst after a stall
+                        *remaining = (*remaining + *interval).max(0.0);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Trigger::AtHour { hour: at, daily } => {
+                let (Some(advance), Some(last)) = (advance, last_hour) else {
+                    return true;
+                };
+                let until = (*at - last).rem_euclid(24.0);
+                if until == 0.0 || until > advance {
+                    return true;
+                }
+                fired.push(timer.event.clone());
+                *daily
+            }
+        });
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(delta: f32) -> GameTime {
+        let mut time = GameTime::default();
+        time.update(delta);
+        time
+    }
+
+    #[test]
+    fn test_one_shot_and_repeating() {
+        let mut scheduler = Scheduler::new();
+        scheduler.after(Clock::Game, 0.75, "once");
+        let repeating = scheduler.every(Clock::Game, 0.5, "tick");
+
+        let step = frame(0.25);
+        let fired: Vec<_> = (0..6).flat_map(|_| scheduler.update(&step, 10.0)).collect();
+        assert_eq!(fired, vec!["tick", "once", "tick", "tick"]);
+        assert_eq!(scheduler.len(), 1);
+
+        assert!(scheduler.cancel(repeating));
+        assert!(!scheduler.cancel(repeating));
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_game_clock_stops_while_paused() {
+        let mut scheduler = Scheduler::new();
+        scheduler.after(Clock::Game, 0.5, "game");
+        scheduler.after(Clock::Real, 0.5, "real");
+
+        let mut time = GameTime::default();
+        time.pause();
+        time.update(0.2);
+        let fired: Vec<_> = (0..3).flat_map(|_| scheduler.update(&time, 10.0)).collect();
+        assert_eq!(fired, vec!["real"]);
+        assert!(scheduler.is_scheduled(|e| *e == "game"));
+    }
+
+    #[test]
+    fn test_time_of_day_crossing() {
+        let mut scheduler = Scheduler::new();
+        scheduler.at_hour(18.0, true, "dusk");
+        scheduler.at_hour(2.0, false, "late");
+        let time = frame(0.0);
+
+        // First update only records the hour
+        assert!(scheduler.update(&time, 17.5).is_empty());
+        assert_eq!(scheduler.update(&time, 18.25), vec!["dusk"]);
+        assert!(scheduler.update(&time, 23.5).is_empty());
+        // Wrapping past midnight
+        assert_eq!(scheduler.update(&time, 2.5), vec!["late"]);
+        // A jump backwards isn't time passing
+        assert!(scheduler.update(&time, 12.0).is_empty());
+        assert_eq!(scheduler.update(&time, 19.0), vec!["dusk"]);
+        assert_eq!(scheduler.len(), 1);
+    }
+
+    #[test]
+    fn test_pending_timers_survive_serialization() {
+        let mut scheduler = Scheduler::new();
+        scheduler.after(Clock::Real, 5.0, 1u32);
+        let dusk = scheduler.at_hour(18.0, true, 2u32);
+        scheduler.update(&frame(0.25), 12.0);
+
+        let json = serde_json::to_string(&scheduler).unwrap();
+        let mut restored: Scheduler<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.trigger(dusk), scheduler.trigger(dusk));
+        let remaining = match restored.trigger(TimerId(0)) {
+            Some(Trigger::After { remaining, .. }) => remaining,
+            other => panic!("unexpected trigger {other:?}"),
+        };
+        assert!((remaining - 4.75).abs() < 1e-4);
+
+        // New timers don't reuse ids
+        assert_eq!(restored.after(Clock::Game, 1.0, 3), TimerId(2));
+    }
+}
@@ -1,11 +1,16 @@
 //! Time system for the Infinite engine
 //!
-//! Handles game time, delta time, and the timeline system for time travel mechanics.
+//! Handles game time, delta time, time scaling, and the timeline system for time
+//! travel mechanics.
 //! The world exists on a continuous year-based timeline. The "present" is a specific
 //! year (for MMO mode), and single-player stories can start at any date.
+//! Changing history forks the timeline into branches, alternate histories
+//! that each keep their own world state.
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Diagnostic, ErrorCode, ErrorDomain, Severity};
+
 /// A timeline representing the game's continuous time system.
 ///
 /// Rather than fixed eras, the world uses a year-based timeline where any year
@@ -21,6 +26,9 @@ pub struct Timeline {
     pub min_year: i64,
     /// Maximum allowed year (how far forward the timeline goes)
     pub max_year: i64,
+    /// Alternate histories and the one the player is on
+    #[serde(default)]
+    pub branches: BranchGraph,
 }
 
 impl Default for Timeline {
@@ -30,6 +38,7 @@ impl Default for Timeline {
             present_year: 2025,
             min_year: -10000,
             max_year: 5000,
+            branches: BranchGraph::default(),
         }
     }
 }
@@ -42,6 +51,7 @@ impl Timeline {
             present_year,
             min_year: -10000,
             max_year: 5000,
+            branches: BranchGraph::default(),
         }
     }
 
@@ -92,6 +102,127 @@ impl Timeline {
     pub fn travel_backward(&mut self, years: i64) -> Result<(), TimelineError> {
         self.travel_to_year(self.active_year - years)
     }
+
+    /// The branch the player is on
+    pub fn active_branch(&self) -> BranchId {
+        self.branches.active()
+    }
+
+    /// Split a new branch off the active one at the active year and move
+    /// onto it
+    pub fn fork_branch(&mut self, name: impl Into<String>) -> BranchId {
+        self.branches.fork(name, self.active_year)
+    }
+
+    /// Move onto another branch, staying in the same year
+    pub fn switch_branch(&mut self, branch: BranchId) -> Result<(), TimelineError> {
+        self.branches.switch(branch)
+    }
+}
+
+/// Identifies one branch of the timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct BranchId(pub u32);
+
+impl BranchId {
+    /// The original history every other branch descends from
+    pub const MAIN: Self = Self(0);
+}
+
+impl std::fmt::Display for BranchId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// One alternate history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineBranch {
+    pub id: BranchId,
+    pub name: String,
+    /// Branch this one split from (None for the main timeline)
+    pub parent: Option<BranchId>,
+    /// Year the histories diverged (0 for the main timeline)
+    pub fork_year: i64,
+}
+
+/// Every branch of the timeline, as a tree rooted at `BranchId::MAIN`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchGraph {
+    branches: Vec<TimelineBranch>,
+    active: BranchId,
+}
+
+impl Default for BranchGraph {
+    fn default() -> Self {
+        Self {
+            branches: vec![TimelineBranch {
+                id: BranchId::MAIN,
+                name: "Main timeline".to_string(),
+                parent: None,
+                fork_year: 0,
+            }],
+            active: BranchId::MAIN,
+        }
+    }
+}
+
+impl BranchGraph {
+    /// The branch the player is on
+    pub fn active(&self) -> BranchId {
+        self.active
+    }
+
+    pub fn get(&self, id: BranchId) -> Option<&TimelineBranch> {
+        self.branches.iter().find(|branch| branch.id == id)
+    }
+
+    /// All branches, in the order they were created
+    pub fn branches(&self) -> &[TimelineBranch] {
+        &self.branches
+    }
+
+    /// Branches split directly off `id`
+    pub fn children(&self, id: BranchId) -> impl Iterator<Item = &TimelineBranch> {
+        self.branches.iter().filter(move |branch| branch.parent == Some(id))
+    }
+
+    /// `id` followed by its parent, grandparent, ... up to the main timeline
+    pub fn ancestry(&self, id: BranchId) -> Vec<BranchId> {
+        let mut lineage = Vec::new();
+        let mut current = self.get(id);
+        while let Some(branch) = current {
+            // Guard against cycles in hand-edited saves
+            if lineage.contains(&branch.id) {
+                break;
+            }
+            lineage.push(branch.id);
+            current = branch.parent.and_then(|parent| self.get(parent));
+        }
+        lineage
+    }
+
+    /// Split a new branch off the active one at `year` and make it active
+    pub fn fork(&mut self, name: impl Into<String>, year: i64) -> BranchId {
+        let id = BranchId(self.branches.iter().map(|branch| branch.id.0).max().map_or(0, |max| max + 1));
+        self.branches.push(TimelineBranch {
+            id,
+            name: name.into(),
+            parent: Some(self.active),
+            fork_year: year,
+        });
+        self.active = id;
+        id
+    }
+
+    /// Make another branch active
+    pub fn switch(&mut self, id: BranchId) -> Result<(), TimelineError> {
+        if self.get(id).is_none() {
+            return Err(TimelineError::UnknownBranch(id));
+        }
+        self.active = id;
+        Ok(())
+    }
 }
 
 /// Format a year for display (e.g., "500 BCE", "2025 CE", "3500 CE")
@@ -108,6 +239,21 @@ pub fn format_year(year: i64) -> String {
 pub enum TimelineError {
     #[error("Year {year} is out of range ({min}..{max})")]
     YearOutOfRange { year: i64, min: i64, max: i64 },
+    #[error("No timeline branch {0}")]
+    UnknownBranch(BranchId),
+}
+
+impl Diagnostic for TimelineError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::YearOutOfRange { .. } => ErrorCode::new(ErrorDomain::Core, 1),
+            Self::UnknownBranch(_) => ErrorCode::new(ErrorDomain::Core, 2),
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
 }
 
 /// Configuration for game time
@@ -134,7 +280,44 @@ impl Default for TimeConfig {
     }
 }
 
-/// Game time tracking
+/// Most fixed steps run in one frame. Time beyond that is dropped rather
+/// than letting physics fall further behind every frame.
+pub const MAX_FIXED_STEPS: u32 = 8;
+
+/// Real seconds a timed time scale spends easing back to full speed
+const SCALE_EASE_OUT: f32 = 0.15;
+
+/// Handle to a scoped time scale pushed onto `GameTime`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeScaleId(u32);
+
+/// A time scale layered on top of the global one
+#[derive(Debug, Clone)]
+struct ScopedScale {
+    id: TimeScaleId,
+    scale: f32,
+    /// Real seconds left, for timed scales
+    remaining: Option<f32>,
+}
+
+impl ScopedScale {
+    /// Timed scales ease back to 1.0 over their last moments
+    fn current(&self) -> f32 {
+        match self.remaining {
+            Some(remaining) if remaining < SCALE_EASE_OUT => {
+                let t = remaining / SCALE_EASE_OUT;
+                1.0 + (self.scale - 1.0) * t
+            }
+            _ => self.scale,
+        }
+    }
+}
+
+/// Game time tracking.
+///
+/// Game time runs at the global scale from `config` multiplied by every
+/// scoped scale (slow motion, a frozen world while a cutscene plays).
+/// `unscaled_delta_time` keeps real time for UI.
 #[derive(Debug, Clone)]
 pub struct GameTime {
     /// Configuration
@@ -151,6 +334,9 @@ pub struct GameTime {
     pub paused: bool,
     /// Accumulated time for fixed timestep
     fixed_accumulator: f32,
+    /// Scoped scales, in the order they were pushed
+    scopes: Vec<ScopedScale>,
+    next_scope: u32,
 }
 
 impl Default for GameTime {
@@ -163,6 +349,8 @@ impl Default for GameTime {
             frame_count: 0,
             paused: false,
             fixed_accumulator: 0.0,
+            scopes: Vec::new(),
+            next_scope: 0,
         }
     }
 }
@@ -186,24 +374,43 @@ impl GameTime {
             return;
         }
 
-        self.delta_time = self.unscaled_delta_time * self.config.time_scale;
+        self.delta_time = self.unscaled_delta_time * self.time_scale();
         self.total_time += self.delta_time as f64;
         self.fixed_accumulator += self.delta_time;
+
+        // Timed scales run out in real time, so slow motion lasts as long
+        // however slow it is
+        let elapsed = self.unscaled_delta_time;
+        self.scopes.retain_mut(|scope| match &mut scope.remaining {
+            Some(remaining) => {
+                *remaining -= elapsed;
+                *remaining > 0.0
+            }
+            None => true,
+        });
     }
 
-    /// Get the number of fixed timesteps to process this frame
+    /// Get the number of fixed timesteps to process this frame (at most
+    /// [`MAX_FIXED_STEPS`])
     pub fn fixed_steps(&mut self) -> u32 {
+        let step = self.config.fixed_timestep;
         let mut steps = 0;
-        while self.fixed_accumulator >= self.config.fixed_timestep {
-            self.fixed_accumulator -= self.config.fixed_timestep;
+        while self.fixed_accumulator >= step {
+            if steps == MAX_FIXED_STEPS {
+                self.fixed_accumulator %= step;
+                break;
+            }
+            self.fixed_accumulator -= step;
             steps += 1;
         }
         steps
     }
 
-    /// Get the interpolation factor for rendering between physics steps
+    /// Get the interpolation factor for rendering between physics steps:
+    /// how far (0-1) time has run past the last fixed step, once this
+    /// frame's steps are taken. Blend a [`crate::TransformHistory`] by it.
     pub fn fixed_interpolation(&self) -> f32 {
-        self.fixed_accumulator / self.config.fixed_timestep
+        (self.fixed_accumulator / self.config.fixed_timestep).min(1.0)
     }
 
     /// Pause the game
@@ -221,10 +428,59 @@ impl GameTime {
         self.paused = !self.paused;
     }
 
-    /// Set the time scale (0.0 = frozen, 1.0 = normal, 2.0 = double speed)
+    /// Set the global time scale (0.0 = frozen, 1.0 = normal, 2.0 = double speed)
     pub fn set_time_scale(&mut self, scale: f32) {
         self.config.time_scale = scale.max(0.0);
     }
+
+    /// How fast game time runs right now: the global scale times every
+    /// scoped scale, or 0 while paused
+    pub fn time_scale(&self) -> f32 {
+        if self.paused {
+            return 0.0;
+        }
+        self.scopes.iter().fold(self.config.time_scale, |scale, scope| scale * scope.current())
+    }
+
+    /// Scale game time until the scale is popped (0.0 freezes the world
+    /// while UI keeps running on unscaled time)
+    pub fn push_time_scale(&mut self, scale: f32) -> TimeScaleId {
+        self.push_scope(scale, None)
+    }
+
+    /// Scale game time for `duration` real seconds, easing back to full
+    /// speed at the end (hit stop, slow motion)
+    pub fn slow_motion(&mut self, scale: f32, duration: f32) -> TimeScaleId {
+        self.push_scope(scale, Some(duration.max(0.0)))
+    }
+
+    /// Remove a scoped scale. Returns false if it had already run out.
+    pub fn pop_time_scale(&mut self, id: TimeScaleId) -> bool {
+        let before = self.scopes.len();
+        self.scopes.retain(|scope| scope.id != id);
+        self.scopes.len() != before
+    }
+
+    /// Whether a scoped scale is still in effect
+    pub fn has_time_scale(&self, id: TimeScaleId) -> bool {
+        self.scopes.iter().any(|scope| scope.id == id)
+    }
+
+    /// Drop every scoped scale, e.g. when leaving the world
+    pub fn clear_time_scales(&mut self) {
+        self.scopes.clear();
+    }
+
+    fn push_scope(&mut self, scale: f32, remaining: Option<f32>) -> TimeScaleId {
+        let id = TimeScaleId(self.next_scope);
+        self.next_scope = self.next_scope.wrapping_add(1);
+        self.scopes.push(ScopedScale {
+            id,
+            scale: scale.max(0.0),
+            remaining,
+        });
+        id
+    }
 }
 
 #[cfg(test)]
@@ -272,6 +528,41 @@ mod tests {
         assert_eq!(timeline.years_from_present(), -7025);
     }
 
+    #[test]
+    fn test_branching() {
+        let mut timeline = Timeline::default();
+        assert_eq!(timeline.active_branch(), BranchId::MAIN);
+
+        timeline.travel_to_year(1200).unwrap();
+        let saved_king = timeline.fork_branch("The king lives");
+        assert_eq!(timeline.active_branch(), saved_king);
+        timeline.travel_to_year(1500).unwrap();
+        let rebellion = timeline.fork_branch("Rebellion");
+
+        let branches = &timeline.branches;
+        assert_eq!(branches.get(rebellion).unwrap().fork_year, 1500);
+        assert_eq!(branches.ancestry(rebellion), vec![rebellion, saved_king, BranchId::MAIN]);
+        assert_eq!(branches.children(BranchId::MAIN).count(), 1);
+
+        timeline.switch_branch(BranchId::MAIN).unwrap();
+        assert_eq!(timeline.active_year, 1500, "switching branch keeps the year");
+        assert!(timeline.switch_branch(BranchId(99)).is_err());
+        assert_eq!(timeline.active_branch(), BranchId::MAIN);
+    }
+
+    #[test]
+    fn test_branches_round_trip() {
+        let mut timeline = Timeline::default();
+        timeline.fork_branch("Alternate");
+        let json = serde_json::to_string(&timeline).unwrap();
+        let restored: Timeline = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.branches, timeline.branches);
+
+        // Timelines saved before branching start on the main timeline
+        let old: Timeline = serde_json::from_str(r#"{"active_year":1,"present_year":2025,"min_year":-10,"max_year":10}"#).unwrap();
+        assert_eq!(old.branches, BranchGraph::default());
+    }
+
     #[test]
     fn test_game_time() {
         let mut time = GameTime::default();
@@ -284,4 +575,50 @@ mod tests {
         time.update(0.016);
         assert_eq!(time.delta_time, 0.0);
     }
+
+    #[test]
+    fn test_scoped_time_scales() {
+        let mut time = GameTime::default();
+        time.set_time_scale(2.0);
+        let frozen = time.push_time_scale(0.0);
+        time.update(0.1);
+        assert_eq!(time.delta_time, 0.0);
+        assert!((time.unscaled_delta_time - 0.1).abs() < 1e-6);
+
+        assert!(time.pop_time_scale(frozen));
+        assert!(!time.pop_time_scale(frozen));
+        let slow = time.slow_motion(0.25, 0.5);
+        time.update(0.1);
+        assert!((time.delta_time - 0.05).abs() < 1e-6);
+
+        // Runs out in real time, easing back up on the way
+        time.update(0.2);
+        time.update(0.1);
+        assert!(time.time_scale() > 0.5 && time.time_scale() < 2.0);
+        time.update(0.2);
+        assert!(!time.has_time_scale(slow));
+        assert_eq!(time.time_scale(), 2.0);
+    }
+
+    #[test]
+    fn test_fixed_steps_follow_the_scale() {
+        let mut time = GameTime::default();
+        let step = time.config.fixed_timestep;
+        time.slow_motion(0.5, 10.0);
+        let mut steps = 0;
+        for _ in 0..60 {
+            time.update(step);
+            steps += time.fixed_steps();
+        }
+        // Half speed: half the steps, with the leftover carried over
+        assert!((29..=30).contains(&steps));
+        assert!(time.fixed_interpolation() < 1.0);
+
+        // A long stall at high speed can't queue up unbounded physics
+        time.clear_time_scales();
+        time.set_time_scale(4.0);
+        time.update(0.25);
+        assert_eq!(time.fixed_steps(), MAX_FIXED_STEPS);
+        assert!(time.fixed_interpolation() < 1.0);
+    }
 }
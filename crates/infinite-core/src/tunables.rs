@@ -0,0 +1,534 @@
+//! Named balance values that can change while the game runs
+//!
+//! A [`Tunable`] is a number the game would otherwise hard-code (attack
+//! range, dodge speed, the XP curve), declared as a `static` next to the
+//! rest of its system's balance and read with [`Tunable::get`]. The value
+//! lives in the static itself, so reading one costs an atomic load and no
+//! plumbing. A [`TunableRegistry`] knows every tunable by name: it loads
+//! and saves them as a RON file (`balance.ron`), notices when that file is
+//! saved so values can be retuned without restarting, and is what the
+//! debug panel edits.
+//!
+//! The file is a nested RON struct, one level per dot in the names:
+//!
+//! ```text
+//! (
+//!     combat: (
+//!         attack_range: 2.5,
+//!     ),
+//! )
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::SystemTime;
+
+use crate::error::{Diagnostic, ErrorCode, ErrorDomain, Severity};
+
+/// Seconds between checks of the balance file for changes
+const RELOAD_POLL_INTERVAL: f32 = 1.0;
+
+/// A balance value, adjustable at runtime
+#[derive(Debug)]
+pub struct Tunable {
+    name: &'static str,
+    description: &'static str,
+    default: f32,
+    min: f32,
+    max: f32,
+    value: AtomicU32,
+}
+
+impl Tunable {
+    /// `name` is dotted by system, e.g. `"combat.attack_range"`
+    pub const fn new(name: &'static str, default: f32, min: f32, max: f32, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            default,
+            min,
+            max,
+            value: AtomicU32::new(default.to_bits()),
+        }
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.value.load(Ordering::Relaxed))
+    }
+
+    /// Set the value, clamped to the tunable's range
+    pub fn set(&self, value: f32) {
+        let value = if value.is_finite() { value.clamp(self.min, self.max) } else { self.default };
+        self.value.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.set(self.default);
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    pub fn default_value(&self) -> f32 {
+        self.default
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.get() == self.default
+    }
+}
+
+/// Failure loading or saving the balance file
+#[derive(Debug, thiserror::Error)]
+pub enum TunableError {
+    #[error("Couldn't access the balance file: {0}")]
+    Io(#[from] io::Error),
+    #[error("Balance file line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+impl Diagnostic for TunableError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Io(_) => ErrorCode::new(ErrorDomain::Core, 3),
+            Self::Parse { .. } => ErrorCode::new(ErrorDomain::Core, 4),
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Io(_) => None,
+            Self::Parse { .. } => Some("Fix that line of balance.ron and save it again"),
+        }
+    }
+}
+
+/// What a load changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Tunables whose value the file set
+    pub applied: usize,
+    /// Names in the file that no tunable has (typos, removed values)
+    pub unknown: Vec<String>,
+}
+
+/// Every tunable by name, and the file they're kept in
+#[derive(Debug, Default)]
+pub struct TunableRegistry {
+    tunables: Vec<&'static Tunable>,
+    path: Option<PathBuf>,
+    /// Modification time of the file when it was last read or written
+    modified: Option<SystemTime>,
+    poll_timer: f32,
+    /// Reload the file when it changes on disk
+    pub hot_reload: bool,
+}
+
+impl TunableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a system's tunables. Registering one twice is harmless.
+    pub fn register(&mut self, group: &[&'static Tunable]) {
+        for &tunable in group {
+            if !self.tunables.iter().any(|known| std::ptr::eq(*known, tunable)) {
+                self.tunables.push(tunable);
+            }
+        }
+        self.tunables.sort_by_key(|tunable| tunable.name);
+    }
+
+    /// All tunables, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = &'static Tunable> + '_ {
+        self.tunables.iter().copied()
+    }
+
+    pub fn find(&self, name: &str) -> Option<&'static Tunable> {
+        self.iter().find(|tunable| tunable.name == name)
+    }
+
+    pub fn reset_all(&self) {
+        for tunable in self.iter() {
+            tunable.reset();
+        }
+    }
+
+    /// Apply the values in RON `text`. Tunables it doesn't mention keep
+    /// their current values.
+    pub fn load_str(&self, text: &str) -> Result<LoadReport, TunableError> {
+        let values = parse(text)?;
+        let mut report = LoadReport::default();
+        for (name, value) in values {
+            match self.find(&name) {
+                Some(tunable) => {
+                    tunable.set(value);
+                    report.applied += 1;
+                }
+                None => report.unknown.push(name),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Load from `path` and keep it as the file to save to and watch. A
+    /// missing file is written out with the current values, so there's
+    /// something to edit.
+    pub fn load_file(&mut self, path: impl Into<PathBuf>) -> Result<LoadReport, TunableError> {
+        let path = path.into();
+        self.path = Some(path.clone());
+        if !path.exists() {
+            self.save()?;
+            return Ok(LoadReport::default());
+        }
+        self.modified = modified_time(&path);
+        self.load_str(&fs::read_to_string(&path)?)
+    }
+
+    /// Read the balance file again, e.g. after discarding edits
+    pub fn reload(&mut self) -> Result<LoadReport, TunableError> {
+        match self.path.clone() {
+            Some(path) => self.load_file(path),
+            None => Ok(LoadReport::default()),
+        }
+    }
+
+    /// The file loaded from, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Write every tunable to the balance file
+    pub fn save(&mut self) -> Result<(), TunableError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_ron())?;
+        self.modified = modified_time(path);
+        Ok(())
+    }
+
+    /// Check the balance file every so often while hot reload is on, and
+    /// reload it if it was saved since. Returns what the reload did.
+    pub fn poll(&mut self, dt: f32) -> Option<Result<LoadReport, TunableError>> {
+        if !self.hot_reload {
+            return None;
+        }
+        self.poll_timer += dt;
+        if self.poll_timer < RELOAD_POLL_INTERVAL {
+            return None;
+        }
+        self.poll_timer = 0.0;
+
+        let path = self.path.clone()?;
+        let modified = modified_time(&path)?;
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+        Some(fs::read_to_string(&path).map_err(TunableError::from).and_then(|text| self.load_str(&text)))
+    }
+
+    /// Every tunable as a RON struct, grouped by the parts of its name
+    pub fn to_ron(&self) -> String {
+        let mut root = Group::default();
+        for tunable in self.iter() {
+            let mut group = &mut root;
+            let mut parts: Vec<&str> = tunable.name.split('.').collect();
+            let key = parts.pop().unwrap_or(tunable.name);
+            for part in parts {
+                group = group.groups.entry(part).or_default();
+            }
+            group.values.push((key, tunable));
+        }
+
+        let mut out = String::from("// Game balance. Debug builds pick up changes when this file is saved.\n");
+        root.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// One level of the written file
+#[derive(Default)]
+struct Group<'a> {
+    values: Vec<(&'a str, &'static Tunable)>,
+    groups: BTreeMap<&'a str, Group<'a>>,
+}
+
+impl Group<'_> {
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = "    ".repeat(depth + 1);
+        out.push_str("(\n");
+        for (key, tunable) in &self.values {
+            out.push_str(&format!("{indent}// {}\n", tunable.description));
+            out.push_str(&format!("{indent}{}: {:?},\n", key, tunable.get()));
+        }
+        for (name, group) in &self.groups {
+            out.push_str(&format!("{indent}{}: ", name));
+            group.write(out, depth + 1);
+            out.push_str(",\n");
+        }
+        out.push_str(&"    ".repeat(depth));
+        out.push(')');
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Colon,
+    Comma,
+    Ident(String),
+    Number(f32),
+}
+
+/// Flatten the nested structs of a balance file into dotted names
+fn parse(text: &str) -> Result<Vec<(String, f32)>, TunableError> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let mut values = Vec::new();
+    // An optional struct name before the outermost parenthesis
+    if let Some((Token::Ident(_), _)) = parser.peek() {
+        parser.pos += 1;
+    }
+    parser.struct_body("", &mut values)?;
+    if let Some((_, line)) = parser.peek() {
+        return Err(parse_error(line, "unexpected text after the closing parenthesis"));
+    }
+    Ok(values)
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> TunableError {
+    TunableError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, TunableError> {
+    let mut tokens = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.split("//").next().unwrap_or("");
+        let mut chars = line.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let token = match c {
+                c if c.is_whitespace() => continue,
+                '(' => Token::Open,
+                ')' => Token::Close,
+                ':' => Token::Colon,
+                ',' => Token::Comma,
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut end = start + c.len_utf8();
+                    while let Some(&(i, next)) = chars.peek() {
+                        if !(next.is_ascii_alphanumeric() || next == '_') {
+                            break;
+                        }
+                        end = i + next.len_utf8();
+                        chars.next();
+                    }
+                    Token::Ident(line[start..end].to_string())
+                }
+                c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                    let mut end = start + 1;
+                    while let Some(&(i, next)) = chars.peek() {
+                        if !(next.is_ascii_alphanumeric() || matches!(next, '.' | '-' | '+' | '_')) {
+                            break;
+                        }
+                        end = i + 1;
+                        chars.next();
+                    }
+                    let literal = line[start..end].replace('_', "");
+                    let value = literal
+                        .parse::<f32>()
+                        .map_err(|_| parse_error(line_number, format!("'{}' isn't a number", &line[start..end])))?;
+                    Token::Number(value)
+                }
+                other => return Err(parse_error(line_number, format!("unexpected '{}'", other))),
+            };
+            tokens.push((token, line_number));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<(&Token, usize)> {
+        self.tokens.get(self.pos).map(|(token, line)| (token, *line))
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, what: &str) -> Result<(), TunableError> {
+        let line = self.line();
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(parse_error(line, format!("expected {}", what))),
+        }
+    }
+
+    /// `( key: value, ... )`, with `prefix` for nested names
+    fn struct_body(&mut self, prefix: &str, values: &mut Vec<(String, f32)>) -> Result<(), TunableError> {
+        self.expect(Token::Open, "'('")?;
+        loop {
+            let line = self.line();
+            let key = match self.next() {
+                Some(Token::Close) => return Ok(()),
+                Some(Token::Ident(key)) => key,
+                _ => return Err(parse_error(line, "expected a name or ')'")),
+            };
+            self.expect(Token::Colon, "':'")?;
+            let name = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+
+            let line = self.line();
+            match self.peek() {
+                Some((Token::Number(value), _)) => {
+                    values.push((name, *value));
+                    self.pos += 1;
+                }
+                Some((Token::Open, _)) => self.struct_body(&name, values)?,
+                Some((Token::Ident(_), _)) if matches!(self.tokens.get(self.pos + 1), Some((Token::Open, _))) => {
+                    // A named struct, e.g. `combat: Combat(...)`
+                    self.pos += 1;
+                    self.struct_body(&name, values)?;
+                }
+                _ => return Err(parse_error(line, format!("expected a number or '(' for {}", name))),
+            }
+
+            let line = self.line();
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::Close) => return Ok(()),
+                _ => return Err(parse_error(line, "expected ',' or ')'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each test declares its own statics, so tests running in parallel
+    // don't see each other's values
+
+    #[test]
+    fn test_round_trips_through_ron() {
+        static RANGE: Tunable = Tunable::new("combat.attack_range", 2.5, 0.5, 10.0, "Melee reach in metres");
+        static SPEED: Tunable = Tunable::new("player.dodge_speed", 15.0, 0.0, 50.0, "Dodge impulse");
+        static CURVE: Tunable = Tunable::new("player.xp.exponent", 1.5, 1.0, 3.0, "XP curve steepness");
+        let mut registry = TunableRegistry::new();
+        registry.register(&[&SPEED, &RANGE]);
+        registry.register(&[&CURVE, &RANGE]);
+        assert_eq!(registry.iter().count(), 3);
+        assert_eq!(registry.find("player.dodge_speed").map(Tunable::get), Some(15.0));
+
+        let text = registry.to_ron();
+        assert!(text.contains("combat: (\n"));
+        assert!(text.contains("        attack_range: 2.5,\n"));
+        assert!(text.contains("        xp: (\n            // XP curve steepness\n            exponent: 1.5,"));
+
+        RANGE.set(7.0);
+        CURVE.set(2.0);
+        let report = registry.load_str(&text).unwrap();
+        assert_eq!(report.applied, 3);
+        assert!(report.unknown.is_empty());
+        assert_eq!(RANGE.get(), 2.5);
+        assert_eq!(CURVE.get(), 1.5);
+    }
+
+    #[test]
+    fn test_load_clamps_and_reports_unknown_names() {
+        static RANGE: Tunable = Tunable::new("combat.attack_range", 2.5, 0.5, 10.0, "Melee reach in metres");
+        static SPEED: Tunable = Tunable::new("player.dodge_speed", 15.0, 0.0, 50.0, "Dodge impulse");
+        let mut registry = TunableRegistry::new();
+        registry.register(&[&RANGE, &SPEED]);
+        let report = registry
+            .load_str(
+                "Balance(
+                    // comments and named structs are fine
+                    combat: Combat(attack_range: 99.0, reach: 3),
+                    player: (dodge_speed: -4e1,),
+                )",
+            )
+            .unwrap();
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.unknown, vec!["combat.reach".to_string()]);
+        assert_eq!(RANGE.get(), 10.0, "clamped to the range");
+        assert_eq!(SPEED.get(), 0.0);
+        registry.reset_all();
+        assert!(RANGE.is_default());
+
+        match registry.load_str("(\n  combat: (\n    attack_range: fast,\n  ),\n)") {
+            Err(TunableError::Parse { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_hot_reload_picks_up_saved_changes() {
+        static KNOCKBACK: Tunable = Tunable::new("combat.knockback", 5.0, 0.0, 30.0, "Hit impulse");
+        let dir = std::env::temp_dir().join(format!("infinite-tunables-{}", std::process::id()));
+        let path = dir.join("balance.ron");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut registry = TunableRegistry::new();
+        registry.register(&[&KNOCKBACK]);
+        registry.hot_reload = true;
+        // A missing file gets written with the defaults
+        assert_eq!(registry.load_file(&path).unwrap().applied, 0);
+        assert!(fs::read_to_string(&path).unwrap().contains("knockback: 5.0"));
+        assert!(registry.poll(2.0).is_none(), "unchanged since it was written");
+
+        fs::write(&path, "(combat: (knockback: 12.5))").unwrap();
+        // Force a different timestamp on filesystems with coarse mtimes
+        registry.modified = None;
+        assert!(registry.poll(0.5).is_none(), "waits for the poll interval");
+        let report = registry.poll(0.5).unwrap().unwrap();
+        assert_eq!(report.applied, 1);
+        assert_eq!(KNOCKBACK.get(), 12.5);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
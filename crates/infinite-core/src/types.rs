@@ -116,6 +116,41 @@ impl Transform {
     }
 }
 
+/// A transform before and after the latest simulation step, so rendering
+/// can blend between the two instead of jumping once per step
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TransformHistory {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
+impl TransformHistory {
+    /// A history resting at `transform`
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+        }
+    }
+
+    /// Record the transform a step ended at
+    pub fn push(&mut self, transform: Transform) {
+        self.previous = self.current;
+        self.current = transform;
+    }
+
+    /// Jump to `transform` with nothing to blend from (teleports, spawns)
+    pub fn snap(&mut self, transform: Transform) {
+        *self = Self::new(transform);
+    }
+
+    /// The transform `alpha` (0-1) of the way from the previous step to
+    /// the current one, e.g. by `GameTime::fixed_interpolation`
+    pub fn interpolate(&self, alpha: f32) -> Transform {
+        Transform::lerp(&self.previous, &self.current, alpha.clamp(0.0, 1.0))
+    }
+}
+
 /// RGBA color with floating point components (0.0 to 1.0)
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Color {
@@ -194,6 +229,24 @@ mod tests {
         assert_eq!(translation, Vec3::new(1.0, 2.0, 3.0));
     }
 
+    #[test]
+    fn test_transform_history_blends_the_last_step() {
+        let mut history = TransformHistory::new(Transform::from_position(Vec3::ZERO));
+        history.push(Transform::from_position(Vec3::X));
+        history.push(Transform::from_position(Vec3::X * 3.0));
+        assert_eq!(history.interpolate(0.0).position, Vec3::X);
+        assert_eq!(history.interpolate(0.5).position, Vec3::X * 2.0);
+        assert_eq!(history.interpolate(2.0).position, Vec3::X * 3.0);
+
+        let turned = Transform::from_position_rotation(Vec3::ZERO, Quat::from_rotation_y(1.0));
+        history.push(turned);
+        let halfway = history.interpolate(0.5);
+        assert!(halfway.rotation.angle_between(Quat::from_rotation_y(0.5)) < 1e-4);
+
+        history.snap(Transform::from_position(Vec3::Y));
+        assert_eq!(history.interpolate(0.0).position, Vec3::Y);
+    }
+
     #[test]
     fn test_color_from_hex() {
         let color = Color::from_hex(0xFF8000);
@@ -110,7 +110,7 @@ impl World {
             return false;
         }
         self.storage::<T>()
-            .map_or(false, |s| s.get(entity.index).is_some())
+            .is_some_and(|s| s.get(entity.index).is_some())
     }
 
     // ---- Queries ----
@@ -141,7 +141,7 @@ impl World {
                     let indices: Vec<u32> = (0..self.entities.generations.len() as u32)
                         .filter(|&i| storage.has(i))
                         .collect();
-                    if best_candidates.as_ref().map_or(true, |b| indices.len() < b.len()) {
+                    if best_candidates.as_ref().is_none_or(|b| indices.len() < b.len()) {
                         best_candidates = Some(indices);
                     }
                 } else {
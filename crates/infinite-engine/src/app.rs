@@ -0,0 +1,25 @@
+//! The hooks a game implements
+
+use crate::engine::Engine;
+use crate::frame::Frame;
+
+/// A game running on the [`Engine`].
+///
+/// Each frame the engine advances time, calls `fixed_update` once per fixed
+/// physics step (stepping physics after each), calls `update` once, streams
+/// the world around [`Engine::focus`], and finally asks `render` to fill in
+/// the frame's camera, draws and lights.
+pub trait App {
+    /// Called once, before the first frame
+    fn init(&mut self, _engine: &mut Engine) {}
+
+    /// Called at the fixed physics rate, before each physics step
+    fn fixed_update(&mut self, _engine: &mut Engine, _step: f32) {}
+
+    /// Called once a frame with the scaled frame time
+    fn update(&mut self, engine: &mut Engine, delta: f32);
+
+    /// Describe what to draw this frame. The engine has already added the
+    /// loaded terrain and the sun.
+    fn render(&mut self, _engine: &Engine, _frame: &mut Frame) {}
+}
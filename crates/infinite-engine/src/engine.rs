@@ -0,0 +1,266 @@
+//! The engine loop and its builder
+
+use glam::{Mat4, Vec3};
+use infinite_core::{GameTime, RngService, TimeConfig, Timeline};
+use infinite_physics::{PhysicsConfig, PhysicsWorld};
+use infinite_world::{ChunkConfig, ChunkManager, RoadConfig, RoadNetwork, TerrainConfig, TimeOfDay, TimeTerrainConfig};
+
+use crate::app::App;
+use crate::frame::{Frame, MeshKey};
+
+/// The year the game's present is set in, unless the builder says otherwise
+const DEFAULT_PRESENT_YEAR: i64 = 2025;
+/// Hour of day a new engine starts at
+const DEFAULT_START_HOUR: f32 = 10.0;
+
+/// Sets up an [`Engine`]
+#[derive(Debug, Clone)]
+pub struct EngineBuilder {
+    seed: u64,
+    time: TimeConfig,
+    physics: PhysicsConfig,
+    world: Option<(ChunkConfig, TerrainConfig)>,
+    roads: Option<RoadConfig>,
+    year: i64,
+    present_year: i64,
+    start_hour: f32,
+}
+
+impl Default for EngineBuilder {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            time: TimeConfig::default(),
+            physics: PhysicsConfig::default(),
+            world: None,
+            roads: None,
+            year: DEFAULT_PRESENT_YEAR,
+            present_year: DEFAULT_PRESENT_YEAR,
+            start_hour: DEFAULT_START_HOUR,
+        }
+    }
+}
+
+impl EngineBuilder {
+    /// World seed for terrain, roads and every random stream
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn time_config(mut self, config: TimeConfig) -> Self {
+        self.time = config;
+        self
+    }
+
+    pub fn physics_config(mut self, config: PhysicsConfig) -> Self {
+        self.physics = config;
+        self
+    }
+
+    /// Stream terrain chunks around [`Engine::focus`]. The terrain seed is
+    /// taken from [`seed`](Self::seed).
+    pub fn world(mut self, chunks: ChunkConfig, terrain: TerrainConfig) -> Self {
+        self.world = Some((chunks, terrain));
+        self
+    }
+
+    /// Lay settlements and roads over the world's terrain
+    pub fn roads(mut self, config: RoadConfig) -> Self {
+        self.roads = Some(config);
+        self
+    }
+
+    /// Start in `year`, with the timeline's present at `present_year`
+    pub fn year(mut self, year: i64, present_year: i64) -> Self {
+        self.year = year;
+        self.present_year = present_year;
+        self
+    }
+
+    pub fn start_hour(mut self, hour: f32) -> Self {
+        self.start_hour = hour;
+        self
+    }
+
+    pub fn build(self) -> Engine {
+        let world = self.world.map(|(chunks, mut terrain)| {
+            terrain.seed = self.seed as u32;
+            let chunk_size = chunks.chunk_size;
+            let mut manager = ChunkManager::new(chunks, terrain);
+            manager.set_time_terrain_config(Some(TimeTerrainConfig::for_year(self.year, self.present_year)));
+            if let Some(roads) = self.roads {
+                manager.set_roads(Some(RoadNetwork::new(roads, self.seed as u32, chunk_size)));
+            }
+            manager
+        });
+        Engine {
+            time: GameTime::new(self.time),
+            timeline: Timeline::new(self.year, self.present_year),
+            time_of_day: TimeOfDay::new(self.start_hour),
+            rng: RngService::new(self.seed),
+            physics: PhysicsWorld::with_config(self.physics),
+            world,
+            focus: Vec3::ZERO,
+            started: false,
+            exit_requested: false,
+        }
+    }
+}
+
+/// Time, randomness, physics and the world, driven a frame at a time for an
+/// [`App`]
+pub struct Engine {
+    pub time: GameTime,
+    pub timeline: Timeline,
+    pub time_of_day: TimeOfDay,
+    pub rng: RngService,
+    pub physics: PhysicsWorld,
+    /// Streamed terrain, if the builder was given a world
+    pub world: Option<ChunkManager>,
+    /// Where the world streams chunks around, usually the player
+    pub focus: Vec3,
+    started: bool,
+    exit_requested: bool,
+}
+
+impl Engine {
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::default()
+    }
+
+    /// Ask the host to stop after this frame
+    pub fn request_exit(&mut self) {
+        self.exit_requested = true;
+    }
+
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    /// Terrain height at a world position, or 0 without a world (or outside
+    /// the loaded chunks)
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        self.world.as_ref().map_or(0.0, |world| world.height_at(x, z))
+    }
+
+    /// Run one frame of `raw_delta` real seconds and return what to draw
+    pub fn frame(&mut self, app: &mut impl App, raw_delta: f32) -> Frame {
+        if !self.started {
+            self.started = true;
+            if let Some(world) = &mut self.world {
+                world.update(self.focus, &mut self.physics);
+            }
+            app.init(self);
+        }
+
+        self.time.update(raw_delta);
+        let step = self.time.config.fixed_timestep;
+        for _ in 0..self.time.fixed_steps() {
+            app.fixed_update(self, step);
+            self.physics.step();
+        }
+
+        let delta = self.time.delta_time;
+        app.update(self, delta);
+        self.time_of_day.update(delta);
+        if let Some(world) = &mut self.world {
+            world.update(self.focus, &mut self.physics);
+        }
+
+        let mut frame = Frame::new(self.time_of_day.light_direction(), self.time_of_day.light_intensity());
+        if let Some(world) = &self.world {
+            for chunk in world.loaded_chunks() {
+                frame.draw(MeshKey::Terrain(chunk.coord), Mat4::IDENTITY, [1.0; 4]);
+            }
+        }
+        app.render(self, &mut frame);
+        frame
+    }
+
+    /// Run `frames` frames of `delta` seconds without a window, for
+    /// servers, tools and tests. Stops early if the app requests an exit.
+    pub fn run_headless(&mut self, app: &mut impl App, frames: u64, delta: f32) {
+        for _ in 0..frames {
+            self.frame(app, delta);
+            if self.exit_requested {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::FrameLight;
+
+    #[derive(Default)]
+    struct Counter {
+        inits: u32,
+        fixed: u32,
+        updates: u32,
+        elapsed: f32,
+    }
+
+    impl App for Counter {
+        fn init(&mut self, _engine: &mut Engine) {
+            self.inits += 1;
+        }
+
+        fn fixed_update(&mut self, _engine: &mut Engine, _step: f32) {
+            self.fixed += 1;
+        }
+
+        fn update(&mut self, engine: &mut Engine, delta: f32) {
+            self.updates += 1;
+            self.elapsed += delta;
+            engine.focus.x += 64.0 * delta;
+            if self.updates == 90 {
+                engine.request_exit();
+            }
+        }
+
+        fn render(&mut self, engine: &Engine, frame: &mut Frame) {
+            frame.light(FrameLight {
+                position: engine.focus,
+                color: [1.0; 3],
+                intensity: 1.0,
+                radius: 5.0,
+            });
+        }
+    }
+
+    #[test]
+    fn test_headless_loop_drives_app_and_world() {
+        let chunks = ChunkConfig {
+            chunk_size: 64.0,
+            subdivisions: 4,
+            load_radius: 1,
+            unload_radius: 2,
+        };
+        let terrain = TerrainConfig {
+            size: 64.0,
+            subdivisions: 4,
+            ..Default::default()
+        };
+        let mut engine = Engine::builder().seed(7).world(chunks, terrain).roads(RoadConfig::default()).build();
+        let mut app = Counter::default();
+        engine.run_headless(&mut app, 120, 1.0 / 60.0);
+
+        assert!(engine.exit_requested());
+        assert_eq!((app.inits, app.updates), (1, 90));
+        assert!((app.fixed as i32 - 90).abs() <= 1, "one fixed step per 60 Hz frame");
+        assert!((app.elapsed - 1.5).abs() < 1e-3);
+
+        // The world followed the focus a chunk and a half east
+        let world = engine.world.as_ref().unwrap();
+        assert!(world.get_chunk(&infinite_world::ChunkCoord::new(2, 0)).is_some());
+        let loaded = world.loaded_count();
+
+        let frame = engine.frame(&mut app, 1.0 / 60.0);
+        assert_eq!(frame.draws.iter().filter(|d| matches!(d.mesh, MeshKey::Terrain(_))).count(), loaded);
+        assert_eq!(frame.lights.len(), 1);
+        assert!(frame.camera.is_none());
+    }
+}
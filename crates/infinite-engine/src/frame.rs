@@ -0,0 +1,84 @@
+//! What to draw in a frame, independent of the renderer
+//!
+//! An [`App`](crate::App) fills a [`Frame`] each frame; the host then hands
+//! it to whichever renderer it runs (the Vulkan renderer in the game, or
+//! nothing at all headless).
+
+use glam::{Mat4, Vec3};
+use infinite_world::ChunkCoord;
+
+/// Which mesh a draw uses
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshKey {
+    /// Unit cube centred on the origin
+    Cube,
+    /// Unit sphere
+    Sphere,
+    /// Upright capsule, as used for characters
+    Capsule,
+    /// A loaded terrain chunk, placed by its own coordinate
+    Terrain(ChunkCoord),
+    /// A loaded asset, by path
+    Asset(String),
+}
+
+/// One mesh to draw
+#[derive(Debug, Clone, PartialEq)]
+pub struct Draw {
+    pub mesh: MeshKey,
+    pub transform: Mat4,
+    pub color: [f32; 4],
+}
+
+/// A point light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameLight {
+    pub position: Vec3,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub radius: f32,
+}
+
+/// Where the frame is seen from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameCamera {
+    pub position: Vec3,
+    pub view: Mat4,
+    pub projection: Mat4,
+}
+
+/// Everything to draw in one frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// None until the app places a camera
+    pub camera: Option<FrameCamera>,
+    pub draws: Vec<Draw>,
+    pub lights: Vec<FrameLight>,
+    /// Direction light from the sun or moon travels
+    pub sun_direction: Vec3,
+    pub sun_intensity: f32,
+}
+
+impl Frame {
+    pub fn new(sun_direction: Vec3, sun_intensity: f32) -> Self {
+        Self {
+            camera: None,
+            draws: Vec::new(),
+            lights: Vec::new(),
+            sun_direction,
+            sun_intensity,
+        }
+    }
+
+    pub fn set_camera(&mut self, position: Vec3, view: Mat4, projection: Mat4) {
+        self.camera = Some(FrameCamera { position, view, projection });
+    }
+
+    pub fn draw(&mut self, mesh: MeshKey, transform: Mat4, color: [f32; 4]) {
+        self.draws.push(Draw { mesh, transform, color });
+    }
+
+    pub fn light(&mut self, light: FrameLight) {
+        self.lights.push(light);
+    }
+}
@@ -0,0 +1,47 @@
+//! Infinite Engine - One crate to build games on
+//!
+//! Gathers the engine crates behind a single dependency:
+//! - [`Engine`] owns game time, the timeline, random streams, physics and
+//!   the streamed world, and is set up with [`EngineBuilder`]
+//! - [`App`] is implemented by a game, with hooks called each frame to
+//!   update and to describe what to draw
+//! - [`prelude`] re-exports the types most games need
+//! - The underlying crates are re-exported whole as [`world`], [`physics`],
+//!   [`game`], [`ecs`], [`assets`] and [`core`], plus `render` and `audio`
+//!   with the features of the same names
+//!
+//! ```
+//! use infinite_engine::prelude::*;
+//!
+//! struct Game;
+//!
+//! impl App for Game {
+//!     fn update(&mut self, engine: &mut Engine, delta: f32) {
+//!         engine.focus += Vec3::X * delta;
+//!     }
+//! }
+//!
+//! let mut engine = Engine::builder().seed(42).world(ChunkConfig::default(), TerrainConfig::default()).build();
+//! engine.run_headless(&mut Game, 60, 1.0 / 60.0);
+//! ```
+
+pub mod app;
+pub mod engine;
+pub mod frame;
+pub mod prelude;
+
+pub use app::App;
+pub use engine::{Engine, EngineBuilder};
+pub use frame::{Draw, Frame, FrameCamera, FrameLight, MeshKey};
+
+pub use infinite_assets as assets;
+pub use infinite_core as core;
+pub use infinite_ecs as ecs;
+pub use infinite_game as game;
+pub use infinite_physics as physics;
+pub use infinite_world as world;
+
+#[cfg(feature = "audio")]
+pub use infinite_audio as audio;
+#[cfg(feature = "render")]
+pub use infinite_render as render;
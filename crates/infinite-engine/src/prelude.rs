@@ -0,0 +1,21 @@
+//! The types most games need, for `use infinite_engine::prelude::*`
+
+pub use crate::app::App;
+pub use crate::engine::{Engine, EngineBuilder};
+pub use crate::frame::{Draw, Frame, FrameCamera, FrameLight, MeshKey};
+
+pub use infinite_core::{
+    Clock, Color, DetRng, GameTime, InfiniteError, Mat4, Quat, RngService, RngStream, Scheduler, TimeConfig, Timeline,
+    Transform, Vec2, Vec3, Vec4,
+};
+pub use infinite_physics::{CharacterController, PhysicsConfig, PhysicsWorld};
+pub use infinite_world::{
+    ChunkConfig, ChunkCoord, ChunkManager, RoadConfig, RoadNetwork, Terrain, TerrainConfig, TimeOfDay,
+    TimeTerrainConfig, Weather,
+};
+pub use infinite_game::npc::manager::NpcManager;
+pub use infinite_game::{
+    CameraConfig, CameraController, CameraMode, InputAction, InputHandler, Interactable, InteractionSystem,
+    PlayerController, WorldFlags,
+};
+pub use infinite_assets::{AssetHandle, AssetServer};
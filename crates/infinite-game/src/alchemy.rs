@@ -0,0 +1,695 @@
+//! Alchemy: brewing potions from reagents at an alchemy table
+//!
+//! Every reagent carries two hidden effects. Brewing two or three different
+//! reagents makes a potion with each effect at least two of them share;
+//! if none do, the brew fails and the reagents are lost. Effects are
+//! learned by tasting a reagent (its first unknown effect) or by seeing
+//! what a brew does, and every brew that works is written down in the
+//! player's [`AlchemyJournal`] to be made again.
+//!
+//! Gathered herbs double as reagents, one kind per era; the rest are sold
+//! by merchants. How strong a potion comes out depends on the brewer's
+//! maximum mana.
+//!
+//! Harmful effects can be brewed into weapon coatings instead of potions.
+//! A coated weapon poisons, burns or shocks what it hits for a number of
+//! charges; enemies of the coating's element shrug it off.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::Element;
+use crate::combat::inventory::Inventory;
+use crate::combat::item::{Item, ItemCategory, ItemId, ItemRarity};
+use crate::combat::status::{StatusEffect, StatusEffectType};
+use crate::gathering::{self, ResourceKind};
+use crate::npc::combat::PlayerCombatState;
+use crate::npc::identity::Era;
+use crate::player::stats::CharacterStats;
+
+/// Most reagents in one brew
+pub const MAX_BREW_REAGENTS: usize = 3;
+/// First item id of bought reagents
+pub const REAGENT_ITEM_ID_BASE: u64 = 3650;
+/// First item id of weapon coatings
+pub const COATING_ITEM_ID_BASE: u64 = 3660;
+/// First item id of brewed potions (potency tier and effects are packed in)
+pub const POTION_ITEM_ID_BASE: u64 = 3700;
+/// Item ids per potency tier, one per combination of effects
+const EFFECT_COMBINATIONS: u64 = 1 << AlchemyEffect::ALL.len();
+/// Hits a coating lasts for
+pub const COATING_CHARGES: u32 = 8;
+/// Seconds the status from a coated hit lasts (before the target's element)
+const COATING_STATUS_DURATION: f32 = 4.0;
+/// Maximum mana at which potions brew at potency 1.0
+const BASE_POTENCY_MANA: f32 = 100.0;
+const MIN_POTENCY: f32 = 0.5;
+const MAX_POTENCY: f32 = 3.0;
+/// Potency is rounded to quarters so potions of about the same strength stack
+const POTENCY_STEPS: f32 = 4.0;
+
+/// What a potion does when drunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AlchemyEffect {
+    Heal,
+    Poison,
+    Invisibility,
+    FireResistance,
+    Burn,
+    Shock,
+}
+
+impl AlchemyEffect {
+    pub const ALL: [AlchemyEffect; 6] = [
+        Self::Heal,
+        Self::Poison,
+        Self::Invisibility,
+        Self::FireResistance,
+        Self::Burn,
+        Self::Shock,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Heal => "Healing",
+            Self::Poison => "Poison",
+            Self::Invisibility => "Invisibility",
+            Self::FireResistance => "Fire Resistance",
+            Self::Burn => "Burning",
+            Self::Shock => "Shock",
+        }
+    }
+
+    /// Whether it hurts whoever drinks it
+    pub fn is_harmful(self) -> bool {
+        matches!(self, Self::Poison | Self::Burn | Self::Shock)
+    }
+
+    /// UI color as [r, g, b] floats
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::Heal => [0.9, 0.3, 0.3],
+            Self::Poison => [0.4, 0.75, 0.2],
+            Self::Invisibility => [0.7, 0.75, 0.95],
+            Self::FireResistance => [1.0, 0.6, 0.2],
+            Self::Burn => [1.0, 0.35, 0.1],
+            Self::Shock => [0.5, 0.7, 1.0],
+        }
+    }
+
+    fn bit(self) -> u64 {
+        1 << Self::ALL.iter().position(|e| *e == self).unwrap_or(0)
+    }
+}
+
+/// Something that goes into a brew
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Reagent {
+    /// Gathered herbs, one kind per era
+    WildHerbs,
+    HealingHerbs,
+    Mint,
+    Glowcap,
+    /// Bought from merchants
+    Nightshade,
+    FireLily,
+    AshSalt,
+    Emberroot,
+    Stormglass,
+    Sulfur,
+}
+
+impl Reagent {
+    pub const ALL: [Reagent; 10] = [
+        Self::WildHerbs,
+        Self::HealingHerbs,
+        Self::Mint,
+        Self::Glowcap,
+        Self::Nightshade,
+        Self::FireLily,
+        Self::AshSalt,
+        Self::Emberroot,
+        Self::Stormglass,
+        Self::Sulfur,
+    ];
+
+    /// Reagents merchants sell
+    pub const BOUGHT: [Reagent; 6] = [
+        Self::Nightshade,
+        Self::FireLily,
+        Self::AshSalt,
+        Self::Emberroot,
+        Self::Stormglass,
+        Self::Sulfur,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::WildHerbs => "Wild Herbs",
+            Self::HealingHerbs => "Healing Herbs",
+            Self::Mint => "Mint",
+            Self::Glowcap => "Glowcap",
+            Self::Nightshade => "Nightshade",
+            Self::FireLily => "Fire Lily",
+            Self::AshSalt => "Ash Salt",
+            Self::Emberroot => "Emberroot",
+            Self::Stormglass => "Stormglass",
+            Self::Sulfur => "Sulfur",
+        }
+    }
+
+    /// The two hidden effects, in the order tasting reveals them
+    pub fn effects(self) -> [AlchemyEffect; 2] {
+        use AlchemyEffect::*;
+        match self {
+            Self::WildHerbs => [Heal, Poison],
+            Self::HealingHerbs => [Heal, FireResistance],
+            Self::Mint => [Invisibility, Heal],
+            Self::Glowcap => [Invisibility, Poison],
+            Self::Nightshade => [Poison, Invisibility],
+            Self::FireLily => [FireResistance, Poison],
+            Self::AshSalt => [FireResistance, Invisibility],
+            Self::Emberroot => [Burn, Heal],
+            Self::Stormglass => [Shock, Invisibility],
+            Self::Sulfur => [Burn, Shock],
+        }
+    }
+
+    /// Gold a merchant charges for one
+    pub fn price(self) -> u64 {
+        match self {
+            Self::Nightshade | Self::FireLily => 14,
+            Self::Stormglass => 18,
+            _ => 10,
+        }
+    }
+
+    /// The herb of an era that doubles as a reagent
+    fn herb_of(era: Era) -> Self {
+        match era {
+            Era::Ancient => Self::WildHerbs,
+            Era::Medieval => Self::HealingHerbs,
+            Era::Modern => Self::Mint,
+            Era::Future => Self::Glowcap,
+        }
+    }
+
+    fn item_id(self) -> Option<ItemId> {
+        let index = Self::BOUGHT.iter().position(|r| *r == self)?;
+        Some(ItemId(REAGENT_ITEM_ID_BASE + index as u64))
+    }
+
+    /// A stack of a bought reagent, or the gathered herb it is
+    pub fn create(self, count: u32) -> Item {
+        let Some(id) = self.item_id() else {
+            let era = Era::ALL.into_iter().find(|era| Self::herb_of(*era) == self).unwrap_or(Era::Ancient);
+            return gathering::create_material(ResourceKind::Herb, era, count);
+        };
+        Item {
+            id,
+            name: self.name().to_string(),
+            description: "An alchemical reagent. Taste it or brew with it to learn what it does.".to_string(),
+            category: ItemCategory::Material,
+            rarity: ItemRarity::Common,
+            stat_modifiers: StatModifiers::default(),
+            element: Element::Physical,
+            weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
+            gem_sockets: vec![],
+            required_level: 1,
+            item_level: 1,
+            stack_count: count,
+            max_stack: 20,
+            origin_year: None,
+        }
+    }
+
+    /// The reagent an item is: gathered herbs and bought reagents
+    pub fn from_item(item: &Item) -> Option<Self> {
+        if let Some((ResourceKind::Herb, era)) = gathering::material_origin(item.id) {
+            return Some(Self::herb_of(era));
+        }
+        Self::BOUGHT.into_iter().find(|r| r.item_id() == Some(item.id))
+    }
+}
+
+/// How many of `reagent` the inventory holds
+pub fn count_reagent(inventory: &Inventory, reagent: Reagent) -> u32 {
+    inventory
+        .items
+        .iter()
+        .filter(|item| Reagent::from_item(item) == Some(reagent))
+        .map(|item| item.stack_count)
+        .sum()
+}
+
+/// Remove one of each reagent from the inventory. Returns false, and
+/// removes nothing, if any is missing.
+pub fn take_reagents(inventory: &mut Inventory, reagents: &[Reagent]) -> bool {
+    let needed = |reagent: &Reagent| reagents.iter().filter(|r| *r == reagent).count() as u32;
+    if reagents.iter().any(|r| count_reagent(inventory, *r) < needed(r)) {
+        return false;
+    }
+    for reagent in reagents {
+        if let Some(index) = inventory.items.iter().position(|item| Reagent::from_item(item) == Some(*reagent)) {
+            inventory.remove_item_stack(index, 1);
+        }
+    }
+    true
+}
+
+/// The effects a set of reagents brews into: every effect at least two of
+/// the (distinct) reagents share, in [`AlchemyEffect::ALL`] order
+pub fn brew_effects(reagents: &[Reagent]) -> Vec<AlchemyEffect> {
+    let distinct: BTreeSet<Reagent> = reagents.iter().copied().collect();
+    AlchemyEffect::ALL
+        .into_iter()
+        .filter(|effect| distinct.iter().filter(|r| r.effects().contains(effect)).count() >= 2)
+        .collect()
+}
+
+/// Potency of potions brewed by someone with these stats
+pub fn potency_for(stats: &CharacterStats) -> f32 {
+    let potency = (stats.max_mana / BASE_POTENCY_MANA).clamp(MIN_POTENCY, MAX_POTENCY);
+    (potency * POTENCY_STEPS).round() / POTENCY_STEPS
+}
+
+/// A brew that worked, written down in the journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrewRecipe {
+    /// Sorted
+    pub reagents: Vec<Reagent>,
+    pub effects: Vec<AlchemyEffect>,
+}
+
+impl BrewRecipe {
+    /// Display name of the potion it makes
+    pub fn name(&self) -> String {
+        potion_name(&self.effects)
+    }
+}
+
+/// The result of brewing
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrewOutcome {
+    /// A potion with these effects; `new_recipe` if it went into the journal
+    Brewed { effects: Vec<AlchemyEffect>, new_recipe: bool },
+    /// The reagents share no effect; they are lost
+    Failed,
+    /// A brew takes two or three different reagents. Nothing is used up.
+    Invalid,
+}
+
+impl BrewOutcome {
+    /// Whether the reagents are used up
+    pub fn consumes_reagents(&self) -> bool {
+        !matches!(self, Self::Invalid)
+    }
+}
+
+/// What the player has learned about alchemy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlchemyJournal {
+    /// Effects found for each reagent
+    known_effects: BTreeMap<Reagent, BTreeSet<AlchemyEffect>>,
+    /// Brews that worked, in the order they were found
+    recipes: Vec<BrewRecipe>,
+}
+
+impl AlchemyJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn knows_effect(&self, reagent: Reagent, effect: AlchemyEffect) -> bool {
+        self.known_effects.get(&reagent).is_some_and(|known| known.contains(&effect))
+    }
+
+    /// A reagent's effects, `None` where not yet known
+    pub fn reagent_effects(&self, reagent: Reagent) -> [Option<AlchemyEffect>; 2] {
+        reagent.effects().map(|effect| self.knows_effect(reagent, effect).then_some(effect))
+    }
+
+    pub fn recipes(&self) -> &[BrewRecipe] {
+        &self.recipes
+    }
+
+    fn learn(&mut self, reagent: Reagent, effect: AlchemyEffect) -> bool {
+        self.known_effects.entry(reagent).or_default().insert(effect)
+    }
+
+    /// Taste a reagent, learning its first unknown effect. Returns it, or
+    /// `None` if there was nothing left to learn.
+    pub fn taste(&mut self, reagent: Reagent) -> Option<AlchemyEffect> {
+        let effect = reagent.effects().into_iter().find(|e| !self.knows_effect(reagent, *e))?;
+        self.learn(reagent, effect);
+        Some(effect)
+    }
+
+    /// Brew two or three different reagents. Every reagent learns the
+    /// effects it added to the potion, and a new working brew is recorded.
+    pub fn brew(&mut self, reagents: &[Reagent]) -> BrewOutcome {
+        let mut sorted = reagents.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        if sorted.len() != reagents.len() || !(2..=MAX_BREW_REAGENTS).contains(&sorted.len()) {
+            return BrewOutcome::Invalid;
+        }
+        let effects = brew_effects(&sorted);
+        if effects.is_empty() {
+            return BrewOutcome::Failed;
+        }
+        for reagent in &sorted {
+            for effect in reagent.effects().into_iter().filter(|e| effects.contains(e)) {
+                self.learn(*reagent, effect);
+            }
+        }
+        let new_recipe = !self.recipes.iter().any(|r| r.reagents == sorted);
+        if new_recipe {
+            self.recipes.push(BrewRecipe { reagents: sorted, effects: effects.clone() });
+        }
+        BrewOutcome::Brewed { effects, new_recipe }
+    }
+}
+
+/// Display name of a potion with these effects
+pub fn potion_name(effects: &[AlchemyEffect]) -> String {
+    let names: Vec<&str> = effects.iter().map(|e| e.name()).collect();
+    let kind = if effects.iter().all(|e| e.is_harmful()) { "Vial" } else { "Potion" };
+    format!("{} of {}", kind, names.join(" & "))
+}
+
+/// A brewed potion. Effects and potency are packed into the item id, so
+/// potions only stack with identical ones.
+pub fn create_potion(effects: &[AlchemyEffect], potency: f32, count: u32) -> Item {
+    let mask: u64 = effects.iter().map(|e| e.bit()).fold(0, |a, b| a | b);
+    let tier = (potency.clamp(MIN_POTENCY, MAX_POTENCY) * POTENCY_STEPS).round() as u64;
+    let harmful = effects.iter().any(|e| e.is_harmful());
+    Item {
+        id: ItemId(POTION_ITEM_ID_BASE + tier * EFFECT_COMBINATIONS + mask),
+        name: potion_name(effects),
+        description: format!(
+            "Home-brewed at potency {:.2}.{}",
+            tier as f32 / POTENCY_STEPS,
+            if harmful { " Something in it is poisonous." } else { "" }
+        ),
+        category: ItemCategory::Consumable,
+        rarity: if effects.len() > 1 { ItemRarity::Uncommon } else { ItemRarity::Common },
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 10,
+        origin_year: None,
+    }
+}
+
+/// The effects and potency of a brewed potion, if `id` is one
+pub fn potion_from_item(id: ItemId) -> Option<(Vec<AlchemyEffect>, f32)> {
+    let packed = id.0.checked_sub(POTION_ITEM_ID_BASE)?;
+    let (tier, mask) = (packed / EFFECT_COMBINATIONS, packed % EFFECT_COMBINATIONS);
+    let potency = tier as f32 / POTENCY_STEPS;
+    if mask == 0 || !(MIN_POTENCY..=MAX_POTENCY).contains(&potency) {
+        return None;
+    }
+    let effects = AlchemyEffect::ALL.into_iter().filter(|e| mask & e.bit() != 0).collect();
+    Some((effects, potency))
+}
+
+/// Drink a potion. Returns the HP it healed.
+pub fn drink_potion(combat: &mut PlayerCombatState, effects: &[AlchemyEffect], potency: f32) -> f32 {
+    let before = combat.stats.current_hp;
+    for effect in effects {
+        match effect {
+            AlchemyEffect::Heal => combat.stats.heal(40.0 * potency),
+            AlchemyEffect::Poison => combat
+                .status_manager
+                .apply(StatusEffect::elemental_proc(StatusEffectType::Poisoned, 6.0 * potency)),
+            AlchemyEffect::Invisibility => combat.status_manager.apply(StatusEffect::stat_modifier(
+                StatusEffectType::Invisible,
+                20.0 * potency,
+                StatModifiers::default(),
+            )),
+            AlchemyEffect::FireResistance => {
+                let mut modifiers = StatModifiers::default();
+                modifiers.elemental_resistance[Element::Fire.index()] = 15.0 * potency;
+                combat
+                    .status_manager
+                    .apply(StatusEffect::stat_modifier(StatusEffectType::FireWarded, 60.0, modifiers));
+            }
+            AlchemyEffect::Burn => combat
+                .status_manager
+                .apply(StatusEffect::elemental_proc(StatusEffectType::Burning, 3.0 * potency)),
+            AlchemyEffect::Shock => combat
+                .status_manager
+                .apply(StatusEffect::elemental_proc(StatusEffectType::Shocked, 3.0 * potency)),
+        }
+    }
+    (combat.stats.current_hp - before).max(0.0)
+}
+
+/// What a weapon coating does to the things it hits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CoatingKind {
+    Poison,
+    Burn,
+    Shock,
+}
+
+impl CoatingKind {
+    pub const ALL: [CoatingKind; 3] = [Self::Poison, Self::Burn, Self::Shock];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Poison => "Poison Coating",
+            Self::Burn => "Burning Oil",
+            Self::Shock => "Static Coating",
+        }
+    }
+
+    /// The coating a harmful effect brews into
+    pub fn from_effect(effect: AlchemyEffect) -> Option<Self> {
+        match effect {
+            AlchemyEffect::Poison => Some(Self::Poison),
+            AlchemyEffect::Burn => Some(Self::Burn),
+            AlchemyEffect::Shock => Some(Self::Shock),
+            _ => None,
+        }
+    }
+
+    pub fn effect(self) -> AlchemyEffect {
+        match self {
+            Self::Poison => AlchemyEffect::Poison,
+            Self::Burn => AlchemyEffect::Burn,
+            Self::Shock => AlchemyEffect::Shock,
+        }
+    }
+
+    /// The element enemies resist it with; poison is no element's
+    pub fn element(self) -> Element {
+        match self {
+            Self::Poison => Element::Physical,
+            Self::Burn => Element::Fire,
+            Self::Shock => Element::Air,
+        }
+    }
+
+    /// The status a coated hit applies
+    pub fn status(self) -> StatusEffect {
+        let effect_type = match self {
+            Self::Poison => StatusEffectType::Poisoned,
+            Self::Burn => StatusEffectType::Burning,
+            Self::Shock => StatusEffectType::Shocked,
+        };
+        StatusEffect::elemental_proc(effect_type, COATING_STATUS_DURATION)
+    }
+
+    fn item_id(self) -> ItemId {
+        let index = Self::ALL.iter().position(|c| *c == self).unwrap_or(0) as u64;
+        ItemId(COATING_ITEM_ID_BASE + index)
+    }
+
+    /// A stack of vials of this coating
+    pub fn create(self, count: u32) -> Item {
+        Item {
+            id: self.item_id(),
+            name: self.name().to_string(),
+            description: format!(
+                "Use to coat your weapon: its next {} hits inflict {}.",
+                COATING_CHARGES,
+                self.effect().name().to_lowercase()
+            ),
+            category: ItemCategory::Consumable,
+            rarity: ItemRarity::Uncommon,
+            stat_modifiers: StatModifiers::default(),
+            element: self.element(),
+            weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
+            gem_sockets: vec![],
+            required_level: 1,
+            item_level: 1,
+            stack_count: count,
+            max_stack: 10,
+            origin_year: None,
+        }
+    }
+
+    /// The coating an item is, if any
+    pub fn from_item(item: &Item) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.item_id() == item.id)
+    }
+}
+
+/// The coatings a brew's harmful effects make, one of each
+pub fn coatings_from(effects: &[AlchemyEffect]) -> Vec<CoatingKind> {
+    effects.iter().filter_map(|e| CoatingKind::from_effect(*e)).collect()
+}
+
+/// A coating on the main-hand weapon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeaponCoating {
+    pub kind: CoatingKind,
+    /// Hits left
+    pub charges: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brews_take_shared_effects() {
+        use AlchemyEffect::*;
+        assert_eq!(brew_effects(&[Reagent::WildHerbs, Reagent::HealingHerbs]), vec![Heal]);
+        assert_eq!(
+            brew_effects(&[Reagent::Mint, Reagent::AshSalt, Reagent::HealingHerbs]),
+            vec![Heal, Invisibility, FireResistance]
+        );
+        assert!(brew_effects(&[Reagent::HealingHerbs, Reagent::Glowcap]).is_empty());
+        // The same reagent twice shares nothing with itself
+        assert!(brew_effects(&[Reagent::Mint, Reagent::Mint]).is_empty());
+    }
+
+    #[test]
+    fn test_journal_learns_by_tasting_and_brewing() {
+        let mut journal = AlchemyJournal::new();
+        assert_eq!(journal.reagent_effects(Reagent::Mint), [None, None]);
+        assert_eq!(journal.taste(Reagent::Mint), Some(AlchemyEffect::Invisibility));
+        assert_eq!(journal.taste(Reagent::Mint), Some(AlchemyEffect::Heal));
+        assert_eq!(journal.taste(Reagent::Mint), None);
+
+        assert_eq!(journal.brew(&[Reagent::Mint, Reagent::Mint]), BrewOutcome::Invalid);
+        assert_eq!(journal.brew(&[Reagent::HealingHerbs, Reagent::Glowcap]), BrewOutcome::Failed);
+        assert!(journal.recipes().is_empty());
+
+        let outcome = journal.brew(&[Reagent::Nightshade, Reagent::Glowcap]);
+        assert_eq!(
+            outcome,
+            BrewOutcome::Brewed {
+                effects: vec![AlchemyEffect::Poison, AlchemyEffect::Invisibility],
+                new_recipe: true
+            }
+        );
+        assert!(journal.knows_effect(Reagent::Nightshade, AlchemyEffect::Poison));
+        assert!(matches!(
+            journal.brew(&[Reagent::Glowcap, Reagent::Nightshade]),
+            BrewOutcome::Brewed { new_recipe: false, .. }
+        ));
+        assert_eq!(journal.recipes().len(), 1);
+        assert_eq!(journal.recipes()[0].name(), "Potion of Poison & Invisibility");
+    }
+
+    #[test]
+    fn test_gathered_herbs_are_reagents() {
+        for reagent in Reagent::ALL {
+            assert_eq!(Reagent::from_item(&reagent.create(1)), Some(reagent));
+        }
+        let herb = gathering::create_material(ResourceKind::Herb, Era::Modern, 1);
+        assert_eq!(Reagent::from_item(&herb), Some(Reagent::Mint));
+        let ore = gathering::create_material(ResourceKind::OreVein, Era::Modern, 1);
+        assert_eq!(Reagent::from_item(&ore), None);
+    }
+
+    #[test]
+    fn test_taking_reagents() {
+        let mut inventory = Inventory::new();
+        inventory.add_item(Reagent::Nightshade.create(2)).unwrap();
+        inventory.add_item(Reagent::Glowcap.create(1)).unwrap();
+        assert!(!take_reagents(&mut inventory, &[Reagent::Nightshade, Reagent::AshSalt]));
+        assert_eq!(count_reagent(&inventory, Reagent::Nightshade), 2);
+        assert!(take_reagents(&mut inventory, &[Reagent::Nightshade, Reagent::Glowcap]));
+        assert_eq!(count_reagent(&inventory, Reagent::Nightshade), 1);
+        assert_eq!(count_reagent(&inventory, Reagent::Glowcap), 0);
+    }
+
+    #[test]
+    fn test_potions_round_trip_and_scale_with_potency() {
+        let stats = CharacterStats { max_mana: 160.0, ..CharacterStats::default() };
+        let potency = potency_for(&stats);
+        assert_eq!(potency, 1.5);
+
+        let effects = vec![AlchemyEffect::Heal, AlchemyEffect::FireResistance];
+        let potion = create_potion(&effects, potency, 1);
+        assert_eq!(potion_from_item(potion.id), Some((effects.clone(), potency)));
+        assert_ne!(create_potion(&effects, 1.0, 1).id, potion.id);
+        assert_eq!(potion_from_item(ItemId(POTION_ITEM_ID_BASE)), None);
+
+        let mut weak = PlayerCombatState::new();
+        let mut strong = PlayerCombatState::new();
+        for player in [&mut weak, &mut strong] {
+            player.stats.current_hp = 10.0;
+        }
+        assert!(drink_potion(&mut strong, &effects, 1.5) > drink_potion(&mut weak, &effects, 1.0));
+        assert!(strong.status_manager.has_effect(StatusEffectType::FireWarded));
+    }
+
+    #[test]
+    fn test_invisibility_ends_on_attack() {
+        let mut player = PlayerCombatState::new();
+        drink_potion(&mut player, &[AlchemyEffect::Invisibility], 1.0);
+        assert!(player.status_manager.has_effect(StatusEffectType::Invisible));
+        assert!(player.queue_attack(crate::combat::damage::AttackType::Light));
+        assert!(!player.status_manager.has_effect(StatusEffectType::Invisible));
+    }
+
+    #[test]
+    fn test_harmful_brews_make_coatings() {
+        let effects = brew_effects(&[Reagent::Emberroot, Reagent::Sulfur, Reagent::Stormglass]);
+        assert_eq!(effects, vec![AlchemyEffect::Burn, AlchemyEffect::Shock]);
+        assert_eq!(coatings_from(&effects), vec![CoatingKind::Burn, CoatingKind::Shock]);
+        assert!(coatings_from(&[AlchemyEffect::Heal]).is_empty());
+        for kind in CoatingKind::ALL {
+            let vial = kind.create(1);
+            assert!(vial.id.0 >= COATING_ITEM_ID_BASE && vial.id.0 < POTION_ITEM_ID_BASE);
+            assert_eq!(CoatingKind::from_item(&vial), Some(kind));
+        }
+        assert_eq!(CoatingKind::from_item(&Reagent::Sulfur.create(1)), None);
+    }
+
+    #[test]
+    fn test_coating_charges_run_out() {
+        let mut player = PlayerCombatState::new();
+        assert!(!player.apply_coating(CoatingKind::Poison), "nothing to coat without a weapon");
+
+        let (_, weapon) = crate::combat::create_starter_items("Vanguard", crate::combat::WeaponType::Sword, Element::Physical);
+        player.equipment.equip(crate::combat::EquipmentSlot::MainHand, weapon).unwrap();
+        assert!(player.apply_coating(CoatingKind::Poison));
+        for _ in 0..COATING_CHARGES {
+            assert_eq!(player.take_coating_charge(), Some(CoatingKind::Poison));
+        }
+        assert_eq!(player.coating, None);
+        assert_eq!(player.take_coating_charge(), None);
+    }
+}
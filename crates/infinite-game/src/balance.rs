@@ -0,0 +1,173 @@
+//! Game balance: the numbers combat, the player and NPCs are tuned by
+//!
+//! Each value is a [`Tunable`] static, read where it's used with `.get()`.
+//! [`register_all`] hands them to a [`TunableRegistry`], which loads them
+//! from `balance.ron`, reloads it when it's saved in debug builds, and backs
+//! the debug balance panel. Defaults here are what ships when the file is
+//! missing.
+
+use infinite_core::{Tunable, TunableRegistry};
+
+/// Player attacks and how hits land
+pub mod combat {
+    use super::Tunable;
+
+    pub static ATTACK_RANGE: Tunable =
+        Tunable::new("combat.attack_range", 2.5, 0.5, 10.0, "Reach of the player's melee attack, in metres");
+    pub static ATTACK_ANGLE: Tunable =
+        Tunable::new("combat.attack_angle", 90.0, 10.0, 360.0, "Width of the melee swing arc, in degrees");
+    pub static KNOCKBACK: Tunable =
+        Tunable::new("combat.knockback", 5.0, 0.0, 50.0, "Impulse an NPC's hit pushes the player back with");
+    pub static PARRY_WINDOW: Tunable = Tunable::new(
+        "combat.parry_window",
+        0.2,
+        0.0,
+        1.0,
+        "Seconds after raising a shield in which a blocked hit counts as a parry",
+    );
+    pub static STAGGER_DURATION: Tunable =
+        Tunable::new("combat.stagger_duration", 1.2, 0.1, 5.0, "Seconds an NPC stays staggered once its poise breaks");
+    pub static SNEAK_ATTACK_MULTIPLIER: Tunable = Tunable::new(
+        "combat.sneak_attack_multiplier",
+        2.5,
+        1.0,
+        10.0,
+        "Damage multiplier for attacks on NPCs that haven't detected the player",
+    );
+
+    pub static AIM_ASSIST_ANGLE: Tunable =
+        Tunable::new("combat.aim_assist_angle", 8.0, 1.0, 45.0, "Width of the cone aim assist looks for targets in, in degrees");
+    pub static AIM_ASSIST_STRENGTH: Tunable = Tunable::new(
+        "combat.aim_assist_strength",
+        3.0,
+        0.0,
+        20.0,
+        "How fast aim assist turns the camera onto its target (fraction of the gap per second)",
+    );
+    pub static SPREAD_PER_SHOT: Tunable =
+        Tunable::new("combat.spread_per_shot", 3.0, 0.0, 12.0, "Degrees a ranged shot blooms the crosshair spread by");
+    pub static SPREAD_RECOVERY: Tunable =
+        Tunable::new("combat.spread_recovery", 10.0, 0.5, 60.0, "Degrees per second the crosshair spread settles back");
+
+    pub static ALL: [&Tunable; 10] = [
+        &ATTACK_RANGE,
+        &ATTACK_ANGLE,
+        &KNOCKBACK,
+        &PARRY_WINDOW,
+        &STAGGER_DURATION,
+        &SNEAK_ATTACK_MULTIPLIER,
+        &AIM_ASSIST_ANGLE,
+        &AIM_ASSIST_STRENGTH,
+        &SPREAD_PER_SHOT,
+        &SPREAD_RECOVERY,
+    ];
+}
+
+/// Player movement and progression
+pub mod player {
+    use super::Tunable;
+
+    pub static DODGE_SPEED: Tunable = Tunable::new("player.dodge_speed", 15.0, 0.0, 50.0, "Impulse of a dodge roll");
+    pub static DODGE_DURATION: Tunable = Tunable::new(
+        "player.dodge_duration",
+        0.3,
+        0.05,
+        2.0,
+        "Seconds a dodge lasts, and the invincibility it grants",
+    );
+    pub static DODGE_COOLDOWN: Tunable =
+        Tunable::new("player.dodge_cooldown", 1.5, 0.0, 10.0, "Seconds before the player can dodge again");
+    pub static XP_BASE: Tunable =
+        Tunable::new("player.xp_base", 100.0, 1.0, 10000.0, "XP curve scale: total XP for level n is base * n^exponent");
+    pub static XP_EXPONENT: Tunable =
+        Tunable::new("player.xp_exponent", 1.5, 1.0, 3.0, "XP curve steepness: higher levels cost more");
+
+    pub static MULTICLASS_GROWTH: Tunable = Tunable::new(
+        "player.multiclass_growth",
+        0.8,
+        0.1,
+        1.0,
+        "Share of two archetypes' averaged growth a multiclass character gains per level",
+    );
+    pub static RESPEC_COST: Tunable =
+        Tunable::new("player.respec_cost", 50.0, 0.0, 1000.0, "Gold per character level to change archetype");
+
+    pub static ALL: [&Tunable; 7] = [
+        &DODGE_SPEED,
+        &DODGE_DURATION,
+        &DODGE_COOLDOWN,
+        &XP_BASE,
+        &XP_EXPONENT,
+        &MULTICLASS_GROWTH,
+        &RESPEC_COST,
+    ];
+}
+
+/// NPC awareness, aggro and barks
+pub mod npc {
+    use super::Tunable;
+
+    pub static THREAT_DECAY: Tunable = Tunable::new(
+        "npc.threat_decay",
+        0.5,
+        0.0,
+        5.0,
+        "Fraction of threat lost per second while an attacker is out of range",
+    );
+    pub static INVESTIGATE_LINGER: Tunable = Tunable::new(
+        "npc.investigate_linger",
+        4.0,
+        0.0,
+        30.0,
+        "Seconds an NPC looks around at a noise before going back to its routine",
+    );
+    pub static AWARENESS_GAIN: Tunable =
+        Tunable::new("npc.awareness_gain", 1.5, 0.1, 10.0, "Awareness gained per second at full sight or noise");
+    pub static AWARENESS_DECAY: Tunable =
+        Tunable::new("npc.awareness_decay", 0.2, 0.0, 5.0, "Awareness lost per second with no stimulus");
+
+    pub static BARK_RANGE: Tunable =
+        Tunable::new("npc.bark_range", 8.0, 1.0, 30.0, "Distance in metres at which NPCs call out to the player");
+    pub static BARK_COOLDOWN: Tunable =
+        Tunable::new("npc.bark_cooldown", 20.0, 0.0, 300.0, "Seconds an NPC stays quiet after a bark");
+
+    pub static ALL: [&Tunable; 6] = [
+        &THREAT_DECAY,
+        &INVESTIGATE_LINGER,
+        &AWARENESS_GAIN,
+        &AWARENESS_DECAY,
+        &BARK_RANGE,
+        &BARK_COOLDOWN,
+    ];
+}
+
+/// Add every balance value to `registry`
+pub fn register_all(registry: &mut TunableRegistry) {
+    registry.register(&combat::ALL);
+    registry.register(&player::ALL);
+    registry.register(&npc::ALL);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_balance_file_matches_the_tunables() {
+        let mut registry = TunableRegistry::new();
+        register_all(&mut registry);
+        let count = combat::ALL.len() + player::ALL.len() + npc::ALL.len();
+        assert_eq!(registry.iter().count(), count, "names are unique");
+
+        for tunable in registry.iter() {
+            let (min, max) = tunable.range();
+            assert!(min <= tunable.default_value() && tunable.default_value() <= max, "{}", tunable.name());
+        }
+
+        // The shipped file holds the defaults, so loading it changes nothing
+        let report = registry.load_str(include_str!("../../../assets/balance.ron")).unwrap();
+        assert_eq!(report.applied, count);
+        assert!(report.unknown.is_empty(), "{:?}", report.unknown);
+        assert!(registry.iter().all(Tunable::is_default));
+    }
+}
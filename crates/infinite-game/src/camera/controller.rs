@@ -1,11 +1,11 @@
 //! Camera controller with mouse look and zoom
 
 use glam::{Mat4, Quat, Vec2, Vec3};
-use infinite_physics::PhysicsWorld;
-use rapier3d::prelude::QueryFilter;
+use infinite_physics::{CastFilter, EntityId, PhysicsWorld};
 
 use crate::input::InputState;
 
+use super::dialogue::{ease, DialogueShot, DIALOGUE_BLEND_TIME};
 use super::CameraConfig;
 
 /// Camera mode (first-person or third-person)
@@ -46,10 +46,20 @@ pub struct CameraController {
     target_distance: f32,
     /// Current interpolated zoom distance
     current_distance: f32,
+    /// Extra third-person distance requested by gameplay (e.g. climbing)
+    target_distance_offset: f32,
+    /// Current interpolated extra distance
+    distance_offset: f32,
     /// Camera world position (computed each frame)
     position: Vec3,
     /// Target position we're looking at
     target: Vec3,
+    /// Conversation being framed; kept after it ends to blend back out
+    dialogue_shot: Option<DialogueShot>,
+    /// Whether a conversation is being framed
+    in_dialogue: bool,
+    /// 0 = gameplay camera, 1 = fully in the dialogue shot (linear)
+    dialogue_blend: f32,
 }
 
 impl CameraController {
@@ -70,8 +80,13 @@ impl CameraController {
             pitch: 0.0,
             target_distance: default_distance,
             current_distance: default_distance,
+            target_distance_offset: 0.0,
+            distance_offset: 0.0,
             position: Vec3::ZERO,
             target: Vec3::ZERO,
+            dialogue_shot: None,
+            in_dialogue: false,
+            dialogue_blend: 0.0,
         }
     }
 
@@ -150,13 +165,12 @@ impl CameraController {
         physics: Option<&PhysicsWorld>,
         dt: f32,
     ) {
-        // Handle mouse look
-        if input.cursor_captured {
+        // Mouse look and zoom wait until the conversation is over
+        if input.cursor_captured && !self.in_dialogue {
             self.handle_mouse_look(input.mouse_delta);
         }
 
-        // Handle zoom
-        if input.scroll_delta.abs() > 0.0 {
+        if input.scroll_delta.abs() > 0.0 && !self.in_dialogue {
             self.handle_zoom(input.scroll_delta);
         }
 
@@ -164,6 +178,7 @@ impl CameraController {
         let zoom_lerp = 1.0 - (1.0 - self.config.zoom_smoothing).powf(dt * 60.0);
         self.current_distance =
             self.current_distance + (self.target_distance - self.current_distance) * zoom_lerp;
+        self.distance_offset += (self.target_distance_offset - self.distance_offset) * zoom_lerp;
 
         // Snap to first-person if below threshold
         if self.current_distance < self.config.fps_threshold {
@@ -185,6 +200,8 @@ impl CameraController {
                 self.position = player_eye_position;
             }
             CameraMode::ThirdPerson { distance } => {
+                let distance = distance + self.distance_offset;
+
                 // Calculate offset direction (opposite of look direction)
                 let offset_dir = Vec3::new(
                     -self.yaw.sin() * self.pitch.cos(),
@@ -198,20 +215,21 @@ impl CameraController {
                 // Calculate ideal camera position
                 let ideal_position = player_eye_position + shoulder + offset_dir * distance;
 
-                // Check for collision with world geometry
+                // Sweep the camera's sphere out from the player for world geometry
                 if let Some(physics) = physics {
                     let ray_start = player_eye_position + shoulder;
                     let ray_dir = (ideal_position - ray_start).normalize();
-                    let ray_length = distance + self.config.collision_radius;
+                    let filter = CastFilter::excluding_entity(EntityId::PLAYER);
 
-                    if let Some((_handle, toi)) = physics.raycast(
+                    if let Some(hit) = physics.cast_sphere(
                         ray_start,
+                        self.config.collision_radius,
                         ray_dir,
-                        ray_length,
-                        QueryFilter::default(),
+                        distance,
+                        filter.query(),
                     ) {
                         // Camera would clip - move it closer
-                        let safe_distance = (toi - self.config.collision_radius).max(0.5);
+                        let safe_distance = hit.distance.max(0.5);
                         self.position = ray_start + ray_dir * safe_distance;
                     } else {
                         self.position = ideal_position;
@@ -221,6 +239,58 @@ impl CameraController {
                 }
             }
         }
+
+        self.blend_dialogue_shot(physics, dt);
+    }
+
+    /// Blend the conversation shot over the gameplay camera
+    fn blend_dialogue_shot(&mut self, physics: Option<&PhysicsWorld>, dt: f32) {
+        let step = dt / DIALOGUE_BLEND_TIME;
+        self.dialogue_blend = if self.in_dialogue {
+            (self.dialogue_blend + step).min(1.0)
+        } else {
+            (self.dialogue_blend - step).max(0.0)
+        };
+        let Some(shot) = self.dialogue_shot.filter(|_| self.dialogue_blend > 0.0) else {
+            self.dialogue_shot = None;
+            return;
+        };
+
+        let (mut position, target) = shot.pose();
+        // Keep the shot out of walls like the orbit camera
+        if let Some(physics) = physics {
+            let offset = position - shot.player_eye;
+            let length = offset.length();
+            let filter = CastFilter::excluding_entity(EntityId::PLAYER);
+            if let Some(hit) = physics.cast_sphere(
+                shot.player_eye,
+                self.config.collision_radius,
+                offset / length,
+                length,
+                filter.query(),
+            ) {
+                position = shot.player_eye + offset / length * hit.distance.max(0.2);
+            }
+        }
+
+        let t = ease(self.dialogue_blend);
+        self.position = self.position.lerp(position, t);
+        self.target = self.target.lerp(target, t);
+    }
+
+    /// Frame a conversation (call each frame while talking, None once it
+    /// ends). The camera blends in and back out on its own.
+    pub fn set_dialogue_shot(&mut self, shot: Option<DialogueShot>) {
+        self.in_dialogue = shot.is_some();
+        if shot.is_some() {
+            self.dialogue_shot = shot;
+        }
+    }
+
+    /// How far into the dialogue shot the camera is (0-1, eased), for
+    /// letterboxing
+    pub fn dialogue_blend(&self) -> f32 {
+        ease(self.dialogue_blend)
     }
 
     /// Set the camera yaw directly
@@ -248,6 +318,11 @@ impl CameraController {
         }
     }
 
+    /// Pull the third-person camera back by an extra distance (smoothed)
+    pub fn set_distance_offset(&mut self, offset: f32) {
+        self.target_distance_offset = offset.max(0.0);
+    }
+
     /// Toggle between first and third person
     pub fn toggle_perspective(&mut self) {
         match self.mode {
@@ -290,6 +365,35 @@ mod tests {
         assert!(camera.mode.is_first_person());
     }
 
+    #[test]
+    fn test_dialogue_shot_blends_in_and_restores_the_camera() {
+        let mut camera = CameraController::new();
+        camera.set_yaw(0.5);
+        let eye = Vec3::new(0.0, 1.7, 0.0);
+        let mut input = InputState::new();
+        input.cursor_captured = true;
+        camera.update(&input, eye, None, 0.016);
+        let gameplay = (camera.position(), camera.target());
+
+        let shot = DialogueShot { player_eye: eye, npc_head: Vec3::new(3.0, 1.6, 0.0) };
+        camera.set_dialogue_shot(Some(shot));
+        input.mouse_delta = Vec2::new(200.0, 0.0);
+        camera.update(&input, eye, None, DIALOGUE_BLEND_TIME * 0.5);
+        assert!(camera.dialogue_blend() > 0.0 && camera.dialogue_blend() < 1.0);
+        camera.update(&input, eye, None, DIALOGUE_BLEND_TIME);
+        assert_eq!(camera.dialogue_blend(), 1.0);
+        assert!((camera.position() - shot.pose().0).length() < 1e-4);
+        // Looking around is ignored mid-conversation
+        assert_eq!(camera.yaw, 0.5);
+
+        camera.set_dialogue_shot(None);
+        input.mouse_delta = Vec2::ZERO;
+        camera.update(&input, eye, None, DIALOGUE_BLEND_TIME);
+        assert_eq!(camera.dialogue_blend(), 0.0);
+        assert!((camera.position() - gameplay.0).length() < 1e-4);
+        assert!((camera.target() - gameplay.1).length() < 1e-4);
+    }
+
     #[test]
     fn test_camera_pitch_clamping() {
         let mut camera = CameraController::new();
@@ -0,0 +1,64 @@
+//! Dialogue camera — an over-the-shoulder two-shot while talking to an NPC
+//!
+//! The gameplay camera keeps its yaw, pitch and zoom underneath the shot,
+//! which is blended over it on the way in and out, so leaving a
+//! conversation lands exactly where the player was looking.
+
+use glam::Vec3;
+
+/// Seconds to blend fully into or out of the shot
+pub const DIALOGUE_BLEND_TIME: f32 = 0.6;
+/// How far behind the player's eye the camera sits
+const SHOT_BACK: f32 = 1.4;
+/// How far to the player's right
+const SHOT_SIDE: f32 = 0.7;
+/// How far above the eye
+const SHOT_RISE: f32 = 0.2;
+/// Where the shot looks, from the player (0) to the NPC (1)
+const SHOT_FOCUS: f32 = 0.65;
+
+/// The two people in a conversation shot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DialogueShot {
+    pub player_eye: Vec3,
+    pub npc_head: Vec3,
+}
+
+impl DialogueShot {
+    /// Camera position and look target: behind the player's right
+    /// shoulder, looking past them at the NPC
+    pub fn pose(&self) -> (Vec3, Vec3) {
+        let dir = (self.npc_head - self.player_eye).with_y(0.0).try_normalize().unwrap_or(Vec3::NEG_Z);
+        let right = dir.cross(Vec3::Y);
+        let position = self.player_eye - dir * SHOT_BACK + right * SHOT_SIDE + Vec3::Y * SHOT_RISE;
+        (position, self.player_eye.lerp(self.npc_head, SHOT_FOCUS))
+    }
+}
+
+/// Smoothstep easing for the blend
+pub(super) fn ease(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_shot_frames_the_npc_over_the_shoulder() {
+        let shot = DialogueShot {
+            player_eye: Vec3::new(0.0, 1.7, 0.0),
+            npc_head: Vec3::new(0.0, 1.6, -3.0),
+        };
+        let (position, target) = shot.pose();
+        // Behind the player (NPC is towards -Z), off to the right (+X), a little higher
+        assert!(position.z > 1.0);
+        assert!(position.x > 0.5);
+        assert!(position.y > shot.player_eye.y);
+        // Looking at a point between the two, nearer the NPC
+        assert!(target.z < -1.5 && target.z > -3.0);
+        let to_npc = (shot.npc_head - position).normalize();
+        assert!((target - position).normalize().dot(to_npc) > 0.95);
+    }
+}
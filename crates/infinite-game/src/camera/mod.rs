@@ -1,9 +1,12 @@
 //! Camera system module
 //!
-//! Provides first-person and third-person camera with mouse look and zoom.
+//! Provides first-person and third-person camera with mouse look and zoom,
+//! and a framed shot for conversations.
 
 mod config;
 mod controller;
+mod dialogue;
 
 pub use config::CameraConfig;
 pub use controller::{CameraController, CameraMode};
+pub use dialogue::{DialogueShot, DIALOGUE_BLEND_TIME};
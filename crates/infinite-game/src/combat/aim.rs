@@ -0,0 +1,134 @@
+//! Aiming — crosshair spread for ranged weapons, and soft aim assist
+//!
+//! Spread blooms with every shot and while moving, then settles back once
+//! the player holds still. Aim assist picks the target nearest the
+//! crosshair inside a small cone and turns the camera part of the way
+//! towards it each frame.
+
+use glam::Vec3;
+
+use crate::balance;
+
+/// Spread standing still and rested, in degrees
+pub const MIN_SPREAD: f32 = 1.0;
+/// Spread never blooms past this
+pub const MAX_SPREAD: f32 = 12.0;
+/// Spread kept up while moving
+const MOVING_SPREAD: f32 = 4.0;
+/// Farthest target aim assist reaches for, in metres
+pub const AIM_ASSIST_RANGE: f32 = 40.0;
+
+/// Current crosshair spread (full cone angle, degrees)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AimSpread {
+    current: f32,
+}
+
+impl AimSpread {
+    pub fn new() -> Self {
+        Self { current: MIN_SPREAD }
+    }
+
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// Bloom from a shot
+    pub fn fire(&mut self) {
+        self.current = (self.current + balance::combat::SPREAD_PER_SHOT.get()).min(MAX_SPREAD);
+    }
+
+    /// Settle towards the resting spread; moving holds it open
+    pub fn update(&mut self, dt: f32, moving: bool) {
+        let floor = if moving { MOVING_SPREAD } else { MIN_SPREAD };
+        self.current = (self.current - balance::combat::SPREAD_RECOVERY.get() * dt).max(floor);
+    }
+}
+
+impl Default for AimSpread {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The candidate nearest the aim line from `eye` along `forward`, within a
+/// cone `cone` degrees wide and `range` metres long
+pub fn assist_target(eye: Vec3, forward: Vec3, cone: f32, range: f32, candidates: impl IntoIterator<Item = Vec3>) -> Option<Vec3> {
+    let half = (cone * 0.5).to_radians();
+    candidates
+        .into_iter()
+        .filter(|target| (*target - eye).length() <= range)
+        .map(|target| (target, forward.angle_between(target - eye)))
+        .filter(|(_, angle)| *angle <= half)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(target, _)| target)
+}
+
+/// Yaw and pitch changes (radians) that turn a camera looking along
+/// `forward` `fraction` of the way to looking along `to_target`
+pub fn assist_turn(forward: Vec3, to_target: Vec3, fraction: f32) -> (f32, f32) {
+    let angles = |v: Vec3| {
+        let v = v.normalize_or_zero();
+        (v.x.atan2(-v.z), v.y.clamp(-1.0, 1.0).asin())
+    };
+    let (yaw, pitch) = angles(forward);
+    let (target_yaw, target_pitch) = angles(to_target);
+    let d_yaw = (target_yaw - yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    let fraction = fraction.clamp(0.0, 1.0);
+    (d_yaw * fraction, (target_pitch - pitch) * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_blooms_and_settles() {
+        let mut spread = AimSpread::new();
+        assert_eq!(spread.current(), MIN_SPREAD);
+        for _ in 0..20 {
+            spread.fire();
+        }
+        assert_eq!(spread.current(), MAX_SPREAD);
+
+        spread.update(0.5, true);
+        assert!(spread.current() < MAX_SPREAD);
+        for _ in 0..100 {
+            spread.update(0.1, true);
+        }
+        assert_eq!(spread.current(), MOVING_SPREAD);
+        for _ in 0..100 {
+            spread.update(0.1, false);
+        }
+        assert_eq!(spread.current(), MIN_SPREAD);
+    }
+
+    #[test]
+    fn test_assist_picks_target_nearest_the_crosshair() {
+        let eye = Vec3::ZERO;
+        let forward = Vec3::NEG_Z;
+        let near_line = Vec3::new(0.5, 0.0, -20.0);
+        let off_line = Vec3::new(1.5, 0.0, -10.0);
+        let behind = Vec3::new(0.0, 0.0, 5.0);
+        let too_far = Vec3::new(0.0, 0.0, -100.0);
+        let candidates = [off_line, near_line, behind, too_far];
+
+        assert_eq!(assist_target(eye, forward, 20.0, 40.0, candidates), Some(near_line));
+        // A tighter cone excludes everything
+        assert_eq!(assist_target(eye, forward, 1.0, 40.0, candidates), None);
+    }
+
+    #[test]
+    fn test_assist_turns_part_way() {
+        // Target 90 degrees to the right (+X) and level
+        let (d_yaw, d_pitch) = assist_turn(Vec3::NEG_Z, Vec3::X, 0.5);
+        assert!((d_yaw - std::f32::consts::FRAC_PI_4).abs() < 1e-4);
+        assert!(d_pitch.abs() < 1e-4);
+
+        // Turning across the -PI/PI seam takes the short way round
+        let behind_left = Vec3::new(-0.1, 0.0, 1.0);
+        let behind_right = Vec3::new(0.1, 0.0, 1.0);
+        let (d_yaw, _) = assist_turn(behind_left, behind_right, 1.0);
+        assert!(d_yaw.abs() < 0.3, "turned {}", d_yaw);
+    }
+}
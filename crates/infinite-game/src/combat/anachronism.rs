@@ -0,0 +1,307 @@
+//! Anachronism rules — gear out of its own time strains the timeline
+//!
+//! Items remember the year they were made in. Using something from far in
+//! the future of the year the player is standing in builds paradox, and
+//! wearing it keeps building it slowly. As the meter fills the world pushes
+//! back: locals grow uneasy, then temporal anomalies start to appear. The
+//! meter drains on its own once the player stops straining it.
+
+use serde::{Deserialize, Serialize};
+
+use super::item::Item;
+
+/// Years ahead of the present (past the tolerance) at which an item is as
+/// anachronistic as it gets
+pub const ANACHRONISM_SPAN: f32 = 3000.0;
+
+/// Full paradox meter
+pub const MAX_PARADOX: f32 = 100.0;
+
+/// Paradox from one use of a fully anachronistic item
+const USE_BUILDUP: f32 = 4.0;
+
+/// Paradox per second from wearing a fully anachronistic item
+const WORN_BUILDUP: f32 = 0.15;
+
+/// Seconds without new paradox before the meter starts draining
+const CALM_DELAY: f32 = 10.0;
+
+/// Gameplay settings for the anachronism rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParadoxConfig {
+    /// Whether out-of-era items build paradox at all
+    pub enabled: bool,
+    /// How far ahead of the present an item may come from before it counts
+    pub tolerance_years: u32,
+    /// Multiplier on all paradox buildup
+    pub buildup_scale: f32,
+    /// Paradox drained per second once things are calm
+    pub decay_per_second: f32,
+}
+
+impl Default for ParadoxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tolerance_years: 300,
+            buildup_scale: 1.0,
+            decay_per_second: 2.0,
+        }
+    }
+}
+
+/// How out of place an item from `origin_year` is in `year`, from 0 (fits
+/// right in) to 1. Only items from the future count; undated items fit any
+/// era.
+pub fn anachronism(origin_year: Option<i64>, year: i64, config: &ParadoxConfig) -> f32 {
+    let Some(origin) = origin_year else {
+        return 0.0;
+    };
+    if !config.enabled {
+        return 0.0;
+    }
+    let ahead = origin - year - config.tolerance_years as i64;
+    if ahead <= 0 {
+        0.0
+    } else {
+        (ahead as f32 / ANACHRONISM_SPAN).min(1.0)
+    }
+}
+
+/// How badly the timeline is straining
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ParadoxStage {
+    Stable,
+    /// Locals sense something is off
+    Ripples,
+    /// Anomalies start tearing through
+    Anomalies,
+    /// The era is coming apart around the player
+    Unraveling,
+}
+
+impl ParadoxStage {
+    pub fn from_level(level: f32) -> Self {
+        if level >= 80.0 {
+            Self::Unraveling
+        } else if level >= 50.0 {
+            Self::Anomalies
+        } else if level >= 25.0 {
+            Self::Ripples
+        } else {
+            Self::Stable
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Stable => "Stable",
+            Self::Ripples => "Ripples",
+            Self::Anomalies => "Anomalies",
+            Self::Unraveling => "Unraveling",
+        }
+    }
+
+    /// Seconds between consequences at this stage
+    fn consequence_interval(self) -> Option<f32> {
+        match self {
+            Self::Stable => None,
+            Self::Ripples => Some(30.0),
+            Self::Anomalies => Some(20.0),
+            Self::Unraveling => Some(10.0),
+        }
+    }
+
+    fn consequences(self) -> &'static [ParadoxEvent] {
+        match self {
+            Self::Stable => &[],
+            Self::Ripples => &[ParadoxEvent::NpcsUnsettled],
+            Self::Anomalies => &[ParadoxEvent::SpawnAnomaly],
+            Self::Unraveling => &[ParadoxEvent::NpcsUnsettled, ParadoxEvent::SpawnAnomaly],
+        }
+    }
+}
+
+/// What the caller should do after the meter changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParadoxEvent {
+    StageChanged { from: ParadoxStage, to: ParadoxStage },
+    /// Nearby NPCs noticed something isn't right
+    NpcsUnsettled,
+    /// A temporal anomaly should appear near the player
+    SpawnAnomaly,
+}
+
+/// Paradox buildup from anachronistic items
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParadoxMeter {
+    level: f32,
+    #[serde(skip)]
+    calm_time: f32,
+    #[serde(skip)]
+    consequence_timer: f32,
+    #[serde(skip)]
+    events: Vec<ParadoxEvent>,
+}
+
+impl ParadoxMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current paradox, 0..=`MAX_PARADOX`
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Current paradox as a fraction of the full meter
+    pub fn fraction(&self) -> f32 {
+        self.level / MAX_PARADOX
+    }
+
+    pub fn stage(&self) -> ParadoxStage {
+        ParadoxStage::from_level(self.level)
+    }
+
+    /// The player used `item` (a swing, a consumable) in `year`. Returns the
+    /// item's anachronism.
+    pub fn on_item_used(&mut self, item: &Item, year: i64, config: &ParadoxConfig) -> f32 {
+        let severity = anachronism(item.origin_year, year, config);
+        self.add(USE_BUILDUP * severity * config.buildup_scale);
+        severity
+    }
+
+    /// Advance by `dt` seconds with `worn` equipped in `year`
+    pub fn update<'a>(&mut self, dt: f32, worn: impl IntoIterator<Item = &'a Item>, year: i64, config: &ParadoxConfig) {
+        if !config.enabled {
+            self.set_level(0.0);
+            self.consequence_timer = 0.0;
+            return;
+        }
+
+        let strain: f32 = worn.into_iter().map(|item| anachronism(item.origin_year, year, config)).sum();
+        if strain > 0.0 {
+            self.add(strain * WORN_BUILDUP * config.buildup_scale * dt);
+        } else {
+            self.calm_time += dt;
+            if self.calm_time >= CALM_DELAY {
+                self.set_level(self.level - config.decay_per_second * dt);
+            }
+        }
+
+        let stage = self.stage();
+        match stage.consequence_interval() {
+            Some(interval) => {
+                self.consequence_timer += dt;
+                if self.consequence_timer >= interval {
+                    self.consequence_timer = 0.0;
+                    self.events.extend_from_slice(stage.consequences());
+                }
+            }
+            None => self.consequence_timer = 0.0,
+        }
+    }
+
+    /// Events since the last drain, oldest first
+    pub fn drain_events(&mut self) -> Vec<ParadoxEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn add(&mut self, amount: f32) {
+        if amount <= 0.0 {
+            return;
+        }
+        self.calm_time = 0.0;
+        self.set_level(self.level + amount);
+    }
+
+    fn set_level(&mut self, level: f32) {
+        let from = self.stage();
+        self.level = level.clamp(0.0, MAX_PARADOX);
+        let to = self.stage();
+        if from != to {
+            self.events.push(ParadoxEvent::StageChanged { from, to });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::starter_items::create_torch;
+
+    fn item_from(year: i64) -> Item {
+        let mut item = create_torch();
+        item.origin_year = Some(year);
+        item
+    }
+
+    #[test]
+    fn test_anachronism_only_counts_the_future() {
+        let config = ParadoxConfig::default();
+        assert_eq!(anachronism(None, -3000, &config), 0.0);
+        assert_eq!(anachronism(Some(-5000), 2000, &config), 0.0);
+        assert_eq!(anachronism(Some(2200), 2000, &config), 0.0, "within tolerance");
+        assert!((anachronism(Some(1800), 0, &config) - 0.5).abs() < 1e-6);
+        assert_eq!(anachronism(Some(3000), -3000, &config), 1.0);
+
+        let disabled = ParadoxConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(anachronism(Some(3000), -3000, &disabled), 0.0);
+    }
+
+    #[test]
+    fn test_future_gear_escalates_then_calms() {
+        let config = ParadoxConfig::default();
+        let blaster = item_from(3000);
+        let mut meter = ParadoxMeter::new();
+
+        for _ in 0..7 {
+            meter.on_item_used(&blaster, -3000, &config);
+        }
+        assert_eq!(meter.stage(), ParadoxStage::Ripples);
+        assert_eq!(
+            meter.drain_events(),
+            vec![ParadoxEvent::StageChanged {
+                from: ParadoxStage::Stable,
+                to: ParadoxStage::Ripples
+            }]
+        );
+
+        // Wearing it keeps the pressure on, and the locals notice
+        for _ in 0..31 {
+            meter.update(1.0, [&blaster], -3000, &config);
+        }
+        assert!(meter.drain_events().contains(&ParadoxEvent::NpcsUnsettled));
+
+        // Put away, the meter drains once things have been calm a while
+        let level = meter.level();
+        meter.update(1.0, [], -3000, &config);
+        assert_eq!(meter.level(), level);
+        for _ in 0..30 {
+            meter.update(1.0, [], -3000, &config);
+        }
+        assert_eq!(meter.level(), 0.0);
+        assert_eq!(meter.stage(), ParadoxStage::Stable);
+    }
+
+    #[test]
+    fn test_high_paradox_spawns_anomalies() {
+        let config = ParadoxConfig::default();
+        let blaster = item_from(5000);
+        let mut meter = ParadoxMeter::new();
+        for _ in 0..13 {
+            meter.on_item_used(&blaster, -3000, &config);
+        }
+        assert_eq!(meter.stage(), ParadoxStage::Anomalies);
+        meter.drain_events();
+
+        for _ in 0..20 {
+            meter.update(1.0, [&blaster], -3000, &config);
+        }
+        assert!(meter.drain_events().contains(&ParadoxEvent::SpawnAnomaly));
+    }
+}
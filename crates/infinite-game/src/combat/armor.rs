@@ -0,0 +1,130 @@
+//! Armor: damage mitigation and durability
+//!
+//! Armor pieces carry an armor value that reduces incoming damage by a
+//! percentage with diminishing returns. Physical hits are reduced by the full
+//! armor value; elemental hits only by a fraction of it, with the rest left to
+//! per-element resistances. Armor wears down with every hit taken and only
+//! gives part of its protection once worn out, until repaired.
+
+use serde::{Deserialize, Serialize};
+
+use super::durability::Durable;
+use super::element::Element;
+
+/// Armor at which damage is reduced by half (before the cap)
+pub const ARMOR_SCALE: f32 = 50.0;
+
+/// Highest fraction of damage armor can prevent
+pub const MAX_ARMOR_REDUCTION: f32 = 0.75;
+
+/// Fraction of armor that counts against elemental damage
+pub const ELEMENTAL_ARMOR_FACTOR: f32 = 0.25;
+
+/// Durability each equipped armor piece loses per hit taken
+pub const DURABILITY_LOSS_PER_HIT: f32 = 1.0;
+
+/// Armor-specific data (for armor pieces)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ArmorData {
+    /// Armor value while intact
+    pub armor: f32,
+    pub durability: f32,
+    pub max_durability: f32,
+}
+
+impl ArmorData {
+    /// New armor piece at full durability
+    pub fn new(armor: f32, max_durability: f32) -> Self {
+        Self {
+            armor,
+            durability: max_durability,
+            max_durability,
+        }
+    }
+
+    /// Armor for items that only specify a defense stat (e.g. server items)
+    pub fn from_defense(defense: f32) -> Self {
+        Self::new(defense * 3.0, 100.0)
+    }
+
+    /// Armor value that currently applies (reduced when worn out)
+    pub fn effective_armor(&self) -> f32 {
+        self.armor * self.effectiveness()
+    }
+}
+
+impl Durable for ArmorData {
+    fn durability(&self) -> f32 {
+        self.durability
+    }
+
+    fn max_durability(&self) -> f32 {
+        self.max_durability
+    }
+
+    fn set_durability(&mut self, durability: f32) {
+        self.durability = durability;
+    }
+}
+
+/// Fraction of damage prevented by an armor value
+pub fn armor_reduction(armor: f32) -> f32 {
+    let armor = armor.max(0.0);
+    (armor / (armor + ARMOR_SCALE)).min(MAX_ARMOR_REDUCTION)
+}
+
+/// Damage after armor and resistance. Physical damage is reduced by the full
+/// armor value; elemental damage by [`ELEMENTAL_ARMOR_FACTOR`] of it, then by
+/// the flat resistance to that element.
+pub fn mitigate(damage: f32, element: Element, armor: f32, resistance: f32) -> f32 {
+    if element == Element::Physical {
+        damage * (1.0 - armor_reduction(armor))
+    } else {
+        let reduced = damage * (1.0 - armor_reduction(armor * ELEMENTAL_ARMOR_FACTOR));
+        (reduced - resistance).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::durability::WORN_OUT_EFFECTIVENESS;
+    use crate::combat::item::ItemRarity;
+
+    #[test]
+    fn test_armor_reduction_diminishes_and_caps() {
+        assert_eq!(armor_reduction(0.0), 0.0);
+        assert!((armor_reduction(ARMOR_SCALE) - 0.5).abs() < 1e-5);
+        assert_eq!(armor_reduction(10_000.0), MAX_ARMOR_REDUCTION);
+        assert!(armor_reduction(20.0) - armor_reduction(10.0) > armor_reduction(60.0) - armor_reduction(50.0));
+    }
+
+    #[test]
+    fn test_physical_vs_elemental_mitigation() {
+        let physical = mitigate(100.0, Element::Physical, 50.0, 0.0);
+        let fire = mitigate(100.0, Element::Fire, 50.0, 0.0);
+        assert!((physical - 50.0).abs() < 1e-4);
+        assert!(fire > physical);
+        // Resistance only applies to elemental damage
+        assert!(mitigate(100.0, Element::Fire, 50.0, 10.0) < fire - 9.9);
+        assert_eq!(mitigate(100.0, Element::Physical, 50.0, 10.0), physical);
+    }
+
+    #[test]
+    fn test_durability_wear_out_and_repair() {
+        let mut armor = ArmorData::new(20.0, 2.0);
+        assert!(!armor.wear(1.0));
+        assert_eq!(armor.effective_armor(), 20.0);
+        assert!(armor.wear(1.0));
+        assert!(armor.is_worn_out());
+        assert_eq!(armor.effective_armor(), 20.0 * WORN_OUT_EFFECTIVENESS);
+        // Already worn out: not reported again
+        assert!(!armor.wear(1.0));
+
+        assert_eq!(armor.repair_cost(ItemRarity::Common), 1);
+        assert_eq!(armor.repair_cost(ItemRarity::Legendary), 5);
+        armor.repair();
+        assert_eq!(armor.durability_fraction(), 1.0);
+        assert_eq!(armor.repair_cost(ItemRarity::Common), 0);
+    }
+}
@@ -10,8 +10,9 @@ pub struct ItemCatalog {
     items: Vec<Item>,
     /// Price per item (index-aligned with items)
     prices: Vec<u64>,
-    /// Server item_id for each item (index-aligned)
-    server_item_ids: Vec<String>,
+    /// Server item_id for each item (index-aligned); `None` for items
+    /// added locally, e.g. by mods
+    server_item_ids: Vec<Option<String>>,
 }
 
 impl ItemCatalog {
@@ -26,7 +27,7 @@ impl ItemCatalog {
             if let Some(game_item) = server_to_game_item(si) {
                 // Price comes from server; round to u64, minimum 1
                 let price = (si.price as u64).max(1);
-                server_item_ids.push(si.item_id.clone());
+                server_item_ids.push(Some(si.item_id.clone()));
                 items.push(game_item);
                 prices.push(price);
             }
@@ -52,7 +53,27 @@ impl ItemCatalog {
     /// Server item_id of the item at `index`.
     #[allow(dead_code)]
     pub fn server_item_id(&self, index: usize) -> Option<&str> {
-        self.server_item_ids.get(index).map(|s| s.as_str())
+        self.server_item_ids.get(index).and_then(|s| s.as_deref())
+    }
+
+    /// Add an item sold for `price`, replacing the one with the same id.
+    /// Returns whether an item was replaced.
+    pub fn insert(&mut self, item: Item, price: u64) -> bool {
+        let price = price.max(1);
+        match self.items.iter().position(|existing| existing.id == item.id) {
+            Some(index) => {
+                self.items[index] = item;
+                self.prices[index] = price;
+                self.server_item_ids[index] = None;
+                true
+            }
+            None => {
+                self.items.push(item);
+                self.prices.push(price);
+                self.server_item_ids.push(None);
+                false
+            }
+        }
     }
 
     /// Filter items by category, returning `(catalog_index, &Item)` pairs.
@@ -118,6 +139,7 @@ mod tests {
                     item_level: Some(1),
                     required_level: Some(1),
                     game_category: Some("Weapon".to_string()),
+                    origin_year: None,
                 }),
             },
             effects: vec![],
@@ -0,0 +1,157 @@
+//! Curses: debuffs that stay until they're cleansed
+//!
+//! Unlike status effects, curses don't wear off. They survive death and are
+//! saved with the character, and only go away when cleansed: at an old
+//! shrine, by a quest giver who knows the rites, or with purifying salts.
+//! Void enemies (temporal anomalies among them) curse with their hits, and
+//! hex glyphs curse whoever steps on them.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::damage::StatModifiers;
+use super::element::Element;
+use super::item::{Item, ItemCategory, ItemId, ItemRarity};
+
+/// Fraction of max HP a Withering curse takes away
+pub const WITHERING_MAX_HP_LOSS: f32 = 0.25;
+
+/// Chance a hit from a Void enemy curses the player
+pub const VOID_CURSE_CHANCE: f32 = 0.15;
+
+/// Gold a quest giver or shrine asks to lift every curse
+pub const CLEANSE_GOLD_COST: u64 = 150;
+
+/// Item id of purifying salts
+pub const PURIFYING_SALTS_ITEM_ID: ItemId = ItemId(3109);
+
+/// Gold a shop charges for purifying salts
+pub const PURIFYING_SALTS_PRICE: u64 = 300;
+
+/// A lasting curse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CurseKind {
+    /// Reduced max HP
+    Withering,
+    /// No sprinting at night
+    Nightdread,
+}
+
+impl CurseKind {
+    pub const ALL: [CurseKind; 2] = [Self::Withering, Self::Nightdread];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Withering => "Withering",
+            Self::Nightdread => "Nightdread",
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::Withering => format!("-{:.0}% max HP", WITHERING_MAX_HP_LOSS * 100.0),
+            Self::Nightdread => "Can't sprint at night".to_string(),
+        }
+    }
+
+    /// The curse a hit from an enemy of `element` can carry
+    pub fn from_enemy_element(element: Element) -> Option<Self> {
+        (element == Element::Void).then_some(Self::Withering)
+    }
+}
+
+/// The curses on a character
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Curses {
+    active: BTreeSet<CurseKind>,
+}
+
+impl Curses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lay a curse. Returns false if it was already on.
+    pub fn afflict(&mut self, kind: CurseKind) -> bool {
+        self.active.insert(kind)
+    }
+
+    pub fn has(&self, kind: CurseKind) -> bool {
+        self.active.contains(&kind)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = CurseKind> + '_ {
+        self.active.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Lift every curse, returning how many there were
+    pub fn cleanse(&mut self) -> usize {
+        let count = self.active.len();
+        self.active.clear();
+        count
+    }
+
+    /// Stat changes from the curses of a character with `base_max_hp`
+    pub fn modifiers(&self, base_max_hp: f32) -> StatModifiers {
+        let mut modifiers = StatModifiers::default();
+        if self.has(CurseKind::Withering) {
+            modifiers.max_hp = -base_max_hp * WITHERING_MAX_HP_LOSS;
+        }
+        modifiers
+    }
+
+    /// Whether the curses forbid sprinting right now
+    pub fn blocks_sprint(&self, is_night: bool) -> bool {
+        is_night && self.has(CurseKind::Nightdread)
+    }
+}
+
+/// Purifying salts, which lift every curse when used
+pub fn create_purifying_salts(count: u32) -> Item {
+    Item {
+        id: PURIFYING_SALTS_ITEM_ID,
+        name: "Purifying Salts".to_string(),
+        description: "Scattered over yourself, they burn away every curse.".to_string(),
+        category: ItemCategory::Consumable,
+        rarity: ItemRarity::Rare,
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Meta,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 5,
+        origin_year: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curses_last_until_cleansed() {
+        let mut curses = Curses::new();
+        assert!(curses.afflict(CurseKind::Nightdread));
+        assert!(!curses.afflict(CurseKind::Nightdread));
+        assert!(curses.blocks_sprint(true));
+        assert!(!curses.blocks_sprint(false));
+        assert_eq!(curses.modifiers(100.0).max_hp, 0.0);
+
+        assert!(curses.afflict(CurseKind::from_enemy_element(Element::Void).unwrap()));
+        assert_eq!(curses.modifiers(100.0).max_hp, -25.0);
+        assert_eq!(CurseKind::from_enemy_element(Element::Fire), None);
+
+        assert_eq!(curses.cleanse(), 2);
+        assert!(curses.is_empty());
+    }
+}
@@ -40,6 +40,14 @@ impl AttackType {
             Self::Heavy => 0.5,
         }
     }
+
+    /// Display name (used as the damage source in the combat log)
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Light => "Light Attack",
+            Self::Heavy => "Heavy Attack",
+        }
+    }
 }
 
 /// Flat stat bonuses from equipment, gems, buffs, etc.
@@ -93,6 +101,20 @@ impl StatModifiers {
         result.add(other);
         result
     }
+
+    /// Copy of these modifiers with every value multiplied by `factor`
+    pub fn scaled(&self, factor: f32) -> StatModifiers {
+        StatModifiers {
+            max_hp: self.max_hp * factor,
+            attack: self.attack * factor,
+            defense: self.defense * factor,
+            speed: self.speed * factor,
+            crit_chance: self.crit_chance * factor,
+            crit_multiplier: self.crit_multiplier * factor,
+            elemental_damage_bonus: self.elemental_damage_bonus.map(|v| v * factor),
+            elemental_resistance: self.elemental_resistance.map(|v| v * factor),
+        }
+    }
 }
 
 /// Result of a damage calculation
@@ -118,6 +140,9 @@ pub struct DamageEvent {
 ///
 /// Pipeline: base_attack + weapon_damage -> attack_type_mult -> weapon_type_mult
 ///           -> element_mult -> elemental_bonus -> crit -> minus defense -> floor at 1.0
+///
+/// `crit_roll` is a uniform roll in `0.0..1.0` (from the combat RNG stream);
+/// the hit crits when it lands under `crit_chance`.
 #[allow(clippy::too_many_arguments)]
 pub fn calculate_combat_damage(
     base_attack: f32,
@@ -126,6 +151,7 @@ pub fn calculate_combat_damage(
     attack_type: AttackType,
     element: Element,
     crit_chance: f32,
+    crit_roll: f32,
     crit_multiplier: f32,
     elemental_damage_bonus: f32,
     target_defense: f32,
@@ -160,7 +186,7 @@ pub fn calculate_combat_damage(
     let base_amount = damage;
 
     // Critical hit
-    let is_crit = rand::random::<f32>() < crit_chance;
+    let is_crit = crit_roll < crit_chance;
     if is_crit {
         damage *= crit_multiplier;
     }
@@ -225,6 +251,7 @@ mod tests {
             AttackType::Light,  // attack_type
             Element::Physical,  // element
             0.0,                // crit_chance (no crit)
+            0.5,                // crit_roll
             1.5,                // crit_multiplier
             0.0,                // elemental_damage_bonus
             0.0,                // target_defense
@@ -240,7 +267,7 @@ mod tests {
     fn test_damage_pipeline_heavy_attack() {
         let event = calculate_combat_damage(
             10.0, 0.0, None, AttackType::Heavy, Element::Physical,
-            0.0, 1.5, 0.0, 0.0, Element::Physical, 0.0, None,
+            0.0, 0.5, 1.5, 0.0, 0.0, Element::Physical, 0.0, None,
         );
         assert_eq!(event.final_amount, 18.0); // 10 * 1.8
     }
@@ -249,7 +276,7 @@ mod tests {
     fn test_damage_pipeline_element_advantage() {
         let event = calculate_combat_damage(
             10.0, 0.0, None, AttackType::Light, Element::Fire,
-            0.0, 1.5, 0.0, 0.0, Element::Earth, 0.0, None,
+            0.0, 0.5, 1.5, 0.0, 0.0, Element::Earth, 0.0, None,
         );
         assert_eq!(event.final_amount, 13.0); // 10 * 1.3
         assert_eq!(event.element_multiplier, 1.3);
@@ -259,7 +286,7 @@ mod tests {
     fn test_damage_pipeline_defense_reduction() {
         let event = calculate_combat_damage(
             10.0, 0.0, None, AttackType::Light, Element::Physical,
-            0.0, 1.5, 0.0, 10.0, Element::Physical, 0.0, None,
+            0.0, 0.5, 1.5, 0.0, 10.0, Element::Physical, 0.0, None,
         );
         assert_eq!(event.final_amount, 5.0); // 10 - 10*0.5
     }
@@ -268,7 +295,7 @@ mod tests {
     fn test_damage_minimum_floor() {
         let event = calculate_combat_damage(
             1.0, 0.0, None, AttackType::Light, Element::Physical,
-            0.0, 1.5, 0.0, 100.0, Element::Physical, 0.0, None,
+            0.0, 0.5, 1.5, 0.0, 100.0, Element::Physical, 0.0, None,
         );
         assert_eq!(event.final_amount, 1.0); // floor at 1
     }
@@ -277,8 +304,25 @@ mod tests {
     fn test_damage_weapon_weakness() {
         let event = calculate_combat_damage(
             10.0, 0.0, Some(WeaponType::Sword), AttackType::Light, Element::Physical,
-            0.0, 1.5, 0.0, 0.0, Element::Physical, 0.0, Some(WeaponType::Sword),
+            0.0, 0.5, 1.5, 0.0, 0.0, Element::Physical, 0.0, Some(WeaponType::Sword),
         );
         assert_eq!(event.final_amount, 10.0 * 1.1 * 1.5); // weapon_mult * weakness
     }
+
+    #[test]
+    fn test_damage_crit_follows_roll() {
+        let crit = calculate_combat_damage(
+            10.0, 0.0, None, AttackType::Light, Element::Physical,
+            0.25, 0.1, 2.0, 0.0, 0.0, Element::Physical, 0.0, None,
+        );
+        assert!(crit.is_crit);
+        assert_eq!(crit.final_amount, 20.0);
+
+        let normal = calculate_combat_damage(
+            10.0, 0.0, None, AttackType::Light, Element::Physical,
+            0.25, 0.25, 2.0, 0.0, 0.0, Element::Physical, 0.0, None,
+        );
+        assert!(!normal.is_crit);
+        assert_eq!(normal.final_amount, 10.0);
+    }
 }
@@ -0,0 +1,303 @@
+//! Floating damage numbers
+//!
+//! Rapid hits on the same target are batched into one counter that keeps
+//! growing instead of stacking a pile of overlapping numbers. Each number
+//! is launched on a slightly different arc, crits pop in larger, and the
+//! total on screen is capped: when it's full, the least important number
+//! (DoT ticks first, then the oldest) makes room.
+
+use glam::Vec3;
+
+/// Most numbers shown at once
+pub const MAX_DAMAGE_NUMBERS: usize = 24;
+/// Hits on the same target this close together add up into one number
+pub const BATCH_WINDOW: f32 = 0.45;
+/// Seconds a number stays up
+const LIFETIME: f32 = 1.1;
+/// Extra time crits stay up
+const CRIT_LIFETIME_BONUS: f32 = 0.4;
+/// Downward acceleration along the arc
+const GRAVITY: f32 = 3.5;
+/// Seconds of the pop-in scale animation
+const POP_TIME: f32 = 0.18;
+
+/// Batching key for numbers floating over the player
+pub const PLAYER_TARGET: u64 = u64::MAX;
+
+/// What a number reports, which decides its style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    /// Damage the player dealt
+    Outgoing,
+    /// Damage the player took
+    Incoming,
+    Healing,
+    /// Damage-over-time tick (burning, poison, ...)
+    DamageOverTime,
+}
+
+impl DamageKind {
+    /// Eviction priority; higher survives longer when the cap is hit
+    fn priority(self) -> u8 {
+        match self {
+            Self::Incoming => 3,
+            Self::Outgoing => 2,
+            Self::Healing => 1,
+            Self::DamageOverTime => 0,
+        }
+    }
+}
+
+/// One floating number
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageNumber {
+    /// Batching key (an NPC id, or `PLAYER_TARGET`)
+    pub target: u64,
+    pub kind: DamageKind,
+    /// Total of all hits batched into this number
+    pub amount: f32,
+    /// Number of hits batched into this number
+    pub hits: u32,
+    pub is_crit: bool,
+    /// Current world position
+    pub position: Vec3,
+    velocity: Vec3,
+    /// Seconds since the number appeared or last took a hit
+    age: f32,
+    lifetime: f32,
+    /// Seconds since the last hit was added, for batching and the pop animation
+    since_hit: f32,
+}
+
+impl DamageNumber {
+    /// 1.0 while fresh, fading to 0.0 over the last third of its life
+    pub fn alpha(&self) -> f32 {
+        let fade_start = self.lifetime * (2.0 / 3.0);
+        if self.age <= fade_start {
+            1.0
+        } else {
+            (1.0 - (self.age - fade_start) / (self.lifetime - fade_start)).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Scale for the pop when a number appears or grows; crits pop bigger
+    pub fn pop_scale(&self) -> f32 {
+        let peak = if self.is_crit { 0.7 } else { 0.3 };
+        let t = (self.since_hit / POP_TIME).min(1.0);
+        1.0 + peak * (1.0 - t) * (1.0 - t)
+    }
+
+    /// Text to show: "-12" incoming, "+30" healing, "48!" crits, "120 x4"
+    /// for batched hits
+    pub fn label(&self) -> String {
+        let amount = self.amount.round();
+        let mut text = match self.kind {
+            DamageKind::Incoming => format!("-{:.0}", amount),
+            DamageKind::Healing => format!("+{:.0}", amount),
+            DamageKind::Outgoing | DamageKind::DamageOverTime => format!("{:.0}", amount),
+        };
+        if self.is_crit {
+            text.push('!');
+        }
+        if self.hits > 1 {
+            text.push_str(&format!(" x{}", self.hits));
+        }
+        text
+    }
+
+    fn expired(&self) -> bool {
+        self.age >= self.lifetime
+    }
+}
+
+/// All floating numbers on screen
+#[derive(Debug, Clone, Default)]
+pub struct DamageNumbers {
+    numbers: Vec<DamageNumber>,
+    /// Spawn counter, spread into launch directions
+    spawned: u32,
+}
+
+impl DamageNumbers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `amount` at `position` (above the target). Hits of the same kind
+    /// on the same target within `BATCH_WINDOW` add up; crits always get
+    /// their own number.
+    pub fn push(&mut self, target: u64, kind: DamageKind, position: Vec3, amount: f32, is_crit: bool) {
+        if amount <= 0.0 {
+            return;
+        }
+        if !is_crit {
+            let batch = self.numbers.iter_mut().find(|n| {
+                n.target == target && n.kind == kind && !n.is_crit && n.since_hit < BATCH_WINDOW
+            });
+            if let Some(number) = batch {
+                number.amount += amount;
+                number.hits += 1;
+                number.since_hit = 0.0;
+                // Keep it readable while hits keep coming
+                number.age = number.age.min(number.lifetime * 0.3);
+                return;
+            }
+        }
+
+        if self.numbers.len() >= MAX_DAMAGE_NUMBERS {
+            self.evict();
+        }
+        let velocity = self.launch_velocity(kind, is_crit);
+        self.numbers.push(DamageNumber {
+            target,
+            kind,
+            amount,
+            hits: 1,
+            is_crit,
+            position,
+            velocity,
+            age: 0.0,
+            lifetime: LIFETIME + if is_crit { CRIT_LIFETIME_BONUS } else { 0.0 },
+            since_hit: 0.0,
+        });
+    }
+
+    /// Move numbers along their arcs and drop expired ones
+    pub fn update(&mut self, delta: f32) {
+        for number in &mut self.numbers {
+            number.age += delta;
+            number.since_hit += delta;
+            number.velocity.y -= GRAVITY * delta;
+            number.position += number.velocity * delta;
+        }
+        self.numbers.retain(|n| !n.expired());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DamageNumber> {
+        self.numbers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.numbers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.numbers.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.numbers.clear();
+    }
+
+    /// Drop the lowest-priority number, the oldest among equals. Crits rank
+    /// above everything of their kind.
+    fn evict(&mut self) {
+        let victim = self
+            .numbers
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (a.kind.priority(), a.is_crit)
+                    .cmp(&(b.kind.priority(), b.is_crit))
+                    .then(b.age.total_cmp(&a.age))
+            })
+            .map(|(i, _)| i);
+        if let Some(i) = victim {
+            self.numbers.remove(i);
+        }
+    }
+
+    /// Upward launch with a sideways spread so consecutive numbers fan out
+    fn launch_velocity(&mut self, kind: DamageKind, is_crit: bool) -> Vec3 {
+        self.spawned = self.spawned.wrapping_add(1);
+        // Golden-ratio sequence: evenly spread, never repeating
+        let t = (self.spawned as f32 * 0.618_034).fract();
+        let angle = t * std::f32::consts::TAU;
+        let spread = match kind {
+            DamageKind::DamageOverTime => 0.3,
+            _ => 0.9,
+        };
+        let up = if is_crit { 3.4 } else { 2.6 } + t * 0.6;
+        Vec3::new(angle.cos() * spread, up, angle.sin() * spread)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rapid_hits_batch() {
+        let mut numbers = DamageNumbers::new();
+        numbers.push(1, DamageKind::Outgoing, Vec3::ZERO, 10.0, false);
+        numbers.update(0.1);
+        numbers.push(1, DamageKind::Outgoing, Vec3::ZERO, 15.0, false);
+        // Different target, kind, or a crit: separate numbers
+        numbers.push(2, DamageKind::Outgoing, Vec3::ZERO, 5.0, false);
+        numbers.push(1, DamageKind::Incoming, Vec3::ZERO, 5.0, false);
+        numbers.push(1, DamageKind::Outgoing, Vec3::ZERO, 40.0, true);
+        assert_eq!(numbers.len(), 4);
+
+        let batched = numbers.iter().next().unwrap();
+        assert_eq!(batched.amount, 25.0);
+        assert_eq!(batched.label(), "25 x2");
+
+        // Past the window a new number starts
+        numbers.update(BATCH_WINDOW + 0.01);
+        numbers.push(1, DamageKind::Outgoing, Vec3::ZERO, 10.0, false);
+        assert_eq!(numbers.len(), 5);
+    }
+
+    #[test]
+    fn test_labels_and_animation() {
+        let mut numbers = DamageNumbers::new();
+        numbers.push(PLAYER_TARGET, DamageKind::Incoming, Vec3::ZERO, 12.4, false);
+        numbers.push(PLAYER_TARGET, DamageKind::Healing, Vec3::ZERO, 30.0, false);
+        numbers.push(3, DamageKind::Outgoing, Vec3::ZERO, 48.0, true);
+        let labels: Vec<String> = numbers.iter().map(DamageNumber::label).collect();
+        assert_eq!(labels, vec!["-12", "+30", "48!"]);
+
+        let crit = numbers.iter().nth(2).unwrap();
+        let normal = numbers.iter().next().unwrap();
+        assert!(crit.pop_scale() > normal.pop_scale());
+        assert_eq!(crit.alpha(), 1.0);
+
+        numbers.update(0.5);
+        let crit = numbers.iter().nth(2).unwrap();
+        assert_eq!(crit.pop_scale(), 1.0);
+        assert!(crit.position.y > 0.0, "numbers float up first");
+
+        numbers.update(LIFETIME - 0.45);
+        assert_eq!(numbers.len(), 1, "crits stay up longer");
+    }
+
+    #[test]
+    fn test_numbers_fan_out() {
+        let mut numbers = DamageNumbers::new();
+        for target in 0..3 {
+            numbers.push(target, DamageKind::Outgoing, Vec3::ZERO, 1.0, false);
+        }
+        numbers.update(0.5);
+        let positions: Vec<Vec3> = numbers.iter().map(|n| n.position).collect();
+        assert!(positions[0].distance(positions[1]) > 0.1);
+        assert!(positions[1].distance(positions[2]) > 0.1);
+    }
+
+    #[test]
+    fn test_cap_evicts_lowest_priority() {
+        let mut numbers = DamageNumbers::new();
+        numbers.push(0, DamageKind::Incoming, Vec3::ZERO, 1.0, false);
+        numbers.push(1, DamageKind::DamageOverTime, Vec3::ZERO, 1.0, false);
+        for target in 2..MAX_DAMAGE_NUMBERS as u64 + 1 {
+            numbers.push(target, DamageKind::Outgoing, Vec3::ZERO, 1.0, false);
+        }
+        assert_eq!(numbers.len(), MAX_DAMAGE_NUMBERS);
+        assert!(numbers.iter().all(|n| n.kind != DamageKind::DamageOverTime));
+
+        numbers.update(0.2);
+        numbers.push(99, DamageKind::Outgoing, Vec3::ZERO, 1.0, false);
+        // The oldest outgoing number made room; the incoming one survived
+        assert!(numbers.iter().all(|n| n.target != 2));
+        assert!(numbers.iter().any(|n| n.kind == DamageKind::Incoming));
+    }
+}
@@ -0,0 +1,147 @@
+//! Durability: wear on weapons, shields and armor, and repairing it
+//!
+//! Weapons wear with every hit they land, shields with every block and
+//! armor with every hit taken. Worn-out gear never breaks; it just works at
+//! [`WORN_OUT_EFFECTIVENESS`] until a blacksmith or a repair kit restores it.
+
+use super::damage::StatModifiers;
+use super::element::Element;
+use super::item::{Item, ItemCategory, ItemId, ItemRarity};
+
+/// Fraction of its damage, block or armor that worn-out gear still gives
+pub const WORN_OUT_EFFECTIVENESS: f32 = 0.5;
+
+/// Gold per point of durability restored (before rarity)
+pub const REPAIR_COST_PER_POINT: f32 = 0.5;
+
+/// Durability a weapon loses per hit it lands
+pub const WEAPON_WEAR_PER_HIT: f32 = 0.5;
+
+/// Durability a shield loses per hit it blocks
+pub const SHIELD_WEAR_PER_BLOCK: f32 = 1.0;
+
+/// Durability of new weapons and shields
+pub const DEFAULT_MAX_DURABILITY: f32 = 100.0;
+
+/// Item id of repair kits
+pub const REPAIR_KIT_ITEM_ID: ItemId = ItemId(3106);
+
+/// Fraction of each equipped piece's durability a repair kit restores
+pub const REPAIR_KIT_RESTORE: f32 = 0.5;
+
+/// Gold a shop charges for a repair kit
+pub const REPAIR_KIT_PRICE: u64 = 40;
+
+/// Serde default for durability fields added after release
+pub(crate) fn default_max_durability() -> f32 {
+    DEFAULT_MAX_DURABILITY
+}
+
+/// Gear that wears down with use
+pub trait Durable {
+    fn durability(&self) -> f32;
+    fn max_durability(&self) -> f32;
+    fn set_durability(&mut self, durability: f32);
+
+    fn is_worn_out(&self) -> bool {
+        self.durability() <= 0.0
+    }
+
+    /// Durability as a 0.0-1.0 fraction
+    fn durability_fraction(&self) -> f32 {
+        if self.max_durability() <= 0.0 {
+            0.0
+        } else {
+            self.durability() / self.max_durability()
+        }
+    }
+
+    /// Multiplier on what the gear does (1.0 until worn out)
+    fn effectiveness(&self) -> f32 {
+        if self.is_worn_out() {
+            WORN_OUT_EFFECTIVENESS
+        } else {
+            1.0
+        }
+    }
+
+    /// Lose durability. Returns true if this wore the piece out.
+    fn wear(&mut self, amount: f32) -> bool {
+        let was_worn_out = self.is_worn_out();
+        self.set_durability((self.durability() - amount).max(0.0));
+        !was_worn_out && self.is_worn_out()
+    }
+
+    /// Restore `amount` durability, up to the maximum
+    fn restore(&mut self, amount: f32) {
+        self.set_durability((self.durability() + amount).min(self.max_durability()));
+    }
+
+    /// Restore full durability
+    fn repair(&mut self) {
+        self.set_durability(self.max_durability());
+    }
+
+    fn is_damaged(&self) -> bool {
+        self.durability() < self.max_durability()
+    }
+
+    /// Gold to repair to full durability
+    fn repair_cost(&self, rarity: ItemRarity) -> u64 {
+        let missing = (self.max_durability() - self.durability()).max(0.0);
+        let rarity_mult = match rarity {
+            ItemRarity::Common => 1.0,
+            ItemRarity::Uncommon => 1.5,
+            ItemRarity::Rare => 2.0,
+            ItemRarity::Epic => 3.0,
+            ItemRarity::Legendary => 5.0,
+        };
+        (missing * REPAIR_COST_PER_POINT * rarity_mult).ceil() as u64
+    }
+}
+
+/// A stack of repair kits. Each one patches up everything equipped.
+pub fn create_repair_kit(count: u32) -> Item {
+    Item {
+        id: REPAIR_KIT_ITEM_ID,
+        name: "Repair Kit".to_string(),
+        description: "Whetstone, rivets and oil. Restores half the durability of everything you have equipped.".to_string(),
+        category: ItemCategory::Consumable,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 5,
+        origin_year: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::weapon::{WeaponData, WeaponType};
+
+    #[test]
+    fn test_worn_out_gear_weakens_instead_of_breaking() {
+        let mut sword = WeaponData::new(WeaponType::Sword, 10.0);
+        assert_eq!(sword.effectiveness(), 1.0);
+        assert!(!sword.wear(DEFAULT_MAX_DURABILITY - 1.0));
+        assert!(sword.wear(1.0));
+        assert!(sword.is_worn_out());
+        assert_eq!(sword.effectiveness(), WORN_OUT_EFFECTIVENESS);
+        assert!(!sword.wear(1.0));
+
+        sword.restore(DEFAULT_MAX_DURABILITY * REPAIR_KIT_RESTORE);
+        assert_eq!(sword.durability_fraction(), REPAIR_KIT_RESTORE);
+        assert_eq!(sword.effectiveness(), 1.0);
+        sword.restore(DEFAULT_MAX_DURABILITY);
+        assert!(!sword.is_damaged());
+    }
+}
@@ -0,0 +1,113 @@
+//! Encumbrance
+//!
+//! Everything carried and worn has weight. How much the player can carry
+//! grows with attack (strength), so gear and status effects that raise it,
+//! like Empowered, let them haul more and Weakened less. The heavier the
+//! load relative to that capacity, the slower they move; overloaded, they
+//! can no longer sprint or dodge.
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::stats::CharacterStats;
+
+/// Kilograms anyone can carry before strength is counted
+pub const BASE_CARRY_CAPACITY: f32 = 40.0;
+/// Extra kilograms per point of attack
+pub const CARRY_PER_ATTACK: f32 = 1.5;
+
+/// Carry capacity in kilograms for a set of (effective) stats
+pub fn carry_capacity(stats: &CharacterStats) -> f32 {
+    BASE_CARRY_CAPACITY + stats.attack.max(0.0) * CARRY_PER_ATTACK
+}
+
+/// How weighed down the player is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Encumbrance {
+    /// Up to half capacity
+    Unburdened,
+    /// Up to three quarters
+    Burdened,
+    /// Up to capacity
+    Strained,
+    /// Over capacity
+    Overloaded,
+}
+
+impl Encumbrance {
+    /// Tier for carrying `weight` kilograms with `capacity`
+    pub fn from_load(weight: f32, capacity: f32) -> Self {
+        let ratio = if capacity > 0.0 { weight / capacity } else { f32::INFINITY };
+        if ratio <= 0.5 {
+            Self::Unburdened
+        } else if ratio <= 0.75 {
+            Self::Burdened
+        } else if ratio <= 1.0 {
+            Self::Strained
+        } else {
+            Self::Overloaded
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Unburdened => "Unburdened",
+            Self::Burdened => "Burdened",
+            Self::Strained => "Strained",
+            Self::Overloaded => "Overloaded",
+        }
+    }
+
+    /// Walking and sprinting speed multiplier
+    pub fn speed_multiplier(self) -> f32 {
+        match self {
+            Self::Unburdened => 1.0,
+            Self::Burdened => 0.9,
+            Self::Strained => 0.75,
+            Self::Overloaded => 0.45,
+        }
+    }
+
+    pub fn can_sprint(self) -> bool {
+        self != Self::Overloaded
+    }
+
+    pub fn can_dodge(self) -> bool {
+        self != Self::Overloaded
+    }
+
+    /// HUD color as [r, g, b] floats
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::Unburdened => [0.7, 0.7, 0.7],
+            Self::Burdened => [0.9, 0.85, 0.4],
+            Self::Strained => [1.0, 0.6, 0.2],
+            Self::Overloaded => [1.0, 0.25, 0.2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers_by_load() {
+        assert_eq!(Encumbrance::from_load(10.0, 40.0), Encumbrance::Unburdened);
+        assert_eq!(Encumbrance::from_load(25.0, 40.0), Encumbrance::Burdened);
+        assert_eq!(Encumbrance::from_load(40.0, 40.0), Encumbrance::Strained);
+        assert_eq!(Encumbrance::from_load(41.0, 40.0), Encumbrance::Overloaded);
+        assert_eq!(Encumbrance::from_load(1.0, 0.0), Encumbrance::Overloaded);
+
+        assert!(Encumbrance::Strained.can_sprint());
+        assert!(!Encumbrance::Overloaded.can_sprint());
+        assert!(!Encumbrance::Overloaded.can_dodge());
+        assert!(Encumbrance::Overloaded.speed_multiplier() < Encumbrance::Strained.speed_multiplier());
+    }
+
+    #[test]
+    fn test_strength_raises_capacity() {
+        let stats = CharacterStats::default();
+        let strong = CharacterStats { attack: stats.attack + 10.0, ..stats.clone() };
+        assert_eq!(carry_capacity(&strong) - carry_capacity(&stats), 10.0 * CARRY_PER_ATTACK);
+    }
+}
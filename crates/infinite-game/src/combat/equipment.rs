@@ -1,14 +1,26 @@
 //! Equipment system with 14 slots
 //!
 //! Manages equipping/unequipping items, validates slot compatibility,
-//! and computes total stat modifiers from all equipped items.
+//! and computes total stat modifiers and armor from all equipped items.
+//!
+//! The off hand holds a shield (blocking), a torch, or a one-handed weapon
+//! for dual wielding. Off-hand weapons contribute half their stats.
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 use super::damage::StatModifiers;
-use super::item::{Item, ItemCategory};
-use super::weapon::{WeaponGrip, WeaponType};
+use super::durability::Durable;
+use super::element::Element;
+use super::item::{Item, ItemCategory, ShieldData};
+use super::starter_items::TORCH_ITEM_ID;
+use super::weapon::{WeaponGrip, WeaponRange, WeaponType};
+
+/// Fraction of an off-hand weapon's stat modifiers that apply
+pub const OFF_HAND_STAT_SCALE: f32 = 0.5;
+
+/// Base damage of a torch swung as an off-hand weapon
+pub const TORCH_DAMAGE: f32 = 4.0;
 
 /// The 14 equipment slots
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -78,6 +90,23 @@ impl EquipmentSlot {
             _ => ItemCategory::Armor,
         }
     }
+
+    /// Whether an item can go in this slot, ignoring two-handed conflicts.
+    /// Shields are armor but belong in the off hand only.
+    pub fn accepts(self, item: &Item) -> bool {
+        if item.is_shield() {
+            return self == Self::OffHand;
+        }
+        item.category == self.valid_category()
+    }
+}
+
+/// What the off hand swings with when dual wielding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffHandStrike {
+    pub weapon_type: Option<WeaponType>,
+    pub base_damage: f32,
+    pub element: Element,
 }
 
 /// Error when equipping an item
@@ -89,6 +118,10 @@ pub enum EquipError {
     TwoHandedConflict,
     /// Can't equip in off-hand when main hand has a two-handed weapon
     MainHandIsTwoHanded,
+    /// Two-handed weapons can't be held in the off hand
+    TwoHandedOffHand,
+    /// Shields only go in the off hand
+    ShieldOffHandOnly,
 }
 
 impl fmt::Display for EquipError {
@@ -103,6 +136,12 @@ impl fmt::Display for EquipError {
             Self::MainHandIsTwoHanded => {
                 write!(f, "Cannot equip off-hand with a two-handed main weapon")
             }
+            Self::TwoHandedOffHand => {
+                write!(f, "Two-handed weapons can't be held in the off hand")
+            }
+            Self::ShieldOffHandOnly => {
+                write!(f, "Shields can only be equipped in the off hand")
+            }
         }
     }
 }
@@ -176,11 +215,12 @@ impl EquipmentSet {
     /// Returns error if the item is incompatible with the slot.
     pub fn equip(&mut self, slot: EquipmentSlot, item: Item) -> Result<Option<Item>, EquipError> {
         // Validate category
-        let expected_cat = slot.valid_category();
-        // Allow weapons in main hand / off hand, accessories in ring/amulet
-        if item.category != expected_cat {
+        if item.is_shield() && slot != EquipmentSlot::OffHand {
+            return Err(EquipError::ShieldOffHandOnly);
+        }
+        if !slot.accepts(&item) {
             return Err(EquipError::WrongCategory {
-                expected: expected_cat,
+                expected: slot.valid_category(),
                 got: item.category,
             });
         }
@@ -195,6 +235,9 @@ impl EquipmentSet {
         }
 
         if slot == EquipmentSlot::OffHand {
+            if item.weapon_data.as_ref().is_some_and(|wd| wd.weapon_type.grip() == WeaponGrip::TwoHanded) {
+                return Err(EquipError::TwoHandedOffHand);
+            }
             if let Some(main) = &self.main_hand {
                 if let Some(wd) = &main.weapon_data {
                     if wd.weapon_type.grip() == WeaponGrip::TwoHanded {
@@ -214,12 +257,22 @@ impl EquipmentSet {
         self.get_mut(slot).take()
     }
 
-    /// Total stat modifiers from all equipped items
+    /// Every equipped item, in slot order
+    pub fn equipped(&self) -> impl Iterator<Item = &Item> {
+        EquipmentSlot::all().iter().filter_map(|&slot| self.get(slot).as_ref())
+    }
+
+    /// Total stat modifiers from all equipped items (off-hand weapons at
+    /// [`OFF_HAND_STAT_SCALE`])
     pub fn total_modifiers(&self) -> StatModifiers {
         let mut total = StatModifiers::default();
         for &slot in EquipmentSlot::all() {
             if let Some(item) = self.get(slot) {
-                total.add(&item.total_modifiers());
+                if slot == EquipmentSlot::OffHand && item.is_weapon() {
+                    total.add(&item.total_modifiers().scaled(OFF_HAND_STAT_SCALE));
+                } else {
+                    total.add(&item.total_modifiers());
+                }
             }
         }
         total
@@ -233,19 +286,161 @@ impl EquipmentSet {
             .map(|wd| wd.weapon_type)
     }
 
-    /// Get the main hand weapon damage (if any)
+    /// Get the main hand weapon damage after wear (if any)
     pub fn main_weapon_damage(&self) -> f32 {
         self.main_hand
             .as_ref()
             .and_then(|item| item.weapon_data.as_ref())
-            .map(|wd| wd.base_damage)
+            .map(|wd| wd.effective_damage())
             .unwrap_or(0.0)
     }
+
+    /// Armor value of each equipped piece that has armor (zero if broken)
+    pub fn armor_by_slot(&self) -> Vec<(EquipmentSlot, f32)> {
+        EquipmentSlot::all()
+            .iter()
+            .filter_map(|&slot| {
+                let armor = self.get(slot).as_ref()?.armor_data?;
+                Some((slot, armor.effective_armor()))
+            })
+            .collect()
+    }
+
+    /// Total armor from all equipped pieces
+    pub fn total_armor(&self) -> f32 {
+        self.armor_by_slot().iter().map(|(_, armor)| armor).sum()
+    }
+
+    /// Combined weight of everything worn or held in kilograms
+    pub fn total_weight(&self) -> f32 {
+        EquipmentSlot::all()
+            .iter()
+            .filter_map(|&slot| self.get(slot).as_ref())
+            .map(Item::total_weight)
+            .sum()
+    }
+
+    /// Wear down every equipped armor piece. Returns the names of pieces that wore out.
+    pub fn wear_armor(&mut self, amount: f32) -> Vec<String> {
+        let mut worn_out = Vec::new();
+        for &slot in EquipmentSlot::all() {
+            if let Some(item) = self.get_mut(slot) {
+                if item.armor_data.as_mut().is_some_and(|armor| armor.wear(amount)) {
+                    worn_out.push(item.name.clone());
+                }
+            }
+        }
+        worn_out
+    }
+
+    /// Wear down the weapon in the main or off hand. Returns its name if it wore out.
+    pub fn wear_weapon(&mut self, off_hand: bool, amount: f32) -> Option<String> {
+        let item = if off_hand { self.off_hand.as_mut() } else { self.main_hand.as_mut() }?;
+        let weapon = item.weapon_data.as_mut()?;
+        weapon.wear(amount).then(|| item.name.clone())
+    }
+
+    /// Wear down the shield. Returns its name if it wore out.
+    pub fn wear_shield(&mut self, amount: f32) -> Option<String> {
+        let item = self.off_hand.as_mut()?;
+        let shield = item.shield_data.as_mut()?;
+        shield.wear(amount).then(|| item.name.clone())
+    }
+
+    /// Gold to repair all equipped gear to full durability
+    pub fn repair_cost(&self) -> u64 {
+        EquipmentSlot::all()
+            .iter()
+            .filter_map(|&slot| self.get(slot).as_ref())
+            .filter_map(|item| item.durable().map(|durable| durable.repair_cost(item.rarity)))
+            .sum()
+    }
+
+    /// Restore all equipped gear to full durability
+    pub fn repair_all(&mut self) {
+        for &slot in EquipmentSlot::all() {
+            if let Some(durable) = self.get_mut(slot).as_mut().and_then(|item| item.durable_mut()) {
+                durable.repair();
+            }
+        }
+    }
+
+    /// Restore `fraction` of each equipped piece's maximum durability.
+    /// Returns false if nothing needed it.
+    pub fn restore_all(&mut self, fraction: f32) -> bool {
+        let mut restored = false;
+        for &slot in EquipmentSlot::all() {
+            if let Some(durable) = self.get_mut(slot).as_mut().and_then(|item| item.durable_mut()) {
+                if durable.is_damaged() {
+                    durable.restore(durable.max_durability() * fraction);
+                    restored = true;
+                }
+            }
+        }
+        restored
+    }
+
+    /// Equipped gear that has lost durability
+    pub fn damaged_gear(&self) -> Vec<(EquipmentSlot, &Item)> {
+        EquipmentSlot::all()
+            .iter()
+            .filter_map(|&slot| {
+                let item = self.get(slot).as_ref()?;
+                item.durable()?.is_damaged().then_some((slot, item))
+            })
+            .collect()
+    }
+
+    /// Shield held in the off hand (if any)
+    pub fn shield(&self) -> Option<&ShieldData> {
+        self.off_hand.as_ref().and_then(|item| item.shield_data.as_ref())
+    }
+
+    /// What the off hand attacks with: a weapon or a torch. Shields don't strike.
+    pub fn off_hand_strike(&self) -> Option<OffHandStrike> {
+        let item = self.off_hand.as_ref()?;
+        if let Some(wd) = &item.weapon_data {
+            return Some(OffHandStrike {
+                weapon_type: Some(wd.weapon_type),
+                base_damage: wd.effective_damage(),
+                element: item.element,
+            });
+        }
+        (item.id == TORCH_ITEM_ID).then_some(OffHandStrike {
+            weapon_type: None,
+            base_damage: TORCH_DAMAGE,
+            element: item.element,
+        })
+    }
+
+    /// Whether weapons are held in both hands
+    pub fn is_dual_wielding(&self) -> bool {
+        self.main_hand.as_ref().is_some_and(|item| item.is_weapon())
+            && self.off_hand.as_ref().is_some_and(|item| item.is_weapon())
+    }
+
+    /// Whether the main hand holds a bow, crossbow or wand
+    pub fn holds_ranged(&self) -> bool {
+        self.main_hand
+            .as_ref()
+            .and_then(|item| item.weapon_data.as_ref())
+            .is_some_and(|weapon| weapon.weapon_type.range_type() == WeaponRange::Ranged)
+    }
+
+    /// Whether a torch is held in either hand
+    pub fn holds_torch(&self) -> bool {
+        [&self.main_hand, &self.off_hand]
+            .into_iter()
+            .flatten()
+            .any(|item| item.id == TORCH_ITEM_ID)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::combat::armor::ArmorData;
+    use crate::combat::durability::{DEFAULT_MAX_DURABILITY, REPAIR_KIT_RESTORE, WORN_OUT_EFFECTIVENESS};
     use crate::combat::element::Element;
     use crate::combat::item::{ItemId, ItemRarity};
     use crate::combat::weapon::WeaponData;
@@ -260,11 +455,15 @@ mod tests {
             stat_modifiers: StatModifiers::default(),
             element: Element::Physical,
             weapon_data: Some(WeaponData::new(weapon_type, 10.0)),
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
             gem_sockets: vec![],
             required_level: 1,
             item_level: 1,
             stack_count: 1,
             max_stack: 1,
+            origin_year: None,
         }
     }
 
@@ -282,11 +481,15 @@ mod tests {
             },
             element: Element::Physical,
             weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
             gem_sockets: vec![],
             required_level: 1,
             item_level: 1,
             stack_count: 1,
             max_stack: 1,
+            origin_year: None,
         }
     }
 
@@ -373,4 +576,129 @@ mod tests {
         assert_eq!(mods.defense, 0.0);
         assert_eq!(mods.max_hp, 0.0);
     }
+
+    fn make_shield() -> Item {
+        Item {
+            id: ItemId(3),
+            name: "Test Shield".to_string(),
+            shield_data: Some(ShieldData::new(12.0)),
+            armor_data: None,
+            treasure_map: None,
+            ..make_armor()
+        }
+    }
+
+    #[test]
+    fn test_shield_off_hand_only() {
+        let mut set = EquipmentSet::new();
+        assert!(matches!(set.equip(EquipmentSlot::Head, make_shield()), Err(EquipError::ShieldOffHandOnly)));
+        assert!(matches!(set.equip(EquipmentSlot::MainHand, make_shield()), Err(EquipError::ShieldOffHandOnly)));
+        // Plain armor still can't go in the off hand
+        assert!(matches!(set.equip(EquipmentSlot::OffHand, make_armor()), Err(EquipError::WrongCategory { .. })));
+
+        set.equip(EquipmentSlot::OffHand, make_shield()).unwrap();
+        assert_eq!(set.shield().map(|s| s.block_value), Some(12.0));
+        // Shields don't attack and a sword + shield isn't dual wielding
+        set.equip(EquipmentSlot::MainHand, make_weapon(WeaponType::Sword)).unwrap();
+        assert!(set.off_hand_strike().is_none());
+        assert!(!set.is_dual_wielding());
+    }
+
+    #[test]
+    fn test_dual_wield_strike_and_scaled_stats() {
+        let mut set = EquipmentSet::new();
+        let mut dagger = make_weapon(WeaponType::Dagger);
+        dagger.stat_modifiers.attack = 10.0;
+        dagger.element = Element::Air;
+        set.equip(EquipmentSlot::MainHand, make_weapon(WeaponType::Sword)).unwrap();
+        set.equip(EquipmentSlot::OffHand, dagger.clone()).unwrap();
+
+        assert!(set.is_dual_wielding());
+        let strike = set.off_hand_strike().unwrap();
+        assert_eq!(strike.weapon_type, Some(WeaponType::Dagger));
+        assert_eq!(strike.element, Element::Air);
+        assert_eq!(set.total_modifiers().attack, 10.0 * OFF_HAND_STAT_SCALE);
+
+        // The same weapon in the main hand counts in full
+        let mut set = EquipmentSet::new();
+        set.equip(EquipmentSlot::MainHand, dagger).unwrap();
+        assert_eq!(set.total_modifiers().attack, 10.0);
+    }
+
+    #[test]
+    fn test_two_handed_not_allowed_in_off_hand() {
+        let mut set = EquipmentSet::new();
+        let result = set.equip(EquipmentSlot::OffHand, make_weapon(WeaponType::Greatsword));
+        assert!(matches!(result, Err(EquipError::TwoHandedOffHand)));
+    }
+
+    #[test]
+    fn test_torch_strikes_with_fire() {
+        let mut set = EquipmentSet::new();
+        set.equip(EquipmentSlot::OffHand, crate::combat::create_torch()).unwrap();
+        let strike = set.off_hand_strike().unwrap();
+        assert_eq!(strike.element, Element::Fire);
+        assert_eq!(strike.base_damage, TORCH_DAMAGE);
+        assert!(!set.is_dual_wielding());
+    }
+
+    #[test]
+    fn test_armor_totals_wear_and_repair() {
+        let mut set = EquipmentSet::new();
+        let helmet = Item {
+            armor_data: Some(ArmorData::new(8.0, 2.0)),
+            treasure_map: None,
+            ..make_armor()
+        };
+        let chest = Item {
+            name: "Test Chest".to_string(),
+            armor_data: Some(ArmorData::new(12.0, 10.0)),
+            treasure_map: None,
+            ..make_armor()
+        };
+        set.equip(EquipmentSlot::Head, helmet).unwrap();
+        set.equip(EquipmentSlot::Chest, chest).unwrap();
+        assert_eq!(set.armor_by_slot().len(), 2);
+        assert_eq!(set.total_armor(), 20.0);
+        assert_eq!(set.repair_cost(), 0);
+
+        assert!(set.wear_armor(1.0).is_empty());
+        assert_eq!(set.wear_armor(1.0), vec!["Test Helmet".to_string()]);
+        // The worn-out helmet gives half its armor
+        assert_eq!(set.total_armor(), 12.0 + 8.0 * WORN_OUT_EFFECTIVENESS);
+        assert_eq!(set.damaged_gear().len(), 2);
+        assert!(set.repair_cost() > 0);
+
+        set.repair_all();
+        assert_eq!(set.total_armor(), 20.0);
+        assert!(set.damaged_gear().is_empty());
+    }
+
+    #[test]
+    fn test_weapon_wear_reduces_damage_until_restored() {
+        let mut set = EquipmentSet::new();
+        set.equip(EquipmentSlot::MainHand, make_weapon(WeaponType::Sword)).unwrap();
+        let full = set.main_weapon_damage();
+        assert!(set.wear_weapon(false, DEFAULT_MAX_DURABILITY - 1.0).is_none());
+        assert_eq!(set.main_weapon_damage(), full);
+        assert!(set.wear_weapon(false, 1.0).is_some());
+        assert_eq!(set.main_weapon_damage(), full * WORN_OUT_EFFECTIVENESS);
+        // Nothing in the off hand to wear
+        assert!(set.wear_weapon(true, 1.0).is_none());
+
+        assert!(set.restore_all(REPAIR_KIT_RESTORE));
+        assert_eq!(set.main_weapon_damage(), full);
+        set.repair_all();
+        assert!(!set.restore_all(REPAIR_KIT_RESTORE));
+    }
+
+    #[test]
+    fn test_holds_torch_in_off_hand() {
+        let mut set = EquipmentSet::new();
+        assert!(!set.holds_torch());
+        set.equip(EquipmentSlot::MainHand, make_weapon(WeaponType::Sword)).unwrap();
+        assert!(!set.holds_torch());
+        set.equip(EquipmentSlot::OffHand, crate::combat::create_torch()).unwrap();
+        assert!(set.holds_torch());
+    }
 }
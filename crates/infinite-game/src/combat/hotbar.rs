@@ -0,0 +1,100 @@
+//! Consumable hotbar
+//!
+//! Four quick-use slots (5-8 by default) bound to consumables by item id,
+//! so a slot keeps pointing at potions or bombs as stacks are used up,
+//! split or re-sorted in the inventory.
+
+use serde::{Deserialize, Serialize};
+
+use super::inventory::Inventory;
+use super::item::ItemId;
+
+/// Number of hotbar slots
+pub const HOTBAR_SLOTS: usize = 4;
+
+/// Item bindings of the consumable hotbar, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConsumableHotbar {
+    slots: [Option<ItemId>; HOTBAR_SLOTS],
+}
+
+impl ConsumableHotbar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind `item` to `slot`, moving it off any other slot.
+    /// Returns false if the slot doesn't exist.
+    pub fn bind(&mut self, slot: usize, item: ItemId) -> bool {
+        if slot >= HOTBAR_SLOTS {
+            return false;
+        }
+        for bound in &mut self.slots {
+            if *bound == Some(item) {
+                *bound = None;
+            }
+        }
+        self.slots[slot] = Some(item);
+        true
+    }
+
+    pub fn clear(&mut self, slot: usize) {
+        if let Some(bound) = self.slots.get_mut(slot) {
+            *bound = None;
+        }
+    }
+
+    /// Item bound to `slot`
+    pub fn get(&self, slot: usize) -> Option<ItemId> {
+        self.slots.get(slot).copied().flatten()
+    }
+
+    /// Slot `item` is bound to
+    pub fn slot_of(&self, item: ItemId) -> Option<usize> {
+        self.slots.iter().position(|bound| *bound == Some(item))
+    }
+
+    /// Inventory index of a stack of the item bound to `slot`
+    pub fn find(&self, slot: usize, inventory: &Inventory) -> Option<usize> {
+        let id = self.get(slot)?;
+        inventory.items.iter().position(|item| item.id == id)
+    }
+
+    /// Total count of the item bound to `slot` across all its stacks
+    pub fn count(&self, slot: usize, inventory: &Inventory) -> u32 {
+        self.get(slot)
+            .map(|id| inventory.items.iter().filter(|item| item.id == id).map(|item| item.stack_count).sum())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::starter_items::create_health_potion;
+
+    #[test]
+    fn test_binding_moves_between_slots() {
+        let mut hotbar = ConsumableHotbar::new();
+        assert!(hotbar.bind(0, ItemId(3000)));
+        assert!(hotbar.bind(2, ItemId(3000)));
+        assert_eq!(hotbar.get(0), None);
+        assert_eq!(hotbar.slot_of(ItemId(3000)), Some(2));
+        assert!(!hotbar.bind(HOTBAR_SLOTS, ItemId(3103)));
+        hotbar.clear(2);
+        assert_eq!(hotbar.get(2), None);
+    }
+
+    #[test]
+    fn test_find_and_count_stacks() {
+        let mut inventory = Inventory::new();
+        inventory.items.push(create_health_potion(10));
+        inventory.items.push(create_health_potion(3));
+        let mut hotbar = ConsumableHotbar::new();
+        hotbar.bind(1, ItemId(3000));
+        assert_eq!(hotbar.find(1, &inventory), Some(0));
+        assert_eq!(hotbar.count(1, &inventory), 13);
+        assert_eq!(hotbar.find(0, &inventory), None);
+        assert_eq!(hotbar.count(0, &inventory), 0);
+    }
+}
@@ -115,6 +115,11 @@ impl Inventory {
         self.items.len() >= self.capacity
     }
 
+    /// Combined weight of every stack in kilograms
+    pub fn total_weight(&self) -> f32 {
+        self.items.iter().map(Item::total_weight).sum()
+    }
+
     /// Sort items by category order: Weapon, Armor, Accessory, Consumable, Material, Gem, Rune
     pub fn sort_by_category(&mut self) {
         self.items.sort_by_key(|item| category_order(item.category));
@@ -174,11 +179,15 @@ mod tests {
             stat_modifiers: StatModifiers::default(),
             element: Element::Physical,
             weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
             gem_sockets: vec![],
             required_level: 1,
             item_level: 1,
             stack_count: 1,
             max_stack: 1,
+            origin_year: None,
         }
     }
 
@@ -192,11 +201,15 @@ mod tests {
             stat_modifiers: StatModifiers::default(),
             element: Element::Physical,
             weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
             gem_sockets: vec![],
             required_level: 1,
             item_level: 1,
             stack_count: count,
             max_stack: 10,
+            origin_year: None,
         }
     }
 
@@ -210,11 +223,15 @@ mod tests {
             stat_modifiers: StatModifiers::default(),
             element: Element::Physical,
             weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
             gem_sockets: vec![],
             required_level: 1,
             item_level: 1,
             stack_count: 1,
             max_stack: 1,
+            origin_year: None,
         }
     }
 
@@ -4,9 +4,12 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::armor::ArmorData;
 use super::damage::StatModifiers;
+use super::durability::{default_max_durability, Durable, DEFAULT_MAX_DURABILITY};
 use super::element::Element;
 use super::gem::{Gem, GemShape};
+use super::treasure::TreasureMapData;
 use super::weapon::WeaponData;
 
 /// Unique item identifier
@@ -70,6 +73,47 @@ pub enum ItemCategory {
     Rune,
 }
 
+/// Shield-specific data (shields are armor worn in the off hand)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShieldData {
+    /// Flat damage absorbed from each blocked hit
+    pub block_value: f32,
+    #[serde(default = "default_max_durability")]
+    pub durability: f32,
+    #[serde(default = "default_max_durability")]
+    pub max_durability: f32,
+}
+
+impl ShieldData {
+    /// New shield at full durability
+    pub fn new(block_value: f32) -> Self {
+        Self {
+            block_value,
+            durability: DEFAULT_MAX_DURABILITY,
+            max_durability: DEFAULT_MAX_DURABILITY,
+        }
+    }
+
+    /// Damage absorbed per block after wear
+    pub fn effective_block(&self) -> f32 {
+        self.block_value * self.effectiveness()
+    }
+}
+
+impl Durable for ShieldData {
+    fn durability(&self) -> f32 {
+        self.durability
+    }
+
+    fn max_durability(&self) -> f32 {
+        self.max_durability
+    }
+
+    fn set_durability(&mut self, durability: f32) {
+        self.durability = durability;
+    }
+}
+
 /// A socket on an item that can hold a gem
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GemSocket {
@@ -105,6 +149,15 @@ pub struct Item {
     pub element: Element,
     /// Weapon-specific data (only for weapons)
     pub weapon_data: Option<WeaponData>,
+    /// Shield-specific data (only for shields)
+    #[serde(default)]
+    pub shield_data: Option<ShieldData>,
+    /// Armor value and durability (only for armor pieces)
+    #[serde(default)]
+    pub armor_data: Option<ArmorData>,
+    /// Marked spot and terrain snippet (only for treasure maps)
+    #[serde(default)]
+    pub treasure_map: Option<TreasureMapData>,
     /// Gem sockets
     pub gem_sockets: Vec<GemSocket>,
     /// Required player level to equip
@@ -115,6 +168,9 @@ pub struct Item {
     pub stack_count: u32,
     /// Maximum stack size
     pub max_stack: u32,
+    /// Year the item was made in; `None` fits any era
+    #[serde(default)]
+    pub origin_year: Option<i64>,
 }
 
 impl Item {
@@ -138,6 +194,67 @@ impl Item {
     pub fn is_weapon(&self) -> bool {
         self.category == ItemCategory::Weapon && self.weapon_data.is_some()
     }
+
+    /// Whether this item is a shield
+    pub fn is_shield(&self) -> bool {
+        self.shield_data.is_some()
+    }
+
+    /// Whether this item is a treasure map
+    pub fn is_treasure_map(&self) -> bool {
+        self.treasure_map.is_some()
+    }
+
+    /// Durability of the weapon, shield or armor data this item carries
+    pub fn durable(&self) -> Option<&dyn Durable> {
+        if let Some(weapon) = &self.weapon_data {
+            Some(weapon)
+        } else if let Some(shield) = &self.shield_data {
+            Some(shield)
+        } else {
+            self.armor_data.as_ref().map(|armor| armor as &dyn Durable)
+        }
+    }
+
+    pub fn durable_mut(&mut self) -> Option<&mut dyn Durable> {
+        if let Some(weapon) = &mut self.weapon_data {
+            Some(weapon)
+        } else if let Some(shield) = &mut self.shield_data {
+            Some(shield)
+        } else {
+            self.armor_data.as_mut().map(|armor| armor as &mut dyn Durable)
+        }
+    }
+
+    /// Weight of one of this item in kilograms. Weapons weigh by type,
+    /// shields and armor by how much they protect, the rest by category.
+    pub fn weight(&self) -> f32 {
+        if let Some(weapon) = &self.weapon_data {
+            return weapon.weapon_type.weight();
+        }
+        if let Some(shield) = &self.shield_data {
+            return 3.0 + shield.block_value * 0.1;
+        }
+        if let Some(armor) = &self.armor_data {
+            return 1.0 + armor.armor * 0.3;
+        }
+        if self.is_treasure_map() {
+            return 0.1;
+        }
+        match self.category {
+            ItemCategory::Weapon => 2.0,
+            ItemCategory::Armor => 2.0,
+            ItemCategory::Accessory => 0.2,
+            ItemCategory::Consumable => 0.5,
+            ItemCategory::Material => 1.0,
+            ItemCategory::Gem | ItemCategory::Rune => 0.1,
+        }
+    }
+
+    /// Weight of the whole stack
+    pub fn total_weight(&self) -> f32 {
+        self.weight() * self.stack_count as f32
+    }
 }
 
 #[cfg(test)]
@@ -160,11 +277,15 @@ mod tests {
                 super::super::weapon::WeaponType::Sword,
                 10.0,
             )),
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
             gem_sockets: vec![GemSocket::new(GemShape::Circle)],
             required_level: 1,
             item_level: 5,
             stack_count: 1,
             max_stack: 1,
+            origin_year: None,
         }
     }
 
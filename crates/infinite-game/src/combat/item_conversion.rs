@@ -5,6 +5,7 @@ use infinite_integration::types::{
     ServerItemStats,
 };
 
+use super::armor::ArmorData;
 use super::damage::StatModifiers;
 use super::element::Element;
 use super::gem::GemShape;
@@ -65,6 +66,8 @@ pub fn server_to_game_item(server: &ServerCharacterItem) -> Option<Item> {
         WeaponData::new(weapon_type, w.base_damage)
     });
 
+    let armor_data = (category == ItemCategory::Armor).then(|| ArmorData::from_defense(stat_modifiers.defense));
+
     let gem_sockets = (0..custom.gem_sockets.unwrap_or(0))
         .map(|_| GemSocket::new(GemShape::Circle))
         .collect();
@@ -81,11 +84,15 @@ pub fn server_to_game_item(server: &ServerCharacterItem) -> Option<Item> {
         stat_modifiers,
         element,
         weapon_data,
+        shield_data: None,
+        armor_data,
+        treasure_map: None,
         gem_sockets,
         required_level: custom.required_level.unwrap_or(1),
         item_level: custom.item_level.unwrap_or(1),
         stack_count: 1,
         max_stack: server.max_stack,
+        origin_year: custom.origin_year,
     })
 }
 
@@ -143,6 +150,7 @@ pub fn game_item_to_server(item: &Item, project_id: &str) -> ServerCharacterItem
         item_level: Some(item.item_level),
         required_level: Some(item.required_level),
         game_category: Some(game_category.to_string()),
+        origin_year: item.origin_year,
     };
 
     ServerCharacterItem {
@@ -251,6 +259,7 @@ mod tests {
                     item_level: Some(10),
                     required_level: Some(5),
                     game_category: Some("Weapon".to_string()),
+                    origin_year: Some(2400),
                 }),
             },
             effects: vec![],
@@ -265,5 +274,9 @@ mod tests {
         assert_eq!(game_item.stat_modifiers.attack, 15.0);
         assert!(game_item.weapon_data.is_some());
         assert_eq!(game_item.gem_sockets.len(), 2);
+        assert_eq!(game_item.origin_year, Some(2400));
+
+        let back = game_item_to_server(&game_item, "proj");
+        assert_eq!(back.stats.custom.unwrap().origin_year, Some(2400));
     }
 }
@@ -0,0 +1,261 @@
+//! Combat log: rolling DPS and damage/kill statistics
+//!
+//! Tracks damage dealt and taken, broken down by element and source (basic
+//! attacks and skills), plus kill counts per enemy type. Statistics are kept
+//! for the current session and aggregated with totals carried in the save.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use super::element::Element;
+
+/// Default window for the rolling DPS figure, in seconds
+pub const DEFAULT_DPS_WINDOW: f32 = 10.0;
+
+/// Aggregated combat statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CombatTotals {
+    /// Total damage dealt
+    pub damage_dealt: f32,
+    /// Total damage taken
+    pub damage_taken: f32,
+    /// Number of hits landed
+    pub hits: u32,
+    /// Number of critical hits landed
+    pub crits: u32,
+    /// Largest single hit dealt
+    pub highest_hit: f32,
+    /// Damage dealt per element
+    #[serde(default)]
+    pub dealt_by_element: HashMap<Element, f32>,
+    /// Damage taken per element
+    #[serde(default)]
+    pub taken_by_element: HashMap<Element, f32>,
+    /// Damage dealt per source ("Light Attack", "Heavy Attack", skill names)
+    #[serde(default)]
+    pub dealt_by_source: HashMap<String, f32>,
+    /// Kills per enemy type
+    #[serde(default)]
+    pub kills: HashMap<String, u32>,
+}
+
+impl CombatTotals {
+    /// Total kills across all enemy types
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    /// Fraction of hits that were critical (0.0 - 1.0)
+    pub fn crit_rate(&self) -> f32 {
+        if self.hits == 0 {
+            0.0
+        } else {
+            self.crits as f32 / self.hits as f32
+        }
+    }
+
+    /// Add another set of totals into this one
+    pub fn merge(&mut self, other: &CombatTotals) {
+        self.damage_dealt += other.damage_dealt;
+        self.damage_taken += other.damage_taken;
+        self.hits += other.hits;
+        self.crits += other.crits;
+        self.highest_hit = self.highest_hit.max(other.highest_hit);
+        for (element, amount) in &other.dealt_by_element {
+            *self.dealt_by_element.entry(*element).or_default() += amount;
+        }
+        for (element, amount) in &other.taken_by_element {
+            *self.taken_by_element.entry(*element).or_default() += amount;
+        }
+        for (source, amount) in &other.dealt_by_source {
+            *self.dealt_by_source.entry(source.clone()).or_default() += amount;
+        }
+        for (enemy, count) in &other.kills {
+            *self.kills.entry(enemy.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Sort a breakdown map by descending value for display
+pub fn sorted_breakdown<K: Clone>(map: &HashMap<K, f32>) -> Vec<(K, f32)> {
+    let mut entries: Vec<(K, f32)> = map.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+    entries
+}
+
+/// Records combat events for the current session
+#[derive(Debug, Clone)]
+pub struct CombatLog {
+    /// Seconds of history used for the DPS figure
+    pub dps_window: f32,
+    /// Session clock in seconds
+    time: f32,
+    /// Recent hits (timestamp, amount) within the DPS window
+    recent: VecDeque<(f32, f32)>,
+    /// Totals since the session started (or the save was loaded)
+    session: CombatTotals,
+    /// Totals carried in from the loaded save
+    saved: CombatTotals,
+}
+
+impl Default for CombatLog {
+    fn default() -> Self {
+        Self {
+            dps_window: DEFAULT_DPS_WINDOW,
+            time: 0.0,
+            recent: VecDeque::new(),
+            session: CombatTotals::default(),
+            saved: CombatTotals::default(),
+        }
+    }
+}
+
+impl CombatLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance the clock and drop hits that fell out of the DPS window
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+        let cutoff = self.time - self.dps_window;
+        while self.recent.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Record damage dealt by the player
+    pub fn record_dealt(&mut self, amount: f32, element: Element, source: &str, is_crit: bool) {
+        self.recent.push_back((self.time, amount));
+        let totals = &mut self.session;
+        totals.damage_dealt += amount;
+        totals.hits += 1;
+        if is_crit {
+            totals.crits += 1;
+        }
+        totals.highest_hit = totals.highest_hit.max(amount);
+        *totals.dealt_by_element.entry(element).or_default() += amount;
+        *totals.dealt_by_source.entry(source.to_string()).or_default() += amount;
+    }
+
+    /// Record damage taken by the player
+    pub fn record_taken(&mut self, amount: f32, element: Element) {
+        self.session.damage_taken += amount;
+        *self.session.taken_by_element.entry(element).or_default() += amount;
+    }
+
+    /// Record a kill of the given enemy type
+    pub fn record_kill(&mut self, enemy_type: &str) {
+        *self.session.kills.entry(enemy_type.to_string()).or_default() += 1;
+    }
+
+    /// Damage per second over the DPS window
+    pub fn dps(&self) -> f32 {
+        let span = self.time.min(self.dps_window);
+        if span <= 0.0 {
+            return 0.0;
+        }
+        self.recent.iter().map(|(_, amount)| amount).sum::<f32>() / span
+    }
+
+    /// Totals for the current session
+    pub fn session(&self) -> &CombatTotals {
+        &self.session
+    }
+
+    /// Totals for the whole save (loaded totals plus this session)
+    pub fn lifetime(&self) -> CombatTotals {
+        let mut totals = self.saved.clone();
+        totals.merge(&self.session);
+        totals
+    }
+
+    /// Totals to write into a save file
+    pub fn to_save_data(&self) -> CombatTotals {
+        self.lifetime()
+    }
+
+    /// Restore totals from a save and start a fresh session
+    pub fn load_save_data(&mut self, totals: CombatTotals) {
+        self.saved = totals;
+        self.reset_session();
+    }
+
+    /// Clear session statistics and the DPS history
+    pub fn reset_session(&mut self) {
+        self.session = CombatTotals::default();
+        self.recent.clear();
+        self.time = 0.0;
+    }
+
+    /// Clear everything, including totals from the save
+    pub fn clear(&mut self) {
+        self.saved = CombatTotals::default();
+        self.reset_session();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dps_window() {
+        let mut log = CombatLog::new();
+        log.dps_window = 5.0;
+        log.update(1.0);
+        log.record_dealt(50.0, Element::Physical, "Light Attack", false);
+        assert!((log.dps() - 50.0).abs() < 1e-4);
+
+        log.update(4.0);
+        log.record_dealt(50.0, Element::Fire, "Fireball", true);
+        assert!((log.dps() - 20.0).abs() < 1e-4);
+
+        // First hit falls out of the window
+        log.update(2.0);
+        assert!((log.dps() - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_breakdowns_and_kills() {
+        let mut log = CombatLog::new();
+        log.record_dealt(10.0, Element::Physical, "Light Attack", false);
+        log.record_dealt(30.0, Element::Fire, "Fireball", true);
+        log.record_dealt(5.0, Element::Fire, "Fireball", false);
+        log.record_taken(12.0, Element::Water);
+        log.record_kill("Enemy");
+        log.record_kill("Enemy");
+
+        let session = log.session();
+        assert_eq!(session.hits, 3);
+        assert_eq!(session.crits, 1);
+        assert_eq!(session.highest_hit, 30.0);
+        assert_eq!(session.dealt_by_element[&Element::Fire], 35.0);
+        assert_eq!(session.taken_by_element[&Element::Water], 12.0);
+        assert_eq!(session.total_kills(), 2);
+
+        let sources = sorted_breakdown(&session.dealt_by_source);
+        assert_eq!(sources[0], ("Fireball".to_string(), 35.0));
+    }
+
+    #[test]
+    fn test_save_aggregation() {
+        let mut log = CombatLog::new();
+        log.record_dealt(20.0, Element::Physical, "Light Attack", false);
+        log.record_kill("Enemy");
+        let saved = log.to_save_data();
+
+        // Loading starts a fresh session on top of the saved totals
+        log.load_save_data(saved);
+        assert_eq!(log.session().total_kills(), 0);
+        log.record_kill("Guard");
+        let lifetime = log.lifetime();
+        assert_eq!(lifetime.total_kills(), 2);
+        assert_eq!(lifetime.damage_dealt, 20.0);
+
+        let json = serde_json::to_string(&lifetime).unwrap();
+        let restored: CombatTotals = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, lifetime);
+    }
+}
@@ -1,31 +1,58 @@
 //! Combat system module
 //!
-//! Provides elements, damage calculation, weapons, items, equipment,
-//! gems, skills, rune composition, and status effects.
+//! Provides elements, damage calculation, armor, weapons, items, equipment,
+//! gems, skills, rune composition, status effects, weapon movesets, treasure
+//! maps, the consumable hotbar, the combat log, floating damage numbers,
+//! curses, and anachronism rules for items used outside their era.
 
+pub mod aim;
+pub mod anachronism;
+pub mod armor;
 pub mod catalog;
+pub mod curse;
 pub mod damage;
+pub mod damage_numbers;
+pub mod durability;
 pub mod element;
+pub mod encumbrance;
 pub mod equipment;
 pub mod gem;
+pub mod hotbar;
 pub mod inventory;
 pub mod item;
 pub mod item_conversion;
+pub mod log;
+pub mod moveset;
 pub mod rune;
 pub mod skill;
 pub mod starter_items;
 pub mod status;
+pub mod treasure;
 pub mod weapon;
 
+pub use aim::{AimSpread, AIM_ASSIST_RANGE};
+pub use anachronism::{anachronism, ParadoxConfig, ParadoxEvent, ParadoxMeter, ParadoxStage, MAX_PARADOX};
+pub use armor::ArmorData;
 pub use catalog::ItemCatalog;
+pub use curse::{
+    create_purifying_salts, CurseKind, Curses, CLEANSE_GOLD_COST, PURIFYING_SALTS_ITEM_ID, PURIFYING_SALTS_PRICE,
+    VOID_CURSE_CHANCE,
+};
 pub use damage::{AttackType, DamageEvent, StatModifiers, calculate_combat_damage};
+pub use damage_numbers::{DamageKind, DamageNumber, DamageNumbers, PLAYER_TARGET};
+pub use durability::{create_repair_kit, Durable, REPAIR_KIT_ITEM_ID, REPAIR_KIT_PRICE, REPAIR_KIT_RESTORE};
 pub use element::Element;
-pub use equipment::{EquipError, EquipmentSet, EquipmentSlot};
+pub use encumbrance::{carry_capacity, Encumbrance, BASE_CARRY_CAPACITY};
+pub use equipment::{EquipError, EquipmentSet, EquipmentSlot, OffHandStrike, OFF_HAND_STAT_SCALE};
 pub use gem::{Gem, GemQuality, GemShape};
-pub use item::{GemSocket, Item, ItemCategory, ItemId, ItemRarity};
+pub use hotbar::{ConsumableHotbar, HOTBAR_SLOTS};
+pub use log::{CombatLog, CombatTotals};
+pub use moveset::{swing_reaches, AttackPhase, ComboHit, ComboState, ComboStep, Moveset, MovesetLibrary, SWING_RADIUS};
+pub use item::{GemSocket, Item, ItemCategory, ItemId, ItemRarity, ShieldData};
 pub use rune::{ComposedSpell, Rune, RuneAmplifier, RuneAspect, RuneComposer, RuneModifier};
 pub use skill::{ActiveSkill, PassiveSkill, Skill, SkillId, SkillSlot, SkillShape, SkillTarget, MAX_SKILL_SLOTS};
 pub use status::{StatusEffect, StatusEffectType, StatusManager};
+pub use treasure::{MapTier, TreasureMapData, SHOVEL_ITEM_ID, TREASURE_MAP_ITEM_ID};
 pub use inventory::{Inventory, MAX_INVENTORY_SIZE};
-pub use starter_items::{create_starter_items, create_starter_skills};
+pub use starter_items::{create_shield, create_starter_items, create_starter_skills, create_torch, TORCH_ITEM_ID};
 pub use weapon::{WeaponData, WeaponGrip, WeaponRange, WeaponType};
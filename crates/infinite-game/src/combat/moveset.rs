@@ -0,0 +1,488 @@
+//! Weapon movesets and combo chains
+//!
+//! Each weapon type has a light and a heavy combo chain: a sequence of steps
+//! with their own timing, damage and poise damage. Pressing attack again
+//! inside a step's combo window continues the chain; the last step of a
+//! chain is its finisher, after which the chain starts over. Movesets are
+//! plain data — the built-in set can be overridden from JSON.
+
+use std::collections::HashMap;
+
+use glam::Vec3;
+use infinite_physics::{CastFilter, EntityId, PhysicsWorld};
+use serde::{Deserialize, Serialize};
+
+use super::damage::AttackType;
+use super::weapon::WeaponType;
+
+/// How long an attack press is remembered before it is dropped
+pub const INPUT_BUFFER_TIME: f32 = 0.3;
+
+/// Radius of the volume a melee swing sweeps
+pub const SWING_RADIUS: f32 = 0.25;
+
+fn default_range_multiplier() -> f32 {
+    1.0
+}
+
+/// One attack in a combo chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboStep {
+    /// Display name (e.g. "Rising Slash")
+    pub name: String,
+    /// Damage multiplier on top of the attack type's
+    pub damage_multiplier: f32,
+    /// Poise damage dealt on hit; targets stagger when their poise breaks
+    pub poise_damage: f32,
+    /// Step duration in seconds at 1.0 weapon speed
+    pub duration: f32,
+    /// Fraction of the duration at which the hit lands
+    pub hit_time: f32,
+    /// Seconds after the step ends in which the next press continues the chain
+    pub combo_window: f32,
+    /// Reach multiplier (lunges, sweeps)
+    #[serde(default = "default_range_multiplier")]
+    pub range_multiplier: f32,
+}
+
+impl ComboStep {
+    fn new(name: &str, damage_multiplier: f32, poise_damage: f32, duration: f32, hit_time: f32, combo_window: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            damage_multiplier,
+            poise_damage,
+            duration,
+            hit_time,
+            combo_window,
+            range_multiplier: 1.0,
+        }
+    }
+
+    fn reach(mut self, range_multiplier: f32) -> Self {
+        self.range_multiplier = range_multiplier;
+        self
+    }
+}
+
+/// Light and heavy combo chains for a weapon type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Moveset {
+    pub light: Vec<ComboStep>,
+    pub heavy: Vec<ComboStep>,
+}
+
+impl Moveset {
+    /// The chain for an attack type
+    pub fn chain(&self, attack_type: AttackType) -> &[ComboStep] {
+        match attack_type {
+            AttackType::Light => &self.light,
+            AttackType::Heavy => &self.heavy,
+        }
+    }
+
+    /// Bare-handed punches
+    pub fn unarmed() -> Self {
+        Self {
+            light: vec![
+                ComboStep::new("Jab", 0.8, 5.0, 0.35, 0.4, 0.3),
+                ComboStep::new("Cross", 0.9, 8.0, 0.35, 0.4, 0.3),
+                ComboStep::new("Uppercut", 1.3, 20.0, 0.5, 0.5, 0.0),
+            ],
+            heavy: vec![ComboStep::new("Haymaker", 1.0, 25.0, 1.0, 0.5, 0.0)],
+        }
+    }
+
+    /// Built-in moveset for a weapon type
+    pub fn for_weapon(weapon: WeaponType) -> Self {
+        use WeaponType::*;
+        match weapon {
+            Sword => Self {
+                light: vec![
+                    ComboStep::new("Slash", 1.0, 10.0, 0.4, 0.45, 0.35),
+                    ComboStep::new("Backslash", 1.0, 10.0, 0.4, 0.45, 0.35),
+                    ComboStep::new("Thrust", 1.4, 25.0, 0.55, 0.5, 0.0).reach(1.2),
+                ],
+                heavy: vec![
+                    ComboStep::new("Overhead", 1.0, 30.0, 1.1, 0.5, 0.4),
+                    ComboStep::new("Rising Cleave", 1.3, 45.0, 1.2, 0.5, 0.0),
+                ],
+            },
+            Dagger | DualBlades => Self {
+                light: vec![
+                    ComboStep::new("Stab", 0.7, 4.0, 0.3, 0.4, 0.3),
+                    ComboStep::new("Stab", 0.7, 4.0, 0.3, 0.4, 0.3),
+                    ComboStep::new("Slice", 0.8, 6.0, 0.3, 0.4, 0.3),
+                    ComboStep::new("Flurry", 1.6, 15.0, 0.5, 0.6, 0.0),
+                ],
+                heavy: vec![ComboStep::new("Lunge", 1.2, 15.0, 0.9, 0.6, 0.0).reach(1.5)],
+            },
+            Axe | Mace | Hammer => Self {
+                light: vec![
+                    ComboStep::new("Chop", 1.0, 18.0, 0.55, 0.55, 0.4),
+                    ComboStep::new("Crushing Blow", 1.5, 40.0, 0.75, 0.6, 0.0),
+                ],
+                heavy: vec![ComboStep::new("Ground Slam", 1.2, 60.0, 1.4, 0.65, 0.0).reach(1.3)],
+            },
+            Greatsword => Self {
+                light: vec![
+                    ComboStep::new("Sweep", 1.0, 20.0, 0.6, 0.5, 0.45),
+                    ComboStep::new("Reverse Sweep", 1.0, 20.0, 0.6, 0.5, 0.45),
+                    ComboStep::new("Cleaver", 1.6, 45.0, 0.9, 0.6, 0.0),
+                ],
+                heavy: vec![
+                    ComboStep::new("Charged Cleave", 1.1, 50.0, 1.3, 0.6, 0.5),
+                    ComboStep::new("Earthsplitter", 1.4, 70.0, 1.4, 0.6, 0.0),
+                ],
+            },
+            Spear | Halberd => Self {
+                light: vec![
+                    ComboStep::new("Thrust", 1.0, 8.0, 0.45, 0.5, 0.35).reach(1.2),
+                    ComboStep::new("Thrust", 1.0, 8.0, 0.45, 0.5, 0.35).reach(1.2),
+                    ComboStep::new("Wide Sweep", 1.3, 25.0, 0.65, 0.55, 0.0),
+                ],
+                heavy: vec![ComboStep::new("Impaling Charge", 1.2, 35.0, 1.2, 0.6, 0.0).reach(1.5)],
+            },
+            Scythe | Whip => Self {
+                light: vec![
+                    ComboStep::new("Lash", 0.9, 8.0, 0.45, 0.5, 0.4).reach(1.1),
+                    ComboStep::new("Reaping Arc", 1.0, 10.0, 0.5, 0.5, 0.4).reach(1.1),
+                    ComboStep::new("Whirl", 1.4, 20.0, 0.7, 0.6, 0.0).reach(1.2),
+                ],
+                heavy: vec![ComboStep::new("Harvest", 1.1, 30.0, 1.2, 0.6, 0.0).reach(1.2)],
+            },
+            Staff => Self {
+                light: vec![
+                    ComboStep::new("Strike", 0.9, 12.0, 0.5, 0.5, 0.4),
+                    ComboStep::new("Spin", 1.3, 25.0, 0.7, 0.55, 0.0),
+                ],
+                heavy: vec![ComboStep::new("Arcane Burst", 1.1, 30.0, 1.3, 0.7, 0.0)],
+            },
+            // Ranged weapons fire single shots; heavy is a charged shot
+            Bow | Crossbow | Wand => Self {
+                light: vec![ComboStep::new("Shot", 1.0, 5.0, 0.4, 0.5, 0.0)],
+                heavy: vec![ComboStep::new("Charged Shot", 1.0, 20.0, 1.2, 0.8, 0.0)],
+            },
+        }
+    }
+}
+
+/// Movesets per weapon type
+#[derive(Debug, Clone)]
+pub struct MovesetLibrary {
+    movesets: HashMap<WeaponType, Moveset>,
+    unarmed: Moveset,
+}
+
+impl MovesetLibrary {
+    /// Library with the built-in movesets for every weapon type
+    pub fn builtin() -> Self {
+        Self {
+            movesets: WeaponType::all()
+                .iter()
+                .map(|&wt| (wt, Moveset::for_weapon(wt)))
+                .collect(),
+            unarmed: Moveset::unarmed(),
+        }
+    }
+
+    /// Moveset for the equipped weapon (None = unarmed)
+    pub fn get(&self, weapon: Option<WeaponType>) -> &Moveset {
+        weapon
+            .and_then(|wt| self.movesets.get(&wt))
+            .unwrap_or(&self.unarmed)
+    }
+
+    /// Replace a weapon type's moveset
+    pub fn set(&mut self, weapon: WeaponType, moveset: Moveset) {
+        self.movesets.insert(weapon, moveset);
+    }
+
+    /// Override movesets from a JSON object keyed by weapon type, e.g.
+    /// `{"Sword": {"light": [...], "heavy": [...]}}`. Returns how many were loaded.
+    pub fn load_overrides(&mut self, json: &str) -> Result<usize, serde_json::Error> {
+        let overrides: HashMap<WeaponType, Moveset> = serde_json::from_str(json)?;
+        let count = overrides.len();
+        self.movesets.extend(overrides);
+        Ok(count)
+    }
+}
+
+impl Default for MovesetLibrary {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// A combo step landing its hit this frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboHit {
+    pub attack_type: AttackType,
+    /// Index of the step in its chain
+    pub step: usize,
+    pub name: String,
+    pub damage_multiplier: f32,
+    pub poise_damage: f32,
+    pub range_multiplier: f32,
+    /// Whether this was the last step of the chain
+    pub finisher: bool,
+    /// Whether the off-hand weapon strikes (alternating when dual wielding)
+    pub off_hand: bool,
+}
+
+/// Where the player is in a combo
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AttackPhase {
+    /// Not attacking
+    #[default]
+    Idle,
+    /// Performing a combo step
+    Swing {
+        chain: AttackType,
+        step: usize,
+        elapsed: f32,
+        hit_landed: bool,
+    },
+    /// Between steps: another press continues the chain
+    Window {
+        chain: AttackType,
+        next_step: usize,
+        remaining: f32,
+    },
+}
+
+/// Attack state machine with input buffering
+#[derive(Debug, Clone, Default)]
+pub struct ComboState {
+    phase: AttackPhase,
+    /// Buffered press and how long it stays buffered
+    buffered: Option<(AttackType, f32)>,
+}
+
+impl ComboState {
+    pub fn phase(&self) -> AttackPhase {
+        self.phase
+    }
+
+    /// Whether a combo step is being performed
+    pub fn is_attacking(&self) -> bool {
+        matches!(self.phase, AttackPhase::Swing { .. })
+    }
+
+    /// Chain and step index of the step being performed
+    pub fn current_step(&self) -> Option<(AttackType, usize)> {
+        match self.phase {
+            AttackPhase::Swing { chain, step, .. } => Some((chain, step)),
+            _ => None,
+        }
+    }
+
+    /// Buffer an attack press; it starts as soon as the current step allows
+    pub fn press(&mut self, attack_type: AttackType) {
+        self.buffered = Some((attack_type, INPUT_BUFFER_TIME));
+    }
+
+    /// Drop the combo and any buffered input (e.g. on dodge or respawn)
+    pub fn cancel(&mut self) {
+        self.phase = AttackPhase::Idle;
+        self.buffered = None;
+    }
+
+    /// Advance the combo. `speed` scales step durations (weapon speed).
+    /// Returns the hit if a step reached its hit time this update.
+    pub fn update(&mut self, delta: f32, moveset: &Moveset, speed: f32) -> Option<ComboHit> {
+        if let Some((_, remaining)) = &mut self.buffered {
+            *remaining -= delta;
+            if *remaining <= 0.0 {
+                self.buffered = None;
+            }
+        }
+
+        let speed = speed.max(0.1);
+        match self.phase {
+            AttackPhase::Idle => {
+                if let Some((attack, _)) = self.buffered.take() {
+                    self.start(attack, 0, moveset);
+                }
+                None
+            }
+            AttackPhase::Window { chain, next_step, remaining } => {
+                if let Some((attack, _)) = self.buffered.take() {
+                    self.start(attack, if attack == chain { next_step } else { 0 }, moveset);
+                } else if remaining <= delta {
+                    self.phase = AttackPhase::Idle;
+                } else {
+                    self.phase = AttackPhase::Window { chain, next_step, remaining: remaining - delta };
+                }
+                None
+            }
+            AttackPhase::Swing { chain, step, elapsed, hit_landed } => {
+                let steps = moveset.chain(chain);
+                let Some(current) = steps.get(step) else {
+                    self.phase = AttackPhase::Idle;
+                    return None;
+                };
+                let duration = current.duration / speed;
+                let elapsed = elapsed + delta;
+
+                let hit = (!hit_landed && elapsed >= duration * current.hit_time).then(|| ComboHit {
+                    attack_type: chain,
+                    step,
+                    name: current.name.clone(),
+                    damage_multiplier: current.damage_multiplier,
+                    poise_damage: current.poise_damage,
+                    range_multiplier: current.range_multiplier,
+                    finisher: step + 1 == steps.len(),
+                    off_hand: false,
+                });
+                let hit_landed = hit_landed || hit.is_some();
+
+                if elapsed < duration {
+                    self.phase = AttackPhase::Swing { chain, step, elapsed, hit_landed };
+                } else {
+                    // The chain starts over after its finisher
+                    let next_step = if step + 1 < steps.len() { step + 1 } else { 0 };
+                    match self.buffered.take() {
+                        Some((attack, _)) => {
+                            self.start(attack, if attack == chain { next_step } else { 0 }, moveset);
+                        }
+                        None if current.combo_window > 0.0 => {
+                            self.phase = AttackPhase::Window { chain, next_step, remaining: current.combo_window };
+                        }
+                        None => self.phase = AttackPhase::Idle,
+                    }
+                }
+                hit
+            }
+        }
+    }
+
+    fn start(&mut self, chain: AttackType, step: usize, moveset: &Moveset) {
+        self.phase = if step < moveset.chain(chain).len() {
+            AttackPhase::Swing { chain, step, elapsed: 0.0, hit_landed: false }
+        } else {
+            AttackPhase::Idle
+        };
+    }
+}
+
+/// Whether a swing from `from` reaches a target at `to` with no wall in
+/// between. The weapon's volume is swept, so it can't slip through a gap
+/// narrower than the blade the way a single ray would.
+pub fn swing_reaches(physics: &PhysicsWorld, from: Vec3, to: Vec3) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    if distance < SWING_RADIUS {
+        return true;
+    }
+    let filter = CastFilter::excluding_entity(EntityId::PLAYER);
+    physics.cast_sphere(from, SWING_RADIUS, offset, distance - SWING_RADIUS, filter.query()).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Run the combo for `seconds`, collecting hits
+    fn run(combo: &mut ComboState, moveset: &Moveset, seconds: f32) -> Vec<ComboHit> {
+        (0..(seconds / DT).round() as u32)
+            .filter_map(|_| combo.update(DT, moveset, 1.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_chain_advances_within_window() {
+        let moveset = Moveset::for_weapon(WeaponType::Sword);
+        let mut combo = ComboState::default();
+
+        combo.press(AttackType::Light);
+        let hits = run(&mut combo, &moveset, 0.45);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].step, 0);
+        assert!(matches!(combo.phase(), AttackPhase::Window { next_step: 1, .. }));
+
+        combo.press(AttackType::Light);
+        let hits = run(&mut combo, &moveset, 0.4);
+        assert_eq!(hits[0].step, 1);
+
+        // Finisher, then the chain starts over
+        combo.press(AttackType::Light);
+        let hits = run(&mut combo, &moveset, 0.6);
+        assert!(hits[0].finisher);
+        assert_eq!(hits[0].name, "Thrust");
+        combo.press(AttackType::Light);
+        assert_eq!(run(&mut combo, &moveset, 0.4)[0].step, 0);
+    }
+
+    #[test]
+    fn test_missed_window_resets_chain() {
+        let moveset = Moveset::for_weapon(WeaponType::Sword);
+        let mut combo = ComboState::default();
+        combo.press(AttackType::Light);
+        run(&mut combo, &moveset, 1.5);
+        assert_eq!(combo.phase(), AttackPhase::Idle);
+
+        combo.press(AttackType::Light);
+        assert_eq!(run(&mut combo, &moveset, 0.4)[0].step, 0);
+    }
+
+    #[test]
+    fn test_input_buffer_during_swing() {
+        let moveset = Moveset::for_weapon(WeaponType::Dagger);
+        let mut combo = ComboState::default();
+        combo.press(AttackType::Light);
+        run(&mut combo, &moveset, 0.1);
+
+        // Pressed mid-swing: queued and started as soon as the step ends
+        combo.press(AttackType::Light);
+        run(&mut combo, &moveset, 0.21);
+        assert_eq!(combo.current_step(), Some((AttackType::Light, 1)));
+
+        // Too early: the buffered press expires before the step ends
+        let hammer = Moveset::for_weapon(WeaponType::Hammer);
+        let mut combo = ComboState::default();
+        combo.press(AttackType::Heavy);
+        run(&mut combo, &hammer, 0.1);
+        combo.press(AttackType::Heavy);
+        run(&mut combo, &hammer, 1.4);
+        assert_eq!(combo.phase(), AttackPhase::Idle);
+    }
+
+    #[test]
+    fn test_movesets_are_distinct_and_overridable() {
+        let library = MovesetLibrary::builtin();
+        let dagger = library.get(Some(WeaponType::Dagger));
+        let hammer = library.get(Some(WeaponType::Hammer));
+        assert!(dagger.light.len() > hammer.light.len());
+        assert!(hammer.light[0].poise_damage > dagger.light[0].poise_damage);
+        assert_eq!(library.get(None), &Moveset::unarmed());
+
+        let mut library = library;
+        let loaded = library
+            .load_overrides(
+                r#"{"Whip": {"light": [{"name": "Crack", "damage_multiplier": 2.0, "poise_damage": 1.0,
+                    "duration": 0.3, "hit_time": 0.5, "combo_window": 0.0}], "heavy": []}}"#,
+            )
+            .unwrap();
+        assert_eq!(loaded, 1);
+        let whip = library.get(Some(WeaponType::Whip));
+        assert_eq!(whip.light[0].name, "Crack");
+        assert_eq!(whip.light[0].range_multiplier, 1.0);
+        assert!(whip.heavy.is_empty());
+        assert!(library.load_overrides("not json").is_err());
+    }
+
+    #[test]
+    fn test_swings_blocked_by_walls_and_narrow_gaps() {
+        let mut physics = PhysicsWorld::new();
+        // Two posts 0.2m apart straight ahead, then nothing
+        physics.create_static_box(Vec3::new(0.5, 1.0, 0.1), Vec3::new(-0.6, 1.0, -1.5));
+        physics.create_static_box(Vec3::new(0.5, 1.0, 0.1), Vec3::new(0.6, 1.0, -1.5));
+        physics.update_query_pipeline();
+        let chest = Vec3::new(0.0, 1.2, 0.0);
+        assert!(!swing_reaches(&physics, chest, Vec3::new(0.0, 1.2, -2.5)), "a ray would fit through the gap");
+        assert!(swing_reaches(&physics, chest, Vec3::new(0.0, 1.2, -1.0)));
+        assert!(swing_reaches(&physics, chest, Vec3::new(3.0, 1.2, 0.0)));
+    }
+}
@@ -51,6 +51,10 @@ pub struct ActiveSkill {
     pub cost: f32,
     pub applies_status: Option<StatusEffectType>,
     pub status_duration: f32,
+    /// Taunt strength: on hit the target switches to the caster with this
+    /// much threat on top of its current highest (0 = no taunt)
+    #[serde(default)]
+    pub taunt_threat: f32,
 }
 
 /// A passive skill that provides ongoing benefits
@@ -182,6 +186,7 @@ mod tests {
             cost: 10.0,
             applies_status: Some(StatusEffectType::Burning),
             status_duration: 5.0,
+            taunt_threat: 0.0,
         }
     }
 
@@ -2,12 +2,20 @@
 //!
 //! Creates starter weapons, armor, consumables, and skills for each character archetype.
 
+use super::armor::ArmorData;
 use super::damage::StatModifiers;
+use super::durability::create_repair_kit;
 use super::element::Element;
-use super::item::{Item, ItemCategory, ItemId, ItemRarity};
+use super::item::{Item, ItemCategory, ItemId, ItemRarity, ShieldData};
 use super::skill::{ActiveSkill, Skill, SkillId, SkillShape, SkillSlot, SkillTarget};
 use super::status::StatusEffectType;
+use super::treasure::create_shovel;
 use super::weapon::{WeaponData, WeaponType};
+use crate::lockpick::create_lockpicks;
+use crate::throwable::{create_throwables, ThrowableKind};
+
+/// Item id shared by all torches (checked to attach a light to the holder)
+pub const TORCH_ITEM_ID: ItemId = ItemId(3100);
 
 /// Create starter items for a given archetype.
 /// Returns `(inventory_items, main_hand_weapon)`.
@@ -29,7 +37,20 @@ pub fn create_starter_items(
     let armor = create_armor(armor_name, element);
     let potions = create_health_potion(3);
 
-    let inventory_items = vec![armor, potions];
+    let mut inventory_items = vec![
+        armor,
+        potions,
+        create_torch(),
+        create_shovel(),
+        create_lockpicks(3),
+        create_repair_kit(1),
+        create_throwables(ThrowableKind::Bomb, 3),
+        create_throwables(ThrowableKind::SmokeBomb, 2),
+        create_throwables(ThrowableKind::NoiseLure, 2),
+    ];
+    if archetype_name == "Vanguard" {
+        inventory_items.push(create_shield("Guardian Shield", 12.0));
+    }
     (inventory_items, weapon)
 }
 
@@ -43,11 +64,15 @@ fn create_weapon(name: &str, weapon_type: WeaponType, element: Element, base_dam
         stat_modifiers: StatModifiers::default(),
         element,
         weapon_data: Some(WeaponData::new(weapon_type, base_damage)),
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
         gem_sockets: vec![],
         required_level: 1,
         item_level: 1,
         stack_count: 1,
         max_stack: 1,
+        origin_year: None,
     }
 }
 
@@ -65,15 +90,20 @@ fn create_armor(name: &str, element: Element) -> Item {
         },
         element,
         weapon_data: None,
+        shield_data: None,
+        armor_data: Some(ArmorData::new(10.0, 60.0)),
+        treasure_map: None,
         gem_sockets: vec![],
         required_level: 1,
         item_level: 1,
         stack_count: 1,
         max_stack: 1,
+        origin_year: None,
     }
 }
 
-fn create_health_potion(count: u32) -> Item {
+/// A stack of minor health potions
+pub fn create_health_potion(count: u32) -> Item {
     Item {
         id: ItemId(3000),
         name: "Minor Health Potion".to_string(),
@@ -83,11 +113,64 @@ fn create_health_potion(count: u32) -> Item {
         stat_modifiers: StatModifiers::default(),
         element: Element::Physical,
         weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
         gem_sockets: vec![],
         required_level: 1,
         item_level: 1,
         stack_count: count,
         max_stack: 10,
+        origin_year: None,
+    }
+}
+
+/// A shield for the off hand. Blocks up to `block_value` damage per hit.
+pub fn create_shield(name: &str, block_value: f32) -> Item {
+    Item {
+        id: ItemId(2100),
+        name: name.to_string(),
+        description: "A sturdy shield. Hold it up to block incoming blows.".to_string(),
+        category: ItemCategory::Armor,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers {
+            defense: 1.0,
+            ..Default::default()
+        },
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: Some(ShieldData::new(block_value)),
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: 1,
+        max_stack: 1,
+        origin_year: None,
+    }
+}
+
+/// A hand-held torch. Equip in either hand to light the surroundings.
+pub fn create_torch() -> Item {
+    Item {
+        id: TORCH_ITEM_ID,
+        name: "Torch".to_string(),
+        description: "A burning torch. Equip it to light your way at night.".to_string(),
+        category: ItemCategory::Weapon,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Fire,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: 1,
+        max_stack: 1,
+        origin_year: None,
     }
 }
 
@@ -108,6 +191,7 @@ pub fn create_starter_skills(archetype_name: &str) -> Vec<SkillSlot> {
             cost: 20.0,
             applies_status: None,
             status_duration: 0.0,
+            taunt_threat: 0.0,
         },
         "TemporalHunter" => ActiveSkill {
             id: SkillId(4002),
@@ -122,11 +206,12 @@ pub fn create_starter_skills(archetype_name: &str) -> Vec<SkillSlot> {
             cost: 15.0,
             applies_status: None,
             status_duration: 0.0,
+            taunt_threat: 0.0,
         },
         "Vanguard" => ActiveSkill {
             id: SkillId(4003),
             name: "Shield Bash".to_string(),
-            description: "Bash enemies with your shield, stunning them.".to_string(),
+            description: "Bash enemies with your shield, stunning and taunting them.".to_string(),
             element: Element::Earth,
             shape: SkillShape::Blast,
             target: SkillTarget::Cone { angle: 90.0, range: 3.0 },
@@ -136,6 +221,7 @@ pub fn create_starter_skills(archetype_name: &str) -> Vec<SkillSlot> {
             cost: 25.0,
             applies_status: Some(StatusEffectType::Stunned),
             status_duration: 2.0,
+            taunt_threat: 30.0,
         },
         "Technomage" => ActiveSkill {
             id: SkillId(4004),
@@ -150,6 +236,7 @@ pub fn create_starter_skills(archetype_name: &str) -> Vec<SkillSlot> {
             cost: 22.0,
             applies_status: Some(StatusEffectType::Burning),
             status_duration: 3.0,
+            taunt_threat: 0.0,
         },
         "ParadoxWeaver" => ActiveSkill {
             id: SkillId(4005),
@@ -164,6 +251,7 @@ pub fn create_starter_skills(archetype_name: &str) -> Vec<SkillSlot> {
             cost: 30.0,
             applies_status: None,
             status_duration: 0.0,
+            taunt_threat: 0.0,
         },
         _ => return vec![SkillSlot::empty(); 4],
     };
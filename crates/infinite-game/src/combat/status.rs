@@ -26,6 +26,18 @@ pub enum StatusEffectType {
     Empowered,
     Hastened,
     Shielded,
+    /// Unseen by NPCs until the next attack
+    Invisible,
+    /// Resisting fire damage
+    FireWarded,
+    /// Shrine boon: more XP
+    Enlightened,
+    /// Shrine boon: harder hits of the player's element
+    Attuned,
+    /// Shrine boon: faster movement
+    Swift,
+    /// Every boon taken in the current rift run, until it ends
+    RiftTouched,
 }
 
 impl StatusEffectType {
@@ -58,6 +70,12 @@ impl StatusEffectType {
             Self::Empowered => "Empowered",
             Self::Hastened => "Hastened",
             Self::Shielded => "Shielded",
+            Self::Invisible => "Invisible",
+            Self::FireWarded => "Fire Warded",
+            Self::Enlightened => "Enlightened",
+            Self::Attuned => "Attuned",
+            Self::Swift => "Swift",
+            Self::RiftTouched => "Rift-Touched",
         }
     }
 }
@@ -293,6 +311,11 @@ impl StatusManager {
         self.effects.iter().any(|e| e.effect_type == effect_type)
     }
 
+    /// End every effect of a type early
+    pub fn remove(&mut self, effect_type: StatusEffectType) {
+        self.effects.retain(|e| e.effect_type != effect_type);
+    }
+
     /// Remove all effects
     pub fn clear(&mut self) {
         self.effects.clear();
@@ -0,0 +1,401 @@
+//! Treasure maps and buried chests
+//!
+//! Treasure maps drop from defeated enemies. Each map marks a spot some way
+//! from where it dropped and carries a snippet of the terrain heights around
+//! the mark for the map view. Higher tier maps lead further and may only be
+//! dug up in a specific era. Digging at the mark with a shovel opens a buried
+//! chest whose loot scales with the map's tier.
+
+use std::fmt;
+
+use glam::Vec3;
+use infinite_core::time::format_year;
+use infinite_core::DetRng;
+use infinite_world::{Terrain, TerrainConfig, TimeTerrainConfig};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::damage::StatModifiers;
+use super::element::Element;
+use super::item::{Item, ItemCategory, ItemId, ItemRarity};
+use super::starter_items::create_health_potion;
+
+/// Item id shared by all treasure maps (each map is told apart by its seed)
+pub const TREASURE_MAP_ITEM_ID: ItemId = ItemId(3200);
+
+/// Item id of the shovel
+pub const SHOVEL_ITEM_ID: ItemId = ItemId(3101);
+
+/// Item id of relics found in ancient treasure
+pub const RELIC_ITEM_ID: ItemId = ItemId(3300);
+
+/// How close to the mark the player must dig
+pub const DIG_RADIUS: f32 = 3.0;
+
+/// Side length in meters of the terrain snippet drawn on a map
+pub const SNIPPET_SIZE: f32 = 48.0;
+
+/// Height samples along each side of the snippet
+pub const SNIPPET_RESOLUTION: u32 = 16;
+
+/// Eras that era-locked maps point to
+pub const MAP_ERAS: [i64; 2] = [-5000, 3500];
+
+/// Treasure map tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MapTier {
+    Worn,
+    Detailed,
+    Ancient,
+}
+
+impl MapTier {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Worn => "Worn",
+            Self::Detailed => "Detailed",
+            Self::Ancient => "Ancient",
+        }
+    }
+
+    pub fn rarity(self) -> ItemRarity {
+        match self {
+            Self::Worn => ItemRarity::Common,
+            Self::Detailed => ItemRarity::Rare,
+            Self::Ancient => ItemRarity::Legendary,
+        }
+    }
+
+    /// Nearest and furthest the mark can be from where the map dropped
+    pub fn distance_range(self) -> (f32, f32) {
+        match self {
+            Self::Worn => (60.0, 150.0),
+            Self::Detailed => (150.0, 300.0),
+            Self::Ancient => (300.0, 600.0),
+        }
+    }
+
+    /// Chance (0.0-1.0) that a map of this tier must be dug up in another era
+    pub fn era_lock_chance(self) -> f32 {
+        match self {
+            Self::Worn => 0.0,
+            Self::Detailed => 0.3,
+            Self::Ancient => 0.7,
+        }
+    }
+}
+
+/// What a treasure map item points to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasureMapData {
+    /// Identifies the map and its dig spot
+    pub seed: u64,
+    pub tier: MapTier,
+    /// Marked world position (x, z)
+    pub target: [f32; 2],
+    /// Year the treasure must be dug up in, if era locked
+    pub required_year: Option<i64>,
+    /// Terrain heights around the mark (row-major, SNIPPET_RESOLUTION per side)
+    pub snippet: Vec<f32>,
+}
+
+/// Why digging failed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DigError {
+    NoShovel,
+    TooFar { distance: f32 },
+    WrongEra { required_year: i64 },
+}
+
+impl fmt::Display for DigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoShovel => write!(f, "You need a shovel to dig"),
+            Self::TooFar { distance } => write!(f, "Nothing here. The mark is {:.0}m away", distance),
+            Self::WrongEra { required_year } => {
+                write!(f, "The ground is untouched. This map was drawn in {}", format_year(*required_year))
+            }
+        }
+    }
+}
+
+impl TreasureMapData {
+    /// Generate a map that dropped at `origin`. The mark and era lock come
+    /// from the seed; the snippet samples the terrain around the mark as it
+    /// looks in the map's era (or the present for maps without one).
+    pub fn generate(seed: u64, origin: Vec3, tier: MapTier, terrain: &TerrainConfig, present_year: i64) -> Self {
+        let mut rng = DetRng::new(seed);
+        let (min, max) = tier.distance_range();
+        let distance = min + rng.next_f32() * (max - min);
+        let angle = rng.next_f32() * std::f32::consts::TAU;
+        let target = [origin.x + angle.cos() * distance, origin.z + angle.sin() * distance];
+
+        let required_year = (rng.next_f32() < tier.era_lock_chance())
+            .then(|| MAP_ERAS[(rng.next_u64() % MAP_ERAS.len() as u64) as usize]);
+
+        let mut snippet_config = TerrainConfig {
+            size: SNIPPET_SIZE,
+            subdivisions: SNIPPET_RESOLUTION - 1,
+            ..terrain.clone()
+        };
+        if let Some(year) = required_year {
+            let era = TimeTerrainConfig::for_year(year, present_year);
+            snippet_config.seed = snippet_config.seed.wrapping_add(era.seed_offset);
+            snippet_config.max_height *= era.height_scale;
+            snippet_config.noise_scale *= era.noise_scale_mult;
+        }
+        let half = SNIPPET_SIZE * 0.5;
+        let snippet = Terrain::generate_chunk(snippet_config, target[0] - half, target[1] - half).heights;
+
+        Self {
+            seed,
+            tier,
+            target,
+            required_year,
+            snippet,
+        }
+    }
+
+    /// Marked position at ground level zero
+    pub fn target_position(&self) -> Vec3 {
+        Vec3::new(self.target[0], 0.0, self.target[1])
+    }
+
+    /// Horizontal distance from a position to the mark
+    pub fn distance_from(&self, position: Vec3) -> f32 {
+        let offset = self.target_position() - position;
+        offset.x.hypot(offset.z)
+    }
+
+    /// Check whether the player can dig this map's treasure up here and now
+    pub fn can_dig(&self, position: Vec3, active_year: i64, has_shovel: bool) -> Result<(), DigError> {
+        if !has_shovel {
+            return Err(DigError::NoShovel);
+        }
+        let distance = self.distance_from(position);
+        if distance > DIG_RADIUS {
+            return Err(DigError::TooFar { distance });
+        }
+        match self.required_year {
+            Some(required_year) if required_year != active_year => Err(DigError::WrongEra { required_year }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Annotation for the map view, e.g. "About 230m north-east"
+    pub fn hint(&self, from: Vec3) -> String {
+        let offset = self.target_position() - from;
+        let distance = offset.x.hypot(offset.z);
+        if distance <= DIG_RADIUS {
+            return "X marks the spot".to_string();
+        }
+        // -z is north
+        let bearing = offset.x.atan2(-offset.z).to_degrees().rem_euclid(360.0);
+        const DIRECTIONS: [&str; 8] = [
+            "north", "north-east", "east", "south-east", "south", "south-west", "west", "north-west",
+        ];
+        let direction = DIRECTIONS[((bearing + 22.5) / 45.0) as usize % 8];
+        format!("About {:.0}m {}", distance, direction)
+    }
+
+    /// Lowest and highest snippet heights (for shading the map)
+    pub fn snippet_range(&self) -> (f32, f32) {
+        self.snippet
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)))
+    }
+}
+
+/// Roll whether a defeated enemy drops a map, and of which tier. Tougher
+/// enemies (higher reward multiplier) drop maps more often and of higher tier.
+pub fn roll_map_drop(seed: u64, reward_multiplier: f32) -> Option<MapTier> {
+    let mut rng = DetRng::new(seed);
+    let chance = (0.08 * reward_multiplier.max(1.0)).min(0.5);
+    if rng.next_f32() >= chance {
+        return None;
+    }
+    let quality = rng.next_f32() * reward_multiplier.max(1.0);
+    Some(if quality > 1.6 {
+        MapTier::Ancient
+    } else if quality > 0.7 {
+        MapTier::Detailed
+    } else {
+        MapTier::Worn
+    })
+}
+
+/// Wrap map data into an inventory item
+pub fn create_treasure_map(map: TreasureMapData) -> Item {
+    let era_note = match map.required_year {
+        Some(year) => format!(" The ink is dated {}.", format_year(year)),
+        None => String::new(),
+    };
+    Item {
+        id: TREASURE_MAP_ITEM_ID,
+        name: format!("{} Treasure Map", map.tier.name()),
+        description: format!("A map with an X scrawled on it. Dig at the mark with a shovel.{}", era_note),
+        category: ItemCategory::Material,
+        rarity: map.tier.rarity(),
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: Some(map),
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: 1,
+        max_stack: 1,
+        origin_year: None,
+    }
+}
+
+/// A shovel. Carry it to dig up treasure at the spots marked on maps.
+pub fn create_shovel() -> Item {
+    Item {
+        id: SHOVEL_ITEM_ID,
+        name: "Shovel".to_string(),
+        description: "A sturdy shovel. Dig where a treasure map's X marks the spot.".to_string(),
+        category: ItemCategory::Material,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: 1,
+        max_stack: 1,
+        origin_year: None,
+    }
+}
+
+/// Contents of a buried chest
+#[derive(Debug, Clone)]
+pub struct BuriedLoot {
+    pub gold: u64,
+    pub items: Vec<Item>,
+}
+
+/// Loot in the chest a map leads to, scaled to its tier
+pub fn buried_loot(map: &TreasureMapData) -> BuriedLoot {
+    let mut rng = DetRng::new(map.seed ^ 0x5eed_7ea5_u64);
+    let (gold_min, gold_max, potions) = match map.tier {
+        MapTier::Worn => (40, 90, 1),
+        MapTier::Detailed => (150, 300, 2),
+        MapTier::Ancient => (500, 900, 3),
+    };
+    let gold = gold_min + rng.next_u64() % (gold_max - gold_min + 1);
+
+    let mut items = vec![create_health_potion(potions)];
+    if map.tier != MapTier::Worn {
+        let power = if map.tier == MapTier::Ancient { 2.0 } else { 1.0 };
+        items.push(Item {
+            id: RELIC_ITEM_ID,
+            name: format!("{} Relic", map.tier.name()),
+            description: "A trinket recovered from buried treasure.".to_string(),
+            category: ItemCategory::Accessory,
+            rarity: if map.tier == MapTier::Ancient { ItemRarity::Epic } else { ItemRarity::Uncommon },
+            stat_modifiers: StatModifiers {
+                attack: 3.0 * power,
+                defense: 2.0 * power,
+                max_hp: 10.0 * power,
+                ..Default::default()
+            },
+            element: Element::Physical,
+            weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
+            gem_sockets: vec![],
+            required_level: 1,
+            item_level: if map.tier == MapTier::Ancient { 10 } else { 5 },
+            stack_count: 1,
+            max_stack: 1,
+            origin_year: None,
+        });
+    }
+    BuriedLoot { gold, items }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(tier: MapTier) -> TreasureMapData {
+        TreasureMapData::generate(11, Vec3::ZERO, tier, &TerrainConfig::default(), 2024)
+    }
+
+    #[test]
+    fn test_map_marks_within_tier_range() {
+        for tier in [MapTier::Worn, MapTier::Detailed, MapTier::Ancient] {
+            let (min, max) = tier.distance_range();
+            for seed in 0..20 {
+                let map = TreasureMapData::generate(seed, Vec3::new(10.0, 0.0, -5.0), tier, &TerrainConfig::default(), 2024);
+                let distance = map.distance_from(Vec3::new(10.0, 0.0, -5.0));
+                assert!(distance >= min - 0.01 && distance <= max + 0.01);
+                assert_eq!(map.snippet.len(), (SNIPPET_RESOLUTION * SNIPPET_RESOLUTION) as usize);
+                if tier == MapTier::Worn {
+                    assert!(map.required_year.is_none());
+                }
+            }
+        }
+        // Snippet matches the terrain at the mark
+        let map = map(MapTier::Worn);
+        let center = (SNIPPET_RESOLUTION / 2) as usize;
+        let terrain = Terrain::generate_chunk(
+            TerrainConfig { size: SNIPPET_SIZE, subdivisions: SNIPPET_RESOLUTION - 1, ..Default::default() },
+            map.target[0] - SNIPPET_SIZE * 0.5,
+            map.target[1] - SNIPPET_SIZE * 0.5,
+        );
+        let index = center * SNIPPET_RESOLUTION as usize + center;
+        assert_eq!(map.snippet[index], terrain.heights[index]);
+    }
+
+    #[test]
+    fn test_can_dig_checks_shovel_distance_and_era() {
+        let mut map = map(MapTier::Detailed);
+        let mark = map.target_position();
+        assert_eq!(map.can_dig(mark, 2025, false), Err(DigError::NoShovel));
+        assert!(matches!(map.can_dig(mark + Vec3::X * 10.0, 2025, true), Err(DigError::TooFar { .. })));
+
+        map.required_year = None;
+        assert_eq!(map.can_dig(mark + Vec3::X, 2025, true), Ok(()));
+        map.required_year = Some(-5000);
+        assert_eq!(map.can_dig(mark, 2025, true), Err(DigError::WrongEra { required_year: -5000 }));
+        assert_eq!(map.can_dig(mark, -5000, true), Ok(()));
+    }
+
+    #[test]
+    fn test_hint_direction() {
+        let mut map = map(MapTier::Worn);
+        map.target = [0.0, -100.0];
+        assert_eq!(map.hint(Vec3::ZERO), "About 100m north");
+        map.target = [70.0, 70.0];
+        assert_eq!(map.hint(Vec3::ZERO), "About 99m south-east");
+        assert_eq!(map.hint(Vec3::new(70.0, 0.0, 69.0)), "X marks the spot");
+    }
+
+    #[test]
+    fn test_drops_and_loot_scale_with_tier() {
+        let normal = (0..2000).filter(|&s| roll_map_drop(s, 1.0).is_some()).count();
+        let elite = (0..2000).filter(|&s| roll_map_drop(s, 3.0).is_some()).count();
+        assert!(normal > 80 && normal < 250, "{} drops", normal);
+        assert!(elite > normal * 2);
+        assert!((0..2000).filter_map(|s| roll_map_drop(s, 1.0)).all(|t| t != MapTier::Ancient));
+
+        let worn = buried_loot(&map(MapTier::Worn));
+        let ancient = buried_loot(&map(MapTier::Ancient));
+        assert!(ancient.gold > worn.gold);
+        assert_eq!(worn.items.len(), 1);
+        assert!(ancient.items.iter().any(|i| i.id == RELIC_ITEM_ID && i.rarity == ItemRarity::Epic));
+
+        let item = create_treasure_map(map(MapTier::Ancient));
+        assert_eq!(item.rarity, ItemRarity::Legendary);
+        assert!(item.treasure_map.is_some());
+    }
+}
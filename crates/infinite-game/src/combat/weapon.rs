@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::durability::{default_max_durability, Durable, DEFAULT_MAX_DURABILITY};
+
 /// The 15 weapon types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WeaponType {
@@ -102,6 +104,27 @@ impl WeaponType {
         }
     }
 
+    /// Carry weight in kilograms
+    pub fn weight(self) -> f32 {
+        match self {
+            Self::Sword => 3.0,
+            Self::Axe => 3.5,
+            Self::Mace => 4.0,
+            Self::Dagger => 1.0,
+            Self::Spear => 3.0,
+            Self::Bow => 1.5,
+            Self::Staff => 2.0,
+            Self::Wand => 0.5,
+            Self::Halberd => 6.0,
+            Self::Crossbow => 4.0,
+            Self::Greatsword => 7.0,
+            Self::DualBlades => 2.5,
+            Self::Scythe => 5.0,
+            Self::Hammer => 8.0,
+            Self::Whip => 1.0,
+        }
+    }
+
     /// Light attack cooldown in seconds (base * 1/speed_multiplier)
     pub fn light_attack_cooldown(self) -> f32 {
         0.4 / self.speed_multiplier()
@@ -181,17 +204,42 @@ pub struct WeaponData {
     pub base_damage: f32,
     /// Attack speed of this specific weapon instance
     pub attack_speed: f32,
+    #[serde(default = "default_max_durability")]
+    pub durability: f32,
+    #[serde(default = "default_max_durability")]
+    pub max_durability: f32,
 }
 
 impl WeaponData {
-    /// Create new weapon data
+    /// Create new weapon data at full durability
     pub fn new(weapon_type: WeaponType, base_damage: f32) -> Self {
         Self {
             weapon_type,
             base_damage,
             attack_speed: weapon_type.speed_multiplier(),
+            durability: DEFAULT_MAX_DURABILITY,
+            max_durability: DEFAULT_MAX_DURABILITY,
         }
     }
+
+    /// Base damage after wear
+    pub fn effective_damage(&self) -> f32 {
+        self.base_damage * self.effectiveness()
+    }
+}
+
+impl Durable for WeaponData {
+    fn durability(&self) -> f32 {
+        self.durability
+    }
+
+    fn max_durability(&self) -> f32 {
+        self.max_durability
+    }
+
+    fn set_durability(&mut self, durability: f32) {
+        self.durability = durability;
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,300 @@
+//! Compass bar: bearings for cardinal directions and tracked markers
+//!
+//! Bearings are in degrees clockwise from north, where north is -Z (the
+//! direction the camera faces at yaw 0) and east is +X.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Kind of thing a compass marker points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarkerCategory {
+    /// Signs, doors, containers, portals and other interactables
+    Interactable,
+    /// NPCs
+    Npc,
+    /// Quest or story objectives
+    Objective,
+    /// Player-placed waypoints
+    Waypoint,
+}
+
+impl MarkerCategory {
+    pub const ALL: [MarkerCategory; 4] = [
+        MarkerCategory::Interactable,
+        MarkerCategory::Npc,
+        MarkerCategory::Objective,
+        MarkerCategory::Waypoint,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Interactable => "Interactables",
+            Self::Npc => "NPCs",
+            Self::Objective => "Objectives",
+            Self::Waypoint => "Waypoints",
+        }
+    }
+
+    /// How far away markers of this category still show on the compass.
+    /// Objectives and waypoints are always shown.
+    pub fn max_distance(&self) -> f32 {
+        match self {
+            Self::Interactable => 40.0,
+            Self::Npc => 30.0,
+            Self::Objective | Self::Waypoint => f32::INFINITY,
+        }
+    }
+
+    /// Whether markers of this category are followed on screen as well
+    /// (see [`crate::screen_markers`])
+    pub fn is_tracked(&self) -> bool {
+        matches!(self, Self::Objective | Self::Waypoint)
+    }
+}
+
+/// Which marker categories the compass shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompassFilter {
+    pub interactables: bool,
+    pub npcs: bool,
+    pub objectives: bool,
+    pub waypoints: bool,
+}
+
+impl Default for CompassFilter {
+    fn default() -> Self {
+        Self {
+            interactables: true,
+            npcs: true,
+            objectives: true,
+            waypoints: true,
+        }
+    }
+}
+
+impl CompassFilter {
+    pub fn allows(&self, category: MarkerCategory) -> bool {
+        match category {
+            MarkerCategory::Interactable => self.interactables,
+            MarkerCategory::Npc => self.npcs,
+            MarkerCategory::Objective => self.objectives,
+            MarkerCategory::Waypoint => self.waypoints,
+        }
+    }
+
+    pub fn toggle_mut(&mut self, category: MarkerCategory) -> &mut bool {
+        match category {
+            MarkerCategory::Interactable => &mut self.interactables,
+            MarkerCategory::Npc => &mut self.npcs,
+            MarkerCategory::Objective => &mut self.objectives,
+            MarkerCategory::Waypoint => &mut self.waypoints,
+        }
+    }
+}
+
+/// Unique identifier for a tracked marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarkerId(pub u64);
+
+/// A world position shown on the compass
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompassMarker {
+    pub position: Vec3,
+    pub label: String,
+    pub category: MarkerCategory,
+}
+
+impl CompassMarker {
+    pub fn new(position: Vec3, label: impl Into<String>, category: MarkerCategory) -> Self {
+        Self {
+            position,
+            label: label.into(),
+            category,
+        }
+    }
+}
+
+/// A marker placed on the compass bar for this frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompassEntry {
+    pub label: String,
+    pub category: MarkerCategory,
+    /// Position across the bar (-1.0 = left edge, 0.0 = centre, 1.0 = right edge)
+    pub offset: f32,
+    /// Horizontal distance in meters
+    pub distance: f32,
+}
+
+/// Cardinal and intercardinal labels with their bearings
+pub const CARDINALS: [(&str, f32); 8] = [
+    ("N", 0.0),
+    ("NE", 45.0),
+    ("E", 90.0),
+    ("SE", 135.0),
+    ("S", 180.0),
+    ("SW", 225.0),
+    ("W", 270.0),
+    ("NW", 315.0),
+];
+
+/// Heading (0..360) for a forward vector
+pub fn heading(forward: Vec3) -> f32 {
+    forward.x.atan2(-forward.z).to_degrees().rem_euclid(360.0)
+}
+
+/// Bearing (0..360) from one position to another on the ground plane
+pub fn bearing(from: Vec3, to: Vec3) -> f32 {
+    heading(to - from)
+}
+
+/// Signed angle from `heading` to `bearing` in -180..180 (positive = right)
+pub fn relative_bearing(heading: f32, bearing: f32) -> f32 {
+    (bearing - heading + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Bar offset for a relative bearing, or None if outside the visible arc
+pub fn bar_offset(relative: f32, visible_arc: f32) -> Option<f32> {
+    let half = visible_arc * 0.5;
+    (relative.abs() <= half).then(|| relative / half)
+}
+
+/// Markers that are tracked independently of the world (objectives, waypoints)
+#[derive(Debug, Default)]
+pub struct CompassTracker {
+    markers: Vec<(MarkerId, CompassMarker)>,
+    next_id: u64,
+}
+
+impl CompassTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, marker: CompassMarker) -> MarkerId {
+        let id = MarkerId(self.next_id);
+        self.next_id += 1;
+        self.markers.push((id, marker));
+        id
+    }
+
+    pub fn remove(&mut self, id: MarkerId) -> Option<CompassMarker> {
+        let index = self.markers.iter().position(|(m, _)| *m == id)?;
+        Some(self.markers.remove(index).1)
+    }
+
+    pub fn get(&self, id: MarkerId) -> Option<&CompassMarker> {
+        self.markers.iter().find(|(m, _)| *m == id).map(|(_, marker)| marker)
+    }
+
+    /// Remove every marker in a category (e.g. all waypoints)
+    pub fn clear_category(&mut self, category: MarkerCategory) {
+        self.markers.retain(|(_, m)| m.category != category);
+    }
+
+    pub fn clear(&mut self) {
+        self.markers.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CompassMarker> {
+        self.markers.iter().map(|(_, m)| m)
+    }
+
+    /// Build the compass entries for this frame from tracked markers plus
+    /// `world` markers (interactables, NPCs) gathered by the caller.
+    /// Entries are sorted far-to-near so nearer markers draw on top.
+    pub fn entries<'a>(
+        &'a self,
+        world: impl IntoIterator<Item = &'a CompassMarker>,
+        player_pos: Vec3,
+        heading: f32,
+        visible_arc: f32,
+        filter: &CompassFilter,
+    ) -> Vec<CompassEntry> {
+        let mut entries: Vec<CompassEntry> = self
+            .iter()
+            .chain(world)
+            .filter(|m| filter.allows(m.category))
+            .filter_map(|m| {
+                let delta = m.position - player_pos;
+                let distance = Vec3::new(delta.x, 0.0, delta.z).length();
+                if distance > m.category.max_distance() || distance < 0.5 {
+                    return None;
+                }
+                let relative = relative_bearing(heading, bearing(player_pos, m.position));
+                let offset = bar_offset(relative, visible_arc)?;
+                Some(CompassEntry {
+                    label: m.label.clone(),
+                    category: m.category,
+                    offset,
+                    distance,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heading_cardinals() {
+        assert!((heading(Vec3::new(0.0, 0.0, -1.0)) - 0.0).abs() < 1e-4);
+        assert!((heading(Vec3::new(1.0, 0.0, 0.0)) - 90.0).abs() < 1e-4);
+        assert!((heading(Vec3::new(0.0, 0.0, 1.0)) - 180.0).abs() < 1e-4);
+        assert!((heading(Vec3::new(-1.0, 0.0, 0.0)) - 270.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_relative_bearing_wraps() {
+        assert!((relative_bearing(350.0, 10.0) - 20.0).abs() < 1e-4);
+        assert!((relative_bearing(10.0, 350.0) + 20.0).abs() < 1e-4);
+        assert!(bar_offset(100.0, 180.0).is_none());
+        assert_eq!(bar_offset(45.0, 180.0), Some(0.5));
+    }
+
+    #[test]
+    fn test_entries_filtered_and_sorted() {
+        let mut tracker = CompassTracker::new();
+        tracker.add(CompassMarker::new(Vec3::new(0.0, 0.0, -500.0), "Far goal", MarkerCategory::Objective));
+        tracker.add(CompassMarker::new(Vec3::new(5.0, 0.0, -20.0), "Camp", MarkerCategory::Waypoint));
+        let world = [
+            CompassMarker::new(Vec3::new(0.0, 0.0, -10.0), "Sign", MarkerCategory::Interactable),
+            // Too far for an interactable
+            CompassMarker::new(Vec3::new(0.0, 0.0, -100.0), "Door", MarkerCategory::Interactable),
+        ];
+
+        let all = tracker.entries(&world, Vec3::ZERO, 0.0, 180.0, &CompassFilter::default());
+        let labels: Vec<&str> = all.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, vec!["Far goal", "Camp", "Sign"]);
+
+        let filter = CompassFilter {
+            waypoints: false,
+            ..Default::default()
+        };
+        let filtered = tracker.entries(&world, Vec3::ZERO, 0.0, 180.0, &filter);
+        assert!(filtered.iter().all(|e| e.category != MarkerCategory::Waypoint));
+    }
+
+    #[test]
+    fn test_behind_player_hidden() {
+        let mut tracker = CompassTracker::new();
+        tracker.add(CompassMarker::new(Vec3::new(0.0, 0.0, 50.0), "Behind", MarkerCategory::Objective));
+        assert!(tracker
+            .entries(&[], Vec3::ZERO, 0.0, 180.0, &CompassFilter::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clear_category() {
+        let mut tracker = CompassTracker::new();
+        tracker.add(CompassMarker::new(Vec3::X, "A", MarkerCategory::Waypoint));
+        let goal = tracker.add(CompassMarker::new(Vec3::Z, "B", MarkerCategory::Objective));
+        tracker.clear_category(MarkerCategory::Waypoint);
+        assert_eq!(tracker.iter().count(), 1);
+        assert!(tracker.get(goal).is_some());
+    }
+}
@@ -0,0 +1,569 @@
+//! Cooking: turning ingredients into meals at a campfire or stove
+//!
+//! Ingredients put in the pot either make one of the [`RECIPES`] or get
+//! ruined. Recipes aren't listed anywhere until cooked once; after that
+//! they're in the player's [`RecipeBook`] and can be cooked again straight
+//! from it. Some dishes only come together in their own era (mammoth stew
+//! needs a mammoth, protein synth a synthesiser), the rest cook anywhere.
+//!
+//! Meals heal a little when eaten and grant timed [`FoodBuff`]s. A new
+//! meal replaces any buff of the same kind rather than stacking with it.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::Element;
+use crate::combat::inventory::Inventory;
+use crate::combat::item::{Item, ItemCategory, ItemId, ItemRarity};
+use crate::gathering::{self, ResourceKind};
+use crate::npc::identity::Era;
+
+/// First item id of bought ingredients
+pub const INGREDIENT_ITEM_ID_BASE: u64 = 3500;
+/// First item id of cooked meals (one per recipe)
+pub const FOOD_ITEM_ID_BASE: u64 = 3600;
+/// Most ingredients the pot holds at once
+pub const MAX_POT_INGREDIENTS: usize = 4;
+
+/// Something that goes in the pot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Ingredient {
+    Meat,
+    MammothMeat,
+    Grain,
+    Herbs,
+    Spices,
+    ProteinPaste,
+    NutrientGel,
+}
+
+impl Ingredient {
+    pub const ALL: [Ingredient; 7] = [
+        Self::Meat,
+        Self::MammothMeat,
+        Self::Grain,
+        Self::Herbs,
+        Self::Spices,
+        Self::ProteinPaste,
+        Self::NutrientGel,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Meat => "Raw Meat",
+            Self::MammothMeat => "Mammoth Meat",
+            Self::Grain => "Grain",
+            Self::Herbs => "Herbs",
+            Self::Spices => "Spices",
+            Self::ProteinPaste => "Protein Paste",
+            Self::NutrientGel => "Nutrient Gel",
+        }
+    }
+
+    /// The era this ingredient belongs to, if it isn't found in every era
+    pub fn era(self) -> Option<Era> {
+        match self {
+            Self::MammothMeat => Some(Era::Ancient),
+            Self::ProteinPaste | Self::NutrientGel => Some(Era::Future),
+            _ => None,
+        }
+    }
+
+    /// Year stamped on the item, so carrying it out of its era counts as
+    /// an anachronism
+    fn origin_year(self) -> Option<i64> {
+        self.era().map(|era| match era {
+            Era::Ancient => -3000,
+            Era::Medieval => 1200,
+            Era::Modern => 2000,
+            Era::Future => 2300,
+        })
+    }
+
+    /// Gold a shop charges for one
+    pub fn price(self) -> u64 {
+        match self {
+            Self::Meat | Self::MammothMeat => 12,
+            Self::Grain | Self::Herbs => 5,
+            Self::Spices => 15,
+            Self::ProteinPaste | Self::NutrientGel => 18,
+        }
+    }
+
+    fn item_id(self) -> ItemId {
+        let index = Self::ALL.iter().position(|i| *i == self).unwrap_or(0) as u64;
+        ItemId(INGREDIENT_ITEM_ID_BASE + index)
+    }
+
+    /// A stack of `count` of this ingredient
+    pub fn create(self, count: u32) -> Item {
+        Item {
+            id: self.item_id(),
+            name: self.name().to_string(),
+            description: "A cooking ingredient. Combine it with others at a campfire or stove.".to_string(),
+            category: ItemCategory::Material,
+            rarity: ItemRarity::Common,
+            stat_modifiers: StatModifiers::default(),
+            element: Element::Physical,
+            weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
+            gem_sockets: vec![],
+            required_level: 1,
+            item_level: 1,
+            stack_count: count,
+            max_stack: 20,
+            origin_year: self.origin_year(),
+        }
+    }
+
+    /// The ingredient an item counts as: bought ingredients, and herbs
+    /// gathered from the wild
+    pub fn from_item(item: &Item) -> Option<Self> {
+        if gathering::material_kind(item.id) == Some(ResourceKind::Herb) {
+            return Some(Self::Herbs);
+        }
+        Self::ALL.into_iter().find(|i| i.item_id() == item.id)
+    }
+}
+
+/// What a meal does for the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FoodBuffKind {
+    /// Heals `strength` HP per second
+    Regen,
+    /// Multiplies stamina regeneration by `strength`
+    Stamina,
+    /// Adds `strength` resistance to cold (water and frost) damage
+    ColdResistance,
+}
+
+impl FoodBuffKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Regen => "Well Fed",
+            Self::Stamina => "Energized",
+            Self::ColdResistance => "Warmed",
+        }
+    }
+
+    /// HUD color as [r, g, b] floats
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::Regen => [0.4, 0.9, 0.4],
+            Self::Stamina => [0.95, 0.85, 0.3],
+            Self::ColdResistance => [1.0, 0.55, 0.3],
+        }
+    }
+}
+
+/// A buff a meal grants when eaten
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FoodBuff {
+    pub kind: FoodBuffKind,
+    pub strength: f32,
+    /// Seconds it lasts
+    pub duration: f32,
+}
+
+/// A dish: what goes in and what it does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recipe {
+    pub name: &'static str,
+    /// Sorted, so a pot matches no matter the order things went in
+    pub ingredients: &'static [Ingredient],
+    /// The only era it can be cooked in, if any
+    pub era: Option<Era>,
+    /// HP restored on eating
+    pub heal: f32,
+    pub buffs: &'static [FoodBuff],
+}
+
+impl Recipe {
+    pub fn available_in(&self, era: Era) -> bool {
+        self.era.is_none_or(|e| e == era)
+    }
+}
+
+const fn buff(kind: FoodBuffKind, strength: f32, duration: f32) -> FoodBuff {
+    FoodBuff { kind, strength, duration }
+}
+
+/// Every dish there is
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        name: "Herbal Tea",
+        ingredients: &[Ingredient::Herbs, Ingredient::Herbs],
+        era: None,
+        heal: 10.0,
+        buffs: &[buff(FoodBuffKind::ColdResistance, 4.0, 120.0)],
+    },
+    Recipe {
+        name: "Roast Meat",
+        ingredients: &[Ingredient::Meat],
+        era: None,
+        heal: 20.0,
+        buffs: &[buff(FoodBuffKind::Regen, 1.0, 60.0)],
+    },
+    Recipe {
+        name: "Hearty Stew",
+        ingredients: &[Ingredient::Meat, Ingredient::Grain, Ingredient::Herbs],
+        era: None,
+        heal: 30.0,
+        buffs: &[buff(FoodBuffKind::Regen, 2.0, 120.0), buff(FoodBuffKind::ColdResistance, 6.0, 180.0)],
+    },
+    Recipe {
+        name: "Mammoth Stew",
+        ingredients: &[Ingredient::MammothMeat, Ingredient::Herbs, Ingredient::Herbs],
+        era: Some(Era::Ancient),
+        heal: 50.0,
+        buffs: &[buff(FoodBuffKind::Regen, 3.0, 180.0), buff(FoodBuffKind::ColdResistance, 12.0, 300.0)],
+    },
+    Recipe {
+        name: "Trail Bread",
+        ingredients: &[Ingredient::Grain, Ingredient::Grain, Ingredient::Herbs],
+        era: Some(Era::Medieval),
+        heal: 15.0,
+        buffs: &[buff(FoodBuffKind::Stamina, 1.5, 240.0)],
+    },
+    Recipe {
+        name: "Spiced Chili",
+        ingredients: &[Ingredient::Meat, Ingredient::Grain, Ingredient::Spices],
+        era: Some(Era::Modern),
+        heal: 25.0,
+        buffs: &[buff(FoodBuffKind::Stamina, 1.3, 180.0), buff(FoodBuffKind::ColdResistance, 8.0, 240.0)],
+    },
+    Recipe {
+        name: "Protein Synth",
+        ingredients: &[Ingredient::ProteinPaste, Ingredient::NutrientGel],
+        era: Some(Era::Future),
+        heal: 35.0,
+        buffs: &[buff(FoodBuffKind::Regen, 2.5, 150.0), buff(FoodBuffKind::Stamina, 1.8, 150.0)],
+    },
+];
+
+/// Look a recipe up by name
+pub fn recipe(name: &str) -> Option<&'static Recipe> {
+    RECIPES.iter().find(|r| r.name == name)
+}
+
+/// The meal a recipe makes
+pub fn create_food(recipe: &Recipe, count: u32) -> Item {
+    let index = RECIPES.iter().position(|r| r.name == recipe.name).unwrap_or(0) as u64;
+    let effects: Vec<&str> = recipe.buffs.iter().map(|b| b.kind.name()).collect();
+    Item {
+        id: ItemId(FOOD_ITEM_ID_BASE + index),
+        name: recipe.name.to_string(),
+        description: format!("A home-cooked meal. Restores {:.0} HP and leaves you {}.", recipe.heal, effects.join(" and ")),
+        category: ItemCategory::Consumable,
+        rarity: if recipe.era.is_some() { ItemRarity::Uncommon } else { ItemRarity::Common },
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 10,
+        origin_year: None,
+    }
+}
+
+/// The recipe a meal was cooked from, if `id` is a meal
+pub fn recipe_for_item(id: ItemId) -> Option<&'static Recipe> {
+    let index = id.0.checked_sub(FOOD_ITEM_ID_BASE)?;
+    RECIPES.get(index as usize)
+}
+
+/// What the player cooks on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StationKind {
+    Campfire,
+    Stove,
+}
+
+impl StationKind {
+    /// Campfires before the modern era, stoves from then on
+    pub fn for_era(era: Era) -> Self {
+        match era {
+            Era::Ancient | Era::Medieval => Self::Campfire,
+            Era::Modern | Era::Future => Self::Stove,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Campfire => "Campfire",
+            Self::Stove => "Stove",
+        }
+    }
+}
+
+/// The result of cooking a pot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CookOutcome {
+    /// The pot made a dish; `discovered` if it's new to the recipe book
+    Cooked { recipe: &'static Recipe, discovered: bool },
+    /// The pot makes a dish that can't be cooked in this era. Nothing is used up.
+    WrongEra(&'static Recipe),
+    /// The pot is on its way to a dish but missing something. Nothing is used up.
+    Incomplete,
+    /// Nothing that goes together; the ingredients are wasted
+    Ruined,
+}
+
+impl CookOutcome {
+    /// Whether the ingredients in the pot are used up
+    pub fn consumes_ingredients(&self) -> bool {
+        matches!(self, Self::Cooked { .. } | Self::Ruined)
+    }
+}
+
+/// Recipes the player has cooked at least once
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecipeBook {
+    known: BTreeSet<String>,
+}
+
+impl RecipeBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn knows(&self, name: &str) -> bool {
+        self.known.contains(name)
+    }
+
+    /// Known recipes, in [`RECIPES`] order
+    pub fn known(&self) -> impl Iterator<Item = &'static Recipe> + '_ {
+        RECIPES.iter().filter(|r| self.knows(r.name))
+    }
+
+    /// Work out what a pot of ingredients makes in `era`, learning the
+    /// recipe if it's new
+    pub fn cook(&mut self, pot: &[Ingredient], era: Era) -> CookOutcome {
+        let mut pot = pot.to_vec();
+        pot.sort();
+        if pot.is_empty() {
+            return CookOutcome::Incomplete;
+        }
+        if let Some(recipe) = RECIPES.iter().find(|r| r.ingredients == pot.as_slice()) {
+            if !recipe.available_in(era) {
+                return CookOutcome::WrongEra(recipe);
+            }
+            let discovered = self.known.insert(recipe.name.to_string());
+            return CookOutcome::Cooked { recipe, discovered };
+        }
+        if RECIPES.iter().any(|r| r.available_in(era) && is_sub_multiset(&pot, r.ingredients)) {
+            CookOutcome::Incomplete
+        } else {
+            CookOutcome::Ruined
+        }
+    }
+}
+
+/// Whether every ingredient of sorted `part` is in sorted `whole`, counting repeats
+fn is_sub_multiset(part: &[Ingredient], whole: &[Ingredient]) -> bool {
+    let mut rest = whole.iter();
+    part.iter().all(|p| rest.any(|w| w == p))
+}
+
+/// Inventory index of a stack holding `ingredient`, if any
+pub fn find_ingredient(inventory: &Inventory, ingredient: Ingredient) -> Option<usize> {
+    inventory
+        .items
+        .iter()
+        .position(|item| Ingredient::from_item(item) == Some(ingredient))
+}
+
+/// How many of `ingredient` the inventory holds
+pub fn count_ingredient(inventory: &Inventory, ingredient: Ingredient) -> u32 {
+    inventory
+        .items
+        .iter()
+        .filter(|item| Ingredient::from_item(item) == Some(ingredient))
+        .map(|item| item.stack_count)
+        .sum()
+}
+
+/// Whether the inventory holds everything in the pot
+pub fn has_ingredients(inventory: &Inventory, pot: &[Ingredient]) -> bool {
+    Ingredient::ALL.into_iter().all(|ingredient| {
+        let needed = pot.iter().filter(|i| **i == ingredient).count() as u32;
+        count_ingredient(inventory, ingredient) >= needed
+    })
+}
+
+/// Remove everything in the pot from the inventory. Returns false, and
+/// removes nothing, if anything is missing.
+pub fn take_ingredients(inventory: &mut Inventory, pot: &[Ingredient]) -> bool {
+    if !has_ingredients(inventory, pot) {
+        return false;
+    }
+    for ingredient in pot {
+        if let Some(index) = find_ingredient(inventory, *ingredient) {
+            inventory.remove_item_stack(index, 1);
+        }
+    }
+    true
+}
+
+/// A food buff being enjoyed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActiveFoodBuff {
+    pub kind: FoodBuffKind,
+    pub strength: f32,
+    pub remaining: f32,
+    /// The dish it came from
+    pub source: &'static str,
+}
+
+/// Food buffs currently on the player
+#[derive(Debug, Clone, Default)]
+pub struct FoodBuffs {
+    active: Vec<ActiveFoodBuff>,
+}
+
+impl FoodBuffs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Eat a meal: its buffs replace any of the same kind. Returns the HP
+    /// it heals straight away.
+    pub fn eat(&mut self, recipe: &'static Recipe) -> f32 {
+        for buff in recipe.buffs {
+            self.active.retain(|a| a.kind != buff.kind);
+            self.active.push(ActiveFoodBuff {
+                kind: buff.kind,
+                strength: buff.strength,
+                remaining: buff.duration,
+                source: recipe.name,
+            });
+        }
+        recipe.heal
+    }
+
+    /// Tick buffs down. Returns the HP regenerated this frame.
+    pub fn update(&mut self, delta: f32) -> f32 {
+        let heal = self.strength(FoodBuffKind::Regen) * delta;
+        for buff in &mut self.active {
+            buff.remaining -= delta;
+        }
+        self.active.retain(|a| a.remaining > 0.0);
+        heal
+    }
+
+    fn strength(&self, kind: FoodBuffKind) -> f32 {
+        self.active
+            .iter()
+            .find(|a| a.kind == kind)
+            .map(|a| a.strength)
+            .unwrap_or(0.0)
+    }
+
+    /// Multiplier on stamina regeneration (1.0 without a stamina buff)
+    pub fn stamina_regen_scale(&self) -> f32 {
+        self.strength(FoodBuffKind::Stamina).max(1.0)
+    }
+
+    /// Extra resistance to cold damage
+    pub fn cold_resistance(&self) -> f32 {
+        self.strength(FoodBuffKind::ColdResistance)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ActiveFoodBuff> {
+        self.active.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gathering::create_material;
+
+    #[test]
+    fn test_recipes_are_sorted_and_unique() {
+        for (i, recipe) in RECIPES.iter().enumerate() {
+            assert!(recipe.ingredients.windows(2).all(|w| w[0] <= w[1]), "{}", recipe.name);
+            assert!(recipe.ingredients.len() <= MAX_POT_INGREDIENTS);
+            assert!(RECIPES[i + 1..].iter().all(|r| r.ingredients != recipe.ingredients));
+            assert_eq!(recipe_for_item(create_food(recipe, 1).id), Some(recipe));
+        }
+    }
+
+    #[test]
+    fn test_experimenting_discovers_recipes() {
+        let mut book = RecipeBook::new();
+        let pot = [Ingredient::Herbs, Ingredient::MammothMeat, Ingredient::Herbs];
+
+        assert!(matches!(
+            book.cook(&pot, Era::Ancient),
+            CookOutcome::Cooked { recipe, discovered: true } if recipe.name == "Mammoth Stew"
+        ));
+        assert!(matches!(book.cook(&pot, Era::Ancient), CookOutcome::Cooked { discovered: false, .. }));
+        assert!(book.knows("Mammoth Stew"));
+
+        // Era-specific dishes don't cook elsewhere, and nothing is wasted
+        let outcome = book.cook(&[Ingredient::ProteinPaste, Ingredient::NutrientGel], Era::Ancient);
+        assert!(matches!(outcome, CookOutcome::WrongEra(r) if r.name == "Protein Synth"));
+        assert!(!outcome.consumes_ingredients());
+
+        assert_eq!(book.cook(&[Ingredient::Meat, Ingredient::Grain], Era::Medieval), CookOutcome::Incomplete);
+        let ruined = book.cook(&[Ingredient::Spices, Ingredient::NutrientGel], Era::Medieval);
+        assert_eq!(ruined, CookOutcome::Ruined);
+        assert!(ruined.consumes_ingredients());
+        assert_eq!(book.known().count(), 1);
+    }
+
+    #[test]
+    fn test_taking_ingredients_counts_gathered_herbs() {
+        let mut inventory = Inventory::new();
+        inventory.add_item(Ingredient::Meat.create(1)).unwrap();
+        inventory.add_item(create_material(ResourceKind::Herb, Era::Medieval, 1)).unwrap();
+        inventory.add_item(Ingredient::Herbs.create(1)).unwrap();
+
+        let pot = [Ingredient::Meat, Ingredient::Herbs, Ingredient::Herbs, Ingredient::Grain];
+        assert!(!take_ingredients(&mut inventory, &pot));
+        assert_eq!(inventory.items.len(), 3);
+
+        assert!(take_ingredients(&mut inventory, &pot[..3]));
+        assert!(inventory.items.is_empty());
+    }
+
+    #[test]
+    fn test_food_buffs_replace_and_expire() {
+        let mut buffs = FoodBuffs::new();
+        assert_eq!(buffs.stamina_regen_scale(), 1.0);
+        let stew = recipe("Hearty Stew").unwrap();
+        assert_eq!(buffs.eat(stew), stew.heal);
+        assert_eq!(buffs.cold_resistance(), 6.0);
+
+        // The stronger stew replaces the weaker one's buffs instead of stacking
+        buffs.eat(recipe("Mammoth Stew").unwrap());
+        assert_eq!(buffs.iter().count(), 2);
+        assert_eq!(buffs.cold_resistance(), 12.0);
+
+        let healed = buffs.update(1.0);
+        assert!((healed - 3.0).abs() < 1e-4);
+        buffs.update(180.0);
+        assert_eq!(buffs.update(1.0), 0.0);
+        assert!(buffs.cold_resistance() > 0.0);
+        buffs.update(300.0);
+        assert!(buffs.is_empty());
+    }
+}
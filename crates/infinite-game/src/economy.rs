@@ -0,0 +1,598 @@
+//! Settlement economy: supply, prices and the caravans that move goods
+//!
+//! Every settlement has a market holding a stock of each category of goods.
+//! Stock drifts toward an equilibrium set by what the settlement produces and
+//! consumes in the current era, with each settlement making more of one
+//! category (its specialty) and wanting more of another. Prices follow
+//! supply: scarce goods cost more, plentiful ones less, and every item the
+//! player buys or sells moves the stock by one unit, so buying in bulk drives
+//! prices up.
+//!
+//! Caravans carry surplus along the roads to neighbouring settlements that
+//! are short of it. The player can escort one by travelling with it, and is
+//! paid on arrival; some caravans are ambushed on the way and are looted
+//! unless the bandits are fought off.
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use infinite_core::DetRng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::item::ItemCategory;
+use crate::npc::NpcId;
+
+/// Categories of goods traded at markets
+pub const GOODS: [ItemCategory; 7] = [
+    ItemCategory::Weapon,
+    ItemCategory::Armor,
+    ItemCategory::Accessory,
+    ItemCategory::Consumable,
+    ItemCategory::Material,
+    ItemCategory::Gem,
+    ItemCategory::Rune,
+];
+
+/// Units of stock at which goods sell at their list price
+const TARGET_STOCK: f32 = 20.0;
+/// How strongly prices react to supply
+const ELASTICITY: f32 = 0.5;
+/// Price multipliers never go beyond these
+const PRICE_RANGE: (f32, f32) = (0.5, 3.0);
+/// Output of a settlement's specialty, and demand for what it needs,
+/// relative to the era's
+const SPECIALTY_FACTOR: f32 = 2.0;
+/// Seconds between looking for surplus to send out
+const DISPATCH_INTERVAL: f32 = 30.0;
+/// A caravan goes out when a neighbour holds this much less of something,
+/// as a fraction of the target stock
+const SHORTAGE_GAP: f32 = 0.6;
+/// Caravan walking speed in m/s, so the player can keep up on foot
+const CARAVAN_SPEED: f32 = 1.6;
+/// The player escorts a caravan by staying this close to it
+pub const ESCORT_RANGE: f32 = 20.0;
+/// Escort pay per unit of cargo delivered
+const REWARD_PER_UNIT: f32 = 6.0;
+/// Chance that a caravan meets bandits on the way
+const AMBUSH_CHANCE: f32 = 0.35;
+/// Seconds bandits need to loot a caravan that isn't defended
+const LOOT_TIME: f32 = 45.0;
+
+/// How an era makes and uses each category of goods, in units per minute
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EraProfile {
+    pub production: [f32; GOODS.len()],
+    pub consumption: [f32; GOODS.len()],
+}
+
+impl EraProfile {
+    /// Weapons, armor, accessories, consumables, materials, gems and runes
+    /// made and used by the era a year falls in
+    pub fn for_year(year: i64) -> Self {
+        let (production, consumption) = match year {
+            // Smiths and quarries; magic is scarce and sought after
+            y if y < 500 => ([1.2, 1.0, 0.4, 1.5, 3.0, 0.6, 0.3], [1.0, 0.8, 0.3, 1.6, 2.0, 0.4, 0.6]),
+            // Guild workshops and rune scribes
+            y if y < 1900 => ([1.5, 1.2, 0.8, 1.5, 2.0, 0.8, 1.0], [1.2, 1.2, 0.6, 1.5, 2.2, 0.6, 0.8]),
+            // Factories make plenty; raw materials run short
+            y if y <= 2100 => ([2.0, 1.5, 1.8, 3.0, 1.2, 0.5, 0.4], [1.5, 1.0, 1.5, 2.5, 2.0, 0.6, 0.5]),
+            // Fabricators make almost anything, but gems and runes stay rare
+            _ => ([2.5, 2.5, 2.0, 3.0, 2.5, 0.3, 0.2], [2.0, 2.0, 1.5, 2.5, 2.0, 0.5, 0.5]),
+        };
+        Self { production, consumption }
+    }
+}
+
+fn good_index(category: ItemCategory) -> usize {
+    GOODS.iter().position(|&good| good == category).unwrap_or(0)
+}
+
+/// A settlement's market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Market {
+    pub settlement: u64,
+    pub name: String,
+    pub position: Vec3,
+    /// Category the settlement makes more of
+    pub specialty: ItemCategory,
+    /// Category the settlement uses more of
+    pub need: ItemCategory,
+    stock: [f32; GOODS.len()],
+}
+
+impl Market {
+    /// A market at equilibrium for `year`. The specialty and need follow from
+    /// the settlement's id.
+    pub fn new(settlement: u64, name: impl Into<String>, position: Vec3, year: i64) -> Self {
+        let mut rng = DetRng::new(settlement);
+        let specialty = (rng.next_f32() * GOODS.len() as f32) as usize % GOODS.len();
+        let need = (specialty + 1 + (rng.next_f32() * (GOODS.len() - 1) as f32) as usize) % GOODS.len();
+        let mut market = Self {
+            settlement,
+            name: name.into(),
+            position,
+            specialty: GOODS[specialty],
+            need: GOODS[need],
+            stock: [0.0; GOODS.len()],
+        };
+        let profile = EraProfile::for_year(year);
+        for (index, stock) in market.stock.iter_mut().enumerate() {
+            let (production, consumption) = market_rates(&profile, GOODS[index], market.specialty, market.need);
+            *stock = TARGET_STOCK * production / consumption;
+        }
+        market
+    }
+
+    /// Units of a category on hand
+    pub fn stock(&self, category: ItemCategory) -> f32 {
+        self.stock[good_index(category)]
+    }
+
+    pub fn in_stock(&self, category: ItemCategory) -> bool {
+        self.stock(category) >= 1.0
+    }
+
+    /// Factor on list prices for a category: above 1.0 when it's scarce
+    pub fn price_multiplier(&self, category: ItemCategory) -> f32 {
+        (TARGET_STOCK / self.stock(category).max(0.1))
+            .powf(ELASTICITY)
+            .clamp(PRICE_RANGE.0, PRICE_RANGE.1)
+    }
+
+    /// What the market charges for an item listed at `base`
+    pub fn buy_price(&self, category: ItemCategory, base: u64) -> u64 {
+        ((base as f32 * self.price_multiplier(category)).round() as u64).max(1)
+    }
+
+    /// What the market pays for an item it would normally pay `base` for
+    pub fn sell_price(&self, category: ItemCategory, base: u64) -> u64 {
+        ((base as f32 * self.price_multiplier(category)).round() as u64).max(1)
+    }
+
+    /// The player bought one item of a category
+    pub fn record_purchase(&mut self, category: ItemCategory) {
+        let stock = &mut self.stock[good_index(category)];
+        *stock = (*stock - 1.0).max(0.0);
+    }
+
+    /// The player sold one item of a category
+    pub fn record_sale(&mut self, category: ItemCategory) {
+        self.stock[good_index(category)] += 1.0;
+    }
+
+    /// Produce and consume for `minutes`. Consumption scales with stock, so
+    /// stock settles where production and consumption balance.
+    fn simulate(&mut self, profile: &EraProfile, minutes: f32) {
+        for (index, stock) in self.stock.iter_mut().enumerate() {
+            let (production, consumption) = market_rates(profile, GOODS[index], self.specialty, self.need);
+            *stock = (*stock + (production - consumption * *stock / TARGET_STOCK) * minutes).max(0.0);
+        }
+    }
+}
+
+/// Production and consumption of a category at a market
+fn market_rates(profile: &EraProfile, category: ItemCategory, specialty: ItemCategory, need: ItemCategory) -> (f32, f32) {
+    let index = good_index(category);
+    let production = profile.production[index] * if category == specialty { SPECIALTY_FACTOR } else { 1.0 };
+    let consumption = profile.consumption[index] * if category == need { SPECIALTY_FACTOR } else { 1.0 };
+    (production, consumption.max(0.01))
+}
+
+/// Where a caravan's ambush stands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Ambush {
+    /// Bandits wait this fraction of the way along the route
+    Waiting(f32),
+    /// Bandits are attacking; the caravan is looted when the timer runs out
+    Fighting {
+        #[serde(skip)]
+        bandits: Vec<NpcId>,
+        timer: f32,
+    },
+    /// Fought off, or there never were any
+    Clear,
+}
+
+/// Goods on their way from one market to another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Caravan {
+    pub id: u64,
+    pub from: u64,
+    pub to: u64,
+    pub cargo: ItemCategory,
+    pub amount: f32,
+    /// Distance travelled along the route
+    pub travelled: f32,
+    pub ambush: Ambush,
+    /// Whether the player has joined as an escort
+    pub escorted: bool,
+}
+
+impl Caravan {
+    /// Gold the escort is paid on arrival
+    pub fn reward(&self) -> u64 {
+        (self.amount * REWARD_PER_UNIT).round() as u64
+    }
+
+    pub fn under_attack(&self) -> bool {
+        matches!(self.ambush, Ambush::Fighting { .. })
+    }
+}
+
+/// A road between two markets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Route {
+    a: u64,
+    b: u64,
+    /// Waypoints from `a` to `b`
+    path: Vec<Vec3>,
+    length: f32,
+}
+
+impl Route {
+    /// Position after `distance` along the route, walked from `from`
+    fn position(&self, from: u64, distance: f32) -> Vec3 {
+        let forward = from == self.a;
+        let mut remaining = if forward { distance } else { self.length - distance }.clamp(0.0, self.length);
+        for pair in self.path.windows(2) {
+            let length = pair[0].distance(pair[1]);
+            if remaining <= length {
+                return pair[0].lerp(pair[1], remaining / length.max(f32::EPSILON));
+            }
+            remaining -= length;
+        }
+        self.path.last().copied().unwrap_or_default()
+    }
+}
+
+/// Something that happened in the economy this update
+#[derive(Debug, Clone, PartialEq)]
+pub enum EconomyEvent {
+    Departed { caravan: u64 },
+    /// The player came close enough to a caravan to escort it
+    EscortJoined { caravan: u64 },
+    /// Bandits sprang on a caravan at `position`. Undefended caravans are
+    /// looted straight away (a `Looted` event follows).
+    Ambushed { caravan: u64, position: Vec3 },
+    /// Every bandit attacking the caravan was defeated
+    Defended { caravan: u64 },
+    Looted { caravan: u64 },
+    /// The caravan delivered its cargo; `reward` is the escort's pay
+    Arrived { caravan: u64, reward: Option<u64> },
+}
+
+/// Markets, the roads between them, and the caravans on the roads.
+/// Persisted with the save.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Economy {
+    markets: BTreeMap<u64, Market>,
+    routes: Vec<Route>,
+    caravans: Vec<Caravan>,
+    next_caravan: u64,
+    dispatch_timer: f32,
+}
+
+impl Economy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a market for a settlement, unless it already has one
+    pub fn add_market(&mut self, market: Market) {
+        self.markets.entry(market.settlement).or_insert(market);
+    }
+
+    /// Join two markets by a road through `path` (from `a` to `b`)
+    pub fn connect(&mut self, a: u64, b: u64, path: Vec<Vec3>) {
+        if a == b || path.len() < 2 || self.route(a, b).is_some() {
+            return;
+        }
+        let length = path.windows(2).map(|pair| pair[0].distance(pair[1])).sum();
+        self.routes.push(Route { a, b, path, length });
+    }
+
+    pub fn market(&self, settlement: u64) -> Option<&Market> {
+        self.markets.get(&settlement)
+    }
+
+    pub fn market_mut(&mut self, settlement: u64) -> Option<&mut Market> {
+        self.markets.get_mut(&settlement)
+    }
+
+    pub fn markets(&self) -> impl Iterator<Item = &Market> {
+        self.markets.values()
+    }
+
+    /// The market closest to a position, which supplies the shops around it
+    pub fn nearest_market(&self, position: Vec3) -> Option<u64> {
+        self.markets
+            .values()
+            .min_by(|a, b| {
+                let da = a.position.with_y(0.0).distance_squared(position.with_y(0.0));
+                let db = b.position.with_y(0.0).distance_squared(position.with_y(0.0));
+                da.total_cmp(&db)
+            })
+            .map(|market| market.settlement)
+    }
+
+    pub fn caravans(&self) -> impl Iterator<Item = &Caravan> {
+        self.caravans.iter()
+    }
+
+    pub fn caravan(&self, id: u64) -> Option<&Caravan> {
+        self.caravans.iter().find(|caravan| caravan.id == id)
+    }
+
+    /// Where a caravan is now
+    pub fn caravan_position(&self, id: u64) -> Option<Vec3> {
+        let caravan = self.caravan(id)?;
+        let route = self.route(caravan.from, caravan.to)?;
+        Some(route.position(caravan.from, caravan.travelled))
+    }
+
+    /// Record the bandits spawned for an ambush, so it ends when they're down
+    pub fn set_bandits(&mut self, caravan: u64, ids: Vec<NpcId>) {
+        if let Some(Ambush::Fighting { bandits, .. }) =
+            self.caravans.iter_mut().find(|c| c.id == caravan).map(|c| &mut c.ambush)
+        {
+            *bandits = ids;
+        }
+    }
+
+    /// Advance the economy by `delta` seconds in `year`. `player` is where
+    /// the player is (None while they're away from the overworld), and
+    /// `alive` tells whether an NPC is still standing.
+    pub fn update(
+        &mut self,
+        delta: f32,
+        year: i64,
+        player: Option<Vec3>,
+        alive: impl Fn(NpcId) -> bool,
+    ) -> Vec<EconomyEvent> {
+        let profile = EraProfile::for_year(year);
+        for market in self.markets.values_mut() {
+            market.simulate(&profile, delta / 60.0);
+        }
+
+        let mut events = Vec::new();
+        self.dispatch_timer += delta;
+        if self.dispatch_timer >= DISPATCH_INTERVAL {
+            self.dispatch_timer = 0.0;
+            self.dispatch(&mut events);
+        }
+
+        let mut finished = Vec::new();
+        for index in 0..self.caravans.len() {
+            let Some(route) = self.route(self.caravans[index].from, self.caravans[index].to).cloned() else {
+                finished.push(index);
+                continue;
+            };
+            let caravan = &mut self.caravans[index];
+            let position = route.position(caravan.from, caravan.travelled);
+            let near = player.is_some_and(|p| p.with_y(0.0).distance(position.with_y(0.0)) <= ESCORT_RANGE);
+            if near && !caravan.escorted && !caravan.under_attack() {
+                caravan.escorted = true;
+                events.push(EconomyEvent::EscortJoined { caravan: caravan.id });
+            }
+
+            match &mut caravan.ambush {
+                Ambush::Fighting { bandits, timer } => {
+                    *timer -= delta;
+                    if bandits.iter().all(|&id| !alive(id)) && !bandits.is_empty() {
+                        caravan.ambush = Ambush::Clear;
+                        events.push(EconomyEvent::Defended { caravan: caravan.id });
+                    } else if *timer <= 0.0 {
+                        events.push(EconomyEvent::Looted { caravan: caravan.id });
+                        finished.push(index);
+                    }
+                    continue;
+                }
+                Ambush::Waiting(at) if caravan.travelled >= *at * route.length => {
+                    events.push(EconomyEvent::Ambushed { caravan: caravan.id, position });
+                    if near {
+                        caravan.ambush = Ambush::Fighting { bandits: Vec::new(), timer: LOOT_TIME };
+                    } else {
+                        events.push(EconomyEvent::Looted { caravan: caravan.id });
+                        finished.push(index);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            caravan.travelled += CARAVAN_SPEED * delta;
+            if caravan.travelled >= route.length {
+                let reward = (caravan.escorted && near).then(|| caravan.reward());
+                events.push(EconomyEvent::Arrived { caravan: caravan.id, reward });
+                if let Some(market) = self.markets.get_mut(&caravan.to) {
+                    market.stock[good_index(caravan.cargo)] += caravan.amount;
+                }
+                finished.push(index);
+            }
+        }
+        for index in finished.into_iter().rev() {
+            self.caravans.remove(index);
+        }
+        events
+    }
+
+    /// Send surplus along each road not already carrying a caravan
+    fn dispatch(&mut self, events: &mut Vec<EconomyEvent>) {
+        let mut departing = Vec::new();
+        for route in &self.routes {
+            let busy = self.caravans.iter().any(|c| (c.from, c.to) == (route.a, route.b) || (c.from, c.to) == (route.b, route.a));
+            let (Some(a), Some(b)) = (self.markets.get(&route.a), self.markets.get(&route.b)) else {
+                continue;
+            };
+            if busy {
+                continue;
+            }
+
+            // The biggest imbalance along the road, in either direction
+            let mut best: Option<(f32, u64, u64, usize)> = None;
+            for index in 0..GOODS.len() {
+                let gap = (a.stock[index] - b.stock[index]) / TARGET_STOCK;
+                let (gap, from, to) = if gap >= 0.0 { (gap, a, b) } else { (-gap, b, a) };
+                if gap >= SHORTAGE_GAP && best.is_none_or(|(largest, ..)| gap > largest) {
+                    best = Some((gap, from.settlement, to.settlement, index));
+                }
+            }
+            let Some((_, from, to, index)) = best else {
+                continue;
+            };
+
+            let id = self.next_caravan;
+            self.next_caravan += 1;
+            let mut rng = DetRng::new(from ^ to.rotate_left(17) ^ id.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+            let ambush = if rng.chance(AMBUSH_CHANCE) { Ambush::Waiting(rng.range_f32(0.3, 0.7)) } else { Ambush::Clear };
+            let amount = ((self.markets[&from].stock[index] - self.markets[&to].stock[index]) * 0.5).floor().max(1.0);
+            departing.push(Caravan {
+                id,
+                from,
+                to,
+                cargo: GOODS[index],
+                amount,
+                travelled: 0.0,
+                ambush,
+                escorted: false,
+            });
+            events.push(EconomyEvent::Departed { caravan: id });
+        }
+
+        for caravan in departing {
+            if let Some(market) = self.markets.get_mut(&caravan.from) {
+                let stock = &mut market.stock[good_index(caravan.cargo)];
+                *stock = (*stock - caravan.amount).max(0.0);
+            }
+            self.caravans.push(caravan);
+        }
+    }
+
+    fn route(&self, a: u64, b: u64) -> Option<&Route> {
+        self.routes
+            .iter()
+            .find(|route| (route.a, route.b) == (a, b) || (route.a, route.b) == (b, a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn economy() -> Economy {
+        let mut economy = Economy::new();
+        economy.add_market(Market::new(1, "Ashford", Vec3::ZERO, 2025));
+        economy.add_market(Market::new(2, "Brookdale", Vec3::new(100.0, 0.0, 0.0), 2025));
+        economy.connect(1, 2, vec![Vec3::ZERO, Vec3::new(50.0, 0.0, 0.0), Vec3::new(100.0, 0.0, 0.0)]);
+        economy
+    }
+
+    #[test]
+    fn test_prices_follow_supply_and_bulk_trade() {
+        let mut market = Market::new(1, "Ashford", Vec3::ZERO, 2025);
+        assert_ne!(market.specialty, market.need);
+        // The specialty is plentiful and cheap, the need scarce and dear
+        assert!(market.price_multiplier(market.specialty) < 1.0);
+        assert!(market.price_multiplier(market.need) > 1.0);
+
+        let category = ItemCategory::Weapon;
+        let before = market.buy_price(category, 100);
+        for _ in 0..10 {
+            market.record_purchase(category);
+        }
+        assert!(market.buy_price(category, 100) > before, "buying in bulk drives the price up");
+        for _ in 0..40 {
+            market.record_sale(category);
+        }
+        assert!(market.sell_price(category, 50) < 50, "a glut pays less");
+
+        // Drained stock recovers toward equilibrium over time
+        while market.in_stock(category) {
+            market.record_purchase(category);
+        }
+        market.simulate(&EraProfile::for_year(2025), 30.0);
+        assert!(market.in_stock(category));
+
+        // Eras make different things
+        assert_ne!(EraProfile::for_year(-200), EraProfile::for_year(2025));
+    }
+
+    #[test]
+    fn test_caravans_carry_surplus_to_shortages() {
+        let mut economy = economy();
+        // Empty Brookdale of weapons so Ashford sends some
+        let market = economy.market_mut(2).unwrap();
+        while market.in_stock(ItemCategory::Weapon) {
+            market.record_purchase(ItemCategory::Weapon);
+        }
+        let events = economy.update(DISPATCH_INTERVAL, 2025, None, |_| true);
+        let caravan = economy.caravans().next().unwrap().clone();
+        assert!(events.contains(&EconomyEvent::Departed { caravan: caravan.id }));
+        assert_eq!((caravan.from, caravan.to, caravan.cargo), (1, 2, ItemCategory::Weapon));
+        assert!(events.len() == 1, "one caravan per road");
+
+        // Walk alongside it the whole way
+        economy.caravans[0].ambush = Ambush::Clear;
+        let mut reward = None;
+        let mut joined = false;
+        for _ in 0..200 {
+            let player = economy.caravan_position(caravan.id);
+            for event in economy.update(1.0, 2025, player, |_| true) {
+                match event {
+                    EconomyEvent::EscortJoined { .. } => joined = true,
+                    EconomyEvent::Arrived { reward: paid, .. } => reward = paid,
+                    _ => {}
+                }
+            }
+            if economy.caravan(caravan.id).is_none() {
+                break;
+            }
+        }
+        assert!(joined);
+        assert_eq!(reward, Some(caravan.reward()));
+        assert!(economy.market(2).unwrap().stock(ItemCategory::Weapon) >= caravan.amount);
+    }
+
+    #[test]
+    fn test_ambushes_loot_undefended_caravans() {
+        let mut economy = economy();
+        economy.caravans.push(Caravan {
+            id: 9,
+            from: 1,
+            to: 2,
+            cargo: ItemCategory::Gem,
+            amount: 4.0,
+            travelled: 0.0,
+            ambush: Ambush::Waiting(0.5),
+            escorted: false,
+        });
+        let mut events = Vec::new();
+        for _ in 0..60 {
+            events.extend(economy.update(1.0, 2025, None, |_| true));
+        }
+        assert!(events.iter().any(|e| matches!(e, EconomyEvent::Ambushed { caravan: 9, .. })));
+        assert!(events.contains(&EconomyEvent::Looted { caravan: 9 }));
+        assert!(economy.caravan(9).is_none());
+
+        // Defended: the caravan waits out the fight and moves on once the
+        // bandits are down
+        economy.caravans.push(Caravan {
+            id: 10,
+            from: 1,
+            to: 2,
+            cargo: ItemCategory::Gem,
+            amount: 4.0,
+            travelled: 50.0,
+            ambush: Ambush::Waiting(0.5),
+            escorted: true,
+        });
+        let here = economy.caravan_position(10);
+        economy.update(1.0, 2025, here, |_| true);
+        assert!(economy.caravan(10).unwrap().under_attack());
+        economy.set_bandits(10, vec![NpcId(5)]);
+        economy.update(1.0, 2025, here, |_| true);
+        assert!(economy.caravan(10).unwrap().under_attack());
+        let events = economy.update(1.0, 2025, here, |_| false);
+        assert_eq!(events, vec![EconomyEvent::Defended { caravan: 10 }]);
+    }
+}
@@ -0,0 +1,395 @@
+//! World flags: a global key-value store for game state
+//!
+//! Quests, dialogue conditions, scripts and interactables record facts about
+//! the world here ("the lever by the gate is on", "the elder told you about
+//! the rifts"). Keys are namespaced per system so they don't collide, every
+//! change is queued as a notification for systems that react to flags, and
+//! the whole store is persisted with the save.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::interaction::InteractionResult;
+
+/// Namespace for state recorded from interactables
+pub const INTERACTION_NAMESPACE: &str = "world";
+/// Namespace for reputation with factions, as ints keyed by faction
+pub const REPUTATION_NAMESPACE: &str = "reputation";
+
+/// A flag value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlagValue {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl FlagValue {
+    /// Truthiness: false, 0 and "" are unset-like
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Bool(b) => *b,
+            Self::Int(i) => *i != 0,
+            Self::Str(s) => !s.is_empty(),
+        }
+    }
+}
+
+impl fmt::Display for FlagValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Int(i) => write!(f, "{}", i),
+            Self::Str(s) => write!(f, "\"{}\"", s),
+        }
+    }
+}
+
+impl From<bool> for FlagValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for FlagValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<&str> for FlagValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<String> for FlagValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+/// A flag that was set, changed or removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagChange {
+    pub namespace: String,
+    pub key: String,
+    pub old: Option<FlagValue>,
+    /// None if the flag was removed
+    pub new: Option<FlagValue>,
+}
+
+/// Global flag store, grouped by namespace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorldFlags {
+    values: BTreeMap<String, BTreeMap<String, FlagValue>>,
+    /// Changes since the last `drain_changes`
+    #[serde(skip)]
+    changes: Vec<FlagChange>,
+}
+
+impl WorldFlags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, namespace: &str, key: &str) -> Option<&FlagValue> {
+        self.values.get(namespace)?.get(key)
+    }
+
+    /// Bool flag, false if unset or not a bool
+    pub fn get_bool(&self, namespace: &str, key: &str) -> bool {
+        matches!(self.get(namespace, key), Some(FlagValue::Bool(true)))
+    }
+
+    /// Int flag, 0 if unset or not an int
+    pub fn get_int(&self, namespace: &str, key: &str) -> i64 {
+        match self.get(namespace, key) {
+            Some(FlagValue::Int(i)) => *i,
+            _ => 0,
+        }
+    }
+
+    pub fn get_str(&self, namespace: &str, key: &str) -> Option<&str> {
+        match self.get(namespace, key) {
+            Some(FlagValue::Str(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Set a flag. A change is only queued if the value actually changed.
+    pub fn set(&mut self, namespace: &str, key: &str, value: impl Into<FlagValue>) {
+        let value = value.into();
+        let old = self
+            .values
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(key.to_string(), value.clone());
+        if old.as_ref() != Some(&value) {
+            self.changes.push(FlagChange {
+                namespace: namespace.to_string(),
+                key: key.to_string(),
+                old,
+                new: Some(value),
+            });
+        }
+    }
+
+    /// Add `delta` to an int flag (unset counts as 0) and return the result
+    pub fn add_int(&mut self, namespace: &str, key: &str, delta: i64) -> i64 {
+        let value = self.get_int(namespace, key) + delta;
+        self.set(namespace, key, value);
+        value
+    }
+
+    /// Remove a flag, returning its old value
+    pub fn remove(&mut self, namespace: &str, key: &str) -> Option<FlagValue> {
+        let entries = self.values.get_mut(namespace)?;
+        let old = entries.remove(key)?;
+        if entries.is_empty() {
+            self.values.remove(namespace);
+        }
+        self.changes.push(FlagChange {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            old: Some(old.clone()),
+            new: None,
+        });
+        Some(old)
+    }
+
+    /// Remove every flag of a namespace (e.g. when a quest is reset)
+    pub fn clear_namespace(&mut self, namespace: &str) {
+        let keys: Vec<String> = self.keys(namespace).map(str::to_string).collect();
+        for key in keys {
+            self.remove(namespace, &key);
+        }
+    }
+
+    /// Namespaces that hold at least one flag, sorted
+    pub fn namespaces(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Flag keys of a namespace, sorted
+    pub fn keys<'a>(&'a self, namespace: &str) -> impl Iterator<Item = &'a str> {
+        self.values
+            .get(namespace)
+            .into_iter()
+            .flat_map(|entries| entries.keys().map(String::as_str))
+    }
+
+    /// All flags as (namespace, key, value), sorted
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &FlagValue)> {
+        self.values.iter().flat_map(|(namespace, entries)| {
+            entries
+                .iter()
+                .map(move |(key, value)| (namespace.as_str(), key.as_str(), value))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Take the changes queued since the last call
+    pub fn drain_changes(&mut self) -> Vec<FlagChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Record the outcome of using an interactable, so conditions can refer
+    /// to switches that were flipped and items that were picked up
+    pub fn record_interaction(&mut self, result: &InteractionResult) {
+        match result {
+            InteractionResult::ToggleDoor { id, now_open } => {
+                self.set(INTERACTION_NAMESPACE, &format!("door.{}.open", id.0), *now_open);
+            }
+            InteractionResult::ToggleLever { id, now_on, .. } => {
+                self.set(INTERACTION_NAMESPACE, &format!("lever.{}.on", id.0), *now_on);
+            }
+            InteractionResult::PressButton { id } => {
+                self.add_int(INTERACTION_NAMESPACE, &format!("button.{}.presses", id.0), 1);
+            }
+            InteractionResult::OpenContainer { id, .. } => {
+                self.set(INTERACTION_NAMESPACE, &format!("container.{}.opened", id.0), true);
+            }
+            InteractionResult::PickupItem(name) => {
+                self.add_int(INTERACTION_NAMESPACE, &format!("picked_up.{}", name), 1);
+            }
+            InteractionResult::DisarmTrap(id) => {
+                self.set(INTERACTION_NAMESPACE, &format!("trap.{}.disarmed", id.0), true);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Test applied to a flag's value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FlagTest {
+    /// Set to a truthy value
+    IsSet,
+    /// Unset or falsy
+    IsUnset,
+    Equals(FlagValue),
+    /// Int flag of at least this value
+    AtLeast(i64),
+}
+
+/// A condition on one flag, e.g. for gating a dialogue response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagCondition {
+    pub namespace: String,
+    pub key: String,
+    pub test: FlagTest,
+}
+
+impl FlagCondition {
+    pub fn new(namespace: &str, key: &str, test: FlagTest) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            test,
+        }
+    }
+
+    /// Shorthand for an `IsSet` condition
+    pub fn is_set(namespace: &str, key: &str) -> Self {
+        Self::new(namespace, key, FlagTest::IsSet)
+    }
+
+    pub fn check(&self, flags: &WorldFlags) -> bool {
+        let value = flags.get(&self.namespace, &self.key);
+        match &self.test {
+            FlagTest::IsSet => value.is_some_and(FlagValue::is_truthy),
+            FlagTest::IsUnset => !value.is_some_and(FlagValue::is_truthy),
+            FlagTest::Equals(expected) => value == Some(expected),
+            FlagTest::AtLeast(min) => matches!(value, Some(FlagValue::Int(i)) if i >= min),
+        }
+    }
+}
+
+/// How an action changes a flag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FlagOp {
+    Set(FlagValue),
+    Add(i64),
+    Remove,
+}
+
+/// A change to one flag, e.g. applied when a dialogue response is chosen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagAction {
+    pub namespace: String,
+    pub key: String,
+    pub op: FlagOp,
+}
+
+impl FlagAction {
+    pub fn new(namespace: &str, key: &str, op: FlagOp) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            op,
+        }
+    }
+
+    /// Shorthand for setting a flag
+    pub fn set(namespace: &str, key: &str, value: impl Into<FlagValue>) -> Self {
+        Self::new(namespace, key, FlagOp::Set(value.into()))
+    }
+
+    pub fn apply(&self, flags: &mut WorldFlags) {
+        match &self.op {
+            FlagOp::Set(value) => flags.set(&self.namespace, &self.key, value.clone()),
+            FlagOp::Add(delta) => {
+                flags.add_int(&self.namespace, &self.key, *delta);
+            }
+            FlagOp::Remove => {
+                flags.remove(&self.namespace, &self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interaction::InteractableId;
+
+    #[test]
+    fn test_set_get_and_namespaces() {
+        let mut flags = WorldFlags::new();
+        flags.set("quest", "met_elder", true);
+        flags.set("quest", "wolves_killed", 3i64);
+        flags.set("dialogue", "met_elder", "twice");
+
+        assert!(flags.get_bool("quest", "met_elder"));
+        assert_eq!(flags.get_str("dialogue", "met_elder"), Some("twice"));
+        assert_eq!(flags.add_int("quest", "wolves_killed", 2), 5);
+        assert_eq!(flags.get_int("quest", "missing"), 0);
+        assert_eq!(flags.namespaces().collect::<Vec<_>>(), vec!["dialogue", "quest"]);
+        assert_eq!(flags.len(), 3);
+
+        flags.clear_namespace("quest");
+        assert_eq!(flags.len(), 1);
+        assert!(!flags.get_bool("quest", "met_elder"));
+    }
+
+    #[test]
+    fn test_change_notifications() {
+        let mut flags = WorldFlags::new();
+        flags.set("quest", "stage", 1i64);
+        flags.set("quest", "stage", 1i64);
+        flags.set("quest", "stage", 2i64);
+        flags.remove("quest", "stage");
+
+        let changes = flags.drain_changes();
+        assert_eq!(changes.len(), 3, "setting an unchanged value isn't a change");
+        assert_eq!(changes[1].old, Some(FlagValue::Int(1)));
+        assert_eq!(changes[1].new, Some(FlagValue::Int(2)));
+        assert_eq!(changes[2].new, None);
+        assert!(flags.drain_changes().is_empty());
+    }
+
+    #[test]
+    fn test_conditions_and_actions() {
+        let mut flags = WorldFlags::new();
+        let heard = FlagCondition::is_set("quest", "heard_of_rifts");
+        let veteran = FlagCondition::new("quest", "rifts_closed", FlagTest::AtLeast(2));
+        assert!(!heard.check(&flags));
+        assert!(FlagCondition::new("quest", "heard_of_rifts", FlagTest::IsUnset).check(&flags));
+
+        FlagAction::set("quest", "heard_of_rifts", true).apply(&mut flags);
+        FlagAction::new("quest", "rifts_closed", FlagOp::Add(2)).apply(&mut flags);
+        assert!(heard.check(&flags));
+        assert!(veteran.check(&flags));
+
+        FlagAction::new("quest", "heard_of_rifts", FlagOp::Remove).apply(&mut flags);
+        assert!(!heard.check(&flags));
+    }
+
+    #[test]
+    fn test_interactions_and_persistence() {
+        let mut flags = WorldFlags::new();
+        flags.record_interaction(&InteractionResult::ToggleLever {
+            id: InteractableId(4),
+            now_on: true,
+            linked: vec![],
+        });
+        flags.record_interaction(&InteractionResult::PickupItem("Gem".into()));
+        flags.record_interaction(&InteractionResult::PickupItem("Gem".into()));
+        assert!(flags.get_bool(INTERACTION_NAMESPACE, "lever.4.on"));
+        assert_eq!(flags.get_int(INTERACTION_NAMESPACE, "picked_up.Gem"), 2);
+
+        let json = serde_json::to_string(&flags).unwrap();
+        let restored: WorldFlags = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.iter().collect::<Vec<_>>(), flags.iter().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,530 @@
+//! Resource gathering: mining ore veins, felling trees and picking herbs
+//!
+//! Every chunk holds a handful of resource nodes placed from the world seed
+//! and shaped by the land and the era: herbs and trees on low ground, ore
+//! veins up in the highlands, timber everywhere in the ancient wilds and
+//! little but ore in the stripped land of the future. Gathering takes a
+//! few seconds of holding Interact with the right tool in the pack, and
+//! yields era-specific materials for crafting.
+//!
+//! Harvested nodes are recorded in their chunk's delta: veins are worked
+//! out for good, trees and herbs grow back after a while. Each kind of
+//! node trains its own gathering skill (mining, woodcutting, herbalism),
+//! whose levels gather faster and bring in more.
+
+use glam::Vec3;
+use infinite_core::{hash_seed, DetRng};
+use infinite_world::ChunkCoord;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::Element;
+use crate::combat::inventory::Inventory;
+use crate::combat::item::{Item, ItemCategory, ItemId, ItemRarity};
+use crate::npc::identity::Era;
+
+/// Item id of pickaxes
+pub const PICKAXE_ITEM_ID: ItemId = ItemId(3107);
+/// Item id of hatchets
+pub const HATCHET_ITEM_ID: ItemId = ItemId(3108);
+/// First item id of gathered materials (one per kind and era)
+pub const MATERIAL_ITEM_ID_BASE: u64 = 3400;
+/// Material item ids set aside for each kind of node, one per era
+const MATERIAL_IDS_PER_KIND: u64 = 4;
+
+/// Gold a shop charges for a pickaxe or hatchet
+pub const GATHERING_TOOL_PRICE: u64 = 60;
+
+/// Spots in each chunk that may hold a node
+const NODE_CANDIDATES: u32 = 8;
+/// Keep nodes this far inside the chunk edge
+const CHUNK_MARGIN: f32 = 2.0;
+/// Keeps resource node seeds apart from other per-chunk seeds
+const NODE_SALT: u64 = 0x90de_5eed_0000_0004;
+/// Highest gathering skill level
+pub const MAX_GATHER_LEVEL: u32 = 50;
+/// Skill levels per extra unit of yield
+const LEVELS_PER_BONUS_YIELD: u32 = 5;
+/// Gather time saved per skill level above 1
+const SPEED_PER_LEVEL: f32 = 0.04;
+
+/// What a resource node is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceKind {
+    OreVein,
+    Tree,
+    Herb,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 3] = [Self::OreVein, Self::Tree, Self::Herb];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OreVein => "Ore Vein",
+            Self::Tree => "Tree",
+            Self::Herb => "Herbs",
+        }
+    }
+
+    /// Interaction prompt
+    pub fn prompt(self) -> &'static str {
+        match self {
+            Self::OreVein => "Mine Ore Vein",
+            Self::Tree => "Chop Tree",
+            Self::Herb => "Pick Herbs",
+        }
+    }
+
+    /// The gathering skill this kind of node trains
+    pub fn skill_name(self) -> &'static str {
+        match self {
+            Self::OreVein => "Mining",
+            Self::Tree => "Woodcutting",
+            Self::Herb => "Herbalism",
+        }
+    }
+
+    /// Tool needed in the inventory; herbs are picked by hand
+    pub fn required_tool(self) -> Option<GatherTool> {
+        match self {
+            Self::OreVein => Some(GatherTool::Pickaxe),
+            Self::Tree => Some(GatherTool::Hatchet),
+            Self::Herb => None,
+        }
+    }
+
+    /// Seconds to gather at skill level 1
+    pub fn gather_time(self) -> f32 {
+        match self {
+            Self::OreVein => 5.0,
+            Self::Tree => 4.0,
+            Self::Herb => 1.5,
+        }
+    }
+
+    /// Seconds of play until a harvested node grows back (veins never do)
+    pub fn regrow_time(self) -> Option<f64> {
+        match self {
+            Self::OreVein => None,
+            Self::Tree => Some(600.0),
+            Self::Herb => Some(180.0),
+        }
+    }
+
+    /// Materials gathered at skill level 1
+    pub fn base_yield(self) -> u32 {
+        match self {
+            Self::OreVein => 2,
+            Self::Tree => 3,
+            Self::Herb => 2,
+        }
+    }
+
+    /// Gathering XP per harvest
+    pub fn xp(self) -> u64 {
+        match self {
+            Self::OreVein => 15,
+            Self::Tree => 12,
+            Self::Herb => 8,
+        }
+    }
+
+    /// Rough size of the node, for its outline and rendering
+    pub fn size(self) -> Vec3 {
+        match self {
+            Self::OreVein => Vec3::new(1.4, 1.0, 1.4),
+            Self::Tree => Vec3::new(0.8, 4.5, 0.8),
+            Self::Herb => Vec3::new(0.6, 0.5, 0.6),
+        }
+    }
+
+    /// Placeholder render color as [r, g, b] floats
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::OreVein => [0.45, 0.42, 0.4],
+            Self::Tree => [0.4, 0.28, 0.16],
+            Self::Herb => [0.35, 0.65, 0.3],
+        }
+    }
+
+    /// Name of the material this kind of node yields in `era`
+    pub fn material_name(self, era: Era) -> &'static str {
+        match (self, era) {
+            (Self::OreVein, Era::Ancient) => "Copper Ore",
+            (Self::OreVein, Era::Medieval) => "Iron Ore",
+            (Self::OreVein, Era::Modern) => "Nickel Ore",
+            (Self::OreVein, Era::Future) => "Alloy Scrap",
+            (Self::Tree, Era::Ancient) => "Ancient Timber",
+            (Self::Tree, Era::Medieval) => "Oak Log",
+            (Self::Tree, Era::Modern) => "Pine Log",
+            (Self::Tree, Era::Future) => "Glassbark Log",
+            (Self::Herb, Era::Ancient) => "Wild Herbs",
+            (Self::Herb, Era::Medieval) => "Healing Herbs",
+            (Self::Herb, Era::Modern) => "Mint",
+            (Self::Herb, Era::Future) => "Glowcap",
+        }
+    }
+
+    /// Chance (before the era) that a candidate spot at normalized
+    /// elevation `elevation` holds this kind of node
+    fn terrain_weight(self, elevation: f32) -> f32 {
+        // Same bands as the ambience biomes: grassland, forest, highland
+        let band = if elevation < 0.35 {
+            0
+        } else if elevation < 0.7 {
+            1
+        } else {
+            2
+        };
+        match self {
+            Self::OreVein => [0.05, 0.1, 0.45][band],
+            Self::Tree => [0.25, 0.5, 0.1][band],
+            Self::Herb => [0.35, 0.15, 0.1][band],
+        }
+    }
+
+    /// How much more (or less) common this kind of node is in `era`
+    fn era_weight(self, era: Era) -> f32 {
+        match (self, era) {
+            (Self::Tree, Era::Ancient) => 1.5,
+            (Self::Herb, Era::Ancient) => 1.3,
+            (Self::Tree, Era::Modern) => 0.8,
+            (Self::OreVein, Era::Future) => 1.5,
+            (Self::Tree, Era::Future) => 0.3,
+            (Self::Herb, Era::Future) => 0.5,
+            _ => 1.0,
+        }
+    }
+}
+
+/// A tool needed to gather from some nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GatherTool {
+    Pickaxe,
+    Hatchet,
+}
+
+impl GatherTool {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pickaxe => "Pickaxe",
+            Self::Hatchet => "Hatchet",
+        }
+    }
+
+    pub fn item_id(self) -> ItemId {
+        match self {
+            Self::Pickaxe => PICKAXE_ITEM_ID,
+            Self::Hatchet => HATCHET_ITEM_ID,
+        }
+    }
+
+    /// The tool as an inventory item
+    pub fn create(self) -> Item {
+        let description = match self {
+            Self::Pickaxe => "A miner's pick. Needed to work ore veins.",
+            Self::Hatchet => "A woodcutter's hatchet. Needed to fell trees.",
+        };
+        Item {
+            id: self.item_id(),
+            name: self.name().to_string(),
+            description: description.to_string(),
+            category: ItemCategory::Material,
+            rarity: ItemRarity::Common,
+            stat_modifiers: StatModifiers::default(),
+            element: Element::Physical,
+            weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
+            gem_sockets: vec![],
+            required_level: 1,
+            item_level: 1,
+            stack_count: 1,
+            max_stack: 1,
+            origin_year: None,
+        }
+    }
+}
+
+/// A gatherable node in the world
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceNode {
+    /// Chunk the node stands in
+    pub chunk: ChunkCoord,
+    /// Index within the chunk (its key in the chunk's delta)
+    pub index: u32,
+    pub kind: ResourceKind,
+    /// World position (y is left at 0 for the caller to place on the terrain)
+    pub position: Vec3,
+}
+
+/// The resource nodes of a chunk in `era`. `elevation` gives the normalized
+/// terrain elevation (0.0 = lowest, 1.0 = highest) at a world XZ position.
+/// Node indices are stable, so a chunk's delta can refer to them.
+pub fn nodes_in_chunk(
+    coord: ChunkCoord,
+    world_seed: u64,
+    chunk_size: f32,
+    era: Era,
+    elevation: impl Fn(f32, f32) -> f32,
+) -> Vec<ResourceNode> {
+    let mut rng = DetRng::new(node_seed(coord, world_seed, era));
+    let origin = coord.world_origin(chunk_size);
+    let span = (chunk_size - 2.0 * CHUNK_MARGIN).max(0.0);
+    let mut nodes = Vec::new();
+    for index in 0..NODE_CANDIDATES {
+        let x = origin.x + CHUNK_MARGIN + rng.gen::<f32>() * span;
+        let z = origin.z + CHUNK_MARGIN + rng.gen::<f32>() * span;
+        let roll = rng.gen::<f32>();
+        let elevation = elevation(x, z);
+        let mut threshold = 0.0;
+        let kind = ResourceKind::ALL.into_iter().find(|kind| {
+            threshold += kind.terrain_weight(elevation) * kind.era_weight(era);
+            roll < threshold
+        });
+        if let Some(kind) = kind {
+            nodes.push(ResourceNode { chunk: coord, index, kind, position: Vec3::new(x, 0.0, z) });
+        }
+    }
+    nodes
+}
+
+fn node_seed(coord: ChunkCoord, world_seed: u64, era: Era) -> u64 {
+    hash_seed(world_seed ^ NODE_SALT, &[coord.x as i64 as u64, coord.z as i64 as u64, era as u64])
+}
+
+/// Gathered materials from a node of `kind` in `era`
+pub fn create_material(kind: ResourceKind, era: Era, count: u32) -> Item {
+    let kind_index = ResourceKind::ALL.iter().position(|k| *k == kind).unwrap_or(0) as u64;
+    Item {
+        id: ItemId(MATERIAL_ITEM_ID_BASE + kind_index * MATERIAL_IDS_PER_KIND + era as u64),
+        name: kind.material_name(era).to_string(),
+        description: format!("Gathered from a {} for crafting.", kind.name().to_lowercase()),
+        category: ItemCategory::Material,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 20,
+        origin_year: None,
+    }
+}
+
+/// The kind of node a gathered material came from, if `id` is one
+pub fn material_kind(id: ItemId) -> Option<ResourceKind> {
+    material_origin(id).map(|(kind, _)| kind)
+}
+
+/// The kind of node and the era a gathered material came from
+pub fn material_origin(id: ItemId) -> Option<(ResourceKind, Era)> {
+    let offset = id.0.checked_sub(MATERIAL_ITEM_ID_BASE)?;
+    let kind = ResourceKind::ALL.get((offset / MATERIAL_IDS_PER_KIND) as usize)?;
+    let era = Era::ALL.get((offset % MATERIAL_IDS_PER_KIND) as usize)?;
+    Some((*kind, *era))
+}
+
+/// Check the inventory holds the tool a node needs
+pub fn check_tool(kind: ResourceKind, inventory: &Inventory) -> Result<(), GatherTool> {
+    match kind.required_tool() {
+        Some(tool) if !inventory.items.iter().any(|item| item.id == tool.item_id()) => Err(tool),
+        _ => Ok(()),
+    }
+}
+
+/// XP and level in one gathering skill
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GatherSkill {
+    pub xp: u64,
+}
+
+impl GatherSkill {
+    /// Total XP needed to reach `level`
+    pub fn xp_for_level(level: u32) -> u64 {
+        let level = level.max(1) as u64;
+        25 * level * (level - 1)
+    }
+
+    pub fn level(&self) -> u32 {
+        (1..MAX_GATHER_LEVEL).take_while(|&level| self.xp >= Self::xp_for_level(level + 1)).count() as u32 + 1
+    }
+
+    /// Progress toward the next level as a 0.0-1.0 fraction
+    pub fn xp_fraction(&self) -> f32 {
+        let level = self.level();
+        if level >= MAX_GATHER_LEVEL {
+            return 1.0;
+        }
+        let (from, to) = (Self::xp_for_level(level), Self::xp_for_level(level + 1));
+        (self.xp - from) as f32 / (to - from) as f32
+    }
+
+    /// Seconds a node of `kind` takes to gather at this level
+    pub fn gather_time(&self, kind: ResourceKind) -> f32 {
+        kind.gather_time() / (1.0 + SPEED_PER_LEVEL * (self.level() - 1) as f32)
+    }
+
+    /// Materials a node of `kind` yields at this level
+    pub fn yield_count(&self, kind: ResourceKind) -> u32 {
+        kind.base_yield() + (self.level() - 1) / LEVELS_PER_BONUS_YIELD
+    }
+}
+
+/// The gathering skills, a progression track alongside the character level
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GatheringSkills {
+    pub mining: GatherSkill,
+    pub woodcutting: GatherSkill,
+    pub herbalism: GatherSkill,
+}
+
+impl GatheringSkills {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The skill nodes of `kind` train
+    pub fn skill(&self, kind: ResourceKind) -> &GatherSkill {
+        match kind {
+            ResourceKind::OreVein => &self.mining,
+            ResourceKind::Tree => &self.woodcutting,
+            ResourceKind::Herb => &self.herbalism,
+        }
+    }
+
+    fn skill_mut(&mut self, kind: ResourceKind) -> &mut GatherSkill {
+        match kind {
+            ResourceKind::OreVein => &mut self.mining,
+            ResourceKind::Tree => &mut self.woodcutting,
+            ResourceKind::Herb => &mut self.herbalism,
+        }
+    }
+
+    /// Award the XP for harvesting a node of `kind`. Returns the skill's
+    /// new level if it went up.
+    pub fn record_harvest(&mut self, kind: ResourceKind) -> Option<u32> {
+        let skill = self.skill_mut(kind);
+        let before = skill.level();
+        skill.xp += kind.xp();
+        let after = skill.level();
+        (after > before).then_some(after)
+    }
+}
+
+/// Gathering from one node, in progress while Interact is held
+#[derive(Debug, Clone)]
+pub struct GatherSession {
+    pub node: ResourceNode,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl GatherSession {
+    pub fn new(node: ResourceNode, skills: &GatheringSkills) -> Self {
+        Self { node, elapsed: 0.0, duration: skills.skill(node.kind).gather_time(node.kind) }
+    }
+
+    /// Advance the timer. Returns true once the node is gathered.
+    pub fn update(&mut self, delta: f32) -> bool {
+        self.elapsed += delta;
+        self.elapsed >= self.duration
+    }
+
+    /// Progress as a 0.0-1.0 fraction, for the progress bar
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).min(1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_follow_seed_terrain_and_era() {
+        let coord = ChunkCoord::new(3, -2);
+        let lowland = nodes_in_chunk(coord, 42, 64.0, Era::Medieval, |_, _| 0.1);
+        assert_eq!(lowland, nodes_in_chunk(coord, 42, 64.0, Era::Medieval, |_, _| 0.1));
+        for node in &lowland {
+            assert_eq!(ChunkCoord::from_world_pos(node.position, 64.0), coord);
+        }
+
+        let count = |era: Era, elevation: f32, kind: ResourceKind| -> usize {
+            (0..200)
+                .map(|x| nodes_in_chunk(ChunkCoord::new(x, 0), 7, 64.0, era, |_, _| elevation))
+                .map(|nodes| nodes.iter().filter(|n| n.kind == kind).count())
+                .sum()
+        };
+        // Ore in the highlands, herbs on low ground
+        assert!(count(Era::Medieval, 0.9, ResourceKind::OreVein) > count(Era::Medieval, 0.1, ResourceKind::OreVein) * 3);
+        assert!(count(Era::Medieval, 0.1, ResourceKind::Herb) > count(Era::Medieval, 0.9, ResourceKind::Herb));
+        // The ancient forests are thicker than the future's
+        assert!(count(Era::Ancient, 0.5, ResourceKind::Tree) > count(Era::Future, 0.5, ResourceKind::Tree) * 3);
+    }
+
+    #[test]
+    fn test_tools_and_materials() {
+        let mut inventory = Inventory::new();
+        assert_eq!(check_tool(ResourceKind::OreVein, &inventory), Err(GatherTool::Pickaxe));
+        assert_eq!(check_tool(ResourceKind::Herb, &inventory), Ok(()));
+        inventory.add_item(GatherTool::Pickaxe.create()).unwrap();
+        assert_eq!(check_tool(ResourceKind::OreVein, &inventory), Ok(()));
+        assert_eq!(check_tool(ResourceKind::Tree, &inventory), Err(GatherTool::Hatchet));
+
+        // Each era's material stacks on its own
+        inventory.add_item(create_material(ResourceKind::OreVein, Era::Ancient, 2)).unwrap();
+        inventory.add_item(create_material(ResourceKind::OreVein, Era::Ancient, 3)).unwrap();
+        inventory.add_item(create_material(ResourceKind::OreVein, Era::Future, 1)).unwrap();
+        let copper = inventory.items.iter().find(|i| i.name == "Copper Ore").unwrap();
+        assert_eq!(copper.stack_count, 5);
+        assert_eq!(inventory.items.len(), 3);
+        for kind in ResourceKind::ALL {
+            assert_eq!(material_kind(create_material(kind, Era::Future, 1).id), Some(kind));
+        }
+        assert_eq!(material_kind(PICKAXE_ITEM_ID), None);
+        let glowcap = create_material(ResourceKind::Herb, Era::Future, 1);
+        assert_eq!(material_origin(glowcap.id), Some((ResourceKind::Herb, Era::Future)));
+    }
+
+    #[test]
+    fn test_skill_levels_speed_up_gathering() {
+        let mut skills = GatheringSkills::new();
+        let node = ResourceNode {
+            chunk: ChunkCoord::new(0, 0),
+            index: 0,
+            kind: ResourceKind::Tree,
+            position: Vec3::ZERO,
+        };
+        let mut session = GatherSession::new(node, &skills);
+        assert!(!session.update(ResourceKind::Tree.gather_time() * 0.5));
+        assert!((session.progress() - 0.5).abs() < 1e-5);
+        assert!(session.update(ResourceKind::Tree.gather_time() * 0.5));
+
+        // 50 XP reaches level 2: 12 XP a tree takes five harvests
+        let levels: Vec<Option<u32>> = (0..5).map(|_| skills.record_harvest(ResourceKind::Tree)).collect();
+        assert_eq!(levels, vec![None, None, None, None, Some(2)]);
+        assert_eq!(skills.mining.level(), 1);
+        let woodcutting = skills.skill(ResourceKind::Tree);
+        assert!(woodcutting.gather_time(ResourceKind::Tree) < ResourceKind::Tree.gather_time());
+
+        let expert = GatherSkill { xp: GatherSkill::xp_for_level(11) };
+        assert_eq!(expert.level(), 11);
+        assert_eq!(expert.yield_count(ResourceKind::Tree), ResourceKind::Tree.base_yield() + 2);
+        let master = GatherSkill { xp: u64::MAX / 2 };
+        assert_eq!(master.level(), MAX_GATHER_LEVEL);
+        assert_eq!(master.xp_fraction(), 1.0);
+    }
+}
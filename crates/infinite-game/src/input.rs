@@ -24,6 +24,8 @@ pub enum InputAction {
     Jump,
     /// Sprint modifier (Shift by default)
     Sprint,
+    /// Crouch / sneak while held (C by default)
+    Crouch,
     /// Zoom in (scroll up)
     ZoomIn,
     /// Zoom out (scroll down)
@@ -32,10 +34,14 @@ pub enum InputAction {
     Pause,
     /// Interact with objects (E by default)
     Interact,
+    /// Focus the next of several overlapping interactables (T by default)
+    CycleInteract,
     /// Quick save (F5 by default)
     QuickSave,
     /// Quick load (F9 by default)
     QuickLoad,
+    /// Toggle the HUD layout edit mode (F4 by default)
+    EditHud,
     /// Attack (Left mouse button by default)
     Attack,
     /// Heavy attack (Right mouse button by default)
@@ -54,6 +60,28 @@ pub enum InputAction {
     Dodge,
     /// Toggle inventory (Tab by default)
     Inventory,
+    /// Place or clear a compass waypoint (G by default)
+    Waypoint,
+    /// Toggle combat statistics panel (L by default)
+    CombatStats,
+    /// Toggle the timeline branch browser (B by default)
+    Timelines,
+    /// Fire or release the grappling hook (Q by default)
+    Grapple,
+    /// Raise an off-hand shield while held (F by default)
+    Block,
+    /// Dismiss the tutorial prompt, or toggle the tutorial checklist (H by default)
+    Tutorial,
+    /// Consumable hotbar slot 1 (5 key by default). Hold to aim throwables.
+    Hotbar1,
+    /// Consumable hotbar slot 2 (6 key by default)
+    Hotbar2,
+    /// Consumable hotbar slot 3 (7 key by default)
+    Hotbar3,
+    /// Consumable hotbar slot 4 (8 key by default)
+    Hotbar4,
+    /// Start or stop recording an input macro (F7 by default)
+    RecordMacro,
 }
 
 /// Current state of all inputs for a frame
@@ -159,10 +187,13 @@ impl Default for InputBindings {
         bindings.bind(KeyCode::Space, InputAction::Jump);
         bindings.bind(KeyCode::ShiftLeft, InputAction::Sprint);
         bindings.bind(KeyCode::ShiftRight, InputAction::Sprint);
+        bindings.bind(KeyCode::KeyC, InputAction::Crouch);
         bindings.bind(KeyCode::Escape, InputAction::Pause);
         bindings.bind(KeyCode::KeyE, InputAction::Interact);
+        bindings.bind(KeyCode::KeyT, InputAction::CycleInteract);
         bindings.bind(KeyCode::F5, InputAction::QuickSave);
         bindings.bind(KeyCode::F9, InputAction::QuickLoad);
+        bindings.bind(KeyCode::F4, InputAction::EditHud);
 
         // Combat
         bindings.bind_mouse(0, InputAction::Attack); // Left mouse button
@@ -174,6 +205,17 @@ impl Default for InputBindings {
         bindings.bind(KeyCode::KeyR, InputAction::RuneCompose);
         bindings.bind(KeyCode::ControlLeft, InputAction::Dodge);
         bindings.bind(KeyCode::Tab, InputAction::Inventory);
+        bindings.bind(KeyCode::KeyG, InputAction::Waypoint);
+        bindings.bind(KeyCode::KeyL, InputAction::CombatStats);
+        bindings.bind(KeyCode::KeyB, InputAction::Timelines);
+        bindings.bind(KeyCode::KeyQ, InputAction::Grapple);
+        bindings.bind(KeyCode::KeyF, InputAction::Block);
+        bindings.bind(KeyCode::KeyH, InputAction::Tutorial);
+        bindings.bind(KeyCode::Digit5, InputAction::Hotbar1);
+        bindings.bind(KeyCode::Digit6, InputAction::Hotbar2);
+        bindings.bind(KeyCode::Digit7, InputAction::Hotbar3);
+        bindings.bind(KeyCode::Digit8, InputAction::Hotbar4);
+        bindings.bind(KeyCode::F7, InputAction::RecordMacro);
 
         bindings
     }
@@ -0,0 +1,383 @@
+//! Input macros for accessibility
+//!
+//! A macro is a short run of actions, such as a combo chain, recorded once
+//! and played back from a single key. The [`MacroRecorder`] watches the
+//! actions pressed and released each frame; the [`MacroPlayer`] presses and
+//! releases them again on the same timings, as if the player had. Macros are
+//! kept short, leave menus and saving to the player, and never run in
+//! competitive play.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use winit::keyboard::KeyCode;
+
+use crate::input::{InputAction, InputState};
+
+/// Most actions one macro can hold
+pub const MAX_MACRO_STEPS: usize = 16;
+/// Longest a recording runs before it stops by itself, in seconds
+pub const MAX_MACRO_LENGTH: f32 = 8.0;
+/// Most macros a profile can hold
+pub const MAX_MACROS: usize = 10;
+
+/// Shortest hold played back for an action tapped within a single frame
+const MIN_HOLD: f32 = 0.05;
+
+/// Keys a macro can be bound to: the ones no action or debug key uses
+pub const MACRO_KEYS: [KeyCode; 17] = [
+    KeyCode::KeyZ,
+    KeyCode::KeyX,
+    KeyCode::KeyV,
+    KeyCode::KeyN,
+    KeyCode::KeyM,
+    KeyCode::KeyI,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::Digit9,
+    KeyCode::Digit0,
+    KeyCode::F6,
+    KeyCode::F8,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+];
+
+/// Name a trigger key is stored under, if macros can be bound to it
+pub fn macro_key_name(key: KeyCode) -> Option<String> {
+    MACRO_KEYS.contains(&key).then(|| format!("{:?}", key))
+}
+
+/// Key as shown to the player: "Z" for `KeyZ`, "9" for `Digit9`
+pub fn macro_key_label(name: &str) -> &str {
+    name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(name)
+}
+
+/// Whether an action can go in a macro. Menus, saving and the record key
+/// itself stay in the player's hands.
+pub fn is_recordable(action: InputAction) -> bool {
+    !matches!(
+        action,
+        InputAction::Pause
+            | InputAction::QuickSave
+            | InputAction::QuickLoad
+            | InputAction::EditHud
+            | InputAction::Inventory
+            | InputAction::CombatStats
+            | InputAction::Timelines
+            | InputAction::Tutorial
+            | InputAction::RecordMacro
+            | InputAction::ZoomIn
+            | InputAction::ZoomOut
+    )
+}
+
+/// Kind of play going on, for deciding whether macros may run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    World,
+    Survival,
+    Rift,
+    /// Against other players, once there is such a thing
+    Pvp,
+}
+
+impl PlayMode {
+    /// Macros are an aid against the game, never against other players
+    pub fn allows_macros(self) -> bool {
+        self != Self::Pvp
+    }
+}
+
+/// One action in a macro
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MacroStep {
+    pub action: InputAction,
+    /// Seconds from the start of the macro to the press
+    pub at: f32,
+    /// Seconds the action is held down
+    pub hold: f32,
+}
+
+/// A recorded macro bound to a key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputMacro {
+    pub name: String,
+    /// [`macro_key_name`] of the key that plays it
+    pub key: String,
+    pub steps: Vec<MacroStep>,
+}
+
+impl InputMacro {
+    /// Seconds from the first press to the last release
+    pub fn length(&self) -> f32 {
+        self.steps.iter().map(|s| s.at + s.hold).fold(0.0, f32::max)
+    }
+}
+
+/// Why a macro couldn't be added
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroError {
+    /// Nothing was recorded
+    Empty,
+    /// Macros can't be bound to that key
+    KeyNotAllowed,
+    /// The profile already holds [`MAX_MACROS`]
+    TooMany,
+}
+
+impl fmt::Display for MacroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The macro has no actions"),
+            Self::KeyNotAllowed => write!(f, "Macros can't be bound to that key"),
+            Self::TooMany => write!(f, "No room for more than {} macros", MAX_MACROS),
+        }
+    }
+}
+
+/// The macros of one player profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MacroSet {
+    #[serde(default)]
+    pub macros: Vec<InputMacro>,
+}
+
+impl MacroSet {
+    /// The macro bound to `key`, by [`macro_key_name`]
+    pub fn find(&self, key: &str) -> Option<&InputMacro> {
+        self.macros.iter().find(|m| m.key == key)
+    }
+
+    /// Add a macro, replacing (and returning) any already on its key
+    pub fn add(&mut self, new: InputMacro) -> Result<Option<InputMacro>, MacroError> {
+        if new.steps.is_empty() {
+            return Err(MacroError::Empty);
+        }
+        if !MACRO_KEYS.iter().any(|&key| macro_key_name(key).as_deref() == Some(new.key.as_str())) {
+            return Err(MacroError::KeyNotAllowed);
+        }
+        if let Some(existing) = self.macros.iter_mut().find(|m| m.key == new.key) {
+            return Ok(Some(std::mem::replace(existing, new)));
+        }
+        if self.macros.len() >= MAX_MACROS {
+            return Err(MacroError::TooMany);
+        }
+        self.macros.push(new);
+        Ok(None)
+    }
+}
+
+/// Records the actions the player presses into macro steps
+#[derive(Debug, Clone, Default)]
+pub struct MacroRecorder {
+    elapsed: f32,
+    steps: Vec<MacroStep>,
+    /// Index of each step whose action hasn't been released yet
+    open: Vec<usize>,
+}
+
+impl MacroRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take in one frame of input. Returns false once the recording is full
+    /// or has run for [`MAX_MACRO_LENGTH`], when it should be finished.
+    pub fn observe(&mut self, state: &InputState, dt: f32) -> bool {
+        for &action in &state.just_released {
+            if let Some(pos) = self.open.iter().position(|&i| self.steps[i].action == action) {
+                let index = self.open.remove(pos);
+                let step = &mut self.steps[index];
+                step.hold = (self.elapsed - step.at).max(MIN_HOLD);
+            }
+        }
+        for &action in &state.just_pressed {
+            if !is_recordable(action) || self.steps.len() >= MAX_MACRO_STEPS {
+                continue;
+            }
+            self.open.push(self.steps.len());
+            self.steps.push(MacroStep { action, at: self.elapsed, hold: MIN_HOLD });
+        }
+        self.elapsed += dt;
+        self.elapsed < MAX_MACRO_LENGTH && self.steps.len() < MAX_MACRO_STEPS
+    }
+
+    /// The recorded steps, timed from the first press. Actions still held
+    /// are let go now.
+    pub fn finish(mut self) -> Vec<MacroStep> {
+        for index in std::mem::take(&mut self.open) {
+            let step = &mut self.steps[index];
+            step.hold = (self.elapsed - step.at).max(MIN_HOLD);
+        }
+        let start = self.steps.first().map_or(0.0, |s| s.at);
+        for step in &mut self.steps {
+            step.at -= start;
+        }
+        self.steps
+    }
+}
+
+/// Plays a macro back into the input state
+#[derive(Debug, Clone)]
+pub struct MacroPlayer {
+    steps: Vec<MacroStep>,
+    elapsed: f32,
+    next: usize,
+    /// Actions pressed by the macro, with when to release them
+    held: Vec<(InputAction, f32)>,
+}
+
+impl MacroPlayer {
+    pub fn new(input_macro: &InputMacro) -> Self {
+        let mut steps = input_macro.steps.clone();
+        steps.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Self { steps, elapsed: 0.0, next: 0, held: Vec::new() }
+    }
+
+    /// Press and release this frame's actions. Returns false once the
+    /// macro has played out.
+    pub fn update(&mut self, dt: f32, state: &mut InputState) -> bool {
+        while let Some(step) = self.steps.get(self.next).filter(|s| s.at <= self.elapsed) {
+            if state.held.insert(step.action) {
+                state.just_pressed.insert(step.action);
+            }
+            self.held.push((step.action, step.at + step.hold));
+            self.next += 1;
+        }
+        let elapsed = self.elapsed;
+        self.held.retain(|&(action, release)| {
+            if release > elapsed {
+                return true;
+            }
+            state.held.remove(&action);
+            state.just_released.insert(action);
+            false
+        });
+        self.elapsed += dt;
+        self.next < self.steps.len() || !self.held.is_empty()
+    }
+
+    /// Stop partway, letting go of everything the macro holds
+    pub fn cancel(self, state: &mut InputState) {
+        for (action, _) in self.held {
+            state.held.remove(&action);
+            state.just_released.insert(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 0.125;
+
+    fn frame(pressed: &[InputAction], released: &[InputAction]) -> InputState {
+        let mut state = InputState::new();
+        state.just_pressed.extend(pressed.iter().copied());
+        state.just_released.extend(released.iter().copied());
+        state
+    }
+
+    #[test]
+    fn test_record_and_play_back() {
+        let mut recorder = MacroRecorder::new();
+        // Idle, then Skill1 tapped, Attack held for three frames, a pause
+        // (which stays out) in between
+        assert!(recorder.observe(&frame(&[], &[]), FRAME));
+        assert!(recorder.observe(&frame(&[InputAction::Skill1], &[]), FRAME));
+        assert!(recorder.observe(&frame(&[InputAction::Attack], &[InputAction::Skill1]), FRAME));
+        assert!(recorder.observe(&frame(&[InputAction::Pause], &[]), FRAME));
+        assert!(recorder.observe(&frame(&[], &[]), FRAME));
+        assert!(recorder.observe(&frame(&[], &[InputAction::Attack]), FRAME));
+        let steps = recorder.finish();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].action, InputAction::Skill1);
+        assert_eq!(steps[0].at, 0.0);
+        assert!((steps[1].at - FRAME).abs() < 1e-5);
+        assert!((steps[1].hold - 3.0 * FRAME).abs() < 1e-5);
+
+        let input_macro = InputMacro { name: "Opener".to_string(), key: "KeyZ".to_string(), steps };
+        let mut player = MacroPlayer::new(&input_macro);
+        let mut pressed = Vec::new();
+        let mut released = Vec::new();
+        let mut frames = 0;
+        loop {
+            let mut state = InputState::new();
+            let playing = player.update(FRAME, &mut state);
+            pressed.extend(state.just_pressed.iter().map(|&a| (frames, a)));
+            released.extend(state.just_released.iter().map(|&a| (frames, a)));
+            frames += 1;
+            if !playing {
+                break;
+            }
+        }
+        assert_eq!(pressed, vec![(0, InputAction::Skill1), (1, InputAction::Attack)]);
+        assert_eq!(released, vec![(1, InputAction::Skill1), (4, InputAction::Attack)]);
+    }
+
+    #[test]
+    fn test_recording_stops_when_full() {
+        let mut recorder = MacroRecorder::new();
+        let mut frames = 0;
+        while recorder.observe(&frame(&[], &[]), 1.0) {
+            frames += 1;
+        }
+        assert_eq!(frames as f32, MAX_MACRO_LENGTH - 1.0);
+        assert!(recorder.finish().is_empty());
+
+        let mut recorder = MacroRecorder::new();
+        for i in 0..MAX_MACRO_STEPS {
+            let action = if i.is_multiple_of(2) { InputAction::Attack } else { InputAction::Dodge };
+            let more = recorder.observe(&frame(&[action], &[action]), FRAME);
+            assert_eq!(more, i + 1 < MAX_MACRO_STEPS);
+        }
+        assert_eq!(recorder.finish().len(), MAX_MACRO_STEPS);
+    }
+
+    #[test]
+    fn test_cancel_lets_go() {
+        let step = MacroStep { action: InputAction::Block, at: 0.0, hold: 5.0 };
+        let input_macro = InputMacro { name: "Guard".to_string(), key: "KeyX".to_string(), steps: vec![step] };
+        let mut player = MacroPlayer::new(&input_macro);
+        let mut state = InputState::new();
+        assert!(player.update(FRAME, &mut state));
+        assert!(state.is_held(InputAction::Block));
+        player.cancel(&mut state);
+        assert!(!state.is_held(InputAction::Block));
+        assert!(state.is_just_released(InputAction::Block));
+    }
+
+    #[test]
+    fn test_macro_set_keys_and_limits() {
+        let step = MacroStep { action: InputAction::Attack, at: 0.0, hold: MIN_HOLD };
+        let with_key = |key: &str| InputMacro { name: key.to_string(), key: key.to_string(), steps: vec![step] };
+        let mut set = MacroSet::default();
+        assert_eq!(set.add(with_key("KeyW")), Err(MacroError::KeyNotAllowed));
+        assert_eq!(
+            set.add(InputMacro { steps: Vec::new(), ..with_key("KeyZ") }),
+            Err(MacroError::Empty)
+        );
+
+        for key in MACRO_KEYS.iter().take(MAX_MACROS) {
+            assert_eq!(set.add(with_key(&macro_key_name(*key).unwrap())), Ok(None));
+        }
+        let spare = macro_key_name(MACRO_KEYS[MAX_MACROS]).unwrap();
+        assert_eq!(set.add(with_key(&spare)), Err(MacroError::TooMany));
+        // Rebinding a key replaces its macro even when full
+        let replaced = set.add(InputMacro { name: "New".to_string(), ..with_key("KeyZ") }).unwrap();
+        assert_eq!(replaced.map(|m| m.name), Some("KeyZ".to_string()));
+        assert_eq!(set.find("KeyZ").map(|m| m.name.as_str()), Some("New"));
+
+        assert_eq!(macro_key_name(KeyCode::KeyW), None);
+        assert_eq!(macro_key_label("KeyZ"), "Z");
+        assert_eq!(macro_key_label("Digit9"), "9");
+        assert_eq!(macro_key_label("F6"), "F6");
+        assert!(!is_recordable(InputAction::QuickSave));
+        assert!(PlayMode::Survival.allows_macros());
+        assert!(!PlayMode::Pvp.allows_macros());
+    }
+}
@@ -1,15 +1,57 @@
 //! Interaction system for interactable objects in the world
 //!
 //! Players can focus on nearby interactables and interact with them (E key).
+//! When several are in reach, candidates are scored by how directly the
+//! player faces them, how close they are and a per-kind priority; the cycle
+//! key (T) steps through the rest. Everything but NPCs also carries a small
+//! sensor collider, so the object the camera looks straight at is focused
+//! ahead of the scores when it is in reach.
 //! Stateful interactables (doors, levers, containers) persist their state
-//! and can be saved/loaded.
+//! and can be saved/loaded. Locked doors open from a linked lever, a matching
+//! key, or (if they have a lock tier) by picking the lock.
+//! Doors get a physical leaf on a hinge once the system has a physics world
+//! to put it in: a motor swings it open and shut with the door's state, and
+//! while shut it blocks the way.
 
 use std::collections::HashMap;
 
-use glam::Vec3;
+use glam::{Quat, Vec3};
+use infinite_physics::{JointBuilder, JointedBody, MotorConfig, PhysicsWorld};
+use infinite_world::DungeonEntrance;
+use rapier3d::prelude::{ActiveCollisionTypes, ColliderBuilder, ColliderHandle, QueryFilter, RigidBodyBuilder};
 use serde::{Deserialize, Serialize};
 
+use crate::cooking::StationKind;
+use crate::gathering::ResourceNode;
+use crate::lockpick::{DoorKey, LockTier};
 use crate::npc::NpcId;
+use crate::seat::{Seat, SeatId, SeatKind};
+use crate::shrine::Shrine;
+use crate::trap::{TrapId, TrapKind};
+
+/// Minimum horizontal facing (dot with forward) to focus an interactable
+const FOCUS_MIN_FACING: f32 = 0.5;
+/// Score for looking straight at a target (scales down to 0 at the facing limit)
+const FOCUS_ANGLE_WEIGHT: f32 = 2.0;
+/// Score for standing on top of a target (scales down to 0 at its radius)
+const FOCUS_DISTANCE_WEIGHT: f32 = 1.0;
+/// How far a cycled-to target may move between frames and stay focused
+const PIN_TOLERANCE: f32 = 1.0;
+/// How far past the player the look ray searches for a target
+const TARGET_RAY_REACH: f32 = 5.0;
+/// Half size of a door's leaf; it hangs from a hinge on its -x edge
+const DOOR_LEAF_HALF_EXTENTS: Vec3 = Vec3::new(0.55, 1.05, 0.05);
+const DOOR_LEAF_MASS: f32 = 30.0;
+/// How far an open door's leaf swings, in radians
+const DOOR_OPEN_ANGLE: f32 = 1.6;
+
+/// Identifies an object's sensor: its kind and position in centimetres
+type SensorKey = (&'static str, [i32; 3]);
+
+fn sensor_key(interactable: &Interactable) -> SensorKey {
+    let cm = (interactable.position * 100.0).round();
+    (interactable.kind.name(), [cm.x as i32, cm.y as i32, cm.z as i32])
+}
 
 /// Unique identifier for a stateful interactable
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -18,7 +60,13 @@ pub struct InteractableId(pub u64);
 /// Persistent state for stateful interactables
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum InteractableState {
-    Door { is_open: bool, is_locked: bool },
+    Door {
+        is_open: bool,
+        is_locked: bool,
+        /// Pickable lock (None: only a lever or key opens it)
+        #[serde(default)]
+        lock: Option<LockTier>,
+    },
     Lever { is_on: bool, linked_ids: Vec<InteractableId> },
     Button { is_pressed: bool },
     Container { is_open: bool, items: Vec<String> },
@@ -52,6 +100,112 @@ pub enum InteractableKind {
     Container { id: InteractableId },
     /// A ladder that can be climbed
     Ladder { height: f32, direction: Vec3 },
+    /// A post that spawns (or resets) an invulnerable training dummy
+    TrainingDummy,
+    /// A practice arena that spawns waves of enemies
+    PracticeArena,
+    /// A portal down into a dungeon's instanced interior
+    DungeonEntrance(DungeonEntrance),
+    /// A portal from a dungeon back to its entrance
+    DungeonExit,
+    /// The spot marked on a treasure map (identified by the map's seed)
+    DigSpot { map_seed: u64 },
+    /// A key lying in the world, cut for one door
+    Key(DoorKey),
+    /// A spotted trap that can be disarmed
+    Trap { id: TrapId },
+    /// A bench, chair or wall to rest on
+    Seat { id: SeatId, kind: SeatKind, facing: Vec3 },
+    /// An ore vein, tree or herb patch to gather from
+    Resource(ResourceNode),
+    /// A campfire or stove to cook at
+    CookingStation(StationKind),
+    /// An alchemy table to brew potions at
+    BrewingStation,
+    /// A shrine to pray at for a boon
+    Shrine(Shrine),
+    /// A tear in time leading into rift runs
+    TimeRift,
+}
+
+impl InteractableKind {
+    /// Short display name (e.g. for compass markers)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sign { .. } => "Sign",
+            Self::TimePortal { .. } => "Portal",
+            Self::Pickup { .. } => "Item",
+            Self::Npc { .. } => "NPC",
+            Self::Door { .. } => "Door",
+            Self::Lever { .. } => "Lever",
+            Self::Button { .. } => "Button",
+            Self::Container { .. } => "Container",
+            Self::Ladder { .. } => "Ladder",
+            Self::TrainingDummy => "Training Dummy",
+            Self::PracticeArena => "Arena",
+            Self::DungeonEntrance(_) => "Dungeon",
+            Self::DungeonExit => "Exit",
+            Self::DigSpot { .. } => "Dig Spot",
+            Self::Key(_) => "Key",
+            Self::Trap { .. } => "Trap",
+            Self::Seat { kind, .. } => kind.name(),
+            Self::Resource(node) => node.kind.name(),
+            Self::CookingStation(kind) => kind.name(),
+            Self::BrewingStation => "Alchemy Table",
+            Self::Shrine(shrine) => shrine.name(),
+            Self::TimeRift => "Time Rift",
+        }
+    }
+
+    /// Focus priority added to the facing and distance score, so e.g. a
+    /// spotted trap wins over the sign next to it
+    pub fn focus_priority(&self) -> f32 {
+        match self {
+            Self::Trap { .. } => 1.0,
+            Self::Npc { .. } => 0.8,
+            Self::Pickup { .. } | Self::Key(_) => 0.7,
+            Self::TimePortal { .. } | Self::DungeonEntrance(_) | Self::DungeonExit | Self::DigSpot { .. } | Self::TimeRift => 0.6,
+            Self::Door { .. } | Self::Lever { .. } | Self::Button { .. } | Self::Container { .. } | Self::Resource(_)
+            | Self::CookingStation(_) | Self::BrewingStation | Self::Shrine(_) => 0.5,
+            Self::TrainingDummy | Self::PracticeArena => 0.4,
+            Self::Ladder { .. } | Self::Seat { .. } => 0.3,
+            Self::Sign { .. } => 0.2,
+        }
+    }
+
+    /// Whether the object gets a sensor for look targeting. NPCs move and
+    /// talk from further away, so they are found by proximity alone.
+    pub fn has_sensor(&self) -> bool {
+        !matches!(self, Self::Npc { .. })
+    }
+
+    /// Rough size of the object, for the focus outline and its sensor
+    pub fn outline_size(&self) -> Vec3 {
+        match self {
+            Self::Npc { .. } => Vec3::new(0.8, 1.9, 0.8),
+            Self::Door { .. } => Vec3::new(1.2, 2.2, 0.3),
+            Self::Ladder { height, .. } => Vec3::new(0.8, *height, 0.3),
+            Self::TimePortal { .. } | Self::DungeonEntrance(_) | Self::DungeonExit | Self::TimeRift => Vec3::new(2.0, 3.0, 2.0),
+            Self::Container { .. } | Self::TrainingDummy => Vec3::new(1.0, 1.0, 1.0),
+            Self::PracticeArena => Vec3::new(1.5, 2.0, 1.5),
+            Self::DigSpot { .. } | Self::Trap { .. } => Vec3::new(1.2, 0.3, 1.2),
+            Self::Sign { .. } | Self::Lever { .. } | Self::Button { .. } => Vec3::new(0.6, 1.2, 0.6),
+            Self::Pickup { .. } | Self::Key(_) => Vec3::splat(0.5),
+            Self::Resource(node) => node.kind.size(),
+            Self::CookingStation(_) => Vec3::new(1.2, 0.8, 1.2),
+            Self::BrewingStation => Vec3::new(1.4, 0.9, 0.8),
+            Self::Shrine(shrine) => shrine.size(),
+            // Axis-aligned, so turned with the seat
+            Self::Seat { kind, facing, .. } => {
+                let size = kind.footprint();
+                if facing.x.abs() > facing.z.abs() {
+                    Vec3::new(size.z, size.y, size.x)
+                } else {
+                    size
+                }
+            }
+        }
+    }
 }
 
 /// Result of interacting with an object
@@ -73,10 +227,38 @@ pub enum InteractionResult {
     PressButton { id: InteractableId },
     /// Open a container and get its items
     OpenContainer { id: InteractableId, items: Vec<String> },
-    /// Start climbing a ladder
-    StartClimbing { height: f32, direction: Vec3 },
+    /// Grab the climbable surface at this position
+    StartClimbing { position: Vec3 },
+    /// Spawn or reset the training dummy next to this position
+    SpawnTrainingDummy { position: Vec3 },
+    /// Start a practice arena run centred on this position
+    StartArena { center: Vec3 },
+    /// Enter this entrance's dungeon
+    EnterDungeon(DungeonEntrance),
+    /// Return from the dungeon to the overworld
+    LeaveDungeon,
+    /// Dig for the treasure of the map with this seed
+    Dig { map_seed: u64 },
+    /// Pick up a door key
+    PickupKey(DoorKey),
+    /// Try to disarm a trap
+    DisarmTrap(TrapId),
+    /// The door is locked; open it with a key or by picking its lock
+    LockedDoor { id: InteractableId, lock: Option<LockTier> },
     /// The object is locked
     Locked,
+    /// Sit or lean on a seat
+    Rest(SeatId),
+    /// Start gathering from a resource node
+    Gather(ResourceNode),
+    /// Open the cooking menu
+    Cook(StationKind),
+    /// Open the brewing menu
+    Brew,
+    /// Open the prayer menu of a shrine
+    Pray(Shrine),
+    /// Open the menu of the time rift at this position
+    OpenRift { gate: Vec3 },
 }
 
 /// An interactable object in the world
@@ -128,6 +310,137 @@ impl Interactable {
         }
     }
 
+    /// Create a training dummy post
+    pub fn training_dummy(position: Vec3) -> Self {
+        Self {
+            kind: InteractableKind::TrainingDummy,
+            position,
+            interaction_radius: 3.0,
+            prompt: "Set Up Training Dummy".to_string(),
+        }
+    }
+
+    /// Create a practice arena gate
+    pub fn practice_arena(position: Vec3) -> Self {
+        Self {
+            kind: InteractableKind::PracticeArena,
+            position,
+            interaction_radius: 3.0,
+            prompt: "Start Arena".to_string(),
+        }
+    }
+
+    /// Create the time rift's tear
+    pub fn time_rift(position: Vec3) -> Self {
+        Self {
+            kind: InteractableKind::TimeRift,
+            position,
+            interaction_radius: 4.0,
+            prompt: "Step into the Rift".to_string(),
+        }
+    }
+
+    /// Create a dungeon entrance portal
+    pub fn dungeon_entrance(entrance: DungeonEntrance) -> Self {
+        Self {
+            kind: InteractableKind::DungeonEntrance(entrance),
+            position: entrance.position,
+            interaction_radius: 4.0,
+            prompt: "Enter Dungeon".to_string(),
+        }
+    }
+
+    /// Create a dungeon return portal
+    pub fn dungeon_exit(position: Vec3) -> Self {
+        Self {
+            kind: InteractableKind::DungeonExit,
+            position,
+            interaction_radius: 4.0,
+            prompt: "Leave Dungeon".to_string(),
+        }
+    }
+
+    /// Create the dig spot marked on a treasure map
+    pub fn dig_spot(position: Vec3, map_seed: u64) -> Self {
+        Self {
+            kind: InteractableKind::DigSpot { map_seed },
+            position,
+            interaction_radius: 3.0,
+            prompt: "Dig".to_string(),
+        }
+    }
+
+    /// Create a key for `door` lying at `position`
+    pub fn key(position: Vec3, door: InteractableId, name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            prompt: format!("Pick up {}", name),
+            kind: InteractableKind::Key(DoorKey { door, name }),
+            position,
+            interaction_radius: 2.5,
+        }
+    }
+
+    /// Create the disarm point of a spotted trap
+    pub fn trap(position: Vec3, id: TrapId, kind: TrapKind) -> Self {
+        Self {
+            kind: InteractableKind::Trap { id },
+            position,
+            interaction_radius: 3.5,
+            prompt: format!("Disarm {}", kind.name()),
+        }
+    }
+
+    /// Create the interactable of a seat
+    pub fn seat(seat: &Seat) -> Self {
+        Self {
+            kind: InteractableKind::Seat { id: seat.id, kind: seat.kind, facing: seat.facing },
+            position: seat.center(),
+            interaction_radius: 2.5,
+            prompt: seat.kind.prompt().to_string(),
+        }
+    }
+
+    /// Create the interactable of a resource node, centred on the node
+    pub fn resource(node: ResourceNode) -> Self {
+        Self {
+            kind: InteractableKind::Resource(node),
+            position: node.position + Vec3::Y * node.kind.size().y * 0.5,
+            interaction_radius: 3.0,
+            prompt: node.kind.prompt().to_string(),
+        }
+    }
+
+    /// Create a campfire or stove to cook at
+    pub fn cooking_station(position: Vec3, kind: StationKind) -> Self {
+        Self {
+            kind: InteractableKind::CookingStation(kind),
+            position,
+            interaction_radius: 2.5,
+            prompt: format!("Cook at {}", kind.name()),
+        }
+    }
+
+    /// Create the interactable of a shrine, centred on the shrine
+    pub fn shrine(shrine: Shrine) -> Self {
+        Self {
+            kind: InteractableKind::Shrine(shrine),
+            position: shrine.position + Vec3::Y * shrine.size().y * 0.5,
+            interaction_radius: 3.0,
+            prompt: format!("Pray at {}", shrine.name()),
+        }
+    }
+
+    /// Create an alchemy table, centred on the table
+    pub fn brewing_station(position: Vec3) -> Self {
+        Self {
+            kind: InteractableKind::BrewingStation,
+            position,
+            interaction_radius: 2.5,
+            prompt: "Brew at Alchemy Table".to_string(),
+        }
+    }
+
     /// Create an NPC interactable
     pub fn npc(position: Vec3, npc_id: NpcId, name: impl Into<String>, interaction_radius: f32) -> Self {
         Self {
@@ -145,6 +458,17 @@ pub struct InteractionSystem {
     interactables: Vec<Interactable>,
     /// Index of the currently focused interactable (if any)
     focused: Option<usize>,
+    /// Indices of every focusable interactable, best score first
+    candidates: Vec<usize>,
+    /// Target picked with the cycle key (kind name and last position), kept
+    /// focused over the best-scoring one while it stays in reach
+    pinned: Option<(&'static str, Vec3)>,
+    /// Sensor collider of each object with one
+    sensors: HashMap<SensorKey, ColliderHandle>,
+    /// Index of the interactable each sensor belongs to, as of the last sync
+    sensor_targets: HashMap<ColliderHandle, usize>,
+    /// Each door's hinged leaf, and whether its motor is set to open it
+    door_leaves: HashMap<InteractableId, (JointedBody, bool)>,
     /// Persistent state for stateful interactables (doors, levers, etc.)
     world_state: HashMap<InteractableId, InteractableState>,
     /// Next ID to assign
@@ -157,6 +481,11 @@ impl InteractionSystem {
         Self {
             interactables: Vec::new(),
             focused: None,
+            candidates: Vec::new(),
+            pinned: None,
+            sensors: HashMap::new(),
+            sensor_targets: HashMap::new(),
+            door_leaves: HashMap::new(),
             world_state: HashMap::new(),
             next_id: 1,
         }
@@ -167,22 +496,34 @@ impl InteractionSystem {
         self.interactables.push(interactable);
     }
 
-    /// Clear all interactables
+    /// Clear all interactables. Their sensors and door leaves are forgotten
+    /// rather than removed, as this goes with replacing the physics world.
     pub fn clear(&mut self) {
         self.interactables.clear();
-        self.focused = None;
+        self.sensors.clear();
+        self.sensor_targets.clear();
+        self.door_leaves.clear();
+        self.reset_focus();
     }
 
     /// Clear interactables but keep world state (for chunk reload)
     pub fn clear_keeping_state(&mut self) {
         self.interactables.clear();
-        self.focused = None;
+        self.reset_focus();
     }
 
-    /// Remove all interactables matching a predicate
+    /// Remove all interactables matching a predicate. The cycled-to target
+    /// survives this (it is matched by kind and position on the next update).
     pub fn retain(&mut self, f: impl Fn(&Interactable) -> bool) {
         self.interactables.retain(|i| f(i));
         self.focused = None;
+        self.candidates.clear();
+    }
+
+    fn reset_focus(&mut self) {
+        self.focused = None;
+        self.candidates.clear();
+        self.pinned = None;
     }
 
     /// Number of interactables
@@ -190,27 +531,97 @@ impl InteractionSystem {
         self.interactables.len()
     }
 
+    /// All registered interactables
+    pub fn iter(&self) -> impl Iterator<Item = &Interactable> {
+        self.interactables.iter()
+    }
+
+    /// The interactable at `index` in `iter` order
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Interactable> {
+        self.interactables.get_mut(index)
+    }
+
+    /// Persistent state of a door, lever, button or container
+    pub fn state_mut(&mut self, id: InteractableId) -> Option<&mut InteractableState> {
+        self.world_state.get_mut(&id)
+    }
+
+    /// Positions and destination years of all time portals (for rendering)
+    pub fn time_portals(&self) -> impl Iterator<Item = (Vec3, i64)> + '_ {
+        self.interactables.iter().filter_map(|i| match i.kind {
+            InteractableKind::TimePortal { target_year } => Some((i.position, target_year)),
+            _ => None,
+        })
+    }
+
+    /// Positions of time rift tears (for rendering)
+    pub fn time_rifts(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.interactables.iter().filter_map(|i| match i.kind {
+            InteractableKind::TimeRift => Some(i.position),
+            _ => None,
+        })
+    }
+
+    /// Positions of dungeon portals, and whether each is a way out (for rendering)
+    pub fn dungeon_portals(&self) -> impl Iterator<Item = (Vec3, bool)> + '_ {
+        self.interactables.iter().filter_map(|i| match i.kind {
+            InteractableKind::DungeonEntrance(_) => Some((i.position, false)),
+            InteractableKind::DungeonExit => Some((i.position, true)),
+            _ => None,
+        })
+    }
+
+    /// Positions of treasure map dig spots (for drawing their decals)
+    pub fn dig_spots(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.interactables.iter().filter_map(|i| match i.kind {
+            InteractableKind::DigSpot { .. } => Some(i.position),
+            _ => None,
+        })
+    }
+
+    /// Where each door's leaf is, turned as far as it has swung: center,
+    /// rotation and half extents (for rendering)
+    pub fn door_leaves<'a>(&'a self, physics: &'a PhysicsWorld) -> impl Iterator<Item = (Vec3, Quat, Vec3)> + 'a {
+        self.door_leaves
+            .values()
+            .filter_map(|(leaf, _)| physics.body_pose(leaf.body))
+            .map(|(center, rotation)| (center, rotation, DOOR_LEAF_HALF_EXTENTS))
+    }
+
+    /// Positions of alchemy tables (for rendering)
+    pub fn brewing_stations(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.interactables.iter().filter_map(|i| match i.kind {
+            InteractableKind::BrewingStation => Some(i.position),
+            _ => None,
+        })
+    }
+
+    /// Shrines in the world (for rendering)
+    pub fn shrines(&self) -> impl Iterator<Item = &Shrine> + '_ {
+        self.interactables.iter().filter_map(|i| match &i.kind {
+            InteractableKind::Shrine(shrine) => Some(shrine),
+            _ => None,
+        })
+    }
+
+    /// Resource nodes there to gather (for rendering)
+    pub fn resource_nodes(&self) -> impl Iterator<Item = &ResourceNode> + '_ {
+        self.interactables.iter().filter_map(|i| match &i.kind {
+            InteractableKind::Resource(node) => Some(node),
+            _ => None,
+        })
+    }
+
     // --- Builder methods for stateful interactables ---
 
     /// Add a door and return its ID
     pub fn add_door(&mut self, position: Vec3, is_locked: bool) -> InteractableId {
-        let id = InteractableId(self.next_id);
-        self.next_id += 1;
-
-        self.world_state.insert(id, InteractableState::Door {
-            is_open: false,
-            is_locked,
-        });
-
-        let prompt = if is_locked { "Locked" } else { "Open Door" };
-        self.interactables.push(Interactable {
-            kind: InteractableKind::Door { id },
-            position,
-            interaction_radius: 3.0,
-            prompt: prompt.to_string(),
-        });
+        self.insert_door(position, is_locked, None)
+    }
 
-        id
+    /// Add a locked door with a pickable lock of the given tier, return its ID
+    pub fn add_locked_door(&mut self, position: Vec3, lock: LockTier) -> InteractableId {
+        self.insert_door(position, true, Some(lock))
     }
 
     /// Add a lever linked to other interactable IDs, return the lever's ID
@@ -284,12 +695,122 @@ impl InteractionSystem {
 
     /// Update focus detection based on player position and facing direction.
     ///
-    /// Finds the nearest interactable that is:
+    /// Candidates are interactables that are:
     /// - Within interaction_radius distance
     /// - Roughly in front of the player (dot product > 0.5 with forward vector)
+    ///
+    /// The best-scoring candidate is focused, unless the player cycled to
+    /// another one that is still a candidate.
     pub fn update(&mut self, player_pos: Vec3, player_forward: Vec3) {
-        let mut best_index: Option<usize> = None;
-        let mut best_distance = f32::MAX;
+        self.score_candidates(player_pos, player_forward);
+        self.settle_focus();
+    }
+
+    /// Update focus like `update`, after casting a ray from `eye` along
+    /// `look` (the camera's view). An object whose sensor the ray hits first
+    /// and that is in reach of the player ranks ahead of every score; a
+    /// wall in between blocks it. `exclude` is the player's own collider.
+    /// Door leaves are built, swung and removed to match the doors here.
+    pub fn update_targeted(
+        &mut self,
+        physics: &mut PhysicsWorld,
+        player_pos: Vec3,
+        player_forward: Vec3,
+        eye: Vec3,
+        look: Vec3,
+        exclude: Option<ColliderHandle>,
+    ) {
+        let sensors_changed = self.sync_sensors(physics);
+        let doors_changed = self.sync_doors(physics);
+        if sensors_changed || doors_changed {
+            physics.update_query_pipeline();
+        }
+        let filter = match exclude {
+            Some(handle) => QueryFilter::default().exclude_collider(handle),
+            None => QueryFilter::default(),
+        };
+        let range = eye.distance(player_pos) + TARGET_RAY_REACH;
+        let looked_at = physics
+            .raycast_with_sensors(eye, look.normalize_or_zero(), range, filter)
+            .and_then(|(handle, _)| self.sensor_targets.get(&handle).copied())
+            .filter(|&i| {
+                let interactable = &self.interactables[i];
+                interactable.position.distance(player_pos) <= interactable.interaction_radius
+            });
+
+        self.score_candidates(player_pos, player_forward);
+        if let Some(i) = looked_at {
+            self.candidates.retain(|&c| c != i);
+            self.candidates.insert(0, i);
+        }
+        self.settle_focus();
+    }
+
+    /// Give each object with a sensor a box the size of its outline, keeping
+    /// the sensors of objects that haven't moved and removing the rest.
+    /// Returns whether any were added or removed.
+    fn sync_sensors(&mut self, physics: &mut PhysicsWorld) -> bool {
+        let mut previous = std::mem::take(&mut self.sensors);
+        let mut changed = false;
+        self.sensor_targets.clear();
+        for (i, interactable) in self.interactables.iter().enumerate() {
+            let key = sensor_key(interactable);
+            if !interactable.kind.has_sensor() || self.sensors.contains_key(&key) {
+                continue;
+            }
+            let handle = previous.remove(&key).unwrap_or_else(|| {
+                changed = true;
+                physics.create_sensor_box(interactable.kind.outline_size() * 0.5, interactable.position)
+            });
+            self.sensors.insert(key, handle);
+            self.sensor_targets.insert(handle, i);
+        }
+        for handle in previous.into_values() {
+            physics.remove_collider(handle);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Give each door a hinged leaf, swinging it to match the door's state,
+    /// and remove the leaves of doors that are gone. Returns whether any
+    /// were added or removed.
+    fn sync_doors(&mut self, physics: &mut PhysicsWorld) -> bool {
+        let mut previous = std::mem::take(&mut self.door_leaves);
+        let mut changed = false;
+        for interactable in &self.interactables {
+            let InteractableKind::Door { id } = interactable.kind else {
+                continue;
+            };
+            let open = matches!(self.world_state.get(&id), Some(InteractableState::Door { is_open: true, .. }));
+            let leaf = match previous.remove(&id) {
+                Some((leaf, was_open)) => {
+                    if was_open != open {
+                        physics.set_joint_motor(&leaf, door_motor(open));
+                    }
+                    leaf
+                }
+                None => {
+                    changed = true;
+                    add_door_leaf(physics, interactable.position, open)
+                }
+            };
+            self.door_leaves.insert(id, (leaf, open));
+        }
+        for (leaf, _) in previous.into_values() {
+            physics.remove_jointed(&leaf);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Collect the interactables in reach and in front of the player, best
+    /// score first
+    fn score_candidates(&mut self, player_pos: Vec3, player_forward: Vec3) {
+        // Use horizontal direction only (ignore Y) for facing check
+        let horizontal_forward = Vec3::new(player_forward.x, 0.0, player_forward.z)
+            .normalize_or_zero();
+        let mut scored: Vec<(usize, f32)> = Vec::new();
 
         for (i, interactable) in self.interactables.iter().enumerate() {
             let to_target = interactable.position - player_pos;
@@ -301,27 +822,48 @@ impl InteractionSystem {
             }
 
             // Check facing direction (must be roughly looking at it)
-            if distance > 0.1 {
-                let dir_to_target = to_target / distance;
-                // Use horizontal direction only (ignore Y) for facing check
-                let horizontal_forward = Vec3::new(player_forward.x, 0.0, player_forward.z)
-                    .normalize_or_zero();
-                let horizontal_to_target = Vec3::new(dir_to_target.x, 0.0, dir_to_target.z)
+            let dot = if distance > 0.1 {
+                let horizontal_to_target = Vec3::new(to_target.x, 0.0, to_target.z)
                     .normalize_or_zero();
-                let dot = horizontal_forward.dot(horizontal_to_target);
-                if dot < 0.5 {
-                    continue;
-                }
+                horizontal_forward.dot(horizontal_to_target)
+            } else {
+                1.0
+            };
+            if dot < FOCUS_MIN_FACING {
+                continue;
             }
 
-            // Track nearest valid target
-            if distance < best_distance {
-                best_distance = distance;
-                best_index = Some(i);
-            }
+            let facing = (dot - FOCUS_MIN_FACING) / (1.0 - FOCUS_MIN_FACING);
+            let closeness = 1.0 - distance / interactable.interaction_radius.max(0.1);
+            let score = interactable.kind.focus_priority()
+                + FOCUS_ANGLE_WEIGHT * facing
+                + FOCUS_DISTANCE_WEIGHT * closeness;
+            scored.push((i, score));
         }
 
-        self.focused = best_index;
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        self.candidates = scored.into_iter().map(|(i, _)| i).collect();
+    }
+
+    /// Focus the cycled-to target if it is still a candidate, else the first
+    fn settle_focus(&mut self) {
+        let pinned = self.pinned.and_then(|(name, position)| {
+            self.candidates.iter().copied().find(|&i| {
+                let interactable = &self.interactables[i];
+                interactable.kind.name() == name
+                    && interactable.position.distance(position) <= PIN_TOLERANCE
+            })
+        });
+        match pinned {
+            Some(i) => {
+                self.pinned = Some((self.interactables[i].kind.name(), self.interactables[i].position));
+                self.focused = Some(i);
+            }
+            None => {
+                self.pinned = None;
+                self.focused = self.candidates.first().copied();
+            }
+        }
 
         // Update prompts for stateful interactables to reflect current state
         self.update_prompts();
@@ -332,6 +874,33 @@ impl InteractionSystem {
         self.focused.and_then(|i| self.interactables.get(i))
     }
 
+    /// Focus the next candidate in score order (wrapping around).
+    /// Returns false if there was nothing else to cycle to.
+    pub fn cycle_focus(&mut self) -> bool {
+        if self.candidates.len() < 2 {
+            return false;
+        }
+        let rank = self
+            .focused
+            .and_then(|f| self.candidates.iter().position(|&i| i == f))
+            .unwrap_or(0);
+        let next = self.candidates[(rank + 1) % self.candidates.len()];
+        let interactable = &self.interactables[next];
+        self.pinned = Some((interactable.kind.name(), interactable.position));
+        self.focused = Some(next);
+        true
+    }
+
+    /// Rank of the focused target among the candidates (1-based) and the
+    /// candidate count, when there is more than one to cycle through
+    pub fn focus_cycle(&self) -> Option<(usize, usize)> {
+        if self.candidates.len() < 2 {
+            return None;
+        }
+        let rank = self.candidates.iter().position(|&i| Some(i) == self.focused)?;
+        Some((rank + 1, self.candidates.len()))
+    }
+
     /// Interact with the currently focused object.
     /// Returns the interaction result if something was focused.
     pub fn interact(&mut self) -> Option<InteractionResult> {
@@ -361,23 +930,65 @@ impl InteractionSystem {
             InteractableKind::Container { id } => {
                 self.interact_container(*id)
             }
-            InteractableKind::Ladder { height, direction } => {
-                InteractionResult::StartClimbing {
-                    height: *height,
-                    direction: *direction,
-                }
-            }
+            InteractableKind::Ladder { .. } => InteractionResult::StartClimbing {
+                position: interactable.position,
+            },
+            InteractableKind::TrainingDummy => InteractionResult::SpawnTrainingDummy {
+                position: interactable.position,
+            },
+            InteractableKind::PracticeArena => InteractionResult::StartArena {
+                center: interactable.position,
+            },
+            InteractableKind::DungeonEntrance(entrance) => InteractionResult::EnterDungeon(*entrance),
+            InteractableKind::DungeonExit => InteractionResult::LeaveDungeon,
+            InteractableKind::DigSpot { map_seed } => InteractionResult::Dig { map_seed: *map_seed },
+            InteractableKind::Key(key) => InteractionResult::PickupKey(key.clone()),
+            InteractableKind::Trap { id } => InteractionResult::DisarmTrap(*id),
+            InteractableKind::Seat { id, .. } => InteractionResult::Rest(*id),
+            InteractableKind::Resource(node) => InteractionResult::Gather(*node),
+            InteractableKind::CookingStation(kind) => InteractionResult::Cook(*kind),
+            InteractableKind::BrewingStation => InteractionResult::Brew,
+            InteractableKind::Shrine(shrine) => InteractionResult::Pray(*shrine),
+            InteractableKind::TimeRift => InteractionResult::OpenRift {
+                gate: interactable.position,
+            },
         };
 
         // Pickups are consumed on interaction
-        if matches!(self.interactables[index].kind, InteractableKind::Pickup { .. }) {
+        if matches!(self.interactables[index].kind, InteractableKind::Pickup { .. } | InteractableKind::Key(_)) {
             self.interactables.remove(index);
             self.focused = None;
+            self.candidates.clear();
         }
 
         Some(result)
     }
 
+    /// Unlock and open a locked door (with a key or a picked lock).
+    /// Returns false if `id` is not a locked door.
+    pub fn unlock_door(&mut self, id: InteractableId) -> bool {
+        match self.world_state.get_mut(&id) {
+            Some(InteractableState::Door { is_open, is_locked, .. }) if *is_locked => {
+                *is_locked = false;
+                *is_open = true;
+                self.update_prompts();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Position of the stateful interactable with this ID
+    pub fn position_of(&self, id: InteractableId) -> Option<Vec3> {
+        self.interactables.iter().find_map(|i| match i.kind {
+            InteractableKind::Door { id: door }
+            | InteractableKind::Lever { id: door }
+            | InteractableKind::Button { id: door }
+            | InteractableKind::Container { id: door } if door == id => Some(i.position),
+            _ => None,
+        })
+    }
+
     /// Trigger linked effects (e.g., lever unlocks doors)
     pub fn trigger_linked(&mut self, linked_ids: &[InteractableId]) {
         for linked_id in linked_ids {
@@ -414,10 +1025,30 @@ impl InteractionSystem {
 
     // --- Private helpers ---
 
+    fn insert_door(&mut self, position: Vec3, is_locked: bool, lock: Option<LockTier>) -> InteractableId {
+        let id = InteractableId(self.next_id);
+        self.next_id += 1;
+
+        self.world_state.insert(id, InteractableState::Door {
+            is_open: false,
+            is_locked,
+            lock,
+        });
+
+        self.interactables.push(Interactable {
+            kind: InteractableKind::Door { id },
+            position,
+            interaction_radius: 3.0,
+            prompt: door_prompt(false, is_locked, lock),
+        });
+
+        id
+    }
+
     fn interact_door(&mut self, id: InteractableId) -> InteractionResult {
-        if let Some(InteractableState::Door { is_open, is_locked }) = self.world_state.get_mut(&id) {
+        if let Some(InteractableState::Door { is_open, is_locked, lock }) = self.world_state.get_mut(&id) {
             if *is_locked {
-                return InteractionResult::Locked;
+                return InteractionResult::LockedDoor { id, lock: *lock };
             }
             *is_open = !*is_open;
             InteractionResult::ToggleDoor { id, now_open: *is_open }
@@ -461,14 +1092,8 @@ impl InteractionSystem {
         for interactable in &mut self.interactables {
             match &interactable.kind {
                 InteractableKind::Door { id } => {
-                    if let Some(InteractableState::Door { is_open, is_locked }) = self.world_state.get(id) {
-                        interactable.prompt = if *is_locked {
-                            "Locked".to_string()
-                        } else if *is_open {
-                            "Close Door".to_string()
-                        } else {
-                            "Open Door".to_string()
-                        };
+                    if let Some(InteractableState::Door { is_open, is_locked, lock }) = self.world_state.get(id) {
+                        interactable.prompt = door_prompt(*is_open, *is_locked, *lock);
                     }
                 }
                 InteractableKind::Lever { id } => {
@@ -497,6 +1122,38 @@ impl InteractionSystem {
     }
 }
 
+fn door_prompt(is_open: bool, is_locked: bool, lock: Option<LockTier>) -> String {
+    match (is_locked, lock) {
+        (true, Some(lock)) => format!("Locked ({} Lock)", lock.name()),
+        (true, None) => "Locked".to_string(),
+        (false, _) if is_open => "Close Door".to_string(),
+        (false, _) => "Open Door".to_string(),
+    }
+}
+
+/// Motor holding a door's leaf open or shut
+fn door_motor(open: bool) -> MotorConfig {
+    MotorConfig::position(if open { DOOR_OPEN_ANGLE } else { 0.0 }, 20.0, 8.0)
+}
+
+/// Hang a leaf for the door at `position`, shut, with its motor set for
+/// `open`. The leaf has no contacts of its own, so the ground under it
+/// can't jam it, but movement and casts still run into it.
+fn add_door_leaf(physics: &mut PhysicsWorld, position: Vec3, open: bool) -> JointedBody {
+    let half = DOOR_LEAF_HALF_EXTENTS;
+    let body = RigidBodyBuilder::dynamic()
+        .translation([position.x, position.y, position.z].into())
+        .build();
+    let collider = ColliderBuilder::cuboid(half.x, half.y, half.z)
+        .mass(DOOR_LEAF_MASS)
+        .active_collision_types(ActiveCollisionTypes::empty())
+        .build();
+    let hinge = JointBuilder::hinge(position - Vec3::X * half.x, Vec3::Y)
+        .limits(0.0, DOOR_OPEN_ANGLE)
+        .motor(door_motor(open));
+    physics.add_jointed(&hinge, body, collider)
+}
+
 impl Default for InteractionSystem {
     fn default() -> Self {
         Self::new()
@@ -583,6 +1240,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_time_portals_listed() {
+        let mut system = InteractionSystem::new();
+        system.add(Interactable::sign(Vec3::ZERO, "Not a portal"));
+        system.add(Interactable::time_portal(Vec3::new(1.0, 0.0, 0.0), -500, "Past"));
+
+        let portals: Vec<_> = system.time_portals().collect();
+        assert_eq!(portals, vec![(Vec3::new(1.0, 0.0, 0.0), -500)]);
+    }
+
     #[test]
     fn test_pickup_consumed_on_interact() {
         let mut system = InteractionSystem::new();
@@ -637,7 +1304,38 @@ mod tests {
 
         system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
         let result = system.interact().unwrap();
-        assert!(matches!(result, InteractionResult::Locked));
+        assert!(matches!(result, InteractionResult::LockedDoor { lock: None, .. }));
+    }
+
+    #[test]
+    fn test_key_and_pickable_lock() {
+        let mut system = InteractionSystem::new();
+        let door_id = system.add_locked_door(Vec3::new(0.0, 0.0, -2.0), LockTier::Sturdy);
+        system.add(Interactable::key(Vec3::new(0.0, 0.0, 2.0), door_id, "Cellar Key"));
+
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(system.focused().unwrap().prompt, "Locked (Sturdy Lock)");
+        match system.interact().unwrap() {
+            InteractionResult::LockedDoor { id, lock } => {
+                assert_eq!(id, door_id);
+                assert_eq!(lock, Some(LockTier::Sturdy));
+            }
+            _ => panic!("Expected LockedDoor"),
+        }
+
+        // Keys are consumed like pickups
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, 1.0));
+        match system.interact().unwrap() {
+            InteractionResult::PickupKey(key) => assert_eq!(key.door, door_id),
+            _ => panic!("Expected PickupKey"),
+        }
+        assert_eq!(system.count(), 1);
+
+        assert!(system.unlock_door(door_id));
+        assert!(!system.unlock_door(door_id));
+        assert_eq!(system.position_of(door_id), Some(Vec3::new(0.0, 0.0, -2.0)));
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(system.focused().unwrap().prompt, "Close Door");
     }
 
     #[test]
@@ -706,14 +1404,28 @@ mod tests {
         let result = system.interact().unwrap();
 
         match result {
-            InteractionResult::StartClimbing { height, direction } => {
-                assert_eq!(height, 5.0);
-                assert_eq!(direction, Vec3::Y);
+            InteractionResult::StartClimbing { position } => {
+                assert_eq!(position, Vec3::new(0.0, 0.0, -2.0));
             }
             _ => panic!("Expected StartClimbing"),
         }
     }
 
+    #[test]
+    fn test_seat() {
+        let mut seats = crate::seat::SeatRegistry::new();
+        let id = seats.add(SeatKind::Bench, Vec3::new(0.0, 0.0, -2.0), Vec3::X);
+        let mut system = InteractionSystem::new();
+        let bench = Interactable::seat(seats.get(id).unwrap());
+        assert_eq!(bench.prompt, "Sit");
+        // A bench facing +X runs along Z
+        assert!(bench.kind.outline_size().z > bench.kind.outline_size().x);
+        system.add(bench);
+
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert!(matches!(system.interact(), Some(InteractionResult::Rest(seat)) if seat == id));
+    }
+
     #[test]
     fn test_button() {
         let mut system = InteractionSystem::new();
@@ -725,6 +1437,196 @@ mod tests {
         assert!(matches!(result, InteractionResult::PressButton { .. }));
     }
 
+    #[test]
+    fn test_training_interactables() {
+        let mut system = InteractionSystem::new();
+        let post = Vec3::new(0.0, 0.0, -2.0);
+        system.add(Interactable::training_dummy(post));
+
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        match system.interact().unwrap() {
+            InteractionResult::SpawnTrainingDummy { position } => assert_eq!(position, post),
+            _ => panic!("Expected SpawnTrainingDummy"),
+        }
+
+        system.clear();
+        system.add(Interactable::practice_arena(post));
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert!(matches!(system.interact(), Some(InteractionResult::StartArena { .. })));
+    }
+
+    #[test]
+    fn test_dungeon_portals() {
+        let mut system = InteractionSystem::new();
+        let entrance = DungeonEntrance {
+            chunk: infinite_world::ChunkCoord::new(0, 0),
+            position: Vec3::new(0.0, 0.0, -2.0),
+            seed: 9,
+        };
+        system.add(Interactable::dungeon_entrance(entrance));
+        system.add(Interactable::dungeon_exit(Vec3::new(50.0, 0.0, 0.0)));
+        assert_eq!(system.dungeon_portals().filter(|(_, exit)| *exit).count(), 1);
+
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        match system.interact() {
+            Some(InteractionResult::EnterDungeon(e)) => assert_eq!(e.seed, 9),
+            other => panic!("Expected EnterDungeon, got {:?}", other),
+        }
+
+        system.update(Vec3::new(50.0, 0.0, 2.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(matches!(system.interact(), Some(InteractionResult::LeaveDungeon)));
+    }
+
+    #[test]
+    fn test_focus_prefers_facing_over_distance() {
+        let mut system = InteractionSystem::new();
+        // Slightly closer but off to the side
+        system.add(Interactable::sign(Vec3::new(-1.2, 0.0, -1.2), "Side"));
+        system.add(Interactable::sign(Vec3::new(0.0, 0.0, -2.0), "Ahead"));
+
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert!(matches!(
+            &system.focused().unwrap().kind,
+            InteractableKind::Sign { text } if text == "Ahead"
+        ));
+    }
+
+    #[test]
+    fn test_focus_priority_breaks_ties() {
+        let mut system = InteractionSystem::new();
+        system.add(Interactable::sign(Vec3::new(0.0, 0.0, -2.0), "Sign"));
+        system.add(Interactable::pickup(Vec3::new(0.0, 0.0, -2.0), "Gem"));
+
+        system.update(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(system.focused().unwrap().kind.name(), "Item");
+        assert_eq!(system.focus_cycle(), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_cycle_focus_sticks_and_wraps() {
+        let mut system = InteractionSystem::new();
+        system.add(Interactable::sign(Vec3::new(0.0, 0.0, -2.0), "Sign"));
+        system.add(Interactable::pickup(Vec3::new(0.3, 0.0, -2.0), "Gem"));
+        let forward = Vec3::new(0.0, 0.0, -1.0);
+
+        system.update(Vec3::ZERO, forward);
+        assert_eq!(system.focused().unwrap().kind.name(), "Item");
+        assert!(system.cycle_focus());
+        assert_eq!(system.focused().unwrap().kind.name(), "Sign");
+
+        // Stays on the cycled-to target across updates and re-adds
+        system.retain(|_| true);
+        system.update(Vec3::ZERO, forward);
+        assert_eq!(system.focused().unwrap().kind.name(), "Sign");
+        assert_eq!(system.focus_cycle(), Some((2, 2)));
+
+        assert!(system.cycle_focus());
+        assert_eq!(system.focused().unwrap().kind.name(), "Item");
+
+        // Walking out of reach drops the pin
+        system.update(Vec3::new(0.0, 0.0, 10.0), forward);
+        assert!(system.focused().is_none());
+        assert!(!system.cycle_focus());
+    }
+
+    #[test]
+    fn test_look_ray_targets_sign_across_counter() {
+        let mut physics = PhysicsWorld::new();
+        // Counter between the player and the sign, below eye height
+        physics.create_static_box(Vec3::new(1.0, 0.5, 0.3), Vec3::new(0.0, 0.5, -1.0));
+        let mut system = InteractionSystem::new();
+        // The shopkeeper outranks the sign on proximity alone
+        system.add(Interactable::npc(Vec3::new(0.8, 1.0, -1.5), NpcId(1), "Shopkeeper", 3.0));
+        system.add(Interactable::sign(Vec3::new(-0.6, 1.6, -2.0), "Prices"));
+        let eye = Vec3::new(0.0, 1.6, 0.0);
+
+        let look = Vec3::new(-0.6, 0.0, -2.0);
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, look, None);
+        assert_eq!(system.focused().unwrap().kind.name(), "Sign");
+        assert_eq!(system.focus_cycle(), Some((1, 2)));
+
+        // Looking away from the sign falls back to proximity
+        let look = Vec3::new(0.8, -0.3, -1.5);
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, look, None);
+        assert_eq!(system.focused().unwrap().kind.name(), "NPC");
+    }
+
+    #[test]
+    fn test_sensors_follow_interactables() {
+        let mut physics = PhysicsWorld::new();
+        let mut system = InteractionSystem::new();
+        system.add(Interactable::sign(Vec3::new(0.0, 1.0, -2.0), "Hello"));
+        let eye = Vec3::new(0.0, 1.0, 0.0);
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+        assert_eq!(system.sensors.len(), 1);
+        let sensor = *system.sensors.values().next().unwrap();
+
+        // Unmoved objects keep their sensor, moved ones get a new one
+        system.retain(|_| true);
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+        assert_eq!(system.sensors.values().next(), Some(&sensor));
+        system.get_mut(0).unwrap().position.x = 0.5;
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+        assert_eq!(system.sensors.len(), 1);
+        assert!(physics.get_collider(sensor).is_none());
+
+        // Removed objects lose theirs
+        system.retain(|_| false);
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+        assert!(system.sensors.is_empty());
+    }
+
+    #[test]
+    fn test_wall_blocks_look_ray() {
+        let mut physics = PhysicsWorld::new();
+        let wall = physics.create_static_box(Vec3::new(2.0, 2.0, 0.1), Vec3::new(0.0, 1.0, -1.0));
+        let mut system = InteractionSystem::new();
+        system.add(Interactable::npc(Vec3::new(0.3, 1.0, -0.5), NpcId(1), "Guard", 3.0));
+        system.add(Interactable::sign(Vec3::new(0.0, 1.0, -2.0), "Behind the wall"));
+        let eye = Vec3::new(0.0, 1.0, 0.0);
+
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+        assert_eq!(system.focused().unwrap().kind.name(), "NPC");
+
+        physics.remove_collider(wall);
+        physics.update_query_pipeline();
+        system.update_targeted(&mut physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+        assert_eq!(system.focused().unwrap().kind.name(), "Sign");
+    }
+
+    #[test]
+    fn test_door_leaves_swing_with_their_doors() {
+        let mut physics = PhysicsWorld::new();
+        let mut system = InteractionSystem::new();
+        let position = Vec3::new(0.0, 1.0, -2.0);
+        let door = system.add_door(position, false);
+        let eye = Vec3::new(0.0, 1.5, 0.0);
+        let settle = |system: &mut InteractionSystem, physics: &mut PhysicsWorld| {
+            for _ in 0..120 {
+                system.update_targeted(physics, Vec3::ZERO, Vec3::NEG_Z, eye, Vec3::NEG_Z, None);
+                physics.step();
+            }
+        };
+        settle(&mut system, &mut physics);
+        let (center, _, _) = system.door_leaves(&physics).next().unwrap();
+        assert!(center.distance(position) < 0.01, "shut leaves stay in the doorway");
+        // A shut door stops a ray through the doorway
+        assert!(physics.raycast(Vec3::new(0.2, 1.0, 0.0), Vec3::NEG_Z, 5.0, QueryFilter::default()).is_some());
+
+        assert!(matches!(system.interact(), Some(InteractionResult::ToggleDoor { now_open: true, .. })));
+        settle(&mut system, &mut physics);
+        let (center, rotation, _) = system.door_leaves(&physics).next().unwrap();
+        assert!((rotation.to_scaled_axis().y - DOOR_OPEN_ANGLE).abs() < 0.1);
+        assert!(center.distance(position) > 0.5, "the leaf swung out of the doorway");
+        assert!(physics.raycast(Vec3::new(0.2, 1.0, 0.0), Vec3::NEG_Z, 5.0, QueryFilter::default()).is_none());
+
+        // Doors that are gone lose their leaves
+        system.retain(|i| !matches!(i.kind, InteractableKind::Door { id } if id == door));
+        settle(&mut system, &mut physics);
+        assert_eq!(system.door_leaves(&physics).count(), 0);
+        assert_eq!(physics.rigid_body_set.len(), 0);
+    }
+
     #[test]
     fn test_save_load_states() {
         let mut system = InteractionSystem::new();
@@ -2,31 +2,85 @@
 //!
 //! Provides player controllers, camera, input handling, and game logic.
 
+pub mod alchemy;
+pub mod balance;
 pub mod camera;
 pub mod combat;
+pub mod compass;
+pub mod cooking;
+pub mod economy;
+pub mod flags;
+pub mod gathering;
 pub mod input;
+pub mod input_macro;
 pub mod interaction;
+pub mod locations;
+pub mod lockpick;
+pub mod mods;
 pub mod npc;
 pub mod player;
+pub mod rift;
+pub mod screen_markers;
+pub mod seat;
+pub mod shrine;
+pub mod speedrun;
+pub mod throwable;
+pub mod trap;
+pub mod tutorial;
 
-pub use camera::{CameraConfig, CameraController, CameraMode};
+pub use alchemy::{AlchemyEffect, AlchemyJournal, BrewOutcome, BrewRecipe, CoatingKind, Reagent, WeaponCoating};
+pub use camera::{CameraConfig, CameraController, CameraMode, DialogueShot, DIALOGUE_BLEND_TIME};
+pub use compass::{CompassEntry, CompassFilter, CompassMarker, CompassTracker, MarkerCategory, MarkerId};
+pub use cooking::{CookOutcome, FoodBuffKind, FoodBuffs, Ingredient, Recipe, RecipeBook, StationKind, RECIPES};
+pub use economy::{Caravan, Economy, EconomyEvent, Market};
+pub use flags::{FlagAction, FlagChange, FlagCondition, FlagOp, FlagTest, FlagValue, WorldFlags};
+pub use gathering::{
+    GatherSession, GatherSkill, GatherTool, GatheringSkills, ResourceKind, ResourceNode, GATHERING_TOOL_PRICE,
+};
 pub use input::{InputAction, InputBindings, InputHandler, InputState};
+pub use input_macro::{InputMacro, MacroError, MacroPlayer, MacroRecorder, MacroSet, MacroStep, PlayMode};
 pub use interaction::{
     Interactable, InteractableId, InteractableKind, InteractableState, InteractionResult,
     InteractionSaveData, InteractionSystem,
 };
+pub use locations::{Location, LocationKind, LocationRegistry};
+pub use lockpick::{DoorKey, KeyRing, LockTier, LockpickSession, PickAttempt, LOCKPICK_ITEM_ID};
+pub use mods::{ModContent, ModError, ModMismatch, ModRecord, ModSet};
 pub use npc::{NpcFaction, NpcId, NpcRole};
 pub use npc::manager::DamageNpcResult;
 pub use npc::ai_dialogue::AiDialogueManager;
+pub use npc::bark::{Bark, BarkCandidate, BarkLibrary, BarkLine, BarkSystem, BarkTrigger, BarkWorld};
+pub use npc::bestiary::{Bestiary, BestiaryEntry, BestiaryUpdate};
 pub use npc::character_cache::NpcCharacterCache;
 pub use npc::game_context::GameContext;
-pub use npc::relationship::{RelationshipManager, RelationshipSaveData};
-pub use player::{CharacterStats, EnemyType, MovementConfig, PlayerController, PlayerProgression, StatGrowth};
+pub use npc::identity::{Era, NpcIdentity, PersonalityTrait};
+pub use npc::lod::{AmbientCrowd, LodConfig, NpcLod};
+pub use npc::population::PopulationConfig;
+pub use npc::relationship::{NpcRelationship, RelationshipManager, RelationshipSaveData, RelationshipTier};
+pub use npc::requirement::{DialoguePlayer, DialogueStat, Requirement, StatCheck};
+pub use npc::survival::{SurvivalError, SurvivalEvent, SurvivalPhase, SurvivalRun, SurvivalUpgrade};
+pub use npc::training::{ArenaConfig, ArenaEvent, DummyHit, PracticeArena, TrainingDummy};
+pub use npc::world_boss::{WorldBoss, WorldBossAnnouncement, WorldBossEvent, WorldBossKind, WorldBossTracker};
+pub use throwable::{
+    create_throwables, predict_arc, segment_hit, throw_velocity, ArcPreview, Impact, SmokeCloud, ThrowableKind, Throwables,
+    FULL_CHARGE_TIME,
+};
+pub use seat::{Occupant, RestPose, Seat, SeatBlock, SeatId, SeatKind, SeatRegistry, REST_TIME_SCALE};
+pub use screen_markers::{ScreenMarker, ScreenPlacement};
+pub use rift::{RiftArena, RiftAttunement, RiftBoon, RiftError, RiftLedger, RiftOutcome, RiftPhase, RiftRun};
+pub use shrine::{Boon, PrayerError, Shrine, ShrineLedger, Tribute};
+pub use speedrun::{PersonalBest, RunEvent, Split, SplitTrigger, SpeedrunRecords, SpeedrunRun};
+pub use trap::{DisarmOutcome, TrapField, TrapId, TrapKind, TrapTarget, TrapTrigger};
+pub use tutorial::{TutorialEvent, TutorialManager, TutorialProgress, TutorialTopic};
+pub use player::{
+    CharacterStats, ClassSetup, ClimbConfig, ClimbState, EnemyType, GliderConfig, GrappleConfig, MovementConfig, PlayerController,
+    PlayerProgression, PlayStatistics, Stamina, StatEvent, StatGrowth, GLIDER_ITEM,
+};
 
 // Combat system re-exports
 pub use combat::{
-    AttackType, DamageEvent, Element, EquipmentSet, EquipmentSlot, Gem, GemQuality, GemShape,
-    Inventory, Item, ItemCategory, ItemId, ItemRarity, MAX_INVENTORY_SIZE, Rune, RuneComposer,
+    anachronism, AttackType, CombatLog, CombatTotals, ConsumableHotbar, DamageEvent, DamageKind, DamageNumbers, PLAYER_TARGET, Element, Encumbrance, EquipmentSet, EquipmentSlot, Gem, GemQuality, GemShape,
+    HOTBAR_SLOTS, Inventory, Item, ItemCategory, ItemId, ItemRarity, MAX_INVENTORY_SIZE, Rune, RuneComposer,
     Skill, SkillId, SkillSlot, StatModifiers, StatusEffect, StatusEffectType, StatusManager,
-    WeaponData, WeaponType,
+    ParadoxConfig, ParadoxEvent, ParadoxMeter, ParadoxStage, WeaponData, WeaponType,
 };
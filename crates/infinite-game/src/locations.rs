@@ -0,0 +1,175 @@
+//! Named locations: places the player can find, see on the map and travel to
+//!
+//! The world registers points of interest as their chunks stream in. Walking
+//! close enough discovers a location, which puts it on the map and makes it
+//! a fast travel destination. The registry is persisted with the save so
+//! discovered places are known even while their chunks aren't loaded.
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::compass::{CompassMarker, MarkerCategory};
+
+/// How close the player has to come to discover a location
+pub const DISCOVERY_RADIUS: f32 = 25.0;
+
+/// What kind of place a location is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LocationKind {
+    Settlement,
+    Bridge,
+    Shrine,
+}
+
+impl LocationKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Settlement => "Settlement",
+            Self::Bridge => "Bridge",
+            Self::Shrine => "Shrine",
+        }
+    }
+}
+
+/// A named place in the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub id: u64,
+    pub name: String,
+    pub kind: LocationKind,
+    pub position: Vec3,
+    /// Year the location exists in, or None if it stands in every era
+    pub year: Option<i64>,
+    pub discovered: bool,
+}
+
+impl Location {
+    pub fn new(id: u64, name: impl Into<String>, kind: LocationKind, position: Vec3, year: Option<i64>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            kind,
+            position,
+            year,
+            discovered: false,
+        }
+    }
+
+    pub fn exists_in(&self, year: i64) -> bool {
+        self.year.is_none_or(|y| y == year)
+    }
+}
+
+/// Every location registered so far, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationRegistry {
+    locations: BTreeMap<u64, Location>,
+}
+
+impl LocationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a location, or update where an already known one is (whether it
+    /// was discovered is kept). Returns true if it's new.
+    pub fn register(&mut self, location: Location) -> bool {
+        match self.locations.get_mut(&location.id) {
+            Some(known) => {
+                known.position = location.position;
+                known.name = location.name;
+                false
+            }
+            None => {
+                self.locations.insert(location.id, location);
+                true
+            }
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Location> {
+        self.locations.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Location> {
+        self.locations.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Discover the undiscovered locations of `year` within
+    /// [`DISCOVERY_RADIUS`] of `position`, returning them
+    pub fn discover_near(&mut self, position: Vec3, year: i64) -> Vec<&Location> {
+        self.locations
+            .values_mut()
+            .filter(|l| !l.discovered && l.exists_in(year))
+            .filter(|l| l.position.with_y(0.0).distance(position.with_y(0.0)) <= DISCOVERY_RADIUS)
+            .map(|l| {
+                l.discovered = true;
+                &*l
+            })
+            .collect()
+    }
+
+    /// Discovered locations of `year` the player can fast travel to, by name
+    pub fn travel_targets(&self, year: i64) -> Vec<&Location> {
+        let mut targets: Vec<&Location> = self.iter().filter(|l| l.discovered && l.exists_in(year)).collect();
+        targets.sort_by(|a, b| a.name.cmp(&b.name));
+        targets
+    }
+
+    /// Where fast travel to a location arrives, if it has been discovered
+    pub fn travel_destination(&self, id: u64, year: i64) -> Option<Vec3> {
+        self.get(id).filter(|l| l.discovered && l.exists_in(year)).map(|l| l.position)
+    }
+
+    /// Map markers for the discovered locations of `year`
+    pub fn markers(&self, year: i64) -> impl Iterator<Item = CompassMarker> + '_ {
+        self.iter()
+            .filter(move |l| l.discovered && l.exists_in(year))
+            .map(|l| CompassMarker::new(l.position, l.name.clone(), MarkerCategory::Interactable))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_unlocks_fast_travel() {
+        let mut registry = LocationRegistry::new();
+        let town = Location::new(1, "Ashford", LocationKind::Settlement, Vec3::new(100.0, 2.0, 0.0), None);
+        let bridge = Location::new(2, "Ravenmere Bridge", LocationKind::Bridge, Vec3::new(0.0, 4.0, 10.0), Some(1200));
+        assert!(registry.register(town.clone()));
+        assert!(registry.register(bridge));
+        assert!(!registry.register(town), "registering again only updates it");
+        assert_eq!(registry.len(), 2);
+        assert!(registry.travel_targets(1200).is_empty());
+        assert_eq!(registry.travel_destination(1, 1200), None);
+
+        // The bridge only stands in its own era
+        assert!(registry.discover_near(Vec3::ZERO, 2025).is_empty());
+        let found: Vec<u64> = registry.discover_near(Vec3::new(0.0, 50.0, 0.0), 1200).iter().map(|l| l.id).collect();
+        assert_eq!(found, vec![2]);
+        assert!(registry.discover_near(Vec3::ZERO, 1200).is_empty(), "only discovered once");
+        assert_eq!(registry.markers(1200).count(), 1);
+        assert_eq!(registry.markers(2025).count(), 0);
+
+        registry.discover_near(Vec3::new(90.0, 0.0, 0.0), 2025);
+        let names: Vec<&str> = registry.travel_targets(1200).iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["Ashford", "Ravenmere Bridge"]);
+        assert_eq!(registry.travel_destination(1, 2025), Some(Vec3::new(100.0, 2.0, 0.0)));
+
+        // Discoveries survive a re-register when the chunk streams in again
+        registry.register(Location::new(1, "Ashford", LocationKind::Settlement, Vec3::new(100.0, 3.0, 0.0), None));
+        assert!(registry.get(1).unwrap().discovered);
+    }
+}
@@ -0,0 +1,337 @@
+//! Door keys and the lockpicking minigame
+//!
+//! Locked doors open from a linked lever, a key cut for that door, or a
+//! successful lockpick. Keys live on the player's key ring rather than in
+//! the inventory grid. Picking a lock is a timing game: the pick sweeps
+//! across the lock and each pin has to be set while the pick is over its
+//! sweet spot. Misses and time spent add tension; a pick that reaches full
+//! tension breaks. Harder lock tiers have more pins, a faster sweep and a
+//! smaller sweet spot, and need more dexterity to attempt at all.
+
+use infinite_core::DetRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::Element;
+use crate::combat::item::{Item, ItemCategory, ItemId, ItemRarity};
+use crate::interaction::InteractableId;
+
+/// Item id of lockpicks
+pub const LOCKPICK_ITEM_ID: ItemId = ItemId(3102);
+
+/// Extra sweet spot width per point of dexterity above a lock's requirement
+const DEXTERITY_WIDTH_BONUS: f32 = 0.04;
+
+/// Difficulty tier of a pickable lock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LockTier {
+    Simple,
+    Sturdy,
+    Masterwork,
+}
+
+impl LockTier {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Simple => "Simple",
+            Self::Sturdy => "Sturdy",
+            Self::Masterwork => "Masterwork",
+        }
+    }
+
+    /// Pins to set before the lock opens
+    pub fn pins(self) -> u32 {
+        match self {
+            Self::Simple => 2,
+            Self::Sturdy => 3,
+            Self::Masterwork => 4,
+        }
+    }
+
+    /// Dexterity needed to attempt the lock
+    pub fn required_dexterity(self) -> f32 {
+        match self {
+            Self::Simple => 0.0,
+            Self::Sturdy => 6.0,
+            Self::Masterwork => 10.0,
+        }
+    }
+
+    /// Full sweeps of the pick across the lock per second
+    fn sweep_speed(self) -> f32 {
+        match self {
+            Self::Simple => 0.8,
+            Self::Sturdy => 1.1,
+            Self::Masterwork => 1.5,
+        }
+    }
+
+    /// Sweet spot width (fraction of the lock) before the dexterity bonus
+    fn sweet_width(self) -> f32 {
+        match self {
+            Self::Simple => 0.16,
+            Self::Sturdy => 0.11,
+            Self::Masterwork => 0.07,
+        }
+    }
+
+    /// Tension added per second while picking
+    fn tension_rate(self) -> f32 {
+        match self {
+            Self::Simple => 0.04,
+            Self::Sturdy => 0.07,
+            Self::Masterwork => 0.1,
+        }
+    }
+
+    /// Tension added by a missed pin
+    fn slip_tension(self) -> f32 {
+        match self {
+            Self::Simple => 0.25,
+            Self::Sturdy => 0.34,
+            Self::Masterwork => 0.5,
+        }
+    }
+}
+
+/// A key cut for one door
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoorKey {
+    pub door: InteractableId,
+    pub name: String,
+}
+
+/// Keys the player carries, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyRing {
+    keys: Vec<DoorKey>,
+}
+
+impl KeyRing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a key, returns false if a key for that door is already on the ring
+    pub fn add(&mut self, key: DoorKey) -> bool {
+        if self.key_for(key.door).is_some() {
+            return false;
+        }
+        self.keys.push(key);
+        true
+    }
+
+    /// The key that opens `door`, if carried
+    pub fn key_for(&self, door: InteractableId) -> Option<&DoorKey> {
+        self.keys.iter().find(|k| k.door == door)
+    }
+
+    /// Keys in the order they were picked up
+    pub fn keys(&self) -> impl Iterator<Item = &DoorKey> {
+        self.keys.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Outcome of setting a pin (or of the pick giving out on its own)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickAttempt {
+    /// A pin was set, more remain
+    PinSet,
+    /// The last pin was set and the lock is open
+    Opened,
+    /// Missed the sweet spot, tension went up
+    Slipped,
+    /// Too much tension: the pick broke
+    Broke,
+}
+
+/// A lockpicking attempt in progress
+#[derive(Debug, Clone)]
+pub struct LockpickSession {
+    pub door: InteractableId,
+    pub lock: LockTier,
+    /// Pick position across the lock (0 - 1, sweeps back and forth)
+    pub pick: f32,
+    /// Centre of the current pin's sweet spot (0 - 1)
+    pub sweet_spot: f32,
+    /// Width of the sweet spot (fraction of the lock)
+    pub sweet_width: f32,
+    /// Tension on the pick (0 - 1, breaks at 1)
+    pub tension: f32,
+    pub pins_set: u32,
+    sweep_direction: f32,
+    rng: DetRng,
+}
+
+impl LockpickSession {
+    /// Start picking `door`'s lock. `dexterity` above the lock's requirement
+    /// widens the sweet spot; `seed` places the pins.
+    pub fn new(door: InteractableId, lock: LockTier, dexterity: f32, seed: u64) -> Self {
+        let bonus = (dexterity - lock.required_dexterity()).max(0.0) * DEXTERITY_WIDTH_BONUS;
+        let mut session = Self {
+            door,
+            lock,
+            pick: 0.0,
+            sweet_spot: 0.5,
+            sweet_width: lock.sweet_width() * (1.0 + bonus).min(2.0),
+            tension: 0.0,
+            pins_set: 0,
+            sweep_direction: 1.0,
+            rng: DetRng::new(seed),
+        };
+        session.place_sweet_spot();
+        session
+    }
+
+    /// Whether `dexterity` is enough to attempt a lock of this tier
+    pub fn can_attempt(lock: LockTier, dexterity: f32) -> bool {
+        dexterity >= lock.required_dexterity()
+    }
+
+    /// Sweep the pick and build tension. Returns `Broke` if the tension maxed out.
+    pub fn update(&mut self, delta: f32) -> Option<PickAttempt> {
+        self.pick += self.sweep_direction * self.lock.sweep_speed() * delta;
+        if self.pick >= 1.0 {
+            self.pick = 2.0 - self.pick;
+            self.sweep_direction = -1.0;
+        } else if self.pick <= 0.0 {
+            self.pick = -self.pick;
+            self.sweep_direction = 1.0;
+        }
+        self.pick = self.pick.clamp(0.0, 1.0);
+
+        self.add_tension(self.lock.tension_rate() * delta)
+    }
+
+    /// Whether the pick is over the sweet spot right now
+    pub fn in_sweet_spot(&self) -> bool {
+        (self.pick - self.sweet_spot).abs() <= self.sweet_width * 0.5
+    }
+
+    /// Try to set the current pin
+    pub fn attempt(&mut self) -> PickAttempt {
+        if !self.in_sweet_spot() {
+            return self.add_tension(self.lock.slip_tension()).unwrap_or(PickAttempt::Slipped);
+        }
+        self.pins_set += 1;
+        if self.pins_set >= self.lock.pins() {
+            return PickAttempt::Opened;
+        }
+        self.place_sweet_spot();
+        PickAttempt::PinSet
+    }
+
+    fn add_tension(&mut self, amount: f32) -> Option<PickAttempt> {
+        self.tension = (self.tension + amount).min(1.0);
+        (self.tension >= 1.0).then_some(PickAttempt::Broke)
+    }
+
+    fn place_sweet_spot(&mut self) {
+        let half = self.sweet_width * 0.5;
+        self.sweet_spot = self.rng.gen_range(half..=1.0 - half);
+    }
+}
+
+/// A bundle of lockpicks. One is used up each time a pick breaks.
+pub fn create_lockpicks(count: u32) -> Item {
+    Item {
+        id: LOCKPICK_ITEM_ID,
+        name: "Lockpick".to_string(),
+        description: "Thin picks for coaxing open a locked door. Breaks under too much tension.".to_string(),
+        category: ItemCategory::Material,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers::default(),
+        element: Element::Physical,
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 10,
+        origin_year: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Advance a session until the pick is centred on the sweet spot
+    fn sweep_to_sweet_spot(session: &mut LockpickSession) {
+        for _ in 0..10_000 {
+            if (session.pick - session.sweet_spot).abs() < 0.01 {
+                return;
+            }
+            session.tension = 0.0;
+            session.update(0.001);
+        }
+        panic!("pick never reached the sweet spot");
+    }
+
+    #[test]
+    fn test_setting_all_pins_opens_lock() {
+        let mut session = LockpickSession::new(InteractableId(1), LockTier::Sturdy, 6.0, 7);
+        for pin in 1..LockTier::Sturdy.pins() {
+            sweep_to_sweet_spot(&mut session);
+            assert_eq!(session.attempt(), PickAttempt::PinSet);
+            assert_eq!(session.pins_set, pin);
+        }
+        sweep_to_sweet_spot(&mut session);
+        assert_eq!(session.attempt(), PickAttempt::Opened);
+    }
+
+    #[test]
+    fn test_misses_break_the_pick() {
+        let mut session = LockpickSession::new(InteractableId(1), LockTier::Masterwork, 10.0, 3);
+        // Park the pick as far from the sweet spot as possible
+        session.pick = if session.sweet_spot > 0.5 { 0.0 } else { 1.0 };
+        assert_eq!(session.attempt(), PickAttempt::Slipped);
+        assert_eq!(session.attempt(), PickAttempt::Broke);
+        assert_eq!(session.pins_set, 0);
+    }
+
+    #[test]
+    fn test_tension_builds_over_time() {
+        let mut session = LockpickSession::new(InteractableId(1), LockTier::Simple, 0.0, 1);
+        let mut outcome = None;
+        for _ in 0..1000 {
+            outcome = session.update(0.1);
+            if outcome.is_some() {
+                break;
+            }
+        }
+        assert_eq!(outcome, Some(PickAttempt::Broke));
+        assert!((0.0..=1.0).contains(&session.pick));
+    }
+
+    #[test]
+    fn test_dexterity_gates_and_widens() {
+        assert!(!LockpickSession::can_attempt(LockTier::Masterwork, 5.0));
+        assert!(LockpickSession::can_attempt(LockTier::Simple, 0.0));
+        let clumsy = LockpickSession::new(InteractableId(1), LockTier::Simple, 0.0, 1);
+        let nimble = LockpickSession::new(InteractableId(1), LockTier::Simple, 20.0, 1);
+        assert!(nimble.sweet_width > clumsy.sweet_width);
+    }
+
+    #[test]
+    fn test_key_ring_matches_doors() {
+        let mut ring = KeyRing::new();
+        assert!(ring.add(DoorKey { door: InteractableId(4), name: "Cellar Key".to_string() }));
+        assert!(!ring.add(DoorKey { door: InteractableId(4), name: "Spare".to_string() }));
+        assert_eq!(ring.key_for(InteractableId(4)).map(|k| k.name.as_str()), Some("Cellar Key"));
+        assert!(ring.key_for(InteractableId(5)).is_none());
+        assert_eq!(ring.len(), 1);
+    }
+}
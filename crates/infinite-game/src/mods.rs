@@ -0,0 +1,769 @@
+//! Mods: content packs loaded from a `mods/` directory
+//!
+//! Each pack is a folder holding a `mod.json` manifest (id, version,
+//! dependencies) and any of `items.json`, `skills.json` and `dialogue.json`.
+//!
+//! [`ModSet::scan`] reads them all, drops the ones that can't load, and
+//! orders the rest so every mod comes after its dependencies, ties broken
+//! by id. Merging walks that order: an entry with the same id as an earlier
+//! one replaces it, and the `apply_*` methods of [`ModContent`] do the same
+//! against the base game's data. Every replacement is reported, so
+//! conflicts show in the log.
+//!
+//! The ids and versions of the active mods are stored with each save, and
+//! [`compare_mods`] lists what changed when a save is loaded.
+//!
+//! Quests, scatter definitions and scripts are not part of mod support.
+//! The game has no quest registry, resource and prop placement is rolled in
+//! code rather than from tables, and there is no script runtime, so there
+//! is nothing for such content to merge into. Packs shipping `quests.json`,
+//! `scatter.json` or a `scripts/` folder load without them, and each one
+//! is reported with [`ModError::Unsupported`] so mod authors can tell.
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain, Severity};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::catalog::ItemCatalog;
+use crate::combat::item::Item;
+use crate::combat::skill::{Skill, SkillSlot};
+use crate::npc::dialogue::{DialogueSystem, DialogueTree};
+
+/// File in each pack's folder describing the mod
+pub const MANIFEST_FILE: &str = "mod.json";
+
+/// Content packs may ship that mods can't change (see the module docs)
+const UNSUPPORTED_CONTENT: [&str; 3] = ["quests.json", "scatter.json", "scripts"];
+
+/// Failure loading a mod; the game carries on without it
+#[derive(Debug, thiserror::Error)]
+pub enum ModError {
+    #[error("couldn't read '{0}': {1}")]
+    Io(PathBuf, #[source] io::Error),
+    #[error("invalid mod file '{0}': {1}")]
+    Parse(PathBuf, String),
+    #[error("two mods use the id '{0}'; only the first is loaded")]
+    DuplicateId(String),
+    #[error("mod '{id}' needs '{dependency}', which isn't installed")]
+    MissingDependency { id: String, dependency: String },
+    #[error("mod '{id}' needs '{dependency}' {wanted} or newer, but {found} is installed")]
+    DependencyTooOld {
+        id: String,
+        dependency: String,
+        wanted: String,
+        found: String,
+    },
+    #[error("mods depend on each other in a loop: {}", .0.join(", "))]
+    Cycle(Vec<String>),
+    #[error("mod '{id}' ships '{file}', which this version of the game doesn't load")]
+    Unsupported { id: String, file: &'static str },
+}
+
+impl Diagnostic for ModError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::Io(..) => 1,
+            Self::Parse(..) => 2,
+            Self::DuplicateId(_) => 3,
+            Self::MissingDependency { .. } => 4,
+            Self::DependencyTooOld { .. } => 5,
+            Self::Cycle(_) => 6,
+            Self::Unsupported { .. } => 7,
+        };
+        ErrorCode::new(ErrorDomain::Mods, number)
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Io(..) | Self::Parse(..) => Some("Reinstall the mod, or remove its folder from mods/"),
+            Self::DuplicateId(_) => Some("Remove one of the copies from mods/"),
+            Self::MissingDependency { .. } | Self::DependencyTooOld { .. } => {
+                Some("Install or update the mods it depends on")
+            }
+            Self::Cycle(_) => Some("Remove one of the mods in the loop"),
+            Self::Unsupported { .. } => Some("The rest of the mod is loaded; check for an update made for this version"),
+        }
+    }
+}
+
+/// Another mod that must load first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModDependency {
+    pub id: String,
+    /// Oldest version that works, e.g. `"1.2"`; `None` accepts any
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// A pack's `mod.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModManifest {
+    pub id: String,
+    /// Shown to players; the id when empty
+    #[serde(default)]
+    pub name: String,
+    /// Dot-separated numbers, e.g. `"1.4.2"`
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub dependencies: Vec<ModDependency>,
+}
+
+impl ModManifest {
+    pub fn new(id: &str, version: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: String::new(),
+            version: version.to_string(),
+            description: String::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    pub fn depends_on(mut self, id: &str, version: Option<&str>) -> Self {
+        self.dependencies.push(ModDependency {
+            id: id.to_string(),
+            version: version.map(str::to_string),
+        });
+        self
+    }
+
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// An item a mod adds to shops; the item's fields plus what it sells for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModItem {
+    #[serde(flatten)]
+    pub item: Item,
+    #[serde(default)]
+    pub price: u64,
+}
+
+/// Everything one pack defines
+#[derive(Debug, Clone, Default)]
+pub struct ModPack {
+    pub items: Vec<ModItem>,
+    pub skills: Vec<Skill>,
+    /// Conversation trees by key; a role's key replaces what NPCs of that
+    /// role say
+    pub dialogue: BTreeMap<String, DialogueTree>,
+}
+
+impl ModPack {
+    /// Read a pack's content files from `dir`; missing files are empty
+    pub fn load(dir: &Path) -> Result<Self, ModError> {
+        let dialogue: BTreeMap<String, DialogueTree> = read_json(&dir.join("dialogue.json"))?.unwrap_or_default();
+        for (key, tree) in &dialogue {
+            tree.validate()
+                .map_err(|message| ModError::Parse(dir.join("dialogue.json"), format!("tree '{}': {}", key, message)))?;
+        }
+        Ok(Self {
+            items: read_json(&dir.join("items.json"))?.unwrap_or_default(),
+            skills: read_json(&dir.join("skills.json"))?.unwrap_or_default(),
+            dialogue,
+        })
+    }
+}
+
+/// A mod whose files loaded
+#[derive(Debug, Clone)]
+pub struct LoadedMod {
+    pub manifest: ModManifest,
+    pub pack: ModPack,
+    /// Folder it was loaded from (empty for mods built in code)
+    pub path: PathBuf,
+}
+
+impl LoadedMod {
+    pub fn new(manifest: ModManifest, pack: ModPack) -> Self {
+        Self {
+            manifest,
+            pack,
+            path: PathBuf::new(),
+        }
+    }
+
+    /// Read the pack in `dir`
+    pub fn load(dir: &Path) -> Result<Self, ModError> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let manifest: ModManifest = read_json(&manifest_path)?
+            .ok_or_else(|| ModError::Parse(manifest_path.clone(), "missing manifest".into()))?;
+        if manifest.id.trim().is_empty() {
+            return Err(ModError::Parse(manifest_path, "the manifest has no id".into()));
+        }
+        Ok(Self {
+            manifest,
+            pack: ModPack::load(dir)?,
+            path: dir.to_path_buf(),
+        })
+    }
+}
+
+/// A mod and version as recorded in a save
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModRecord {
+    pub id: String,
+    pub version: String,
+}
+
+/// How the mods differ from when a save was made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModMismatch {
+    /// Active when saved, not now
+    Missing(ModRecord),
+    /// Active now, not when saved
+    Added(ModRecord),
+    VersionChanged { id: String, saved: String, active: String },
+}
+
+impl fmt::Display for ModMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(record) => write!(f, "{} {} is no longer active", record.id, record.version),
+            Self::Added(record) => write!(f, "{} {} wasn't active when this was saved", record.id, record.version),
+            Self::VersionChanged { id, saved, active } => write!(f, "{} changed from {} to {}", id, saved, active),
+        }
+    }
+}
+
+/// What changed between the mods a save was made with and the active ones
+pub fn compare_mods(saved: &[ModRecord], active: &[ModRecord]) -> Vec<ModMismatch> {
+    let mut mismatches = Vec::new();
+    for record in saved {
+        match active.iter().find(|a| a.id == record.id) {
+            None => mismatches.push(ModMismatch::Missing(record.clone())),
+            Some(a) if a.version != record.version => mismatches.push(ModMismatch::VersionChanged {
+                id: record.id.clone(),
+                saved: record.version.clone(),
+                active: a.version.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for record in active {
+        if !saved.iter().any(|s| s.id == record.id) {
+            mismatches.push(ModMismatch::Added(record.clone()));
+        }
+    }
+    mismatches
+}
+
+/// Whether `version` is `minimum` or newer. Missing parts count as 0 and
+/// anything after the digits of a part (`-beta`) is ignored.
+pub fn version_at_least(version: &str, minimum: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim()
+            .split('.')
+            .map(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().unwrap_or(0)
+            })
+            .collect()
+    }
+    let (have, want) = (parts(version), parts(minimum));
+    for i in 0..have.len().max(want.len()) {
+        let (h, w) = (have.get(i).copied().unwrap_or(0), want.get(i).copied().unwrap_or(0));
+        if h != w {
+            return h > w;
+        }
+    }
+    true
+}
+
+/// Kind of content an entry belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ContentKind {
+    Item,
+    Skill,
+    Dialogue,
+}
+
+impl ContentKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Item => "item",
+            Self::Skill => "skill",
+            Self::Dialogue => "dialogue",
+        }
+    }
+}
+
+/// A mod's entry replacing an earlier one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentOverride {
+    pub kind: ContentKind,
+    pub id: String,
+    /// Mod whose entry won
+    pub by: String,
+    /// Mod whose entry was replaced; `None` for the base game's
+    pub replaced: Option<String>,
+}
+
+impl fmt::Display for ContentOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let replaced = self.replaced.as_deref().unwrap_or("the base game");
+        write!(f, "{} '{}' from {} replaced by {}", self.kind.name(), self.id, replaced, self.by)
+    }
+}
+
+/// An entry and the mod it came from
+#[derive(Debug, Clone)]
+struct Sourced<T> {
+    value: T,
+    by: String,
+}
+
+/// The content of every active mod, merged in load order
+#[derive(Debug, Clone, Default)]
+pub struct ModContent {
+    items: BTreeMap<u64, Sourced<ModItem>>,
+    skills: BTreeMap<u64, Sourced<Skill>>,
+    dialogue: BTreeMap<String, Sourced<DialogueTree>>,
+    /// Entries one mod replaced in another
+    pub overrides: Vec<ContentOverride>,
+}
+
+impl ModContent {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.skills.is_empty() && self.dialogue.is_empty()
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &ModItem> {
+        self.items.values().map(|s| &s.value)
+    }
+
+    pub fn skills(&self) -> impl Iterator<Item = &Skill> {
+        self.skills.values().map(|s| &s.value)
+    }
+
+    pub fn dialogue(&self, key: &str) -> Option<&DialogueTree> {
+        self.dialogue.get(key).map(|s| &s.value)
+    }
+
+    /// Mod that defined the winning entry
+    pub fn source(&self, kind: ContentKind, id: &str) -> Option<&str> {
+        let by = match kind {
+            ContentKind::Item => id.parse().ok().and_then(|id| self.items.get(&id)).map(|s| &s.by),
+            ContentKind::Skill => id.parse().ok().and_then(|id| self.skills.get(&id)).map(|s| &s.by),
+            ContentKind::Dialogue => self.dialogue.get(id).map(|s| &s.by),
+        };
+        by.map(String::as_str)
+    }
+
+    /// Add `pack`'s entries on top of what's merged so far
+    fn merge(&mut self, id: &str, pack: &ModPack) {
+        let overrides = &mut self.overrides;
+        for item in &pack.items {
+            merge_entry(&mut self.items, overrides, ContentKind::Item, item.item.id.0, item.clone(), id);
+        }
+        for skill in &pack.skills {
+            merge_entry(&mut self.skills, overrides, ContentKind::Skill, skill.id().0, skill.clone(), id);
+        }
+        for (key, tree) in &pack.dialogue {
+            merge_entry(&mut self.dialogue, overrides, ContentKind::Dialogue, key.clone(), tree.clone(), id);
+        }
+    }
+
+    /// Add the mods' items to a shop catalog, replacing base items with
+    /// the same id
+    pub fn apply_items(&self, catalog: &mut ItemCatalog) -> Vec<ContentOverride> {
+        let mut replaced = Vec::new();
+        for (id, entry) in &self.items {
+            if catalog.insert(entry.value.item.clone(), entry.value.price) {
+                replaced.push(base_override(ContentKind::Item, id.to_string(), &entry.by));
+            }
+        }
+        replaced
+    }
+
+    /// Swap skills in `slots` for the mods' versions with the same id
+    pub fn apply_skills(&self, slots: &mut [SkillSlot]) -> Vec<ContentOverride> {
+        let mut replaced = Vec::new();
+        for slot in slots {
+            let Some(id) = slot.skill.as_ref().map(|skill| skill.id().0) else {
+                continue;
+            };
+            if let Some(entry) = self.skills.get(&id) {
+                slot.skill = Some(entry.value.clone());
+                replaced.push(base_override(ContentKind::Skill, id.to_string(), &entry.by));
+            }
+        }
+        replaced
+    }
+
+    /// Register the mods' conversation trees, replacing base ones with the
+    /// same key
+    pub fn apply_dialogue(&self, system: &mut DialogueSystem) -> Vec<ContentOverride> {
+        let mut replaced = Vec::new();
+        for (key, entry) in &self.dialogue {
+            if system.insert_tree(key.clone(), entry.value.clone()).is_some() {
+                replaced.push(base_override(ContentKind::Dialogue, key.clone(), &entry.by));
+            }
+        }
+        replaced
+    }
+}
+
+fn merge_entry<K: Ord + ToString, T>(
+    entries: &mut BTreeMap<K, Sourced<T>>,
+    overrides: &mut Vec<ContentOverride>,
+    kind: ContentKind,
+    key: K,
+    value: T,
+    by: &str,
+) {
+    let id = key.to_string();
+    if let Some(previous) = entries.insert(key, Sourced { value, by: by.to_string() }) {
+        overrides.push(ContentOverride {
+            kind,
+            id,
+            by: by.to_string(),
+            replaced: Some(previous.by),
+        });
+    }
+}
+
+fn base_override(kind: ContentKind, id: String, by: &str) -> ContentOverride {
+    ContentOverride {
+        kind,
+        id,
+        by: by.to_string(),
+        replaced: None,
+    }
+}
+
+/// The mods that loaded, in load order
+#[derive(Debug, Clone, Default)]
+pub struct ModSet {
+    mods: Vec<LoadedMod>,
+}
+
+impl ModSet {
+    /// Load every pack folder in `dir`. A missing directory means no mods.
+    /// Packs that fail to load, and the mods depending on them, are left out
+    /// and reported, as is content the game doesn't load.
+    pub fn scan(dir: &Path) -> (Self, Vec<ModError>) {
+        let mut errors = Vec::new();
+        let mut folders = match fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(Result::ok).map(|e| e.path()).filter(|p| p.is_dir()).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                errors.push(ModError::Io(dir.to_path_buf(), e));
+                Vec::new()
+            }
+        };
+        folders.sort();
+        let mut mods = Vec::new();
+        for folder in folders {
+            match LoadedMod::load(&folder) {
+                Ok(loaded) => {
+                    errors.extend(
+                        UNSUPPORTED_CONTENT
+                            .into_iter()
+                            .filter(|file| folder.join(file).exists())
+                            .map(|file| ModError::Unsupported { id: loaded.manifest.id.clone(), file }),
+                    );
+                    mods.push(loaded);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        let (set, resolve_errors) = Self::resolve(mods);
+        errors.extend(resolve_errors);
+        (set, errors)
+    }
+
+    /// Order `mods` by their dependencies, leaving out duplicates and those
+    /// whose dependencies are missing, too old or circular
+    pub fn resolve(mods: Vec<LoadedMod>) -> (Self, Vec<ModError>) {
+        let mut errors = Vec::new();
+        let mut pending: BTreeMap<String, LoadedMod> = BTreeMap::new();
+        for loaded in mods {
+            match pending.entry(loaded.manifest.id.clone()) {
+                Entry::Occupied(entry) => errors.push(ModError::DuplicateId(entry.key().clone())),
+                Entry::Vacant(entry) => {
+                    entry.insert(loaded);
+                }
+            }
+        }
+
+        // Dropping a mod can leave its dependents unmet, so repeat until
+        // nothing else drops
+        loop {
+            let unmet: Vec<(String, ModError)> = pending
+                .values()
+                .filter_map(|loaded| {
+                    let error = loaded.manifest.dependencies.iter().find_map(|dep| unmet_dependency(&loaded.manifest, dep, &pending))?;
+                    Some((loaded.manifest.id.clone(), error))
+                })
+                .collect();
+            if unmet.is_empty() {
+                break;
+            }
+            for (id, error) in unmet {
+                pending.remove(&id);
+                errors.push(error);
+            }
+        }
+
+        let mut placed = HashSet::new();
+        let mut mods = Vec::new();
+        while !pending.is_empty() {
+            let ready = pending
+                .values()
+                .find(|loaded| loaded.manifest.dependencies.iter().all(|dep| placed.contains(&dep.id)))
+                .map(|loaded| loaded.manifest.id.clone());
+            let Some(id) = ready else {
+                errors.push(ModError::Cycle(pending.keys().cloned().collect()));
+                break;
+            };
+            placed.insert(id.clone());
+            mods.extend(pending.remove(&id));
+        }
+        (Self { mods }, errors)
+    }
+
+    pub fn mods(&self) -> &[LoadedMod] {
+        &self.mods
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mods.is_empty()
+    }
+
+    /// What to store in a save
+    pub fn records(&self) -> Vec<ModRecord> {
+        self.mods
+            .iter()
+            .map(|loaded| ModRecord {
+                id: loaded.manifest.id.clone(),
+                version: loaded.manifest.version.clone(),
+            })
+            .collect()
+    }
+
+    /// Merge every mod's content, later mods winning
+    pub fn content(&self) -> ModContent {
+        let mut content = ModContent::default();
+        for loaded in &self.mods {
+            content.merge(&loaded.manifest.id, &loaded.pack);
+        }
+        content
+    }
+}
+
+fn unmet_dependency(manifest: &ModManifest, dep: &ModDependency, mods: &BTreeMap<String, LoadedMod>) -> Option<ModError> {
+    let Some(found) = mods.get(&dep.id) else {
+        return Some(ModError::MissingDependency {
+            id: manifest.id.clone(),
+            dependency: dep.id.clone(),
+        });
+    };
+    let wanted = dep.version.as_ref()?;
+    (!version_at_least(&found.manifest.version, wanted)).then(|| ModError::DependencyTooOld {
+        id: manifest.id.clone(),
+        dependency: dep.id.clone(),
+        wanted: wanted.clone(),
+        found: found.manifest.version.clone(),
+    })
+}
+
+/// Parse `path`, or `None` if it doesn't exist
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, ModError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ModError::Io(path.to_path_buf(), e)),
+    };
+    serde_json::from_str(&text).map(Some).map_err(|e| ModError::Parse(path.to_path_buf(), e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::starter_items::{create_health_potion, create_starter_skills};
+    use crate::npc::dialogue::DialogueNode;
+    use crate::npc::{NpcId, NpcRole};
+
+    fn tree(text: &str) -> DialogueTree {
+        DialogueTree {
+            nodes: vec![DialogueNode {
+                speaker: String::new(),
+                text: text.into(),
+                responses: Vec::new(),
+            }],
+            start_node: 0,
+        }
+    }
+
+    fn pack_with_dialogue(key: &str, text: &str) -> ModPack {
+        let mut pack = ModPack::default();
+        pack.dialogue.insert(key.into(), tree(text));
+        pack
+    }
+
+    #[test]
+    fn test_dependencies_order_and_overrides() {
+        let mods = vec![
+            LoadedMod::new(
+                ModManifest::new("zz_patch", "1.0").depends_on("core_expansion", Some("2.1")),
+                pack_with_dialogue("villager", "Patched greeting"),
+            ),
+            LoadedMod::new(ModManifest::new("core_expansion", "2.3.0"), pack_with_dialogue("villager", "Expanded greeting")),
+            LoadedMod::new(ModManifest::new("needs_missing", "1.0").depends_on("nowhere", None), ModPack::default()),
+            LoadedMod::new(ModManifest::new("needs_newer", "1.0").depends_on("core_expansion", Some("3")), ModPack::default()),
+            LoadedMod::new(ModManifest::new("core_expansion", "9.9"), ModPack::default()),
+            LoadedMod::new(ModManifest::new("loop_a", "1.0").depends_on("loop_b", None), ModPack::default()),
+            LoadedMod::new(ModManifest::new("loop_b", "1.0").depends_on("loop_a", None), ModPack::default()),
+        ];
+        let (set, errors) = ModSet::resolve(mods);
+        let ids: Vec<&str> = set.mods().iter().map(|m| m.manifest.id.as_str()).collect();
+        assert_eq!(ids, vec!["core_expansion", "zz_patch"], "dependency first, the rest dropped");
+        assert!(errors.iter().any(|e| matches!(e, ModError::DuplicateId(id) if id == "core_expansion")));
+        assert!(errors.iter().any(|e| matches!(e, ModError::MissingDependency { id, .. } if id == "needs_missing")));
+        assert!(errors.iter().any(|e| matches!(e, ModError::DependencyTooOld { found, .. } if found == "2.3.0")));
+        assert!(errors.iter().any(|e| matches!(e, ModError::Cycle(ids) if ids.len() == 2)));
+
+        let content = set.content();
+        assert_eq!(content.dialogue("villager").unwrap().nodes[0].text, "Patched greeting");
+        assert_eq!(content.source(ContentKind::Dialogue, "villager"), Some("zz_patch"));
+        assert_eq!(
+            content.overrides,
+            vec![ContentOverride {
+                kind: ContentKind::Dialogue,
+                id: "villager".into(),
+                by: "zz_patch".into(),
+                replaced: Some("core_expansion".into()),
+            }]
+        );
+
+        // Against base data
+        let mut dialogue = DialogueSystem::new();
+        let replaced = content.apply_dialogue(&mut dialogue);
+        assert_eq!(replaced.len(), 1);
+        assert_eq!(replaced[0].replaced, None);
+        dialogue.start_dialogue(NpcId(1), "Ada".into(), NpcRole::Villager);
+        assert_eq!(dialogue.current_node().unwrap().text, "Patched greeting");
+    }
+
+    #[test]
+    fn test_items_and_skills_replace_base_by_id() {
+        let mut potion = create_health_potion(1);
+        potion.name = "Modded Potion".into();
+        let mut skill = create_starter_skills("Vanguard")[0].skill.clone().unwrap();
+        if let Skill::Active(active) = &mut skill {
+            active.base_damage = 999.0;
+        }
+        let mut pack = ModPack::default();
+        pack.items.push(ModItem { item: potion.clone(), price: 40 });
+        pack.skills.push(skill);
+        let (set, errors) = ModSet::resolve(vec![LoadedMod::new(ModManifest::new("potions", "1.0"), pack)]);
+        assert!(errors.is_empty());
+        let content = set.content();
+
+        let mut catalog = ItemCatalog::load_from_server(Vec::new());
+        assert!(content.apply_items(&mut catalog).is_empty(), "new to the catalog");
+        let mut catalog = ItemCatalog::load_from_server(Vec::new());
+        assert!(!catalog.insert(create_health_potion(1), 5));
+        assert_eq!(content.apply_items(&mut catalog).len(), 1, "replaces the base potion");
+        assert_eq!(catalog.len(), 1);
+        assert_eq!(catalog.items()[0].name, "Modded Potion");
+        assert_eq!(catalog.price(0), 40);
+        assert_eq!(catalog.server_item_id(0), None);
+
+        let mut slots = create_starter_skills("Vanguard");
+        assert_eq!(content.apply_skills(&mut slots).len(), 1);
+        assert!(matches!(&slots[0].skill, Some(Skill::Active(a)) if a.base_damage == 999.0));
+        let mut other = create_starter_skills("Chronomancer");
+        assert!(content.apply_skills(&mut other).is_empty(), "different skill id");
+    }
+
+    #[test]
+    fn test_scan_reads_pack_folders() {
+        let dir = std::env::temp_dir().join(format!("infinite-mods-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let good = dir.join("rift_tales");
+        fs::create_dir_all(good.join("scripts")).unwrap();
+        fs::write(good.join(MANIFEST_FILE), r#"{"id": "rift_tales", "version": "0.3"}"#).unwrap();
+        fs::write(
+            good.join("dialogue.json"),
+            r#"{"guard": {"nodes": [{"text": "Halt!", "responses": [{"text": "Bye"}]}]}}"#,
+        )
+        .unwrap();
+        fs::write(
+            good.join("quests.json"),
+            r#"[{"id": "lost_watch", "title": "The Lost Watch", "reward_gold": 50}]"#,
+        )
+        .unwrap();
+        fs::write(good.join("scripts").join("intro.lua"), "print('hi')").unwrap();
+        let broken = dir.join("broken");
+        fs::create_dir_all(&broken).unwrap();
+        fs::write(broken.join(MANIFEST_FILE), r#"{"version": "1.0"}"#).unwrap();
+        let bad_tree = dir.join("bad_tree");
+        fs::create_dir_all(&bad_tree).unwrap();
+        fs::write(bad_tree.join(MANIFEST_FILE), r#"{"id": "bad_tree", "version": "1.0"}"#).unwrap();
+        fs::write(bad_tree.join("dialogue.json"), r#"{"guard": {"nodes": [], "start_node": 0}}"#).unwrap();
+
+        let (set, errors) = ModSet::scan(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(set.records(), vec![ModRecord { id: "rift_tales".into(), version: "0.3".into() }]);
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert_eq!(errors.iter().filter(|e| matches!(e, ModError::Parse(..))).count(), 2);
+        let unsupported: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| match e {
+                ModError::Unsupported { id, file } if id == "rift_tales" => Some(*file),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(unsupported, vec!["quests.json", "scripts"], "loaded without them");
+
+        let content = set.content();
+        assert_eq!(content.dialogue("guard").unwrap().nodes[0].responses[0].next_node, None);
+
+        let (empty, errors) = ModSet::scan(&dir);
+        assert!(empty.is_empty() && errors.is_empty(), "no mods directory");
+    }
+
+    #[test]
+    fn test_compare_mods_and_versions() {
+        let record = |id: &str, version: &str| ModRecord { id: id.into(), version: version.into() };
+        let saved = vec![record("a", "1.0"), record("b", "1.0")];
+        let active = vec![record("b", "1.1"), record("c", "2.0")];
+        let mismatches = compare_mods(&saved, &active);
+        assert_eq!(
+            mismatches,
+            vec![
+                ModMismatch::Missing(record("a", "1.0")),
+                ModMismatch::VersionChanged { id: "b".into(), saved: "1.0".into(), active: "1.1".into() },
+                ModMismatch::Added(record("c", "2.0")),
+            ]
+        );
+        assert!(compare_mods(&active, &active).is_empty());
+
+        assert!(version_at_least("1.10", "1.9"));
+        assert!(version_at_least("2", "2.0.0"));
+        assert!(version_at_least("1.2.0-beta", "1.2"));
+        assert!(!version_at_least("1.2", "1.2.1"));
+    }
+}
@@ -6,7 +6,12 @@ use infinite_integration::{
     ChatMessage, ChatRequest, ChatResponse, IntegrationClient, PendingRequest, ServerCharacter,
 };
 
+use super::dialogue::DialogueResponse;
 use super::game_context::GameContext;
+use super::relationship::RelationshipTier;
+use super::requirement::{DialoguePlayer, DialogueStat, Requirement, StatCheck};
+use super::voice::SpeakLine;
+use crate::flags::WorldFlags;
 
 /// A message displayed in the dialogue UI
 #[derive(Debug, Clone)]
@@ -46,6 +51,25 @@ pub struct AiDialogueManager {
     active: Option<ActiveAiDialogue>,
     /// Stored conversation histories keyed by persistent_key (survives across dialogue sessions)
     conversation_histories: HashMap<u64, Vec<ChatMessage>>,
+    /// NPC lines received since the last `drain_speak_lines`
+    speak_lines: Vec<SpeakLine>,
+    /// Canned player lines offered beside the text box. They lock and make
+    /// stat checks like scripted responses; only the text, flag actions
+    /// and check outcome matter here.
+    pub quick_responses: Vec<DialogueResponse>,
+}
+
+/// Quick responses every AI conversation starts with
+fn default_quick_responses() -> Vec<DialogueResponse> {
+    vec![
+        DialogueResponse::new("Hello", None),
+        DialogueResponse::new("Tell me about this place", None),
+        DialogueResponse::new("What year is it?", None),
+        DialogueResponse::new("Can I ask you something personal?", None)
+            .needs(Requirement::Tier(RelationshipTier::Friend)),
+        DialogueResponse::new("Tell me what you're hiding. Now.", None)
+            .with_check(StatCheck::new(DialogueStat::Attack, 15.0, None, None)),
+    ]
 }
 
 impl AiDialogueManager {
@@ -53,6 +77,8 @@ impl AiDialogueManager {
         Self {
             active: None,
             conversation_histories: HashMap::new(),
+            speak_lines: Vec::new(),
+            quick_responses: default_quick_responses(),
         }
     }
 
@@ -152,6 +178,34 @@ impl AiDialogueManager {
         active.state = AiDialogueState::WaitingForResponse { pending, messages };
     }
 
+    /// Say a quick response, applying its flag actions. A stat check's
+    /// outcome is sent along as a stage direction so the NPC reacts to it.
+    /// Locked responses are ignored. Returns whether a check passed, if the
+    /// response made one.
+    pub fn send_quick_response(
+        &mut self,
+        index: usize,
+        flags: &mut WorldFlags,
+        player: &DialoguePlayer,
+        client: &IntegrationClient,
+    ) -> Option<bool> {
+        let response = self.quick_responses.get(index)?;
+        if !response.is_available(flags) || !response.is_unlocked(player, flags) {
+            return None;
+        }
+        for action in &response.actions {
+            action.apply(flags);
+        }
+        let passed = response.check.as_ref().map(|c| c.passes(player));
+        let text = match (&response.check, passed) {
+            (Some(check), Some(true)) => format!("{} *({} check succeeded: they back down)*", response.text, check.label()),
+            (Some(check), Some(false)) => format!("{} *({} check failed: they aren't impressed)*", response.text, check.label()),
+            _ => response.text.clone(),
+        };
+        self.send_player_message(text, client);
+        passed
+    }
+
     /// Poll for AI response. Returns true when a new message arrives.
     pub fn update(&mut self) -> bool {
         let active = match &mut self.active {
@@ -170,6 +224,13 @@ impl AiDialogueManager {
                             is_player: false,
                         });
 
+                        self.speak_lines.push(SpeakLine {
+                            npc_id: active.npc_id,
+                            speaker: active.npc_name.clone(),
+                            text: response.content.clone(),
+                            line_id: None,
+                        });
+
                         // Record in chat history
                         active.chat_history.push(ChatMessage {
                             role: "assistant".into(),
@@ -207,6 +268,11 @@ impl AiDialogueManager {
         self.active.is_some()
     }
 
+    /// Take the NPC lines received since the last call (for voice playback)
+    pub fn drain_speak_lines(&mut self) -> Vec<SpeakLine> {
+        std::mem::take(&mut self.speak_lines)
+    }
+
     /// Get the current dialogue state for UI rendering
     pub fn active_state(&self) -> Option<&AiDialogueState> {
         self.active.as_ref().map(|a| &a.state)
@@ -1,5 +1,6 @@
 //! Maps NPC roles and time periods to character archetypes and system prompts
 
+use super::identity::PersonalityTrait;
 use super::NpcRole;
 
 /// Get an archetype key for an NPC role at a given year
@@ -28,16 +29,18 @@ pub fn archetype_for(role: NpcRole, year: i64) -> &'static str {
     }
 }
 
-/// Generate a system prompt for an NPC based on their role and era
-pub fn generate_system_prompt(name: &str, role: NpcRole, year: i64) -> String {
+/// Generate a system prompt for an NPC based on their role, era and
+/// personality traits
+pub fn generate_system_prompt(name: &str, role: NpcRole, year: i64, traits: &[PersonalityTrait]) -> String {
     let archetype = archetype_for(role, year);
     let era_context = era_context(year);
     let role_personality = role_personality(role);
+    let traits = traits.iter().map(|t| t.description()).collect::<Vec<_>>().join(" ");
 
     format!(
         "You are {name}, a {archetype_desc} in a world where time travel exists.\n\
          {era_context}\n\
-         {role_personality}\n\n\
+         {role_personality} {traits}\n\n\
          RULES:\n\
          - Stay in character at all times\n\
          - Keep responses under 3 sentences\n\
@@ -110,12 +113,24 @@ mod tests {
 
     #[test]
     fn test_system_prompt_non_empty() {
-        let prompt = generate_system_prompt("Elder Morvyn", NpcRole::QuestGiver, 1200);
+        let prompt = generate_system_prompt("Elder Morvyn", NpcRole::QuestGiver, 1200, &[]);
         assert!(!prompt.is_empty());
         assert!(prompt.contains("Elder Morvyn"));
         assert!(prompt.contains("wise"));
     }
 
+    #[test]
+    fn test_system_prompt_includes_traits() {
+        let prompt = generate_system_prompt(
+            "Finn",
+            NpcRole::Villager,
+            1200,
+            &[PersonalityTrait::Gruff, PersonalityTrait::Secretive],
+        );
+        assert!(prompt.contains(PersonalityTrait::Gruff.description()));
+        assert!(prompt.contains(PersonalityTrait::Secretive.description()));
+    }
+
     #[test]
     fn test_all_roles_all_eras() {
         let roles = [NpcRole::Villager, NpcRole::Guard, NpcRole::Shopkeeper, NpcRole::QuestGiver, NpcRole::Enemy];
@@ -125,7 +140,7 @@ mod tests {
             for year in &years {
                 let archetype = archetype_for(*role, *year);
                 assert!(!archetype.is_empty());
-                let prompt = generate_system_prompt("Test", *role, *year);
+                let prompt = generate_system_prompt("Test", *role, *year, &[]);
                 assert!(!prompt.is_empty());
             }
         }
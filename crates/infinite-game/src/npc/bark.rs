@@ -0,0 +1,548 @@
+//! Ambient barks — short lines NPCs call out unprompted as the player
+//! passes: greetings, warnings, and remarks on the weather, the era and how
+//! well they know the player
+//!
+//! Lines come from a built-in set, data files (`BarkLibrary::load_json`) and
+//! lines cached from AI conversations. Each NPC waits out a cooldown between
+//! barks, only a few float at once, and of the triggers an NPC has a fitting
+//! line for, the most pressing wins.
+
+use std::collections::{HashMap, HashSet};
+
+use glam::Vec3;
+use infinite_core::DetRng;
+use infinite_world::WeatherState;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::relationship::RelationshipTier;
+use super::voice::SpeakLine;
+use super::{NpcId, NpcRole};
+use crate::balance;
+
+/// At most this many barks float at once
+const MAX_ACTIVE: usize = 3;
+/// Seconds between any two barks starting
+const GLOBAL_GAP: f32 = 1.5;
+/// Chance per second that an NPC in range remarks on something
+const REMARK_CHANCE: f32 = 0.1;
+/// An NPC that walked out past this multiple of the bark range greets
+/// the player again when they next meet
+const REGREET_RANGE_FACTOR: f32 = 1.5;
+/// Lines kept per NPC from AI conversations
+const MAX_GENERATED: usize = 4;
+/// Longest AI line kept as a bark, in characters
+const MAX_GENERATED_LEN: usize = 80;
+
+/// What prompts a bark, most pressing first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BarkTrigger {
+    /// A hostile NPC spots the player
+    Warning,
+    /// Greeting for a player the NPC knows well
+    Reputation,
+    /// The player comes into range
+    Greeting,
+    /// Remark on the current weather
+    Weather,
+    /// Remark on the times the NPC lives in
+    Era,
+}
+
+impl BarkTrigger {
+    /// Said when the player comes into range (the rest are idle remarks)
+    fn on_approach(self) -> bool {
+        matches!(self, Self::Warning | Self::Reputation | Self::Greeting)
+    }
+}
+
+/// A line an NPC may bark, and when it fits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BarkLine {
+    /// Stable id for pre-recorded audio
+    #[serde(default)]
+    pub id: Option<String>,
+    pub trigger: BarkTrigger,
+    pub text: String,
+    /// Roles that say it (empty: any)
+    #[serde(default)]
+    pub roles: Vec<NpcRole>,
+    /// Only in this weather
+    #[serde(default)]
+    pub weather: Option<WeatherState>,
+    /// Only from this year on
+    #[serde(default)]
+    pub min_year: Option<i64>,
+    /// Only up to this year
+    #[serde(default)]
+    pub max_year: Option<i64>,
+    /// Only once the NPC likes the player this much
+    #[serde(default)]
+    pub min_tier: Option<RelationshipTier>,
+}
+
+impl BarkLine {
+    pub fn new(trigger: BarkTrigger, text: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            trigger,
+            text: text.into(),
+            roles: Vec::new(),
+            weather: None,
+            min_year: None,
+            max_year: None,
+            min_tier: None,
+        }
+    }
+
+    pub fn for_roles(mut self, roles: &[NpcRole]) -> Self {
+        self.roles = roles.to_vec();
+        self
+    }
+
+    fn fits(&self, npc: &BarkCandidate, world: &BarkWorld) -> bool {
+        (self.roles.is_empty() || self.roles.contains(&npc.role))
+            && self.weather.is_none_or(|w| w == world.weather)
+            && self.min_year.is_none_or(|y| world.year >= y)
+            && self.max_year.is_none_or(|y| world.year <= y)
+            && self.min_tier.is_none_or(|t| RelationshipTier::from_affection(npc.affection) >= t)
+    }
+}
+
+/// Every line NPCs can bark
+#[derive(Debug, Clone, Default)]
+pub struct BarkLibrary {
+    lines: Vec<BarkLine>,
+    /// Short lines from AI conversations, by NPC persistent key, greeted
+    /// with in place of the stock greetings
+    generated: HashMap<u64, Vec<String>>,
+}
+
+impl BarkLibrary {
+    /// A few stock lines so NPCs have something to say without data files
+    pub fn builtin() -> Self {
+        use BarkTrigger::*;
+        Self {
+            lines: vec![
+                BarkLine::new(Greeting, "Hello there."),
+                BarkLine::new(Greeting, "Good day to you."),
+                BarkLine::new(Greeting, "Stay out of trouble.").for_roles(&[NpcRole::Guard]),
+                BarkLine::new(Greeting, "Come, have a look at my wares!").for_roles(&[NpcRole::Shopkeeper]),
+                BarkLine::new(Warning, "You there! Stop!"),
+                BarkLine::new(Warning, "Another step and you're done for."),
+                BarkLine {
+                    min_tier: Some(RelationshipTier::Friend),
+                    ..BarkLine::new(Reputation, "Good to see you again, friend.")
+                },
+                BarkLine {
+                    weather: Some(WeatherState::Rain),
+                    ..BarkLine::new(Weather, "This rain won't let up.")
+                },
+                BarkLine {
+                    weather: Some(WeatherState::Storm),
+                    ..BarkLine::new(Weather, "Best find shelter before the storm gets worse.")
+                },
+                BarkLine {
+                    weather: Some(WeatherState::Clear),
+                    ..BarkLine::new(Weather, "Fine weather today.")
+                },
+            ],
+            generated: HashMap::new(),
+        }
+    }
+
+    /// Add lines from a JSON array of `BarkLine`s, e.g.
+    /// `[{"trigger": "Weather", "text": "Looks like rain.", "weather": "Cloudy"}]`.
+    /// Returns how many were loaded.
+    pub fn load_json(&mut self, json: &str) -> Result<usize, serde_json::Error> {
+        let lines: Vec<BarkLine> = serde_json::from_str(json)?;
+        let count = lines.len();
+        self.lines.extend(lines);
+        Ok(count)
+    }
+
+    /// Keep a line an NPC said in an AI conversation to greet the player
+    /// with later. Long lines are skipped; the oldest go past the cap.
+    pub fn remember_generated(&mut self, persistent_key: u64, text: &str) {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_GENERATED_LEN {
+            return;
+        }
+        let lines = self.generated.entry(persistent_key).or_default();
+        if lines.iter().any(|l| l == text) {
+            return;
+        }
+        if lines.len() == MAX_GENERATED {
+            lines.remove(0);
+        }
+        lines.push(text.to_string());
+    }
+
+    /// Number of data and built-in lines (not counting AI ones)
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// A random fitting line for the trigger: id and text
+    fn pick(
+        &self,
+        trigger: BarkTrigger,
+        npc: &BarkCandidate,
+        world: &BarkWorld,
+        rng: &mut DetRng,
+    ) -> Option<(Option<String>, String)> {
+        if trigger == BarkTrigger::Greeting {
+            if let Some(lines) = self.generated.get(&npc.persistent_key).filter(|l| !l.is_empty()) {
+                return Some((None, lines[rng.gen_range(0..lines.len())].clone()));
+            }
+        }
+        let fitting: Vec<&BarkLine> = self
+            .lines
+            .iter()
+            .filter(|l| l.trigger == trigger && l.fits(npc, world))
+            .collect();
+        if fitting.is_empty() {
+            return None;
+        }
+        let line = fitting[rng.gen_range(0..fitting.len())];
+        Some((line.id.clone(), line.text.clone()))
+    }
+}
+
+/// World state barks can comment on
+#[derive(Debug, Clone, Copy)]
+pub struct BarkWorld {
+    pub player_pos: Vec3,
+    pub year: i64,
+    pub weather: WeatherState,
+}
+
+/// An NPC that might bark this frame
+#[derive(Debug, Clone, Copy)]
+pub struct BarkCandidate<'a> {
+    pub npc_id: NpcId,
+    pub persistent_key: u64,
+    pub speaker: &'a str,
+    pub role: NpcRole,
+    /// Hostile or provoked: warns instead of greeting
+    pub hostile: bool,
+    /// Head height, where the text floats
+    pub position: Vec3,
+    /// The NPC's affection for the player (0-100)
+    pub affection: f32,
+}
+
+/// A line floating over an NPC
+#[derive(Debug, Clone)]
+pub struct Bark {
+    pub npc_id: NpcId,
+    pub speaker: String,
+    pub text: String,
+    pub trigger: BarkTrigger,
+    pub position: Vec3,
+    age: f32,
+    duration: f32,
+}
+
+impl Bark {
+    /// Opacity: fades out over the last half second
+    pub fn alpha(&self) -> f32 {
+        ((self.duration - self.age) / 0.5).clamp(0.0, 1.0)
+    }
+}
+
+/// Picks who barks what and keeps the barks floating
+pub struct BarkSystem {
+    pub library: BarkLibrary,
+    active: Vec<Bark>,
+    /// Seconds until each NPC may bark again
+    cooldowns: HashMap<NpcId, f32>,
+    /// NPCs that had their chance to greet since coming into range
+    greeted: HashSet<NpcId>,
+    since_last: f32,
+    speak_lines: Vec<SpeakLine>,
+    rng: DetRng,
+}
+
+impl BarkSystem {
+    /// Barks from `library`, with lines picked by a generator seeded from
+    /// `seed`
+    pub fn new(library: BarkLibrary, seed: u64) -> Self {
+        Self {
+            library,
+            active: Vec::new(),
+            cooldowns: HashMap::new(),
+            greeted: HashSet::new(),
+            since_last: GLOBAL_GAP,
+            speak_lines: Vec::new(),
+            rng: DetRng::new(seed),
+        }
+    }
+
+    /// Restart line picking from `seed`, e.g. for a new world
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = DetRng::new(seed);
+    }
+
+    /// Barks currently floating
+    pub fn barks(&self) -> impl Iterator<Item = &Bark> {
+        self.active.iter()
+    }
+
+    /// Take the barks started since the last call (for voice playback)
+    pub fn drain_speak_lines(&mut self) -> Vec<SpeakLine> {
+        std::mem::take(&mut self.speak_lines)
+    }
+
+    /// Drop every bark and cooldown (e.g. when the world changes)
+    pub fn clear(&mut self) {
+        self.active.clear();
+        self.cooldowns.clear();
+        self.greeted.clear();
+        self.speak_lines.clear();
+        self.since_last = GLOBAL_GAP;
+    }
+
+    /// Age the floating barks and start at most one new one. NPCs that come
+    /// into range greet (or warn) once; the rest now and then remark on the
+    /// weather or the era. Missing NPCs' barks vanish.
+    pub fn update(&mut self, dt: f32, world: &BarkWorld, npcs: &[BarkCandidate]) {
+        self.since_last += dt;
+        self.cooldowns.retain(|_, left| {
+            *left -= dt;
+            *left > 0.0
+        });
+        self.active.retain_mut(|bark| {
+            bark.age += dt;
+            match npcs.iter().find(|n| n.npc_id == bark.npc_id) {
+                Some(npc) => bark.position = npc.position,
+                None => return false,
+            }
+            bark.age < bark.duration
+        });
+
+        let range = balance::npc::BARK_RANGE.get();
+        self.greeted.retain(|id| {
+            npcs.iter()
+                .find(|n| n.npc_id == *id)
+                .is_some_and(|n| n.position.distance(world.player_pos) <= range * REGREET_RANGE_FACTOR)
+        });
+
+        if self.active.len() >= MAX_ACTIVE || self.since_last < GLOBAL_GAP {
+            return;
+        }
+
+        let remarks = self.rng.gen::<f32>() < REMARK_CHANCE * dt;
+        // Most pressing trigger first, then the nearest NPC
+        let mut best: Option<(BarkTrigger, f32, usize, Option<String>, String)> = None;
+        for (i, npc) in npcs.iter().enumerate() {
+            let distance = npc.position.distance(world.player_pos);
+            if distance > range
+                || self.cooldowns.contains_key(&npc.npc_id)
+                || self.active.iter().any(|b| b.npc_id == npc.npc_id)
+            {
+                continue;
+            }
+            let approaching = !self.greeted.contains(&npc.npc_id);
+            let approach: &[BarkTrigger] = match (approaching, npc.hostile) {
+                (false, _) => &[],
+                (true, true) => &[BarkTrigger::Warning],
+                (true, false) => &[BarkTrigger::Reputation, BarkTrigger::Greeting],
+            };
+            let remark: &[BarkTrigger] = if remarks && !npc.hostile {
+                &[BarkTrigger::Weather, BarkTrigger::Era]
+            } else {
+                &[]
+            };
+            let picked = approach
+                .iter()
+                .chain(remark)
+                .find_map(|&t| self.library.pick(t, npc, world, &mut self.rng).map(|line| (t, line)));
+            if approaching && picked.as_ref().is_none_or(|(t, _)| !t.on_approach()) {
+                // Nothing to say on approach; don't check again until they return
+                self.greeted.insert(npc.npc_id);
+            }
+            let Some((trigger, (id, text))) = picked else {
+                continue;
+            };
+            let better = best.as_ref().is_none_or(|(best_trigger, best_distance, ..)| {
+                (trigger as u8, distance) < (*best_trigger as u8, *best_distance)
+            });
+            if better {
+                best = Some((trigger, distance, i, id, text));
+            }
+        }
+
+        let Some((trigger, _, i, line_id, text)) = best else {
+            return;
+        };
+        let npc = &npcs[i];
+        if trigger.on_approach() {
+            self.greeted.insert(npc.npc_id);
+        }
+        self.cooldowns.insert(npc.npc_id, balance::npc::BARK_COOLDOWN.get());
+        self.since_last = 0.0;
+        self.speak_lines.push(SpeakLine {
+            npc_id: npc.npc_id,
+            speaker: npc.speaker.to_string(),
+            text: text.clone(),
+            line_id,
+        });
+        self.active.push(Bark {
+            npc_id: npc.npc_id,
+            speaker: npc.speaker.to_string(),
+            duration: 2.0 + text.chars().count() as f32 * 0.05,
+            text,
+            trigger,
+            position: npc.position,
+            age: 0.0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> BarkWorld {
+        BarkWorld {
+            player_pos: Vec3::ZERO,
+            year: 1200,
+            weather: WeatherState::Clear,
+        }
+    }
+
+    fn npc(id: u64, position: Vec3) -> BarkCandidate<'static> {
+        BarkCandidate {
+            npc_id: NpcId(id),
+            persistent_key: id,
+            speaker: "Ada",
+            role: NpcRole::Villager,
+            hostile: false,
+            position,
+            affection: 0.0,
+        }
+    }
+
+    fn system(lines: Vec<BarkLine>) -> BarkSystem {
+        let library = BarkLibrary {
+            lines,
+            generated: HashMap::new(),
+        };
+        BarkSystem::new(library, 7)
+    }
+
+    #[test]
+    fn test_greets_once_on_approach() {
+        let mut barks = system(vec![BarkLine::new(BarkTrigger::Greeting, "Hello")]);
+        let far = [npc(1, Vec3::new(0.0, 0.0, 50.0))];
+        barks.update(0.1, &world(), &far);
+        assert_eq!(barks.barks().count(), 0);
+
+        let near = [npc(1, Vec3::new(0.0, 0.0, 3.0))];
+        barks.update(0.1, &world(), &near);
+        let lines = barks.drain_speak_lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].text, "Hello");
+
+        // Long after the cooldown, still no second greeting while nearby
+        for _ in 0..100 {
+            barks.update(1.0, &world(), &near);
+        }
+        assert!(barks.drain_speak_lines().is_empty());
+    }
+
+    #[test]
+    fn test_warning_outranks_greeting_and_one_bark_starts_at_a_time() {
+        let mut barks = system(vec![
+            BarkLine::new(BarkTrigger::Greeting, "Hello"),
+            BarkLine::new(BarkTrigger::Warning, "Halt!"),
+        ]);
+        let mut enemy = npc(2, Vec3::new(0.0, 0.0, 6.0));
+        enemy.hostile = true;
+        let npcs = [npc(1, Vec3::new(0.0, 0.0, 2.0)), enemy];
+
+        barks.update(0.1, &world(), &npcs);
+        let lines = barks.drain_speak_lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].text, "Halt!");
+
+        // The villager waits out the gap between barks
+        barks.update(0.1, &world(), &npcs);
+        assert!(barks.drain_speak_lines().is_empty());
+        barks.update(GLOBAL_GAP, &world(), &npcs);
+        assert_eq!(barks.drain_speak_lines()[0].text, "Hello");
+    }
+
+    #[test]
+    fn test_lines_fit_weather_year_and_tier() {
+        let rain = BarkLine {
+            weather: Some(WeatherState::Rain),
+            ..BarkLine::new(BarkTrigger::Weather, "Wet")
+        };
+        let modern = BarkLine {
+            min_year: Some(1900),
+            ..BarkLine::new(BarkTrigger::Era, "Modern")
+        };
+        let friend = BarkLine {
+            min_tier: Some(RelationshipTier::Friend),
+            ..BarkLine::new(BarkTrigger::Reputation, "Friend")
+        };
+        let villager = npc(1, Vec3::ZERO);
+        let mut liked = villager;
+        liked.affection = 50.0;
+        let world = world();
+
+        assert!(!rain.fits(&villager, &world));
+        assert!(rain.fits(&villager, &BarkWorld { weather: WeatherState::Rain, ..world }));
+        assert!(!modern.fits(&villager, &world));
+        assert!(modern.fits(&villager, &BarkWorld { year: 2020, ..world }));
+        assert!(!friend.fits(&villager, &world));
+        assert!(friend.fits(&liked, &world));
+        let guards_only = BarkLine::new(BarkTrigger::Greeting, "Move along").for_roles(&[NpcRole::Guard]);
+        assert!(!guards_only.fits(&villager, &world));
+    }
+
+    #[test]
+    fn test_remarks_wait_out_the_cooldown() {
+        let mut barks = system(vec![BarkLine::new(BarkTrigger::Weather, "Nice day")]);
+        let near = [npc(1, Vec3::new(0.0, 0.0, 3.0))];
+        // A long frame makes the remark roll certain
+        barks.update(20.0, &world(), &near);
+        assert_eq!(barks.drain_speak_lines()[0].text, "Nice day");
+        barks.update(balance::npc::BARK_COOLDOWN.get() - 1.0, &world(), &near);
+        assert!(barks.drain_speak_lines().is_empty());
+        barks.update(20.0, &world(), &near);
+        assert_eq!(barks.drain_speak_lines().len(), 1);
+    }
+
+    #[test]
+    fn test_generated_lines_replace_stock_greetings() {
+        let mut barks = system(vec![BarkLine::new(BarkTrigger::Greeting, "Hello")]);
+        barks.library.remember_generated(1, "  Back again, are we?  ");
+        barks.library.remember_generated(1, &"far too long ".repeat(10));
+        barks.update(0.1, &world(), &[npc(1, Vec3::new(0.0, 0.0, 3.0))]);
+        assert_eq!(barks.drain_speak_lines()[0].text, "Back again, are we?");
+    }
+
+    #[test]
+    fn test_barks_follow_and_vanish_with_their_npc() {
+        let mut barks = system(vec![BarkLine::new(BarkTrigger::Greeting, "Hello")]);
+        barks.update(0.1, &world(), &[npc(1, Vec3::new(0.0, 0.0, 3.0))]);
+        barks.update(0.1, &world(), &[npc(1, Vec3::new(1.0, 0.0, 3.0))]);
+        assert_eq!(barks.barks().next().unwrap().position.x, 1.0);
+        barks.update(0.1, &world(), &[]);
+        assert_eq!(barks.barks().count(), 0);
+    }
+
+    #[test]
+    fn test_shipped_bark_file_parses() {
+        let mut library = BarkLibrary::default();
+        let count = library.load_json(include_str!("../../../../assets/barks.json")).unwrap();
+        assert!(count > 0);
+    }
+}
@@ -0,0 +1,245 @@
+//! Bestiary: enemy discovery tracking
+//!
+//! The first kill of each enemy archetype (keyed by name, e.g. "Bandit" or
+//! "Dungeon Warden") unlocks its bestiary entry. Entries record the enemy's
+//! stats, elemental affinity and weapon weakness, the years and biomes it
+//! was fought in, and reveal the enemy drop table row by row as the kill
+//! count grows.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::combat::CombatStats;
+use crate::combat::element::Element;
+use crate::combat::weapon::WeaponType;
+
+/// One row of the enemy drop table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropTableEntry {
+    pub name: &'static str,
+    /// Rough odds, as shown in the bestiary
+    pub chance: &'static str,
+    /// Kills of an archetype needed before this row is revealed
+    pub reveal_at: u32,
+}
+
+/// What defeated enemies can drop, in reveal order
+pub const DROP_TABLE: &[DropTableEntry] = &[
+    DropTableEntry { name: "Gold", chance: "Always", reveal_at: 1 },
+    DropTableEntry { name: "Worn Treasure Map", chance: "Uncommon", reveal_at: 3 },
+    DropTableEntry { name: "Detailed Treasure Map", chance: "Rare", reveal_at: 8 },
+    DropTableEntry { name: "Ancient Treasure Map", chance: "Very rare (elites)", reveal_at: 15 },
+];
+
+/// Combat profile of an enemy archetype
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemyProfile {
+    pub max_hp: f32,
+    pub attack: f32,
+    pub defense: f32,
+    pub armor: f32,
+    pub element: Element,
+    pub weapon_weakness: Option<WeaponType>,
+}
+
+impl EnemyProfile {
+    pub fn from_stats(stats: &CombatStats) -> Self {
+        Self {
+            max_hp: stats.max_hp,
+            attack: stats.attack,
+            defense: stats.defense,
+            armor: stats.armor,
+            element: stats.element,
+            weapon_weakness: stats.weapon_weakness,
+        }
+    }
+
+    /// Elements that deal bonus damage to this enemy
+    pub fn weak_to(&self) -> Vec<Element> {
+        Element::all().iter().copied().filter(|e| e.is_strong_against(self.element)).collect()
+    }
+
+    /// Elements that deal reduced damage to this enemy
+    pub fn resists(&self) -> Vec<Element> {
+        Element::all().iter().copied().filter(|e| e.is_weak_against(self.element)).collect()
+    }
+}
+
+/// A defeated hostile NPC, as reported by the NPC manager
+#[derive(Debug, Clone, PartialEq)]
+pub struct KillRecord {
+    pub name: String,
+    pub profile: EnemyProfile,
+    pub elite: bool,
+}
+
+/// Everything known about one enemy archetype
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestiaryEntry {
+    pub name: String,
+    pub profile: EnemyProfile,
+    pub kills: u32,
+    #[serde(default)]
+    pub elite_kills: u32,
+    /// Years the archetype was defeated in
+    #[serde(default)]
+    pub years: BTreeSet<i64>,
+    /// Biomes the archetype was defeated in
+    #[serde(default)]
+    pub biomes: BTreeSet<String>,
+}
+
+impl BestiaryEntry {
+    /// Drop table rows revealed so far
+    pub fn revealed_drops(&self) -> impl Iterator<Item = &'static DropTableEntry> + '_ {
+        DROP_TABLE.iter().filter(|d| self.kills >= d.reveal_at)
+    }
+
+    /// Kills needed for the next drop table row (None when all are revealed)
+    pub fn next_reveal(&self) -> Option<u32> {
+        DROP_TABLE.iter().map(|d| d.reveal_at).find(|&at| at > self.kills)
+    }
+}
+
+/// What a recorded kill taught the bestiary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BestiaryUpdate {
+    /// First kill: a new entry
+    Discovered,
+    /// This kill revealed a drop table row
+    DropRevealed(&'static str),
+    /// Nothing new
+    Known,
+}
+
+impl BestiaryUpdate {
+    /// Notification text for this update
+    pub fn message(self, name: &str) -> Option<String> {
+        match self {
+            Self::Discovered => Some(format!("New bestiary entry: {}", name)),
+            Self::DropRevealed(drop) => Some(format!("Bestiary: {} can drop {}", name, drop)),
+            Self::Known => None,
+        }
+    }
+}
+
+/// Discovered enemy archetypes, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bestiary {
+    entries: BTreeMap<String, BestiaryEntry>,
+}
+
+impl Bestiary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a kill in `year` and `biome`
+    pub fn record_kill(&mut self, kill: &KillRecord, year: i64, biome: &str) -> BestiaryUpdate {
+        let discovered = !self.entries.contains_key(&kill.name);
+        let entry = self.entries.entry(kill.name.clone()).or_insert_with(|| BestiaryEntry {
+            name: kill.name.clone(),
+            profile: kill.profile.clone(),
+            kills: 0,
+            elite_kills: 0,
+            years: BTreeSet::new(),
+            biomes: BTreeSet::new(),
+        });
+        // Elites have boosted stats; prefer a regular kill's profile once one is seen
+        if !kill.elite && entry.elite_kills == entry.kills {
+            entry.profile = kill.profile.clone();
+        }
+        entry.kills += 1;
+        if kill.elite {
+            entry.elite_kills += 1;
+        }
+        entry.years.insert(year);
+        entry.biomes.insert(biome.to_string());
+
+        if discovered {
+            BestiaryUpdate::Discovered
+        } else if let Some(drop) = DROP_TABLE.iter().find(|d| d.reveal_at == entry.kills) {
+            BestiaryUpdate::DropRevealed(drop.name)
+        } else {
+            BestiaryUpdate::Known
+        }
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&BestiaryEntry> {
+        self.entries.get(name)
+    }
+
+    /// Entries sorted by name
+    pub fn entries(&self) -> impl Iterator<Item = &BestiaryEntry> {
+        self.entries.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kill(name: &str, elite: bool) -> KillRecord {
+        let mut stats = CombatStats::default_enemy();
+        if elite {
+            stats.max_hp *= 2.0;
+        }
+        KillRecord { name: name.to_string(), profile: EnemyProfile::from_stats(&stats), elite }
+    }
+
+    #[test]
+    fn test_first_kill_discovers_entry() {
+        let mut bestiary = Bestiary::new();
+        assert!(bestiary.is_empty());
+        assert_eq!(bestiary.record_kill(&kill("Bandit", false), 2024, "Forest"), BestiaryUpdate::Discovered);
+        assert_eq!(bestiary.record_kill(&kill("Bandit", false), 1200, "Forest"), BestiaryUpdate::Known);
+        assert_eq!(bestiary.record_kill(&kill("Raider", false), 2024, "Highland"), BestiaryUpdate::Discovered);
+
+        let bandit = bestiary.entry("Bandit").unwrap();
+        assert_eq!(bandit.kills, 2);
+        assert_eq!(bandit.years.iter().copied().collect::<Vec<_>>(), vec![1200, 2024]);
+        assert_eq!(bandit.biomes.len(), 1);
+        let names: Vec<&str> = bestiary.entries().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Bandit", "Raider"]);
+    }
+
+    #[test]
+    fn test_drop_table_reveals_progressively() {
+        let mut bestiary = Bestiary::new();
+        let mut revealed = Vec::new();
+        for _ in 0..DROP_TABLE.last().unwrap().reveal_at {
+            if let BestiaryUpdate::DropRevealed(drop) = bestiary.record_kill(&kill("Thug", false), 2024, "Grassland") {
+                revealed.push(drop);
+            }
+        }
+        // The first row comes with the discovery itself
+        assert_eq!(revealed, DROP_TABLE[1..].iter().map(|d| d.name).collect::<Vec<_>>());
+        let entry = bestiary.entry("Thug").unwrap();
+        assert_eq!(entry.revealed_drops().count(), DROP_TABLE.len());
+        assert_eq!(entry.next_reveal(), None);
+    }
+
+    #[test]
+    fn test_regular_kill_replaces_elite_profile() {
+        let mut bestiary = Bestiary::new();
+        bestiary.record_kill(&kill("Brute", true), 2024, "Forest");
+        let elite_hp = bestiary.entry("Brute").unwrap().profile.max_hp;
+        bestiary.record_kill(&kill("Brute", false), 2024, "Forest");
+        let entry = bestiary.entry("Brute").unwrap();
+        assert!(entry.profile.max_hp < elite_hp);
+        assert_eq!(entry.elite_kills, 1);
+
+        // Later elite kills keep the regular profile
+        bestiary.record_kill(&kill("Brute", true), 2024, "Forest");
+        assert!(bestiary.entry("Brute").unwrap().profile.max_hp < elite_hp);
+    }
+}
@@ -78,6 +78,8 @@ mod tests {
             appearance: None,
             project_id: String::new(),
             user_id: String::new(),
+            client_id: None,
+            snapshot: None,
         };
         cache.set_ready(1, character);
         assert!(matches!(cache.get(&1), Some(CharacterCacheEntry::Ready(_))));
@@ -1,15 +1,26 @@
 //! NPC combat system — stats, damage calculation, and aggro
 
+use crate::alchemy::{CoatingKind, WeaponCoating, COATING_CHARGES};
+use crate::balance;
+use crate::combat::armor::{mitigate, DURABILITY_LOSS_PER_HIT};
+use crate::combat::curse::Curses;
+use crate::combat::durability::{SHIELD_WEAR_PER_BLOCK, WEAPON_WEAR_PER_HIT};
 use crate::combat::damage::{AttackType, calculate_combat_damage};
 use crate::combat::element::Element;
+use crate::combat::encumbrance::{carry_capacity, Encumbrance};
 use crate::combat::equipment::EquipmentSet;
 use crate::combat::inventory::Inventory;
+use crate::combat::moveset::{ComboHit, ComboState, MovesetLibrary};
 use crate::combat::rune::{Rune, RuneComposer};
 use crate::combat::skill::SkillSlot;
-use crate::combat::status::StatusManager;
+use crate::combat::status::{StatusEffectType, StatusManager};
 use crate::combat::weapon::WeaponType;
-use crate::player::stats::{CharacterStats, PlayerProgression, StatGrowth};
-use super::NpcRole;
+use crate::cooking::FoodBuffs;
+use crate::shrine::XP_BOON_BONUS;
+use crate::player::stats::{CharacterStats, ClassSetup, PlayerProgression, StatGrowth};
+use std::collections::HashMap;
+
+use super::{NpcId, NpcRole};
 use serde::{Deserialize, Serialize};
 
 /// Basic combat statistics for an NPC
@@ -29,6 +40,12 @@ pub struct CombatStats {
     pub element: Element,
     /// Weapon type this NPC is weak against
     pub weapon_weakness: Option<WeaponType>,
+    /// Threat generated by each attacker (drives target selection)
+    pub threat: ThreatTable,
+    /// Stagger resistance worn down by heavy hits
+    pub poise: Poise,
+    /// Armor reducing physical (and partly elemental) hits by a percentage
+    pub armor: f32,
 }
 
 impl CombatStats {
@@ -47,6 +64,9 @@ impl CombatStats {
             attack_timer: 0.0,
             element: Element::Physical,
             weapon_weakness: None,
+            threat: ThreatTable::default(),
+            poise: Poise::new(40.0),
+            armor: 5.0,
         }
     }
 
@@ -72,6 +92,9 @@ impl CombatStats {
             attack_timer: 0.0,
             element,
             weapon_weakness: None,
+            threat: ThreatTable::default(),
+            poise: Poise::new(200.0),
+            armor: 30.0,
         }
     }
 
@@ -101,6 +124,9 @@ impl CombatStats {
             attack_timer: 0.0,
             element: Element::Physical,
             weapon_weakness: None,
+            threat: ThreatTable::default(),
+            poise: Poise::new(60.0),
+            armor: 20.0,
         }
     }
 
@@ -119,6 +145,9 @@ impl CombatStats {
             attack_timer: 0.0,
             element: Element::Physical,
             weapon_weakness: None,
+            threat: ThreatTable::default(),
+            poise: Poise::new(15.0),
+            armor: 0.0,
         }
     }
 
@@ -137,6 +166,9 @@ impl CombatStats {
             attack_timer: 0.0,
             element: Element::Physical,
             weapon_weakness: None,
+            threat: ThreatTable::default(),
+            poise: Poise::new(20.0),
+            armor: 0.0,
         }
     }
 
@@ -155,6 +187,9 @@ impl CombatStats {
             attack_timer: 0.0,
             element: Element::Physical,
             weapon_weakness: None,
+            threat: ThreatTable::default(),
+            poise: Poise::new(40.0),
+            armor: 5.0,
         }
     }
 
@@ -177,7 +212,11 @@ impl CombatStats {
     }
 
     /// Update attack cooldown timer. Returns true if an attack can fire.
+    /// Staggered NPCs can't attack.
     pub fn update_attack(&mut self, delta: f32) -> bool {
+        if self.poise.is_staggered() {
+            return false;
+        }
         self.attack_timer -= delta;
         if self.attack_timer <= 0.0 {
             self.attack_timer = self.attack_cooldown;
@@ -188,6 +227,183 @@ impl CombatStats {
     }
 }
 
+/// Threat gained per second at point-blank range inside the aggro radius
+pub const PROXIMITY_THREAT_RATE: f32 = 5.0;
+/// Threat gained per point of damage dealt
+pub const DAMAGE_THREAT_PER_POINT: f32 = 1.0;
+/// Minimum threat before an attacker can become the target
+pub const AGGRO_THRESHOLD: f32 = 1.0;
+/// A challenger must exceed the current target's threat by this ratio to
+/// pull aggro (hysteresis — prevents flip-flopping between close values)
+pub const TARGET_SWITCH_RATIO: f32 = 1.1;
+
+/// Seconds without poise damage before poise starts recovering
+const POISE_RECOVERY_DELAY: f32 = 2.0;
+/// Fraction of max poise recovered per second
+const POISE_RECOVERY_RATE: f32 = 0.5;
+
+/// Stagger resistance: poise damage from hits wears it down, and at zero
+/// the NPC is staggered (can't act) until it recovers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poise {
+    pub current: f32,
+    pub max: f32,
+    since_hit: f32,
+    stagger_timer: f32,
+}
+
+impl Poise {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            since_hit: 0.0,
+            stagger_timer: 0.0,
+        }
+    }
+
+    /// Apply poise damage. Returns true if this broke the poise.
+    pub fn damage(&mut self, amount: f32) -> bool {
+        if self.is_staggered() || self.max <= 0.0 {
+            return false;
+        }
+        self.since_hit = 0.0;
+        self.current = (self.current - amount.max(0.0)).max(0.0);
+        if self.current <= 0.0 {
+            self.stagger_timer = balance::combat::STAGGER_DURATION.get();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_staggered(&self) -> bool {
+        self.stagger_timer > 0.0
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.stagger_timer > 0.0 {
+            self.stagger_timer = (self.stagger_timer - delta).max(0.0);
+            if self.stagger_timer <= 0.0 {
+                self.current = self.max;
+            }
+            return;
+        }
+        self.since_hit += delta;
+        if self.since_hit >= POISE_RECOVERY_DELAY {
+            self.current = (self.current + self.max * POISE_RECOVERY_RATE * delta).min(self.max);
+        }
+    }
+}
+
+/// Something that can generate threat on an NPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThreatSource {
+    Player,
+    /// Companions or other NPCs
+    Npc(NpcId),
+}
+
+/// Per-attacker threat for one NPC
+#[derive(Debug, Clone, Default)]
+pub struct ThreatTable {
+    threat: HashMap<ThreatSource, f32>,
+    target: Option<ThreatSource>,
+}
+
+impl ThreatTable {
+    /// Add threat from an attacker (damage, healing, abilities)
+    pub fn add(&mut self, source: ThreatSource, amount: f32) {
+        *self.threat.entry(source).or_default() += amount.max(0.0);
+        self.select_target();
+    }
+
+    /// Taunt: jump to the top of the table plus `bonus` and take the target
+    pub fn taunt(&mut self, source: ThreatSource, bonus: f32) {
+        let top = self.threat.values().copied().fold(0.0, f32::max);
+        let entry = self.threat.entry(source).or_default();
+        *entry = entry.max(top) + bonus.max(AGGRO_THRESHOLD);
+        self.target = Some(source);
+    }
+
+    pub fn threat_of(&self, source: ThreatSource) -> f32 {
+        self.threat.get(&source).copied().unwrap_or(0.0)
+    }
+
+    /// Current target (highest threat, with hysteresis)
+    pub fn target(&self) -> Option<ThreatSource> {
+        self.target
+    }
+
+    /// Sources currently on the table
+    pub fn sources(&self) -> impl Iterator<Item = ThreatSource> + '_ {
+        self.threat.keys().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.threat.clear();
+        self.target = None;
+    }
+
+    /// Build proximity threat and decay threat from attackers out of range.
+    ///
+    /// `distance_to` returns the distance to a source, or None if it no
+    /// longer exists. Sources in `nearby` build proximity threat even when
+    /// not yet on the table (e.g. the player for hostile NPCs).
+    pub fn update(
+        &mut self,
+        delta: f32,
+        nearby: &[ThreatSource],
+        distance_to: impl Fn(ThreatSource) -> Option<f32>,
+        aggro_radius: f32,
+        de_aggro_radius: f32,
+    ) {
+        for source in nearby {
+            if let Some(distance) = distance_to(*source) {
+                if distance <= aggro_radius && aggro_radius > 0.0 {
+                    let closeness = 1.0 - distance / aggro_radius;
+                    *self.threat.entry(*source).or_default() +=
+                        PROXIMITY_THREAT_RATE * (0.2 + 0.8 * closeness) * delta;
+                }
+            }
+        }
+
+        let decay = (1.0 - balance::npc::THREAT_DECAY.get() * delta).max(0.0);
+        self.threat.retain(|source, threat| match distance_to(*source) {
+            Some(distance) if distance <= de_aggro_radius => true,
+            Some(_) => {
+                *threat *= decay;
+                *threat >= 0.1
+            }
+            None => false,
+        });
+
+        self.select_target();
+    }
+
+    fn select_target(&mut self) {
+        let best = self
+            .threat
+            .iter()
+            .filter(|(_, t)| **t >= AGGRO_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(s, t)| (*s, *t));
+
+        let current = self.target.and_then(|s| self.threat.get(&s).map(|t| (s, *t)));
+        self.target = match (current, best) {
+            (_, None) => None,
+            (None, Some((s, _))) => Some(s),
+            (Some((cur, cur_t)), Some((s, t))) => {
+                if s != cur && t > cur_t * TARGET_SWITCH_RATIO {
+                    Some(s)
+                } else {
+                    Some(cur)
+                }
+            }
+        };
+    }
+}
+
 /// Player combat state with full stats, progression, and attack mechanics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerCombatState {
@@ -230,18 +446,21 @@ pub struct PlayerCombatState {
     /// Current attack type being performed (runtime only)
     #[serde(skip)]
     pub active_attack_type: Option<AttackType>,
-    /// Heavy attack windup timer (runtime only)
+    /// Light/heavy combo state machine (runtime only)
     #[serde(skip)]
-    pub heavy_attack_timer: f32,
+    pub combo: ComboState,
+    /// Combo chains per weapon type (runtime only)
+    #[serde(skip)]
+    pub movesets: MovesetLibrary,
+    /// Combo hit landed since the last `take_combo_hit` (runtime only)
+    #[serde(skip)]
+    pending_hit: Option<ComboHit>,
     /// Player inventory
     #[serde(default)]
     pub inventory: Inventory,
     /// Player gold currency
     #[serde(default)]
     pub gold: u64,
-    /// Dodge cooldown duration in seconds
-    #[serde(skip)]
-    pub dodge_cooldown: f32,
     /// Remaining dodge cooldown timer
     #[serde(skip)]
     pub dodge_cooldown_timer: f32,
@@ -251,15 +470,45 @@ pub struct PlayerCombatState {
     /// Remaining dodge duration timer
     #[serde(skip)]
     pub dodge_timer: f32,
-    /// Dodge duration in seconds
+    /// Whether the player is raising a shield
     #[serde(skip)]
-    pub dodge_duration: f32,
+    pub is_blocking: bool,
+    /// Seconds the shield has been up
+    #[serde(skip)]
+    guard_time: f32,
+    /// Damage absorbed by the shield on the last hit taken
+    #[serde(skip)]
+    pub last_blocked_amount: f32,
+    /// Whether the last hit taken was blocked within [`balance::combat::PARRY_WINDOW`] of
+    /// raising the shield
+    #[serde(skip)]
+    pub last_hit_parried: bool,
+    /// Gear that wore out since the last `take_worn_out` (runtime only)
+    #[serde(skip)]
+    worn_out: Vec<String>,
+    /// Whether gear wears with use; off is for players who'd rather not
+    /// bother with repairs (runtime only, from settings)
+    #[serde(skip, default = "default_true")]
+    durability_enabled: bool,
+    /// Buffs from meals eaten (runtime only)
+    #[serde(skip)]
+    pub food: FoodBuffs,
+    /// Coating on the main-hand weapon, spent a charge per hit
+    #[serde(default)]
+    pub coating: Option<WeaponCoating>,
+    /// Curses, kept through death until cleansed
+    #[serde(default)]
+    pub curses: Curses,
 }
 
 fn default_skill_slots() -> Vec<SkillSlot> {
     vec![SkillSlot::empty(); 4]
 }
 
+fn default_true() -> bool {
+    true
+}
+
 impl PlayerCombatState {
     /// Create new player combat state with default stats
     pub fn new() -> Self {
@@ -278,14 +527,23 @@ impl PlayerCombatState {
             rune_composer: RuneComposer::default(),
             status_manager: StatusManager::new(),
             active_attack_type: None,
-            heavy_attack_timer: 0.0,
+            combo: ComboState::default(),
+            movesets: MovesetLibrary::builtin(),
+            pending_hit: None,
             inventory: Inventory::new(),
             gold: 0,
-            dodge_cooldown: 1.5,
             dodge_cooldown_timer: 0.0,
             is_dodging: false,
             dodge_timer: 0.0,
-            dodge_duration: 0.3,
+            is_blocking: false,
+            guard_time: 0.0,
+            last_blocked_amount: 0.0,
+            last_hit_parried: false,
+            worn_out: Vec::new(),
+            durability_enabled: true,
+            food: FoodBuffs::new(),
+            coating: None,
+            curses: Curses::new(),
         }
     }
 
@@ -306,14 +564,23 @@ impl PlayerCombatState {
             rune_composer: RuneComposer::default(),
             status_manager: StatusManager::new(),
             active_attack_type: None,
-            heavy_attack_timer: 0.0,
+            combo: ComboState::default(),
+            movesets: MovesetLibrary::builtin(),
+            pending_hit: None,
             inventory: Inventory::new(),
             gold: 0,
-            dodge_cooldown: 1.5,
             dodge_cooldown_timer: 0.0,
             is_dodging: false,
             dodge_timer: 0.0,
-            dodge_duration: 0.3,
+            is_blocking: false,
+            guard_time: 0.0,
+            last_blocked_amount: 0.0,
+            last_hit_parried: false,
+            worn_out: Vec::new(),
+            durability_enabled: true,
+            food: FoodBuffs::new(),
+            coating: None,
+            curses: Curses::new(),
         }
     }
 
@@ -327,23 +594,60 @@ impl PlayerCombatState {
         self.stats.current_hp
     }
 
-    /// Max HP (for backward compatibility)
+    /// Max HP, less what curses take away
     pub fn max_hp(&self) -> f32 {
-        self.stats.max_hp
+        self.stats.max_hp + self.curses.modifiers(self.stats.max_hp).max_hp
     }
 
-    /// Take damage (respects invincibility frames)
-    /// Returns the actual damage taken (0 if invincible)
+    /// Take physical damage (respects invincibility frames, shield blocks and armor)
+    /// Returns the actual damage taken (0 if invincible or fully blocked)
     pub fn take_damage(&mut self, damage: f32) -> f32 {
+        self.take_hit(damage, Element::Physical)
+    }
+
+    /// Apply a hit: shield block, then armor and resistance for the damage's
+    /// element, then flat defense. Equipped armor loses durability.
+    fn take_hit(&mut self, damage: f32, element: Element) -> f32 {
         if self.invincibility_timer > 0.0 {
             return 0.0;
         }
 
-        let actual = (damage - self.stats.defense * 0.5).max(1.0);
+        self.last_blocked_amount = 0.0;
+        self.last_hit_parried = false;
+        let mut damage = damage;
+        if self.is_blocking {
+            if let Some(shield) = self.equipment.shield() {
+                self.last_blocked_amount = damage.min(shield.effective_block());
+                self.last_hit_parried = self.guard_time <= balance::combat::PARRY_WINDOW.get();
+                damage -= self.last_blocked_amount;
+                if self.durability_enabled {
+                    self.worn_out.extend(self.equipment.wear_shield(SHIELD_WEAR_PER_BLOCK));
+                }
+                if damage <= 0.0 {
+                    return 0.0;
+                }
+            }
+        }
+
+        let mut resistance = self
+            .equipment
+            .total_modifiers()
+            .combined(&self.status_manager.combined_modifiers())
+            .elemental_resistance[element.index()];
+        // A warm meal keeps out the cold
+        if element == Element::Water {
+            resistance += self.food.cold_resistance();
+        }
+        let mitigated = mitigate(damage, element, self.equipment.total_armor(), resistance);
+        let actual = (mitigated - self.stats.defense * 0.5).max(1.0);
         self.stats.current_hp = (self.stats.current_hp - actual).max(0.0);
         self.damage_flash_timer = 0.3;
         self.last_damage_amount = actual;
         self.invincibility_timer = 0.5; // Half second of i-frames
+        if self.durability_enabled {
+            let worn_out = self.equipment.wear_armor(DURABILITY_LOSS_PER_HIT);
+            self.worn_out.extend(worn_out);
+        }
 
         actual
     }
@@ -357,7 +661,8 @@ impl PlayerCombatState {
     pub fn effective_stats(&self) -> CharacterStats {
         let equip_mods = self.equipment.total_modifiers();
         let status_mods = self.status_manager.combined_modifiers();
-        let combined = equip_mods.combined(&status_mods);
+        let curse_mods = self.curses.modifiers(self.stats.max_hp);
+        let combined = equip_mods.combined(&status_mods).combined(&curse_mods);
         self.stats.effective_stats(&combined)
     }
 
@@ -371,71 +676,122 @@ impl PlayerCombatState {
         false
     }
 
-    /// Try to start a light attack. Returns true if started.
-    pub fn try_light_attack(&mut self) -> bool {
-        if self.status_manager.are_attacks_prevented() {
+    /// Press light or heavy attack. The press is buffered and continues the
+    /// equipped weapon's combo chain when timing allows. Returns false if
+    /// attacks are prevented (e.g. stunned).
+    pub fn queue_attack(&mut self, attack_type: AttackType) -> bool {
+        if self.is_blocking || self.status_manager.are_attacks_prevented() {
             return false;
         }
-        if self.attack_timer <= 0.0 && !self.is_attacking {
-            self.is_attacking = true;
-            self.active_attack_type = Some(AttackType::Light);
-            // Apply weapon speed to cooldown
-            let weapon_cd = self.equipment.main_weapon_type()
-                .map(|wt| wt.light_attack_cooldown())
-                .unwrap_or(AttackType::Light.base_cooldown());
-            self.attack_cooldown = weapon_cd;
-            self.attack_timer = weapon_cd;
-            return true;
-        }
-        false
+        self.combo.press(attack_type);
+        // Attacking gives the player away
+        self.status_manager.remove(StatusEffectType::Invisible);
+        true
     }
 
-    /// Try to start a heavy attack. Returns true if started.
-    pub fn try_heavy_attack(&mut self) -> bool {
-        if self.status_manager.are_attacks_prevented() {
-            return false;
+    /// Raise or lower the off-hand shield. Only works with a shield equipped
+    /// and not while dodging or stunned; raising it cancels the combo.
+    pub fn set_blocking(&mut self, held: bool) {
+        let can_block = self.equipment.shield().is_some()
+            && !self.is_dodging
+            && !self.status_manager.are_attacks_prevented();
+        let blocking = held && can_block;
+        if blocking && !self.is_blocking {
+            self.combo.cancel();
+            self.pending_hit = None;
+            self.guard_time = 0.0;
         }
-        if self.attack_timer <= 0.0 && !self.is_attacking {
-            self.is_attacking = true;
-            self.active_attack_type = Some(AttackType::Heavy);
-            let weapon_cd = self.equipment.main_weapon_type()
-                .map(|wt| wt.heavy_attack_cooldown())
-                .unwrap_or(AttackType::Heavy.base_cooldown());
-            self.attack_cooldown = weapon_cd;
-            self.attack_timer = weapon_cd;
-            self.heavy_attack_timer = AttackType::Heavy.windup();
-            return true;
-        }
-        false
+        self.is_blocking = blocking;
     }
 
-    /// Calculate full damage against a target using the damage pipeline
+    /// Take the combo hit that landed this frame, if any
+    pub fn take_combo_hit(&mut self) -> Option<ComboHit> {
+        self.pending_hit.take()
+    }
+
+    /// Calculate full damage against a target using the damage pipeline.
+    /// `crit_roll` is a uniform roll in `0.0..1.0` from the combat stream.
     pub fn calculate_full_damage(
         &self,
         target_defense: f32,
+        target_armor: f32,
+        target_element: Element,
+        weapon_weakness: Option<WeaponType>,
+        crit_roll: f32,
+    ) -> crate::combat::damage::DamageEvent {
+        let attack_type = self.active_attack_type.unwrap_or(AttackType::Light);
+        self.damage_event(attack_type, false, target_defense, target_armor, target_element, weapon_weakness, crit_roll)
+    }
+
+    /// Damage for a landed combo hit: the attack type's damage scaled by the
+    /// combo step's multiplier. Off-hand hits use the off-hand weapon.
+    pub fn calculate_hit_damage(
+        &self,
+        hit: &ComboHit,
+        target_defense: f32,
+        target_armor: f32,
         target_element: Element,
         weapon_weakness: Option<WeaponType>,
+        crit_roll: f32,
+    ) -> crate::combat::damage::DamageEvent {
+        let mut event = self.damage_event(
+            hit.attack_type,
+            hit.off_hand,
+            target_defense,
+            target_armor,
+            target_element,
+            weapon_weakness,
+            crit_roll,
+        );
+        event.final_amount = (event.final_amount * hit.damage_multiplier).max(1.0);
+        event
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn damage_event(
+        &self,
+        attack_type: AttackType,
+        off_hand: bool,
+        target_defense: f32,
+        target_armor: f32,
+        target_element: Element,
+        weapon_weakness: Option<WeaponType>,
+        crit_roll: f32,
     ) -> crate::combat::damage::DamageEvent {
         let effective = self.effective_stats();
         let equip_mods = self.equipment.total_modifiers();
-        let attack_type = self.active_attack_type.unwrap_or(AttackType::Light);
-        let element = self.stats.elemental_affinity;
-        let elemental_bonus = equip_mods.elemental_damage_bonus[element.index()];
+        let mut element = self.stats.elemental_affinity;
+        let mut weapon_damage = self.equipment.main_weapon_damage();
+        let mut weapon_type = self.equipment.main_weapon_type();
+        // Off-hand strikes use that weapon, and an elemental off hand (e.g. a torch) its element
+        if let Some(strike) = self.equipment.off_hand_strike().filter(|_| off_hand) {
+            weapon_damage = strike.base_damage;
+            weapon_type = strike.weapon_type;
+            if strike.element != Element::Physical {
+                element = strike.element;
+            }
+        }
+        let elemental_bonus = equip_mods.elemental_damage_bonus[element.index()]
+            + self.status_manager.combined_modifiers().elemental_damage_bonus[element.index()];
 
-        calculate_combat_damage(
+        let mut event = calculate_combat_damage(
             effective.attack,
-            self.equipment.main_weapon_damage(),
-            self.equipment.main_weapon_type(),
+            weapon_damage,
+            weapon_type,
             attack_type,
             element,
             effective.crit_chance,
+            crit_roll,
             effective.crit_multiplier,
             elemental_bonus,
             target_defense,
             target_element,
             0.0, // target elemental resistance (NPCs don't have this yet)
             weapon_weakness,
-        )
+        );
+        // Target armor reduces the hit by a percentage for its damage type
+        event.final_amount = mitigate(event.final_amount, event.element, target_armor, 0.0).max(1.0);
+        event
     }
 
     /// Try to use a skill in the given slot (0-3). Returns true if activated.
@@ -455,25 +811,100 @@ impl PlayerCombatState {
         if self.dodge_cooldown_timer > 0.0 || self.is_dodging {
             return false;
         }
-        if self.status_manager.is_movement_prevented() {
+        if self.status_manager.is_movement_prevented() || !self.encumbrance().can_dodge() {
             return false;
         }
         self.is_dodging = true;
-        self.dodge_timer = self.dodge_duration;
-        self.dodge_cooldown_timer = self.dodge_cooldown;
+        self.is_blocking = false;
+        self.combo.cancel();
+        let duration = balance::player::DODGE_DURATION.get();
+        self.dodge_timer = duration;
+        self.dodge_cooldown_timer = balance::player::DODGE_COOLDOWN.get();
         // Grant invincibility during dodge
-        self.invincibility_timer = self.invincibility_timer.max(self.dodge_duration);
+        self.invincibility_timer = self.invincibility_timer.max(duration);
         true
     }
 
-    /// Take elemental damage (applies status effects from element)
-    pub fn take_elemental_damage(&mut self, damage: f32, _element: Element) -> f32 {
+    /// Take damage of an element (shield effects absorb first, then armor
+    /// and that element's resistance apply)
+    pub fn take_elemental_damage(&mut self, damage: f32, element: Element) -> f32 {
         // Let shields absorb first
         let after_shield = self.status_manager.absorb_damage(damage);
         if after_shield <= 0.0 {
             return 0.0;
         }
-        self.take_damage(after_shield)
+        self.take_hit(after_shield, element)
+    }
+
+    /// Movement speed multiplier from status effects (Slowed, Frozen,
+    /// Hastened) and how much is being carried
+    pub fn movement_speed_scale(&self) -> f32 {
+        (1.0 + self.status_manager.combined_modifiers().speed).clamp(0.1, 2.0) * self.encumbrance().speed_multiplier()
+    }
+
+    /// Kilograms carried in the inventory and worn
+    pub fn carried_weight(&self) -> f32 {
+        self.inventory.total_weight() + self.equipment.total_weight()
+    }
+
+    /// Kilograms the player can carry, from effective attack (so strength
+    /// from gear and status effects counts)
+    pub fn carry_capacity(&self) -> f32 {
+        carry_capacity(&self.effective_stats())
+    }
+
+    pub fn encumbrance(&self) -> Encumbrance {
+        Encumbrance::from_load(self.carried_weight(), self.carry_capacity())
+    }
+
+    /// Names of gear that wore out since the last call
+    pub fn take_worn_out(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.worn_out)
+    }
+
+    /// Wear the weapon that landed a hit
+    pub fn wear_weapon(&mut self, off_hand: bool) {
+        if self.durability_enabled {
+            self.worn_out.extend(self.equipment.wear_weapon(off_hand, WEAPON_WEAR_PER_HIT));
+        }
+    }
+
+    /// Coat the main-hand weapon, replacing any coating already on it.
+    /// Returns false when there is no weapon to coat.
+    pub fn apply_coating(&mut self, kind: CoatingKind) -> bool {
+        if self.equipment.main_weapon_type().is_none() {
+            return false;
+        }
+        self.coating = Some(WeaponCoating { kind, charges: COATING_CHARGES });
+        true
+    }
+
+    /// Spend a coating charge on a main-hand hit, returning the coating it
+    /// carried. The coating is gone once its last charge is spent.
+    pub fn take_coating_charge(&mut self) -> Option<CoatingKind> {
+        let coating = self.coating.as_mut()?;
+        let kind = coating.kind;
+        coating.charges = coating.charges.saturating_sub(1);
+        if coating.charges == 0 {
+            self.coating = None;
+        }
+        Some(kind)
+    }
+
+    pub fn durability_enabled(&self) -> bool {
+        self.durability_enabled
+    }
+
+    /// Turn gear wear on or off. Turning it off also mends everything
+    /// carried, so nothing stays weakened while wear can't be repaired away.
+    pub fn set_durability_enabled(&mut self, enabled: bool) {
+        self.durability_enabled = enabled;
+        if !enabled {
+            self.equipment.repair_all();
+            for durable in self.inventory.items.iter_mut().filter_map(|item| item.durable_mut()) {
+                durable.repair();
+            }
+        }
     }
 
     /// Check if we can deal damage (attack animation timing)
@@ -485,8 +916,8 @@ impl PlayerCombatState {
 
     /// Calculate damage to deal to a target
     /// Returns (damage, is_crit)
-    pub fn calculate_damage(&self, target_defense: f32) -> (f32, bool) {
-        self.stats.calculate_damage(target_defense)
+    pub fn calculate_damage(&self, target_defense: f32, crit_roll: f32) -> (f32, bool) {
+        self.stats.calculate_damage(target_defense, crit_roll)
     }
 
     /// Update timers each frame. Returns DOT damage taken from status effects.
@@ -502,9 +933,24 @@ impl PlayerCombatState {
             self.active_attack_type = None;
         }
 
-        // Heavy attack windup
-        if self.heavy_attack_timer > 0.0 {
-            self.heavy_attack_timer = (self.heavy_attack_timer - delta).max(0.0);
+        // Combo chain for the equipped weapon
+        let weapon = self.equipment.main_weapon_type();
+        let speed = weapon.map(|wt| wt.speed_multiplier()).unwrap_or(1.0);
+        if let Some(mut hit) = self.combo.update(delta, self.movesets.get(weapon), speed) {
+            // Dual wielding alternates hands on light chains: every second step is the off hand
+            hit.off_hand = hit.attack_type == AttackType::Light
+                && hit.step % 2 == 1
+                && self.equipment.off_hand_strike().is_some();
+            self.pending_hit = Some(hit);
+        }
+        if let Some((chain, _)) = self.combo.current_step() {
+            self.active_attack_type = Some(chain);
+        } else if !self.is_attacking {
+            self.active_attack_type = None;
+        }
+
+        if self.is_blocking {
+            self.guard_time += delta;
         }
 
         // Invincibility frames
@@ -536,6 +982,14 @@ impl PlayerCombatState {
             slot.update(delta);
         }
 
+        // Food buffs
+        let food_heal = self.food.update(delta);
+        if food_heal > 0.0 && self.stats.current_hp > 0.0 {
+            self.stats.heal(food_heal);
+        }
+        // Healing stops short of what curses take off max HP
+        self.stats.current_hp = self.stats.current_hp.min(self.max_hp());
+
         // Status effects (returns DOT damage)
         let dot_damage = self.status_manager.update(delta);
         if dot_damage > 0.0 {
@@ -546,6 +1000,11 @@ impl PlayerCombatState {
 
     /// Add XP and return levels gained
     pub fn add_xp(&mut self, amount: u64) -> Vec<u32> {
+        let amount = if self.status_manager.has_effect(StatusEffectType::Enlightened) {
+            (amount as f32 * (1.0 + XP_BOON_BONUS)).round() as u64
+        } else {
+            amount
+        };
         self.progression.add_xp(amount)
     }
 
@@ -554,20 +1013,48 @@ impl PlayerCombatState {
         self.stats.apply_growth(growth);
     }
 
+    /// Change class, keeping level and XP: stats are rebuilt from the new
+    /// class's level 1 `base` and `growth`, and the respec cost is paid.
+    /// Returns the gold spent, or `None` if the player can't afford it or
+    /// nothing changes.
+    pub fn respec(&mut self, primary: &str, secondary: Option<&str>, base: &CharacterStats, growth: &StatGrowth) -> Option<u64> {
+        let class = &self.progression.class;
+        let secondary = secondary.filter(|s| *s != primary);
+        if class.primary.as_deref() == Some(primary) && class.secondary.as_deref() == secondary {
+            return None;
+        }
+        let cost = self.progression.respec_cost();
+        if self.gold < cost {
+            return None;
+        }
+        let respecs = class.respecs + u32::from(class.primary.is_some());
+        self.gold -= cost;
+        self.progression.class = ClassSetup {
+            primary: Some(primary.to_string()),
+            secondary: secondary.map(str::to_string),
+            respecs,
+        };
+        self.stats = base.at_level(growth, self.progression.level);
+        Some(cost)
+    }
+
     /// Respawn player (full heal, reset state)
     pub fn respawn(&mut self) {
-        self.stats.current_hp = self.stats.max_hp;
+        self.stats.current_hp = self.max_hp();
         self.stats.current_mana = self.stats.max_mana;
         self.damage_flash_timer = 0.0;
         self.attack_timer = 0.0;
         self.is_attacking = false;
         self.invincibility_timer = 1.0; // Brief invincibility on respawn
         self.active_attack_type = None;
-        self.heavy_attack_timer = 0.0;
+        self.combo.cancel();
+        self.pending_hit = None;
+        self.is_blocking = false;
         self.is_dodging = false;
         self.dodge_timer = 0.0;
         self.dodge_cooldown_timer = 0.0;
         self.status_manager.clear();
+        self.food.clear();
     }
 
     /// Current level
@@ -600,6 +1087,7 @@ impl Default for PlayerCombatState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::combat::{ArmorData, EquipmentSlot, Item, ShieldData};
 
     #[test]
     fn test_default_enemy_stats() {
@@ -630,6 +1118,46 @@ mod tests {
         assert!(!stats.update_attack(0.016)); // on cooldown
     }
 
+    #[test]
+    fn test_threat_proximity_and_decay() {
+        let mut table = ThreatTable::default();
+        let near = |_: ThreatSource| Some(2.0);
+        for _ in 0..10 {
+            table.update(0.1, &[ThreatSource::Player], near, 12.0, 20.0);
+        }
+        assert_eq!(table.target(), Some(ThreatSource::Player));
+
+        // Out of range: threat decays away and the target is dropped
+        let far = |_: ThreatSource| Some(50.0);
+        for _ in 0..100 {
+            table.update(0.1, &[ThreatSource::Player], far, 12.0, 20.0);
+        }
+        assert_eq!(table.threat_of(ThreatSource::Player), 0.0);
+        assert_eq!(table.target(), None);
+    }
+
+    #[test]
+    fn test_threat_switch_hysteresis_and_taunt() {
+        let companion = ThreatSource::Npc(NpcId(7));
+        let mut table = ThreatTable::default();
+        table.add(ThreatSource::Player, 20.0);
+        assert_eq!(table.target(), Some(ThreatSource::Player));
+
+        // Slightly more threat is not enough to pull aggro
+        table.add(companion, 21.0);
+        assert_eq!(table.target(), Some(ThreatSource::Player));
+        table.add(companion, 5.0);
+        assert_eq!(table.target(), Some(companion));
+
+        table.taunt(ThreatSource::Player, 10.0);
+        assert_eq!(table.target(), Some(ThreatSource::Player));
+        assert!(table.threat_of(ThreatSource::Player) > table.threat_of(companion));
+
+        // Sources that disappear are removed
+        table.update(0.1, &[], |s| (s == ThreatSource::Player).then_some(1.0), 12.0, 20.0);
+        assert_eq!(table.sources().count(), 1);
+    }
+
     #[test]
     fn test_player_combat() {
         let mut player = PlayerCombatState::new();
@@ -672,6 +1200,35 @@ mod tests {
         assert!(dmg3 > 0.0);
     }
 
+    #[test]
+    fn test_player_respec_keeps_level() {
+        let mut player = PlayerCombatState::new();
+        player.progression.level = 6;
+        player.progression.current_xp = 40;
+        let base = CharacterStats { attack: 20.0, ..Default::default() };
+        let growth = StatGrowth::default();
+
+        // First choice is free
+        assert_eq!(player.respec("Wanderer", None, &base, &growth), Some(0));
+        assert_eq!(player.progression.class.respecs, 0);
+        assert_eq!(player.stats.attack, base.at_level(&growth, 6).attack);
+        // Choosing the same class again changes nothing
+        assert_eq!(player.respec("Wanderer", Some("Wanderer"), &base, &growth), None);
+
+        // Changing class costs gold
+        let cost = player.progression.respec_cost();
+        assert!(cost > 0);
+        assert_eq!(player.respec("Warden", Some("Wanderer"), &base, &growth), None);
+        player.gold = cost + 5;
+        assert_eq!(player.respec("Warden", Some("Wanderer"), &base, &growth), Some(cost));
+        assert_eq!(player.gold, 5);
+        assert_eq!(player.progression.level, 6);
+        assert_eq!(player.progression.current_xp, 40);
+        assert_eq!(player.progression.class.primary.as_deref(), Some("Warden"));
+        assert!(player.progression.class.is_multiclass());
+        assert_eq!(player.progression.class.respecs, 1);
+    }
+
     #[test]
     fn test_player_respawn() {
         let mut player = PlayerCombatState::new();
@@ -696,6 +1253,30 @@ mod tests {
         assert_eq!(player.level(), 2);
     }
 
+    #[test]
+    fn test_overloaded_player_slows_and_cannot_dodge() {
+        let mut player = PlayerCombatState::new();
+        assert_eq!(player.encumbrance(), Encumbrance::Unburdened);
+        let speed = player.movement_speed_scale();
+
+        let mut rocks = crate::combat::starter_items::create_torch();
+        rocks.stack_count = 1;
+        let per_unit = rocks.weight();
+        rocks.stack_count = (player.carry_capacity() / per_unit) as u32 + 1;
+        player.inventory.items.push(rocks);
+        assert_eq!(player.encumbrance(), Encumbrance::Overloaded);
+        assert!(player.movement_speed_scale() < speed);
+        assert!(!player.try_dodge());
+
+        // Empowered's strength lifts the load back under capacity
+        player.status_manager.apply(crate::combat::status::StatusEffect::elemental_proc(
+            crate::combat::status::StatusEffectType::Empowered,
+            5.0,
+        ));
+        assert_eq!(player.encumbrance(), Encumbrance::Strained);
+        assert!(player.try_dodge());
+    }
+
     #[test]
     fn test_dodge() {
         let mut player = PlayerCombatState::new();
@@ -729,4 +1310,200 @@ mod tests {
         // Should have regenerated
         assert!(player.stats.current_mana > 50.0);
     }
+
+    #[test]
+    fn test_poise_breaks_and_recovers() {
+        let mut stats = CombatStats::default_enemy();
+        assert!(!stats.poise.damage(25.0));
+        assert!(stats.poise.damage(25.0));
+        assert!(stats.poise.is_staggered());
+        // Staggered NPCs can't attack
+        stats.attack_timer = 0.0;
+        assert!(!stats.update_attack(0.016));
+
+        stats.poise.update(balance::combat::STAGGER_DURATION.get());
+        assert!(!stats.poise.is_staggered());
+        assert_eq!(stats.poise.current, stats.poise.max);
+    }
+
+    #[test]
+    fn test_queued_attacks_land_combo_hits() {
+        let mut player = PlayerCombatState::new();
+        assert!(player.queue_attack(AttackType::Light));
+        let mut hits = Vec::new();
+        for _ in 0..30 {
+            let _ = player.update(1.0 / 60.0);
+            hits.extend(player.take_combo_hit());
+        }
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].attack_type, AttackType::Light);
+
+        let event = player.calculate_hit_damage(&hits[0], 0.0, 0.0, Element::Physical, None, 0.99);
+        assert!(event.final_amount >= 1.0);
+
+        // Dodging cancels a buffered attack
+        player.queue_attack(AttackType::Heavy);
+        player.try_dodge();
+        let _ = player.update(2.0);
+        assert!(player.take_combo_hit().is_none());
+    }
+
+    #[test]
+    fn test_off_hand_torch_alternates_light_hits() {
+        let mut player = PlayerCombatState::new();
+        player.equipment.equip(EquipmentSlot::OffHand, crate::combat::create_torch()).unwrap();
+        let mut hits = Vec::new();
+        for frame in 0..120 {
+            if frame % 10 == 0 {
+                player.queue_attack(AttackType::Light);
+            }
+            let _ = player.update(1.0 / 60.0);
+            hits.extend(player.take_combo_hit());
+        }
+        assert!(hits.len() >= 2);
+        assert!(!hits[0].off_hand && hits[1].off_hand);
+
+        let main = player.calculate_hit_damage(&hits[0], 0.0, 0.0, Element::Physical, None, 0.99);
+        let off = player.calculate_hit_damage(&hits[1], 0.0, 0.0, Element::Physical, None, 0.99);
+        assert_eq!(main.element, player.stats.elemental_affinity);
+        assert_eq!(off.element, Element::Fire);
+    }
+
+    #[test]
+    fn test_armor_mitigates_and_wears() {
+        let mut bare = PlayerCombatState::new();
+        let mut armored = PlayerCombatState::new();
+        let mut armor = crate::combat::create_starter_items("Vanguard", WeaponType::Sword, Element::Physical).0.remove(0);
+        armor.armor_data = Some(ArmorData::new(50.0, 1.0));
+        armored.equipment.equip(EquipmentSlot::Chest, armor).unwrap();
+
+        let bare_taken = bare.take_damage(40.0);
+        let armored_taken = armored.take_damage(40.0);
+        assert!(armored_taken < bare_taken * 0.6);
+        // The chest piece wore out on that hit and now protects half as much
+        assert_eq!(armored.take_worn_out().len(), 1);
+        assert!(armored.take_worn_out().is_empty());
+        assert_eq!(armored.equipment.total_armor(), 25.0);
+
+        // With durability off nothing wears, and turning it off mends the chest
+        armored.set_durability_enabled(false);
+        assert_eq!(armored.equipment.total_armor(), 50.0);
+        armored.invincibility_timer = 0.0;
+        armored.take_damage(40.0);
+        assert!(armored.take_worn_out().is_empty());
+        assert_eq!(armored.equipment.total_armor(), 50.0);
+
+        // Elemental hits are reduced less by armor
+        let mut a = PlayerCombatState::new();
+        let mut b = PlayerCombatState::new();
+        for p in [&mut a, &mut b] {
+            p.equipment.chest = Some(Item {
+                armor_data: Some(ArmorData::new(50.0, 100.0)),
+                treasure_map: None,
+                ..crate::combat::create_torch()
+            });
+        }
+        assert!(a.take_elemental_damage(40.0, Element::Fire) > b.take_damage(40.0));
+    }
+
+    #[test]
+    fn test_warm_meal_resists_cold_and_regenerates() {
+        let mut fed = PlayerCombatState::new();
+        let mut hungry = PlayerCombatState::new();
+        fed.food.eat(crate::cooking::recipe("Mammoth Stew").unwrap());
+        assert!(fed.take_elemental_damage(30.0, Element::Water) < hungry.take_elemental_damage(30.0, Element::Water));
+
+        let hp = fed.current_hp();
+        let _ = fed.update(1.0);
+        assert!(fed.current_hp() > hp);
+        fed.respawn();
+        assert!(fed.food.is_empty());
+    }
+
+    #[test]
+    fn test_target_armor_reduces_dealt_damage() {
+        let mut player = PlayerCombatState::new();
+        player.stats.crit_chance = 0.0;
+        let open = player.calculate_full_damage(0.0, 0.0, Element::Physical, None, 0.99);
+        let armored = player.calculate_full_damage(0.0, 50.0, Element::Physical, None, 0.99);
+        assert!((armored.final_amount - open.final_amount * 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_curses_survive_respawn_and_cap_hp() {
+        use crate::combat::curse::CurseKind;
+
+        let mut player = PlayerCombatState::new();
+        let full = player.max_hp();
+        player.curses.afflict(CurseKind::Withering);
+        assert!(player.max_hp() < full);
+        player.update(0.1);
+        assert_eq!(player.current_hp(), player.max_hp());
+
+        player.stats.current_hp = 0.0;
+        player.respawn();
+        assert!(player.curses.has(CurseKind::Withering));
+        assert_eq!(player.current_hp(), player.max_hp());
+        player.stats.heal(1000.0);
+        player.update(0.1);
+        assert_eq!(player.current_hp(), player.max_hp());
+    }
+
+    #[test]
+    fn test_shield_block() {
+        let mut player = PlayerCombatState::new();
+        // No shield: can't block
+        player.set_blocking(true);
+        assert!(!player.is_blocking);
+
+        let shield = Item {
+            shield_data: Some(ShieldData::new(15.0)),
+            ..crate::combat::create_torch()
+        };
+        player.equipment.off_hand = Some(shield);
+        player.set_blocking(true);
+        assert!(player.is_blocking);
+        assert!(!player.queue_attack(AttackType::Light));
+
+        // Fully blocked
+        let hp = player.current_hp();
+        assert_eq!(player.take_damage(10.0), 0.0);
+        assert_eq!(player.last_blocked_amount, 10.0);
+        assert_eq!(player.current_hp(), hp);
+
+        // Partially blocked
+        let taken = player.take_damage(40.0);
+        assert_eq!(player.last_blocked_amount, 15.0);
+        assert!(taken < 40.0 - 15.0 + 0.01);
+        assert!(player.current_hp() < hp);
+
+        // Dodging drops the guard
+        player.try_dodge();
+        assert!(!player.is_blocking);
+    }
+
+    #[test]
+    fn test_parry_window() {
+        let mut player = PlayerCombatState::new();
+        player.equipment.off_hand = Some(Item {
+            shield_data: Some(ShieldData::new(15.0)),
+            ..crate::combat::create_torch()
+        });
+
+        player.set_blocking(true);
+        player.update(balance::combat::PARRY_WINDOW.get() * 0.5);
+        player.take_damage(10.0);
+        assert!(player.last_hit_parried);
+
+        // Holding the guard up is a plain block
+        player.update(balance::combat::PARRY_WINDOW.get());
+        player.take_damage(10.0);
+        assert!(!player.last_hit_parried);
+
+        // Raising it again reopens the window
+        player.set_blocking(false);
+        player.set_blocking(true);
+        player.take_damage(10.0);
+        assert!(player.last_hit_parried);
+    }
 }
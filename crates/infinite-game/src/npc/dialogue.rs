@@ -2,31 +2,133 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use super::relationship::{RelationshipMessage, RelationshipTier};
+use super::requirement::{DialoguePlayer, DialogueStat, Requirement, StatCheck};
+use super::voice::{tree_line_id, SpeakLine};
 use super::{NpcId, NpcRole};
+use crate::combat::curse::CLEANSE_GOLD_COST;
+use crate::flags::{FlagAction, FlagCondition, FlagOp, WorldFlags, REPUTATION_NAMESPACE};
 
 /// A single dialogue step
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueNode {
+    #[serde(default)]
     pub speaker: String,
     pub text: String,
+    #[serde(default)]
     pub responses: Vec<DialogueResponse>,
 }
 
 /// A player response option
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueResponse {
     pub text: String,
     /// Index of next DialogueNode (None = end conversation)
+    #[serde(default)]
     pub next_node: Option<usize>,
+    /// Only offered while this world flag condition holds
+    #[serde(default)]
+    pub condition: Option<FlagCondition>,
+    /// Flag changes applied when the response is chosen
+    #[serde(default)]
+    pub actions: Vec<FlagAction>,
+    /// Offered but locked until every requirement is met
+    #[serde(default)]
+    pub requirements: Vec<Requirement>,
+    /// Stat check whose branches replace `next_node`
+    #[serde(default)]
+    pub check: Option<StatCheck>,
+}
+
+impl DialogueResponse {
+    pub fn new(text: impl Into<String>, next_node: Option<usize>) -> Self {
+        Self {
+            text: text.into(),
+            next_node,
+            condition: None,
+            actions: Vec::new(),
+            requirements: Vec::new(),
+            check: None,
+        }
+    }
+
+    /// Only offer this response while `condition` holds
+    pub fn requires(mut self, condition: FlagCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Apply `action` when this response is chosen
+    pub fn sets(mut self, action: FlagAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Lock this response until `requirement` is met
+    pub fn needs(mut self, requirement: Requirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    /// Decide the next node with a stat check
+    pub fn with_check(mut self, check: StatCheck) -> Self {
+        self.check = Some(check);
+        self
+    }
+
+    /// Whether the response is offered with the current flags
+    pub fn is_available(&self, flags: &WorldFlags) -> bool {
+        self.condition.as_ref().is_none_or(|c| c.check(flags))
+    }
+
+    /// Whether the player meets every requirement
+    pub fn is_unlocked(&self, player: &DialoguePlayer, flags: &WorldFlags) -> bool {
+        self.requirements.iter().all(|r| r.is_met(player, flags))
+    }
+
+    /// Requirements and check shown before the text, e.g. "Attack 15"
+    pub fn tag(&self) -> Option<String> {
+        let labels: Vec<String> = self
+            .requirements
+            .iter()
+            .map(Requirement::label)
+            .chain(self.check.iter().map(StatCheck::label))
+            .collect();
+        (!labels.is_empty()).then(|| labels.join(", "))
+    }
+
+    /// Every node this response can lead to
+    fn next_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        let branches = self.check.iter().flat_map(|c| [c.success, c.failure]);
+        std::iter::once(self.next_node).chain(branches).flatten()
+    }
 }
 
 /// A full conversation tree
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueTree {
     pub nodes: Vec<DialogueNode>,
+    #[serde(default)]
     pub start_node: usize,
 }
 
+impl DialogueTree {
+    /// Check that the start node and every response's next node exist
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_node >= self.nodes.len() {
+            return Err(format!("start node {} doesn't exist", self.start_node));
+        }
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let Some(next) = node.responses.iter().flat_map(DialogueResponse::next_nodes).find(|&next| next >= self.nodes.len()) {
+                return Err(format!("node {} leads to node {}, which doesn't exist", index, next));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Tracks which NPCs have been talked to (for quest flags etc.)
 #[derive(Debug, Clone, Default)]
 pub struct ConversationHistory {
@@ -40,6 +142,18 @@ pub struct ActiveDialogue {
     pub npc_name: String,
     pub tree_key: String,
     pub current_node: usize,
+    /// Lines said so far, both sides
+    pub transcript: Vec<RelationshipMessage>,
+    /// Quest flags the player's choices set
+    pub quests: Vec<String>,
+}
+
+/// A conversation that has ended, for the NPC's relationship log
+#[derive(Debug, Clone)]
+pub struct FinishedDialogue {
+    pub npc_id: NpcId,
+    pub transcript: Vec<RelationshipMessage>,
+    pub quests: Vec<String>,
 }
 
 /// Manages dialogue trees and active conversations
@@ -47,6 +161,10 @@ pub struct DialogueSystem {
     trees: HashMap<String, DialogueTree>,
     active: Option<ActiveDialogue>,
     pub history: ConversationHistory,
+    /// Lines spoken since the last `drain_speak_lines`
+    speak_lines: Vec<SpeakLine>,
+    /// Conversations ended since the last `drain_finished`
+    finished: Vec<FinishedDialogue>,
 }
 
 impl DialogueSystem {
@@ -55,6 +173,8 @@ impl DialogueSystem {
             trees: HashMap::new(),
             active: None,
             history: ConversationHistory::default(),
+            speak_lines: Vec::new(),
+            finished: Vec::new(),
         };
         sys.register_defaults();
         sys
@@ -64,19 +184,30 @@ impl DialogueSystem {
     pub fn start_dialogue(&mut self, npc_id: NpcId, npc_name: String, role: NpcRole) {
         let tree_key = role_tree_key(role);
         if self.trees.contains_key(&tree_key) {
+            self.end_dialogue();
             let start = self.trees[&tree_key].start_node;
             self.active = Some(ActiveDialogue {
                 npc_id,
                 npc_name,
                 tree_key,
                 current_node: start,
+                transcript: Vec::new(),
+                quests: Vec::new(),
             });
             if !self.history.talked_to.contains(&npc_id) {
                 self.history.talked_to.push(npc_id);
             }
+            self.emit_current_line();
         }
     }
 
+    /// Add a conversation tree, replacing the one with the same key. The
+    /// role keys (`villager`, `guard`, ...) change what every NPC of that
+    /// role says.
+    pub fn insert_tree(&mut self, key: impl Into<String>, tree: DialogueTree) -> Option<DialogueTree> {
+        self.trees.insert(key.into(), tree)
+    }
+
     /// Get the current dialogue node (if a conversation is active)
     pub fn current_node(&self) -> Option<&DialogueNode> {
         let active = self.active.as_ref()?;
@@ -94,40 +225,115 @@ impl DialogueSystem {
         self.active.is_some()
     }
 
-    /// Choose a response by index, advancing the dialogue
-    pub fn choose_response(&mut self, index: usize) {
-        let active = match &self.active {
-            Some(a) => a,
-            None => return,
+    /// Responses of the current node offered with the current flags, with
+    /// their index for `choose_response`
+    pub fn available_responses<'a>(&'a self, flags: &WorldFlags) -> Vec<(usize, &'a DialogueResponse)> {
+        self.current_node()
+            .map(|node| {
+                node.responses
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, r)| r.is_available(flags))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Choose a response by index, applying its flag actions and advancing
+    /// the dialogue. Responses whose condition fails or that `player` hasn't
+    /// unlocked are ignored. Returns whether a stat check passed, if the
+    /// response made one.
+    pub fn choose_response(&mut self, index: usize, flags: &mut WorldFlags, player: &DialoguePlayer) -> Option<bool> {
+        let active = self.active.as_ref()?;
+        let tree = self.trees.get(&active.tree_key)?;
+        let node = tree.nodes.get(active.current_node)?;
+        let response = match node.responses.get(index) {
+            Some(r) if r.is_available(flags) && r.is_unlocked(player, flags) => r,
+            _ => return None,
         };
-        let tree = match self.trees.get(&active.tree_key) {
-            Some(t) => t,
-            None => return,
+        for action in &response.actions {
+            action.apply(flags);
+        }
+        let passed = response.check.as_ref().map(|c| c.passes(player));
+        let text = match (&response.check, passed) {
+            (Some(check), Some(passed)) => {
+                let outcome = if passed { "success" } else { "failure" };
+                format!("{} [{}: {}]", response.text, check.label(), outcome)
+            }
+            _ => response.text.clone(),
         };
-        let node = match tree.nodes.get(active.current_node) {
-            Some(n) => n,
-            None => return,
+        let said = RelationshipMessage {
+            speaker: "You".to_string(),
+            text,
+            is_player: true,
         };
-        let response = match node.responses.get(index) {
-            Some(r) => r,
-            None => return,
+        let quests: Vec<String> = response
+            .actions
+            .iter()
+            .filter(|a| a.namespace == "quest")
+            .map(|a| a.key.clone())
+            .collect();
+        let next_node = match (&response.check, passed) {
+            (Some(check), Some(passed)) => check.next_node(passed),
+            _ => response.next_node,
         };
+        if let Some(active) = &mut self.active {
+            active.transcript.push(said);
+            active.quests.extend(quests);
+        }
 
-        match response.next_node {
+        match next_node {
             Some(next) => {
                 if let Some(active) = &mut self.active {
                     active.current_node = next;
                 }
+                self.emit_current_line();
             }
-            None => {
-                self.active = None;
-            }
+            None => self.end_dialogue(),
         }
+        passed
     }
 
     /// End the current dialogue
     pub fn end_dialogue(&mut self) {
-        self.active = None;
+        if let Some(active) = self.active.take() {
+            self.finished.push(FinishedDialogue {
+                npc_id: active.npc_id,
+                transcript: active.transcript,
+                quests: active.quests,
+            });
+        }
+    }
+
+    /// Take the conversations ended since the last call
+    pub fn drain_finished(&mut self) -> Vec<FinishedDialogue> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Take the lines spoken since the last call (for voice playback)
+    pub fn drain_speak_lines(&mut self) -> Vec<SpeakLine> {
+        std::mem::take(&mut self.speak_lines)
+    }
+
+    fn emit_current_line(&mut self) {
+        let (Some(active), Some(node)) = (&self.active, self.current_node()) else {
+            return;
+        };
+        let line = SpeakLine {
+            npc_id: active.npc_id,
+            speaker: active.npc_name.clone(),
+            text: node.text.clone(),
+            line_id: Some(tree_line_id(&active.tree_key, active.current_node)),
+        };
+        let said = RelationshipMessage {
+            speaker: line.speaker.clone(),
+            text: line.text.clone(),
+            is_player: false,
+        };
+        self.speak_lines.push(line);
+        if let Some(active) = &mut self.active {
+            active.transcript.push(said);
+        }
     }
 
     /// Register default dialogue trees for each NPC role
@@ -164,9 +370,9 @@ fn villager_tree() -> DialogueTree {
                 speaker: String::new(), // filled in at runtime with NPC name
                 text: "Hello, traveler! It's not often we see new faces around here.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Tell me about this place.".into(), next_node: Some(1) },
-                    DialogueResponse { text: "What era is this?".into(), next_node: Some(2) },
-                    DialogueResponse { text: "Goodbye.".into(), next_node: None },
+                    DialogueResponse::new("Tell me about this place.", Some(1)),
+                    DialogueResponse::new("What era is this?", Some(2)),
+                    DialogueResponse::new("Goodbye.", None),
                 ],
             },
             // 1: about the area
@@ -174,8 +380,10 @@ fn villager_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "These rolling hills stretch as far as the eye can see. They say the terrain itself changes as the eras shift. Quite unsettling, really.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Interesting. Tell me more.".into(), next_node: Some(2) },
-                    DialogueResponse { text: "Thanks. Goodbye.".into(), next_node: None },
+                    DialogueResponse::new("Interesting. Tell me more.", Some(2)),
+                    DialogueResponse::new("Thanks. Goodbye.", None),
+                    DialogueResponse::new("Anything you wouldn't tell a stranger?", Some(4))
+                        .needs(Requirement::Tier(RelationshipTier::Friend)),
                 ],
             },
             // 2: about the era
@@ -183,8 +391,10 @@ fn villager_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "The era? Well, you can feel it in the air, can't you? The world shifts around us. Some say there are portals that let you travel through time itself.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Where can I find these portals?".into(), next_node: Some(3) },
-                    DialogueResponse { text: "Thanks for the info. Goodbye.".into(), next_node: None },
+                    DialogueResponse::new("Where can I find these portals?", Some(3)),
+                    DialogueResponse::new("An elder spoke of missions across the eras.", Some(3))
+                        .requires(FlagCondition::is_set("quest", "heard_of_rifts")),
+                    DialogueResponse::new("Thanks for the info. Goodbye.", None),
                 ],
             },
             // 3: portals
@@ -192,7 +402,15 @@ fn villager_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Look for the glowing stones scattered across the land. Step through one and you might find yourself in a very different time. Be careful — the past and future both hold dangers.".into(),
                 responses: vec![
-                    DialogueResponse { text: "I'll keep my eyes open. Goodbye.".into(), next_node: None },
+                    DialogueResponse::new("I'll keep my eyes open. Goodbye.", None),
+                ],
+            },
+            // 4: a secret, for friends
+            DialogueNode {
+                speaker: String::new(),
+                text: "Between you and me? The old well east of here wasn't always dry. My grandmother swore it ran with light the night the first portal opened.".into(),
+                responses: vec![
+                    DialogueResponse::new("I'll keep that to myself. Thank you.", None),
                 ],
             },
         ],
@@ -208,9 +426,11 @@ fn guard_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Move along, citizen. These are troubled times.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Any threats I should know about?".into(), next_node: Some(1) },
-                    DialogueResponse { text: "What are you guarding?".into(), next_node: Some(2) },
-                    DialogueResponse { text: "Understood. Moving on.".into(), next_node: None },
+                    DialogueResponse::new("Any threats I should know about?", Some(1)),
+                    DialogueResponse::new("What are you guarding?", Some(2)),
+                    DialogueResponse::new("Stand aside. I'm going where I please.", None)
+                        .with_check(StatCheck::new(DialogueStat::Attack, 15.0, Some(3), Some(4))),
+                    DialogueResponse::new("Understood. Moving on.", None),
                 ],
             },
             // 1: threats
@@ -218,8 +438,10 @@ fn guard_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Bandits have been spotted in the outskirts. Stay alert, especially at night. They're more aggressive when the sun goes down.".into(),
                 responses: vec![
-                    DialogueResponse { text: "I can handle myself.".into(), next_node: None },
-                    DialogueResponse { text: "Thanks for the warning.".into(), next_node: None },
+                    DialogueResponse::new("I can handle myself.", None),
+                    DialogueResponse::new("Thanks for the warning.", None),
+                    DialogueResponse::new("I'll keep watch out there too.", None)
+                        .sets(FlagAction::new(REPUTATION_NAMESPACE, "town_watch", FlagOp::Add(5))),
                 ],
             },
             // 2: duty
@@ -227,7 +449,23 @@ fn guard_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "I patrol this area to keep the peace. Someone has to do it. If you see anything suspicious, let me know.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Will do. Stay safe.".into(), next_node: None },
+                    DialogueResponse::new("Will do. Stay safe.", None),
+                ],
+            },
+            // 3: intimidation worked
+            DialogueNode {
+                speaker: String::new(),
+                text: "...Fine. Fine! Go on, then. I didn't see you.".into(),
+                responses: vec![
+                    DialogueResponse::new("Wise choice.", None),
+                ],
+            },
+            // 4: intimidation failed
+            DialogueNode {
+                speaker: String::new(),
+                text: "Ha! Come back when you can swing a sword without falling over. Now move along.".into(),
+                responses: vec![
+                    DialogueResponse::new("...Right.", None),
                 ],
             },
         ],
@@ -243,9 +481,11 @@ fn shopkeeper_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Welcome to my shop! I've got wares from across the eras. What catches your eye?".into(),
                 responses: vec![
-                    DialogueResponse { text: "What do you have for sale?".into(), next_node: Some(1) },
-                    DialogueResponse { text: "How's business?".into(), next_node: Some(2) },
-                    DialogueResponse { text: "Just browsing. Goodbye.".into(), next_node: None },
+                    DialogueResponse::new("What do you have for sale?", Some(1)),
+                    DialogueResponse::new("How's business?", Some(2)),
+                    DialogueResponse::new("What would you give me for this old torch?", Some(3))
+                        .needs(Requirement::Item("Torch".into())),
+                    DialogueResponse::new("Just browsing. Goodbye.", None),
                 ],
             },
             // 1: wares
@@ -253,7 +493,7 @@ fn shopkeeper_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Well, the shop system isn't quite set up yet. But between you and me, I'll have the best inventory in the realm once it is. Check back soon!".into(),
                 responses: vec![
-                    DialogueResponse { text: "I'll be back.".into(), next_node: None },
+                    DialogueResponse::new("I'll be back.", None),
                 ],
             },
             // 2: business
@@ -261,7 +501,26 @@ fn shopkeeper_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Business is... complicated when your customers keep vanishing into different eras. One minute they're here, next they're a thousand years in the past!".into(),
                 responses: vec![
-                    DialogueResponse { text: "Ha! I can imagine. Goodbye.".into(), next_node: None },
+                    DialogueResponse::new("Ha! I can imagine. Goodbye.", None),
+                    DialogueResponse::new("Any deals for a friend of the guild?", Some(4))
+                        .needs(Requirement::Reputation { faction: "merchants".into(), min: 5 }),
+                ],
+            },
+            // 3: the torch
+            DialogueNode {
+                speaker: String::new(),
+                text: "A torch that's burned through three eras? Keep it, you'll need it more than I will. But I'll tell the guild who showed it to me.".into(),
+                responses: vec![
+                    DialogueResponse::new("Much obliged.", None)
+                        .sets(FlagAction::new(REPUTATION_NAMESPACE, "merchants", FlagOp::Add(5))),
+                ],
+            },
+            // 4: for friends of the guild
+            DialogueNode {
+                speaker: String::new(),
+                text: "For you? Come by right after an era shift. The rarest goods wash up then, and I set them aside for friends.".into(),
+                responses: vec![
+                    DialogueResponse::new("I'll remember that.", None),
                 ],
             },
         ],
@@ -277,8 +536,10 @@ fn quest_giver_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Ah, you have the look of an adventurer. I could use someone with your talents...".into(),
                 responses: vec![
-                    DialogueResponse { text: "What do you need?".into(), next_node: Some(1) },
-                    DialogueResponse { text: "I'm busy right now.".into(), next_node: None },
+                    DialogueResponse::new("What do you need?", Some(1)),
+                    DialogueResponse::new("Can you help me walk a different path?", Some(3)),
+                    DialogueResponse::new("Can you lift a curse?", Some(4)),
+                    DialogueResponse::new("I'm busy right now.", None),
                 ],
             },
             // 1: quest details
@@ -286,8 +547,9 @@ fn quest_giver_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "The quest system is still being built, but when it's ready, I'll have important missions that span across the eras themselves. The fate of the timeline may depend on it.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Sounds exciting. I'll check back.".into(), next_node: Some(2) },
-                    DialogueResponse { text: "Not interested.".into(), next_node: None },
+                    DialogueResponse::new("Sounds exciting. I'll check back.", Some(2))
+                        .sets(FlagAction::set("quest", "heard_of_rifts", true)),
+                    DialogueResponse::new("Not interested.", None),
                 ],
             },
             // 2: farewell
@@ -295,7 +557,25 @@ fn quest_giver_tree() -> DialogueTree {
                 speaker: String::new(),
                 text: "Good. The world needs heroes who aren't afraid to walk between eras. Until we meet again, traveler.".into(),
                 responses: vec![
-                    DialogueResponse { text: "Farewell.".into(), next_node: None },
+                    DialogueResponse::new("Farewell.", None),
+                ],
+            },
+            // 3: respec, opened by the game when the flag is set
+            DialogueNode {
+                speaker: String::new(),
+                text: "Every era shaped you a certain way, but none of it is fixed. For a price, I can help you become something else - or something in between.".into(),
+                responses: vec![
+                    DialogueResponse::new("Show me.", None).sets(FlagAction::set("services", "respec", true)),
+                    DialogueResponse::new("I'm happy as I am.", None),
+                ],
+            },
+            // 4: cleansing, paid for by the game when the flag is set
+            DialogueNode {
+                speaker: String::new(),
+                text: format!("I know the old rites. Whatever clings to you, I can burn it away - for {} gold.", CLEANSE_GOLD_COST),
+                responses: vec![
+                    DialogueResponse::new("Do it.", None).sets(FlagAction::set("services", "cleanse", true)),
+                    DialogueResponse::new("Not today.", None),
                 ],
             },
         ],
@@ -305,6 +585,7 @@ fn quest_giver_tree() -> DialogueTree {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::npc::requirement::reputation;
 
     #[test]
     fn test_dialogue_lifecycle() {
@@ -319,19 +600,61 @@ mod tests {
         assert_eq!(node.responses.len(), 3);
     }
 
+    #[test]
+    fn test_speak_lines_emitted() {
+        let mut system = DialogueSystem::new();
+        let player = DialoguePlayer::default();
+        system.start_dialogue(NpcId(7), "Mara".into(), NpcRole::Guard);
+        system.choose_response(0, &mut WorldFlags::new(), &player);
+
+        let lines = system.drain_speak_lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].npc_id, NpcId(7));
+        assert_eq!(lines[0].speaker, "Mara");
+        assert_eq!(lines[0].line_id.as_deref(), Some("guard.0"));
+        assert_eq!(lines[1].line_id.as_deref(), Some("guard.1"));
+        assert!(system.drain_speak_lines().is_empty());
+    }
+
+    #[test]
+    fn test_finished_dialogue_transcript() {
+        let mut system = DialogueSystem::new();
+        let player = DialoguePlayer::default();
+        let mut flags = WorldFlags::new();
+        system.start_dialogue(NpcId(2), "Sage".into(), NpcRole::QuestGiver);
+        system.choose_response(0, &mut flags, &player);
+        system.choose_response(0, &mut flags, &player);
+        assert!(system.drain_finished().is_empty());
+        system.choose_response(0, &mut flags, &player);
+
+        let finished = system.drain_finished();
+        assert_eq!(finished.len(), 1);
+        let transcript = &finished[0].transcript;
+        assert_eq!(transcript.len(), 6);
+        assert!(!transcript[0].is_player && transcript[0].speaker == "Sage");
+        assert!(transcript[1].is_player);
+        assert_eq!(finished[0].quests, vec!["heard_of_rifts"]);
+
+        // Starting over another conversation finishes the first
+        system.start_dialogue(NpcId(1), "Finn".into(), NpcRole::Villager);
+        system.start_dialogue(NpcId(3), "Bron".into(), NpcRole::Guard);
+        assert_eq!(system.drain_finished()[0].npc_id, NpcId(1));
+    }
+
     #[test]
     fn test_dialogue_navigation() {
         let mut system = DialogueSystem::new();
+        let player = DialoguePlayer::default();
         system.start_dialogue(NpcId(1), "Finn".into(), NpcRole::Villager);
 
         // Choose first response: "Tell me about this place"
-        system.choose_response(0);
+        system.choose_response(0, &mut WorldFlags::new(), &player);
         assert!(system.is_active());
         let node = system.current_node().unwrap();
         assert!(node.text.contains("rolling hills"));
 
         // Choose "Thanks. Goodbye."
-        system.choose_response(1);
+        system.choose_response(1, &mut WorldFlags::new(), &player);
         assert!(!system.is_active());
     }
 
@@ -350,13 +673,97 @@ mod tests {
         assert!(system.history.talked_to.contains(&NpcId(42)));
     }
 
+    #[test]
+    fn test_flag_gated_responses() {
+        let mut system = DialogueSystem::new();
+        let player = DialoguePlayer::default();
+        let mut flags = WorldFlags::new();
+        system.start_dialogue(NpcId(1), "Finn".into(), NpcRole::Villager);
+        system.choose_response(1, &mut flags, &player);
+        assert_eq!(system.available_responses(&flags).len(), 2);
+        // Hidden responses can't be chosen either
+        system.choose_response(1, &mut flags, &player);
+        assert!(system.current_node().unwrap().text.contains("The era?"));
+
+        system.start_dialogue(NpcId(2), "Sage".into(), NpcRole::QuestGiver);
+        system.choose_response(0, &mut flags, &player);
+        system.choose_response(0, &mut flags, &player);
+        assert!(flags.get_bool("quest", "heard_of_rifts"));
+
+        system.start_dialogue(NpcId(2), "Sage".into(), NpcRole::QuestGiver);
+        system.choose_response(1, &mut flags, &player);
+        system.choose_response(0, &mut flags, &player);
+        assert!(flags.get_bool("services", "respec"));
+        assert!(!system.is_active());
+
+        system.start_dialogue(NpcId(2), "Sage".into(), NpcRole::QuestGiver);
+        system.choose_response(2, &mut flags, &player);
+        system.choose_response(0, &mut flags, &player);
+        assert!(flags.get_bool("services", "cleanse"));
+
+        system.start_dialogue(NpcId(1), "Finn".into(), NpcRole::Villager);
+        system.choose_response(1, &mut flags, &player);
+        let offered: Vec<usize> = system.available_responses(&flags).iter().map(|(i, _)| *i).collect();
+        assert_eq!(offered, vec![0, 1, 2]);
+        system.choose_response(1, &mut flags, &player);
+        assert!(system.current_node().unwrap().text.contains("glowing stones"));
+    }
+
+    #[test]
+    fn test_locked_responses_and_stat_checks() {
+        let mut system = DialogueSystem::new();
+        let mut flags = WorldFlags::new();
+        let stranger = DialoguePlayer::default();
+        let friend = DialoguePlayer { tier: RelationshipTier::Friend, ..stranger };
+
+        // Locked responses are offered but can't be chosen
+        system.start_dialogue(NpcId(1), "Finn".into(), NpcRole::Villager);
+        system.choose_response(0, &mut flags, &stranger);
+        let (_, secret) = system.available_responses(&flags)[2];
+        assert_eq!(secret.tag().as_deref(), Some("Friend"));
+        assert!(!secret.is_unlocked(&stranger, &flags));
+        assert_eq!(system.choose_response(2, &mut flags, &stranger), None);
+        assert!(system.current_node().unwrap().text.contains("rolling hills"));
+        system.choose_response(2, &mut flags, &friend);
+        assert!(system.current_node().unwrap().text.contains("old well"));
+
+        // Stat checks branch on the stat
+        system.start_dialogue(NpcId(3), "Bron".into(), NpcRole::Guard);
+        assert_eq!(system.choose_response(2, &mut flags, &stranger), Some(false));
+        assert!(system.current_node().unwrap().text.contains("Come back"));
+        system.start_dialogue(NpcId(3), "Bron".into(), NpcRole::Guard);
+        let strong = DialoguePlayer { attack: 15.0, ..stranger };
+        assert_eq!(system.choose_response(2, &mut flags, &strong), Some(true));
+        assert!(system.current_node().unwrap().text.contains("I didn't see you"));
+        system.end_dialogue();
+        let finished = system.drain_finished();
+        let transcript = &finished.last().unwrap().transcript;
+        assert_eq!(transcript[1].text, "Stand aside. I'm going where I please. [Attack 15: success]");
+
+        // Showing the torch earns the reputation the guild deal needs
+        let torch = [crate::combat::starter_items::create_torch()];
+        let carrying = DialoguePlayer { items: &torch, ..stranger };
+        system.start_dialogue(NpcId(4), "Ilsa".into(), NpcRole::Shopkeeper);
+        system.choose_response(1, &mut flags, &carrying);
+        assert_eq!(system.choose_response(1, &mut flags, &carrying), None);
+        system.start_dialogue(NpcId(4), "Ilsa".into(), NpcRole::Shopkeeper);
+        system.choose_response(2, &mut flags, &carrying);
+        system.choose_response(0, &mut flags, &carrying);
+        assert_eq!(reputation(&flags, "merchants"), 5);
+        system.start_dialogue(NpcId(4), "Ilsa".into(), NpcRole::Shopkeeper);
+        system.choose_response(1, &mut flags, &carrying);
+        system.choose_response(1, &mut flags, &carrying);
+        assert!(system.current_node().unwrap().text.contains("era shift"));
+    }
+
     #[test]
     fn test_all_default_trees_valid() {
         let system = DialogueSystem::new();
         for role in [NpcRole::Villager, NpcRole::Guard, NpcRole::Shopkeeper, NpcRole::QuestGiver] {
             let key = role_tree_key(role);
-            let tree = system.trees.get(&key).expect(&format!("missing tree for {:?}", role));
+            let tree = system.trees.get(&key).unwrap_or_else(|| panic!("missing tree for {:?}", role));
             assert!(!tree.nodes.is_empty());
+            assert_eq!(tree.validate(), Ok(()), "tree {}", key);
             // Verify all next_node references are valid
             for node in &tree.nodes {
                 for response in &node.responses {
@@ -0,0 +1,262 @@
+//! Elite enemies and their affixes
+//!
+//! Some enemies spawn as elites with one or two affixes rolled
+//! deterministically from their persistent key. Elites are tougher, tinted
+//! by their first affix, show their affixes on the nameplate and pay out
+//! scaled rewards. The affix behaviors hook into the NPC manager (shields,
+//! splitting, speed, lifesteal) and the player's status effects (frost aura).
+
+use infinite_core::rng::mix;
+
+use super::combat::CombatStats;
+
+/// One in this many enemies spawns as an elite
+pub const ELITE_ODDS: u64 = 6;
+
+/// Radius of the Frozen aura that slows the player
+pub const FROZEN_AURA_RADIUS: f32 = 5.0;
+
+/// Fraction of damage dealt that Vampiric elites heal
+pub const VAMPIRIC_LIFESTEAL: f32 = 0.5;
+
+/// Number of fragments a Splitting elite breaks into
+pub const SPLIT_COUNT: usize = 2;
+
+/// Max HP of each fragment as a fraction of the elite's max HP
+pub const SPLIT_HP_FRACTION: f32 = 0.3;
+
+/// Shield of a Shielded elite as a fraction of its max HP
+pub const SHIELD_FRACTION: f32 = 0.4;
+
+/// Seconds without taking damage before a Shielded elite's shield regenerates
+pub const SHIELD_REGEN_DELAY: f32 = 5.0;
+
+/// Movement and attack speed multiplier for Swift elites
+pub const SWIFT_SPEED_MULTIPLIER: f32 = 1.6;
+
+/// An elite modifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EliteAffix {
+    /// Slows the player while nearby
+    FrozenAura,
+    /// Heals from damage dealt
+    Vampiric,
+    /// Breaks into smaller enemies on death
+    Splitting,
+    /// Regenerating damage shield
+    Shielded,
+    /// Moves and attacks faster
+    Swift,
+}
+
+impl EliteAffix {
+    /// All affix variants
+    pub fn all() -> &'static [EliteAffix] {
+        &[
+            Self::FrozenAura,
+            Self::Vampiric,
+            Self::Splitting,
+            Self::Shielded,
+            Self::Swift,
+        ]
+    }
+
+    /// Nameplate prefix
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::FrozenAura => "Frozen",
+            Self::Vampiric => "Vampiric",
+            Self::Splitting => "Splitting",
+            Self::Shielded => "Shielded",
+            Self::Swift => "Swift",
+        }
+    }
+
+    /// Body tint / nameplate color
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::FrozenAura => [0.55, 0.85, 1.0],
+            Self::Vampiric => [0.6, 0.05, 0.15],
+            Self::Splitting => [0.45, 0.8, 0.25],
+            Self::Shielded => [0.85, 0.8, 0.4],
+            Self::Swift => [0.95, 0.95, 0.95],
+        }
+    }
+}
+
+/// Affixes and runtime state of an elite enemy
+#[derive(Debug, Clone, PartialEq)]
+pub struct EliteModifiers {
+    pub affixes: Vec<EliteAffix>,
+    /// Remaining shield (Shielded only)
+    pub shield: f32,
+    pub shield_max: f32,
+    since_hit: f32,
+}
+
+impl EliteModifiers {
+    pub fn new(affixes: Vec<EliteAffix>) -> Self {
+        Self {
+            affixes,
+            shield: 0.0,
+            shield_max: 0.0,
+            since_hit: 0.0,
+        }
+    }
+
+    /// Roll elite status from a seed (the NPC's persistent key). Most seeds
+    /// produce no elite; elites get one or two distinct affixes.
+    pub fn roll(seed: u64) -> Option<Self> {
+        let h = mix(seed ^ 0x9e37_79b9_7f4a_7c15);
+        if !h.is_multiple_of(ELITE_ODDS) {
+            return None;
+        }
+
+        let all = EliteAffix::all();
+        let first = all[((h >> 8) % all.len() as u64) as usize];
+        let mut affixes = vec![first];
+        if (h >> 16).is_multiple_of(2) {
+            let offset = 1 + ((h >> 24) % (all.len() as u64 - 1)) as usize;
+            let first_index = all.iter().position(|a| *a == first).unwrap_or(0);
+            affixes.push(all[(first_index + offset) % all.len()]);
+        }
+        Some(Self::new(affixes))
+    }
+
+    pub fn has(&self, affix: EliteAffix) -> bool {
+        self.affixes.contains(&affix)
+    }
+
+    /// Make base stats elite: tougher, harder hitting, and affix adjustments
+    pub fn apply_to_stats(&mut self, stats: &mut CombatStats) {
+        stats.max_hp *= 2.0;
+        stats.current_hp = stats.max_hp;
+        stats.attack *= 1.3;
+        stats.armor += 10.0;
+        stats.poise.max *= 1.5;
+        stats.poise.current = stats.poise.max;
+        if self.has(EliteAffix::Swift) {
+            stats.attack_cooldown /= SWIFT_SPEED_MULTIPLIER;
+        }
+        if self.has(EliteAffix::Shielded) {
+            self.shield_max = stats.max_hp * SHIELD_FRACTION;
+            self.shield = self.shield_max;
+        }
+    }
+
+    /// Movement speed multiplier
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.has(EliteAffix::Swift) {
+            SWIFT_SPEED_MULTIPLIER
+        } else {
+            1.0
+        }
+    }
+
+    /// XP and gold multiplier for defeating this elite
+    pub fn reward_multiplier(&self) -> f32 {
+        1.5 + 0.75 * self.affixes.len() as f32
+    }
+
+    /// Blend a base color towards the first affix's color
+    pub fn tint(&self, base: [f32; 4]) -> [f32; 4] {
+        let Some(affix) = self.affixes.first() else {
+            return base;
+        };
+        let c = affix.color();
+        [
+            base[0] * 0.3 + c[0] * 0.7,
+            base[1] * 0.3 + c[1] * 0.7,
+            base[2] * 0.3 + c[2] * 0.7,
+            base[3],
+        ]
+    }
+
+    /// Affix names joined for the nameplate, e.g. "Frozen Vampiric"
+    pub fn title(&self) -> String {
+        self.affixes.iter().map(|a| a.name()).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Absorb damage with the shield. Returns the damage left over.
+    pub fn absorb(&mut self, damage: f32) -> f32 {
+        self.since_hit = 0.0;
+        let absorbed = damage.min(self.shield);
+        self.shield -= absorbed;
+        damage - absorbed
+    }
+
+    /// Regenerate the shield after a while without taking damage
+    pub fn update(&mut self, delta: f32) {
+        self.since_hit += delta;
+        if self.shield_max > 0.0 && self.since_hit >= SHIELD_REGEN_DELAY {
+            self.shield = (self.shield + self.shield_max * 0.25 * delta).min(self.shield_max);
+        }
+    }
+
+    /// HP healed from dealing `damage`
+    pub fn lifesteal(&self, damage: f32) -> f32 {
+        if self.has(EliteAffix::Vampiric) {
+            damage * VAMPIRIC_LIFESTEAL
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roll_is_deterministic_and_distinct() {
+        let elites: Vec<EliteModifiers> = (0..600).filter_map(EliteModifiers::roll).collect();
+        // Roughly one in ELITE_ODDS
+        assert!(elites.len() > 60 && elites.len() < 160, "{} elites", elites.len());
+        assert!(elites.iter().any(|e| e.affixes.len() == 2));
+        for elite in &elites {
+            assert!(!elite.affixes.is_empty() && elite.affixes.len() <= 2);
+            if elite.affixes.len() == 2 {
+                assert_ne!(elite.affixes[0], elite.affixes[1]);
+            }
+        }
+        for seed in 0..50 {
+            assert_eq!(EliteModifiers::roll(seed), EliteModifiers::roll(seed));
+        }
+    }
+
+    #[test]
+    fn test_apply_to_stats() {
+        let mut elite = EliteModifiers::new(vec![EliteAffix::Swift, EliteAffix::Shielded]);
+        let base = CombatStats::default_enemy();
+        let mut stats = base.clone();
+        elite.apply_to_stats(&mut stats);
+        assert_eq!(stats.max_hp, base.max_hp * 2.0);
+        assert!(stats.attack_cooldown < base.attack_cooldown);
+        assert_eq!(elite.shield, stats.max_hp * SHIELD_FRACTION);
+        assert!(elite.reward_multiplier() > 2.0);
+        assert_eq!(elite.title(), "Swift Shielded");
+    }
+
+    #[test]
+    fn test_shield_absorbs_and_regenerates() {
+        let mut elite = EliteModifiers::new(vec![EliteAffix::Shielded]);
+        elite.apply_to_stats(&mut CombatStats::default_enemy());
+        let max = elite.shield_max;
+        assert_eq!(elite.absorb(max + 10.0), 10.0);
+        assert_eq!(elite.shield, 0.0);
+
+        // No regen until the delay passes
+        elite.update(SHIELD_REGEN_DELAY * 0.5);
+        assert_eq!(elite.shield, 0.0);
+        for _ in 0..10 {
+            elite.update(1.0);
+        }
+        assert_eq!(elite.shield, max);
+    }
+
+    #[test]
+    fn test_lifesteal_only_when_vampiric() {
+        assert_eq!(EliteModifiers::new(vec![EliteAffix::Swift]).lifesteal(10.0), 0.0);
+        assert_eq!(EliteModifiers::new(vec![EliteAffix::Vampiric]).lifesteal(10.0), 10.0 * VAMPIRIC_LIFESTEAL);
+    }
+}
@@ -6,6 +6,9 @@ pub struct GameContext {
     pub time_of_day: f32,
     pub weather: String,
     pub player_name: String,
+    /// How the player looks, e.g. "tall and slim, with dark skin", for the
+    /// NPC to remark on
+    pub player_appearance: Option<String>,
     pub npc_goap_state: String,
     pub npc_location_desc: String,
     pub relationship_level: f32,
@@ -55,6 +58,10 @@ impl GameContext {
             self.relationship_level,
         );
 
+        if let Some(appearance) = &self.player_appearance {
+            context.push_str(&format!("\nPlayer appearance: {}", appearance));
+        }
+
         if let Some(summary) = &self.conversation_summary {
             context.push_str(&format!(
                 "\n\n[PREVIOUS CONVERSATION SUMMARY]\n{}",
@@ -77,6 +84,7 @@ mod tests {
             time_of_day: 14.5,
             weather: "Clear".into(),
             player_name: "TestPlayer".into(),
+            player_appearance: Some("tall and slim, with red hair".into()),
             npc_goap_state: "idle".into(),
             npc_location_desc: "village square".into(),
             relationship_level: 25.0,
@@ -90,6 +98,7 @@ mod tests {
         assert!(result.contains("Afternoon"));
         assert!(result.contains("TestPlayer"));
         assert!(result.contains("Acquaintance"));
+        assert!(result.contains("Player appearance: tall and slim, with red hair"));
     }
 
     #[test]
@@ -99,6 +108,7 @@ mod tests {
             time_of_day: 8.0,
             weather: "Rainy".into(),
             player_name: "Hero".into(),
+            player_appearance: None,
             npc_goap_state: "patrolling".into(),
             npc_location_desc: "city walls".into(),
             relationship_level: 50.0,
@@ -131,6 +141,7 @@ mod tests {
                 time_of_day: 12.0,
                 weather: "Clear".into(),
                 player_name: "Test".into(),
+                player_appearance: None,
                 npc_goap_state: "idle".into(),
                 npc_location_desc: "here".into(),
                 relationship_level: 0.0,
@@ -125,6 +125,11 @@ impl NpcBrain {
                 desired_state: WorldState::from_bool("at_home", true),
                 priority: 0.3,
             },
+            Goal {
+                name: "take_a_seat".into(),
+                desired_state: WorldState::from_bool("rested", true),
+                priority: 0.35,
+            },
             Goal {
                 name: "wander_around".into(),
                 desired_state: WorldState::from_bool("has_wandered", true),
@@ -161,6 +166,7 @@ impl NpcBrain {
                 cost: 1.0,
                 duration: 3.0,
             },
+            Self::sit_down(),
         ];
 
         (goals, actions)
@@ -267,6 +273,11 @@ impl NpcBrain {
                 desired_state: WorldState::from_bool("waiting", true),
                 priority: 0.5,
             },
+            Goal {
+                name: "take_a_seat".into(),
+                desired_state: WorldState::from_bool("rested", true),
+                priority: 0.35,
+            },
         ];
 
         let actions = vec![
@@ -284,6 +295,7 @@ impl NpcBrain {
                 cost: 0.5,
                 duration: 5.0,
             },
+            Self::sit_down(),
             Action {
                 name: "wander".into(),
                 preconditions: WorldState::new(),
@@ -296,6 +308,18 @@ impl NpcBrain {
         (goals, actions)
     }
 
+    /// Walk to a free bench, chair or wall nearby and rest there for a
+    /// while (the manager times the sit, not the action duration)
+    fn sit_down() -> Action {
+        Action {
+            name: "sit_down".into(),
+            preconditions: WorldState::from_bool("seat_nearby", true),
+            effects: WorldState::from_bool("rested", true),
+            cost: 1.0,
+            duration: 20.0,
+        }
+    }
+
     fn enemy_setup() -> (Vec<Goal>, Vec<Action>) {
         let goals = vec![
             Goal {
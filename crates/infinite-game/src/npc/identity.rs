@@ -0,0 +1,248 @@
+//! Persistent NPC identities
+//!
+//! A world NPC's name, look and personality are derived from its persistent
+//! key (a resident's population ledger id, or a hostile's spawn point key),
+//! an era and the world seed. The same NPC is therefore the same person every
+//! session and after loading a save. The personality traits also shape the
+//! AI character prompt.
+
+use infinite_core::{hash_seed, DetRng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::NpcRole;
+
+/// Personality traits rolled per NPC
+pub const TRAITS_PER_NPC: usize = 2;
+
+/// Broad time period an NPC lives in. Identities are stable within an era,
+/// so hopping a few years keeps the same people around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Era {
+    Ancient,
+    Medieval,
+    Modern,
+    Future,
+}
+
+impl Era {
+    pub const ALL: [Era; 4] = [Self::Ancient, Self::Medieval, Self::Modern, Self::Future];
+
+    pub fn for_year(year: i64) -> Self {
+        match year {
+            y if y < 500 => Self::Ancient,
+            y if y < 1900 => Self::Medieval,
+            y if y <= 2100 => Self::Modern,
+            _ => Self::Future,
+        }
+    }
+
+    fn given_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Ancient => &[
+                "Aru", "Nefer", "Kasha", "Talos", "Ishme", "Doros", "Meren", "Sabi",
+                "Hektor", "Zanura", "Oren", "Lysa", "Baal", "Tamar", "Kiro", "Ninsa",
+            ],
+            Self::Medieval => &[
+                "Finn", "Elara", "Rowan", "Iris", "Aldric", "Senna", "Bram", "Lila",
+                "Oswin", "Thea", "Cedric", "Mira", "Gareth", "Yara", "Dorian", "Vena",
+            ],
+            Self::Modern => &[
+                "Sam", "Maya", "Leo", "Priya", "Jonas", "Nora", "Mateo", "Hana",
+                "Owen", "Zoe", "Ravi", "Clara", "Diego", "Ingrid", "Felix", "Amara",
+            ],
+            Self::Future => &[
+                "Kaelix", "Vesper", "Nyro", "Solenne", "Jax", "Oriel", "Tessaly", "Rune",
+                "Cyra", "Daxen", "Lumi", "Zephra", "Quill", "Astra", "Vhal", "Ione",
+            ],
+        }
+    }
+
+    /// Titles put in front of the given name (empty for plain villagers)
+    fn titles(self, role: NpcRole) -> &'static [&'static str] {
+        match (role, self) {
+            (NpcRole::Villager, _) => &[],
+            (NpcRole::Guard, Self::Ancient) => &["Spearman", "Hoplite", "Watchman"],
+            (NpcRole::Guard, Self::Medieval) => &["Captain", "Sentinel", "Warden"],
+            (NpcRole::Guard, Self::Modern) => &["Officer", "Sergeant", "Deputy"],
+            (NpcRole::Guard, Self::Future) => &["Enforcer", "Marshal", "Unit"],
+            (NpcRole::Shopkeeper, Self::Ancient | Self::Medieval) => &["Merchant", "Trader", "Peddler"],
+            (NpcRole::Shopkeeper, Self::Modern) => &["Vendor", "Dealer", "Broker"],
+            (NpcRole::Shopkeeper, Self::Future) => &["Trader", "Fabricator", "Broker"],
+            (NpcRole::QuestGiver, Self::Ancient) => &["Oracle", "Priestess", "Augur"],
+            (NpcRole::QuestGiver, Self::Medieval) => &["Elder", "Sage", "Seer"],
+            (NpcRole::QuestGiver, Self::Modern) => &["Dr.", "Professor", "Curator"],
+            (NpcRole::QuestGiver, Self::Future) => &["Archivist", "Oracle", "Navigator"],
+            (NpcRole::Enemy, Self::Ancient | Self::Medieval) => &["Bandit", "Brigand", "Marauder"],
+            (NpcRole::Enemy, Self::Modern) => &["Thug", "Looter", "Smuggler"],
+            (NpcRole::Enemy, Self::Future) => &["Raider", "Scavenger", "Rogue Unit"],
+        }
+    }
+}
+
+/// A personality trait, described to the AI character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PersonalityTrait {
+    Cheerful,
+    Gruff,
+    Curious,
+    Cautious,
+    Boastful,
+    Superstitious,
+    Generous,
+    Greedy,
+    Honest,
+    Secretive,
+    Witty,
+    Melancholy,
+}
+
+impl PersonalityTrait {
+    pub const ALL: [Self; 12] = [
+        Self::Cheerful,
+        Self::Gruff,
+        Self::Curious,
+        Self::Cautious,
+        Self::Boastful,
+        Self::Superstitious,
+        Self::Generous,
+        Self::Greedy,
+        Self::Honest,
+        Self::Secretive,
+        Self::Witty,
+        Self::Melancholy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cheerful => "Cheerful",
+            Self::Gruff => "Gruff",
+            Self::Curious => "Curious",
+            Self::Cautious => "Cautious",
+            Self::Boastful => "Boastful",
+            Self::Superstitious => "Superstitious",
+            Self::Generous => "Generous",
+            Self::Greedy => "Greedy",
+            Self::Honest => "Honest",
+            Self::Secretive => "Secretive",
+            Self::Witty => "Witty",
+            Self::Melancholy => "Melancholy",
+        }
+    }
+
+    /// One sentence for the character prompt
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Cheerful => "You are upbeat and quick to laugh.",
+            Self::Gruff => "You are blunt and short with words.",
+            Self::Curious => "You ask travelers questions about where they come from.",
+            Self::Cautious => "You are wary of strangers until they prove themselves.",
+            Self::Boastful => "You like to brag about your deeds.",
+            Self::Superstitious => "You see omens everywhere and fear the time rifts.",
+            Self::Generous => "You freely share what you have.",
+            Self::Greedy => "You always look for a way to profit.",
+            Self::Honest => "You never lie, even when it would be easier.",
+            Self::Secretive => "You hint at things you know but rarely say them outright.",
+            Self::Witty => "You answer with dry jokes and wordplay.",
+            Self::Melancholy => "You often dwell on what has been lost.",
+        }
+    }
+}
+
+/// Who an NPC is: stable for a spawn point, era and world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcIdentity {
+    pub name: String,
+    pub era: Era,
+    /// Seeds the NPC's look (color variation)
+    pub appearance_seed: u64,
+    pub traits: Vec<PersonalityTrait>,
+}
+
+impl NpcIdentity {
+    /// Derive the identity of the NPC at `persistent_key` when met in `year`
+    pub fn generate(persistent_key: u64, role: NpcRole, year: i64, world_seed: u64) -> Self {
+        let era = Era::for_year(year);
+        let mut rng = DetRng::new(identity_seed(persistent_key, era, world_seed));
+
+        let given_names = era.given_names();
+        let given = given_names[rng.gen_range(0..given_names.len())];
+        let titles = era.titles(role);
+        let name = if titles.is_empty() {
+            given.to_string()
+        } else {
+            format!("{} {}", titles[rng.gen_range(0..titles.len())], given)
+        };
+
+        let mut traits = Vec::with_capacity(TRAITS_PER_NPC);
+        while traits.len() < TRAITS_PER_NPC {
+            let candidate = PersonalityTrait::ALL[rng.gen_range(0..PersonalityTrait::ALL.len())];
+            if !traits.contains(&candidate) {
+                traits.push(candidate);
+            }
+        }
+
+        Self {
+            name,
+            era,
+            appearance_seed: rng.gen(),
+            traits,
+        }
+    }
+
+    /// Vary a role color by up to +-15% per channel so NPCs of one role
+    /// don't all look identical
+    pub fn tint(&self, color: [f32; 4]) -> [f32; 4] {
+        let mut out = color;
+        for (channel, value) in out.iter_mut().take(3).enumerate() {
+            let bits = (self.appearance_seed >> (channel * 16)) & 0xFFFF;
+            let factor = 0.85 + 0.3 * bits as f32 / 65535.0;
+            *value = (*value * factor).clamp(0.0, 1.0);
+        }
+        out
+    }
+}
+
+/// Mix the identity inputs into one RNG seed
+fn identity_seed(persistent_key: u64, era: Era, world_seed: u64) -> u64 {
+    hash_seed(world_seed, &[persistent_key, era as u64])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_is_deterministic() {
+        let a = NpcIdentity::generate(42, NpcRole::Guard, 1200, 7);
+        let b = NpcIdentity::generate(42, NpcRole::Guard, 1350, 7);
+        assert_eq!(a, b, "same era should give the same person");
+        assert_eq!(a.traits.len(), TRAITS_PER_NPC);
+        assert_ne!(a.traits[0], a.traits[1]);
+    }
+
+    #[test]
+    fn test_era_and_world_change_identity() {
+        let base = NpcIdentity::generate(42, NpcRole::Villager, 1200, 7);
+        let future = NpcIdentity::generate(42, NpcRole::Villager, 3000, 7);
+        assert_eq!(future.era, Era::Future);
+        assert!(Era::Future.given_names().contains(&future.name.as_str()));
+        assert_ne!(base.appearance_seed, future.appearance_seed);
+
+        let other_world = NpcIdentity::generate(42, NpcRole::Villager, 1200, 8);
+        assert_ne!(base.appearance_seed, other_world.appearance_seed);
+    }
+
+    #[test]
+    fn test_titles_and_tint() {
+        let guard = NpcIdentity::generate(9, NpcRole::Guard, 2025, 1);
+        let title = guard.name.split_whitespace().next().unwrap();
+        assert!(Era::Modern.titles(NpcRole::Guard).contains(&title));
+
+        let color = guard.tint([0.5, 0.5, 0.5, 1.0]);
+        for value in &color[..3] {
+            assert!((0.425..=0.575).contains(value));
+        }
+        assert_eq!(color[3], 1.0);
+    }
+}
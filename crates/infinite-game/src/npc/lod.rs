@@ -0,0 +1,130 @@
+//! NPC level of detail
+//!
+//! NPCs close to the player run their full GOAP simulation every frame.
+//! Further out they tick at a reduced rate with simple wandering, and beyond
+//! that they drop to an "ambient" tier: not simulated at all and drawn as
+//! one crowd marker per chunk. Tier changes use a hysteresis band so NPCs
+//! near a boundary don't flicker between tiers, and an ambient NPC promoted
+//! back is placed somewhere along its usual wander area rather than where
+//! it froze. Reduced NPCs move once per tick, so they are drawn blending
+//! from their previous tick's transform to their latest.
+
+use glam::Vec3;
+use infinite_core::TransformHistory;
+use infinite_world::ChunkCoord;
+
+/// Simulation tier of an NPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NpcLod {
+    /// Full GOAP update every frame
+    #[default]
+    Full,
+    /// Simple wandering at a reduced tick rate
+    Reduced,
+    /// Not simulated; represented by its chunk's crowd
+    Ambient,
+}
+
+/// Distances (horizontal, from the player) and rates for the LOD tiers
+#[derive(Debug, Clone, Copy)]
+pub struct LodConfig {
+    /// NPCs within this range get the full simulation
+    pub full_radius: f32,
+    /// NPCs beyond this range are ambient
+    pub ambient_radius: f32,
+    /// Seconds between updates of reduced NPCs
+    pub reduced_interval: f32,
+    /// How far past a boundary an NPC has to move before changing tier
+    pub hysteresis: f32,
+}
+
+impl Default for LodConfig {
+    fn default() -> Self {
+        Self {
+            full_radius: 48.0,
+            ambient_radius: 128.0,
+            reduced_interval: 0.25,
+            hysteresis: 6.0,
+        }
+    }
+}
+
+impl LodConfig {
+    /// The same tiers with their radii multiplied by `scale`, for shedding
+    /// detail under memory pressure
+    pub fn scaled(&self, scale: f32) -> Self {
+        Self {
+            full_radius: self.full_radius * scale,
+            ambient_radius: self.ambient_radius * scale,
+            ..*self
+        }
+    }
+
+    /// Tier for an NPC `distance` from the player that is currently `current`
+    pub fn tier_for(&self, distance: f32, current: NpcLod) -> NpcLod {
+        // Boundaries shift outward for NPCs already inside them, so
+        // demotion happens a little further out than promotion
+        let (full, ambient) = match current {
+            NpcLod::Full => (self.full_radius + self.hysteresis, self.ambient_radius + self.hysteresis),
+            NpcLod::Reduced => (self.full_radius - self.hysteresis, self.ambient_radius + self.hysteresis),
+            NpcLod::Ambient => (self.full_radius - self.hysteresis, self.ambient_radius - self.hysteresis),
+        };
+        if distance <= full {
+            NpcLod::Full
+        } else if distance <= ambient {
+            NpcLod::Reduced
+        } else {
+            NpcLod::Ambient
+        }
+    }
+}
+
+/// Per-NPC LOD bookkeeping
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LodState {
+    pub tier: NpcLod,
+    /// Time banked since the last reduced-rate update
+    pub pending: f32,
+    /// Transform before and after the latest update
+    pub motion: TransformHistory,
+}
+
+/// Ambient NPCs of one chunk, drawn as a single crowd marker
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbientCrowd {
+    pub chunk: ChunkCoord,
+    /// Average position of the crowd's NPCs
+    pub center: Vec3,
+    pub count: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers_by_distance() {
+        let config = LodConfig::default();
+        assert_eq!(config.tier_for(10.0, NpcLod::Full), NpcLod::Full);
+        assert_eq!(config.tier_for(80.0, NpcLod::Full), NpcLod::Reduced);
+        assert_eq!(config.tier_for(300.0, NpcLod::Full), NpcLod::Ambient);
+
+        let reduced = config.scaled(0.5);
+        assert_eq!(reduced.tier_for(40.0, NpcLod::Full), NpcLod::Reduced);
+        assert_eq!(reduced.tier_for(80.0, NpcLod::Reduced), NpcLod::Ambient);
+        assert_eq!(reduced.hysteresis, config.hysteresis);
+    }
+
+    #[test]
+    fn test_hysteresis_band() {
+        let config = LodConfig::default();
+        let just_outside = config.full_radius + config.hysteresis * 0.5;
+        // Stays full while inside the band, but a reduced NPC isn't promoted
+        assert_eq!(config.tier_for(just_outside, NpcLod::Full), NpcLod::Full);
+        assert_eq!(config.tier_for(just_outside, NpcLod::Reduced), NpcLod::Reduced);
+
+        let near_ambient = config.ambient_radius - config.hysteresis * 0.5;
+        assert_eq!(config.tier_for(near_ambient, NpcLod::Ambient), NpcLod::Ambient);
+        assert_eq!(config.tier_for(near_ambient, NpcLod::Reduced), NpcLod::Reduced);
+    }
+}
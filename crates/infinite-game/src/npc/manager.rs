@@ -2,17 +2,31 @@
 
 use std::collections::{HashMap, HashSet};
 
-use glam::Vec3;
-use infinite_world::ChunkCoord;
+use glam::{Vec2, Vec3};
+use infinite_core::{Transform, TransformHistory};
+use infinite_integration::ServerCharacter;
+use infinite_world::{ChunkCoord, PopulationLedger, PopulationSaveData, Resident, Road, RoadNetwork};
 
+use super::bestiary::{EnemyProfile, KillRecord};
 use super::character_cache::NpcCharacterCache;
 use super::goap::NpcBrain;
+use super::identity::NpcIdentity;
+use super::lod::{AmbientCrowd, LodConfig, LodState, NpcLod};
 use super::npc_generator::NpcGenerator;
-use super::spawn::{compute_persistent_key, generate_spawn_points, NpcSpawnPoint};
-use super::{NpcBehaviorState, NpcFaction, NpcId, NpcInstance, NpcRole};
-use super::combat::CombatStats;
+use super::perception::{hearing_stimulus, sight_stimulus, Awareness, DetectionState, PerceptionConfig, StealthInputs};
+use super::population::PopulationConfig;
+use super::spawn::{
+    compute_persistent_key, generate_extra_spawn_points, generate_spawn_points, role_defaults, NpcSpawnPoint,
+    EXTRA_SPAWN_INDEX,
+};
+use super::{NpcBehaviorState, NpcData, NpcFaction, NpcId, NpcInstance, NpcRole};
+use super::combat::{CombatStats, ThreatSource, DAMAGE_THREAT_PER_POINT};
+use super::elite::{EliteAffix, EliteModifiers, FROZEN_AURA_RADIUS, SPLIT_COUNT, SPLIT_HP_FRACTION};
+use crate::balance;
 use crate::combat::damage::AttackType;
 use crate::combat::element::Element;
+use crate::combat::status::{StatusEffect, StatusEffectType, StatusManager};
+use crate::seat::{Occupant, RestPose, SeatRegistry};
 
 /// Pending damage event from an NPC to the player
 #[derive(Debug, Clone)]
@@ -36,6 +50,25 @@ pub struct DamageNpcResult {
     pub role: NpcRole,
     /// Whether the NPC's original faction was Friendly or Neutral
     pub was_friendly: bool,
+    /// XP and gold multiplier (above 1.0 for elites)
+    pub reward_multiplier: f32,
+    /// Whether a Splitting elite broke into fragments on defeat
+    pub split: bool,
+    /// Bestiary record of a defeated hostile NPC
+    pub kill: Option<KillRecord>,
+}
+
+impl DamageNpcResult {
+    fn new(defeated: bool, role: NpcRole, was_friendly: bool) -> Self {
+        Self {
+            defeated,
+            role,
+            was_friendly,
+            reward_multiplier: 1.0,
+            split: false,
+            kill: None,
+        }
+    }
 }
 
 /// Manages all active NPC instances
@@ -43,12 +76,26 @@ pub struct NpcManager {
     npcs: HashMap<NpcId, NpcInstance>,
     next_id: u64,
     chunk_size: f32,
+    /// World seed NPC identities are derived from
+    world_seed: u64,
+    /// Year NPCs were last spawned for (respawns reuse it)
+    spawn_year: i64,
+    /// Who lives where in which year; world NPCs spawn from it
+    pub population: PopulationLedger,
+    /// Ledger entries of spawned residents (non-hostile world NPCs)
+    residents: HashMap<NpcId, Resident>,
     /// Combat stats for NPCs that have them
     pub combat_stats: HashMap<NpcId, CombatStats>,
     /// NPCs provoked by the player (attack triggered hostility)
     provoked_npcs: HashSet<NpcId>,
     /// Respawn timers: chunk coord → list of (spawn point index, timer remaining)
     respawn_timers: Vec<(ChunkCoord, usize, f32)>,
+    /// Density, hostile cap and ambient life that chunk spawns follow
+    population_config: PopulationConfig,
+    /// Chunks loaded and not yet unloaded
+    loaded_chunks: HashSet<ChunkCoord>,
+    /// Spawn point index of NPCs spawned from chunk spawn tables
+    chunk_spawns: HashMap<NpcId, usize>,
     /// Cache of server characters keyed by persistent_key
     pub character_cache: NpcCharacterCache,
     /// Lazy NPC character generator
@@ -57,6 +104,121 @@ pub struct NpcManager {
     pending_player_damage: Vec<PendingPlayerDamage>,
     /// Track which NPCs have landed their attack this frame (to prevent double-hits)
     attack_landed: std::collections::HashSet<NpcId>,
+    /// NPCs spawned directly (training dummies, arena waves) — never respawn
+    custom_spawns: HashSet<NpcId>,
+    /// NPCs that take hits without losing HP (training dummies)
+    invulnerable: HashSet<NpcId>,
+    /// How aware each NPC is of the player (stealth)
+    awareness: HashMap<NpcId, Awareness>,
+    /// Sense ranges used for all NPCs
+    pub perception: PerceptionConfig,
+    /// NPCs can neither sense nor target the player (GM invisibility)
+    pub player_hidden: bool,
+    /// Elite affixes of elite enemies
+    elites: HashMap<NpcId, EliteModifiers>,
+    /// Status effects on NPCs (poisoned, burning, shocked by coated weapons)
+    statuses: HashMap<NpcId, StatusManager>,
+    /// Noises NPCs are walking over to check out
+    investigations: HashMap<NpcId, Investigation>,
+    /// Distances and tick rate of the simulation LOD tiers
+    pub lod_config: LodConfig,
+    /// Current LOD tier of each NPC
+    lod: HashMap<NpcId, LodState>,
+    /// Roads townsfolk keep to when wandering
+    roads: Option<RoadNetwork>,
+    /// Roads crossing each loaded chunk
+    nearby_roads: HashMap<ChunkCoord, Vec<Road>>,
+    /// Benches, chairs and walls, shared with the player
+    pub seats: SeatRegistry,
+    /// How long each seated NPC has been sitting
+    rest_timers: HashMap<NpcId, f32>,
+    /// Time until an NPC that got up wants to sit again
+    rest_cooldowns: HashMap<NpcId, f32>,
+}
+
+/// Spawn points of a chunk that are households (everything but hostiles)
+fn household_points(coord: ChunkCoord, chunk_size: f32) -> impl Iterator<Item = NpcSpawnPoint> {
+    generate_spawn_points(coord.x, coord.z, chunk_size)
+        .into_iter()
+        .filter(|p| p.data.faction != NpcFaction::Hostile)
+}
+
+/// Bytes allocated for a map's entries
+fn map_bytes<K, V>(map: &HashMap<K, V>) -> usize {
+    map.capacity() * (std::mem::size_of::<K>() + std::mem::size_of::<V>())
+}
+
+/// How far beyond its wander area an NPC will go to walk along a road
+const ROAD_REACH: f32 = 20.0;
+/// An NPC closer than this to a road's centreline is walking on it
+const ON_ROAD: f32 = 1.5;
+/// How fast a talking NPC turns to face the player (radians per second)
+const TALK_TURN_RATE: f32 = 6.0;
+/// How long an NPC stays on a seat
+const NPC_REST_TIME: f32 = 20.0;
+/// How long after getting up before an NPC sits again
+const NPC_REST_COOLDOWN: f32 = 60.0;
+/// How often a hostile held back by the population cap checks for room
+const CAPPED_RESPAWN_RETRY: f32 = 10.0;
+
+/// Turn `yaw` towards `target` by at most `max_step`, the short way round
+fn turn_towards(yaw: f32, target: f32, max_step: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    let diff = (target - yaw + PI).rem_euclid(TAU) - PI;
+    yaw + diff.clamp(-max_step, max_step)
+}
+
+/// Turn an NPC towards `target` at the talking turn rate (same yaw
+/// convention as walking NPCs)
+fn face(npc: &mut NpcInstance, target: Vec3, delta: f32) {
+    let dir = Vec3::new(target.x - npc.position.x, 0.0, target.z - npc.position.z);
+    if dir.length_squared() > 1e-4 {
+        npc.yaw = turn_towards(npc.yaw, dir.z.atan2(dir.x), TALK_TURN_RATE * delta);
+    }
+}
+
+/// Nearest road to `position` among those crossing `chunk`: the closest
+/// centreline point, the road's direction there, and the distance
+fn nearest_road(roads: &HashMap<ChunkCoord, Vec<Road>>, chunk: ChunkCoord, position: Vec3) -> Option<(Vec2, Vec2, f32)> {
+    let point = Vec2::new(position.x, position.z);
+    roads
+        .get(&chunk)?
+        .iter()
+        .filter_map(|road| road.nearest(point))
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+}
+
+/// Heading for a wandering NPC that keeps to a road through its wander
+/// area: towards the road while off it, then along it (whichever way is
+/// closer to `wish`). None when no road is in reach of home.
+fn road_heading(
+    roads: &HashMap<ChunkCoord, Vec<Road>>,
+    chunk: ChunkCoord,
+    position: Vec3,
+    home: Vec3,
+    wander_radius: f32,
+    wish: Vec3,
+) -> Option<Vec3> {
+    let (point, direction, distance) = nearest_road(roads, chunk, position)?;
+    if point.distance(Vec2::new(home.x, home.z)) > wander_radius + ROAD_REACH {
+        return None;
+    }
+    let heading = if distance > ON_ROAD {
+        Vec2::new(point.x - position.x, point.y - position.z).normalize_or_zero()
+    } else if direction.dot(Vec2::new(wish.x, wish.z)) >= 0.0 {
+        direction
+    } else {
+        -direction
+    };
+    Some(Vec3::new(heading.x, 0.0, heading.y))
+}
+
+/// A noise an NPC heard and is going to check out
+#[derive(Debug, Clone, Copy)]
+struct Investigation {
+    target: Vec3,
+    /// Seconds left looking around once there
+    linger: f32,
 }
 
 impl NpcManager {
@@ -65,31 +227,89 @@ impl NpcManager {
             npcs: HashMap::new(),
             next_id: 1,
             chunk_size,
+            world_seed: 0,
+            spawn_year: 0,
+            population: PopulationLedger::new(0),
+            residents: HashMap::new(),
             combat_stats: HashMap::new(),
             provoked_npcs: HashSet::new(),
             respawn_timers: Vec::new(),
+            population_config: PopulationConfig::default(),
+            loaded_chunks: HashSet::new(),
+            chunk_spawns: HashMap::new(),
             character_cache: NpcCharacterCache::new(),
             npc_generator: NpcGenerator::new(),
             pending_player_damage: Vec::new(),
             attack_landed: std::collections::HashSet::new(),
+            custom_spawns: HashSet::new(),
+            invulnerable: HashSet::new(),
+            awareness: HashMap::new(),
+            perception: PerceptionConfig::default(),
+            player_hidden: false,
+            elites: HashMap::new(),
+            statuses: HashMap::new(),
+            investigations: HashMap::new(),
+            lod_config: LodConfig::default(),
+            lod: HashMap::new(),
+            roads: None,
+            nearby_roads: HashMap::new(),
+            seats: SeatRegistry::new(),
+            rest_timers: HashMap::new(),
+            rest_cooldowns: HashMap::new(),
         }
     }
 
+    /// Derive NPC identities from `seed`, so each world has its own people
+    pub fn with_world_seed(mut self, seed: u64) -> Self {
+        self.world_seed = seed;
+        self.population = PopulationLedger::new(seed);
+        self
+    }
+
+    /// Have non-hostile NPCs walk along nearby roads (chunks loaded from
+    /// now on)
+    pub fn with_roads(mut self, roads: RoadNetwork) -> Self {
+        self.roads = Some(roads);
+        self
+    }
+
+    /// Spawn chunks by these population settings
+    pub fn with_population(mut self, config: PopulationConfig) -> Self {
+        self.population_config = config;
+        self
+    }
+
     fn next_npc_id(&mut self) -> NpcId {
         let id = NpcId(self.next_id);
         self.next_id += 1;
         id
     }
 
-    /// Called when a chunk finishes loading. Spawns NPCs for that chunk.
+    /// Called when a chunk finishes loading. Spawns NPCs for that chunk:
+    /// hostiles from its spawn points, and whoever the population ledger
+    /// says lives there in `active_year` (including residents who moved in
+    /// from nearby chunks).
     pub fn on_chunk_loaded(
         &mut self,
         coord: ChunkCoord,
         active_year: i64,
         height_fn: impl Fn(f32, f32) -> f32,
     ) {
-        let spawn_points = generate_spawn_points(coord.x, coord.z, self.chunk_size);
+        self.spawn_year = active_year;
+        if let Some(roads) = &self.roads {
+            self.nearby_roads.insert(coord, roads.roads_near(coord, ROAD_REACH));
+        }
+        self.loaded_chunks.insert(coord);
+        self.populate_chunk(coord, &height_fn);
+    }
+
+    /// Spawn everyone the population settings allow in a chunk who isn't
+    /// there already
+    fn populate_chunk(&mut self, coord: ChunkCoord, height_fn: &impl Fn(f32, f32) -> f32) {
+        let active_year = self.spawn_year;
         let origin = coord.world_origin(self.chunk_size);
+        let mut spawn_points = generate_spawn_points(coord.x, coord.z, self.chunk_size);
+        spawn_points.extend(generate_extra_spawn_points(coord.x, coord.z, self.chunk_size));
 
         for point in &spawn_points {
             if let Some((min_year, max_year)) = point.year_range {
@@ -97,16 +317,139 @@ impl NpcManager {
                     continue;
                 }
             }
-            self.spawn_npc(coord, point, origin, &height_fn);
+            let slot = compute_persistent_key(coord.x, coord.z, point.spawn_index);
+            let extra = point.spawn_index >= EXTRA_SPAWN_INDEX;
+            if point.data.faction == NpcFaction::Hostile || extra {
+                // Visitors and monsters: nobody's household
+                if !self.point_taken(coord, point.spawn_index, slot)
+                    && self.population_config.allows(point.data.role, slot, extra)
+                    && (point.data.faction != NpcFaction::Hostile
+                        || self.population_config.room_for_hostile(self.hostile_count()))
+                {
+                    self.spawn_npc(coord, point, origin, height_fn, None);
+                }
+                continue;
+            }
+            if let Some(resident) = self.population.occupant(slot, coord, active_year) {
+                if self.may_spawn_resident(point.data.role, &resident) {
+                    self.spawn_npc(coord, point, origin, height_fn, Some(resident));
+                }
+            }
+        }
+
+        let chunk_size = self.chunk_size;
+        let migrants = self.population.migrants_into(coord, active_year, |home| {
+            household_points(home, chunk_size)
+                .map(|p| compute_persistent_key(home.x, home.z, p.spawn_index))
+                .collect()
+        });
+        for resident in migrants {
+            let home = resident.home;
+            let point = household_points(home, chunk_size)
+                .find(|p| compute_persistent_key(home.x, home.z, p.spawn_index) == resident.slot);
+            if let Some(point) = point.filter(|p| self.may_spawn_resident(p.data.role, &resident)) {
+                self.spawn_npc(coord, &point, origin, height_fn, Some(resident));
+            }
         }
     }
 
+    /// Whether a chunk spawn point already has its NPC, or is waiting to
+    /// respawn it
+    fn point_taken(&self, coord: ChunkCoord, spawn_index: usize, key: u64) -> bool {
+        self.npcs.values().any(|npc| npc.persistent_key == key)
+            || self.respawn_timers.iter().any(|(c, i, _)| *c == coord && *i == spawn_index)
+    }
+
+    fn may_spawn_resident(&self, role: NpcRole, resident: &Resident) -> bool {
+        self.population_config.allows(role, resident.id, false)
+            && !self.residents.values().any(|r| r.id == resident.id)
+    }
+
+    /// Hostile NPCs alive, custom spawns included
+    pub fn hostile_count(&self) -> usize {
+        self.npcs.values().filter(|npc| npc.data.faction == NpcFaction::Hostile).count()
+    }
+
+    /// Population settings chunk spawns follow
+    pub fn population_config(&self) -> PopulationConfig {
+        self.population_config
+    }
+
+    /// Change the population settings, applied to the loaded world at once:
+    /// chunk NPCs the new settings rule out leave (unless busy with the
+    /// player), the newest hostiles first when over the cap, and loaded
+    /// chunks fill up with whoever the settings now allow
+    pub fn set_population_config(&mut self, config: PopulationConfig, height_fn: impl Fn(f32, f32) -> f32) {
+        self.population_config = config;
+        let leaving: Vec<NpcId> = self
+            .chunk_spawns
+            .iter()
+            .filter(|(id, index)| {
+                self.npcs.get(id).is_some_and(|npc| {
+                    !config.allows(npc.data.role, npc.persistent_key, **index >= EXTRA_SPAWN_INDEX)
+                }) && !self.is_engaged(**id)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in leaving {
+            self.despawn(id);
+        }
+
+        if let Some(max) = config.max_hostiles {
+            let mut hostiles: Vec<NpcId> = self
+                .chunk_spawns
+                .keys()
+                .copied()
+                .filter(|id| {
+                    self.npcs.get(id).is_some_and(|npc| npc.data.faction == NpcFaction::Hostile) && !self.is_engaged(*id)
+                })
+                .collect();
+            hostiles.sort_by_key(|id| std::cmp::Reverse(id.0));
+            let excess = self.hostile_count().saturating_sub(max);
+            for id in hostiles.into_iter().take(excess) {
+                self.despawn(id);
+            }
+        }
+
+        let mut chunks: Vec<ChunkCoord> = self.loaded_chunks.iter().copied().collect();
+        chunks.sort_by_key(|c| (c.x, c.z));
+        for coord in chunks {
+            self.populate_chunk(coord, &height_fn);
+        }
+    }
+
+    /// Restore the player's changes to the population from a save, removing
+    /// spawned residents who are dead in the loaded history
+    pub fn load_population(&mut self, data: PopulationSaveData) {
+        self.population.load_save_data(data);
+        let year = self.spawn_year;
+        let dead: Vec<NpcId> = self
+            .residents
+            .iter()
+            .filter(|(_, r)| {
+                self.population
+                    .resident(r.slot, r.home, r.generation)
+                    .is_none_or(|now| !now.alive_in(year))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in dead {
+            self.despawn(id);
+        }
+    }
+
+    /// Ledger entry of a resident NPC (None for hostiles and custom spawns)
+    pub fn resident(&self, id: NpcId) -> Option<&Resident> {
+        self.residents.get(&id)
+    }
+
     fn spawn_npc(
         &mut self,
         coord: ChunkCoord,
         point: &NpcSpawnPoint,
         chunk_origin: Vec3,
         height_fn: &impl Fn(f32, f32) -> f32,
+        resident: Option<Resident>,
     ) -> NpcId {
         let id = self.next_npc_id();
         let world_x = chunk_origin.x + point.offset.x;
@@ -120,7 +463,15 @@ impl NpcManager {
         let brain = NpcBrain::for_role(data.role);
 
         let role = data.role;
-        let persistent_key = compute_persistent_key(coord.x, coord.z, point.spawn_index);
+        // Residents are keyed (and named for their birth era) per person,
+        // so an heir or a migrant is somebody new
+        let (persistent_key, identity_year) = match &resident {
+            Some(resident) => (resident.id, resident.born),
+            None => (compute_persistent_key(coord.x, coord.z, point.spawn_index), self.spawn_year),
+        };
+        let identity = NpcIdentity::generate(persistent_key, role, identity_year, self.world_seed);
+        data.name = identity.name.clone();
+        data.color = identity.tint(data.color);
 
         let instance = NpcInstance {
             id,
@@ -132,16 +483,235 @@ impl NpcManager {
             state: NpcBehaviorState::Idle { timer: 2.0 },
             brain: Some(brain),
             persistent_key,
+            identity: Some(identity),
         };
 
         self.npcs.insert(id, instance);
+        self.chunk_spawns.insert(id, point.spawn_index);
         self.combat_stats.insert(id, CombatStats::for_role(role));
+        if let Some(resident) = resident {
+            self.residents.insert(id, resident);
+        }
+        if role == NpcRole::Enemy {
+            if let Some(elite) = EliteModifiers::roll(persistent_key) {
+                self.make_elite(id, elite);
+            }
+        }
+
+        id
+    }
+
+    /// Turn an NPC into an elite: scale its stats and tint it
+    pub fn make_elite(&mut self, id: NpcId, mut elite: EliteModifiers) {
+        if let Some(stats) = self.combat_stats.get_mut(&id) {
+            elite.apply_to_stats(stats);
+        }
+        if let Some(npc) = self.npcs.get_mut(&id) {
+            npc.data.color = elite.tint(npc.data.color);
+        }
+        self.elites.insert(id, elite);
+    }
+
+    /// Elite affixes of an NPC (None for regular NPCs)
+    pub fn elite(&self, id: NpcId) -> Option<&EliteModifiers> {
+        self.elites.get(&id)
+    }
+
+    /// Put a status effect of `element` on an NPC. NPCs of the same element
+    /// resist it outright (returns false); otherwise it lasts longer on
+    /// NPCs weak to the element and shorter on those strong against it.
+    pub fn apply_status(&mut self, id: NpcId, mut effect: StatusEffect, element: Element) -> bool {
+        let Some(stats) = self.combat_stats.get(&id) else {
+            return false;
+        };
+        if element != Element::Physical && stats.element == element {
+            return false;
+        }
+        effect.duration *= element.multiplier_against(stats.element);
+        self.statuses.entry(id).or_default().apply(effect);
+        true
+    }
+
+    /// Status effects on an NPC, if any
+    pub fn statuses(&self, id: NpcId) -> Option<&StatusManager> {
+        self.statuses.get(&id).filter(|statuses| statuses.count() > 0)
+    }
+
+    /// Tick NPC status effects. Returns the damage-over-time each NPC took
+    /// this frame and the effect that dealt it, for the caller to apply.
+    pub fn update_statuses(&mut self, delta: f32) -> Vec<(NpcId, f32, StatusEffectType)> {
+        let npcs = &self.npcs;
+        self.statuses.retain(|id, statuses| npcs.contains_key(id) && statuses.count() > 0);
+        let mut ticks = Vec::new();
+        for (id, statuses) in &mut self.statuses {
+            let source = statuses.effects.iter().find(|e| e.damage_per_tick > 0.0).map(|e| e.effect_type);
+            let damage = statuses.update(delta);
+            if let Some(source) = source.filter(|_| damage > 0.0) {
+                ticks.push((*id, damage, source));
+            }
+        }
+        ticks
+    }
+
+    /// Whether `pos` is inside any Frozen elite's aura
+    pub fn in_frozen_aura(&self, pos: Vec3) -> bool {
+        self.elites
+            .iter()
+            .filter(|(_, elite)| elite.has(EliteAffix::FrozenAura))
+            .filter_map(|(id, _)| self.npcs.get(id))
+            .any(|npc| (npc.position - pos).length() < FROZEN_AURA_RADIUS)
+    }
+
+    /// An NPC's attack hit the player for `damage`. Vampiric elites heal from
+    /// it; returns the amount healed.
+    pub fn on_npc_hit_player(&mut self, id: NpcId, damage: f32) -> f32 {
+        let heal = self.elites.get(&id).map(|e| e.lifesteal(damage)).unwrap_or(0.0);
+        if heal <= 0.0 {
+            return 0.0;
+        }
+        match self.combat_stats.get_mut(&id) {
+            Some(stats) => {
+                let before = stats.current_hp;
+                stats.current_hp = (stats.current_hp + heal).min(stats.max_hp);
+                stats.current_hp - before
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Spawn the fragments of a defeated Splitting elite around `position`
+    fn split_elite(&mut self, data: &NpcData, position: Vec3, elite_max_hp: f32) {
+        for i in 0..SPLIT_COUNT {
+            let angle = i as f32 / SPLIT_COUNT as f32 * std::f32::consts::TAU;
+            let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * 1.5;
+            let mut fragment = data.clone();
+            fragment.name = format!("{} Fragment", data.name);
+            fragment.color = NpcRole::Enemy.color();
+            let mut stats = CombatStats::default_enemy();
+            stats.max_hp = elite_max_hp * SPLIT_HP_FRACTION;
+            stats.current_hp = stats.max_hp;
+            let id = self.spawn_custom(fragment, position + offset, stats, true);
+            // Fragments come out fighting
+            self.provoke_npc(id);
+        }
+    }
+
+    /// Spawn an NPC outside the chunk spawn tables (training dummies, arena
+    /// waves). These NPCs do not respawn when defeated. Without a brain they
+    /// fall back to simple wandering within `data.wander_radius`.
+    pub fn spawn_custom(
+        &mut self,
+        mut data: NpcData,
+        position: Vec3,
+        stats: CombatStats,
+        with_brain: bool,
+    ) -> NpcId {
+        let id = self.next_npc_id();
+        data.home_position = position;
+        let brain = with_brain.then(|| NpcBrain::for_role(data.role));
+
+        let instance = NpcInstance {
+            id,
+            data,
+            position,
+            velocity: Vec3::ZERO,
+            yaw: 0.0,
+            chunk: ChunkCoord::from_world_pos(position, self.chunk_size),
+            state: NpcBehaviorState::Idle { timer: 2.0 },
+            brain,
+            // Outside the range of chunk-derived keys
+            persistent_key: u64::MAX - id.0,
+            identity: None,
+        };
 
+        self.npcs.insert(id, instance);
+        self.combat_stats.insert(id, stats);
+        self.custom_spawns.insert(id);
+        id
+    }
+
+    /// Spawn an NPC from a server character template (admin tools). The
+    /// template is its AI dialogue character, so talking to it needs no
+    /// generation round trip.
+    pub fn spawn_from_template(&mut self, character: ServerCharacter, role: NpcRole, position: Vec3) -> NpcId {
+        let (faction, wander_radius) = role_defaults(role);
+        let data = NpcData {
+            name: character.name.clone(),
+            role,
+            faction,
+            home_position: position,
+            wander_radius,
+            interaction_radius: 3.0,
+            color: role.color(),
+            server_character_id: Some(character.id.clone()),
+        };
+        let id = self.spawn_custom(data, position, CombatStats::for_role(role), true);
+        if let Some(npc) = self.npcs.get(&id) {
+            self.character_cache.set_ready(npc.persistent_key, character);
+        }
         id
     }
 
+    /// Spawn a generic NPC of `role` (GM tools)
+    pub fn spawn_role(&mut self, role: NpcRole, position: Vec3) -> NpcId {
+        let (faction, wander_radius) = role_defaults(role);
+        let data = NpcData {
+            name: role.name().to_string(),
+            role,
+            faction,
+            home_position: position,
+            wander_radius,
+            interaction_radius: if role == NpcRole::Enemy { 0.0 } else { 3.0 },
+            color: role.color(),
+            server_character_id: None,
+        };
+        self.spawn_custom(data, position, CombatStats::for_role(role), true)
+    }
+
+    /// Remove an NPC immediately (no respawn)
+    pub fn despawn(&mut self, id: NpcId) {
+        if let Some(npc) = self.npcs.remove(&id) {
+            self.character_cache.clear_key(npc.persistent_key);
+        }
+        self.combat_stats.remove(&id);
+        self.provoked_npcs.remove(&id);
+        self.custom_spawns.remove(&id);
+        self.invulnerable.remove(&id);
+        self.elites.remove(&id);
+        self.statuses.remove(&id);
+        self.investigations.remove(&id);
+        self.residents.remove(&id);
+        self.chunk_spawns.remove(&id);
+        self.lod.remove(&id);
+        self.forget_rest(id);
+    }
+
+    /// Free an NPC's seat and forget its rest timers
+    fn forget_rest(&mut self, id: NpcId) {
+        self.seats.release(Occupant::Npc(id));
+        self.rest_timers.remove(&id);
+        self.rest_cooldowns.remove(&id);
+    }
+
+    /// How an NPC is resting, once it has sat down
+    pub fn rest_pose(&self, id: NpcId) -> Option<RestPose> {
+        self.rest_timers.get(&id)?;
+        self.seats.seat_of(Occupant::Npc(id)).map(|(seat, _)| seat.kind.pose())
+    }
+
+    /// Make an NPC ignore damage (it still registers hits)
+    pub fn set_invulnerable(&mut self, id: NpcId, invulnerable: bool) {
+        if invulnerable {
+            self.invulnerable.insert(id);
+        } else {
+            self.invulnerable.remove(&id);
+        }
+    }
+
     /// Called when a chunk is unloaded. Removes all NPCs from that chunk.
     pub fn on_chunk_unloaded(&mut self, coord: ChunkCoord) {
+        self.nearby_roads.remove(&coord);
+        self.loaded_chunks.remove(&coord);
         let to_remove: Vec<(NpcId, u64)> = self
             .npcs
             .values()
@@ -152,6 +722,15 @@ impl NpcManager {
             self.npcs.remove(id);
             self.combat_stats.remove(id);
             self.provoked_npcs.remove(id);
+            self.custom_spawns.remove(id);
+            self.invulnerable.remove(id);
+            self.elites.remove(id);
+            self.statuses.remove(id);
+            self.investigations.remove(id);
+            self.residents.remove(id);
+            self.chunk_spawns.remove(id);
+            self.lod.remove(id);
+            self.forget_rest(*id);
             self.character_cache.clear_key(*key);
         }
     }
@@ -176,27 +755,149 @@ impl NpcManager {
             }
         });
 
-        // Process respawns
-        for (coord, _idx) in respawns_ready {
-            let points = generate_spawn_points(coord.x, coord.z, chunk_size);
-            if let Some(point) = points.get(_idx) {
-                let origin = coord.world_origin(chunk_size);
-                self.spawn_npc(coord, point, origin, &height_fn);
+        // Process respawns, unless the population settings have since
+        // ruled the point out; capped hostiles wait for room
+        for (coord, index) in respawns_ready {
+            let points = if index >= EXTRA_SPAWN_INDEX {
+                generate_extra_spawn_points(coord.x, coord.z, chunk_size)
+            } else {
+                generate_spawn_points(coord.x, coord.z, chunk_size)
+            };
+            let Some(point) = points.iter().find(|p| p.spawn_index == index) else { continue };
+            let key = compute_persistent_key(coord.x, coord.z, index);
+            if !self.population_config.allows(point.data.role, key, index >= EXTRA_SPAWN_INDEX) {
+                continue;
+            }
+            if point.data.faction == NpcFaction::Hostile && !self.population_config.room_for_hostile(self.hostile_count()) {
+                self.respawn_timers.push((coord, index, CAPPED_RESPAWN_RETRY));
+                continue;
             }
+            let origin = coord.world_origin(chunk_size);
+            self.spawn_npc(coord, point, origin, &height_fn, None);
+        }
+
+        for stats in self.combat_stats.values_mut() {
+            stats.poise.update(delta);
+        }
+        for elite in self.elites.values_mut() {
+            elite.update(delta);
         }
 
         // Collect NPC ids for iteration (avoid borrow issues)
         let ids: Vec<NpcId> = self.npcs.keys().copied().collect();
+        self.lod.retain(|id, _| self.npcs.contains_key(id));
 
         for id in ids {
-            // Try GOAP brain first
-            let has_brain = self.npcs.get(&id).map(|n| n.brain.is_some()).unwrap_or(false);
-            if has_brain {
-                self.update_npc_goap(id, delta, player_pos, &height_fn);
+            let Some(distance) = self.npcs.get(&id).map(|n| (n.position - player_pos).with_y(0.0).length()) else {
+                continue;
+            };
+            let mut state = self.lod.get(&id).copied().unwrap_or_default();
+            let previous = state.tier;
+            state.tier = if self.is_engaged(id) {
+                NpcLod::Full
             } else {
-                self.update_npc_simple(id, delta, player_pos, &height_fn);
+                self.lod_config.tier_for(distance, previous)
+            };
+            if previous == NpcLod::Ambient && state.tier != NpcLod::Ambient {
+                self.promote_from_ambient(id, &height_fn);
             }
+
+            let before = self.npcs.get(&id).map(NpcInstance::transform).unwrap_or_default();
+            let mut stepped = true;
+            match state.tier {
+                NpcLod::Full => {
+                    state.pending = 0.0;
+                    // Try GOAP brain first
+                    let has_brain = self.npcs.get(&id).map(|n| n.brain.is_some()).unwrap_or(false);
+                    if has_brain {
+                        self.update_npc_goap(id, delta, player_pos, &height_fn);
+                    } else {
+                        self.update_npc_simple(id, delta, player_pos, &height_fn);
+                    }
+                }
+                NpcLod::Reduced => {
+                    state.pending += delta;
+                    if state.pending >= self.lod_config.reduced_interval {
+                        let step = std::mem::take(&mut state.pending);
+                        self.update_npc_simple(id, step, player_pos, &height_fn);
+                    } else {
+                        stepped = false;
+                    }
+                }
+                NpcLod::Ambient => {
+                    state.pending = 0.0;
+                    if let Some(npc) = self.npcs.get_mut(&id) {
+                        npc.velocity = Vec3::ZERO;
+                    }
+                }
+            }
+            if stepped {
+                let after = self.npcs.get(&id).map(NpcInstance::transform).unwrap_or_default();
+                state.motion = TransformHistory { previous: before, current: after };
+            }
+            self.lod.insert(id, state);
+        }
+    }
+
+    /// NPCs busy with the player (fighting, chasing, investigating or
+    /// talking) keep the full simulation at any distance
+    fn is_engaged(&self, id: NpcId) -> bool {
+        self.provoked_npcs.contains(&id)
+            || self.investigations.contains_key(&id)
+            || self.combat_stats.get(&id).is_some_and(|s| s.threat.target().is_some())
+            || self.awareness.get(&id).is_some_and(|a| a.state() != DetectionState::Unaware)
+            || self.npcs.get(&id).is_some_and(|n| matches!(n.state, NpcBehaviorState::Talking))
+    }
+
+    /// An ambient NPC wasn't simulated while away, so put it somewhere in
+    /// its wander area (varying with where it froze) instead of resuming
+    /// exactly where it stopped
+    fn promote_from_ambient(&mut self, id: NpcId, height_fn: &impl Fn(f32, f32) -> f32) {
+        let Some(npc) = self.npcs.get_mut(&id) else { return };
+        let home = npc.data.home_position;
+        let seed = npc.id.0 as f32 * 2.399 + npc.position.x * 0.37 + npc.position.z * 0.11;
+        let angle = seed % std::f32::consts::TAU;
+        let dist = npc.data.wander_radius * (0.3 + 0.4 * (seed * 0.5).sin().abs());
+        let x = home.x + angle.cos() * dist;
+        let z = home.z + angle.sin() * dist;
+        npc.position = Vec3::new(x, height_fn(x, z) + 0.9, z);
+        npc.velocity = Vec3::ZERO;
+        npc.state = NpcBehaviorState::Idle { timer: 1.0 };
+    }
+
+    /// Simulation tier of an NPC (Full until its first update)
+    pub fn lod(&self, id: NpcId) -> NpcLod {
+        self.lod.get(&id).map(|s| s.tier).unwrap_or_default()
+    }
+
+    /// Where to draw an NPC. Reduced-rate NPCs are drawn blending from their
+    /// previous update to their latest by the time banked towards the next,
+    /// so they glide instead of jumping once per tick; an NPC moved outside
+    /// an update is drawn where it is.
+    pub fn render_transform(&self, npc: &NpcInstance) -> Transform {
+        let live = npc.transform();
+        match self.lod.get(&npc.id) {
+            Some(state) if state.tier == NpcLod::Reduced && state.motion.current == live => {
+                state.motion.interpolate(state.pending / self.lod_config.reduced_interval)
+            }
+            _ => live,
+        }
+    }
+
+    /// Ambient NPCs grouped per chunk, for drawing one crowd marker each
+    pub fn ambient_crowds(&self) -> Vec<AmbientCrowd> {
+        let mut crowds: HashMap<ChunkCoord, (Vec3, usize)> = HashMap::new();
+        for npc in self.npcs.values().filter(|n| self.lod(n.id) == NpcLod::Ambient) {
+            let entry = crowds.entry(npc.chunk).or_insert((Vec3::ZERO, 0));
+            entry.0 += npc.position;
+            entry.1 += 1;
         }
+        let mut crowds: Vec<AmbientCrowd> = crowds
+            .into_iter()
+            .map(|(chunk, (sum, count))| AmbientCrowd { chunk, center: sum / count as f32, count })
+            .collect();
+        crowds.sort_by_key(|c| (c.chunk.x, c.chunk.z));
+        crowds
     }
 
     /// Simple state machine update (fallback when no GOAP brain)
@@ -204,9 +905,10 @@ impl NpcManager {
         &mut self,
         id: NpcId,
         delta: f32,
-        _player_pos: Vec3,
+        player_pos: Vec3,
         height_fn: &impl Fn(f32, f32) -> f32,
     ) {
+        let roads = &self.nearby_roads;
         let npc = match self.npcs.get_mut(&id) {
             Some(n) => n,
             None => return,
@@ -223,11 +925,19 @@ impl NpcManager {
                     // Pick a random-ish wander target using a simple deterministic approach
                     let angle = (npc.id.0 as f32 * 1.618 + npc.position.x * 0.1) % std::f32::consts::TAU;
                     let dist = wander_radius * 0.5;
-                    let target = Vec3::new(
+                    let mut target = Vec3::new(
                         home.x + angle.cos() * dist,
                         0.0,
                         home.z + angle.sin() * dist,
                     );
+                    // Townsfolk stroll to the nearest stretch of road instead
+                    if npc.data.faction != NpcFaction::Hostile {
+                        if let Some((point, _, _)) = nearest_road(roads, npc.chunk, target) {
+                            if point.distance(Vec2::new(home.x, home.z)) <= wander_radius + ROAD_REACH {
+                                target = Vec3::new(point.x, 0.0, point.y);
+                            }
+                        }
+                    }
                     let target_y = height_fn(target.x, target.z) + 0.9;
                     npc.state = NpcBehaviorState::Walking {
                         target: Vec3::new(target.x, target_y, target.z),
@@ -243,7 +953,8 @@ impl NpcManager {
                 } else {
                     let dir = Vec3::new(to_target.x, 0.0, to_target.z).normalize();
                     npc.velocity = dir * speed;
-                    npc.position += npc.velocity * delta;
+                    // Don't overshoot on the long steps of reduced-rate updates
+                    npc.position += dir * (speed * delta).min(horizontal_dist);
                     // Snap to terrain
                     npc.position.y = height_fn(npc.position.x, npc.position.z) + 0.9;
                     npc.yaw = dir.z.atan2(dir.x);
@@ -251,6 +962,7 @@ impl NpcManager {
             }
             NpcBehaviorState::Talking => {
                 npc.velocity = Vec3::ZERO;
+                face(npc, player_pos, delta);
             }
         }
     }
@@ -263,27 +975,121 @@ impl NpcManager {
         player_pos: Vec3,
         height_fn: &impl Fn(f32, f32) -> f32,
     ) {
+        // Staggered NPCs stand still until they recover
+        if self.combat_stats.get(&id).is_some_and(|s| s.poise.is_staggered()) {
+            if let Some(npc) = self.npcs.get_mut(&id) {
+                npc.velocity = Vec3::ZERO;
+            }
+            return;
+        }
+
+        // Talking NPCs stop what they're doing and turn to face the player
+        if let Some(npc) = self.npcs.get_mut(&id).filter(|n| matches!(n.state, NpcBehaviorState::Talking)) {
+            npc.velocity = Vec3::ZERO;
+            face(npc, player_pos, delta);
+            return;
+        }
+
+        // Update the threat table: hostile or provoked NPCs build threat on a
+        // nearby player once they have detected them; everyone decays threat
+        // from attackers out of range
+        let (npc_pos, hostile) = match self.npcs.get(&id) {
+            Some(n) => (n.position, n.data.faction == NpcFaction::Hostile),
+            None => return,
+        };
+        let mut target_pos = None;
+        // A hidden player drops out of every threat table
+        let player = (!self.player_hidden).then_some((ThreatSource::Player, player_pos));
+        if let Some(stats) = self.combat_stats.get_mut(&id) {
+            let source_positions: HashMap<ThreatSource, Vec3> = stats
+                .threat
+                .sources()
+                .filter_map(|source| match source {
+                    ThreatSource::Player => player,
+                    ThreatSource::Npc(other) => self.npcs.get(&other).map(|n| (source, n.position)),
+                })
+                .chain(player)
+                .collect();
+            let detected = self.awareness.get(&id).is_some_and(|a| a.state() == DetectionState::Alerted);
+            let nearby: &[ThreatSource] = if detected && (hostile || self.provoked_npcs.contains(&id)) {
+                &[ThreatSource::Player]
+            } else {
+                &[]
+            };
+            stats.threat.update(
+                delta,
+                nearby,
+                |source| source_positions.get(&source).map(|p| (*p - npc_pos).length()),
+                stats.aggro_radius,
+                stats.de_aggro_radius,
+            );
+            target_pos = stats.threat.target().and_then(|t| source_positions.get(&t).copied());
+        }
+
+        let roads = &self.nearby_roads;
         let npc = match self.npcs.get_mut(&id) {
             Some(n) => n,
             None => return,
         };
 
+        // Investigating a noise takes over from the plan until there's a
+        // target to chase
+        if target_pos.is_some() {
+            self.investigations.remove(&id);
+        } else if let Some(investigation) = self.investigations.get_mut(&id) {
+            let to_noise = Vec3::new(investigation.target.x - npc_pos.x, 0.0, investigation.target.z - npc_pos.z);
+            if to_noise.length() > 1.5 {
+                let dir = to_noise.normalize();
+                npc.velocity = dir * 2.5;
+                npc.position += npc.velocity * delta;
+                npc.position.y = height_fn(npc.position.x, npc.position.z) + 0.9;
+                npc.yaw = dir.z.atan2(dir.x);
+            } else {
+                npc.velocity = Vec3::ZERO;
+                npc.yaw += delta * 1.5;
+                investigation.linger -= delta;
+                if investigation.linger <= 0.0 {
+                    self.investigations.remove(&id);
+                }
+            }
+            return;
+        }
+
         let brain = match &mut npc.brain {
             Some(b) => b,
             None => return,
         };
 
-        // Update sensors
+        // Update sensors. Aggro and attack range follow the threat target
+        // rather than raw proximity to the player.
         let distance_to_player = (npc.position - player_pos).length();
-        let npc_pos = npc.position;
         let home_pos = npc.data.home_position;
+        let distance_to_target = target_pos.map(|p| (npc_pos - p).length());
 
         brain.world_state.set_float("distance_to_player", distance_to_player);
         brain.world_state.set_bool("player_nearby", distance_to_player < 15.0);
-        brain.world_state.set_bool("player_in_aggro_range", distance_to_player < 12.0);
-        brain.world_state.set_bool("player_in_attack_range", distance_to_player < 2.5);
+        brain.world_state.set_bool("player_in_aggro_range", target_pos.is_some());
+        brain.world_state.set_bool(
+            "player_in_attack_range",
+            distance_to_target.is_some_and(|d| d < 2.5),
+        );
         brain.world_state.set_bool("at_home", (npc_pos - home_pos).length() < 3.0);
 
+        // Resting: a free seat within the wander area, and a while since the last sit
+        let occupant = Occupant::Npc(id);
+        if let Some(cooldown) = self.rest_cooldowns.get_mut(&id) {
+            *cooldown -= delta;
+            if *cooldown <= 0.0 {
+                self.rest_cooldowns.remove(&id);
+            }
+        }
+        let seat_reach = npc.data.wander_radius;
+        brain.world_state.set_bool("rested", self.rest_cooldowns.contains_key(&id));
+        brain.world_state.set_bool(
+            "seat_nearby",
+            self.seats.seat_of(occupant).is_some() || self.seats.nearest_free(home_pos, seat_reach).is_some(),
+        );
+
         // Check combat stats for health
         if let Some(stats) = self.combat_stats.get(&id) {
             brain.world_state.set_bool("health_low", stats.current_hp / stats.max_hp < 0.2);
@@ -301,10 +1107,54 @@ impl NpcManager {
         }
 
         // Execute current action
-        let speed = if npc.data.role == NpcRole::Enemy { 3.5 } else { 2.0 };
+        let base_speed = if npc.data.role == NpcRole::Enemy { 3.5 } else { 2.0 };
+        let speed = base_speed * self.elites.get(&id).map(|e| e.speed_multiplier()).unwrap_or(1.0);
+
+        // Anything but resting gets the NPC off its seat
+        if brain.current_action_name() != Some("sit_down") && self.seats.release(occupant).is_some() {
+            self.rest_timers.remove(&id);
+        }
 
         if let Some(action_name) = brain.current_action_name() {
             match action_name {
+                "sit_down" => {
+                    let held = self
+                        .seats
+                        .seat_of(occupant)
+                        .map(|(seat, spot)| (seat.id, spot))
+                        .or_else(|| self.seats.claim_nearest(occupant, home_pos, seat_reach));
+                    let Some((seat, spot)) = held.and_then(|(seat, spot)| Some((*self.seats.get(seat)?, spot))) else {
+                        brain.advance_plan();
+                        npc.velocity = Vec3::ZERO;
+                        return;
+                    };
+                    let target = seat.spot(spot);
+                    let to_seat = Vec3::new(target.x - npc.position.x, 0.0, target.z - npc.position.z);
+                    let distance = to_seat.length();
+                    if distance > 0.3 && !self.rest_timers.contains_key(&id) {
+                        let dir = to_seat / distance;
+                        npc.velocity = dir * speed;
+                        npc.position += dir * (speed * delta).min(distance);
+                        npc.position.y = height_fn(npc.position.x, npc.position.z) + 0.9;
+                        npc.yaw = dir.z.atan2(dir.x);
+                    } else {
+                        // Settle into the seat facing out
+                        npc.velocity = Vec3::ZERO;
+                        npc.position = target + Vec3::Y * (0.9 - seat.kind.pose().body_drop());
+                        npc.yaw = seat.facing.z.atan2(seat.facing.x);
+                        let sat = self.rest_timers.entry(id).or_insert(0.0);
+                        *sat += delta;
+                        if *sat >= NPC_REST_TIME {
+                            self.rest_timers.remove(&id);
+                            self.seats.release(occupant);
+                            self.rest_cooldowns.insert(id, NPC_REST_COOLDOWN);
+                            let exit = seat.exit_point(spot);
+                            npc.position = Vec3::new(exit.x, height_fn(exit.x, exit.z) + 0.9, exit.z);
+                            brain.world_state.set_bool("rested", true);
+                            brain.advance_plan();
+                        }
+                    }
+                }
                 "go_home" | "return_to_post" => {
                     let to_home = home_pos - npc_pos;
                     let horizontal = Vec3::new(to_home.x, 0.0, to_home.z);
@@ -328,20 +1178,28 @@ impl NpcManager {
                     } else {
                         let t = brain.action_timer;
                         let angle = (id.0 as f32 * 2.71 + t) % std::f32::consts::TAU;
-                        let dir = Vec3::new(angle.cos(), 0.0, angle.sin());
+                        let mut dir = Vec3::new(angle.cos(), 0.0, angle.sin());
+                        let mut radius = npc.data.wander_radius;
+                        // Townsfolk keep to a road through their area
+                        if npc.data.faction != NpcFaction::Hostile {
+                            if let Some(heading) = road_heading(roads, npc.chunk, npc.position, home_pos, radius, dir) {
+                                dir = heading;
+                                radius += ROAD_REACH;
+                            }
+                        }
                         npc.velocity = dir * speed * 0.5;
                         npc.position += npc.velocity * delta;
                         // Clamp to wander radius
                         let from_home = npc.position - home_pos;
-                        if from_home.length() > npc.data.wander_radius {
-                            npc.position = home_pos + from_home.normalize() * npc.data.wander_radius;
+                        if from_home.length() > radius {
+                            npc.position = home_pos + from_home.normalize() * radius;
                         }
                         npc.position.y = height_fn(npc.position.x, npc.position.z) + 0.9;
                         npc.yaw = dir.z.atan2(dir.x);
                     }
                 }
                 "chase_target" | "chase_enemy" => {
-                    let to_player = player_pos - npc_pos;
+                    let to_player = target_pos.unwrap_or(player_pos) - npc_pos;
                     let horizontal = Vec3::new(to_player.x, 0.0, to_player.z);
                     if horizontal.length() < 2.5 {
                         brain.advance_plan();
@@ -359,8 +1217,8 @@ impl NpcManager {
                         brain.advance_plan();
                     }
                     npc.velocity = Vec3::ZERO;
-                    // Face player
-                    let to_player = player_pos - npc_pos;
+                    // Face target
+                    let to_player = target_pos.unwrap_or(player_pos) - npc_pos;
                     npc.yaw = to_player.z.atan2(to_player.x);
                 }
                 "wait" | "wait_for_customer" | "open_shop" | "close_shop" | "sleep" | "eat_food" | "talk_to_npc" => {
@@ -417,10 +1275,8 @@ impl NpcManager {
         let mut best: Option<(NpcId, f32)> = None;
         for npc in self.npcs.values() {
             let dist = (npc.position - pos).length();
-            if dist < radius {
-                if best.is_none() || dist < best.unwrap().1 {
-                    best = Some((npc.id, dist));
-                }
+            if dist < radius && (best.is_none() || dist < best.unwrap().1) {
+                best = Some((npc.id, dist));
             }
         }
         best.map(|(id, _)| id)
@@ -431,6 +1287,27 @@ impl NpcManager {
         self.npcs.len()
     }
 
+    /// Approximate host memory held for NPCs: the instances and every
+    /// per-NPC table, by allocated capacity
+    pub fn memory_bytes(&self) -> usize {
+        map_bytes(&self.npcs)
+            + map_bytes(&self.residents)
+            + map_bytes(&self.combat_stats)
+            + map_bytes(&self.awareness)
+            + map_bytes(&self.elites)
+            + map_bytes(&self.investigations)
+            + map_bytes(&self.lod)
+            + map_bytes(&self.rest_timers)
+            + map_bytes(&self.rest_cooldowns)
+            + map_bytes(&self.nearby_roads)
+            + self
+                .nearby_roads
+                .values()
+                .flatten()
+                .map(|road| road.points.capacity() * std::mem::size_of::<Vec2>())
+                .sum::<usize>()
+    }
+
     /// Count NPCs by faction
     pub fn count_by_faction(&self, faction: NpcFaction) -> usize {
         self.npcs.values().filter(|n| n.data.faction == faction).count()
@@ -444,24 +1321,65 @@ impl NpcManager {
             .unwrap_or((NpcRole::Enemy, NpcFaction::Hostile));
         let was_friendly = faction == NpcFaction::Friendly || faction == NpcFaction::Neutral;
 
+        if self.invulnerable.contains(&id) {
+            return DamageNpcResult::new(false, role, was_friendly);
+        }
+
+        self.awareness.entry(id).or_default().alert();
+
+        // Elite shields soak damage before HP
+        let damage = match self.elites.get_mut(&id) {
+            Some(elite) => elite.absorb(damage),
+            None => damage,
+        };
+        let reward_multiplier = self.elites.get(&id).map(|e| e.reward_multiplier()).unwrap_or(1.0);
+        let splits = self.elites.get(&id).is_some_and(|e| e.has(EliteAffix::Splitting));
+
         if let Some(stats) = self.combat_stats.get_mut(&id) {
+            if damage <= 0.0 {
+                stats.threat.add(ThreatSource::Player, DAMAGE_THREAT_PER_POINT);
+                return DamageNpcResult::new(false, role, was_friendly);
+            }
             let actual = (damage - stats.defense).max(1.0);
             stats.current_hp = (stats.current_hp - actual).max(0.0);
+            stats.threat.add(ThreatSource::Player, actual * DAMAGE_THREAT_PER_POINT);
             if stats.current_hp <= 0.0 {
-                // Defeated — remove and start respawn timer
-                if let Some(npc) = self.npcs.remove(&id) {
-                    let chunk = npc.chunk;
-                    let key = npc.persistent_key;
-                    // Find spawn index by matching persistent_key
-                    let points = generate_spawn_points(chunk.x, chunk.z, self.chunk_size);
-                    let idx = points.iter().position(|p| {
-                        compute_persistent_key(chunk.x, chunk.z, p.spawn_index) == key
-                    }).unwrap_or(0);
-                    self.respawn_timers.push((chunk, idx, 30.0));
+                let max_hp = stats.max_hp;
+                let profile = EnemyProfile::from_stats(stats);
+                let elite = self.elites.contains_key(&id);
+                // Defeated — remove and start respawn timer (custom spawns never respawn)
+                let custom = self.custom_spawns.remove(&id);
+                let removed = self.npcs.remove(&id);
+                let kill = removed.as_ref()
+                    .filter(|npc| npc.data.faction == NpcFaction::Hostile)
+                    .map(|npc| KillRecord { name: npc.data.name.clone(), profile, elite });
+                if splits {
+                    if let Some(npc) = &removed {
+                        let (data, position) = (npc.data.clone(), npc.position);
+                        self.split_elite(&data, position, max_hp);
+                    }
                 }
+                if let Some(resident) = self.residents.remove(&id) {
+                    // Residents don't respawn: the ledger remembers the death
+                    self.population.record_death(&resident, self.spawn_year);
+                } else if let Some(npc) = removed.filter(|_| !custom) {
+                    if let Some(&index) = self.chunk_spawns.get(&id) {
+                        self.respawn_timers.push((npc.chunk, index, 30.0));
+                    }
+                }
+                self.chunk_spawns.remove(&id);
                 self.combat_stats.remove(&id);
                 self.provoked_npcs.remove(&id);
-                return DamageNpcResult { defeated: true, role, was_friendly };
+                self.elites.remove(&id);
+                self.statuses.remove(&id);
+                self.investigations.remove(&id);
+                self.forget_rest(id);
+                return DamageNpcResult {
+                    reward_multiplier,
+                    split: splits,
+                    kill,
+                    ..DamageNpcResult::new(true, role, was_friendly)
+                };
             }
         }
 
@@ -474,7 +1392,40 @@ impl NpcManager {
             }
         }
 
-        DamageNpcResult { defeated: false, role, was_friendly }
+        DamageNpcResult::new(false, role, was_friendly)
+    }
+
+    /// Apply poise damage from a hit. Returns true if it staggered the NPC.
+    pub fn apply_poise_damage(&mut self, id: NpcId, amount: f32) -> bool {
+        if self.invulnerable.contains(&id) {
+            return false;
+        }
+        self.combat_stats
+            .get_mut(&id)
+            .is_some_and(|stats| stats.poise.damage(amount))
+    }
+
+    /// Turn an NPC to face a position. Conversations freeze game time, so
+    /// the partner is turned on real time from outside the update.
+    pub fn turn_to_face(&mut self, id: NpcId, target: Vec3, delta: f32) {
+        if let Some(npc) = self.npcs.get_mut(&id) {
+            face(npc, target, delta);
+        }
+    }
+
+    /// Let everyone still marked as talking, except the current
+    /// conversation partner, go back to their routine
+    pub fn release_talking(&mut self, partner: Option<NpcId>) {
+        for npc in self.npcs.values_mut() {
+            if matches!(npc.state, NpcBehaviorState::Talking) && Some(npc.id) != partner {
+                npc.state = NpcBehaviorState::Idle { timer: 2.0 };
+            }
+        }
+    }
+
+    /// Whether an NPC is staggered from a broken poise
+    pub fn is_staggered(&self, id: NpcId) -> bool {
+        self.combat_stats.get(&id).is_some_and(|s| s.poise.is_staggered())
     }
 
     /// Check if an enemy NPC is currently attacking (in attack range and has attack action)
@@ -529,9 +1480,98 @@ impl NpcManager {
         1
     }
 
+    /// Update every NPC's awareness of the player from sight and hearing.
+    /// `line_of_sight` raycasts between two points (see `perception::line_of_sight`).
+    pub fn update_perception(
+        &mut self,
+        delta: f32,
+        player: &StealthInputs,
+        line_of_sight: impl Fn(Vec3, Vec3) -> bool,
+    ) {
+        let config = self.perception;
+        self.awareness.retain(|id, _| self.npcs.contains_key(id));
+        for npc in self.npcs.values() {
+            if !self.combat_stats.contains_key(&npc.id) || self.lod(npc.id) == NpcLod::Ambient {
+                continue;
+            }
+            // NPC eye sits a little below the top of the capsule
+            let eye = npc.position + Vec3::Y * 0.7;
+            let forward = Vec3::new(npc.yaw.cos(), 0.0, npc.yaw.sin());
+            let stimulus = if self.player_hidden {
+                0.0
+            } else {
+                sight_stimulus(eye, forward, &config, player, &line_of_sight)
+                    .max(hearing_stimulus(npc.position, &config, player))
+            };
+            self.awareness.entry(npc.id).or_default().update(delta, stimulus);
+        }
+    }
+
+    /// A loud noise at `pos`: NPCs with combat stats within `radius` that
+    /// haven't detected the player become suspicious and walk over to
+    /// investigate. Returns how many NPCs heard it.
+    pub fn hear_noise(&mut self, pos: Vec3, radius: f32) -> usize {
+        let listeners: Vec<NpcId> = self.npcs.values()
+            .filter(|n| self.combat_stats.contains_key(&n.id))
+            .filter(|n| (n.position - pos).length() <= radius)
+            .map(|n| n.id)
+            .collect();
+        let mut heard = 0;
+        for id in listeners {
+            let awareness = self.awareness.entry(id).or_default();
+            if awareness.state() == DetectionState::Alerted {
+                continue;
+            }
+            awareness.suspect();
+            self.investigations.insert(id, Investigation { target: pos, linger: balance::npc::INVESTIGATE_LINGER.get() });
+            heard += 1;
+        }
+        heard
+    }
+
+    /// Where an NPC is going to check out a noise, if it is
+    pub fn investigating(&self, id: NpcId) -> Option<Vec3> {
+        self.investigations.get(&id).map(|i| i.target)
+    }
+
+    /// How aware an NPC is of the player
+    pub fn detection_state(&self, id: NpcId) -> DetectionState {
+        self.awareness.get(&id).map(|a| a.state()).unwrap_or_default()
+    }
+
+    /// Whether an attack on this NPC counts as a sneak attack
+    pub fn is_sneak_attack(&self, id: NpcId) -> bool {
+        self.detection_state(id) != DetectionState::Alerted
+    }
+
     /// Mark an NPC as provoked (attacked by player)
     pub fn provoke_npc(&mut self, id: NpcId) {
         self.provoked_npcs.insert(id);
+        self.awareness.entry(id).or_default().alert();
+        if let Some(stats) = self.combat_stats.get_mut(&id) {
+            if stats.threat.target().is_none() {
+                stats.threat.taunt(ThreatSource::Player, 0.0);
+            }
+        }
+    }
+
+    /// Add threat from an attacker to an NPC
+    pub fn add_threat(&mut self, id: NpcId, source: ThreatSource, amount: f32) {
+        if let Some(stats) = self.combat_stats.get_mut(&id) {
+            stats.threat.add(source, amount);
+        }
+    }
+
+    /// Force an NPC to target `source` (taunt skills)
+    pub fn taunt(&mut self, id: NpcId, source: ThreatSource, bonus: f32) {
+        if let Some(stats) = self.combat_stats.get_mut(&id) {
+            stats.threat.taunt(source, bonus);
+        }
+    }
+
+    /// Who an NPC is currently targeting
+    pub fn threat_target(&self, id: NpcId) -> Option<ThreatSource> {
+        self.combat_stats.get(&id).and_then(|s| s.threat.target())
     }
 
     /// Check if an NPC has been provoked
@@ -566,12 +1606,436 @@ mod tests {
         let coord = ChunkCoord::new(5, 7);
         mgr.on_chunk_loaded(coord, 2025, test_height);
         let count_before = mgr.count();
+        assert!(mgr.memory_bytes() >= count_before * std::mem::size_of::<NpcInstance>());
 
         mgr.on_chunk_unloaded(coord);
         assert_eq!(mgr.count(), 0, "all NPCs should be removed on chunk unload");
         assert!(count_before <= 3, "should spawn 0-3 NPCs per chunk");
     }
 
+    #[test]
+    fn test_custom_spawn_invulnerable_and_no_respawn() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let dummy = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::new(3.0, 0.0, 3.0),
+            TrainingDummy::combat_stats(),
+            false,
+        );
+        mgr.set_invulnerable(dummy, true);
+        let result = mgr.damage_npc(dummy, 1000.0, Element::Fire, AttackType::Heavy);
+        assert!(!result.defeated);
+        assert_eq!(mgr.combat_stats[&dummy].current_hp, mgr.combat_stats[&dummy].max_hp);
+
+        let fighter = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::ZERO,
+            CombatStats::default_enemy(),
+            true,
+        );
+        let result = mgr.damage_npc(fighter, 1000.0, Element::Physical, AttackType::Heavy);
+        assert!(result.defeated);
+        assert!(mgr.get(fighter).is_none());
+        assert!(mgr.respawn_timers.is_empty(), "custom spawns never respawn");
+
+        mgr.despawn(dummy);
+        assert_eq!(mgr.count(), 0);
+    }
+
+    #[test]
+    fn test_statuses_respect_npc_elements() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let fire = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::ZERO,
+            CombatStats { element: Element::Fire, ..CombatStats::default_enemy() },
+            true,
+        );
+        let burn = StatusEffect::elemental_proc(StatusEffectType::Burning, 4.0);
+        assert!(!mgr.apply_status(fire, burn.clone(), Element::Fire), "fire NPCs don't burn");
+        assert!(mgr.statuses(fire).is_none());
+
+        // Water douses fire, so burning lasts less on water NPCs
+        let water = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::ZERO,
+            CombatStats { element: Element::Water, ..CombatStats::default_enemy() },
+            true,
+        );
+        assert!(mgr.apply_status(water, burn, Element::Fire));
+        let duration = mgr.statuses(water).unwrap().effects[0].duration;
+        assert!(duration < 4.0);
+
+        let ticks = mgr.update_statuses(1.0);
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].0, water);
+        assert_eq!(ticks[0].2, StatusEffectType::Burning);
+
+        mgr.despawn(water);
+        assert!(mgr.update_statuses(1.0).is_empty());
+    }
+
+    #[test]
+    fn test_talking_npcs_face_the_player_until_released() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let simple = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::new(2.0, 0.9, 0.0),
+            CombatStats::default_enemy(),
+            false,
+        );
+        let planner = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::new(-2.0, 0.9, 0.0),
+            CombatStats::default_enemy(),
+            true,
+        );
+        for id in [simple, planner] {
+            let npc = mgr.get_mut(id).unwrap();
+            npc.yaw = std::f32::consts::FRAC_PI_2;
+            npc.state = NpcBehaviorState::Talking;
+        }
+
+        for _ in 0..60 {
+            mgr.update(0.05, Vec3::new(0.0, 0.9, 0.0), test_height);
+        }
+        // Facing -X (yaw PI) and +X (yaw 0) towards the player at the origin
+        let simple_yaw = mgr.get(simple).unwrap().yaw;
+        assert!((simple_yaw.abs() - std::f32::consts::PI).abs() < 1e-3, "yaw {simple_yaw}");
+        assert!(mgr.get(planner).unwrap().yaw.abs() < 1e-3);
+        assert_eq!(mgr.get(planner).unwrap().position, Vec3::new(-2.0, 0.9, 0.0));
+
+        mgr.release_talking(Some(simple));
+        assert!(matches!(mgr.get(simple).unwrap().state, NpcBehaviorState::Talking));
+        assert!(matches!(mgr.get(planner).unwrap().state, NpcBehaviorState::Idle { .. }));
+    }
+
+    #[test]
+    fn test_villagers_sit_on_nearby_seats() {
+        use crate::seat::SeatKind;
+
+        let mut mgr = NpcManager::new(64.0);
+        let home = Vec3::new(0.0, 0.9, 0.0);
+        let villager = mgr.spawn_role(NpcRole::Villager, home);
+        let chair = mgr.seats.add(SeatKind::Chair, Vec3::new(4.0, 0.0, 0.0), Vec3::NEG_X);
+        // The player out of earshot but close enough for the full simulation
+        let player = Vec3::new(0.0, 0.0, 30.0);
+
+        for _ in 0..200 {
+            mgr.update(0.05, player, test_height);
+        }
+        assert_eq!(mgr.rest_pose(villager), Some(RestPose::Sitting));
+        let npc = mgr.get(villager).unwrap();
+        assert!((npc.position - Vec3::new(4.0, 0.9 - RestPose::Sitting.body_drop(), 0.0)).length() < 1e-4);
+        // Facing out of the chair, towards -X
+        assert!((npc.yaw.abs() - std::f32::consts::PI).abs() < 1e-3);
+        assert!(mgr.seats.claim(chair, Occupant::Player, Vec3::ZERO).is_none(), "the chair is taken");
+
+        // Gets up after a while and frees the chair
+        for _ in 0..((NPC_REST_TIME / 0.05) as usize + 20) {
+            mgr.update(0.05, player, test_height);
+        }
+        assert_eq!(mgr.rest_pose(villager), None);
+        assert!(mgr.seats.claim(chair, Occupant::Player, Vec3::ZERO).is_some());
+
+        mgr.despawn(villager);
+        assert!(mgr.rest_cooldowns.is_empty());
+    }
+
+    #[test]
+    fn test_spawn_from_template_links_the_character() {
+        let mut mgr = NpcManager::new(64.0);
+        let template = ServerCharacter {
+            id: "npc1".into(),
+            name: "Elder Morvyn".into(),
+            system_prompt: "You are a wise elder.".into(),
+            lore: None,
+            appearance: None,
+            project_id: String::new(),
+            user_id: String::new(),
+            client_id: None,
+            snapshot: None,
+        };
+        let id = mgr.spawn_from_template(template, NpcRole::QuestGiver, Vec3::new(4.0, 0.0, 4.0));
+        let npc = mgr.get(id).unwrap();
+        assert_eq!(npc.data.name, "Elder Morvyn");
+        assert_eq!(npc.data.faction, NpcFaction::Friendly);
+        assert_eq!(npc.data.server_character_id.as_deref(), Some("npc1"));
+        assert!(npc.brain.is_some());
+        let key = npc.persistent_key;
+        assert!(matches!(mgr.character_cache.get(&key), Some(super::super::character_cache::CharacterCacheEntry::Ready(c)) if c.id == "npc1"));
+
+        mgr.despawn(id);
+        assert!(mgr.character_cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_threat_drives_enemy_target() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let enemy = mgr.spawn_custom(
+            TrainingDummy::npc_data(),
+            Vec3::ZERO,
+            CombatStats::default_enemy(),
+            true,
+        );
+        assert_eq!(mgr.threat_target(enemy), None);
+
+        let position = Vec3::new(3.0, 0.0, 0.0);
+        let player = StealthInputs {
+            position,
+            eye_position: position + Vec3::Y * 0.7,
+            crouching: false,
+            speed: 5.0,
+            walk_speed: 5.0,
+            light_level: 1.0,
+        };
+        for _ in 0..20 {
+            mgr.update_perception(0.1, &player, |_, _| true);
+            mgr.update(0.1, position, test_height);
+        }
+        assert_eq!(mgr.detection_state(enemy), DetectionState::Alerted);
+        assert_eq!(mgr.threat_target(enemy), Some(ThreatSource::Player));
+
+        // A taunt from a companion takes the target away from the player
+        let companion = ThreatSource::Npc(NpcId(999));
+        mgr.taunt(enemy, companion, 10.0);
+        assert_eq!(mgr.threat_target(enemy), Some(companion));
+    }
+
+    #[test]
+    fn test_hidden_player_is_forgotten() {
+        let mut mgr = NpcManager::new(64.0);
+        let enemy = mgr.spawn_role(NpcRole::Enemy, Vec3::ZERO);
+        assert_eq!(mgr.get(enemy).unwrap().data.faction, NpcFaction::Hostile);
+
+        let position = Vec3::new(3.0, 0.0, 0.0);
+        let player = StealthInputs {
+            position,
+            eye_position: position + Vec3::Y * 0.7,
+            crouching: false,
+            speed: 5.0,
+            walk_speed: 5.0,
+            light_level: 1.0,
+        };
+        for _ in 0..20 {
+            mgr.update_perception(0.1, &player, |_, _| true);
+            mgr.update(0.1, position, test_height);
+        }
+        assert_eq!(mgr.threat_target(enemy), Some(ThreatSource::Player));
+
+        // Vanishing drops the target at once, and awareness fades
+        mgr.player_hidden = true;
+        mgr.update(0.1, position, test_height);
+        assert_eq!(mgr.threat_target(enemy), None);
+        for _ in 0..100 {
+            mgr.update_perception(0.1, &player, |_, _| true);
+            mgr.update(0.1, position, test_height);
+        }
+        assert_eq!(mgr.detection_state(enemy), DetectionState::Unaware);
+        assert_eq!(mgr.threat_target(enemy), None);
+    }
+
+    #[test]
+    fn test_noise_draws_npcs_to_investigate() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let near = mgr.spawn_custom(TrainingDummy::npc_data(), Vec3::ZERO, CombatStats::default_enemy(), true);
+        let far = mgr.spawn_custom(TrainingDummy::npc_data(), Vec3::new(50.0, 0.0, 0.0), CombatStats::default_enemy(), true);
+        let noise = Vec3::new(8.0, 0.0, 0.0);
+
+        assert_eq!(mgr.hear_noise(noise, 12.0), 1);
+        assert_eq!(mgr.detection_state(near), DetectionState::Suspicious);
+        assert_eq!(mgr.investigating(near), Some(noise));
+        assert_eq!(mgr.investigating(far), None);
+
+        // Walks over, looks around, then gives up
+        let player_pos = Vec3::new(0.0, 0.0, 100.0);
+        for _ in 0..40 {
+            mgr.update(0.1, player_pos, test_height);
+        }
+        assert!((mgr.get(near).unwrap().position - noise).with_y(0.0).length() < 1.6);
+        for _ in 0..50 {
+            mgr.update(0.1, player_pos, test_height);
+        }
+        assert_eq!(mgr.investigating(near), None);
+    }
+
+    #[test]
+    fn test_elite_shield_split_and_rewards() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let elite = mgr.spawn_custom(TrainingDummy::npc_data(), Vec3::ZERO, CombatStats::default_enemy(), true);
+        mgr.make_elite(elite, EliteModifiers::new(vec![EliteAffix::Shielded, EliteAffix::Splitting]));
+        let max_hp = mgr.combat_stats[&elite].max_hp;
+        assert_eq!(max_hp, CombatStats::default_enemy().max_hp * 2.0);
+
+        // The shield soaks the first hit entirely
+        let shield = mgr.elite(elite).unwrap().shield;
+        let result = mgr.damage_npc(elite, shield, Element::Physical, AttackType::Light);
+        assert!(!result.defeated);
+        assert_eq!(mgr.combat_stats[&elite].current_hp, max_hp);
+
+        let result = mgr.damage_npc(elite, 10_000.0, Element::Physical, AttackType::Heavy);
+        assert!(result.defeated && result.split);
+        assert!(result.reward_multiplier > 1.0);
+        let kill = result.kill.expect("hostile kills are recorded for the bestiary");
+        assert!(kill.elite);
+        assert_eq!(kill.profile.max_hp, max_hp);
+        assert!(mgr.elite(elite).is_none());
+        // Two fragments with a share of the elite's HP
+        assert_eq!(mgr.count(), SPLIT_COUNT);
+        for npc in mgr.npcs_iter() {
+            assert!(mgr.elite(npc.id).is_none());
+            assert_eq!(mgr.combat_stats[&npc.id].max_hp, max_hp * SPLIT_HP_FRACTION);
+        }
+    }
+
+    #[test]
+    fn test_vampiric_heal_and_frozen_aura() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let elite = mgr.spawn_custom(TrainingDummy::npc_data(), Vec3::ZERO, CombatStats::default_enemy(), false);
+        mgr.make_elite(elite, EliteModifiers::new(vec![EliteAffix::Vampiric, EliteAffix::FrozenAura]));
+        mgr.damage_npc(elite, 30.0, Element::Physical, AttackType::Light);
+        let hp = mgr.combat_stats[&elite].current_hp;
+        assert!(mgr.on_npc_hit_player(elite, 10.0) > 0.0);
+        assert!(mgr.combat_stats[&elite].current_hp > hp);
+
+        assert!(mgr.in_frozen_aura(Vec3::new(2.0, 0.0, 0.0)));
+        assert!(!mgr.in_frozen_aura(Vec3::new(FROZEN_AURA_RADIUS + 1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_killed_residents_stay_dead() {
+        let mut mgr = NpcManager::new(64.0).with_world_seed(3);
+        let coords: Vec<ChunkCoord> = (0..10).flat_map(|x| (0..10).map(move |z| ChunkCoord::new(x, z))).collect();
+        for coord in &coords {
+            mgr.on_chunk_loaded(*coord, 2025, test_height);
+        }
+        let (id, key, chunk) = mgr
+            .npcs_iter()
+            .find(|npc| mgr.resident(npc.id).is_some())
+            .map(|npc| (npc.id, npc.persistent_key, npc.chunk))
+            .expect("no residents spawned");
+        assert_eq!(mgr.resident(id).unwrap().id, key);
+
+        let result = mgr.damage_npc(id, 10_000.0, Element::Physical, AttackType::Heavy);
+        assert!(result.defeated);
+        assert!(mgr.respawn_timers.iter().all(|(c, _, _)| *c != chunk), "residents never respawn");
+        assert_eq!(mgr.population.deaths().len(), 1);
+
+        // Reloading the chunk in the same year doesn't bring them back
+        mgr.on_chunk_unloaded(chunk);
+        mgr.on_chunk_loaded(chunk, 2025, test_height);
+        assert!(mgr.npcs_iter().all(|npc| npc.persistent_key != key));
+    }
+
+    #[test]
+    fn test_population_settings_apply_live() {
+        let mut mgr = NpcManager::new(64.0).with_world_seed(5);
+        for x in 0..10 {
+            for z in 0..10 {
+                mgr.on_chunk_loaded(ChunkCoord::new(x, z), 2025, test_height);
+            }
+        }
+        let normal = mgr.count();
+        let essential = |mgr: &NpcManager| {
+            mgr.npcs_iter().filter(|n| matches!(n.data.role, NpcRole::Shopkeeper | NpcRole::QuestGiver)).count()
+        };
+        let shops = essential(&mgr);
+        assert!(mgr.hostile_count() > 3 && shops > 0);
+
+        let quiet = PopulationConfig { density: 0.0, ambient_life: false, ..Default::default() };
+        mgr.set_population_config(quiet, test_height);
+        assert_eq!(mgr.count(), shops);
+        assert_eq!(essential(&mgr), shops);
+
+        let capped = PopulationConfig { max_hostiles: Some(3), ..Default::default() };
+        mgr.set_population_config(capped, test_height);
+        assert_eq!(mgr.hostile_count(), 3);
+        assert!(mgr.count() < normal);
+
+        let busy = PopulationConfig { density: PopulationConfig::MAX_DENSITY, ..Default::default() };
+        mgr.set_population_config(busy, test_height);
+        let crowded = mgr.count();
+        assert!(crowded > normal + normal / 2, "{crowded} vs {normal}");
+
+        // Back to normal gives the same people as before
+        mgr.set_population_config(PopulationConfig::default(), test_height);
+        assert_eq!(mgr.count(), normal);
+    }
+
+    #[test]
+    fn test_lod_tiers_and_promotion() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let home = Vec3::new(300.0, 0.0, 0.0);
+        let far = mgr.spawn_custom(TrainingDummy::npc_data(), home, CombatStats::default_enemy(), false);
+        let mid = mgr.spawn_custom(TrainingDummy::npc_data(), Vec3::new(80.0, 0.0, 0.0), CombatStats::default_enemy(), false);
+
+        mgr.update(0.016, Vec3::ZERO, test_height);
+        assert_eq!(mgr.lod(far), NpcLod::Ambient);
+        assert_eq!(mgr.lod(mid), NpcLod::Reduced);
+        let crowds = mgr.ambient_crowds();
+        assert_eq!(crowds.len(), 1);
+        assert_eq!((crowds[0].center, crowds[0].count), (home, 1));
+
+        // Walking up promotes it back into its wander area
+        mgr.update(0.016, home, test_height);
+        assert_eq!(mgr.lod(far), NpcLod::Full);
+        let wander = mgr.get(far).unwrap().data.wander_radius;
+        assert!((mgr.get(far).unwrap().position - home).with_y(0.0).length() <= wander);
+        // ...while the other one is now far behind
+        assert_eq!(mgr.lod(mid), NpcLod::Ambient);
+        assert_eq!(mgr.ambient_crowds().len(), 1);
+
+        // Provoked NPCs stay fully simulated at any range
+        mgr.provoke_npc(far);
+        mgr.update(0.016, Vec3::ZERO, test_height);
+        assert_eq!(mgr.lod(far), NpcLod::Full);
+    }
+
+    #[test]
+    fn test_reduced_npcs_drawn_between_ticks() {
+        use super::super::training::TrainingDummy;
+
+        let mut mgr = NpcManager::new(64.0);
+        let start = Vec3::new(80.0, 0.9, 0.0);
+        let id = mgr.spawn_custom(TrainingDummy::npc_data(), start, CombatStats::default_enemy(), false);
+        mgr.get_mut(id).unwrap().state = NpcBehaviorState::Walking { target: Vec3::new(80.0, 0.9, 40.0) };
+
+        // One reduced tick walks it forward; drawing starts from where it was
+        mgr.update(0.3, Vec3::ZERO, test_height);
+        assert_eq!(mgr.lod(id), NpcLod::Reduced);
+        let ticked = mgr.get(id).unwrap().position;
+        assert!(ticked.z > start.z);
+        let npc = mgr.get(id).unwrap();
+        assert!((mgr.render_transform(npc).position - start).length() < 1e-4);
+
+        // Halfway to the next tick, drawn halfway along
+        mgr.update(0.125, Vec3::ZERO, test_height);
+        let npc = mgr.get(id).unwrap();
+        assert_eq!(npc.position, ticked);
+        assert!((mgr.render_transform(npc).position - start.lerp(ticked, 0.5)).length() < 1e-4);
+
+        // Moved outside an update: drawn where it is
+        mgr.get_mut(id).unwrap().position = Vec3::new(90.0, 0.9, 0.0);
+        let npc = mgr.get(id).unwrap();
+        assert_eq!(mgr.render_transform(npc).position, npc.position);
+    }
+
     #[test]
     fn test_manager_update_no_crash() {
         let mut mgr = NpcManager::new(64.0);
@@ -609,7 +2073,45 @@ mod tests {
         for _ in 0..200 {
             mgr.update(0.1, Vec3::new(1000.0, 0.0, 1000.0), test_height);
         }
-        // Should not crash and NPCs should still exist
-        assert!(mgr.count() > 0 || true); // some chunks might have 0 NPCs
+        // Should not crash; some chunks might have 0 NPCs
+        let _ = mgr.count();
+    }
+
+    #[test]
+    fn test_wandering_keeps_to_nearby_roads() {
+        let chunk = ChunkCoord::new(0, 0);
+        let road = Road {
+            from: (0, 0),
+            to: (1, 0),
+            points: vec![Vec2::new(0.0, 10.0), Vec2::new(64.0, 10.0)],
+        };
+        let roads = HashMap::from([(chunk, vec![road])]);
+        let home = Vec3::new(30.0, 0.0, 20.0);
+
+        // Off the road: head for it
+        let heading = road_heading(&roads, chunk, home, home, 5.0, Vec3::X).unwrap();
+        assert!((heading - Vec3::NEG_Z).length() < 1e-5);
+        // On it: follow it the way the NPC was going
+        let on_road = Vec3::new(30.0, 0.0, 10.5);
+        assert_eq!(road_heading(&roads, chunk, on_road, home, 5.0, Vec3::new(-1.0, 0.0, 0.3)), Some(Vec3::NEG_X));
+        assert_eq!(road_heading(&roads, chunk, on_road, home, 5.0, Vec3::new(0.2, 0.0, 1.0)), Some(Vec3::X));
+        // Too far from home, or no roads loaded
+        let far_home = Vec3::new(30.0, 0.0, 60.0);
+        assert!(road_heading(&roads, chunk, far_home, far_home, 5.0, Vec3::X).is_none());
+        assert!(road_heading(&roads, ChunkCoord::new(1, 0), home, home, 5.0, Vec3::X).is_none());
+
+        // Loading chunks picks up the network's roads
+        let network = RoadNetwork::new(
+            infinite_world::RoadConfig { settlement_chance: 1.0, ..Default::default() },
+            7,
+            64.0,
+        );
+        let mut mgr = NpcManager::new(64.0).with_roads(network.clone());
+        let settlement = network.settlement_in_region((0, 0)).unwrap();
+        let coord = ChunkCoord::from_world_pos(settlement.position, 64.0);
+        mgr.on_chunk_loaded(coord, 2025, test_height);
+        assert_eq!(mgr.nearby_roads.get(&coord), Some(&network.roads_near(coord, ROAD_REACH)));
+        mgr.on_chunk_unloaded(coord);
+        assert!(mgr.nearby_roads.is_empty());
     }
 }
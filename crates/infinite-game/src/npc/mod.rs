@@ -2,17 +2,30 @@
 
 pub mod ai_dialogue;
 pub mod archetype_mapping;
+pub mod bark;
+pub mod bestiary;
 pub mod character_cache;
 pub mod combat;
 pub mod dialogue;
+pub mod elite;
 pub mod game_context;
 pub mod goap;
+pub mod identity;
+pub mod lod;
 pub mod manager;
 pub mod npc_generator;
+pub mod perception;
+pub mod population;
 pub mod relationship;
+pub mod requirement;
 pub mod spawn;
+pub mod survival;
+pub mod training;
+pub mod voice;
+pub mod world_boss;
 
-use glam::Vec3;
+use glam::{Quat, Vec3};
+use infinite_core::Transform;
 use serde::{Deserialize, Serialize};
 
 /// Unique identifier for an NPC instance
@@ -94,6 +107,8 @@ pub struct NpcInstance {
     pub brain: Option<goap::NpcBrain>,
     /// Deterministic key for persistence (hash of chunk coords + spawn index)
     pub persistent_key: u64,
+    /// Name, look and personality (None for training dummies and arena waves)
+    pub identity: Option<identity::NpcIdentity>,
 }
 
 impl NpcInstance {
@@ -102,6 +117,12 @@ impl NpcInstance {
         &self.data.name
     }
 
+    /// Position and heading as a transform (local +X turned to face along
+    /// `yaw`)
+    pub fn transform(&self) -> Transform {
+        Transform::from_position_rotation(self.position, Quat::from_rotation_y(-self.yaw))
+    }
+
     /// Whether this NPC can be interacted with
     pub fn is_interactable(&self) -> bool {
         self.data.faction != NpcFaction::Hostile || self.data.role != NpcRole::Enemy
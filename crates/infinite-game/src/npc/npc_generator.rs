@@ -6,6 +6,7 @@ use infinite_integration::{
 
 use super::archetype_mapping::generate_system_prompt;
 use super::character_cache::NpcCharacterCache;
+use super::identity::PersonalityTrait;
 use super::NpcRole;
 
 /// A pending character generation
@@ -33,14 +34,17 @@ impl NpcGenerator {
         npc_name: &str,
         role: NpcRole,
         year: i64,
+        traits: &[PersonalityTrait],
         client: &IntegrationClient,
     ) {
-        let system_prompt = generate_system_prompt(npc_name, role, year);
+        let system_prompt = generate_system_prompt(npc_name, role, year, traits);
 
         let req = CreateCharacterRequest {
             name: npc_name.to_string(),
             system_prompt,
             lore: None,
+            client_id: None,
+            snapshot: None,
         };
 
         let pending = client.create_character(req);
@@ -0,0 +1,291 @@
+//! NPC perception — sight, hearing, and detection for stealth
+//!
+//! Each NPC accumulates awareness of the player from two senses:
+//! - Sight: the player must be inside the NPC's view cone, within view
+//!   distance and in line of sight; how quickly they are noticed scales
+//!   with the light level and whether they are crouching.
+//! - Hearing: movement noise (speed, reduced when crouching) within the
+//!   hearing radius, regardless of facing.
+//!
+//! Attacks on NPCs that have not detected the player are sneak attacks.
+
+use crate::balance;
+use glam::Vec3;
+use infinite_physics::PhysicsWorld;
+use rapier3d::prelude::{ColliderHandle, QueryFilter};
+
+/// Awareness at which an NPC becomes suspicious
+const SUSPICIOUS_THRESHOLD: f32 = 0.3;
+/// Awareness at which an NPC detects the player
+const ALERTED_THRESHOLD: f32 = 1.0;
+/// Alerted NPCs only calm down once awareness falls below this
+const CALM_THRESHOLD: f32 = 0.5;
+/// Fraction of visibility and noise kept while crouching
+const CROUCH_VISIBILITY: f32 = 0.5;
+const CROUCH_NOISE: f32 = 0.3;
+/// Minimum light level (starlight, ambient)
+const MIN_LIGHT: f32 = 0.1;
+
+/// How aware an NPC is of the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetectionState {
+    /// Has not noticed the player
+    #[default]
+    Unaware,
+    /// Noticed something — investigating
+    Suspicious,
+    /// Has detected the player
+    Alerted,
+}
+
+impl DetectionState {
+    /// Short HUD marker ("" / "?" / "!")
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Self::Unaware => "",
+            Self::Suspicious => "?",
+            Self::Alerted => "!",
+        }
+    }
+}
+
+/// Sense ranges for an NPC
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerceptionConfig {
+    /// Maximum sight distance in meters
+    pub view_distance: f32,
+    /// Full view cone angle in degrees
+    pub view_angle: f32,
+    /// Distance at which full-speed movement is heard
+    pub hearing_radius: f32,
+}
+
+impl Default for PerceptionConfig {
+    fn default() -> Self {
+        Self {
+            view_distance: 20.0,
+            view_angle: 110.0,
+            hearing_radius: 8.0,
+        }
+    }
+}
+
+/// The player's stealth-relevant state for this frame
+#[derive(Debug, Clone, Copy)]
+pub struct StealthInputs {
+    /// Player capsule centre
+    pub position: Vec3,
+    /// Player eye position (used for line of sight)
+    pub eye_position: Vec3,
+    pub crouching: bool,
+    /// Horizontal speed in m/s
+    pub speed: f32,
+    /// Walking speed, used to normalize noise
+    pub walk_speed: f32,
+    /// Light level at the player (0.0 - 1.0), e.g. from `TimeOfDay::light_intensity`
+    pub light_level: f32,
+}
+
+impl StealthInputs {
+    /// How visible the player is (0.0 - 1.0)
+    pub fn visibility(&self) -> f32 {
+        let light = self.light_level.clamp(MIN_LIGHT, 1.0);
+        if self.crouching {
+            light * CROUCH_VISIBILITY
+        } else {
+            light
+        }
+    }
+
+    /// How much noise the player makes (0.0 when still, 1.0 at walking speed)
+    pub fn noise(&self) -> f32 {
+        let noise = self.speed / self.walk_speed.max(0.1);
+        if self.crouching {
+            noise * CROUCH_NOISE
+        } else {
+            noise
+        }
+    }
+}
+
+/// Sight stimulus (0.0 - 1.0) for an NPC at `npc_eye` facing `npc_forward`.
+/// `has_line_of_sight` is only consulted when the player is inside the cone.
+pub fn sight_stimulus(
+    npc_eye: Vec3,
+    npc_forward: Vec3,
+    config: &PerceptionConfig,
+    player: &StealthInputs,
+    has_line_of_sight: impl FnOnce(Vec3, Vec3) -> bool,
+) -> f32 {
+    let to_player = player.eye_position - npc_eye;
+    let distance = to_player.length();
+    if distance > config.view_distance || distance < 0.01 {
+        return if distance < 0.01 { 1.0 } else { 0.0 };
+    }
+
+    let flat_to = Vec3::new(to_player.x, 0.0, to_player.z).normalize_or_zero();
+    let flat_fwd = Vec3::new(npc_forward.x, 0.0, npc_forward.z).normalize_or_zero();
+    let half_angle = (config.view_angle * 0.5).to_radians();
+    if flat_fwd.dot(flat_to) < half_angle.cos() {
+        return 0.0;
+    }
+    if !has_line_of_sight(npc_eye, player.eye_position) {
+        return 0.0;
+    }
+
+    // Closer players are noticed faster
+    let closeness = 1.0 - distance / config.view_distance;
+    player.visibility() * (0.3 + 0.7 * closeness)
+}
+
+/// Hearing stimulus (0.0 - 1.0+) for an NPC at `npc_pos`
+pub fn hearing_stimulus(npc_pos: Vec3, config: &PerceptionConfig, player: &StealthInputs) -> f32 {
+    let distance = (player.position - npc_pos).length();
+    let range = config.hearing_radius * player.noise();
+    if range <= 0.0 || distance > range {
+        return 0.0;
+    }
+    1.0 - distance / range
+}
+
+/// Raycast line of sight through the physics world. `exclude` should be the
+/// player's own collider so it doesn't block the ray to itself.
+pub fn line_of_sight(
+    physics: &PhysicsWorld,
+    from: Vec3,
+    to: Vec3,
+    exclude: Option<ColliderHandle>,
+) -> bool {
+    let delta = to - from;
+    let distance = delta.length();
+    if distance < 0.01 {
+        return true;
+    }
+    let mut filter = QueryFilter::default();
+    if let Some(handle) = exclude {
+        filter = filter.exclude_collider(handle);
+    }
+    // Small margin so geometry right at the target doesn't count as blocking
+    physics
+        .raycast(from, delta / distance, (distance - 0.3).max(0.0), filter)
+        .is_none()
+}
+
+/// Accumulated awareness of the player for one NPC
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Awareness {
+    value: f32,
+    state: DetectionState,
+}
+
+impl Awareness {
+    pub fn state(&self) -> DetectionState {
+        self.state
+    }
+
+    /// Awareness level (0.0 - 1.0)
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Feed this frame's strongest stimulus (sight or hearing)
+    pub fn update(&mut self, delta: f32, stimulus: f32) {
+        if stimulus > 0.0 {
+            self.value += stimulus * balance::npc::AWARENESS_GAIN.get() * delta;
+        } else {
+            self.value -= balance::npc::AWARENESS_DECAY.get() * delta;
+        }
+        self.value = self.value.clamp(0.0, ALERTED_THRESHOLD);
+
+        self.state = match self.state {
+            DetectionState::Alerted if self.value >= CALM_THRESHOLD => DetectionState::Alerted,
+            _ if self.value >= ALERTED_THRESHOLD => DetectionState::Alerted,
+            _ if self.value >= SUSPICIOUS_THRESHOLD => DetectionState::Suspicious,
+            _ => DetectionState::Unaware,
+        };
+    }
+
+    /// Raise awareness to at least suspicious (e.g. a noise nearby).
+    /// Does nothing to an NPC that has already detected the player.
+    pub fn suspect(&mut self) {
+        if self.state == DetectionState::Unaware {
+            self.value = self.value.max(SUSPICIOUS_THRESHOLD);
+            self.state = DetectionState::Suspicious;
+        }
+    }
+
+    /// Immediately detect the player (e.g. after being hit)
+    pub fn alert(&mut self) {
+        self.value = ALERTED_THRESHOLD;
+        self.state = DetectionState::Alerted;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(crouching: bool, speed: f32, light_level: f32) -> StealthInputs {
+        let position = Vec3::new(0.0, 0.0, -10.0);
+        StealthInputs {
+            position,
+            eye_position: position + Vec3::Y * 1.6,
+            crouching,
+            speed,
+            walk_speed: 5.0,
+            light_level,
+        }
+    }
+
+    #[test]
+    fn test_view_cone_and_line_of_sight() {
+        let config = PerceptionConfig::default();
+        let eye = Vec3::Y * 1.6;
+        let p = player(false, 0.0, 1.0);
+
+        // Facing the player (-Z) with clear sight
+        assert!(sight_stimulus(eye, Vec3::NEG_Z, &config, &p, |_, _| true) > 0.0);
+        // Facing away
+        assert_eq!(sight_stimulus(eye, Vec3::Z, &config, &p, |_, _| true), 0.0);
+        // Blocked by geometry
+        assert_eq!(sight_stimulus(eye, Vec3::NEG_Z, &config, &p, |_, _| false), 0.0);
+    }
+
+    #[test]
+    fn test_crouch_and_darkness_reduce_detection() {
+        let config = PerceptionConfig::default();
+        let eye = Vec3::Y * 1.6;
+        let standing_day = sight_stimulus(eye, Vec3::NEG_Z, &config, &player(false, 0.0, 1.0), |_, _| true);
+        let crouching_night = sight_stimulus(eye, Vec3::NEG_Z, &config, &player(true, 0.0, 0.15), |_, _| true);
+        assert!(crouching_night < standing_day * 0.2);
+
+        // Sprinting is heard from further away than sneaking
+        let npc = Vec3::new(0.0, 0.0, -4.0);
+        assert!(hearing_stimulus(npc, &config, &player(false, 9.0, 1.0)) > 0.0);
+        assert_eq!(hearing_stimulus(npc, &config, &player(true, 2.0, 1.0)), 0.0);
+        assert_eq!(hearing_stimulus(npc, &config, &player(false, 0.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn test_awareness_states_with_hysteresis() {
+        let mut awareness = Awareness::default();
+        awareness.update(0.25, 1.0);
+        assert_eq!(awareness.state(), DetectionState::Suspicious);
+        awareness.update(1.0, 1.0);
+        assert_eq!(awareness.state(), DetectionState::Alerted);
+
+        // Stays alerted while decaying above the calm threshold
+        awareness.update(1.0, 0.0);
+        assert_eq!(awareness.state(), DetectionState::Alerted);
+        awareness.update(2.0, 0.0);
+        assert_eq!(awareness.state(), DetectionState::Suspicious);
+        awareness.update(5.0, 0.0);
+        assert_eq!(awareness.state(), DetectionState::Unaware);
+
+        awareness.suspect();
+        assert_eq!(awareness.state(), DetectionState::Suspicious);
+        awareness.alert();
+        awareness.suspect();
+        assert_eq!(awareness.state(), DetectionState::Alerted);
+    }
+}
@@ -0,0 +1,118 @@
+//! Population scaling
+//!
+//! How busy the world is comes from the player's settings. A density
+//! multiplier thins out chunk spawn points below 1, and above 1 it opens
+//! each chunk's extra points for visitors and more monsters. Hostiles can
+//! be capped, and ambient life (the villagers and guards going about
+//! their day) can be turned off; shopkeepers and quest givers always
+//! spawn. Each point has a fixed roll, so a density always keeps the same
+//! NPCs, and raising it only ever adds to them.
+
+use infinite_core::DetRng;
+use serde::{Deserialize, Serialize};
+
+use super::spawn::EXTRA_SPAWN_COUNT;
+use super::NpcRole;
+
+/// Population settings the NPC manager spawns by
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PopulationConfig {
+    /// Share of spawn points populated (0 - 1), or above 1 how far the
+    /// extra points are opened (all of them at [`PopulationConfig::MAX_DENSITY`])
+    pub density: f32,
+    /// Most hostile NPCs alive at once (None = no limit)
+    pub max_hostiles: Option<usize>,
+    /// Spawn villagers and guards
+    pub ambient_life: bool,
+}
+
+impl Default for PopulationConfig {
+    fn default() -> Self {
+        Self {
+            density: 1.0,
+            max_hostiles: None,
+            ambient_life: true,
+        }
+    }
+}
+
+impl PopulationConfig {
+    /// Highest density the settings offer
+    pub const MAX_DENSITY: f32 = 2.0;
+
+    /// Whether a `role` NPC keyed `key` spawns at this density. `extra` is
+    /// for the chunk's extra points.
+    pub fn allows(&self, role: NpcRole, key: u64, extra: bool) -> bool {
+        let ambient = matches!(role, NpcRole::Villager | NpcRole::Guard);
+        if ambient && !self.ambient_life {
+            return false;
+        }
+        let threshold = if extra {
+            // Spread so the extras add as many NPCs as a chunk has on average
+            (self.density - 1.0) * AVERAGE_CHUNK_NPCS / EXTRA_SPAWN_COUNT as f32
+        } else if ambient || role == NpcRole::Enemy {
+            self.density
+        } else {
+            return true;
+        };
+        roll(key) < threshold
+    }
+
+    /// Whether another hostile can spawn while `alive` are
+    pub fn room_for_hostile(&self, alive: usize) -> bool {
+        self.max_hostiles.is_none_or(|max| alive < max)
+    }
+}
+
+/// NPCs per chunk from the regular spawn table, on average
+const AVERAGE_CHUNK_NPCS: f32 = 1.125;
+
+/// Fixed roll (0 - 1) of the spawn point keyed `key`
+fn roll(key: u64) -> f32 {
+    DetRng::new(key).next_f32()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept(config: &PopulationConfig, role: NpcRole, extra: bool) -> usize {
+        (0..1000u64).filter(|&key| config.allows(role, key, extra)).count()
+    }
+
+    #[test]
+    fn test_density_scales_spawns() {
+        let half = PopulationConfig { density: 0.5, ..Default::default() };
+        let full = PopulationConfig::default();
+        let busy = PopulationConfig { density: PopulationConfig::MAX_DENSITY, ..Default::default() };
+
+        assert!((400..600).contains(&kept(&half, NpcRole::Enemy, false)));
+        assert_eq!(kept(&full, NpcRole::Villager, false), 1000);
+        assert_eq!(kept(&full, NpcRole::Villager, true), 0);
+        assert!((300..450).contains(&kept(&busy, NpcRole::Enemy, true)));
+        // Shopkeepers and quest givers are never thinned out
+        let empty = PopulationConfig { density: 0.0, ..Default::default() };
+        assert_eq!(kept(&empty, NpcRole::Shopkeeper, false), 1000);
+        assert_eq!(kept(&empty, NpcRole::Guard, false), 0);
+    }
+
+    #[test]
+    fn test_raising_density_only_adds() {
+        let low = PopulationConfig { density: 0.3, ..Default::default() };
+        let high = PopulationConfig { density: 0.7, ..Default::default() };
+        for key in 0..500 {
+            assert!(!low.allows(NpcRole::Enemy, key, false) || high.allows(NpcRole::Enemy, key, false));
+        }
+    }
+
+    #[test]
+    fn test_ambient_life_and_hostile_cap() {
+        let quiet = PopulationConfig { ambient_life: false, max_hostiles: Some(2), ..Default::default() };
+        assert_eq!(kept(&quiet, NpcRole::Villager, false), 0);
+        assert_eq!(kept(&quiet, NpcRole::QuestGiver, false), 1000);
+        assert!(quiet.room_for_hostile(1));
+        assert!(!quiet.room_for_hostile(2));
+        assert!(PopulationConfig::default().room_for_hostile(10_000));
+    }
+}
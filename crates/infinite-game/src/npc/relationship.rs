@@ -1,11 +1,20 @@
 //! NPC relationship tracking — affection, conversation memory, and tiers
+//!
+//! Besides the condensed memory fed to AI dialogue, each relationship keeps
+//! a log of past conversations, the gifts the player gave and the quests
+//! the NPC brought up, for the player to look back on.
 
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-/// Relationship tier based on affection level
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Conversations kept in each NPC's log; older ones drop off
+const MAX_LOGGED_CONVERSATIONS: usize = 25;
+/// Affection a gift earns
+const GIFT_AFFECTION: f32 = 3.0;
+
+/// Relationship tier based on affection level, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RelationshipTier {
     Stranger,
     Acquaintance,
@@ -41,13 +50,21 @@ impl RelationshipTier {
 }
 
 /// A message in the relationship history
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RelationshipMessage {
     pub speaker: String,
     pub text: String,
     pub is_player: bool,
 }
 
+/// One past conversation, as the player saw it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationRecord {
+    /// Year the conversation took place in
+    pub year: i64,
+    pub messages: Vec<RelationshipMessage>,
+}
+
 /// Relationship data for a single NPC
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NpcRelationship {
@@ -55,6 +72,18 @@ pub struct NpcRelationship {
     pub times_spoken: u32,
     pub conversation_summary: Option<String>,
     pub recent_messages: Vec<RelationshipMessage>,
+    /// The NPC's name when last spoken to (for menus, while it's unloaded)
+    #[serde(default)]
+    pub name: String,
+    /// Past conversations, oldest first
+    #[serde(default)]
+    pub conversations: Vec<ConversationRecord>,
+    /// Names of the items given to the NPC, oldest first
+    #[serde(default)]
+    pub gifts: Vec<String>,
+    /// Quest flags set while talking to the NPC
+    #[serde(default)]
+    pub quests: Vec<String>,
 }
 
 impl NpcRelationship {
@@ -64,6 +93,10 @@ impl NpcRelationship {
             times_spoken: 0,
             conversation_summary: None,
             recent_messages: Vec::new(),
+            name: String::new(),
+            conversations: Vec::new(),
+            gifts: Vec::new(),
+            quests: Vec::new(),
         }
     }
 
@@ -87,6 +120,33 @@ impl NpcRelationship {
         self.condense_if_needed();
     }
 
+    /// Add a conversation to the log the player can read back
+    pub fn log_conversation(&mut self, year: i64, messages: &[RelationshipMessage]) {
+        if messages.is_empty() {
+            return;
+        }
+        if self.conversations.len() == MAX_LOGGED_CONVERSATIONS {
+            self.conversations.remove(0);
+        }
+        self.conversations.push(ConversationRecord {
+            year,
+            messages: messages.to_vec(),
+        });
+    }
+
+    /// Record a gift; gifts always please
+    pub fn record_gift(&mut self, item_name: impl Into<String>) {
+        self.affection = (self.affection + GIFT_AFFECTION).min(100.0);
+        self.gifts.push(item_name.into());
+    }
+
+    /// Note a quest flag the NPC is involved in
+    pub fn note_quest(&mut self, key: &str) {
+        if !self.quests.iter().any(|q| q == key) {
+            self.quests.push(key.to_string());
+        }
+    }
+
     /// Condense older messages into a summary when >30 messages
     fn condense_if_needed(&mut self) {
         const MAX_RECENT: usize = 30;
@@ -145,6 +205,19 @@ impl RelationshipManager {
         self.relationships.get(&persistent_key)
     }
 
+    /// Every relationship with its NPC's persistent key
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &NpcRelationship)> {
+        self.relationships.iter().map(|(k, v)| (*k, v))
+    }
+
+    /// Number of NPCs at Friend tier or above
+    pub fn befriended_count(&self) -> usize {
+        self.relationships
+            .values()
+            .filter(|r| !matches!(r.tier(), RelationshipTier::Stranger | RelationshipTier::Acquaintance))
+            .count()
+    }
+
     /// Convert to save data
     pub fn to_save_data(&self) -> RelationshipSaveData {
         let relationships = self
@@ -258,4 +331,53 @@ mod tests {
         assert_eq!(restored_rel.affection, 50.0);
         assert_eq!(restored_rel.times_spoken, 3);
     }
+
+    #[test]
+    fn test_conversation_log_keeps_the_latest() {
+        let mut rel = NpcRelationship::new();
+        rel.log_conversation(1200, &[]);
+        assert!(rel.conversations.is_empty());
+
+        for i in 0..MAX_LOGGED_CONVERSATIONS + 5 {
+            let message = RelationshipMessage { speaker: "Ada".into(), text: format!("Chat {}", i), is_player: false };
+            rel.log_conversation(1200 + i as i64, &[message]);
+        }
+        assert_eq!(rel.conversations.len(), MAX_LOGGED_CONVERSATIONS);
+        assert_eq!(rel.conversations[0].year, 1205);
+        assert_eq!(rel.conversations.last().unwrap().messages[0].text, "Chat 29");
+        // Logging alone doesn't change affection
+        assert_eq!(rel.affection, 0.0);
+    }
+
+    #[test]
+    fn test_gifts_and_quests() {
+        let mut rel = NpcRelationship::new();
+        rel.record_gift("Apple");
+        rel.record_gift("Apple");
+        assert_eq!(rel.gifts, vec!["Apple", "Apple"]);
+        assert_eq!(rel.affection, GIFT_AFFECTION * 2.0);
+
+        rel.note_quest("heard_of_rifts");
+        rel.note_quest("heard_of_rifts");
+        assert_eq!(rel.quests, vec!["heard_of_rifts"]);
+    }
+
+    #[test]
+    fn test_old_saves_load_without_a_log() {
+        let json = r#"{"relationships":{"7":{"affection":20.0,"times_spoken":2,"conversation_summary":null,"recent_messages":[]}}}"#;
+        let data: RelationshipSaveData = serde_json::from_str(json).unwrap();
+        let manager = RelationshipManager::from_save_data(&data);
+        let rel = manager.get(7).unwrap();
+        assert_eq!(rel.times_spoken, 2);
+        assert!(rel.conversations.is_empty() && rel.gifts.is_empty() && rel.quests.is_empty());
+    }
+
+    #[test]
+    fn test_befriended_count() {
+        let mut manager = RelationshipManager::new();
+        manager.get_or_create(1).affection = 10.0;
+        manager.get_or_create(2).affection = 40.0;
+        manager.get_or_create(3).affection = 95.0;
+        assert_eq!(manager.befriended_count(), 2);
+    }
 }
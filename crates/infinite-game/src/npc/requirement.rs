@@ -0,0 +1,230 @@
+//! Dialogue requirements and stat checks
+//!
+//! A response can be locked behind the player's standing with the NPC,
+//! their reputation with a faction, a stat, an item they carry or a world
+//! flag. Locked responses stay visible with their requirements so the
+//! player knows what would open them. A stat check is always offered
+//! instead, and branches on whether the player's stat meets its difficulty.
+
+use serde::{Deserialize, Serialize};
+
+use super::relationship::RelationshipTier;
+use crate::combat::item::Item;
+use crate::flags::{FlagCondition, WorldFlags, REPUTATION_NAMESPACE};
+use crate::player::stats::CharacterStats;
+
+/// Player stat a requirement or check reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DialogueStat {
+    Level,
+    Attack,
+    Defense,
+    Speed,
+    MaxHp,
+}
+
+impl DialogueStat {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Level => "Level",
+            Self::Attack => "Attack",
+            Self::Defense => "Defense",
+            Self::Speed => "Speed",
+            Self::MaxHp => "Max HP",
+        }
+    }
+}
+
+/// What the player brings to a conversation
+#[derive(Debug, Clone, Copy)]
+pub struct DialoguePlayer<'a> {
+    /// Standing with the NPC being talked to
+    pub tier: RelationshipTier,
+    pub level: u32,
+    pub attack: f32,
+    pub defense: f32,
+    pub speed: f32,
+    pub max_hp: f32,
+    /// Items carried
+    pub items: &'a [Item],
+}
+
+impl<'a> DialoguePlayer<'a> {
+    pub fn new(tier: RelationshipTier, level: u32, stats: &CharacterStats, items: &'a [Item]) -> Self {
+        Self {
+            tier,
+            level,
+            attack: stats.attack,
+            defense: stats.defense,
+            speed: stats.speed,
+            max_hp: stats.max_hp,
+            items,
+        }
+    }
+
+    pub fn stat(&self, stat: DialogueStat) -> f32 {
+        match stat {
+            DialogueStat::Level => self.level as f32,
+            DialogueStat::Attack => self.attack,
+            DialogueStat::Defense => self.defense,
+            DialogueStat::Speed => self.speed,
+            DialogueStat::MaxHp => self.max_hp,
+        }
+    }
+
+    /// Whether an item with this name is carried (case-insensitive)
+    pub fn holds(&self, item_name: &str) -> bool {
+        self.items.iter().any(|item| item.name.eq_ignore_ascii_case(item_name))
+    }
+}
+
+impl Default for DialoguePlayer<'_> {
+    /// A level 1 stranger with base stats and empty pockets
+    fn default() -> Self {
+        Self::new(RelationshipTier::Stranger, 1, &CharacterStats::default(), &[])
+    }
+}
+
+/// Reputation with a faction (0 = unknown to them)
+pub fn reputation(flags: &WorldFlags, faction: &str) -> i64 {
+    flags.get_int(REPUTATION_NAMESPACE, faction)
+}
+
+/// Something the player needs before a response unlocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Requirement {
+    /// At least this close to the NPC
+    Tier(RelationshipTier),
+    /// Reputation of at least `min` with a faction
+    Reputation { faction: String, min: i64 },
+    /// A stat of at least `min`
+    Stat { stat: DialogueStat, min: f32 },
+    /// Carrying an item with this name
+    Item(String),
+    /// A world flag condition, shown to the player as `label`
+    Flag { condition: FlagCondition, label: String },
+}
+
+impl Requirement {
+    pub fn is_met(&self, player: &DialoguePlayer, flags: &WorldFlags) -> bool {
+        match self {
+            Self::Tier(tier) => player.tier >= *tier,
+            Self::Reputation { faction, min } => reputation(flags, faction) >= *min,
+            Self::Stat { stat, min } => player.stat(*stat) >= *min,
+            Self::Item(name) => player.holds(name),
+            Self::Flag { condition, .. } => condition.check(flags),
+        }
+    }
+
+    /// Short text shown on the response, e.g. "Friend" or "Attack 15"
+    pub fn label(&self) -> String {
+        match self {
+            Self::Tier(tier) => tier.name().to_string(),
+            Self::Reputation { faction, min } => format!("{} {}", faction_name(faction), min),
+            Self::Stat { stat, min } => format!("{} {:.0}", stat.name(), min),
+            Self::Item(name) => name.clone(),
+            Self::Flag { label, .. } => label.clone(),
+        }
+    }
+}
+
+/// A check offered to everyone: leads to `success` when the player's stat
+/// reaches `difficulty`, otherwise to `failure` (None ends the conversation)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatCheck {
+    pub stat: DialogueStat,
+    pub difficulty: f32,
+    #[serde(default)]
+    pub success: Option<usize>,
+    #[serde(default)]
+    pub failure: Option<usize>,
+}
+
+impl StatCheck {
+    pub fn new(stat: DialogueStat, difficulty: f32, success: Option<usize>, failure: Option<usize>) -> Self {
+        Self {
+            stat,
+            difficulty,
+            success,
+            failure,
+        }
+    }
+
+    pub fn passes(&self, player: &DialoguePlayer) -> bool {
+        player.stat(self.stat) >= self.difficulty
+    }
+
+    /// Node the check leads to
+    pub fn next_node(&self, passed: bool) -> Option<usize> {
+        if passed {
+            self.success
+        } else {
+            self.failure
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} {:.0}", self.stat.name(), self.difficulty)
+    }
+}
+
+/// "town_watch" -> "Town Watch"
+fn faction_name(key: &str) -> String {
+    key.split(['_', '-', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requirements() {
+        let stats = CharacterStats::default();
+        let torch = crate::combat::starter_items::create_torch();
+        let items = [torch];
+        let player = DialoguePlayer::new(RelationshipTier::Friend, 4, &stats, &items);
+        let mut flags = WorldFlags::new();
+
+        assert!(Requirement::Tier(RelationshipTier::Acquaintance).is_met(&player, &flags));
+        assert!(!Requirement::Tier(RelationshipTier::Trusted).is_met(&player, &flags));
+        assert!(Requirement::Item("torch".into()).is_met(&player, &flags));
+        assert!(!Requirement::Item("Lantern".into()).is_met(&player, &flags));
+        assert!(Requirement::Stat { stat: DialogueStat::Level, min: 4.0 }.is_met(&player, &flags));
+        assert!(!Requirement::Stat { stat: DialogueStat::Attack, min: 15.0 }.is_met(&player, &flags));
+
+        let standing = Requirement::Reputation { faction: "town_watch".into(), min: 10 };
+        assert_eq!(standing.label(), "Town Watch 10");
+        assert!(!standing.is_met(&player, &flags));
+        flags.add_int(REPUTATION_NAMESPACE, "town_watch", 10);
+        assert!(standing.is_met(&player, &flags));
+
+        let flag = Requirement::Flag {
+            condition: FlagCondition::is_set("quest", "heard_of_rifts"),
+            label: "Heard of the rifts".into(),
+        };
+        assert!(!flag.is_met(&player, &flags));
+        flags.set("quest", "heard_of_rifts", true);
+        assert!(flag.is_met(&player, &flags));
+    }
+
+    #[test]
+    fn test_stat_check_branches() {
+        let check = StatCheck::new(DialogueStat::Attack, 15.0, Some(3), Some(4));
+        assert_eq!(check.label(), "Attack 15");
+
+        let weak = DialoguePlayer::default();
+        assert!(!check.passes(&weak));
+        assert_eq!(check.next_node(check.passes(&weak)), Some(4));
+
+        let strong = DialoguePlayer { attack: 18.0, ..weak };
+        assert!(check.passes(&strong));
+        assert_eq!(check.next_node(check.passes(&strong)), Some(3));
+    }
+}
@@ -49,91 +49,88 @@ pub fn generate_spawn_points(cx: i32, cz: i32, chunk_size: f32) -> Vec<NpcSpawnP
         _ => 3,     // 12.5%
     };
 
-    let mut points = Vec::with_capacity(npc_count as usize);
-    for i in 0..npc_count {
-        let sub_hash = hash.wrapping_add(i as u64 * 7919);
-        let fx = ((sub_hash >> 0) & 0xFFFF) as f32 / 65535.0;
-        let fz = ((sub_hash >> 16) & 0xFFFF) as f32 / 65535.0;
-        let role_bits = ((sub_hash >> 32) & 0xFFFF) % 10;
-
-        let offset_x = fx * chunk_size * 0.8 + chunk_size * 0.1;
-        let offset_z = fz * chunk_size * 0.8 + chunk_size * 0.1;
-
-        let (role, faction, wander_radius) = match role_bits {
-            0..=3 => (NpcRole::Villager, NpcFaction::Friendly, 10.0),
-            4..=5 => (NpcRole::Guard, NpcFaction::Friendly, 15.0),
-            6 => (NpcRole::Shopkeeper, NpcFaction::Neutral, 3.0),
-            7 => (NpcRole::QuestGiver, NpcFaction::Friendly, 5.0),
-            _ => (NpcRole::Enemy, NpcFaction::Hostile, 20.0),
-        };
-
-        let npc_name_index = (sub_hash >> 48) % 16;
-        let name = npc_name(role, npc_name_index as usize);
-
-        let year_range = if role == NpcRole::Enemy {
-            // Enemies don't spawn in the deep past (before 1000 BCE)
-            Some((-1000_i64, 5000_i64))
-        } else {
-            None
-        };
-
-        points.push(NpcSpawnPoint {
-            offset: Vec3::new(offset_x, 0.0, offset_z),
-            data: NpcData {
-                name,
-                role,
-                faction,
-                home_position: Vec3::ZERO, // set during spawn (world coords)
-                wander_radius,
-                interaction_radius: 3.0,
-                color: role.color(),
-                server_character_id: None,
-            },
-            year_range,
-            spawn_index: i as usize,
-        });
-    }
+    (0..npc_count)
+        .map(|i| {
+            let sub_hash = hash.wrapping_add(i as u64 * 7919);
+            let role = match ((sub_hash >> 32) & 0xFFFF) % 10 {
+                0..=3 => NpcRole::Villager,
+                4..=5 => NpcRole::Guard,
+                6 => NpcRole::Shopkeeper,
+                7 => NpcRole::QuestGiver,
+                _ => NpcRole::Enemy,
+            };
+            spawn_point(sub_hash, role, i as usize, chunk_size)
+        })
+        .collect()
+}
 
-    points
+/// Spawn index of a chunk's first extra point
+pub const EXTRA_SPAWN_INDEX: usize = 8;
+/// Extra points a chunk can have
+pub const EXTRA_SPAWN_COUNT: usize = 3;
+
+/// Extra spawn points of a chunk, for population densities above 1:
+/// visiting townsfolk and more monsters. Visitors have no household, so
+/// these never touch the population ledger.
+pub fn generate_extra_spawn_points(cx: i32, cz: i32, chunk_size: f32) -> Vec<NpcSpawnPoint> {
+    let hash = chunk_hash(cx, cz) ^ 0x9e37_79b9_7f4a_7c15;
+    (0..EXTRA_SPAWN_COUNT)
+        .map(|i| {
+            let sub_hash = hash.wrapping_add(i as u64 * 104729);
+            let role = if ((sub_hash >> 32) & 0xFFFF) % 10 < 6 { NpcRole::Villager } else { NpcRole::Enemy };
+            spawn_point(sub_hash, role, EXTRA_SPAWN_INDEX + i, chunk_size)
+        })
+        .collect()
 }
 
-fn npc_name(role: NpcRole, index: usize) -> String {
-    let names: &[&str] = match role {
-        NpcRole::Villager => &[
-            "Finn", "Elara", "Rowan", "Iris", "Aldric", "Senna",
-            "Bram", "Lila", "Oswin", "Thea", "Cedric", "Mira",
-            "Gareth", "Yara", "Dorian", "Vena",
-        ],
-        NpcRole::Guard => &[
-            "Captain Bron", "Sentinel Kael", "Warden Thorne", "Guard Voss",
-            "Patrol Hagen", "Watch Sera", "Guard Drex", "Shield Lynne",
-            "Sentry Orsk", "Guard Pike", "Watch Farl", "Guard Brin",
-            "Shield Tarn", "Guard Nyx", "Watch Rael", "Guard Siv",
-        ],
-        NpcRole::Shopkeeper => &[
-            "Merchant Haldo", "Trader Pim", "Vendor Gris", "Seller Bea",
-            "Peddler Tock", "Dealer Faye", "Hawker Rust", "Buyer Nell",
-            "Broker Joss", "Vendor Skye", "Trader Opal", "Dealer Wren",
-            "Seller Tane", "Vendor Mace", "Trader Glen", "Dealer Sage",
-        ],
-        NpcRole::QuestGiver => &[
-            "Elder Morvyn", "Sage Althea", "Scholar Tobin", "Mystic Fen",
-            "Oracle Rhea", "Seer Callum", "Lorekeeper Ida", "Prophet Zev",
-            "Diviner Shae", "Augur Brynn", "Hermit Gale", "Sage Lorin",
-            "Elder Frey", "Seer Nola", "Oracle Dane", "Wise Elm",
-        ],
-        NpcRole::Enemy => &[
-            "Bandit", "Marauder", "Raider", "Thug",
-            "Brigand", "Outlaw", "Rogue", "Cutthroat",
-            "Prowler", "Scavenger", "Pillager", "Wretch",
-            "Vandal", "Looter", "Brute", "Thief",
-        ],
+/// A point somewhere in the middle 80% of the chunk, placed by `hash`
+fn spawn_point(hash: u64, role: NpcRole, spawn_index: usize, chunk_size: f32) -> NpcSpawnPoint {
+    let fx = (hash & 0xFFFF) as f32 / 65535.0;
+    let fz = ((hash >> 16) & 0xFFFF) as f32 / 65535.0;
+    let offset_x = fx * chunk_size * 0.8 + chunk_size * 0.1;
+    let offset_z = fz * chunk_size * 0.8 + chunk_size * 0.1;
+    let (faction, wander_radius) = role_defaults(role);
+
+    let year_range = if role == NpcRole::Enemy {
+        // Enemies don't spawn in the deep past (before 1000 BCE)
+        Some((-1000_i64, 5000_i64))
+    } else {
+        None
     };
-    names[index % names.len()].to_string()
+
+    NpcSpawnPoint {
+        offset: Vec3::new(offset_x, 0.0, offset_z),
+        data: NpcData {
+            // Named on spawn from the NPC's identity (era and world dependent)
+            name: role.name().to_string(),
+            role,
+            faction,
+            home_position: Vec3::ZERO, // set during spawn (world coords)
+            wander_radius,
+            interaction_radius: 3.0,
+            color: role.color(),
+            server_character_id: None,
+        },
+        year_range,
+        spawn_index,
+    }
+}
+
+/// Faction and wander radius an NPC of `role` spawns with
+pub(super) fn role_defaults(role: NpcRole) -> (NpcFaction, f32) {
+    match role {
+        NpcRole::Villager => (NpcFaction::Friendly, 10.0),
+        NpcRole::Guard => (NpcFaction::Friendly, 15.0),
+        NpcRole::Shopkeeper => (NpcFaction::Neutral, 3.0),
+        NpcRole::QuestGiver => (NpcFaction::Friendly, 5.0),
+        NpcRole::Enemy => (NpcFaction::Hostile, 20.0),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -187,4 +184,32 @@ mod tests {
             }
         }
     }
+
+    proptest! {
+        #[test]
+        fn test_spawns_are_pure_functions_of_the_chunk(cx in any::<i32>(), cz in any::<i32>()) {
+            let points = generate_spawn_points(cx, cz, 64.0);
+            let again = generate_spawn_points(cx, cz, 64.0);
+            prop_assert_eq!(points.len(), again.len());
+            for (a, b) in points.iter().zip(&again) {
+                prop_assert_eq!(a.offset, b.offset);
+                prop_assert_eq!(a.data.role, b.data.role);
+                prop_assert_eq!(a.spawn_index, b.spawn_index);
+            }
+
+            let extra = generate_extra_spawn_points(cx, cz, 64.0);
+            let mut keys: Vec<u64> = points
+                .iter()
+                .chain(&extra)
+                .map(|p| compute_persistent_key(cx, cz, p.spawn_index))
+                .collect();
+            for p in points.iter().chain(&extra) {
+                prop_assert!((0.0..=64.0).contains(&p.offset.x) && (0.0..=64.0).contains(&p.offset.z), "{:?}", p.offset);
+            }
+            // Every NPC in a chunk keeps its own relationships
+            keys.sort_unstable();
+            keys.dedup();
+            prop_assert_eq!(keys.len(), points.len() + extra.len());
+        }
+    }
 }
@@ -0,0 +1,504 @@
+//! Survival mode: endless enemy waves in an arena, played for score
+//!
+//! Every wave brings more and tougher enemies than the last, with a
+//! champion leading every fifth. Kills score points scaled by the wave and
+//! by a combo multiplier that builds while kills come quickly and resets
+//! when the player lets it lapse. Between waves the player gets a breather
+//! to spend points in the arena shop; the score itself only ever grows, and
+//! is what goes to the leaderboard when the player finally falls.
+
+use glam::Vec3;
+
+use super::combat::{CombatStats, PlayerCombatState};
+use super::{NpcData, NpcFaction, NpcId, NpcRole};
+use crate::combat::element::Element;
+use crate::combat::starter_items::create_health_potion;
+
+/// Leaderboard the runs are submitted to
+pub const SURVIVAL_BOARD: &str = "survival";
+
+/// Seconds before the first wave
+pub const FIRST_WAVE_DELAY: f32 = 8.0;
+
+/// Seconds of breather between waves
+pub const INTERMISSION_SECONDS: f32 = 25.0;
+
+/// Seconds after a kill the next one still counts toward the combo
+pub const COMBO_WINDOW: f32 = 4.0;
+
+/// Multiplier added per kill in a combo after the first
+const COMBO_STEP: f32 = 0.25;
+
+/// Highest the combo multiplier goes
+pub const MAX_COMBO_MULTIPLIER: f32 = 4.0;
+
+/// A champion leads every this many waves
+pub const CHAMPION_EVERY: u32 = 5;
+
+/// Enemies in the first wave, and how many more each wave brings
+const FIRST_WAVE_SIZE: u32 = 3;
+const WAVE_GROWTH: u32 = 1;
+
+/// Most enemies a single wave sends in
+const MAX_WAVE_SIZE: u32 = 12;
+
+/// Extra enemy health and attack per wave after the first
+const HP_PER_WAVE: f32 = 0.15;
+const ATTACK_PER_WAVE: f32 = 0.1;
+
+/// Points for a kill before the wave and combo multipliers
+const KILL_POINTS: u64 = 10;
+const CHAMPION_POINTS: u64 = 100;
+
+/// Bonus for clearing a wave, times the wave number
+const WAVE_CLEAR_POINTS: u64 = 25;
+
+/// Distance from the arena centre enemies come in at
+const SPAWN_RADIUS: f32 = 22.0;
+
+/// Enemy elements, cycled through a wave at a time
+const WAVE_ELEMENTS: [Element; 6] = [
+    Element::Physical,
+    Element::Fire,
+    Element::Earth,
+    Element::Water,
+    Element::Air,
+    Element::Void,
+];
+
+/// Where a run stands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SurvivalPhase {
+    /// Waiting for the next wave, with the shop open
+    Intermission { remaining: f32 },
+    /// A wave is on the floor
+    Fighting,
+    /// The player fell
+    Over,
+}
+
+/// What the caller should do after a survival update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurvivalEvent {
+    /// Spawn the given wave (1-based) using `SurvivalRun::wave_spawns`
+    SpawnWave(u32),
+    /// The wave was cleared and the intermission began
+    WaveCleared(u32),
+}
+
+/// Something the arena shop sells for points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurvivalUpgrade {
+    /// A health potion for the inventory
+    Potion,
+    /// Back to full health
+    Heal,
+    /// +3 attack
+    Sharpen,
+    /// +2 defense
+    Toughen,
+    /// +20 max HP
+    Vigor,
+}
+
+impl SurvivalUpgrade {
+    pub const ALL: [SurvivalUpgrade; 5] = [Self::Potion, Self::Heal, Self::Sharpen, Self::Toughen, Self::Vigor];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Potion => "Health Potion",
+            Self::Heal => "Field Dressing",
+            Self::Sharpen => "Whetstone",
+            Self::Toughen => "Iron Plating",
+            Self::Vigor => "Gladiator's Draught",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Potion => "One potion for the belt",
+            Self::Heal => "Restore all health",
+            Self::Sharpen => "+3 attack for the run",
+            Self::Toughen => "+2 defense for the run",
+            Self::Vigor => "+20 max HP for the run",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn base_cost(self) -> u64 {
+        match self {
+            Self::Potion => 40,
+            Self::Heal => 60,
+            Self::Sharpen | Self::Toughen => 120,
+            Self::Vigor => 100,
+        }
+    }
+
+    /// Points it costs after `bought` earlier purchases; stat boosts get
+    /// dearer every time, consumables stay flat
+    pub fn cost(self, bought: u32) -> u64 {
+        match self {
+            Self::Potion | Self::Heal => self.base_cost(),
+            _ => self.base_cost() * (1 + bought as u64),
+        }
+    }
+
+    /// Apply to the player. Fails only when a potion won't fit.
+    fn apply(self, combat: &mut PlayerCombatState) -> Result<(), SurvivalError> {
+        match self {
+            Self::Potion => combat
+                .inventory
+                .add_item(create_health_potion(1))
+                .map_err(|_| SurvivalError::InventoryFull)?,
+            Self::Heal => combat.stats.current_hp = combat.max_hp(),
+            Self::Sharpen => combat.stats.attack += 3.0,
+            Self::Toughen => combat.stats.defense += 2.0,
+            Self::Vigor => {
+                combat.stats.max_hp += 20.0;
+                combat.stats.current_hp += 20.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why the arena shop turned a purchase down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurvivalError {
+    /// The shop only opens between waves
+    WaveUnderway,
+    NotEnoughPoints,
+    InventoryFull,
+}
+
+/// A survival run in progress
+#[derive(Debug, Clone)]
+pub struct SurvivalRun {
+    pub center: Vec3,
+    phase: SurvivalPhase,
+    /// Current wave (0 before the first wave spawns)
+    wave: u32,
+    alive: Vec<NpcId>,
+    champion: Option<NpcId>,
+    score: u64,
+    /// Points left to spend in the shop
+    points: u64,
+    kills: u32,
+    combo: u32,
+    combo_timer: f32,
+    best_combo: u32,
+    purchases: [u32; SurvivalUpgrade::ALL.len()],
+}
+
+impl SurvivalRun {
+    pub fn new(center: Vec3) -> Self {
+        Self {
+            center,
+            phase: SurvivalPhase::Intermission { remaining: FIRST_WAVE_DELAY },
+            wave: 0,
+            alive: Vec::new(),
+            champion: None,
+            score: 0,
+            points: 0,
+            kills: 0,
+            combo: 0,
+            combo_timer: 0.0,
+            best_combo: 0,
+            purchases: [0; SurvivalUpgrade::ALL.len()],
+        }
+    }
+
+    pub fn phase(&self) -> SurvivalPhase {
+        self.phase
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    pub fn kills(&self) -> u32 {
+        self.kills
+    }
+
+    /// Kills in the current combo
+    pub fn combo(&self) -> u32 {
+        self.combo
+    }
+
+    pub fn best_combo(&self) -> u32 {
+        self.best_combo
+    }
+
+    /// Seconds left to keep the combo going
+    pub fn combo_time_left(&self) -> f32 {
+        self.combo_timer
+    }
+
+    /// What the next kill's points are multiplied by for the combo
+    pub fn combo_multiplier(&self) -> f32 {
+        (1.0 + self.combo as f32 * COMBO_STEP).min(MAX_COMBO_MULTIPLIER)
+    }
+
+    /// Enemies from the current wave still standing
+    pub fn remaining(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// Enemy IDs still alive (for despawning when the run ends)
+    pub fn alive(&self) -> &[NpcId] {
+        &self.alive
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.phase == SurvivalPhase::Over
+    }
+
+    /// Points the upgrade costs right now
+    pub fn cost(&self, upgrade: SurvivalUpgrade) -> u64 {
+        upgrade.cost(self.purchases[upgrade.index()])
+    }
+
+    /// NPC data and stats (with ground-plane spawn positions) for the next wave
+    pub fn wave_spawns(&self) -> Vec<(Vec3, NpcData, CombatStats)> {
+        let wave = self.wave + 1;
+        let element = WAVE_ELEMENTS[(wave as usize - 1) % WAVE_ELEMENTS.len()];
+        let champion = wave.is_multiple_of(CHAMPION_EVERY);
+        let mut count = (FIRST_WAVE_SIZE + WAVE_GROWTH * (wave - 1)).min(MAX_WAVE_SIZE);
+        // A champion wave is the champion and a smaller escort
+        if champion {
+            count = (count / 2).max(1);
+        }
+        let scale = wave as f32 - 1.0;
+
+        (0..count)
+            .map(|i| {
+                let is_champion = champion && i == 0;
+                let angle = i as f32 / count as f32 * std::f32::consts::TAU + wave as f32 * 0.9;
+                let position = self.center + Vec3::new(angle.cos(), 0.0, angle.sin()) * SPAWN_RADIUS;
+                let data = NpcData {
+                    name: if is_champion { "Arena Champion" } else { "Arena Fighter" }.to_string(),
+                    role: NpcRole::Enemy,
+                    faction: NpcFaction::Hostile,
+                    home_position: position,
+                    wander_radius: SPAWN_RADIUS,
+                    interaction_radius: 0.0,
+                    color: NpcRole::Enemy.color(),
+                    server_character_id: None,
+                };
+                let mut stats = if is_champion {
+                    CombatStats::boss(element)
+                } else {
+                    CombatStats::elemental_enemy(element)
+                };
+                stats.max_hp *= 1.0 + HP_PER_WAVE * scale;
+                stats.current_hp = stats.max_hp;
+                stats.attack *= 1.0 + ATTACK_PER_WAVE * scale;
+                // Nowhere to hide in the arena
+                stats.aggro_radius = SPAWN_RADIUS * 4.0;
+                stats.de_aggro_radius = SPAWN_RADIUS * 5.0;
+                (position, data, stats)
+            })
+            .collect()
+    }
+
+    /// Record the NPCs spawned for the next wave. A champion wave's champion
+    /// comes first.
+    pub fn begin_wave(&mut self, ids: Vec<NpcId>) {
+        self.wave += 1;
+        self.champion = self.wave.is_multiple_of(CHAMPION_EVERY).then(|| ids.first().copied()).flatten();
+        self.alive = ids;
+        self.phase = SurvivalPhase::Fighting;
+    }
+
+    /// Count kills, run the combo clock and the intermission, and report
+    /// when a wave is cleared or the next one should spawn
+    pub fn update(&mut self, delta: f32, is_alive: impl Fn(NpcId) -> bool) -> Option<SurvivalEvent> {
+        if self.combo_timer > 0.0 {
+            self.combo_timer -= delta;
+            if self.combo_timer <= 0.0 {
+                self.combo = 0;
+            }
+        }
+
+        match self.phase {
+            SurvivalPhase::Over => None,
+            SurvivalPhase::Intermission { remaining } => {
+                let remaining = remaining - delta;
+                if remaining <= 0.0 {
+                    Some(SurvivalEvent::SpawnWave(self.wave + 1))
+                } else {
+                    self.phase = SurvivalPhase::Intermission { remaining };
+                    None
+                }
+            }
+            SurvivalPhase::Fighting => {
+                let fallen: Vec<NpcId> = self.alive.iter().copied().filter(|id| !is_alive(*id)).collect();
+                for id in fallen {
+                    self.alive.retain(|alive| *alive != id);
+                    let base = if self.champion == Some(id) { CHAMPION_POINTS } else { KILL_POINTS };
+                    self.on_kill(base);
+                }
+                if !self.alive.is_empty() {
+                    return None;
+                }
+                self.award(WAVE_CLEAR_POINTS * self.wave as u64);
+                self.phase = SurvivalPhase::Intermission { remaining: INTERMISSION_SECONDS };
+                Some(SurvivalEvent::WaveCleared(self.wave))
+            }
+        }
+    }
+
+    /// Score a kill worth `base` points before the multipliers
+    fn on_kill(&mut self, base: u64) {
+        let multiplier = self.combo_multiplier() * self.wave as f32;
+        self.award((base as f32 * multiplier).round() as u64);
+        self.kills += 1;
+        self.combo += 1;
+        self.combo_timer = COMBO_WINDOW;
+        self.best_combo = self.best_combo.max(self.combo);
+    }
+
+    fn award(&mut self, amount: u64) {
+        self.score += amount;
+        self.points += amount;
+    }
+
+    /// Cut the intermission short and bring on the next wave
+    pub fn skip_intermission(&mut self) {
+        if let SurvivalPhase::Intermission { .. } = self.phase {
+            self.phase = SurvivalPhase::Intermission { remaining: 0.0 };
+        }
+    }
+
+    /// Spend points in the arena shop
+    pub fn buy(&mut self, upgrade: SurvivalUpgrade, combat: &mut PlayerCombatState) -> Result<(), SurvivalError> {
+        if !matches!(self.phase, SurvivalPhase::Intermission { .. }) {
+            return Err(SurvivalError::WaveUnderway);
+        }
+        let cost = self.cost(upgrade);
+        if self.points < cost {
+            return Err(SurvivalError::NotEnoughPoints);
+        }
+        upgrade.apply(combat)?;
+        self.points -= cost;
+        self.purchases[upgrade.index()] += 1;
+        Ok(())
+    }
+
+    /// The player fell: the run is over and its score final
+    pub fn end(&mut self) {
+        self.phase = SurvivalPhase::Over;
+        self.combo = 0;
+        self.combo_timer = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waves_escalate_with_champions() {
+        let mut run = SurvivalRun::new(Vec3::ZERO);
+        assert_eq!(run.update(FIRST_WAVE_DELAY - 1.0, |_| false), None);
+        assert_eq!(run.update(1.0, |_| false), Some(SurvivalEvent::SpawnWave(1)));
+
+        let first = run.wave_spawns();
+        assert_eq!(first.len(), FIRST_WAVE_SIZE as usize);
+        assert!(first.iter().all(|(p, _, _)| (p.length() - SPAWN_RADIUS).abs() < 1e-3));
+
+        run.wave = 3;
+        let fourth = run.wave_spawns();
+        assert_eq!(fourth.len(), 6);
+        assert!(fourth[0].2.max_hp > first[0].2.max_hp);
+        assert_eq!(fourth[0].2.current_hp, fourth[0].2.max_hp);
+
+        // Every fifth wave is a champion and its escort
+        run.wave = 4;
+        let fifth = run.wave_spawns();
+        assert_eq!(fifth[0].1.name, "Arena Champion");
+        assert_eq!(fifth.len(), 3);
+        assert!(fifth[1..].iter().all(|(_, data, _)| data.name == "Arena Fighter"));
+    }
+
+    #[test]
+    fn test_combo_multiplies_kill_points() {
+        let mut run = SurvivalRun::new(Vec3::ZERO);
+        run.begin_wave(vec![NpcId(1), NpcId(2), NpcId(3)]);
+        assert_eq!(run.phase(), SurvivalPhase::Fighting);
+
+        assert_eq!(run.update(0.1, |id| id != NpcId(1)), None);
+        assert_eq!(run.score(), 10);
+        assert_eq!(run.combo(), 1);
+
+        // Keeping the combo going adds to the multiplier
+        assert_eq!(run.update(1.0, |id| id == NpcId(3)), None);
+        assert_eq!(run.score(), 10 + 13);
+
+        // Letting it lapse resets it
+        run.update(COMBO_WINDOW, |id| id == NpcId(3));
+        assert_eq!(run.combo(), 0);
+        assert_eq!(run.best_combo(), 2);
+        assert_eq!(run.update(0.1, |_| false), Some(SurvivalEvent::WaveCleared(1)));
+        assert_eq!(run.kills(), 3);
+        assert_eq!(run.score(), 10 + 13 + 10 + WAVE_CLEAR_POINTS);
+        assert!(matches!(run.phase(), SurvivalPhase::Intermission { .. }));
+
+        run.combo = 100;
+        assert_eq!(run.combo_multiplier(), MAX_COMBO_MULTIPLIER);
+    }
+
+    #[test]
+    fn test_champion_kill_and_wave_scaling() {
+        let mut run = SurvivalRun::new(Vec3::ZERO);
+        run.wave = CHAMPION_EVERY - 1;
+        run.begin_wave(vec![NpcId(7), NpcId(8)]);
+        run.update(0.1, |id| id != NpcId(7));
+        assert_eq!(run.score(), CHAMPION_POINTS * CHAMPION_EVERY as u64);
+    }
+
+    #[test]
+    fn test_shop_spends_points_between_waves() {
+        let mut combat = PlayerCombatState::new();
+        let mut run = SurvivalRun::new(Vec3::ZERO);
+        assert_eq!(run.buy(SurvivalUpgrade::Sharpen, &mut combat), Err(SurvivalError::NotEnoughPoints));
+
+        run.award(250);
+        let attack = combat.stats.attack;
+        assert_eq!(run.buy(SurvivalUpgrade::Sharpen, &mut combat), Ok(()));
+        assert_eq!(combat.stats.attack, attack + 3.0);
+        assert_eq!(run.points(), 130);
+        // Stat boosts get dearer, but the score keeps what was earned
+        assert_eq!(run.cost(SurvivalUpgrade::Sharpen), 240);
+        assert_eq!(run.score(), 250);
+
+        combat.stats.current_hp = 1.0;
+        assert_eq!(run.buy(SurvivalUpgrade::Heal, &mut combat), Ok(()));
+        assert_eq!(combat.stats.current_hp, combat.max_hp());
+
+        run.begin_wave(vec![NpcId(1)]);
+        assert_eq!(run.buy(SurvivalUpgrade::Potion, &mut combat), Err(SurvivalError::WaveUnderway));
+
+        run.end();
+        assert!(run.is_over());
+        assert_eq!(run.update(100.0, |_| false), None);
+    }
+
+    #[test]
+    fn test_skip_intermission() {
+        let mut run = SurvivalRun::new(Vec3::ZERO);
+        run.skip_intermission();
+        assert_eq!(run.update(0.0, |_| false), Some(SurvivalEvent::SpawnWave(1)));
+    }
+}
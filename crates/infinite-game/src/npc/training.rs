@@ -0,0 +1,351 @@
+//! Training dummy and practice arena — targets for testing builds
+//!
+//! The dummy is an invulnerable NPC that records every hit it takes so the
+//! HUD can show incoming DPS and a breakdown of the last hit. The arena
+//! spawns configurable waves of enemies around a centre point and advances
+//! to the next wave once the current one is defeated.
+
+use std::collections::VecDeque;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use super::combat::CombatStats;
+use super::{NpcData, NpcFaction, NpcId, NpcRole};
+use crate::combat::damage::DamageEvent;
+use crate::combat::element::Element;
+
+/// Seconds of history used for the dummy's DPS readout
+const DUMMY_DPS_WINDOW: f32 = 5.0;
+
+/// Breakdown of a single hit on the training dummy
+#[derive(Debug, Clone, PartialEq)]
+pub struct DummyHit {
+    pub amount: f32,
+    pub element: Element,
+    /// "Light Attack", "Heavy Attack" or a skill name
+    pub source: String,
+    pub is_crit: bool,
+    /// Damage before multipliers and defense
+    pub base_amount: f32,
+    pub element_multiplier: f32,
+    pub weapon_multiplier: f32,
+}
+
+impl DummyHit {
+    /// A hit with no multiplier breakdown (e.g. skills)
+    pub fn new(amount: f32, element: Element, source: impl Into<String>, is_crit: bool) -> Self {
+        Self {
+            amount,
+            element,
+            source: source.into(),
+            is_crit,
+            base_amount: amount,
+            element_multiplier: 1.0,
+            weapon_multiplier: 1.0,
+        }
+    }
+
+    /// A hit from the full damage pipeline
+    pub fn from_event(event: &DamageEvent, source: impl Into<String>) -> Self {
+        Self {
+            amount: event.final_amount,
+            element: event.element,
+            source: source.into(),
+            is_crit: event.is_crit,
+            base_amount: event.base_amount,
+            element_multiplier: event.element_multiplier,
+            weapon_multiplier: event.weapon_multiplier,
+        }
+    }
+}
+
+/// Readout for a spawned training dummy
+#[derive(Debug, Clone)]
+pub struct TrainingDummy {
+    /// The dummy NPC
+    pub id: NpcId,
+    pub position: Vec3,
+    time: f32,
+    recent: VecDeque<(f32, f32)>,
+    total: f32,
+    hits: u32,
+    last_hit: Option<DummyHit>,
+}
+
+impl TrainingDummy {
+    pub fn new(id: NpcId, position: Vec3) -> Self {
+        Self {
+            id,
+            position,
+            time: 0.0,
+            recent: VecDeque::new(),
+            total: 0.0,
+            hits: 0,
+            last_hit: None,
+        }
+    }
+
+    /// NPC data for spawning a dummy: hostile so it can be targeted, but
+    /// stationary and without a brain so it never fights back
+    pub fn npc_data() -> NpcData {
+        NpcData {
+            name: "Training Dummy".to_string(),
+            role: NpcRole::Enemy,
+            faction: NpcFaction::Hostile,
+            home_position: Vec3::ZERO,
+            wander_radius: 0.0,
+            interaction_radius: 0.0,
+            color: [0.75, 0.6, 0.35, 1.0],
+            server_character_id: None,
+        }
+    }
+
+    /// Combat stats for the dummy (no defense or armor so readouts show raw damage)
+    pub fn combat_stats() -> CombatStats {
+        let mut stats = CombatStats::default_enemy();
+        stats.attack = 0.0;
+        stats.defense = 0.0;
+        stats.armor = 0.0;
+        stats.aggro_radius = 0.0;
+        stats
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+        let cutoff = self.time - DUMMY_DPS_WINDOW;
+        while self.recent.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.recent.pop_front();
+        }
+    }
+
+    pub fn record_hit(&mut self, hit: DummyHit) {
+        self.recent.push_back((self.time, hit.amount));
+        self.total += hit.amount;
+        self.hits += 1;
+        self.last_hit = Some(hit);
+    }
+
+    /// Incoming DPS, measured from the first hit in the window so a single
+    /// burst isn't averaged over idle time
+    pub fn dps(&self) -> f32 {
+        let Some((first, _)) = self.recent.front() else {
+            return 0.0;
+        };
+        let span = (self.time - first).max(1.0);
+        self.recent.iter().map(|(_, amount)| amount).sum::<f32>() / span
+    }
+
+    pub fn total(&self) -> f32 {
+        self.total
+    }
+
+    pub fn hits(&self) -> u32 {
+        self.hits
+    }
+
+    pub fn last_hit(&self) -> Option<&DummyHit> {
+        self.last_hit.as_ref()
+    }
+
+    /// Clear the readout
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.total = 0.0;
+        self.hits = 0;
+        self.last_hit = None;
+    }
+}
+
+/// Settings for a practice arena run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArenaConfig {
+    /// Number of waves
+    pub waves: u32,
+    /// Enemies in the first wave
+    pub enemies_per_wave: u32,
+    /// Extra enemies added each wave
+    pub wave_growth: u32,
+    /// Element of spawned enemies
+    pub element: Element,
+    /// Replace the last wave with a single boss
+    pub boss_final_wave: bool,
+    /// Distance from the arena centre enemies spawn at
+    pub spawn_radius: f32,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self {
+            waves: 3,
+            enemies_per_wave: 2,
+            wave_growth: 1,
+            element: Element::Physical,
+            boss_final_wave: true,
+            spawn_radius: 8.0,
+        }
+    }
+}
+
+/// What the caller should do after an arena update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaEvent {
+    /// Spawn the given wave (1-based) using `PracticeArena::wave_spawns`
+    SpawnWave(u32),
+    /// All waves have been defeated
+    Completed,
+}
+
+/// A running practice arena
+#[derive(Debug, Clone)]
+pub struct PracticeArena {
+    pub config: ArenaConfig,
+    pub center: Vec3,
+    /// Current wave (0 before the first wave spawns)
+    wave: u32,
+    alive: Vec<NpcId>,
+}
+
+impl PracticeArena {
+    pub fn new(config: ArenaConfig, center: Vec3) -> Self {
+        Self {
+            config,
+            center,
+            wave: 0,
+            alive: Vec::new(),
+        }
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Enemies from the current wave still standing
+    pub fn remaining(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// NPC data and stats (with ground-plane spawn positions) for the next wave
+    pub fn wave_spawns(&self) -> Vec<(Vec3, NpcData, CombatStats)> {
+        let wave = self.wave + 1;
+        let boss = self.config.boss_final_wave && wave == self.config.waves;
+        let count = if boss {
+            1
+        } else {
+            (self.config.enemies_per_wave + self.config.wave_growth * (wave - 1)).max(1)
+        };
+
+        (0..count)
+            .map(|i| {
+                let angle = i as f32 / count as f32 * std::f32::consts::TAU + wave as f32 * 0.7;
+                let position = self.center
+                    + Vec3::new(angle.cos(), 0.0, angle.sin()) * self.config.spawn_radius;
+                let data = NpcData {
+                    name: if boss { "Arena Champion" } else { "Arena Fighter" }.to_string(),
+                    role: NpcRole::Enemy,
+                    faction: NpcFaction::Hostile,
+                    home_position: position,
+                    wander_radius: self.config.spawn_radius,
+                    interaction_radius: 0.0,
+                    color: NpcRole::Enemy.color(),
+                    server_character_id: None,
+                };
+                let stats = if boss {
+                    CombatStats::boss(self.config.element)
+                } else {
+                    CombatStats::elemental_enemy(self.config.element)
+                };
+                (position, data, stats)
+            })
+            .collect()
+    }
+
+    /// Record the NPCs spawned for the next wave
+    pub fn begin_wave(&mut self, ids: Vec<NpcId>) {
+        self.wave += 1;
+        self.alive = ids;
+    }
+
+    /// Drop defeated enemies and report when to spawn the next wave
+    pub fn update(&mut self, is_alive: impl Fn(NpcId) -> bool) -> Option<ArenaEvent> {
+        self.alive.retain(|id| is_alive(*id));
+        if !self.alive.is_empty() {
+            return None;
+        }
+        if self.wave >= self.config.waves {
+            Some(ArenaEvent::Completed)
+        } else {
+            Some(ArenaEvent::SpawnWave(self.wave + 1))
+        }
+    }
+
+    /// Enemy IDs still alive (for despawning when the arena is abandoned)
+    pub fn alive(&self) -> &[NpcId] {
+        &self.alive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dummy_readout() {
+        let mut dummy = TrainingDummy::new(NpcId(1), Vec3::ZERO);
+        assert_eq!(dummy.dps(), 0.0);
+
+        dummy.record_hit(DummyHit::new(20.0, Element::Fire, "Fireball", false));
+        dummy.update(2.0);
+        dummy.record_hit(DummyHit::new(10.0, Element::Physical, "Light Attack", true));
+        assert!((dummy.dps() - 15.0).abs() < 1e-4);
+        assert_eq!(dummy.total(), 30.0);
+        assert_eq!(dummy.last_hit().unwrap().source, "Light Attack");
+
+        // Hits older than the window drop out of the DPS figure
+        dummy.update(DUMMY_DPS_WINDOW + 1.0);
+        assert_eq!(dummy.dps(), 0.0);
+        assert_eq!(dummy.hits(), 2);
+
+        dummy.reset();
+        assert!(dummy.last_hit().is_none());
+    }
+
+    #[test]
+    fn test_arena_waves() {
+        let config = ArenaConfig {
+            waves: 3,
+            enemies_per_wave: 2,
+            wave_growth: 1,
+            boss_final_wave: true,
+            ..Default::default()
+        };
+        let mut arena = PracticeArena::new(config, Vec3::ZERO);
+        assert_eq!(arena.update(|_| false), Some(ArenaEvent::SpawnWave(1)));
+
+        assert_eq!(arena.wave_spawns().len(), 2);
+        arena.begin_wave(vec![NpcId(1), NpcId(2)]);
+        assert_eq!(arena.update(|id| id == NpcId(2)), None);
+        assert_eq!(arena.remaining(), 1);
+        assert_eq!(arena.update(|_| false), Some(ArenaEvent::SpawnWave(2)));
+
+        assert_eq!(arena.wave_spawns().len(), 3);
+        arena.begin_wave(vec![NpcId(3)]);
+        arena.update(|_| false);
+
+        // Final wave is a single boss
+        let boss_wave = arena.wave_spawns();
+        assert_eq!(boss_wave.len(), 1);
+        assert_eq!(boss_wave[0].2.max_hp, CombatStats::boss(Element::Physical).max_hp);
+        arena.begin_wave(vec![NpcId(4)]);
+        assert_eq!(arena.update(|_| false), Some(ArenaEvent::Completed));
+    }
+
+    #[test]
+    fn test_spawn_positions_on_ring() {
+        let arena = PracticeArena::new(ArenaConfig::default(), Vec3::new(10.0, 0.0, 10.0));
+        for (pos, _, _) in arena.wave_spawns() {
+            let dist = (pos - arena.center).length();
+            assert!((dist - arena.config.spawn_radius).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Speak-line events emitted by the dialogue systems for voice playback
+
+use super::NpcId;
+
+/// A line of dialogue an NPC has just started speaking.
+///
+/// Emitted by `DialogueSystem` and `AiDialogueManager`; the application
+/// drains these each frame and hands them to the audio layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakLine {
+    pub npc_id: NpcId,
+    pub speaker: String,
+    pub text: String,
+    /// Stable id for pre-recorded audio (None for generated lines)
+    pub line_id: Option<String>,
+}
+
+/// Line id for a node in a static dialogue tree (e.g. `"guard.1"`)
+pub fn tree_line_id(tree_key: &str, node: usize) -> String {
+    format!("{}.{}", tree_key, node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_line_id() {
+        assert_eq!(tree_line_id("villager", 3), "villager.3");
+    }
+}
@@ -0,0 +1,532 @@
+//! World bosses: rare enemies announced across the map
+//!
+//! Every so often a world boss is announced ahead of time, with where it
+//! will appear and how long until it does, for the HUD banner and map
+//! marker. The boss is picked by era, watches a far larger area than other
+//! enemies and fights in phases that harden as its HP drops; the last phase
+//! calls in help. Damage is credited per participant so a kill can be shared
+//! once other players join, and everyone credited earns the boss's trophy.
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use infinite_core::hash_seed;
+use serde::{Deserialize, Serialize};
+
+use super::combat::CombatStats;
+use super::identity::Era;
+use super::{NpcData, NpcFaction, NpcId, NpcRole};
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::Element;
+use crate::combat::item::{Item, ItemCategory, ItemId, ItemRarity};
+use crate::compass::{CompassMarker, MarkerCategory};
+
+/// Seconds of play time between world bosses
+pub const WORLD_BOSS_INTERVAL: f64 = 2400.0;
+/// Seconds of warning between the announcement and the boss appearing
+pub const WORLD_BOSS_WARNING: f64 = 120.0;
+/// How far from the player (min, max) a boss is announced
+pub const WORLD_BOSS_DISTANCE: (f32, f32) = (120.0, 220.0);
+/// Radius a world boss notices the player in
+pub const WORLD_BOSS_AGGRO_RADIUS: f32 = 45.0;
+/// Radius a world boss gives up the chase at
+pub const WORLD_BOSS_LEASH_RADIUS: f32 = 70.0;
+/// Seconds a world boss stays before it leaves undefeated
+pub const WORLD_BOSS_STAY: f64 = 900.0;
+/// Share of the boss's max HP a participant must deal to be credited
+pub const PARTICIPATION_SHARE: f32 = 0.05;
+/// Gold each credited participant earns
+pub const WORLD_BOSS_GOLD: u64 = 1000;
+/// Item ids of the trophies start here, one per boss
+pub const TROPHY_ITEM_ID_BASE: u64 = 3310;
+/// Flag namespace defeats are recorded in, for achievements to pick up
+pub const ACHIEVEMENT_NAMESPACE: &str = "achievements";
+
+/// Who dealt damage to a world boss. Only the local player for now; other
+/// players will get their own ids once multiplayer exists.
+pub type ParticipantId = u64;
+
+/// The player on this machine
+pub const LOCAL_PARTICIPANT: ParticipantId = 0;
+
+/// One of the world bosses, one per era
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WorldBossKind {
+    Colossus,
+    Wyrm,
+    Juggernaut,
+    Leviathan,
+}
+
+impl WorldBossKind {
+    pub const ALL: [WorldBossKind; 4] = [Self::Colossus, Self::Wyrm, Self::Juggernaut, Self::Leviathan];
+
+    pub fn for_era(era: Era) -> Self {
+        match era {
+            Era::Ancient => Self::Colossus,
+            Era::Medieval => Self::Wyrm,
+            Era::Modern => Self::Juggernaut,
+            Era::Future => Self::Leviathan,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Colossus => "Bronze Colossus",
+            Self::Wyrm => "Fen Wyrm",
+            Self::Juggernaut => "Iron Juggernaut",
+            Self::Leviathan => "Chrono Leviathan",
+        }
+    }
+
+    /// Flag set in [`ACHIEVEMENT_NAMESPACE`] once the boss is defeated
+    pub fn achievement_key(self) -> &'static str {
+        match self {
+            Self::Colossus => "world_boss_colossus",
+            Self::Wyrm => "world_boss_wyrm",
+            Self::Juggernaut => "world_boss_juggernaut",
+            Self::Leviathan => "world_boss_leviathan",
+        }
+    }
+
+    pub fn element(self) -> Element {
+        match self {
+            Self::Colossus => Element::Earth,
+            Self::Wyrm => Element::Fire,
+            Self::Juggernaut => Element::Physical,
+            Self::Leviathan => Element::Void,
+        }
+    }
+
+    pub fn color(self) -> [f32; 4] {
+        match self {
+            Self::Colossus => [0.75, 0.55, 0.25, 1.0],
+            Self::Wyrm => [0.35, 0.5, 0.2, 1.0],
+            Self::Juggernaut => [0.45, 0.45, 0.5, 1.0],
+            Self::Leviathan => [0.5, 0.25, 0.95, 1.0],
+        }
+    }
+
+    /// Boss stats, several times tougher than a dungeon boss and watching a
+    /// much larger area
+    pub fn stats(self) -> CombatStats {
+        let mut stats = CombatStats::boss(self.element());
+        stats.max_hp *= 4.0;
+        stats.current_hp = stats.max_hp;
+        stats.attack *= 1.5;
+        stats.aggro_radius = WORLD_BOSS_AGGRO_RADIUS;
+        stats.de_aggro_radius = WORLD_BOSS_LEASH_RADIUS;
+        stats.poise.max *= 2.0;
+        stats.poise.current = stats.poise.max;
+        stats
+    }
+
+    pub fn npc_data(self, position: Vec3) -> NpcData {
+        NpcData {
+            name: self.name().to_string(),
+            role: NpcRole::Enemy,
+            faction: NpcFaction::Hostile,
+            home_position: position,
+            wander_radius: 10.0,
+            interaction_radius: 0.0,
+            color: self.color(),
+            server_character_id: None,
+        }
+    }
+
+    /// One of the enemies the boss calls in
+    pub fn summon(self, position: Vec3) -> (NpcData, CombatStats) {
+        let data = NpcData {
+            name: format!("{} Thrall", self.name()),
+            wander_radius: 6.0,
+            ..self.npc_data(position)
+        };
+        (data, CombatStats::elemental_enemy(self.element()))
+    }
+
+    /// The boss's guaranteed drop
+    pub fn trophy(self) -> Item {
+        let index = Self::ALL.iter().position(|k| *k == self).unwrap_or(0) as u64;
+        let mut stat_modifiers = StatModifiers {
+            attack: 8.0,
+            defense: 6.0,
+            max_hp: 40.0,
+            ..Default::default()
+        };
+        stat_modifiers.elemental_damage_bonus[self.element().index()] = 10.0;
+        Item {
+            id: ItemId(TROPHY_ITEM_ID_BASE + index),
+            name: format!("Heart of the {}", self.name()),
+            description: format!("Torn from the {} as it fell. It still beats.", self.name()),
+            category: ItemCategory::Accessory,
+            rarity: ItemRarity::Legendary,
+            stat_modifiers,
+            element: self.element(),
+            weapon_data: None,
+            shield_data: None,
+            armor_data: None,
+            treasure_map: None,
+            gem_sockets: vec![],
+            required_level: 1,
+            item_level: 20,
+            stack_count: 1,
+            max_stack: 1,
+            origin_year: None,
+        }
+    }
+}
+
+/// A stage of a world boss fight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BossPhase {
+    pub name: &'static str,
+    /// The phase starts once the boss's HP fraction falls to this
+    pub hp_below: f32,
+    /// Attack relative to the boss's base attack
+    pub attack_multiplier: f32,
+    /// Attack cooldown relative to the base cooldown
+    pub cooldown_multiplier: f32,
+    /// Enemies the boss calls in when the phase starts
+    pub summons: usize,
+}
+
+/// The phases every world boss goes through, in order
+pub const BOSS_PHASES: [BossPhase; 3] = [
+    BossPhase { name: "Awakened", hp_below: 1.0, attack_multiplier: 1.0, cooldown_multiplier: 1.0, summons: 0 },
+    BossPhase { name: "Enraged", hp_below: 0.66, attack_multiplier: 1.3, cooldown_multiplier: 0.8, summons: 0 },
+    BossPhase { name: "Desperate", hp_below: 0.33, attack_multiplier: 1.6, cooldown_multiplier: 0.6, summons: 2 },
+];
+
+/// Index of the phase a boss at `hp_fraction` of its HP is in
+pub fn phase_for(hp_fraction: f32) -> usize {
+    BOSS_PHASES.iter().rposition(|p| hp_fraction <= p.hp_below).unwrap_or(0)
+}
+
+/// A world boss that is on its way
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldBossAnnouncement {
+    pub kind: WorldBossKind,
+    /// Where it appears (y is left at 0 for the caller to place on the terrain)
+    pub position: Vec3,
+    /// Play time it appears at
+    pub spawn_at: f64,
+}
+
+impl WorldBossAnnouncement {
+    /// Seconds until the boss appears
+    pub fn time_left(&self, now: f64) -> f64 {
+        (self.spawn_at - now).max(0.0)
+    }
+}
+
+/// A world boss in the world
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldBoss {
+    pub kind: WorldBossKind,
+    pub npc: NpcId,
+    pub position: Vec3,
+    phase: usize,
+    max_hp: f32,
+    last_hp: f32,
+    base_attack: f32,
+    base_cooldown: f32,
+    leaves_at: f64,
+    damage: BTreeMap<ParticipantId, f32>,
+}
+
+impl WorldBoss {
+    pub fn phase(&self) -> &'static BossPhase {
+        &BOSS_PHASES[self.phase]
+    }
+
+    pub fn hp_fraction(&self) -> f32 {
+        if self.max_hp > 0.0 {
+            self.last_hp / self.max_hp
+        } else {
+            0.0
+        }
+    }
+
+    /// Count damage a participant dealt
+    pub fn credit(&mut self, participant: ParticipantId, damage: f32) {
+        *self.damage.entry(participant).or_default() += damage;
+    }
+
+    /// Participants that dealt at least [`PARTICIPATION_SHARE`] of the boss's HP
+    pub fn credited(&self) -> Vec<ParticipantId> {
+        let needed = self.max_hp * PARTICIPATION_SHARE;
+        self.damage.iter().filter(|(_, dealt)| **dealt >= needed).map(|(id, _)| *id).collect()
+    }
+
+    /// Set the boss's attack and cooldown for its current phase
+    pub fn apply_phase(&self, stats: &mut CombatStats) {
+        let phase = self.phase();
+        stats.attack = self.base_attack * phase.attack_multiplier;
+        stats.attack_cooldown = self.base_cooldown * phase.cooldown_multiplier;
+    }
+}
+
+/// What the caller should do after a world boss update
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldBossEvent {
+    /// A boss was announced
+    Announced(WorldBossAnnouncement),
+    /// Spawn the announced boss, then hand it to [`WorldBossTracker::begin`]
+    Spawn(WorldBossAnnouncement),
+    /// The boss entered this phase: apply it with [`WorldBoss::apply_phase`]
+    /// and spawn its summons
+    PhaseChanged(usize),
+    /// The boss fell; reward the credited participants
+    Defeated { kind: WorldBossKind, credited: Vec<ParticipantId> },
+    /// The boss left undefeated
+    Departed(WorldBossKind),
+}
+
+/// Schedules world bosses and follows the fight with the current one
+#[derive(Debug, Clone)]
+pub struct WorldBossTracker {
+    seed: u64,
+    /// Play time the next boss appears at
+    next_at: f64,
+    /// Bosses announced so far, for picking their positions
+    count: u64,
+    announced: Option<WorldBossAnnouncement>,
+    active: Option<WorldBoss>,
+}
+
+impl WorldBossTracker {
+    /// A tracker whose first boss appears [`WORLD_BOSS_INTERVAL`] after `now`
+    pub fn new(seed: u64, now: f64) -> Self {
+        Self {
+            seed,
+            next_at: now + WORLD_BOSS_INTERVAL,
+            count: 0,
+            announced: None,
+            active: None,
+        }
+    }
+
+    pub fn announced(&self) -> Option<&WorldBossAnnouncement> {
+        self.announced.as_ref()
+    }
+
+    pub fn active(&self) -> Option<&WorldBoss> {
+        self.active.as_ref()
+    }
+
+    /// Map marker for the announced or active boss
+    pub fn marker(&self) -> Option<CompassMarker> {
+        let (kind, position) = match (&self.announced, &self.active) {
+            (_, Some(boss)) => (boss.kind, boss.position),
+            (Some(announced), None) => (announced.kind, announced.position),
+            (None, None) => return None,
+        };
+        Some(CompassMarker::new(position, kind.name(), MarkerCategory::Objective))
+    }
+
+    /// Advance the schedule: announce the next boss near `player_pos`, say
+    /// when to spawn it, and send an active boss away once its time is up
+    pub fn update(&mut self, now: f64, player_pos: Vec3, era: Era) -> Option<WorldBossEvent> {
+        if let Some(boss) = &self.active {
+            if now < boss.leaves_at {
+                return None;
+            }
+            let kind = boss.kind;
+            self.finish(now);
+            return Some(WorldBossEvent::Departed(kind));
+        }
+        match self.announced {
+            Some(announced) if now >= announced.spawn_at => {
+                self.announced = None;
+                Some(WorldBossEvent::Spawn(announced))
+            }
+            Some(_) => None,
+            None if now >= self.next_at - WORLD_BOSS_WARNING => {
+                let announced = WorldBossAnnouncement {
+                    kind: WorldBossKind::for_era(era),
+                    position: self.pick_position(player_pos),
+                    spawn_at: self.next_at.max(now),
+                };
+                self.count += 1;
+                self.announced = Some(announced);
+                Some(WorldBossEvent::Announced(announced))
+            }
+            None => None,
+        }
+    }
+
+    /// Start following the boss spawned for `announced`
+    pub fn begin(&mut self, announced: &WorldBossAnnouncement, npc: NpcId, position: Vec3, stats: &CombatStats, now: f64) {
+        self.active = Some(WorldBoss {
+            kind: announced.kind,
+            npc,
+            position,
+            phase: 0,
+            max_hp: stats.max_hp,
+            last_hp: stats.current_hp,
+            base_attack: stats.attack,
+            base_cooldown: stats.attack_cooldown,
+            leaves_at: now + WORLD_BOSS_STAY,
+            damage: BTreeMap::new(),
+        });
+    }
+
+    /// Follow the active boss's HP and position, with `npc` None once its
+    /// NPC is gone. HP it lost while the local player was within
+    /// [`WORLD_BOSS_LEASH_RADIUS`] is credited to them. A boss that
+    /// vanishes with the player that close was killed; otherwise it left
+    /// with its chunk.
+    pub fn observe(&mut self, now: f64, npc: Option<(f32, Vec3)>, player_pos: Vec3) -> Option<WorldBossEvent> {
+        let boss = self.active.as_mut()?;
+        if let Some((_, position)) = npc {
+            boss.position = position;
+        }
+        let in_range = boss.position.with_y(0.0).distance(player_pos.with_y(0.0)) <= WORLD_BOSS_LEASH_RADIUS;
+        let hp = npc.map(|(hp, _)| hp);
+        let lost = (boss.last_hp - hp.unwrap_or(0.0)).max(0.0);
+        if in_range && lost > 0.0 {
+            boss.credit(LOCAL_PARTICIPANT, lost);
+        }
+        let Some(hp) = hp else {
+            let event = if in_range {
+                WorldBossEvent::Defeated { kind: boss.kind, credited: boss.credited() }
+            } else {
+                WorldBossEvent::Departed(boss.kind)
+            };
+            self.finish(now);
+            return Some(event);
+        };
+        boss.last_hp = hp;
+        let phase = phase_for(boss.hp_fraction());
+        if phase > boss.phase {
+            boss.phase = phase;
+            return Some(WorldBossEvent::PhaseChanged(phase));
+        }
+        None
+    }
+
+    /// Call off the announced or active boss (time travel, loading a save)
+    /// and start the schedule over. Returns the boss NPC to despawn.
+    pub fn reset(&mut self, now: f64) -> Option<NpcId> {
+        self.announced = None;
+        let npc = self.active.as_ref().map(|boss| boss.npc);
+        self.finish(now);
+        npc
+    }
+
+    /// Forget the active boss and schedule the next
+    fn finish(&mut self, now: f64) {
+        self.active = None;
+        self.next_at = now + WORLD_BOSS_INTERVAL;
+    }
+
+    fn pick_position(&self, player_pos: Vec3) -> Vec3 {
+        let h = hash_seed(self.seed, &[self.count + 1]);
+        let angle = (h & 0xffff) as f32 / 65536.0 * std::f32::consts::TAU;
+        let t = ((h >> 16) & 0xffff) as f32 / 65536.0;
+        let distance = WORLD_BOSS_DISTANCE.0 + t * (WORLD_BOSS_DISTANCE.1 - WORLD_BOSS_DISTANCE.0);
+        Vec3::new(player_pos.x + angle.cos() * distance, 0.0, player_pos.z + angle.sin() * distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawned(tracker: &mut WorldBossTracker) -> (WorldBossAnnouncement, CombatStats) {
+        let now = WORLD_BOSS_INTERVAL - WORLD_BOSS_WARNING;
+        let Some(WorldBossEvent::Announced(announced)) = tracker.update(now, Vec3::ZERO, Era::Medieval) else {
+            panic!("expected an announcement");
+        };
+        assert_eq!(tracker.update(now + 1.0, Vec3::ZERO, Era::Medieval), None);
+        assert_eq!(
+            tracker.update(announced.spawn_at, Vec3::ZERO, Era::Medieval),
+            Some(WorldBossEvent::Spawn(announced))
+        );
+        let stats = announced.kind.stats();
+        tracker.begin(&announced, NpcId(7), announced.position, &stats, announced.spawn_at);
+        (announced, stats)
+    }
+
+    #[test]
+    fn test_bosses_are_announced_before_they_spawn() {
+        let mut tracker = WorldBossTracker::new(3, 0.0);
+        assert_eq!(tracker.update(10.0, Vec3::ZERO, Era::Medieval), None);
+        let (announced, stats) = spawned(&mut tracker);
+        assert_eq!(announced.kind, WorldBossKind::Wyrm);
+        assert_eq!(announced.spawn_at, WORLD_BOSS_INTERVAL);
+        let distance = announced.position.length();
+        assert!(distance >= WORLD_BOSS_DISTANCE.0 && distance <= WORLD_BOSS_DISTANCE.1);
+        assert!(stats.aggro_radius > CombatStats::boss(Element::Fire).aggro_radius);
+        assert!(tracker.marker().is_some());
+
+        // Left alone, it leaves and the next is scheduled
+        let later = announced.spawn_at + WORLD_BOSS_STAY;
+        assert_eq!(tracker.update(later, Vec3::ZERO, Era::Medieval), Some(WorldBossEvent::Departed(WorldBossKind::Wyrm)));
+        assert!(tracker.active().is_none());
+        assert_eq!(tracker.update(later + 1.0, Vec3::ZERO, Era::Medieval), None);
+    }
+
+    #[test]
+    fn test_phases_harden_the_boss() {
+        let mut tracker = WorldBossTracker::new(3, 0.0);
+        let (announced, stats) = spawned(&mut tracker);
+        let (max, at) = (stats.max_hp, announced.position);
+        assert_eq!(tracker.observe(0.0, Some((max * 0.8, at)), at), None);
+        assert_eq!(tracker.observe(0.0, Some((max * 0.6, at)), at), Some(WorldBossEvent::PhaseChanged(1)));
+        // A big hit can skip straight to the last phase
+        assert_eq!(tracker.observe(0.0, Some((max * 0.1, at)), at), Some(WorldBossEvent::PhaseChanged(2)));
+        let boss = tracker.active().unwrap();
+        assert_eq!(boss.phase().summons, 2);
+        let mut phased = stats.clone();
+        boss.apply_phase(&mut phased);
+        assert!(phased.attack > stats.attack);
+        assert!(phased.attack_cooldown < stats.attack_cooldown);
+    }
+
+    #[test]
+    fn test_kills_are_credited_to_participants() {
+        let mut tracker = WorldBossTracker::new(3, 0.0);
+        let (announced, stats) = spawned(&mut tracker);
+        let max = stats.max_hp;
+        // HP lost while the player is far off isn't their doing, and the
+        // boss is followed as it moves
+        let moved = announced.position + Vec3::X * 5.0;
+        tracker.observe(0.0, Some((max * 0.5, moved)), Vec3::ZERO);
+        tracker.observe(0.0, Some((max * 0.48, moved)), moved);
+        assert!(tracker.active().unwrap().credited().is_empty());
+        assert_eq!(tracker.marker().unwrap().position, moved);
+        assert_eq!(
+            tracker.observe(0.0, None, moved),
+            Some(WorldBossEvent::Defeated { kind: WorldBossKind::Wyrm, credited: vec![LOCAL_PARTICIPANT] })
+        );
+        assert!(tracker.active().is_none());
+
+        // Vanishing with the player far away means its chunk unloaded
+        let mut tracker = WorldBossTracker::new(3, 0.0);
+        spawned(&mut tracker);
+        assert_eq!(tracker.observe(0.0, None, Vec3::ZERO), Some(WorldBossEvent::Departed(WorldBossKind::Wyrm)));
+    }
+
+    #[test]
+    fn test_reset_calls_off_the_boss() {
+        let mut tracker = WorldBossTracker::new(3, 0.0);
+        spawned(&mut tracker);
+        assert_eq!(tracker.reset(100.0), Some(NpcId(7)));
+        assert!(tracker.marker().is_none());
+        assert_eq!(tracker.update(100.0 + WORLD_BOSS_INTERVAL - WORLD_BOSS_WARNING - 1.0, Vec3::ZERO, Era::Future), None);
+        assert!(matches!(
+            tracker.update(100.0 + WORLD_BOSS_INTERVAL, Vec3::ZERO, Era::Future),
+            Some(WorldBossEvent::Announced(WorldBossAnnouncement { kind: WorldBossKind::Leviathan, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_trophies_are_legendary_and_distinct() {
+        let ids: Vec<ItemId> = WorldBossKind::ALL.iter().map(|k| k.trophy().id).collect();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(!ids[i + 1..].contains(id));
+        }
+        assert!(WorldBossKind::ALL.iter().all(|k| k.trophy().rarity == ItemRarity::Legendary));
+    }
+}
@@ -0,0 +1,294 @@
+//! Free climbing: climbable surface detection, ledge grabs and stamina
+//!
+//! A surface is climbable if its collider is tagged with
+//! [`infinite_physics::CLIMBABLE_FLAG`] or if it is steeper than
+//! `ClimbConfig::min_wall_angle`. Walls are found with a sphere cast from
+//! chest height; ledges with a downward ray just behind the wall face and a
+//! sphere swept up from there for headroom.
+
+use glam::{Vec2, Vec3};
+use infinite_physics::PhysicsWorld;
+use rapier3d::prelude::{ColliderHandle, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+/// Climbing configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClimbConfig {
+    /// Climbing speed in meters per second
+    pub climb_speed: f32,
+    /// Minimum surface steepness in degrees (from horizontal) to climb untagged surfaces
+    pub min_wall_angle: f32,
+    /// How far in front of the player walls are detected
+    pub reach: f32,
+    /// Radius of the sphere cast used to find walls
+    pub probe_radius: f32,
+    /// How far above the eyes a ledge can be grabbed
+    pub ledge_reach: f32,
+    /// Seconds to pull up onto a ledge
+    pub mantle_duration: f32,
+    /// Maximum stamina
+    pub max_stamina: f32,
+    /// Stamina per second while moving on a wall
+    pub climb_cost: f32,
+    /// Stamina per second while hanging still
+    pub hang_cost: f32,
+    /// Stamina for jumping off a wall
+    pub jump_cost: f32,
+    /// Stamina regained per second while not climbing
+    pub stamina_regen: f32,
+    /// Horizontal speed when jumping away from a wall
+    pub wall_jump_speed: f32,
+}
+
+impl Default for ClimbConfig {
+    fn default() -> Self {
+        Self {
+            climb_speed: 2.5,
+            min_wall_angle: 75.0,
+            reach: 0.6,
+            probe_radius: 0.3,
+            ledge_reach: 0.8,
+            mantle_duration: 0.4,
+            max_stamina: 100.0,
+            climb_cost: 10.0,
+            hang_cost: 3.0,
+            jump_cost: 15.0,
+            stamina_regen: 25.0,
+            wall_jump_speed: 5.0,
+        }
+    }
+}
+
+/// Climbing stamina
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Stamina {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Spend stamina, returning false if there wasn't enough
+    pub fn drain(&mut self, amount: f32) -> bool {
+        self.current = (self.current - amount).max(0.0);
+        self.current > 0.0
+    }
+
+    pub fn regen(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            0.0
+        } else {
+            self.current / self.max
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.current >= self.max
+    }
+}
+
+/// A climbable surface in front of the player
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallContact {
+    pub point: Vec3,
+    /// Surface normal, pointing away from the wall
+    pub normal: Vec3,
+    pub collider: ColliderHandle,
+}
+
+/// Current climbing state
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ClimbState {
+    /// Walking, jumping or falling
+    #[default]
+    None,
+    /// Attached to a wall
+    Climbing { normal: Vec3 },
+    /// Pulling up onto a ledge
+    Mantling { from: Vec3, to: Vec3, elapsed: f32 },
+}
+
+/// Check if a surface normal is steep enough to climb. Slight overhangs are
+/// allowed, ceilings are not.
+pub fn is_climbable_normal(normal: Vec3, min_wall_angle: f32) -> bool {
+    let steepness = normal.y.clamp(-1.0, 1.0).acos().to_degrees();
+    (min_wall_angle..=120.0).contains(&steepness)
+}
+
+/// Velocity along a wall for climb input (`x` = right, `y` = up)
+pub fn climb_velocity(normal: Vec3, input: Vec2, speed: f32) -> Vec3 {
+    let right = Vec3::Y.cross(normal).normalize_or_zero();
+    let up = normal.cross(right).normalize_or_zero();
+    let dir = right * input.x + up * input.y;
+    if dir.length_squared() > 1.0 {
+        dir.normalize() * speed
+    } else {
+        dir * speed
+    }
+}
+
+/// Sphere-cast from `origin` along `direction` for a climbable surface
+pub fn probe_wall(
+    physics: &PhysicsWorld,
+    origin: Vec3,
+    direction: Vec3,
+    config: &ClimbConfig,
+    exclude: Option<ColliderHandle>,
+) -> Option<WallContact> {
+    let direction = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return None;
+    }
+    let hit = physics.cast_sphere(
+        origin,
+        config.probe_radius,
+        direction,
+        config.reach,
+        filter(exclude),
+    )?;
+    let climbable = physics.is_climbable(hit.collider) || is_climbable_normal(hit.normal, config.min_wall_angle);
+    // Only walls roughly facing the player
+    if !climbable || hit.normal.dot(direction) > -0.3 {
+        return None;
+    }
+    Some(WallContact {
+        point: hit.point,
+        normal: hit.normal,
+        collider: hit.collider,
+    })
+}
+
+/// Find a ledge top above a wall contact that the player can stand on.
+/// Returns the feet position to mantle onto.
+pub fn probe_ledge(
+    physics: &PhysicsWorld,
+    wall: &WallContact,
+    eye: Vec3,
+    body_height: f32,
+    config: &ClimbConfig,
+    exclude: Option<ColliderHandle>,
+) -> Option<Vec3> {
+    let inset = Vec3::new(wall.normal.x, 0.0, wall.normal.z).normalize_or_zero() * (config.probe_radius + 0.2);
+    let top = eye.y + config.ledge_reach;
+    let start = Vec3::new(wall.point.x, top, wall.point.z) - inset;
+    let drop = config.ledge_reach + body_height * 0.5;
+
+    let hit = physics.raycast_detailed(start, Vec3::NEG_Y, drop, filter(exclude))?;
+    // A hit at the ray origin means the wall continues upward
+    if hit.distance < 0.01 || hit.normal.y < 0.7 {
+        return None;
+    }
+
+    // Room to stand at the top: sweep a body-wide sphere up from the ledge
+    let radius = config.probe_radius;
+    let clearance = physics.cast_sphere(
+        hit.point + Vec3::Y * (radius + 0.05),
+        radius,
+        Vec3::Y,
+        (body_height - 2.0 * radius).max(0.0),
+        filter(exclude),
+    );
+    if clearance.is_some() {
+        return None;
+    }
+    Some(hit.point)
+}
+
+fn filter(exclude: Option<ColliderHandle>) -> QueryFilter<'static> {
+    let filter = QueryFilter::default();
+    match exclude {
+        Some(handle) => filter.exclude_collider(handle),
+        None => filter,
+    }
+}
+
+/// Smoothstep interpolation of a mantle: rise first, then move forward
+pub fn mantle_position(from: Vec3, to: Vec3, t: f32) -> Vec3 {
+    let t = t.clamp(0.0, 1.0);
+    let rise = (t * 2.0).min(1.0);
+    let forward = ((t - 0.3) / 0.7).clamp(0.0, 1.0);
+    let smooth = |x: f32| x * x * (3.0 - 2.0 * x);
+    Vec3::new(
+        from.x + (to.x - from.x) * smooth(forward),
+        from.y + (to.y - from.y) * smooth(rise),
+        from.z + (to.z - from.z) * smooth(forward),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_climbable_normals() {
+        assert!(is_climbable_normal(Vec3::Z, 75.0));
+        assert!(is_climbable_normal(Vec3::new(0.0, -0.2, 1.0).normalize(), 75.0));
+        // Gentle slopes are walked, ceilings can't be climbed
+        assert!(!is_climbable_normal(Vec3::new(0.0, 1.0, 1.0).normalize(), 75.0));
+        assert!(!is_climbable_normal(Vec3::NEG_Y, 75.0));
+    }
+
+    #[test]
+    fn test_climb_velocity_follows_wall() {
+        // Wall facing +Z: up is +Y and right is +X
+        let v = climb_velocity(Vec3::Z, Vec2::new(0.0, 1.0), 2.0);
+        assert!((v - Vec3::Y * 2.0).length() < 1e-4);
+        let v = climb_velocity(Vec3::Z, Vec2::new(1.0, 0.0), 2.0);
+        assert!((v - Vec3::X * 2.0).length() < 1e-4);
+        // Diagonal input isn't faster
+        assert!((climb_velocity(Vec3::Z, Vec2::new(1.0, 1.0), 2.0).length() - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_wall_and_ledge_probes() {
+        let mut physics = PhysicsWorld::new();
+        // 2m-high block in front of the player, top at y = 2
+        physics.create_static_box(Vec3::new(2.0, 1.0, 1.0), Vec3::new(0.0, 1.0, -2.0));
+        physics.update_query_pipeline();
+        let config = ClimbConfig::default();
+
+        let chest = Vec3::new(0.0, 1.2, -0.5);
+        let wall = probe_wall(&physics, chest, Vec3::NEG_Z, &config, None).expect("wall ahead");
+        assert!(wall.normal.z > 0.99);
+        assert!(probe_wall(&physics, chest, Vec3::Z, &config, None).is_none());
+
+        // Eyes near the top: ledge within reach
+        let ledge = probe_ledge(&physics, &wall, Vec3::new(0.0, 1.6, -0.5), 1.8, &config, None)
+            .expect("ledge within reach");
+        assert!((ledge.y - 2.0).abs() < 0.01);
+        assert!(ledge.z < -1.0);
+
+        // Eyes far below the top: wall continues upward
+        assert!(probe_ledge(&physics, &wall, Vec3::new(0.0, 0.2, -0.5), 1.8, &config, None).is_none());
+
+        // An overhang just off to the side leaves no headroom, though a
+        // single ray up from the ledge would miss it
+        physics.create_static_box(Vec3::new(0.9, 0.3, 1.0), Vec3::new(1.1, 3.5, -2.0));
+        physics.update_query_pipeline();
+        assert!(probe_ledge(&physics, &wall, Vec3::new(0.0, 1.6, -0.5), 1.8, &config, None).is_none());
+    }
+
+    #[test]
+    fn test_stamina_and_mantle() {
+        let mut stamina = Stamina::new(10.0);
+        assert!(stamina.drain(4.0));
+        assert!(!stamina.drain(8.0));
+        stamina.regen(20.0);
+        assert!(stamina.is_full());
+
+        let from = Vec3::ZERO;
+        let to = Vec3::new(0.0, 2.0, -1.0);
+        assert_eq!(mantle_position(from, to, 0.0), from);
+        assert_eq!(mantle_position(from, to, 1.0), to);
+        // Halfway through the body has cleared the edge height before moving forward
+        let mid = mantle_position(from, to, 0.5);
+        assert!((mid.y - 2.0).abs() < 1e-4 && mid.z > -1.0);
+    }
+}
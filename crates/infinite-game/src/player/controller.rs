@@ -1,10 +1,15 @@
 //! Player controller with WASD movement and physics
 
-use glam::Vec3;
-use infinite_physics::{CharacterController, PhysicsWorld};
+use glam::{Vec2, Vec3};
+use infinite_core::{Transform, TransformHistory};
+use infinite_physics::{CastFilter, CharacterController, ColliderOwner, CollisionLayer, EntityId, PhysicsWorld, Rope};
 
 use crate::input::{InputAction, InputState};
+use crate::seat::RestPose;
 
+use super::climbing::{self, ClimbState, Stamina};
+use super::glider::{self, Glider};
+use super::grapple;
 use super::MovementConfig;
 
 /// Player controller handling input, movement, and physics
@@ -25,13 +30,55 @@ pub struct PlayerController {
     jump_buffered: bool,
     /// Whether we were grounded last frame
     was_grounded: bool,
+    /// Whether the player is crouching (sneaking)
+    crouching: bool,
+    /// Wall climbing / mantling state
+    climb_state: ClimbState,
+    /// Climbing and gliding stamina
+    stamina: Stamina,
+    /// Glider item state
+    glider: Glider,
+    /// Wind velocity affecting the glider
+    wind: Vec3,
+    /// Attached grappling rope
+    grapple: Option<Rope>,
+    /// Set when the rope snapped under excessive force
+    grapple_snapped: bool,
+    /// Movement speed multiplier from status effects and encumbrance
+    speed_scale: f32,
+    /// Whether holding Sprint sprints (off while overloaded)
+    can_sprint: bool,
+    /// Stamina regeneration multiplier (e.g. from food)
+    stamina_regen_scale: f32,
+    /// Free flight through terrain, ignoring gravity and collisions (GM tools)
+    noclip: bool,
+    /// Sitting or leaning on a seat
+    rest: Option<Rest>,
+    /// Where the last fixed step started and ended, for drawing in between
+    motion: TransformHistory,
 }
 
+/// How the player is resting, and where they get up to
+#[derive(Debug, Clone, Copy)]
+struct Rest {
+    pose: RestPose,
+    facing: Vec3,
+    exit: Vec3,
+}
+
+/// How far the eye drops while crouching
+const CROUCH_EYE_DROP: f32 = 0.6;
+/// Free-flight speed in m/s, and its multiplier while sprinting
+const NOCLIP_SPEED: f32 = 12.0;
+const NOCLIP_SPRINT: f32 = 4.0;
+
 impl PlayerController {
     /// Create a new player controller
     pub fn new() -> Self {
+        let config = MovementConfig::default();
+        let stamina = Stamina::new(config.climb.max_stamina);
         Self {
-            config: MovementConfig::default(),
+            config,
             character: CharacterController::new(),
             horizontal_velocity: Vec3::ZERO,
             vertical_velocity: 0.0,
@@ -39,12 +86,26 @@ impl PlayerController {
             time_since_jump_pressed: f32::MAX,
             jump_buffered: false,
             was_grounded: false,
+            crouching: false,
+            climb_state: ClimbState::None,
+            stamina,
+            glider: Glider::default(),
+            wind: Vec3::ZERO,
+            grapple: None,
+            grapple_snapped: false,
+            speed_scale: 1.0,
+            can_sprint: true,
+            stamina_regen_scale: 1.0,
+            noclip: false,
+            rest: None,
+            motion: TransformHistory::default(),
         }
     }
 
     /// Create a player controller with custom config
     pub fn with_config(config: MovementConfig) -> Self {
         Self {
+            stamina: Stamina::new(config.climb.max_stamina),
             config,
             ..Self::new()
         }
@@ -52,7 +113,8 @@ impl PlayerController {
 
     /// Spawn the player in the world at a position
     pub fn spawn(&mut self, physics: &mut PhysicsWorld, position: Vec3) {
-        self.character.spawn(physics, position);
+        let handle = self.character.spawn(physics, position);
+        physics.set_owner(handle, ColliderOwner::new(EntityId::PLAYER, CollisionLayer::PLAYER));
         self.horizontal_velocity = Vec3::ZERO;
         self.vertical_velocity = 0.0;
         self.time_since_grounded = 0.0;
@@ -63,9 +125,171 @@ impl PlayerController {
         self.character.position
     }
 
-    /// Get the player's eye position (for camera)
+    /// Get the player's eye position (for camera), lowered while crouching
+    /// or sitting
     pub fn eye_position(&self) -> Vec3 {
-        self.character.eye_position()
+        let eye = self.character.eye_position();
+        if let Some(rest) = self.rest {
+            eye - Vec3::Y * rest.pose.eye_drop()
+        } else if self.crouching {
+            eye - Vec3::Y * CROUCH_EYE_DROP
+        } else {
+            eye
+        }
+    }
+
+    /// Where to draw the capsule's centre, `alpha` of the way through the
+    /// current fixed step (`GameTime::fixed_interpolation`)
+    pub fn render_center_position(&self, alpha: f32) -> Vec3 {
+        self.character.center_position() + self.render_offset(alpha)
+    }
+
+    /// Eye position blended between fixed steps like
+    /// `render_center_position`, for the camera to follow smoothly
+    pub fn render_eye_position(&self, alpha: f32) -> Vec3 {
+        self.eye_position() + self.render_offset(alpha)
+    }
+
+    /// How far behind the simulated position the drawn one is. Moves made
+    /// outside a step (teleports, sitting down) aren't blended.
+    fn render_offset(&self, alpha: f32) -> Vec3 {
+        if self.motion.current.position != self.character.position {
+            return Vec3::ZERO;
+        }
+        self.motion.interpolate(alpha).position - self.character.position
+    }
+
+    /// Check if the player is crouching
+    pub fn is_crouching(&self) -> bool {
+        self.crouching
+    }
+
+    /// Current horizontal speed in m/s (for movement noise)
+    pub fn horizontal_speed(&self) -> f32 {
+        self.horizontal_velocity.length()
+    }
+
+    /// Check if the player is on a wall or pulling up onto a ledge
+    pub fn is_climbing(&self) -> bool {
+        self.climb_state != ClimbState::None
+    }
+
+    /// Current climbing state
+    pub fn climb_state(&self) -> ClimbState {
+        self.climb_state
+    }
+
+    /// Climbing and gliding stamina
+    pub fn stamina(&self) -> &Stamina {
+        &self.stamina
+    }
+
+    /// Glider state (openness drives the deploy/retract animation)
+    pub fn glider(&self) -> &Glider {
+        &self.glider
+    }
+
+    /// Check if the glider is deployed
+    pub fn is_gliding(&self) -> bool {
+        self.glider.is_active()
+    }
+
+    /// Unlock or remove the glider
+    pub fn set_glider_owned(&mut self, owned: bool) {
+        self.glider.owned = owned;
+        if !owned {
+            self.glider.retract();
+        }
+    }
+
+    /// Set the wind velocity (m/s) that pushes the glider
+    pub fn set_wind(&mut self, wind: Vec3) {
+        self.wind = wind;
+    }
+
+    /// Scale walking and sprinting speed (e.g. slowed by a status effect)
+    pub fn set_speed_scale(&mut self, scale: f32) {
+        self.speed_scale = scale.max(0.0);
+    }
+
+    /// Allow or forbid sprinting (e.g. when carrying too much)
+    pub fn set_can_sprint(&mut self, can_sprint: bool) {
+        self.can_sprint = can_sprint;
+    }
+
+    /// Scale how fast stamina comes back (e.g. well fed)
+    pub fn set_stamina_regen_scale(&mut self, scale: f32) {
+        self.stamina_regen_scale = scale.max(0.0);
+    }
+
+    /// Whether the player is hanging from the grappling hook
+    pub fn is_grappling(&self) -> bool {
+        self.grapple.is_some()
+    }
+
+    /// Where the grappling rope is attached
+    pub fn grapple_anchor(&self) -> Option<Vec3> {
+        self.grapple.map(|rope| rope.anchor)
+    }
+
+    /// Returns true once after the rope snapped
+    pub fn take_grapple_snapped(&mut self) -> bool {
+        std::mem::take(&mut self.grapple_snapped)
+    }
+
+    /// Sit or lean at `spot`, facing `facing`, until moving or jumping.
+    /// Getting up puts the player at `exit`.
+    pub fn rest(&mut self, physics: &mut PhysicsWorld, pose: RestPose, spot: Vec3, facing: Vec3, exit: Vec3) {
+        if self.grapple.is_some() {
+            self.release_grapple(physics);
+        }
+        self.glider.retract();
+        self.release_climb();
+        self.crouching = false;
+        self.horizontal_velocity = Vec3::ZERO;
+        self.vertical_velocity = 0.0;
+        self.character.velocity = Vec3::ZERO;
+        self.character.set_position(physics, spot);
+        self.rest = Some(Rest { pose, facing, exit });
+    }
+
+    /// Get up from a seat
+    pub fn stand_up(&mut self, physics: &mut PhysicsWorld) {
+        if let Some(rest) = self.rest.take() {
+            self.character.set_position(physics, rest.exit);
+        }
+    }
+
+    /// Whether the player is sitting or leaning
+    pub fn is_resting(&self) -> bool {
+        self.rest.is_some()
+    }
+
+    /// How the player is resting
+    pub fn rest_pose(&self) -> Option<RestPose> {
+        self.rest.map(|rest| rest.pose)
+    }
+
+    /// Direction the player faces while resting
+    pub fn rest_facing(&self) -> Option<Vec3> {
+        self.rest.map(|rest| rest.facing)
+    }
+
+    /// Whether free flight is on
+    pub fn is_noclip(&self) -> bool {
+        self.noclip
+    }
+
+    /// Turn free flight on or off. Turning it on drops any climb, glide or
+    /// rope; turning it off lets the player fall from where they are.
+    pub fn set_noclip(&mut self, physics: &mut PhysicsWorld, enabled: bool) {
+        if enabled && self.grapple.is_some() {
+            self.release_grapple(physics);
+        }
+        self.noclip = enabled;
+        self.rest = None;
+        self.glider.retract();
+        self.release_climb();
     }
 
     /// Check if the player is grounded
@@ -86,6 +310,49 @@ impl PlayerController {
         camera_yaw: f32,
         dt: f32,
     ) {
+        let before = Transform::from_position(self.character.position);
+        self.step(physics, input, camera_yaw, dt);
+        self.motion = TransformHistory {
+            previous: before,
+            current: Transform::from_position(self.character.position),
+        };
+    }
+
+    fn step(&mut self, physics: &mut PhysicsWorld, input: &InputState, camera_yaw: f32, dt: f32) {
+        self.glider.update(dt, &self.config.glider);
+
+        if self.noclip {
+            self.update_noclip(physics, input, camera_yaw, dt);
+            return;
+        }
+
+        if let Some(rope) = self.grapple {
+            self.update_grapple(physics, input, rope, camera_yaw, dt);
+            return;
+        }
+
+        // Seated until the player moves or jumps
+        if self.rest.is_some() {
+            if input.is_just_pressed(InputAction::Jump) || Self::move_direction(input, camera_yaw) != Vec3::ZERO {
+                self.stand_up(physics);
+            } else {
+                self.stamina.regen(self.config.climb.stamina_regen * self.stamina_regen_scale * dt);
+            }
+            return;
+        }
+
+        match self.climb_state {
+            ClimbState::Climbing { normal } => {
+                self.update_climbing(physics, input, normal, dt);
+                return;
+            }
+            ClimbState::Mantling { from, to, elapsed } => {
+                self.update_mantle(physics, from, to, elapsed + dt);
+                return;
+            }
+            ClimbState::None => {}
+        }
+
         let grounded = self.character.is_grounded();
 
         // Track coyote time
@@ -105,7 +372,340 @@ impl PlayerController {
             self.jump_buffered = false;
         }
 
-        // Calculate movement direction from input
+        let move_dir = Self::move_direction(input, camera_yaw);
+
+        // Crouch while held on the ground; crouching overrides sprint
+        self.crouching = grounded && input.is_held(InputAction::Crouch);
+        let sprinting = input.is_held(InputAction::Sprint) && !self.crouching && self.can_sprint;
+        let max_speed = if self.crouching {
+            self.config.crouch_speed()
+        } else {
+            self.config.max_speed(sprinting)
+        } * self.speed_scale;
+
+        // Glider: hold jump while falling to deploy; release, land or run out of stamina to fold
+        let facing = Vec3::new(camera_yaw.sin(), 0.0, -camera_yaw.cos());
+        if self.glider.is_active() {
+            if grounded
+                || !input.is_held(InputAction::Jump)
+                || !self.stamina.drain(self.config.glider.stamina_cost * dt)
+            {
+                self.glider.retract();
+            }
+        } else if !grounded
+            && self.vertical_velocity < 0.0
+            && input.is_held(InputAction::Jump)
+            && self.stamina.current > 0.0
+        {
+            self.glider.deploy();
+        }
+
+        if self.glider.is_active() {
+            let velocity = glider::glide_velocity(
+                Vec3::new(self.horizontal_velocity.x, self.vertical_velocity, self.horizontal_velocity.z),
+                facing,
+                input.is_held(InputAction::MoveBackward),
+                self.wind,
+                &self.config.glider,
+                dt,
+            );
+            self.horizontal_velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+            self.vertical_velocity = velocity.y;
+            self.jump_buffered = false;
+        } else {
+            // Apply horizontal movement with acceleration
+            if move_dir.length_squared() > 0.0 {
+                let target_velocity = move_dir * max_speed;
+                let accel = self.config.acceleration(grounded);
+
+                // Accelerate towards target velocity
+                self.horizontal_velocity = Self::move_towards_vec3(
+                    self.horizontal_velocity,
+                    target_velocity,
+                    accel * dt,
+                );
+            } else {
+                // Decelerate when no input
+                let decel = self.config.deceleration(grounded);
+                self.horizontal_velocity = Self::move_towards_vec3(
+                    self.horizontal_velocity,
+                    Vec3::ZERO,
+                    decel * dt,
+                );
+            }
+
+            // Handle jumping
+            let can_jump = self.can_jump();
+            if self.jump_buffered && can_jump {
+                self.vertical_velocity = self.config.jump_velocity;
+                self.jump_buffered = false;
+                self.time_since_grounded = self.config.coyote_time; // Consume coyote time
+            }
+
+            // Apply gravity
+            if !grounded {
+                let gravity = 9.81 * self.config.gravity_scale;
+                self.vertical_velocity -= gravity * dt;
+            } else if self.vertical_velocity < 0.0 {
+                // Reset vertical velocity when landing
+                self.vertical_velocity = 0.0;
+            }
+
+            // Blasts, updrafts and storm wind (the glider takes the wind itself)
+            let push = physics.force_field_push(self.character.center_position(), dt);
+            self.horizontal_velocity += Vec3::new(push.x, 0.0, push.z);
+            self.vertical_velocity += push.y;
+        }
+
+        // Combine velocities and move
+        let total_velocity = Vec3::new(
+            self.horizontal_velocity.x,
+            self.vertical_velocity,
+            self.horizontal_velocity.z,
+        );
+
+        self.character.velocity = total_velocity;
+        self.character.update(physics, dt);
+
+        // Track grounded state change
+        self.was_grounded = grounded;
+
+        if grounded {
+            self.stamina.regen(self.config.climb.stamina_regen * self.stamina_regen_scale * dt);
+        } else if input.is_held(InputAction::MoveForward) {
+            // Grab walls and ledges when jumping or falling against them
+            self.grab_wall(physics, facing);
+        }
+    }
+
+    /// Try to grab a climbable surface in `direction`. Mantles straight onto
+    /// the top if a ledge is within reach. Returns true if the player latched on.
+    pub fn grab_wall(&mut self, physics: &PhysicsWorld, direction: Vec3) -> bool {
+        let config = &self.config.climb;
+        let exclude = self.character.collider_handle;
+        let chest = self.character.center_position() + Vec3::Y * 0.3;
+        let Some(wall) = climbing::probe_wall(physics, chest, direction, config, exclude) else {
+            return false;
+        };
+
+        if let Some(ledge) = climbing::probe_ledge(
+            physics,
+            &wall,
+            self.character.eye_position(),
+            self.character.config.height,
+            config,
+            exclude,
+        ) {
+            self.start_mantle(ledge);
+            return true;
+        }
+
+        if self.stamina.current <= 0.0 {
+            return false;
+        }
+        self.glider.retract();
+        self.climb_state = ClimbState::Climbing { normal: wall.normal };
+        self.crouching = false;
+        self.horizontal_velocity = Vec3::ZERO;
+        self.vertical_velocity = 0.0;
+        true
+    }
+
+    /// Let go of the wall
+    pub fn release_climb(&mut self) {
+        self.climb_state = ClimbState::None;
+        self.horizontal_velocity = Vec3::ZERO;
+        self.vertical_velocity = 0.0;
+    }
+
+    fn start_mantle(&mut self, to: Vec3) {
+        self.glider.retract();
+        self.climb_state = ClimbState::Mantling {
+            from: self.character.position,
+            to,
+            elapsed: 0.0,
+        };
+        self.crouching = false;
+        self.horizontal_velocity = Vec3::ZERO;
+        self.vertical_velocity = 0.0;
+    }
+
+    fn update_mantle(&mut self, physics: &mut PhysicsWorld, from: Vec3, to: Vec3, elapsed: f32) {
+        let t = elapsed / self.config.climb.mantle_duration.max(0.01);
+        self.character
+            .set_position(physics, climbing::mantle_position(from, to, t));
+        self.climb_state = if t >= 1.0 {
+            ClimbState::None
+        } else {
+            ClimbState::Mantling { from, to, elapsed }
+        };
+    }
+
+    fn update_climbing(&mut self, physics: &mut PhysicsWorld, input: &InputState, normal: Vec3, dt: f32) {
+        let config = self.config.climb.clone();
+        self.crouching = false;
+
+        // Jump away from the wall, or drop with crouch
+        if input.is_just_pressed(InputAction::Jump) {
+            self.release_climb();
+            if self.stamina.current >= config.jump_cost {
+                self.stamina.drain(config.jump_cost);
+                let away = Vec3::new(normal.x, 0.0, normal.z).normalize_or_zero();
+                self.horizontal_velocity = away * config.wall_jump_speed;
+                self.vertical_velocity = self.config.jump_velocity * 0.7;
+            }
+            return;
+        }
+        if input.is_just_pressed(InputAction::Crouch) {
+            self.release_climb();
+            return;
+        }
+
+        let axis = |pos: InputAction, neg: InputAction| {
+            input.is_held(pos) as i32 as f32 - input.is_held(neg) as i32 as f32
+        };
+        let climb_input = Vec2::new(
+            axis(InputAction::MoveRight, InputAction::MoveLeft),
+            axis(InputAction::MoveForward, InputAction::MoveBackward),
+        );
+
+        let cost = if climb_input == Vec2::ZERO {
+            config.hang_cost
+        } else {
+            config.climb_cost
+        };
+        if !self.stamina.drain(cost * dt) {
+            self.release_climb();
+            return;
+        }
+
+        let exclude = self.character.collider_handle;
+        let chest = self.character.center_position() + Vec3::Y * 0.3;
+        let Some(wall) = climbing::probe_wall(physics, chest, -normal, &config, exclude) else {
+            // Climbed off the side or the surface ended
+            self.release_climb();
+            return;
+        };
+
+        // Pull up when climbing into a ledge
+        if climb_input.y > 0.0 {
+            if let Some(ledge) = climbing::probe_ledge(
+                physics,
+                &wall,
+                self.character.eye_position(),
+                self.character.config.height,
+                &config,
+                exclude,
+            ) {
+                self.start_mantle(ledge);
+                return;
+            }
+        }
+
+        // Move along the wall, pressing in slightly to stay attached
+        let velocity = climbing::climb_velocity(wall.normal, climb_input, config.climb_speed) - wall.normal;
+        self.character.velocity = velocity;
+        self.character.update(physics, dt);
+        self.climb_state = ClimbState::Climbing { normal: wall.normal };
+
+        // Climbed back down to the ground
+        if climb_input.y < 0.0 && self.character.is_grounded() {
+            self.release_climb();
+        }
+    }
+
+    /// Fire the grappling hook from `origin` along `direction`, hooking onto
+    /// the aimed-at anchor point or tagged surface. Returns true if it attached.
+    pub fn fire_grapple(
+        &mut self,
+        physics: &mut PhysicsWorld,
+        origin: Vec3,
+        direction: Vec3,
+        anchors: &[Vec3],
+    ) -> bool {
+        if self.grapple.is_some() {
+            return false;
+        }
+        let config = &self.config.grapple;
+        let exclude = self.character.collider_handle;
+        let Some(anchor) = grapple::find_anchor(physics, origin, direction, anchors, config, exclude) else {
+            return false;
+        };
+        let center = self.character.center_position();
+        if (anchor - center).length() < config.min_length {
+            return false;
+        }
+
+        let velocity = Vec3::new(self.horizontal_velocity.x, self.vertical_velocity, self.horizontal_velocity.z);
+        self.grapple = Some(physics.attach_rope(anchor, center, velocity, config.mass));
+        self.release_climb();
+        self.glider.retract();
+        self.crouching = false;
+        true
+    }
+
+    /// Let go of the grappling rope, keeping the swing momentum
+    pub fn release_grapple(&mut self, physics: &mut PhysicsWorld) {
+        if let Some(rope) = self.grapple.take() {
+            let velocity = physics.rope_bob_velocity(&rope);
+            physics.remove_rope(&rope);
+            self.horizontal_velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+            self.vertical_velocity = velocity.y;
+        }
+    }
+
+    fn update_grapple(&mut self, physics: &mut PhysicsWorld, input: &InputState, rope: Rope, camera_yaw: f32, dt: f32) {
+        let config = &self.config.grapple;
+
+        // Jump lets go
+        if input.is_just_pressed(InputAction::Jump) {
+            self.jump_buffered = false;
+            self.release_grapple(physics);
+            return;
+        }
+        // Too much force snaps the rope
+        if physics.rope_tension(&rope) > config.break_force {
+            self.release_grapple(physics);
+            self.grapple_snapped = true;
+            return;
+        }
+
+        // Sprint reels in, crouch lets out
+        let length = physics.rope_length(&rope);
+        if input.is_held(InputAction::Sprint) {
+            physics.set_rope_length(&rope, (length - config.reel_speed * dt).max(config.min_length));
+        } else if input.is_held(InputAction::Crouch) {
+            physics.set_rope_length(&rope, (length + config.reel_speed * dt).min(config.range));
+        }
+
+        // Pump the swing with movement input
+        let move_dir = Self::move_direction(input, camera_yaw);
+        if move_dir != Vec3::ZERO {
+            physics.apply_rope_impulse(&rope, move_dir * config.swing_force * dt);
+        }
+
+        // Follow the swinging bob with collision; if blocked, pull the bob back
+        // and drop the velocity into the obstacle
+        let target = physics.rope_bob_position(&rope);
+        let start = self.character.center_position();
+        self.character.move_character(physics, target - start, dt);
+        let reached = self.character.center_position();
+        let blocked = target - reached;
+        if blocked.length() > 0.05 {
+            let normal = blocked.normalize();
+            let velocity = physics.rope_bob_velocity(&rope);
+            let velocity = velocity - normal * velocity.dot(normal).max(0.0);
+            physics.set_rope_bob(&rope, reached, velocity);
+        }
+
+        let velocity = physics.rope_bob_velocity(&rope);
+        self.horizontal_velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+        self.vertical_velocity = velocity.y;
+        self.was_grounded = self.character.is_grounded();
+    }
+
+    /// Camera-relative horizontal movement direction from input (normalized or zero)
+    fn move_direction(input: &InputState, camera_yaw: f32) -> Vec3 {
         let mut move_dir = Vec3::ZERO;
         if input.is_held(InputAction::MoveForward) {
             move_dir.z -= 1.0;
@@ -134,61 +734,7 @@ impl PlayerController {
             );
             move_dir = rotated;
         }
-
-        // Check sprint
-        let sprinting = input.is_held(InputAction::Sprint);
-        let max_speed = self.config.max_speed(sprinting);
-
-        // Apply horizontal movement with acceleration
-        if move_dir.length_squared() > 0.0 {
-            let target_velocity = move_dir * max_speed;
-            let accel = self.config.acceleration(grounded);
-
-            // Accelerate towards target velocity
-            self.horizontal_velocity = Self::move_towards_vec3(
-                self.horizontal_velocity,
-                target_velocity,
-                accel * dt,
-            );
-        } else {
-            // Decelerate when no input
-            let decel = self.config.deceleration(grounded);
-            self.horizontal_velocity = Self::move_towards_vec3(
-                self.horizontal_velocity,
-                Vec3::ZERO,
-                decel * dt,
-            );
-        }
-
-        // Handle jumping
-        let can_jump = self.can_jump();
-        if self.jump_buffered && can_jump {
-            self.vertical_velocity = self.config.jump_velocity;
-            self.jump_buffered = false;
-            self.time_since_grounded = self.config.coyote_time; // Consume coyote time
-        }
-
-        // Apply gravity
-        if !grounded {
-            let gravity = 9.81 * self.config.gravity_scale;
-            self.vertical_velocity -= gravity * dt;
-        } else if self.vertical_velocity < 0.0 {
-            // Reset vertical velocity when landing
-            self.vertical_velocity = 0.0;
-        }
-
-        // Combine velocities and move
-        let total_velocity = Vec3::new(
-            self.horizontal_velocity.x,
-            self.vertical_velocity,
-            self.horizontal_velocity.z,
-        );
-
-        self.character.velocity = total_velocity;
-        self.character.update(physics, dt);
-
-        // Track grounded state change
-        self.was_grounded = grounded;
+        move_dir
     }
 
     /// Move a vector towards a target by a maximum delta
@@ -203,11 +749,44 @@ impl PlayerController {
         }
     }
 
-    /// Teleport the player to a position
+    /// Free flight: move along the camera yaw, Jump rises and Crouch sinks.
+    /// The capsule is placed directly, so terrain doesn't stop it.
+    fn update_noclip(&mut self, physics: &mut PhysicsWorld, input: &InputState, camera_yaw: f32, dt: f32) {
+        let mut velocity = Self::move_direction(input, camera_yaw);
+        if input.is_held(InputAction::Jump) {
+            velocity.y += 1.0;
+        }
+        if input.is_held(InputAction::Crouch) {
+            velocity.y -= 1.0;
+        }
+        let speed = if input.is_held(InputAction::Sprint) {
+            NOCLIP_SPEED * NOCLIP_SPRINT
+        } else {
+            NOCLIP_SPEED
+        };
+        let position = self.position() + velocity.normalize_or_zero() * speed * dt;
+        self.character.set_position(physics, position);
+        self.character.velocity = Vec3::ZERO;
+        self.crouching = false;
+    }
+
+    /// First point a ray from `origin` hits, ignoring the player's own
+    /// capsule (e.g. where the crosshair points)
+    pub fn aim_point(&self, physics: &PhysicsWorld, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<Vec3> {
+        let filter = CastFilter::excluding_entity(EntityId::PLAYER);
+        physics.raycast_detailed(origin, direction.normalize_or_zero(), max_distance, filter.query()).map(|hit| hit.point)
+    }
+
+    /// Teleport the player to a position (getting up from any seat)
     pub fn teleport(&mut self, physics: &mut PhysicsWorld, position: Vec3) {
+        self.rest = None;
+        if self.grapple.is_some() {
+            self.release_grapple(physics);
+            self.horizontal_velocity = Vec3::ZERO;
+            self.vertical_velocity = 0.0;
+        }
         self.character.set_position(physics, position);
-        self.horizontal_velocity = Vec3::ZERO;
-        self.vertical_velocity = 0.0;
+        self.release_climb();
     }
 }
 
@@ -220,6 +799,7 @@ impl Default for PlayerController {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use infinite_physics::ForceField;
 
     #[test]
     fn test_player_controller_creation() {
@@ -227,6 +807,56 @@ mod tests {
         assert_eq!(player.position(), Vec3::ZERO);
     }
 
+    #[test]
+    fn test_render_position_blends_the_last_step() {
+        let mut physics = PhysicsWorld::new();
+        physics.create_ground(0.0);
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::new(0.0, 5.0, 0.0));
+        physics.update_query_pipeline();
+        let input = InputState::default();
+        for _ in 0..10 {
+            player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+        }
+        let before = player.position();
+        player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+        let fallen = before.y - player.position().y;
+        assert!(fallen > 0.0);
+
+        let center = player.character.center_position();
+        assert!((player.render_center_position(0.0).y - (center.y + fallen)).abs() < 1e-4);
+        assert!((player.render_center_position(0.5).y - (center.y + fallen / 2.0)).abs() < 1e-4);
+        assert_eq!(player.render_center_position(1.0), center);
+
+        // A teleport between steps is drawn where it lands
+        player.teleport(&mut physics, Vec3::new(10.0, 5.0, 0.0));
+        assert_eq!(player.render_eye_position(0.0), player.eye_position());
+    }
+
+    #[test]
+    fn test_explosions_throw_the_player() {
+        let mut physics = PhysicsWorld::new();
+        physics.create_ground(0.0);
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::ZERO);
+        physics.update_query_pipeline();
+        let input = InputState::default();
+        for _ in 0..30 {
+            player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+            physics.step();
+        }
+        let start = player.position();
+
+        physics.add_force_field(ForceField::explosion(start + Vec3::new(-1.0, -0.5, 0.0), 4.0, 12.0));
+        physics.step();
+        for _ in 0..10 {
+            player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+            physics.step();
+        }
+        let thrown = player.position() - start;
+        assert!(thrown.x > 0.3 && thrown.y > 0.1, "{thrown}");
+    }
+
     #[test]
     fn test_move_towards() {
         let result = PlayerController::move_towards_vec3(
@@ -236,4 +866,128 @@ mod tests {
         );
         assert!((result.x - 5.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_grab_wall_climbs_or_mantles() {
+        let mut physics = PhysicsWorld::new();
+        // Tall wall on the left, chest-high block on the right
+        physics.create_static_box(Vec3::new(1.0, 3.0, 1.0), Vec3::new(-3.0, 3.0, -2.0));
+        physics.create_static_box(Vec3::new(1.0, 0.75, 1.0), Vec3::new(3.0, 0.75, -2.0));
+
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::new(-3.0, 0.0, -0.6));
+        physics.update_query_pipeline();
+        assert!(player.grab_wall(&physics, Vec3::NEG_Z));
+        assert!(matches!(player.climb_state(), ClimbState::Climbing { .. }));
+
+        player.teleport(&mut physics, Vec3::new(3.0, 0.0, -0.6));
+        physics.update_query_pipeline();
+        assert!(!player.is_climbing());
+        assert!(player.grab_wall(&physics, Vec3::NEG_Z));
+        assert!(matches!(player.climb_state(), ClimbState::Mantling { .. }));
+
+        // Nothing to grab facing away
+        player.release_climb();
+        assert!(!player.grab_wall(&physics, Vec3::Z));
+    }
+
+    #[test]
+    fn test_rest_holds_still_until_moving() {
+        let mut physics = PhysicsWorld::new();
+        physics.create_static_box(Vec3::new(10.0, 0.5, 10.0), Vec3::new(0.0, -0.5, 0.0));
+
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::new(0.0, 0.0, 3.0));
+        physics.update_query_pipeline();
+        let standing_eye = player.eye_position().y;
+        let (spot, exit) = (Vec3::ZERO, Vec3::new(0.0, 0.0, 0.75));
+        player.rest(&mut physics, RestPose::Sitting, spot, Vec3::Z, exit);
+        assert_eq!(player.rest_pose(), Some(RestPose::Sitting));
+        assert!((player.eye_position().y - (standing_eye - RestPose::Sitting.eye_drop())).abs() < 1e-4);
+
+        let mut input = InputState::default();
+        for _ in 0..30 {
+            player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+            physics.step();
+        }
+        assert_eq!(player.position(), spot);
+
+        input.held.insert(InputAction::MoveForward);
+        player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+        assert!(!player.is_resting());
+        assert_eq!(player.position(), exit);
+    }
+
+    #[test]
+    fn test_noclip_flies_through_walls() {
+        let mut physics = PhysicsWorld::new();
+        physics.create_static_box(Vec3::new(2.0, 3.0, 0.5), Vec3::new(0.0, 3.0, -3.0));
+
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::new(0.0, 1.0, 0.0));
+        physics.update_query_pipeline();
+        player.set_noclip(&mut physics, true);
+        assert!(player.is_noclip());
+
+        let mut input = InputState::default();
+        input.held.insert(InputAction::MoveForward);
+        input.held.insert(InputAction::Jump);
+        for _ in 0..60 {
+            player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+            physics.step();
+        }
+        // Straight through the wall, and up without falling back
+        let position = player.position();
+        assert!(position.z < -6.0, "stopped at z = {}", position.z);
+        assert!(position.y > 5.0);
+
+        player.set_noclip(&mut physics, false);
+        assert!(!player.is_noclip());
+    }
+
+    #[test]
+    fn test_aim_point_skips_own_capsule() {
+        let mut physics = PhysicsWorld::new();
+        physics.create_static_box(Vec3::new(2.0, 3.0, 0.5), Vec3::new(0.0, 3.0, -5.0));
+
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::ZERO);
+        physics.update_query_pipeline();
+        let origin = player.character.center_position();
+        let hit = player.aim_point(&physics, origin, Vec3::NEG_Z, 20.0).unwrap();
+        assert!((hit.z + 4.5).abs() < 0.01, "hit at {:?}", hit);
+        assert!(player.aim_point(&physics, origin, Vec3::Z, 20.0).is_none());
+    }
+
+    #[test]
+    fn test_grapple_swing_and_release() {
+        let mut physics = PhysicsWorld::new();
+        let anchor = Vec3::new(0.0, 12.0, -6.0);
+
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, Vec3::new(0.0, 8.0, 0.0));
+        physics.update_query_pipeline();
+        // Nothing to hook onto in open air
+        let origin = player.eye_position();
+        assert!(!player.fire_grapple(&mut physics, origin, Vec3::NEG_Z, &[]));
+        assert!(player.fire_grapple(&mut physics, origin, anchor - origin, &[anchor]));
+        assert_eq!(player.grapple_anchor(), Some(anchor));
+
+        // Swinging keeps the player on the rope
+        let input = InputState::default();
+        let length = (anchor - player.character.center_position()).length();
+        for _ in 0..30 {
+            physics.step();
+            player.fixed_update(&mut physics, &input, 0.0, 1.0 / 60.0);
+            let distance = (player.character.center_position() - anchor).length();
+            assert!(distance < length + 0.3, "rope stretched to {}", distance);
+        }
+        assert!(player.character.center_position().z < -0.5);
+
+        // Releasing keeps the swing momentum
+        player.release_grapple(&mut physics);
+        assert!(!player.is_grappling());
+        assert!(player.horizontal_velocity.z < 0.0);
+        assert!(!player.take_grapple_snapped());
+    }
 }
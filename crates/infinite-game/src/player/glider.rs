@@ -0,0 +1,172 @@
+//! Glider traversal item
+//!
+//! Once owned, holding jump while falling deploys the glider: the player
+//! keeps a steady forward airspeed, sinks slowly under reduced gravity and
+//! drifts with the wind, draining stamina until landing or releasing jump.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Item name that unlocks the glider when collected
+pub const GLIDER_ITEM: &str = "Glider";
+
+/// Glider configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GliderConfig {
+    /// Forward airspeed in meters per second
+    pub airspeed: f32,
+    /// Airspeed while braking (holding back)
+    pub brake_airspeed: f32,
+    /// Fastest the glider sinks in meters per second
+    pub sink_speed: f32,
+    /// Gravity multiplier while gliding
+    pub gravity_scale: f32,
+    /// How quickly horizontal velocity turns towards the facing direction
+    pub steer_acceleration: f32,
+    /// Fraction of the wind velocity added to the glide
+    pub wind_influence: f32,
+    /// Stamina per second while gliding
+    pub stamina_cost: f32,
+    /// Seconds for the canopy to fully open or fold
+    pub deploy_time: f32,
+}
+
+impl Default for GliderConfig {
+    fn default() -> Self {
+        Self {
+            airspeed: 10.0,
+            brake_airspeed: 5.0,
+            sink_speed: 2.0,
+            gravity_scale: 0.25,
+            steer_acceleration: 6.0,
+            wind_influence: 0.5,
+            stamina_cost: 4.0,
+            deploy_time: 0.3,
+        }
+    }
+}
+
+/// Glider ownership and deploy state
+#[derive(Debug, Clone, Default)]
+pub struct Glider {
+    /// Whether the player owns a glider
+    pub owned: bool,
+    active: bool,
+    openness: f32,
+}
+
+impl Glider {
+    /// Whether the glider is deployed and carrying the player
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Canopy open amount (0.0 folded - 1.0 open) for deploy/retract animation
+    pub fn openness(&self) -> f32 {
+        self.openness
+    }
+
+    pub fn deploy(&mut self) {
+        if self.owned {
+            self.active = true;
+        }
+    }
+
+    pub fn retract(&mut self) {
+        self.active = false;
+    }
+
+    /// Animate the canopy towards its deploy state
+    pub fn update(&mut self, dt: f32, config: &GliderConfig) {
+        let rate = dt / config.deploy_time.max(0.01);
+        self.openness = if self.active {
+            (self.openness + rate).min(1.0)
+        } else {
+            (self.openness - rate).max(0.0)
+        };
+    }
+}
+
+/// Velocity after one glide step. `facing` is the horizontal look direction.
+pub fn glide_velocity(
+    current: Vec3,
+    facing: Vec3,
+    braking: bool,
+    wind: Vec3,
+    config: &GliderConfig,
+    dt: f32,
+) -> Vec3 {
+    let facing = Vec3::new(facing.x, 0.0, facing.z).normalize_or_zero();
+    let airspeed = if braking { config.brake_airspeed } else { config.airspeed };
+    let target = facing * airspeed + Vec3::new(wind.x, 0.0, wind.z) * config.wind_influence;
+
+    let horizontal = Vec3::new(current.x, 0.0, current.z);
+    let diff = target - horizontal;
+    let max_step = config.steer_acceleration * dt;
+    let horizontal = if diff.length() <= max_step {
+        target
+    } else {
+        horizontal + diff.normalize() * max_step
+    };
+
+    // Reduced gravity, and a fast fall is braked down to the sink speed
+    let vertical = if current.y < -config.sink_speed {
+        (current.y + 9.81 * 2.0 * dt).min(-config.sink_speed)
+    } else {
+        (current.y - 9.81 * config.gravity_scale * dt).max(-config.sink_speed)
+    };
+
+    Vec3::new(horizontal.x, vertical, horizontal.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glide_for(seconds: f32, start: Vec3, wind: Vec3) -> Vec3 {
+        let config = GliderConfig::default();
+        let mut v = start;
+        let dt = 1.0 / 60.0;
+        for _ in 0..(seconds / dt) as u32 {
+            v = glide_velocity(v, Vec3::NEG_Z, false, wind, &config, dt);
+        }
+        v
+    }
+
+    #[test]
+    fn test_glide_reaches_airspeed_and_sink_rate() {
+        let config = GliderConfig::default();
+        // Deployed mid-fall: descent is braked to the sink speed
+        let v = glide_for(5.0, Vec3::new(0.0, -12.0, 0.0), Vec3::ZERO);
+        assert!((v.y + config.sink_speed).abs() < 1e-3);
+        assert!((v.z + config.airspeed).abs() < 1e-3);
+        assert!(v.x.abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_wind_drift() {
+        let calm = glide_for(5.0, Vec3::ZERO, Vec3::ZERO);
+        let windy = glide_for(5.0, Vec3::ZERO, Vec3::new(6.0, 0.0, 0.0));
+        assert!(windy.x > 2.9 && calm.x.abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_deploy_requires_ownership_and_animates() {
+        let config = GliderConfig::default();
+        let mut glider = Glider::default();
+        glider.deploy();
+        assert!(!glider.is_active());
+
+        glider.owned = true;
+        glider.deploy();
+        glider.update(config.deploy_time * 0.5, &config);
+        assert!(glider.is_active());
+        assert!((glider.openness() - 0.5).abs() < 1e-4);
+        glider.update(config.deploy_time, &config);
+        assert_eq!(glider.openness(), 1.0);
+
+        glider.retract();
+        glider.update(config.deploy_time * 2.0, &config);
+        assert_eq!(glider.openness(), 0.0);
+    }
+}
@@ -0,0 +1,141 @@
+//! Grappling hook: anchor targeting and rope settings
+//!
+//! The hook attaches to explicit anchor points near the aim direction, or to
+//! any collider tagged with [`infinite_physics::GRAPPLE_FLAG`] under the
+//! crosshair. Once attached the player hangs from a rope joint (see
+//! [`infinite_physics::Rope`]) and can swing, reel in and out, or let go.
+
+use glam::Vec3;
+use infinite_physics::{PhysicsWorld, GRAPPLE_FLAG};
+use rapier3d::prelude::{ColliderHandle, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+/// Grappling hook configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrappleConfig {
+    /// Maximum distance to an anchor (also the longest the rope can be let out)
+    pub range: f32,
+    /// Half-angle in degrees around the aim direction that snaps to anchor points
+    pub aim_cone: f32,
+    /// Shortest the rope can be reeled in
+    pub min_length: f32,
+    /// Reel speed in meters per second
+    pub reel_speed: f32,
+    /// Force in newtons the player can push with while swinging
+    pub swing_force: f32,
+    /// Rope tension in newtons at which the rope snaps
+    pub break_force: f32,
+    /// Mass of the player on the rope in kilograms
+    pub mass: f32,
+}
+
+impl Default for GrappleConfig {
+    fn default() -> Self {
+        Self {
+            range: 35.0,
+            aim_cone: 8.0,
+            min_length: 2.0,
+            reel_speed: 8.0,
+            swing_force: 600.0,
+            break_force: 9000.0,
+            mass: 80.0,
+        }
+    }
+}
+
+/// Find where the hook attaches when fired from `origin` along `direction`.
+/// Anchor points inside the aim cone win over tagged surfaces; the closest to
+/// the crosshair is chosen.
+pub fn find_anchor(
+    physics: &PhysicsWorld,
+    origin: Vec3,
+    direction: Vec3,
+    anchors: &[Vec3],
+    config: &GrappleConfig,
+    exclude: Option<ColliderHandle>,
+) -> Option<Vec3> {
+    let direction = direction.normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return None;
+    }
+    let filter = match exclude {
+        Some(handle) => QueryFilter::default().exclude_collider(handle),
+        None => QueryFilter::default(),
+    };
+
+    let min_dot = config.aim_cone.to_radians().cos();
+    let best_point = anchors
+        .iter()
+        .filter_map(|&anchor| {
+            let to_anchor = anchor - origin;
+            let distance = to_anchor.length();
+            if distance < 0.01 || distance > config.range {
+                return None;
+            }
+            let dot = direction.dot(to_anchor / distance);
+            if dot < min_dot {
+                return None;
+            }
+            // Blocked if something is hit well before the anchor
+            let blocked = physics
+                .raycast(origin, to_anchor / distance, distance, filter)
+                .is_some_and(|(_, toi)| toi < distance - 0.5);
+            (!blocked).then_some((anchor, dot))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(anchor, _)| anchor);
+    if best_point.is_some() {
+        return best_point;
+    }
+
+    let hit = physics.raycast_detailed(origin, direction, config.range, filter)?;
+    physics
+        .has_surface_flag(hit.collider, GRAPPLE_FLAG)
+        .then_some(hit.point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world_with_post() -> PhysicsWorld {
+        let mut physics = PhysicsWorld::new();
+        // Tagged post ahead, untagged wall to the right
+        let post = physics.create_static_box(Vec3::new(0.5, 5.0, 0.5), Vec3::new(0.0, 5.0, -15.0));
+        physics.set_surface_flag(post, GRAPPLE_FLAG, true);
+        physics.create_static_box(Vec3::new(0.5, 5.0, 3.0), Vec3::new(10.0, 5.0, 0.0));
+        physics.update_query_pipeline();
+        physics
+    }
+
+    #[test]
+    fn test_tagged_surfaces_only() {
+        let physics = world_with_post();
+        let config = GrappleConfig::default();
+        let origin = Vec3::new(0.0, 2.0, 0.0);
+
+        let hit = find_anchor(&physics, origin, Vec3::NEG_Z, &[], &config, None).expect("tagged post");
+        assert!((hit.z + 14.5).abs() < 0.01);
+        assert!(find_anchor(&physics, origin, Vec3::X, &[], &config, None).is_none());
+
+        // Out of range
+        let short = GrappleConfig { range: 10.0, ..Default::default() };
+        assert!(find_anchor(&physics, origin, Vec3::NEG_Z, &[], &short, None).is_none());
+    }
+
+    #[test]
+    fn test_anchor_points_snap_within_cone() {
+        let physics = world_with_post();
+        let config = GrappleConfig::default();
+        let origin = Vec3::new(0.0, 2.0, 0.0);
+        let anchor = Vec3::new(-2.0, 12.0, -12.0);
+
+        // Aiming roughly at the anchor snaps to it
+        let aim = (anchor - origin).normalize() + Vec3::new(0.05, 0.0, 0.0);
+        assert_eq!(find_anchor(&physics, origin, aim, &[anchor], &config, None), Some(anchor));
+
+        // An anchor behind the untagged wall is blocked
+        let hidden = Vec3::new(14.0, 4.0, 0.0);
+        assert!(find_anchor(&physics, origin, Vec3::X, &[hidden], &config, None).is_none());
+    }
+}
@@ -2,13 +2,21 @@
 //!
 //! Provides first/third-person player movement with physics integration.
 
+pub mod climbing;
 mod controller;
+pub mod glider;
+pub mod grapple;
 mod movement;
+pub mod statistics;
 pub mod stats;
 
+pub use climbing::{ClimbConfig, ClimbState, Stamina};
 pub use controller::PlayerController;
+pub use glider::{Glider, GliderConfig, GLIDER_ITEM};
+pub use grapple::GrappleConfig;
 pub use movement::MovementConfig;
-pub use stats::{CharacterStats, EnemyType, PlayerProgression, StatGrowth};
+pub use statistics::{PlayStatistics, StatEvent};
+pub use stats::{CharacterStats, ClassSetup, EnemyType, PlayerProgression, StatGrowth};
 
 // Re-export combat types for convenience
 pub use crate::combat;
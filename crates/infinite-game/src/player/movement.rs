@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::{ClimbConfig, GliderConfig, GrappleConfig};
+
 /// Movement configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MovementConfig {
@@ -25,6 +27,22 @@ pub struct MovementConfig {
     pub coyote_time: f32,
     /// Jump buffer - how long a jump input is remembered before landing
     pub jump_buffer: f32,
+    /// Crouch speed multiplier (applied to walk_speed)
+    #[serde(default = "default_crouch_multiplier")]
+    pub crouch_multiplier: f32,
+    /// Free climbing settings
+    #[serde(default)]
+    pub climb: ClimbConfig,
+    /// Glider settings
+    #[serde(default)]
+    pub glider: GliderConfig,
+    /// Grappling hook settings
+    #[serde(default)]
+    pub grapple: GrappleConfig,
+}
+
+fn default_crouch_multiplier() -> f32 {
+    0.45
 }
 
 impl Default for MovementConfig {
@@ -40,6 +58,10 @@ impl Default for MovementConfig {
             gravity_scale: 1.0,
             coyote_time: 0.15,
             jump_buffer: 0.1,
+            crouch_multiplier: default_crouch_multiplier(),
+            climb: ClimbConfig::default(),
+            glider: GliderConfig::default(),
+            grapple: GrappleConfig::default(),
         }
     }
 }
@@ -54,6 +76,11 @@ impl MovementConfig {
         }
     }
 
+    /// Get the max speed while crouching
+    pub fn crouch_speed(&self) -> f32 {
+        self.walk_speed * self.crouch_multiplier
+    }
+
     /// Get the current acceleration based on grounded state
     pub fn acceleration(&self, grounded: bool) -> f32 {
         if grounded {
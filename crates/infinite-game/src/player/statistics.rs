@@ -0,0 +1,109 @@
+//! Play statistics: lifetime totals for the statistics screen
+//!
+//! Updated from gameplay events (kills, world bosses, gold changes, deaths,
+//! time travel) and from the player's position each frame for distance
+//! traveled.
+
+use std::collections::BTreeSet;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// Movement between two samples longer than this is a teleport (respawn,
+/// dungeon entry, loading) and does not count as distance traveled
+pub const TELEPORT_THRESHOLD: f32 = 20.0;
+
+/// A gameplay event the statistics care about
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatEvent {
+    EnemyDefeated,
+    WorldBossDefeated,
+    VisitedYear(i64),
+    GoldEarned(u64),
+    GoldSpent(u64),
+    Died,
+}
+
+/// Lifetime play statistics, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayStatistics {
+    /// Meters traveled on foot (horizontal)
+    pub distance_traveled: f32,
+    pub enemies_defeated: u32,
+    #[serde(default)]
+    pub world_bosses_defeated: u32,
+    pub years_visited: BTreeSet<i64>,
+    pub gold_earned: u64,
+    pub gold_spent: u64,
+    pub deaths: u32,
+    #[serde(skip)]
+    last_position: Option<Vec3>,
+}
+
+impl PlayStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, event: StatEvent) {
+        match event {
+            StatEvent::EnemyDefeated => self.enemies_defeated += 1,
+            StatEvent::WorldBossDefeated => self.world_bosses_defeated += 1,
+            StatEvent::VisitedYear(year) => {
+                self.years_visited.insert(year);
+            }
+            StatEvent::GoldEarned(amount) => self.gold_earned += amount,
+            StatEvent::GoldSpent(amount) => self.gold_spent += amount,
+            StatEvent::Died => self.deaths += 1,
+        }
+    }
+
+    /// Sample the player's position, adding the horizontal distance since
+    /// the last sample unless it was a teleport
+    pub fn track_position(&mut self, position: Vec3) {
+        if let Some(last) = self.last_position {
+            let step = (position - last).with_y(0.0).length();
+            if step < TELEPORT_THRESHOLD {
+                self.distance_traveled += step;
+            }
+        }
+        self.last_position = Some(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_events() {
+        let mut stats = PlayStatistics::new();
+        stats.record(StatEvent::EnemyDefeated);
+        stats.record(StatEvent::EnemyDefeated);
+        stats.record(StatEvent::GoldEarned(30));
+        stats.record(StatEvent::GoldSpent(12));
+        stats.record(StatEvent::VisitedYear(2024));
+        stats.record(StatEvent::VisitedYear(2024));
+        stats.record(StatEvent::VisitedYear(-500));
+        stats.record(StatEvent::Died);
+        stats.record(StatEvent::WorldBossDefeated);
+        assert_eq!(stats.enemies_defeated, 2);
+        assert_eq!(stats.world_bosses_defeated, 1);
+        assert_eq!((stats.gold_earned, stats.gold_spent), (30, 12));
+        assert_eq!(stats.years_visited.len(), 2);
+        assert_eq!(stats.deaths, 1);
+    }
+
+    #[test]
+    fn test_distance_ignores_height_and_teleports() {
+        let mut stats = PlayStatistics::new();
+        stats.track_position(Vec3::ZERO);
+        stats.track_position(Vec3::new(3.0, 5.0, 4.0));
+        assert!((stats.distance_traveled - 5.0).abs() < 1e-5);
+
+        stats.track_position(Vec3::new(1000.0, 0.0, 0.0));
+        assert!((stats.distance_traveled - 5.0).abs() < 1e-5);
+        stats.track_position(Vec3::new(1001.0, 0.0, 0.0));
+        assert!((stats.distance_traveled - 6.0).abs() < 1e-5);
+    }
+}
@@ -4,6 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::balance;
 use crate::combat::damage::StatModifiers;
 use crate::combat::element::Element;
 
@@ -112,10 +113,17 @@ impl CharacterStats {
         self.current_hp > 0.0
     }
 
-    /// Calculate damage dealt to a target with given defense
+    /// Finesse for delicate work like lockpicking, from crit chance and speed
+    /// (5 with default stats)
+    pub fn dexterity(&self) -> f32 {
+        self.crit_chance * 100.0 + (self.speed - 1.0) * 20.0
+    }
+
+    /// Calculate damage dealt to a target with given defense, critting when
+    /// `crit_roll` (uniform in `0.0..1.0`) lands under the crit chance
     /// Returns (damage, is_crit)
-    pub fn calculate_damage(&self, target_defense: f32) -> (f32, bool) {
-        let is_crit = rand::random::<f32>() < self.crit_chance;
+    pub fn calculate_damage(&self, target_defense: f32, crit_roll: f32) -> (f32, bool) {
+        let is_crit = crit_roll < self.crit_chance;
         let base_damage = self.attack - target_defense * 0.5;
         let damage = if is_crit {
             base_damage * self.crit_multiplier
@@ -130,6 +138,19 @@ impl CharacterStats {
         (self.attack - target_defense * 0.5).max(1.0)
     }
 
+    /// These stats as a level 1 base, grown to `level`, at full HP and mana
+    pub fn at_level(&self, growth: &StatGrowth, level: u32) -> CharacterStats {
+        let levels = level.saturating_sub(1) as f32;
+        let mut stats = self.clone();
+        stats.max_hp += growth.hp_per_level * levels;
+        stats.attack += growth.attack_per_level * levels;
+        stats.defense += growth.defense_per_level * levels;
+        stats.speed += growth.speed_per_level * levels;
+        stats.current_hp = stats.max_hp;
+        stats.current_mana = stats.max_mana;
+        stats
+    }
+
     /// Apply level-up stat growth
     pub fn apply_growth(&mut self, growth: &StatGrowth) {
         self.max_hp += growth.hp_per_level;
@@ -177,7 +198,7 @@ impl CharacterStats {
 }
 
 /// Per-level stat growth rates
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StatGrowth {
     /// HP gained per level
     pub hp_per_level: f32,
@@ -200,6 +221,38 @@ impl Default for StatGrowth {
     }
 }
 
+impl StatGrowth {
+    /// Growth of a character multiclassing into `other`: the average of the
+    /// two tables, cut to [`balance::player::MULTICLASS_GROWTH`] of it
+    pub fn blend(&self, other: &StatGrowth) -> StatGrowth {
+        let share = balance::player::MULTICLASS_GROWTH.get() * 0.5;
+        StatGrowth {
+            hp_per_level: (self.hp_per_level + other.hp_per_level) * share,
+            attack_per_level: (self.attack_per_level + other.attack_per_level) * share,
+            defense_per_level: (self.defense_per_level + other.defense_per_level) * share,
+            speed_per_level: (self.speed_per_level + other.speed_per_level) * share,
+        }
+    }
+}
+
+/// The archetypes a character's stats come from, by name. The game maps
+/// the names to its archetypes' base stats and growth tables.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassSetup {
+    /// Archetype the base stats come from; `None` until one is chosen
+    pub primary: Option<String>,
+    /// Second archetype whose growth is blended in when multiclassing
+    pub secondary: Option<String>,
+    /// Times the class has been changed after it was first chosen
+    pub respecs: u32,
+}
+
+impl ClassSetup {
+    pub fn is_multiclass(&self) -> bool {
+        self.secondary.is_some()
+    }
+}
+
 /// Player-specific progression data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerProgression {
@@ -209,6 +262,9 @@ pub struct PlayerProgression {
     pub current_xp: u64,
     /// Total XP earned across all levels
     pub total_xp: u64,
+    /// Chosen archetypes
+    #[serde(default)]
+    pub class: ClassSetup,
 }
 
 impl Default for PlayerProgression {
@@ -217,6 +273,7 @@ impl Default for PlayerProgression {
             level: 1,
             current_xp: 0,
             total_xp: 0,
+            class: ClassSetup::default(),
         }
     }
 }
@@ -228,12 +285,24 @@ impl PlayerProgression {
     }
 
     /// XP required to reach a given level (cumulative from level 1)
-    /// Formula: 100 * level^1.5 (rounded)
+    /// Formula: base * level^exponent (rounded), tuned by
+    /// [`balance::player::XP_BASE`] and [`balance::player::XP_EXPONENT`]
     pub fn xp_for_level(level: u32) -> u64 {
         if level <= 1 {
             return 0;
         }
-        (100.0 * (level as f64).powf(1.5)).round() as u64
+        let base = balance::player::XP_BASE.get() as f64;
+        let exponent = balance::player::XP_EXPONENT.get() as f64;
+        (base * (level as f64).powf(exponent)).round() as u64
+    }
+
+    /// Gold to change class: nothing for the first choice, then
+    /// [`balance::player::RESPEC_COST`] per level
+    pub fn respec_cost(&self) -> u64 {
+        if self.class.primary.is_none() {
+            return 0;
+        }
+        (balance::player::RESPEC_COST.get() * self.level as f32).round() as u64
     }
 
     /// XP needed to go from current level to next level
@@ -294,6 +363,15 @@ pub fn xp_for_enemy(enemy_level: u32, enemy_type: EnemyType) -> u64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_dexterity() {
+        let mut stats = CharacterStats::default();
+        assert!((stats.dexterity() - 5.0).abs() < 1e-4);
+        stats.crit_chance = 0.1;
+        stats.speed = 1.1;
+        assert!((stats.dexterity() - 12.0).abs() < 1e-4);
+    }
+
     #[test]
     fn test_character_stats_default() {
         let stats = CharacterStats::default();
@@ -305,14 +383,14 @@ mod tests {
     fn test_damage_calculation() {
         let stats = CharacterStats::new(100.0, 20.0, 5.0, 1.0);
         // With 10 defense, damage should be 20 - 10*0.5 = 15 (minimum)
-        let (damage, _) = stats.calculate_damage(10.0);
+        let (damage, _) = stats.calculate_damage(10.0, 0.5);
         assert!(damage >= 1.0);
     }
 
     #[test]
     fn test_damage_minimum() {
         let stats = CharacterStats::new(100.0, 5.0, 5.0, 1.0);
-        let (damage, _) = stats.calculate_damage(100.0);
+        let (damage, _) = stats.calculate_damage(100.0, 0.99);
         assert_eq!(damage, 1.0); // Minimum 1 damage
     }
 
@@ -433,8 +511,10 @@ mod tests {
 
     #[test]
     fn test_regenerate_mana() {
-        let mut stats = CharacterStats::default();
-        stats.current_mana = 50.0;
+        let mut stats = CharacterStats {
+            current_mana: 50.0,
+            ..Default::default()
+        };
 
         stats.regenerate_mana(1.0); // 2.0 per second
         assert_eq!(stats.current_mana, 52.0);
@@ -445,6 +525,45 @@ mod tests {
         assert_eq!(stats.current_mana, 100.0);
     }
 
+    #[test]
+    fn test_stats_at_level() {
+        let base = CharacterStats::default();
+        let growth = StatGrowth::default();
+        assert_eq!(base.at_level(&growth, 1).max_hp, base.max_hp);
+
+        let mut grown = base.clone();
+        for _ in 0..4 {
+            grown.apply_growth(&growth);
+        }
+        let rebuilt = base.at_level(&growth, 5);
+        assert!((rebuilt.max_hp - grown.max_hp).abs() < 1e-3);
+        assert!((rebuilt.attack - grown.attack).abs() < 1e-3);
+        assert_eq!(rebuilt.current_hp, rebuilt.max_hp);
+    }
+
+    #[test]
+    fn test_growth_blend_applies_multiclass_penalty() {
+        let a = StatGrowth { hp_per_level: 10.0, attack_per_level: 2.0, defense_per_level: 0.0, speed_per_level: 0.0 };
+        let b = StatGrowth { hp_per_level: 20.0, attack_per_level: 0.0, defense_per_level: 2.0, speed_per_level: 0.0 };
+        let share = balance::player::MULTICLASS_GROWTH.get();
+        let blend = a.blend(&b);
+        assert!((blend.hp_per_level - 15.0 * share).abs() < 1e-4);
+        assert!((blend.attack_per_level - share).abs() < 1e-4);
+        assert!((blend.defense_per_level - share).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_respec_cost_and_old_saves() {
+        let mut progression = PlayerProgression::default();
+        assert_eq!(progression.respec_cost(), 0);
+        progression.class.primary = Some("Wanderer".into());
+        progression.level = 4;
+        assert_eq!(progression.respec_cost(), (balance::player::RESPEC_COST.get() * 4.0).round() as u64);
+
+        let old: PlayerProgression = serde_json::from_str(r#"{"level":3,"current_xp":10,"total_xp":400}"#).unwrap();
+        assert_eq!(old.class, ClassSetup::default());
+    }
+
     #[test]
     fn test_mana_fraction() {
         let mut stats = CharacterStats::default();
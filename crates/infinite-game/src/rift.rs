@@ -0,0 +1,545 @@
+//! Time rifts: run-based forays through arenas torn from random eras
+//!
+//! A rift run chains arenas, each a freshly generated dungeon floor from a
+//! random era, held by enemies of that era's element who grow tougher the
+//! deeper the run goes. Clearing an arena offers a choice of boons; the ones
+//! taken stack for the rest of the run as the Rift-Touched status. Death
+//! ends the run and takes its boons with it, but the rift shards earned on
+//! the way are banked in the [`RiftLedger`], saved with the game and spent
+//! on attunements that help every later run.
+
+use glam::Vec3;
+use infinite_core::DetRng;
+use infinite_world::DungeonLayout;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::{Element, ELEMENT_COUNT};
+use crate::combat::status::{StatusEffect, StatusEffectType};
+use crate::npc::combat::{CombatStats, PlayerCombatState};
+use crate::npc::identity::Era;
+use crate::npc::{NpcData, NpcFaction, NpcId, NpcRole};
+
+/// Boons offered after an arena, before attunements add more
+pub const BOON_CHOICES: usize = 3;
+/// Shards for clearing an arena, times its depth
+pub const SHARDS_PER_ARENA: u64 = 4;
+/// Fraction of max HP restored on taking a boon
+pub const CLEAR_HEAL: f32 = 0.3;
+/// Extra enemy health and attack per arena after the first
+const HP_PER_DEPTH: f32 = 0.2;
+const ATTACK_PER_DEPTH: f32 = 0.12;
+
+/// A boon offered between rift arenas. Boons stack, including repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RiftBoon {
+    Ferocity,
+    Bulwark,
+    Celerity,
+    Precision,
+    Carnage,
+    Warding,
+    /// Harder hits of the player's element
+    Resonance,
+}
+
+impl RiftBoon {
+    pub const ALL: [RiftBoon; 7] = [
+        Self::Ferocity,
+        Self::Bulwark,
+        Self::Celerity,
+        Self::Precision,
+        Self::Carnage,
+        Self::Warding,
+        Self::Resonance,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ferocity => "Ferocity",
+            Self::Bulwark => "Bulwark",
+            Self::Celerity => "Celerity",
+            Self::Precision => "Precision",
+            Self::Carnage => "Carnage",
+            Self::Warding => "Warding",
+            Self::Resonance => "Resonance",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Ferocity => "+6 attack",
+            Self::Bulwark => "+4 defense",
+            Self::Celerity => "+10% movement speed",
+            Self::Precision => "+8% critical chance",
+            Self::Carnage => "+30% critical damage",
+            Self::Warding => "+10% resistance to every element",
+            Self::Resonance => "+10 damage to attacks of your element",
+        }
+    }
+
+    /// What the boon adds for a player of `player`'s affinity
+    pub fn modifiers(self, player: &PlayerCombatState) -> StatModifiers {
+        let mut modifiers = StatModifiers::default();
+        match self {
+            Self::Ferocity => modifiers.attack = 6.0,
+            Self::Bulwark => modifiers.defense = 4.0,
+            Self::Celerity => modifiers.speed = 0.1,
+            Self::Precision => modifiers.crit_chance = 0.08,
+            Self::Carnage => modifiers.crit_multiplier = 0.3,
+            Self::Warding => modifiers.elemental_resistance = [0.1; ELEMENT_COUNT],
+            Self::Resonance => modifiers.elemental_damage_bonus[player.stats.elemental_affinity.index()] = 10.0,
+        }
+        modifiers
+    }
+}
+
+/// A lasting upgrade bought with rift shards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiftAttunement {
+    /// One more boon to choose from per level
+    Foresight,
+    /// More shards per arena
+    Plunder,
+    /// A random boon to start each run with per level
+    Echo,
+}
+
+impl RiftAttunement {
+    pub const ALL: [RiftAttunement; 3] = [Self::Foresight, Self::Plunder, Self::Echo];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Foresight => "Foresight",
+            Self::Plunder => "Plunder",
+            Self::Echo => "Echo",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Foresight => "One more boon to choose from after each arena",
+            Self::Plunder => "+25% rift shards",
+            Self::Echo => "Start each run with a random boon",
+        }
+    }
+
+    pub fn max_level(self) -> u32 {
+        match self {
+            Self::Foresight => 2,
+            Self::Plunder | Self::Echo => 3,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Shards the next level costs after reaching `level`
+    fn cost(self, level: u32) -> u64 {
+        let base = match self {
+            Self::Foresight => 40,
+            Self::Plunder => 25,
+            Self::Echo => 30,
+        };
+        base * (level as u64 + 1)
+    }
+}
+
+/// Why a rift choice or purchase was turned down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiftError {
+    /// There's no boon on offer right now
+    NotChoosing,
+    NoSuchChoice,
+    NotEnoughShards,
+    /// The attunement is already at its highest level
+    Maxed,
+}
+
+/// Shards, records and attunements carried between runs, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiftLedger {
+    /// Shards banked and not yet spent
+    pub shards: u64,
+    /// Deepest arena ever cleared
+    pub best_depth: u32,
+    pub runs: u32,
+    attunements: [u32; RiftAttunement::ALL.len()],
+}
+
+impl RiftLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn level(&self, attunement: RiftAttunement) -> u32 {
+        self.attunements[attunement.index()]
+    }
+
+    /// Shards the attunement's next level costs, or None if it's maxed
+    pub fn cost(&self, attunement: RiftAttunement) -> Option<u64> {
+        let level = self.level(attunement);
+        (level < attunement.max_level()).then(|| attunement.cost(level))
+    }
+
+    /// Spend shards on the attunement's next level
+    pub fn attune(&mut self, attunement: RiftAttunement) -> Result<(), RiftError> {
+        let cost = self.cost(attunement).ok_or(RiftError::Maxed)?;
+        if self.shards < cost {
+            return Err(RiftError::NotEnoughShards);
+        }
+        self.shards -= cost;
+        self.attunements[attunement.index()] += 1;
+        Ok(())
+    }
+
+    /// Bank what a finished run earned
+    pub fn bank(&mut self, outcome: RiftOutcome) {
+        self.shards += outcome.shards;
+        self.best_depth = self.best_depth.max(outcome.cleared);
+        self.runs += 1;
+    }
+}
+
+/// One arena of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiftArena {
+    /// 1-based
+    pub depth: u32,
+    pub era: Era,
+    /// Seed its dungeon floor is generated from
+    pub seed: u64,
+}
+
+impl RiftArena {
+    pub fn name(&self) -> &'static str {
+        match self.era {
+            Era::Ancient => "Sunken Agora",
+            Era::Medieval => "Burning Keep",
+            Era::Modern => "Shattered Metro",
+            Era::Future => "Null Spire",
+        }
+    }
+
+    /// Element the arena's enemies fight with
+    pub fn element(&self) -> Element {
+        match self.era {
+            Era::Ancient => Element::Earth,
+            Era::Medieval => Element::Fire,
+            Era::Modern => Element::Physical,
+            Era::Future => Element::Void,
+        }
+    }
+
+    fn enemy_name(&self, boss: bool) -> &'static str {
+        match (self.era, boss) {
+            (_, true) => "Rift Tyrant",
+            (Era::Ancient, _) => "Rift Hoplite",
+            (Era::Medieval, _) => "Rift Revenant",
+            (Era::Modern, _) => "Rift Marauder",
+            (Era::Future, _) => "Rift Synth",
+        }
+    }
+}
+
+/// Where a run stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiftPhase {
+    /// Enemies are on the floor
+    Fighting,
+    /// The arena is clear and a boon is waiting to be picked
+    Choosing,
+    /// The player fell or walked out
+    Over,
+}
+
+/// What a finished run earned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiftOutcome {
+    /// Arenas cleared
+    pub cleared: u32,
+    pub shards: u64,
+}
+
+/// A rift run in progress
+#[derive(Debug, Clone)]
+pub struct RiftRun {
+    rng: DetRng,
+    arena: RiftArena,
+    phase: RiftPhase,
+    alive: Vec<NpcId>,
+    boons: Vec<RiftBoon>,
+    offer: Vec<RiftBoon>,
+    shards: u64,
+    cleared: u32,
+    /// Boons offered after each arena
+    choices: usize,
+    /// Multiplier on shards earned
+    shard_scale: f32,
+}
+
+impl RiftRun {
+    /// Start a run with the ledger's attunements, granting any Echo boons
+    pub fn new(seed: u64, ledger: &RiftLedger, player: &mut PlayerCombatState) -> Self {
+        let mut rng = DetRng::new(seed);
+        let arena = roll_arena(&mut rng, 1, None);
+        let boons = (0..ledger.level(RiftAttunement::Echo))
+            .map(|_| *RiftBoon::ALL.choose(&mut rng).unwrap_or(&RiftBoon::Ferocity))
+            .collect();
+        let run = Self {
+            rng,
+            arena,
+            phase: RiftPhase::Fighting,
+            alive: Vec::new(),
+            boons,
+            offer: Vec::new(),
+            shards: 0,
+            cleared: 0,
+            choices: BOON_CHOICES + ledger.level(RiftAttunement::Foresight) as usize,
+            shard_scale: 1.0 + 0.25 * ledger.level(RiftAttunement::Plunder) as f32,
+        };
+        run.apply_boons(player);
+        run
+    }
+
+    pub fn arena(&self) -> &RiftArena {
+        &self.arena
+    }
+
+    pub fn phase(&self) -> RiftPhase {
+        self.phase
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.phase == RiftPhase::Over
+    }
+
+    /// Arenas cleared so far
+    pub fn cleared(&self) -> u32 {
+        self.cleared
+    }
+
+    pub fn boons(&self) -> &[RiftBoon] {
+        &self.boons
+    }
+
+    /// Boons to pick from after a cleared arena
+    pub fn offer(&self) -> &[RiftBoon] {
+        &self.offer
+    }
+
+    pub fn shards(&self) -> u64 {
+        self.shards
+    }
+
+    /// Enemies of the current arena still standing
+    pub fn remaining(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// Enemy IDs still alive (for despawning when the run ends)
+    pub fn alive(&self) -> &[NpcId] {
+        &self.alive
+    }
+
+    /// NPC data and stats, at the packs' floor positions, for the current
+    /// arena built from `layout`
+    pub fn arena_spawns(&self, layout: &DungeonLayout) -> Vec<(Vec3, NpcData, CombatStats)> {
+        let element = self.arena.element();
+        let scale = self.arena.depth as f32 - 1.0;
+        layout
+            .packs
+            .iter()
+            .flat_map(|pack| pack.positions.iter().map(move |position| (*position, pack.boss)))
+            .map(|(position, boss)| {
+                let data = NpcData {
+                    name: self.arena.enemy_name(boss).to_string(),
+                    role: NpcRole::Enemy,
+                    faction: NpcFaction::Hostile,
+                    home_position: position,
+                    wander_radius: 4.0,
+                    interaction_radius: 0.0,
+                    color: NpcRole::Enemy.color(),
+                    server_character_id: None,
+                };
+                let mut stats = if boss { CombatStats::boss(element) } else { CombatStats::elemental_enemy(element) };
+                stats.max_hp *= 1.0 + HP_PER_DEPTH * scale;
+                stats.current_hp = stats.max_hp;
+                stats.attack *= 1.0 + ATTACK_PER_DEPTH * scale;
+                (position, data, stats)
+            })
+            .collect()
+    }
+
+    /// Record the NPCs spawned for the current arena
+    pub fn begin_arena(&mut self, ids: Vec<NpcId>) {
+        self.alive = ids;
+        self.phase = RiftPhase::Fighting;
+    }
+
+    /// Count the fallen and, once the arena is clear, award its shards and
+    /// roll the boons on offer. Returns the depth cleared.
+    pub fn update(&mut self, is_alive: impl Fn(NpcId) -> bool) -> Option<u32> {
+        if self.phase != RiftPhase::Fighting {
+            return None;
+        }
+        self.alive.retain(|id| is_alive(*id));
+        if !self.alive.is_empty() {
+            return None;
+        }
+        self.cleared = self.arena.depth;
+        let earned = SHARDS_PER_ARENA * self.arena.depth as u64;
+        self.shards += (earned as f32 * self.shard_scale).round() as u64;
+        let mut pool = RiftBoon::ALL.to_vec();
+        pool.shuffle(&mut self.rng);
+        pool.truncate(self.choices);
+        self.offer = pool;
+        self.phase = RiftPhase::Choosing;
+        Some(self.arena.depth)
+    }
+
+    /// Take one of the boons on offer: it joins the Rift-Touched status,
+    /// some health comes back, and the run moves on to the next arena for
+    /// the caller to build
+    pub fn choose(&mut self, index: usize, player: &mut PlayerCombatState) -> Result<RiftBoon, RiftError> {
+        if self.phase != RiftPhase::Choosing {
+            return Err(RiftError::NotChoosing);
+        }
+        let boon = *self.offer.get(index).ok_or(RiftError::NoSuchChoice)?;
+        self.boons.push(boon);
+        self.offer.clear();
+        self.apply_boons(player);
+        let max_hp = player.max_hp();
+        player.stats.current_hp = (player.stats.current_hp + max_hp * CLEAR_HEAL).min(max_hp);
+
+        self.arena = roll_arena(&mut self.rng, self.arena.depth + 1, Some(self.arena.era));
+        self.alive.clear();
+        self.phase = RiftPhase::Fighting;
+        Ok(boon)
+    }
+
+    /// Everything the run's boons add up to
+    pub fn modifiers(&self, player: &PlayerCombatState) -> StatModifiers {
+        let mut total = StatModifiers::default();
+        for boon in &self.boons {
+            total.add(&boon.modifiers(player));
+        }
+        total
+    }
+
+    /// Swap the player's Rift-Touched status for one carrying every boon
+    fn apply_boons(&self, player: &mut PlayerCombatState) {
+        player.status_manager.remove(StatusEffectType::RiftTouched);
+        if !self.boons.is_empty() {
+            let status = StatusEffect::stat_modifier(StatusEffectType::RiftTouched, f32::INFINITY, self.modifiers(player));
+            player.status_manager.apply(status);
+        }
+    }
+
+    /// The run is over, by death or by walking out: the boons fade and the
+    /// shards are the caller's to bank
+    pub fn end(&mut self, player: &mut PlayerCombatState) -> RiftOutcome {
+        self.phase = RiftPhase::Over;
+        self.offer.clear();
+        player.status_manager.remove(StatusEffectType::RiftTouched);
+        RiftOutcome { cleared: self.cleared, shards: self.shards }
+    }
+}
+
+/// The arena at `depth`, from a different era than the one before it
+fn roll_arena(rng: &mut DetRng, depth: u32, previous: Option<Era>) -> RiftArena {
+    let eras: Vec<Era> = Era::ALL.into_iter().filter(|era| Some(*era) != previous).collect();
+    let era = *eras.choose(rng).unwrap_or(&Era::Ancient);
+    RiftArena { depth, era, seed: rng.gen() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use infinite_world::DungeonConfig;
+
+    fn clear(run: &mut RiftRun) -> u32 {
+        run.begin_arena(vec![NpcId(1)]);
+        assert_eq!(run.update(|_| true), None);
+        run.update(|_| false).expect("arena cleared")
+    }
+
+    #[test]
+    fn test_arenas_change_era_and_scale_up() {
+        let mut player = PlayerCombatState::new();
+        let mut run = RiftRun::new(9, &RiftLedger::new(), &mut player);
+        let layout = DungeonLayout::generate(run.arena().seed, &DungeonConfig::default());
+        let first = run.arena_spawns(&layout);
+        assert!(!first.is_empty());
+        assert!(first.iter().all(|(_, _, stats)| stats.element == run.arena().element()));
+        let bosses: usize = layout.packs.iter().filter(|p| p.boss).map(|p| p.positions.len()).sum();
+        assert_eq!(first.iter().filter(|(_, data, _)| data.name == "Rift Tyrant").count(), bosses);
+
+        let era = run.arena().era;
+        assert_eq!(clear(&mut run), 1);
+        assert_eq!(run.offer().len(), BOON_CHOICES);
+        run.choose(0, &mut player).unwrap();
+        assert_eq!(run.arena().depth, 2);
+        assert_ne!(run.arena().era, era);
+
+        let layout = DungeonLayout::generate(run.arena().seed, &DungeonConfig::default());
+        let deeper = run.arena_spawns(&layout);
+        // Weakest enemy's health against an unscaled one of its element
+        let hp = |spawns: &[(Vec3, NpcData, CombatStats)]| {
+            spawns
+                .iter()
+                .map(|(_, _, s)| s.max_hp / CombatStats::elemental_enemy(s.element).max_hp)
+                .fold(f32::MAX, f32::min)
+        };
+        assert!(hp(&deeper) > hp(&first));
+    }
+
+    #[test]
+    fn test_boons_stack_as_one_status() {
+        let mut player = PlayerCombatState::new();
+        let mut run = RiftRun::new(3, &RiftLedger::new(), &mut player);
+        assert!(!player.status_manager.has_effect(StatusEffectType::RiftTouched));
+        assert_eq!(run.choose(0, &mut player), Err(RiftError::NotChoosing));
+
+        for _ in 0..2 {
+            clear(&mut run);
+            let offered = run.offer()[0];
+            assert_eq!(run.choose(0, &mut player), Ok(offered));
+        }
+        assert_eq!(run.boons().len(), 2);
+        let touched = player.status_manager.effects.iter().filter(|e| e.effect_type == StatusEffectType::RiftTouched);
+        assert_eq!(touched.count(), 1);
+        let carried = player.status_manager.combined_modifiers();
+        let expected = run.modifiers(&player);
+        assert_eq!((carried.attack, carried.defense, carried.speed), (expected.attack, expected.defense, expected.speed));
+
+        let outcome = run.end(&mut player);
+        assert_eq!(outcome, RiftOutcome { cleared: 2, shards: SHARDS_PER_ARENA * 3 });
+        assert!(!player.status_manager.has_effect(StatusEffectType::RiftTouched));
+    }
+
+    #[test]
+    fn test_shards_buy_attunements_for_later_runs() {
+        let mut ledger = RiftLedger::new();
+        assert_eq!(ledger.attune(RiftAttunement::Echo), Err(RiftError::NotEnoughShards));
+        ledger.bank(RiftOutcome { cleared: 4, shards: 200 });
+        ledger.bank(RiftOutcome { cleared: 2, shards: 0 });
+        assert_eq!((ledger.best_depth, ledger.runs), (4, 2));
+
+        ledger.attune(RiftAttunement::Echo).unwrap();
+        ledger.attune(RiftAttunement::Foresight).unwrap();
+        ledger.attune(RiftAttunement::Foresight).unwrap();
+        assert_eq!(ledger.attune(RiftAttunement::Foresight), Err(RiftError::Maxed));
+        assert_eq!(ledger.shards, 200 - 30 - 40 - 80);
+
+        let mut player = PlayerCombatState::new();
+        let mut run = RiftRun::new(5, &ledger, &mut player);
+        assert_eq!(run.boons().len(), 1);
+        assert!(player.status_manager.has_effect(StatusEffectType::RiftTouched));
+        clear(&mut run);
+        assert_eq!(run.offer().len(), BOON_CHOICES + 2);
+    }
+}
@@ -0,0 +1,162 @@
+//! Screen-space markers for tracked world positions
+//!
+//! Objectives, waypoints and anything else worth following get a marker on
+//! screen as well as on the compass. In view, it sits over its target; out
+//! of view (or behind the camera) it is pinned to the edge of the screen on
+//! the side to turn towards, with the distance to go. Positions here are in
+//! normalized device coordinates, y up; the HUD maps them to pixels.
+
+use glam::{Mat4, Vec2, Vec3};
+
+use crate::compass::{CompassFilter, CompassMarker, MarkerCategory};
+
+/// Markers closer than this are reached and not shown
+const MIN_DISTANCE: f32 = 2.0;
+
+/// Where a world position shows up on screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScreenPlacement {
+    /// In view, at this position
+    OnScreen(Vec2),
+    /// Out of view: pinned to the screen's edge at `position`, with
+    /// `direction` (unit length) pointing the way to turn
+    Edge { position: Vec2, direction: Vec2 },
+}
+
+impl ScreenPlacement {
+    pub fn position(&self) -> Vec2 {
+        match *self {
+            Self::OnScreen(position) | Self::Edge { position, .. } => position,
+        }
+    }
+}
+
+/// A tracked marker placed on screen for this frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenMarker {
+    pub label: String,
+    pub category: MarkerCategory,
+    pub placement: ScreenPlacement,
+    /// Distance from the player in meters
+    pub distance: f32,
+}
+
+/// `world_pos` in normalized device coordinates, or None when it is behind
+/// the camera. Points in front but outside the view land beyond ±1.
+pub fn project(world_pos: Vec3, view_proj: Mat4) -> Option<Vec2> {
+    let clip = view_proj * world_pos.extend(1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    Some(Vec2::new(clip.x, clip.y) / clip.w)
+}
+
+/// Normalized device coordinates to a pixel position on a `screen_size`
+/// screen (y down)
+pub fn ndc_to_screen(ndc: Vec2, screen_size: Vec2) -> Vec2 {
+    Vec2::new((ndc.x + 1.0) * 0.5 * screen_size.x, (1.0 - ndc.y) * 0.5 * screen_size.y)
+}
+
+/// Place `world_pos` on screen. Out of view it is pinned `margin` (in
+/// normalized device units) inside the edge.
+pub fn place(world_pos: Vec3, view_proj: Mat4, margin: Vec2) -> ScreenPlacement {
+    let clip = view_proj * world_pos.extend(1.0);
+    if clip.w > 0.0 {
+        let ndc = Vec2::new(clip.x, clip.y) / clip.w;
+        if ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 {
+            return ScreenPlacement::OnScreen(ndc);
+        }
+    }
+    // Dividing by |w| keeps points behind the camera on the side they are
+    // on rather than mirroring them across the screen
+    let offset = Vec2::new(clip.x, clip.y) / clip.w.abs().max(1e-5);
+    // Straight behind has no side; point down, to turn around
+    let direction = offset.try_normalize().unwrap_or(Vec2::NEG_Y);
+    let bounds = (Vec2::ONE - margin).max(Vec2::ZERO);
+    let reach = (bounds / direction.abs().max(Vec2::splat(1e-5))).min_element();
+    ScreenPlacement::Edge {
+        position: direction * reach,
+        direction,
+    }
+}
+
+/// On-screen markers for `markers` the `filter` allows, seen from a camera
+/// with `view_proj` by a player at `player_pos`. Sorted far to near, so
+/// nearer markers draw on top.
+pub fn screen_markers<'a>(
+    markers: impl IntoIterator<Item = &'a CompassMarker>,
+    player_pos: Vec3,
+    view_proj: Mat4,
+    margin: Vec2,
+    filter: &CompassFilter,
+) -> Vec<ScreenMarker> {
+    let mut placed: Vec<ScreenMarker> = markers
+        .into_iter()
+        .filter(|m| filter.allows(m.category))
+        .filter_map(|m| {
+            let distance = m.position.distance(player_pos);
+            (distance >= MIN_DISTANCE).then(|| ScreenMarker {
+                label: m.label.clone(),
+                category: m.category,
+                placement: place(m.position, view_proj, margin),
+                distance,
+            })
+        })
+        .collect();
+    placed.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+    placed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera at the origin looking down -Z (north), 90° field of view
+    fn view_proj() -> Mat4 {
+        Mat4::perspective_rh(90f32.to_radians(), 1.0, 0.1, 1000.0)
+            * Mat4::look_at_rh(Vec3::ZERO, Vec3::NEG_Z, Vec3::Y)
+    }
+
+    #[test]
+    fn test_in_view_markers_sit_over_targets() {
+        let ahead = place(Vec3::new(0.0, 0.0, -10.0), view_proj(), Vec2::ZERO);
+        assert_eq!(ahead, ScreenPlacement::OnScreen(Vec2::ZERO));
+        assert_eq!(project(Vec3::new(0.0, 0.0, 10.0), view_proj()), None);
+        assert_eq!(ndc_to_screen(Vec2::new(-1.0, 1.0), Vec2::new(800.0, 600.0)), Vec2::ZERO);
+        assert_eq!(ndc_to_screen(Vec2::ZERO, Vec2::new(800.0, 600.0)), Vec2::new(400.0, 300.0));
+    }
+
+    #[test]
+    fn test_off_screen_markers_pin_to_the_edge() {
+        let margin = Vec2::splat(0.1);
+        // Far off to the right, and behind on the left
+        let ScreenPlacement::Edge { position, direction } = place(Vec3::new(50.0, 0.0, -10.0), view_proj(), margin) else {
+            panic!("expected an edge marker");
+        };
+        assert!((position.x - 0.9).abs() < 1e-4 && direction.x > 0.99);
+
+        let ScreenPlacement::Edge { position, .. } = place(Vec3::new(-5.0, 0.0, 20.0), view_proj(), margin) else {
+            panic!("expected an edge marker");
+        };
+        assert!((position.x + 0.9).abs() < 1e-4);
+
+        // Straight behind points down
+        let behind = place(Vec3::new(0.0, 0.0, 20.0), view_proj(), margin);
+        assert_eq!(behind.position(), Vec2::new(0.0, -0.9));
+    }
+
+    #[test]
+    fn test_markers_filtered_and_sorted() {
+        let markers = [
+            CompassMarker::new(Vec3::new(0.0, 0.0, -40.0), "Goal", MarkerCategory::Objective),
+            CompassMarker::new(Vec3::new(0.0, 0.0, -200.0), "Camp", MarkerCategory::Waypoint),
+            CompassMarker::new(Vec3::new(0.0, 0.0, -1.0), "Here", MarkerCategory::Objective),
+        ];
+        let placed = screen_markers(&markers, Vec3::ZERO, view_proj(), Vec2::ZERO, &CompassFilter::default());
+        let labels: Vec<&str> = placed.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(labels, ["Camp", "Goal"]);
+
+        let filter = CompassFilter { waypoints: false, ..Default::default() };
+        assert_eq!(screen_markers(&markers, Vec3::ZERO, view_proj(), Vec2::ZERO, &filter).len(), 1);
+    }
+}
@@ -0,0 +1,369 @@
+//! Seats — benches and chairs to sit on, and walls to lean against
+//!
+//! The player and NPCs share the same seats: each seat has one or more
+//! spots, and a spot holds one occupant at a time. Resting snaps the
+//! occupant into an idle pose facing out from the seat, and the player
+//! regenerates health and mana faster while resting (and may let time pass
+//! quicker).
+
+use std::collections::HashMap;
+
+use glam::{Quat, Vec3};
+
+use crate::npc::NpcId;
+use crate::player::stats::CharacterStats;
+
+/// Game time multiplier while the player holds the fast-forward key seated
+pub const REST_TIME_SCALE: f32 = 10.0;
+/// Health regained per second while sitting, as a fraction of max HP
+pub const REST_HP_REGEN: f32 = 0.02;
+/// Extra mana regeneration while sitting, in multiples of the normal rate
+pub const REST_MANA_BONUS: f32 = 2.0;
+/// Leaning gives this share of the sitting bonus
+const LEAN_REGEN_SHARE: f32 = 0.5;
+
+/// Seat surface height
+const SEAT_HEIGHT: f32 = 0.45;
+/// How far apart the two spots on a bench are
+const BENCH_SPOT_SPACING: f32 = 0.9;
+/// How far in front of a seat its occupant steps when standing up
+const EXIT_DISTANCE: f32 = 0.75;
+
+/// Unique identifier for a seat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeatId(pub u64);
+
+/// How a resting body is posed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestPose {
+    Sitting,
+    Leaning,
+}
+
+impl RestPose {
+    /// How far the body sinks below standing height
+    pub fn body_drop(self) -> f32 {
+        match self {
+            Self::Sitting => SEAT_HEIGHT,
+            Self::Leaning => 0.0,
+        }
+    }
+
+    /// How far the body tips back, in radians
+    pub fn tilt(self) -> f32 {
+        match self {
+            Self::Sitting => 0.0,
+            Self::Leaning => 0.15,
+        }
+    }
+
+    /// How far the eye drops below standing height
+    pub fn eye_drop(self) -> f32 {
+        match self {
+            Self::Sitting => 0.5,
+            Self::Leaning => 0.05,
+        }
+    }
+}
+
+/// The kind of seat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeatKind {
+    /// Seats two side by side
+    Bench,
+    Chair,
+    /// A stretch of wall to lean against
+    Wall,
+}
+
+impl SeatKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bench => "Bench",
+            Self::Chair => "Chair",
+            Self::Wall => "Wall",
+        }
+    }
+
+    pub fn pose(self) -> RestPose {
+        match self {
+            Self::Bench | Self::Chair => RestPose::Sitting,
+            Self::Wall => RestPose::Leaning,
+        }
+    }
+
+    /// Interaction prompt
+    pub fn prompt(self) -> &'static str {
+        match self.pose() {
+            RestPose::Sitting => "Sit",
+            RestPose::Leaning => "Lean",
+        }
+    }
+
+    /// Number of occupants it holds
+    pub fn spots(self) -> usize {
+        match self {
+            Self::Bench => 2,
+            Self::Chair | Self::Wall => 1,
+        }
+    }
+
+    /// Size of the furniture: width across, height, depth along facing
+    pub fn footprint(self) -> Vec3 {
+        match self {
+            Self::Bench => Vec3::new(2.0, 1.2, 0.7),
+            Self::Chair => Vec3::new(0.7, 1.2, 0.7),
+            Self::Wall => Vec3::new(2.2, 1.9, 0.5),
+        }
+    }
+
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::Bench | Self::Chair => [0.45, 0.3, 0.18],
+            Self::Wall => [0.55, 0.53, 0.5],
+        }
+    }
+}
+
+/// Who is resting on a seat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Occupant {
+    Player,
+    Npc(NpcId),
+}
+
+/// A block of a seat's furniture, for drawing and collision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeatBlock {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub rotation: Quat,
+}
+
+/// A place to rest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Seat {
+    pub id: SeatId,
+    pub kind: SeatKind,
+    /// Middle of the seat on the ground (for a wall, where the leaner stands)
+    pub position: Vec3,
+    /// Horizontal direction an occupant faces
+    pub facing: Vec3,
+}
+
+impl Seat {
+    /// Direction to the occupant's right
+    fn right(&self) -> Vec3 {
+        self.facing.cross(Vec3::Y)
+    }
+
+    /// Ground position of a spot
+    pub fn spot(&self, index: usize) -> Vec3 {
+        match self.kind {
+            SeatKind::Bench => {
+                let side = if index == 0 { -0.5 } else { 0.5 };
+                self.position + self.right() * (side * BENCH_SPOT_SPACING)
+            }
+            SeatKind::Chair | SeatKind::Wall => self.position,
+        }
+    }
+
+    /// Where an occupant stands after getting up
+    pub fn exit_point(&self, spot: usize) -> Vec3 {
+        match self.kind.pose() {
+            RestPose::Sitting => self.spot(spot) + self.facing * EXIT_DISTANCE,
+            RestPose::Leaning => self.spot(spot),
+        }
+    }
+
+    /// Middle of the furniture (focus point and sensor of its interactable)
+    pub fn center(&self) -> Vec3 {
+        let height = self.kind.footprint().y * 0.5;
+        match self.kind {
+            SeatKind::Bench | SeatKind::Chair => self.position + Vec3::Y * height,
+            SeatKind::Wall => self.position - self.facing * 0.45 + Vec3::Y * height,
+        }
+    }
+
+    /// Rotation turning local -Z onto the facing direction
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_y((-self.facing.x).atan2(-self.facing.z))
+    }
+
+    /// Boxes the furniture is built from
+    pub fn blocks(&self) -> Vec<SeatBlock> {
+        let rotation = self.rotation();
+        let half_width = self.kind.footprint().x * 0.5;
+        let block = |offset: Vec3, half_extents: Vec3| SeatBlock {
+            center: self.position + rotation * offset,
+            half_extents,
+            rotation,
+        };
+        match self.kind {
+            SeatKind::Bench | SeatKind::Chair => vec![
+                // Seat, resting on the ground
+                block(
+                    Vec3::new(0.0, SEAT_HEIGHT * 0.5, 0.0),
+                    Vec3::new(half_width - 0.1, SEAT_HEIGHT * 0.5, 0.25),
+                ),
+                // Backrest
+                block(Vec3::new(0.0, 0.75, 0.27), Vec3::new(half_width - 0.1, 0.3, 0.04)),
+            ],
+            SeatKind::Wall => vec![block(Vec3::new(0.0, 0.9, 0.45), Vec3::new(half_width - 0.1, 0.9, 0.15))],
+        }
+    }
+}
+
+/// Every seat, and who is resting where
+#[derive(Debug, Default)]
+pub struct SeatRegistry {
+    seats: Vec<Seat>,
+    occupants: HashMap<Occupant, (SeatId, usize)>,
+    next_id: u64,
+}
+
+impl SeatRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place a seat facing `facing` (flattened to horizontal)
+    pub fn add(&mut self, kind: SeatKind, position: Vec3, facing: Vec3) -> SeatId {
+        let id = SeatId(self.next_id);
+        self.next_id += 1;
+        let facing = facing.with_y(0.0).try_normalize().unwrap_or(Vec3::NEG_Z);
+        self.seats.push(Seat { id, kind, position, facing });
+        id
+    }
+
+    /// Remove every seat and empty them
+    pub fn clear(&mut self) {
+        self.seats.clear();
+        self.occupants.clear();
+    }
+
+    pub fn get(&self, id: SeatId) -> Option<&Seat> {
+        self.seats.iter().find(|s| s.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Seat> {
+        self.seats.iter()
+    }
+
+    fn is_taken(&self, id: SeatId, spot: usize) -> bool {
+        self.occupants.values().any(|&taken| taken == (id, spot))
+    }
+
+    /// The free spot of a seat nearest `near`
+    fn free_spot(&self, seat: &Seat, near: Vec3) -> Option<usize> {
+        (0..seat.kind.spots())
+            .filter(|&spot| !self.is_taken(seat.id, spot))
+            .min_by(|&a, &b| seat.spot(a).distance(near).total_cmp(&seat.spot(b).distance(near)))
+    }
+
+    /// Take the free spot of a seat nearest `near`, giving up any other
+    /// seat the occupant held. Returns the spot, or None if the seat is full.
+    pub fn claim(&mut self, id: SeatId, occupant: Occupant, near: Vec3) -> Option<usize> {
+        if let Some(&(held, spot)) = self.occupants.get(&occupant) {
+            if held == id {
+                return Some(spot);
+            }
+        }
+        let seat = *self.get(id)?;
+        let spot = self.free_spot(&seat, near)?;
+        self.occupants.insert(occupant, (id, spot));
+        Some(spot)
+    }
+
+    /// Claim a free spot on the nearest seat within `radius` of `near`
+    pub fn claim_nearest(&mut self, occupant: Occupant, near: Vec3, radius: f32) -> Option<(SeatId, usize)> {
+        let id = self.nearest_free(near, radius)?;
+        let spot = self.claim(id, occupant, near)?;
+        Some((id, spot))
+    }
+
+    /// Nearest seat within `radius` of `near` with a free spot
+    pub fn nearest_free(&self, near: Vec3, radius: f32) -> Option<SeatId> {
+        self.seats
+            .iter()
+            .filter(|seat| seat.position.distance(near) <= radius && self.free_spot(seat, near).is_some())
+            .min_by(|a, b| a.position.distance(near).total_cmp(&b.position.distance(near)))
+            .map(|seat| seat.id)
+    }
+
+    /// Give up a seat. Returns the seat and spot that were held.
+    pub fn release(&mut self, occupant: Occupant) -> Option<(SeatId, usize)> {
+        self.occupants.remove(&occupant)
+    }
+
+    /// The seat and spot an occupant holds
+    pub fn seat_of(&self, occupant: Occupant) -> Option<(&Seat, usize)> {
+        let (id, spot) = *self.occupants.get(&occupant)?;
+        Some((self.get(id)?, spot))
+    }
+}
+
+/// Health and mana regained while resting for `delta` seconds
+pub fn rest_regen(stats: &mut CharacterStats, pose: RestPose, delta: f32) {
+    let share = match pose {
+        RestPose::Sitting => 1.0,
+        RestPose::Leaning => LEAN_REGEN_SHARE,
+    };
+    stats.heal(stats.max_hp * REST_HP_REGEN * share * delta);
+    stats.regenerate_mana(REST_MANA_BONUS * share * delta);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_seats_two_then_is_full() {
+        let mut seats = SeatRegistry::new();
+        let bench = seats.add(SeatKind::Bench, Vec3::ZERO, Vec3::Z);
+        let npc = Occupant::Npc(NpcId(7));
+
+        // The player takes the spot on their side; the NPC gets the other
+        let seat = *seats.get(bench).unwrap();
+        let player_spot = seats.claim(bench, Occupant::Player, seat.spot(1) + Vec3::Z).unwrap();
+        assert_eq!(player_spot, 1);
+        assert_eq!(seats.claim_nearest(npc, Vec3::ZERO, 5.0), Some((bench, 0)));
+        assert_eq!(seats.nearest_free(Vec3::ZERO, 5.0), None);
+        assert_eq!(seats.claim(bench, Occupant::Npc(NpcId(8)), Vec3::ZERO), None);
+
+        // Claiming again keeps the same spot; getting up frees it
+        assert_eq!(seats.claim(bench, Occupant::Player, Vec3::ZERO), Some(1));
+        assert_eq!(seats.release(Occupant::Player), Some((bench, 1)));
+        assert!(seats.seat_of(Occupant::Player).is_none());
+        assert_eq!(seats.nearest_free(Vec3::ZERO, 5.0), Some(bench));
+    }
+
+    #[test]
+    fn test_seat_layout_faces_out() {
+        let mut seats = SeatRegistry::new();
+        let id = seats.add(SeatKind::Chair, Vec3::new(2.0, 1.0, 0.0), Vec3::X);
+        let chair = *seats.get(id).unwrap();
+        assert!((chair.exit_point(0) - Vec3::new(2.0 + EXIT_DISTANCE, 1.0, 0.0)).length() < 1e-5);
+        // The backrest is behind the occupant
+        let back = chair.blocks()[1];
+        assert!(back.center.x < chair.position.x);
+
+        let id = seats.add(SeatKind::Wall, Vec3::ZERO, Vec3::NEG_Z);
+        let wall = *seats.get(id).unwrap();
+        assert_eq!(wall.exit_point(0), wall.position);
+        assert!(wall.blocks()[0].center.z > 0.3);
+        assert_eq!(wall.kind.prompt(), "Lean");
+    }
+
+    #[test]
+    fn test_resting_regenerates() {
+        let mut stats = CharacterStats { current_hp: 10.0, current_mana: 0.0, ..CharacterStats::default() };
+        rest_regen(&mut stats, RestPose::Sitting, 1.0);
+        let sat = (stats.current_hp, stats.current_mana);
+        assert!((sat.0 - (10.0 + stats.max_hp * REST_HP_REGEN)).abs() < 1e-4);
+
+        let mut leaning = CharacterStats { current_hp: 10.0, current_mana: 0.0, ..CharacterStats::default() };
+        rest_regen(&mut leaning, RestPose::Leaning, 1.0);
+        assert!(leaning.current_hp < sat.0 && leaning.current_mana < sat.1);
+    }
+}
@@ -0,0 +1,355 @@
+//! Shrines: wayside altars that grant temporary boons
+//!
+//! A few chunks hold a shrine built in the style of the era they're in.
+//! Praying at one offers a choice of boons (more XP, harder hits of the
+//! player's element, a quicker stride), each a long-lasting status effect
+//! paid for with gold or an offering of the era's herbs. The oldest shrines
+//! can also lift curses. A shrine that answered rests for a while before it
+//! answers again; the rest is tracked per shrine in the [`ShrineLedger`] and
+//! saved with the game.
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use infinite_core::{hash_seed, DetRng};
+use infinite_world::ChunkCoord;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::curse::CLEANSE_GOLD_COST;
+use crate::combat::damage::StatModifiers;
+use crate::combat::inventory::Inventory;
+use crate::combat::item::ItemId;
+use crate::combat::status::{StatusEffect, StatusEffectType};
+use crate::gathering::{self, ResourceKind};
+use crate::npc::combat::PlayerCombatState;
+use crate::npc::identity::Era;
+
+/// Chance a chunk holds a shrine
+pub const SHRINE_CHANCE: f32 = 0.12;
+/// Seconds a boon lasts
+pub const BOON_DURATION: f32 = 600.0;
+/// Seconds of play time a shrine rests after granting a boon
+pub const SHRINE_COOLDOWN: f64 = 1200.0;
+/// Extra XP while Enlightened (fraction of the XP earned)
+pub const XP_BOON_BONUS: f32 = 0.25;
+/// Flat damage added to attacks of the player's element while Attuned
+pub const ELEMENT_BOON_BONUS: f32 = 8.0;
+/// Movement speed added while Swift (fraction of base speed)
+pub const SPEED_BOON_BONUS: f32 = 0.2;
+/// Herbs of the shrine's era an offering takes
+pub const OFFERING_COUNT: u32 = 3;
+/// How far from the chunk's edge a shrine stands
+const CHUNK_MARGIN: f32 = 6.0;
+/// Keeps shrine seeds apart from other per-chunk seeds
+const SHRINE_SALT: u64 = 0x5417_4e3e_0000_0003;
+
+/// A boon a shrine can grant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Boon {
+    /// More XP from everything
+    Wisdom,
+    /// Harder hits of the player's element
+    Might,
+    /// Faster movement
+    Swiftness,
+}
+
+impl Boon {
+    pub const ALL: [Boon; 3] = [Self::Wisdom, Self::Might, Self::Swiftness];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wisdom => "Wisdom",
+            Self::Might => "Might",
+            Self::Swiftness => "Swiftness",
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Self::Wisdom => format!("+{:.0}% XP", XP_BOON_BONUS * 100.0),
+            Self::Might => format!("+{:.0} damage to attacks of your element", ELEMENT_BOON_BONUS),
+            Self::Swiftness => format!("+{:.0}% movement speed", SPEED_BOON_BONUS * 100.0),
+        }
+    }
+
+    /// The status effect the boon is carried as
+    pub fn effect_type(self) -> StatusEffectType {
+        match self {
+            Self::Wisdom => StatusEffectType::Enlightened,
+            Self::Might => StatusEffectType::Attuned,
+            Self::Swiftness => StatusEffectType::Swift,
+        }
+    }
+
+    /// The boon's status effect for a player of `player`'s affinity
+    pub fn status(self, player: &PlayerCombatState) -> StatusEffect {
+        let mut modifiers = StatModifiers::default();
+        match self {
+            Self::Wisdom => {}
+            Self::Might => modifiers.elemental_damage_bonus[player.stats.elemental_affinity.index()] = ELEMENT_BOON_BONUS,
+            Self::Swiftness => modifiers.speed = SPEED_BOON_BONUS,
+        }
+        StatusEffect::stat_modifier(self.effect_type(), BOON_DURATION, modifiers)
+    }
+
+    /// Gold a boon costs at `level`
+    pub fn gold_cost(self, level: u32) -> u64 {
+        40 + 10 * level as u64
+    }
+}
+
+/// How a boon is paid for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tribute {
+    Gold,
+    /// [`OFFERING_COUNT`] herbs of the shrine's era
+    Offering,
+}
+
+/// A shrine standing in the world
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shrine {
+    /// Stable id (also its location id)
+    pub id: u64,
+    pub chunk: ChunkCoord,
+    /// Era it was built in, which sets its look and offering
+    pub era: Era,
+    /// World position (y is left at 0 for the caller to place on the terrain)
+    pub position: Vec3,
+}
+
+impl Shrine {
+    pub fn name(&self) -> &'static str {
+        match self.era {
+            Era::Ancient => "Standing Stones",
+            Era::Medieval => "Wayside Shrine",
+            Era::Modern => "Memorial Chapel",
+            Era::Future => "Resonance Obelisk",
+        }
+    }
+
+    /// Rough size, for rendering and the focus outline
+    pub fn size(&self) -> Vec3 {
+        match self.era {
+            Era::Ancient => Vec3::new(1.6, 2.4, 0.6),
+            Era::Medieval => Vec3::new(1.2, 1.8, 1.0),
+            Era::Modern => Vec3::new(1.8, 2.0, 1.4),
+            Era::Future => Vec3::new(0.7, 3.0, 0.7),
+        }
+    }
+
+    pub fn color(&self) -> [f32; 3] {
+        match self.era {
+            Era::Ancient => [0.5, 0.5, 0.45],
+            Era::Medieval => [0.65, 0.55, 0.4],
+            Era::Modern => [0.8, 0.8, 0.85],
+            Era::Future => [0.3, 0.8, 0.9],
+        }
+    }
+
+    /// Whether the shrine can lift curses (only the old rites can)
+    pub fn cleanses(&self) -> bool {
+        matches!(self.era, Era::Ancient | Era::Medieval)
+    }
+
+    /// Item id of the herbs the shrine takes as an offering
+    pub fn offering_id(&self) -> ItemId {
+        gathering::create_material(ResourceKind::Herb, self.era, 1).id
+    }
+}
+
+/// The shrine of a chunk in `era`, if it has one
+pub fn shrine_in_chunk(coord: ChunkCoord, world_seed: u64, chunk_size: f32, era: Era) -> Option<Shrine> {
+    let id = shrine_seed(coord, world_seed, era);
+    let mut rng = DetRng::new(id);
+    if rng.gen::<f32>() >= SHRINE_CHANCE {
+        return None;
+    }
+    let origin = coord.world_origin(chunk_size);
+    let span = (chunk_size - 2.0 * CHUNK_MARGIN).max(0.0);
+    let x = origin.x + CHUNK_MARGIN + rng.gen::<f32>() * span;
+    let z = origin.z + CHUNK_MARGIN + rng.gen::<f32>() * span;
+    Some(Shrine { id, chunk: coord, era, position: Vec3::new(x, 0.0, z) })
+}
+
+fn shrine_seed(coord: ChunkCoord, world_seed: u64, era: Era) -> u64 {
+    hash_seed(world_seed ^ SHRINE_SALT, &[coord.x as i64 as u64, coord.z as i64 as u64, era as u64])
+}
+
+/// Why a prayer went unanswered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrayerError {
+    /// The shrine answers again after this many seconds
+    Resting(f64),
+    /// Not enough gold or herbs for the tribute
+    CantAfford,
+}
+
+/// When each shrine answers again, persisted in the save
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShrineLedger {
+    /// Play time each resting shrine is ready at
+    ready_at: BTreeMap<u64, f64>,
+}
+
+impl ShrineLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds until a shrine answers again, or None if it's ready
+    pub fn cooldown_left(&self, shrine: u64, now: f64) -> Option<f64> {
+        self.ready_at.get(&shrine).map(|ready| ready - now).filter(|left| *left > 0.0)
+    }
+
+    /// Pray for a boon: pay the tribute, grant the boon's status effect and
+    /// put the shrine to rest. Nothing is paid if the prayer fails.
+    pub fn pray(
+        &mut self,
+        shrine: &Shrine,
+        boon: Boon,
+        tribute: Tribute,
+        player: &mut PlayerCombatState,
+        now: f64,
+    ) -> Result<(), PrayerError> {
+        if let Some(left) = self.cooldown_left(shrine.id, now) {
+            return Err(PrayerError::Resting(left));
+        }
+        match tribute {
+            Tribute::Gold => {
+                let cost = boon.gold_cost(player.progression.level);
+                if player.gold < cost {
+                    return Err(PrayerError::CantAfford);
+                }
+                player.gold -= cost;
+            }
+            Tribute::Offering => {
+                if !take_offering(&mut player.inventory, shrine.offering_id()) {
+                    return Err(PrayerError::CantAfford);
+                }
+            }
+        }
+        let status = boon.status(player);
+        player.status_manager.apply(status);
+        self.ready_at.insert(shrine.id, now + SHRINE_COOLDOWN);
+        Ok(())
+    }
+
+    /// Lift the player's curses at a shrine that [`Shrine::cleanses`], for
+    /// [`CLEANSE_GOLD_COST`]. Returns how many curses were lifted; nothing
+    /// is paid and the shrine doesn't rest if there were none.
+    pub fn cleanse(&mut self, shrine: &Shrine, player: &mut PlayerCombatState, now: f64) -> Result<usize, PrayerError> {
+        if !shrine.cleanses() || player.curses.is_empty() {
+            return Ok(0);
+        }
+        if let Some(left) = self.cooldown_left(shrine.id, now) {
+            return Err(PrayerError::Resting(left));
+        }
+        if player.gold < CLEANSE_GOLD_COST {
+            return Err(PrayerError::CantAfford);
+        }
+        player.gold -= CLEANSE_GOLD_COST;
+        self.ready_at.insert(shrine.id, now + SHRINE_COOLDOWN);
+        Ok(player.curses.cleanse())
+    }
+}
+
+/// Herbs of an offering's kind carried
+pub fn count_offering(inventory: &Inventory, id: ItemId) -> u32 {
+    inventory.items.iter().filter(|item| item.id == id).map(|item| item.stack_count).sum()
+}
+
+/// Remove [`OFFERING_COUNT`] of the item from the inventory. Returns false,
+/// and removes nothing, if there aren't enough.
+fn take_offering(inventory: &mut Inventory, id: ItemId) -> bool {
+    if count_offering(inventory, id) < OFFERING_COUNT {
+        return false;
+    }
+    let mut left = OFFERING_COUNT;
+    while left > 0 {
+        let Some(index) = inventory.items.iter().position(|item| item.id == id) else {
+            break;
+        };
+        let take = inventory.items[index].stack_count.min(left);
+        inventory.remove_item_stack(index, take);
+        left -= take;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shrine() -> Shrine {
+        Shrine { id: 7, chunk: ChunkCoord::new(0, 0), era: Era::Medieval, position: Vec3::ZERO }
+    }
+
+    #[test]
+    fn test_shrines_are_stable_and_sparse() {
+        let found: Vec<Shrine> = (0..20)
+            .flat_map(|x| (0..20).map(move |z| ChunkCoord::new(x, z)))
+            .filter_map(|coord| shrine_in_chunk(coord, 42, 64.0, Era::Ancient))
+            .collect();
+        assert!(!found.is_empty() && found.len() < 400 / 4);
+        let first = found[0];
+        assert_eq!(shrine_in_chunk(first.chunk, 42, 64.0, Era::Ancient), Some(first));
+        let origin = first.chunk.world_origin(64.0);
+        assert!(first.position.x >= origin.x && first.position.x <= origin.x + 64.0);
+    }
+
+    #[test]
+    fn test_praying_costs_gold_and_rests_the_shrine() {
+        let mut ledger = ShrineLedger::new();
+        let mut player = PlayerCombatState::new();
+        let shrine = shrine();
+        assert_eq!(ledger.pray(&shrine, Boon::Swiftness, Tribute::Gold, &mut player, 0.0), Err(PrayerError::CantAfford));
+
+        player.gold = 500;
+        let base_speed = player.movement_speed_scale();
+        ledger.pray(&shrine, Boon::Swiftness, Tribute::Gold, &mut player, 0.0).unwrap();
+        assert_eq!(player.gold, 500 - Boon::Swiftness.gold_cost(1));
+        assert!(player.status_manager.has_effect(StatusEffectType::Swift));
+        assert!(player.movement_speed_scale() > base_speed);
+
+        assert_eq!(
+            ledger.pray(&shrine, Boon::Wisdom, Tribute::Gold, &mut player, 100.0),
+            Err(PrayerError::Resting(SHRINE_COOLDOWN - 100.0))
+        );
+        assert!(ledger.pray(&shrine, Boon::Wisdom, Tribute::Gold, &mut player, SHRINE_COOLDOWN).is_ok());
+    }
+
+    #[test]
+    fn test_old_shrines_lift_curses() {
+        use crate::combat::curse::CurseKind;
+
+        let mut ledger = ShrineLedger::new();
+        let mut player = PlayerCombatState::new();
+        player.gold = CLEANSE_GOLD_COST;
+        player.curses.afflict(CurseKind::Nightdread);
+        let obelisk = Shrine { era: Era::Future, ..shrine() };
+        assert_eq!(ledger.cleanse(&obelisk, &mut player, 0.0), Ok(0));
+        assert_eq!(ledger.cleanse(&shrine(), &mut player, 0.0), Ok(1));
+        assert!(player.curses.is_empty());
+        assert_eq!(player.gold, 0);
+    }
+
+    #[test]
+    fn test_offerings_take_era_herbs() {
+        let mut ledger = ShrineLedger::new();
+        let mut player = PlayerCombatState::new();
+        let shrine = shrine();
+        player.inventory.add_item(gathering::create_material(ResourceKind::Herb, Era::Ancient, 5)).unwrap();
+        assert_eq!(ledger.pray(&shrine, Boon::Wisdom, Tribute::Offering, &mut player, 0.0), Err(PrayerError::CantAfford));
+
+        player.inventory.add_item(gathering::create_material(ResourceKind::Herb, Era::Medieval, 4)).unwrap();
+        ledger.pray(&shrine, Boon::Wisdom, Tribute::Offering, &mut player, 0.0).unwrap();
+        assert_eq!(count_offering(&player.inventory, shrine.offering_id()), 1);
+
+        let before = player.progression.total_xp;
+        player.add_xp(100);
+        assert_eq!(player.progression.total_xp - before, 125);
+    }
+}
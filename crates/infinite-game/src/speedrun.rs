@@ -0,0 +1,461 @@
+//! Speedrun and practice timing
+//!
+//! A [`SpeedrunRun`] times play from the moment a world starts, splitting on
+//! a route of [`Split`]s the player chooses: reach a year, defeat a world
+//! boss, reach a level, defeat a number of enemies. Splits are taken in
+//! order and trigger on their own from game events, so the player never
+//! has to press a key. [`SpeedrunRecords`] keeps the personal best for each
+//! world seed, and a run can be written out as a LiveSplit `.lss` file.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::npc::world_boss::WorldBossKind;
+
+/// What completes a split
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SplitTrigger {
+    /// Arrive in this exact year
+    ReachYear(i64),
+    /// Take part in this world boss's defeat
+    DefeatWorldBoss(WorldBossKind),
+    /// Reach this character level or higher
+    ReachLevel(u32),
+    /// Defeat this many enemies since the run started
+    DefeatEnemies(u32),
+}
+
+impl SplitTrigger {
+    /// Short description, used as the split's name until the player renames it
+    pub fn describe(self) -> String {
+        match self {
+            Self::ReachYear(year) => format!("Reach {}", infinite_core::time::format_year(year)),
+            Self::DefeatWorldBoss(kind) => format!("Defeat the {}", kind.name()),
+            Self::ReachLevel(level) => format!("Reach level {}", level),
+            Self::DefeatEnemies(count) => format!("Defeat {} enemies", count),
+        }
+    }
+
+    fn met_by(self, event: RunEvent, kills: u32) -> bool {
+        match (self, event) {
+            (Self::ReachYear(year), RunEvent::YearReached(reached)) => year == reached,
+            (Self::DefeatWorldBoss(kind), RunEvent::WorldBossDefeated(defeated)) => kind == defeated,
+            (Self::ReachLevel(level), RunEvent::LevelReached(reached)) => reached >= level,
+            (Self::DefeatEnemies(count), _) => kills >= count,
+            _ => false,
+        }
+    }
+}
+
+/// One step of a route
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Split {
+    pub name: String,
+    pub trigger: SplitTrigger,
+}
+
+impl Split {
+    pub fn new(trigger: SplitTrigger) -> Self {
+        Self { name: trigger.describe(), trigger }
+    }
+}
+
+/// The route a new player starts with: out to the ancient past and its
+/// colossus, then across to the far future
+pub fn default_route() -> Vec<Split> {
+    vec![
+        Split::new(SplitTrigger::DefeatEnemies(5)),
+        Split::new(SplitTrigger::ReachYear(-5000)),
+        Split::new(SplitTrigger::ReachLevel(5)),
+        Split::new(SplitTrigger::DefeatWorldBoss(WorldBossKind::Colossus)),
+        Split::new(SplitTrigger::ReachYear(3500)),
+    ]
+}
+
+/// Something that happened in play that a split may be waiting on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunEvent {
+    YearReached(i64),
+    WorldBossDefeated(WorldBossKind),
+    LevelReached(u32),
+    EnemyDefeated,
+}
+
+/// A timed attempt at a route in one world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeedrunRun {
+    seed: u64,
+    splits: Vec<Split>,
+    /// Seconds since the run started
+    time: f64,
+    /// Time at which each completed split was taken, from the start
+    times: Vec<f64>,
+    kills: u32,
+}
+
+impl SpeedrunRun {
+    pub fn new(seed: u64, splits: Vec<Split>) -> Self {
+        Self { seed, splits, time: 0.0, times: Vec::new(), kills: 0 }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn splits(&self) -> &[Split] {
+        &self.splits
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// When split `index` was taken, from the start of the run
+    pub fn split_time(&self, index: usize) -> Option<f64> {
+        self.times.get(index).copied()
+    }
+
+    /// How long split `index` took on its own
+    pub fn segment_time(&self, index: usize) -> Option<f64> {
+        let end = self.split_time(index)?;
+        let start = if index == 0 { 0.0 } else { self.split_time(index - 1)? };
+        Some(end - start)
+    }
+
+    /// The split the run is waiting on
+    pub fn current(&self) -> Option<usize> {
+        (self.times.len() < self.splits.len()).then_some(self.times.len())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// Run the clock. It stops for good once the last split is taken.
+    pub fn update(&mut self, delta: f32) {
+        if !self.is_finished() {
+            self.time += delta as f64;
+        }
+    }
+
+    /// Take every split in a row that the event completes. Returns the last
+    /// one taken, if any.
+    pub fn observe(&mut self, event: RunEvent) -> Option<usize> {
+        if self.is_finished() {
+            return None;
+        }
+        if event == RunEvent::EnemyDefeated {
+            self.kills += 1;
+        }
+        let mut taken = None;
+        while let Some(index) = self.current() {
+            if !self.splits[index].trigger.met_by(event, self.kills) {
+                break;
+            }
+            self.times.push(self.time);
+            taken = Some(index);
+        }
+        taken
+    }
+
+    /// Seconds ahead of (negative) or behind (positive) the personal best at
+    /// split `index`
+    pub fn delta(&self, index: usize, best: &PersonalBest) -> Option<f64> {
+        Some(self.split_time(index)? - best.splits.get(index)?)
+    }
+
+    fn route(&self) -> Vec<SplitTrigger> {
+        self.splits.iter().map(|s| s.trigger).collect()
+    }
+
+    /// The run as a LiveSplit splits file. The personal best's times are the
+    /// comparison, or this run's if it is the only one.
+    pub fn to_lss(&self, records: &SpeedrunRecords) -> String {
+        let best = records.best(self);
+        let attempts = records.attempts(self.seed).max(1);
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<Run version=\"1.7.0\">\n");
+        out.push_str("  <GameIcon />\n");
+        out.push_str("  <GameName>Infinite</GameName>\n");
+        let _ = writeln!(out, "  <CategoryName>Seed {}</CategoryName>", self.seed);
+        out.push_str("  <Offset>00:00:00</Offset>\n");
+        let _ = writeln!(out, "  <AttemptCount>{}</AttemptCount>", attempts);
+        out.push_str("  <AttemptHistory />\n");
+        out.push_str("  <Segments>\n");
+        for (index, split) in self.splits.iter().enumerate() {
+            let pb = best.and_then(|b| b.splits.get(index).copied()).or(self.split_time(index));
+            let segment = best
+                .and_then(|b| b.best_segments.get(index).copied())
+                .into_iter()
+                .chain(self.segment_time(index))
+                .reduce(f64::min);
+            out.push_str("    <Segment>\n");
+            let _ = writeln!(out, "      <Name>{}</Name>", escape_xml(&split.name));
+            out.push_str("      <Icon />\n");
+            out.push_str("      <SplitTimes>\n");
+            match pb {
+                Some(time) => {
+                    out.push_str("        <SplitTime name=\"Personal Best\">\n");
+                    let _ = writeln!(out, "          <RealTime>{}</RealTime>", format_lss_time(time));
+                    out.push_str("        </SplitTime>\n");
+                }
+                None => out.push_str("        <SplitTime name=\"Personal Best\" />\n"),
+            }
+            out.push_str("      </SplitTimes>\n");
+            match segment {
+                Some(time) => {
+                    out.push_str("      <BestSegmentTime>\n");
+                    let _ = writeln!(out, "        <RealTime>{}</RealTime>", format_lss_time(time));
+                    out.push_str("      </BestSegmentTime>\n");
+                }
+                None => out.push_str("      <BestSegmentTime />\n"),
+            }
+            out.push_str("      <SegmentHistory />\n");
+            out.push_str("    </Segment>\n");
+        }
+        out.push_str("  </Segments>\n");
+        out.push_str("  <AutoSplitterSettings />\n");
+        out.push_str("</Run>\n");
+        out
+    }
+}
+
+/// Fastest finished run of a route in one world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalBest {
+    /// The triggers of the route, so a changed route isn't compared
+    pub route: Vec<SplitTrigger>,
+    /// Split times of the best run, from the start
+    pub splits: Vec<f64>,
+    /// Fastest each segment has ever been, across all finished runs
+    pub best_segments: Vec<f64>,
+}
+
+impl PersonalBest {
+    pub fn total(&self) -> f64 {
+        self.splits.last().copied().unwrap_or(0.0)
+    }
+}
+
+/// Attempts and personal best for one seed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeedRecord {
+    pub attempts: u32,
+    #[serde(default)]
+    pub best: Option<PersonalBest>,
+}
+
+/// Personal bests for every world seed that has been run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeedrunRecords {
+    #[serde(default)]
+    pub seeds: BTreeMap<u64, SeedRecord>,
+}
+
+impl SpeedrunRecords {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn attempts(&self, seed: u64) -> u32 {
+        self.seeds.get(&seed).map_or(0, |r| r.attempts)
+    }
+
+    /// Count a new attempt in this world
+    pub fn start_attempt(&mut self, seed: u64) {
+        self.seeds.entry(seed).or_default().attempts += 1;
+    }
+
+    /// Personal best for the run's seed, if it was set on the same route
+    pub fn best(&self, run: &SpeedrunRun) -> Option<&PersonalBest> {
+        let best = self.seeds.get(&run.seed)?.best.as_ref()?;
+        (best.route == run.route()).then_some(best)
+    }
+
+    /// Record a finished run. Returns true when it is a new personal best.
+    pub fn submit(&mut self, run: &SpeedrunRun) -> bool {
+        if !run.is_finished() || run.splits.is_empty() {
+            return false;
+        }
+        let route = run.route();
+        let segments: Vec<f64> = (0..run.splits.len()).filter_map(|i| run.segment_time(i)).collect();
+        let record = self.seeds.entry(run.seed).or_default();
+        match &mut record.best {
+            Some(best) if best.route == route => {
+                for (fastest, segment) in best.best_segments.iter_mut().zip(&segments) {
+                    *fastest = fastest.min(*segment);
+                }
+                if run.time >= best.total() {
+                    return false;
+                }
+                best.splits = run.times.clone();
+            }
+            _ => {
+                record.best = Some(PersonalBest { route, splits: run.times.clone(), best_segments: segments });
+            }
+        }
+        true
+    }
+}
+
+/// Run time as shown on the overlay: "4:05.2", or "1:04:05.2" past the hour
+pub fn format_run_time(seconds: f64) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).floor() as u64;
+    let (hours, minutes, secs, tenth) = (tenths / 36_000, tenths / 600 % 60, tenths / 10 % 60, tenths % 10);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{}", hours, minutes, secs, tenth)
+    } else {
+        format!("{}:{:02}.{}", minutes, secs, tenth)
+    }
+}
+
+/// Gain or loss against the personal best: "-1.3" ahead, "+0:12.0" behind
+pub fn format_delta(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { '-' } else { '+' };
+    let magnitude = seconds.abs();
+    if magnitude < 60.0 {
+        format!("{}{:.1}", sign, magnitude)
+    } else {
+        format!("{}{}", sign, format_run_time(magnitude))
+    }
+}
+
+/// Time in the form LiveSplit reads: hours, minutes, seconds and seven
+/// decimal places
+fn format_lss_time(seconds: f64) -> String {
+    let ticks = (seconds.max(0.0) * 10_000_000.0).round() as u64;
+    let whole = ticks / 10_000_000;
+    format!("{:02}:{:02}:{:02}.{:07}", whole / 3600, whole / 60 % 60, whole % 60, ticks % 10_000_000)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> Vec<Split> {
+        vec![
+            Split::new(SplitTrigger::DefeatEnemies(2)),
+            Split::new(SplitTrigger::ReachLevel(3)),
+            Split::new(SplitTrigger::ReachYear(-5000)),
+        ]
+    }
+
+    /// Play a run through, taking a second between each event
+    fn finish(run: &mut SpeedrunRun, events: &[RunEvent]) {
+        for event in events {
+            run.update(1.0);
+            run.observe(*event);
+        }
+    }
+
+    #[test]
+    fn test_splits_are_taken_in_order() {
+        let mut run = SpeedrunRun::new(7, route());
+        run.update(1.0);
+        assert_eq!(run.observe(RunEvent::YearReached(-5000)), None, "the year split isn't up yet");
+        assert_eq!(run.observe(RunEvent::EnemyDefeated), None);
+        run.update(1.0);
+        assert_eq!(run.observe(RunEvent::EnemyDefeated), Some(0));
+        assert_eq!(run.split_time(0), Some(2.0));
+        assert_eq!(run.observe(RunEvent::LevelReached(2)), None);
+        run.update(0.5);
+        assert_eq!(run.observe(RunEvent::LevelReached(4)), Some(1), "levelling past the target still splits");
+        run.update(1.0);
+        assert_eq!(run.observe(RunEvent::YearReached(-5000)), Some(2));
+        assert!(run.is_finished());
+        assert_eq!(run.segment_time(2), Some(1.0));
+
+        run.update(5.0);
+        assert_eq!(run.time(), 3.5, "the clock stops at the last split");
+    }
+
+    #[test]
+    fn test_one_event_can_take_several_splits() {
+        let mut run = SpeedrunRun::new(7, vec![
+            Split::new(SplitTrigger::ReachLevel(2)),
+            Split::new(SplitTrigger::ReachLevel(3)),
+            Split::new(SplitTrigger::DefeatWorldBoss(WorldBossKind::Wyrm)),
+        ]);
+        assert_eq!(run.observe(RunEvent::LevelReached(3)), Some(1));
+        assert_eq!(run.observe(RunEvent::WorldBossDefeated(WorldBossKind::Colossus)), None);
+        assert_eq!(run.observe(RunEvent::WorldBossDefeated(WorldBossKind::Wyrm)), Some(2));
+    }
+
+    #[test]
+    fn test_personal_best_per_seed_and_route() {
+        let events = [RunEvent::EnemyDefeated, RunEvent::EnemyDefeated, RunEvent::LevelReached(3), RunEvent::YearReached(-5000)];
+        let mut records = SpeedrunRecords::new();
+        records.start_attempt(7);
+        let mut first = SpeedrunRun::new(7, route());
+        finish(&mut first, &events);
+        assert!(records.submit(&first));
+        assert_eq!(records.best(&first).map(|b| b.total()), Some(4.0));
+
+        // Slower overall, but with a faster final segment
+        records.start_attempt(7);
+        let mut second = SpeedrunRun::new(7, route());
+        second.update(3.0);
+        finish(&mut second, &events[..3]);
+        second.update(0.5);
+        second.observe(RunEvent::YearReached(-5000));
+        assert!(!records.submit(&second));
+        let best = records.best(&second).unwrap();
+        assert_eq!(best.total(), 4.0);
+        assert_eq!(best.best_segments[2], 0.5);
+        assert_eq!(second.delta(2, best), Some(2.5));
+        assert_eq!(records.attempts(7), 2);
+
+        let other_seed = SpeedrunRun::new(8, route());
+        assert!(records.best(&other_seed).is_none());
+        let other_route = SpeedrunRun::new(7, route()[..2].to_vec());
+        assert!(records.best(&other_route).is_none(), "a different route has no comparison");
+
+        let unfinished = SpeedrunRun::new(7, route());
+        assert!(!records.submit(&unfinished));
+    }
+
+    #[test]
+    fn test_lss_export() {
+        let mut splits = route();
+        splits[0].name = "Warm <up> & go".to_string();
+        let mut run = SpeedrunRun::new(7, splits);
+        run.update(61.25);
+        run.observe(RunEvent::EnemyDefeated);
+        run.observe(RunEvent::EnemyDefeated);
+
+        let lss = run.to_lss(&SpeedrunRecords::new());
+        assert!(lss.starts_with("<?xml"));
+        assert!(lss.contains("<CategoryName>Seed 7</CategoryName>"));
+        assert!(lss.contains("<Name>Warm &lt;up&gt; &amp; go</Name>"));
+        assert!(lss.contains("<RealTime>00:01:01.2500000</RealTime>"));
+        assert_eq!(lss.matches("<Segment>").count(), 3);
+        assert_eq!(lss.matches("<SplitTime name=\"Personal Best\" />").count(), 2, "untaken splits are left empty");
+    }
+
+    #[test]
+    fn test_time_formats() {
+        assert_eq!(format_run_time(65.27), "1:05.2");
+        assert_eq!(format_run_time(3723.0), "1:02:03.0");
+        assert_eq!(format_delta(-1.26), "-1.3");
+        assert_eq!(format_delta(75.0), "+1:15.0");
+        assert_eq!(format_lss_time(3723.5), "01:02:03.5000000");
+    }
+}
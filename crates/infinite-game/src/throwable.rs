@@ -0,0 +1,450 @@
+//! Throwable items: bombs, smoke bombs and noise lures
+//!
+//! Throwables are consumables used from the hotbar. Holding the hotbar key
+//! aims and winds up the throw while an arc preview shows where it will
+//! land; releasing throws it. The preview and the thrown projectile step the
+//! same fixed-timestep ballistic integration against the same hit test, so
+//! the previewed arc is the path the projectile actually flies. On impact a
+//! bomb explodes, a smoke bomb leaves a cloud that blocks NPC sight lines,
+//! and a noise lure makes a racket nearby NPCs go to investigate.
+
+use glam::Vec3;
+use infinite_physics::{ForceField, PhysicsWorld};
+use rapier3d::prelude::{ColliderHandle, QueryFilter};
+
+use crate::combat::damage::StatModifiers;
+use crate::combat::element::Element;
+use crate::combat::item::{Item, ItemCategory, ItemId, ItemRarity};
+
+/// Item id of bombs
+pub const BOMB_ITEM_ID: ItemId = ItemId(3103);
+/// Item id of smoke bombs
+pub const SMOKE_BOMB_ITEM_ID: ItemId = ItemId(3104);
+/// Item id of noise lures
+pub const NOISE_LURE_ITEM_ID: ItemId = ItemId(3105);
+
+/// Downward acceleration on thrown items (matches the physics world)
+pub const THROW_GRAVITY: f32 = 9.81;
+
+/// Seconds of wind-up for a full-strength throw
+pub const FULL_CHARGE_TIME: f32 = 1.0;
+
+/// Radius of a thrown item, for hitting the world
+pub const THROWABLE_RADIUS: f32 = 0.1;
+
+/// Integration step shared by the preview and the projectile
+const SIM_STEP: f32 = 1.0 / 60.0;
+/// Projectiles still flying after this long are dropped
+const MAX_FLIGHT_TIME: f32 = 4.0;
+/// Throw speed (m/s) of a tap and of a full wind-up
+const MIN_THROW_SPEED: f32 = 8.0;
+const MAX_THROW_SPEED: f32 = 20.0;
+/// Upward bias added to the aim so level throws still arc
+const THROW_LIFT: f32 = 0.3;
+/// Seconds a smoke cloud lingers
+const SMOKE_DURATION: f32 = 12.0;
+/// Speed (m/s) a bomb throws things at its centre
+const BOMB_BLAST_SPEED: f32 = 12.0;
+
+/// The kind of throwable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThrowableKind {
+    /// Explodes on impact, hurting everything in the blast
+    Bomb,
+    /// Leaves a cloud of smoke that enemies can't see through
+    SmokeBomb,
+    /// Clatters loudly, drawing enemies to where it lands
+    NoiseLure,
+}
+
+impl ThrowableKind {
+    pub const ALL: [ThrowableKind; 3] = [Self::Bomb, Self::SmokeBomb, Self::NoiseLure];
+
+    /// The throwable an item is, if any
+    pub fn from_item_id(id: ItemId) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.item_id() == id)
+    }
+
+    pub fn item_id(self) -> ItemId {
+        match self {
+            Self::Bomb => BOMB_ITEM_ID,
+            Self::SmokeBomb => SMOKE_BOMB_ITEM_ID,
+            Self::NoiseLure => NOISE_LURE_ITEM_ID,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bomb => "Bomb",
+            Self::SmokeBomb => "Smoke Bomb",
+            Self::NoiseLure => "Noise Lure",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Bomb => "Explodes where it lands, burning everything nearby. Mind the blast.",
+            Self::SmokeBomb => "Bursts into a thick cloud that enemies can't see through.",
+            Self::NoiseLure => "A rattling trinket. Enemies that hear it land go to investigate.",
+        }
+    }
+
+    /// Radius of the effect on impact: blast, smoke cloud, or hearing range
+    pub fn radius(self) -> f32 {
+        match self {
+            Self::Bomb => 4.0,
+            Self::SmokeBomb => 5.0,
+            Self::NoiseLure => 18.0,
+        }
+    }
+
+    /// Blast damage at the centre (only bombs deal damage)
+    pub fn damage(self) -> f32 {
+        match self {
+            Self::Bomb => 45.0,
+            Self::SmokeBomb | Self::NoiseLure => 0.0,
+        }
+    }
+
+    pub fn element(self) -> Element {
+        match self {
+            Self::Bomb => Element::Fire,
+            Self::SmokeBomb | Self::NoiseLure => Element::Physical,
+        }
+    }
+
+    /// Damage at `distance` from the impact, falling off to half at the edge
+    pub fn blast_damage(self, distance: f32) -> f32 {
+        let radius = self.radius();
+        if distance > radius {
+            return 0.0;
+        }
+        self.damage() * (1.0 - 0.5 * distance / radius)
+    }
+
+    /// Shove the impact gives loose props and anyone near it (only bombs
+    /// blow things around)
+    pub fn blast_field(self, position: Vec3) -> Option<ForceField> {
+        match self {
+            Self::Bomb => Some(ForceField::explosion(position, self.radius(), BOMB_BLAST_SPEED)),
+            Self::SmokeBomb | Self::NoiseLure => None,
+        }
+    }
+
+    /// Render color (RGB)
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::Bomb => [0.15, 0.15, 0.18],
+            Self::SmokeBomb => [0.6, 0.6, 0.65],
+            Self::NoiseLure => [0.85, 0.7, 0.2],
+        }
+    }
+}
+
+/// Launch velocity for a throw along `aim`. `charge` (0 - 1) is how far the
+/// throw was wound up.
+pub fn throw_velocity(aim: Vec3, charge: f32) -> Vec3 {
+    let speed = MIN_THROW_SPEED + (MAX_THROW_SPEED - MIN_THROW_SPEED) * charge.clamp(0.0, 1.0);
+    (aim.normalize_or_zero() + Vec3::Y * THROW_LIFT).normalize_or_zero() * speed
+}
+
+/// One integration step (semi-implicit Euler)
+fn integrate(position: Vec3, velocity: Vec3) -> (Vec3, Vec3) {
+    let velocity = velocity - Vec3::Y * THROW_GRAVITY * SIM_STEP;
+    (position + velocity * SIM_STEP, velocity)
+}
+
+/// Predicted flight path of a throw
+#[derive(Debug, Clone, Default)]
+pub struct ArcPreview {
+    /// Points along the arc, one per integration step
+    pub points: Vec<Vec3>,
+    /// Where it lands, if it lands within the flight time
+    pub impact: Option<Vec3>,
+}
+
+/// Predict a throw from `origin` at `velocity`. `hit_test(from, to)` returns
+/// where the segment between two steps first hits the world, if it does.
+pub fn predict_arc(
+    origin: Vec3,
+    velocity: Vec3,
+    mut hit_test: impl FnMut(Vec3, Vec3) -> Option<Vec3>,
+) -> ArcPreview {
+    let mut preview = ArcPreview { points: vec![origin], impact: None };
+    let (mut position, mut velocity) = (origin, velocity);
+    let mut time = 0.0;
+    while time < MAX_FLIGHT_TIME {
+        let (next, next_velocity) = integrate(position, velocity);
+        if let Some(hit) = hit_test(position, next) {
+            preview.points.push(hit);
+            preview.impact = Some(hit);
+            break;
+        }
+        preview.points.push(next);
+        (position, velocity) = (next, next_velocity);
+        time += SIM_STEP;
+    }
+    preview
+}
+
+/// Where a thrown item moving from `from` to `to` first touches a physics
+/// collider, sweeping its whole size rather than a point. `exclude` should
+/// be the thrower's own collider.
+pub fn segment_hit(
+    physics: &PhysicsWorld,
+    from: Vec3,
+    to: Vec3,
+    exclude: Option<ColliderHandle>,
+) -> Option<Vec3> {
+    let step = to - from;
+    let length = step.length();
+    if length < 1e-4 {
+        return None;
+    }
+    let mut filter = QueryFilter::default();
+    if let Some(handle) = exclude {
+        filter = filter.exclude_collider(handle);
+    }
+    physics.cast_sphere(from, THROWABLE_RADIUS, step / length, length, filter).map(|hit| hit.position)
+}
+
+/// A throwable in flight
+#[derive(Debug, Clone, Copy)]
+pub struct ThrownProjectile {
+    pub kind: ThrowableKind,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// Seconds in flight
+    age: f32,
+    /// Frame time not yet integrated
+    pending: f32,
+}
+
+/// A throwable landing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Impact {
+    pub kind: ThrowableKind,
+    pub position: Vec3,
+}
+
+/// A lingering cloud of smoke
+#[derive(Debug, Clone, Copy)]
+pub struct SmokeCloud {
+    pub position: Vec3,
+    pub radius: f32,
+    /// Seconds until it clears
+    pub remaining: f32,
+}
+
+impl SmokeCloud {
+    pub fn contains(&self, point: Vec3) -> bool {
+        (point - self.position).length() <= self.radius
+    }
+
+    /// Whether the sight line from `from` to `to` passes through the cloud
+    pub fn blocks(&self, from: Vec3, to: Vec3) -> bool {
+        let segment = to - from;
+        let length_sq = segment.length_squared();
+        let t = if length_sq > 0.0 {
+            ((self.position - from).dot(segment) / length_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.contains(from + segment * t)
+    }
+}
+
+/// Thrown projectiles in flight and the smoke they leave behind
+#[derive(Debug, Clone, Default)]
+pub struct Throwables {
+    projectiles: Vec<ThrownProjectile>,
+    smoke: Vec<SmokeCloud>,
+}
+
+impl Throwables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Throw a `kind` from `origin` at `velocity` (see [`throw_velocity`])
+    pub fn throw(&mut self, kind: ThrowableKind, origin: Vec3, velocity: Vec3) {
+        self.projectiles.push(ThrownProjectile { kind, position: origin, velocity, age: 0.0, pending: 0.0 });
+    }
+
+    /// Fly projectiles and thin out smoke. Returns this frame's impacts;
+    /// smoke bombs also leave a cloud where they land.
+    pub fn update(&mut self, delta: f32, mut hit_test: impl FnMut(Vec3, Vec3) -> Option<Vec3>) -> Vec<Impact> {
+        for cloud in &mut self.smoke {
+            cloud.remaining -= delta;
+        }
+        self.smoke.retain(|c| c.remaining > 0.0);
+
+        let mut impacts = Vec::new();
+        self.projectiles.retain_mut(|projectile| {
+            projectile.pending += delta;
+            while projectile.pending >= SIM_STEP {
+                projectile.pending -= SIM_STEP;
+                let (next, velocity) = integrate(projectile.position, projectile.velocity);
+                if let Some(hit) = hit_test(projectile.position, next) {
+                    impacts.push(Impact { kind: projectile.kind, position: hit });
+                    return false;
+                }
+                projectile.position = next;
+                projectile.velocity = velocity;
+                projectile.age += SIM_STEP;
+                if projectile.age >= MAX_FLIGHT_TIME {
+                    return false;
+                }
+            }
+            true
+        });
+
+        for impact in impacts.iter().filter(|i| i.kind == ThrowableKind::SmokeBomb) {
+            self.smoke.push(SmokeCloud {
+                position: impact.position,
+                radius: impact.kind.radius(),
+                remaining: SMOKE_DURATION,
+            });
+        }
+        impacts
+    }
+
+    pub fn projectiles(&self) -> impl Iterator<Item = &ThrownProjectile> {
+        self.projectiles.iter()
+    }
+
+    pub fn smoke_clouds(&self) -> impl Iterator<Item = &SmokeCloud> {
+        self.smoke.iter()
+    }
+
+    /// Whether smoke blocks the sight line from `from` to `to`
+    pub fn blocks_sight(&self, from: Vec3, to: Vec3) -> bool {
+        self.smoke.iter().any(|cloud| cloud.blocks(from, to))
+    }
+
+    pub fn clear(&mut self) {
+        self.projectiles.clear();
+        self.smoke.clear();
+    }
+}
+
+/// A stack of throwables
+pub fn create_throwables(kind: ThrowableKind, count: u32) -> Item {
+    Item {
+        id: kind.item_id(),
+        name: kind.name().to_string(),
+        description: kind.description().to_string(),
+        category: ItemCategory::Consumable,
+        rarity: ItemRarity::Common,
+        stat_modifiers: StatModifiers::default(),
+        element: kind.element(),
+        weapon_data: None,
+        shield_data: None,
+        armor_data: None,
+        treasure_map: None,
+        gem_sockets: vec![],
+        required_level: 1,
+        item_level: 1,
+        stack_count: count,
+        max_stack: 10,
+        origin_year: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat ground at y = 0
+    fn ground(from: Vec3, to: Vec3) -> Option<Vec3> {
+        if to.y > 0.0 {
+            return None;
+        }
+        let t = from.y / (from.y - to.y);
+        Some(from.lerp(to, t))
+    }
+
+    #[test]
+    fn test_arc_lands_ahead_and_peaks_above_origin() {
+        let origin = Vec3::new(0.0, 1.5, 0.0);
+        let preview = predict_arc(origin, throw_velocity(Vec3::NEG_Z, 0.5), ground);
+        let impact = preview.impact.expect("throw should land");
+        assert!(impact.z < -5.0);
+        assert!(impact.y.abs() < 1e-4);
+        assert!(preview.points.iter().any(|p| p.y > origin.y + 0.5));
+        assert_eq!(preview.points.last(), Some(&impact));
+    }
+
+    #[test]
+    fn test_projectile_follows_the_preview() {
+        let origin = Vec3::new(2.0, 1.5, 0.0);
+        let velocity = throw_velocity(Vec3::new(1.0, 0.2, 1.0), 1.0);
+        let preview = predict_arc(origin, velocity, ground);
+
+        let mut throwables = Throwables::new();
+        throwables.throw(ThrowableKind::Bomb, origin, velocity);
+        // Uneven frame times still integrate the same fixed steps
+        let mut impacts = Vec::new();
+        for frame in 0..600 {
+            let delta = if frame % 3 == 0 { 0.031 } else { 0.009 };
+            impacts.extend(throwables.update(delta, ground));
+            if !impacts.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(impacts.len(), 1);
+        assert!((impacts[0].position - preview.impact.unwrap()).length() < 1e-3);
+        assert_eq!(throwables.projectiles().count(), 0);
+    }
+
+    #[test]
+    fn test_smoke_blocks_sight_until_it_clears() {
+        let mut throwables = Throwables::new();
+        throwables.throw(ThrowableKind::SmokeBomb, Vec3::new(0.0, 0.5, 0.0), Vec3::ZERO);
+        let impacts = throwables.update(1.0, ground);
+        assert_eq!(impacts[0].kind, ThrowableKind::SmokeBomb);
+
+        let cloud = impacts[0].position;
+        let (left, right) = (cloud - Vec3::X * 10.0, cloud + Vec3::X * 10.0);
+        assert!(throwables.blocks_sight(left + Vec3::Y, right + Vec3::Y));
+        assert!(!throwables.blocks_sight(left + Vec3::Z * 8.0, right + Vec3::Z * 8.0));
+
+        throwables.update(SMOKE_DURATION, ground);
+        assert!(!throwables.blocks_sight(left, right));
+    }
+
+    #[test]
+    fn test_blast_falloff_and_item_kinds() {
+        let bomb = ThrowableKind::Bomb;
+        assert_eq!(bomb.blast_damage(0.0), bomb.damage());
+        assert_eq!(bomb.blast_damage(bomb.radius()), bomb.damage() * 0.5);
+        assert_eq!(bomb.blast_damage(bomb.radius() + 0.1), 0.0);
+        assert_eq!(ThrowableKind::NoiseLure.blast_damage(0.0), 0.0);
+
+        for kind in ThrowableKind::ALL {
+            let item = create_throwables(kind, 2);
+            assert_eq!(ThrowableKind::from_item_id(item.id), Some(kind));
+        }
+        assert_eq!(ThrowableKind::from_item_id(ItemId(3000)), None);
+    }
+
+    #[test]
+    fn test_wind_up_throws_further() {
+        let origin = Vec3::Y * 1.5;
+        let tap = predict_arc(origin, throw_velocity(Vec3::NEG_Z, 0.0), ground).impact.unwrap();
+        let full = predict_arc(origin, throw_velocity(Vec3::NEG_Z, 1.0), ground).impact.unwrap();
+        assert!(full.z < tap.z * 2.0);
+    }
+
+    #[test]
+    fn test_thrown_items_clip_edges() {
+        let mut physics = PhysicsWorld::new();
+        // A post whose edge is 5cm off the throw line
+        physics.create_static_box(Vec3::splat(0.25), Vec3::new(0.3, 1.0, -3.0));
+        physics.update_query_pipeline();
+        let from = Vec3::new(0.0, 1.0, 0.0);
+        let hit = segment_hit(&physics, from, Vec3::new(0.0, 1.0, -5.0), None).expect("the item's edge catches the post");
+        assert!(hit.z > -3.0 + 0.25 - 0.01 && hit.z < -2.5);
+        assert!(segment_hit(&physics, from, Vec3::new(-0.4, 1.0, -5.0), None).is_none());
+    }
+}
@@ -0,0 +1,413 @@
+//! Traps and hazard volumes
+//!
+//! Traps are hidden trigger volumes: pressure plates that loose a dart
+//! volley, spike pits, poison gas vents, hex glyphs that curse, and in
+//! far-future eras laser grids.
+//! Anything that walks into an armed trap sets it off — the player, or the
+//! enemies chasing them, so traps can be used against pursuers. The player
+//! spots hidden traps by getting close (crouching and daylight help), and
+//! spotted traps can be disarmed, at the risk of setting them off.
+
+use glam::Vec3;
+
+use crate::combat::curse::CurseKind;
+use crate::combat::element::Element;
+use crate::combat::status::{StatusEffect, StatusEffectType};
+use crate::npc::NpcId;
+
+/// Years past the present from which the future's laser grids appear
+pub const LASER_ERA_OFFSET: i64 = 500;
+
+/// Awareness needed to spot a hidden trap
+const DETECTION_THRESHOLD: f32 = 1.0;
+
+/// Actors more than this far above or below a trap are not inside it
+const VOLUME_HALF_HEIGHT: f32 = 2.0;
+
+/// Unique identifier for a trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrapId(pub u64);
+
+/// The kind of trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapKind {
+    /// Fires a dart volley at whoever steps on it
+    PressurePlate,
+    SpikePit,
+    /// Vents a cloud of poison gas
+    PoisonVent,
+    /// Future-era grid of burning beams
+    LaserGrid,
+    /// Carved sigil that curses whoever steps on it
+    HexGlyph,
+}
+
+impl TrapKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PressurePlate => "Pressure Plate",
+            Self::SpikePit => "Spike Pit",
+            Self::PoisonVent => "Poison Vent",
+            Self::LaserGrid => "Laser Grid",
+            Self::HexGlyph => "Hex Glyph",
+        }
+    }
+
+    /// Trap kinds that can be placed in `year`
+    pub fn for_era(year: i64, present_year: i64) -> &'static [TrapKind] {
+        if year >= present_year + LASER_ERA_OFFSET {
+            &[Self::LaserGrid, Self::PressurePlate]
+        } else {
+            &[Self::PressurePlate, Self::SpikePit, Self::PoisonVent, Self::HexGlyph]
+        }
+    }
+
+    /// Radius of the trigger volume
+    pub fn radius(self) -> f32 {
+        match self {
+            Self::PressurePlate => 1.0,
+            Self::SpikePit => 1.2,
+            Self::PoisonVent => 2.5,
+            Self::LaserGrid => 1.8,
+            Self::HexGlyph => 1.0,
+        }
+    }
+
+    pub fn damage(self) -> f32 {
+        match self {
+            Self::PressurePlate => 10.0,
+            Self::SpikePit => 25.0,
+            Self::PoisonVent => 5.0,
+            Self::LaserGrid => 18.0,
+            Self::HexGlyph => 4.0,
+        }
+    }
+
+    pub fn element(self) -> Element {
+        match self {
+            Self::PressurePlate | Self::SpikePit | Self::PoisonVent => Element::Physical,
+            Self::LaserGrid => Element::Fire,
+            Self::HexGlyph => Element::Void,
+        }
+    }
+
+    /// Status effect applied to the player, with its duration in seconds
+    pub fn status(self) -> Option<(StatusEffectType, f32)> {
+        match self {
+            Self::PressurePlate => Some((StatusEffectType::Stunned, 1.0)),
+            Self::SpikePit => Some((StatusEffectType::Slowed, 3.0)),
+            Self::PoisonVent => Some((StatusEffectType::Poisoned, 6.0)),
+            Self::LaserGrid => Some((StatusEffectType::Burning, 3.0)),
+            Self::HexGlyph => Some((StatusEffectType::Weakened, 5.0)),
+        }
+    }
+
+    /// Curse laid on the player
+    pub fn curse(self) -> Option<CurseKind> {
+        match self {
+            Self::HexGlyph => Some(CurseKind::Nightdread),
+            _ => None,
+        }
+    }
+
+    /// Seconds before the trap can go off again
+    pub fn rearm_time(self) -> f32 {
+        match self {
+            Self::PressurePlate => 3.0,
+            Self::SpikePit => 2.0,
+            Self::PoisonVent => 4.0,
+            Self::LaserGrid => 1.0,
+            Self::HexGlyph => 6.0,
+        }
+    }
+
+    /// Distance from which a hidden trap can be spotted
+    pub fn detect_range(self) -> f32 {
+        match self {
+            Self::PressurePlate => 4.0,
+            Self::SpikePit => 6.0,
+            Self::PoisonVent => 7.0,
+            Self::LaserGrid => 8.0,
+            Self::HexGlyph => 5.0,
+        }
+    }
+
+    /// Dexterity at which disarming succeeds about half the time
+    pub fn disarm_difficulty(self) -> f32 {
+        match self {
+            Self::PressurePlate => 3.0,
+            Self::SpikePit => 5.0,
+            Self::PoisonVent => 7.0,
+            Self::LaserGrid => 10.0,
+            Self::HexGlyph => 8.0,
+        }
+    }
+
+    /// Marker color as [r, g, b] floats
+    pub fn color(self) -> [f32; 3] {
+        match self {
+            Self::PressurePlate => [0.55, 0.5, 0.4],
+            Self::SpikePit => [0.35, 0.3, 0.3],
+            Self::PoisonVent => [0.4, 0.75, 0.25],
+            Self::LaserGrid => [1.0, 0.2, 0.2],
+            Self::HexGlyph => [0.45, 0.15, 0.6],
+        }
+    }
+}
+
+/// A trap placed in the world
+#[derive(Debug, Clone)]
+pub struct Trap {
+    pub id: TrapId,
+    pub kind: TrapKind,
+    pub position: Vec3,
+    /// Disarmed traps never go off
+    pub armed: bool,
+    /// Whether the player has spotted it
+    pub detected: bool,
+    /// Player awareness of the hidden trap (spotted at 1.0)
+    awareness: f32,
+    /// Seconds until it can go off again
+    cooldown: f32,
+}
+
+impl Trap {
+    /// Whether a point is inside the trigger volume
+    pub fn contains(&self, point: Vec3) -> bool {
+        let offset = point - self.position;
+        offset.y.abs() <= VOLUME_HALF_HEIGHT && offset.with_y(0.0).length() <= self.kind.radius()
+    }
+}
+
+/// Who set off a trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapTarget {
+    Player,
+    Npc(NpcId),
+}
+
+/// A trap going off on one target
+#[derive(Debug, Clone)]
+pub struct TrapTrigger {
+    pub trap: TrapId,
+    pub kind: TrapKind,
+    pub target: TrapTarget,
+    pub damage: f32,
+    pub element: Element,
+    pub status: Option<StatusEffect>,
+    /// Curse laid if the target is the player
+    pub curse: Option<CurseKind>,
+}
+
+impl TrapTrigger {
+    fn new(trap: &Trap, target: TrapTarget) -> Self {
+        Self {
+            trap: trap.id,
+            kind: trap.kind,
+            target,
+            damage: trap.kind.damage(),
+            element: trap.kind.element(),
+            status: trap.kind.status().map(|(effect, duration)| StatusEffect::elemental_proc(effect, duration)),
+            curse: trap.kind.curse(),
+        }
+    }
+}
+
+/// Result of trying to disarm a trap
+#[derive(Debug, Clone)]
+pub enum DisarmOutcome {
+    Disarmed,
+    /// The attempt failed and the trap went off on the player
+    Triggered(TrapTrigger),
+}
+
+/// All traps in the loaded world
+#[derive(Debug, Clone, Default)]
+pub struct TrapField {
+    traps: Vec<Trap>,
+    next_id: u64,
+}
+
+impl TrapField {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place an armed, hidden trap and return its ID
+    pub fn add(&mut self, kind: TrapKind, position: Vec3) -> TrapId {
+        let id = TrapId(self.next_id);
+        self.next_id += 1;
+        self.traps.push(Trap {
+            id,
+            kind,
+            position,
+            armed: true,
+            detected: false,
+            awareness: 0.0,
+            cooldown: 0.0,
+        });
+        id
+    }
+
+    pub fn clear(&mut self) {
+        self.traps.clear();
+    }
+
+    /// Keep only traps matching the predicate
+    pub fn retain(&mut self, f: impl Fn(&Trap) -> bool) {
+        self.traps.retain(f);
+    }
+
+    pub fn get(&self, id: TrapId) -> Option<&Trap> {
+        self.traps.iter().find(|t| t.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Trap> {
+        self.traps.iter()
+    }
+
+    /// Spotted traps that are still armed (can be disarmed)
+    pub fn detected_armed(&self) -> impl Iterator<Item = &Trap> {
+        self.traps.iter().filter(|t| t.detected && t.armed)
+    }
+
+    /// Set off armed traps that anyone is standing in. `npcs` are the NPCs
+    /// traps can catch (e.g. hostile pursuers). A trap that goes off hits
+    /// everyone inside it and is revealed.
+    pub fn update(
+        &mut self,
+        delta: f32,
+        player_pos: Vec3,
+        npcs: impl IntoIterator<Item = (NpcId, Vec3)>,
+    ) -> Vec<TrapTrigger> {
+        let npcs: Vec<(NpcId, Vec3)> = npcs.into_iter().collect();
+        let mut triggers = Vec::new();
+        for trap in &mut self.traps {
+            trap.cooldown = (trap.cooldown - delta).max(0.0);
+            if !trap.armed || trap.cooldown > 0.0 {
+                continue;
+            }
+            let mut targets: Vec<TrapTarget> = npcs.iter()
+                .filter(|(_, pos)| trap.contains(*pos))
+                .map(|(id, _)| TrapTarget::Npc(*id))
+                .collect();
+            if trap.contains(player_pos) {
+                targets.push(TrapTarget::Player);
+            }
+            if targets.is_empty() {
+                continue;
+            }
+            trap.cooldown = trap.kind.rearm_time();
+            trap.detected = true;
+            triggers.extend(targets.into_iter().map(|target| TrapTrigger::new(trap, target)));
+        }
+        triggers
+    }
+
+    /// Build the player's awareness of nearby hidden traps. `perception`
+    /// scales how quickly they are spotted (1.0 baseline). Returns traps
+    /// spotted this update.
+    pub fn update_detection(&mut self, delta: f32, player_pos: Vec3, perception: f32) -> Vec<TrapId> {
+        let mut spotted = Vec::new();
+        for trap in self.traps.iter_mut().filter(|t| t.armed && !t.detected) {
+            let range = trap.kind.detect_range();
+            let distance = (trap.position - player_pos).length();
+            if distance > range {
+                continue;
+            }
+            trap.awareness += perception * (1.0 - distance / range) * 2.0 * delta;
+            if trap.awareness >= DETECTION_THRESHOLD {
+                trap.detected = true;
+                spotted.push(trap.id);
+            }
+        }
+        spotted
+    }
+
+    /// Chance that disarming a trap succeeds at the given dexterity
+    pub fn disarm_chance(kind: TrapKind, dexterity: f32) -> f32 {
+        (0.5 + (dexterity - kind.disarm_difficulty()) * 0.06).clamp(0.1, 0.95)
+    }
+
+    /// Try to disarm a spotted trap. `roll` is uniform in 0..1; rolls under
+    /// the disarm chance succeed. Returns None if it is not an armed trap.
+    pub fn disarm(&mut self, id: TrapId, dexterity: f32, roll: f32) -> Option<DisarmOutcome> {
+        let trap = self.traps.iter_mut().find(|t| t.id == id && t.armed)?;
+        if roll < Self::disarm_chance(trap.kind, dexterity) {
+            trap.armed = false;
+            return Some(DisarmOutcome::Disarmed);
+        }
+        trap.cooldown = trap.kind.rearm_time();
+        Some(DisarmOutcome::Triggered(TrapTrigger::new(trap, TrapTarget::Player)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trap_hits_everyone_inside_then_rearms() {
+        let mut field = TrapField::new();
+        let id = field.add(TrapKind::PoisonVent, Vec3::ZERO);
+        let enemy = NpcId(7);
+
+        let triggers = field.update(0.1, Vec3::new(1.0, 0.0, 0.0), [(enemy, Vec3::new(-1.0, 0.5, 0.0))]);
+        let targets: Vec<TrapTarget> = triggers.iter().map(|t| t.target).collect();
+        assert_eq!(targets, vec![TrapTarget::Npc(enemy), TrapTarget::Player]);
+        assert!(triggers.iter().all(|t| t.status.as_ref().map(|s| s.effect_type) == Some(StatusEffectType::Poisoned)));
+        // Going off reveals it
+        assert!(field.get(id).unwrap().detected);
+
+        // On cooldown, then it can go off again
+        assert!(field.update(0.1, Vec3::ZERO, []).is_empty());
+        assert_eq!(field.update(TrapKind::PoisonVent.rearm_time(), Vec3::ZERO, []).len(), 1);
+    }
+
+    #[test]
+    fn test_lured_enemy_triggers_trap() {
+        let mut field = TrapField::new();
+        field.add(TrapKind::SpikePit, Vec3::ZERO);
+        let player_far = Vec3::new(10.0, 0.0, 0.0);
+        let triggers = field.update(0.1, player_far, [(NpcId(1), Vec3::new(0.5, 0.0, 0.0)), (NpcId(2), Vec3::new(5.0, 0.0, 0.0))]);
+        assert_eq!(triggers.len(), 1);
+        assert_eq!(triggers[0].target, TrapTarget::Npc(NpcId(1)));
+        assert_eq!(triggers[0].damage, TrapKind::SpikePit.damage());
+    }
+
+    #[test]
+    fn test_detection_needs_time_and_proximity() {
+        let mut field = TrapField::new();
+        let id = field.add(TrapKind::SpikePit, Vec3::ZERO);
+        assert!(field.update_detection(10.0, Vec3::new(20.0, 0.0, 0.0), 1.0).is_empty());
+        assert!(field.update_detection(0.1, Vec3::new(2.0, 0.0, 0.0), 1.0).is_empty());
+
+        let mut spotted = Vec::new();
+        for _ in 0..100 {
+            spotted.extend(field.update_detection(0.1, Vec3::new(2.0, 0.0, 0.0), 2.0));
+        }
+        assert_eq!(spotted, vec![id]);
+        assert_eq!(field.detected_armed().count(), 1);
+    }
+
+    #[test]
+    fn test_disarm() {
+        let mut field = TrapField::new();
+        let id = field.add(TrapKind::LaserGrid, Vec3::ZERO);
+        assert!(matches!(
+            field.disarm(id, 0.0, 0.99),
+            Some(DisarmOutcome::Triggered(TrapTrigger { target: TrapTarget::Player, element: Element::Fire, .. }))
+        ));
+        assert!(matches!(field.disarm(id, 20.0, 0.0), Some(DisarmOutcome::Disarmed)));
+        assert!(field.disarm(id, 20.0, 0.0).is_none());
+        // Disarmed traps stay quiet
+        assert!(field.update(10.0, Vec3::ZERO, []).is_empty());
+    }
+
+    #[test]
+    fn test_laser_grids_only_in_the_future() {
+        assert!(TrapKind::for_era(2024, 2024).iter().all(|k| *k != TrapKind::LaserGrid));
+        assert!(TrapKind::for_era(-5000, 2024).iter().all(|k| *k != TrapKind::LaserGrid));
+        assert!(TrapKind::for_era(3500, 2024).contains(&TrapKind::LaserGrid));
+    }
+}
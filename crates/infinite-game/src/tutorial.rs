@@ -0,0 +1,303 @@
+//! Tutorials: contextual onboarding prompts and a checklist
+//!
+//! Each tutorial topic is shown as a prompt the first time its trigger event
+//! happens (first enemy sighted, first level-up, first portal, ...) and is
+//! completed when the player does what it teaches. Prompts can be dismissed
+//! early; dismissed topics are not prompted again but stay open on the
+//! checklist until completed. Progress is kept per save.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds a prompt stays up before it hides itself (it can still be completed)
+pub const PROMPT_DURATION: f32 = 15.0;
+
+/// Something that happened in the game that tutorials react to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TutorialEvent {
+    /// Entered the world
+    Started,
+    Moved,
+    /// Looked at something interactable
+    InteractableFocused,
+    Interacted,
+    /// A hostile NPC came into view
+    EnemySighted,
+    EnemyDefeated,
+    LeveledUp,
+    InventoryOpened,
+    /// A time portal is close by
+    PortalNearby,
+    TimeTraveled,
+    TreasureMapFound,
+    TreasureDug,
+    Saved,
+}
+
+/// One thing the tutorials teach
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TutorialTopic {
+    Movement,
+    Interacting,
+    Combat,
+    LevelingUp,
+    TimeTravel,
+    TreasureMaps,
+    Saving,
+}
+
+impl TutorialTopic {
+    /// All topics, in checklist order
+    pub const ALL: [TutorialTopic; 7] = [
+        TutorialTopic::Movement,
+        TutorialTopic::Interacting,
+        TutorialTopic::Combat,
+        TutorialTopic::LevelingUp,
+        TutorialTopic::TimeTravel,
+        TutorialTopic::TreasureMaps,
+        TutorialTopic::Saving,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Movement => "Getting Around",
+            Self::Interacting => "Interacting",
+            Self::Combat => "Combat",
+            Self::LevelingUp => "Leveling Up",
+            Self::TimeTravel => "Time Travel",
+            Self::TreasureMaps => "Treasure Maps",
+            Self::Saving => "Saving",
+        }
+    }
+
+    /// Prompt text
+    pub fn text(self) -> &'static str {
+        match self {
+            Self::Movement => "WASD to move, Space to jump, Shift to sprint, C to sneak. Scroll to zoom the camera.",
+            Self::Interacting => "Press E to interact with what you're looking at.",
+            Self::Combat => "Enemy ahead! Left click for a light attack, right click for a heavy one, Ctrl to dodge. Skills are on 1-4.",
+            Self::LevelingUp => "You leveled up! Press Tab to open your inventory and check your stats.",
+            Self::TimeTravel => "A time portal! Step through it with E to visit another era.",
+            Self::TreasureMaps => "You found a treasure map. Check it in your inventory, then dig at the mark with a shovel.",
+            Self::Saving => "Press F5 to quicksave and F9 to quickload. The game also saves automatically.",
+        }
+    }
+
+    /// Event that first shows this topic's prompt
+    pub fn trigger(self) -> TutorialEvent {
+        match self {
+            Self::Movement => TutorialEvent::Started,
+            Self::Interacting => TutorialEvent::InteractableFocused,
+            Self::Combat => TutorialEvent::EnemySighted,
+            Self::LevelingUp => TutorialEvent::LeveledUp,
+            Self::TimeTravel => TutorialEvent::PortalNearby,
+            Self::TreasureMaps => TutorialEvent::TreasureMapFound,
+            Self::Saving => TutorialEvent::EnemyDefeated,
+        }
+    }
+
+    /// Event that completes this topic
+    pub fn completion(self) -> TutorialEvent {
+        match self {
+            Self::Movement => TutorialEvent::Moved,
+            Self::Interacting => TutorialEvent::Interacted,
+            Self::Combat => TutorialEvent::EnemyDefeated,
+            Self::LevelingUp => TutorialEvent::InventoryOpened,
+            Self::TimeTravel => TutorialEvent::TimeTraveled,
+            Self::TreasureMaps => TutorialEvent::TreasureDug,
+            Self::Saving => TutorialEvent::Saved,
+        }
+    }
+}
+
+/// Per-save tutorial progress
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TutorialProgress {
+    /// Topics whose prompt has been shown
+    #[serde(default)]
+    pub seen: BTreeSet<TutorialTopic>,
+    /// Topics the player has done
+    #[serde(default)]
+    pub completed: BTreeSet<TutorialTopic>,
+}
+
+/// The prompt currently on screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TutorialPrompt {
+    pub topic: TutorialTopic,
+    /// Seconds left on screen
+    pub remaining: f32,
+}
+
+/// Tracks tutorial progress and which prompt to show
+#[derive(Debug, Clone)]
+pub struct TutorialManager {
+    /// When false no prompts are shown (progress is still tracked)
+    pub enabled: bool,
+    progress: TutorialProgress,
+    current: Option<TutorialPrompt>,
+    queue: VecDeque<TutorialTopic>,
+}
+
+impl Default for TutorialManager {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl TutorialManager {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            progress: TutorialProgress::default(),
+            current: None,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// React to a game event: complete topics it finishes, queue prompts it triggers
+    pub fn handle(&mut self, event: TutorialEvent) {
+        for topic in TutorialTopic::ALL {
+            if topic.completion() == event {
+                self.progress.completed.insert(topic);
+            }
+        }
+        // Drop prompts for anything just completed
+        let completed = &self.progress.completed;
+        self.queue.retain(|t| !completed.contains(t));
+        if self.current.is_some_and(|p| completed.contains(&p.topic)) {
+            self.current = None;
+        }
+
+        for topic in TutorialTopic::ALL {
+            if topic.trigger() == event
+                && !self.progress.seen.contains(&topic)
+                && !self.progress.completed.contains(&topic)
+                && !self.queue.contains(&topic)
+            {
+                self.queue.push_back(topic);
+            }
+        }
+    }
+
+    /// Advance the prompt timer and bring up the next queued prompt
+    pub fn update(&mut self, delta: f32) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(prompt) = &mut self.current {
+            prompt.remaining -= delta;
+            if prompt.remaining <= 0.0 {
+                self.current = None;
+            }
+        }
+        if self.current.is_none() {
+            if let Some(topic) = self.queue.pop_front() {
+                self.progress.seen.insert(topic);
+                self.current = Some(TutorialPrompt { topic, remaining: PROMPT_DURATION });
+            }
+        }
+    }
+
+    /// Prompt to show this frame
+    pub fn prompt(&self) -> Option<TutorialPrompt> {
+        self.current.filter(|_| self.enabled)
+    }
+
+    /// Close the current prompt without completing it
+    pub fn dismiss(&mut self) {
+        self.current = None;
+    }
+
+    pub fn is_completed(&self, topic: TutorialTopic) -> bool {
+        self.progress.completed.contains(&topic)
+    }
+
+    /// Checklist rows: every topic with whether it's done
+    pub fn checklist(&self) -> impl Iterator<Item = (TutorialTopic, bool)> + '_ {
+        TutorialTopic::ALL.into_iter().map(|t| (t, self.is_completed(t)))
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.progress.completed.len()
+    }
+
+    pub fn to_save_data(&self) -> TutorialProgress {
+        self.progress.clone()
+    }
+
+    /// Restore progress from a save (clears pending prompts)
+    pub fn load_save_data(&mut self, progress: TutorialProgress) {
+        self.progress = progress;
+        self.current = None;
+        self.queue.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_shows_prompt_once() {
+        let mut tutorials = TutorialManager::new(true);
+        tutorials.handle(TutorialEvent::EnemySighted);
+        tutorials.update(0.1);
+        assert_eq!(tutorials.prompt().map(|p| p.topic), Some(TutorialTopic::Combat));
+
+        tutorials.dismiss();
+        tutorials.handle(TutorialEvent::EnemySighted);
+        tutorials.update(0.1);
+        assert_eq!(tutorials.prompt(), None);
+        // Dismissed, not completed
+        assert!(!tutorials.is_completed(TutorialTopic::Combat));
+    }
+
+    #[test]
+    fn test_completion_clears_prompt_and_queue() {
+        let mut tutorials = TutorialManager::new(true);
+        tutorials.handle(TutorialEvent::Started);
+        tutorials.handle(TutorialEvent::InteractableFocused);
+        tutorials.update(0.1);
+        assert_eq!(tutorials.prompt().map(|p| p.topic), Some(TutorialTopic::Movement));
+
+        tutorials.handle(TutorialEvent::Moved);
+        assert!(tutorials.is_completed(TutorialTopic::Movement));
+        tutorials.update(0.1);
+        assert_eq!(tutorials.prompt().map(|p| p.topic), Some(TutorialTopic::Interacting));
+
+        // Doing something before its prompt comes up skips the prompt
+        tutorials.handle(TutorialEvent::EnemySighted);
+        tutorials.handle(TutorialEvent::EnemyDefeated);
+        tutorials.handle(TutorialEvent::Interacted);
+        tutorials.update(0.1);
+        assert_eq!(tutorials.prompt().map(|p| p.topic), Some(TutorialTopic::Saving));
+        assert_eq!(tutorials.completed_count(), 3);
+    }
+
+    #[test]
+    fn test_prompt_times_out() {
+        let mut tutorials = TutorialManager::new(true);
+        tutorials.handle(TutorialEvent::LeveledUp);
+        tutorials.update(0.1);
+        assert!(tutorials.prompt().is_some());
+        tutorials.update(PROMPT_DURATION);
+        assert!(tutorials.prompt().is_none());
+    }
+
+    #[test]
+    fn test_disabled_tracks_progress_without_prompts() {
+        let mut tutorials = TutorialManager::new(false);
+        tutorials.handle(TutorialEvent::PortalNearby);
+        tutorials.update(0.1);
+        assert!(tutorials.prompt().is_none());
+        tutorials.handle(TutorialEvent::TimeTraveled);
+        assert!(tutorials.is_completed(TutorialTopic::TimeTravel));
+
+        let mut restored = TutorialManager::new(true);
+        restored.load_save_data(tutorials.to_save_data());
+        let done: Vec<TutorialTopic> = restored.checklist().filter(|(_, done)| *done).map(|(t, _)| t).collect();
+        assert_eq!(done, vec![TutorialTopic::TimeTravel]);
+    }
+}
@@ -1,11 +1,11 @@
 use reqwest::Client;
 
 use crate::error::IntegrationError;
-use crate::types::{ChatRequest, ChatResponse};
+use crate::types::{ChatRequest, ChatResponse, TtsRequest};
 
 const BASE_URL: &str = "https://pixygon-server.onrender.com";
 
-/// API client for AI chat via PixygonServer (no auth required)
+/// API client for AI chat and text-to-speech via PixygonServer (no auth required)
 pub struct AiChatApi {
     client: Client,
 }
@@ -36,4 +36,26 @@ impl AiChatApi {
 
         Ok(response.json().await?)
     }
+
+    /// Synthesize speech for a line of text. Returns encoded audio bytes.
+    pub async fn tts(&self, request: &TtsRequest) -> Result<Vec<u8>, IntegrationError> {
+        let url = format!("{}/v1/ai/tts", BASE_URL);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(IntegrationError::ServerError {
+                status: status.as_u16(),
+                message: text,
+            });
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
 }
@@ -78,6 +78,21 @@ impl CharacterApi {
 
         handle_response(response).await
     }
+
+    /// Delete a character
+    pub async fn delete(&self, auth: &AuthManager, character_id: &str) -> Result<serde_json::Value, IntegrationError> {
+        let token = auth.token().ok_or_else(|| IntegrationError::AuthFailed("Not authenticated".into()))?;
+        let user_id = auth.user_id().ok_or_else(|| IntegrationError::AuthFailed("No user ID".into()))?;
+
+        let url = format!("{}/v1/characters/{}/{}/{}", BASE_URL, PROJECT_ID, user_id, character_id);
+        let response = self.client
+            .delete(&url)
+            .bearer_auth(&token)
+            .send()
+            .await?;
+
+        handle_response(response).await
+    }
 }
 
 async fn handle_response<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, IntegrationError> {
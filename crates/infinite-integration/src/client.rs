@@ -9,6 +9,7 @@ use crate::ai_chat::AiChatApi;
 use crate::character::CharacterApi;
 use crate::character_item::CharacterItemApi;
 use crate::game_story::GameStoryApi;
+use crate::leaderboard::LeaderboardApi;
 use crate::error::IntegrationError;
 use crate::types::*;
 
@@ -38,6 +39,7 @@ pub struct IntegrationClient {
     character_api: Arc<CharacterApi>,
     character_item_api: Arc<CharacterItemApi>,
     game_story_api: Arc<GameStoryApi>,
+    leaderboard_api: Arc<LeaderboardApi>,
     ai_chat_api: Arc<AiChatApi>,
     online: Arc<std::sync::atomic::AtomicBool>,
 }
@@ -60,6 +62,7 @@ impl IntegrationClient {
         let character_api = Arc::new(CharacterApi::new(client.clone()));
         let character_item_api = Arc::new(CharacterItemApi::new(client.clone()));
         let game_story_api = Arc::new(GameStoryApi::new(client.clone()));
+        let leaderboard_api = Arc::new(LeaderboardApi::new(client.clone()));
         let ai_chat_api = Arc::new(AiChatApi::new(client));
 
         Ok(Self {
@@ -68,6 +71,7 @@ impl IntegrationClient {
             character_api,
             character_item_api,
             game_story_api,
+            leaderboard_api,
             ai_chat_api,
             online: Arc::new(std::sync::atomic::AtomicBool::new(false)),
         })
@@ -134,6 +138,57 @@ impl IntegrationClient {
         PendingRequest { receiver: rx }
     }
 
+    /// Replace a character's name, prompt and lore (NPC templates).
+    pub fn update_character(&self, character_id: &str, req: CreateCharacterRequest) -> PendingRequest<ServerCharacter> {
+        let (tx, rx) = mpsc::channel();
+        let auth = Arc::clone(&self.auth);
+        let api = Arc::clone(&self.character_api);
+        let character_id = character_id.to_string();
+
+        self.runtime.spawn(async move {
+            let result = match serde_json::to_value(&req) {
+                Ok(updates) => api.update(&auth, &character_id, updates).await,
+                Err(e) => Err(e.into()),
+            };
+            let _ = tx.send(result);
+        });
+
+        PendingRequest { receiver: rx }
+    }
+
+    /// Write a player character's progress snapshot to the server.
+    pub fn update_character_snapshot(&self, character_id: &str, snapshot: CharacterSnapshot) -> PendingRequest<ServerCharacter> {
+        let (tx, rx) = mpsc::channel();
+        let auth = Arc::clone(&self.auth);
+        let api = Arc::clone(&self.character_api);
+        let character_id = character_id.to_string();
+
+        self.runtime.spawn(async move {
+            let result = match serde_json::to_value(&snapshot) {
+                Ok(snapshot) => api.update(&auth, &character_id, serde_json::json!({ "snapshot": snapshot })).await,
+                Err(e) => Err(e.into()),
+            };
+            let _ = tx.send(result);
+        });
+
+        PendingRequest { receiver: rx }
+    }
+
+    /// Delete a character from the server.
+    pub fn delete_character(&self, character_id: &str) -> PendingRequest<serde_json::Value> {
+        let (tx, rx) = mpsc::channel();
+        let auth = Arc::clone(&self.auth);
+        let api = Arc::clone(&self.character_api);
+        let character_id = character_id.to_string();
+
+        self.runtime.spawn(async move {
+            let result = api.delete(&auth, &character_id).await;
+            let _ = tx.send(result);
+        });
+
+        PendingRequest { receiver: rx }
+    }
+
     /// Send a chat request to the AI endpoint (no auth required).
     pub fn send_chat(&self, request: ChatRequest) -> PendingRequest<ChatResponse> {
         let (tx, rx) = mpsc::channel();
@@ -147,6 +202,19 @@ impl IntegrationClient {
         PendingRequest { receiver: rx }
     }
 
+    /// Request text-to-speech audio for a line (no auth required).
+    pub fn send_tts(&self, request: TtsRequest) -> PendingRequest<Vec<u8>> {
+        let (tx, rx) = mpsc::channel();
+        let api = Arc::clone(&self.ai_chat_api);
+
+        self.runtime.spawn(async move {
+            let result = api.tts(&request).await;
+            let _ = tx.send(result);
+        });
+
+        PendingRequest { receiver: rx }
+    }
+
     // ============================================
     // CharacterItem API wrappers
     // ============================================
@@ -271,6 +339,35 @@ impl IntegrationClient {
         PendingRequest { receiver: rx }
     }
 
+    /// Submit a finished run's score to its leaderboard.
+    pub fn submit_score(&self, entry: LeaderboardEntry) -> PendingRequest<LeaderboardEntry> {
+        let (tx, rx) = mpsc::channel();
+        let auth = Arc::clone(&self.auth);
+        let api = Arc::clone(&self.leaderboard_api);
+
+        self.runtime.spawn(async move {
+            let result = api.submit(&auth, entry).await;
+            let _ = tx.send(result);
+        });
+
+        PendingRequest { receiver: rx }
+    }
+
+    /// Fetch the top `limit` scores of a leaderboard.
+    pub fn fetch_leaderboard(&self, board: &str, limit: u32) -> PendingRequest<Vec<LeaderboardEntry>> {
+        let (tx, rx) = mpsc::channel();
+        let auth = Arc::clone(&self.auth);
+        let api = Arc::clone(&self.leaderboard_api);
+        let board = board.to_string();
+
+        self.runtime.spawn(async move {
+            let result = api.top(&auth, &board, limit).await;
+            let _ = tx.send(result);
+        });
+
+        PendingRequest { receiver: rx }
+    }
+
     /// Whether the server appears to be online (based on last request result).
     pub fn is_online(&self) -> bool {
         self.online.load(std::sync::atomic::Ordering::Relaxed)
@@ -1,3 +1,4 @@
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain, Severity};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -21,6 +22,92 @@ pub enum IntegrationError {
     Serialization(String),
 }
 
+impl IntegrationError {
+    /// What the server said was wrong with a rejected request, one line per
+    /// problem (`field: message` where it names the field). Empty for
+    /// errors that aren't a rejection.
+    pub fn validation_messages(&self) -> Vec<String> {
+        let Self::ServerError { status: 400..=499, message } = self else {
+            return Vec::new();
+        };
+        let Ok(body) = serde_json::from_str::<serde_json::Value>(message) else {
+            return match message.trim() {
+                "" => Vec::new(),
+                text => vec![text.to_string()],
+            };
+        };
+
+        let mut lines = Vec::new();
+        match body.get("errors") {
+            // [{ "path": "name", "msg": "..." }] or ["..."]
+            Some(serde_json::Value::Array(errors)) => {
+                for error in errors {
+                    let field = ["path", "param", "field"].iter().find_map(|k| error.get(*k).and_then(|v| v.as_str()));
+                    if let Some(text) = message_of(error) {
+                        lines.push(with_field(field, text));
+                    }
+                }
+            }
+            // { "name": { "message": "..." } } or { "name": "..." }
+            Some(serde_json::Value::Object(errors)) => {
+                for (field, error) in errors {
+                    if let Some(text) = message_of(error) {
+                        lines.push(with_field(Some(field), text));
+                    }
+                }
+            }
+            _ => {}
+        }
+        if lines.is_empty() {
+            if let Some(text) = body.get("message").or_else(|| body.get("error")).and_then(|v| v.as_str()) {
+                lines.push(text.to_string());
+            }
+        }
+        lines
+    }
+}
+
+fn message_of(error: &serde_json::Value) -> Option<&str> {
+    error.as_str().or_else(|| ["msg", "message"].iter().find_map(|k| error.get(*k).and_then(|v| v.as_str())))
+}
+
+fn with_field(field: Option<&str>, text: &str) -> String {
+    match field {
+        Some(field) => format!("{}: {}", field, text),
+        None => text.to_string(),
+    }
+}
+
+impl Diagnostic for IntegrationError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::Network(_) => 1,
+            Self::AuthFailed(_) => 2,
+            Self::ServerError { .. } => 3,
+            Self::Offline => 4,
+            Self::Timeout => 5,
+            Self::Serialization(_) => 6,
+        };
+        ErrorCode::new(ErrorDomain::Integration, number)
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            // The game falls back to offline play on its own
+            Self::Offline | Self::Timeout => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::AuthFailed(_) => Some("Log in again from the main menu"),
+            Self::Network(_) | Self::Offline | Self::Timeout => Some("Online features are unavailable until the connection returns"),
+            _ => None,
+        }
+    }
+}
+
 impl From<reqwest::Error> for IntegrationError {
     fn from(err: reqwest::Error) -> Self {
         if err.is_timeout() {
@@ -38,3 +125,28 @@ impl From<serde_json::Error> for IntegrationError {
         IntegrationError::Serialization(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejected(status: u16, message: &str) -> IntegrationError {
+        IntegrationError::ServerError { status, message: message.into() }
+    }
+
+    #[test]
+    fn test_validation_messages() {
+        let listed = rejected(422, r#"{"errors":[{"path":"name","msg":"Name is required"},"Too many items"]}"#);
+        assert_eq!(listed.validation_messages(), vec!["name: Name is required", "Too many items"]);
+
+        let keyed = rejected(400, r#"{"message":"Validation failed","errors":{"price":{"message":"Must be positive"}}}"#);
+        assert_eq!(keyed.validation_messages(), vec!["price: Must be positive"]);
+
+        let plain = rejected(400, r#"{"message":"Item id already exists"}"#);
+        assert_eq!(plain.validation_messages(), vec!["Item id already exists"]);
+        assert_eq!(rejected(409, "Duplicate").validation_messages(), vec!["Duplicate"]);
+
+        assert!(rejected(500, r#"{"message":"boom"}"#).validation_messages().is_empty());
+        assert!(IntegrationError::Offline.validation_messages().is_empty());
+    }
+}
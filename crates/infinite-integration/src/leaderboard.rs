@@ -0,0 +1,88 @@
+//! Leaderboard API client for survival mode scores
+
+use reqwest::Client;
+
+use crate::auth::AuthManager;
+use crate::error::IntegrationError;
+use crate::types::*;
+
+const BASE_URL: &str = "https://pixygon-server.onrender.com";
+const PROJECT_ID: &str = "6981e8eda259e89734bd007a";
+
+/// API client for submitting and reading leaderboard scores
+pub struct LeaderboardApi {
+    client: Client,
+}
+
+impl LeaderboardApi {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Submit a finished run to its board
+    pub async fn submit(
+        &self,
+        auth: &AuthManager,
+        mut entry: LeaderboardEntry,
+    ) -> Result<LeaderboardEntry, IntegrationError> {
+        let token = auth.token().ok_or_else(|| IntegrationError::AuthFailed("Not authenticated".into()))?;
+        entry.project_id = PROJECT_ID.to_string();
+
+        let url = format!("{}/v1/leaderboards/{}/entries", BASE_URL, entry.board);
+        let response = self.client
+            .post(&url)
+            .bearer_auth(&token)
+            .json(&entry)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(IntegrationError::ServerError { status: status.as_u16(), message: text });
+        }
+
+        // Try parsing as { success, entry } or as the raw entry
+        let text = response.text().await?;
+        if let Ok(resp) = serde_json::from_str::<LeaderboardMutationResponse>(&text) {
+            return Ok(resp.entry);
+        }
+        if let Ok(entry) = serde_json::from_str::<LeaderboardEntry>(&text) {
+            return Ok(entry);
+        }
+        Err(IntegrationError::Serialization("Failed to parse submit score response".into()))
+    }
+
+    /// The best `limit` scores on a board, highest first
+    pub async fn top(
+        &self,
+        auth: &AuthManager,
+        board: &str,
+        limit: u32,
+    ) -> Result<Vec<LeaderboardEntry>, IntegrationError> {
+        let token = auth.token().ok_or_else(|| IntegrationError::AuthFailed("Not authenticated".into()))?;
+
+        let url = format!("{}/v1/leaderboards/{}?projectId={}&limit={}", BASE_URL, board, PROJECT_ID, limit);
+        let response = self.client
+            .get(&url)
+            .bearer_auth(&token)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(IntegrationError::ServerError { status: status.as_u16(), message: text });
+        }
+
+        // Try to parse as array first (simple response), then as wrapped object
+        let text = response.text().await?;
+        if let Ok(entries) = serde_json::from_str::<Vec<LeaderboardEntry>>(&text) {
+            return Ok(entries);
+        }
+        if let Ok(resp) = serde_json::from_str::<LeaderboardListResponse>(&text) {
+            return Ok(resp.entries);
+        }
+        Err(IntegrationError::Serialization("Failed to parse leaderboard response".into()))
+    }
+}
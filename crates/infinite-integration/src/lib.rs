@@ -8,6 +8,7 @@ pub mod auth;
 pub mod character;
 pub mod character_item;
 pub mod game_story;
+pub mod leaderboard;
 pub mod ai_chat;
 pub mod client;
 
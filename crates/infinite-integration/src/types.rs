@@ -39,6 +39,40 @@ pub struct ServerCharacter {
     pub project_id: String,
     #[serde(default)]
     pub user_id: String,
+    /// Id the game gave the character before it reached the server, so a
+    /// create that was sent twice can be recognised
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Progress of a player character; NPC characters have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<CharacterSnapshot>,
+}
+
+/// A player character's progress, written after each session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterSnapshot {
+    #[serde(default)]
+    pub level: u32,
+    #[serde(default)]
+    pub total_xp: u64,
+    #[serde(default)]
+    pub gold: u64,
+    #[serde(default)]
+    pub archetype: Option<String>,
+    #[serde(default)]
+    pub secondary_archetype: Option<String>,
+    /// Names of the equipped items
+    #[serde(default)]
+    pub equipment: Vec<String>,
+    #[serde(default)]
+    pub play_time_seconds: u64,
+    /// Game-defined look and identity of the character, opaque to the server
+    #[serde(default)]
+    pub profile: Option<serde_json::Value>,
+    /// Unix time (seconds) of the session that wrote this snapshot
+    #[serde(default)]
+    pub updated_at: i64,
 }
 
 /// Lore/backstory for a character
@@ -71,6 +105,10 @@ pub struct CreateCharacterRequest {
     pub system_prompt: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lore: Option<CharacterLore>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<CharacterSnapshot>,
 }
 
 /// A single chat message
@@ -96,6 +134,16 @@ pub struct ChatResponse {
     pub content: String,
 }
 
+/// Request body for `/v1/ai/tts` (response is raw audio bytes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsRequest {
+    pub text: String,
+    /// Voice preset name (server default if None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+}
+
 // ============================================
 // CharacterItem types (server item catalog)
 // ============================================
@@ -172,6 +220,9 @@ pub struct GameItemCustomStats {
     pub required_level: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub game_category: Option<String>,
+    /// Year the item comes from, for anachronism rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_year: Option<i64>,
 }
 
 /// Stat modifiers in the custom blob
@@ -353,6 +404,51 @@ pub struct StoryMutationResponse {
     pub story: ServerGameStory,
 }
 
+// ============================================
+// Leaderboard types (survival mode)
+// ============================================
+
+/// A finished run on a leaderboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub project_id: String,
+    /// Which board the run counts for, e.g. "survival"
+    pub board: String,
+    /// Account name, filled in by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+    #[serde(default)]
+    pub character_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archetype: Option<String>,
+    pub score: u64,
+    /// Furthest wave reached
+    #[serde(default)]
+    pub wave: u32,
+    #[serde(default)]
+    pub kills: u32,
+    #[serde(default)]
+    pub best_combo: u32,
+}
+
+/// Response from list leaderboard endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeaderboardListResponse {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// Response from submit score endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeaderboardMutationResponse {
+    #[serde(default)]
+    pub success: bool,
+    pub entry: LeaderboardEntry,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -399,6 +495,39 @@ mod tests {
         assert!(character.lore.is_none());
     }
 
+    #[test]
+    fn test_player_character_snapshot_serde() {
+        let json = r#"{
+            "_id": "c1",
+            "name": "Ayla",
+            "clientId": "local-1",
+            "snapshot": {
+                "level": 7,
+                "totalXp": 1200,
+                "archetype": "Vanguard",
+                "equipment": ["Iron Sword"],
+                "updatedAt": 1700000000
+            }
+        }"#;
+        let character: ServerCharacter = serde_json::from_str(json).unwrap();
+        assert_eq!(character.client_id.as_deref(), Some("local-1"));
+        let snapshot = character.snapshot.unwrap();
+        assert_eq!(snapshot.level, 7);
+        assert_eq!(snapshot.gold, 0);
+        assert_eq!(snapshot.equipment, vec!["Iron Sword".to_string()]);
+
+        let req = CreateCharacterRequest {
+            name: "Ayla".into(),
+            system_prompt: String::new(),
+            lore: None,
+            client_id: None,
+            snapshot: Some(snapshot),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("\"totalXp\":1200"));
+        assert!(!json.contains("clientId"));
+    }
+
     #[test]
     fn test_chat_request_serde() {
         let req = ChatRequest {
@@ -414,6 +543,41 @@ mod tests {
         assert!(json.contains("grok"));
     }
 
+    #[test]
+    fn test_tts_request_serde() {
+        let req = TtsRequest {
+            text: "Move along, citizen.".into(),
+            voice: None,
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("Move along"));
+        assert!(!json.contains("voice"));
+    }
+
+    #[test]
+    fn test_leaderboard_entry_serde() {
+        let entry = LeaderboardEntry {
+            id: None,
+            project_id: String::new(),
+            board: "survival".into(),
+            user_name: None,
+            character_name: "Brakka".into(),
+            archetype: Some("warrior".into()),
+            score: 4200,
+            wave: 12,
+            kills: 57,
+            best_combo: 9,
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"bestCombo\":9"));
+        assert!(!json.contains("_id"));
+
+        let list: LeaderboardListResponse =
+            serde_json::from_str(r#"{ "entries": [{ "board": "survival", "score": 10, "userName": "testuser" }] }"#).unwrap();
+        assert_eq!(list.entries[0].user_name.as_deref(), Some("testuser"));
+        assert_eq!(list.entries[0].wave, 0);
+    }
+
     #[test]
     fn test_auth_response_serde() {
         let json = r#"{
@@ -0,0 +1,138 @@
+//! Continuous collision for fast bodies
+//!
+//! A body that covers more than its own thickness in one step can pass
+//! straight through a thin collider (a fence, a wall, a narrow ridge of
+//! terrain) without a contact ever being found. CCD sweeps such a body
+//! along its motion between steps instead; soft CCD is the cheaper
+//! alternative that looks for contacts a distance ahead of the body.
+//! [`PhysicsWorld::register_fast_body`] sets both up for a body's top speed.
+
+use glam::Vec3;
+use rapier3d::prelude::*;
+
+use crate::PhysicsWorld;
+
+/// Continuous collision settings of one rigid body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CcdConfig {
+    /// Sweep the body between steps so it can't pass through colliders
+    pub enabled: bool,
+    /// How far ahead of the body (in metres) soft CCD predicts contacts;
+    /// 0 turns soft CCD off
+    pub prediction_distance: f32,
+}
+
+impl CcdConfig {
+    /// No continuous collision (rapier's default)
+    pub const OFF: Self = Self { enabled: false, prediction_distance: 0.0 };
+
+    /// Settings for a body moving at up to `max_speed` m/s in steps of
+    /// `timestep` seconds: swept, and predicting a whole step ahead
+    pub fn for_speed(max_speed: f32, timestep: f32) -> Self {
+        Self {
+            enabled: true,
+            prediction_distance: (max_speed * timestep).max(0.0),
+        }
+    }
+}
+
+impl Default for CcdConfig {
+    fn default() -> Self {
+        Self::OFF
+    }
+}
+
+impl PhysicsWorld {
+    /// Apply continuous collision settings to a body
+    pub fn set_ccd(&mut self, handle: RigidBodyHandle, config: CcdConfig) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            body.enable_ccd(config.enabled);
+            body.set_soft_ccd_prediction(config.prediction_distance);
+        }
+    }
+
+    /// A body's continuous collision settings
+    pub fn ccd(&self, handle: RigidBodyHandle) -> Option<CcdConfig> {
+        self.rigid_body_set.get(handle).map(|body| CcdConfig {
+            enabled: body.is_ccd_enabled(),
+            prediction_distance: body.soft_ccd_prediction(),
+        })
+    }
+
+    /// Turn swept CCD on or off for a body
+    pub fn set_ccd_enabled(&mut self, handle: RigidBodyHandle, enabled: bool) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            body.enable_ccd(enabled);
+        }
+    }
+
+    /// Set how far ahead soft CCD predicts a body's contacts (0 = off)
+    pub fn set_ccd_prediction(&mut self, handle: RigidBodyHandle, distance: f32) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            body.set_soft_ccd_prediction(distance.max(0.0));
+        }
+    }
+
+    /// Set up continuous collision for a body that can reach `max_speed`
+    /// m/s, so it can't tunnel through thin terrain at the world's step rate
+    pub fn register_fast_body(&mut self, handle: RigidBodyHandle, max_speed: f32) {
+        self.set_ccd(handle, CcdConfig::for_speed(max_speed, self.config.timestep));
+    }
+
+    /// Launch a ball of `radius` and `mass` from `position` at `velocity`,
+    /// registered as a fast body for its launch speed
+    pub fn add_projectile(
+        &mut self,
+        position: Vec3,
+        velocity: Vec3,
+        radius: f32,
+        mass: f32,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let body = RigidBodyBuilder::dynamic()
+            .translation(vector![position.x, position.y, position.z])
+            .linvel(vector![velocity.x, velocity.y, velocity.z])
+            .build();
+        let collider = ColliderBuilder::ball(radius).mass(mass.max(0.01)).restitution(0.0).build();
+        let handles = self.add_dynamic_body(body, collider);
+        self.register_fast_body(handles.0, velocity.length());
+        handles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fire_at_thin_wall(ccd: bool) -> f32 {
+        let mut world = PhysicsWorld::new();
+        // 4cm wall ten metres ahead
+        world.create_static_box(Vec3::new(2.0, 2.0, 0.02), Vec3::new(0.0, 1.0, -10.0));
+        let (body, _) = world.add_projectile(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, -300.0), 0.05, 0.1);
+        if !ccd {
+            world.set_ccd(body, CcdConfig::OFF);
+        }
+        for _ in 0..10 {
+            world.step();
+        }
+        world.get_rigid_body(body).unwrap().translation().z
+    }
+
+    #[test]
+    fn test_fast_projectiles_stop_at_thin_walls() {
+        assert!(fire_at_thin_wall(false) < -20.0, "without CCD it tunnels");
+        assert!(fire_at_thin_wall(true) > -10.1);
+    }
+
+    #[test]
+    fn test_ccd_config_per_body() {
+        let mut world = PhysicsWorld::new();
+        let (body, _) = world.add_projectile(Vec3::ZERO, Vec3::new(60.0, 0.0, 0.0), 0.1, 1.0);
+        let config = world.ccd(body).unwrap();
+        assert!(config.enabled);
+        assert!((config.prediction_distance - 1.0).abs() < 1e-4);
+
+        world.set_ccd_enabled(body, false);
+        world.set_ccd_prediction(body, -1.0);
+        assert_eq!(world.ccd(body), Some(CcdConfig::OFF));
+    }
+}
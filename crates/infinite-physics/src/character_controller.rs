@@ -61,20 +61,22 @@ impl CharacterController {
 
     /// Create a new character controller with custom config
     pub fn with_config(config: CharacterControllerConfig) -> Self {
-        let mut controller = KinematicCharacterController::default();
-        controller.max_slope_climb_angle = config.max_slope_angle.to_radians();
-        controller.min_slope_slide_angle = config.max_slope_angle.to_radians();
-        controller.autostep = Some(CharacterAutostep {
-            max_height: CharacterLength::Absolute(config.step_height),
-            min_width: CharacterLength::Relative(0.5),
-            include_dynamic_bodies: true,
-        });
-        controller.snap_to_ground = if config.snap_to_ground {
-            Some(CharacterLength::Absolute(config.ground_snap_distance))
-        } else {
-            None
+        let controller = KinematicCharacterController {
+            max_slope_climb_angle: config.max_slope_angle.to_radians(),
+            min_slope_slide_angle: config.max_slope_angle.to_radians(),
+            autostep: Some(CharacterAutostep {
+                max_height: CharacterLength::Absolute(config.step_height),
+                min_width: CharacterLength::Relative(0.5),
+                include_dynamic_bodies: true,
+            }),
+            snap_to_ground: if config.snap_to_ground {
+                Some(CharacterLength::Absolute(config.ground_snap_distance))
+            } else {
+                None
+            },
+            offset: CharacterLength::Absolute(config.skin_width),
+            ..Default::default()
         };
-        controller.offset = CharacterLength::Absolute(config.skin_width);
 
         Self {
             config,
@@ -139,7 +141,7 @@ impl CharacterController {
             shape,
             &current_pos,
             vector![desired_translation.x, desired_translation.y, desired_translation.z],
-            QueryFilter::default().exclude_collider(collider_handle),
+            QueryFilter::default().exclude_collider(collider_handle).exclude_sensors(),
             |_| {},
         );
 
@@ -0,0 +1,334 @@
+//! Force fields: explosions, geysers and storm wind
+//!
+//! A [`ForceField`] pushes every dynamic body inside it, each step. A
+//! field's strength is the velocity change it gives rather than a force,
+//! so a crate and a barrel caught in the same blast fly the same way:
+//! m/s² for a continuous field, m/s for a one-shot that fires once on the
+//! next step and is gone. Characters aren't rigid bodies, so their
+//! controllers ask [`PhysicsWorld::force_field_push`] for the same push.
+//!
+//! Continuous fields leave sleeping bodies asleep, so a steady wind doesn't
+//! keep every resting prop simulated; a one-shot wakes whatever it reaches.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::PhysicsWorld;
+
+/// Ids are unique across worlds, so one kept from a replaced world never
+/// names a field of the new one
+static NEXT_FIELD: AtomicU64 = AtomicU64::new(0);
+
+/// Handle to a registered force field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ForceFieldId(u64);
+
+/// Region a field covers and the way it pushes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FieldShape {
+    /// Away from `center` (towards it with a negative strength)
+    Radial { center: Vec3, radius: f32 },
+    /// Along `direction`, within `radius` of `center`
+    Directional { direction: Vec3, center: Vec3, radius: f32 },
+    /// Along `direction` everywhere
+    Global { direction: Vec3 },
+}
+
+/// How a field weakens from its centre to its edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Falloff {
+    /// Full strength up to the edge
+    Constant,
+    #[default]
+    Linear,
+    /// Strong near the centre, dying away quickly
+    Quadratic,
+    /// Eases in and out (smoothstep)
+    Smooth,
+}
+
+impl Falloff {
+    /// Share of full strength at `t` (0 at the centre, 1 at the edge)
+    pub fn factor(self, t: f32) -> f32 {
+        if !(0.0..=1.0).contains(&t) {
+            return 0.0;
+        }
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => 1.0 - t,
+            Falloff::Quadratic => (1.0 - t) * (1.0 - t),
+            Falloff::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Whether a field pushes every step or once
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FieldMode {
+    /// Every step, for `remaining` seconds or until removed
+    Continuous { remaining: Option<f32> },
+    /// Once, on the next step
+    OneShot,
+}
+
+/// A region that pushes bodies and characters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ForceField {
+    pub shape: FieldShape,
+    /// Full-strength velocity change: m/s² while continuous, m/s for a one-shot
+    pub strength: f32,
+    pub falloff: Falloff,
+    pub mode: FieldMode,
+}
+
+impl ForceField {
+    /// A blast throwing things out from `center`, once
+    pub fn explosion(center: Vec3, radius: f32, strength: f32) -> Self {
+        Self {
+            shape: FieldShape::Radial { center, radius },
+            strength,
+            falloff: Falloff::Linear,
+            mode: FieldMode::OneShot,
+        }
+    }
+
+    /// A steady push out from `center` (or a pull, if `strength` is negative)
+    pub fn radial(center: Vec3, radius: f32, strength: f32) -> Self {
+        Self {
+            shape: FieldShape::Radial { center, radius },
+            strength,
+            falloff: Falloff::Linear,
+            mode: FieldMode::Continuous { remaining: None },
+        }
+    }
+
+    /// An updraft of `radius` over a vent at `center`
+    pub fn geyser(center: Vec3, radius: f32, strength: f32) -> Self {
+        Self {
+            shape: FieldShape::Directional { direction: Vec3::Y, center, radius },
+            strength,
+            falloff: Falloff::Smooth,
+            mode: FieldMode::Continuous { remaining: None },
+        }
+    }
+
+    /// The same push everywhere, such as storm wind
+    pub fn global(direction: Vec3, strength: f32) -> Self {
+        Self {
+            shape: FieldShape::Global { direction },
+            strength,
+            falloff: Falloff::Constant,
+            mode: FieldMode::Continuous { remaining: None },
+        }
+    }
+
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Make a continuous field expire after `seconds`
+    pub fn lasting(mut self, seconds: f32) -> Self {
+        if let FieldMode::Continuous { remaining } = &mut self.mode {
+            *remaining = Some(seconds);
+        }
+        self
+    }
+
+    pub fn is_one_shot(&self) -> bool {
+        self.mode == FieldMode::OneShot
+    }
+
+    /// The field's velocity change at `point` (per second while continuous)
+    pub fn at(&self, point: Vec3) -> Vec3 {
+        let (direction, t) = match self.shape {
+            FieldShape::Radial { center, radius } => {
+                let offset = point - center;
+                // Straight up from the centre itself
+                (offset.try_normalize().unwrap_or(Vec3::Y), offset.length() / radius.max(f32::EPSILON))
+            }
+            FieldShape::Directional { direction, center, radius } => {
+                (direction.normalize_or_zero(), point.distance(center) / radius.max(f32::EPSILON))
+            }
+            FieldShape::Global { direction } => (direction.normalize_or_zero(), 0.0),
+        };
+        direction * self.strength * self.falloff.factor(t)
+    }
+}
+
+/// The fields registered in a world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct ForceFields {
+    /// Iterated in id order, so worlds stepped alike sum pushes alike
+    active: BTreeMap<ForceFieldId, ForceField>,
+    /// One-shots fired by the last step, for characters to feel until the next
+    fired: Vec<ForceField>,
+}
+
+impl PhysicsWorld {
+    /// Register a field; it starts pushing on the next step
+    pub fn add_force_field(&mut self, field: ForceField) -> ForceFieldId {
+        let id = ForceFieldId(NEXT_FIELD.fetch_add(1, Ordering::Relaxed));
+        self.force_fields.active.insert(id, field);
+        id
+    }
+
+    pub fn remove_force_field(&mut self, id: ForceFieldId) -> Option<ForceField> {
+        self.force_fields.active.remove(&id)
+    }
+
+    /// A field still registered (ones that fired or ran out are gone)
+    pub fn force_field(&self, id: ForceFieldId) -> Option<&ForceField> {
+        self.force_fields.active.get(&id)
+    }
+
+    /// Change a field in place, e.g. to follow the wind
+    pub fn force_field_mut(&mut self, id: ForceFieldId) -> Option<&mut ForceField> {
+        self.force_fields.active.get_mut(&id)
+    }
+
+    pub fn force_field_count(&self) -> usize {
+        self.force_fields.active.len()
+    }
+
+    /// Velocity change for a character at `point` over a `dt` second
+    /// update: the continuous fields over `dt`, plus any one-shot the last
+    /// step fired
+    pub fn force_field_push(&self, point: Vec3, dt: f32) -> Vec3 {
+        let continuous: Vec3 = self
+            .force_fields
+            .active
+            .values()
+            .filter(|field| !field.is_one_shot())
+            .map(|field| field.at(point) * dt)
+            .sum();
+        continuous + self.force_fields.fired.iter().map(|field| field.at(point)).sum::<Vec3>()
+    }
+
+    /// Give every dynamic body this step's push, fire the one-shots and
+    /// age the fields that expire. Called by `step()` before integrating.
+    pub(crate) fn apply_force_fields(&mut self) {
+        let dt = self.config.timestep;
+        let fields = &mut self.force_fields;
+        fields.fired.clear();
+        if fields.active.is_empty() {
+            return;
+        }
+
+        for (_, body) in self.rigid_body_set.iter_mut() {
+            if !body.is_dynamic() || !body.is_enabled() {
+                continue;
+            }
+            let (position, mass, asleep) = (body.translation(), body.mass(), body.is_sleeping());
+            let point = Vec3::new(position.x, position.y, position.z);
+            let mut push = Vec3::ZERO;
+            let mut wake = false;
+            for field in fields.active.values() {
+                let change = field.at(point);
+                if field.is_one_shot() {
+                    push += change;
+                    wake |= change != Vec3::ZERO;
+                } else if !asleep {
+                    push += change * dt;
+                }
+            }
+            if push != Vec3::ZERO {
+                let impulse = push * mass;
+                body.apply_impulse(nalgebra::vector![impulse.x, impulse.y, impulse.z], wake);
+            }
+        }
+
+        fields.active.retain(|_, field| match &mut field.mode {
+            FieldMode::OneShot => {
+                fields.fired.push(*field);
+                false
+            }
+            FieldMode::Continuous { remaining: Some(left) } => {
+                *left -= dt;
+                *left > 0.0
+            }
+            FieldMode::Continuous { remaining: None } => true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rapier3d::prelude::*;
+
+    fn world_with_crate(at: Vec3) -> (PhysicsWorld, RigidBodyHandle) {
+        let mut world = PhysicsWorld::with_config(crate::PhysicsConfig {
+            gravity: Vec3::ZERO,
+            ..Default::default()
+        });
+        let body = RigidBodyBuilder::dynamic().translation(vector![at.x, at.y, at.z]).build();
+        let (handle, _) = world.add_dynamic_body(body, ColliderBuilder::cuboid(0.5, 0.5, 0.5).density(3.0).build());
+        (world, handle)
+    }
+
+    fn velocity(world: &PhysicsWorld, handle: RigidBodyHandle) -> Vec3 {
+        let v = world.get_rigid_body(handle).unwrap().linvel();
+        Vec3::new(v.x, v.y, v.z)
+    }
+
+    #[test]
+    fn test_falloff_curves() {
+        for falloff in [Falloff::Constant, Falloff::Linear, Falloff::Quadratic, Falloff::Smooth] {
+            assert_eq!(falloff.factor(0.0), 1.0);
+            assert_eq!(falloff.factor(1.5), 0.0);
+        }
+        assert_eq!(Falloff::Linear.factor(0.5), 0.5);
+        assert_eq!(Falloff::Quadratic.factor(0.5), 0.25);
+        assert_eq!(Falloff::Smooth.factor(0.5), 0.5);
+        assert!(Falloff::Smooth.factor(0.1) > Falloff::Linear.factor(0.1));
+    }
+
+    #[test]
+    fn test_explosion_fires_once() {
+        let (mut world, handle) = world_with_crate(Vec3::new(2.0, 0.0, 0.0));
+        let id = world.add_force_field(ForceField::explosion(Vec3::ZERO, 4.0, 10.0));
+        world.step();
+
+        // Half way out of a linear blast: 5 m/s outwards, whatever the mass
+        let after = velocity(&world, handle);
+        assert!((after.x - 5.0).abs() < 1e-3, "{after}");
+        assert!(world.force_field(id).is_none());
+        // Characters feel it until the next step
+        assert!((world.force_field_push(Vec3::new(0.0, 0.0, -1.0), 1.0 / 60.0).z + 7.5).abs() < 1e-4);
+
+        world.step();
+        assert!((velocity(&world, handle).x - after.x).abs() < 1e-3);
+        assert_eq!(world.force_field_push(Vec3::new(0.0, 0.0, -1.0), 1.0 / 60.0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_continuous_fields_push_and_expire() {
+        let (mut world, handle) = world_with_crate(Vec3::new(0.0, 1.0, 0.0));
+        world.add_force_field(ForceField::geyser(Vec3::ZERO, 3.0, 12.0).lasting(0.5));
+        let wind = world.add_force_field(ForceField::global(Vec3::X, 2.0));
+        for _ in 0..60 {
+            world.step();
+        }
+        let v = velocity(&world, handle);
+        // The wind ran the whole second; the geyser only the first half
+        assert!((v.x - 2.0).abs() < 0.05, "{v}");
+        assert!(v.y > 2.0 && v.y < 6.0, "{v}");
+        assert_eq!(world.force_field_count(), 1);
+
+        world.force_field_mut(wind).unwrap().strength = 0.0;
+        assert_eq!(world.force_field_push(Vec3::ZERO, 1.0), Vec3::ZERO);
+        assert!(world.remove_force_field(wind).is_some());
+    }
+
+    #[test]
+    fn test_fields_reach_only_their_radius() {
+        let field = ForceField::radial(Vec3::ZERO, 2.0, -4.0).with_falloff(Falloff::Constant);
+        assert_eq!(field.at(Vec3::new(0.0, 0.0, 1.0)), Vec3::new(0.0, 0.0, -4.0));
+        assert_eq!(field.at(Vec3::new(0.0, 0.0, 3.0)), Vec3::ZERO);
+        assert_eq!(ForceField::explosion(Vec3::ZERO, 2.0, 1.0).at(Vec3::ZERO), Vec3::Y);
+    }
+}
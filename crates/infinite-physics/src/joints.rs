@@ -0,0 +1,483 @@
+//! Hinged, sliding and chained bodies
+//!
+//! Builders for the joints structures are made of: a hinge for a door leaf
+//! or a drawbridge deck swinging on its frame, a slider for a portcullis or
+//! a lift running along a guide, and a chain of linked segments hanging
+//! from an anchor. Hinges and sliders join a dynamic body to a fixed frame
+//! at the anchor, and can be given travel limits and a motor that drives
+//! the body to a target angle or offset (or at a speed), which is how a
+//! door is swung open or a gate raised.
+
+use glam::{Quat, Vec3};
+use nalgebra::Unit;
+use rapier3d::prelude::*;
+
+use crate::PhysicsWorld;
+
+/// How a jointed body moves relative to its frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointKind {
+    /// Turns about the joint axis; positions are angles in radians
+    Hinge,
+    /// Slides along the joint axis; positions are offsets in metres
+    Slider,
+}
+
+impl JointKind {
+    /// The joint's free axis, in the joint frame
+    fn axis(self) -> JointAxis {
+        match self {
+            Self::Hinge => JointAxis::AngX,
+            Self::Slider => JointAxis::LinX,
+        }
+    }
+
+    fn locked_axes(self) -> JointAxesMask {
+        match self {
+            Self::Hinge => JointAxesMask::LOCKED_REVOLUTE_AXES,
+            Self::Slider => JointAxesMask::LOCKED_PRISMATIC_AXES,
+        }
+    }
+}
+
+/// A motor driving a hinge or slider. Stiffness pulls the joint towards
+/// `target_position`, damping towards `target_velocity`; both are
+/// accelerations, so the same motor moves a light and a heavy body alike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorConfig {
+    pub target_position: f32,
+    pub target_velocity: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+    /// Most force (or torque) the motor can apply
+    pub max_force: f32,
+}
+
+impl MotorConfig {
+    /// A motor that does nothing, leaving the joint free
+    pub const OFF: Self = Self {
+        target_position: 0.0,
+        target_velocity: 0.0,
+        stiffness: 0.0,
+        damping: 0.0,
+        max_force: f32::MAX,
+    };
+
+    /// Drive the joint to `target` and hold it there
+    pub fn position(target: f32, stiffness: f32, damping: f32) -> Self {
+        Self { target_position: target, stiffness, damping, ..Self::OFF }
+    }
+
+    /// Drive the joint at `speed` (radians or metres per second)
+    pub fn velocity(speed: f32, damping: f32) -> Self {
+        Self { target_velocity: speed, damping, ..Self::OFF }
+    }
+
+    pub fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = max_force.max(0.0);
+        self
+    }
+}
+
+/// Setup of a hinge or slider, in world space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointBuilder {
+    kind: JointKind,
+    anchor: Vec3,
+    axis: Vec3,
+    limits: Option<[f32; 2]>,
+    motor: Option<MotorConfig>,
+}
+
+impl JointBuilder {
+    /// A hinge at `anchor` turning about `axis`
+    pub fn hinge(anchor: Vec3, axis: Vec3) -> Self {
+        Self::new(JointKind::Hinge, anchor, axis)
+    }
+
+    /// A slider with its frame at `anchor`, running along `axis`
+    pub fn slider(anchor: Vec3, axis: Vec3) -> Self {
+        Self::new(JointKind::Slider, anchor, axis)
+    }
+
+    fn new(kind: JointKind, anchor: Vec3, axis: Vec3) -> Self {
+        Self {
+            kind,
+            anchor,
+            axis: axis.try_normalize().unwrap_or(Vec3::Y),
+            limits: None,
+            motor: None,
+        }
+    }
+
+    /// Keep the joint between `min` and `max` (radians or metres from where
+    /// the body was built)
+    pub fn limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some([min.min(max), max.max(min)]);
+        self
+    }
+
+    pub fn motor(mut self, motor: MotorConfig) -> Self {
+        self.motor = Some(motor);
+        self
+    }
+
+    pub fn kind(&self) -> JointKind {
+        self.kind
+    }
+}
+
+/// A dynamic body on a hinge or slider to a fixed frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointedBody {
+    pub kind: JointKind,
+    /// Fixed body at the joint anchor
+    pub frame: RigidBodyHandle,
+    pub body: RigidBodyHandle,
+    pub collider: ColliderHandle,
+    pub joint: ImpulseJointHandle,
+}
+
+/// Setup of a chain of segments from a fixed anchor, in world space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainBuilder {
+    start: Vec3,
+    end: Vec3,
+    links: usize,
+    radius: f32,
+    link_mass: f32,
+    end_body: Option<RigidBodyHandle>,
+}
+
+impl ChainBuilder {
+    /// A chain hanging from `start`, laid out straight to `end`
+    pub fn new(start: Vec3, end: Vec3) -> Self {
+        Self {
+            start,
+            end,
+            links: 8,
+            radius: 0.05,
+            link_mass: 0.5,
+            end_body: None,
+        }
+    }
+
+    /// Number of segments (at least one)
+    pub fn links(mut self, links: usize) -> Self {
+        self.links = links.max(1);
+        self
+    }
+
+    /// Thickness of each segment's collider
+    pub fn link_radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(0.01);
+        self
+    }
+
+    pub fn link_mass(mut self, mass: f32) -> Self {
+        self.link_mass = mass.max(0.01);
+        self
+    }
+
+    /// Fasten the far end of the chain to `body` (at `end`)
+    pub fn attach_end(mut self, body: RigidBodyHandle) -> Self {
+        self.end_body = Some(body);
+        self
+    }
+}
+
+/// A chain of segments joined end to end
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    /// Fixed body at the top of the chain
+    pub anchor_body: RigidBodyHandle,
+    /// Segments from the anchor out
+    pub links: Vec<RigidBodyHandle>,
+    /// Joints from the anchor out, then the two holding the end body (to the
+    /// last segment, and a rope from the anchor taking its weight)
+    pub joints: Vec<ImpulseJointHandle>,
+    /// Length of each segment
+    pub link_length: f32,
+}
+
+impl PhysicsWorld {
+    /// Add `body` with `collider` on a hinge or slider to a new fixed frame
+    /// at the builder's anchor. The joint starts at position 0.
+    pub fn add_jointed(&mut self, builder: &JointBuilder, body: RigidBody, collider: Collider) -> JointedBody {
+        let anchor = vector![builder.anchor.x, builder.anchor.y, builder.anchor.z];
+        let frame = self
+            .rigid_body_set
+            .insert(RigidBodyBuilder::fixed().translation(anchor).build());
+        let body_pose = *body.position();
+        let (body, collider) = self.add_dynamic_body(body, collider);
+
+        // Both joint frames start where the anchor is, with the joint's
+        // free axis along the builder's axis
+        let axis = Unit::new_normalize(vector![builder.axis.x, builder.axis.y, builder.axis.z]);
+        let orientation = GenericJoint::complete_ang_frame(axis);
+        let world_frame = Isometry::from_parts(anchor.into(), orientation);
+        let mut joint = GenericJointBuilder::new(builder.kind.locked_axes())
+            .local_frame1(Isometry::from_parts(Translation::identity(), orientation))
+            .local_frame2(body_pose.inverse() * world_frame)
+            .contacts_enabled(false)
+            .build();
+        let free = builder.kind.axis();
+        if let Some(limits) = builder.limits {
+            joint.set_limits(free, limits);
+        }
+        if let Some(motor) = builder.motor {
+            apply_motor(&mut joint, free, motor);
+        }
+        let joint = self.impulse_joint_set.insert(frame, body, joint, true);
+
+        JointedBody { kind: builder.kind, frame, body, collider, joint }
+    }
+
+    /// Remove a jointed body, its joint and its frame
+    pub fn remove_jointed(&mut self, jointed: &JointedBody) {
+        self.remove_rigid_body(jointed.body);
+        self.remove_rigid_body(jointed.frame);
+    }
+
+    /// Drive a hinge or slider with `motor`
+    pub fn set_joint_motor(&mut self, jointed: &JointedBody, motor: MotorConfig) {
+        if let Some(joint) = self.impulse_joint_set.get_mut(jointed.joint) {
+            apply_motor(&mut joint.data, jointed.kind.axis(), motor);
+        }
+        self.wake(jointed.body);
+    }
+
+    /// Change how far a hinge or slider can travel
+    pub fn set_joint_limits(&mut self, jointed: &JointedBody, min: f32, max: f32) {
+        if let Some(joint) = self.impulse_joint_set.get_mut(jointed.joint) {
+            joint.data.set_limits(jointed.kind.axis(), [min.min(max), max.max(min)]);
+        }
+        self.wake(jointed.body);
+    }
+
+    /// How far a hinge has turned (radians) or a slider has moved (metres)
+    /// from where it was built
+    pub fn joint_position(&self, jointed: &JointedBody) -> f32 {
+        let (Some(joint), Some(frame), Some(body)) = (
+            self.impulse_joint_set.get(jointed.joint),
+            self.rigid_body_set.get(jointed.frame),
+            self.rigid_body_set.get(jointed.body),
+        ) else {
+            return 0.0;
+        };
+        let relative = (frame.position() * joint.data.local_frame1).inverse() * (body.position() * joint.data.local_frame2);
+        match jointed.kind {
+            JointKind::Hinge => {
+                let rotation = relative.rotation;
+                let half = rotation.i.clamp(-1.0, 1.0).asin() * 2.0;
+                if rotation.w < 0.0 {
+                    -half
+                } else {
+                    half
+                }
+            }
+            JointKind::Slider => relative.translation.x,
+        }
+    }
+
+    /// World position and rotation of a rigid body (for drawing it)
+    pub fn body_pose(&self, handle: RigidBodyHandle) -> Option<(Vec3, Quat)> {
+        self.rigid_body_set.get(handle).map(|body| {
+            let (t, r) = (body.translation(), body.rotation());
+            (Vec3::new(t.x, t.y, t.z), Quat::from_xyzw(r.i, r.j, r.k, r.w))
+        })
+    }
+
+    /// Hang a chain from the builder's start. Segments are balls joined end
+    /// to end, so the chain bends freely but keeps its length.
+    pub fn add_chain(&mut self, builder: &ChainBuilder) -> Chain {
+        let along = builder.end - builder.start;
+        let direction = along.try_normalize().unwrap_or(Vec3::NEG_Y);
+        let link_length = (along.length() / builder.links as f32).max(builder.radius * 2.0);
+        let half = direction * (link_length * 0.5);
+        let half = vector![half.x, half.y, half.z];
+
+        let start = vector![builder.start.x, builder.start.y, builder.start.z];
+        let anchor_body = self
+            .rigid_body_set
+            .insert(RigidBodyBuilder::fixed().translation(start).build());
+        let mut links = Vec::with_capacity(builder.links);
+        let mut joints = Vec::with_capacity(builder.links + 1);
+        let mut previous = (anchor_body, Point::origin());
+        for i in 0..builder.links {
+            let center = builder.start + direction * (link_length * (i as f32 + 0.5));
+            let body = RigidBodyBuilder::dynamic()
+                .translation(vector![center.x, center.y, center.z])
+                .linear_damping(0.2)
+                .angular_damping(0.5)
+                .build();
+            let collider = ColliderBuilder::ball(builder.radius).mass(builder.link_mass).build();
+            let (link, _) = self.add_dynamic_body(body, collider);
+            let joint = SphericalJointBuilder::new()
+                .local_anchor1(previous.1)
+                .local_anchor2(Point::from(-half))
+                .contacts_enabled(false);
+            joints.push(self.impulse_joint_set.insert(previous.0, link, joint, true));
+            links.push(link);
+            previous = (link, Point::from(half));
+        }
+
+        if let Some(end_body) = builder.end_body.filter(|handle| self.rigid_body_set.contains(*handle)) {
+            let end = vector![builder.end.x, builder.end.y, builder.end.z];
+            let local = self.rigid_body_set[end_body].position().inverse_transform_point(&end.into());
+            let joint = SphericalJointBuilder::new()
+                .local_anchor1(previous.1)
+                .local_anchor2(local)
+                .contacts_enabled(false);
+            joints.push(self.impulse_joint_set.insert(previous.0, end_body, joint, true));
+            // Segment joints are too soft to carry a body much heavier than
+            // they are, so a rope from the anchor takes its weight and the
+            // segments only have to hang along it
+            let tether = RopeJointBuilder::new(link_length * builder.links as f32)
+                .local_anchor2(local)
+                .contacts_enabled(false);
+            joints.push(self.impulse_joint_set.insert(anchor_body, end_body, tether, true));
+        }
+
+        Chain { anchor_body, links, joints, link_length }
+    }
+
+    /// Remove a chain's segments and anchor (and its joint to the end body)
+    pub fn remove_chain(&mut self, chain: &Chain) {
+        for &link in &chain.links {
+            self.remove_rigid_body(link);
+        }
+        self.remove_rigid_body(chain.anchor_body);
+    }
+
+    /// Positions along a chain from its anchor to its last segment's end
+    /// (for drawing it)
+    pub fn chain_points(&self, chain: &Chain) -> Vec<Vec3> {
+        let mut points: Vec<Vec3> = self.body_pose(chain.anchor_body).map(|(p, _)| p).into_iter().collect();
+        points.extend(chain.links.iter().filter_map(|&link| self.body_pose(link).map(|(p, _)| p)));
+        points
+    }
+
+    fn wake(&mut self, handle: RigidBodyHandle) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            body.wake_up(true);
+        }
+    }
+}
+
+fn apply_motor(joint: &mut GenericJoint, axis: JointAxis, motor: MotorConfig) {
+    joint.set_motor(axis, motor.target_position, motor.target_velocity, motor.stiffness, motor.damping);
+    joint.set_motor_max_force(axis, motor.max_force);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(world: &mut PhysicsWorld, seconds: f32) {
+        for _ in 0..(seconds * 60.0) as usize {
+            world.step();
+        }
+    }
+
+    fn leaf(center: Vec3) -> (RigidBody, Collider) {
+        let body = RigidBodyBuilder::dynamic().translation(vector![center.x, center.y, center.z]).build();
+        (body, ColliderBuilder::cuboid(0.5, 1.0, 0.05).mass(20.0).build())
+    }
+
+    #[test]
+    fn test_hinge_motor_swings_within_limits() {
+        let mut world = PhysicsWorld::new();
+        let (body, collider) = leaf(Vec3::new(0.5, 1.0, 0.0));
+        let hinge = JointBuilder::hinge(Vec3::new(0.0, 1.0, 0.0), Vec3::Y)
+            .limits(0.0, 1.5)
+            .motor(MotorConfig::position(1.2, 40.0, 10.0));
+        let door = world.add_jointed(&hinge, body, collider);
+        assert!(world.joint_position(&door).abs() < 1e-4);
+
+        run(&mut world, 3.0);
+        assert!((world.joint_position(&door) - 1.2).abs() < 0.05, "swung to {}", world.joint_position(&door));
+        // The leaf turns about its edge, which stays put
+        let (center, _) = world.body_pose(door.body).unwrap();
+        assert!((center.with_y(0.0).length() - 0.5).abs() < 0.02);
+        assert!(center.z < -0.4);
+
+        // Driven past its limit, it stops there
+        world.set_joint_motor(&door, MotorConfig::position(3.0, 40.0, 10.0));
+        run(&mut world, 3.0);
+        assert!(world.joint_position(&door) < 1.55);
+        world.set_joint_motor(&door, MotorConfig::position(0.0, 40.0, 10.0));
+        run(&mut world, 3.0);
+        assert!(world.joint_position(&door).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_slider_lifts_against_gravity() {
+        let mut world = PhysicsWorld::new();
+        let (body, collider) = leaf(Vec3::new(0.0, 1.0, 0.0));
+        let slider = JointBuilder::slider(Vec3::new(0.0, 1.0, 0.0), Vec3::Y).limits(0.0, 2.0);
+        let gate = world.add_jointed(&slider, body, collider);
+
+        // Without a motor it rests on its lower limit
+        run(&mut world, 1.0);
+        assert!(world.joint_position(&gate).abs() < 0.02);
+
+        world.set_joint_motor(&gate, MotorConfig::position(2.0, 200.0, 30.0));
+        run(&mut world, 3.0);
+        let (center, rotation) = world.body_pose(gate.body).unwrap();
+        assert!((world.joint_position(&gate) - 2.0).abs() < 0.1);
+        assert!((center - Vec3::new(0.0, 3.0, 0.0)).length() < 0.1, "only moves along its guide");
+        assert!(rotation.angle_between(Quat::IDENTITY) < 0.01);
+
+        world.set_joint_limits(&gate, 0.0, 1.0);
+        run(&mut world, 2.0);
+        assert!(world.joint_position(&gate) < 1.05);
+
+        world.remove_jointed(&gate);
+        assert_eq!(world.rigid_body_set.len(), 0);
+        assert!(world.impulse_joint_set.is_empty());
+    }
+
+    #[test]
+    fn test_chain_keeps_its_length() {
+        let mut world = PhysicsWorld::new();
+        let start = Vec3::new(0.0, 10.0, 0.0);
+        // Laid out sideways, it swings down and hangs below the anchor
+        let chain = world.add_chain(&ChainBuilder::new(start, Vec3::new(4.0, 10.0, 0.0)).links(8));
+        assert_eq!(chain.links.len(), 8);
+        assert_eq!(chain.joints.len(), 8);
+        run(&mut world, 6.0);
+
+        let points = world.chain_points(&chain);
+        assert_eq!(points.len(), 9);
+        for pair in points.windows(2).skip(1) {
+            assert!((pair[0].distance(pair[1]) - chain.link_length).abs() < 0.05);
+        }
+        let last = *points.last().unwrap();
+        assert!(last.y < 7.0 && last.x.abs() < 1.5, "hangs down, at {last}");
+
+        world.remove_chain(&chain);
+        assert_eq!(world.rigid_body_set.len(), 0);
+    }
+
+    #[test]
+    fn test_chain_holds_an_end_body() {
+        let mut world = PhysicsWorld::new();
+        // A drawbridge deck hinged at the bank, held level by a chain from
+        // the gatehouse to its far end
+        let (body, collider) = (
+            RigidBodyBuilder::dynamic().translation(vector![2.0, 0.0, 0.0]).build(),
+            ColliderBuilder::cuboid(2.0, 0.1, 1.0).mass(200.0).build(),
+        );
+        let deck = world.add_jointed(&JointBuilder::hinge(Vec3::ZERO, Vec3::Z), body, collider);
+        let chain = world.add_chain(
+            &ChainBuilder::new(Vec3::new(0.0, 4.0, 0.0), Vec3::new(4.0, 0.0, 0.0))
+                .links(6)
+                .link_mass(5.0)
+                .attach_end(deck.body),
+        );
+        assert_eq!(chain.joints.len(), 8);
+        run(&mut world, 4.0);
+        assert!(world.joint_position(&deck).abs() < 0.2, "dropped to {}", world.joint_position(&deck));
+    }
+}
@@ -2,21 +2,46 @@
 //!
 //! Provides collision detection, rigid body dynamics, and character controllers.
 
+mod ccd;
 mod character_controller;
+mod force_field;
+mod joints;
+mod ownership;
+mod rope;
+mod snapshot;
 
+pub use ccd::CcdConfig;
 pub use character_controller::CharacterController;
+pub use force_field::{Falloff, FieldMode, FieldShape, ForceField, ForceFieldId};
+pub use joints::{Chain, ChainBuilder, JointBuilder, JointKind, JointedBody, MotorConfig};
+pub use ownership::{CastFilter, ColliderOwner, CollisionLayer, EntityId, FactionId};
+pub use rope::Rope;
+pub use snapshot::{PhysicsSnapshot, SnapshotError};
 
-use glam::Vec3;
+use std::num::NonZeroUsize;
+
+use glam::{Quat, Vec3};
 use nalgebra::Unit;
+use rapier3d::parry::query::{ShapeCastOptions, ShapeCastStatus};
 use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Collider `user_data` bit marking a surface as climbable regardless of its slope
+pub const CLIMBABLE_FLAG: u128 = 1;
+/// Collider `user_data` bit marking a surface the grappling hook can attach to
+pub const GRAPPLE_FLAG: u128 = 1 << 1;
 
 /// Physics world configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PhysicsConfig {
     /// Gravity vector (default: -9.81 on Y axis)
     pub gravity: Vec3,
     /// Physics timestep (default: 1/60)
     pub timestep: f32,
+    /// Pinned solver settings for reproducible stepping (replays,
+    /// rollback). `None` leaves rapier's defaults.
+    #[serde(default)]
+    pub determinism: Option<DeterminismConfig>,
 }
 
 impl Default for PhysicsConfig {
@@ -24,10 +49,54 @@ impl Default for PhysicsConfig {
         Self {
             gravity: Vec3::new(0.0, -9.81, 0.0),
             timestep: 1.0 / 60.0,
+            determinism: None,
+        }
+    }
+}
+
+/// Solver work pinned per step, so two worlds built and stepped the same
+/// way end up bit-identical. Stepping is single-threaded and visits pairs
+/// in the order colliders were added; restoring a [`PhysicsSnapshot`]
+/// brings back the broad and narrow phases as they were rather than
+/// rebuilding them, so a restored world keeps that order too. Bit-exact
+/// results across different CPUs also need the crate's `deterministic`
+/// feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeterminismConfig {
+    /// Velocity solver iterations per step
+    pub solver_iterations: usize,
+    /// Extra friction iterations per step
+    pub friction_iterations: usize,
+    /// Inner solver (PGS) iterations per solver iteration
+    pub pgs_iterations: usize,
+    /// Position stabilization iterations
+    pub stabilization_iterations: usize,
+    /// Substeps continuous collision may take per step
+    pub ccd_substeps: usize,
+}
+
+impl Default for DeterminismConfig {
+    fn default() -> Self {
+        Self {
+            solver_iterations: 4,
+            friction_iterations: 0,
+            pgs_iterations: 1,
+            stabilization_iterations: 2,
+            ccd_substeps: 1,
         }
     }
 }
 
+impl DeterminismConfig {
+    fn apply(&self, parameters: &mut IntegrationParameters) {
+        parameters.num_solver_iterations = NonZeroUsize::new(self.solver_iterations).unwrap_or(NonZeroUsize::MIN);
+        parameters.num_additional_friction_iterations = self.friction_iterations;
+        parameters.num_internal_pgs_iterations = self.pgs_iterations;
+        parameters.num_internal_stabilization_iterations = self.stabilization_iterations;
+        parameters.max_ccd_substeps = self.ccd_substeps;
+    }
+}
+
 /// The main physics world containing all simulation state
 pub struct PhysicsWorld {
     /// Configuration
@@ -56,6 +125,8 @@ pub struct PhysicsWorld {
     ccd_solver: CCDSolver,
     /// Query pipeline for raycasts and shape casts
     query_pipeline: QueryPipeline,
+    /// Explosions, updrafts and wind
+    force_fields: force_field::ForceFields,
 }
 
 impl PhysicsWorld {
@@ -66,8 +137,13 @@ impl PhysicsWorld {
 
     /// Create a new physics world with custom configuration
     pub fn with_config(config: PhysicsConfig) -> Self {
-        let mut integration_parameters = IntegrationParameters::default();
-        integration_parameters.dt = config.timestep;
+        let mut integration_parameters = IntegrationParameters {
+            dt: config.timestep,
+            ..Default::default()
+        };
+        if let Some(determinism) = &config.determinism {
+            determinism.apply(&mut integration_parameters);
+        }
 
         Self {
             config,
@@ -82,12 +158,14 @@ impl PhysicsWorld {
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
             query_pipeline: QueryPipeline::new(),
+            force_fields: Default::default(),
         }
     }
 
     /// Step the physics simulation
     pub fn step(&mut self) {
         let gravity = vector![self.config.gravity.x, self.config.gravity.y, self.config.gravity.z];
+        self.apply_force_fields();
 
         self.physics_pipeline.step(
             &gravity,
@@ -180,7 +258,8 @@ impl PhysicsWorld {
         self.collider_set.get(handle)
     }
 
-    /// Cast a ray and return the first hit
+    /// Cast a ray and return the first hit. Sensors are passed through
+    /// (see `raycast_with_sensors`); the same goes for the other casts.
     pub fn raycast(
         &self,
         origin: Vec3,
@@ -193,9 +272,26 @@ impl PhysicsWorld {
             vector![direction.x, direction.y, direction.z],
         );
 
+        self.query_pipeline
+            .cast_ray(&self.rigid_body_set, &self.collider_set, &ray, max_distance, true, filter.exclude_sensors())
+    }
+
+    /// Cast a ray and return the first hit, which may be a sensor volume
+    /// (e.g. an interaction target) as well as a solid
+    pub fn raycast_with_sensors(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, f32)> {
+        let ray = Ray::new(
+            point![origin.x, origin.y, origin.z],
+            vector![direction.x, direction.y, direction.z],
+        );
+
         self.query_pipeline
             .cast_ray(&self.rigid_body_set, &self.collider_set, &ray, max_distance, true, filter)
-            .map(|(handle, toi)| (handle, toi))
     }
 
     /// Cast a ray and get detailed hit information
@@ -212,7 +308,7 @@ impl PhysicsWorld {
         );
 
         self.query_pipeline
-            .cast_ray_and_get_normal(&self.rigid_body_set, &self.collider_set, &ray, max_distance, true, filter)
+            .cast_ray_and_get_normal(&self.rigid_body_set, &self.collider_set, &ray, max_distance, true, filter.exclude_sensors())
             .map(|(handle, intersection)| RaycastHit {
                 collider: handle,
                 distance: intersection.time_of_impact,
@@ -229,6 +325,166 @@ impl PhysicsWorld {
             })
     }
 
+    /// Sweep a shape from `origin`, turned by `rotation`, along a direction
+    /// and return the first hit. A shape that starts out overlapping
+    /// something hits it at distance zero.
+    pub fn cast_shape(
+        &self,
+        shape: CastShape,
+        origin: Vec3,
+        rotation: Quat,
+        direction: Vec3,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Option<ShapeHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return None;
+        }
+        let axis_angle = rotation.to_scaled_axis();
+        let shape_pos = Isometry::new(vector![origin.x, origin.y, origin.z], vector![axis_angle.x, axis_angle.y, axis_angle.z]);
+        let shape_vel = vector![direction.x, direction.y, direction.z];
+        let options = ShapeCastOptions::with_max_time_of_impact(max_distance);
+        let filter = filter.exclude_sensors();
+
+        let (handle, hit) = match shape {
+            CastShape::Sphere { radius } => self.query_pipeline.cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &shape_pos,
+                &shape_vel,
+                &Ball::new(radius),
+                options,
+                filter,
+            ),
+            CastShape::Capsule { half_height, radius } => self.query_pipeline.cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &shape_pos,
+                &shape_vel,
+                &Capsule::new_y(half_height, radius),
+                options,
+                filter,
+            ),
+            CastShape::Cuboid { half_extents } => self.query_pipeline.cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &shape_pos,
+                &shape_vel,
+                &Cuboid::new(vector![half_extents.x, half_extents.y, half_extents.z]),
+                options,
+                filter,
+            ),
+        }?;
+        Some(ShapeHit {
+            collider: handle,
+            distance: hit.time_of_impact,
+            point: Vec3::new(hit.witness1.x, hit.witness1.y, hit.witness1.z),
+            normal: Vec3::new(hit.normal1.x, hit.normal1.y, hit.normal1.z),
+            position: origin + direction * hit.time_of_impact,
+            penetrating: hit.status == ShapeCastStatus::PenetratingOrWithinTargetDist,
+        })
+    }
+
+    /// Cast a sphere along a direction and return the first hit
+    pub fn cast_sphere(
+        &self,
+        origin: Vec3,
+        radius: f32,
+        direction: Vec3,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Option<ShapeHit> {
+        self.cast_shape(CastShape::Sphere { radius }, origin, Quat::IDENTITY, direction, max_distance, filter)
+    }
+
+    /// Cast a capsule, upright before `rotation`, along a direction and
+    /// return the first hit
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_capsule(
+        &self,
+        origin: Vec3,
+        rotation: Quat,
+        half_height: f32,
+        radius: f32,
+        direction: Vec3,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Option<ShapeHit> {
+        self.cast_shape(CastShape::Capsule { half_height, radius }, origin, rotation, direction, max_distance, filter)
+    }
+
+    /// Cast a box turned by `rotation` along a direction and return the
+    /// first hit
+    pub fn cast_box(
+        &self,
+        origin: Vec3,
+        rotation: Quat,
+        half_extents: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Option<ShapeHit> {
+        self.cast_shape(CastShape::Cuboid { half_extents }, origin, rotation, direction, max_distance, filter)
+    }
+
+    /// Set or clear a surface tag bit (e.g. `CLIMBABLE_FLAG`) on a collider
+    pub fn set_surface_flag(&mut self, handle: ColliderHandle, flag: u128, enabled: bool) {
+        if let Some(collider) = self.collider_set.get_mut(handle) {
+            if enabled {
+                collider.user_data |= flag;
+            } else {
+                collider.user_data &= !flag;
+            }
+        }
+    }
+
+    /// Check whether a collider carries a surface tag bit
+    pub fn has_surface_flag(&self, handle: ColliderHandle, flag: u128) -> bool {
+        self.collider_set
+            .get(handle)
+            .is_some_and(|c| c.user_data & flag != 0)
+    }
+
+    /// Register a collider as belonging to an entity, for `CastFilter`s to
+    /// tell apart. Surface flags are kept.
+    pub fn set_owner(&mut self, handle: ColliderHandle, owner: ColliderOwner) {
+        if let Some(collider) = self.collider_set.get_mut(handle) {
+            collider.user_data = owner.pack(collider.user_data);
+        }
+    }
+
+    /// Return a collider to the unowned world layer
+    pub fn clear_owner(&mut self, handle: ColliderHandle) {
+        if let Some(collider) = self.collider_set.get_mut(handle) {
+            collider.user_data = ownership::clear_owner(collider.user_data);
+        }
+    }
+
+    /// Who a collider was registered to, if anyone
+    pub fn owner(&self, handle: ColliderHandle) -> Option<ColliderOwner> {
+        self.collider_set.get(handle).and_then(|c| ColliderOwner::unpack(c.user_data))
+    }
+
+    /// Every collider registered to `entity`
+    pub fn owned_colliders(&self, entity: EntityId) -> Vec<ColliderHandle> {
+        self.collider_set
+            .iter()
+            .filter(|(_, c)| ColliderOwner::unpack(c.user_data).is_some_and(|owner| owner.entity == entity))
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
+    /// Tag or untag a collider as climbable
+    pub fn set_climbable(&mut self, handle: ColliderHandle, climbable: bool) {
+        self.set_surface_flag(handle, CLIMBABLE_FLAG, climbable);
+    }
+
+    /// Check whether a collider is tagged as climbable
+    pub fn is_climbable(&self, handle: ColliderHandle) -> bool {
+        self.has_surface_flag(handle, CLIMBABLE_FLAG)
+    }
+
     /// Create a ground plane collider
     pub fn create_ground(&mut self, y: f32) -> ColliderHandle {
         let normal = Unit::new_normalize(vector![0.0, 1.0, 0.0]);
@@ -280,19 +536,25 @@ impl PhysicsWorld {
         scale: Vec3,
         position: Vec3,
     ) -> ColliderHandle {
+        let collider = Self::heightfield_collider_at(heights, nrows, ncols, scale, position);
+        self.add_static_collider(collider)
+    }
+
+    /// Build the collider `create_heightfield_at` adds, without adding it.
+    /// The heightfield shape is shared between clones, so a kept copy can be
+    /// added again later without rebuilding it.
+    pub fn heightfield_collider_at(heights: &[f32], nrows: usize, ncols: usize, scale: Vec3, position: Vec3) -> Collider {
         use nalgebra::DMatrix;
 
         let matrix = DMatrix::from_fn(nrows, ncols, |r, c| {
             heights[r * ncols + c]
         });
 
-        let collider = ColliderBuilder::heightfield(matrix, vector![scale.x, scale.y, scale.z])
+        ColliderBuilder::heightfield(matrix, vector![scale.x, scale.y, scale.z])
             .translation(vector![position.x, position.y, position.z])
             .friction(0.7)
             .restitution(0.0)
-            .build();
-
-        self.add_static_collider(collider)
+            .build()
     }
 
     /// Create a static box collider
@@ -303,6 +565,27 @@ impl PhysicsWorld {
             .build();
         self.add_static_collider(collider)
     }
+
+    /// Create a static box collider turned by `rotation`
+    pub fn create_static_box_rotated(&mut self, half_extents: Vec3, position: Vec3, rotation: Quat) -> ColliderHandle {
+        let axis_angle = rotation.to_scaled_axis();
+        let collider = ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            .translation(vector![position.x, position.y, position.z])
+            .rotation(vector![axis_angle.x, axis_angle.y, axis_angle.z])
+            .friction(0.7)
+            .build();
+        self.add_static_collider(collider)
+    }
+
+    /// Create a static sensor box: nothing collides with it and only
+    /// `raycast_with_sensors` hits it
+    pub fn create_sensor_box(&mut self, half_extents: Vec3, position: Vec3) -> ColliderHandle {
+        let collider = ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            .translation(vector![position.x, position.y, position.z])
+            .sensor(true)
+            .build();
+        self.add_static_collider(collider)
+    }
 }
 
 impl Default for PhysicsWorld {
@@ -324,6 +607,33 @@ pub struct RaycastHit {
     pub normal: Vec3,
 }
 
+/// Volume swept by [`PhysicsWorld::cast_shape`], centred on the cast origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CastShape {
+    Sphere { radius: f32 },
+    /// Capsule along its local Y axis, `half_height` from the middle to
+    /// each cap's centre
+    Capsule { half_height: f32, radius: f32 },
+    Cuboid { half_extents: Vec3 },
+}
+
+/// First hit of a shape cast
+#[derive(Debug, Clone)]
+pub struct ShapeHit {
+    /// The collider that was hit
+    pub collider: ColliderHandle,
+    /// Distance the shape travelled before touching
+    pub distance: f32,
+    /// World-space contact point on the hit collider
+    pub point: Vec3,
+    /// Surface normal of the hit collider at the contact
+    pub normal: Vec3,
+    /// Where the shape's origin is when it touches
+    pub position: Vec3,
+    /// The shape already overlapped the collider where it started
+    pub penetrating: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -355,4 +665,107 @@ mod tests {
         );
         assert!(hit.is_some());
     }
+
+    #[test]
+    fn test_sphere_cast_against_wall() {
+        let mut world = PhysicsWorld::new();
+        let wall = world.create_static_box(Vec3::new(2.0, 2.0, 0.25), Vec3::new(0.0, 2.0, -3.0));
+        world.set_climbable(wall, true);
+        world.update_query_pipeline();
+
+        let hit = world
+            .cast_sphere(Vec3::new(0.0, 1.0, 0.0), 0.3, Vec3::NEG_Z, 5.0, QueryFilter::default())
+            .expect("sphere should hit the wall");
+        assert_eq!(hit.collider, wall);
+        // Sphere stops with its surface touching the wall face at z = -2.75
+        assert!((hit.distance - 2.45).abs() < 0.01);
+        assert!((hit.point.z + 2.75).abs() < 0.01);
+        assert!(hit.normal.z > 0.99);
+        assert!((hit.position.z + 2.45).abs() < 0.01);
+        assert!(!hit.penetrating);
+        assert!(world.is_climbable(wall));
+
+        world.set_climbable(wall, false);
+        assert!(!world.is_climbable(wall));
+    }
+
+    #[test]
+    fn test_rotated_capsule_and_box_sweeps() {
+        let mut world = PhysicsWorld::new();
+        world.create_ground(0.0);
+        let wall = world.create_static_box(Vec3::new(2.0, 2.0, 0.25), Vec3::new(0.0, 2.0, -3.0));
+        world.update_query_pipeline();
+        let filter = QueryFilter::default;
+        let quarter = std::f32::consts::FRAC_PI_2;
+
+        // Upright the capsule's lower cap is 0.8 below its middle; lying down, 0.3
+        let drop = |rotation| world.cast_capsule(Vec3::new(0.0, 3.0, 5.0), rotation, 0.5, 0.3, Vec3::NEG_Y, 10.0, filter());
+        assert!((drop(Quat::IDENTITY).unwrap().distance - 2.2).abs() < 0.01);
+        let lying = drop(Quat::from_rotation_x(quarter)).unwrap();
+        assert!((lying.distance - 2.7).abs() < 0.01);
+        assert!((lying.position.y - 0.3).abs() < 0.01);
+        assert!(lying.normal.y > 0.99);
+
+        // A plank pointing at the wall reaches it a metre early; turned sideways, 0.1
+        let plank = |rotation| world.cast_box(Vec3::new(0.0, 1.0, 0.0), rotation, Vec3::new(0.1, 0.1, 1.0), Vec3::NEG_Z, 5.0, filter());
+        let end_on = plank(Quat::IDENTITY).unwrap();
+        assert_eq!(end_on.collider, wall);
+        assert!((end_on.distance - 1.75).abs() < 0.01);
+        assert!((plank(Quat::from_rotation_y(quarter)).unwrap().distance - 2.65).abs() < 0.01);
+
+        let inside = world.cast_sphere(Vec3::new(0.0, 1.0, -3.0), 0.2, Vec3::Z, 5.0, filter()).unwrap();
+        assert!(inside.penetrating);
+        assert_eq!(inside.distance, 0.0);
+        assert!(world.cast_sphere(Vec3::new(0.0, 1.0, 0.0), 0.2, Vec3::ZERO, 5.0, filter()).is_none());
+    }
+
+    #[test]
+    fn test_sensors_only_stop_sensor_rays() {
+        let mut world = PhysicsWorld::new();
+        let sensor = world.create_sensor_box(Vec3::splat(0.3), Vec3::new(0.0, 1.0, -2.0));
+        let wall = world.create_static_box(Vec3::new(2.0, 2.0, 0.25), Vec3::new(0.0, 2.0, -5.0));
+        world.update_query_pipeline();
+
+        let origin = Vec3::new(0.0, 1.0, 0.0);
+        let hit = world.raycast(origin, Vec3::NEG_Z, 10.0, QueryFilter::default());
+        assert_eq!(hit.map(|(handle, _)| handle), Some(wall));
+        let hit = world.raycast_with_sensors(origin, Vec3::NEG_Z, 10.0, QueryFilter::default());
+        assert_eq!(hit.map(|(handle, _)| handle), Some(sensor));
+    }
+
+    #[test]
+    fn test_cast_filters_skip_owned_colliders() {
+        let mut world = PhysicsWorld::new();
+        let box_at = |world: &mut PhysicsWorld, z: f32| world.create_static_box(Vec3::splat(0.5), Vec3::new(0.0, 1.0, z));
+        let player = box_at(&mut world, -2.0);
+        let guard = box_at(&mut world, -4.0);
+        let crate_box = box_at(&mut world, -6.0);
+        let wall = box_at(&mut world, -8.0);
+        world.set_climbable(player, true);
+        world.set_owner(player, ColliderOwner::new(EntityId::PLAYER, CollisionLayer::PLAYER));
+        world.set_owner(guard, ColliderOwner::new(EntityId(7), CollisionLayer::NPC).with_faction(FactionId(2)));
+        world.set_owner(crate_box, ColliderOwner::new(EntityId(8), CollisionLayer::PROP));
+        world.update_query_pipeline();
+        assert!(world.is_climbable(player), "surface flags survive the owner");
+        assert_eq!(world.owner(guard).and_then(|o| o.faction), Some(FactionId(2)));
+        assert_eq!(world.owned_colliders(EntityId::PLAYER), vec![player]);
+
+        let first_hit = |world: &PhysicsWorld, filter: &CastFilter| {
+            world.raycast(Vec3::new(0.0, 1.0, 0.0), Vec3::NEG_Z, 20.0, filter.query()).map(|(handle, _)| handle)
+        };
+        assert_eq!(first_hit(&world, &CastFilter::new()), Some(player));
+        assert_eq!(first_hit(&world, &CastFilter::excluding_entity(EntityId::PLAYER)), Some(guard));
+        assert_eq!(first_hit(&world, &CastFilter::excluding_entity(EntityId::PLAYER).and_faction(FactionId(2))), Some(crate_box));
+        assert_eq!(
+            first_hit(&world, &CastFilter::excluding_layer(CollisionLayer::PLAYER).and_layer(CollisionLayer::NPC).and_layer(CollisionLayer::PROP)),
+            Some(wall),
+        );
+        assert_eq!(first_hit(&world, &CastFilter::excluding_faction(FactionId(2))), Some(player));
+
+        world.clear_owner(player);
+        assert!(world.owner(player).is_none());
+        assert!(world.is_climbable(player));
+        assert_eq!(first_hit(&world, &CastFilter::excluding_entity(EntityId::PLAYER)), Some(player));
+        assert_eq!(first_hit(&world, &CastFilter::excluding_layer(CollisionLayer::WORLD)), Some(guard));
+    }
 }
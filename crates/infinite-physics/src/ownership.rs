@@ -0,0 +1,191 @@
+//! Which game entity a collider belongs to, and casts that skip them
+//!
+//! A collider can be registered as owned by a logical entity (the player,
+//! an NPC) with a faction and a collision layer. The owner is packed into
+//! the collider's `user_data` above the surface flag bits, so a
+//! [`CastFilter`] can pass over an entity's own colliders, a whole faction
+//! or a layer without the caller building rapier filters by hand.
+
+use rapier3d::prelude::{Collider, ColliderHandle, QueryFilter};
+
+/// Bits of `user_data` left to the surface flags (`CLIMBABLE_FLAG` and co.)
+const FLAG_BITS: u128 = 0xFFFF;
+const LAYER_SHIFT: u32 = 16;
+const FACTION_SHIFT: u32 = 32;
+const ENTITY_SHIFT: u32 = 64;
+
+/// A logical thing in the game that may own several colliders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(pub u64);
+
+impl EntityId {
+    /// The local player
+    pub const PLAYER: Self = Self(0);
+}
+
+/// Side an entity is on, for casts that should ignore a whole side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FactionId(pub u16);
+
+/// Broad category of collider. Colliders nobody registered are in
+/// [`CollisionLayer::WORLD`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CollisionLayer(u16);
+
+impl CollisionLayer {
+    pub const WORLD: Self = Self(0);
+    pub const PLAYER: Self = Self(1);
+    pub const NPC: Self = Self(1 << 1);
+    pub const PROP: Self = Self(1 << 2);
+    pub const PROJECTILE: Self = Self(1 << 3);
+}
+
+/// Who a collider belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColliderOwner {
+    pub entity: EntityId,
+    pub faction: Option<FactionId>,
+    pub layer: CollisionLayer,
+}
+
+impl ColliderOwner {
+    pub fn new(entity: EntityId, layer: CollisionLayer) -> Self {
+        Self { entity, faction: None, layer }
+    }
+
+    pub fn with_faction(mut self, faction: FactionId) -> Self {
+        self.faction = Some(faction);
+        self
+    }
+
+    /// `user_data` with this owner in place of any earlier one, keeping
+    /// the surface flags
+    pub(crate) fn pack(self, user_data: u128) -> u128 {
+        let faction = self.faction.map_or(0, |f| f.0 as u128 + 1);
+        (user_data & FLAG_BITS)
+            | (self.layer.0 as u128) << LAYER_SHIFT
+            | faction << FACTION_SHIFT
+            | (self.entity.0 as u128 + 1) << ENTITY_SHIFT
+    }
+
+    pub(crate) fn unpack(user_data: u128) -> Option<Self> {
+        let entity = (user_data >> ENTITY_SHIFT) as u64;
+        if entity == 0 {
+            return None;
+        }
+        let faction = (user_data >> FACTION_SHIFT) as u16;
+        Some(Self {
+            entity: EntityId(entity - 1),
+            faction: (faction != 0).then(|| FactionId(faction - 1)),
+            layer: CollisionLayer((user_data >> LAYER_SHIFT) as u16),
+        })
+    }
+}
+
+/// What the owner bits of a collider's `user_data` are cleared to
+pub(crate) fn clear_owner(user_data: u128) -> u128 {
+    user_data & FLAG_BITS
+}
+
+/// Entities, factions and layers a cast passes through
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Exclusions {
+    entities: Vec<EntityId>,
+    factions: Vec<FactionId>,
+    layers: Vec<CollisionLayer>,
+}
+
+impl Exclusions {
+    fn hits(&self, collider: &Collider) -> bool {
+        let Some(owner) = ColliderOwner::unpack(collider.user_data) else {
+            return !self.layers.contains(&CollisionLayer::WORLD);
+        };
+        !self.entities.contains(&owner.entity)
+            && !owner.faction.is_some_and(|f| self.factions.contains(&f))
+            && !self.layers.contains(&owner.layer)
+    }
+}
+
+type Predicate = Box<dyn Fn(ColliderHandle, &Collider) -> bool + Send + Sync>;
+
+/// Filter for the world's ray and shape casts that skips colliders by
+/// owner. Build it once and pass `query()` to the cast:
+///
+/// ```ignore
+/// let filter = CastFilter::excluding_entity(EntityId::PLAYER);
+/// physics.raycast(eye, forward, 50.0, filter.query());
+/// ```
+pub struct CastFilter {
+    exclusions: Exclusions,
+    predicate: Predicate,
+}
+
+impl CastFilter {
+    /// A filter that skips nothing
+    pub fn new() -> Self {
+        Self::from_exclusions(Exclusions::default())
+    }
+
+    /// Skip every collider `entity` owns
+    pub fn excluding_entity(entity: EntityId) -> Self {
+        Self::new().and_entity(entity)
+    }
+
+    /// Skip every collider owned by a member of `faction`
+    pub fn excluding_faction(faction: FactionId) -> Self {
+        Self::new().and_faction(faction)
+    }
+
+    /// Skip every collider in `layer`
+    pub fn excluding_layer(layer: CollisionLayer) -> Self {
+        Self::new().and_layer(layer)
+    }
+
+    pub fn and_entity(self, entity: EntityId) -> Self {
+        let mut exclusions = self.exclusions;
+        exclusions.entities.push(entity);
+        Self::from_exclusions(exclusions)
+    }
+
+    pub fn and_faction(self, faction: FactionId) -> Self {
+        let mut exclusions = self.exclusions;
+        exclusions.factions.push(faction);
+        Self::from_exclusions(exclusions)
+    }
+
+    pub fn and_layer(self, layer: CollisionLayer) -> Self {
+        let mut exclusions = self.exclusions;
+        exclusions.layers.push(layer);
+        Self::from_exclusions(exclusions)
+    }
+
+    /// Whether a cast with this filter can hit `collider`
+    pub fn hits(&self, collider: &Collider) -> bool {
+        self.exclusions.hits(collider)
+    }
+
+    /// The rapier filter to pass to a cast, borrowing this one
+    pub fn query(&self) -> QueryFilter<'_> {
+        QueryFilter::default().predicate(&self.predicate)
+    }
+
+    fn from_exclusions(exclusions: Exclusions) -> Self {
+        let captured = exclusions.clone();
+        Self {
+            exclusions,
+            predicate: Box::new(move |_, collider| captured.hits(collider)),
+        }
+    }
+}
+
+impl Default for CastFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for CastFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CastFilter").field("exclusions", &self.exclusions).finish()
+    }
+}
@@ -0,0 +1,173 @@
+//! Rope joints between a fixed anchor and a dynamic bob
+//!
+//! Used by the grappling hook: the bob is a collider-less dynamic body that
+//! the simulation swings on a rope joint, and the character controller
+//! follows it with collision.
+
+use glam::Vec3;
+use rapier3d::prelude::*;
+
+use crate::PhysicsWorld;
+
+/// A rope attached between a fixed anchor and a swinging bob body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rope {
+    /// Fixed body at the anchor point
+    pub anchor_body: RigidBodyHandle,
+    /// Dynamic body hanging from the rope
+    pub bob: RigidBodyHandle,
+    /// The rope joint
+    pub joint: ImpulseJointHandle,
+    /// World-space anchor position
+    pub anchor: Vec3,
+}
+
+impl PhysicsWorld {
+    /// Hang a bob of `mass` at `position` from `anchor` with a rope of the
+    /// current distance, starting with `velocity`
+    pub fn attach_rope(&mut self, anchor: Vec3, position: Vec3, velocity: Vec3, mass: f32) -> Rope {
+        let anchor_body = self.rigid_body_set.insert(
+            RigidBodyBuilder::fixed()
+                .translation(vector![anchor.x, anchor.y, anchor.z])
+                .build(),
+        );
+        let bob = self.rigid_body_set.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(vector![position.x, position.y, position.z])
+                .linvel(vector![velocity.x, velocity.y, velocity.z])
+                .additional_mass(mass.max(0.1))
+                .lock_rotations()
+                .linear_damping(0.05)
+                .build(),
+        );
+
+        let length = (position - anchor).length().max(0.1);
+        let joint = self.impulse_joint_set.insert(
+            anchor_body,
+            bob,
+            RopeJointBuilder::new(length).build(),
+            true,
+        );
+
+        Rope {
+            anchor_body,
+            bob,
+            joint,
+            anchor,
+        }
+    }
+
+    /// Remove a rope and its bodies
+    pub fn remove_rope(&mut self, rope: &Rope) {
+        self.impulse_joint_set.remove(rope.joint, false);
+        self.remove_rigid_body(rope.bob);
+        self.remove_rigid_body(rope.anchor_body);
+    }
+
+    /// Set the maximum rope length (reeling in or out)
+    pub fn set_rope_length(&mut self, rope: &Rope, length: f32) {
+        if let Some(joint) = self.impulse_joint_set.get_mut(rope.joint) {
+            joint.data.set_limits(JointAxis::LinX, [0.0, length.max(0.1)]);
+        }
+        if let Some(bob) = self.rigid_body_set.get_mut(rope.bob) {
+            bob.wake_up(true);
+        }
+    }
+
+    /// Current maximum rope length
+    pub fn rope_length(&self, rope: &Rope) -> f32 {
+        self.impulse_joint_set
+            .get(rope.joint)
+            .and_then(|j| j.data.limits(JointAxis::LinX))
+            .map(|l| l.max)
+            .unwrap_or(0.0)
+    }
+
+    /// Position of the bob
+    pub fn rope_bob_position(&self, rope: &Rope) -> Vec3 {
+        self.rigid_body_set
+            .get(rope.bob)
+            .map(|b| {
+                let t = b.translation();
+                Vec3::new(t.x, t.y, t.z)
+            })
+            .unwrap_or(rope.anchor)
+    }
+
+    /// Velocity of the bob
+    pub fn rope_bob_velocity(&self, rope: &Rope) -> Vec3 {
+        self.rigid_body_set
+            .get(rope.bob)
+            .map(|b| {
+                let v = b.linvel();
+                Vec3::new(v.x, v.y, v.z)
+            })
+            .unwrap_or(Vec3::ZERO)
+    }
+
+    /// Move the bob (e.g. when the character following it is blocked)
+    pub fn set_rope_bob(&mut self, rope: &Rope, position: Vec3, velocity: Vec3) {
+        if let Some(bob) = self.rigid_body_set.get_mut(rope.bob) {
+            bob.set_translation(vector![position.x, position.y, position.z], true);
+            bob.set_linvel(vector![velocity.x, velocity.y, velocity.z], true);
+        }
+    }
+
+    /// Push the bob (swinging)
+    pub fn apply_rope_impulse(&mut self, rope: &Rope, impulse: Vec3) {
+        if let Some(bob) = self.rigid_body_set.get_mut(rope.bob) {
+            bob.apply_impulse(vector![impulse.x, impulse.y, impulse.z], true);
+        }
+    }
+
+    /// Rope tension in newtons: the pull needed to keep the bob on a taut
+    /// rope against gravity and its swing (zero while slack)
+    pub fn rope_tension(&self, rope: &Rope) -> f32 {
+        let Some(bob) = self.rigid_body_set.get(rope.bob) else {
+            return 0.0;
+        };
+        let length = self.rope_length(rope);
+        let offset = rope.anchor - self.rope_bob_position(rope);
+        let distance = offset.length();
+        if distance < 0.01 || distance < length - 0.05 {
+            return 0.0;
+        }
+
+        let to_anchor = offset / distance;
+        let velocity = self.rope_bob_velocity(rope);
+        let tangential = velocity - to_anchor * velocity.dot(to_anchor);
+        let centripetal = tangential.length_squared() / distance;
+        let gravity_pull = -self.config.gravity.dot(to_anchor);
+        (bob.mass() * (centripetal + gravity_pull)).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rope_swings_within_length() {
+        let mut world = PhysicsWorld::new();
+        let anchor = Vec3::new(0.0, 10.0, 0.0);
+        let rope = world.attach_rope(anchor, Vec3::new(5.0, 10.0, 0.0), Vec3::ZERO, 80.0);
+        assert!((world.rope_length(&rope) - 5.0).abs() < 1e-4);
+
+        let mut max_tension: f32 = 0.0;
+        for _ in 0..60 {
+            world.step();
+            let dist = (world.rope_bob_position(&rope) - anchor).length();
+            assert!(dist < 5.2, "bob stretched the rope to {}", dist);
+            max_tension = max_tension.max(world.rope_tension(&rope));
+        }
+        // Swung down under the anchor, pulling on the rope
+        assert!(world.rope_bob_position(&rope).x < 4.0);
+        assert!(max_tension > 80.0 * 9.81 * 0.5);
+
+        world.set_rope_length(&rope, 3.0);
+        assert!((world.rope_length(&rope) - 3.0).abs() < 1e-4);
+
+        world.remove_rope(&rope);
+        assert!(world.get_rigid_body(rope.bob).is_none());
+    }
+}
@@ -0,0 +1,225 @@
+//! Whole-world snapshots
+//!
+//! A [`PhysicsSnapshot`] holds everything a step reads and writes: every
+//! rigid body with its velocity and sleep state, the colliders, joints and
+//! islands, the broad and narrow phases with their contact caches, and
+//! the force fields.
+//! Stepping a restored world gives exactly what the original gave from the
+//! same point, which is what replays, rollback experiments and exact save
+//! restoration need. Handles taken before a snapshot stay valid after
+//! restoring it.
+//!
+//! [`PhysicsSnapshot::to_bytes`] writes a snapshot for saves or the
+//! network. Rapier's state holds non-finite floats (unbounded AABBs, the
+//! infinite mass of fixed bodies) and map keys that aren't strings, which
+//! JSON can't carry, so the state is written with bincode behind a small
+//! header:
+//!
+//! ```text
+//! magic "IPHS" | version: u16 | state
+//! ```
+
+use rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::force_field::ForceFields;
+use crate::{PhysicsConfig, PhysicsWorld};
+
+const MAGIC: &[u8; 4] = b"IPHS";
+const VERSION: u16 = 3;
+
+/// Why a snapshot couldn't be written or read
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("not a physics snapshot")]
+    NotSnapshot,
+    #[error("unsupported snapshot version {0}")]
+    Version(u16),
+    #[error("physics state could not be encoded or decoded: {0}")]
+    Encoding(#[from] bincode::Error),
+}
+
+/// Saved state of a whole [`PhysicsWorld`]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PhysicsSnapshot {
+    config: PhysicsConfig,
+    integration_parameters: IntegrationParameters,
+    rigid_bodies: RigidBodySet,
+    colliders: ColliderSet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    islands: IslandManager,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    force_fields: ForceFields,
+}
+
+impl PhysicsSnapshot {
+    /// Configuration of the world the snapshot was taken from
+    pub fn config(&self) -> &PhysicsConfig {
+        &self.config
+    }
+
+    pub fn body_count(&self) -> usize {
+        self.rigid_bodies.len()
+    }
+
+    pub fn collider_count(&self) -> usize {
+        self.colliders.len()
+    }
+
+    /// The snapshot as bytes, to store or send
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SnapshotError> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Read a snapshot written by [`PhysicsSnapshot::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let header = MAGIC.len() + 2;
+        if bytes.len() < header || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(SnapshotError::NotSnapshot);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(SnapshotError::Version(version));
+        }
+        Ok(bincode::deserialize(&bytes[header..])?)
+    }
+}
+
+impl std::fmt::Debug for PhysicsSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PhysicsSnapshot")
+            .field("config", &self.config)
+            .field("bodies", &self.rigid_bodies.len())
+            .field("colliders", &self.colliders.len())
+            .field("joints", &self.impulse_joints.len())
+            .finish()
+    }
+}
+
+impl PhysicsWorld {
+    /// Save the whole simulation state
+    pub fn snapshot(&self) -> PhysicsSnapshot {
+        PhysicsSnapshot {
+            config: self.config.clone(),
+            integration_parameters: self.integration_parameters,
+            rigid_bodies: self.rigid_body_set.clone(),
+            colliders: self.collider_set.clone(),
+            impulse_joints: self.impulse_joint_set.clone(),
+            multibody_joints: self.multibody_joint_set.clone(),
+            islands: self.island_manager.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            ccd_solver: self.ccd_solver.clone(),
+            force_fields: self.force_fields.clone(),
+        }
+    }
+
+    /// Put the simulation back the way it was when `snapshot` was taken
+    pub fn restore(&mut self, snapshot: &PhysicsSnapshot) {
+        *self = Self::from_snapshot(snapshot.clone());
+    }
+
+    /// A world continuing from a snapshot
+    pub fn from_snapshot(snapshot: PhysicsSnapshot) -> Self {
+        let mut world = Self::with_config(snapshot.config);
+        world.integration_parameters = snapshot.integration_parameters;
+        world.rigid_body_set = snapshot.rigid_bodies;
+        world.collider_set = snapshot.colliders;
+        world.impulse_joint_set = snapshot.impulse_joints;
+        world.multibody_joint_set = snapshot.multibody_joints;
+        world.island_manager = snapshot.islands;
+        world.broad_phase = snapshot.broad_phase;
+        world.narrow_phase = snapshot.narrow_phase;
+        world.ccd_solver = snapshot.ccd_solver;
+        world.force_fields = snapshot.force_fields;
+        world.update_query_pipeline();
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeterminismConfig;
+    use glam::Vec3;
+
+    fn deterministic_world() -> PhysicsWorld {
+        let mut world = PhysicsWorld::with_config(PhysicsConfig {
+            determinism: Some(DeterminismConfig::default()),
+            ..Default::default()
+        });
+        world.create_ground(0.0);
+        // A leaning stack of boxes that topples, and a swinging rope
+        for i in 0..4 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(vector![i as f32 * 0.2, 0.5 + i as f32 * 1.01, 0.0])
+                .build();
+            world.add_dynamic_body(body, ColliderBuilder::cuboid(0.5, 0.5, 0.5).build());
+        }
+        world.attach_rope(Vec3::new(5.0, 6.0, 0.0), Vec3::new(8.0, 6.0, 0.0), Vec3::ZERO, 10.0);
+        world
+    }
+
+    /// Position and velocity bits of every body
+    fn state(world: &PhysicsWorld) -> Vec<[u32; 6]> {
+        world
+            .rigid_body_set
+            .iter()
+            .map(|(_, body)| {
+                let (t, v) = (body.translation(), body.linvel());
+                [t.x, t.y, t.z, v.x, v.y, v.z].map(f32::to_bits)
+            })
+            .collect()
+    }
+
+    fn run(world: &mut PhysicsWorld, steps: usize) -> Vec<[u32; 6]> {
+        for _ in 0..steps {
+            world.step();
+        }
+        state(world)
+    }
+
+    #[test]
+    fn test_deterministic_worlds_match_bit_for_bit() {
+        let (mut a, mut b) = (deterministic_world(), deterministic_world());
+        assert_eq!(run(&mut a, 120), run(&mut b, 120));
+    }
+
+    #[test]
+    fn test_snapshot_restores_exactly() {
+        let mut world = deterministic_world();
+        let start = run(&mut world, 30);
+        let snapshot = world.snapshot();
+        assert_eq!(snapshot.body_count(), 6);
+        let expected = run(&mut world, 60);
+        assert_ne!(expected, start);
+
+        // Through bytes, as a save or a network message would go
+        let bytes = snapshot.to_bytes().unwrap();
+        let mut loaded = PhysicsWorld::from_snapshot(PhysicsSnapshot::from_bytes(&bytes).unwrap());
+        assert_eq!(state(&loaded), start);
+        assert_eq!(run(&mut loaded, 60), expected);
+
+        // Rolling the same world back
+        world.restore(&snapshot);
+        assert_eq!(state(&world), start);
+        assert_eq!(run(&mut world, 60), expected);
+        assert!(world.raycast(Vec3::new(0.0, 20.0, 3.0), Vec3::NEG_Y, 30.0, QueryFilter::default()).is_some());
+    }
+
+    #[test]
+    fn test_corrupt_snapshot_bytes_rejected() {
+        let bytes = deterministic_world().snapshot().to_bytes().unwrap();
+        assert!(PhysicsSnapshot::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+        assert!(PhysicsSnapshot::from_bytes(b"IRGN\x01\x00").is_err());
+        let mut future = bytes.clone();
+        future[4] = 9;
+        assert!(PhysicsSnapshot::from_bytes(&future).is_err());
+    }
+}
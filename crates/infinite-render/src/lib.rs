@@ -3,10 +3,29 @@
 //! Provides both hardware ray tracing (VK_KHR_ray_tracing_pipeline) and
 //! compute shader fallback for universal compatibility.
 
+pub mod lights;
 pub mod mesh;
+pub mod mesh_pool;
+pub mod occlusion;
+pub mod pipeline_cache;
+pub mod queues;
+pub mod resolution;
 pub mod scene;
+pub mod uniforms;
+pub mod upload;
 pub mod vertex;
 
+pub use lights::{LightId, PointLight, PointLightRegistry, PointLightUniforms, MAX_POINT_LIGHTS};
 pub use mesh::{Mesh, SkyMesh};
-pub use scene::{BasicPushConstants, SceneUniforms, SkyColors, SkyPushConstants};
+pub use mesh_pool::{MeshPool, MeshPoolStats, RangeAllocator};
+pub use occlusion::{model_bounds, OcclusionBuffer, OcclusionStats};
+pub use scene::{
+    BasicPushConstants, CloudLayer, FogUniforms, PortalPushConstants, SceneUniforms, SkyColors, SkyPushConstants, SkyStyle,
+    SkyUniforms, VEGETATION_SWAY,
+};
+pub use pipeline_cache::{CacheIdentity, PersistentPipelineCache, PipelineCacheError, PipelineCacheStats};
+pub use queues::transfer_queue_family;
+pub use resolution::{scaled_extent, DynamicResolution, GpuTimer, ResolutionConfig, SceneTarget, SceneTargetError};
+pub use uniforms::{FrameUniforms, RingCursor, UniformError, UniformRing, FRAME_SET};
+pub use upload::{GpuMesh, MeshUploader, StagingPool, UploadError};
 pub use vertex::{SkyVertex, Vertex3D};
@@ -0,0 +1,231 @@
+//! Dynamic point lights
+//!
+//! Gameplay code registers lights (torches, campfires, portals) in a
+//! `PointLightRegistry`; each frame the renderer picks the most relevant
+//! `MAX_POINT_LIGHTS` around the camera and uploads them as a uniform buffer.
+
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+/// Maximum number of point lights shaded per frame (must match basic.frag)
+pub const MAX_POINT_LIGHTS: usize = 8;
+
+/// Lights further than this beyond their radius are never selected
+const LIGHT_CULL_DISTANCE: f32 = 60.0;
+
+/// A point light in world space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: Vec3,
+    pub color: Vec3,
+    pub intensity: f32,
+    /// Distance at which the light falls off to zero
+    pub radius: f32,
+    /// Flicker amount (0.0 = steady, 1.0 = strong fire flicker)
+    pub flicker: f32,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, color: Vec3, intensity: f32, radius: f32) -> Self {
+        Self {
+            position,
+            color,
+            intensity,
+            radius,
+            flicker: 0.0,
+        }
+    }
+
+    /// Warm, flickering hand-held torch
+    pub fn torch(position: Vec3) -> Self {
+        Self {
+            flicker: 0.25,
+            ..Self::new(position, Vec3::new(1.0, 0.65, 0.3), 1.6, 9.0)
+        }
+    }
+
+    /// Larger, strongly flickering campfire
+    pub fn campfire(position: Vec3) -> Self {
+        Self {
+            flicker: 0.4,
+            ..Self::new(position, Vec3::new(1.0, 0.55, 0.2), 2.5, 14.0)
+        }
+    }
+
+    /// Steady glow around a time portal
+    pub fn portal(position: Vec3, color: Vec3) -> Self {
+        Self::new(position, color, 2.0, 12.0)
+    }
+}
+
+/// Handle to a registered light
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LightId(u32);
+
+/// All active point lights in the scene
+#[derive(Debug, Default)]
+pub struct PointLightRegistry {
+    lights: HashMap<LightId, PointLight>,
+    next_id: u32,
+}
+
+impl PointLightRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a light and return its handle
+    pub fn add(&mut self, light: PointLight) -> LightId {
+        let id = LightId(self.next_id);
+        self.next_id += 1;
+        self.lights.insert(id, light);
+        id
+    }
+
+    /// Remove a light. Returns the light if it was registered.
+    pub fn remove(&mut self, id: LightId) -> Option<PointLight> {
+        self.lights.remove(&id)
+    }
+
+    /// Move a light (e.g. a torch following its holder)
+    pub fn set_position(&mut self, id: LightId, position: Vec3) {
+        if let Some(light) = self.lights.get_mut(&id) {
+            light.position = position;
+        }
+    }
+
+    pub fn get(&self, id: LightId) -> Option<&PointLight> {
+        self.lights.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.lights.clear();
+    }
+
+    /// The `max` most relevant lights for a viewer, with flicker applied at
+    /// `time` seconds. Lights are ranked by distance relative to their radius,
+    /// so a large campfire outranks a nearby but small torch.
+    pub fn prioritized(&self, viewer: Vec3, time: f32, max: usize) -> Vec<PointLight> {
+        let mut candidates: Vec<(f32, LightId, &PointLight)> = self
+            .lights
+            .iter()
+            .filter_map(|(id, light)| {
+                let distance = light.position.distance(viewer);
+                if distance > light.radius + LIGHT_CULL_DISTANCE {
+                    return None;
+                }
+                Some((distance / light.radius.max(0.1), *id, light))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1 .0.cmp(&b.1 .0)));
+
+        candidates
+            .into_iter()
+            .take(max)
+            .map(|(_, id, light)| {
+                let mut light = *light;
+                light.intensity *= 1.0 - light.flicker * flicker_noise(time, id.0);
+                light
+            })
+            .collect()
+    }
+}
+
+/// Smooth pseudo-random flicker in 0..1, decorrelated per light
+fn flicker_noise(time: f32, seed: u32) -> f32 {
+    let phase = seed as f32 * 1.618;
+    let n = (time * 7.3 + phase).sin() * 0.5
+        + (time * 13.1 + phase * 2.7).sin() * 0.3
+        + (time * 23.7 + phase * 0.6).sin() * 0.2;
+    n * 0.5 + 0.5
+}
+
+/// GPU layout of the point light uniform buffer (std140)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PointLightUniforms {
+    pub count: [u32; 4],                                // x = active light count
+    pub position_radius: [[f32; 4]; MAX_POINT_LIGHTS], // xyz = position, w = radius
+    pub color_intensity: [[f32; 4]; MAX_POINT_LIGHTS], // rgb = color, a = intensity
+}
+
+impl Default for PointLightUniforms {
+    fn default() -> Self {
+        bytemuck::Zeroable::zeroed()
+    }
+}
+
+impl PointLightUniforms {
+    /// Pack up to `MAX_POINT_LIGHTS` lights; extras are ignored
+    pub fn from_lights(lights: &[PointLight]) -> Self {
+        let mut uniforms = Self::default();
+        let count = lights.len().min(MAX_POINT_LIGHTS);
+        uniforms.count[0] = count as u32;
+        for (i, light) in lights.iter().take(count).enumerate() {
+            let p = light.position;
+            let c = light.color;
+            uniforms.position_radius[i] = [p.x, p.y, p.z, light.radius];
+            uniforms.color_intensity[i] = [c.x, c.y, c.z, light.intensity];
+        }
+        uniforms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prioritizes_nearest_relative_to_radius() {
+        let mut registry = PointLightRegistry::new();
+        registry.add(PointLight::new(Vec3::new(10.0, 0.0, 0.0), Vec3::ONE, 1.0, 5.0));
+        let big = registry.add(PointLight::new(Vec3::new(12.0, 0.0, 0.0), Vec3::ONE, 1.0, 20.0));
+
+        let picked = registry.prioritized(Vec3::ZERO, 0.0, 1);
+        assert_eq!(picked.len(), 1);
+        assert_eq!(picked[0].position, registry.get(big).unwrap().position);
+    }
+
+    #[test]
+    fn test_distant_lights_culled() {
+        let mut registry = PointLightRegistry::new();
+        registry.add(PointLight::torch(Vec3::new(500.0, 0.0, 0.0)));
+        assert!(registry.prioritized(Vec3::ZERO, 0.0, MAX_POINT_LIGHTS).is_empty());
+    }
+
+    #[test]
+    fn test_flicker_only_dims() {
+        let mut registry = PointLightRegistry::new();
+        let base = PointLight::campfire(Vec3::ZERO);
+        registry.add(base);
+        for i in 0..100 {
+            let light = registry.prioritized(Vec3::ZERO, i as f32 * 0.1, 1)[0];
+            assert!(light.intensity <= base.intensity);
+            assert!(light.intensity >= base.intensity * (1.0 - base.flicker) - 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_uniforms_truncate() {
+        let lights = vec![PointLight::torch(Vec3::ZERO); MAX_POINT_LIGHTS + 3];
+        let uniforms = PointLightUniforms::from_lights(&lights);
+        assert_eq!(uniforms.count[0], MAX_POINT_LIGHTS as u32);
+    }
+
+    #[test]
+    fn test_remove_light() {
+        let mut registry = PointLightRegistry::new();
+        let id = registry.add(PointLight::torch(Vec3::ZERO));
+        assert!(registry.remove(id).is_some());
+        assert!(registry.is_empty());
+    }
+}
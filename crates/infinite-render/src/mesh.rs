@@ -286,6 +286,38 @@ impl Mesh {
         Self { vertices, indices }
     }
 
+    /// Generate a unit cube centred on the origin with flat-shaded faces.
+    /// Scale it with the model matrix to draw boxes of any size.
+    pub fn cuboid(color: [f32; 4]) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // Face normal and two edge directions whose cross product is the normal
+        let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+            ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+            ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            ([0.0, 0.0, -1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+        ];
+
+        for (normal, u, v) in faces {
+            let base = vertices.len() as u32;
+            for (su, sv) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+                let position = [
+                    normal[0] * 0.5 + u[0] * su + v[0] * sv,
+                    normal[1] * 0.5 + u[1] * su + v[1] * sv,
+                    normal[2] * 0.5 + u[2] * su + v[2] * sv,
+                ];
+                vertices.push(Vertex3D::new(position, normal, color));
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+
+        Self { vertices, indices }
+    }
+
     /// Generate a plane with heightmap
     pub fn terrain(
         size: f32,
@@ -402,6 +434,56 @@ impl Mesh {
 
         Self { vertices, indices }
     }
+
+    /// Generate a flat disc in the XY plane facing +Z (used for portals).
+    /// Shaders can recover radial coordinates from `position.xy / radius`.
+    pub fn disc(radius: f32, segments: u32, color: [f32; 4]) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let normal = [0.0, 0.0, 1.0];
+
+        vertices.push(Vertex3D::new([0.0, 0.0, 0.0], normal, color));
+        for seg in 0..=segments {
+            let theta = 2.0 * PI * seg as f32 / segments as f32;
+            vertices.push(Vertex3D::new(
+                [radius * theta.cos(), radius * theta.sin(), 0.0],
+                normal,
+                color,
+            ));
+        }
+
+        for seg in 0..segments {
+            indices.push(0);
+            indices.push(seg + 1);
+            indices.push(seg + 2);
+        }
+
+        Self { vertices, indices }
+    }
+
+    /// Generate a thin triangular prism from (0, 0, 0) to (0, 1, 0) for
+    /// drawing lines (ropes, debug rays) with the wireframe pipeline. Scale
+    /// and orient it with the model matrix.
+    pub fn line(thickness: f32, color: [f32; 4]) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let radius = thickness * 0.5;
+
+        for y in [0.0, 1.0] {
+            for side in 0..3 {
+                let theta = 2.0 * PI * side as f32 / 3.0;
+                let (sin, cos) = theta.sin_cos();
+                vertices.push(Vertex3D::new([radius * cos, y, radius * sin], [cos, 0.0, sin], color));
+            }
+        }
+
+        for side in 0..3 {
+            let next = (side + 1) % 3;
+            indices.extend_from_slice(&[side, side + 3, next, next, side + 3, next + 3]);
+        }
+
+        Self { vertices, indices }
+    }
 }
 
 /// Hermite smoothstep interpolation
@@ -0,0 +1,309 @@
+//! Sub-allocated storage for streamed meshes
+//!
+//! Chunk meshes come and go constantly. Instead of a buffer pair per mesh,
+//! a `MeshPool` carves vertex and index ranges out of a few large
+//! persistent device-local blocks. Ranges freed by unloaded meshes are
+//! reused, and a mesh re-uploaded under the same key (a reshaped chunk, an
+//! era swap) is rewritten in place whenever it still fits its range.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+use bytemuck::Pod;
+use vulkano::buffer::{BufferContents, BufferUsage, Subbuffer};
+use vulkano::DeviceSize;
+
+use crate::upload::{GpuMesh, MeshUploader, UploadError};
+
+/// First-fit free list over `0..capacity`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeAllocator {
+    capacity: DeviceSize,
+    /// Sorted, non-overlapping and never adjacent (adjacent ranges merge)
+    free: Vec<Range<DeviceSize>>,
+}
+
+impl RangeAllocator {
+    pub fn new(capacity: DeviceSize) -> Self {
+        Self {
+            capacity,
+            free: Some(0..capacity).filter(|range| !range.is_empty()).into_iter().collect(),
+        }
+    }
+
+    pub fn capacity(&self) -> DeviceSize {
+        self.capacity
+    }
+
+    /// Claim `len` units from the first free range that holds them
+    pub fn allocate(&mut self, len: DeviceSize) -> Option<Range<DeviceSize>> {
+        if len == 0 {
+            return None;
+        }
+        let index = self.free.iter().position(|range| range.end - range.start >= len)?;
+        let range = &mut self.free[index];
+        let claimed = range.start..range.start + len;
+        range.start += len;
+        if range.is_empty() {
+            self.free.remove(index);
+        }
+        Some(claimed)
+    }
+
+    /// Return a range, merging it with its free neighbours
+    pub fn free(&mut self, range: Range<DeviceSize>) {
+        if range.is_empty() {
+            return;
+        }
+        let index = self.free.partition_point(|free| free.start < range.start);
+        let merges_prev = index > 0 && self.free[index - 1].end == range.start;
+        let merges_next = index < self.free.len() && self.free[index].start == range.end;
+        match (merges_prev, merges_next) {
+            (true, true) => {
+                self.free[index - 1].end = self.free[index].end;
+                self.free.remove(index);
+            }
+            (true, false) => self.free[index - 1].end = range.end,
+            (false, true) => self.free[index].start = range.start,
+            (false, false) => self.free.insert(index, range),
+        }
+    }
+
+    /// Units not currently allocated
+    pub fn free_len(&self) -> DeviceSize {
+        self.free.iter().map(|range| range.end - range.start).sum()
+    }
+}
+
+/// Pool occupancy, for the debug overlay
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MeshPoolStats {
+    pub blocks: usize,
+    pub meshes: usize,
+    pub used_vertices: DeviceSize,
+    pub vertex_capacity: DeviceSize,
+    pub used_indices: DeviceSize,
+    pub index_capacity: DeviceSize,
+    /// Device memory held by the blocks, used or not
+    pub allocated_bytes: DeviceSize,
+}
+
+/// One persistent vertex/index buffer pair
+struct Block<V: BufferContents> {
+    vertices: Subbuffer<[V]>,
+    indices: Subbuffer<[u32]>,
+    vertex_ranges: RangeAllocator,
+    index_ranges: RangeAllocator,
+}
+
+struct Slot<V: BufferContents> {
+    block: usize,
+    /// Allocated ranges; the mesh may use only a prefix of them
+    vertex_range: Range<DeviceSize>,
+    index_range: Range<DeviceSize>,
+    mesh: GpuMesh<V>,
+}
+
+/// Meshes keyed by `K`, sub-allocated from shared blocks.
+///
+/// Writes go straight into buffers earlier frames may still be reading:
+/// wait for the frames in flight before `insert_all`.
+pub struct MeshPool<K, V: BufferContents> {
+    block_vertices: DeviceSize,
+    block_indices: DeviceSize,
+    blocks: Vec<Block<V>>,
+    slots: HashMap<K, Slot<V>>,
+}
+
+impl<K: Eq + Hash, V: BufferContents + Pod> MeshPool<K, V> {
+    /// Blocks are allocated on demand, each sized for at least
+    /// `block_vertices` vertices and `block_indices` indices
+    pub fn new(block_vertices: DeviceSize, block_indices: DeviceSize) -> Self {
+        Self {
+            block_vertices,
+            block_indices,
+            blocks: Vec::new(),
+            slots: HashMap::new(),
+        }
+    }
+
+    /// The mesh, with buffers sliced to its ranges, ready to bind and draw
+    pub fn get(&self, key: &K) -> Option<&GpuMesh<V>> {
+        self.slots.get(key).map(|slot| &slot.mesh)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.slots.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Upload meshes in one batch, replacing any already stored under the
+    /// same key (in place when they fit)
+    pub fn insert_all<'a>(
+        &mut self,
+        uploader: &mut MeshUploader,
+        meshes: impl IntoIterator<Item = (K, &'a [V], &'a [u32])>,
+    ) -> Result<(), UploadError> {
+        let mut writes = Vec::new();
+        for (key, vertices, indices) in meshes {
+            if vertices.is_empty() || indices.is_empty() {
+                return Err(UploadError::Empty);
+            }
+            let (vertex_len, index_len) = (vertices.len() as DeviceSize, indices.len() as DeviceSize);
+
+            let reused = self.slots.remove(&key).and_then(|slot| {
+                let fits = slot.vertex_range.end - slot.vertex_range.start >= vertex_len
+                    && slot.index_range.end - slot.index_range.start >= index_len;
+                if fits {
+                    Some((slot.block, slot.vertex_range, slot.index_range))
+                } else {
+                    self.release(slot);
+                    None
+                }
+            });
+            let (block, vertex_range, index_range) = match reused {
+                Some(ranges) => ranges,
+                None => self.allocate(uploader, vertex_len, index_len)?,
+            };
+
+            let block_buffers = &self.blocks[block];
+            let mesh = GpuMesh {
+                vertex_buffer: block_buffers.vertices.clone().slice(vertex_range.start..vertex_range.start + vertex_len),
+                index_buffer: block_buffers.indices.clone().slice(index_range.start..index_range.start + index_len),
+                index_count: indices.len() as u32,
+            };
+            writes.push((bytemuck::cast_slice(vertices), mesh.vertex_buffer.as_bytes().clone()));
+            writes.push((bytemuck::cast_slice(indices), mesh.index_buffer.as_bytes().clone()));
+            self.slots.insert(
+                key,
+                Slot {
+                    block,
+                    vertex_range,
+                    index_range,
+                    mesh,
+                },
+            );
+        }
+        uploader.write_all(writes)
+    }
+
+    /// Free a mesh's ranges for reuse
+    pub fn remove(&mut self, key: &K) -> bool {
+        match self.slots.remove(key) {
+            Some(slot) => {
+                self.release(slot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keep only the meshes whose key passes `keep`
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        let dropped: Vec<Slot<V>> = self.slots.extract_if(|key, _| !keep(key)).map(|(_, slot)| slot).collect();
+        for slot in dropped {
+            self.release(slot);
+        }
+    }
+
+    /// Drop every mesh; the blocks stay allocated for the next ones
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        for block in &mut self.blocks {
+            block.vertex_ranges = RangeAllocator::new(block.vertex_ranges.capacity());
+            block.index_ranges = RangeAllocator::new(block.index_ranges.capacity());
+        }
+    }
+
+    pub fn stats(&self) -> MeshPoolStats {
+        let mut stats = MeshPoolStats {
+            blocks: self.blocks.len(),
+            meshes: self.slots.len(),
+            ..Default::default()
+        };
+        for block in &self.blocks {
+            stats.vertex_capacity += block.vertex_ranges.capacity();
+            stats.used_vertices += block.vertex_ranges.capacity() - block.vertex_ranges.free_len();
+            stats.index_capacity += block.index_ranges.capacity();
+            stats.used_indices += block.index_ranges.capacity() - block.index_ranges.free_len();
+            stats.allocated_bytes += block.vertices.size() + block.indices.size();
+        }
+        stats
+    }
+
+    fn release(&mut self, slot: Slot<V>) {
+        let block = &mut self.blocks[slot.block];
+        block.vertex_ranges.free(slot.vertex_range);
+        block.index_ranges.free(slot.index_range);
+    }
+
+    /// Ranges for a new mesh, from the first block with room or a new one
+    fn allocate(
+        &mut self,
+        uploader: &MeshUploader,
+        vertex_len: DeviceSize,
+        index_len: DeviceSize,
+    ) -> Result<(usize, Range<DeviceSize>, Range<DeviceSize>), UploadError> {
+        for (index, block) in self.blocks.iter_mut().enumerate() {
+            let Some(vertex_range) = block.vertex_ranges.allocate(vertex_len) else {
+                continue;
+            };
+            match block.index_ranges.allocate(index_len) {
+                Some(index_range) => return Ok((index, vertex_range, index_range)),
+                None => block.vertex_ranges.free(vertex_range),
+            }
+        }
+
+        let (vertex_capacity, index_capacity) = (self.block_vertices.max(vertex_len), self.block_indices.max(index_len));
+        let mut block = Block {
+            vertices: uploader.device_local(BufferUsage::VERTEX_BUFFER, vertex_capacity)?,
+            indices: uploader.device_local(BufferUsage::INDEX_BUFFER, index_capacity)?,
+            vertex_ranges: RangeAllocator::new(vertex_capacity),
+            index_ranges: RangeAllocator::new(index_capacity),
+        };
+        let ranges = block.vertex_ranges.allocate(vertex_len).zip(block.index_ranges.allocate(index_len));
+        let (vertex_range, index_range) = ranges.expect("a fresh block holds the mesh it was sized for");
+        self.blocks.push(block);
+        Ok((self.blocks.len() - 1, vertex_range, index_range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocations_reuse_freed_ranges() {
+        let mut ranges = RangeAllocator::new(100);
+        let a = ranges.allocate(40).unwrap();
+        let b = ranges.allocate(40).unwrap();
+        assert_eq!((a.clone(), b.clone()), (0..40, 40..80));
+        assert_eq!(ranges.allocate(30), None);
+
+        ranges.free(a);
+        assert_eq!(ranges.allocate(30), Some(0..30));
+        assert_eq!(ranges.free_len(), 30);
+    }
+
+    #[test]
+    fn test_freed_neighbours_merge() {
+        let mut ranges = RangeAllocator::new(90);
+        let parts: Vec<_> = (0..3).map(|_| ranges.allocate(30).unwrap()).collect();
+        ranges.free(parts[0].clone());
+        ranges.free(parts[2].clone());
+        assert_eq!(ranges.allocate(60), None, "free space is split");
+
+        ranges.free(parts[1].clone());
+        assert_eq!(ranges, RangeAllocator::new(90));
+        assert_eq!(ranges.allocate(90), Some(0..90));
+        assert_eq!(ranges.allocate(0), None);
+    }
+}
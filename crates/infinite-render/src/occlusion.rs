@@ -0,0 +1,381 @@
+//! Coarse software occlusion culling
+//!
+//! Each frame the terrain is rasterized on the CPU into a small depth
+//! buffer, which is reduced into a hierarchical-Z (HiZ) pyramid holding the
+//! farthest depth per texel. An object's screen rectangle is then tested
+//! against the few pyramid texels covering it: if its nearest point is
+//! behind all of them, the object is hidden and its draw is skipped.
+//!
+//! Occluders are built from per-block minimum heights, so the occluding
+//! surface always lies at or below the real terrain, and anything touching
+//! the near plane counts as visible. The test errs towards drawing.
+
+use glam::{Mat4, Vec3, Vec4};
+
+/// Default buffer resolution; coarse on purpose, only big occluders matter
+pub const OCCLUSION_WIDTH: usize = 256;
+pub const OCCLUSION_HEIGHT: usize = 144;
+
+/// Depth of a texel no occluder touched
+const FAR: f32 = 1.0;
+
+/// Per-frame culling counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OcclusionStats {
+    /// Objects tested against the buffer
+    pub tested: u32,
+    /// Objects found hidden
+    pub culled: u32,
+    /// Occluder triangles rasterized
+    pub occluder_triangles: u32,
+}
+
+/// CPU depth buffer of the frame's occluders with its HiZ pyramid
+pub struct OcclusionBuffer {
+    width: usize,
+    height: usize,
+    view_proj: Mat4,
+    /// Mip 0 is the full-resolution depth; each level keeps the max of 2x2
+    levels: Vec<Vec<f32>>,
+    stats: OcclusionStats,
+    /// World bounds of everything culled this frame, for the debug overlay
+    culled: Vec<(Vec3, Vec3)>,
+}
+
+impl Default for OcclusionBuffer {
+    fn default() -> Self {
+        Self::new(OCCLUSION_WIDTH, OCCLUSION_HEIGHT)
+    }
+}
+
+impl OcclusionBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut levels = Vec::new();
+        let (mut w, mut h) = (width.max(1), height.max(1));
+        loop {
+            levels.push(vec![FAR; w * h]);
+            if w == 1 && h == 1 {
+                break;
+            }
+            w = w.div_ceil(2);
+            h = h.div_ceil(2);
+        }
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            view_proj: Mat4::IDENTITY,
+            levels,
+            stats: OcclusionStats::default(),
+            culled: Vec::new(),
+        }
+    }
+
+    /// Start a frame: clear the buffer and the counters
+    pub fn begin(&mut self, view_proj: Mat4) {
+        self.view_proj = view_proj;
+        for level in &mut self.levels {
+            level.fill(FAR);
+        }
+        self.stats = OcclusionStats::default();
+        self.culled.clear();
+    }
+
+    /// Rasterize a terrain heightfield as an occluder.
+    ///
+    /// `heights` is row-major with `subdivisions + 1` samples per side,
+    /// centered on `origin` and spanning `size`. It is decimated to one
+    /// quad per `block` cells, each corner taking the lowest height of the
+    /// blocks around it.
+    pub fn add_heightfield(&mut self, origin: Vec3, size: f32, subdivisions: u32, heights: &[f32], block: u32) {
+        let vertex_count = subdivisions as usize + 1;
+        if subdivisions == 0 || heights.len() < vertex_count * vertex_count {
+            return;
+        }
+        let block = (block.max(1) as usize).min(subdivisions as usize);
+        let coarse = (subdivisions as usize).div_ceil(block) + 1;
+        let step = size / subdivisions as f32;
+        let half_size = size / 2.0;
+        let fine_index = |i: usize| (i * block).min(subdivisions as usize);
+
+        let mut corners = Vec::with_capacity(coarse * coarse);
+        for cz in 0..coarse {
+            for cx in 0..coarse {
+                let (x, z) = (fine_index(cx), fine_index(cz));
+                let (x0, x1) = (fine_index(cx.saturating_sub(1)), fine_index(cx + 1));
+                let (z0, z1) = (fine_index(cz.saturating_sub(1)), fine_index(cz + 1));
+                let mut lowest = f32::MAX;
+                for row in heights[z0 * vertex_count..(z1 + 1) * vertex_count].chunks(vertex_count) {
+                    for &height in &row[x0..=x1] {
+                        lowest = lowest.min(height);
+                    }
+                }
+                let local = Vec3::new(-half_size + x as f32 * step, lowest, -half_size + z as f32 * step);
+                corners.push(self.view_proj * (origin + local).extend(1.0));
+            }
+        }
+
+        for cz in 0..coarse - 1 {
+            for cx in 0..coarse - 1 {
+                let i = cz * coarse + cx;
+                let (a, b, c, d) = (corners[i], corners[i + 1], corners[i + coarse], corners[i + coarse + 1]);
+                self.rasterize_clip(a, c, b);
+                self.rasterize_clip(b, c, d);
+            }
+        }
+    }
+
+    /// Rasterize one world-space occluder triangle
+    pub fn add_triangle(&mut self, a: Vec3, b: Vec3, c: Vec3) {
+        let project = |p: Vec3| self.view_proj * p.extend(1.0);
+        let (a, b, c) = (project(a), project(b), project(c));
+        self.rasterize_clip(a, b, c);
+    }
+
+    /// Reduce the depth buffer into the HiZ pyramid; call after the occluders
+    pub fn finish(&mut self) {
+        let (mut w, mut h) = (self.width, self.height);
+        for level in 1..self.levels.len() {
+            let (pw, ph) = (w, h);
+            w = w.div_ceil(2);
+            h = h.div_ceil(2);
+            let (front, back) = self.levels.split_at_mut(level);
+            let (prev, next) = (&front[level - 1], &mut back[0]);
+            for y in 0..h {
+                for x in 0..w {
+                    let (x0, y0) = (x * 2, y * 2);
+                    let (x1, y1) = ((x0 + 1).min(pw - 1), (y0 + 1).min(ph - 1));
+                    next[y * w + x] = prev[y0 * pw + x0]
+                        .max(prev[y0 * pw + x1])
+                        .max(prev[y1 * pw + x0])
+                        .max(prev[y1 * pw + x1]);
+                }
+            }
+        }
+    }
+
+    /// Whether a world-space box is entirely hidden behind the occluders
+    pub fn is_occluded(&self, min: Vec3, max: Vec3) -> bool {
+        let mut nearest = f32::MAX;
+        let (mut lo_x, mut lo_y) = (f32::MAX, f32::MAX);
+        let (mut hi_x, mut hi_y) = (f32::MIN, f32::MIN);
+        for i in 0..8 {
+            let corner = Vec3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            );
+            let clip = self.view_proj * corner.extend(1.0);
+            // Crossing the near plane: the camera is inside or right at it
+            if clip.w <= f32::EPSILON || clip.z < 0.0 {
+                return false;
+            }
+            let (sx, sy, depth) = self.to_screen(clip);
+            nearest = nearest.min(depth);
+            lo_x = lo_x.min(sx);
+            lo_y = lo_y.min(sy);
+            hi_x = hi_x.max(sx);
+            hi_y = hi_y.max(sy);
+        }
+
+        // Off screen is the frustum's business, not ours
+        if hi_x < 0.0 || hi_y < 0.0 || lo_x > self.width as f32 || lo_y > self.height as f32 {
+            return false;
+        }
+
+        // Grow by a texel so occluder edges sampled at texel centers can't
+        // hide something peeking past a silhouette
+        let x0 = (lo_x - 1.0).max(0.0) as usize;
+        let y0 = (lo_y - 1.0).max(0.0) as usize;
+        let x1 = ((hi_x + 1.0) as usize).min(self.width - 1);
+        let y1 = ((hi_y + 1.0) as usize).min(self.height - 1);
+
+        // Smallest level where the rectangle spans at most 2x2 texels
+        let extent = (x1 - x0).max(y1 - y0) + 1;
+        let mut level = 0;
+        while (extent >> level) > 2 && level + 1 < self.levels.len() {
+            level += 1;
+        }
+        let level_width = self.width.div_ceil(1 << level);
+        let depths = &self.levels[level];
+        for y in (y0 >> level)..=(y1 >> level) {
+            for x in (x0 >> level)..=(x1 >> level) {
+                if nearest <= depths[y * level_width + x] {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Test a box and record the result; true if its draw can be skipped
+    pub fn cull(&mut self, min: Vec3, max: Vec3) -> bool {
+        self.stats.tested += 1;
+        let occluded = self.is_occluded(min, max);
+        if occluded {
+            self.stats.culled += 1;
+            self.culled.push((min, max));
+        }
+        occluded
+    }
+
+    /// `cull` for a mesh centred on its origin, `half_extent` in model space
+    pub fn cull_model(&mut self, model: Mat4, half_extent: Vec3) -> bool {
+        let (min, max) = model_bounds(model, half_extent);
+        self.cull(min, max)
+    }
+
+    pub fn stats(&self) -> OcclusionStats {
+        self.stats
+    }
+
+    /// Bounds of the objects culled this frame
+    pub fn culled(&self) -> &[(Vec3, Vec3)] {
+        &self.culled
+    }
+
+    /// Clip space to buffer pixels (x, y) and NDC depth
+    fn to_screen(&self, clip: Vec4) -> (f32, f32, f32) {
+        let ndc = clip.truncate() / clip.w;
+        (
+            (ndc.x * 0.5 + 0.5) * self.width as f32,
+            (ndc.y * 0.5 + 0.5) * self.height as f32,
+            ndc.z,
+        )
+    }
+
+    fn rasterize_clip(&mut self, a: Vec4, b: Vec4, c: Vec4) {
+        // Triangles crossing the near plane are dropped rather than
+        // clipped: losing an occluder only means drawing more
+        if [a, b, c].iter().any(|v| v.w <= f32::EPSILON || v.z < 0.0) {
+            return;
+        }
+        let (a, b, c) = (self.to_screen(a), self.to_screen(b), self.to_screen(c));
+        let area = edge(a, b, c.0, c.1);
+        if area.abs() <= f32::EPSILON {
+            return;
+        }
+
+        let min_x = a.0.min(b.0).min(c.0).floor().max(0.0) as usize;
+        let min_y = a.1.min(b.1).min(c.1).floor().max(0.0) as usize;
+        let max_x = a.0.max(b.0).max(c.0).ceil().min(self.width as f32) as usize;
+        let max_y = a.1.max(b.1).max(c.1).ceil().min(self.height as f32) as usize;
+        if min_x >= max_x || min_y >= max_y {
+            return;
+        }
+        self.stats.occluder_triangles += 1;
+
+        let depths = &mut self.levels[0];
+        for y in min_y..max_y {
+            let py = y as f32 + 0.5;
+            for x in min_x..max_x {
+                let px = x as f32 + 0.5;
+                // Barycentric weights, normalized so either winding works
+                let wa = edge(b, c, px, py) / area;
+                let wb = edge(c, a, px, py) / area;
+                let wc = 1.0 - wa - wb;
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+                // NDC depth is linear in screen space
+                let depth = wa * a.2 + wb * b.2 + wc * c.2;
+                let texel = &mut depths[y * self.width + x];
+                if depth < *texel {
+                    *texel = depth;
+                }
+            }
+        }
+    }
+}
+
+/// World-space bounds of a model-space box centred on the origin
+pub fn model_bounds(model: Mat4, half_extent: Vec3) -> (Vec3, Vec3) {
+    let center = model.w_axis.truncate();
+    // Each world axis extent is the sum of the rotated, scaled half axes
+    let extent = model.x_axis.truncate().abs() * half_extent.x
+        + model.y_axis.truncate().abs() * half_extent.y
+        + model.z_axis.truncate().abs() * half_extent.z;
+    (center - extent, center + extent)
+}
+
+/// Twice the signed area of (a, b, p)
+fn edge(a: (f32, f32, f32), b: (f32, f32, f32), px: f32, py: f32) -> f32 {
+    (b.0 - a.0) * (py - a.1) - (b.1 - a.1) * (px - a.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera at the origin looking down -Z
+    fn buffer() -> OcclusionBuffer {
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, -10.0), Vec3::Y);
+        let projection = Mat4::perspective_rh(60f32.to_radians(), 16.0 / 9.0, 0.1, 1000.0);
+        let mut buffer = OcclusionBuffer::new(128, 72);
+        buffer.begin(projection * view);
+        buffer
+    }
+
+    /// A wide wall at `z`, `height` tall
+    fn wall(buffer: &mut OcclusionBuffer, z: f32, height: f32) {
+        let (a, b) = (Vec3::new(-50.0, -5.0, z), Vec3::new(50.0, -5.0, z));
+        let (c, d) = (Vec3::new(-50.0, height, z), Vec3::new(50.0, height, z));
+        buffer.add_triangle(a, b, c);
+        buffer.add_triangle(b, d, c);
+    }
+
+    #[test]
+    fn test_hidden_behind_wall_only() {
+        let mut buffer = buffer();
+        wall(&mut buffer, -10.0, 6.0);
+        buffer.finish();
+
+        // Behind the wall and below its top
+        assert!(buffer.is_occluded(Vec3::new(-1.0, 0.0, -31.0), Vec3::new(1.0, 2.0, -29.0)));
+        // In front of the wall
+        assert!(!buffer.is_occluded(Vec3::new(-1.0, 0.0, -6.0), Vec3::new(1.0, 2.0, -4.0)));
+        // Behind the wall but poking over its top
+        assert!(!buffer.is_occluded(Vec3::new(-1.0, 0.0, -31.0), Vec3::new(1.0, 40.0, -29.0)));
+        // Containing the camera
+        assert!(!buffer.is_occluded(Vec3::splat(-1.0), Vec3::splat(2.0)));
+    }
+
+    #[test]
+    fn test_model_bounds() {
+        let model = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0))
+            * Mat4::from_rotation_y(std::f32::consts::FRAC_PI_2)
+            * Mat4::from_scale(Vec3::new(4.0, 1.0, 2.0));
+        let (min, max) = model_bounds(model, Vec3::splat(0.5));
+        assert!(min.abs_diff_eq(Vec3::new(0.0, 1.5, 1.0), 1e-5));
+        assert!(max.abs_diff_eq(Vec3::new(2.0, 2.5, 5.0), 1e-5));
+    }
+
+    #[test]
+    fn test_empty_buffer_culls_nothing() {
+        let mut buffer = buffer();
+        buffer.finish();
+        assert!(!buffer.cull(Vec3::new(-1.0, 0.0, -31.0), Vec3::new(1.0, 2.0, -29.0)));
+        assert_eq!(buffer.stats(), OcclusionStats { tested: 1, culled: 0, occluder_triangles: 0 });
+    }
+
+    #[test]
+    fn test_heightfield_hill_occludes() {
+        let mut buffer = buffer();
+        // A 16m ridge centered 20m ahead, flat ground behind it
+        let subdivisions = 16;
+        let heights: Vec<f32> = (0..17 * 17)
+            .map(|i| {
+                let z = i / 17;
+                if (6..=10).contains(&z) { 12.0 } else { 0.0 }
+            })
+            .collect();
+        buffer.add_heightfield(Vec3::new(0.0, 0.0, -20.0), 64.0, subdivisions, &heights, 2);
+        buffer.finish();
+        assert!(buffer.stats().occluder_triangles > 0);
+
+        assert!(buffer.cull(Vec3::new(-1.0, 0.0, -60.0), Vec3::new(1.0, 2.0, -58.0)));
+        assert!(!buffer.cull(Vec3::new(-1.0, 0.0, -60.0), Vec3::new(1.0, 60.0, -58.0)));
+        assert_eq!(buffer.culled().len(), 1);
+        buffer.begin(Mat4::IDENTITY);
+        assert!(buffer.culled().is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//! Pipeline cache persistence
+//!
+//! Drivers compile shaders into GPU code when a pipeline is created, which
+//! makes up much of startup. A Vulkan pipeline cache keeps that code; saved
+//! on shutdown and loaded on the next start, pipelines come out of it
+//! instead of the compiler. The saved file wraps the driver's data with a
+//! checksum, and the data's own header names the GPU and driver that made
+//! it, so a damaged file or one from another GPU or driver is dropped
+//! rather than handed to the driver, which trusts it blindly.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use vulkano::device::Device;
+use vulkano::pipeline::cache::{PipelineCache, PipelineCacheCreateInfo};
+use vulkano::{Validated, VulkanError};
+
+const MAGIC: [u8; 8] = *b"INFPCACH";
+const FORMAT_VERSION: u32 = 1;
+/// Magic, format version, payload length and CRC32
+const FILE_HEADER_LEN: usize = 8 + 4 + 8 + 4;
+/// Vulkan's own header: its length, version, vendor ID, device ID, cache UUID
+const VK_HEADER_LEN: usize = 32;
+const VK_HEADER_VERSION_ONE: u32 = 1;
+
+/// The GPU and driver a cache was made by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheIdentity {
+    pub vendor_id: u32,
+    pub device_id: u32,
+    /// Changes with the driver version
+    pub uuid: [u8; 16],
+}
+
+impl CacheIdentity {
+    pub fn of(device: &Device) -> Self {
+        let properties = device.physical_device().properties();
+        Self {
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            uuid: properties.pipeline_cache_uuid,
+        }
+    }
+}
+
+/// How well the loaded cache served this run's pipelines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineCacheStats {
+    /// Bytes of cache data loaded from disk (0 on a first start)
+    pub loaded_bytes: usize,
+    /// Pipelines created through the cache
+    pub pipelines: u32,
+    /// Of those, the ones the cache already held
+    pub hits: u32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineCacheError {
+    #[error("failed to read cache data: {0}")]
+    Vulkan(#[from] VulkanError),
+    #[error("failed to write cache file: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// A pipeline cache loaded from and saved to a file
+pub struct PersistentPipelineCache {
+    cache: Arc<PipelineCache>,
+    path: PathBuf,
+    stats: PipelineCacheStats,
+}
+
+impl PersistentPipelineCache {
+    /// The cache saved at `path`, or an empty one if there is none usable
+    pub fn load(device: Arc<Device>, path: PathBuf) -> Result<Self, Validated<VulkanError>> {
+        let identity = CacheIdentity::of(&device);
+        let initial_data = fs::read(&path).ok().and_then(|file| unwrap_cache(&file, identity)).unwrap_or_default();
+        let loaded_bytes = initial_data.len();
+        let create = |initial_data| {
+            // Safety: the data is empty, or passed its checksum and was made by
+            // this GPU and driver
+            unsafe { PipelineCache::new(device.clone(), PipelineCacheCreateInfo { initial_data, ..Default::default() }) }
+        };
+        let (cache, loaded_bytes) = match create(initial_data) {
+            Ok(cache) => (cache, loaded_bytes),
+            Err(_) if loaded_bytes > 0 => (create(Vec::new())?, 0),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            cache,
+            path,
+            stats: PipelineCacheStats { loaded_bytes, ..Default::default() },
+        })
+    }
+
+    pub fn cache(&self) -> &Arc<PipelineCache> {
+        &self.cache
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn stats(&self) -> PipelineCacheStats {
+        self.stats
+    }
+
+    /// Create a pipeline through the cache. It counts as a hit when the
+    /// cache didn't grow, since a miss adds the newly compiled code.
+    pub fn create<T>(&mut self, create: impl FnOnce(Arc<PipelineCache>) -> Option<T>) -> Option<T> {
+        let before = self.data_len();
+        let pipeline = create(self.cache.clone())?;
+        self.stats.pipelines += 1;
+        if before.is_some() && self.data_len() == before {
+            self.stats.hits += 1;
+        }
+        Some(pipeline)
+    }
+
+    /// Write the cache to its file, returning the bytes written
+    pub fn save(&self) -> Result<usize, PipelineCacheError> {
+        let data = self.cache.get_data()?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Through a temporary file, so a crash mid-write leaves the old cache
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, wrap_cache(&data))?;
+        fs::rename(&temp, &self.path)?;
+        Ok(data.len())
+    }
+
+    fn data_len(&self) -> Option<usize> {
+        self.cache.get_data().ok().map(|data| data.len())
+    }
+}
+
+/// Cache data with the file header in front
+fn wrap_cache(data: &[u8]) -> Vec<u8> {
+    let mut file = Vec::with_capacity(FILE_HEADER_LEN + data.len());
+    file.extend_from_slice(&MAGIC);
+    file.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    file.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+    file.extend_from_slice(data);
+    file
+}
+
+/// The cache data in `file`, if it is intact and made by `identity`
+fn unwrap_cache(file: &[u8], identity: CacheIdentity) -> Option<Vec<u8>> {
+    let (header, data) = file.split_at_checked(FILE_HEADER_LEN)?;
+    let u32_at = |bytes: &[u8], at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    let len = u64::from_le_bytes(header[12..20].try_into().unwrap());
+    let intact = header[..8] == MAGIC
+        && u32_at(header, 8) == FORMAT_VERSION
+        && len == data.len() as u64
+        && u32_at(header, 20) == crc32fast::hash(data);
+    if !intact || data.len() < VK_HEADER_LEN {
+        return None;
+    }
+    // The driver's header is always little-endian
+    let ours = u32_at(data, 0) as usize >= VK_HEADER_LEN
+        && u32_at(data, 4) == VK_HEADER_VERSION_ONE
+        && u32_at(data, 8) == identity.vendor_id
+        && u32_at(data, 12) == identity.device_id
+        && data[16..32] == identity.uuid;
+    ours.then(|| data.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPU: CacheIdentity = CacheIdentity { vendor_id: 0x10de, device_id: 0x2684, uuid: [7; 16] };
+
+    /// Cache data as a driver would write it for `identity`
+    fn driver_data(identity: CacheIdentity) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(VK_HEADER_LEN as u32).to_le_bytes());
+        data.extend_from_slice(&VK_HEADER_VERSION_ONE.to_le_bytes());
+        data.extend_from_slice(&identity.vendor_id.to_le_bytes());
+        data.extend_from_slice(&identity.device_id.to_le_bytes());
+        data.extend_from_slice(&identity.uuid);
+        data.extend_from_slice(b"compiled pipelines");
+        data
+    }
+
+    #[test]
+    fn test_cache_file_round_trips() {
+        let data = driver_data(GPU);
+        assert_eq!(unwrap_cache(&wrap_cache(&data), GPU), Some(data));
+    }
+
+    #[test]
+    fn test_damaged_files_are_dropped() {
+        let file = wrap_cache(&driver_data(GPU));
+        let mut flipped = file.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert_eq!(unwrap_cache(&flipped, GPU), None);
+        assert_eq!(unwrap_cache(&file[..file.len() - 1], GPU), None);
+        assert_eq!(unwrap_cache(&file[..10], GPU), None);
+        assert_eq!(unwrap_cache(b"not a cache file at all, just some bytes", GPU), None);
+    }
+
+    #[test]
+    fn test_other_gpus_and_drivers_are_dropped() {
+        let file = wrap_cache(&driver_data(GPU));
+        let updated_driver = CacheIdentity { uuid: [8; 16], ..GPU };
+        let other_gpu = CacheIdentity { device_id: 0x1234, ..GPU };
+        assert_eq!(unwrap_cache(&file, updated_driver), None);
+        assert_eq!(unwrap_cache(&file, other_gpu), None);
+    }
+}
@@ -0,0 +1,52 @@
+//! Queue family selection
+//!
+//! Besides the graphics family, most desktop GPUs expose a transfer-only
+//! family (a DMA engine). Copies submitted there run alongside the frame on
+//! the graphics queue, with a semaphore making the graphics queue wait only
+//! where it needs the result.
+//!
+//! There is no async compute queue yet. The renderer has no compute passes,
+//! so moving particle and post-processing work onto a compute family waits
+//! until those passes exist; the selection below would pick that family the
+//! same way, with `QueueFlags::COMPUTE` wanted and graphics excluded.
+
+use vulkano::device::physical::PhysicalDevice;
+use vulkano::device::QueueFlags;
+
+/// A queue family that only does transfers (a DMA engine), if the device
+/// has one. Copies there run alongside rendering on the graphics queue.
+pub fn transfer_queue_family(physical_device: &PhysicalDevice) -> Option<u32> {
+    dedicated_family(&family_flags(physical_device), QueueFlags::TRANSFER, QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
+}
+
+fn family_flags(physical_device: &PhysicalDevice) -> Vec<QueueFlags> {
+    physical_device.queue_family_properties().iter().map(|family| family.queue_flags).collect()
+}
+
+/// First family that can do all of `wanted` and none of `excluded`
+fn dedicated_family(families: &[QueueFlags], wanted: QueueFlags, excluded: QueueFlags) -> Option<u32> {
+    families
+        .iter()
+        .position(|flags| flags.contains(wanted) && !flags.intersects(excluded))
+        .map(|index| index as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedicated_families() {
+        // A typical discrete GPU: all-purpose, compute + transfer, transfer only
+        let families = [
+            QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER,
+            QueueFlags::COMPUTE | QueueFlags::TRANSFER,
+            QueueFlags::TRANSFER | QueueFlags::SPARSE_BINDING,
+        ];
+        let transfer = dedicated_family(&families, QueueFlags::TRANSFER, QueueFlags::GRAPHICS | QueueFlags::COMPUTE);
+        assert_eq!(transfer, Some(2));
+
+        // Integrated GPUs often have just the one family
+        assert_eq!(dedicated_family(&families[..1], QueueFlags::TRANSFER, QueueFlags::GRAPHICS | QueueFlags::COMPUTE), None);
+    }
+}
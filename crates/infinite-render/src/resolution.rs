@@ -0,0 +1,346 @@
+//! Dynamic resolution
+//!
+//! The 3D scene is drawn into an offscreen [`SceneTarget`] and scaled up
+//! onto the swapchain image, with the UI drawn over it afterwards at native
+//! resolution. The target is allocated at the window's size and the scene
+//! drawn into the top-left part of it that the current scale covers, so
+//! changing the scale costs nothing. [`DynamicResolution`] picks the scale
+//! from recent GPU frame times, which a [`GpuTimer`] measures with
+//! timestamp queries.
+
+use std::sync::Arc;
+
+use vulkano::command_buffer::{AutoCommandBufferBuilder, BlitImageInfo};
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::image::sampler::Filter;
+use vulkano::image::view::ImageView;
+use vulkano::image::{AllocateImageError, Image, ImageCreateInfo, ImageType, ImageUsage};
+use vulkano::memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator};
+use vulkano::query::{QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType};
+use vulkano::render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass};
+use vulkano::sync::PipelineStage;
+use vulkano::{Validated, ValidationError, VulkanError};
+
+/// Share of the frame budget the GPU is steered towards, leaving headroom
+/// for spikes
+const TARGET_LOAD: f32 = 0.9;
+/// Loads within this band of the target leave the scale alone
+const LOAD_DEADBAND: f32 = 0.08;
+/// Largest scale change in one adjustment
+const MAX_STEP: f32 = 0.1;
+/// Frames between adjustments, so each change shows in the timings first
+const ADJUST_INTERVAL: u32 = 12;
+/// Weight of the newest frame in the smoothed GPU time
+const SMOOTHING: f32 = 0.15;
+
+/// Frame rate and scale range dynamic resolution works within
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolutionConfig {
+    /// Frame rate the GPU time is kept within
+    pub target_fps: f32,
+    /// Smallest share of the native width and height drawn
+    pub min_scale: f32,
+    /// Largest share of the native width and height drawn (at most 1)
+    pub max_scale: f32,
+}
+
+impl Default for ResolutionConfig {
+    fn default() -> Self {
+        Self {
+            target_fps: 60.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+        }
+    }
+}
+
+impl ResolutionConfig {
+    /// Milliseconds a frame may take at the target frame rate
+    pub fn frame_budget_ms(&self) -> f32 {
+        1000.0 / self.target_fps.max(1.0)
+    }
+
+    fn range(&self) -> (f32, f32) {
+        let max = self.max_scale.clamp(0.1, 1.0);
+        (self.min_scale.clamp(0.1, max), max)
+    }
+}
+
+/// Render scale that follows the GPU's frame times
+#[derive(Debug, Clone)]
+pub struct DynamicResolution {
+    config: ResolutionConfig,
+    scale: f32,
+    /// Smoothed GPU frame time (ms)
+    gpu_ms: Option<f32>,
+    /// Frames until the scale may change again
+    cooldown: u32,
+}
+
+impl DynamicResolution {
+    /// Starts at the top of the range
+    pub fn new(config: ResolutionConfig) -> Self {
+        Self {
+            scale: config.range().1,
+            config,
+            gpu_ms: None,
+            cooldown: 0,
+        }
+    }
+
+    pub fn config(&self) -> &ResolutionConfig {
+        &self.config
+    }
+
+    /// Change the target or range, keeping the scale inside the new range
+    pub fn set_config(&mut self, config: ResolutionConfig) {
+        let (min, max) = config.range();
+        self.config = config;
+        self.scale = self.scale.clamp(min, max);
+    }
+
+    /// Share of the native width and height drawn
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Smoothed GPU frame time in milliseconds, once a frame was measured
+    pub fn gpu_ms(&self) -> Option<f32> {
+        self.gpu_ms
+    }
+
+    /// Feed one finished frame's GPU time. GPU work mostly grows with the
+    /// pixel count, the square of the scale, so the scale moves by the
+    /// square root of how far the load is from the target.
+    pub fn record(&mut self, gpu_ms: f32) -> f32 {
+        let smoothed = match self.gpu_ms {
+            Some(previous) => previous + (gpu_ms - previous) * SMOOTHING,
+            None => gpu_ms,
+        };
+        self.gpu_ms = Some(smoothed);
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return self.scale;
+        }
+
+        let load = smoothed / self.config.frame_budget_ms();
+        if (load - TARGET_LOAD).abs() <= LOAD_DEADBAND || load <= 0.0 {
+            return self.scale;
+        }
+        let (min, max) = self.config.range();
+        let wanted = self.scale * (TARGET_LOAD / load).sqrt();
+        let next = wanted.clamp(self.scale - MAX_STEP, self.scale + MAX_STEP).clamp(min, max);
+        if (next - self.scale).abs() > 0.005 {
+            self.scale = next;
+            self.cooldown = ADJUST_INTERVAL;
+        }
+        self.scale
+    }
+}
+
+/// Pixel size of the part of a `native` sized target drawn at `scale`
+pub fn scaled_extent(native: [u32; 2], scale: f32) -> [u32; 2] {
+    native.map(|side| ((side as f32 * scale).round() as u32).clamp(1, side.max(1)))
+}
+
+/// Offscreen colour and depth the 3D scene is drawn into
+pub struct SceneTarget {
+    pub color: Arc<Image>,
+    pub depth: Arc<ImageView>,
+    pub framebuffer: Arc<Framebuffer>,
+}
+
+/// Why a scene target couldn't be built
+#[derive(Debug, thiserror::Error)]
+pub enum SceneTargetError {
+    #[error("failed to allocate image: {0}")]
+    Allocate(#[from] Validated<AllocateImageError>),
+    #[error("failed to create view or framebuffer: {0}")]
+    Vulkan(#[from] Validated<VulkanError>),
+}
+
+impl SceneTarget {
+    /// A target of `extent` for `render_pass`, whose attachments are a
+    /// `format` colour and a 32-bit depth
+    pub fn new(
+        allocator: Arc<StandardMemoryAllocator>,
+        render_pass: Arc<RenderPass>,
+        format: Format,
+        extent: [u32; 2],
+    ) -> Result<Self, SceneTargetError> {
+        let image = |format, usage| {
+            Image::new(
+                allocator.clone(),
+                ImageCreateInfo {
+                    image_type: ImageType::Dim2d,
+                    format,
+                    extent: [extent[0], extent[1], 1],
+                    usage,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+        };
+        let color = image(format, ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC)?;
+        let depth = ImageView::new_default(image(Format::D32_SFLOAT, ImageUsage::DEPTH_STENCIL_ATTACHMENT)?)?;
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![ImageView::new_default(color.clone())?, depth.clone()],
+                ..Default::default()
+            },
+        )?;
+        Ok(Self { color, depth, framebuffer })
+    }
+
+    pub fn extent(&self) -> [u32; 2] {
+        let [width, height, _] = self.color.extent();
+        [width, height]
+    }
+
+    /// Stretch the part drawn at `scale` over the whole of `destination`
+    pub fn blit_to(&self, destination: Arc<Image>, scale: f32) -> BlitImageInfo {
+        let [width, height] = scaled_extent(self.extent(), scale);
+        let mut blit = BlitImageInfo::images(self.color.clone(), destination);
+        blit.regions[0].src_offsets = [[0, 0, 0], [width, height, 1]];
+        blit.filter = Filter::Linear;
+        blit
+    }
+}
+
+/// GPU time of whole frames, from timestamps written at the start and end
+/// of each frame's commands. Results are read a few frames later, once the
+/// GPU is done with them; a slot still in flight skips timing that frame.
+pub struct GpuTimer {
+    pool: Arc<QueryPool>,
+    /// Nanoseconds per timestamp tick
+    period: f32,
+    /// Mask of the bits the queue's timestamps use
+    valid_mask: u64,
+    /// Frame slots written and not yet read
+    pending: Vec<bool>,
+    next: usize,
+    recording: bool,
+    finished: Option<f32>,
+}
+
+impl GpuTimer {
+    /// A timer with `frames` slots, or None if the queue family can't write
+    /// timestamps
+    pub fn new(device: Arc<Device>, queue_family_index: u32, frames: u32) -> Option<Self> {
+        let physical = device.physical_device();
+        let bits = physical.queue_family_properties().get(queue_family_index as usize)?.timestamp_valid_bits?;
+        let period = physical.properties().timestamp_period;
+        let pool = QueryPool::new(
+            device.clone(),
+            QueryPoolCreateInfo {
+                query_count: frames.max(1) * 2,
+                ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+            },
+        )
+        .ok()?;
+        Some(Self {
+            pool,
+            period,
+            valid_mask: if bits >= 64 { u64::MAX } else { (1u64 << bits) - 1 },
+            pending: vec![false; frames.max(1) as usize],
+            next: 0,
+            recording: false,
+            finished: None,
+        })
+    }
+
+    /// Start timing this frame (call outside a render pass, before any work)
+    pub fn begin<L>(&mut self, builder: &mut AutoCommandBufferBuilder<L>) -> Result<(), Box<ValidationError>> {
+        let slot = self.next;
+        if self.pending[slot] {
+            let mut ticks = [0u64; 2];
+            let range = slot as u32 * 2..slot as u32 * 2 + 2;
+            match self.pool.get_results(range, &mut ticks, QueryResultFlags::empty()) {
+                Ok(true) => {
+                    let elapsed = ticks[1].wrapping_sub(ticks[0]) & self.valid_mask;
+                    self.finished = Some(elapsed as f32 * self.period / 1_000_000.0);
+                    self.pending[slot] = false;
+                }
+                // Still in flight: leave it and time a later frame
+                _ => return Ok(()),
+            }
+        }
+        let first = slot as u32 * 2;
+        // The slot's queries aren't in use by any submitted frame
+        unsafe {
+            builder
+                .reset_query_pool(self.pool.clone(), first..first + 2)?
+                .write_timestamp(self.pool.clone(), first, PipelineStage::TopOfPipe)?;
+        }
+        self.recording = true;
+        Ok(())
+    }
+
+    /// Finish timing this frame (after its last command)
+    pub fn end<L>(&mut self, builder: &mut AutoCommandBufferBuilder<L>) -> Result<(), Box<ValidationError>> {
+        if !std::mem::take(&mut self.recording) {
+            return Ok(());
+        }
+        let slot = self.next;
+        // Written once begin() reset the query
+        unsafe {
+            builder.write_timestamp(self.pool.clone(), slot as u32 * 2 + 1, PipelineStage::BottomOfPipe)?;
+        }
+        self.pending[slot] = true;
+        self.next = (slot + 1) % self.pending.len();
+        Ok(())
+    }
+
+    /// GPU milliseconds of the latest frame read back since the last call
+    pub fn take_frame_time(&mut self) -> Option<f32> {
+        self.finished.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GPU time of a scene costing `full_ms` at native resolution
+    fn run(resolution: &mut DynamicResolution, full_ms: f32, frames: usize) -> f32 {
+        for _ in 0..frames {
+            let scale = resolution.scale();
+            resolution.record(full_ms * scale * scale);
+        }
+        resolution.scale()
+    }
+
+    #[test]
+    fn test_heavy_scenes_drop_resolution_to_fit() {
+        let mut resolution = DynamicResolution::new(ResolutionConfig::default());
+        // 25ms at native against a 16.7ms budget
+        let scale = run(&mut resolution, 25.0, 600);
+        assert!(scale < 0.85 && scale > 0.7, "{scale}");
+        let load = resolution.gpu_ms().unwrap() / resolution.config().frame_budget_ms();
+        assert!((load - TARGET_LOAD).abs() < LOAD_DEADBAND + 0.02, "{load}");
+
+        // Once the scene gets cheap again it climbs back to native
+        assert_eq!(run(&mut resolution, 6.0, 600), 1.0);
+    }
+
+    #[test]
+    fn test_scale_stays_in_range() {
+        let config = ResolutionConfig { target_fps: 144.0, min_scale: 0.6, max_scale: 0.9 };
+        let mut resolution = DynamicResolution::new(config);
+        assert_eq!(resolution.scale(), 0.9);
+        assert_eq!(run(&mut resolution, 100.0, 600), 0.6);
+
+        resolution.set_config(ResolutionConfig { min_scale: 0.7, ..config });
+        assert_eq!(resolution.scale(), 0.7);
+    }
+
+    #[test]
+    fn test_scaled_extent() {
+        assert_eq!(scaled_extent([1920, 1080], 1.0), [1920, 1080]);
+        assert_eq!(scaled_extent([1920, 1080], 0.5), [960, 540]);
+        assert_eq!(scaled_extent([3, 2], 0.01), [1, 1]);
+        assert_eq!(scaled_extent([100, 100], 2.0), [100, 100]);
+    }
+}
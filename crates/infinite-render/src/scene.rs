@@ -1,6 +1,6 @@
 //! Scene rendering coordination
 
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Vec2, Vec3};
 
 /// Scene-wide uniforms for rendering
 #[derive(Clone, Copy, Debug)]
@@ -32,6 +32,9 @@ impl Default for SceneUniforms {
     }
 }
 
+/// Wind sway amount for grass and foliage meshes (see `BasicPushConstants::with_wind`)
+pub const VEGETATION_SWAY: f32 = 0.08;
+
 /// Push constants for basic 3D rendering
 #[repr(C)]
 #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -41,6 +44,8 @@ pub struct BasicPushConstants {
     pub projection: [[f32; 4]; 4],
     pub sun_direction: [f32; 4], // xyz = direction, w = intensity
     pub sun_color: [f32; 4],     // xyz = color, w = ambient intensity
+    pub wind: [f32; 4],          // xy = wind vector (x, z), z = time, w = sway amount (0 = rigid)
+    pub highlight: [f32; 4],     // xyz = rim color, w = strength (0 = off)
 }
 
 impl BasicPushConstants {
@@ -59,9 +64,26 @@ impl BasicPushConstants {
             projection: projection.to_cols_array_2d(),
             sun_direction: [sun_direction.x, sun_direction.y, sun_direction.z, sun_intensity],
             sun_color: [sun_color.x, sun_color.y, sun_color.z, ambient_intensity],
+            wind: [0.0; 4],
+            highlight: [0.0; 4],
         }
     }
 
+    /// Enable wind sway for this draw. `sway` scales the bend per unit of
+    /// height above the model origin (0 for rigid meshes, ~0.1 for grass
+    /// and foliage).
+    pub fn with_wind(mut self, wind: Vec2, time: f32, sway: f32) -> Self {
+        self.wind = [wind.x, wind.y, time, sway];
+        self
+    }
+
+    /// Add a rim light in `color` around the mesh's silhouette, used to
+    /// outline the focused interactable. `strength` 0 turns it off.
+    pub fn with_highlight(mut self, color: Vec3, strength: f32) -> Self {
+        self.highlight = [color.x, color.y, color.z, strength];
+        self
+    }
+
     pub fn from_uniforms(model: Mat4, uniforms: &SceneUniforms) -> Self {
         Self::new(
             model,
@@ -130,3 +152,140 @@ impl SkyPushConstants {
         }
     }
 }
+
+/// Height fog and distance haze for the basic shader, in its frame set
+/// (binding 1)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FogUniforms {
+    pub color: [f32; 4],  // rgb = color, a = density at the base height
+    pub height: [f32; 4], // x = height falloff, y = base height
+    pub haze: [f32; 4],   // x = haze start, y = haze end distance
+    pub camera: [f32; 4], // xyz = camera position
+}
+
+impl FogUniforms {
+    pub fn new(
+        camera: Vec3,
+        color: Vec3,
+        density: f32,
+        height_falloff: f32,
+        base_height: f32,
+        haze_start: f32,
+        haze_end: f32,
+    ) -> Self {
+        Self {
+            color: [color.x, color.y, color.z, density],
+            height: [height_falloff, base_height, 0.0, 0.0],
+            haze: [haze_start, haze_end, 0.0, 0.0],
+            camera: [camera.x, camera.y, camera.z, 0.0],
+        }
+    }
+}
+
+/// Era look of the sun, moon and stars
+#[derive(Clone, Copy, Debug)]
+pub struct SkyStyle {
+    pub sun_tint: Vec3,
+    /// Sun disc size relative to `SkyColors::sun_size`
+    pub sun_scale: f32,
+    pub moon_tint: Vec3,
+    /// Moon disc size (1 - cosine of its angular radius)
+    pub moon_size: f32,
+    pub star_tint: Vec3,
+    /// Share of the sky holding a star
+    pub star_density: f32,
+    pub cloud_tint: Vec3,
+    /// Size of a second moon (0.0 = none)
+    pub second_moon: f32,
+    /// Brightness of a ring across the sky (0.0 = none)
+    pub ring: f32,
+}
+
+/// Procedural cloud layers
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CloudLayer {
+    /// Share of the sky covered (0.0 - 1.0)
+    pub coverage: f32,
+    /// Opacity of the clouds (0.0 - 1.0)
+    pub density: f32,
+    /// Darkness of their undersides (0.0 - 1.0)
+    pub darkness: f32,
+    /// How far the clouds drift per second, in cloud texture units
+    pub drift: Vec2,
+}
+
+/// Moon, stars and clouds for the sky dome, in its frame set (binding 0)
+/// as the push constants are full
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SkyUniforms {
+    pub moon_direction: [f32; 4], // xyz = direction, w = phase (0 = new, 0.5 = full)
+    pub moon_color: [f32; 4],     // rgb = tint, a = disc size
+    pub sun_color: [f32; 4],      // rgb = tint, a = disc size scale
+    pub stars: [f32; 4],          // rgb = tint, a = density
+    pub clouds: [f32; 4],         // x = coverage, y = density, z = darkness, w = time
+    pub cloud_color: [f32; 4],    // rgb = sunlit tint, a = horizon haze
+    pub wind: [f32; 4],           // xy = cloud drift per second
+    pub alien: [f32; 4],          // x = second moon size, y = ring brightness
+}
+
+impl SkyUniforms {
+    pub fn new(moon_direction: Vec3, moon_phase: f32, style: &SkyStyle, clouds: &CloudLayer, time: f32) -> Self {
+        Self {
+            moon_direction: [moon_direction.x, moon_direction.y, moon_direction.z, moon_phase],
+            moon_color: [style.moon_tint.x, style.moon_tint.y, style.moon_tint.z, style.moon_size],
+            sun_color: [style.sun_tint.x, style.sun_tint.y, style.sun_tint.z, style.sun_scale],
+            stars: [style.star_tint.x, style.star_tint.y, style.star_tint.z, style.star_density],
+            clouds: [clouds.coverage, clouds.density, clouds.darkness, time],
+            cloud_color: [style.cloud_tint.x, style.cloud_tint.y, style.cloud_tint.z, 0.0],
+            wind: [clouds.drift.x, clouds.drift.y, 0.0, 0.0],
+            alien: [style.second_moon, style.ring, 0.0, 0.0],
+        }
+    }
+
+    /// Fade the sky towards its horizon color near the horizon, so thick
+    /// fog on the ground meets the sky without a seam. `haze` 0 turns it off.
+    pub fn with_haze(mut self, haze: f32) -> Self {
+        self.cloud_color[3] = haze;
+        self
+    }
+}
+
+/// Push constants for emissive time-portal rendering
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PortalPushConstants {
+    pub model: [[f32; 4]; 4],
+    pub view: [[f32; 4]; 4],
+    pub projection: [[f32; 4]; 4],
+    pub rim_color: [f32; 4],       // rgb = swirl/rim color, a = time
+    pub preview_zenith: [f32; 4],  // rgb = destination sky zenith
+    pub preview_horizon: [f32; 4], // rgb = destination sky horizon
+    pub preview_ground: [f32; 4],  // rgb = destination ground, a = preview strength (0 = swirl only)
+}
+
+impl PortalPushConstants {
+    /// `preview` holds the destination's (zenith, horizon, ground) colors;
+    /// `preview_strength` 0.0 disables the destination glimpse.
+    pub fn new(
+        model: Mat4,
+        view: Mat4,
+        projection: Mat4,
+        rim_color: Vec3,
+        time: f32,
+        preview: [Vec3; 3],
+        preview_strength: f32,
+    ) -> Self {
+        let [zenith, horizon, ground] = preview;
+        Self {
+            model: model.to_cols_array_2d(),
+            view: view.to_cols_array_2d(),
+            projection: projection.to_cols_array_2d(),
+            rim_color: [rim_color.x, rim_color.y, rim_color.z, time],
+            preview_zenith: [zenith.x, zenith.y, zenith.z, 0.0],
+            preview_horizon: [horizon.x, horizon.y, horizon.z, 0.0],
+            preview_ground: [ground.x, ground.y, ground.z, preview_strength],
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Per-frame uniform data and descriptor sets
+//!
+//! Push constants only hold 128 bytes or so, which is not enough for lights,
+//! materials or anything with textures. Uniform data goes into a
+//! [`UniformRing`] instead: one persistent host-visible buffer split into a
+//! region per frame in flight, which each frame bump-allocates from and
+//! then leaves to the GPU while the next frames use the other regions.
+//!
+//! Camera, lights and fog live in set [`FRAME_SET`], built once per frame
+//! from the ring. Per-material and per-object sets can take the next set
+//! indices once a pipeline needs them.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain};
+use vulkano::buffer::{AllocateBufferError, Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer};
+use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
+use vulkano::descriptor_set::{DescriptorSet, WriteDescriptorSet};
+use vulkano::device::DeviceOwned;
+use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator};
+use vulkano::pipeline::PipelineLayout;
+use vulkano::sync::HostAccessError;
+use vulkano::{DeviceSize, Validated, VulkanError};
+
+/// Set index of the per-frame descriptor set
+pub const FRAME_SET: u32 = 0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UniformError {
+    #[error("frame uniform space used up ({needed} more bytes, {capacity} per frame)")]
+    Full { needed: DeviceSize, capacity: DeviceSize },
+    #[error("failed to allocate uniform buffer: {0}")]
+    Allocate(#[from] Validated<AllocateBufferError>),
+    #[error("failed to write uniforms: {0}")]
+    HostAccess(#[from] HostAccessError),
+    #[error("failed to create descriptor set: {0}")]
+    Vulkan(#[from] Validated<VulkanError>),
+    #[error("pipeline layout has no descriptor set {0}")]
+    MissingSet(u32),
+}
+
+impl Diagnostic for UniformError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::Full { .. } => 111,
+            Self::Allocate(_) => 112,
+            Self::HostAccess(_) => 113,
+            Self::Vulkan(_) => 114,
+            Self::MissingSet(_) => 115,
+        };
+        ErrorCode::new(ErrorDomain::Render, number)
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Allocate(_) => Some("Out of video memory. Try a lower view distance"),
+            _ => None,
+        }
+    }
+}
+
+/// Bump allocation over `frames` regions of `frame_capacity` bytes, one
+/// region per frame in flight
+#[derive(Debug, Clone)]
+pub struct RingCursor {
+    frame_capacity: DeviceSize,
+    frames: DeviceSize,
+    alignment: DeviceSize,
+    frame: DeviceSize,
+    used: DeviceSize,
+}
+
+impl RingCursor {
+    pub fn new(frame_capacity: DeviceSize, frames: u32, alignment: DeviceSize) -> Self {
+        Self {
+            frame_capacity,
+            frames: frames.max(1) as DeviceSize,
+            alignment: alignment.max(1),
+            frame: 0,
+            used: 0,
+        }
+    }
+
+    /// Bytes of the whole ring
+    pub fn total_size(&self) -> DeviceSize {
+        self.frame_capacity * self.frames
+    }
+
+    /// Move to the next frame's region, dropping what it held
+    pub fn begin_frame(&mut self) {
+        self.frame = (self.frame + 1) % self.frames;
+        self.used = 0;
+    }
+
+    /// Bytes allocated in the current frame, padding included
+    pub fn used(&self) -> DeviceSize {
+        self.used
+    }
+
+    pub fn frame_capacity(&self) -> DeviceSize {
+        self.frame_capacity
+    }
+
+    /// Byte range of `size` bytes in the current frame's region, starting at
+    /// a multiple of `alignment` (and of the ring's own alignment)
+    pub fn allocate(&mut self, size: DeviceSize, alignment: DeviceSize) -> Option<Range<DeviceSize>> {
+        let start = align_up(self.used, self.alignment.max(alignment));
+        let end = start.checked_add(size)?;
+        if end > self.frame_capacity {
+            return None;
+        }
+        self.used = end;
+        let base = self.frame * self.frame_capacity;
+        Some(base + start..base + end)
+    }
+}
+
+fn align_up(offset: DeviceSize, alignment: DeviceSize) -> DeviceSize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Uniform buffer shared by every frame in flight, each writing only its
+/// own region
+pub struct UniformRing {
+    buffer: Subbuffer<[u8]>,
+    cursor: RingCursor,
+}
+
+impl UniformRing {
+    /// A ring of `frames` regions of `frame_capacity` bytes
+    pub fn new(
+        allocator: Arc<StandardMemoryAllocator>,
+        frame_capacity: DeviceSize,
+        frames: u32,
+    ) -> Result<Self, UniformError> {
+        let alignment = allocator.device().physical_device().properties().min_uniform_buffer_offset_alignment;
+        let cursor = RingCursor::new(frame_capacity, frames, alignment.as_devicesize());
+        let buffer = Buffer::new_slice::<u8>(
+            allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::UNIFORM_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            cursor.total_size(),
+        )?;
+        Ok(Self { buffer, cursor })
+    }
+
+    /// Start a frame in the next region. The frame that last used it must
+    /// be done on the GPU; writes into a region still being read fail.
+    pub fn begin_frame(&mut self) {
+        self.cursor.begin_frame();
+    }
+
+    /// Bytes this frame has used of its region
+    pub fn used(&self) -> DeviceSize {
+        self.cursor.used()
+    }
+
+    /// Bytes of the whole ring
+    pub fn size_bytes(&self) -> DeviceSize {
+        self.cursor.total_size()
+    }
+
+    /// Copy `value` into this frame's region
+    pub fn push<T: BufferContents + Copy>(&mut self, value: T) -> Result<Subbuffer<T>, UniformError> {
+        let size = size_of::<T>() as DeviceSize;
+        let range = self.cursor.allocate(size, align_of::<T>() as DeviceSize).ok_or(UniformError::Full {
+            needed: size,
+            capacity: self.cursor.frame_capacity(),
+        })?;
+        let uniform = self.buffer.clone().slice(range).reinterpret::<T>();
+        *uniform.write()? = value;
+        Ok(uniform)
+    }
+}
+
+/// The ring plus the descriptor sets built from it each frame
+pub struct FrameUniforms {
+    ring: UniformRing,
+    allocator: Arc<StandardDescriptorSetAllocator>,
+}
+
+impl FrameUniforms {
+    pub fn new(ring: UniformRing, allocator: Arc<StandardDescriptorSetAllocator>) -> Self {
+        Self { ring, allocator }
+    }
+
+    pub fn ring(&self) -> &UniformRing {
+        &self.ring
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.ring.begin_frame();
+    }
+
+    /// This frame's [`FRAME_SET`] for `layout`, with `data` at binding 0
+    pub fn frame_set<T: BufferContents + Copy>(
+        &mut self,
+        layout: &PipelineLayout,
+        data: T,
+    ) -> Result<Arc<DescriptorSet>, UniformError> {
+        self.uniform_set(layout, FRAME_SET, data)
+    }
+
+    /// This frame's [`FRAME_SET`] for `layout`, with `data` at binding 0
+    /// and `more` at binding 1
+    pub fn frame_set_with<T: BufferContents + Copy, U: BufferContents + Copy>(
+        &mut self,
+        layout: &PipelineLayout,
+        data: T,
+        more: U,
+    ) -> Result<Arc<DescriptorSet>, UniformError> {
+        let set_layout = layout.set_layouts().get(FRAME_SET as usize).ok_or(UniformError::MissingSet(FRAME_SET))?;
+        let writes = [
+            WriteDescriptorSet::buffer(0, self.ring.push(data)?),
+            WriteDescriptorSet::buffer(1, self.ring.push(more)?),
+        ];
+        Ok(DescriptorSet::new(self.allocator.clone(), set_layout.clone(), writes, [])?)
+    }
+
+    fn uniform_set<T: BufferContents + Copy>(
+        &mut self,
+        layout: &PipelineLayout,
+        set: u32,
+        data: T,
+    ) -> Result<Arc<DescriptorSet>, UniformError> {
+        let set_layout = layout.set_layouts().get(set as usize).ok_or(UniformError::MissingSet(set))?;
+        let uniform = self.ring.push(data)?;
+        Ok(DescriptorSet::new(
+            self.allocator.clone(),
+            set_layout.clone(),
+            [WriteDescriptorSet::buffer(0, uniform)],
+            [],
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocations_are_aligned_and_bounded() {
+        let mut ring = RingCursor::new(1024, 3, 256);
+        assert_eq!(ring.allocate(100, 16), Some(0..100));
+        assert_eq!(ring.allocate(100, 16), Some(256..356));
+        assert_eq!(ring.allocate(512, 16), Some(512..1024));
+        assert_eq!(ring.allocate(1, 16), None);
+        assert_eq!(ring.used(), 1024);
+    }
+
+    #[test]
+    fn test_frames_cycle_through_regions() {
+        let mut ring = RingCursor::new(1024, 3, 64);
+        assert_eq!(ring.total_size(), 3072);
+        ring.allocate(10, 4);
+        ring.begin_frame();
+        assert_eq!(ring.used(), 0);
+        assert_eq!(ring.allocate(10, 4), Some(1024..1034));
+        ring.begin_frame();
+        assert_eq!(ring.allocate(10, 4), Some(2048..2058));
+        // Back to the first frame's region once it is done on the GPU
+        ring.begin_frame();
+        assert_eq!(ring.allocate(10, 4), Some(0..10));
+    }
+}
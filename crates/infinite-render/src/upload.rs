@@ -0,0 +1,313 @@
+//! Device-local mesh uploads through pooled staging buffers
+//!
+//! Mesh data is written into host-visible staging buffers and copied into
+//! device-local vertex/index buffers on the transfer queue. Uploads return
+//! as soon as the copies are submitted: the next frame joins
+//! [`MeshUploader::take_pending`] into its submission and the graphics
+//! queue waits on a semaphore for them, so the CPU never blocks on a copy.
+//! A whole batch of meshes (e.g. every chunk loaded this frame) shares one
+//! submission.
+
+use std::sync::Arc;
+
+use bytemuck::Pod;
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain};
+use vulkano::buffer::{AllocateBufferError, Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer};
+use vulkano::command_buffer::allocator::StandardCommandBufferAllocator;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBufferExecError, CommandBufferUsage, CopyBufferInfo};
+use vulkano::device::Queue;
+use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator};
+use vulkano::sync::{self, GpuFuture, HostAccessError, Sharing};
+use vulkano::{DeviceSize, Validated, ValidationError, VulkanError};
+
+/// Smallest staging buffer worth pooling
+const MIN_STAGING_SIZE: DeviceSize = 64 * 1024;
+/// Staging memory kept around between uploads
+const MAX_POOLED_BYTES: DeviceSize = 32 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error("cannot upload an empty buffer")]
+    Empty,
+    #[error("failed to allocate buffer: {0}")]
+    Allocate(#[from] Validated<AllocateBufferError>),
+    #[error("failed to write staging buffer: {0}")]
+    HostAccess(#[from] HostAccessError),
+    #[error("transfer submission failed: {0}")]
+    Vulkan(#[from] Validated<VulkanError>),
+    #[error("invalid transfer command: {0}")]
+    Validation(#[from] Box<ValidationError>),
+    #[error("failed to execute transfer: {0}")]
+    Execute(#[from] CommandBufferExecError),
+}
+
+impl Diagnostic for UploadError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::Empty => 101,
+            Self::Allocate(_) => 102,
+            Self::HostAccess(_) => 103,
+            Self::Vulkan(_) => 104,
+            Self::Validation(_) => 105,
+            Self::Execute(_) => 106,
+        };
+        ErrorCode::new(ErrorDomain::Render, number)
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Allocate(_) => Some("Out of video memory. Try a lower view distance"),
+            _ => None,
+        }
+    }
+}
+
+/// Vertex and index buffers resident in device-local memory
+pub struct GpuMesh<V: BufferContents> {
+    pub vertex_buffer: Subbuffer<[V]>,
+    pub index_buffer: Subbuffer<[u32]>,
+    pub index_count: u32,
+}
+
+impl<V: BufferContents> GpuMesh<V> {
+    /// Bytes held by both buffers
+    pub fn size_bytes(&self) -> DeviceSize {
+        self.vertex_buffer.size() + self.index_buffer.size()
+    }
+}
+
+/// Host-visible staging buffers, reused across uploads. Buffers handed
+/// back may still be read by copies in flight; those refuse host access
+/// until the frame waiting on the copies has finished, and are skipped.
+pub struct StagingPool {
+    allocator: Arc<StandardMemoryAllocator>,
+    free: Vec<Subbuffer<[u8]>>,
+}
+
+impl StagingPool {
+    pub fn new(allocator: Arc<StandardMemoryAllocator>) -> Self {
+        Self { allocator, free: Vec::new() }
+    }
+
+    /// A staging buffer of at least `len` bytes
+    pub fn acquire(&mut self, len: DeviceSize) -> Result<Subbuffer<[u8]>, UploadError> {
+        let sizes: Vec<DeviceSize> = self
+            .free
+            .iter()
+            .map(|buffer| if buffer.write().is_ok() { buffer.len() } else { 0 })
+            .collect();
+        if let Some(index) = best_fit(&sizes, len) {
+            return Ok(self.free.swap_remove(index));
+        }
+        let buffer = Buffer::new_slice::<u8>(
+            self.allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            staging_size(len),
+        )?;
+        Ok(buffer)
+    }
+
+    /// Hand a buffer back once its copies are submitted
+    pub fn release(&mut self, buffer: Subbuffer<[u8]>) {
+        if self.pooled_bytes() + buffer.len() <= MAX_POOLED_BYTES {
+            self.free.push(buffer);
+        }
+    }
+
+    pub fn pooled_bytes(&self) -> DeviceSize {
+        self.free.iter().map(Subbuffer::len).sum()
+    }
+}
+
+/// Uploads meshes into device-local memory on the transfer queue
+pub struct MeshUploader {
+    queue: Arc<Queue>,
+    /// Queue families the uploaded buffers are used from, when the transfer
+    /// queue isn't the graphics queue's family
+    shared_families: Vec<u32>,
+    memory_allocator: Arc<StandardMemoryAllocator>,
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+    staging: StagingPool,
+    /// Copies submitted since the last [`MeshUploader::take_pending`]
+    pending: Option<Box<dyn GpuFuture>>,
+}
+
+impl MeshUploader {
+    /// `queue` runs the copies; `graphics_family` is where the meshes are drawn
+    pub fn new(memory_allocator: Arc<StandardMemoryAllocator>, queue: Arc<Queue>, graphics_family: u32) -> Self {
+        let transfer_family = queue.queue_family_index();
+        let shared_families = if transfer_family == graphics_family {
+            Vec::new()
+        } else {
+            vec![graphics_family, transfer_family]
+        };
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            queue.device().clone(),
+            Default::default(),
+        ));
+        Self {
+            queue,
+            shared_families,
+            staging: StagingPool::new(memory_allocator.clone()),
+            memory_allocator,
+            command_buffer_allocator,
+            pending: None,
+        }
+    }
+
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Staging memory kept around for the next uploads
+    pub fn staging_bytes(&self) -> DeviceSize {
+        self.staging.pooled_bytes()
+    }
+
+    /// Upload one mesh, drawable by the next frame that waits on
+    /// [`MeshUploader::take_pending`]
+    pub fn upload<V: BufferContents + Pod>(&mut self, vertices: &[V], indices: &[u32]) -> Result<GpuMesh<V>, UploadError> {
+        let mut meshes = self.upload_all([(vertices, indices)])?;
+        Ok(meshes.remove(0))
+    }
+
+    /// Upload a batch of meshes in one submission, drawable by the next
+    /// frame that waits on [`MeshUploader::take_pending`]
+    pub fn upload_all<'a, V: BufferContents + Pod>(
+        &mut self,
+        meshes: impl IntoIterator<Item = (&'a [V], &'a [u32])>,
+    ) -> Result<Vec<GpuMesh<V>>, UploadError> {
+        let mut uploaded = Vec::new();
+        let mut writes = Vec::new();
+        for (vertices, indices) in meshes {
+            let vertex_buffer = self.device_local::<V>(BufferUsage::VERTEX_BUFFER, vertices.len() as DeviceSize)?;
+            let index_buffer = self.device_local::<u32>(BufferUsage::INDEX_BUFFER, indices.len() as DeviceSize)?;
+            writes.push((bytemuck::cast_slice(vertices), vertex_buffer.as_bytes().clone()));
+            writes.push((bytemuck::cast_slice(indices), index_buffer.as_bytes().clone()));
+            uploaded.push(GpuMesh {
+                vertex_buffer,
+                index_buffer,
+                index_count: indices.len() as u32,
+            });
+        }
+        self.write_all(writes)?;
+        Ok(uploaded)
+    }
+
+    /// Copy each byte slice into its device-local target in one
+    /// submission, returning once it is submitted. The targets must not be
+    /// in use by frames still in flight.
+    pub fn write_all<'a>(&mut self, writes: impl IntoIterator<Item = (&'a [u8], Subbuffer<[u8]>)>) -> Result<(), UploadError> {
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.command_buffer_allocator.clone(),
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+
+        let mut staged = Vec::new();
+        let result = (|| {
+            for (bytes, target) in writes {
+                let staging = self.staging.acquire(bytes.len() as DeviceSize)?;
+                staging.write()?[..bytes.len()].copy_from_slice(bytes);
+                builder.copy_buffer(CopyBufferInfo::buffers(
+                    staging.clone().slice(..bytes.len() as DeviceSize),
+                    target,
+                ))?;
+                staged.push(staging);
+            }
+            if !staged.is_empty() {
+                // Chained after earlier copies not yet handed to a frame
+                let previous = self.pending.take().unwrap_or_else(|| sync::now(self.queue.device().clone()).boxed());
+                let copies = previous.then_execute(self.queue.clone(), builder.build()?)?;
+                copies.flush()?;
+                self.pending = Some(copies.boxed());
+            }
+            Ok(())
+        })();
+
+        // The pool skips them until the copies reading them are done
+        for staging in staged {
+            self.staging.release(staging);
+        }
+        result
+    }
+
+    /// The copies submitted since the last call, as a future that signals
+    /// a semaphore once they have landed. Join it into the next frame's
+    /// submission so the graphics queue waits for the meshes on the GPU.
+    pub fn take_pending(&mut self) -> Result<Option<Box<dyn GpuFuture>>, UploadError> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(None);
+        };
+        Ok(Some(pending.then_signal_semaphore_and_flush()?.boxed()))
+    }
+
+    /// An uninitialized device-local buffer that uploads can copy into
+    pub fn device_local<T: BufferContents>(&self, usage: BufferUsage, len: DeviceSize) -> Result<Subbuffer<[T]>, UploadError> {
+        if len == 0 {
+            return Err(UploadError::Empty);
+        }
+        let sharing = if self.shared_families.is_empty() {
+            Sharing::Exclusive
+        } else {
+            Sharing::Concurrent(self.shared_families.iter().copied().collect())
+        };
+        let buffer = Buffer::new_slice::<T>(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: usage | BufferUsage::TRANSFER_DST,
+                sharing,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+            len,
+        )?;
+        Ok(buffer)
+    }
+}
+
+/// Staging allocations are rounded up to a power of two so they can be
+/// reused for differently sized meshes
+fn staging_size(len: DeviceSize) -> DeviceSize {
+    len.max(MIN_STAGING_SIZE).next_power_of_two()
+}
+
+/// Index of the smallest free buffer that holds `len` bytes
+fn best_fit(sizes: &[DeviceSize], len: DeviceSize) -> Option<usize> {
+    sizes
+        .iter()
+        .enumerate()
+        .filter(|(_, &size)| size >= len)
+        .min_by_key(|(_, &size)| size)
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staging_sizes_round_up() {
+        assert_eq!(staging_size(1), MIN_STAGING_SIZE);
+        assert_eq!(staging_size(MIN_STAGING_SIZE + 1), MIN_STAGING_SIZE * 2);
+        assert_eq!(staging_size(3 << 20), 4 << 20);
+    }
+
+    #[test]
+    fn test_best_fit_picks_smallest_that_fits() {
+        let sizes = [1 << 20, 64 << 10, 256 << 10];
+        assert_eq!(best_fit(&sizes, 100 << 10), Some(2));
+        assert_eq!(best_fit(&sizes, 64 << 10), Some(1));
+        assert_eq!(best_fit(&sizes, 2 << 20), None);
+    }
+}
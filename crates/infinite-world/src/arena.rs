@@ -0,0 +1,134 @@
+//! Survival arena: a walled fighting floor that stands in for the streamed
+//! world in survival mode
+//!
+//! The arena is a square floor ringed by walls, with four pillars for cover
+//! between the centre and the corners. It is built from the same boxes as
+//! dungeon interiors, so it renders and collides the same way.
+
+use glam::Vec3;
+use infinite_physics::PhysicsWorld;
+use rapier3d::prelude::ColliderHandle;
+
+use crate::dungeon::{BlockKind, DungeonBlock};
+
+/// Half the side length of the arena floor
+pub const ARENA_HALF_SIZE: f32 = 30.0;
+
+/// Height of the walls around the floor
+const WALL_HEIGHT: f32 = 5.0;
+
+/// Thickness of the floor slab and the walls
+const SLAB_THICKNESS: f32 = 1.0;
+
+/// Half the side length of a pillar
+const PILLAR_HALF_SIZE: f32 = 1.5;
+
+/// Distance along each axis from the centre to a pillar
+const PILLAR_OFFSET: f32 = 12.0;
+
+/// The survival arena built into the physics world. Remove it when the run
+/// is over.
+pub struct SurvivalArena {
+    /// Centre of the floor surface
+    pub center: Vec3,
+    blocks: Vec<DungeonBlock>,
+    colliders: Vec<ColliderHandle>,
+}
+
+impl SurvivalArena {
+    /// Build the arena with its floor surface centred on `center`
+    pub fn build(center: Vec3, physics: &mut PhysicsWorld) -> Self {
+        let blocks = Self::layout(center);
+        let colliders = blocks
+            .iter()
+            .map(|block| physics.create_static_box(block.half_extents, block.center))
+            .collect();
+        Self { center, blocks, colliders }
+    }
+
+    /// Floor, walls and pillars of an arena centred on `center`
+    fn layout(center: Vec3) -> Vec<DungeonBlock> {
+        let half = ARENA_HALF_SIZE;
+        let wall_y = center.y + WALL_HEIGHT * 0.5;
+        let mut blocks = vec![DungeonBlock {
+            center: center - Vec3::Y * (SLAB_THICKNESS * 0.5),
+            half_extents: Vec3::new(half, SLAB_THICKNESS * 0.5, half),
+            kind: BlockKind::Floor,
+        }];
+        let along_x = Vec3::new(half + SLAB_THICKNESS, WALL_HEIGHT * 0.5, SLAB_THICKNESS * 0.5);
+        let along_z = Vec3::new(SLAB_THICKNESS * 0.5, WALL_HEIGHT * 0.5, half + SLAB_THICKNESS);
+        let edge = half + SLAB_THICKNESS * 0.5;
+        for side in [-1.0, 1.0] {
+            blocks.push(DungeonBlock {
+                center: Vec3::new(center.x, wall_y, center.z + side * edge),
+                half_extents: along_x,
+                kind: BlockKind::Wall,
+            });
+            blocks.push(DungeonBlock {
+                center: Vec3::new(center.x + side * edge, wall_y, center.z),
+                half_extents: along_z,
+                kind: BlockKind::Wall,
+            });
+        }
+        for (x, z) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+            blocks.push(DungeonBlock {
+                center: Vec3::new(center.x + x * PILLAR_OFFSET, wall_y, center.z + z * PILLAR_OFFSET),
+                half_extents: Vec3::new(PILLAR_HALF_SIZE, WALL_HEIGHT * 0.5, PILLAR_HALF_SIZE),
+                kind: BlockKind::Wall,
+            });
+        }
+        blocks
+    }
+
+    /// Remove the arena's geometry from the physics world
+    pub fn remove(self, physics: &mut PhysicsWorld) {
+        for handle in self.colliders {
+            physics.remove_collider(handle);
+        }
+    }
+
+    /// Geometry to render
+    pub fn blocks(&self) -> &[DungeonBlock] {
+        &self.blocks
+    }
+
+    /// Where the player starts a run: on the floor, south of the centre
+    pub fn spawn(&self) -> Vec3 {
+        self.center + Vec3::new(0.0, 0.0, ARENA_HALF_SIZE * 0.5)
+    }
+
+    /// Floor height at a world position, if it is inside the arena
+    pub fn floor_height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let inside = (x - self.center.x).abs() <= ARENA_HALF_SIZE && (z - self.center.z).abs() <= ARENA_HALF_SIZE;
+        inside.then_some(self.center.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arena_builds_and_removes_geometry() {
+        let mut physics = PhysicsWorld::new();
+        let center = Vec3::new(0.0, 2.0, 0.0);
+        let arena = SurvivalArena::build(center, &mut physics);
+        assert_eq!(physics.collider_set.len(), arena.blocks().len());
+        assert_eq!(arena.blocks().iter().filter(|b| b.kind == BlockKind::Floor).count(), 1);
+
+        let spawn = arena.spawn();
+        assert_eq!(arena.floor_height_at(spawn.x, spawn.z), Some(2.0));
+        assert_eq!(arena.floor_height_at(ARENA_HALF_SIZE + 5.0, 0.0), None);
+
+        // The floor under the spawn is solid, and a wall stops anyone walking off
+        physics.update_query_pipeline();
+        let filter = rapier3d::prelude::QueryFilter::default();
+        let hit = physics.raycast(spawn + Vec3::Y, Vec3::NEG_Y, 2.0, filter);
+        assert!(hit.is_some_and(|(_, toi)| (toi - 1.0).abs() < 0.01));
+        let wall = physics.raycast(center + Vec3::Y, Vec3::X, ARENA_HALF_SIZE + 2.0, filter);
+        assert!(wall.is_some_and(|(_, toi)| (toi - ARENA_HALF_SIZE).abs() < 0.01));
+
+        arena.remove(&mut physics);
+        assert_eq!(physics.collider_set.len(), 0);
+    }
+}
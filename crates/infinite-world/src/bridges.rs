@@ -0,0 +1,393 @@
+//! Bridges carrying roads over ravines
+//!
+//! Where the ground along a road drops well below the road's height profile,
+//! the road is carried across on a bridge instead of being filled in. The
+//! spans are found while the road is laid (see [`RoadNetwork::bridges_in`]),
+//! so like the roads themselves they follow from the world seed alone.
+//!
+//! A bridge is built from boxes: a deck spanning bank to bank, a rail along
+//! either edge and piers down to the ravine floor, sized by the era's style.
+//! Bridges take damage from blasts and collapse once it runs out, leaving the
+//! piers standing.
+//!
+//! [`RoadNetwork::bridges_in`]: crate::roads::RoadNetwork::bridges_in
+
+use glam::{Quat, Vec3};
+use infinite_physics::PhysicsWorld;
+use rapier3d::prelude::ColliderHandle;
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkCoord;
+use crate::roads::RoadQuality;
+
+/// How far the deck reaches past each bank, so there's no lip to trip on
+const BANK_OVERLAP: f32 = 1.0;
+/// Piers only go where the deck is at least this high off the ground
+const MIN_PIER_HEIGHT: f32 = 0.5;
+/// How deep piers are sunk into the ground
+const PIER_FOOTING: f32 = 0.5;
+
+/// How an era builds its bridges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BridgeStyle {
+    /// Planks on log trestles
+    Timber,
+    /// Stone arches
+    Stone,
+    /// Girders on concrete piers
+    Steel,
+    /// Hard-light decks with nothing underneath
+    Energy,
+}
+
+/// A piece of a bridge (for rendering)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgePart {
+    Deck,
+    Rail,
+    Pier,
+}
+
+impl BridgeStyle {
+    /// Bridges of the roads of an era
+    pub fn for_quality(quality: RoadQuality) -> Self {
+        match quality {
+            RoadQuality::DirtTrack => Self::Timber,
+            RoadQuality::Cobblestone => Self::Stone,
+            RoadQuality::Paved => Self::Steel,
+            RoadQuality::Hoverway => Self::Energy,
+        }
+    }
+
+    /// What the style's bridges are called, after the place name
+    pub fn noun(self) -> &'static str {
+        match self {
+            Self::Timber => "Crossing",
+            Self::Stone => "Bridge",
+            Self::Steel => "Viaduct",
+            Self::Energy => "Lightbridge",
+        }
+    }
+
+    pub fn deck_thickness(self) -> f32 {
+        match self {
+            Self::Timber => 0.3,
+            Self::Stone => 0.8,
+            Self::Steel => 0.5,
+            Self::Energy => 0.2,
+        }
+    }
+
+    /// Height and thickness of the rails
+    pub fn rail(self) -> (f32, f32) {
+        match self {
+            Self::Timber => (1.0, 0.15),
+            Self::Stone => (0.9, 0.45),
+            Self::Steel => (1.1, 0.1),
+            Self::Energy => (1.2, 0.05),
+        }
+    }
+
+    /// Distance between piers, and their thickness along the deck. Energy
+    /// bridges have none.
+    pub fn piers(self) -> Option<(f32, f32)> {
+        match self {
+            Self::Timber => Some((6.0, 0.4)),
+            Self::Stone => Some((10.0, 1.6)),
+            Self::Steel => Some((16.0, 0.9)),
+            Self::Energy => None,
+        }
+    }
+
+    /// Damage a bridge takes before it collapses
+    pub fn max_health(self) -> f32 {
+        match self {
+            Self::Timber => 60.0,
+            Self::Stone => 400.0,
+            Self::Steel => 250.0,
+            Self::Energy => 150.0,
+        }
+    }
+
+    pub fn color(self, part: BridgePart) -> [f32; 3] {
+        match (self, part) {
+            (Self::Timber, BridgePart::Pier) => [0.3, 0.22, 0.14],
+            (Self::Timber, _) => [0.45, 0.33, 0.2],
+            (Self::Stone, BridgePart::Deck) => [0.5, 0.48, 0.44],
+            (Self::Stone, _) => [0.58, 0.55, 0.5],
+            (Self::Steel, BridgePart::Pier) => [0.55, 0.55, 0.53],
+            (Self::Steel, _) => [0.35, 0.38, 0.42],
+            (Self::Energy, BridgePart::Deck) => [0.55, 0.85, 0.95],
+            (Self::Energy, _) => [0.8, 0.95, 1.0],
+        }
+    }
+}
+
+/// A bridge on a road
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bridge {
+    /// Stable identifier, the same whenever the bridge is generated
+    pub id: u64,
+    pub name: String,
+    /// Chunk the middle of the deck is over
+    pub chunk: ChunkCoord,
+    pub style: BridgeStyle,
+    /// Deck surface at either bank
+    pub start: Vec3,
+    pub end: Vec3,
+    /// Width of the deck in metres
+    pub width: f32,
+}
+
+/// An oriented box of bridge geometry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BridgeBlock {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub rotation: Quat,
+    pub part: BridgePart,
+}
+
+impl Bridge {
+    /// Middle of the deck surface
+    pub fn center(&self) -> Vec3 {
+        (self.start + self.end) * 0.5
+    }
+
+    /// Horizontal distance from bank to bank
+    pub fn span(&self) -> f32 {
+        (self.end - self.start).with_y(0.0).length()
+    }
+
+    /// Distance from `point` to the deck's centreline
+    pub fn distance_to(&self, point: Vec3) -> f32 {
+        let along = self.end - self.start;
+        let t = ((point - self.start).dot(along) / along.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        (self.start + along * t).distance(point)
+    }
+
+    /// The deck, rails and piers. `ground` gives the natural height the
+    /// piers stand on at a world x/z.
+    pub fn blocks(&self, ground: impl Fn(f32, f32) -> f32) -> Vec<BridgeBlock> {
+        let along = self.end - self.start;
+        let flat = along.with_y(0.0);
+        let yaw = Quat::from_rotation_y((-flat.z).atan2(flat.x));
+        let rotation = yaw * Quat::from_rotation_z(along.y.atan2(flat.length()));
+        let (up, side) = (rotation * Vec3::Y, rotation * Vec3::Z);
+        let half_length = along.length() * 0.5;
+        let center = self.center();
+        let thickness = self.style.deck_thickness();
+        let (rail_height, rail_thickness) = self.style.rail();
+
+        let mut blocks = vec![BridgeBlock {
+            center: center - up * (thickness * 0.5),
+            half_extents: Vec3::new(half_length + BANK_OVERLAP, thickness * 0.5, self.width * 0.5),
+            rotation,
+            part: BridgePart::Deck,
+        }];
+        for sign in [1.0, -1.0] {
+            blocks.push(BridgeBlock {
+                center: center + up * (rail_height * 0.5) + side * (sign * (self.width - rail_thickness) * 0.5),
+                half_extents: Vec3::new(half_length, rail_height * 0.5, rail_thickness * 0.5),
+                rotation,
+                part: BridgePart::Rail,
+            });
+        }
+
+        if let Some((spacing, pier_thickness)) = self.style.piers() {
+            let count = (self.span() / spacing).ceil().max(1.0) as usize;
+            for step in 1..count {
+                let top = self.start + along * (step as f32 / count as f32) - Vec3::Y * thickness;
+                let floor = ground(top.x, top.z) - PIER_FOOTING;
+                if top.y - floor < MIN_PIER_HEIGHT + PIER_FOOTING {
+                    continue;
+                }
+                blocks.push(BridgeBlock {
+                    center: top.with_y((top.y + floor) * 0.5),
+                    half_extents: Vec3::new(pier_thickness * 0.5, (top.y - floor) * 0.5, self.width * 0.4),
+                    rotation: yaw,
+                    part: BridgePart::Pier,
+                });
+            }
+        }
+        blocks
+    }
+}
+
+/// A bridge built into the physics world
+pub struct BridgeInstance {
+    pub bridge: Bridge,
+    health: f32,
+    blocks: Vec<BridgeBlock>,
+    /// One per block
+    colliders: Vec<ColliderHandle>,
+}
+
+impl BridgeInstance {
+    /// Add the bridge's geometry to the physics world. A bridge that has
+    /// already collapsed is built as its ruins.
+    pub fn build(
+        bridge: Bridge,
+        ground: impl Fn(f32, f32) -> f32,
+        collapsed: bool,
+        physics: &mut PhysicsWorld,
+    ) -> Self {
+        let mut blocks = bridge.blocks(ground);
+        if collapsed {
+            blocks.retain(|block| block.part == BridgePart::Pier);
+        }
+        let colliders = blocks
+            .iter()
+            .map(|block| physics.create_static_box_rotated(block.half_extents, block.center, block.rotation))
+            .collect();
+        let health = if collapsed { 0.0 } else { bridge.style.max_health() };
+        Self {
+            bridge,
+            health,
+            blocks,
+            colliders,
+        }
+    }
+
+    /// Remove the bridge's geometry from the physics world
+    pub fn remove(self, physics: &mut PhysicsWorld) {
+        for handle in self.colliders {
+            physics.remove_collider(handle);
+        }
+    }
+
+    /// Geometry to render
+    pub fn blocks(&self) -> &[BridgeBlock] {
+        &self.blocks
+    }
+
+    /// Damage left before the bridge collapses
+    pub fn health(&self) -> f32 {
+        self.health
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    /// Wear the bridge down. Returns true if this brought it down: the deck
+    /// and rails are gone and only the piers are left.
+    pub fn damage(&mut self, amount: f32, physics: &mut PhysicsWorld) -> bool {
+        if self.is_collapsed() || amount <= 0.0 {
+            return false;
+        }
+        self.health = (self.health - amount).max(0.0);
+        if !self.is_collapsed() {
+            return false;
+        }
+
+        let mut index = 0;
+        while index < self.blocks.len() {
+            if self.blocks[index].part == BridgePart::Pier {
+                index += 1;
+            } else {
+                self.blocks.swap_remove(index);
+                physics.remove_collider(self.colliders.swap_remove(index));
+            }
+        }
+        true
+    }
+}
+
+/// Stretches of a road to bridge: runs of samples where the ground is below
+/// the road `profile`, at least one of them by more than `depth`, from the
+/// last sample on the bank before to the first one after
+pub(crate) fn find_spans(raw: &[f32], profile: &[f32], depth: f32) -> Vec<(usize, usize)> {
+    let below = |i: usize| raw[i] < profile[i];
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < raw.len() {
+        if profile[i] - raw[i] <= depth {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        while start > 0 && below(start) {
+            start -= 1;
+        }
+        let mut end = i;
+        while end + 1 < raw.len() && below(end) {
+            end += 1;
+        }
+        spans.push((start, end));
+        i = end + 1;
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bridge(style: BridgeStyle) -> Bridge {
+        Bridge {
+            id: 7,
+            name: "Test Bridge".to_string(),
+            chunk: ChunkCoord::new(0, 0),
+            style,
+            start: Vec3::new(0.0, 10.0, 0.0),
+            end: Vec3::new(30.0, 12.0, 20.0),
+            width: 6.0,
+        }
+    }
+
+    #[test]
+    fn test_spans_run_from_bank_to_bank() {
+        let profile = vec![5.0; 12];
+        let mut raw = vec![5.5; 12];
+        // A ravine from samples 3 to 7, with a shallow dip that gets filled
+        raw[3..=7].copy_from_slice(&[4.0, 1.0, 0.5, 1.5, 4.5]);
+        raw[10] = 4.8;
+        assert_eq!(find_spans(&raw, &profile, 1.5), vec![(2, 8)]);
+        assert!(find_spans(&raw, &profile, 5.0).is_empty(), "too shallow to bridge");
+        assert!(find_spans(&profile, &profile, 1.5).is_empty());
+    }
+
+    #[test]
+    fn test_blocks_follow_the_deck() {
+        let bridge = bridge(BridgeStyle::Stone);
+        let blocks = bridge.blocks(|_, _| 0.0);
+        let deck = blocks.iter().find(|b| b.part == BridgePart::Deck).unwrap();
+        // The deck's local x axis runs from bank to bank
+        let axis = deck.rotation * Vec3::X;
+        assert!(axis.dot((bridge.end - bridge.start).normalize()) > 0.999);
+        assert!((deck.rotation * Vec3::Z).y.abs() < 1e-4, "no roll");
+        assert_eq!(blocks.iter().filter(|b| b.part == BridgePart::Rail).count(), 2);
+
+        let piers: Vec<_> = blocks.iter().filter(|b| b.part == BridgePart::Pier).collect();
+        assert_eq!(piers.len(), (bridge.span() / 10.0).ceil() as usize - 1);
+        for pier in piers {
+            let bottom = pier.center.y - pier.half_extents.y;
+            assert!((bottom + PIER_FOOTING).abs() < 1e-4, "piers stand on the ground");
+        }
+
+        // Energy bridges float, and no piers go where the ground is high
+        let energy = Bridge { style: BridgeStyle::Energy, ..bridge.clone() };
+        assert!(energy.blocks(|_, _| 0.0).iter().all(|b| b.part != BridgePart::Pier));
+        assert!(bridge.blocks(|_, _| 11.0).iter().all(|b| b.part != BridgePart::Pier));
+    }
+
+    #[test]
+    fn test_bridges_collapse_to_their_piers() {
+        let mut physics = PhysicsWorld::new();
+        let mut instance = BridgeInstance::build(bridge(BridgeStyle::Timber), |_, _| 0.0, false, &mut physics);
+        let piers = instance.blocks().iter().filter(|b| b.part == BridgePart::Pier).count();
+        assert!(piers > 0);
+        assert!(!instance.damage(20.0, &mut physics));
+        assert_eq!(instance.health(), 40.0);
+        assert!(instance.damage(100.0, &mut physics));
+        assert!(instance.is_collapsed());
+        assert_eq!(instance.blocks().len(), piers);
+        assert_eq!(instance.colliders.len(), piers);
+        assert!(!instance.damage(10.0, &mut physics), "only collapses once");
+
+        let ruins = BridgeInstance::build(bridge(BridgeStyle::Timber), |_, _| 0.0, true, &mut physics);
+        assert!(ruins.is_collapsed());
+        assert_eq!(ruins.blocks().len(), piers);
+    }
+}
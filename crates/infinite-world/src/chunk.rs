@@ -1,14 +1,25 @@
 //! Chunk-based world streaming system
 //!
 //! Replaces monolithic terrain with a grid of chunks that load/unload around the player.
+//! Changes the player makes are kept in a `ChunkStore` and merged back onto
+//! each chunk as it's generated. An era change can either swap the terrain
+//! outright or blend it over from the previous era's heights. Built
+//! colliders are kept in a `ColliderCache`, so a chunk loading again with
+//! the same terrain reuses its heightfield.
 
 use std::collections::HashMap;
 
 use glam::Vec3;
+use infinite_core::BranchId;
 use infinite_physics::PhysicsWorld;
-use rapier3d::prelude::ColliderHandle;
+use rapier3d::prelude::{Collider, ColliderHandle};
 
+use crate::chunk_store::{ChunkDelta, ChunkStore, TerrainEdit};
+use crate::collider_cache::{ColliderCache, ColliderKey};
+use crate::error::WorldError;
 use crate::era_config::TimeTerrainConfig;
+use crate::bridges::Bridge;
+use crate::roads::RoadNetwork;
 use crate::terrain::{Terrain, TerrainConfig};
 
 /// Grid coordinate for a chunk
@@ -102,6 +113,30 @@ pub struct ChunkManager {
     pub newly_loaded: Vec<ChunkCoord>,
     /// Chunks that were just unloaded this frame (for mesh cleanup)
     pub newly_unloaded: Vec<ChunkCoord>,
+    /// Loaded chunks whose terrain was edited since the last `take_edited`
+    edited: Vec<ChunkCoord>,
+    /// Loaded chunks still showing the previous era's terrain
+    stale: Vec<ChunkCoord>,
+    /// Persistent player changes, merged onto chunks as they load
+    store: ChunkStore,
+    /// In-progress blend from the previous era's terrain
+    era_blend: Option<EraBlend>,
+    /// Problems recovered from since the last `take_errors`
+    errors: Vec<WorldError>,
+    /// Roads flattened into chunks as they generate
+    roads: Option<RoadNetwork>,
+    /// Heightfields built before, for chunks that load again unchanged
+    colliders: ColliderCache,
+}
+
+/// Terrain morphing from one era into the current one
+struct EraBlend {
+    /// Era being blended away from
+    from: Option<TimeTerrainConfig>,
+    /// 0.0 = the old era's terrain, 1.0 = the current one's
+    t: f32,
+    /// Both eras' unedited terrain per chunk, generated once per blend
+    sources: HashMap<ChunkCoord, (Terrain, Terrain)>,
 }
 
 impl ChunkManager {
@@ -114,10 +149,110 @@ impl ChunkManager {
             time_terrain_config: None,
             newly_loaded: Vec::new(),
             newly_unloaded: Vec::new(),
+            edited: Vec::new(),
+            stale: Vec::new(),
+            store: ChunkStore::in_memory(),
+            era_blend: None,
+            errors: Vec::new(),
+            roads: None,
+            colliders: ColliderCache::default(),
+        }
+    }
+
+    /// Lay roads into chunks generated from now on (`reload_all` or
+    /// `begin_regeneration` to apply them to loaded ones)
+    pub fn set_roads(&mut self, roads: Option<RoadNetwork>) {
+        self.roads = roads;
+        self.colliders.clear();
+    }
+
+    pub fn roads(&self) -> Option<&RoadNetwork> {
+        self.roads.as_ref()
+    }
+
+    /// Replace the store of persistent chunk changes. Loaded chunks keep
+    /// their terrain until they reload. Returns the old store's final
+    /// flush result.
+    pub fn set_store(&mut self, store: ChunkStore) -> std::io::Result<()> {
+        let result = self.flush_store();
+        self.store = store;
+        self.colliders.clear();
+        result
+    }
+
+    /// Switch the store to another year's changes (before `reload_all` or
+    /// `begin_regeneration`)
+    pub fn set_store_year(&mut self, year: i64) {
+        self.store.set_year(year);
+    }
+
+    /// Switch the store to another timeline branch's changes (before
+    /// `reload_all` or `begin_regeneration`)
+    pub fn set_store_branch(&mut self, branch: BranchId) {
+        self.store.set_branch(branch);
+    }
+
+    /// Fork the store's changes into a new timeline branch. The loaded
+    /// chunks already match the copy.
+    pub fn fork_store_branch(&mut self, branch: BranchId) -> std::io::Result<()> {
+        self.store.fork(branch)
+    }
+
+    /// Changes recorded for a chunk
+    pub fn chunk_delta(&mut self, coord: ChunkCoord) -> Option<&ChunkDelta> {
+        self.store.delta(coord)
+    }
+
+    /// Record a change to a chunk (harvested resource, destroyed prop,
+    /// placed item). Terrain edits go through `edit_terrain`.
+    pub fn chunk_delta_mut(&mut self, coord: ChunkCoord) -> &mut ChunkDelta {
+        self.store.delta_mut(coord)
+    }
+
+    /// Reshape the terrain. The edit is recorded in every chunk it touches
+    /// and applied right away to the loaded ones; see `take_edited`.
+    pub fn edit_terrain(&mut self, edit: TerrainEdit, physics: &mut PhysicsWorld) {
+        for coord in edit.affected_chunks(self.config.chunk_size) {
+            self.store.delta_mut(coord).terrain_edits.push(edit);
+            self.colliders.remove(&self.collider_key(coord));
+            let origin = coord.world_origin(self.config.chunk_size);
+            let Some(chunk) = self.loaded_chunks.get_mut(&coord) else {
+                continue;
+            };
+            edit.apply(&mut chunk.terrain, origin);
+            if let Some(handle) = chunk.collider_handle.take() {
+                physics.remove_collider(handle);
+            }
+            chunk.collider_handle = Some(Self::create_collider(&chunk.terrain, coord, self.config.chunk_size, physics));
+            chunk.mesh_dirty = true;
+            if !self.edited.contains(&coord) {
+                self.edited.push(coord);
+            }
         }
     }
 
-    /// Set the time-period terrain config (triggers full reload on next update)
+    /// Loaded chunks whose terrain changed since the last call, by edits or
+    /// regeneration (to rebuild their meshes)
+    pub fn take_edited(&mut self) -> Vec<ChunkCoord> {
+        let loaded = &self.loaded_chunks;
+        self.edited.drain(..).filter(|coord| loaded.contains_key(coord)).collect()
+    }
+
+    /// Problems the world recovered from since the last call, oldest first
+    pub fn take_errors(&mut self) -> Vec<WorldError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Write pending chunk changes to disk
+    pub fn flush_store(&mut self) -> std::io::Result<()> {
+        match self.store.take_error() {
+            Some(e) => Err(e),
+            None => self.store.flush(),
+        }
+    }
+
+    /// Set the time-period terrain config. Newly loaded chunks use it right
+    /// away; `begin_regeneration` or `reload_all` applies it to loaded ones.
     pub fn set_time_terrain_config(&mut self, config: Option<TimeTerrainConfig>) {
         self.time_terrain_config = config;
     }
@@ -147,6 +282,36 @@ impl ChunkManager {
         self.loaded_chunks.len()
     }
 
+    /// Collider cache budget in bytes, dropping colliders until it fits
+    pub fn set_collider_budget(&mut self, bytes: usize) {
+        self.colliders.set_budget(bytes);
+    }
+
+    pub fn collider_cache(&self) -> &ColliderCache {
+        &self.colliders
+    }
+
+    /// Approximate host memory held for loaded chunks: their heightfields,
+    /// the copies handed to physics, any era blend sources and the
+    /// collider cache
+    pub fn memory_bytes(&self) -> usize {
+        let chunks: usize = self
+            .loaded_chunks
+            .values()
+            .map(|chunk| {
+                let collider = if chunk.collider_handle.is_some() { chunk.terrain.heap_bytes() } else { 0 };
+                std::mem::size_of::<Chunk>() + chunk.terrain.heap_bytes() + collider
+            })
+            .sum();
+        let blend: usize = self
+            .era_blend
+            .iter()
+            .flat_map(|blend| blend.sources.values())
+            .map(|(from, to)| from.heap_bytes() + to.heap_bytes())
+            .sum();
+        chunks + blend + self.colliders.bytes()
+    }
+
     /// Unload all chunks and reload around the given position.
     /// Used for time-period transitions.
     pub fn reload_all(&mut self, player_pos: Vec3, physics: &mut PhysicsWorld) {
@@ -168,6 +333,97 @@ impl ChunkManager {
         }
     }
 
+    /// Mark every loaded chunk for regeneration with the current terrain
+    /// config. Unlike `reload_all` the chunks stay loaded (with their old
+    /// terrain and colliders) until `regenerate` gets to them.
+    pub fn begin_regeneration(&mut self) {
+        self.stale = self.loaded_chunks.keys().copied().collect();
+    }
+
+    /// Regenerate up to `budget` stale chunks, nearest to the player first.
+    /// Rebuilt chunks are reported by `take_edited`. Returns how many were
+    /// rebuilt.
+    pub fn regenerate(&mut self, player_pos: Vec3, physics: &mut PhysicsWorld, budget: usize) -> usize {
+        let center = self.player_chunk(player_pos);
+        // Farthest first so the nearest can be popped off the end
+        self.stale.sort_by_key(|coord| {
+            let (dx, dz) = ((coord.x - center.x) as i64, (coord.z - center.z) as i64);
+            std::cmp::Reverse(dx * dx + dz * dz)
+        });
+
+        let mut rebuilt = 0;
+        while rebuilt < budget {
+            let Some(coord) = self.stale.pop() else {
+                break;
+            };
+            if !self.loaded_chunks.contains_key(&coord) {
+                continue;
+            }
+            let terrain = self.generate_terrain(coord);
+            let collider = self.chunk_collider(&terrain, coord);
+            let Some(chunk) = self.loaded_chunks.get_mut(&coord) else {
+                continue;
+            };
+            if let Some(handle) = chunk.collider_handle.take() {
+                physics.remove_collider(handle);
+            }
+            chunk.collider_handle = Some(physics.add_static_collider(collider));
+            chunk.terrain = terrain;
+            chunk.mesh_dirty = true;
+            if !self.edited.contains(&coord) {
+                self.edited.push(coord);
+            }
+            rebuilt += 1;
+        }
+        rebuilt
+    }
+
+    /// Start blending from `from` into the current time-period config (set
+    /// it first). Chunks keep showing the old era until `set_era_blend`
+    /// moves the blend along.
+    pub fn begin_era_blend(&mut self, from: Option<TimeTerrainConfig>) {
+        self.era_blend = Some(EraBlend {
+            from,
+            t: 0.0,
+            sources: HashMap::new(),
+        });
+    }
+
+    /// Move the blend to `t` (0.0 = old era, 1.0 = new era) and mark every
+    /// loaded chunk for regeneration at that blend
+    pub fn set_era_blend(&mut self, t: f32) {
+        let Some(blend) = &mut self.era_blend else {
+            return;
+        };
+        let t = t.clamp(0.0, 1.0);
+        if blend.t != t {
+            blend.t = t;
+            self.begin_regeneration();
+        }
+    }
+
+    /// Current blend factor, while a blend is in progress
+    pub fn era_blend(&self) -> Option<f32> {
+        self.era_blend.as_ref().map(|blend| blend.t)
+    }
+
+    /// End the blend. Chunks regenerated at 1.0 already match the new era;
+    /// any still stale are rebuilt with it directly.
+    pub fn finish_era_blend(&mut self) {
+        self.era_blend = None;
+    }
+
+    /// Chunks still waiting for `regenerate`
+    pub fn stale_count(&self) -> usize {
+        self.stale.len()
+    }
+
+    /// Stale chunks within `radius` chunks of the player
+    pub fn stale_within(&self, player_pos: Vec3, radius: u32) -> usize {
+        let center = self.player_chunk(player_pos);
+        self.stale.iter().filter(|coord| coord.distance(&center) <= radius).count()
+    }
+
     /// Update chunk loading/unloading based on player position.
     /// Call this each frame.
     pub fn update(&mut self, player_pos: Vec3, physics: &mut PhysicsWorld) {
@@ -184,9 +440,13 @@ impl ChunkManager {
             .copied()
             .collect();
 
+        let unloaded_any = !to_unload.is_empty();
         for coord in to_unload {
             self.unload_chunk(coord, physics);
         }
+        if unloaded_any {
+            self.store.evict(self.loaded_chunks.keys().copied());
+        }
 
         // Load nearby chunks
         let radius = self.config.load_radius as i32;
@@ -217,9 +477,79 @@ impl ChunkManager {
     }
 
     fn load_chunk(&mut self, coord: ChunkCoord, physics: &mut PhysicsWorld) {
+        let terrain = self.generate_terrain(coord);
+        let collider_handle = physics.add_static_collider(self.chunk_collider(&terrain, coord));
+
+        self.loaded_chunks.insert(
+            coord,
+            Chunk {
+                coord,
+                terrain,
+                collider_handle: Some(collider_handle),
+                mesh_dirty: true,
+            },
+        );
+        self.newly_loaded.push(coord);
+    }
+
+    /// Terrain for a chunk under the current era (or blend), with its
+    /// stored edits
+    fn generate_terrain(&mut self, coord: ChunkCoord) -> Terrain {
         let origin = coord.world_origin(self.config.chunk_size);
+        let mut terrain = match self.era_blend.take() {
+            Some(mut blend) => {
+                let (from, to) = blend.sources.entry(coord).or_insert_with(|| {
+                    (
+                        self.era_terrain(coord, blend.from.as_ref()),
+                        self.era_terrain(coord, self.time_terrain_config.as_ref()),
+                    )
+                });
+                let terrain = from.blend(to, blend.t);
+                self.era_blend = Some(blend);
+                terrain
+            }
+            None => self.era_terrain(coord, self.time_terrain_config.as_ref()),
+        };
+        if let Some(delta) = self.store.delta(coord) {
+            for edit in &delta.terrain_edits {
+                edit.apply(&mut terrain, origin);
+            }
+        }
+        let bad_samples = terrain.sanitize();
+        if bad_samples > 0 {
+            self.errors.push(WorldError::ChunkGeneration { coord, bad_samples });
+        }
+        terrain
+    }
+
+    /// Bridges over the chunk under the current era, if roads are laid
+    pub fn bridges_in(&self, coord: ChunkCoord) -> Vec<Bridge> {
+        let Some(roads) = &self.roads else {
+            return Vec::new();
+        };
+        let era = self.time_terrain_config.as_ref();
+        let quality = era.map(|tc| tc.road_quality).unwrap_or_default();
+        roads.bridges_in(coord, quality, &self.chunk_terrain_config(era))
+    }
 
-        // Build a terrain config for this chunk
+    /// Unedited terrain for a chunk under an era's modifiers
+    fn era_terrain(&self, coord: ChunkCoord, era: Option<&TimeTerrainConfig>) -> Terrain {
+        let origin = coord.world_origin(self.config.chunk_size);
+
+        // Generate terrain for this chunk at its world offset
+        let mut terrain = Terrain::generate_chunk(
+            self.chunk_terrain_config(era),
+            origin.x,
+            origin.z,
+        );
+        if let Some(roads) = &self.roads {
+            roads.apply(&mut terrain, coord, era.map(|tc| tc.road_quality).unwrap_or_default());
+        }
+        terrain
+    }
+
+    /// Terrain config for a chunk under an era's modifiers
+    fn chunk_terrain_config(&self, era: Option<&TimeTerrainConfig>) -> TerrainConfig {
         let mut chunk_terrain_config = TerrainConfig {
             size: self.config.chunk_size,
             subdivisions: self.config.subdivisions,
@@ -232,41 +562,63 @@ impl ChunkManager {
         };
 
         // Apply time-period terrain modifiers if present
-        if let Some(tc) = &self.time_terrain_config {
+        if let Some(tc) = era {
             chunk_terrain_config.seed = chunk_terrain_config.seed.wrapping_add(tc.seed_offset);
             chunk_terrain_config.max_height *= tc.height_scale;
             chunk_terrain_config.noise_scale *= tc.noise_scale_mult;
         }
+        chunk_terrain_config
+    }
 
-        // Generate terrain for this chunk at its world offset
-        let terrain = Terrain::generate_chunk(
-            chunk_terrain_config,
-            origin.x,
-            origin.z,
-        );
+    /// What a chunk's collider would be built from right now
+    fn collider_key(&self, coord: ChunkCoord) -> ColliderKey {
+        ColliderKey {
+            seed: self.terrain_config.seed,
+            year: self.store.year(),
+            branch: self.store.branch(),
+            coord,
+        }
+    }
 
-        // Create physics heightfield at the chunk's world position
+    /// Collider for freshly generated terrain, from the cache when the
+    /// chunk has loaded this way before. Blended terrain is never cached.
+    fn chunk_collider(&mut self, terrain: &Terrain, coord: ChunkCoord) -> Collider {
+        if self.era_blend.is_some() {
+            return Self::build_collider(terrain, coord, self.config.chunk_size);
+        }
+        let key = self.collider_key(coord);
+        if let Some(collider) = self.colliders.get(&key, self.time_terrain_config.as_ref()) {
+            return collider;
+        }
+        let collider = Self::build_collider(terrain, coord, self.config.chunk_size);
+        self.colliders.insert(key, self.time_terrain_config.clone(), collider.clone());
+        collider
+    }
+
+    fn build_collider(terrain: &Terrain, coord: ChunkCoord, chunk_size: f32) -> Collider {
+        let (nrows, ncols) = terrain.physics_dimensions();
+        let center = coord.world_center(chunk_size);
+        PhysicsWorld::heightfield_collider_at(
+            &terrain.physics_heights(),
+            nrows,
+            ncols,
+            Vec3::new(chunk_size, 1.0, chunk_size),
+            Vec3::new(center.x, 0.0, center.z),
+        )
+    }
+
+    /// Physics heightfield at the chunk's world position
+    fn create_collider(terrain: &Terrain, coord: ChunkCoord, chunk_size: f32, physics: &mut PhysicsWorld) -> ColliderHandle {
         let (nrows, ncols) = terrain.physics_dimensions();
         let heights = terrain.physics_heights();
-        let center = coord.world_center(self.config.chunk_size);
-        let collider_handle = physics.create_heightfield_at(
+        let center = coord.world_center(chunk_size);
+        physics.create_heightfield_at(
             &heights,
             nrows,
             ncols,
-            Vec3::new(self.config.chunk_size, 1.0, self.config.chunk_size),
+            Vec3::new(chunk_size, 1.0, chunk_size),
             Vec3::new(center.x, 0.0, center.z),
-        );
-
-        self.loaded_chunks.insert(
-            coord,
-            Chunk {
-                coord,
-                terrain,
-                collider_handle: Some(collider_handle),
-                mesh_dirty: true,
-            },
-        );
-        self.newly_loaded.push(coord);
+        )
     }
 
     fn unload_chunk(&mut self, coord: ChunkCoord, physics: &mut PhysicsWorld) {
@@ -274,6 +626,10 @@ impl ChunkManager {
             if let Some(handle) = chunk.collider_handle {
                 physics.remove_collider(handle);
             }
+            self.stale.retain(|stale| *stale != coord);
+            if let Some(blend) = &mut self.era_blend {
+                blend.sources.remove(&coord);
+            }
             self.newly_unloaded.push(coord);
         }
     }
@@ -339,6 +695,7 @@ mod tests {
 
         // Should have (2*1+1)^2 = 9 chunks loaded
         assert_eq!(manager.loaded_count(), 9);
+        assert!(manager.memory_bytes() >= 9 * 25 * std::mem::size_of::<f32>());
 
         // Move far away - old chunks should unload, new ones load
         manager.update(Vec3::new(500.0, 0.0, 500.0), &mut physics);
@@ -352,6 +709,166 @@ mod tests {
         assert!(manager.get_chunk(&new_center).is_some());
     }
 
+    #[test]
+    fn test_terrain_edits_survive_reload() {
+        let config = ChunkConfig {
+            chunk_size: 64.0,
+            subdivisions: 4,
+            load_radius: 0,
+            unload_radius: 0,
+        };
+        let terrain_config = TerrainConfig {
+            size: 64.0,
+            subdivisions: 4,
+            ..Default::default()
+        };
+        let mut manager = ChunkManager::new(config, terrain_config);
+        let mut physics = PhysicsWorld::new();
+        manager.update(Vec3::ZERO, &mut physics);
+        let before = manager.height_at(32.0, 32.0);
+
+        manager.edit_terrain(TerrainEdit::new(Vec3::new(32.0, 0.0, 32.0), 10.0, -2.0), &mut physics);
+        assert_eq!(manager.take_edited(), vec![ChunkCoord::new(0, 0)]);
+        assert!((manager.height_at(32.0, 32.0) - (before - 2.0)).abs() < 1e-4);
+
+        // Regenerated from noise, with the edit merged back on
+        manager.update(Vec3::new(500.0, 0.0, 500.0), &mut physics);
+        manager.update(Vec3::ZERO, &mut physics);
+        assert!((manager.height_at(32.0, 32.0) - (before - 2.0)).abs() < 1e-4);
+        assert!(manager.take_edited().is_empty());
+    }
+
+    #[test]
+    fn test_colliders_reused_across_time_travel() {
+        let config = ChunkConfig {
+            chunk_size: 64.0,
+            subdivisions: 4,
+            load_radius: 0,
+            unload_radius: 0,
+        };
+        let terrain_config = TerrainConfig {
+            size: 64.0,
+            subdivisions: 4,
+            ..Default::default()
+        };
+        let mut manager = ChunkManager::new(config, terrain_config);
+        let mut physics = PhysicsWorld::new();
+        // The collider's heights add up to the chunk's terrain's
+        let matches_terrain = |manager: &ChunkManager, physics: &PhysicsWorld| {
+            let chunk = manager.get_chunk(&ChunkCoord::new(0, 0)).unwrap();
+            let collider = physics.get_collider(chunk.collider_handle.unwrap()).unwrap();
+            let heightfield = collider.shape().as_heightfield().unwrap();
+            (heightfield.heights().sum() - chunk.terrain.heights.iter().sum::<f32>()).abs() < 1e-3
+        };
+        manager.set_store_year(2025);
+        manager.update(Vec3::ZERO, &mut physics);
+
+        let travel = |manager: &mut ChunkManager, physics: &mut PhysicsWorld, year: i64| {
+            manager.set_time_terrain_config((year != 2025).then(|| TimeTerrainConfig::for_year(year, 2025)));
+            manager.set_store_year(year);
+            manager.reload_all(Vec3::ZERO, physics);
+        };
+        travel(&mut manager, &mut physics, -3000);
+        assert_eq!(manager.collider_cache().stats(), (0, 2));
+        travel(&mut manager, &mut physics, 2025);
+        assert_eq!(manager.collider_cache().stats(), (1, 2), "back in a visited year");
+        assert!(matches_terrain(&manager, &physics));
+        assert_eq!(manager.collider_cache().len(), 2);
+
+        // An edit is rebuilt into the collider, not reused from before it
+        manager.edit_terrain(TerrainEdit::new(Vec3::new(32.0, 0.0, 32.0), 10.0, -2.0), &mut physics);
+        travel(&mut manager, &mut physics, 2025);
+        assert_eq!(manager.collider_cache().stats(), (1, 3));
+        assert!(matches_terrain(&manager, &physics));
+
+        manager.set_collider_budget(0);
+        assert!(manager.collider_cache().is_empty());
+    }
+
+    #[test]
+    fn test_regeneration_is_budgeted_nearest_first() {
+        let config = ChunkConfig {
+            chunk_size: 64.0,
+            subdivisions: 4,
+            load_radius: 1,
+            unload_radius: 2,
+        };
+        let terrain_config = TerrainConfig {
+            size: 64.0,
+            subdivisions: 4,
+            ..Default::default()
+        };
+        let mut manager = ChunkManager::new(config, terrain_config);
+        let mut physics = PhysicsWorld::new();
+        let player = Vec3::new(32.0, 0.0, 32.0);
+        manager.update(player, &mut physics);
+        let heights = |manager: &ChunkManager, coord| manager.get_chunk(&coord).unwrap().terrain.heights.clone();
+        let (center, corner) = (ChunkCoord::new(0, 0), ChunkCoord::new(1, 1));
+        let (center_before, corner_before) = (heights(&manager, center), heights(&manager, corner));
+
+        manager.set_time_terrain_config(Some(TimeTerrainConfig::for_year(-3000, 2025)));
+        manager.begin_regeneration();
+        assert_eq!(manager.stale_within(player, 0), 1);
+
+        // The player's chunk goes first; the rest keep their old terrain
+        assert_eq!(manager.regenerate(player, &mut physics, 1), 1);
+        assert_eq!(manager.stale_within(player, 0), 0);
+        assert_eq!(manager.stale_count(), 8);
+        assert_ne!(heights(&manager, center), center_before);
+        assert_eq!(heights(&manager, corner), corner_before);
+        assert_eq!(manager.take_edited(), vec![center]);
+
+        assert_eq!(manager.regenerate(player, &mut physics, 100), 8);
+        assert_eq!(manager.stale_count(), 0);
+        assert_ne!(heights(&manager, corner), corner_before);
+        assert_eq!(manager.loaded_count(), 9);
+    }
+
+    #[test]
+    fn test_era_blend_morphs_terrain() {
+        let config = ChunkConfig {
+            chunk_size: 64.0,
+            subdivisions: 4,
+            load_radius: 0,
+            unload_radius: 1,
+        };
+        let terrain_config = TerrainConfig {
+            size: 64.0,
+            subdivisions: 4,
+            ..Default::default()
+        };
+        let mut manager = ChunkManager::new(config, terrain_config);
+        let mut physics = PhysicsWorld::new();
+        let player = Vec3::new(32.0, 0.0, 32.0);
+        manager.update(player, &mut physics);
+        let heights = |manager: &ChunkManager| manager.get_chunk(&ChunkCoord::new(0, 0)).unwrap().terrain.heights.clone();
+        let present = heights(&manager);
+
+        manager.set_time_terrain_config(Some(TimeTerrainConfig::for_year(-3000, 2025)));
+        manager.begin_era_blend(None);
+        assert_eq!(manager.era_blend(), Some(0.0));
+        assert_eq!(manager.stale_count(), 0, "nothing changes until the blend moves");
+
+        manager.set_era_blend(0.5);
+        assert_eq!(manager.regenerate(player, &mut physics, 10), 1);
+        let half = heights(&manager);
+        manager.set_era_blend(1.0);
+        manager.regenerate(player, &mut physics, 10);
+        let past = heights(&manager);
+        assert_ne!(half, present);
+        assert_ne!(half, past);
+        for ((a, b), mid) in present.iter().zip(&past).zip(&half) {
+            assert!((mid - (a + b) / 2.0).abs() < 1e-4);
+        }
+
+        // Once finished, regeneration gives the same terrain directly
+        manager.finish_era_blend();
+        assert_eq!(manager.era_blend(), None);
+        manager.begin_regeneration();
+        manager.regenerate(player, &mut physics, 10);
+        assert_eq!(heights(&manager), past);
+    }
+
     #[test]
     fn test_chunk_terrain_generation_offsets() {
         let config = TerrainConfig {
@@ -0,0 +1,523 @@
+//! Persistent per-chunk world changes
+//!
+//! Chunks are regenerated from noise every time they load, so anything the
+//! player changes is recorded as a `ChunkDelta` and merged back onto the
+//! generated chunk. Deltas are grouped into compressed region files (see
+//! `region`) of `REGION_SIZE` x `REGION_SIZE` chunks, one directory per
+//! year, so the world remembers changes separately in every era. Timeline
+//! branches other than the main one get their own tree of years:
+//!
+//! ```text
+//! <dir>/<year>/r.<region x>.<region z>.region
+//! <dir>/branches/<branch>/<year>/r.<region x>.<region z>.region
+//! ```
+//!
+//! Only chunks changed since the last flush are re-encoded; everything
+//! else in a region is written back as the compressed bytes it was read as.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use glam::Vec3;
+use infinite_core::BranchId;
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkCoord;
+use crate::region::RegionFile;
+use crate::terrain::Terrain;
+
+/// Chunks per region file along each axis
+pub const REGION_SIZE: i32 = 16;
+
+/// A brush stroke on the terrain: raises (positive depth) or lowers the
+/// ground within `radius` of `center`, with a smooth falloff to the rim
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TerrainEdit {
+    /// World XZ position of the brush center
+    pub center: [f32; 2],
+    pub radius: f32,
+    /// Height change at the center in meters
+    pub depth: f32,
+}
+
+impl TerrainEdit {
+    pub fn new(center: Vec3, radius: f32, depth: f32) -> Self {
+        Self {
+            center: [center.x, center.z],
+            radius,
+            depth,
+        }
+    }
+
+    /// Chunks whose terrain this edit touches
+    pub fn affected_chunks(&self, chunk_size: f32) -> Vec<ChunkCoord> {
+        let min = ChunkCoord::from_world_pos(
+            Vec3::new(self.center[0] - self.radius, 0.0, self.center[1] - self.radius),
+            chunk_size,
+        );
+        let max = ChunkCoord::from_world_pos(
+            Vec3::new(self.center[0] + self.radius, 0.0, self.center[1] + self.radius),
+            chunk_size,
+        );
+        (min.z..=max.z)
+            .flat_map(|z| (min.x..=max.x).map(move |x| ChunkCoord::new(x, z)))
+            .collect()
+    }
+
+    /// Apply the edit to a chunk's terrain, whose min corner is at `origin`
+    pub fn apply(&self, terrain: &mut Terrain, origin: Vec3) {
+        let vertex_count = terrain.config.subdivisions + 1;
+        let step = terrain.config.size / terrain.config.subdivisions as f32;
+        for z in 0..vertex_count {
+            for x in 0..vertex_count {
+                let dx = origin.x + x as f32 * step - self.center[0];
+                let dz = origin.z + z as f32 * step - self.center[1];
+                let t = (dx * dx + dz * dz) / (self.radius * self.radius);
+                if t < 1.0 {
+                    terrain.heights[(z * vertex_count + x) as usize] += self.depth * (1.0 - t) * (1.0 - t);
+                }
+            }
+        }
+        terrain.min_height = terrain.heights.iter().copied().fold(f32::MAX, f32::min);
+        terrain.max_height = terrain.heights.iter().copied().fold(f32::MIN, f32::max);
+    }
+}
+
+/// An item the player left lying in the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlacedItem {
+    pub item: String,
+    pub position: [f32; 3],
+}
+
+/// Everything the player changed in one chunk
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkDelta {
+    /// Resource nodes (by their index within the chunk) harvested for good
+    #[serde(default)]
+    pub harvested: BTreeSet<u32>,
+    /// Harvested resource nodes that grow back, with the play time (in
+    /// seconds) at which they do
+    #[serde(default)]
+    pub regrowing: BTreeMap<u32, f64>,
+    /// Props (by their index within the chunk) that were destroyed
+    #[serde(default)]
+    pub destroyed_props: BTreeSet<u32>,
+    #[serde(default)]
+    pub placed_items: Vec<PlacedItem>,
+    /// Terrain edits touching this chunk, in the order they were made
+    #[serde(default)]
+    pub terrain_edits: Vec<TerrainEdit>,
+}
+
+impl ChunkDelta {
+    pub fn is_empty(&self) -> bool {
+        self.harvested.is_empty()
+            && self.regrowing.is_empty()
+            && self.destroyed_props.is_empty()
+            && self.placed_items.is_empty()
+            && self.terrain_edits.is_empty()
+    }
+
+    /// Whether resource node `index` can be harvested at play time `now`
+    pub fn node_available(&self, index: u32, now: f64) -> bool {
+        !self.harvested.contains(&index) && self.regrowing.get(&index).is_none_or(|&at| at <= now)
+    }
+
+    /// Record resource node `index` as harvested at play time `now`. It grows
+    /// back after `regrow` seconds, or never. Nodes that have grown back
+    /// since are forgotten.
+    pub fn harvest_node(&mut self, index: u32, now: f64, regrow: Option<f64>) {
+        self.regrowing.retain(|_, at| *at > now);
+        match regrow {
+            Some(seconds) => {
+                self.regrowing.insert(index, now + seconds);
+            }
+            None => {
+                self.harvested.insert(index);
+            }
+        }
+    }
+}
+
+/// A cached region: the file's compressed slots plus the deltas decoded
+/// from it so far
+struct Region {
+    file: RegionFile,
+    chunks: HashMap<ChunkCoord, ChunkDelta>,
+    /// Chunks changed since the last flush
+    dirty: HashSet<ChunkCoord>,
+    /// Slots whose data was unreadable
+    undecodable: usize,
+}
+
+impl Region {
+    fn new(file: RegionFile) -> Self {
+        Self {
+            file,
+            chunks: HashMap::new(),
+            dirty: HashSet::new(),
+            undecodable: 0,
+        }
+    }
+
+    /// Decoded delta for a chunk, reading it from its slot the first time
+    fn chunk(&mut self, coord: ChunkCoord) -> &mut ChunkDelta {
+        let Self { file, chunks, undecodable, .. } = self;
+        chunks.entry(coord).or_insert_with(|| match file.get(slot_of(coord)) {
+            Some(data) => serde_json::from_slice(&data).unwrap_or_else(|_| {
+                *undecodable += 1;
+                ChunkDelta::default()
+            }),
+            None => ChunkDelta::default(),
+        })
+    }
+
+    /// Re-encode the changed chunks into their slots
+    fn encode_dirty(&mut self) -> io::Result<()> {
+        for coord in std::mem::take(&mut self.dirty) {
+            let index = slot_of(coord);
+            match self.chunks.get(&coord).filter(|d| !d.is_empty()) {
+                Some(delta) => self.file.set(index, &serde_json::to_vec(delta)?)?,
+                None => self.file.remove(index),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Region coordinate containing a chunk
+fn region_of(coord: ChunkCoord) -> (i32, i32) {
+    (coord.x.div_euclid(REGION_SIZE), coord.z.div_euclid(REGION_SIZE))
+}
+
+/// Slot of a chunk within its region file
+fn slot_of(coord: ChunkCoord) -> usize {
+    (coord.z.rem_euclid(REGION_SIZE) * REGION_SIZE + coord.x.rem_euclid(REGION_SIZE)) as usize
+}
+
+/// Chunk deltas for the active branch and year, read from and written to
+/// region files on demand. Without a directory the store only lives in
+/// memory.
+pub struct ChunkStore {
+    dir: Option<PathBuf>,
+    branch: BranchId,
+    year: i64,
+    regions: HashMap<(i32, i32), Region>,
+    /// First IO error since the last `take_error`
+    error: Option<io::Error>,
+}
+
+impl ChunkStore {
+    /// Store that forgets everything when dropped
+    pub fn in_memory() -> Self {
+        Self {
+            dir: None,
+            branch: BranchId::MAIN,
+            year: 0,
+            regions: HashMap::new(),
+            error: None,
+        }
+    }
+
+    /// Store backed by region files under `dir`
+    pub fn open(dir: PathBuf, year: i64) -> Self {
+        Self {
+            dir: Some(dir),
+            year,
+            ..Self::in_memory()
+        }
+    }
+
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    pub fn branch(&self) -> BranchId {
+        self.branch
+    }
+
+    /// Switch to another timeline branch's changes, writing out the
+    /// current ones
+    pub fn set_branch(&mut self, branch: BranchId) {
+        if branch == self.branch {
+            return;
+        }
+        self.flush_or_keep_error();
+        if self.dir.is_some() {
+            self.regions.clear();
+        }
+        self.branch = branch;
+    }
+
+    /// Start a new branch holding a copy of the current branch's changes
+    /// in every year, and switch to it
+    pub fn fork(&mut self, branch: BranchId) -> io::Result<()> {
+        self.flush()?;
+        if let Some(dir) = &self.dir {
+            copy_years(&branch_dir(dir, self.branch), &branch_dir(dir, branch))?;
+        }
+        // The cached regions already hold exactly what was copied
+        self.branch = branch;
+        Ok(())
+    }
+
+    /// Switch to another year's changes, writing out the current ones
+    pub fn set_year(&mut self, year: i64) {
+        if year == self.year {
+            return;
+        }
+        self.flush_or_keep_error();
+        // In-memory stores keep nothing to reload, so keep their regions
+        if self.dir.is_some() {
+            self.regions.clear();
+        }
+        self.year = year;
+    }
+
+    /// Changes recorded for a chunk, if any
+    pub fn delta(&mut self, coord: ChunkCoord) -> Option<&ChunkDelta> {
+        let delta = self.region(region_of(coord)).chunk(coord);
+        (!delta.is_empty()).then_some(delta)
+    }
+
+    /// Changes for a chunk, to modify. The chunk is written on the next
+    /// flush.
+    pub fn delta_mut(&mut self, coord: ChunkCoord) -> &mut ChunkDelta {
+        let region = self.region(region_of(coord));
+        region.dirty.insert(coord);
+        region.chunk(coord)
+    }
+
+    /// Write every region with changed chunks to disk, one file write per
+    /// region however many of its chunks changed
+    pub fn flush(&mut self) -> io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let dir = branch_dir(dir, self.branch).join(self.year.to_string());
+        for (&key, region) in self.regions.iter_mut().filter(|(_, r)| !r.dirty.is_empty()) {
+            region.encode_dirty()?;
+            region.file.write(&region_path(&dir, key))?;
+        }
+        Ok(())
+    }
+
+    /// Chunks lost to damaged region files in the regions read so far
+    pub fn corrupted_chunks(&self) -> usize {
+        self.regions.values().map(|r| r.file.corrupted() + r.undecodable).sum()
+    }
+
+    /// Write out and drop cached regions that contain none of `keep`
+    pub fn evict(&mut self, keep: impl IntoIterator<Item = ChunkCoord>) {
+        if self.dir.is_none() {
+            return;
+        }
+        let keep: BTreeSet<(i32, i32)> = keep.into_iter().map(region_of).collect();
+        self.flush_or_keep_error();
+        self.regions.retain(|key, region| !region.dirty.is_empty() || keep.contains(key));
+    }
+
+    /// IO error from a read or a flush done along the way (evicting,
+    /// switching year)
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    fn flush_or_keep_error(&mut self) {
+        if let Err(e) = self.flush() {
+            self.error.get_or_insert(e);
+        }
+    }
+
+    /// Cached region, read from disk the first time it's needed
+    fn region(&mut self, key: (i32, i32)) -> &mut Region {
+        let path = self
+            .dir
+            .as_ref()
+            .map(|dir| region_path(&branch_dir(dir, self.branch).join(self.year.to_string()), key));
+        let error = &mut self.error;
+        self.regions.entry(key).or_insert_with(|| {
+            let empty = || RegionFile::new(REGION_SIZE as u16);
+            let file = match path.map(|path| RegionFile::read(&path, REGION_SIZE as u16)) {
+                Some(Ok(file)) => file,
+                // Unreadable right now: carry on as if nothing changed there
+                Some(Err(e)) => {
+                    error.get_or_insert(e);
+                    empty()
+                }
+                None => empty(),
+            };
+            Region::new(file)
+        })
+    }
+}
+
+fn region_path(dir: &Path, (x, z): (i32, i32)) -> PathBuf {
+    dir.join(format!("r.{}.{}.region", x, z))
+}
+
+/// Root of a branch's year directories. The main timeline keeps the
+/// original layout so worlds from before branching still load.
+fn branch_dir(dir: &Path, branch: BranchId) -> PathBuf {
+    if branch == BranchId::MAIN {
+        dir.to_path_buf()
+    } else {
+        dir.join("branches").join(branch.to_string())
+    }
+}
+
+/// Copy the region files of every year directory under `from` into `to`
+fn copy_years(from: &Path, to: &Path) -> io::Result<()> {
+    let years = match std::fs::read_dir(from) {
+        Ok(years) => years,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for year in years {
+        let year = year?;
+        // Skips the main timeline's `branches` directory
+        let is_year = year.file_name().to_str().is_some_and(|name| name.parse::<i64>().is_ok());
+        if !is_year || !year.file_type()?.is_dir() {
+            continue;
+        }
+        let target = to.join(year.file_name());
+        std::fs::create_dir_all(&target)?;
+        for file in std::fs::read_dir(year.path())? {
+            let file = file?;
+            if file.path().extension().is_some_and(|ext| ext == "region") {
+                std::fs::copy(file.path(), target.join(file.file_name()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::TerrainConfig;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("infinite_chunk_store_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_region_round_trip() {
+        let dir = temp_dir("round_trip");
+        let far = ChunkCoord::new(-17, 40);
+        {
+            let mut store = ChunkStore::open(dir.clone(), 2025);
+            store.delta_mut(ChunkCoord::new(0, 0)).harvested.insert(3);
+            store.delta_mut(far).placed_items.push(PlacedItem {
+                item: "Ancient Coin".to_string(),
+                position: [1.0, 2.0, 3.0],
+            });
+            // Touched but left empty: not written
+            store.delta_mut(ChunkCoord::new(5, 5));
+            store.flush().unwrap();
+        }
+        assert!(dir.join("2025").join("r.0.0.region").exists());
+        assert!(dir.join("2025").join("r.-2.2.region").exists());
+
+        let mut store = ChunkStore::open(dir.clone(), 2025);
+        assert!(store.delta(ChunkCoord::new(0, 0)).unwrap().harvested.contains(&3));
+        assert_eq!(store.delta(far).unwrap().placed_items[0].item, "Ancient Coin");
+        assert!(store.delta(ChunkCoord::new(5, 5)).is_none());
+        assert_eq!(store.corrupted_chunks(), 0);
+
+        // A damaged region file reads as unchanged instead of failing
+        fs::write(dir.join("2025").join("r.0.0.region"), b"not a region").unwrap();
+        let mut store = ChunkStore::open(dir.clone(), 2025);
+        assert!(store.delta(ChunkCoord::new(0, 0)).is_none());
+        assert_eq!(store.corrupted_chunks(), 1);
+        assert!(store.take_error().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_harvested_nodes_regrow() {
+        let mut delta = ChunkDelta::default();
+        delta.harvest_node(0, 10.0, None);
+        delta.harvest_node(1, 10.0, Some(60.0));
+        assert!(!delta.node_available(0, 1_000.0));
+        assert!(!delta.node_available(1, 30.0));
+        assert!(delta.node_available(1, 70.0));
+        assert!(delta.node_available(2, 10.0));
+
+        // Grown-back nodes drop out, so the chunk can go back to unchanged
+        let mut delta = ChunkDelta::default();
+        delta.harvest_node(1, 10.0, Some(60.0));
+        delta.harvest_node(2, 80.0, Some(60.0));
+        assert_eq!(delta.regrowing.keys().copied().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn test_years_are_separate() {
+        let dir = temp_dir("years");
+        let coord = ChunkCoord::new(1, 1);
+        let mut store = ChunkStore::open(dir.clone(), 2025);
+        store.delta_mut(coord).destroyed_props.insert(7);
+
+        store.set_year(1200);
+        assert!(store.delta(coord).is_none(), "changes stay in their own era");
+        store.set_year(2025);
+        assert!(store.delta(coord).unwrap().destroyed_props.contains(&7));
+        assert!(store.take_error().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_branches_fork_a_copy() {
+        let dir = temp_dir("branches");
+        let coord = ChunkCoord::new(3, -4);
+        let mut store = ChunkStore::open(dir.clone(), 1200);
+        store.delta_mut(coord).destroyed_props.insert(1);
+        store.set_year(2025);
+        store.delta_mut(coord).destroyed_props.insert(2);
+
+        let branch = BranchId(1);
+        store.fork(branch).unwrap();
+        assert!(dir.join("branches").join("1").join("2025").join("r.0.-1.region").exists());
+        store.delta_mut(coord).destroyed_props.insert(3);
+        store.set_year(1200);
+        assert!(store.delta(coord).unwrap().destroyed_props.contains(&1), "every year is copied");
+
+        // The main timeline never sees the branch's changes
+        store.set_branch(BranchId::MAIN);
+        store.set_year(2025);
+        let main = &store.delta(coord).unwrap().destroyed_props;
+        assert!(main.contains(&2) && !main.contains(&3));
+        store.set_branch(branch);
+        assert!(store.delta(coord).unwrap().destroyed_props.contains(&3));
+        assert!(store.take_error().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_terrain_edit_falloff() {
+        let config = TerrainConfig {
+            size: 8.0,
+            subdivisions: 8,
+            ..TerrainConfig::default()
+        };
+        let mut terrain = Terrain::generate_chunk(config, 0.0, 0.0);
+        let before = terrain.heights.clone();
+        let edit = TerrainEdit::new(Vec3::new(4.0, 0.0, 4.0), 2.0, -1.0);
+        edit.apply(&mut terrain, Vec3::ZERO);
+
+        let at = |heights: &[f32], x: usize, z: usize| heights[z * 9 + x];
+        assert!((at(&terrain.heights, 4, 4) - (at(&before, 4, 4) - 1.0)).abs() < 1e-5);
+        assert!(at(&terrain.heights, 5, 4) < at(&before, 5, 4));
+        assert!(at(&terrain.heights, 5, 4) > at(&before, 5, 4) - 1.0);
+        assert_eq!(at(&terrain.heights, 0, 0), at(&before, 0, 0));
+
+        // A stroke on a chunk corner touches all four chunks around it
+        let corner = TerrainEdit::new(Vec3::new(64.0, 0.0, 64.0), 1.0, 1.0);
+        assert_eq!(corner.affected_chunks(64.0).len(), 4);
+    }
+}
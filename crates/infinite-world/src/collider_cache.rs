@@ -0,0 +1,228 @@
+//! Built chunk colliders kept for reuse
+//!
+//! A chunk's heightfield collider only depends on the world seed, the year
+//! (its era's terrain and the edits made in it), the timeline branch and the
+//! chunk itself, so when a chunk loads again with all of those the same -
+//! walking back over visited ground, or travelling back to a year already
+//! seen - the collider built last time can go straight back into physics.
+//! The heightfield shape is shared between copies, so a cached collider
+//! costs nothing to reuse. The cache is held to a byte budget, dropping the
+//! least recently used colliders first.
+
+use std::collections::{BTreeMap, HashMap};
+
+use infinite_core::BranchId;
+use rapier3d::prelude::Collider;
+
+use crate::chunk::ChunkCoord;
+use crate::era_config::TimeTerrainConfig;
+
+/// Budget the cache starts with, in bytes
+pub const DEFAULT_COLLIDER_BUDGET: usize = 32 * 1024 * 1024;
+
+/// What a chunk's collider was built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColliderKey {
+    /// Base world seed
+    pub seed: u32,
+    pub year: i64,
+    pub branch: BranchId,
+    pub coord: ChunkCoord,
+}
+
+struct CachedCollider {
+    collider: Collider,
+    /// Era modifiers the collider was built under, in case they changed
+    /// without the year changing
+    era: Option<TimeTerrainConfig>,
+    bytes: usize,
+    /// Position in the use order
+    used: u64,
+}
+
+/// Heightfield colliders by chunk, year and seed, within a size budget
+pub struct ColliderCache {
+    entries: HashMap<ColliderKey, CachedCollider>,
+    /// Keys by last use, oldest first
+    order: BTreeMap<u64, ColliderKey>,
+    clock: u64,
+    bytes: usize,
+    budget: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl ColliderCache {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+            bytes: 0,
+            budget,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Copy of the collider built for `key` under `era`, if there is one
+    pub fn get(&mut self, key: &ColliderKey, era: Option<&TimeTerrainConfig>) -> Option<Collider> {
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        if entry.era.as_ref() != era {
+            self.misses += 1;
+            self.remove(key);
+            return None;
+        }
+        self.clock += 1;
+        self.order.remove(&entry.used);
+        entry.used = self.clock;
+        self.order.insert(self.clock, *key);
+        self.hits += 1;
+        Some(entry.collider.clone())
+    }
+
+    /// Keep a copy of a newly built collider, making room for it. Colliders
+    /// bigger than the whole budget aren't kept.
+    pub fn insert(&mut self, key: ColliderKey, era: Option<TimeTerrainConfig>, collider: Collider) {
+        self.remove(&key);
+        let bytes = collider_bytes(&collider);
+        if bytes > self.budget {
+            return;
+        }
+        self.bytes += bytes;
+        self.clock += 1;
+        self.order.insert(self.clock, key);
+        self.entries.insert(key, CachedCollider { collider, era, bytes, used: self.clock });
+        self.evict();
+    }
+
+    /// Forget the collider for `key` (its terrain was edited)
+    pub fn remove(&mut self, key: &ColliderKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.used);
+            self.bytes -= entry.bytes;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.bytes = 0;
+    }
+
+    /// Change the budget, dropping colliders until the cache fits it
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict();
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Approximate bytes held by the cached colliders
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Lookups answered from the cache and lookups that had to build, since
+    /// the cache was made
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+
+    /// Drop the least recently used colliders until the cache fits its budget
+    fn evict(&mut self) {
+        while self.bytes > self.budget {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.bytes -= entry.bytes;
+            }
+        }
+    }
+}
+
+impl Default for ColliderCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_COLLIDER_BUDGET)
+    }
+}
+
+/// Heap a heightfield collider holds: its heights and a status byte per cell
+fn collider_bytes(collider: &Collider) -> usize {
+    let cells = collider.shape().as_heightfield().map_or(0, |heightfield| {
+        let heights = heightfield.heights();
+        heights.nrows() * heights.ncols()
+    });
+    std::mem::size_of::<CachedCollider>() + cells * (std::mem::size_of::<f32>() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+    use infinite_physics::PhysicsWorld;
+
+    fn collider(size: usize) -> Collider {
+        let heights = vec![1.0; size * size];
+        PhysicsWorld::heightfield_collider_at(&heights, size, size, Vec3::new(64.0, 1.0, 64.0), Vec3::ZERO)
+    }
+
+    fn key(x: i32, year: i64) -> ColliderKey {
+        ColliderKey { seed: 42, year, branch: BranchId::MAIN, coord: ChunkCoord::new(x, 0) }
+    }
+
+    #[test]
+    fn test_cached_per_year_and_era() {
+        let mut cache = ColliderCache::default();
+        cache.insert(key(0, 2025), None, collider(5));
+        assert!(cache.get(&key(0, 2025), None).is_some());
+        assert!(cache.get(&key(0, -3000), None).is_none(), "another year has its own terrain");
+        assert!(cache.get(&key(1, 2025), None).is_none());
+
+        let past = TimeTerrainConfig::for_year(-3000, 2025);
+        cache.insert(key(0, -3000), Some(past.clone()), collider(5));
+        let elsewhen = TimeTerrainConfig::for_year(-1000, 2025);
+        assert!(cache.get(&key(0, -3000), Some(&elsewhen)).is_none(), "changed modifiers aren't reused");
+        assert_eq!(cache.len(), 1, "the mismatched collider is dropped");
+        assert_eq!(cache.stats(), (1, 3));
+    }
+
+    #[test]
+    fn test_least_recently_used_evicted_over_budget() {
+        let one = collider_bytes(&collider(9));
+        let mut cache = ColliderCache::new(one * 2);
+        cache.insert(key(0, 2025), None, collider(9));
+        cache.insert(key(1, 2025), None, collider(9));
+        // Touch the first, so the second is the oldest
+        assert!(cache.get(&key(0, 2025), None).is_some());
+        cache.insert(key(2, 2025), None, collider(9));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.bytes() <= cache.budget());
+        assert!(cache.get(&key(1, 2025), None).is_none());
+        assert!(cache.get(&key(0, 2025), None).is_some());
+
+        cache.set_budget(one);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&key(0, 2025), None).is_some(), "the most recently used is kept");
+
+        cache.set_budget(one / 2);
+        assert!(cache.is_empty());
+        cache.insert(key(3, 2025), None, collider(9));
+        assert!(cache.is_empty(), "a collider over the whole budget isn't kept");
+        assert_eq!(cache.bytes(), 0);
+    }
+}
@@ -0,0 +1,704 @@
+//! Dungeon points of interest and their instanced interiors
+//!
+//! Some chunks hold a dungeon entrance, placed deterministically from the
+//! world seed with at most one per region of chunks. Entering a dungeon
+//! builds its interior far away from the streamed overworld: a tree of rooms
+//! joined by corridors, generated from the entrance's seed, with enemy packs
+//! along the way and a boss room holding a chest at the far end. Return
+//! portals in the first and last rooms lead back to the entrance. A
+//! portcullis can be lowered across the boss room's doorway, sliding up on
+//! its guide when it is raised.
+
+use std::collections::HashSet;
+
+use glam::{Vec2, Vec3};
+use infinite_core::rng::{hash_seed, mix};
+use infinite_physics::{JointBuilder, JointedBody, MotorConfig, PhysicsWorld};
+use rapier3d::prelude::{ActiveCollisionTypes, ColliderBuilder, ColliderHandle, RigidBodyBuilder};
+
+use crate::chunk::ChunkCoord;
+
+/// Where dungeon interiors are built, well away from the overworld the
+/// player normally explores
+pub const DUNGEON_ORIGIN: Vec3 = Vec3::new(20_000.0, 0.0, 20_000.0);
+
+/// Gap left around a portcullis in its doorway, so it slides freely
+const GATE_CLEARANCE: f32 = 0.05;
+const GATE_MASS: f32 = 400.0;
+
+/// Grid steps from a room to its neighbours
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Dungeon placement and layout settings
+#[derive(Clone, Debug)]
+pub struct DungeonConfig {
+    /// Side length in chunks of the regions that each hold at most one entrance
+    pub region_size: i32,
+    /// Chance (0.0-1.0) that a region has an entrance
+    pub entrance_chance: f32,
+    /// Fewest rooms in a dungeon, including the entrance and boss rooms
+    pub min_rooms: usize,
+    /// Most rooms in a dungeon
+    pub max_rooms: usize,
+    /// Distance between neighbouring room centers
+    pub cell_size: f32,
+    /// Smallest room half-extent
+    pub room_half_min: f32,
+    /// Largest room half-extent (must leave space for corridors in a cell)
+    pub room_half_max: f32,
+    /// Width of corridors and doorways
+    pub corridor_width: f32,
+    pub wall_height: f32,
+    pub wall_thickness: f32,
+    /// Fewest enemies in a combat room's pack
+    pub pack_min: usize,
+    /// Most enemies in a combat room's pack
+    pub pack_max: usize,
+}
+
+impl Default for DungeonConfig {
+    fn default() -> Self {
+        Self {
+            region_size: 4,
+            entrance_chance: 0.5,
+            min_rooms: 5,
+            max_rooms: 9,
+            cell_size: 26.0,
+            room_half_min: 6.0,
+            room_half_max: 10.0,
+            corridor_width: 4.0,
+            wall_height: 5.0,
+            wall_thickness: 0.5,
+            pack_min: 2,
+            pack_max: 4,
+        }
+    }
+}
+
+/// A dungeon entrance in the overworld
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DungeonEntrance {
+    /// Chunk the entrance stands in
+    pub chunk: ChunkCoord,
+    /// World position (y is left at 0 for the caller to place on the terrain)
+    pub position: Vec3,
+    /// Seed of the dungeon's layout
+    pub seed: u64,
+}
+
+impl DungeonConfig {
+    /// The dungeon entrance in a chunk, if its region placed one there
+    pub fn entrance_in_chunk(&self, coord: ChunkCoord, world_seed: u32, chunk_size: f32) -> Option<DungeonEntrance> {
+        let region = self.region_size.max(1);
+        let (region_x, region_z) = (coord.x.div_euclid(region), coord.z.div_euclid(region));
+        let mut rng = SplitMix::new(hash3(world_seed as u64, region_x as i64, region_z as i64));
+        if rng.float() >= self.entrance_chance {
+            return None;
+        }
+
+        let chosen_x = region_x * region + rng.range(0, region as usize - 1) as i32;
+        let chosen_z = region_z * region + rng.range(0, region as usize - 1) as i32;
+        if (chosen_x, chosen_z) != (coord.x, coord.z) {
+            return None;
+        }
+
+        let offset = Vec3::new(rng.float() - 0.5, 0.0, rng.float() - 0.5) * (chunk_size * 0.5);
+        Some(DungeonEntrance {
+            chunk: coord,
+            position: coord.world_center(chunk_size) + offset,
+            seed: rng.next(),
+        })
+    }
+}
+
+/// What a room is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomKind {
+    /// Where the player arrives, with a return portal
+    Entrance,
+    /// Holds an enemy pack
+    Combat,
+    /// The deepest room: boss, chest and a second return portal
+    Boss,
+}
+
+/// A rectangular room on the layout grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct DungeonRoom {
+    /// Grid cell of the room
+    pub cell: (i32, i32),
+    /// Center of the floor surface
+    pub center: Vec3,
+    /// Half-size along x and z
+    pub half_extents: Vec2,
+    pub kind: RoomKind,
+    /// Corridors between this room and the entrance room
+    pub depth: u32,
+}
+
+/// A corridor joining two neighbouring rooms (indices into the room list)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DungeonCorridor {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Enemies placed together in one room
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnemyPack {
+    /// Index of the room the pack guards
+    pub room: usize,
+    /// Floor positions of each enemy
+    pub positions: Vec<Vec3>,
+    /// Whether this is the boss
+    pub boss: bool,
+}
+
+/// What a piece of dungeon geometry is (for rendering)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Floor,
+    Wall,
+    /// The boss room's portcullis
+    Gate,
+}
+
+/// An axis-aligned box of dungeon geometry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DungeonBlock {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub kind: BlockKind,
+}
+
+/// A generated dungeon: rooms, corridors, and what is placed in them. Floor
+/// surfaces are at the layout's y.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DungeonLayout {
+    pub rooms: Vec<DungeonRoom>,
+    pub corridors: Vec<DungeonCorridor>,
+    pub packs: Vec<EnemyPack>,
+    /// Where the player arrives
+    pub spawn: Vec3,
+    /// Treasure chest in the boss room
+    pub chest: Vec3,
+    /// Portals back to the overworld
+    pub return_portals: Vec<Vec3>,
+}
+
+impl DungeonLayout {
+    /// Generate a layout from a seed. Rooms grow as a tree from the entrance
+    /// room, and the deepest room becomes the boss room.
+    pub fn generate(seed: u64, config: &DungeonConfig) -> Self {
+        let mut rng = SplitMix::new(seed);
+        let room_count = rng.range(config.min_rooms.max(2), config.max_rooms.max(config.min_rooms.max(2)));
+
+        let mut rooms = vec![Self::room((0, 0), RoomKind::Entrance, 0, config, &mut rng)];
+        let mut corridors = Vec::new();
+        let mut occupied: HashSet<(i32, i32)> = HashSet::from([(0, 0)]);
+        let mut attempts = 0;
+        while rooms.len() < room_count && attempts < room_count * 50 {
+            attempts += 1;
+            let parent = rng.range(0, rooms.len() - 1);
+            let (dx, dz) = DIRECTIONS[rng.range(0, DIRECTIONS.len() - 1)];
+            let cell = (rooms[parent].cell.0 + dx, rooms[parent].cell.1 + dz);
+            if !occupied.insert(cell) {
+                continue;
+            }
+            let depth = rooms[parent].depth + 1;
+            rooms.push(Self::room(cell, RoomKind::Combat, depth, config, &mut rng));
+            corridors.push(DungeonCorridor { from: parent, to: rooms.len() - 1 });
+        }
+
+        // Deepest room (first found) holds the boss
+        let boss = (1..rooms.len())
+            .max_by_key(|&i| (rooms[i].depth, std::cmp::Reverse(i)))
+            .unwrap_or(0);
+        rooms[boss].kind = RoomKind::Boss;
+        rooms[boss].half_extents = Vec2::splat(config.room_half_max);
+
+        let mut packs = Vec::new();
+        for (index, room) in rooms.iter().enumerate() {
+            match room.kind {
+                RoomKind::Combat => {
+                    let count = rng.range(config.pack_min, config.pack_max.max(config.pack_min));
+                    let radius = room.half_extents.min_element() * 0.5;
+                    let start = rng.float() * std::f32::consts::TAU;
+                    let positions = (0..count)
+                        .map(|i| {
+                            let angle = start + i as f32 / count as f32 * std::f32::consts::TAU;
+                            room.center + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius
+                        })
+                        .collect();
+                    packs.push(EnemyPack { room: index, positions, boss: false });
+                }
+                RoomKind::Boss => {
+                    packs.push(EnemyPack { room: index, positions: vec![room.center], boss: true });
+                }
+                RoomKind::Entrance => {}
+            }
+        }
+
+        let entrance = &rooms[0];
+        let boss_room = &rooms[boss];
+        Self {
+            spawn: entrance.center,
+            chest: boss_room.center + Vec3::new(0.0, 0.0, boss_room.half_extents.y * 0.6),
+            return_portals: vec![
+                entrance.center - Vec3::new(0.0, 0.0, entrance.half_extents.y * 0.6),
+                boss_room.center - Vec3::new(0.0, 0.0, boss_room.half_extents.y * 0.6),
+            ],
+            rooms,
+            corridors,
+            packs,
+        }
+    }
+
+    fn room(cell: (i32, i32), kind: RoomKind, depth: u32, config: &DungeonConfig, rng: &mut SplitMix) -> DungeonRoom {
+        let span = config.room_half_max - config.room_half_min;
+        DungeonRoom {
+            cell,
+            center: Vec3::new(cell.0 as f32 * config.cell_size, 0.0, cell.1 as f32 * config.cell_size),
+            half_extents: Vec2::new(
+                config.room_half_min + rng.float() * span,
+                config.room_half_min + rng.float() * span,
+            ),
+            kind,
+            depth,
+        }
+    }
+
+    /// Move every position in the layout by `offset`
+    pub fn offset(&mut self, offset: Vec3) {
+        for room in &mut self.rooms {
+            room.center += offset;
+        }
+        for pack in &mut self.packs {
+            for position in &mut pack.positions {
+                *position += offset;
+            }
+        }
+        self.spawn += offset;
+        self.chest += offset;
+        for portal in &mut self.return_portals {
+            *portal += offset;
+        }
+    }
+
+    /// Index of the boss room
+    pub fn boss_room(&self) -> usize {
+        self.rooms.iter().position(|r| r.kind == RoomKind::Boss).unwrap_or(0)
+    }
+
+    /// Floors and walls: a floor slab per room and corridor, room walls with
+    /// doorways where corridors attach, and walls along each corridor
+    pub fn blocks(&self, config: &DungeonConfig) -> Vec<DungeonBlock> {
+        let floor_half = 0.25;
+        let t = config.wall_thickness;
+        let h = config.wall_height;
+        let w = config.corridor_width;
+        let mut blocks = Vec::new();
+
+        for (index, room) in self.rooms.iter().enumerate() {
+            let c = room.center;
+            let half = room.half_extents;
+            blocks.push(DungeonBlock {
+                center: c - Vec3::Y * floor_half,
+                half_extents: Vec3::new(half.x, floor_half, half.y),
+                kind: BlockKind::Floor,
+            });
+
+            // Which sides have a doorway
+            let connected: Vec<(i32, i32)> = self
+                .corridors
+                .iter()
+                .filter_map(|corridor| {
+                    let other = if corridor.from == index {
+                        corridor.to
+                    } else if corridor.to == index {
+                        corridor.from
+                    } else {
+                        return None;
+                    };
+                    let cell = self.rooms[other].cell;
+                    Some((cell.0 - room.cell.0, cell.1 - room.cell.1))
+                })
+                .collect();
+
+            // Walls along x (at +-z) and along z (at +-x), corners included
+            for (dir, sign) in [((0, 1), 1.0_f32), ((0, -1), -1.0)] {
+                let z = c.z + sign * (half.y + t * 0.5);
+                let door = connected.contains(&dir).then_some(c.x);
+                for (min, max) in wall_segments(c.x - half.x - t, c.x + half.x + t, door, w) {
+                    blocks.push(DungeonBlock {
+                        center: Vec3::new((min + max) * 0.5, c.y + h * 0.5, z),
+                        half_extents: Vec3::new((max - min) * 0.5, h * 0.5, t * 0.5),
+                        kind: BlockKind::Wall,
+                    });
+                }
+            }
+            for (dir, sign) in [((1, 0), 1.0_f32), ((-1, 0), -1.0)] {
+                let x = c.x + sign * (half.x + t * 0.5);
+                let door = connected.contains(&dir).then_some(c.z);
+                for (min, max) in wall_segments(c.z - half.y, c.z + half.y, door, w) {
+                    blocks.push(DungeonBlock {
+                        center: Vec3::new(x, c.y + h * 0.5, (min + max) * 0.5),
+                        half_extents: Vec3::new(t * 0.5, h * 0.5, (max - min) * 0.5),
+                        kind: BlockKind::Wall,
+                    });
+                }
+            }
+        }
+
+        for corridor in &self.corridors {
+            let (a, b) = (&self.rooms[corridor.from], &self.rooms[corridor.to]);
+            let along_x = a.cell.1 == b.cell.1;
+            let (first, second) = if (along_x && a.center.x < b.center.x) || (!along_x && a.center.z < b.center.z) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+
+            if along_x {
+                let start = first.center.x + first.half_extents.x + t;
+                let end = second.center.x - second.half_extents.x - t;
+                let mid = Vec3::new((start + end) * 0.5, first.center.y, first.center.z);
+                let half_len = ((end - start) * 0.5).max(0.0);
+                blocks.push(DungeonBlock {
+                    center: mid - Vec3::Y * floor_half,
+                    // Overlap into the rooms' doorways
+                    half_extents: Vec3::new(half_len + t, floor_half, w * 0.5),
+                    kind: BlockKind::Floor,
+                });
+                for sign in [1.0_f32, -1.0] {
+                    blocks.push(DungeonBlock {
+                        center: Vec3::new(mid.x, mid.y + h * 0.5, mid.z + sign * (w * 0.5 + t * 0.5)),
+                        half_extents: Vec3::new(half_len, h * 0.5, t * 0.5),
+                        kind: BlockKind::Wall,
+                    });
+                }
+            } else {
+                let start = first.center.z + first.half_extents.y + t;
+                let end = second.center.z - second.half_extents.y - t;
+                let mid = Vec3::new(first.center.x, first.center.y, (start + end) * 0.5);
+                let half_len = ((end - start) * 0.5).max(0.0);
+                blocks.push(DungeonBlock {
+                    center: mid - Vec3::Y * floor_half,
+                    half_extents: Vec3::new(w * 0.5, floor_half, half_len + t),
+                    kind: BlockKind::Floor,
+                });
+                for sign in [1.0_f32, -1.0] {
+                    blocks.push(DungeonBlock {
+                        center: Vec3::new(mid.x + sign * (w * 0.5 + t * 0.5), mid.y + h * 0.5, mid.z),
+                        half_extents: Vec3::new(t * 0.5, h * 0.5, half_len),
+                        kind: BlockKind::Wall,
+                    });
+                }
+            }
+        }
+
+        blocks
+    }
+}
+
+/// Split a wall spanning `min..max` around an optional doorway of `width`
+fn wall_segments(min: f32, max: f32, door: Option<f32>, width: f32) -> Vec<(f32, f32)> {
+    match door {
+        Some(center) => vec![(min, center - width * 0.5), (center + width * 0.5, max)],
+        None => vec![(min, max)],
+    }
+}
+
+/// A dungeon interior built into the physics world. Remove it when the
+/// player leaves.
+pub struct DungeonInstance {
+    /// The overworld entrance this interior belongs to
+    pub entrance: DungeonEntrance,
+    /// The layout, in world space
+    pub layout: DungeonLayout,
+    blocks: Vec<DungeonBlock>,
+    colliders: Vec<ColliderHandle>,
+    gate: Option<BossGate>,
+}
+
+/// A portcullis sliding up and down in a doorway
+struct BossGate {
+    slider: JointedBody,
+    half_extents: Vec3,
+    /// How far it lifts to clear the doorway. Until it is raised it rests
+    /// on its guide's lower end.
+    lift: f32,
+    raised: bool,
+}
+
+impl DungeonInstance {
+    /// Generate the entrance's dungeon at [`DUNGEON_ORIGIN`] and add its
+    /// geometry to the physics world
+    pub fn build(entrance: DungeonEntrance, config: &DungeonConfig, physics: &mut PhysicsWorld) -> Self {
+        let mut layout = DungeonLayout::generate(entrance.seed, config);
+        layout.offset(DUNGEON_ORIGIN);
+        let blocks = layout.blocks(config);
+        let colliders = blocks
+            .iter()
+            .map(|block| physics.create_static_box(block.half_extents, block.center))
+            .collect();
+        Self {
+            entrance,
+            layout,
+            blocks,
+            colliders,
+            gate: None,
+        }
+    }
+
+    /// Remove the interior's geometry from the physics world
+    pub fn remove(self, physics: &mut PhysicsWorld) {
+        for handle in self.colliders {
+            physics.remove_collider(handle);
+        }
+        if let Some(gate) = self.gate {
+            physics.remove_jointed(&gate.slider);
+        }
+    }
+
+    /// Lower a portcullis across the boss room's doorway, keeping the boss
+    /// room shut until [`DungeonInstance::raise_boss_gate`]. Returns false
+    /// if there is already one, or the boss room has no doorway.
+    pub fn close_boss_gate(&mut self, config: &DungeonConfig, physics: &mut PhysicsWorld) -> bool {
+        let boss = self.layout.boss_room();
+        let Some(other) = self.layout.corridors.iter().find_map(|c| match (c.from, c.to) {
+            (from, to) if from == boss => Some(to),
+            (from, to) if to == boss => Some(from),
+            _ => None,
+        }) else {
+            return false;
+        };
+        if self.gate.is_some() {
+            return false;
+        }
+
+        let room = &self.layout.rooms[boss];
+        let (dx, dz) = (self.layout.rooms[other].cell.0 - room.cell.0, self.layout.rooms[other].cell.1 - room.cell.1);
+        let (t, h) = (config.wall_thickness, config.wall_height);
+        let across = config.corridor_width * 0.5 - GATE_CLEARANCE;
+        let half_extents = if dx != 0 {
+            Vec3::new(t * 0.5, h * 0.5 - GATE_CLEARANCE, across)
+        } else {
+            Vec3::new(across, h * 0.5 - GATE_CLEARANCE, t * 0.5)
+        };
+        let center = room.center
+            + Vec3::new(
+                dx as f32 * (room.half_extents.x + t * 0.5),
+                h * 0.5,
+                dz as f32 * (room.half_extents.y + t * 0.5),
+            );
+
+        let body = RigidBodyBuilder::dynamic().translation([center.x, center.y, center.z].into()).build();
+        // Guided by its slider alone, so it can't catch on the doorway
+        let collider = ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            .mass(GATE_MASS)
+            .active_collision_types(ActiveCollisionTypes::empty())
+            .build();
+        let lift = h - GATE_CLEARANCE;
+        let slider = JointBuilder::slider(center, Vec3::Y).limits(0.0, lift);
+        self.gate = Some(BossGate {
+            slider: physics.add_jointed(&slider, body, collider),
+            half_extents,
+            lift,
+            raised: false,
+        });
+        true
+    }
+
+    /// Raise the boss room's portcullis. Returns false if there is none or
+    /// it is already up.
+    pub fn raise_boss_gate(&mut self, physics: &mut PhysicsWorld) -> bool {
+        match &mut self.gate {
+            Some(gate) if !gate.raised => {
+                gate.raised = true;
+                physics.set_joint_motor(&gate.slider, MotorConfig::position(gate.lift, 60.0, 15.0));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the boss room is shut behind a lowered portcullis
+    pub fn boss_gate_closed(&self) -> bool {
+        self.gate.as_ref().is_some_and(|gate| !gate.raised)
+    }
+
+    /// The portcullis where it is now (for rendering)
+    pub fn gate_block(&self, physics: &PhysicsWorld) -> Option<DungeonBlock> {
+        let gate = self.gate.as_ref()?;
+        let (center, _) = physics.body_pose(gate.slider.body)?;
+        Some(DungeonBlock { center, half_extents: gate.half_extents, kind: BlockKind::Gate })
+    }
+
+    /// Geometry to render
+    pub fn blocks(&self) -> &[DungeonBlock] {
+        &self.blocks
+    }
+
+    /// Floor height at a world position, if it is inside the dungeon
+    pub fn floor_height_at(&self, x: f32, z: f32) -> Option<f32> {
+        self.blocks
+            .iter()
+            .filter(|b| b.kind == BlockKind::Floor)
+            .find(|b| (x - b.center.x).abs() <= b.half_extents.x && (z - b.center.z).abs() <= b.half_extents.z)
+            .map(|b| b.center.y + b.half_extents.y)
+    }
+}
+
+/// Small deterministic generator so layouts only depend on their seed
+pub(crate) struct SplitMix(u64);
+
+impl SplitMix {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix(self.0)
+    }
+
+    /// Uniform in 0.0..1.0
+    pub(crate) fn float(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `min..=max`
+    pub(crate) fn range(&mut self, min: usize, max: usize) -> usize {
+        if max <= min {
+            return min;
+        }
+        min + (self.next() % (max - min + 1) as u64) as usize
+    }
+}
+
+pub(crate) fn hash3(seed: u64, x: i64, z: i64) -> u64 {
+    hash_seed(seed, &[x as u64, z as u64])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entrances_are_deterministic_and_sparse() {
+        let config = DungeonConfig::default();
+        let mut found = Vec::new();
+        for x in -16..16 {
+            for z in -16..16 {
+                let coord = ChunkCoord::new(x, z);
+                let entrance = config.entrance_in_chunk(coord, 42, 64.0);
+                assert_eq!(entrance, config.entrance_in_chunk(coord, 42, 64.0));
+                if let Some(entrance) = entrance {
+                    assert_eq!(ChunkCoord::from_world_pos(entrance.position, 64.0), coord);
+                    found.push(coord);
+                }
+            }
+        }
+        // 64 regions at 50% odds, and never two in one region
+        assert!(found.len() > 15 && found.len() < 50, "{} entrances", found.len());
+        let regions: HashSet<(i32, i32)> = found.iter().map(|c| (c.x.div_euclid(4), c.z.div_euclid(4))).collect();
+        assert_eq!(regions.len(), found.len());
+    }
+
+    #[test]
+    fn test_layout_rooms_corridors_and_boss() {
+        let config = DungeonConfig::default();
+        for seed in 0..30 {
+            let layout = DungeonLayout::generate(seed, &config);
+            assert_eq!(layout, DungeonLayout::generate(seed, &config));
+            assert!(layout.rooms.len() >= config.min_rooms && layout.rooms.len() <= config.max_rooms);
+            // A tree: one corridor per room after the first, between neighbouring cells
+            assert_eq!(layout.corridors.len(), layout.rooms.len() - 1);
+            for corridor in &layout.corridors {
+                let (a, b) = (layout.rooms[corridor.from].cell, layout.rooms[corridor.to].cell);
+                assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 1);
+            }
+            let cells: HashSet<(i32, i32)> = layout.rooms.iter().map(|r| r.cell).collect();
+            assert_eq!(cells.len(), layout.rooms.len());
+
+            let boss = layout.boss_room();
+            assert_eq!(layout.rooms[0].kind, RoomKind::Entrance);
+            assert!(layout.rooms.iter().all(|r| r.depth <= layout.rooms[boss].depth));
+            assert_eq!(layout.packs.iter().filter(|p| p.boss).count(), 1);
+            assert!(layout.packs.iter().filter(|p| !p.boss).all(|p| {
+                p.positions.len() >= config.pack_min && p.positions.len() <= config.pack_max
+            }));
+            assert_eq!(layout.return_portals.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_instance_builds_and_removes_geometry() {
+        let config = DungeonConfig::default();
+        let mut physics = PhysicsWorld::new();
+        let entrance = DungeonEntrance {
+            chunk: ChunkCoord::new(0, 0),
+            position: Vec3::new(10.0, 3.0, 10.0),
+            seed: 7,
+        };
+        let instance = DungeonInstance::build(entrance, &config, &mut physics);
+        assert_eq!(physics.collider_set.len(), instance.blocks().len());
+
+        // Spawn, chest and portals all stand on the floor; the overworld does not
+        let layout = &instance.layout;
+        for point in [layout.spawn, layout.chest].iter().chain(&layout.return_portals) {
+            assert_eq!(instance.floor_height_at(point.x, point.z), Some(DUNGEON_ORIGIN.y));
+        }
+        assert_eq!(instance.floor_height_at(entrance.position.x, entrance.position.z), None);
+
+        // The spawn room's floor is solid
+        physics.update_query_pipeline();
+        let hit = physics.raycast(layout.spawn + Vec3::Y, Vec3::NEG_Y, 2.0, rapier3d::prelude::QueryFilter::default());
+        assert!(hit.is_some_and(|(_, toi)| (toi - 1.0).abs() < 0.01));
+
+        instance.remove(&mut physics);
+        assert_eq!(physics.collider_set.len(), 0);
+    }
+
+    #[test]
+    fn test_boss_gate_shuts_and_lifts() {
+        let config = DungeonConfig::default();
+        let mut physics = PhysicsWorld::new();
+        let entrance = DungeonEntrance { chunk: ChunkCoord::new(0, 0), position: Vec3::ZERO, seed: 11 };
+        let mut instance = DungeonInstance::build(entrance, &config, &mut physics);
+        assert!(instance.gate_block(&physics).is_none());
+        assert!(instance.close_boss_gate(&config, &mut physics));
+        assert!(!instance.close_boss_gate(&config, &mut physics));
+        assert!(instance.boss_gate_closed());
+
+        // Looking from the boss room's centre out through its doorway
+        let boss = instance.layout.rooms[instance.layout.boss_room()].clone();
+        let doorway = instance.gate_block(&physics).unwrap().center.with_y(boss.center.y + 1.0);
+        let eye = boss.center + Vec3::Y;
+        let step = |physics: &mut PhysicsWorld, seconds: f32| {
+            for _ in 0..(seconds * 60.0) as usize {
+                physics.step();
+            }
+        };
+        step(&mut physics, 1.0);
+        physics.update_query_pipeline();
+        let look = |physics: &PhysicsWorld| {
+            physics
+                .raycast(eye, (doorway - eye).normalize(), eye.distance(doorway) + 1.0, rapier3d::prelude::QueryFilter::default())
+                .is_some()
+        };
+        assert!(look(&physics), "the lowered gate fills the doorway");
+        assert_eq!(instance.gate_block(&physics).unwrap().kind, BlockKind::Gate);
+
+        assert!(instance.raise_boss_gate(&mut physics));
+        assert!(!instance.raise_boss_gate(&mut physics));
+        step(&mut physics, 3.0);
+        physics.update_query_pipeline();
+        assert!(!look(&physics), "raised, it clears the doorway");
+        assert!(instance.gate_block(&physics).unwrap().center.y > boss.center.y + config.wall_height);
+
+        instance.remove(&mut physics);
+        assert_eq!(physics.rigid_body_set.len(), 0);
+    }
+}
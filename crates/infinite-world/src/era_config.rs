@@ -5,8 +5,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::roads::RoadQuality;
+
 /// Terrain modifiers for a specific time period (year)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeTerrainConfig {
     /// Added to the base seed to produce different noise patterns per time period
     pub seed_offset: u32,
@@ -14,6 +16,9 @@ pub struct TimeTerrainConfig {
     pub height_scale: f32,
     /// Multiplier on noise_scale (1.0 = default)
     pub noise_scale_mult: f32,
+    /// How roads between settlements are built
+    #[serde(default)]
+    pub road_quality: RoadQuality,
 }
 
 impl TimeTerrainConfig {
@@ -34,6 +39,7 @@ impl TimeTerrainConfig {
                 seed_offset: 0,
                 height_scale: 1.0,
                 noise_scale_mult: 1.0,
+                road_quality: RoadQuality::for_year(year),
             };
         }
 
@@ -47,6 +53,7 @@ impl TimeTerrainConfig {
                 seed_offset: (abs_years as u32 / 10).wrapping_mul(73),
                 height_scale: 1.0 + t * 1.0,         // 1.0 to 2.0
                 noise_scale_mult: 1.0 - t * 0.4,      // 1.0 to 0.6
+                road_quality: RoadQuality::for_year(year),
             }
         } else {
             // Future: terrain gets flatter and more detailed the further forward
@@ -55,9 +62,90 @@ impl TimeTerrainConfig {
                 seed_offset: (abs_years as u32 / 10).wrapping_mul(97),
                 height_scale: 1.0 - t * 0.4,         // 1.0 to 0.6
                 noise_scale_mult: 1.0 + t * 0.5,      // 1.0 to 1.5
+                road_quality: RoadQuality::for_year(year),
             }
         }
     }
+
+    /// Modifiers part way from `self` (t = 0) to `other` (t = 1). Noise
+    /// seeds and road quality can't be blended, so they switch over at the
+    /// midpoint;
+    /// for a smooth change blend the two eras' terrains instead (see
+    /// `Terrain::blend`).
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self {
+            seed_offset: if t < 0.5 { self.seed_offset } else { other.seed_offset },
+            height_scale: self.height_scale + (other.height_scale - self.height_scale) * t,
+            noise_scale_mult: self.noise_scale_mult + (other.noise_scale_mult - self.noise_scale_mult) * t,
+            road_quality: if t < 0.5 { self.road_quality } else { other.road_quality },
+        }
+    }
+
+    /// Modifiers part way between two years
+    pub fn between_years(from_year: i64, to_year: i64, t: f32, present_year: i64) -> Self {
+        Self::for_year(from_year, present_year).lerp(&Self::for_year(to_year, present_year), t)
+    }
+}
+
+/// Colors for a glimpse of a time period (e.g. inside a time portal)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EraPalette {
+    pub sky_zenith: [f32; 3],
+    pub sky_horizon: [f32; 3],
+    pub ground: [f32; 3],
+}
+
+impl TimeTerrainConfig {
+    /// How far from the present this period is: -1.0 = far future,
+    /// 0.0 = present, 1.0 = far past
+    pub fn age(&self) -> f32 {
+        if self.height_scale >= 1.0 {
+            (self.height_scale - 1.0).min(1.0)
+        } else {
+            -((1.0 - self.height_scale) / 0.4).min(1.0)
+        }
+    }
+
+    /// Preview palette for this period. Taller (past) terrain reads as warm,
+    /// hazy skies over rocky ground; flatter (future) terrain as cool, clear
+    /// skies over lush lowland.
+    pub fn preview_palette(&self) -> EraPalette {
+        let age = self.age();
+
+        let present_zenith = [0.25, 0.45, 0.85];
+        let present_horizon = [0.65, 0.75, 0.9];
+        let present_ground = [0.25, 0.45, 0.15];
+
+        let (zenith, horizon, ground) = if age >= 0.0 {
+            (
+                lerp3(present_zenith, [0.45, 0.35, 0.55], age),
+                lerp3(present_horizon, [0.95, 0.65, 0.4], age),
+                lerp3(present_ground, [0.45, 0.38, 0.3], age),
+            )
+        } else {
+            let t = -age;
+            (
+                lerp3(present_zenith, [0.05, 0.3, 0.6], t),
+                lerp3(present_horizon, [0.5, 0.9, 1.0], t),
+                lerp3(present_ground, [0.2, 0.55, 0.45], t),
+            )
+        };
+
+        EraPalette {
+            sky_zenith: zenith,
+            sky_horizon: horizon,
+            ground,
+        }
+    }
+}
+
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+    ]
 }
 
 #[cfg(test)]
@@ -92,4 +180,34 @@ mod tests {
         let b = TimeTerrainConfig::for_year(3025, 2025);
         assert_ne!(a.seed_offset, b.seed_offset);
     }
+
+    #[test]
+    fn test_lerp_between_years() {
+        let past = TimeTerrainConfig::for_year(-3000, 2025);
+        let present = TimeTerrainConfig::for_year(2025, 2025);
+
+        let start = TimeTerrainConfig::between_years(-3000, 2025, 0.0, 2025);
+        assert_eq!(start.height_scale, past.height_scale);
+        assert_eq!(start.seed_offset, past.seed_offset);
+
+        let middle = past.lerp(&present, 0.25);
+        assert!(middle.height_scale < past.height_scale && middle.height_scale > present.height_scale);
+        assert_eq!(middle.seed_offset, past.seed_offset);
+
+        let end = past.lerp(&present, 2.0);
+        assert_eq!(end.height_scale, present.height_scale);
+        assert_eq!(end.seed_offset, present.seed_offset);
+    }
+
+    #[test]
+    fn test_preview_palette_by_era() {
+        let present = TimeTerrainConfig::for_year(2025, 2025).preview_palette();
+        let past = TimeTerrainConfig::for_year(-5000, 2025).preview_palette();
+        let future = TimeTerrainConfig::for_year(5025, 2025).preview_palette();
+
+        // Past horizon is warmer (more red), future horizon cooler (more blue)
+        assert!(past.sky_horizon[0] > present.sky_horizon[0]);
+        assert!(future.sky_horizon[2] > present.sky_horizon[2]);
+        assert_ne!(past, future);
+    }
 }
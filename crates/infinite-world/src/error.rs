@@ -0,0 +1,33 @@
+//! World errors, for failures the world recovers from on its own
+
+use std::io;
+
+use infinite_core::{Diagnostic, ErrorCode, ErrorDomain};
+
+use crate::chunk::ChunkCoord;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorldError {
+    #[error("failed to read or write saved world changes: {0}")]
+    ChunkStore(#[from] io::Error),
+
+    #[error("chunk ({}, {}) generated {bad_samples} invalid heights", coord.x, coord.z)]
+    ChunkGeneration { coord: ChunkCoord, bad_samples: usize },
+}
+
+impl Diagnostic for WorldError {
+    fn code(&self) -> ErrorCode {
+        let number = match self {
+            Self::ChunkStore(_) => 1,
+            Self::ChunkGeneration { .. } => 2,
+        };
+        ErrorCode::new(ErrorDomain::World, number)
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::ChunkStore(_) => Some("Recent changes to the world may not be saved. Check there is free disk space"),
+            Self::ChunkGeneration { .. } => Some("The broken ground was flattened so the world stays walkable"),
+        }
+    }
+}
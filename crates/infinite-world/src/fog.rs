@@ -0,0 +1,113 @@
+//! Height fog and distance haze
+//!
+//! Fog thins out exponentially with height, so it lies thickest in the
+//! lowlands and hilltops rise out of it. It takes the sky's horizon color,
+//! so distant terrain fades into the sky behind it. On top of that, haze
+//! closes in towards the edge of the loaded world, where everything is fog
+//! color, so chunks streaming in there don't pop against the sky.
+
+use glam::Vec3;
+
+use crate::time_of_day::SkyColors;
+use crate::weather::Weather;
+
+/// Fog at ground level on a clear day, per metre
+const CLEAR_DENSITY: f32 = 0.002;
+/// Fog added at full [`Weather::fog_density`], per metre
+const WEATHER_DENSITY: f32 = 0.03;
+
+/// Fog as the basic shader draws it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fog {
+    pub color: Vec3,
+    /// Extinction per metre at `base_height`
+    pub density: f32,
+    /// How quickly the fog thins with height, per metre (0 = evenly thick)
+    pub height_falloff: f32,
+    /// Height `density` is given at
+    pub base_height: f32,
+    /// Distance the haze starts closing in at
+    pub haze_start: f32,
+    /// Distance past which everything is fog color
+    pub haze_end: f32,
+}
+
+impl Fog {
+    /// Outdoor fog under `sky` in `weather`, hazing out towards
+    /// `view_distance`. Thick fog hugs the ground more closely.
+    pub fn atmospheric(sky: &SkyColors, weather: &Weather, view_distance: f32) -> Self {
+        let thickness = weather.fog_density.clamp(0.0, 1.0);
+        Self {
+            color: sky.horizon * Vec3::from_array(weather.sky_tint()),
+            density: CLEAR_DENSITY + thickness * WEATHER_DENSITY,
+            height_falloff: 0.03 + thickness * 0.05,
+            base_height: 0.0,
+            haze_start: view_distance * 0.6,
+            haze_end: view_distance,
+        }
+    }
+
+    /// Fog for a camera `depth` metres under water, for when swimming
+    /// lands: dense, blue-green, even at every depth, and darker further down
+    pub fn underwater(depth: f32) -> Self {
+        let light = 1.0 - (depth.max(0.0) / 30.0).min(0.8);
+        Self {
+            color: Vec3::new(0.05, 0.25, 0.3) * light,
+            density: 0.08,
+            height_falloff: 0.0,
+            base_height: 0.0,
+            haze_start: 20.0,
+            haze_end: 40.0,
+        }
+    }
+
+    /// How much of what is at `point` the fog hides from `camera`
+    /// (0.0 - 1.0), as the shader works it out
+    pub fn opacity(&self, camera: Vec3, point: Vec3) -> f32 {
+        let ray = point - camera;
+        let distance = ray.length();
+        // Density integrated over the ray's change in height
+        let climb = self.height_falloff * ray.y;
+        let along = if climb.abs() > 1e-4 { (1.0 - (-climb).exp()) / climb } else { 1.0 };
+        let at_camera = self.density * (-self.height_falloff * (camera.y - self.base_height)).exp();
+        let fog = 1.0 - (-at_camera * along * distance).exp();
+        let haze = ((distance - self.haze_start) / (self.haze_end - self.haze_start).max(1e-3)).clamp(0.0, 1.0);
+        fog.max(haze * haze * (3.0 - 2.0 * haze))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::WeatherState;
+
+    #[test]
+    fn test_fog_thickens_with_distance_and_weather() {
+        let sky = SkyColors::noon();
+        let clear = Fog::atmospheric(&sky, &Weather::new(WeatherState::Clear), 200.0);
+        let foggy = Fog::atmospheric(&sky, &Weather::new(WeatherState::Fog), 200.0);
+        let camera = Vec3::new(0.0, 2.0, 0.0);
+        let near = Vec3::new(30.0, 2.0, 0.0);
+        let far = Vec3::new(100.0, 2.0, 0.0);
+
+        assert!(clear.opacity(camera, near) < clear.opacity(camera, far));
+        assert!(foggy.opacity(camera, far) > clear.opacity(camera, far));
+        assert!(foggy.opacity(camera, far) > 0.9);
+        // The loaded world's edge is hidden whatever the weather
+        assert_eq!(clear.opacity(camera, Vec3::new(200.0, 2.0, 0.0)), 1.0);
+    }
+
+    #[test]
+    fn test_fog_lies_low() {
+        let fog = Fog::atmospheric(&SkyColors::noon(), &Weather::new(WeatherState::Fog), 1000.0);
+        let valley = fog.opacity(Vec3::new(0.0, 1.0, 0.0), Vec3::new(60.0, 1.0, 0.0));
+        let hilltops = fog.opacity(Vec3::new(0.0, 40.0, 0.0), Vec3::new(60.0, 40.0, 0.0));
+        assert!(hilltops < valley);
+
+        // Under water it is the same at every height
+        let water = Fog::underwater(5.0);
+        let shallow = water.opacity(Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0));
+        let deep = water.opacity(Vec3::new(0.0, -20.0, 0.0), Vec3::new(10.0, -20.0, 0.0));
+        assert!((shallow - deep).abs() < 1e-6);
+    }
+}
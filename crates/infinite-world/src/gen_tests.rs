@@ -0,0 +1,239 @@
+//! Property and golden-seed tests for procedural generation
+//!
+//! Terrain, roads, bridges, dungeon entrances and the population ledger are
+//! all meant to be pure functions of the world seed, the year and the chunk
+//! coordinate. These tests check that over many generated cases:
+//! neighbouring chunks meet without seams, regenerating gives the same
+//! chunk, entrances are scattered the same way every time, and the physics
+//! heightfield sits where the mesh is. The golden tests pin a few seeds to
+//! checksums so any change to what a seed generates shows up in review.
+//!
+//! Cases are drawn by proptest over the whole seed range, so a failure is
+//! shrunk to a minimal case and recorded in `proptest-regressions/` to be
+//! replayed on the next run.
+
+use glam::Vec3;
+use infinite_physics::PhysicsWorld;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use rapier3d::prelude::QueryFilter;
+
+use crate::chunk::{ChunkConfig, ChunkCoord, ChunkManager};
+use crate::dungeon::DungeonConfig;
+use crate::era_config::TimeTerrainConfig;
+use crate::population::PopulationLedger;
+use crate::roads::{RoadConfig, RoadNetwork};
+use crate::terrain::{Terrain, TerrainConfig};
+
+const PRESENT_YEAR: i64 = 2025;
+const CHUNK_SIZE: f32 = 64.0;
+const SUBDIVISIONS: u32 = 16;
+/// Heights are compared to within this; generation is deterministic, but
+/// neighbouring chunks reach their shared edge through different sums
+const HEIGHT_TOLERANCE: f32 = 1e-3;
+
+/// One generated world to check
+#[derive(Debug, Clone, Copy)]
+struct Case {
+    seed: u32,
+    year: i64,
+    center: ChunkCoord,
+}
+
+impl Case {
+    /// A chunk manager for the case with the 3x3 chunks around its centre
+    /// loaded
+    fn load(&self, physics: &mut PhysicsWorld) -> ChunkManager {
+        let config = ChunkConfig {
+            chunk_size: CHUNK_SIZE,
+            subdivisions: SUBDIVISIONS,
+            load_radius: 1,
+            unload_radius: 2,
+        };
+        let terrain_config = TerrainConfig {
+            size: CHUNK_SIZE,
+            subdivisions: SUBDIVISIONS,
+            seed: self.seed,
+            ..Default::default()
+        };
+        let mut manager = ChunkManager::new(config, terrain_config);
+        manager.set_roads(Some(RoadNetwork::new(RoadConfig::default(), self.seed, CHUNK_SIZE)));
+        manager.set_time_terrain_config(Some(TimeTerrainConfig::for_year(self.year, PRESENT_YEAR)));
+        manager.update(self.center.world_center(CHUNK_SIZE), physics);
+        manager
+    }
+}
+
+fn chunk_coords() -> impl Strategy<Value = ChunkCoord> {
+    (-60..60, -60..60).prop_map(|(x, z)| ChunkCoord::new(x, z))
+}
+
+/// Any seed, a year from the deep past to the far future, and a chunk
+/// within a few kilometres of the origin
+fn cases() -> impl Strategy<Value = Case> {
+    (any::<u32>(), -6000i64..5000, chunk_coords()).prop_map(|(seed, year, center)| Case { seed, year, center })
+}
+
+/// Streaming in a 3x3 block takes a while, so chunk properties run on
+/// fewer cases than proptest's default
+fn config(cases: u32) -> ProptestConfig {
+    ProptestConfig::with_cases(cases)
+}
+
+fn terrain(manager: &ChunkManager, coord: ChunkCoord) -> Result<&Terrain, TestCaseError> {
+    manager
+        .get_chunk(&coord)
+        .map(|chunk| &chunk.terrain)
+        .ok_or_else(|| TestCaseError::fail(format!("chunk {coord:?} wasn't loaded")))
+}
+
+proptest! {
+    #![proptest_config(config(24))]
+
+    #[test]
+    fn test_neighbouring_chunks_share_edges(case in cases()) {
+        let mut physics = PhysicsWorld::new();
+        let manager = case.load(&mut physics);
+        let row = SUBDIVISIONS as usize + 1;
+        for dz in -1..=1 {
+            for dx in -1..=1 {
+                let coord = ChunkCoord::new(case.center.x + dx, case.center.z + dz);
+                let here = terrain(&manager, coord)?;
+                // East edge against the next chunk's west edge, and south
+                // against the next chunk's north, as (here, there) indices
+                let east = (ChunkCoord::new(coord.x + 1, coord.z), (row - 1, row), (0, row));
+                let south = (ChunkCoord::new(coord.x, coord.z + 1), ((row - 1) * row, 1), (0, 1));
+                for (neighbour, (here_start, here_stride), (there_start, there_stride)) in [east, south] {
+                    let Ok(there) = terrain(&manager, neighbour) else {
+                        continue;
+                    };
+                    for i in 0..row {
+                        let a = here.heights[here_start + i * here_stride];
+                        let b = there.heights[there_start + i * there_stride];
+                        prop_assert!(
+                            (a - b).abs() <= HEIGHT_TOLERANCE,
+                            "seam of {} m between {:?} and {:?} at edge vertex {}", (a - b).abs(), coord, neighbour, i
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(config(16))]
+
+    #[test]
+    fn test_regeneration_is_deterministic(case in cases()) {
+        let first = case.load(&mut PhysicsWorld::new());
+        let mut physics = PhysicsWorld::new();
+        let mut second = case.load(&mut physics);
+        // Stream out and back in, so the second copy comes from regeneration
+        second.update(Vec3::new(1.0e5, 0.0, 1.0e5), &mut physics);
+        second.update(case.center.world_center(CHUNK_SIZE), &mut physics);
+
+        for chunk in first.loaded_chunks() {
+            let again = terrain(&second, chunk.coord)?;
+            prop_assert!(
+                chunk.terrain.heights == again.heights && chunk.terrain.road_cover == again.road_cover,
+                "chunk {:?} regenerated differently", chunk.coord
+            );
+            prop_assert_eq!(first.bridges_in(chunk.coord), second.bridges_in(chunk.coord));
+        }
+
+        let (a, b) = (PopulationLedger::new(case.seed as u64), PopulationLedger::new(case.seed as u64));
+        let generation = crate::population::generation_of(case.year);
+        for slot in 0..8 {
+            prop_assert_eq!(a.resident(slot, case.center, generation), b.resident(slot, case.center, generation));
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(config(12))]
+
+    #[test]
+    fn test_colliders_match_mesh_heights(
+        case in cases(),
+        // Vertices, where the heightfield and the mesh must agree exactly
+        // (cells are triangulated differently, so only their corners are
+        // comparable)
+        vertices in prop::collection::vec((0..SUBDIVISIONS, 0..SUBDIVISIONS), 32),
+    ) {
+        let mut physics = PhysicsWorld::new();
+        let manager = case.load(&mut physics);
+        physics.update_query_pipeline();
+        let origin = case.center.world_origin(CHUNK_SIZE);
+        let step = CHUNK_SIZE / SUBDIVISIONS as f32;
+        for (i, j) in vertices {
+            let (x, z) = (origin.x + i as f32 * step + 0.01, origin.z + j as f32 * step + 0.01);
+            let mesh = manager.height_at(x, z);
+            let top = 1000.0;
+            let hit = physics.raycast(Vec3::new(x, top, z), Vec3::NEG_Y, 2000.0, QueryFilter::default());
+            let Some((_, distance)) = hit else {
+                return Err(TestCaseError::fail(format!("no collider under ({x}, {z})")));
+            };
+            let collider = top - distance;
+            prop_assert!(
+                (collider - mesh).abs() <= 0.05,
+                "collider at {} but mesh at {} at ({}, {})", collider, mesh, x, z
+            );
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_dungeon_entrances_scatter_deterministically(seed in any::<u32>(), center in chunk_coords()) {
+        let config = DungeonConfig::default();
+        let region = config.region_size;
+        let (region_x, region_z) = (center.x.div_euclid(region) * region, center.z.div_euclid(region) * region);
+        let mut entrances = Vec::new();
+        for z in region_z..region_z + region {
+            for x in region_x..region_x + region {
+                let coord = ChunkCoord::new(x, z);
+                let entrance = config.entrance_in_chunk(coord, seed, CHUNK_SIZE);
+                prop_assert_eq!(entrance, config.entrance_in_chunk(coord, seed, CHUNK_SIZE));
+                if let Some(entrance) = entrance {
+                    prop_assert_eq!(entrance.chunk, coord);
+                    prop_assert_eq!(ChunkCoord::from_world_pos(entrance.position, CHUNK_SIZE), coord);
+                    entrances.push(entrance);
+                }
+            }
+        }
+        // A region holds at most one entrance
+        prop_assert!(entrances.len() <= 1, "{} entrances in one region", entrances.len());
+    }
+}
+
+/// Checksum of a chunk's heights and road cover, to the millimetre
+fn chunk_checksum(terrain: &Terrain) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    for value in terrain.heights.iter().chain(&terrain.road_cover) {
+        hasher.update(&((value * 1000.0).round() as i32).to_le_bytes());
+    }
+    hasher.finalize()
+}
+
+/// Pinned seeds and what they generate. If generation is changed on
+/// purpose, update the checksums to the values the failure reports.
+#[test]
+fn test_golden_seeds() {
+    let golden: [(u32, i64, ChunkCoord, u32); 4] = [
+        (42, PRESENT_YEAR, ChunkCoord::new(0, 0), 0x3b2e_19ae),
+        (42, PRESENT_YEAR, ChunkCoord::new(3, -2), 0xf573_f050),
+        (7, 1200, ChunkCoord::new(-5, 9), 0x88d5_3c25),
+        (1234, 3000, ChunkCoord::new(12, 4), 0x01e2_2f8d),
+    ];
+    let mut mismatches = Vec::new();
+    for (seed, year, center, expected) in golden {
+        let case = Case { seed, year, center };
+        let manager = case.load(&mut PhysicsWorld::new());
+        let checksum = chunk_checksum(terrain(&manager, center).unwrap());
+        if checksum != expected {
+            mismatches.push(format!("seed {seed}, year {year}, chunk {center:?}: {checksum:#010x}"));
+        }
+    }
+    assert!(mismatches.is_empty(), "generation changed for:\n{}", mismatches.join("\n"));
+}
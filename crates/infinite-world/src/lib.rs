@@ -1,15 +1,44 @@
 //! Infinite World - World management and time travel system
 //!
-//! Provides chunk-based world streaming, year-based timeline terrain, and time portals.
+//! Provides chunk-based world streaming with persistent per-chunk changes,
+//! year-based timeline terrain, time portals, instanced dungeons, the
+//! survival arena, settlements and the roads and bridges between them, and
+//! the population ledger of who lives where in each year.
 
+pub mod arena;
+pub mod bridges;
 pub mod chunk;
+pub mod chunk_store;
+pub mod collider_cache;
+pub mod dungeon;
 pub mod era_config;
+pub mod error;
+pub mod fog;
+pub mod population;
+pub mod region;
+pub mod roads;
+pub mod sky;
 pub mod terrain;
 pub mod time_of_day;
 pub mod weather;
+pub mod wind;
 
+#[cfg(test)]
+mod gen_tests;
+
+pub use arena::SurvivalArena;
+pub use bridges::{Bridge, BridgeBlock, BridgeInstance, BridgePart, BridgeStyle};
 pub use chunk::{Chunk, ChunkConfig, ChunkCoord, ChunkManager};
-pub use era_config::TimeTerrainConfig;
+pub use chunk_store::{ChunkDelta, ChunkStore, PlacedItem, TerrainEdit};
+pub use collider_cache::{ColliderCache, ColliderKey};
+pub use dungeon::{DungeonConfig, DungeonEntrance, DungeonInstance, DungeonLayout};
+pub use era_config::{EraPalette, TimeTerrainConfig};
+pub use error::WorldError;
+pub use fog::Fog;
+pub use population::{PopulationLedger, PopulationSaveData, Resident};
+pub use roads::{Road, RoadConfig, RoadNetwork, RoadQuality, Settlement};
+pub use sky::{moon_illumination, moon_phase, EraSky, LUNAR_CYCLE_DAYS};
 pub use terrain::{Terrain, TerrainConfig};
 pub use time_of_day::{SkyColors, TimeOfDay};
-pub use weather::{Weather, WeatherState};
+pub use weather::{CloudCover, Weather, WeatherState};
+pub use wind::Wind;
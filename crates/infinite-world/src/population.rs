@@ -0,0 +1,306 @@
+//! Population ledger: who lives where in which year
+//!
+//! Persistent NPCs hold household slots in their home chunk. Each slot is
+//! held by one resident per generation; a resident may die early (leaving
+//! the house empty until the next generation takes over) or migrate to a
+//! nearby chunk partway through their life. This natural history is derived
+//! from the world seed, so every visit to an era shows the same people.
+//!
+//! On top of it the ledger records deaths the player caused. A resident
+//! killed before raising an heir ends their slot's line, so travelling
+//! forward afterwards finds the house empty in every later era.
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkCoord;
+use crate::dungeon::{hash3, SplitMix};
+
+/// Years between one resident of a slot and the next
+pub const GENERATION_YEARS: i64 = 40;
+/// Age at which a resident has raised the heir who takes over their slot
+pub const HEIR_AGE: i64 = 35;
+/// Furthest a resident moves (in chunks, on each axis)
+pub const MIGRATION_RANGE: i32 = 2;
+
+/// Chance a resident dies before the next generation takes over
+const EARLY_DEATH_CHANCE: f32 = 0.15;
+/// Chance a resident moves to a nearby chunk during their tenure
+const MIGRATION_CHANCE: f32 = 0.2;
+
+/// Generation that holds the slots in `year`
+pub fn generation_of(year: i64) -> i64 {
+    year.div_euclid(GENERATION_YEARS)
+}
+
+/// A resident leaving their home chunk for another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub year: i64,
+    pub to: ChunkCoord,
+}
+
+/// One person holding a household slot for a generation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resident {
+    /// Stable for this person across sessions (usable as a persistent key)
+    pub id: u64,
+    /// Household slot (the spawn point's persistent key)
+    pub slot: u64,
+    pub home: ChunkCoord,
+    pub generation: i64,
+    pub born: i64,
+    /// Year they take over the household
+    pub arrives: i64,
+    /// Year they die, naturally or at the player's hand
+    pub dies: i64,
+    pub migration: Option<Migration>,
+}
+
+impl Resident {
+    pub fn alive_in(&self, year: i64) -> bool {
+        self.born <= year && year < self.dies
+    }
+
+    /// Chunk the resident lives in during `year`. None before they take
+    /// over the household, after they die, or once their heir has taken
+    /// over (unless they moved away, in which case they stay put).
+    pub fn location_in(&self, year: i64) -> Option<ChunkCoord> {
+        if year < self.arrives || !self.alive_in(year) {
+            return None;
+        }
+        match self.migration {
+            Some(migration) if year >= migration.year => Some(migration.to),
+            _ if year < self.arrives + GENERATION_YEARS => Some(self.home),
+            _ => None,
+        }
+    }
+}
+
+/// A death the player caused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedDeath {
+    pub slot: u64,
+    pub generation: i64,
+    pub year: i64,
+    /// Died before raising an heir: the slot stays empty afterwards
+    pub ends_line: bool,
+}
+
+/// Serializable part of the ledger (the natural history is re-derived)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PopulationSaveData {
+    pub deaths: Vec<RecordedDeath>,
+}
+
+/// Natural population history of the world plus the player's changes to it
+#[derive(Debug, Clone, Default)]
+pub struct PopulationLedger {
+    world_seed: u64,
+    deaths: Vec<RecordedDeath>,
+}
+
+impl PopulationLedger {
+    pub fn new(world_seed: u64) -> Self {
+        Self {
+            world_seed,
+            deaths: Vec::new(),
+        }
+    }
+
+    /// The resident of `slot` (homed in `home`) for `generation`, or None
+    /// if the player ended the slot's line in an earlier generation
+    pub fn resident(&self, slot: u64, home: ChunkCoord, generation: i64) -> Option<Resident> {
+        let line_ended = self
+            .deaths
+            .iter()
+            .any(|d| d.slot == slot && d.ends_line && d.generation < generation);
+        if line_ended {
+            return None;
+        }
+
+        let id = hash3(self.world_seed ^ slot, generation, 0);
+        let mut rng = SplitMix::new(id);
+        let arrives = generation * GENERATION_YEARS;
+        let born = arrives - rng.range(20, 30) as i64;
+        let natural_death = if rng.float() < EARLY_DEATH_CHANCE {
+            arrives + rng.range(1, GENERATION_YEARS as usize - 1) as i64
+        } else {
+            arrives + GENERATION_YEARS + rng.range(0, 30) as i64
+        };
+        let migration = if rng.float() < MIGRATION_CHANCE {
+            let year = arrives + rng.range(1, GENERATION_YEARS as usize - 1) as i64;
+            let span = (MIGRATION_RANGE * 2) as usize;
+            let mut offset = (0, 0);
+            while offset == (0, 0) {
+                offset = (rng.range(0, span) as i32 - MIGRATION_RANGE, rng.range(0, span) as i32 - MIGRATION_RANGE);
+            }
+            Some(Migration {
+                year,
+                to: ChunkCoord::new(home.x + offset.0, home.z + offset.1),
+            })
+        } else {
+            None
+        };
+
+        let dies = self
+            .deaths
+            .iter()
+            .filter(|d| d.slot == slot && d.generation == generation)
+            .map(|d| d.year)
+            .fold(natural_death, i64::min);
+
+        Some(Resident {
+            id,
+            slot,
+            home,
+            generation,
+            born,
+            arrives,
+            dies,
+            migration: migration.filter(|m| m.year < dies),
+        })
+    }
+
+    /// Who lives in `slot` of `home` in `year` (None: the house is empty)
+    pub fn occupant(&self, slot: u64, home: ChunkCoord, year: i64) -> Option<Resident> {
+        self.resident(slot, home, generation_of(year))
+            .filter(|r| r.location_in(year) == Some(home))
+    }
+
+    /// Residents of nearby chunks who moved into `coord` and live there in
+    /// `year`. `slots_in` lists the household slots homed in a chunk.
+    pub fn migrants_into(
+        &self,
+        coord: ChunkCoord,
+        year: i64,
+        slots_in: impl Fn(ChunkCoord) -> Vec<u64>,
+    ) -> Vec<Resident> {
+        let generation = generation_of(year);
+        let mut migrants = Vec::new();
+        for dx in -MIGRATION_RANGE..=MIGRATION_RANGE {
+            for dz in -MIGRATION_RANGE..=MIGRATION_RANGE {
+                if dx == 0 && dz == 0 {
+                    continue;
+                }
+                let home = ChunkCoord::new(coord.x + dx, coord.z + dz);
+                for slot in slots_in(home) {
+                    // Migrants outlive their generation, so check the previous one too
+                    for gen in [generation - 1, generation] {
+                        if let Some(resident) = self.resident(slot, home, gen) {
+                            if resident.location_in(year) == Some(coord) {
+                                migrants.push(resident);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        migrants
+    }
+
+    /// The player killed `resident` in `year`
+    pub fn record_death(&mut self, resident: &Resident, year: i64) {
+        self.deaths.push(RecordedDeath {
+            slot: resident.slot,
+            generation: resident.generation,
+            year,
+            ends_line: year - resident.born < HEIR_AGE,
+        });
+    }
+
+    /// Deaths the player caused, oldest first
+    pub fn deaths(&self) -> &[RecordedDeath] {
+        &self.deaths
+    }
+
+    pub fn to_save_data(&self) -> PopulationSaveData {
+        PopulationSaveData {
+            deaths: self.deaths.clone(),
+        }
+    }
+
+    pub fn load_save_data(&mut self, data: PopulationSaveData) {
+        self.deaths = data.deaths;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First slot (searching upward) whose natural resident for generation 50
+    /// passes `filter`
+    fn find_slot(ledger: &PopulationLedger, filter: impl Fn(&Resident) -> bool) -> Resident {
+        (0..10_000u64)
+            .filter_map(|slot| ledger.resident(slot, ChunkCoord::new(0, 0), 50))
+            .find(|r| filter(r))
+            .expect("no matching slot")
+    }
+
+    #[test]
+    fn test_history_is_deterministic() {
+        let a = PopulationLedger::new(7);
+        let b = PopulationLedger::new(7);
+        let home = ChunkCoord::new(3, -2);
+        assert_eq!(a.resident(11, home, 50), b.resident(11, home, 50));
+        assert_ne!(
+            a.resident(11, home, 50).map(|r| r.id),
+            PopulationLedger::new(8).resident(11, home, 50).map(|r| r.id)
+        );
+
+        // One person holds the slot for the whole generation
+        let settled = find_slot(&a, |r| r.migration.is_none() && r.dies >= r.arrives + GENERATION_YEARS);
+        let home = settled.home;
+        let first = a.occupant(settled.slot, home, settled.arrives);
+        let last = a.occupant(settled.slot, home, settled.arrives + GENERATION_YEARS - 1);
+        assert_eq!(first.map(|r| r.id), Some(settled.id));
+        assert_eq!(last.map(|r| r.id), Some(settled.id));
+        assert_ne!(a.occupant(settled.slot, home, settled.arrives + GENERATION_YEARS).map(|r| r.id), Some(settled.id));
+    }
+
+    #[test]
+    fn test_migrants_show_up_in_their_new_chunk() {
+        let ledger = PopulationLedger::new(7);
+        let mover = find_slot(&ledger, |r| r.migration.is_some() && r.dies > r.migration.unwrap().year + 1);
+        let migration = mover.migration.unwrap();
+        let year = migration.year;
+
+        assert_eq!(ledger.occupant(mover.slot, mover.home, year), None);
+        let slot = mover.slot;
+        let arrivals = ledger.migrants_into(migration.to, year, |c| {
+            if c == mover.home { vec![slot] } else { vec![] }
+        });
+        assert_eq!(arrivals.len(), 1);
+        assert_eq!(arrivals[0].id, mover.id);
+        assert_eq!(ledger.occupant(mover.slot, mover.home, year - 1).map(|r| r.id), Some(mover.id));
+    }
+
+    #[test]
+    fn test_killing_young_resident_ends_the_line() {
+        let mut ledger = PopulationLedger::new(7);
+        let victim = find_slot(&ledger, |r| r.migration.is_none() && r.dies > r.arrives + 5 && r.arrives - r.born < HEIR_AGE - 2);
+        let home = victim.home;
+        let next = ledger.resident(victim.slot, home, 51);
+        assert!(next.is_some());
+
+        ledger.record_death(&victim, victim.arrives + 1);
+        assert!(ledger.deaths()[0].ends_line);
+        assert_eq!(ledger.occupant(victim.slot, home, victim.arrives + 2), None);
+        assert_eq!(ledger.occupant(victim.slot, home, victim.arrives), Some(Resident { dies: victim.arrives + 1, ..victim.clone() }));
+        assert_eq!(ledger.resident(victim.slot, home, 51), None);
+
+        // Survives a save round trip
+        let mut restored = PopulationLedger::new(7);
+        restored.load_save_data(ledger.to_save_data());
+        assert_eq!(restored.resident(victim.slot, home, 52), None);
+    }
+
+    #[test]
+    fn test_killing_parent_keeps_heir() {
+        let mut ledger = PopulationLedger::new(7);
+        let victim = find_slot(&ledger, |r| r.dies > r.born + HEIR_AGE + 1);
+        ledger.record_death(&victim, victim.born + HEIR_AGE + 1);
+        assert!(!ledger.deaths()[0].ends_line);
+        assert!(ledger.resident(victim.slot, victim.home, 51).is_some());
+    }
+}
@@ -0,0 +1,201 @@
+//! Region file container: many chunks' data in one compressed file
+//!
+//! Layout (little endian):
+//!
+//! ```text
+//! magic "IRGN" | version: u16 | size: u16
+//! index: size * size entries of (offset: u32, length: u32, crc32: u32)
+//! payloads: zstd-compressed chunk data, back to back
+//! ```
+//!
+//! An entry with length 0 is an empty slot. Reads are corruption tolerant:
+//! a bad header loses the region, a bad entry (out of bounds, checksum
+//! mismatch, undecodable) only loses that chunk.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"IRGN";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 8;
+const ENTRY_LEN: usize = 12;
+/// zstd level: small, fast writes matter more than the last few bytes
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// The chunk slots of one region, kept compressed in memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionFile {
+    size: u16,
+    slots: Vec<Option<Vec<u8>>>,
+    /// Slots (or the whole header) that failed to read
+    corrupted: usize,
+}
+
+impl RegionFile {
+    /// Empty region of `size` x `size` slots
+    pub fn new(size: u16) -> Self {
+        Self {
+            size,
+            slots: vec![None; size as usize * size as usize],
+            corrupted: 0,
+        }
+    }
+
+    /// Read a region from disk; a missing file is an empty region
+    pub fn read(path: &Path, size: u16) -> io::Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Self::parse(&bytes, size)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new(size)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parse region bytes, keeping every slot that checks out
+    pub fn parse(bytes: &[u8], size: u16) -> Self {
+        let mut region = Self::new(size);
+        let slot_count = region.slots.len();
+        let index_end = HEADER_LEN + slot_count * ENTRY_LEN;
+        let header_ok = bytes.len() >= index_end
+            && &bytes[0..4] == MAGIC
+            && u16::from_le_bytes([bytes[4], bytes[5]]) == VERSION
+            && u16::from_le_bytes([bytes[6], bytes[7]]) == size;
+        if !header_ok {
+            region.corrupted = 1;
+            return region;
+        }
+
+        let word = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+        for index in 0..slot_count {
+            let entry = HEADER_LEN + index * ENTRY_LEN;
+            let (offset, length, crc) = (word(entry) as usize, word(entry + 4) as usize, word(entry + 8));
+            if length == 0 {
+                continue;
+            }
+            match bytes.get(offset..offset + length) {
+                Some(payload) if offset >= index_end && crc32fast::hash(payload) == crc => {
+                    region.slots[index] = Some(payload.to_vec());
+                }
+                _ => region.corrupted += 1,
+            }
+        }
+        region
+    }
+
+    /// Decompressed data in a slot. A payload that doesn't decompress
+    /// counts as corrupted and is dropped.
+    pub fn get(&mut self, index: usize) -> Option<Vec<u8>> {
+        let payload = self.slots.get(index)?.as_ref()?;
+        match zstd::decode_all(payload.as_slice()) {
+            Ok(data) => Some(data),
+            Err(_) => {
+                self.slots[index] = None;
+                self.corrupted += 1;
+                None
+            }
+        }
+    }
+
+    /// Compress `data` into a slot
+    pub fn set(&mut self, index: usize, data: &[u8]) -> io::Result<()> {
+        self.slots[index] = Some(zstd::encode_all(data, COMPRESSION_LEVEL)?);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.slots[index] = None;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Slots lost to corruption when this region was read
+    pub fn corrupted(&self) -> usize {
+        self.corrupted
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let index_end = HEADER_LEN + self.slots.len() * ENTRY_LEN;
+        let mut index = Vec::with_capacity(index_end);
+        index.extend_from_slice(MAGIC);
+        index.extend_from_slice(&VERSION.to_le_bytes());
+        index.extend_from_slice(&self.size.to_le_bytes());
+        let mut payloads = Vec::new();
+        for slot in &self.slots {
+            let (offset, length, crc) = match slot {
+                Some(payload) => {
+                    let offset = index_end + payloads.len();
+                    payloads.extend_from_slice(payload);
+                    (offset as u32, payload.len() as u32, crc32fast::hash(payload))
+                }
+                None => (0, 0, 0),
+            };
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&length.to_le_bytes());
+            index.extend_from_slice(&crc.to_le_bytes());
+        }
+        index.extend_from_slice(&payloads);
+        index
+    }
+
+    /// Write the region, or delete its file once it's empty. The new file
+    /// is written beside the old one and swapped in, so a crash never
+    /// leaves a truncated region.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if self.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, self.to_bytes())?;
+        fs::rename(&temp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut region = RegionFile::new(4);
+        region.set(0, b"first chunk").unwrap();
+        region.set(15, &[7; 4096]).unwrap();
+        let bytes = region.to_bytes();
+        assert!(bytes.len() < 4096, "payloads are compressed");
+
+        let mut read = RegionFile::parse(&bytes, 4);
+        assert_eq!(read.corrupted(), 0);
+        assert_eq!(read.get(0).unwrap(), b"first chunk");
+        assert_eq!(read.get(15).unwrap(), vec![7; 4096]);
+        assert_eq!(read.get(3), None);
+    }
+
+    #[test]
+    fn test_corruption_only_loses_bad_slots() {
+        let mut region = RegionFile::new(2);
+        region.set(0, b"intact").unwrap();
+        region.set(1, b"damaged").unwrap();
+        let mut bytes = region.to_bytes();
+        // Flip a byte in the last payload
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+
+        let mut read = RegionFile::parse(&bytes, 2);
+        assert_eq!(read.corrupted(), 1);
+        assert_eq!(read.get(0).unwrap(), b"intact");
+        assert_eq!(read.get(1), None);
+
+        // Truncated or foreign files lose the region but don't fail
+        let truncated = RegionFile::parse(&bytes[..10], 2);
+        assert!(truncated.is_empty());
+        assert_eq!(truncated.corrupted(), 1);
+        assert!(RegionFile::parse(b"{\"version\":1}", 2).is_empty());
+    }
+}
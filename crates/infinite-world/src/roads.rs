@@ -0,0 +1,651 @@
+//! Settlements and the roads between them
+//!
+//! Settlements are placed like dungeon entrances: deterministically from the
+//! world seed, at most one per region of chunks. Each is joined to the
+//! settlements in the next regions east and south by a road, a Catmull-Rom
+//! spline through a couple of jittered control points so it meanders rather
+//! than running dead straight. Everything follows from the seed, so a chunk
+//! can work out which roads cross it without knowing what else is loaded.
+//!
+//! As a chunk generates, the ground under a road is flattened to a smoothed
+//! height profile along the road and blended back into the slope at the
+//! edges, and the road surface is marked on the terrain so the mesh can be
+//! coloured with the era's paving. Where the ground drops too far below the
+//! road it's left alone and the road crosses on a bridge instead.
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::bridges::{find_spans, Bridge, BridgeStyle};
+use crate::chunk::ChunkCoord;
+use crate::dungeon::{hash3, SplitMix};
+use crate::terrain::{Terrain, TerrainConfig};
+
+/// Keeps settlement placement independent of the dungeon regions
+const SETTLEMENT_SALT: u64 = 0x5e77_1e3e_47a1_0001;
+/// Keeps bridge ids apart from settlement seeds
+const BRIDGE_SALT: u64 = 0xb41d_6e5a_17c0_0002;
+/// Metres between samples along a road's centreline
+const SAMPLE_SPACING: f32 = 4.0;
+/// Settlements sit at least this fraction of a region in from its edges
+const SETTLEMENT_MARGIN: f32 = 0.15;
+
+/// Road building of an era
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum RoadQuality {
+    DirtTrack,
+    Cobblestone,
+    #[default]
+    Paved,
+    /// Levelled lanes for hovering traffic
+    Hoverway,
+}
+
+impl RoadQuality {
+    /// Roads of the era a year falls in
+    pub fn for_year(year: i64) -> Self {
+        match year {
+            y if y < 500 => Self::DirtTrack,
+            y if y < 1900 => Self::Cobblestone,
+            y if y <= 2100 => Self::Paved,
+            _ => Self::Hoverway,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DirtTrack => "Dirt track",
+            Self::Cobblestone => "Cobblestone",
+            Self::Paved => "Paved road",
+            Self::Hoverway => "Hoverway",
+        }
+    }
+
+    /// Width of the road surface in metres
+    pub fn width(self) -> f32 {
+        match self {
+            Self::DirtTrack => 3.0,
+            Self::Cobblestone => 4.0,
+            Self::Paved => 6.0,
+            Self::Hoverway => 8.0,
+        }
+    }
+
+    /// Colour of the road surface
+    pub fn color(self) -> [f32; 4] {
+        match self {
+            Self::DirtTrack => [0.42, 0.33, 0.22, 1.0],
+            Self::Cobblestone => [0.47, 0.45, 0.42, 1.0],
+            Self::Paved => [0.2, 0.2, 0.22, 1.0],
+            Self::Hoverway => [0.55, 0.82, 0.92, 1.0],
+        }
+    }
+
+    /// Samples either side averaged into the height profile: better built
+    /// roads cut straighter through the hills
+    fn smoothing(self) -> usize {
+        match self {
+            Self::DirtTrack => 2,
+            Self::Cobblestone => 4,
+            Self::Paved => 6,
+            Self::Hoverway => 10,
+        }
+    }
+}
+
+/// Settlement placement and road shape settings
+#[derive(Clone, Debug)]
+pub struct RoadConfig {
+    /// Side length in chunks of the regions that each hold at most one settlement
+    pub region_size: i32,
+    /// Chance (0.0-1.0) that a region has a settlement
+    pub settlement_chance: f32,
+    /// Radius of the levelled ground in a settlement
+    pub settlement_radius: f32,
+    /// Distance over which road edges blend back into the terrain
+    pub blend_width: f32,
+    /// Furthest a road bends away from the straight line, as a fraction of
+    /// its length
+    pub meander: f32,
+    /// How far the ground has to drop below a road for it to be bridged
+    pub bridge_depth: f32,
+    /// Shortest bridge, bank to bank; narrower dips are filled in
+    pub min_bridge_span: f32,
+}
+
+impl Default for RoadConfig {
+    fn default() -> Self {
+        Self {
+            region_size: 5,
+            settlement_chance: 0.7,
+            settlement_radius: 14.0,
+            blend_width: 6.0,
+            meander: 0.15,
+            bridge_depth: 0.8,
+            min_bridge_span: 8.0,
+        }
+    }
+}
+
+/// A settlement point of interest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settlement {
+    /// Region of chunks the settlement belongs to
+    pub region: (i32, i32),
+    /// World position (y is left at 0 for the caller to place on the terrain)
+    pub position: Vec3,
+    pub seed: u64,
+}
+
+impl Settlement {
+    pub fn name(&self) -> String {
+        place_name(self.seed)
+    }
+}
+
+/// A road between two settlements, sampled along its centreline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Road {
+    /// Regions of the settlements at either end
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+    /// Centreline samples in world x/z, from one end to the other
+    pub points: Vec<Vec2>,
+}
+
+impl Road {
+    /// Length along the centreline in metres
+    pub fn length(&self) -> f32 {
+        self.points.windows(2).map(|pair| pair[0].distance(pair[1])).sum()
+    }
+
+    /// Closest point on the centreline to `point`, the road's direction
+    /// there, and how far away it is
+    pub fn nearest(&self, point: Vec2) -> Option<(Vec2, Vec2, f32)> {
+        self.points
+            .windows(2)
+            .map(|pair| {
+                let (closest, _) = closest_on_segment(point, pair[0], pair[1]);
+                (closest, (pair[1] - pair[0]).normalize_or_zero(), closest.distance(point))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+}
+
+/// Where settlements are and the roads joining them, for one world seed
+#[derive(Debug, Clone)]
+pub struct RoadNetwork {
+    pub config: RoadConfig,
+    seed: u32,
+    chunk_size: f32,
+}
+
+impl RoadNetwork {
+    pub fn new(config: RoadConfig, seed: u32, chunk_size: f32) -> Self {
+        Self { config, seed, chunk_size }
+    }
+
+    /// Region containing a chunk
+    pub fn region_of(&self, coord: ChunkCoord) -> (i32, i32) {
+        let region = self.config.region_size.max(1);
+        (coord.x.div_euclid(region), coord.z.div_euclid(region))
+    }
+
+    /// The settlement of a region, if it has one
+    pub fn settlement_in_region(&self, region: (i32, i32)) -> Option<Settlement> {
+        let mut rng = SplitMix::new(hash3(self.seed as u64 ^ SETTLEMENT_SALT, region.0 as i64, region.1 as i64));
+        if rng.float() >= self.config.settlement_chance {
+            return None;
+        }
+        let extent = self.region_extent();
+        let span = 1.0 - 2.0 * SETTLEMENT_MARGIN;
+        let x = (region.0 as f32 + SETTLEMENT_MARGIN + rng.float() * span) * extent;
+        let z = (region.1 as f32 + SETTLEMENT_MARGIN + rng.float() * span) * extent;
+        Some(Settlement {
+            region,
+            position: Vec3::new(x, 0.0, z),
+            seed: rng.next(),
+        })
+    }
+
+    /// Settlements in the chunk's region and the ones around it
+    pub fn settlements_near(&self, coord: ChunkCoord) -> Vec<Settlement> {
+        let (rx, rz) = self.region_of(coord);
+        (rz - 1..=rz + 1)
+            .flat_map(|z| (rx - 1..=rx + 1).map(move |x| (x, z)))
+            .filter_map(|region| self.settlement_in_region(region))
+            .collect()
+    }
+
+    /// Roads passing within `margin` metres of a chunk
+    pub fn roads_near(&self, coord: ChunkCoord, margin: f32) -> Vec<Road> {
+        let origin = coord.world_origin(self.chunk_size);
+        let min = Vec2::new(origin.x, origin.z) - Vec2::splat(margin);
+        let max = min + Vec2::splat(self.chunk_size + margin * 2.0);
+
+        // Roads run east and south from the region that owns them, bending
+        // at most `meander` of their length, so the owners two regions back
+        // are the furthest that can reach this chunk
+        let (rx, rz) = self.region_of(coord);
+        let mut roads = Vec::new();
+        for z in rz - 2..=rz + 1 {
+            for x in rx - 2..=rx + 1 {
+                let Some(from) = self.settlement_in_region((x, z)) else {
+                    continue;
+                };
+                for to in [(x + 1, z), (x, z + 1)] {
+                    let Some(to) = self.settlement_in_region(to) else {
+                        continue;
+                    };
+                    let controls = self.control_points(&from, &to);
+                    let slack = from.position.distance(to.position) * 0.1;
+                    let (lo, hi) = bounds(&controls);
+                    if hi.x + slack < min.x || lo.x - slack > max.x || hi.y + slack < min.y || lo.y - slack > max.y {
+                        continue;
+                    }
+                    let road = Road {
+                        from: from.region,
+                        to: to.region,
+                        points: sample_spline(&controls),
+                    };
+                    let (lo, hi) = bounds(&road.points);
+                    if hi.x >= min.x && lo.x <= max.x && hi.y >= min.y && lo.y <= max.y {
+                        roads.push(road);
+                    }
+                }
+            }
+        }
+        roads
+    }
+
+    /// Flatten the chunk's terrain under roads and settlements and mark the
+    /// road surface on it. `terrain` must be the chunk's freshly generated,
+    /// unedited terrain.
+    pub fn apply(&self, terrain: &mut Terrain, coord: ChunkCoord, quality: RoadQuality) {
+        let half_width = quality.width() / 2.0;
+        let blend = self.config.blend_width;
+        let reach = half_width.max(self.config.settlement_radius) + blend;
+        let origin = coord.world_origin(self.chunk_size);
+        let chunk_min = Vec2::new(origin.x, origin.z) - Vec2::splat(reach);
+        let chunk_max = chunk_min + Vec2::splat(self.chunk_size + reach * 2.0);
+        let sample = Terrain::height_sampler(&terrain.config);
+        let natural = |p: Vec2| sample(p.x, p.y);
+
+        // Plazas: levelled discs at the settlement's natural height
+        let plazas: Vec<(Vec2, f32)> = self
+            .settlements_near(coord)
+            .iter()
+            .map(|s| Vec2::new(s.position.x, s.position.z))
+            .map(|center| (center, natural(center)))
+            .collect();
+        let plaza_height = |region: (i32, i32)| {
+            self.settlement_in_region(region)
+                .map(|s| natural(Vec2::new(s.position.x, s.position.z)))
+        };
+
+        // Road segments near the chunk, with their heights at either end.
+        // Ground under a bridge is left as it is.
+        let mut segments: Vec<(Vec2, Vec2, f32, f32)> = Vec::new();
+        for road in self.roads_near(coord, reach) {
+            let (profile, spans) = self.road_heights(&road, quality, natural, plaza_height);
+            for (i, pair) in road.points.windows(2).enumerate() {
+                let (a, b) = (pair[0], pair[1]);
+                if a.max(b).cmplt(chunk_min).any() || a.min(b).cmpgt(chunk_max).any() {
+                    continue;
+                }
+                if spans.iter().any(|&(start, end)| (start..end).contains(&i)) {
+                    continue;
+                }
+                segments.push((a, b, profile[i], profile[i + 1]));
+            }
+        }
+        if segments.is_empty() && plazas.iter().all(|(center, _)| !in_box(*center, chunk_min, chunk_max)) {
+            return;
+        }
+
+        let vertex_count = terrain.config.subdivisions + 1;
+        let step = terrain.config.size / terrain.config.subdivisions as f32;
+        let mut cover = vec![0.0; terrain.heights.len()];
+        let mut touched = false;
+        for z in 0..vertex_count {
+            for x in 0..vertex_count {
+                let p = Vec2::new(origin.x + x as f32 * step, origin.z + z as f32 * step);
+                let index = (z * vertex_count + x) as usize;
+
+                // The strongest pull wins where roads meet, and plazas win
+                // ties so they stay level where roads enter them
+                let mut best: Option<(f32, f32, f32)> = None;
+                let mut consider = |distance: f32, half: f32, target: f32| {
+                    if distance >= half + blend {
+                        return;
+                    }
+                    let pull = if distance <= half { 1.0 } else { smoothstep((half + blend - distance) / blend) };
+                    let surface = ((half + 1.0 - distance) / 2.0).clamp(0.0, 1.0);
+                    if best.is_none_or(|(strongest, _, _)| pull > strongest) {
+                        best = Some((pull, target, surface));
+                    }
+                };
+                for &(center, height) in &plazas {
+                    consider(center.distance(p), self.config.settlement_radius, height);
+                }
+                for &(a, b, ha, hb) in &segments {
+                    let (closest, t) = closest_on_segment(p, a, b);
+                    consider(closest.distance(p), half_width, ha + (hb - ha) * t);
+                }
+
+                if let Some((pull, target, surface)) = best {
+                    let height = &mut terrain.heights[index];
+                    *height += (target - *height) * pull;
+                    cover[index] = surface;
+                    touched |= surface > 0.0;
+                }
+            }
+        }
+
+        terrain.min_height = terrain.heights.iter().copied().fold(f32::MAX, f32::min);
+        terrain.max_height = terrain.heights.iter().copied().fold(f32::MIN, f32::max);
+        if touched {
+            terrain.road_cover = cover;
+            terrain.road_color = quality.color();
+        }
+    }
+
+    /// Bridges whose middle is over the chunk. `terrain_config` is the
+    /// config the chunk's terrain is generated with.
+    pub fn bridges_in(&self, coord: ChunkCoord, quality: RoadQuality, terrain_config: &TerrainConfig) -> Vec<Bridge> {
+        let sample = Terrain::height_sampler(terrain_config);
+        let natural = |p: Vec2| sample(p.x, p.y);
+        let plaza_height = |region: (i32, i32)| {
+            self.settlement_in_region(region)
+                .map(|s| natural(Vec2::new(s.position.x, s.position.z)))
+        };
+        let style = BridgeStyle::for_quality(quality);
+
+        let mut bridges = Vec::new();
+        for road in self.roads_near(coord, 0.0) {
+            let (profile, spans) = self.road_heights(&road, quality, natural, plaza_height);
+            for (start, end) in spans {
+                let (a, b) = (road.points[start], road.points[end]);
+                let middle = (a + b) * 0.5;
+                if ChunkCoord::from_world_pos(Vec3::new(middle.x, 0.0, middle.y), self.chunk_size) != coord {
+                    continue;
+                }
+                let road_id = hash3(self.seed as u64 ^ BRIDGE_SALT, road.from.0 as i64, road.from.1 as i64);
+                let id = hash3(road_id ^ hash3(0, road.to.0 as i64, road.to.1 as i64), start as i64, 0);
+                bridges.push(Bridge {
+                    id,
+                    name: format!("{} {}", place_name(id), style.noun()),
+                    chunk: coord,
+                    style,
+                    start: Vec3::new(a.x, profile[start], a.y),
+                    end: Vec3::new(b.x, profile[end], b.y),
+                    width: quality.width(),
+                });
+            }
+        }
+        bridges
+    }
+
+    /// Height profile along a road, and the stretches of it to bridge
+    fn road_heights(
+        &self,
+        road: &Road,
+        quality: RoadQuality,
+        natural: impl Fn(Vec2) -> f32,
+        plaza_height: impl Fn((i32, i32)) -> Option<f32>,
+    ) -> (Vec<f32>, Vec<(usize, usize)>) {
+        let raw: Vec<f32> = road.points.iter().map(|&p| natural(p)).collect();
+        let ends = (plaza_height(road.from), plaza_height(road.to));
+        let profile = height_profile(&raw, quality.smoothing(), ends);
+        let mut spans = find_spans(&raw, &profile, self.config.bridge_depth);
+        spans.retain(|&(start, end)| road.points[start].distance(road.points[end]) >= self.config.min_bridge_span);
+        (profile, spans)
+    }
+
+    fn region_extent(&self) -> f32 {
+        self.config.region_size.max(1) as f32 * self.chunk_size
+    }
+
+    /// Ends of the road and two jittered points between them
+    fn control_points(&self, from: &Settlement, to: &Settlement) -> [Vec2; 4] {
+        let a = Vec2::new(from.position.x, from.position.z);
+        let b = Vec2::new(to.position.x, to.position.z);
+        let mut rng = SplitMix::new(from.seed ^ to.seed.rotate_left(17));
+        let along = b - a;
+        let side = along.perp() * self.config.meander;
+        let mut jitter = || rng.float() * 2.0 - 1.0;
+        [
+            a,
+            a + along / 3.0 + side * jitter(),
+            a + along * (2.0 / 3.0) + side * jitter(),
+            b,
+        ]
+    }
+}
+
+/// A made-up place name, the same for the same seed
+fn place_name(seed: u64) -> String {
+    const STARTS: [&str; 16] = [
+        "Ash", "Brook", "Elm", "Fen", "Gold", "Hale", "Iron", "Kings", "Lark", "Mill", "Oak", "Raven", "Stone", "Thorn",
+        "Wolf", "Wyn",
+    ];
+    const ENDS: [&str; 12] = [
+        "ford", "bury", "dale", "field", "gate", "haven", "holm", "mere", "stead", "ton", "wick", "worth",
+    ];
+    let mut rng = SplitMix::new(seed);
+    let start = STARTS[rng.range(0, STARTS.len() - 1)];
+    let end = ENDS[rng.range(0, ENDS.len() - 1)];
+    format!("{}{}", start, end)
+}
+
+/// Sample a Catmull-Rom spline through `controls` about every
+/// `SAMPLE_SPACING` metres
+fn sample_spline(controls: &[Vec2]) -> Vec<Vec2> {
+    let mut points = vec![controls[0]];
+    for i in 0..controls.len() - 1 {
+        let p0 = controls[i.saturating_sub(1)];
+        let (p1, p2) = (controls[i], controls[i + 1]);
+        let p3 = controls[(i + 2).min(controls.len() - 1)];
+        let steps = (p1.distance(p2) / SAMPLE_SPACING).ceil().max(1.0) as usize;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let (t2, t3) = (t * t, t * t * t);
+            let point = 0.5
+                * (2.0 * p1
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3);
+            points.push(point);
+        }
+    }
+    points
+}
+
+/// Heights along a road: the natural heights averaged over a window, easing
+/// into the plaza heights at settlements
+fn height_profile(raw: &[f32], window: usize, ends: (Option<f32>, Option<f32>)) -> Vec<f32> {
+    let last = raw.len().saturating_sub(1);
+    let mut profile: Vec<f32> = (0..raw.len())
+        .map(|i| {
+            let lo = i.saturating_sub(window);
+            let hi = (i + window).min(last);
+            raw[lo..=hi].iter().sum::<f32>() / (hi - lo + 1) as f32
+        })
+        .collect();
+    let ramp = window.max(1).min(raw.len());
+    for i in 0..ramp {
+        let w = i as f32 / ramp as f32;
+        if let Some(start) = ends.0 {
+            profile[i] = start + (profile[i] - start) * w;
+        }
+        if let Some(end) = ends.1 {
+            profile[last - i] = end + (profile[last - i] - end) * w;
+        }
+    }
+    profile
+}
+
+/// Closest point to `p` on the segment `a`-`b`, and how far along it is
+fn closest_on_segment(p: Vec2, a: Vec2, b: Vec2) -> (Vec2, f32) {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return (a, 0.0);
+    }
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    (a + ab * t, t)
+}
+
+fn bounds(points: &[Vec2]) -> (Vec2, Vec2) {
+    points
+        .iter()
+        .fold((Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)), |(lo, hi), &p| (lo.min(p), hi.max(p)))
+}
+
+fn in_box(p: Vec2, min: Vec2, max: Vec2) -> bool {
+    p.cmpge(min).all() && p.cmple(max).all()
+}
+
+fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::TerrainConfig;
+
+    const CHUNK: f32 = 64.0;
+
+    fn network() -> RoadNetwork {
+        RoadNetwork::new(
+            RoadConfig {
+                settlement_chance: 1.0,
+                ..Default::default()
+            },
+            42,
+            CHUNK,
+        )
+    }
+
+    fn chunk_terrain(coord: ChunkCoord) -> Terrain {
+        let config = TerrainConfig {
+            size: CHUNK,
+            subdivisions: 32,
+            max_height: 20.0,
+            ..Default::default()
+        };
+        let origin = coord.world_origin(CHUNK);
+        Terrain::generate_chunk(config, origin.x, origin.z)
+    }
+
+    #[test]
+    fn test_settlements_are_deterministic_and_inside_their_region() {
+        let roads = network();
+        let extent = roads.region_extent();
+        for region in [(0, 0), (-3, 2), (7, -7)] {
+            let settlement = roads.settlement_in_region(region).unwrap();
+            assert_eq!(Some(settlement), roads.settlement_in_region(region));
+            let cell = ((settlement.position.x / extent).floor() as i32, (settlement.position.z / extent).floor() as i32);
+            assert_eq!(cell, region);
+        }
+        let sparse = RoadNetwork::new(RoadConfig { settlement_chance: 0.0, ..Default::default() }, 42, CHUNK);
+        assert!(sparse.settlement_in_region((0, 0)).is_none());
+        assert!(sparse.roads_near(ChunkCoord::new(0, 0), 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_roads_join_neighbouring_settlements_across_chunks() {
+        let roads = network();
+        let a = roads.settlement_in_region((0, 0)).unwrap();
+        let b = roads.settlement_in_region((1, 0)).unwrap();
+        let (ax, bx) = (Vec2::new(a.position.x, a.position.z), Vec2::new(b.position.x, b.position.z));
+
+        // Every chunk the road crosses finds the same road
+        let road = Road {
+            from: a.region,
+            to: b.region,
+            points: sample_spline(&roads.control_points(&a, &b)),
+        };
+        assert_eq!(road.points.first(), Some(&ax));
+        assert!(road.points.last().unwrap().distance(bx) < 1e-3);
+        assert!(road.length() >= ax.distance(bx));
+        let mid = road.points[road.points.len() / 2];
+        let coord = ChunkCoord::from_world_pos(Vec3::new(mid.x, 0.0, mid.y), CHUNK);
+        let found = roads.roads_near(coord, 0.0);
+        assert!(found.contains(&road), "the chunk under the road's midpoint sees it");
+        assert_eq!(road.nearest(mid).unwrap().2, 0.0);
+    }
+
+    #[test]
+    fn test_apply_flattens_and_marks_the_road() {
+        let roads = network();
+        let settlement = roads.settlement_in_region((0, 0)).unwrap();
+        let coord = ChunkCoord::from_world_pos(settlement.position, CHUNK);
+        let mut terrain = chunk_terrain(coord);
+        let before = terrain.clone();
+        roads.apply(&mut terrain, coord, RoadQuality::Cobblestone);
+
+        assert_eq!(terrain.road_cover.len(), terrain.heights.len());
+        assert_eq!(terrain.road_color, RoadQuality::Cobblestone.color());
+        // The plaza is level at the settlement's natural height
+        let origin = coord.world_origin(CHUNK);
+        let half = CHUNK / 2.0;
+        let local = settlement.position - origin;
+        let plaza = Terrain::sample_height(&terrain.config, settlement.position.x, settlement.position.z);
+        for offset in [Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(0.0, -4.0)] {
+            let (x, z) = (local.x - half + offset.x, local.z - half + offset.y);
+            if terrain.contains(x, z) {
+                assert!((terrain.height_at(x, z) - plaza).abs() < 0.05);
+            }
+        }
+        assert_ne!(terrain.heights, before.heights);
+        assert!(terrain.road_cover.contains(&1.0));
+
+        // The same chunk flattens the same way every time
+        let mut again = chunk_terrain(coord);
+        roads.apply(&mut again, coord, RoadQuality::Cobblestone);
+        assert_eq!(again.heights, terrain.heights);
+    }
+
+    #[test]
+    fn test_ravines_are_bridged_not_filled() {
+        let roads = network();
+        let config = chunk_terrain(ChunkCoord::new(0, 0)).config;
+        let found: Vec<Bridge> = (-6..6)
+            .flat_map(|x| (-6..6).map(move |z| ChunkCoord::new(x, z)))
+            .flat_map(|coord| roads.bridges_in(coord, RoadQuality::Cobblestone, &config))
+            .collect();
+        assert!(!found.is_empty());
+
+        for bridge in &found {
+            let again = roads.bridges_in(bridge.chunk, RoadQuality::Cobblestone, &config);
+            assert_eq!(again.iter().find(|b| b.id == bridge.id), Some(bridge));
+            assert_eq!(bridge.style, BridgeStyle::Stone);
+            assert!(bridge.span() >= roads.config.min_bridge_span);
+            assert!(bridge.name.ends_with(" Bridge"));
+        }
+        let mut ids: Vec<u64> = found.iter().map(|b| b.id).collect();
+        ids.dedup();
+        assert_eq!(ids.len(), found.len(), "each bridge belongs to one chunk");
+
+        // The ground under the middle of the deck keeps its natural height
+        let bridge = &found[0];
+        let mut terrain = chunk_terrain(bridge.chunk);
+        roads.apply(&mut terrain, bridge.chunk, RoadQuality::Cobblestone);
+        let center = bridge.center() - bridge.chunk.world_center(CHUNK);
+        let natural = Terrain::sample_height(&terrain.config, bridge.center().x, bridge.center().z);
+        assert!((terrain.height_at(center.x, center.z) - natural).abs() < 0.3);
+        assert!(natural < bridge.center().y - 1.0);
+    }
+
+    #[test]
+    fn test_quality_follows_the_era() {
+        assert_eq!(RoadQuality::for_year(-300), RoadQuality::DirtTrack);
+        assert_eq!(RoadQuality::for_year(1200), RoadQuality::Cobblestone);
+        assert_eq!(RoadQuality::for_year(2025), RoadQuality::Paved);
+        assert_eq!(RoadQuality::for_year(2500), RoadQuality::Hoverway);
+        assert!(RoadQuality::Hoverway.width() > RoadQuality::DirtTrack.width());
+    }
+}
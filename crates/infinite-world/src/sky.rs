@@ -0,0 +1,132 @@
+//! Night sky, moon and era sky styles
+//!
+//! The sky dome's gradient comes from the time of day ([`SkyColors`]); over
+//! it go the sun and moon discs and a star field, and clouds as thick as the
+//! weather makes them ([`Weather::cloud_cover`]). The moon runs through its
+//! phases over [`LUNAR_CYCLE_DAYS`] game days. How all of it looks depends
+//! on the era: the deep past has a nearer, larger moon and more stars, the
+//! far future an alien sky with a second moon and a planetary ring.
+//!
+//! [`SkyColors`]: crate::SkyColors
+//! [`Weather::cloud_cover`]: crate::Weather::cloud_cover
+
+use std::f32::consts::TAU;
+
+use glam::Vec3;
+
+use crate::era_config::TimeTerrainConfig;
+
+/// Game days from one new moon to the next
+pub const LUNAR_CYCLE_DAYS: u32 = 8;
+
+/// Phase of the moon `time_hours` into `day` (0.0 = new, 0.5 = full)
+pub fn moon_phase(day: u32, time_hours: f32) -> f32 {
+    ((day % LUNAR_CYCLE_DAYS) as f32 + time_hours / 24.0) / LUNAR_CYCLE_DAYS as f32
+}
+
+/// Lit share of the moon's disc at `phase` (0.0 at new moon, 1.0 at full)
+pub fn moon_illumination(phase: f32) -> f32 {
+    (1.0 - (phase * TAU).cos()) * 0.5
+}
+
+/// How the sky looks in one era
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EraSky {
+    /// Color of the sun's disc and glow
+    pub sun_tint: Vec3,
+    /// Sun disc size relative to the present's
+    pub sun_scale: f32,
+    pub moon_tint: Vec3,
+    /// Moon disc size (1 - cosine of its angular radius)
+    pub moon_size: f32,
+    pub star_tint: Vec3,
+    /// Share of the sky holding a star
+    pub star_density: f32,
+    /// Color of sunlit cloud tops
+    pub cloud_tint: Vec3,
+    /// Size of a second moon (0.0 = none)
+    pub second_moon: f32,
+    /// Brightness of a ring across the sky (0.0 = none)
+    pub ring: f32,
+}
+
+impl Default for EraSky {
+    fn default() -> Self {
+        Self {
+            sun_tint: Vec3::new(1.0, 0.95, 0.85),
+            sun_scale: 1.0,
+            moon_tint: Vec3::new(0.9, 0.92, 1.0),
+            moon_size: 0.004,
+            star_tint: Vec3::ONE,
+            star_density: 0.002,
+            cloud_tint: Vec3::ONE,
+            second_moon: 0.0,
+            ring: 0.0,
+        }
+    }
+}
+
+impl EraSky {
+    /// The sky of `era`'s period
+    pub fn for_era(era: &TimeTerrainConfig) -> Self {
+        let present = Self::default();
+        let age = era.age();
+        if age >= 0.0 {
+            // No light pollution and a moon that has yet to drift away
+            Self {
+                sun_tint: present.sun_tint.lerp(Vec3::new(1.0, 0.85, 0.65), age),
+                moon_tint: present.moon_tint.lerp(Vec3::new(1.0, 0.9, 0.8), age),
+                moon_size: present.moon_size * (1.0 + age * 0.6),
+                star_density: present.star_density * (1.0 + age * 1.5),
+                cloud_tint: present.cloud_tint.lerp(Vec3::new(1.0, 0.92, 0.85), age),
+                ..present
+            }
+        } else {
+            let t = -age;
+            Self {
+                sun_tint: present.sun_tint.lerp(Vec3::new(0.85, 0.95, 1.0), t),
+                sun_scale: 1.0 - t * 0.3,
+                moon_tint: present.moon_tint.lerp(Vec3::new(0.7, 1.0, 0.9), t),
+                star_tint: Vec3::ONE.lerp(Vec3::new(0.75, 0.85, 1.0), t),
+                star_density: present.star_density * (1.0 + t),
+                cloud_tint: present.cloud_tint.lerp(Vec3::new(0.85, 0.8, 1.0), t),
+                // A captured moon and a debris ring only far enough ahead
+                second_moon: ((t - 0.5) * 2.0).max(0.0) * present.moon_size * 0.6,
+                ring: ((t - 0.3) / 0.7).clamp(0.0, 1.0) * 0.6,
+                ..present
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moon_phases_follow_days() {
+        assert_eq!(moon_phase(0, 0.0), 0.0);
+        assert_eq!(moon_phase(LUNAR_CYCLE_DAYS / 2, 0.0), 0.5);
+        assert_eq!(moon_phase(LUNAR_CYCLE_DAYS, 0.0), 0.0);
+        assert!(moon_phase(1, 12.0) > moon_phase(1, 0.0));
+
+        assert!(moon_illumination(moon_phase(0, 0.0)) < 0.01);
+        assert!(moon_illumination(moon_phase(LUNAR_CYCLE_DAYS / 2, 0.0)) > 0.99);
+    }
+
+    #[test]
+    fn test_era_skies() {
+        let present = EraSky::for_era(&TimeTerrainConfig::for_year(2025, 2025));
+        let past = EraSky::for_era(&TimeTerrainConfig::for_year(-5000, 2025));
+        let near_future = EraSky::for_era(&TimeTerrainConfig::for_year(2525, 2025));
+        let far_future = EraSky::for_era(&TimeTerrainConfig::for_year(5025, 2025));
+
+        assert_eq!(present, EraSky::default());
+        assert!(past.moon_size > present.moon_size);
+        assert!(past.star_density > present.star_density);
+        // Only the far future's sky turns alien
+        assert_eq!(near_future.second_moon, 0.0);
+        assert!(far_future.second_moon > 0.0);
+        assert!(far_future.ring > 0.0);
+    }
+}
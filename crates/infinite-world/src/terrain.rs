@@ -51,6 +51,11 @@ pub struct Terrain {
     pub min_height: f32,
     /// Maximum height in the terrain
     pub max_height: f32,
+    /// How much of each vertex is road surface (0.0-1.0, same layout as
+    /// `heights`); empty when no road crosses the terrain
+    pub road_cover: Vec<f32>,
+    /// Colour of the road surface
+    pub road_color: [f32; 4],
 }
 
 impl Terrain {
@@ -93,6 +98,29 @@ impl Terrain {
             heights,
             min_height,
             max_height,
+            road_cover: Vec::new(),
+            road_color: [0.0; 4],
+        }
+    }
+
+    /// Height of unedited terrain at a world position, as `generate_chunk`
+    /// would produce it for any chunk covering that point
+    pub fn sample_height(config: &TerrainConfig, world_x: f32, world_z: f32) -> f32 {
+        Self::height_sampler(config)(world_x, world_z)
+    }
+
+    /// `sample_height` for many points, setting the noise up once
+    pub fn height_sampler(config: &TerrainConfig) -> impl Fn(f32, f32) -> f32 + '_ {
+        let perlin = Perlin::new(config.seed);
+        move |world_x, world_z| {
+            fractal_noise(
+                &perlin,
+                (world_x * config.noise_scale) as f64,
+                (world_z * config.noise_scale) as f64,
+                config.octaves,
+                config.persistence,
+                config.lacunarity,
+            ) * config.max_height
         }
     }
 
@@ -145,8 +173,30 @@ impl Terrain {
         x >= -half_size && x <= half_size && z >= -half_size && z <= half_size
     }
 
-    /// Get terrain color based on height and position
-    pub fn color_at(&self, _x: f32, height: f32, _z: f32) -> [f32; 4] {
+    /// Get terrain color based on height and position, with roads painted
+    /// over the ground
+    pub fn color_at(&self, x: f32, height: f32, z: f32) -> [f32; 4] {
+        let ground = self.ground_color(height);
+        match self.road_cover_at(x, z) {
+            cover if cover > 0.0 => lerp_color(ground, self.road_color, cover),
+            _ => ground,
+        }
+    }
+
+    /// Road surface at the vertex nearest a position (0.0 off road)
+    pub fn road_cover_at(&self, x: f32, z: f32) -> f32 {
+        if self.road_cover.is_empty() {
+            return 0.0;
+        }
+        let half_size = self.config.size / 2.0;
+        let step = self.config.size / self.config.subdivisions as f32;
+        let grid_x = ((x + half_size) / step).round().clamp(0.0, self.config.subdivisions as f32) as u32;
+        let grid_z = ((z + half_size) / step).round().clamp(0.0, self.config.subdivisions as f32) as u32;
+        let index = (grid_z * (self.config.subdivisions + 1) + grid_x) as usize;
+        self.road_cover.get(index).copied().unwrap_or(0.0)
+    }
+
+    fn ground_color(&self, height: f32) -> [f32; 4] {
         let height_normalized =
             (height - self.min_height) / (self.max_height - self.min_height).max(0.01);
 
@@ -225,9 +275,70 @@ impl Terrain {
             heights,
             min_height,
             max_height,
+            road_cover: Vec::new(),
+            road_color: [0.0; 4],
+        }
+    }
+
+    /// Heights (and roads) part way from `self` (t = 0) to `other` (t = 1),
+    /// which must have the same size and subdivisions
+    pub fn blend(&self, other: &Terrain, t: f32) -> Terrain {
+        debug_assert_eq!(self.heights.len(), other.heights.len());
+        let t = t.clamp(0.0, 1.0);
+        let heights: Vec<f32> = self
+            .heights
+            .iter()
+            .zip(&other.heights)
+            .map(|(a, b)| a * (1.0 - t) + b * t)
+            .collect();
+        let min_height = heights.iter().copied().fold(f32::MAX, f32::min);
+        let max_height = heights.iter().copied().fold(f32::MIN, f32::max);
+
+        // A road only one side has fades in or out in its own colour
+        let cover = |terrain: &Terrain, i: usize| terrain.road_cover.get(i).copied().unwrap_or(0.0);
+        let road_cover = if self.road_cover.is_empty() && other.road_cover.is_empty() {
+            Vec::new()
+        } else {
+            (0..heights.len()).map(|i| cover(self, i) * (1.0 - t) + cover(other, i) * t).collect()
+        };
+        let road_color = match (self.road_cover.is_empty(), other.road_cover.is_empty()) {
+            (false, true) => self.road_color,
+            (true, false) => other.road_color,
+            _ => lerp_color(self.road_color, other.road_color, t),
+        };
+        Terrain {
+            config: if t < 0.5 { self.config.clone() } else { other.config.clone() },
+            heights,
+            min_height,
+            max_height,
+            road_cover,
+            road_color,
         }
     }
 
+    /// Replace heights that aren't finite (degenerate configs can produce
+    /// them, and the physics heightfield rejects them) with sea level.
+    /// Returns how many were replaced.
+    pub fn sanitize(&mut self) -> usize {
+        let mut replaced = 0;
+        for height in &mut self.heights {
+            if !height.is_finite() {
+                *height = 0.0;
+                replaced += 1;
+            }
+        }
+        if replaced > 0 {
+            self.min_height = self.heights.iter().copied().fold(f32::MAX, f32::min);
+            self.max_height = self.heights.iter().copied().fold(f32::MIN, f32::max);
+        }
+        replaced
+    }
+
+    /// Heap memory held by the heightfield and road cover
+    pub fn heap_bytes(&self) -> usize {
+        (self.heights.capacity() + self.road_cover.capacity()) * std::mem::size_of::<f32>()
+    }
+
     /// Get heights for physics heightfield collider
     /// Returns heights in the format expected by rapier3d
     pub fn physics_heights(&self) -> Vec<f32> {
@@ -256,7 +367,7 @@ fn fractal_noise(
     let mut max_value = 0.0f32;
 
     for _ in 0..octaves {
-        let value = perlin.get([x as f64 * frequency as f64, z as f64 * frequency as f64]) as f32;
+        let value = perlin.get([x * frequency as f64, z * frequency as f64]) as f32;
         total += value * amplitude;
         max_value += amplitude;
         amplitude *= persistence;
@@ -293,6 +404,22 @@ mod tests {
         assert_eq!(terrain.heights.len(), 25); // 5x5 vertices
     }
 
+    #[test]
+    fn test_sanitize_flattens_invalid_heights() {
+        let mut terrain = Terrain::generate(TerrainConfig {
+            size: 10.0,
+            subdivisions: 2,
+            max_height: 4.0,
+            ..Default::default()
+        });
+        terrain.heights[0] = f32::NAN;
+        terrain.heights[4] = f32::INFINITY;
+        assert_eq!(terrain.sanitize(), 2);
+        assert!(terrain.heights.iter().all(|h| h.is_finite()));
+        assert!(terrain.min_height.is_finite() && terrain.max_height.is_finite());
+        assert_eq!(terrain.sanitize(), 0);
+    }
+
     #[test]
     fn test_height_at() {
         let config = TerrainConfig {
@@ -323,4 +450,30 @@ mod tests {
         assert!(terrain.contains(49.0, 49.0));
         assert!(!terrain.contains(60.0, 0.0));
     }
+
+    #[test]
+    fn test_blend_interpolates_heights() {
+        let low = Terrain::generate(TerrainConfig {
+            size: 10.0,
+            subdivisions: 4,
+            max_height: 1.0,
+            ..Default::default()
+        });
+        let high = Terrain::generate(TerrainConfig {
+            size: 10.0,
+            subdivisions: 4,
+            max_height: 3.0,
+            seed: 7,
+            ..Default::default()
+        });
+
+        assert_eq!(low.blend(&high, 0.0).heights, low.heights);
+        assert_eq!(low.blend(&high, 1.0).heights, high.heights);
+        let half = low.blend(&high, 0.5);
+        for ((a, b), mid) in low.heights.iter().zip(&high.heights).zip(&half.heights) {
+            assert!((mid - (a + b) / 2.0).abs() < 1e-5);
+        }
+        assert!(half.min_height <= half.max_height);
+        assert_eq!(half.max_height, half.heights.iter().copied().fold(f32::MIN, f32::max));
+    }
 }
@@ -4,6 +4,8 @@ use glam::Vec3;
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
+use crate::sky::{moon_illumination, moon_phase};
+
 /// Time of day configuration and state
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TimeOfDay {
@@ -13,6 +15,9 @@ pub struct TimeOfDay {
     pub cycle_duration: f32,
     /// Whether the cycle is paused
     pub paused: bool,
+    /// Days passed since the game began, for the moon's phases
+    #[serde(default)]
+    pub day: u32,
 }
 
 impl Default for TimeOfDay {
@@ -21,6 +26,7 @@ impl Default for TimeOfDay {
             time_hours: 10.0, // Start at 10 AM
             cycle_duration: 1440.0,
             paused: false,
+            day: 0,
         }
     }
 }
@@ -42,8 +48,9 @@ impl TimeOfDay {
 
         // Convert real seconds to game hours
         let hours_per_second = 24.0 / self.cycle_duration;
-        self.time_hours += delta_seconds * hours_per_second;
-        self.time_hours = self.time_hours.rem_euclid(24.0);
+        let hours = self.time_hours + delta_seconds.max(0.0) * hours_per_second;
+        self.day = self.day.wrapping_add((hours / 24.0) as u32);
+        self.time_hours = hours.rem_euclid(24.0);
     }
 
     /// Set the time directly
@@ -114,8 +121,13 @@ impl TimeOfDay {
             return 0.0;
         }
 
-        // Simple moon intensity (could be expanded for moon phases)
-        0.15
+        // A new moon still leaves some starlight
+        0.05 + moon_illumination(self.moon_phase()) * 0.15
+    }
+
+    /// Phase of the moon (0.0 = new, 0.5 = full)
+    pub fn moon_phase(&self) -> f32 {
+        moon_phase(self.day, self.time_hours)
     }
 
     /// Get the effective light direction (sun during day, moon at night)
@@ -319,4 +331,18 @@ mod tests {
         tod.update(1.0);
         assert!((tod.time_hours - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_days_turn_the_moon() {
+        let mut tod = TimeOfDay::new(23.0);
+        tod.cycle_duration = 24.0;
+        let new_moon = tod.moon_intensity();
+
+        tod.update(2.0);
+        assert_eq!(tod.day, 1);
+        assert!((tod.time_hours - 1.0).abs() < 0.01);
+
+        tod.day = crate::sky::LUNAR_CYCLE_DAYS / 2;
+        assert!(tod.moon_intensity() > new_moon);
+    }
 }
@@ -8,6 +8,8 @@ pub enum WeatherState {
     #[default]
     Clear,
     Cloudy,
+    /// Thick ground fog
+    Fog,
     Rain,
     Storm,
 }
@@ -17,6 +19,7 @@ impl WeatherState {
         match self {
             Self::Clear => "Clear",
             Self::Cloudy => "Cloudy",
+            Self::Fog => "Fog",
             Self::Rain => "Rain",
             Self::Storm => "Storm",
         }
@@ -26,7 +29,8 @@ impl WeatherState {
     pub fn next(&self) -> Self {
         match self {
             Self::Clear => Self::Cloudy,
-            Self::Cloudy => Self::Rain,
+            Self::Cloudy => Self::Fog,
+            Self::Fog => Self::Rain,
             Self::Rain => Self::Storm,
             Self::Storm => Self::Clear,
         }
@@ -37,12 +41,24 @@ impl WeatherState {
         match self {
             Self::Clear => Self::Storm,
             Self::Cloudy => Self::Clear,
-            Self::Rain => Self::Cloudy,
+            Self::Fog => Self::Cloudy,
+            Self::Rain => Self::Fog,
             Self::Storm => Self::Rain,
         }
     }
 }
 
+/// Cloud layers over the sky dome
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CloudCover {
+    /// Share of the sky covered (0.0 - 1.0)
+    pub coverage: f32,
+    /// Opacity of the clouds there are (0.0 - 1.0)
+    pub density: f32,
+    /// How dark their undersides are (0.0 = white, 1.0 = storm grey)
+    pub darkness: f32,
+}
+
 /// Weather configuration and state
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Weather {
@@ -52,7 +68,7 @@ pub struct Weather {
     pub cloud_coverage: f32,
     /// Fog density (0.0 = none, 1.0 = heavy)
     pub fog_density: f32,
-    /// Wind strength (affects particles if implemented)
+    /// Wind strength (drives the global `Wind`)
     pub wind_strength: f32,
     /// Target weather (for transitions)
     target: WeatherState,
@@ -138,6 +154,7 @@ impl Weather {
         match self.current {
             WeatherState::Clear => 1.0,
             WeatherState::Cloudy => 0.6,
+            WeatherState::Fog => 0.5,
             WeatherState::Rain => 0.3,
             WeatherState::Storm => 0.1,
         }
@@ -148,6 +165,7 @@ impl Weather {
         match self.current {
             WeatherState::Clear => 1.0,
             WeatherState::Cloudy => 1.2, // Softer shadows, more ambient
+            WeatherState::Fog => 1.3,
             WeatherState::Rain => 0.8,
             WeatherState::Storm => 0.5,
         }
@@ -163,11 +181,32 @@ impl Weather {
         matches!(self.current, WeatherState::Rain | WeatherState::Storm)
     }
 
+    /// Precipitation intensity (0.0 = dry, 1.0 = storm)
+    pub fn precipitation_intensity(&self) -> f32 {
+        match self.current {
+            WeatherState::Clear | WeatherState::Cloudy | WeatherState::Fog => 0.0,
+            WeatherState::Rain => 0.6,
+            WeatherState::Storm => 1.0,
+        }
+    }
+
+    /// Clouds for the sky dome. Even clear skies keep a few fair-weather
+    /// clouds; they thicken and darken with coverage, following transitions.
+    pub fn cloud_cover(&self) -> CloudCover {
+        let coverage = self.cloud_coverage.clamp(0.0, 1.0);
+        CloudCover {
+            coverage: 0.1 + coverage * 0.9,
+            density: 0.5 + coverage * 0.5,
+            darkness: ((coverage - 0.4) / 0.6).clamp(0.0, 1.0) * 0.8,
+        }
+    }
+
     /// Get sky color modifiers
     pub fn sky_tint(&self) -> [f32; 3] {
         match self.current {
             WeatherState::Clear => [1.0, 1.0, 1.0],
             WeatherState::Cloudy => [0.8, 0.8, 0.85],
+            WeatherState::Fog => [0.75, 0.75, 0.78],
             WeatherState::Rain => [0.5, 0.55, 0.6],
             WeatherState::Storm => [0.3, 0.3, 0.35],
         }
@@ -180,6 +219,7 @@ impl WeatherState {
         match self {
             Self::Clear => (0.0, 0.0, 0.1),
             Self::Cloudy => (0.6, 0.1, 0.2),
+            Self::Fog => (0.4, 0.9, 0.05),
             Self::Rain => (0.9, 0.3, 0.4),
             Self::Storm => (1.0, 0.5, 0.8),
         }
@@ -203,6 +243,27 @@ mod tests {
         assert!(clear.visibility_modifier() > storm.visibility_modifier());
     }
 
+    #[test]
+    fn test_cloud_cover_follows_weather() {
+        let clear = Weather::new(WeatherState::Clear).cloud_cover();
+        let cloudy = Weather::new(WeatherState::Cloudy).cloud_cover();
+        let storm = Weather::new(WeatherState::Storm).cloud_cover();
+
+        assert!(clear.coverage > 0.0 && clear.darkness == 0.0);
+        assert!(cloudy.coverage > clear.coverage && cloudy.density > clear.density);
+        assert_eq!(storm.coverage, 1.0);
+        assert!(storm.darkness > cloudy.darkness);
+    }
+
+    #[test]
+    fn test_precipitation_intensity() {
+        assert_eq!(Weather::new(WeatherState::Clear).precipitation_intensity(), 0.0);
+        assert!(
+            Weather::new(WeatherState::Storm).precipitation_intensity()
+                > Weather::new(WeatherState::Rain).precipitation_intensity()
+        );
+    }
+
     #[test]
     fn test_weather_cycle() {
         let mut weather = Weather::new(WeatherState::Clear);
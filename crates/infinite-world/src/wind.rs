@@ -0,0 +1,154 @@
+//! Global wind driven by the weather, with slow veering and gusts
+
+use glam::{Vec2, Vec3};
+use infinite_physics::ForceField;
+use serde::{Deserialize, Serialize};
+
+use crate::weather::{Weather, WeatherState};
+
+/// Wind speed in m/s at strength 1.0 (used for particle drift)
+const MAX_WIND_SPEED: f32 = 14.0;
+
+/// How quickly the steady wind follows weather changes (per second)
+const STRENGTH_RESPONSE: f32 = 0.5;
+
+/// Push on bodies and characters (m/s²) per m/s of wind
+const WIND_PUSH: f32 = 0.15;
+
+/// Global wind state shared by vegetation, particles and audio
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Wind {
+    /// Prevailing direction in radians (0 = +X, counter-clockwise towards +Z)
+    pub heading: f32,
+    /// Steady wind strength (0.0 = calm, 1.0 = gale), follows the weather
+    pub base_strength: f32,
+    /// Current gust amount (0.0 = none, 1.0 = strongest gust)
+    gust: f32,
+    /// Accumulated time, drives veering, gusts and shader sway phase
+    time: f32,
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Self {
+            heading: 0.6,
+            base_strength: 0.1,
+            gust: 0.0,
+            time: 0.0,
+        }
+    }
+}
+
+impl Wind {
+    /// Advance the wind, easing towards the weather's wind strength
+    pub fn update(&mut self, delta: f32, weather: &Weather) {
+        self.time += delta;
+
+        let target = weather.wind_strength;
+        let t = (delta * STRENGTH_RESPONSE).min(1.0);
+        self.base_strength += (target - self.base_strength) * t;
+
+        // Slow veer around the prevailing heading
+        self.heading += (self.time * 0.05).sin() * 0.02 * delta;
+
+        // Layered sines give irregular but smooth gusts
+        let wave = (self.time * 0.37).sin() * 0.5
+            + (self.time * 0.91 + 1.3).sin() * 0.3
+            + (self.time * 2.3 + 0.7).sin() * 0.2;
+        self.gust = wave.max(0.0) * gustiness(weather.current);
+    }
+
+    /// Effective strength including gusts (0.0 - ~1.5)
+    pub fn strength(&self) -> f32 {
+        self.base_strength * (1.0 + self.gust)
+    }
+
+    /// Current gust amount (0.0 - 1.0)
+    pub fn gust(&self) -> f32 {
+        self.gust
+    }
+
+    /// Unit direction on the ground plane (x, z)
+    pub fn direction(&self) -> Vec2 {
+        Vec2::new(self.heading.cos(), self.heading.sin())
+    }
+
+    /// Direction scaled by effective strength, as fed to the vegetation shader
+    pub fn vector(&self) -> Vec2 {
+        self.direction() * self.strength()
+    }
+
+    /// World-space wind velocity in m/s (for particle drift)
+    pub fn velocity(&self) -> Vec3 {
+        let v = self.vector() * MAX_WIND_SPEED;
+        Vec3::new(v.x, 0.0, v.y)
+    }
+
+    /// The push the wind gives loose props and people, as a world-wide field
+    pub fn force_field(&self) -> ForceField {
+        let velocity = self.velocity();
+        ForceField::global(velocity, velocity.length() * WIND_PUSH)
+    }
+
+    /// Animation time for shader sway phase
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+}
+
+/// How strongly gusts modulate the steady wind for each weather state
+fn gustiness(state: WeatherState) -> f32 {
+    match state {
+        WeatherState::Clear => 0.2,
+        WeatherState::Cloudy => 0.35,
+        WeatherState::Fog => 0.1,
+        WeatherState::Rain => 0.5,
+        WeatherState::Storm => 0.9,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_follows_weather() {
+        let storm = Weather::new(WeatherState::Storm);
+        let mut wind = Wind::default();
+        for _ in 0..600 {
+            wind.update(0.05, &storm);
+        }
+        assert!((wind.base_strength - storm.wind_strength).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_gusts_bounded() {
+        let storm = Weather::new(WeatherState::Storm);
+        let mut wind = Wind::default();
+        for _ in 0..2000 {
+            wind.update(0.1, &storm);
+            assert!((0.0..=1.0).contains(&wind.gust()));
+            assert!(wind.strength() >= wind.base_strength);
+        }
+    }
+
+    #[test]
+    fn test_direction_normalized() {
+        let wind = Wind::default();
+        assert!((wind.direction().length() - 1.0).abs() < 1e-5);
+        assert_eq!(wind.velocity().y, 0.0);
+    }
+
+    #[test]
+    fn test_storms_push_harder() {
+        let mut wind = Wind::default();
+        let calm = wind.force_field();
+        for _ in 0..600 {
+            wind.update(0.05, &Weather::new(WeatherState::Storm));
+        }
+        let storm = wind.force_field();
+        assert!(storm.strength > calm.strength * 3.0);
+        let push = storm.at(Vec3::new(500.0, 20.0, -300.0));
+        assert!(push.normalize().dot(wind.velocity().normalize()) > 0.999);
+    }
+}
@@ -0,0 +1,432 @@
+//! Shareable character builds
+//!
+//! A build is a character's archetype, level, gear, skills and look, written
+//! out as JSON or packed into a short code for pasting to other players.
+//! Only item and skill ids travel with it: importing looks every one of them
+//! up in the item catalog and the archetype's starter kit, so a build can't
+//! bring in gear the game doesn't have. Builds from other versions of the
+//! game import as far as they can be understood.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use infinite_game::combat::equipment::{EquipmentSet, EquipmentSlot};
+use infinite_game::combat::item::Item;
+use infinite_game::combat::skill::{SkillId, SkillSlot};
+use infinite_game::combat::starter_items::{create_starter_items, create_starter_skills};
+use infinite_game::npc::combat::PlayerCombatState;
+use infinite_game::player::stats::{CharacterStats, PlayerProgression};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{Archetype, CharacterAppearance, CharacterData, Loadout, Sex};
+
+/// Format version written into new builds
+pub const BUILD_VERSION: u32 = 1;
+
+/// Highest level a build imports at
+pub const MAX_BUILD_LEVEL: u32 = 100;
+
+/// Start of every build code, telling a pasted code from JSON or a path
+const CODE_PREFIX: &str = "INFB:";
+
+/// zstd level for build codes; they're tiny, so squeeze them hard
+const CODE_COMPRESSION: i32 = 19;
+
+/// Longest character name the creator allows
+const MAX_NAME_LEN: usize = 24;
+
+/// Why a build couldn't be exported or imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// The text isn't a build code, build JSON or a readable build file
+    Unreadable(String),
+    /// The build's main archetype isn't one this game has
+    UnknownArchetype(String),
+    /// The build couldn't be packed into a code
+    Encode(String),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable(reason) => write!(f, "Not a character build: {}", reason),
+            Self::UnknownArchetype(key) => write!(f, "Unknown archetype '{}'", key),
+            Self::Encode(reason) => write!(f, "Couldn't create a build code: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// A piece of gear in a build, by slot and item id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildItem {
+    /// Name of the [`EquipmentSlot`], kept as text so unknown slots from
+    /// other versions can be skipped rather than failing the whole build
+    pub slot: String,
+    pub id: u64,
+    /// Shown when the item can't be found
+    #[serde(default)]
+    pub name: String,
+}
+
+/// A character build as it's shared
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterBuild {
+    /// Format version the build was written with
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub sex: Option<Sex>,
+    /// [`Archetype::key`] of the main class
+    pub archetype: String,
+    /// [`Archetype::key`] of the second class, when multiclassing
+    #[serde(default)]
+    pub secondary: Option<String>,
+    #[serde(default = "first_level")]
+    pub level: u32,
+    /// Stats at export, for show; imports work them out again from the
+    /// classes and level
+    #[serde(default, deserialize_with = "lenient")]
+    pub stats: Option<CharacterStats>,
+    #[serde(default)]
+    pub equipment: Vec<BuildItem>,
+    /// Id of the skill in each skill slot
+    #[serde(default)]
+    pub skills: Vec<Option<u64>>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub appearance: Option<CharacterAppearance>,
+}
+
+fn first_level() -> u32 {
+    1
+}
+
+/// Read a part of the build that may have changed shape between versions,
+/// leaving it out rather than failing when it doesn't fit
+fn lenient<'de, D: Deserializer<'de>, T: DeserializeOwned>(deserializer: D) -> Result<Option<T>, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// A build turned into a character ready to add to the roster or take into
+/// the arena
+#[derive(Debug, Clone)]
+pub struct ImportedBuild {
+    pub character: CharacterData,
+    /// What didn't make it across, for telling the player
+    pub notes: Vec<String>,
+}
+
+impl CharacterBuild {
+    /// The build of a character as it is now
+    pub fn capture(character: &CharacterData, combat: &PlayerCombatState) -> Self {
+        let class = &combat.progression.class;
+        let equipment = EquipmentSlot::all()
+            .iter()
+            .filter_map(|&slot| {
+                combat.equipment.get(slot).as_ref().map(|item| BuildItem {
+                    slot: format!("{:?}", slot),
+                    id: item.id.0,
+                    name: item.name.clone(),
+                })
+            })
+            .collect();
+        Self {
+            version: BUILD_VERSION,
+            name: character.name.clone(),
+            sex: Some(character.sex),
+            archetype: class
+                .primary
+                .clone()
+                .or_else(|| character.archetype.map(|a| a.key()))
+                .unwrap_or_default(),
+            secondary: class.secondary.clone(),
+            level: combat.progression.level,
+            stats: Some(combat.stats.clone()),
+            equipment,
+            skills: combat.skill_slots.iter().map(|slot| slot.skill.as_ref().map(|s| s.id().0)).collect(),
+            appearance: Some(character.appearance.clone()),
+        }
+    }
+
+    /// Pretty JSON, for build files
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Compact code for pasting: compressed JSON in URL-safe base64
+    pub fn to_code(&self) -> Result<String, BuildError> {
+        let json = serde_json::to_vec(self).map_err(|e| BuildError::Encode(e.to_string()))?;
+        let packed = zstd::encode_all(json.as_slice(), CODE_COMPRESSION).map_err(|e| BuildError::Encode(e.to_string()))?;
+        Ok(format!("{}{}", CODE_PREFIX, URL_SAFE_NO_PAD.encode(packed)))
+    }
+
+    /// Parse a build code or build JSON
+    pub fn parse(text: &str) -> Result<Self, BuildError> {
+        let text = text.trim();
+        if let Some(code) = text.strip_prefix(CODE_PREFIX) {
+            let packed = URL_SAFE_NO_PAD
+                .decode(code.trim())
+                .map_err(|_| BuildError::Unreadable("the code is damaged or incomplete".to_string()))?;
+            let json = zstd::decode_all(packed.as_slice())
+                .map_err(|_| BuildError::Unreadable("the code is damaged or incomplete".to_string()))?;
+            serde_json::from_slice(&json).map_err(|e| BuildError::Unreadable(e.to_string()))
+        } else {
+            serde_json::from_str(text).map_err(|e| BuildError::Unreadable(e.to_string()))
+        }
+    }
+
+    /// Read a build from pasted text: a code, JSON, or the path of a build file
+    pub fn read(text: &str) -> Result<Self, BuildError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(BuildError::Unreadable("nothing was entered".to_string()));
+        }
+        if text.starts_with(CODE_PREFIX) || text.starts_with('{') {
+            return Self::parse(text);
+        }
+        let contents = fs::read_to_string(Path::new(text))
+            .map_err(|e| BuildError::Unreadable(format!("couldn't read '{}': {}", text, e)))?;
+        Self::parse(&contents)
+    }
+
+    /// Turn the build into a new character. `catalog` is every item the
+    /// game knows of besides the starter kits; gear that isn't in it or the
+    /// archetype's kit, or that the level can't wear, is left behind.
+    pub fn import(&self, catalog: &[Item]) -> Result<ImportedBuild, BuildError> {
+        let archetype =
+            Archetype::from_key(&self.archetype).ok_or_else(|| BuildError::UnknownArchetype(self.archetype.clone()))?;
+        let mut notes = Vec::new();
+        if self.version > BUILD_VERSION {
+            notes.push("Made with a newer version of the game; anything this version doesn't know was skipped".to_string());
+        }
+
+        let secondary = match self.secondary.as_deref().map(|key| (key, Archetype::from_key(key))) {
+            Some((_, Some(other))) if other != archetype => Some(other),
+            Some((key, None)) => {
+                notes.push(format!("Unknown second archetype '{}' was dropped", key));
+                None
+            }
+            _ => None,
+        };
+
+        let level = self.level.clamp(1, MAX_BUILD_LEVEL);
+        if level != self.level {
+            notes.push(format!("Level {} was brought to {}", self.level, level));
+        }
+
+        let name: String = self.name.trim().chars().take(MAX_NAME_LEN).collect();
+        let name = if name.chars().count() < 2 { archetype.name().to_string() } else { name };
+
+        let sex = self.sex.unwrap_or_else(|| {
+            notes.push("The body type couldn't be read".to_string());
+            Sex::default()
+        });
+        let mut character = match &self.appearance {
+            Some(appearance) => CharacterData::with_appearance(name, sex, appearance.clone()),
+            None => {
+                notes.push("The look couldn't be read, so it's the default one".to_string());
+                CharacterData::new(name, sex)
+            }
+        };
+        character.set_archetype(archetype);
+
+        let (kit, weapon) =
+            create_starter_items(&archetype.key(), archetype.starting_weapon_type(), archetype.starting_element());
+        let mut known: Vec<&Item> = vec![&weapon];
+        known.extend(kit.iter());
+        known.extend(catalog.iter());
+
+        let mut fitted = EquipmentSet::new();
+        for entry in &self.equipment {
+            let label = if entry.name.is_empty() { format!("item #{}", entry.id) } else { entry.name.clone() };
+            let Some(slot) = EquipmentSlot::all().iter().copied().find(|s| format!("{:?}", s) == entry.slot) else {
+                notes.push(format!("{} was in an unknown slot '{}'", label, entry.slot));
+                continue;
+            };
+            let Some(item) = known.iter().find(|item| item.id.0 == entry.id) else {
+                notes.push(format!("{} isn't in the item catalog", label));
+                continue;
+            };
+            if item.required_level > level {
+                notes.push(format!("{} needs level {}", item.name, item.required_level));
+                continue;
+            }
+            if let Err(e) = fitted.equip(slot, (*item).clone()) {
+                notes.push(format!("{} doesn't fit the {:?} slot: {}", item.name, slot, e));
+            }
+        }
+        let equipment: Vec<(EquipmentSlot, Item)> = EquipmentSlot::all()
+            .iter()
+            .filter_map(|&slot| fitted.get(slot).clone().map(|item| (slot, item)))
+            .collect();
+
+        let mut skills = create_starter_skills(&archetype.key());
+        if !self.skills.is_empty() {
+            let mut learnable: Vec<SkillSlot> = skills.clone();
+            if let Some(other) = secondary {
+                learnable.extend(create_starter_skills(&other.key()));
+            }
+            let find = |id: u64| learnable.iter().find_map(|slot| slot.skill.clone().filter(|s| s.id() == SkillId(id)));
+            for (slot, id) in skills.iter_mut().zip(&self.skills) {
+                slot.skill = id.and_then(|id| {
+                    let skill = find(id);
+                    if skill.is_none() {
+                        notes.push(format!("Skill #{} isn't one this character can learn", id));
+                    }
+                    skill
+                });
+            }
+        }
+
+        character.snapshot.level = level;
+        character.snapshot.total_xp = PlayerProgression::xp_for_level(level);
+        character.snapshot.archetype = Some(archetype.key());
+        character.snapshot.secondary_archetype = secondary.map(|a| a.key());
+        character.snapshot.equipment = equipment.iter().map(|(_, item)| item.name.clone()).collect();
+        character.loadout = Some(Loadout { equipment, skills });
+        Ok(ImportedBuild { character, notes })
+    }
+}
+
+/// Get the builds directory path
+fn builds_dir() -> anyhow::Result<PathBuf> {
+    let dir = dirs::data_local_dir()
+        .context("Could not determine local data directory")?
+        .join("infinite")
+        .join("builds");
+    fs::create_dir_all(&dir).context("Failed to create builds directory")?;
+    Ok(dir)
+}
+
+/// Write a build to a JSON file in the builds directory
+pub fn export_build_file(build: &CharacterBuild) -> anyhow::Result<PathBuf> {
+    let safe_name: String = build
+        .name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+        .take(64)
+        .collect();
+    let stem = if safe_name.is_empty() { "build".to_string() } else { safe_name };
+    let path = builds_dir()?.join(format!("{}_level{}.json", stem, build.level));
+    fs::write(&path, build.to_json()).context("Failed to write build file")?;
+    tracing::info!("Exported build '{}' to {:?}", build.name, path);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use infinite_game::combat::item::ItemId;
+
+    /// A Vanguard as freshly made, wearing the starter kit
+    fn vanguard() -> (CharacterData, PlayerCombatState) {
+        let archetype = Archetype::Vanguard;
+        let mut character = CharacterData::new("Brann".to_string(), Sex::Male);
+        character.set_archetype(archetype);
+        let mut combat = PlayerCombatState::from_stats(archetype.base_stats());
+        combat.progression.class.primary = Some(archetype.key());
+        combat.progression.level = 5;
+        let (kit, weapon) =
+            create_starter_items(&archetype.key(), archetype.starting_weapon_type(), archetype.starting_element());
+        combat.equipment.equip(EquipmentSlot::MainHand, weapon).unwrap();
+        let armor = kit.into_iter().find(|item| item.id == ItemId(2000)).unwrap();
+        combat.equipment.equip(EquipmentSlot::Chest, armor).unwrap();
+        combat.skill_slots = create_starter_skills(&archetype.key());
+        (character, combat)
+    }
+
+    #[test]
+    fn test_build_code_round_trip() {
+        let (character, combat) = vanguard();
+        let build = CharacterBuild::capture(&character, &combat);
+        assert_eq!(build.equipment.len(), 2);
+
+        let code = build.to_code().unwrap();
+        assert!(code.starts_with(CODE_PREFIX));
+        assert!(code.len() < build.to_json().len());
+        let read = CharacterBuild::read(&code).unwrap();
+        assert_eq!(read.archetype, "Vanguard");
+        assert_eq!(read.level, 5);
+        assert_eq!(read.equipment, build.equipment);
+        assert_eq!(read.skills, build.skills);
+        assert!(CharacterBuild::read(&build.to_json()).is_ok());
+
+        let imported = read.import(&[]).unwrap();
+        assert!(imported.notes.is_empty(), "{:?}", imported.notes);
+        let loadout = imported.character.loadout.unwrap();
+        assert_eq!(loadout.equipment.len(), 2);
+        assert_eq!(imported.character.snapshot.level, 5);
+        assert_eq!(imported.character.archetype, Some(Archetype::Vanguard));
+        assert_ne!(imported.character.id, character.id);
+
+        assert!(matches!(CharacterBuild::read(&code[..code.len() / 2]), Err(BuildError::Unreadable(_))));
+        assert!(CharacterBuild::read("").is_err());
+    }
+
+    #[test]
+    fn test_import_checks_gear_against_catalog() {
+        let (character, combat) = vanguard();
+        let mut build = CharacterBuild::capture(&character, &combat);
+        let mut ring = combat.equipment.get(EquipmentSlot::Chest).clone().unwrap();
+        ring.id = ItemId(77);
+        ring.name = "Heavy Mantle".to_string();
+        ring.required_level = 10;
+        build.equipment.push(BuildItem { slot: "Shoulders".to_string(), id: 77, name: ring.name.clone() });
+        build.equipment.push(BuildItem { slot: "Cape".to_string(), id: 9_999_999, name: "Forged Cape".to_string() });
+        build.skills = vec![Some(4003), Some(4001)];
+
+        // Too low a level for the mantle, and the cape doesn't exist
+        let imported = build.import(std::slice::from_ref(&ring)).unwrap();
+        let loadout = imported.character.loadout.as_ref().unwrap();
+        assert_eq!(loadout.equipment.len(), 2);
+        assert_eq!(imported.notes.len(), 3, "{:?}", imported.notes);
+        assert!(loadout.skills[0].skill.is_some());
+        assert!(loadout.skills[1].skill.is_none(), "another archetype's skill");
+
+        build.level = 12;
+        build.secondary = Some("Chronomancer".to_string());
+        let imported = build.import(std::slice::from_ref(&ring)).unwrap();
+        let loadout = imported.character.loadout.as_ref().unwrap();
+        assert_eq!(loadout.equipment.len(), 3);
+        assert!(loadout.skills[1].skill.is_some(), "learned from the second class");
+        assert_eq!(imported.character.snapshot.total_xp, PlayerProgression::xp_for_level(12));
+    }
+
+    #[test]
+    fn test_import_tolerates_other_versions() {
+        let json = r#"{
+            "version": 7,
+            "name": "  Tessa  ",
+            "sex": "Nonexistent",
+            "archetype": "Technomage",
+            "secondary": "Bard",
+            "level": 500,
+            "stats": { "power_level": 9000 },
+            "equipment": [{ "slot": "Tail", "id": 2000 }],
+            "appearance": { "hologram": true },
+            "pets": ["owl"]
+        }"#;
+        let build = CharacterBuild::read(json).unwrap();
+        let imported = build.import(&[]).unwrap();
+        let character = &imported.character;
+        assert_eq!(character.name, "Tessa");
+        assert_eq!(character.snapshot.level, MAX_BUILD_LEVEL);
+        assert_eq!(character.snapshot.secondary_archetype, None);
+        assert!(character.loadout.as_ref().unwrap().equipment.is_empty());
+        assert!(character.loadout.as_ref().unwrap().skills[0].skill.is_some());
+        // Newer version, bad sex, unknown secondary, level, slot and look
+        assert_eq!(imported.notes.len(), 6, "{:?}", imported.notes);
+
+        let unknown = CharacterBuild::read(&json.replace("Technomage", "Bard")).unwrap();
+        assert_eq!(unknown.import(&[]).unwrap_err(), BuildError::UnknownArchetype("Bard".to_string()));
+    }
+}
@@ -2,12 +2,20 @@
 //!
 //! Defines all character customization options and save/load functionality.
 
+mod build;
 mod persistence;
+mod roster;
 
+pub use build::{export_build_file, CharacterBuild, ImportedBuild};
 pub use persistence::save_character;
+pub use roster::{CharacterRoster, RosterStatus, SyncState};
 
 use chrono::{DateTime, Utc};
+use infinite_integration::CharacterSnapshot;
 use infinite_game::combat::element::Element;
+use infinite_game::combat::equipment::EquipmentSlot;
+use infinite_game::combat::item::Item;
+use infinite_game::combat::skill::SkillSlot;
 use infinite_game::combat::weapon::WeaponType;
 use infinite_game::player::stats::{CharacterStats, StatGrowth};
 use serde::{Deserialize, Serialize};
@@ -46,6 +54,26 @@ pub struct CharacterData {
     pub created_at: DateTime<Utc>,
     /// Total play time in seconds
     pub play_time: u64,
+    /// Id of the character on PixygonServer, once uploaded
+    #[serde(default)]
+    pub server_id: Option<String>,
+    /// Whether the server has this character's latest state
+    #[serde(default)]
+    pub sync: SyncState,
+    /// Progress as of the last session
+    #[serde(default)]
+    pub snapshot: CharacterSnapshot,
+    /// Gear and skills an imported build starts with, in place of the
+    /// starter kit
+    #[serde(default)]
+    pub loadout: Option<Loadout>,
+}
+
+/// Equipment and skill slots brought in with a [`CharacterBuild`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Loadout {
+    pub equipment: Vec<(EquipmentSlot, Item)>,
+    pub skills: Vec<SkillSlot>,
 }
 
 impl CharacterData {
@@ -63,6 +91,13 @@ impl CharacterData {
             appearance,
             created_at: Utc::now(),
             play_time: 0,
+            server_id: None,
+            sync: SyncState::default(),
+            snapshot: CharacterSnapshot {
+                level: 1,
+                ..Default::default()
+            },
+            loadout: None,
         }
     }
 
@@ -76,16 +111,16 @@ impl CharacterData {
     }
 
     /// Set the character's archetype
-    #[allow(dead_code)]
     pub fn set_archetype(&mut self, archetype: Archetype) {
         self.archetype = Some(archetype);
     }
 }
 
 /// Character archetypes (classes)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Archetype {
     /// Master of time manipulation, bends reality itself
+    #[default]
     Chronomancer,
     /// Swift hunter from the timeline, tracks temporal anomalies
     TemporalHunter,
@@ -270,16 +305,28 @@ impl Archetype {
             },
         }
     }
-}
 
-impl Default for Archetype {
-    fn default() -> Self {
-        Self::Chronomancer
+    /// Name stored in saves and [`infinite_game::ClassSetup`]
+    pub fn key(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// Look up an archetype by its [`Archetype::key`]
+    pub fn from_key(key: &str) -> Option<Archetype> {
+        Self::all().iter().copied().find(|a| a.key() == key)
+    }
+
+    /// Per-level growth, blended with `secondary`'s when multiclassing
+    pub fn growth_with(&self, secondary: Option<Archetype>) -> StatGrowth {
+        match secondary {
+            Some(other) if other != *self => self.stat_growth().blend(&other.stat_growth()),
+            _ => self.stat_growth(),
+        }
     }
 }
 
 /// Complete character appearance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CharacterAppearance {
     /// Body shape and proportions
     pub body: BodyCustomization,
@@ -291,17 +338,6 @@ pub struct CharacterAppearance {
     pub skin: SkinCustomization,
 }
 
-impl Default for CharacterAppearance {
-    fn default() -> Self {
-        Self {
-            body: BodyCustomization::default(),
-            face: FaceCustomization::default(),
-            hair: HairCustomization::default(),
-            skin: SkinCustomization::default(),
-        }
-    }
-}
-
 impl CharacterAppearance {
     /// Default male appearance preset
     pub fn default_male() -> Self {
@@ -353,6 +389,104 @@ impl CharacterAppearance {
         self.hair.randomize(&mut rng);
         self.skin.randomize(&mut rng);
     }
+
+    /// A short plain-language description, e.g. "tall and slim, with dark
+    /// skin, long red hair and green eyes", for NPCs to remark on
+    pub fn describe(&self) -> String {
+        let mut build = Vec::new();
+        if self.body.height < 0.3 {
+            build.push("short");
+        } else if self.body.height > 0.7 {
+            build.push("tall");
+        }
+        if self.body.build < 0.3 {
+            build.push("slim");
+        } else if self.body.build > 0.7 {
+            build.push("heavy-set");
+        }
+        if self.skin.age > 0.7 {
+            build.push("elderly");
+        } else if self.skin.age < 0.15 {
+            build.push("youthful");
+        }
+
+        let skin = match self.skin.tone {
+            t if t < 0.2 => "pale",
+            t if t < 0.45 => "fair",
+            t if t < 0.7 => "tan",
+            t if t < 0.85 => "brown",
+            _ => "dark",
+        };
+        let mut features = vec![format!("{} skin", skin)];
+        features.push(if self.hair.length < 0.05 {
+            "a shaved head".to_string()
+        } else {
+            let length = match self.hair.length {
+                l if l < 0.35 => "short",
+                l if l < 0.65 => "shoulder-length",
+                _ => "long",
+            };
+            format!("{} {} hair", length, hair_color_name(&self.hair))
+        });
+        features.push(format!("{} eyes", hue_name(self.face.eye_color)));
+        if self.hair.facial_hair_style > 0 {
+            features.push(if self.hair.facial_hair_density > 0.5 { "a full beard" } else { "stubble" }.to_string());
+        }
+        if self.skin.tattoo_style > 0 {
+            features.push("tattoos".to_string());
+        }
+        if self.skin.face_paint_style > 0 {
+            features.push("face paint".to_string());
+        }
+
+        let build = if build.is_empty() { "of average height and build".to_string() } else { join_list(&build) };
+        format!("{}, with {}", build, join_list(&features))
+    }
+}
+
+/// "a, b and c"
+fn join_list<S: AsRef<str>>(parts: &[S]) -> String {
+    match parts {
+        [] => String::new(),
+        [only] => only.as_ref().to_string(),
+        [rest @ .., last] => {
+            let rest: Vec<&str> = rest.iter().map(AsRef::as_ref).collect();
+            format!("{} and {}", rest.join(", "), last.as_ref())
+        }
+    }
+}
+
+/// Colour word for a hue in 0-1
+fn hue_name(hue: f32) -> &'static str {
+    match hue.rem_euclid(1.0) {
+        h if h < 0.05 => "red",
+        h if h < 0.11 => "amber",
+        h if h < 0.18 => "hazel",
+        h if h < 0.45 => "green",
+        h if h < 0.7 => "blue",
+        h if h < 0.85 => "violet",
+        h if h < 0.95 => "pink",
+        _ => "red",
+    }
+}
+
+fn hair_color_name(hair: &HairCustomization) -> &'static str {
+    if hair.color_brightness < 0.15 {
+        return "black";
+    }
+    if hair.color_saturation < 0.15 {
+        return if hair.color_brightness > 0.7 { "white" } else { "grey" };
+    }
+    let hue = hair.color_hue.rem_euclid(1.0);
+    if !(0.17..0.95).contains(&hue) {
+        return match hair.color_brightness {
+            b if b < 0.4 => "brown",
+            _ if !(0.06..0.95).contains(&hue) => "red",
+            b if b < 0.6 => "light brown",
+            _ => "blonde",
+        };
+    }
+    hue_name(hue)
 }
 
 /// Body shape customization
@@ -677,6 +811,21 @@ impl SkinCustomization {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_archetype_keys_and_multiclass_growth() {
+        for archetype in Archetype::all() {
+            assert_eq!(Archetype::from_key(&archetype.key()), Some(*archetype));
+        }
+        assert_eq!(Archetype::from_key("Bard"), None);
+
+        let vanguard = Archetype::Vanguard;
+        assert_eq!(vanguard.growth_with(None), vanguard.stat_growth());
+        assert_eq!(vanguard.growth_with(Some(vanguard)), vanguard.stat_growth());
+        let blended = vanguard.growth_with(Some(Archetype::TemporalHunter));
+        assert!(blended.hp_per_level < vanguard.stat_growth().hp_per_level);
+        assert!(blended.attack_per_level > vanguard.stat_growth().attack_per_level * 0.5);
+    }
+
     #[test]
     fn test_character_creation() {
         let character = CharacterData::new("Test".to_string(), Sex::Male);
@@ -704,6 +853,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_describe_appearance() {
+        let mut appearance = CharacterAppearance::default();
+        assert_eq!(
+            appearance.describe(),
+            "of average height and build, with fair skin, short brown hair and blue eyes"
+        );
+
+        appearance.body.height = 0.9;
+        appearance.body.build = 0.1;
+        appearance.skin.tone = 0.95;
+        appearance.hair.length = 0.8;
+        appearance.hair.color_hue = 0.02;
+        appearance.hair.color_brightness = 0.5;
+        appearance.face.eye_color = 0.3;
+        appearance.hair.facial_hair_style = 2;
+        appearance.hair.facial_hair_density = 0.9;
+        appearance.skin.tattoo_style = 3;
+        assert_eq!(
+            appearance.describe(),
+            "tall and slim, with dark skin, long red hair, green eyes, a full beard and tattoos"
+        );
+    }
+
     #[test]
     fn test_appearance_randomize() {
         let mut appearance = CharacterAppearance::default();
@@ -20,10 +20,8 @@ fn characters_dir() -> Result<PathBuf> {
     Ok(data_dir)
 }
 
-/// Save a character to disk
-pub fn save_character(character: &CharacterData) -> Result<PathBuf> {
-    let dir = characters_dir()?;
-
+/// File name (without extension) a character is saved under
+pub(super) fn file_stem(character: &CharacterData) -> String {
     // Sanitize filename (remove invalid characters)
     let safe_name: String = character
         .name
@@ -32,13 +30,16 @@ pub fn save_character(character: &CharacterData) -> Result<PathBuf> {
         .take(64)
         .collect();
 
-    let filename = if safe_name.is_empty() {
-        character.id.clone()
-    } else {
-        format!("{}_{}", safe_name, &character.id[..8])
-    };
+    match character.id.get(..8) {
+        Some(short_id) if !safe_name.is_empty() => format!("{}_{}", safe_name, short_id),
+        _ => character.id.clone(),
+    }
+}
 
-    let path = dir.join(format!("{}.json", filename));
+/// Save a character to disk
+pub fn save_character(character: &CharacterData) -> Result<PathBuf> {
+    let dir = characters_dir()?;
+    let path = dir.join(format!("{}.json", file_stem(character)));
 
     let json = serde_json::to_string_pretty(character).context("Failed to serialize character")?;
 
@@ -80,7 +81,6 @@ pub fn load_character_by_id(id: &str) -> Result<CharacterData> {
 }
 
 /// List all saved characters
-#[allow(dead_code)]
 pub fn list_characters() -> Result<Vec<(String, CharacterData)>> {
     let dir = characters_dir()?;
 
@@ -90,7 +90,7 @@ pub fn list_characters() -> Result<Vec<(String, CharacterData)>> {
         let entry = entry.context("Failed to read directory entry")?;
         let path = entry.path();
 
-        if path.extension().map_or(false, |ext| ext == "json") {
+        if path.extension().is_some_and(|ext| ext == "json") {
             if let Ok(json) = fs::read_to_string(&path) {
                 if let Ok(character) = serde_json::from_str::<CharacterData>(&json) {
                     let filename = path
@@ -105,13 +105,12 @@ pub fn list_characters() -> Result<Vec<(String, CharacterData)>> {
     }
 
     // Sort by creation date (newest first)
-    characters.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
+    characters.sort_by_key(|c| std::cmp::Reverse(c.1.created_at));
 
     Ok(characters)
 }
 
 /// Delete a character by filename
-#[allow(dead_code)]
 pub fn delete_character(filename: &str) -> Result<()> {
     let dir = characters_dir()?;
     let path = dir.join(format!("{}.json", filename));
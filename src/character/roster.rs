@@ -0,0 +1,542 @@
+//! Character roster: the account's characters, kept on PixygonServer
+//!
+//! Characters are always saved locally first and uploaded when the server
+//! can be reached, so play works offline. A sync lists the server's
+//! characters and reconciles them with the local ones: the newer snapshot
+//! wins, characters made offline are uploaded, deletions made offline are
+//! sent, and characters made on another machine are downloaded.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use infinite_integration::{
+    CharacterSnapshot, CreateCharacterRequest, IntegrationClient, IntegrationError, PendingRequest, ServerCharacter,
+};
+use serde::{Deserialize, Serialize};
+
+use super::persistence;
+use super::{Archetype, CharacterAppearance, CharacterData, Sex};
+
+/// Whether the server has a character's latest state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyncState {
+    /// Changed locally since the server last saw it
+    #[default]
+    Pending,
+    /// Matches the server
+    Synced,
+    /// Deleted locally; the server copy still has to be removed
+    Deleted,
+}
+
+/// Where the roster stands with the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterStatus {
+    /// Not logged in, or the server couldn't be reached; changes wait
+    Offline,
+    Syncing,
+    Synced,
+}
+
+/// A change a sync has to make, decided by [`reconcile`]
+#[derive(Debug, Clone, PartialEq)]
+enum SyncOp {
+    /// Write the local character file
+    Save(String),
+    /// Remove the local character file
+    Remove { id: String, file: String },
+    /// Send the character to the server, creating it there if needed
+    Upload(String),
+    /// Delete the character from the server
+    DeleteRemote { id: String, server_id: String },
+}
+
+/// The parts of a character the server keeps as an opaque profile
+#[derive(Serialize, Deserialize)]
+struct CharacterProfile {
+    sex: Sex,
+    appearance: CharacterAppearance,
+    created_at: DateTime<Utc>,
+}
+
+enum Request {
+    List(PendingRequest<Vec<ServerCharacter>>),
+    Upload { id: String, sent_at: i64, request: PendingRequest<ServerCharacter> },
+    Delete { id: String, request: PendingRequest<serde_json::Value> },
+}
+
+/// The characters to choose from at the main menu
+pub struct CharacterRoster {
+    characters: Vec<CharacterData>,
+    requests: Vec<Request>,
+    status: RosterStatus,
+}
+
+impl CharacterRoster {
+    /// Load the characters saved on this machine
+    pub fn load() -> Self {
+        let characters = match persistence::list_characters() {
+            Ok(characters) => characters.into_iter().map(|(_, character)| character).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to list saved characters: {}", e);
+                Vec::new()
+            }
+        };
+        Self {
+            characters,
+            requests: Vec::new(),
+            status: RosterStatus::Offline,
+        }
+    }
+
+    /// Characters that haven't been deleted, newest first
+    pub fn characters(&self) -> impl Iterator<Item = &CharacterData> {
+        self.characters.iter().filter(|c| c.sync != SyncState::Deleted)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CharacterData> {
+        self.characters().find(|c| c.id == id)
+    }
+
+    pub fn status(&self) -> RosterStatus {
+        self.status
+    }
+
+    /// Changes (including deletions) the server hasn't seen yet
+    pub fn unsynced(&self) -> usize {
+        self.characters.iter().filter(|c| c.sync != SyncState::Synced).count()
+    }
+
+    /// Fetch the server's characters and reconcile with them
+    pub fn sync(&mut self, client: Option<&IntegrationClient>) {
+        if self.requests.iter().any(|r| matches!(r, Request::List(_))) {
+            return;
+        }
+        match client.filter(|c| c.is_authenticated()) {
+            Some(client) => {
+                self.requests.push(Request::List(client.list_characters()));
+                self.status = RosterStatus::Syncing;
+            }
+            None => self.status = RosterStatus::Offline,
+        }
+    }
+
+    /// Add a newly created character
+    pub fn add(&mut self, mut character: CharacterData, client: Option<&IntegrationClient>) {
+        character.sync = SyncState::Pending;
+        let id = character.id.clone();
+        self.characters.retain(|c| c.id != id);
+        self.characters.insert(0, character);
+        self.run(vec![SyncOp::Save(id.clone()), SyncOp::Upload(id)], client);
+    }
+
+    /// Record a finished session's progress
+    pub fn record_session(&mut self, id: &str, mut snapshot: CharacterSnapshot, client: Option<&IntegrationClient>) {
+        let Some(character) = self.characters.iter_mut().find(|c| c.id == id) else {
+            return;
+        };
+        snapshot.updated_at = Utc::now().timestamp().max(character.snapshot.updated_at + 1);
+        snapshot.profile = None;
+        character.play_time = snapshot.play_time_seconds;
+        character.archetype = snapshot.archetype.as_deref().and_then(Archetype::from_key).or(character.archetype);
+        character.snapshot = snapshot;
+        character.sync = SyncState::Pending;
+        self.run(vec![SyncOp::Save(id.to_string()), SyncOp::Upload(id.to_string())], client);
+    }
+
+    /// Delete a character here and, once it can be reached, on the server
+    pub fn delete(&mut self, id: &str, client: Option<&IntegrationClient>) {
+        let uploading = self.requests.iter().any(|r| matches!(r, Request::Upload { id: upload, .. } if upload == id));
+        let ops = mark_deleted(&mut self.characters, id, uploading);
+        self.run(ops, client);
+    }
+
+    /// Handle finished server requests
+    pub fn poll(&mut self, client: Option<&IntegrationClient>) {
+        if self.requests.is_empty() {
+            return;
+        }
+        let mut finished = Vec::new();
+        self.requests.retain(|request| {
+            let result = match request {
+                Request::List(pending) => pending.try_recv().map(Finished::List),
+                Request::Upload { id, sent_at, request } => {
+                    request.try_recv().map(|r| Finished::Upload(id.clone(), *sent_at, r.map(|remote| remote.id)))
+                }
+                Request::Delete { id, request } => request.try_recv().map(|r| Finished::Delete(id.clone(), r)),
+            };
+            match result {
+                Some(done) => {
+                    finished.push(done);
+                    false
+                }
+                None => true,
+            }
+        });
+
+        let mut offline = false;
+        for done in finished {
+            match done {
+                Finished::List(Ok(server)) => {
+                    let ops = reconcile(&mut self.characters, &server);
+                    self.run(ops, client);
+                }
+                Finished::Upload(id, sent_at, Ok(server_id)) => {
+                    let ops = uploaded(&mut self.characters, id, sent_at, server_id);
+                    self.run(ops, client);
+                }
+                Finished::Delete(id, Ok(_)) | Finished::Delete(id, Err(IntegrationError::ServerError { status: 404, .. })) => {
+                    if let Some(index) = self.characters.iter().position(|c| c.id == id) {
+                        let op = remove(&self.characters.remove(index));
+                        self.run(vec![op], client);
+                    }
+                }
+                Finished::List(Err(e)) | Finished::Upload(_, _, Err(e)) | Finished::Delete(_, Err(e)) => {
+                    tracing::warn!("Character sync failed, will retry later: {}", e);
+                    offline = true;
+                }
+            }
+        }
+
+        self.status = if offline {
+            RosterStatus::Offline
+        } else if self.requests.is_empty() {
+            RosterStatus::Synced
+        } else {
+            RosterStatus::Syncing
+        };
+    }
+
+    fn run(&mut self, ops: Vec<SyncOp>, client: Option<&IntegrationClient>) {
+        let client = client.filter(|c| c.is_authenticated());
+        for op in ops {
+            match op {
+                SyncOp::Save(id) => {
+                    if let Some(character) = self.characters.iter().find(|c| c.id == id) {
+                        if let Err(e) = persistence::save_character(character) {
+                            tracing::error!("Failed to save character '{}': {}", character.name, e);
+                        }
+                    }
+                }
+                SyncOp::Remove { file, .. } => {
+                    if let Err(e) = persistence::delete_character(&file) {
+                        tracing::error!("Failed to delete character file '{}': {}", file, e);
+                    }
+                }
+                SyncOp::Upload(id) => {
+                    let Some(client) = client else {
+                        self.status = RosterStatus::Offline;
+                        continue;
+                    };
+                    let Some(character) = self.characters.iter().find(|c| c.id == id) else {
+                        continue;
+                    };
+                    let snapshot = upload_snapshot(character);
+                    let sent_at = snapshot.updated_at;
+                    let request = match &character.server_id {
+                        Some(server_id) => client.update_character_snapshot(server_id, snapshot),
+                        None => client.create_character(CreateCharacterRequest {
+                            name: character.name.clone(),
+                            system_prompt: String::new(),
+                            lore: None,
+                            client_id: Some(character.id.clone()),
+                            snapshot: Some(snapshot),
+                        }),
+                    };
+                    self.requests.push(Request::Upload { id, sent_at, request });
+                }
+                SyncOp::DeleteRemote { id, server_id } => match client {
+                    Some(client) => {
+                        let request = client.delete_character(&server_id);
+                        self.requests.push(Request::Delete { id, request });
+                    }
+                    None => self.status = RosterStatus::Offline,
+                },
+            }
+        }
+    }
+}
+
+enum Finished {
+    List(Result<Vec<ServerCharacter>, IntegrationError>),
+    /// Character id, snapshot time sent, and the id the server gave it
+    Upload(String, i64, Result<String, IntegrationError>),
+    Delete(String, Result<serde_json::Value, IntegrationError>),
+}
+
+fn remove(character: &CharacterData) -> SyncOp {
+    SyncOp::Remove {
+        id: character.id.clone(),
+        file: persistence::file_stem(character),
+    }
+}
+
+/// Mark `id` deleted. A character the server has never seen is removed
+/// outright, unless its create is still in flight: then it stays as a
+/// tombstone until the server's id comes back to delete.
+fn mark_deleted(characters: &mut Vec<CharacterData>, id: &str, uploading: bool) -> Vec<SyncOp> {
+    let Some(character) = characters.iter_mut().find(|c| c.id == id) else {
+        return Vec::new();
+    };
+    match character.server_id.clone() {
+        Some(server_id) => {
+            character.sync = SyncState::Deleted;
+            vec![SyncOp::Save(id.to_string()), SyncOp::DeleteRemote { id: id.to_string(), server_id }]
+        }
+        None if uploading => {
+            character.sync = SyncState::Deleted;
+            vec![SyncOp::Save(id.to_string())]
+        }
+        None => {
+            let op = remove(character);
+            characters.retain(|c| c.id != id);
+            vec![op]
+        }
+    }
+}
+
+/// Record the server's id for an upload that went through. If the
+/// character was deleted while the upload was in flight, the server copy
+/// it just made is deleted too.
+fn uploaded(characters: &mut [CharacterData], id: String, sent_at: i64, server_id: String) -> Vec<SyncOp> {
+    let Some(character) = characters.iter_mut().find(|c| c.id == id) else {
+        return vec![SyncOp::DeleteRemote { id, server_id }];
+    };
+    character.server_id = Some(server_id.clone());
+    match character.sync {
+        SyncState::Deleted => vec![SyncOp::Save(id.clone()), SyncOp::DeleteRemote { id, server_id }],
+        // A later session may have been recorded while this was in flight
+        SyncState::Pending if sent_at >= character.snapshot.updated_at => {
+            character.sync = SyncState::Synced;
+            vec![SyncOp::Save(id)]
+        }
+        _ => vec![SyncOp::Save(id)],
+    }
+}
+
+/// A character's snapshot with its profile filled in, for the server
+fn upload_snapshot(character: &CharacterData) -> CharacterSnapshot {
+    let profile = CharacterProfile {
+        sex: character.sex,
+        appearance: character.appearance.clone(),
+        created_at: character.created_at,
+    };
+    CharacterSnapshot {
+        profile: serde_json::to_value(profile).ok(),
+        ..character.snapshot.clone()
+    }
+}
+
+/// A character made on another machine
+fn adopt(remote: &ServerCharacter, snapshot: &CharacterSnapshot) -> CharacterData {
+    let profile = snapshot.profile.clone().and_then(|p| serde_json::from_value::<CharacterProfile>(p).ok());
+    let mut character = match profile {
+        Some(profile) => CharacterData {
+            created_at: profile.created_at,
+            ..CharacterData::with_appearance(remote.name.clone(), profile.sex, profile.appearance)
+        },
+        None => CharacterData::new(remote.name.clone(), Sex::default()),
+    };
+    if let Some(id) = &remote.client_id {
+        character.id = id.clone();
+    }
+    character.server_id = Some(remote.id.clone());
+    take_snapshot(&mut character, snapshot);
+    character
+}
+
+/// Bring a character up to the server's newer snapshot
+fn take_snapshot(character: &mut CharacterData, snapshot: &CharacterSnapshot) {
+    character.snapshot = CharacterSnapshot {
+        profile: None,
+        ..snapshot.clone()
+    };
+    character.play_time = snapshot.play_time_seconds;
+    character.archetype = snapshot.archetype.as_deref().and_then(Archetype::from_key);
+    character.sync = SyncState::Synced;
+}
+
+/// Match local characters to the server's, updating `local` and returning
+/// what still has to be written or sent. Server characters without a
+/// snapshot are NPCs and are left alone.
+fn reconcile(local: &mut Vec<CharacterData>, server: &[ServerCharacter]) -> Vec<SyncOp> {
+    let players: Vec<(&ServerCharacter, &CharacterSnapshot)> =
+        server.iter().filter_map(|c| c.snapshot.as_ref().map(|s| (c, s))).collect();
+    let mut matched = HashSet::new();
+    let mut ops = Vec::new();
+
+    for character in local.iter_mut() {
+        let remote = players.iter().find(|(remote, _)| {
+            character.server_id.as_deref() == Some(remote.id.as_str())
+                || remote.client_id.as_deref() == Some(character.id.as_str())
+        });
+        let id = character.id.clone();
+        match remote {
+            Some((remote, snapshot)) => {
+                matched.insert(remote.id.as_str());
+                character.server_id = Some(remote.id.clone());
+                if character.sync == SyncState::Deleted {
+                    ops.push(SyncOp::DeleteRemote { id, server_id: remote.id.clone() });
+                } else if snapshot.updated_at > character.snapshot.updated_at {
+                    take_snapshot(character, snapshot);
+                    ops.push(SyncOp::Save(id));
+                } else if character.sync == SyncState::Pending {
+                    ops.push(SyncOp::Upload(id));
+                }
+            }
+            // Deleted on another machine
+            None if character.sync == SyncState::Synced && character.server_id.is_some() => {
+                ops.push(remove(character));
+            }
+            None if character.sync == SyncState::Deleted => ops.push(remove(character)),
+            // Never uploaded, or changed here after it was deleted elsewhere
+            None => {
+                character.server_id = None;
+                ops.push(SyncOp::Upload(id));
+            }
+        }
+    }
+    local.retain(|c| !ops.iter().any(|op| matches!(op, SyncOp::Remove { id, .. } if *id == c.id)));
+
+    for (remote, snapshot) in players {
+        if !matched.contains(remote.id.as_str()) {
+            let character = adopt(remote, snapshot);
+            ops.push(SyncOp::Save(character.id.clone()));
+            local.push(character);
+        }
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(id: &str, client_id: Option<&str>, level: u32, updated_at: i64) -> ServerCharacter {
+        ServerCharacter {
+            id: id.into(),
+            name: format!("Server {}", id),
+            system_prompt: String::new(),
+            lore: None,
+            appearance: None,
+            project_id: String::new(),
+            user_id: String::new(),
+            client_id: client_id.map(str::to_string),
+            snapshot: Some(CharacterSnapshot {
+                level,
+                updated_at,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn local(name: &str, server_id: Option<&str>, sync: SyncState, updated_at: i64) -> CharacterData {
+        let mut character = CharacterData::new(name.into(), Sex::Female);
+        character.server_id = server_id.map(str::to_string);
+        character.sync = sync;
+        character.snapshot.updated_at = updated_at;
+        character
+    }
+
+    #[test]
+    fn test_reconcile_uploads_offline_changes_and_takes_newer_server_state() {
+        let mut characters = vec![
+            local("Offline", None, SyncState::Pending, 10),
+            local("Stale", Some("s1"), SyncState::Synced, 10),
+            local("Played", Some("s2"), SyncState::Pending, 50),
+        ];
+        let offline_id = characters[0].id.clone();
+        let server = vec![remote("s1", None, 9, 40), remote("s2", None, 3, 20)];
+
+        let ops = reconcile(&mut characters, &server);
+        assert_eq!(ops, vec![
+            SyncOp::Upload(offline_id),
+            SyncOp::Save(characters[1].id.clone()),
+            SyncOp::Upload(characters[2].id.clone()),
+        ]);
+        assert_eq!(characters[1].snapshot.level, 9);
+        assert_eq!(characters[1].sync, SyncState::Synced);
+        assert_eq!(characters[2].snapshot.level, 1);
+    }
+
+    #[test]
+    fn test_reconcile_deletions_and_new_server_characters() {
+        let mut characters = vec![
+            local("Gone elsewhere", Some("s1"), SyncState::Synced, 10),
+            local("Deleted here", Some("s2"), SyncState::Deleted, 10),
+            local("Deleted offline", Some("s3"), SyncState::Deleted, 10),
+        ];
+        let deleted_here = characters[1].id.clone();
+        let removed: Vec<SyncOp> = [&characters[0], &characters[2]].into_iter().map(remove).collect();
+        let mut npc = remote("npc", None, 0, 0);
+        npc.snapshot = None;
+        let server = vec![remote("s2", None, 1, 10), remote("s4", Some("made-elsewhere"), 12, 30), npc];
+
+        let ops = reconcile(&mut characters, &server);
+        assert_eq!(ops, vec![
+            removed[0].clone(),
+            SyncOp::DeleteRemote { id: deleted_here, server_id: "s2".into() },
+            removed[1].clone(),
+            SyncOp::Save("made-elsewhere".into()),
+        ]);
+
+        let names: Vec<&str> = characters.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Deleted here", "Server s4"]);
+        assert_eq!(characters[1].snapshot.level, 12);
+        assert_eq!(characters[1].server_id.as_deref(), Some("s4"));
+    }
+
+    #[test]
+    fn test_reconcile_recognises_a_create_sent_twice() {
+        let mut characters = vec![local("Ayla", None, SyncState::Pending, 10)];
+        let id = characters[0].id.clone();
+        let server = vec![remote("s9", Some(&id), 1, 10)];
+
+        let ops = reconcile(&mut characters, &server);
+        assert_eq!(ops, vec![SyncOp::Upload(id)]);
+        assert_eq!(characters[0].server_id.as_deref(), Some("s9"));
+        assert_eq!(characters.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_during_create_deletes_the_server_copy() {
+        let mut characters = vec![local("Ayla", None, SyncState::Pending, 10)];
+        let id = characters[0].id.clone();
+
+        assert_eq!(mark_deleted(&mut characters, &id, true), vec![SyncOp::Save(id.clone())]);
+        assert_eq!(characters[0].sync, SyncState::Deleted);
+        let ops = uploaded(&mut characters, id.clone(), 10, "s1".into());
+        assert_eq!(ops, vec![SyncOp::Save(id.clone()), SyncOp::DeleteRemote { id: id.clone(), server_id: "s1".into() }]);
+        assert_eq!(characters[0].sync, SyncState::Deleted);
+
+        // A sync that lands first drops the tombstone; the upload still has to delete
+        let mut characters = vec![local("Ayla", None, SyncState::Deleted, 10)];
+        let id = characters[0].id.clone();
+        reconcile(&mut characters, &[]);
+        assert!(characters.is_empty());
+        let ops = uploaded(&mut characters, id.clone(), 10, "s1".into());
+        assert_eq!(ops, vec![SyncOp::DeleteRemote { id, server_id: "s1".into() }]);
+    }
+
+    #[test]
+    fn test_delete_of_a_never_uploaded_character_removes_it() {
+        let mut characters = vec![local("Ayla", None, SyncState::Pending, 10)];
+        let removed = remove(&characters[0]);
+        let id = characters[0].id.clone();
+        assert_eq!(mark_deleted(&mut characters, &id, false), vec![removed]);
+        assert!(characters.is_empty());
+    }
+
+    #[test]
+    fn test_profile_survives_the_server() {
+        let mut character = CharacterData::new("Ayla".into(), Sex::Female);
+        character.appearance.body.height = 1.1;
+        let snapshot = upload_snapshot(&character);
+        let adopted = adopt(&remote("s1", Some(&character.id), 1, 0), &snapshot);
+        assert_eq!(adopted.id, character.id);
+        assert_eq!(adopted.sex, Sex::Female);
+        assert_eq!(adopted.appearance.body.height, 1.1);
+        assert_eq!(adopted.created_at, character.created_at);
+        assert!(adopted.snapshot.profile.is_none());
+    }
+}
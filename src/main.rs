@@ -8,6 +8,7 @@ mod settings;
 mod state;
 mod ui;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -16,10 +17,9 @@ use egui_winit_vulkano::{Gui, GuiConfig};
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 use vulkano::{
-    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
     command_buffer::{
         allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
-        RenderPassBeginInfo, SubpassBeginInfo, SubpassContents, SubpassEndInfo,
+        RenderPassBeginInfo, SubpassBeginInfo, SubpassContents,
     },
     descriptor_set::allocator::StandardDescriptorSetAllocator,
     device::{
@@ -27,7 +27,7 @@ use vulkano::{
         QueueCreateInfo, QueueFlags,
     },
     format::Format,
-    image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
+    image::{view::ImageView, Image, ImageUsage},
     instance::{
         debug::{
             DebugUtilsMessageSeverity, DebugUtilsMessenger,
@@ -35,10 +35,10 @@ use vulkano::{
         },
         Instance, InstanceCreateFlags, InstanceCreateInfo,
     },
-    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    memory::allocator::StandardMemoryAllocator,
     pipeline::{
         graphics::{
-            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
             depth_stencil::{DepthState, DepthStencilState},
             input_assembly::InputAssemblyState,
             multisample::MultisampleState,
@@ -47,8 +47,10 @@ use vulkano::{
             viewport::{Viewport, ViewportState},
             GraphicsPipelineCreateInfo,
         },
+        cache::PipelineCache,
         layout::PipelineDescriptorSetLayoutCreateInfo,
-        DynamicState, GraphicsPipeline, Pipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
     },
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
     swapchain::{
@@ -59,65 +61,163 @@ use vulkano::{
 };
 use winit::{
     application::ApplicationHandler,
-    event::{DeviceEvent, ElementState, KeyEvent, WindowEvent},
+    event::{DeviceEvent, ElementState, KeyEvent, MouseButton, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
     keyboard::{Key, NamedKey, PhysicalKey, KeyCode},
     window::{CursorGrabMode, Window, WindowAttributes, WindowId},
 };
 
 use glam::{Mat4, Vec3};
-use infinite_core::{GameTime, Timeline, time::format_year};
+use infinite_core::{ErrorCode, ErrorDomain, GameTime, InfiniteError, Severity, Timeline, time::format_year};
+use infinite_game::balance;
 use infinite_game::{
-    AiDialogueManager, CameraController, GameContext, InputAction, InputHandler,
-    Interactable, InteractionResult, InteractionSystem, NpcId, PlayerController,
+    AiDialogueManager, ArenaConfig, ArenaEvent, CameraController, CombatLog, DummyHit, CompassMarker, CompassTracker, GameContext, InputAction, InputHandler,
+    MarkerCategory, MarkerId, PracticeArena, TrainingDummy,
+    Interactable, InteractionResult, InteractionSystem, NpcId, PlayerController, GLIDER_ITEM,
     RelationshipManager,
 };
 use infinite_game::npc::ai_dialogue::AiDialogueState;
+use infinite_game::npc::combat::ThreatSource;
+use infinite_game::npc::perception::{self, DetectionState, StealthInputs};
 use infinite_game::npc::character_cache::CharacterCacheEntry;
 use infinite_game::npc::combat::PlayerCombatState;
 use infinite_game::npc::dialogue::DialogueSystem;
 use infinite_game::npc::manager::NpcManager;
 use infinite_game::npc::relationship::RelationshipMessage;
+use infinite_audio::{AmbientConditions, AudioEngine, Biome, DayPeriod};
 use infinite_integration::IntegrationClient;
-use infinite_physics::PhysicsWorld;
-use infinite_render::{BasicPushConstants, Mesh, SkyMesh, SkyPushConstants, Vertex3D, SkyVertex};
+use infinite_physics::{ForceFieldId, PhysicsWorld, GRAPPLE_FLAG};
+use rand::RngCore;
+use infinite_render::{
+    BasicPushConstants, CloudLayer, DynamicResolution, FogUniforms, FrameUniforms, GpuMesh, GpuTimer, LightId, Mesh, MeshPool, MeshUploader, OcclusionBuffer,
+    PersistentPipelineCache, PointLight,
+    PointLightRegistry, PointLightUniforms, PortalPushConstants, ResolutionConfig, SceneTarget, SkyMesh, SkyPushConstants, Vertex3D,
+    SkyStyle, SkyUniforms, SkyVertex, UniformRing, MAX_POINT_LIGHTS, scaled_extent,
+};
 use infinite_world::{
-    ChunkConfig, ChunkCoord, ChunkManager, TimeTerrainConfig, Terrain, TerrainConfig, TimeOfDay,
-    Weather,
+    ChunkConfig, ChunkCoord, ChunkManager, DungeonConfig, DungeonEntrance, DungeonInstance, EraSky, Fog,
+    TimeTerrainConfig, Terrain, TerrainConfig, TimeOfDay, Weather, Wind,
 };
 
-use crate::character::CharacterData;
-use crate::save::{SaveData, PlayerSaveData, WorldSaveData};
-use crate::settings::GameSettings;
+use crate::character::{export_build_file, Archetype, CharacterAppearance, CharacterBuild, CharacterData, CharacterRoster, ImportedBuild};
+use crate::save::{AutosaveTrigger, Autosaver, BranchWorldState, SaveData, SaveSlot, SaveWorker, PlayerSaveData, ScheduledEvent, TimelineSaveData, WorldSaveData};
+use crate::settings::{GameSettings, HudWidget, MacroSettings, TimeTravelTransition};
 use crate::state::{ApplicationState, StateTransition};
-use crate::ui::{AdminPanel, CharacterCreator, InventoryAction, InventoryMenu, LoadingScreen, LoginMenu, MainMenu, PauseMenu, SaveLoadAction, SaveLoadMenu, SettingsMenu, ShopAction, ShopMenu, sell_price_for};
-use std::collections::HashMap;
+use crate::ui::{apply_layout, AdminPanel, AuditAction, AuditKind, AuditLog, BalancePanel, CharacterCreator, CombatStatsPanel, CompassHud, check_outcome, DamageNumberHud, dialogue_buttons, draw_barks, draw_crosshair, draw_letterbox, EntityInspector, ErrorDialog, ErrorDialogAction, GmAction, GmObject, GmSpawn, GmTools, HudEditor, ImportTarget, InspectTarget, InventoryAction, InventoryMenu, LoadingScreen, LoginMenu, MainMenu, MinimapHud, PauseMenu, PausePage, PauseSummary, relationship_details, response_button, RespecAction, RespecMenu, CookingAction, CookingMenu, BrewingAction, BrewingMenu, SaveLoadAction, SaveLoadMenu, ScreenMarkerHud, SettingsMenu, ShopAction, ShopMenu, ShrineAction, ShrineMenu, draw_rift_hud, render_boon_choice, RiftAction, RiftMenu, draw_speedrun_overlay, ScoreStatus, SurvivalAction, SurvivalMenu, draw_survival_hud, TemplateSpawner, TimelineAction, TimelineBrowser, buy_price_for, draw_world_boss_banner, market_sell_price};
+use std::collections::{HashMap, HashSet};
+
+/// Height of the grapple anchor posts in meters
+const GRAPPLE_POST_HEIGHT: f32 = 10.0;
+
+/// Rim and outline color of the focused interactable
+const FOCUS_HIGHLIGHT_COLOR: Vec3 = Vec3::new(1.0, 0.85, 0.35);
+
+/// Terrain cells merged into one occluder quad (32 subdivisions -> 8x8 quads per chunk)
+const OCCLUDER_BLOCK: u32 = 4;
+
+/// Chunks rebuilt per frame for a new era while the screen is faded out, and after
+const ERA_REBUILDS_BEHIND_FADE: usize = 4;
+const ERA_REBUILDS_PER_FRAME: usize = 1;
+
+/// Chunks around the player (Chebyshev radius) that must show the new era before the fade lifts
+const ERA_READY_RADIUS: u32 = 1;
+
+/// Seconds the terrain takes to morph into a new era, in steps of blended terrain
+const ERA_BLEND_DURATION: f32 = 6.0;
+const ERA_BLEND_STEPS: f32 = 12.0;
+/// Chunks rebuilt per frame while the player watches the terrain morph
+const ERA_BLEND_REBUILDS_PER_FRAME: usize = 8;
+
+/// Game speed and real seconds of the slow motion after a parry
+const PARRY_SLOW_MOTION: (f32, f32) = (0.3, 0.6);
+/// Game speed and real seconds of the hit stop when an enemy is staggered
+const STAGGER_HIT_STOP: (f32, f32) = (0.1, 0.08);
+
+/// How far around the player locals react to paradox
+const PARADOX_UNREST_RADIUS: f32 = 30.0;
+/// How far from the player temporal anomalies appear
+const PARADOX_ANOMALY_DISTANCE: f32 = 6.0;
+
+/// Chunk meshes that fit in one block of the chunk mesh pool (49 load at the default radius)
+const CHUNK_MESHES_PER_BLOCK: u64 = 64;
+
+/// Render loop failures (REN-1xx are mesh uploads)
+const RENDER_SWAPCHAIN_FAILED: ErrorCode = ErrorCode::new(ErrorDomain::Render, 1);
+const RENDER_ACQUIRE_FAILED: ErrorCode = ErrorCode::new(ErrorDomain::Render, 2);
+const RENDER_SUBMIT_FAILED: ErrorCode = ErrorCode::new(ErrorDomain::Render, 3);
+const RENDER_DEVICE_LOST: ErrorCode = ErrorCode::new(ErrorDomain::Render, 4);
+const RENDER_INIT_FAILED: ErrorCode = ErrorCode::new(ErrorDomain::Render, 5);
+/// Frames in a row that may fail to draw before the renderer is rebuilt
+const MAX_FAILED_FRAMES: u32 = 60;
+/// Frames of GPU timestamps in flight (results are read this many frames late)
+const GPU_TIMER_FRAMES: u32 = 4;
+/// Uniform data one frame may write (lights and sky today, materials and objects later)
+const UNIFORM_RING_FRAME_BYTES: u64 = 64 * 1024;
+/// Cloud texture units the sky's clouds drift per second at full wind
+const CLOUD_DRIFT: f32 = 0.02;
+
+/// Chunk load radius, NPC LOD range scale and chunk collider cache budget
+/// (MiB) at each memory downgrade level
+const MEMORY_DETAIL: [(u32, f32, usize); infinite_core::MAX_DOWNGRADE as usize + 1] =
+    [(3, 1.0, 32), (2, 0.75, 16), (2, 0.5, 4), (1, 0.35, 0)];
+
+/// Half extent of the NPC capsule mesh
+const NPC_HALF_EXTENT: Vec3 = Vec3::new(0.35, 0.8, 0.35);
+
+/// How far away the entity inspector can pick things
+const INSPECT_RANGE: f32 = 150.0;
+
+/// How far the crosshair reaches when GM tools place something
+const GM_SPAWN_RANGE: f32 = 60.0;
+
+/// Game balance values, hot-reloaded in debug builds
+const BALANCE_PATH: &str = "assets/balance.ron";
+/// Content packs, one folder per mod
+const MODS_PATH: &str = "mods";
+/// Ambient NPC lines, added to the built-in ones
+const BARKS_PATH: &str = "assets/barks.json";
+
+/// World flag namespace recording which bridges have collapsed, by id
+const BRIDGE_FLAGS: &str = "bridge";
+
+/// Caravans within this distance of the player walk the road as NPCs
+const CARAVAN_SPAWN_RANGE: f32 = 80.0;
+
+/// Bandits that spring on an escorted caravan
+const CARAVAN_BANDITS: usize = 3;
+
+/// Seconds between checks for resource nodes that have grown back
+const RESOURCE_REGROW_CHECK: f32 = 5.0;
+
+/// Scores shown on the survival leaderboard after a run
+const LEADERBOARD_SIZE: u32 = 10;
+
+/// Glow of the time rift's tear
+const RIFT_TINT: Vec3 = Vec3::new(1.0, 0.4, 0.25);
+
+/// Furthest (horizontally) the player can stray from a node while gathering
+const GATHER_REACH: f32 = 4.0;
+
+/// Quick-use keys of the consumable hotbar slots, in slot order
+const HOTBAR_ACTIONS: [InputAction; infinite_game::HOTBAR_SLOTS] =
+    [InputAction::Hotbar1, InputAction::Hotbar2, InputAction::Hotbar3, InputAction::Hotbar4];
 
 /// Mesh buffers for GPU rendering
-struct MeshBuffers {
-    vertex_buffer: Subbuffer<[Vertex3D]>,
-    index_buffer: Subbuffer<[u32]>,
-    index_count: u32,
-}
+type MeshBuffers = GpuMesh<Vertex3D>;
 
 /// Sky mesh buffers
-struct SkyMeshBuffers {
-    vertex_buffer: Subbuffer<[SkyVertex]>,
-    index_buffer: Subbuffer<[u32]>,
-    index_count: u32,
-}
-
-/// Floating damage number for combat feedback
-struct DamageNumber {
-    /// World position of the damage number
-    position: Vec3,
-    /// Damage amount to display
-    amount: f32,
-    /// Whether this was a critical hit
-    is_crit: bool,
-    /// Remaining display time
-    timer: f32,
-}
+type SkyMeshBuffers = GpuMesh<SkyVertex>;
+
+/// Swapchain and its images, the scene and UI render passes, the
+/// swapchain framebuffers and the offscreen scene target
+type SwapchainParts = (
+    Arc<Swapchain>,
+    Vec<Arc<Image>>,
+    Arc<RenderPass>,
+    Arc<RenderPass>,
+    Vec<Arc<Framebuffer>>,
+    SceneTarget,
+);
 
 /// Vulkan rendering context
 struct RenderContext {
@@ -125,31 +225,52 @@ struct RenderContext {
     queue: Arc<Queue>,
     swapchain: Arc<Swapchain>,
     images: Vec<Arc<Image>>,
+    /// Pass the 3D scene is drawn in, into the scene target
     render_pass: Arc<RenderPass>,
+    /// Pass the UI is drawn in, over the upscaled scene on the swapchain image
+    ui_render_pass: Arc<RenderPass>,
+    /// UI framebuffers, one per swapchain image
     framebuffers: Vec<Arc<Framebuffer>>,
     memory_allocator: Arc<StandardMemoryAllocator>,
     command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
-    _descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>,
+    /// Staging uploads into device-local mesh buffers
+    mesh_uploader: MeshUploader,
     recreate_swapchain: bool,
     previous_frame_end: Option<Box<dyn GpuFuture>>,
 
-    // Depth buffer
-    depth_buffer: Arc<ImageView>,
+    /// Per-frame uniform ring and the descriptor sets built from it
+    frame_uniforms: FrameUniforms,
+    /// Compiled pipelines, saved on shutdown for the next start
+    pipeline_cache: PersistentPipelineCache,
+    /// Offscreen colour and depth the scene is drawn into at the render scale
+    scene_target: SceneTarget,
+    /// Times frames on the GPU for dynamic resolution (None if the queue
+    /// can't write timestamps)
+    gpu_timer: Option<GpuTimer>,
 
     // 3D pipelines
     basic_pipeline: Option<Arc<GraphicsPipeline>>,
     sky_pipeline: Option<Arc<GraphicsPipeline>>,
     wireframe_pipeline: Option<Arc<GraphicsPipeline>>,
+    portal_pipeline: Option<Arc<GraphicsPipeline>>,
 
     // Mesh buffers
     capsule_mesh: Option<MeshBuffers>,
     terrain_mesh: Option<MeshBuffers>,
-    /// Per-chunk terrain meshes (keyed by ChunkCoord)
-    chunk_meshes: HashMap<ChunkCoord, MeshBuffers>,
+    /// Per-chunk terrain meshes (keyed by ChunkCoord), sub-allocated from shared buffers
+    chunk_meshes: MeshPool<ChunkCoord, Vertex3D>,
     /// Shared NPC capsule mesh (reused for all NPCs with per-NPC push constants)
     npc_capsule_mesh: Option<MeshBuffers>,
     sky_mesh: Option<SkyMeshBuffers>,
     debug_capsule_mesh: Option<MeshBuffers>,
+    /// Unit disc shared by all time portals
+    portal_mesh: Option<MeshBuffers>,
+    /// Unit plane scaled into the glider canopy
+    glider_mesh: Option<MeshBuffers>,
+    /// Unit line for the grappling rope and anchor posts
+    rope_mesh: Option<MeshBuffers>,
+    /// Unit cube scaled into dungeon floors and walls
+    box_mesh: Option<MeshBuffers>,
 }
 
 /// Application state
@@ -176,6 +297,8 @@ struct InfiniteApp {
     last_frame: Instant,
     /// Game settings
     settings: GameSettings,
+    /// Audio engine (None if no output device could be opened)
+    audio: Option<AudioEngine>,
     /// Loading screen UI
     loading_screen: LoadingScreen,
     /// Main menu UI
@@ -192,8 +315,26 @@ struct InfiniteApp {
     character_creator: CharacterCreator,
     /// Admin panel (created when needed, admin-only)
     admin_panel: Option<AdminPanel>,
+    /// Admin content changes this session (outlives the admin panel)
+    admin_audit: AuditLog,
+    /// In-game window for spawning NPC templates (admin-only)
+    template_spawner: TemplateSpawner,
+    /// Noclip, invisibility, teleport and spawn tools (admin-only)
+    gm_tools: GmTools,
+    /// Items GM tools dropped into the world, waiting to be picked up
+    gm_drops: Vec<infinite_game::Item>,
+    /// Set the player down on the ground once the chunks around a GM
+    /// teleport have streamed in
+    gm_landing: bool,
     /// Current character (when playing)
     current_character: Option<CharacterData>,
+    /// The account's characters, synced with the server when online
+    roster: CharacterRoster,
+    /// How the player looks in game; the character's appearance, or the
+    /// one restored from a save
+    player_appearance: CharacterAppearance,
+    /// Rebuild the player mesh from `player_appearance` next frame
+    player_mesh_dirty: bool,
     /// Simulated loading timer
     loading_timer: f32,
 
@@ -218,6 +359,137 @@ struct InfiniteApp {
     time_of_day: TimeOfDay,
     /// Weather system
     weather: Weather,
+    /// Global wind (vegetation sway, ambience)
+    wind: Wind,
+    /// The wind's push in the physics world, once registered
+    wind_field: Option<ForceFieldId>,
+    /// Dynamic point lights (torches, campfires, portals)
+    point_lights: PointLightRegistry,
+    /// Light following the player while a torch is held
+    torch_light: Option<LightId>,
+    /// Compass bar HUD
+    compass_hud: CompassHud,
+    minimap_hud: MinimapHud,
+    /// Drag-to-position HUD layout mode (F4)
+    hud_editor: HudEditor,
+    /// Objective and waypoint markers shown on the compass
+    compass_tracker: CompassTracker,
+    /// Player-placed waypoint (if any)
+    waypoint: Option<MarkerId>,
+    /// Damage, DPS and kill statistics
+    combat_log: CombatLog,
+    /// Enemy archetypes discovered by defeating them
+    bestiary: infinite_game::Bestiary,
+    /// Named places for the map and fast travel
+    locations: infinite_game::LocationRegistry,
+    /// Contextual onboarding prompts and their per-save progress
+    tutorials: infinite_game::TutorialManager,
+    /// Lifetime totals for the pause menu's statistics page
+    play_stats: infinite_game::PlayStatistics,
+    /// Door keys the player has picked up
+    key_ring: infinite_game::KeyRing,
+    /// Lock being picked, if any
+    lockpicking: Option<infinite_game::LockpickSession>,
+    /// Resource node being gathered from, if any
+    gathering: Option<infinite_game::GatherSession>,
+    /// Mining, woodcutting and herbalism progress
+    gathering_skills: infinite_game::GatheringSkills,
+    /// Counts down to the next check for regrown resource nodes
+    resource_regrow_timer: f32,
+    /// Recipes the player has cooked
+    recipe_book: infinite_game::RecipeBook,
+    /// Reagent effects and brews the player has found
+    alchemy_journal: infinite_game::AlchemyJournal,
+    /// When each shrine answers again
+    shrine_ledger: infinite_game::ShrineLedger,
+    /// Rift shards and attunements, kept between runs
+    rift_ledger: infinite_game::RiftLedger,
+    /// Announces world bosses and follows the fight with the current one
+    world_bosses: infinite_game::WorldBossTracker,
+    /// Hidden traps and hazard volumes in the loaded area
+    traps: infinite_game::TrapField,
+    /// Consumables bound to the 5-8 quick-use keys
+    hotbar: infinite_game::ConsumableHotbar,
+    /// Paradox built up by using gear from the future
+    paradox: infinite_game::ParadoxMeter,
+    /// Per-system random streams, seeded from the world seed
+    rng: infinite_core::RngService,
+    /// Holds the world still while a conversation is open
+    dialogue_freeze: Option<infinite_core::TimeScaleId>,
+    /// Slow motion from the last parry, while it lasts
+    parry_slow_motion: Option<infinite_core::TimeScaleId>,
+    /// Time scale pushed while fast-forwarding on a seat
+    rest_fast_forward: Option<infinite_core::TimeScaleId>,
+    /// Encumbrance tier last frame, to announce becoming overloaded
+    encumbrance: infinite_game::Encumbrance,
+    /// Global quest/dialogue/interaction flags
+    world_flags: infinite_game::WorldFlags,
+    /// Thrown bombs, smoke bombs and lures in flight, and lingering smoke
+    throwables: infinite_game::Throwables,
+    /// Hotbar slot held to aim a throw, and how long it has been held
+    throw_aim: Option<(usize, f32)>,
+    /// Predicted arc of the throw being aimed
+    throw_preview: Option<infinite_game::ArcPreview>,
+    /// Tutorial checklist panel (H when no prompt is up)
+    show_tutorial_checklist: bool,
+    /// Seconds spent walking, for completing the movement tutorial
+    tutorial_walk_time: f32,
+    /// Combat statistics panel (L)
+    combat_stats_panel: CombatStatsPanel,
+    /// Timeline branch browser (B)
+    timeline_browser: TimelineBrowser,
+    /// Fields of the NPC or object last clicked with the debug overlay up
+    inspector: EntityInspector,
+    /// Errors waiting for the player to read them
+    error_dialog: ErrorDialog,
+    /// Memory held per category, against the budget in the video settings
+    memory: infinite_core::MemoryTracker,
+    /// Every balance tunable, loaded from `BALANCE_PATH`
+    tunables: infinite_core::TunableRegistry,
+    /// Sliders for the tunables (from the debug overlay)
+    balance_panel: BalancePanel,
+    /// Frames in a row that failed to draw
+    failed_frames: u32,
+    /// The renderer is beyond recovery (device lost) and must be rebuilt
+    rebuild_renderer: bool,
+    /// World state left behind on the timeline branches the player isn't on
+    parked_branches: Vec<BranchWorldState>,
+    /// Branch to move onto at the next time transition's full black
+    pending_branch_switch: Option<infinite_core::BranchId>,
+    /// History was changed (forking the timeline) since arriving in this year
+    history_forked: bool,
+    /// Spawned training dummy and its damage readout
+    training_dummy: Option<TrainingDummy>,
+    /// Crosshair spread of the held ranged weapon
+    aim_spread: infinite_game::combat::AimSpread,
+    /// Aim assist is pulling onto a target this frame
+    aim_locked: bool,
+    /// Running practice arena (if any)
+    arena: Option<PracticeArena>,
+    /// Wave settings used when starting an arena
+    arena_config: ArenaConfig,
+    /// Dungeon interior the player is in (if any); chunk streaming pauses meanwhile
+    dungeon: Option<DungeonInstance>,
+    /// Bridges over the loaded chunks, by id
+    bridges: HashMap<u64, infinite_world::BridgeInstance>,
+    /// Settlement markets and the caravans trading between them
+    economy: infinite_game::Economy,
+    /// Mods loaded from `MODS_PATH`, in load order
+    mods: infinite_game::ModSet,
+    /// Their content merged, applied over base dialogue, items and skills
+    mod_content: infinite_game::ModContent,
+    /// NPCs walking with the caravans near the player, by caravan id
+    caravan_npcs: HashMap<u64, Vec<infinite_game::NpcId>>,
+    /// Market supplying the open shop
+    shop_market: Option<u64>,
+    /// Enemies spawned for the current dungeon
+    dungeon_enemies: Vec<NpcId>,
+    /// The dungeon's boss pack, a subset of `dungeon_enemies`
+    dungeon_bosses: Vec<NpcId>,
+    /// Treasure map seeds the dig spots were last synced for
+    dig_spot_seeds: Vec<u64>,
+    /// Dungeon placement and layout settings
+    dungeon_config: DungeonConfig,
     /// Interaction system
     interaction_system: InteractionSystem,
     /// NPC manager
@@ -226,6 +498,8 @@ struct InfiniteApp {
     dialogue_system: DialogueSystem,
     /// AI dialogue manager
     ai_dialogue: AiDialogueManager,
+    /// Ambient lines NPCs call out as the player passes
+    barks: infinite_game::BarkSystem,
     /// NPC relationship manager
     relationship_manager: RelationshipManager,
     /// PixygonServer integration client
@@ -234,6 +508,8 @@ struct InfiniteApp {
     player_combat: PlayerCombatState,
     /// Text input buffer for AI dialogue
     ai_dialogue_input: String,
+    /// Whether the conversation history window is open beside dialogue
+    dialogue_history_open: bool,
     /// Text overlay to show (from sign interactions)
     interaction_text: Option<String>,
     /// Timer for hiding interaction text
@@ -250,22 +526,25 @@ struct InfiniteApp {
     time_transitioning: bool,
     /// Source year for tinted transition
     time_transition_source: i64,
+    /// Progress (0.0 to 1.0) of a "watch the world change" era blend
+    era_blend_progress: Option<f32>,
 
-    // Climbing state
-    /// Whether the player is currently climbing a ladder
-    climbing: bool,
-    /// Direction of climbing (typically Vec3::Y)
-    climb_direction: Vec3,
-    /// Remaining distance to climb
-    climb_remaining: f32,
 
     // Collected items (pre-inventory)
     /// Items the player has collected
     collected_items: Vec<String>,
+    /// Anchor points the grappling hook snaps to
+    grapple_anchors: Vec<Vec3>,
     /// Total play time in seconds
     play_time: f64,
-    /// Auto-save countdown timer
-    auto_save_timer: f32,
+    /// Pending timed events (auto-save, ...)
+    scheduler: infinite_core::Scheduler<ScheduledEvent>,
+    /// Event-driven autosave requests waiting for the interval guard
+    autosaver: Autosaver,
+    /// Background thread doing save/load serialization and disk IO
+    save_worker: SaveWorker<InfiniteApp>,
+    /// Keeps the saving spinner up briefly after a quick save finishes
+    save_indicator_timer: f32,
 
     // Debug
     /// Whether the debug overlay is visible
@@ -274,10 +553,18 @@ struct InfiniteApp {
     debug_wireframe: bool,
     /// Show collider shapes
     debug_colliders: bool,
+    /// Skip drawing chunks, props and NPCs hidden behind terrain
+    occlusion_culling: bool,
+    /// Outline culled objects through the terrain
+    debug_show_culled: bool,
+    /// Render scale of the 3D scene, picked from GPU frame times
+    dynamic_resolution: DynamicResolution,
+    /// Software HiZ buffer rebuilt from the terrain every frame
+    occlusion: OcclusionBuffer,
 
     // Combat UI
     /// Floating damage numbers
-    damage_numbers: Vec<DamageNumber>,
+    damage_numbers: infinite_game::DamageNumbers,
     /// Level-up notification (level, timer)
     level_up_notification: Option<(u32, f32)>,
     /// Stat growth for current archetype (cached)
@@ -294,16 +581,111 @@ struct InfiniteApp {
     show_shop: bool,
     /// Shop menu state
     shop_menu: ShopMenu,
+    /// Archetype change menu, open while talking archetypes with a quest giver
+    respec_menu: Option<RespecMenu>,
+    /// Cooking menu, open while cooking at a campfire or stove
+    cooking_menu: Option<CookingMenu>,
+    /// Brewing menu, open while at an alchemy table
+    brewing_menu: Option<BrewingMenu>,
+    /// Prayer menu, open while at a shrine
+    shrine_menu: Option<ShrineMenu>,
+    /// Time rift gate menu, open while at the rift
+    rift_menu: Option<RiftMenu>,
+    /// Rift run in progress; its arenas are dungeon interiors
+    rift: Option<infinite_game::RiftRun>,
+    /// Gate the current rift run was entered from
+    rift_gate: Vec3,
+
+    // Input macros (accessibility)
+    /// Macro being recorded from the player's input
+    macro_recorder: Option<infinite_game::MacroRecorder>,
+    /// Recorded steps waiting for the player to press a key to bind them to
+    macro_pending: Option<Vec<infinite_game::MacroStep>>,
+    /// Macro playing back into the input
+    macro_player: Option<infinite_game::MacroPlayer>,
+
+    // Speedrun timing
+    /// Run being timed in this world, when speedruns are on
+    speedrun: Option<infinite_game::SpeedrunRun>,
+    /// Kills and boss defeats since the last frame, for the run to split on
+    speedrun_events: Vec<infinite_game::RunEvent>,
+    /// Attempts and personal bests for every seed
+    speedrun_records: infinite_game::SpeedrunRecords,
+
+    // Survival mode
+    /// Survival run in progress; the open world isn't streamed during one
+    survival: Option<infinite_game::SurvivalRun>,
+    /// The floor survival runs are fought on
+    survival_arena: Option<infinite_world::SurvivalArena>,
+    /// Arena shop between waves, and the results once the run is over
+    survival_menu: Option<SurvivalMenu>,
+    /// The finished run's score on its way to the leaderboard
+    pending_score: Option<infinite_integration::PendingRequest<infinite_integration::LeaderboardEntry>>,
+    /// The leaderboard, fetched once the score is in
+    pending_leaderboard: Option<infinite_integration::PendingRequest<Vec<infinite_integration::LeaderboardEntry>>>,
     /// Item catalog loaded from server
     item_catalog: Option<infinite_game::combat::ItemCatalog>,
     /// Pending catalog fetch request
     pending_catalog: Option<infinite_integration::PendingRequest<Vec<infinite_integration::types::ServerCharacterItem>>>,
+
+    // Voice lines
+    /// Pending text-to-speech audio for the current line
+    pending_tts: Option<infinite_integration::PendingRequest<Vec<u8>>>,
+    /// Caption for the line currently being spoken (speaker, text)
+    subtitle: Option<(String, String)>,
+    /// Minimum time left to show the subtitle
+    subtitle_timer: f32,
 }
 
 impl InfiniteApp {
     fn new(instance: Arc<Instance>) -> Self {
         let settings = GameSettings::load();
+        let mut error_dialog = ErrorDialog::new();
+        let memory = infinite_core::MemoryTracker::new(settings.video.memory_budget.clone());
+        let mut tunables = infinite_core::TunableRegistry::new();
+        balance::register_all(&mut tunables);
+        tunables.hot_reload = cfg!(debug_assertions);
+        match tunables.load_file(BALANCE_PATH) {
+            Ok(report) if !report.unknown.is_empty() => {
+                tracing::warn!("Unknown balance values in {}: {}", BALANCE_PATH, report.unknown.join(", "));
+            }
+            Ok(_) => {}
+            Err(e) => error_dialog.report(&InfiniteError::from(e).context("loading game balance")),
+        }
+        let (mods, mod_errors) = infinite_game::ModSet::scan(std::path::Path::new(MODS_PATH));
+        for e in mod_errors {
+            error_dialog.report(&InfiniteError::from(e).context("loading mods"));
+        }
+        if !mods.is_empty() {
+            let names: Vec<String> = mods.mods().iter()
+                .map(|m| format!("{} {}", m.manifest.display_name(), m.manifest.version))
+                .collect();
+            info!("Loaded {} mods: {}", names.len(), names.join(", "));
+        }
+        let mod_content = mods.content();
+        for conflict in &mod_content.overrides {
+            info!("Mod conflict: {}", conflict);
+        }
+        let mut dialogue_system = DialogueSystem::new();
+        for replaced in mod_content.apply_dialogue(&mut dialogue_system) {
+            info!("Mod override: {}", replaced);
+        }
+        let mut bark_library = infinite_game::BarkLibrary::builtin();
+        match std::fs::read_to_string(BARKS_PATH).map(|json| bark_library.load_json(&json)) {
+            Ok(Ok(count)) => info!("Loaded {} NPC barks from {}", count, BARKS_PATH),
+            Ok(Err(e)) => tracing::warn!("Failed to parse {}: {}", BARKS_PATH, e),
+            Err(e) => tracing::warn!("Failed to read {}: {}", BARKS_PATH, e),
+        }
+        let audio = match AudioEngine::new(settings.audio.to_audio_config()) {
+            Ok(engine) => Some(engine),
+            Err(e) => {
+                error_dialog.report(&InfiniteError::from(e).context("starting the audio engine"));
+                None
+            }
+        };
 
+        let mut rng = infinite_core::RngService::new(0);
+        let bark_seed = rng.stream(infinite_core::RngStream::Npc).next_u64();
         Self {
             instance,
             window: None,
@@ -316,6 +698,7 @@ impl InfiniteApp {
             timeline: Timeline::default(),
             last_frame: Instant::now(),
             settings,
+            audio,
             loading_screen: LoadingScreen::new(),
             main_menu: MainMenu::new(),
             pause_menu: PauseMenu::new(),
@@ -324,7 +707,15 @@ impl InfiniteApp {
             login_menu: LoginMenu::new(),
             character_creator: CharacterCreator::new(),
             admin_panel: None,
+            admin_audit: AuditLog::new(),
+            template_spawner: TemplateSpawner::new(),
+            gm_tools: GmTools::new(),
+            gm_drops: Vec::new(),
+            gm_landing: false,
             current_character: None,
+            roster: CharacterRoster::load(),
+            player_appearance: CharacterAppearance::default(),
+            player_mesh_dirty: true,
             loading_timer: 0.0,
             physics_world: None,
             player: None,
@@ -336,14 +727,82 @@ impl InfiniteApp {
             chunk_manager: None,
             time_of_day: TimeOfDay::default(),
             weather: Weather::default(),
+            wind: Wind::default(),
+            wind_field: None,
+            point_lights: PointLightRegistry::new(),
+            torch_light: None,
+            compass_hud: CompassHud::new(),
+            minimap_hud: MinimapHud::new(),
+            hud_editor: HudEditor::new(),
+            compass_tracker: CompassTracker::new(),
+            waypoint: None,
+            combat_log: CombatLog::new(),
+            bestiary: infinite_game::Bestiary::new(),
+            locations: infinite_game::LocationRegistry::new(),
+            tutorials: infinite_game::TutorialManager::default(),
+            play_stats: infinite_game::PlayStatistics::new(),
+            key_ring: infinite_game::KeyRing::new(),
+            lockpicking: None,
+            gathering: None,
+            gathering_skills: infinite_game::GatheringSkills::new(),
+            resource_regrow_timer: 0.0,
+            recipe_book: infinite_game::RecipeBook::new(),
+            alchemy_journal: infinite_game::AlchemyJournal::new(),
+            shrine_ledger: infinite_game::ShrineLedger::new(),
+            rift_ledger: infinite_game::RiftLedger::new(),
+            world_bosses: infinite_game::WorldBossTracker::new(0, 0.0),
+            traps: infinite_game::TrapField::new(),
+            hotbar: infinite_game::ConsumableHotbar::new(),
+            paradox: infinite_game::ParadoxMeter::new(),
+            rng,
+            dialogue_freeze: None,
+            parry_slow_motion: None,
+            rest_fast_forward: None,
+            encumbrance: infinite_game::Encumbrance::Unburdened,
+            world_flags: infinite_game::WorldFlags::new(),
+            throwables: infinite_game::Throwables::new(),
+            throw_aim: None,
+            throw_preview: None,
+            show_tutorial_checklist: false,
+            tutorial_walk_time: 0.0,
+            combat_stats_panel: CombatStatsPanel::new(),
+            timeline_browser: TimelineBrowser::new(),
+            inspector: EntityInspector::new(),
+            tunables,
+            balance_panel: BalancePanel::new(),
+            error_dialog,
+            memory,
+            failed_frames: 0,
+            rebuild_renderer: false,
+            parked_branches: Vec::new(),
+            pending_branch_switch: None,
+            history_forked: false,
+            training_dummy: None,
+            aim_spread: infinite_game::combat::AimSpread::new(),
+            aim_locked: false,
+            arena: None,
+            arena_config: ArenaConfig::default(),
+            dungeon: None,
+            bridges: HashMap::new(),
+            economy: infinite_game::Economy::new(),
+            mods,
+            mod_content,
+            caravan_npcs: HashMap::new(),
+            shop_market: None,
+            dungeon_enemies: Vec::new(),
+            dungeon_bosses: Vec::new(),
+            dig_spot_seeds: Vec::new(),
+            dungeon_config: DungeonConfig::default(),
             interaction_system: InteractionSystem::new(),
             npc_manager: None,
-            dialogue_system: DialogueSystem::new(),
+            dialogue_system,
             ai_dialogue: AiDialogueManager::new(),
+            barks: infinite_game::BarkSystem::new(bark_library, bark_seed),
             relationship_manager: RelationshipManager::new(),
             integration_client: IntegrationClient::new().ok(),
             player_combat: PlayerCombatState::new(),
             ai_dialogue_input: String::new(),
+            dialogue_history_open: false,
             interaction_text: None,
             interaction_text_timer: 0.0,
             notification_text: None,
@@ -352,20 +811,26 @@ impl InfiniteApp {
             pending_time_transition: None,
             time_transitioning: false,
             time_transition_source: 2025,
+            era_blend_progress: None,
 
-            climbing: false,
-            climb_direction: Vec3::ZERO,
-            climb_remaining: 0.0,
 
             collected_items: Vec::new(),
+            grapple_anchors: Vec::new(),
             play_time: 0.0,
-            auto_save_timer: 300.0,
+            scheduler: Self::initial_scheduler(),
+            autosaver: Autosaver::new(),
+            save_worker: SaveWorker::new(),
+            save_indicator_timer: 0.0,
 
             debug_visible: false,
             debug_wireframe: false,
             debug_colliders: false,
+            occlusion_culling: true,
+            debug_show_culled: false,
+            dynamic_resolution: DynamicResolution::new(ResolutionConfig::default()),
+            occlusion: OcclusionBuffer::default(),
 
-            damage_numbers: Vec::new(),
+            damage_numbers: infinite_game::DamageNumbers::new(),
             level_up_notification: None,
             archetype_growth: None,
 
@@ -374,12 +839,76 @@ impl InfiniteApp {
 
             show_shop: false,
             shop_menu: ShopMenu::new(),
+            respec_menu: None,
+            cooking_menu: None,
+            brewing_menu: None,
+            shrine_menu: None,
+            rift_menu: None,
+            rift: None,
+            rift_gate: Vec3::ZERO,
+            macro_recorder: None,
+            macro_pending: None,
+            macro_player: None,
+            speedrun: None,
+            speedrun_events: Vec::new(),
+            speedrun_records: save::load_speedrun_records(),
+            survival: None,
+            survival_arena: None,
+            survival_menu: None,
+            pending_score: None,
+            pending_leaderboard: None,
             item_catalog: None,
             pending_catalog: None,
+            pending_tts: None,
+            subtitle: None,
+            subtitle_timer: 0.0,
         }
     }
 
     /// Initialize game systems when entering Playing state
+    /// Pick up a roster character where its last session left off
+    fn resume_character_progress(&mut self) {
+        let Some(character) = &self.current_character else {
+            return;
+        };
+        let snapshot = character.snapshot.clone();
+        let archetype = character.archetype;
+        let combat = &mut self.player_combat;
+        combat.progression.level = snapshot.level.max(1);
+        combat.progression.total_xp = snapshot.total_xp;
+        combat.gold = snapshot.gold;
+        if snapshot.archetype.is_some() {
+            combat.progression.class.primary = snapshot.archetype.clone();
+            combat.progression.class.secondary = snapshot.secondary_archetype.clone();
+        }
+        self.play_time = snapshot.play_time_seconds as f64;
+        self.refresh_class_growth();
+        if let (Some(archetype), Some(growth)) = (archetype, &self.archetype_growth) {
+            self.player_combat.stats = archetype.base_stats().at_level(growth, self.player_combat.progression.level);
+        }
+    }
+
+    /// Write the current character's progress to the roster, which uploads
+    /// it when the server can be reached
+    fn record_character_session(&mut self) {
+        let Some(character) = &self.current_character else {
+            return;
+        };
+        let progression = &self.player_combat.progression;
+        let snapshot = infinite_integration::CharacterSnapshot {
+            level: progression.level,
+            total_xp: progression.total_xp,
+            gold: self.player_combat.gold,
+            archetype: progression.class.primary.clone(),
+            secondary_archetype: progression.class.secondary.clone(),
+            equipment: self.player_combat.equipment.equipped().map(|item| item.name.clone()).collect(),
+            play_time_seconds: self.play_time as u64,
+            profile: None,
+            updated_at: 0,
+        };
+        self.roster.record_session(&character.id, snapshot, self.integration_client.as_ref());
+    }
+
     fn init_game_systems(&mut self) {
         // Create physics world
         let mut physics = PhysicsWorld::new();
@@ -388,11 +917,12 @@ impl InfiniteApp {
         physics.create_ground(-50.0);
 
         // Set up chunk manager for streaming terrain
+        let (load_radius, npc_lod_scale, collider_budget) = MEMORY_DETAIL[self.memory.level() as usize];
         let chunk_config = ChunkConfig {
             chunk_size: 64.0,
             subdivisions: 32,
-            load_radius: 3,
-            unload_radius: 4,
+            load_radius,
+            unload_radius: load_radius + 1,
         };
         let terrain_config = TerrainConfig {
             size: 64.0, // matches chunk_size
@@ -404,6 +934,21 @@ impl InfiniteApp {
         };
 
         let mut chunk_manager = ChunkManager::new(chunk_config.clone(), terrain_config.clone());
+        chunk_manager.set_collider_budget(collider_budget * 1024 * 1024);
+        // Settlements and the roads between them are carved into the terrain
+        let roads = infinite_world::RoadNetwork::new(
+            infinite_world::RoadConfig::default(),
+            terrain_config.seed,
+            chunk_config.chunk_size,
+        );
+        chunk_manager.set_roads(Some(roads.clone()));
+        // Changes the player makes to the world are kept beside the saves
+        match save::world_dir() {
+            Ok(dir) => {
+                let _ = chunk_manager.set_store(infinite_world::ChunkStore::open(dir, self.timeline.active_year));
+            }
+            Err(e) => tracing::warn!("World changes won't persist: {}", e),
+        }
 
         // Apply time-period terrain config if not in the present year
         if !self.timeline.is_present() {
@@ -433,27 +978,17 @@ impl InfiniteApp {
 
         // Create chunk terrain meshes for initially loaded chunks
         if let Some(render_ctx) = &mut self.render_ctx {
-            for chunk in chunk_manager.loaded_chunks() {
-                let terrain = &chunk.terrain;
-                let terrain_mesh_data = Mesh::terrain(
-                    terrain.config.size,
-                    terrain.config.subdivisions,
-                    &terrain.heights,
-                    |x, h, z| terrain.color_at(x, h, z),
-                );
-
-                if let Ok(buffers) = create_mesh_buffers(
-                    render_ctx.memory_allocator.clone(),
-                    &terrain_mesh_data.vertices,
-                    &terrain_mesh_data.indices,
-                ) {
-                    render_ctx.chunk_meshes.insert(chunk.coord, buffers);
-                }
+            if let Err(e) = upload_chunk_meshes(render_ctx, chunk_manager.loaded_chunks()) {
+                self.error_dialog.report(&e);
             }
         }
 
         // Create NPC manager and spawn NPCs for initial chunks
-        let mut npc_manager = NpcManager::new(chunk_config.chunk_size);
+        let mut npc_manager = NpcManager::new(chunk_config.chunk_size)
+            .with_world_seed(chunk_manager.terrain_config.seed as u64)
+            .with_roads(roads)
+            .with_population(self.settings.gameplay.population);
+        npc_manager.lod_config = infinite_game::LodConfig::default().scaled(npc_lod_scale);
         let active_year = self.timeline.active_year;
         for chunk in chunk_manager.loaded_chunks() {
             let coord = chunk.coord;
@@ -462,64 +997,21 @@ impl InfiniteApp {
         }
         self.npc_manager = Some(npc_manager);
 
-        // Create NPC capsule mesh (smaller than player)
         if let Some(render_ctx) = &mut self.render_ctx {
-            if render_ctx.npc_capsule_mesh.is_none() {
-                let npc_mesh_data = Mesh::capsule(1.6, 0.35, 12, 8, [1.0, 1.0, 1.0, 1.0]);
-                if let Ok(buffers) = create_mesh_buffers(
-                    render_ctx.memory_allocator.clone(),
-                    &npc_mesh_data.vertices,
-                    &npc_mesh_data.indices,
-                ) {
-                    render_ctx.npc_capsule_mesh = Some(buffers);
-                }
-            }
+            upload_npc_mesh(render_ctx);
         }
 
         self.chunk_manager = Some(chunk_manager);
 
         // Reset dialogue, AI, and combat state
         self.dialogue_system = DialogueSystem::new();
+        self.mod_content.apply_dialogue(&mut self.dialogue_system);
         self.ai_dialogue = AiDialogueManager::new();
         self.ai_dialogue_input = String::new();
 
-        // Initialize player combat stats from archetype
-        if let Some(character) = &self.current_character {
-            if let Some(archetype) = character.archetype {
-                let stats = archetype.base_stats();
-                self.player_combat = infinite_game::npc::combat::PlayerCombatState::from_stats(stats);
-                self.archetype_growth = Some(archetype.stat_growth());
-
-                // Create starter items for this archetype
-                let (inv_items, main_weapon) = infinite_game::combat::starter_items::create_starter_items(
-                    &format!("{:?}", archetype),
-                    archetype.starting_weapon_type(),
-                    archetype.starting_element(),
-                );
-                let _ = self.player_combat.equipment.equip(
-                    infinite_game::combat::equipment::EquipmentSlot::MainHand,
-                    main_weapon,
-                );
-                for item in inv_items {
-                    let _ = self.player_combat.inventory.add_item(item);
-                }
-
-                // Create starter skills for this archetype
-                self.player_combat.skill_slots = infinite_game::combat::starter_items::create_starter_skills(
-                    &format!("{:?}", archetype),
-                );
-            } else {
-                self.player_combat = PlayerCombatState::new();
-                self.archetype_growth = None;
-            }
-        } else {
-            self.player_combat = PlayerCombatState::new();
-            self.archetype_growth = None;
-        }
-
-        // Reset combat UI
-        self.damage_numbers.clear();
-        self.level_up_notification = None;
+        self.init_player_combat();
+        self.reset_play_state(terrain_config.seed as u64);
+        self.start_speedrun(terrain_config.seed as u64);
 
         // Create player - spawn above terrain
         let mut player = PlayerController::new();
@@ -557,6 +1049,19 @@ impl InfiniteApp {
             "Far Future (3500 CE)",
         ));
 
+        // Point lights: portal glows and a test campfire
+        self.point_lights.clear();
+        self.torch_light = None;
+        let present_year = self.timeline.present_year;
+        let portals: Vec<(Vec3, i64)> = self.interaction_system.time_portals().collect();
+        for (position, target_year) in portals {
+            self.point_lights.add(PointLight::portal(
+                position + Vec3::new(0.0, 0.5, 0.0),
+                portal_tint(target_year, present_year),
+            ));
+        }
+        self.point_lights.add(PointLight::campfire(Vec3::new(-4.0, spawn_height + 0.5, 6.0)));
+
         // Stateful interactables for testing
         let locked_door_id = self.interaction_system.add_door(
             Vec3::new(10.0, spawn_height + 1.0, -5.0),
@@ -570,21 +1075,221 @@ impl InfiniteApp {
             Vec3::new(8.0, spawn_height + 1.0, -5.0),
             vec![locked_door_id],
         );
+        // Pickable doors: a simple lock, and a masterwork one with its key nearby
+        self.interaction_system.add_locked_door(
+            Vec3::new(-10.0, spawn_height + 1.0, -5.0),
+            infinite_game::LockTier::Simple,
+        );
+        let vault_door_id = self.interaction_system.add_locked_door(
+            Vec3::new(-15.0, spawn_height + 1.0, -5.0),
+            infinite_game::LockTier::Masterwork,
+        );
+        self.interaction_system.add(Interactable::key(
+            Vec3::new(-2.0, spawn_height + 0.5, -8.0),
+            vault_door_id,
+            "Vault Key",
+        ));
         self.interaction_system.add_container(
             Vec3::new(0.0, spawn_height + 0.5, -8.0),
             vec!["Ancient Coin".to_string(), "Health Potion".to_string()],
         );
-        self.interaction_system.add_ladder(
-            Vec3::new(-8.0, spawn_height + 0.5, 0.0),
-            6.0,
-            Vec3::Y,
-        );
+        self.place_overworld_traps();
+        self.place_seats();
+        self.place_crafting_stations();
+        // Ladder: a thin climbable panel players free-climb like any other wall
+        let ladder_pos = Vec3::new(-8.0, spawn_height + 0.5, 0.0);
+        let ladder_height = 6.0;
+        self.interaction_system.add_ladder(ladder_pos, ladder_height, Vec3::Y);
+        if let Some(physics) = &mut self.physics_world {
+            let ladder = physics.create_static_box(
+                Vec3::new(0.5, ladder_height * 0.5, 0.1),
+                ladder_pos + Vec3::Y * (ladder_height * 0.5 - 0.5),
+            );
+            physics.set_climbable(ladder, true);
+        }
+
+        // Grapple post: hook onto the top or anywhere along its tagged surface
+        let post_pos = Vec3::new(14.0, spawn_height, -20.0);
+        self.grapple_anchors = vec![post_pos + Vec3::Y * GRAPPLE_POST_HEIGHT];
+        if let Some(physics) = &mut self.physics_world {
+            let post = physics.create_static_box(
+                Vec3::new(0.35, GRAPPLE_POST_HEIGHT * 0.5, 0.35),
+                post_pos + Vec3::Y * (GRAPPLE_POST_HEIGHT * 0.5),
+            );
+            physics.set_surface_flag(post, GRAPPLE_FLAG, true);
+        }
+
+        // Glider on the hill behind spawn, until it has been collected
+        if !self.collected_items.iter().any(|i| i == GLIDER_ITEM) {
+            self.interaction_system.add(Interactable::pickup(
+                Vec3::new(6.0, spawn_height + 1.0, -14.0),
+                GLIDER_ITEM,
+            ));
+        }
+
+        // Training grounds: dummy post and practice arena gate
+        self.interaction_system.add(Interactable::training_dummy(
+            Vec3::new(-12.0, spawn_height + 1.0, 10.0),
+        ));
+        self.interaction_system.add(Interactable::practice_arena(
+            Vec3::new(-20.0, spawn_height + 1.0, 18.0),
+        ));
+        // The time rift, past the training grounds
+        self.interaction_system.add(Interactable::time_rift(Vec3::new(-30.0, spawn_height + 1.0, 26.0)));
+        self.point_lights.add(PointLight::portal(
+            Vec3::new(-30.0, spawn_height + 1.5, 26.0),
+            RIFT_TINT,
+        ));
+
+        // Dungeon entrances, bridges, resource nodes and shrines in the chunks around spawn
+        self.sync_dungeon_entrances();
+        self.sync_bridges();
+        self.sync_resource_nodes();
+        self.sync_shrines();
 
         info!("Game systems initialized with chunk-based terrain");
     }
 
+    /// Set up a survival run: the arena floor in place of the streamed world,
+    /// with the character's archetype starting over from its starter kit
+    fn init_survival_systems(&mut self) {
+        let mut physics = PhysicsWorld::new();
+        physics.create_ground(-50.0);
+        let arena = infinite_world::SurvivalArena::build(Vec3::ZERO, &mut physics);
+
+        // No chunks to populate: the only NPCs are the waves
+        self.npc_manager = Some(NpcManager::new(64.0));
+        if let Some(render_ctx) = &mut self.render_ctx {
+            upload_npc_mesh(render_ctx);
+        }
+
+        self.dialogue_system = DialogueSystem::new();
+        self.ai_dialogue = AiDialogueManager::new();
+        self.ai_dialogue_input = String::new();
+        self.init_player_combat();
+        let seed = self.rng.stream(infinite_core::RngStream::Npc).next_u64();
+        self.reset_play_state(seed);
+
+        let mut player = PlayerController::new();
+        player.spawn(&mut physics, arena.spawn() + Vec3::Y * 2.0);
+        physics.update_query_pipeline();
+
+        self.physics_world = Some(physics);
+        self.player = Some(player);
+        self.camera = Some(CameraController::new());
+        self.interaction_system.clear();
+        self.point_lights.clear();
+        self.torch_light = None;
+
+        self.survival = Some(infinite_game::SurvivalRun::new(arena.center));
+        self.survival_arena = Some(arena);
+        self.survival_menu = Some(SurvivalMenu::new());
+        self.notification_text = Some("Survive as long as you can!".to_string());
+        self.notification_timer = 3.0;
+
+        info!("Survival arena initialized");
+    }
+
+    /// Give the player the current character's archetype stats, starter
+    /// items and skills
+    fn init_player_combat(&mut self) {
+        if let Some(character) = &self.current_character {
+            if let Some(archetype) = character.archetype {
+                let stats = archetype.base_stats();
+                self.player_combat = infinite_game::npc::combat::PlayerCombatState::from_stats(stats);
+                self.player_combat.progression.class.primary = Some(archetype.key());
+                self.archetype_growth = Some(archetype.stat_growth());
+
+                // Create starter items for this archetype
+                let (inv_items, main_weapon) = infinite_game::combat::starter_items::create_starter_items(
+                    &format!("{:?}", archetype),
+                    archetype.starting_weapon_type(),
+                    archetype.starting_element(),
+                );
+                let _ = self.player_combat.equipment.equip(
+                    infinite_game::combat::equipment::EquipmentSlot::MainHand,
+                    main_weapon,
+                );
+                for item in inv_items {
+                    let _ = self.player_combat.inventory.add_item(item);
+                }
+
+                // Create starter skills for this archetype
+                self.player_combat.skill_slots = infinite_game::combat::starter_items::create_starter_skills(
+                    &format!("{:?}", archetype),
+                );
+
+                // An imported build brings its own gear, skills and level
+                if let Some(loadout) = &character.loadout {
+                    self.player_combat.equipment = infinite_game::combat::equipment::EquipmentSet::new();
+                    for (slot, item) in &loadout.equipment {
+                        let _ = self.player_combat.equipment.equip(*slot, item.clone());
+                    }
+                    if !loadout.skills.is_empty() {
+                        self.player_combat.skill_slots = loadout.skills.clone();
+                    }
+                    let progression = &mut self.player_combat.progression;
+                    progression.level = character.snapshot.level.max(1);
+                    progression.total_xp = character.snapshot.total_xp;
+                    progression.class.secondary = character.snapshot.secondary_archetype.clone();
+                    let secondary = progression.class.secondary.as_deref().and_then(Archetype::from_key);
+                    let growth = archetype.growth_with(secondary);
+                    self.player_combat.stats = archetype.base_stats().at_level(&growth, progression.level);
+                    self.archetype_growth = Some(growth);
+                }
+                self.mod_content.apply_skills(&mut self.player_combat.skill_slots);
+            } else {
+                self.player_combat = PlayerCombatState::new();
+                self.archetype_growth = None;
+            }
+        } else {
+            self.player_combat = PlayerCombatState::new();
+            self.archetype_growth = None;
+        }
+    }
+
+    /// Reset the combat UI and the per-session systems for a fresh start
+    fn reset_play_state(&mut self, seed: u64) {
+        self.damage_numbers.clear();
+        self.level_up_notification = None;
+        self.tutorials.load_save_data(Default::default());
+        self.tutorial_walk_time = 0.0;
+        self.play_stats = infinite_game::PlayStatistics::new();
+        self.key_ring = infinite_game::KeyRing::new();
+        self.lockpicking = None;
+        self.gathering = None;
+        self.gathering_skills = infinite_game::GatheringSkills::new();
+        self.recipe_book = infinite_game::RecipeBook::new();
+        self.alchemy_journal = infinite_game::AlchemyJournal::new();
+        self.shrine_ledger = infinite_game::ShrineLedger::new();
+        self.rift_ledger = infinite_game::RiftLedger::new();
+        self.world_bosses = infinite_game::WorldBossTracker::new(seed, self.play_time);
+        self.paradox = infinite_game::ParadoxMeter::new();
+        self.rng.reseed(seed);
+        self.barks.reseed(self.rng.stream(infinite_core::RngStream::Npc).next_u64());
+        self.game_time.clear_time_scales();
+        self.dialogue_freeze = None;
+        self.parry_slow_motion = None;
+        self.rest_fast_forward = None;
+        // Potions and the starter throwables start out on the hotbar
+        self.hotbar = infinite_game::ConsumableHotbar::new();
+        self.hotbar.bind(0, infinite_game::ItemId(3000));
+        for (slot, kind) in infinite_game::ThrowableKind::ALL.into_iter().enumerate() {
+            self.hotbar.bind(slot + 1, kind.item_id());
+        }
+        self.throwables.clear();
+        self.throw_aim = None;
+        self.throw_preview = None;
+        self.speedrun = None;
+        self.speedrun_events.clear();
+    }
+
     /// Cleanup game systems when leaving Playing state
     fn cleanup_game_systems(&mut self) {
+        self.flush_world();
+        self.macro_recorder = None;
+        self.macro_pending = None;
+        self.macro_player = None;
         self.physics_world = None;
         self.player = None;
         self.camera = None;
@@ -595,14 +1300,22 @@ impl InfiniteApp {
         self.ai_dialogue.end_dialogue();
         self.ai_dialogue_input.clear();
         self.interaction_system.clear();
+        self.barks.clear();
+        self.traps.clear();
         self.interaction_text = None;
         self.notification_text = None;
         self.time_transitioning = false;
         self.pending_time_transition = None;
-        self.climbing = false;
-        self.climb_remaining = 0.0;
+        self.pending_branch_switch = None;
+        self.era_blend_progress = None;
         self.show_inventory = false;
         self.show_shop = false;
+        self.respec_menu = None;
+        self.cooking_menu = None;
+        self.brewing_menu = None;
+        self.shrine_menu = None;
+        self.rift_menu = None;
+        self.rift = None;
 
         // Clear terrain meshes
         if let Some(render_ctx) = &mut self.render_ctx {
@@ -610,12 +1323,46 @@ impl InfiniteApp {
             render_ctx.chunk_meshes.clear();
         }
 
+        if let Some(audio) = &mut self.audio {
+            audio.stop_ambience(std::time::Duration::from_secs(1));
+            audio.stop_voice(std::time::Duration::from_millis(200));
+        }
+        self.pending_tts = None;
+        self.subtitle = None;
+        self.point_lights.clear();
+        self.torch_light = None;
+        self.compass_tracker.clear();
+        self.waypoint = None;
+        self.gm_tools = GmTools::new();
+        self.gm_drops.clear();
+        self.gm_landing = false;
+        self.combat_log.clear();
+        self.combat_stats_panel.visible = false;
+        self.training_dummy = None;
+        self.arena = None;
+        self.dungeon = None;
+        self.bridges.clear();
+        self.caravan_npcs.clear();
+        self.dungeon_enemies.clear();
+        self.dungeon_bosses.clear();
+        self.survival = None;
+        self.survival_arena = None;
+        self.survival_menu = None;
+        self.pending_score = None;
+        self.pending_leaderboard = None;
+
         info!("Game systems cleaned up");
     }
 
-    /// Gather all current game state into a SaveData struct
+    /// Snapshot all current game state into a SaveData struct. This is the
+    /// only part of saving done on the main thread: it just clones state,
+    /// serialization and IO happen on the save worker.
     fn gather_save_data(&self, slot_name: &str) -> SaveData {
-        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+        // Dungeon interiors are not saved: save at the entrance instead
+        let player_pos = match &self.dungeon {
+            Some(dungeon) => dungeon.entrance.position + Vec3::new(0.0, 1.0, 3.0),
+            None => self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO),
+        };
         let (yaw, pitch) = self.camera.as_ref().map(|c| (c.yaw, c.pitch)).unwrap_or((0.0, 0.0));
         let char_name = self.current_character.as_ref()
             .map(|c| c.name.clone())
@@ -632,6 +1379,7 @@ impl InfiniteApp {
             world: WorldSaveData {
                 active_year: self.timeline.active_year,
                 time_of_day: self.time_of_day.time_hours,
+                day: self.time_of_day.day,
             },
             timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             slot_name: slot_name.to_string(),
@@ -646,818 +1394,4178 @@ impl InfiniteApp {
             known_runes: Some(self.player_combat.known_runes.clone()),
             inventory: Some(self.player_combat.inventory.items.clone()),
             gold: Some(self.player_combat.gold),
+            combat_stats: Some(self.combat_log.to_save_data()),
+            bestiary: Some(self.bestiary.clone()),
+            tutorials: Some(self.tutorials.to_save_data()),
+            play_stats: Some(self.play_stats.clone()),
+            key_ring: Some(self.key_ring.clone()),
+            gathering_skills: Some(self.gathering_skills.clone()),
+            recipe_book: Some(self.recipe_book.clone()),
+            alchemy_journal: Some(self.alchemy_journal.clone()),
+            shrine_ledger: Some(self.shrine_ledger.clone()),
+            rift_ledger: Some(self.rift_ledger.clone()),
+            hotbar: Some(self.hotbar.clone()),
+            world_flags: Some(self.world_flags.clone()),
+            population: self.npc_manager.as_ref().map(|m| m.population.to_save_data()),
+            scheduler: Some(self.scheduler.clone()),
+            timeline: Some(TimelineSaveData {
+                branches: self.timeline.branches.clone(),
+                parked: self.parked_branches.clone(),
+            }),
+            paradox: Some(self.paradox.clone()),
+            rng: Some(self.rng.snapshot()),
+            locations: Some(self.locations.clone()),
+            economy: Some(self.economy.clone()),
+            mods: Some(self.mods.records()),
+            appearance: Some(self.player_appearance.clone()),
+            speedrun: self.speedrun.clone(),
+        }
+    }
+
+    /// The world state that belongs to the active timeline branch
+    fn branch_world_state(&self) -> BranchWorldState {
+        BranchWorldState {
+            branch: self.timeline.active_branch(),
+            world_flags: self.world_flags.clone(),
+            interactions: self.interaction_system.save_states(),
+            population: self.npc_manager.as_ref().map(|m| m.population.to_save_data()),
         }
     }
 
+    fn apply_branch_world_state(&mut self, state: BranchWorldState) {
+        self.world_flags = state.world_flags;
+        self.interaction_system.load_states(state.interactions);
+        if let Some(npc_manager) = &mut self.npc_manager {
+            npc_manager.load_population(state.population.unwrap_or_default());
+        }
+    }
+
+    /// Split a new timeline branch off the current one, here and now. The
+    /// player carries on in the new branch; the branch left behind keeps
+    /// the world as it was, minus `undone` (the changes that altered history).
+    fn fork_timeline(&mut self, name: String, undone: &[infinite_game::FlagChange]) {
+        let mut left_behind = self.branch_world_state();
+        for change in undone.iter().rev() {
+            match &change.old {
+                Some(value) => left_behind.world_flags.set(&change.namespace, &change.key, value.clone()),
+                None => {
+                    left_behind.world_flags.remove(&change.namespace, &change.key);
+                }
+            }
+        }
+        left_behind.world_flags.drain_changes();
+        self.parked_branches.push(left_behind);
+
+        let branch = self.timeline.fork_branch(name.clone());
+        if let Some(chunk_manager) = &mut self.chunk_manager {
+            if let Err(e) = chunk_manager.fork_store_branch(branch) {
+                let err = InfiniteError::from(infinite_world::WorldError::from(e))
+                    .context("copying world changes into the new timeline branch");
+                self.error_dialog.report(&err);
+            }
+        }
+        self.history_forked = true;
+        info!("Forked timeline branch {}: {}", branch, name);
+        self.notification_text = Some(format!("Timeline branched: {}", name));
+        self.notification_timer = 3.0;
+        self.autosaver.request(AutosaveTrigger::TimelineBranched);
+    }
+
+    /// Swap the world state over to another timeline branch. The chunks
+    /// follow when the time transition regenerates them.
+    fn switch_branch(&mut self, branch: infinite_core::BranchId) {
+        let left_behind = self.branch_world_state();
+        if let Err(e) = self.timeline.switch_branch(branch) {
+            tracing::error!("Failed to switch timeline branch: {}", e);
+            return;
+        }
+        let arriving = self.parked_branches.iter().position(|state| state.branch == branch);
+        let arriving = arriving.map(|index| self.parked_branches.swap_remove(index));
+        self.parked_branches.push(left_behind);
+        if let Some(state) = arriving {
+            self.apply_branch_world_state(state);
+        }
+        if let Some(branch) = self.timeline.branches.get(branch) {
+            info!("Switched to timeline branch: {}", branch.name);
+        }
+    }
+
+    /// Travel over to another timeline branch, in the current year
+    fn start_branch_switch(&mut self, branch: infinite_core::BranchId) {
+        if self.time_transitioning || branch == self.timeline.active_branch() {
+            return;
+        }
+        self.time_transition_source = self.timeline.active_year;
+        self.pending_time_transition = Some(self.timeline.active_year);
+        self.pending_branch_switch = Some(branch);
+        self.time_transitioning = true;
+        self.time_transition_alpha = 0.0;
+    }
+
     /// Quick save the game (F5)
     fn do_quicksave(&mut self) {
         let data = self.gather_save_data("");
-
-        match save::save_game(&data) {
+        self.flush_world();
+        self.save_indicator_timer = 1.0;
+        self.save_worker.save(SaveSlot::Quicksave, data, |app, result| match result {
             Ok(()) => {
-                self.notification_text = Some("Game Saved".to_string());
-                self.notification_timer = 2.0;
+                app.tutorials.handle(infinite_game::TutorialEvent::Saved);
+                app.notification_text = Some("Game Saved".to_string());
+                app.notification_timer = 2.0;
                 info!("Game saved successfully");
             }
             Err(e) => {
-                self.notification_text = Some(format!("Save failed: {}", e));
-                self.notification_timer = 3.0;
-                tracing::error!("Failed to save game: {}", e);
+                app.notification_text = Some("Save failed".to_string());
+                app.notification_timer = 3.0;
+                app.error_dialog.report(&save::save_error(save::SAVE_WRITE_FAILED, &e));
             }
-        }
+        });
     }
 
-    /// Auto-save the game
-    fn do_autosave(&mut self) {
-        let data = self.gather_save_data("Autosave");
+    /// Spawn a training dummy beside `post`, or reset the existing one
+    fn spawn_training_dummy(&mut self, post: Vec3) {
+        let Some(npc_manager) = &mut self.npc_manager else { return };
 
-        match save::autosave(&data) {
-            Ok(()) => {
-                self.notification_text = Some("Auto-saved".to_string());
+        if let Some(dummy) = &mut self.training_dummy {
+            if npc_manager.get(dummy.id).is_some() {
+                dummy.reset();
+                self.notification_text = Some("Training dummy reset".to_string());
                 self.notification_timer = 1.5;
+                return;
             }
-            Err(e) => {
-                tracing::error!("Failed to auto-save: {}", e);
-            }
         }
+
+        let mut position = post + Vec3::new(0.0, 0.0, 2.5);
+        if let Some(chunk_manager) = &self.chunk_manager {
+            position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
+        }
+        let id = npc_manager.spawn_custom(
+            TrainingDummy::npc_data(),
+            position,
+            TrainingDummy::combat_stats(),
+            false,
+        );
+        npc_manager.set_invulnerable(id, true);
+        self.training_dummy = Some(TrainingDummy::new(id, position));
+        self.notification_text = Some("Training dummy ready".to_string());
+        self.notification_timer = 1.5;
     }
 
-    /// Restore game state from save data
-    fn restore_from_save(&mut self, data: SaveData) {
-        // Restore player position
-        if let (Some(player), Some(physics)) = (&mut self.player, &mut self.physics_world) {
-            let pos = Vec3::new(data.player.position[0], data.player.position[1], data.player.position[2]);
-            player.teleport(physics, pos);
+    /// Place an NPC built from a server template in front of the player
+    fn spawn_npc_template(&mut self, character: infinite_integration::ServerCharacter, role: infinite_game::NpcRole) {
+        let Some(npc_manager) = &mut self.npc_manager else { return };
+        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+
+        let mut position = player_pos + Vec3::new(0.0, 0.0, 3.0);
+        if let Some(chunk_manager) = &self.chunk_manager {
+            position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
         }
+        let name = character.name.clone();
+        npc_manager.spawn_from_template(character, role, position);
+        self.admin_audit.record(AuditKind::Npc, AuditAction::Spawned, &name, None);
+        self.notification_text = Some(format!("Spawned {}", name));
+        self.notification_timer = 1.5;
+    }
 
-        // Restore camera rotation
-        if let Some(camera) = &mut self.camera {
-            camera.set_yaw(data.player.rotation_yaw);
-            camera.set_pitch(data.player.rotation_pitch);
+    /// Settle the crosshair spread, and with aim assist on pull the camera
+    /// onto the enemy nearest the crosshair while a ranged weapon is held
+    fn update_aim(&mut self, delta: f32) {
+        let moving = self.player.as_ref().is_some_and(|p| p.horizontal_speed() > 0.5);
+        self.aim_spread.update(delta, moving);
+
+        self.aim_locked = false;
+        if !self.settings.gameplay.aim_assist || !self.cursor_captured || !self.player_combat.equipment.holds_ranged() {
+            return;
         }
+        let (Some(camera), Some(npc_manager)) = (&mut self.camera, &self.npc_manager) else {
+            return;
+        };
+        let eye = camera.position();
+        let forward = camera.forward();
+        // Enemies and anyone the player picked a fight with, aimed at the chest
+        let targets = npc_manager
+            .npcs_iter()
+            .filter(|n| n.data.faction == infinite_game::NpcFaction::Hostile || npc_manager.is_provoked(n.id))
+            .filter(|n| npc_manager.combat_stats.get(&n.id).is_some_and(|s| s.is_alive()))
+            .map(|n| n.position + Vec3::Y * 0.4);
+        let Some(target) = infinite_game::combat::aim::assist_target(
+            eye,
+            forward,
+            balance::combat::AIM_ASSIST_ANGLE.get(),
+            infinite_game::combat::AIM_ASSIST_RANGE,
+            targets,
+        ) else {
+            return;
+        };
+        let (d_yaw, d_pitch) =
+            infinite_game::combat::aim::assist_turn(forward, target - eye, balance::combat::AIM_ASSIST_STRENGTH.get() * delta);
+        camera.set_yaw(camera.yaw + d_yaw);
+        camera.set_pitch(camera.pitch + d_pitch);
+        self.aim_locked = true;
+    }
 
-        // Restore year (if different)
-        if data.world.active_year != self.timeline.active_year {
-            if !self.time_transitioning {
-                self.time_transition_source = self.timeline.active_year;
-                self.pending_time_transition = Some(data.world.active_year);
-                self.time_transitioning = true;
-                self.time_transition_alpha = 0.0;
+    /// Carry out a choice made in the GM tools window
+    fn apply_gm_action(&mut self, action: GmAction) {
+        match action {
+            GmAction::SetNoclip(enabled) => {
+                if let (Some(player), Some(physics)) = (&mut self.player, &mut self.physics_world) {
+                    player.set_noclip(physics, enabled);
+                }
+                self.notification_text = Some(if enabled { "Noclip on" } else { "Noclip off" }.to_string());
+                self.notification_timer = 1.5;
+            }
+            GmAction::Teleport(target) => {
+                if self.dungeon.is_some() {
+                    self.notification_text = Some("Leave the dungeon before teleporting".to_string());
+                    self.notification_timer = 2.0;
+                    return;
+                }
+                let (Some(player), Some(physics)) = (&mut self.player, &mut self.physics_world) else {
+                    return;
+                };
+                // Hold height until the chunks there have loaded
+                player.teleport(physics, Vec3::new(target.x, player.position().y, target.z));
+                self.gm_landing = true;
+                self.notification_text = Some(format!("Teleported to {:.0}, {:.0}", target.x, target.z));
+                self.notification_timer = 1.5;
             }
+            GmAction::Spawn(spawn) => self.gm_spawn(spawn),
         }
+    }
 
-        // Restore time of day
-        self.time_of_day.set_time(data.world.time_of_day);
+    /// Where the crosshair points, or the ground a few meters ahead if it
+    /// points at the sky
+    fn crosshair_point(&self) -> Option<Vec3> {
+        let (Some(camera), Some(player)) = (&self.camera, &self.player) else {
+            return None;
+        };
+        let hit = self
+            .physics_world
+            .as_ref()
+            .and_then(|physics| player.aim_point(physics, camera.position(), camera.forward(), GM_SPAWN_RANGE));
+        Some(hit.unwrap_or_else(|| {
+            let forward = camera.forward();
+            let mut ahead = player.position() + Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero() * 5.0;
+            if let Some(chunk_manager) = &self.chunk_manager {
+                ahead.y = chunk_manager.height_at(ahead.x, ahead.z);
+            }
+            ahead
+        }))
+    }
 
-        // Restore collected items and play time
-        self.collected_items = data.collected_items;
-        self.play_time = data.play_time_seconds;
+    /// Place an NPC, item or object from the GM tools at the crosshair
+    fn gm_spawn(&mut self, spawn: GmSpawn) {
+        let Some(ground) = self.crosshair_point() else { return };
+        let name = match spawn {
+            GmSpawn::Npc(role) => {
+                let Some(npc_manager) = &mut self.npc_manager else { return };
+                npc_manager.spawn_role(role, ground + Vec3::Y * 0.9);
+                self.admin_audit.record(AuditKind::Npc, AuditAction::Spawned, role.name(), None);
+                role.name().to_string()
+            }
+            GmSpawn::Item(index) => {
+                let Some(item) = self.item_catalog.as_ref().and_then(|c| c.items().get(index)).cloned() else {
+                    return;
+                };
+                self.interaction_system.add(Interactable::pickup(ground + Vec3::Y, item.name.clone()));
+                self.admin_audit.record(AuditKind::Item, AuditAction::Spawned, &item.name, None);
+                let name = item.name.clone();
+                self.gm_drops.push(item);
+                name
+            }
+            GmSpawn::Object(object) => {
+                let position = ground + Vec3::Y;
+                match object {
+                    GmObject::Sign => self.interaction_system.add(Interactable::sign(position, "Placed by a game master")),
+                    GmObject::Container => {
+                        self.interaction_system.add_container(position, Vec::new());
+                    }
+                    GmObject::Door => {
+                        self.interaction_system.add_door(position, false);
+                    }
+                    GmObject::Button => {
+                        self.interaction_system.add_button(position);
+                    }
+                    GmObject::TrainingDummy => self.interaction_system.add(Interactable::training_dummy(position)),
+                }
+                object.name().to_string()
+            }
+        };
+        self.notification_text = Some(format!("Spawned {}", name));
+        self.notification_timer = 1.5;
+    }
 
-        // Restore interaction states
-        self.interaction_system.load_states(data.interactions);
+    /// Start a practice arena run around `center` with the current arena settings
+    fn start_arena(&mut self, center: Vec3) {
+        if let (Some(arena), Some(npc_manager)) = (&self.arena, &mut self.npc_manager) {
+            for id in arena.alive() {
+                npc_manager.despawn(*id);
+            }
+        }
+        self.arena = Some(PracticeArena::new(self.arena_config.clone(), center));
+        self.spawn_arena_wave();
+    }
 
-        // Restore NPC relationships
-        self.relationship_manager = RelationshipManager::from_save_data(&data.npc_relationships);
+    /// Spawn the next wave of the running arena
+    fn spawn_arena_wave(&mut self) {
+        let (Some(arena), Some(npc_manager)) = (&mut self.arena, &mut self.npc_manager) else {
+            return;
+        };
+        let ids = arena
+            .wave_spawns()
+            .into_iter()
+            .map(|(mut position, data, stats)| {
+                if let Some(chunk_manager) = &self.chunk_manager {
+                    position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
+                }
+                npc_manager.spawn_custom(data, position, stats, true)
+            })
+            .collect();
+        arena.begin_wave(ids);
+        self.notification_text = Some(format!("Arena wave {}/{}", arena.wave(), arena.config.waves));
+        self.notification_timer = 2.0;
+    }
 
-        // Restore player combat stats and progression
-        if let Some(stats) = data.player_stats {
-            self.player_combat.stats = stats;
+    /// Run the survival waves: bring each in when it's due and open the shop
+    /// once one is cleared
+    fn update_survival(&mut self, delta: f32) {
+        let (Some(run), Some(npc_manager)) = (&mut self.survival, &self.npc_manager) else {
+            return;
+        };
+        let event = run.update(delta, |id| npc_manager.get(id).is_some());
+        match event {
+            Some(infinite_game::SurvivalEvent::SpawnWave(_)) => self.spawn_survival_wave(),
+            Some(infinite_game::SurvivalEvent::WaveCleared(wave)) => {
+                if let Some(menu) = &mut self.survival_menu {
+                    menu.shop_closed = false;
+                }
+                self.notification_text = Some(format!("Wave {} cleared!", wave));
+                self.notification_timer = 2.0;
+            }
+            None => {}
         }
-        if let Some(progression) = data.player_progression {
-            self.player_combat.progression = progression;
+    }
+
+    /// Spawn the survival run's next wave at the arena's edge
+    fn spawn_survival_wave(&mut self) {
+        let (Some(run), Some(npc_manager)) = (&mut self.survival, &mut self.npc_manager) else {
+            return;
+        };
+        let ids = run
+            .wave_spawns()
+            .into_iter()
+            .map(|(mut position, data, stats)| {
+                position.y += 0.9;
+                let id = npc_manager.spawn_custom(data, position, stats, true);
+                npc_manager.provoke_npc(id);
+                id
+            })
+            .collect();
+        run.begin_wave(ids);
+        if let Some(menu) = &mut self.survival_menu {
+            menu.shop_closed = false;
         }
+        self.notification_text = Some(format!("Wave {}", run.wave()));
+        self.notification_timer = 2.0;
+    }
 
-        // Restore inventory
-        if let Some(items) = data.inventory {
-            self.player_combat.inventory.items = items;
+    /// The player fell in the arena: clear the floor, stand them back up for
+    /// the results and send the score to the leaderboard
+    fn end_survival_run(&mut self) {
+        let (Some(run), Some(menu)) = (&mut self.survival, &mut self.survival_menu) else {
+            return;
+        };
+        if let Some(npc_manager) = &mut self.npc_manager {
+            for id in run.alive() {
+                npc_manager.despawn(*id);
+            }
         }
+        run.end();
+        self.player_combat.respawn();
+        self.play_stats.record(infinite_game::StatEvent::Died);
 
-        // Restore gold
-        if let Some(gold) = data.gold {
-            self.player_combat.gold = gold;
+        let client = self.integration_client.as_ref().filter(|c| c.is_authenticated());
+        let Some(client) = client else {
+            menu.score_status = ScoreStatus::Failed("playing offline".to_string());
+            return;
+        };
+        let entry = infinite_integration::LeaderboardEntry {
+            id: None,
+            project_id: String::new(),
+            board: infinite_game::npc::survival::SURVIVAL_BOARD.to_string(),
+            user_name: None,
+            character_name: self.current_character.as_ref().map(|c| c.name.clone()).unwrap_or_default(),
+            archetype: self.player_combat.progression.class.primary.clone(),
+            score: run.score(),
+            wave: run.wave(),
+            kills: run.kills(),
+            best_combo: run.best_combo(),
+        };
+        menu.score_status = ScoreStatus::Submitting;
+        self.pending_score = Some(client.submit_score(entry));
+    }
+
+    /// Whether a survival or rift run is on, which saving and loading wait out
+    fn in_unsaved_run(&self) -> bool {
+        self.survival.is_some() || self.rift.is_some()
+    }
+
+    /// Whether the survival shop or results window is up
+    fn survival_window_open(&self) -> bool {
+        matches!((&self.survival, &self.survival_menu), (Some(run), Some(menu)) if menu.is_open(run))
+    }
+
+    /// Whether the rift gate menu or a boon choice is up
+    fn rift_window_open(&self) -> bool {
+        self.rift_menu.is_some() || self.rift.as_ref().is_some_and(|run| run.phase() == infinite_game::RiftPhase::Choosing)
+    }
+
+    /// What kind of play is on, for the things that differ between modes
+    fn play_mode(&self) -> infinite_game::PlayMode {
+        if self.survival.is_some() {
+            infinite_game::PlayMode::Survival
+        } else if self.rift.is_some() {
+            infinite_game::PlayMode::Rift
+        } else {
+            infinite_game::PlayMode::World
         }
+    }
 
-        // Reset climbing state
-        self.climbing = false;
-        self.climb_remaining = 0.0;
+    /// Macros belong to the logged-in account, or the local profile offline
+    fn macro_profile(&self) -> String {
+        self.integration_client.as_ref()
+            .and_then(|c| c.user_name())
+            .unwrap_or_else(|| MacroSettings::LOCAL_PROFILE.to_string())
     }
 
-    /// Quick load the game (F9)
-    fn do_quickload(&mut self) {
-        match save::load_game() {
-            Ok(data) => {
-                self.restore_from_save(data);
-                self.notification_text = Some("Game Loaded".to_string());
-                self.notification_timer = 2.0;
-                info!("Game loaded successfully");
+    /// Record macros from this frame's input, or play one back into it
+    fn update_macros(&mut self, delta: f32) {
+        // A macro mustn't keep pressing keys behind a window
+        if !self.cursor_captured {
+            if let Some(player) = self.macro_player.take() {
+                player.cancel(&mut self.input_handler.state);
             }
-            Err(e) => {
-                self.notification_text = Some(format!("Load failed: {}", e));
+        }
+
+        if self.input_handler.state.is_just_pressed(InputAction::RecordMacro) {
+            if self.macro_recorder.is_some() {
+                self.finish_macro_recording();
+            } else if !self.settings.macros.enabled {
+                self.notification_text = Some("Macros are turned off in Settings".to_string());
                 self.notification_timer = 3.0;
-                tracing::error!("Failed to load game: {}", e);
+            } else if !self.play_mode().allows_macros() {
+                self.notification_text = Some("Macros can't be used here".to_string());
+                self.notification_timer = 3.0;
+            } else {
+                if let Some(player) = self.macro_player.take() {
+                    player.cancel(&mut self.input_handler.state);
+                }
+                self.macro_pending = None;
+                self.macro_recorder = Some(infinite_game::MacroRecorder::new());
+                self.notification_text = Some("Recording a macro - press F7 to stop".to_string());
+                self.notification_timer = infinite_game::input_macro::MAX_MACRO_LENGTH;
+            }
+        } else if let Some(recorder) = &mut self.macro_recorder {
+            if !recorder.observe(&self.input_handler.state, delta) {
+                self.finish_macro_recording();
+            }
+        }
+
+        if let Some(player) = &mut self.macro_player {
+            if !player.update(delta, &mut self.input_handler.state) {
+                self.macro_player = None;
             }
         }
     }
 
-    /// Update cursor capture state
-    fn update_cursor_capture(&mut self, should_capture: bool) {
-        if self.cursor_captured == should_capture {
+    /// Stop recording and wait for the key to bind the macro to
+    fn finish_macro_recording(&mut self) {
+        let Some(recorder) = self.macro_recorder.take() else {
+            return;
+        };
+        let steps = recorder.finish();
+        if steps.is_empty() {
+            self.notification_text = Some("Nothing was recorded".to_string());
+            self.notification_timer = 3.0;
             return;
         }
+        let keys: Vec<String> = infinite_game::input_macro::MACRO_KEYS.iter()
+            .filter_map(|&key| infinite_game::input_macro::macro_key_name(key))
+            .map(|name| infinite_game::input_macro::macro_key_label(&name).to_string())
+            .collect();
+        self.notification_text = Some(format!(
+            "Recorded {} actions. Press a key to bind them to ({}), or Esc to discard",
+            steps.len(),
+            keys.join(" "),
+        ));
+        self.notification_timer = 10.0;
+        self.macro_pending = Some(steps);
+    }
 
-        if let Some(window) = &self.window {
-            if should_capture {
-                let _ = window.set_cursor_grab(CursorGrabMode::Locked)
-                    .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined));
-                window.set_cursor_visible(false);
+    /// A key pressed while playing: binds a just-recorded macro, or plays the
+    /// macro bound to it. Returns whether the key was used up.
+    fn handle_macro_key(&mut self, key: KeyCode) -> bool {
+        let name = infinite_game::input_macro::macro_key_name(key);
+        if let Some(steps) = self.macro_pending.take() {
+            let Some(name) = name else {
+                // Keep waiting; the key still does what it normally does
+                self.macro_pending = Some(steps);
+                return false;
+            };
+            let profile = self.macro_profile();
+            let set = self.settings.macros.profile_mut(&profile);
+            let label = infinite_game::input_macro::macro_key_label(&name).to_string();
+            let input_macro = infinite_game::InputMacro {
+                name: format!("Macro {}", label),
+                key: name,
+                steps,
+            };
+            self.notification_text = Some(match set.add(input_macro) {
+                Ok(Some(_)) => format!("Macro on {} replaced", label),
+                Ok(None) => format!("Macro bound to {}", label),
+                Err(e) => e.to_string(),
+            });
+            self.notification_timer = 3.0;
+            if let Err(e) = self.settings.save() {
+                tracing::error!("Failed to save settings: {}", e);
+            }
+            return true;
+        }
+
+        let Some(name) = name else {
+            return false;
+        };
+        let profile = self.macro_profile();
+        let Some(input_macro) = self.settings.macros.profile(&profile).and_then(|set| set.find(&name)) else {
+            return false;
+        };
+        if !self.settings.macros.enabled || self.macro_recorder.is_some() || self.macro_player.is_some() {
+            return true;
+        }
+        if !self.play_mode().allows_macros() {
+            self.notification_text = Some("Macros can't be used here".to_string());
+            self.notification_timer = 2.0;
+            return true;
+        }
+        self.macro_player = Some(infinite_game::MacroPlayer::new(input_macro));
+        true
+    }
+
+    /// Start timing a world from the beginning, when speedruns are on
+    fn start_speedrun(&mut self, seed: u64) {
+        let settings = &self.settings.speedrun;
+        self.speedrun_events.clear();
+        if !settings.enabled || settings.splits.is_empty() {
+            self.speedrun = None;
+            return;
+        }
+        self.speedrun = Some(infinite_game::SpeedrunRun::new(seed, settings.splits.clone()));
+        self.speedrun_records.start_attempt(seed);
+        self.write_speedrun_records();
+    }
+
+    fn write_speedrun_records(&self) {
+        if let Err(e) = save::write_speedrun_records(&self.speedrun_records) {
+            tracing::error!("Failed to save speedrun records: {}", e);
+        }
+    }
+
+    /// Run the speedrun clock and split on whatever happened this frame
+    fn update_speedrun(&mut self, delta: f32) {
+        let Some(run) = &mut self.speedrun else {
+            self.speedrun_events.clear();
+            return;
+        };
+        run.update(delta);
+        let mut events = std::mem::take(&mut self.speedrun_events);
+        events.push(infinite_game::RunEvent::YearReached(self.timeline.active_year));
+        events.push(infinite_game::RunEvent::LevelReached(self.player_combat.progression.level));
+        let Some(index) = events.into_iter().fold(None, |taken, event| run.observe(event).or(taken)) else {
+            return;
+        };
+
+        let time = infinite_game::speedrun::format_run_time(run.split_time(index).unwrap_or_default());
+        let delta = self.speedrun_records.best(run).and_then(|best| run.delta(index, best));
+        let mut text = format!("{}  {}", run.splits()[index].name, time);
+        if let Some(delta) = delta {
+            text.push_str(&format!("  ({})", infinite_game::speedrun::format_delta(delta)));
+        }
+        if run.is_finished() {
+            if self.speedrun_records.submit(run) {
+                text = format!("Run complete in {}, a new personal best!", time);
             } else {
-                let _ = window.set_cursor_grab(CursorGrabMode::None);
-                window.set_cursor_visible(true);
+                text = format!("Run complete in {}", time);
             }
-            self.cursor_captured = should_capture;
-            self.input_handler.set_cursor_captured(should_capture);
+            self.write_speedrun_records();
         }
+        self.notification_text = Some(text);
+        self.notification_timer = 3.0;
     }
 
-    fn create_swapchain_and_framebuffers(
-        device: Arc<Device>,
-        surface: Arc<Surface>,
-        window: Arc<Window>,
-        memory_allocator: Arc<StandardMemoryAllocator>,
-    ) -> Result<(
-        Arc<Swapchain>,
-        Vec<Arc<Image>>,
-        Arc<RenderPass>,
-        Vec<Arc<Framebuffer>>,
-        Arc<ImageView>,
-    )> {
-        let surface_capabilities = device
-            .physical_device()
-            .surface_capabilities(&surface, Default::default())
-            .context("Failed to get surface capabilities")?;
+    /// Add dungeon entrances for newly loaded chunks and drop those whose chunk unloaded
+    fn sync_dungeon_entrances(&mut self) {
+        let Some(chunk_manager) = &self.chunk_manager else {
+            return;
+        };
+        self.interaction_system.retain(|i| match &i.kind {
+            infinite_game::InteractableKind::DungeonEntrance(e) => chunk_manager.get_chunk(&e.chunk).is_some(),
+            _ => true,
+        });
+        let present: HashSet<ChunkCoord> = self.interaction_system.iter()
+            .filter_map(|i| match &i.kind {
+                infinite_game::InteractableKind::DungeonEntrance(e) => Some(e.chunk),
+                _ => None,
+            })
+            .collect();
 
-        let image_format = device
-            .physical_device()
-            .surface_formats(&surface, Default::default())
-            .context("Failed to get surface formats")?[0]
-            .0;
+        let chunk_size = chunk_manager.config.chunk_size;
+        let seed = chunk_manager.terrain_config.seed;
+        for chunk in chunk_manager.loaded_chunks() {
+            if present.contains(&chunk.coord) {
+                continue;
+            }
+            if let Some(mut entrance) = self.dungeon_config.entrance_in_chunk(chunk.coord, seed, chunk_size) {
+                entrance.position.y = chunk_manager.height_at(entrance.position.x, entrance.position.z) + 1.0;
+                self.interaction_system.add(Interactable::dungeon_entrance(entrance));
+            }
+        }
+    }
 
-        let window_size = window.inner_size();
+    /// Build the bridges over loaded chunks and drop the ones whose chunks
+    /// unloaded. Bridges an era change restyled or a branch switch collapsed
+    /// (or restored) are rebuilt. Bridges and settlements are registered as
+    /// named locations as they come into range.
+    fn sync_bridges(&mut self) {
+        let (Some(chunk_manager), Some(physics)) = (&self.chunk_manager, &mut self.physics_world) else {
+            return;
+        };
+        let year = self.timeline.active_year;
+        let mut wanted: HashMap<u64, infinite_world::Bridge> = HashMap::new();
+        for chunk in chunk_manager.loaded_chunks() {
+            wanted.extend(chunk_manager.bridges_in(chunk.coord).into_iter().map(|bridge| (bridge.id, bridge)));
+        }
 
-        let (swapchain, images) = Swapchain::new(
-            device.clone(),
-            surface.clone(),
-            SwapchainCreateInfo {
-                min_image_count: surface_capabilities.min_image_count.max(2),
-                image_format,
-                image_extent: [window_size.width, window_size.height],
-                image_usage: ImageUsage::COLOR_ATTACHMENT,
-                composite_alpha: surface_capabilities
-                    .supported_composite_alpha
-                    .into_iter()
-                    .next()
-                    .unwrap(),
-                ..Default::default()
-            },
-        )
-        .context("Failed to create swapchain")?;
+        let world_flags = &self.world_flags;
+        let collapsed = |id: u64| world_flags.get_bool(BRIDGE_FLAGS, &id.to_string());
+        let stale: Vec<u64> = self.bridges.iter()
+            .filter(|(id, instance)| wanted.get(*id) != Some(&instance.bridge) || instance.is_collapsed() != collapsed(**id))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in stale {
+            if let Some(instance) = self.bridges.remove(&id) {
+                instance.remove(physics);
+            }
+        }
+        for (id, bridge) in wanted {
+            if self.bridges.contains_key(&id) {
+                continue;
+            }
+            self.locations.register(infinite_game::Location::new(
+                id,
+                bridge.name.clone(),
+                infinite_game::LocationKind::Bridge,
+                bridge.center(),
+                Some(year),
+            ));
+            let instance = infinite_world::BridgeInstance::build(
+                bridge,
+                |x, z| chunk_manager.height_at(x, z),
+                collapsed(id),
+                physics,
+            );
+            self.bridges.insert(id, instance);
+        }
 
-        // Create depth buffer
-        let depth_buffer = ImageView::new_default(
-            Image::new(
-                memory_allocator.clone(),
-                ImageCreateInfo {
-                    image_type: vulkano::image::ImageType::Dim2d,
-                    format: Format::D32_SFLOAT,
-                    extent: [window_size.width, window_size.height, 1],
-                    usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT,
-                    ..Default::default()
-                },
-                AllocationCreateInfo::default(),
-            )
-            .context("Failed to create depth buffer")?,
-        )
-        .context("Failed to create depth buffer view")?;
+        if let Some(roads) = chunk_manager.roads() {
+            let chunk_size = chunk_manager.config.chunk_size;
+            for chunk in chunk_manager.loaded_chunks() {
+                let Some(settlement) = roads.settlement_in_region(roads.region_of(chunk.coord)) else {
+                    continue;
+                };
+                if ChunkCoord::from_world_pos(settlement.position, chunk_size) != chunk.coord {
+                    continue;
+                }
+                let (x, z) = (settlement.position.x, settlement.position.z);
+                let position = Vec3::new(x, chunk_manager.height_at(x, z), z);
+                self.locations.register(infinite_game::Location::new(
+                    settlement.seed,
+                    settlement.name(),
+                    infinite_game::LocationKind::Settlement,
+                    position,
+                    None,
+                ));
+                self.economy.add_market(infinite_game::Market::new(settlement.seed, settlement.name(), position, year));
+                for road in roads.roads_near(chunk.coord, 0.0) {
+                    let (Some(a), Some(b)) = (roads.settlement_in_region(road.from), roads.settlement_in_region(road.to)) else {
+                        continue;
+                    };
+                    let path = road.points.iter()
+                        .map(|p| Vec3::new(p.x, chunk_manager.height_at(p.x, p.y), p.y))
+                        .collect();
+                    self.economy.connect(a.seed, b.seed, path);
+                }
+            }
+        }
+        physics.update_query_pipeline();
+    }
 
-        // Create render pass with two subpasses:
-        // Subpass 0: 3D scene rendering with depth
-        // Subpass 1: UI overlay (no depth)
-        let render_pass = vulkano::ordered_passes_renderpass!(
-            device.clone(),
-            attachments: {
-                color: {
-                    format: image_format,
-                    samples: 1,
-                    load_op: Clear,
-                    store_op: Store,
-                },
-                depth: {
-                    format: Format::D32_SFLOAT,
-                    samples: 1,
-                    load_op: Clear,
-                    store_op: DontCare,
+    /// Run the settlement markets and their caravans. Caravans near the
+    /// player walk the road as NPCs; further out they travel unseen.
+    fn update_economy(&mut self, delta: f32) {
+        let player = self.player.as_ref().filter(|_| self.dungeon.is_none()).map(|p| p.position());
+        let npc_manager = &self.npc_manager;
+        let events = self.economy.update(delta, self.timeline.active_year, player, |id| {
+            npc_manager.as_ref().is_some_and(|manager| manager.get(id).is_some())
+        });
+        for event in events {
+            match event {
+                infinite_game::EconomyEvent::EscortJoined { caravan } => {
+                    let Some(caravan) = self.economy.caravan(caravan) else {
+                        continue;
+                    };
+                    let destination = self.economy.market(caravan.to).map_or("the next town", |m| m.name.as_str());
+                    self.notification_text = Some(format!(
+                        "Escorting a caravan of {:?} to {} ({} gold on arrival)",
+                        caravan.cargo, destination, caravan.reward(),
+                    ));
+                    self.notification_timer = 3.0;
                 }
-            },
-            passes: [
-                // Subpass 0: 3D scene with depth
+                infinite_game::EconomyEvent::Ambushed { caravan, position }
+                    if self.economy.caravan(caravan).is_some_and(|c| c.under_attack()) =>
                 {
-                    color: [color],
-                    depth_stencil: {depth},
-                    input: []
-                },
-                // Subpass 1: UI overlay (no depth)
-                {
-                    color: [color],
-                    depth_stencil: {},
-                    input: []
+                    let bandits = self.spawn_bandits(position);
+                    self.economy.set_bandits(caravan, bandits);
+                    self.notification_text = Some("Bandits ambush the caravan!".to_string());
+                    self.notification_timer = 2.5;
                 }
-            ]
-        )
-        .context("Failed to create render pass")?;
+                infinite_game::EconomyEvent::Defended { .. } => {
+                    self.notification_text = Some("The bandits are driven off".to_string());
+                    self.notification_timer = 2.0;
+                }
+                infinite_game::EconomyEvent::Looted { caravan } if self.caravan_npcs.contains_key(&caravan) => {
+                    self.notification_text = Some("Bandits looted the caravan".to_string());
+                    self.notification_timer = 2.5;
+                }
+                infinite_game::EconomyEvent::Arrived { reward: Some(gold), .. } => {
+                    self.player_combat.gold += gold;
+                    self.play_stats.record(infinite_game::StatEvent::GoldEarned(gold));
+                    self.notification_text = Some(format!("Caravan delivered: earned {} gold", gold));
+                    self.notification_timer = 2.5;
+                }
+                _ => {}
+            }
+        }
 
-        let framebuffers = images
-            .iter()
-            .map(|image| {
-                let view = ImageView::new_default(image.clone()).unwrap();
-                Framebuffer::new(
-                    render_pass.clone(),
-                    FramebufferCreateInfo {
-                        attachments: vec![view, depth_buffer.clone()],
-                        ..Default::default()
-                    },
-                )
-                .unwrap()
+        let Some(npc_manager) = &mut self.npc_manager else {
+            return;
+        };
+        let near: Vec<(u64, Vec3)> = self.economy.caravans()
+            .filter_map(|caravan| Some((caravan.id, self.economy.caravan_position(caravan.id)?)))
+            .filter(|(_, position)| {
+                player.is_some_and(|p| p.with_y(0.0).distance(position.with_y(0.0)) <= CARAVAN_SPAWN_RANGE)
             })
             .collect();
+        self.caravan_npcs.retain(|id, members| {
+            let keep = near.iter().any(|(caravan, _)| caravan == id);
+            if !keep {
+                for member in members.iter() {
+                    npc_manager.despawn(*member);
+                }
+            }
+            keep
+        });
+        for (id, position) in near {
+            let members = self.caravan_npcs.entry(id).or_insert_with(|| {
+                [("Caravan Master", infinite_game::NpcRole::Villager), ("Caravan Guard", infinite_game::NpcRole::Guard)]
+                    .into_iter()
+                    .map(|(name, role)| {
+                        let data = infinite_game::npc::NpcData {
+                            name: name.to_string(),
+                            role,
+                            faction: infinite_game::NpcFaction::Friendly,
+                            home_position: position,
+                            wander_radius: 0.0,
+                            interaction_radius: 2.0,
+                            color: role.color(),
+                            server_character_id: None,
+                        };
+                        let stats = infinite_game::npc::combat::CombatStats::for_role(role);
+                        let member = npc_manager.spawn_custom(data, position, stats, false);
+                        npc_manager.set_invulnerable(member, true);
+                        member
+                    })
+                    .collect()
+            });
+            for (index, member) in members.iter().enumerate() {
+                let Some(npc) = npc_manager.get_mut(*member) else {
+                    continue;
+                };
+                let (x, z) = (position.x + index as f32 * 1.5, position.z);
+                let y = self.chunk_manager.as_ref().map_or(position.y, |c| c.height_at(x, z)) + 0.9;
+                npc.position = Vec3::new(x, y, z);
+            }
+        }
+    }
 
-        Ok((swapchain, images, render_pass, framebuffers, depth_buffer))
+    /// Bandits springing on a caravan at `near`
+    fn spawn_bandits(&mut self, near: Vec3) -> Vec<infinite_game::NpcId> {
+        let Some(npc_manager) = &mut self.npc_manager else {
+            return Vec::new();
+        };
+        let rng = self.rng.stream(infinite_core::RngStream::Npc);
+        (0..CARAVAN_BANDITS)
+            .map(|_| {
+                let angle = rng.range_f32(0.0, std::f32::consts::TAU);
+                let mut position = near + Vec3::new(angle.cos(), 0.0, angle.sin()) * 8.0;
+                if let Some(chunk_manager) = &self.chunk_manager {
+                    position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
+                }
+                let data = infinite_game::npc::NpcData {
+                    name: "Bandit".to_string(),
+                    role: infinite_game::NpcRole::Enemy,
+                    faction: infinite_game::NpcFaction::Hostile,
+                    home_position: position,
+                    wander_radius: 10.0,
+                    interaction_radius: 0.0,
+                    color: infinite_game::NpcRole::Enemy.color(),
+                    server_character_id: None,
+                };
+                let id = npc_manager.spawn_custom(data, position, infinite_game::npc::combat::CombatStats::default_enemy(), true);
+                npc_manager.provoke_npc(id);
+                id
+            })
+            .collect()
     }
 
-    fn recreate_swapchain(&mut self) {
-        let Some(window) = &self.window else { return };
-        let Some(_surface) = &self.surface else { return };
-        let Some(render_ctx) = &mut self.render_ctx else {
+    /// Discover named locations the player walks up to
+    fn update_locations(&mut self) {
+        let Some(player) = &self.player else {
             return;
         };
-
-        let window_size = window.inner_size();
-        if window_size.width == 0 || window_size.height == 0 {
+        if self.dungeon.is_some() {
             return;
         }
-
-        let (new_swapchain, new_images) = render_ctx
-            .swapchain
-            .recreate(SwapchainCreateInfo {
-                image_extent: [window_size.width, window_size.height],
-                ..render_ctx.swapchain.create_info()
-            })
-            .expect("Failed to recreate swapchain");
-
-        // Recreate depth buffer with new size
-        let new_depth_buffer = ImageView::new_default(
-            Image::new(
-                render_ctx.memory_allocator.clone(),
-                ImageCreateInfo {
-                    image_type: vulkano::image::ImageType::Dim2d,
-                    format: Format::D32_SFLOAT,
-                    extent: [window_size.width, window_size.height, 1],
-                    usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT,
-                    ..Default::default()
-                },
-                AllocationCreateInfo::default(),
-            )
-            .expect("Failed to recreate depth buffer"),
-        )
-        .expect("Failed to create depth buffer view");
-
-        render_ctx.swapchain = new_swapchain;
-        render_ctx.images = new_images.clone();
-        render_ctx.depth_buffer = new_depth_buffer.clone();
-        render_ctx.framebuffers = new_images
+        let found: Vec<String> = self.locations
+            .discover_near(player.position(), self.timeline.active_year)
             .iter()
-            .map(|image| {
-                let view = ImageView::new_default(image.clone()).unwrap();
-                Framebuffer::new(
-                    render_ctx.render_pass.clone(),
-                    FramebufferCreateInfo {
-                        attachments: vec![view, new_depth_buffer.clone()],
-                        ..Default::default()
-                    },
-                )
-                .unwrap()
-            })
+            .map(|location| location.name.clone())
             .collect();
+        if let Some(name) = found.last() {
+            self.notification_text = Some(format!("Discovered: {}", name));
+            self.notification_timer = 3.0;
+        }
+    }
 
-        render_ctx.recreate_swapchain = false;
+    /// Feed this frame's tutorial events, advance the prompt and handle the
+    /// dismiss / checklist key
+    fn update_tutorials(&mut self, delta: f32) {
+        use infinite_game::TutorialEvent;
+
+        self.tutorials.enabled = self.settings.gameplay.show_tutorials;
+        self.tutorials.handle(TutorialEvent::Started);
+
+        let state = &self.input_handler.state;
+        let walking = [InputAction::MoveForward, InputAction::MoveBackward, InputAction::MoveLeft, InputAction::MoveRight]
+            .into_iter()
+            .any(|action| state.is_held(action));
+        if walking {
+            self.tutorial_walk_time += delta;
+            if self.tutorial_walk_time > 3.0 {
+                self.tutorials.handle(TutorialEvent::Moved);
+            }
+        }
+
+        if self.interaction_system.focused().is_some() {
+            self.tutorials.handle(TutorialEvent::InteractableFocused);
+        }
+        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+        if self.interaction_system.time_portals().any(|(pos, _)| (pos - player_pos).length() < 15.0) {
+            self.tutorials.handle(TutorialEvent::PortalNearby);
+        }
+        let enemy_in_view = self.npc_manager.as_ref().is_some_and(|npc_manager| {
+            npc_manager.npcs_iter().any(|npc| {
+                npc.data.faction == infinite_game::NpcFaction::Hostile
+                    && (npc.position - player_pos).length() < 20.0
+            })
+        });
+        if enemy_in_view {
+            self.tutorials.handle(TutorialEvent::EnemySighted);
+        }
+        if self.level_up_notification.is_some() {
+            self.tutorials.handle(TutorialEvent::LeveledUp);
+        }
+
+        self.tutorials.update(delta);
+        if self.input_handler.state.is_just_pressed(InputAction::Tutorial) {
+            if self.tutorials.prompt().is_some() {
+                self.tutorials.dismiss();
+            } else {
+                self.show_tutorial_checklist = !self.show_tutorial_checklist;
+            }
+        }
     }
 
-    fn update(&mut self, delta: f32) {
-        self.game_time.update(delta);
+    /// Biome around the player, classified from terrain elevation
+    fn player_biome(&self) -> Biome {
+        let player_pos = self.player.as_ref()
+            .map(|p| p.position())
+            .unwrap_or(Vec3::ZERO);
+        let elevation = self.chunk_manager.as_ref()
+            .and_then(|cm| cm.get_chunk(&cm.player_chunk(player_pos)))
+            .map(|chunk| {
+                let t = &chunk.terrain;
+                (player_pos.y - t.min_height) / (t.max_height - t.min_height).max(0.01)
+            })
+            .unwrap_or(0.0);
+        Biome::from_elevation(elevation)
+    }
 
-        // Poll pending item catalog fetch
-        if let Some(pending) = &self.pending_catalog {
-            if let Some(result) = pending.try_recv() {
-                match result {
-                    Ok(server_items) => {
-                        let catalog = infinite_game::combat::ItemCatalog::load_from_server(server_items);
-                        info!("Item catalog loaded: {} items", catalog.len());
-                        self.item_catalog = Some(catalog);
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to load item catalog: {}", e);
-                    }
-                }
-                self.pending_catalog = None;
+    /// Keep a dig spot at the mark of every carried treasure map whose chunk
+    /// is loaded
+    fn sync_dig_spots(&mut self) {
+        let Some(chunk_manager) = &self.chunk_manager else {
+            return;
+        };
+        let chunk_size = chunk_manager.config.chunk_size;
+        let maps: Vec<&infinite_game::combat::TreasureMapData> = self.player_combat.inventory.items.iter()
+            .filter_map(|item| item.treasure_map.as_ref())
+            .filter(|map| {
+                let coord = ChunkCoord::from_world_pos(map.target_position(), chunk_size);
+                chunk_manager.get_chunk(&coord).is_some()
+            })
+            .collect();
+        self.interaction_system.retain(|i| match i.kind {
+            infinite_game::InteractableKind::DigSpot { map_seed } => maps.iter().any(|m| m.seed == map_seed),
+            _ => true,
+        });
+        let present: HashSet<u64> = self.interaction_system.iter()
+            .filter_map(|i| match i.kind {
+                infinite_game::InteractableKind::DigSpot { map_seed } => Some(map_seed),
+                _ => None,
+            })
+            .collect();
+        for map in maps {
+            if present.contains(&map.seed) {
+                continue;
             }
+            let mut position = map.target_position();
+            position.y = chunk_manager.height_at(position.x, position.z) + 0.3;
+            self.interaction_system.add(Interactable::dig_spot(position, map.seed));
         }
+        self.dig_spot_seeds = self.player_combat.inventory.items.iter()
+            .filter_map(|item| item.treasure_map.as_ref().map(|map| map.seed))
+            .collect();
+    }
 
-        // Update based on current state
-        match &self.app_state {
-            ApplicationState::Loading(phase) => {
-                self.loading_timer += delta;
-                self.loading_screen.update(delta, phase.progress());
+    /// Keep an interactable on every resource node of the loaded chunks
+    /// that is there to gather in this era: not harvested, or grown back
+    fn sync_resource_nodes(&mut self) {
+        let Some(chunk_manager) = &mut self.chunk_manager else {
+            return;
+        };
+        let chunk_size = chunk_manager.config.chunk_size;
+        let seed = chunk_manager.terrain_config.seed as u64;
+        let era = infinite_game::Era::for_year(self.timeline.active_year);
+        let mut nodes = Vec::new();
+        for chunk in chunk_manager.loaded_chunks() {
+            let (low, high) = (chunk.terrain.min_height, chunk.terrain.max_height);
+            let elevation = |x: f32, z: f32| (chunk_manager.height_at(x, z) - low) / (high - low).max(0.01);
+            nodes.extend(infinite_game::gathering::nodes_in_chunk(chunk.coord, seed, chunk_size, era, elevation));
+        }
+        let now = self.play_time;
+        nodes.retain(|node| {
+            chunk_manager.chunk_delta(node.chunk).is_none_or(|delta| delta.node_available(node.index, now))
+        });
+        let wanted: HashMap<(ChunkCoord, u32), infinite_game::ResourceNode> = nodes.into_iter()
+            .map(|mut node| {
+                node.position.y = chunk_manager.height_at(node.position.x, node.position.z);
+                ((node.chunk, node.index), node)
+            })
+            .collect();
 
-                // Simulate loading phases (advance every 0.5 seconds)
-                if self.loading_timer >= 0.5 {
-                    self.loading_timer = 0.0;
-                    if let Some(next_phase) = phase.next() {
-                        self.app_state = ApplicationState::Loading(next_phase);
-                    } else {
-                        // Loading complete — go to login screen (or main menu if offline)
-                        if self.integration_client.is_some() {
-                            self.app_state = ApplicationState::Login;
-                        } else {
-                            self.app_state = ApplicationState::MainMenu;
-                        }
-                        info!(
-                            "Loading complete - Year: {}",
-                            self.timeline.year_label()
-                        );
-                    }
-                }
+        // Nodes whose terrain changed under them (a new era) are replaced
+        self.interaction_system.retain(|i| match &i.kind {
+            infinite_game::InteractableKind::Resource(node) => wanted.get(&(node.chunk, node.index)) == Some(node),
+            _ => true,
+        });
+        let present: HashSet<(ChunkCoord, u32)> = self.interaction_system.iter()
+            .filter_map(|i| match &i.kind {
+                infinite_game::InteractableKind::Resource(node) => Some((node.chunk, node.index)),
+                _ => None,
+            })
+            .collect();
+        for (key, node) in wanted {
+            if !present.contains(&key) {
+                self.interaction_system.add(Interactable::resource(node));
             }
-            ApplicationState::Playing => {
-                // Release cursor when debug overlay or any dialogue is active
-                let dialogue_active = self.dialogue_system.is_active() || self.ai_dialogue.is_active();
-                self.update_cursor_capture(!self.debug_visible && !dialogue_active && !self.show_shop);
+        }
+    }
 
-                // Update world systems
-                self.time_of_day.update(delta);
-                self.weather.update(delta);
+    /// Keep an interactable on the shrine of every loaded chunk that has one
+    /// in this era, and register each as a location so finding it puts it
+    /// on the map
+    fn sync_shrines(&mut self) {
+        let Some(chunk_manager) = &self.chunk_manager else {
+            return;
+        };
+        let chunk_size = chunk_manager.config.chunk_size;
+        let seed = chunk_manager.terrain_config.seed as u64;
+        let year = self.timeline.active_year;
+        let era = infinite_game::Era::for_year(year);
+        let wanted: HashMap<u64, infinite_game::Shrine> = chunk_manager.loaded_chunks()
+            .filter_map(|chunk| infinite_game::shrine::shrine_in_chunk(chunk.coord, seed, chunk_size, era))
+            .map(|mut shrine| {
+                shrine.position.y = chunk_manager.height_at(shrine.position.x, shrine.position.z);
+                (shrine.id, shrine)
+            })
+            .collect();
 
-                // --- Time transition fade ---
-                if self.time_transitioning {
-                    if let Some(target_year) = self.pending_time_transition {
-                        if self.time_transition_alpha < 1.0 {
-                            // Fade to black
-                            self.time_transition_alpha = (self.time_transition_alpha + delta * 2.0).min(1.0);
-                        } else {
-                            // At full black: switch year, regenerate terrain
-                            if let Err(e) = self.timeline.travel_to_year(target_year) {
-                                tracing::error!("Failed to travel to year {}: {}", target_year, e);
-                            } else {
-                                info!("Switched to year: {}", self.timeline.year_label());
-                            }
+        self.interaction_system.retain(|i| match &i.kind {
+            infinite_game::InteractableKind::Shrine(shrine) => wanted.get(&shrine.id) == Some(shrine),
+            _ => true,
+        });
+        let present: HashSet<u64> = self.interaction_system.shrines().map(|shrine| shrine.id).collect();
+        for (id, shrine) in wanted {
+            if present.contains(&id) {
+                continue;
+            }
+            self.locations.register(infinite_game::Location::new(
+                id,
+                shrine.name(),
+                infinite_game::LocationKind::Shrine,
+                shrine.position,
+                Some(year),
+            ));
+            self.interaction_system.add(Interactable::shrine(shrine));
+        }
+    }
 
-                            // Regenerate chunks with new time-period terrain config
-                            let time_config = if self.timeline.is_present() {
-                                None
-                            } else {
-                                Some(TimeTerrainConfig::for_year(target_year, self.timeline.present_year))
-                            };
+    /// Pray at the open shrine for a boon
+    fn pray(&mut self, boon: infinite_game::Boon, tribute: infinite_game::Tribute) {
+        let Some(menu) = &self.shrine_menu else {
+            return;
+        };
+        let shrine = menu.shrine;
+        self.notification_text = Some(match self.shrine_ledger.pray(&shrine, boon, tribute, &mut self.player_combat, self.play_time) {
+            Ok(()) => {
+                self.shrine_menu = None;
+                self.update_cursor_capture(true);
+                format!("The {} grants you {}", shrine.name(), boon.name())
+            }
+            Err(infinite_game::PrayerError::Resting(_)) => "The shrine is silent".to_string(),
+            Err(infinite_game::PrayerError::CantAfford) => "You can't pay that tribute".to_string(),
+        });
+        self.notification_timer = 2.0;
+    }
 
-                            if let (Some(chunk_manager), Some(physics)) =
-                                (&mut self.chunk_manager, &mut self.physics_world)
-                            {
-                                chunk_manager.set_time_terrain_config(time_config);
-                                let player_pos = self.player.as_ref()
-                                    .map(|p| p.position())
-                                    .unwrap_or(Vec3::ZERO);
-                                chunk_manager.reload_all(player_pos, physics);
-                                physics.update_query_pipeline();
-
-                                // Rebuild chunk meshes
-                                if let Some(render_ctx) = &mut self.render_ctx {
-                                    render_ctx.chunk_meshes.clear();
-                                    for chunk in chunk_manager.loaded_chunks() {
-                                        let terrain = &chunk.terrain;
-                                        let mesh_data = Mesh::terrain(
-                                            terrain.config.size,
-                                            terrain.config.subdivisions,
-                                            &terrain.heights,
-                                            |x, h, z| terrain.color_at(x, h, z),
-                                        );
-                                        if let Ok(buffers) = create_mesh_buffers(
-                                            render_ctx.memory_allocator.clone(),
-                                            &mesh_data.vertices,
-                                            &mesh_data.indices,
-                                        ) {
-                                            render_ctx.chunk_meshes.insert(chunk.coord, buffers);
-                                        }
-                                    }
-                                }
-                            }
+    /// Have the open shrine lift the player's curses
+    fn cleanse_at_shrine(&mut self) {
+        let Some(menu) = &self.shrine_menu else {
+            return;
+        };
+        let shrine = menu.shrine;
+        self.notification_text = Some(match self.shrine_ledger.cleanse(&shrine, &mut self.player_combat, self.play_time) {
+            Ok(0) => "There's nothing here to lift".to_string(),
+            Ok(_) => format!("The {} lifts your curses", shrine.name()),
+            Err(infinite_game::PrayerError::Resting(_)) => "The shrine is silent".to_string(),
+            Err(infinite_game::PrayerError::CantAfford) => "You can't pay for the rite".to_string(),
+        });
+        self.notification_timer = 2.0;
+    }
 
-                            self.pending_time_transition = None;
+    /// Start gathering from a node the player picked, if they carry the
+    /// tool it needs
+    fn start_gathering(&mut self, node: infinite_game::ResourceNode) {
+        if let Err(tool) = infinite_game::gathering::check_tool(node.kind, &self.player_combat.inventory) {
+            self.notification_text = Some(format!("You need a {} for this {}", tool.name(), node.kind.name().to_lowercase()));
+            self.notification_timer = 2.0;
+            return;
+        }
+        self.gathering = Some(infinite_game::GatherSession::new(node, &self.gathering_skills));
+    }
 
-                            // Auto-save on time transition
-                            if self.settings.gameplay.auto_save {
-                                self.do_autosave();
-                                self.auto_save_timer = self.settings.gameplay.auto_save_interval as f32;
-                            }
-                        }
-                    } else {
-                        // No pending transition, fade back in
-                        self.time_transition_alpha = (self.time_transition_alpha - delta * 2.0).max(0.0);
-                        if self.time_transition_alpha <= 0.0 {
-                            self.time_transitioning = false;
-                        }
-                    }
-                }
+    /// Gather while Interact is held and the player stays in reach of the
+    /// node; letting go or walking off stops it
+    fn update_gathering(&mut self, delta: f32) {
+        let Some(session) = &mut self.gathering else {
+            return;
+        };
+        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+        let in_reach = (session.node.position - player_pos).with_y(0.0).length() < GATHER_REACH;
+        if !in_reach || !self.input_handler.state.is_held(InputAction::Interact) {
+            self.gathering = None;
+            return;
+        }
+        if !session.update(delta) {
+            return;
+        }
+        let node = session.node;
+        self.gathering = None;
 
-                // --- Climbing mode update ---
-                if self.climbing {
-                    let climb_speed = 3.0;
-                    let climb_step = climb_speed * delta;
+        // The chunk remembers the node is gone until it grows back
+        let now = self.play_time;
+        if let Some(chunk_manager) = &mut self.chunk_manager {
+            chunk_manager.chunk_delta_mut(node.chunk).harvest_node(node.index, now, node.kind.regrow_time());
+        }
+        let era = infinite_game::Era::for_year(self.timeline.active_year);
+        let count = self.gathering_skills.skill(node.kind).yield_count(node.kind);
+        let material = infinite_game::gathering::create_material(node.kind, era, count);
+        let mut message = format!("+{} {}", count, material.name);
+        if let Err(item) = self.player_combat.inventory.add_item(material) {
+            self.collected_items.push(item.name);
+        }
+        match self.gathering_skills.record_harvest(node.kind) {
+            Some(level) => message.push_str(&format!("  ·  {} level {}!", node.kind.skill_name(), level)),
+            None => message.push_str(&format!("  ·  +{} {} XP", node.kind.xp(), node.kind.skill_name())),
+        }
+        self.notification_text = Some(message);
+        self.notification_timer = 2.5;
+        self.sync_resource_nodes();
+    }
 
-                    // Check for exit: Jump to dismount, or reached top/bottom
-                    if self.input_handler.state.is_just_pressed(InputAction::Jump)
-                        || self.climb_remaining <= 0.0
-                    {
-                        self.climbing = false;
-                        self.climb_remaining = 0.0;
-                    } else if let (Some(player), Some(physics)) =
-                        (&mut self.player, &mut self.physics_world)
-                    {
-                        // Move player up/down based on W/S input
-                        let move_dir = if self.input_handler.state.is_held(InputAction::MoveForward) {
-                            1.0
-                        } else if self.input_handler.state.is_held(InputAction::MoveBackward) {
-                            -1.0
-                        } else {
-                            0.0
-                        };
+    /// Replace the overworld test traps with a row of this era's trap kinds
+    fn place_overworld_traps(&mut self) {
+        let Some(chunk_manager) = &self.chunk_manager else {
+            return;
+        };
+        self.traps.clear();
+        let kinds = infinite_game::TrapKind::for_era(self.timeline.active_year, self.timeline.present_year);
+        for (index, kind) in kinds.iter().enumerate() {
+            let (x, z) = (-6.0 + index as f32 * 6.0, -16.0);
+            self.traps.add(*kind, Vec3::new(x, chunk_manager.height_at(x, z), z));
+        }
+        self.sync_trap_interactables();
+    }
 
-                        if move_dir != 0.0 {
-                            let displacement = self.climb_direction * move_dir * climb_step;
-                            let pos = player.position() + displacement;
-                            player.teleport(physics, pos);
-                            if move_dir > 0.0 {
-                                self.climb_remaining -= climb_step;
-                            }
-                        }
-                    }
+    /// Benches and chairs around the campfire and a wall near the chest to
+    /// lean on, shared by the player and villagers
+    fn place_seats(&mut self) {
+        let (Some(chunk_manager), Some(npc_manager)) = (&self.chunk_manager, &mut self.npc_manager) else {
+            return;
+        };
+        let ground = |x: f32, z: f32| Vec3::new(x, chunk_manager.height_at(x, z), z);
+        let seats = &mut npc_manager.seats;
+        seats.clear();
+        seats.add(infinite_game::SeatKind::Bench, ground(-4.0, 3.8), Vec3::Z);
+        seats.add(infinite_game::SeatKind::Bench, ground(-4.0, 8.2), Vec3::NEG_Z);
+        seats.add(infinite_game::SeatKind::Chair, ground(-1.8, 6.0), Vec3::NEG_X);
+        seats.add(infinite_game::SeatKind::Wall, ground(4.0, -9.0), Vec3::Z);
+
+        self.interaction_system.retain(|i| !matches!(i.kind, infinite_game::InteractableKind::Seat { .. }));
+        for seat in seats.iter() {
+            self.interaction_system.add(Interactable::seat(seat));
+            if let Some(physics) = &mut self.physics_world {
+                for block in seat.blocks() {
+                    physics.create_static_box_rotated(block.half_extents, block.center, block.rotation);
                 }
+            }
+        }
+    }
 
-                // --- Fixed timestep physics update ---
-                let fixed_dt = self.game_time.config.fixed_timestep;
-                let steps = self.game_time.fixed_steps();
+    /// The campfire by the benches doubles as a cooking spot (an open fire
+    /// in the early eras, a stove from the modern era on), with an alchemy
+    /// table across the clearing
+    fn place_crafting_stations(&mut self) {
+        let Some(chunk_manager) = &self.chunk_manager else {
+            return;
+        };
+        let kind = infinite_game::StationKind::for_era(infinite_game::Era::for_year(self.timeline.active_year));
+        let position = Vec3::new(-4.0, chunk_manager.height_at(-4.0, 6.0) + 0.4, 6.0);
+        self.interaction_system.retain(|i| !matches!(i.kind, infinite_game::InteractableKind::CookingStation(_)));
+        self.interaction_system.add(Interactable::cooking_station(position, kind));
+
+        let (x, z) = (1.5, 9.0);
+        let table = Vec3::new(x, chunk_manager.height_at(x, z) + 0.45, z);
+        self.interaction_system.retain(|i| !matches!(i.kind, infinite_game::InteractableKind::BrewingStation));
+        self.interaction_system.add(Interactable::brewing_station(table));
+    }
 
-                // Update query pipeline so character controller sees all colliders
-                if let Some(physics) = &mut self.physics_world {
-                    physics.update_query_pipeline();
-                }
+    /// Brew the picked reagents into a potion, or into weapon coatings (one
+    /// vial per harmful effect) if `coating`; a failed brew wastes them
+    fn brew(&mut self, reagents: &[infinite_game::Reagent], coating: bool) {
+        use infinite_game::alchemy::{coatings_from, count_reagent, create_potion, potency_for, potion_name, take_reagents};
 
-                for _ in 0..steps {
-                    if let (Some(physics), Some(player), Some(camera)) =
-                        (&mut self.physics_world, &mut self.player, &self.camera)
-                    {
-                        if !self.climbing {
-                            player.fixed_update(
-                                physics,
-                                &self.input_handler.state,
-                                camera.yaw,
-                                fixed_dt,
-                            );
-                        }
-                        physics.step();
+        if reagents.iter().any(|r| count_reagent(&self.player_combat.inventory, *r) == 0) {
+            self.notification_text = Some("You don't have all of that".to_string());
+            self.notification_timer = 1.5;
+            return;
+        }
+        let outcome = self.alchemy_journal.brew(reagents);
+        if outcome.consumes_reagents() {
+            take_reagents(&mut self.player_combat.inventory, reagents);
+            if let Some(menu) = &mut self.brewing_menu {
+                menu.clear_selection();
+            }
+        }
+        self.notification_text = Some(match outcome {
+            infinite_game::BrewOutcome::Brewed { effects, new_recipe } if coating => {
+                let coatings = coatings_from(&effects);
+                for kind in &coatings {
+                    if let Err(item) = self.player_combat.inventory.add_item(kind.create(1)) {
+                        self.collected_items.push(item.name);
                     }
                 }
-
-                // --- Variable timestep camera update ---
-                if let (Some(physics), Some(player), Some(camera)) =
-                    (&self.physics_world, &self.player, &mut self.camera)
-                {
-                    camera.update(
-                        &self.input_handler.state,
-                        player.eye_position(),
-                        Some(physics),
-                        delta,
-                    );
+                let names: Vec<&str> = coatings.iter().map(|kind| kind.name()).collect();
+                match (names.is_empty(), new_recipe) {
+                    (true, _) => format!("{} is too gentle to coat a blade", potion_name(&effects)),
+                    (false, true) => format!("New brew: {}! Bottled {}", potion_name(&effects), names.join(" and ")),
+                    (false, false) => format!("Bottled {}", names.join(" and ")),
                 }
+            }
+            infinite_game::BrewOutcome::Brewed { effects, new_recipe } => {
+                let potency = potency_for(&self.player_combat.effective_stats());
+                if let Err(item) = self.player_combat.inventory.add_item(create_potion(&effects, potency, 1)) {
+                    self.collected_items.push(item.name);
+                }
+                if new_recipe {
+                    format!("New brew: {}!", potion_name(&effects))
+                } else {
+                    format!("Brewed {}", potion_name(&effects))
+                }
+            }
+            infinite_game::BrewOutcome::Failed => "The mixture fizzles into sludge".to_string(),
+            infinite_game::BrewOutcome::Invalid => "A brew takes two or three different reagents".to_string(),
+        });
+        self.notification_timer = 2.0;
+    }
 
-                // --- Chunk streaming ---
-                let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
-                if let (Some(chunk_manager), Some(physics)) =
-                    (&mut self.chunk_manager, &mut self.physics_world)
-                {
-                    chunk_manager.update(player_pos, physics);
-
-                    // Remove meshes for unloaded chunks
-                    if let Some(render_ctx) = &mut self.render_ctx {
-                        for coord in &chunk_manager.newly_unloaded {
-                            render_ctx.chunk_meshes.remove(coord);
-                        }
+    /// Eat a reagent to learn one of its effects
+    fn taste_reagent(&mut self, reagent: infinite_game::Reagent) {
+        if !infinite_game::alchemy::take_reagents(&mut self.player_combat.inventory, &[reagent]) {
+            return;
+        }
+        self.notification_text = Some(match self.alchemy_journal.taste(reagent) {
+            Some(effect) => format!("{} tastes of {}", reagent.name(), effect.name().to_lowercase()),
+            None => format!("Nothing new to learn from {}", reagent.name()),
+        });
+        self.notification_timer = 2.0;
+    }
 
-                        // Create meshes for newly loaded chunks
-                        for coord in &chunk_manager.newly_loaded {
-                            if let Some(chunk) = chunk_manager.get_chunk(coord) {
-                                let terrain = &chunk.terrain;
-                                let mesh_data = Mesh::terrain(
-                                    terrain.config.size,
-                                    terrain.config.subdivisions,
-                                    &terrain.heights,
-                                    |x, h, z| terrain.color_at(x, h, z),
-                                );
-                                if let Ok(buffers) = create_mesh_buffers(
-                                    render_ctx.memory_allocator.clone(),
-                                    &mesh_data.vertices,
-                                    &mesh_data.indices,
-                                ) {
-                                    render_ctx.chunk_meshes.insert(*coord, buffers);
-                                }
-                            }
-                        }
-                    }
+    /// Cook what's in the pot: a known or newly discovered dish goes into
+    /// the pack, a ruined pot wastes its ingredients
+    fn cook(&mut self, pot: &[infinite_game::Ingredient]) {
+        use infinite_game::cooking::{create_food, has_ingredients, take_ingredients};
 
-                    physics.update_query_pipeline();
+        if !has_ingredients(&self.player_combat.inventory, pot) {
+            self.notification_text = Some("You don't have all of that".to_string());
+            self.notification_timer = 1.5;
+            return;
+        }
+        let era = infinite_game::Era::for_year(self.timeline.active_year);
+        let outcome = self.recipe_book.cook(pot, era);
+        if outcome.consumes_ingredients() {
+            take_ingredients(&mut self.player_combat.inventory, pot);
+            if let Some(menu) = &mut self.cooking_menu {
+                menu.clear_pot();
+            }
+        }
+        self.notification_text = Some(match outcome {
+            infinite_game::CookOutcome::Cooked { recipe, discovered } => {
+                if let Err(item) = self.player_combat.inventory.add_item(create_food(recipe, 1)) {
+                    self.collected_items.push(item.name);
                 }
+                if discovered {
+                    format!("New recipe: {}!", recipe.name)
+                } else {
+                    format!("Cooked {}", recipe.name)
+                }
+            }
+            infinite_game::CookOutcome::WrongEra(_) => "These don't come together in this era".to_string(),
+            infinite_game::CookOutcome::Incomplete => "Something's missing...".to_string(),
+            infinite_game::CookOutcome::Ruined => "The pot burns to a crisp".to_string(),
+        });
+        self.notification_timer = 2.0;
+    }
 
-                // --- NPC spawning/despawning with chunks ---
-                if let (Some(npc_manager), Some(chunk_manager)) =
-                    (&mut self.npc_manager, &self.chunk_manager)
-                {
-                    let active_year = self.timeline.active_year;
-
-                    // Despawn NPCs from unloaded chunks
-                    for coord in &chunk_manager.newly_unloaded {
-                        npc_manager.on_chunk_unloaded(*coord);
-                    }
+    /// Sit or lean on a seat the player picked, if there's room
+    fn rest_on_seat(&mut self, id: infinite_game::SeatId) {
+        let (Some(player), Some(physics), Some(npc_manager)) =
+            (&mut self.player, &mut self.physics_world, &mut self.npc_manager)
+        else {
+            return;
+        };
+        let Some(seat) = npc_manager.seats.get(id).copied() else {
+            return;
+        };
+        let Some(spot) = npc_manager.seats.claim(id, infinite_game::Occupant::Player, player.position()) else {
+            self.notification_text = Some(format!("Someone is already using the {}", seat.kind.name().to_lowercase()));
+            self.notification_timer = 1.5;
+            return;
+        };
+        player.rest(physics, seat.kind.pose(), seat.spot(spot), seat.facing, seat.exit_point(spot));
+        if let Some(camera) = &mut self.camera {
+            camera.set_yaw(seat.facing.x.atan2(-seat.facing.z));
+        }
+    }
 
-                    // Spawn NPCs for newly loaded chunks
-                    for coord in &chunk_manager.newly_loaded {
-                        let cm_ref = chunk_manager;
-                        npc_manager.on_chunk_loaded(*coord, active_year, |x, z| cm_ref.height_at(x, z));
-                    }
+    /// Resting: free the seat once the player gets up, regain health and
+    /// mana while down, and let time pass faster while Sprint is held
+    fn update_rest(&mut self, delta: f32) {
+        let pose = self.player.as_ref().and_then(|p| p.rest_pose());
+        match pose {
+            Some(pose) => infinite_game::seat::rest_regen(&mut self.player_combat.stats, pose, delta),
+            None => {
+                if let Some(npc_manager) = &mut self.npc_manager {
+                    npc_manager.seats.release(infinite_game::Occupant::Player);
                 }
+            }
+        }
+        let fast_forward = pose.is_some()
+            && self.settings.gameplay.rest_fast_forward
+            && self.input_handler.state.is_held(InputAction::Sprint);
+        match (fast_forward, self.rest_fast_forward) {
+            (true, None) => self.rest_fast_forward = Some(self.game_time.push_time_scale(infinite_game::REST_TIME_SCALE)),
+            (false, Some(id)) => {
+                self.game_time.pop_time_scale(id);
+                self.rest_fast_forward = None;
+            }
+            _ => {}
+        }
+    }
 
-                // --- NPC update ---
-                if let (Some(npc_manager), Some(chunk_manager)) =
-                    (&mut self.npc_manager, &self.chunk_manager)
-                {
-                    let cm_ref = chunk_manager;
-                    npc_manager.update(delta, player_pos, |x, z| cm_ref.height_at(x, z));
+    /// Keep a disarm interactable on every spotted, armed trap
+    fn sync_trap_interactables(&mut self) {
+        self.interaction_system.retain(|i| !matches!(i.kind, infinite_game::InteractableKind::Trap { .. }));
+        for trap in self.traps.detected_armed() {
+            self.interaction_system.add(Interactable::trap(trap.position + Vec3::Y * 0.5, trap.id, trap.kind));
+        }
+    }
 
-                    // Sync NPC positions to interaction system:
-                    // Remove old NPC interactables
-                    self.interaction_system.retain(|i| {
-                        !matches!(i.kind, infinite_game::InteractableKind::Npc { .. })
-                    });
+    /// Spot hidden traps near the player and set off traps that the player or
+    /// hostile NPCs walk into
+    fn update_traps(&mut self, delta: f32) {
+        let Some(player) = &self.player else {
+            return;
+        };
+        let player_pos = player.position();
+        // Crouching to search and good light make traps easier to spot
+        let light = if self.player_combat.equipment.holds_torch() {
+            self.time_of_day.light_intensity().max(0.9)
+        } else {
+            self.time_of_day.light_intensity()
+        };
+        let perception = if player.is_crouching() { 2.0 } else { 1.0 } * (0.4 + 0.6 * light);
+        let spotted = self.traps.update_detection(delta, player_pos, perception);
+        if let Some(trap) = spotted.first().and_then(|id| self.traps.get(*id)) {
+            self.notification_text = Some(format!("You spot a {}", trap.kind.name()));
+            self.notification_timer = 2.0;
+        }
 
-                    // Add current NPC interactables (non-hostile and not provoked)
-                    for npc in npc_manager.npcs_iter() {
-                        if npc.data.faction != infinite_game::NpcFaction::Hostile
-                            && !npc_manager.is_provoked(npc.id)
-                        {
-                            self.interaction_system.add(Interactable::npc(
-                                npc.position,
-                                npc.id,
-                                npc.name(),
-                                npc.data.interaction_radius,
-                            ));
-                        }
-                    }
+        let hostiles: Vec<(NpcId, Vec3)> = self.npc_manager.as_ref()
+            .map(|npc_manager| {
+                npc_manager.npcs_iter()
+                    .filter(|npc| npc.data.faction == infinite_game::NpcFaction::Hostile)
+                    .map(|npc| (npc.id, npc.position))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let triggers = self.traps.update(delta, player_pos, hostiles);
+        let revealed = !spotted.is_empty() || !triggers.is_empty();
+        for trigger in triggers {
+            self.apply_trap_trigger(trigger);
+        }
+        if revealed {
+            self.sync_trap_interactables();
+        }
+    }
 
-                    // --- NPC combat: enemies and provoked NPCs damage player ---
-                    // Collect attacking NPC IDs and positions first (avoids borrow conflicts)
-                    let attacking_enemies: Vec<(NpcId, Vec3)> = npc_manager.npcs_iter()
-                        .filter(|n| {
-                            n.data.role == infinite_game::NpcRole::Enemy
-                            || npc_manager.is_provoked(n.id)
-                        })
-                        .filter(|n| {
-                            n.brain.as_ref()
-                                .and_then(|b| b.current_action_name())
-                                .map(|name| name == "attack_melee")
-                                .unwrap_or(false)
-                        })
-                        .map(|n| (n.id, n.position))
-                        .collect();
+    /// Worn gear from the future strains the timeline; play out whatever the
+    /// paradox meter demands
+    fn update_paradox(&mut self, delta: f32) {
+        let year = self.timeline.active_year;
+        let config = &self.settings.gameplay.paradox;
+        self.paradox.update(delta, self.player_combat.equipment.equipped(), year, config);
 
-                    for (npc_id, npc_pos) in &attacking_enemies {
-                        if let Some(stats) = npc_manager.combat_stats.get_mut(npc_id) {
-                            if stats.is_alive() && stats.update_attack(delta) {
-                                // Check if player is in attack range
-                                let dist = (player_pos - *npc_pos).length();
-                                if dist < stats.attack_radius {
-                                    let dmg = stats.attack;
-                                    let actual_dmg = self.player_combat.take_damage(dmg);
-                                    if actual_dmg > 0.0 {
-                                        // Apply knockback
-                                        if let Some(player) = &mut self.player {
-                                            let knockback_dir = (player_pos - *npc_pos).normalize_or_zero();
-                                            player.character.apply_impulse(knockback_dir * 5.0);
-                                        }
-                                    }
-                                }
-                            }
+        let Some(player_pos) = self.player.as_ref().map(|p| p.position()) else {
+            return;
+        };
+        for event in self.paradox.drain_events() {
+            match event {
+                infinite_game::ParadoxEvent::StageChanged { from, to } => {
+                    self.notification_text = Some(if to > from {
+                        format!("The timeline strains: {}", to.name())
+                    } else {
+                        format!("The timeline settles: {}", to.name())
+                    });
+                    self.notification_timer = 2.5;
+                }
+                infinite_game::ParadoxEvent::NpcsUnsettled => {
+                    if let Some(npc_manager) = &mut self.npc_manager {
+                        npc_manager.hear_noise(player_pos, PARADOX_UNREST_RADIUS);
+                        if self.paradox.stage() == infinite_game::ParadoxStage::Unraveling {
+                            npc_manager.alert_nearby_guards(player_pos, PARADOX_UNREST_RADIUS);
                         }
                     }
+                    self.notification_text = Some("The locals stare at your strange gear".to_string());
+                    self.notification_timer = 2.0;
                 }
+                infinite_game::ParadoxEvent::SpawnAnomaly => self.spawn_anomaly(player_pos),
+            }
+        }
+    }
 
-                // --- Player attack input (light + heavy) ---
-                if let Some(camera) = &self.camera {
-                    let attack_range = 2.5_f32;
-                    let attack_angle = 90.0_f32.to_radians();
-                    let player_forward = camera.forward();
-                    let player_forward_xz = Vec3::new(player_forward.x, 0.0, player_forward.z).normalize_or_zero();
+    /// Open the entity inspector on whatever is under the mouse cursor,
+    /// unless the click was on a window
+    fn inspect_under_cursor(&mut self) {
+        let (Some(gui), Some(camera)) = (&self.gui, &self.camera) else {
+            return;
+        };
+        let ctx = gui.context();
+        if ctx.is_pointer_over_area() {
+            return;
+        }
+        let Some(pointer) = ctx.input(|input| input.pointer.latest_pos()) else {
+            return;
+        };
+        let (origin, direction) = screen_ray(camera, pointer, ctx.screen_rect().size());
 
-                    // Helper closure: find closest NPC with combat stats in attack cone
-                    let find_target = |npc_manager: &NpcManager, range: f32| -> Option<(NpcId, Vec3, f32)> {
-                        npc_manager.npcs_iter()
-                            .filter(|n| npc_manager.combat_stats.contains_key(&n.id))
-                            .filter_map(|n| {
-                                let to_npc = n.position - player_pos;
-                                let to_npc_xz = Vec3::new(to_npc.x, 0.0, to_npc.z);
-                                let distance = to_npc_xz.length();
-                                if distance < range && distance > 0.01 {
-                                    let angle = player_forward_xz.dot(to_npc_xz.normalize());
-                                    if angle > (attack_angle / 2.0).cos() {
-                                        return Some((n.id, n.position, distance));
-                                    }
-                                }
-                                None
-                            })
-                            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
-                    };
+        let npcs = self
+            .npc_manager
+            .iter()
+            .flat_map(|npc_manager| npc_manager.npcs_iter())
+            .map(|npc| (InspectTarget::Npc(npc.id), npc.position, NPC_HALF_EXTENT));
+        // NPCs' interactables would shadow the NPCs themselves
+        let objects = self
+            .interaction_system
+            .iter()
+            .enumerate()
+            .filter(|(_, interactable)| !matches!(interactable.kind, infinite_game::InteractableKind::Npc { .. }))
+            .map(|(index, interactable)| {
+                let target = InspectTarget::Interactable { index, kind: interactable.kind.name() };
+                (target, interactable.position, interactable.kind.outline_size() * 0.5)
+            });
+        self.inspector.target = crate::ui::pick(origin, direction, INSPECT_RANGE, npcs.chain(objects));
+    }
 
-                    // Light attack (left click)
-                    if self.input_handler.state.is_just_pressed(InputAction::Attack)
-                        && self.player_combat.try_light_attack()
-                    {
-                        if let Some(npc_manager) = &mut self.npc_manager {
-                                if let Some((npc_id, npc_pos, _)) = find_target(npc_manager, attack_range) {
-                                    let npc_defense = npc_manager.combat_stats.get(&npc_id)
-                                        .map(|s| s.defense).unwrap_or(0.0);
-                                    let npc_element = npc_manager.combat_stats.get(&npc_id)
-                                        .map(|s| s.element).unwrap_or(infinite_game::combat::element::Element::Physical);
-                                    let npc_weakness = npc_manager.combat_stats.get(&npc_id)
-                                        .and_then(|s| s.weapon_weakness);
-
-                                    let event = self.player_combat.calculate_full_damage(
-                                        npc_defense, npc_element, npc_weakness,
-                                    );
-                                    let result = npc_manager.damage_npc(
-                                        npc_id, event.final_amount, event.element, event.attack_type,
-                                    );
+    /// Measure what each system holds, and shed or restore detail as the
+    /// totals cross the budget
+    fn update_memory(&mut self, delta: f32) {
+        use infinite_core::MemoryCategory;
 
-                                    self.damage_numbers.push(DamageNumber {
-                                        position: npc_pos + Vec3::Y * 1.5,
-                                        amount: event.final_amount,
-                                        is_crit: event.is_crit,
-                                        timer: 1.0,
-                                    });
+        if let Some(render_ctx) = &self.render_ctx {
+            let fixed_meshes: u64 = [
+                &render_ctx.capsule_mesh,
+                &render_ctx.terrain_mesh,
+                &render_ctx.npc_capsule_mesh,
+                &render_ctx.debug_capsule_mesh,
+                &render_ctx.portal_mesh,
+                &render_ctx.glider_mesh,
+                &render_ctx.rope_mesh,
+                &render_ctx.box_mesh,
+            ]
+            .into_iter()
+            .flatten()
+            .map(|mesh| mesh.size_bytes())
+            .sum::<u64>()
+                + render_ctx.sky_mesh.as_ref().map_or(0, |mesh| mesh.size_bytes());
+            let targets = render_ctx
+                .images
+                .iter()
+                .chain([&render_ctx.scene_target.color, render_ctx.scene_target.depth.image()])
+                .map(|image| image_bytes(image))
+                .sum();
+            self.memory.set(MemoryCategory::Meshes, render_ctx.chunk_meshes.stats().allocated_bytes + fixed_meshes);
+            self.memory.set(MemoryCategory::Textures, targets);
+            self.memory.set(
+                MemoryCategory::Staging,
+                render_ctx.mesh_uploader.staging_bytes() + render_ctx.frame_uniforms.ring().size_bytes(),
+            );
+        }
+        if let Some(gui) = &self.gui {
+            let textures = gui.context().tex_manager().read().allocated().map(|(_, meta)| meta.bytes_used() as u64).sum();
+            self.memory.set(MemoryCategory::Ui, textures);
+        }
+        let chunk_bytes = self.chunk_manager.as_ref().map_or(0, |cm| cm.memory_bytes());
+        self.memory.set(MemoryCategory::ChunkData, chunk_bytes as u64);
+        let npc_bytes = self.npc_manager.as_ref().map_or(0, |npcs| npcs.memory_bytes());
+        self.memory.set(MemoryCategory::NpcState, npc_bytes as u64);
+
+        match self.memory.update(delta) {
+            Some(infinite_core::MemoryEvent::Downgraded { level }) => {
+                tracing::warn!(
+                    "Memory {} (VRAM {}, RAM {}); reducing detail to level {}",
+                    self.memory.pressure().name(),
+                    infinite_core::format_bytes(self.memory.device_total()),
+                    infinite_core::format_bytes(self.memory.host_total()),
+                    level
+                );
+                self.apply_memory_detail(level);
+                self.notification_text = Some("Running low on memory: reducing detail".to_string());
+                self.notification_timer = 2.5;
+            }
+            Some(infinite_core::MemoryEvent::Restored { level }) => {
+                info!("Memory back under budget; restoring detail to level {}", level);
+                self.apply_memory_detail(level);
+            }
+            None => {}
+        }
+    }
 
-                                    if result.defeated {
-                                        let npc_level = npc_manager.npc_level(npc_id);
-                                        let xp = infinite_game::player::stats::xp_for_enemy(
-                                            npc_level, infinite_game::player::stats::EnemyType::Normal,
-                                        );
-                                        let levels_gained = self.player_combat.add_xp(xp);
-                                        for new_level in levels_gained {
-                                            if let Some(growth) = &self.archetype_growth {
-                                                self.player_combat.apply_level_up(growth);
-                                            }
-                                            self.level_up_notification = Some((new_level, 3.0));
-                                        }
-                                        let gold_reward = match result.role {
-                                            infinite_game::NpcRole::Guard => 25 * npc_level as u64,
-                                            infinite_game::NpcRole::Shopkeeper => 50 * npc_level as u64,
-                                            infinite_game::NpcRole::Villager => 2 * npc_level as u64,
-                                            infinite_game::NpcRole::QuestGiver => 15 * npc_level as u64,
-                                            infinite_game::NpcRole::Enemy => 10 * npc_level as u64,
-                                        };
-                                        self.player_combat.gold += gold_reward;
-                                        if result.was_friendly {
-                                            self.notification_text = Some(format!("You murdered a {}!  +{} Gold", result.role.name(), gold_reward));
-                                        } else {
-                                            self.notification_text = Some(format!("+{} XP  +{} Gold", xp, gold_reward));
-                                        }
-                                        self.notification_timer = 1.5;
-                                    }
-                                }
-                            }
-                    }
+    /// Pick up edits to the balance file
+    fn update_balance(&mut self, delta: f32) {
+        let Some(result) = self.tunables.poll(delta) else {
+            return;
+        };
+        self.balance_panel.reloaded(&result);
+        match result {
+            Ok(report) => {
+                info!("Reloaded {} balance values from {}", report.applied, BALANCE_PATH);
+                if !report.unknown.is_empty() {
+                    tracing::warn!("Unknown balance values in {}: {}", BALANCE_PATH, report.unknown.join(", "));
+                }
+                self.notification_text = Some("Balance reloaded".to_string());
+            }
+            Err(e) => {
+                self.notification_text = Some(format!("Balance not reloaded: {}", e));
+                self.error_dialog.report(&InfiniteError::from(e).context("reloading game balance"));
+            }
+        }
+        self.notification_timer = 2.0;
+    }
 
-                    // Heavy attack (right click)
-                    if self.input_handler.state.is_just_pressed(InputAction::HeavyAttack) {
-                        self.player_combat.try_heavy_attack();
-                    }
+    /// Stream fewer chunks and simplify NPCs sooner at higher downgrade levels
+    fn apply_memory_detail(&mut self, level: u8) {
+        let (load_radius, npc_lod_scale, collider_budget) = MEMORY_DETAIL[level as usize];
+        if let Some(chunk_manager) = &mut self.chunk_manager {
+            chunk_manager.config.load_radius = load_radius;
+            chunk_manager.config.unload_radius = load_radius + 1;
+            chunk_manager.set_collider_budget(collider_budget * 1024 * 1024);
+        }
+        if let Some(npc_manager) = &mut self.npc_manager {
+            npc_manager.lod_config = infinite_game::LodConfig::default().scaled(npc_lod_scale);
+        }
+    }
 
-                    // Heavy attack damage: deal damage when windup completes
-                    if self.player_combat.active_attack_type == Some(infinite_game::combat::damage::AttackType::Heavy)
-                        && self.player_combat.heavy_attack_timer <= 0.0
-                        && self.player_combat.can_deal_damage()
-                    {
-                        if let Some(npc_manager) = &mut self.npc_manager {
-                            if let Some((npc_id, npc_pos, _)) = find_target(npc_manager, attack_range + 0.5) {
-                                let npc_defense = npc_manager.combat_stats.get(&npc_id)
-                                    .map(|s| s.defense).unwrap_or(0.0);
-                                let npc_element = npc_manager.combat_stats.get(&npc_id)
-                                    .map(|s| s.element).unwrap_or(infinite_game::combat::element::Element::Physical);
-                                let npc_weakness = npc_manager.combat_stats.get(&npc_id)
-                                    .and_then(|s| s.weapon_weakness);
+    /// Announce, spawn and follow world bosses, and reward the player when
+    /// one they fought falls
+    fn update_world_boss(&mut self) {
+        use infinite_game::WorldBossEvent;
 
-                                let event = self.player_combat.calculate_full_damage(
-                                    npc_defense, npc_element, npc_weakness,
-                                );
-                                let result = npc_manager.damage_npc(
-                                    npc_id, event.final_amount, event.element, event.attack_type,
-                                );
+        // The survival arena is no place for them
+        if self.survival.is_some() {
+            return;
+        }
 
-                                self.damage_numbers.push(DamageNumber {
-                                    position: npc_pos + Vec3::Y * 1.5,
-                                    amount: event.final_amount,
-                                    is_crit: event.is_crit,
-                                    timer: 1.0,
-                                });
+        let now = self.play_time;
+        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+        let mut events = Vec::new();
+        if self.dungeon.is_none() {
+            let era = infinite_game::Era::for_year(self.timeline.active_year);
+            events.extend(self.world_bosses.update(now, player_pos, era));
+        }
+        let npc = self.world_bosses.active().map(|boss| boss.npc);
+        if let (Some(npc), Some(npc_manager)) = (npc, &self.npc_manager) {
+            let seen = npc_manager.combat_stats.get(&npc).zip(npc_manager.get(npc)).map(|(stats, n)| (stats.current_hp, n.position));
+            events.extend(self.world_bosses.observe(now, seen, player_pos));
+        }
 
-                                if result.defeated {
-                                    let npc_level = npc_manager.npc_level(npc_id);
-                                    let xp = infinite_game::player::stats::xp_for_enemy(
-                                        npc_level, infinite_game::player::stats::EnemyType::Normal,
-                                    );
-                                    let levels_gained = self.player_combat.add_xp(xp);
-                                    for new_level in levels_gained {
-                                        if let Some(growth) = &self.archetype_growth {
-                                            self.player_combat.apply_level_up(growth);
-                                        }
-                                        self.level_up_notification = Some((new_level, 3.0));
-                                    }
-                                    let gold_reward = match result.role {
-                                        infinite_game::NpcRole::Guard => 25 * npc_level as u64,
-                                        infinite_game::NpcRole::Shopkeeper => 50 * npc_level as u64,
-                                        infinite_game::NpcRole::Villager => 2 * npc_level as u64,
-                                        infinite_game::NpcRole::QuestGiver => 15 * npc_level as u64,
-                                        infinite_game::NpcRole::Enemy => 10 * npc_level as u64,
-                                    };
-                                    self.player_combat.gold += gold_reward;
-                                    if result.was_friendly {
-                                        self.notification_text = Some(format!("You murdered a {}!  +{} Gold", result.role.name(), gold_reward));
-                                    } else {
-                                        self.notification_text = Some(format!("+{} XP  +{} Gold", xp, gold_reward));
-                                    }
-                                    self.notification_timer = 1.5;
-                                }
-                            }
-                        }
+        for event in events {
+            match event {
+                WorldBossEvent::Announced(announced) => {
+                    let distance = announced.position.with_y(0.0).distance(player_pos.with_y(0.0));
+                    self.notification_text = Some(format!(
+                        "The {} will rise {:.0} m from here in {:.0} minutes! It's marked on your map.",
+                        announced.kind.name(),
+                        distance,
+                        (announced.time_left(now) / 60.0).ceil(),
+                    ));
+                    self.notification_timer = 4.0;
+                }
+                WorldBossEvent::Spawn(announced) => self.spawn_world_boss(announced),
+                WorldBossEvent::PhaseChanged(_) => self.enter_world_boss_phase(),
+                WorldBossEvent::Defeated { kind, credited } => {
+                    if credited.contains(&infinite_game::npc::world_boss::LOCAL_PARTICIPANT) {
+                        self.reward_world_boss(kind);
+                    } else {
+                        self.notification_text = Some(format!("The {} has fallen, but you played no part in it", kind.name()));
+                        self.notification_timer = 3.0;
                     }
+                }
+                WorldBossEvent::Departed(kind) => {
+                    self.notification_text = Some(format!("The {} has moved on", kind.name()));
+                    self.notification_timer = 3.0;
+                }
+            }
+        }
+    }
 
-                    // --- Skill slots (1-4 keys) ---
-                    for (slot_idx, action) in [
-                        InputAction::Skill1, InputAction::Skill2,
-                        InputAction::Skill3, InputAction::Skill4,
-                    ].iter().enumerate() {
-                        if self.input_handler.state.is_just_pressed(*action) {
-                            // Check if skill exists and get its cost
-                            let skill_info = self.player_combat.skill_slots.get(slot_idx)
-                                .and_then(|slot| {
-                                    if let Some(infinite_game::combat::skill::Skill::Active(ref active)) = slot.skill {
-                                        Some((active.cost, active.base_damage * active.damage_multiplier, active.element))
-                                    } else {
-                                        None
-                                    }
-                                });
+    /// Bring the announced world boss into the world
+    fn spawn_world_boss(&mut self, announced: infinite_game::WorldBossAnnouncement) {
+        let Some(npc_manager) = &mut self.npc_manager else {
+            return;
+        };
+        let mut position = announced.position;
+        if let Some(chunk_manager) = &self.chunk_manager {
+            position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
+        }
+        let stats = announced.kind.stats();
+        let id = npc_manager.spawn_custom(announced.kind.npc_data(position), position, stats.clone(), true);
+        self.world_bosses.begin(&announced, id, position, &stats, self.play_time);
+        self.notification_text = Some(format!("The {} has risen!", announced.kind.name()));
+        self.notification_timer = 3.0;
+    }
 
-                            if let Some((mana_cost, skill_damage, skill_element)) = skill_info {
-                                if self.player_combat.stats.current_mana >= mana_cost {
-                                    if self.player_combat.try_use_skill(slot_idx) {
-                                        self.player_combat.stats.use_mana(mana_cost);
+    /// Harden the world boss for the phase it just entered and bring in the
+    /// help it calls for
+    fn enter_world_boss_phase(&mut self) {
+        let (Some(boss), Some(npc_manager)) = (self.world_bosses.active(), &mut self.npc_manager) else {
+            return;
+        };
+        if let Some(stats) = npc_manager.combat_stats.get_mut(&boss.npc) {
+            boss.apply_phase(stats);
+        }
+        let phase = boss.phase();
+        for i in 0..phase.summons {
+            let angle = i as f32 / phase.summons as f32 * std::f32::consts::TAU;
+            let mut position = boss.position + Vec3::new(angle.cos(), 0.0, angle.sin()) * 4.0;
+            if let Some(chunk_manager) = &self.chunk_manager {
+                position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
+            }
+            let (data, stats) = boss.kind.summon(position);
+            let id = npc_manager.spawn_custom(data, position, stats, true);
+            npc_manager.provoke_npc(id);
+        }
+        self.notification_text = Some(if phase.summons > 0 {
+            format!("The {} grows {} and calls for aid!", boss.kind.name(), phase.name.to_lowercase())
+        } else {
+            format!("The {} grows {}!", boss.kind.name(), phase.name.to_lowercase())
+        });
+        self.notification_timer = 2.5;
+    }
 
-                                        // Apply skill damage to nearest enemy in range
-                                        let skill_range = 10.0_f32;
-                                        if let Some(npc_manager) = &mut self.npc_manager {
-                                            if let Some((npc_id, npc_pos, _)) = find_target(npc_manager, skill_range) {
-                                                let npc_defense = npc_manager.combat_stats.get(&npc_id)
-                                                    .map(|s| s.defense).unwrap_or(0.0);
-                                                let damage = (skill_damage - npc_defense * 0.5).max(1.0);
-                                                let result = npc_manager.damage_npc(
+    /// Pay out a world boss the player helped defeat: its trophy, gold and
+    /// XP, and the achievement flag
+    fn reward_world_boss(&mut self, kind: infinite_game::WorldBossKind) {
+        let trophy = kind.trophy();
+        let trophy_name = trophy.name.clone();
+        let kept = self.player_combat.inventory.add_item(trophy).is_ok();
+        let gold = infinite_game::npc::world_boss::WORLD_BOSS_GOLD;
+        self.player_combat.gold += gold;
+        self.play_stats.record(infinite_game::StatEvent::GoldEarned(gold));
+        self.play_stats.record(infinite_game::StatEvent::WorldBossDefeated);
+        self.speedrun_events.push(infinite_game::RunEvent::WorldBossDefeated(kind));
+        let xp = infinite_game::player::stats::xp_for_enemy(
+            self.player_combat.progression.level, infinite_game::player::stats::EnemyType::Boss,
+        );
+        for new_level in self.player_combat.add_xp(xp) {
+            if let Some(growth) = &self.archetype_growth {
+                self.player_combat.apply_level_up(growth);
+            }
+            self.level_up_notification = Some((new_level, 3.0));
+        }
+        self.world_flags.set(infinite_game::npc::world_boss::ACHIEVEMENT_NAMESPACE, kind.achievement_key(), true);
+        self.autosaver.request(AutosaveTrigger::BossDefeated);
+        self.notification_text = Some(if kept {
+            format!("The {} is defeated!  +{} XP  +{} Gold  Got {}", kind.name(), xp, gold, trophy_name)
+        } else {
+            format!("The {} is defeated!  +{} XP  +{} Gold  No room for the {}", kind.name(), xp, gold, trophy_name)
+        });
+        self.notification_timer = 4.0;
+    }
+
+    /// Call off the world boss (loading, time travel) and start its
+    /// schedule over
+    fn dismiss_world_boss(&mut self) {
+        let npc = self.world_bosses.reset(self.play_time);
+        if let (Some(npc), Some(npc_manager)) = (npc, &mut self.npc_manager) {
+            npc_manager.despawn(npc);
+        }
+    }
+
+    /// A hostile temporal anomaly tears open a few metres from `near`
+    fn spawn_anomaly(&mut self, near: Vec3) {
+        let Some(npc_manager) = &mut self.npc_manager else {
+            return;
+        };
+        let angle = self.rng.stream(infinite_core::RngStream::Npc).range_f32(0.0, std::f32::consts::TAU);
+        let mut position = near + Vec3::new(angle.cos(), 0.0, angle.sin()) * PARADOX_ANOMALY_DISTANCE;
+        if let Some(chunk_manager) = &self.chunk_manager {
+            position.y = chunk_manager.height_at(position.x, position.z) + 0.9;
+        }
+        let data = infinite_game::npc::NpcData {
+            name: "Temporal Anomaly".to_string(),
+            role: infinite_game::NpcRole::Enemy,
+            faction: infinite_game::NpcFaction::Hostile,
+            home_position: position,
+            wander_radius: 6.0,
+            interaction_radius: 0.0,
+            color: [0.6, 0.3, 1.0, 1.0],
+            server_character_id: None,
+        };
+        let stats = infinite_game::npc::combat::CombatStats::elemental_enemy(infinite_game::Element::Void);
+        let id = npc_manager.spawn_custom(data, position, stats, true);
+        npc_manager.provoke_npc(id);
+        self.notification_text = Some("A temporal anomaly tears open nearby!".to_string());
+        self.notification_timer = 2.5;
+    }
+
+    /// Deal a trap's damage and status to whoever set it off. Enemies lured
+    /// into traps still count as the player's kills.
+    fn apply_trap_trigger(&mut self, trigger: infinite_game::TrapTrigger) {
+        match trigger.target {
+            infinite_game::TrapTarget::Player => {
+                let dealt = self.player_combat.take_elemental_damage(trigger.damage, trigger.element);
+                if let Some(effect) = trigger.status {
+                    self.player_combat.status_manager.apply(effect);
+                }
+                if dealt > 0.0 {
+                    self.combat_log.record_taken(dealt, trigger.element);
+                }
+                self.show_player_number(infinite_game::DamageKind::Incoming, dealt);
+                self.notification_text = Some(match trigger.curse {
+                    Some(curse) if self.player_combat.curses.afflict(curse) => {
+                        format!("{}! -{:.0} HP. You've been cursed with {}!", trigger.kind.name(), dealt, curse.name())
+                    }
+                    _ => format!("{}! -{:.0} HP", trigger.kind.name(), dealt),
+                });
+                self.notification_timer = 2.0;
+            }
+            infinite_game::TrapTarget::Npc(npc_id) => {
+                self.damage_npc_indirect(npc_id, trigger.damage, trigger.element, trigger.kind.name());
+            }
+        }
+    }
+
+    /// Float a number over the player's head
+    fn show_player_number(&mut self, kind: infinite_game::DamageKind, amount: f32) {
+        if let Some(player) = &self.player {
+            self.damage_numbers.push(infinite_game::PLAYER_TARGET, kind, player.position() + Vec3::Y * 2.0, amount, false);
+        }
+    }
+
+    /// Damage an NPC with something other than a weapon or skill (traps,
+    /// bombs). Kills still count as the player's.
+    fn damage_npc_indirect(&mut self, npc_id: NpcId, damage: f32, element: infinite_game::Element, cause: &str) {
+        let habitat = if self.dungeon.is_some() { "Dungeon" } else { self.player_biome().name() };
+        let Some(npc_manager) = &mut self.npc_manager else {
+            return;
+        };
+        let Some(npc) = npc_manager.get(npc_id) else {
+            return;
+        };
+        let (name, position) = (npc.name().to_string(), npc.position);
+        let result = npc_manager.damage_npc(npc_id, damage, element, infinite_game::AttackType::Heavy);
+        self.damage_numbers.push(npc_id.0, infinite_game::DamageKind::Outgoing, position + Vec3::Y * 2.0, damage, false);
+        if !result.defeated {
+            return;
+        }
+        self.combat_log.record_kill(result.role.name());
+        let xp = (infinite_game::player::stats::xp_for_enemy(
+            npc_manager.npc_level(npc_id), infinite_game::player::stats::EnemyType::Normal,
+        ) as f32 * result.reward_multiplier) as u64;
+        for new_level in self.player_combat.add_xp(xp) {
+            if let Some(growth) = &self.archetype_growth {
+                self.player_combat.apply_level_up(growth);
+            }
+            self.level_up_notification = Some((new_level, 3.0));
+        }
+        if let Some(kill) = &result.kill {
+            self.play_stats.record(infinite_game::StatEvent::EnemyDefeated);
+            self.speedrun_events.push(infinite_game::RunEvent::EnemyDefeated);
+            self.bestiary.record_kill(kill, self.timeline.active_year, habitat);
+        }
+        self.notification_text = Some(format!("{} fell to the {}! +{} XP", name, cause, xp));
+        self.notification_timer = 2.5;
+    }
+
+    /// Use the consumable at `inventory_index` (inventory Use button or hotbar)
+    fn use_consumable(&mut self, inventory_index: usize) {
+        let Some(item) = self.player_combat.inventory.get(inventory_index) else {
+            return;
+        };
+        let item_name = item.name.clone();
+        let is_health_potion = item_name.to_lowercase().contains("health");
+        let is_throwable = infinite_game::ThrowableKind::from_item_id(item.id).is_some();
+        let is_repair_kit = item.id == infinite_game::combat::REPAIR_KIT_ITEM_ID;
+        let is_salts = item.id == infinite_game::combat::PURIFYING_SALTS_ITEM_ID;
+        let food = infinite_game::cooking::recipe_for_item(item.id);
+        let brewed = infinite_game::alchemy::potion_from_item(item.id);
+        let coating = infinite_game::CoatingKind::from_item(item);
+        if is_health_potion {
+            let year = self.timeline.active_year;
+            self.paradox.on_item_used(item, year, &self.settings.gameplay.paradox);
+        }
+
+        if is_health_potion {
+            let before = self.player_combat.stats.current_hp;
+            self.player_combat.stats.heal(30.0);
+            let healed = self.player_combat.stats.current_hp - before;
+            self.show_player_number(infinite_game::DamageKind::Healing, healed);
+            self.player_combat.inventory.remove_item_stack(inventory_index, 1);
+            self.notification_text = Some(format!("Used {}", item_name));
+            self.notification_timer = 1.5;
+            // Green healing flash (re-use damage flash with positive indicator)
+            self.player_combat.damage_flash_timer = 0.3;
+        } else if let Some(recipe) = food {
+            let before = self.player_combat.stats.current_hp;
+            let heal = self.player_combat.food.eat(recipe);
+            self.player_combat.stats.heal(heal);
+            let healed = self.player_combat.stats.current_hp - before;
+            if healed > 0.0 {
+                self.show_player_number(infinite_game::DamageKind::Healing, healed);
+            }
+            self.player_combat.inventory.remove_item_stack(inventory_index, 1);
+            self.notification_text = Some(format!("Ate {}", item_name));
+            self.notification_timer = 1.5;
+        } else if let Some((effects, potency)) = brewed {
+            let healed = infinite_game::alchemy::drink_potion(&mut self.player_combat, &effects, potency);
+            if healed > 0.0 {
+                self.show_player_number(infinite_game::DamageKind::Healing, healed);
+            }
+            self.player_combat.inventory.remove_item_stack(inventory_index, 1);
+            self.notification_text = Some(format!("Drank {}", item_name));
+            self.notification_timer = 1.5;
+        } else if let Some(kind) = coating {
+            if self.player_combat.apply_coating(kind) {
+                self.player_combat.inventory.remove_item_stack(inventory_index, 1);
+                self.notification_text = Some(format!("Applied {} to your weapon", item_name));
+            } else {
+                self.notification_text = Some("Equip a weapon to coat it".to_string());
+            }
+            self.notification_timer = 1.5;
+        } else if is_repair_kit {
+            if self.player_combat.equipment.restore_all(infinite_game::combat::REPAIR_KIT_RESTORE) {
+                self.player_combat.inventory.remove_item_stack(inventory_index, 1);
+                self.notification_text = Some("Patched up your gear".to_string());
+            } else {
+                self.notification_text = Some("Your gear is in good shape".to_string());
+            }
+            self.notification_timer = 1.5;
+        } else if is_salts {
+            if self.player_combat.curses.cleanse() > 0 {
+                self.player_combat.inventory.remove_item_stack(inventory_index, 1);
+                self.notification_text = Some("The salts burn away your curses".to_string());
+            } else {
+                self.notification_text = Some("You aren't cursed".to_string());
+            }
+            self.notification_timer = 1.5;
+        } else if is_throwable {
+            self.notification_text = Some(format!("Bind the {} to the hotbar (5-8) and hold its key to throw", item_name));
+            self.notification_timer = 2.5;
+        } else {
+            self.notification_text = Some("Cannot use this item.".to_string());
+            self.notification_timer = 1.5;
+        }
+    }
+
+    /// Hotbar keys: a potion is used as soon as its key is pressed, a
+    /// throwable starts aiming and is thrown when the key is released
+    fn update_hotbar(&mut self) {
+        if self.throw_aim.is_some() {
+            return;
+        }
+        for (slot, action) in HOTBAR_ACTIONS.into_iter().enumerate() {
+            if !self.input_handler.state.is_just_pressed(action) {
+                continue;
+            }
+            let Some(index) = self.hotbar.find(slot, &self.player_combat.inventory) else {
+                self.notification_text = Some(match self.hotbar.get(slot) {
+                    Some(_) => format!("Hotbar slot {} is used up", slot + 5),
+                    None => format!("Hotbar slot {} is empty", slot + 5),
+                });
+                self.notification_timer = 1.5;
+                continue;
+            };
+            let id = self.player_combat.inventory.items[index].id;
+            if infinite_game::ThrowableKind::from_item_id(id).is_some() {
+                self.throw_aim = Some((slot, 0.0));
+            } else {
+                self.use_consumable(index);
+            }
+            return;
+        }
+    }
+
+    /// Wind up and preview the throw being aimed, throw it on release, and
+    /// fly thrown items, setting them off where they land
+    fn update_throwables(&mut self, delta: f32) {
+        let (Some(physics), Some(player), Some(camera)) = (&self.physics_world, &self.player, &self.camera) else {
+            return;
+        };
+        let chunk_manager = self.chunk_manager.as_ref();
+        let dungeon = self.dungeon.as_ref();
+        let exclude = player.character.collider_handle;
+        // Thrown items stop at colliders, or at the ground if it has none
+        let hit_test = |from: Vec3, to: Vec3| -> Option<Vec3> {
+            if let Some(hit) = infinite_game::segment_hit(physics, from, to, exclude) {
+                return Some(hit);
+            }
+            let ground = dungeon
+                .and_then(|d| d.floor_height_at(to.x, to.z))
+                .or_else(|| chunk_manager.map(|cm| cm.height_at(to.x, to.z)))?;
+            (to.y <= ground).then(|| to.with_y(ground))
+        };
+
+        if let Some((slot, held)) = self.throw_aim {
+            let origin = player.eye_position() + camera.forward() * 0.5;
+            let charge = held / infinite_game::FULL_CHARGE_TIME;
+            let velocity = infinite_game::throw_velocity(camera.forward(), charge);
+            if self.input_handler.state.is_held(HOTBAR_ACTIONS[slot]) {
+                self.throw_aim = Some((slot, held + delta));
+                self.throw_preview = Some(infinite_game::predict_arc(origin, velocity, hit_test));
+            } else {
+                self.throw_aim = None;
+                self.throw_preview = None;
+                let thrown = self.hotbar.find(slot, &self.player_combat.inventory).and_then(|index| {
+                    infinite_game::ThrowableKind::from_item_id(self.player_combat.inventory.items[index].id)
+                        .map(|kind| (index, kind))
+                });
+                if let Some((index, kind)) = thrown {
+                    let year = self.timeline.active_year;
+                    self.paradox.on_item_used(&self.player_combat.inventory.items[index], year, &self.settings.gameplay.paradox);
+                    self.player_combat.inventory.remove_item_stack(index, 1);
+                    self.throwables.throw(kind, origin, velocity);
+                }
+            }
+        }
+
+        let impacts = self.throwables.update(delta, hit_test);
+        for impact in impacts {
+            self.apply_throwable_impact(impact);
+        }
+    }
+
+    /// Set off a landed throwable. Bombs hurt everything in the blast, the
+    /// player included; lures send nearby enemies to investigate. Smoke
+    /// clouds are left behind by the throwables themselves.
+    fn apply_throwable_impact(&mut self, impact: infinite_game::Impact) {
+        let kind = impact.kind;
+        if let (Some(field), Some(physics)) = (kind.blast_field(impact.position), &mut self.physics_world) {
+            physics.add_force_field(field);
+        }
+        match kind {
+            infinite_game::ThrowableKind::Bomb => {
+                if let Some(player) = &self.player {
+                    let damage = kind.blast_damage((player.position() - impact.position).length());
+                    if damage > 0.0 {
+                        let dealt = self.player_combat.take_elemental_damage(damage, kind.element());
+                        if dealt > 0.0 {
+                            self.combat_log.record_taken(dealt, kind.element());
+                        }
+                        self.show_player_number(infinite_game::DamageKind::Incoming, dealt);
+                    }
+                }
+                let caught: Vec<(NpcId, f32)> = self.npc_manager.as_ref()
+                    .map(|npc_manager| {
+                        npc_manager.npcs_iter()
+                            .filter(|npc| npc_manager.combat_stats.contains_key(&npc.id))
+                            .map(|npc| (npc.id, kind.blast_damage((npc.position - impact.position).length())))
+                            .filter(|(_, damage)| *damage > 0.0)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                for (npc_id, damage) in caught {
+                    if let Some(npc_manager) = &mut self.npc_manager {
+                        npc_manager.provoke_npc(npc_id);
+                    }
+                    self.damage_npc_indirect(npc_id, damage, kind.element(), kind.name());
+                }
+                self.damage_bridges(impact.position, |distance| kind.blast_damage(distance));
+            }
+            infinite_game::ThrowableKind::SmokeBomb => {}
+            infinite_game::ThrowableKind::NoiseLure => {
+                let heard = self.npc_manager.as_mut()
+                    .map(|npc_manager| npc_manager.hear_noise(impact.position, kind.radius()))
+                    .unwrap_or(0);
+                if heard > 0 {
+                    self.notification_text = Some(format!("{} heard the lure and came to look", heard));
+                    self.notification_timer = 2.0;
+                }
+            }
+        }
+    }
+
+    /// Damage bridges near a blast. `damage` gives the damage at a distance
+    /// from it; bridges that collapse are recorded in the world flags.
+    fn damage_bridges(&mut self, position: Vec3, damage: impl Fn(f32) -> f32) {
+        let Some(physics) = &mut self.physics_world else {
+            return;
+        };
+        let mut collapsed = Vec::new();
+        for (id, instance) in &mut self.bridges {
+            let amount = damage(instance.bridge.distance_to(position));
+            if instance.damage(amount, physics) {
+                collapsed.push((*id, instance.bridge.name.clone()));
+            }
+        }
+        if collapsed.is_empty() {
+            return;
+        }
+        physics.update_query_pipeline();
+        for (id, name) in collapsed {
+            self.world_flags.set(BRIDGE_FLAGS, &id.to_string(), true);
+            self.notification_text = Some(format!("{} collapsed", name));
+            self.notification_timer = 3.0;
+        }
+    }
+
+    /// Try to disarm a spotted trap; failing sets it off on the player
+    fn disarm_trap(&mut self, id: infinite_game::TrapId) {
+        let dexterity = self.player_combat.effective_stats().dexterity();
+        let Some(kind) = self.traps.get(id).map(|t| t.kind) else {
+            return;
+        };
+        let roll = self.rng.stream(infinite_core::RngStream::Loot).next_f32();
+        match self.traps.disarm(id, dexterity, roll) {
+            Some(infinite_game::DisarmOutcome::Disarmed) => {
+                self.notification_text = Some(format!("Disarmed the {}", kind.name()));
+                self.notification_timer = 2.0;
+            }
+            Some(infinite_game::DisarmOutcome::Triggered(trigger)) => {
+                self.apply_trap_trigger(trigger);
+                let text = self.notification_text.take().unwrap_or_default();
+                self.notification_text = Some(format!("You set off the {}!  {}", kind.name(), text));
+            }
+            None => {}
+        }
+        self.sync_trap_interactables();
+    }
+
+    /// Open a locked door with a key from the key ring, or start picking its
+    /// lock if it has one and the player has a lockpick and the dexterity
+    fn try_unlock_door(&mut self, id: infinite_game::InteractableId, lock: Option<infinite_game::LockTier>) {
+        if let Some(key) = self.key_ring.key_for(id) {
+            let name = key.name.clone();
+            self.interaction_system.unlock_door(id);
+            self.notification_text = Some(format!("Unlocked with the {}", name));
+            self.notification_timer = 2.0;
+            return;
+        }
+
+        let Some(lock) = lock else {
+            self.notification_text = Some("It's locked. It must open from somewhere else".to_string());
+            self.notification_timer = 2.0;
+            return;
+        };
+        let has_lockpick = self.player_combat.inventory.items.iter()
+            .any(|item| item.id == infinite_game::LOCKPICK_ITEM_ID);
+        let dexterity = self.player_combat.effective_stats().dexterity();
+        if !has_lockpick {
+            self.notification_text = Some(format!("{} lock. You need its key or a lockpick", lock.name()));
+        } else if !infinite_game::LockpickSession::can_attempt(lock, dexterity) {
+            self.notification_text = Some(format!(
+                "{} lock. Too intricate to pick (needs {:.0} dexterity, you have {:.0})",
+                lock.name(),
+                lock.required_dexterity(),
+                dexterity,
+            ));
+        } else {
+            let seed = self.rng.stream(infinite_core::RngStream::Loot).next_u64();
+            self.lockpicking = Some(infinite_game::LockpickSession::new(id, lock, dexterity, seed));
+            self.notification_text = Some("Picking the lock... E: Set pin | ESC: Stop".to_string());
+        }
+        self.notification_timer = 2.5;
+    }
+
+    /// Advance the lockpicking minigame: sweep the pick, set pins on E, and
+    /// break a lockpick at full tension. Walking away ends the attempt.
+    fn update_lockpicking(&mut self, delta: f32) {
+        use infinite_game::PickAttempt;
+
+        let Some(session) = &mut self.lockpicking else {
+            return;
+        };
+        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+        let near_door = self.interaction_system.position_of(session.door)
+            .is_some_and(|pos| (pos - player_pos).length() < 4.0);
+        if !near_door {
+            self.lockpicking = None;
+            return;
+        }
+
+        let mut outcome = session.update(delta);
+        if outcome.is_none() && self.input_handler.state.is_just_pressed(InputAction::Interact) {
+            outcome = Some(session.attempt());
+        }
+        let door = session.door;
+        match outcome {
+            None | Some(PickAttempt::PinSet) => {}
+            Some(PickAttempt::Slipped) => {
+                self.notification_text = Some("The pick slips".to_string());
+                self.notification_timer = 1.0;
+            }
+            Some(PickAttempt::Opened) => {
+                self.lockpicking = None;
+                self.interaction_system.unlock_door(door);
+                self.notification_text = Some("Lock picked!".to_string());
+                self.notification_timer = 2.0;
+            }
+            Some(PickAttempt::Broke) => {
+                self.lockpicking = None;
+                let inventory = &mut self.player_combat.inventory;
+                if let Some(index) = inventory.items.iter().position(|item| item.id == infinite_game::LOCKPICK_ITEM_ID) {
+                    inventory.remove_item_stack(index, 1);
+                }
+                self.notification_text = Some("Your lockpick broke".to_string());
+                self.notification_timer = 2.0;
+            }
+        }
+    }
+
+    /// Dig at a treasure map's mark: needs a shovel, the right spot and,
+    /// for some maps, the era they were drawn in
+    fn dig_treasure(&mut self, map_seed: u64) {
+        let Some((index, map)) = self.player_combat.inventory.items.iter().enumerate()
+            .find_map(|(i, item)| item.treasure_map.as_ref().filter(|m| m.seed == map_seed).map(|m| (i, m.clone())))
+        else {
+            return;
+        };
+        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+        let has_shovel = self.player_combat.inventory.items.iter()
+            .any(|item| item.id == infinite_game::combat::SHOVEL_ITEM_ID);
+        if let Err(e) = map.can_dig(player_pos, self.timeline.active_year, has_shovel) {
+            self.notification_text = Some(e.to_string());
+            self.notification_timer = 2.5;
+            return;
+        }
+
+        self.player_combat.inventory.remove_item(index);
+        let loot = infinite_game::combat::treasure::buried_loot(&map);
+        self.player_combat.gold += loot.gold;
+        self.play_stats.record(infinite_game::StatEvent::GoldEarned(loot.gold));
+        let mut found = vec![format!("+{} Gold", loot.gold)];
+        for item in loot.items {
+            found.push(item.name.clone());
+            if let Err(item) = self.player_combat.inventory.add_item(item) {
+                self.collected_items.push(item.name);
+            }
+        }
+        // The hole stays in the world
+        if let (Some(chunk_manager), Some(physics)) = (&mut self.chunk_manager, &mut self.physics_world) {
+            chunk_manager.edit_terrain(infinite_world::TerrainEdit::new(player_pos, 1.6, -0.7), physics);
+        }
+        self.tutorials.handle(infinite_game::TutorialEvent::TreasureDug);
+        self.notification_text = Some(format!("Dug up a buried chest!  {}", found.join(", ")));
+        self.notification_timer = 4.0;
+        self.sync_dig_spots();
+    }
+
+    /// Build an entrance's dungeon interior, fill it and move the player inside
+    fn enter_dungeon(&mut self, entrance: DungeonEntrance) {
+        let Some(mut dungeon) = self.build_dungeon(entrance) else {
+            return;
+        };
+        // The boss room stays shut until its guards are dealt with
+        if let Some(physics) = &mut self.physics_world {
+            dungeon.close_boss_gate(&self.dungeon_config, physics);
+        }
+        // A trap halfway along each corridor
+        let trap_kinds = infinite_game::TrapKind::for_era(self.timeline.active_year, self.timeline.present_year);
+        for (index, corridor) in dungeon.layout.corridors.iter().enumerate() {
+            let from = dungeon.layout.rooms[corridor.from].center;
+            let to = dungeon.layout.rooms[corridor.to].center;
+            let kind = trap_kinds[(dungeon.entrance.seed as usize).wrapping_add(index) % trap_kinds.len()];
+            self.traps.add(kind, (from + to) * 0.5);
+        }
+        self.interaction_system.add_container(
+            dungeon.layout.chest + Vec3::Y * 0.5,
+            vec!["Dungeon Relic".to_string(), "Ancient Coin".to_string(), "Health Potion".to_string()],
+        );
+
+        if let Some(npc_manager) = &mut self.npc_manager {
+            for pack in &dungeon.layout.packs {
+                for position in &pack.positions {
+                    let position = *position + Vec3::Y * 0.9;
+                    let data = infinite_game::npc::NpcData {
+                        name: if pack.boss { "Dungeon Warden" } else { "Dungeon Lurker" }.to_string(),
+                        role: infinite_game::NpcRole::Enemy,
+                        faction: infinite_game::NpcFaction::Hostile,
+                        home_position: position,
+                        wander_radius: 4.0,
+                        interaction_radius: 0.0,
+                        color: infinite_game::NpcRole::Enemy.color(),
+                        server_character_id: None,
+                    };
+                    let stats = if pack.boss {
+                        infinite_game::npc::combat::CombatStats::boss(infinite_game::Element::Physical)
+                    } else {
+                        infinite_game::npc::combat::CombatStats::default_enemy()
+                    };
+                    let id = npc_manager.spawn_custom(data, position, stats, true);
+                    if pack.boss {
+                        self.dungeon_bosses.push(id);
+                    }
+                    self.dungeon_enemies.push(id);
+                }
+            }
+        }
+
+        self.dungeon = Some(dungeon);
+        self.notification_text = Some("You descend into the dungeon...".to_string());
+        self.notification_timer = 2.5;
+    }
+
+    /// Build a dungeon interior, put the player at its spawn and open its
+    /// way out. None if the player is already in one.
+    fn build_dungeon(&mut self, entrance: DungeonEntrance) -> Option<DungeonInstance> {
+        if self.dungeon.is_some() {
+            return None;
+        }
+        let (Some(physics), Some(player)) = (&mut self.physics_world, &mut self.player) else {
+            return None;
+        };
+        let dungeon = DungeonInstance::build(entrance, &self.dungeon_config, physics);
+        physics.update_query_pipeline();
+        player.teleport(physics, dungeon.layout.spawn + Vec3::Y);
+
+        for portal in &dungeon.layout.return_portals {
+            self.interaction_system.add(Interactable::dungeon_exit(*portal + Vec3::Y));
+        }
+        Some(dungeon)
+    }
+
+    /// Start a rift run from the gate at `gate`, with the ledger's
+    /// attunements, and send the player into its first arena
+    fn start_rift_run(&mut self, gate: Vec3) {
+        if self.dungeon.is_some() || self.rift.is_some() {
+            return;
+        }
+        let seed = self.rng.stream(infinite_core::RngStream::Npc).next_u64();
+        self.rift = Some(infinite_game::RiftRun::new(seed, &self.rift_ledger, &mut self.player_combat));
+        self.rift_gate = gate;
+        self.enter_rift_arena();
+    }
+
+    /// Build the rift run's current arena as a dungeon interior and fill it
+    /// with the era's enemies
+    fn enter_rift_arena(&mut self) {
+        let Some(arena) = self.rift.as_ref().map(|run| *run.arena()) else {
+            return;
+        };
+        let chunk_size = self.chunk_manager.as_ref().map_or(64.0, |c| c.config.chunk_size);
+        let entrance = DungeonEntrance {
+            chunk: infinite_world::ChunkCoord::from_world_pos(self.rift_gate, chunk_size),
+            position: self.rift_gate,
+            seed: arena.seed,
+        };
+        let Some(dungeon) = self.build_dungeon(entrance) else {
+            return;
+        };
+        if let (Some(run), Some(npc_manager)) = (&mut self.rift, &mut self.npc_manager) {
+            let ids = run
+                .arena_spawns(&dungeon.layout)
+                .into_iter()
+                .map(|(mut position, data, stats)| {
+                    position.y += 0.9;
+                    npc_manager.spawn_custom(data, position, stats, true)
+                })
+                .collect();
+            run.begin_arena(ids);
+        }
+        self.dungeon = Some(dungeon);
+        self.notification_text = Some(format!("Depth {}: the {}", arena.depth, arena.name()));
+        self.notification_timer = 2.5;
+    }
+
+    /// Watch for the rift arena being cleared, and free the cursor for the
+    /// boon choice when it is
+    fn update_rift(&mut self) {
+        let (Some(run), Some(npc_manager)) = (&mut self.rift, &self.npc_manager) else {
+            return;
+        };
+        if let Some(depth) = run.update(|id| npc_manager.get(id).is_some()) {
+            self.notification_text = Some(format!("Arena {} cleared! Choose a boon.", depth));
+            self.notification_timer = 2.5;
+            self.update_cursor_capture(false);
+        }
+    }
+
+    /// Take a boon from the offer and move on to the next arena
+    fn choose_rift_boon(&mut self, index: usize) {
+        let Some(run) = &mut self.rift else {
+            return;
+        };
+        if run.choose(index, &mut self.player_combat).is_err() {
+            return;
+        }
+        self.leave_dungeon();
+        self.enter_rift_arena();
+        self.update_cursor_capture(true);
+    }
+
+    /// The run is over, by death or through an exit portal: clear the arena,
+    /// return to the gate and bank the shards
+    fn end_rift_run(&mut self, died: bool) {
+        let Some(mut run) = self.rift.take() else {
+            return;
+        };
+        if let Some(npc_manager) = &mut self.npc_manager {
+            for id in run.alive() {
+                npc_manager.despawn(*id);
+            }
+        }
+        if died {
+            self.player_combat.respawn();
+            self.play_stats.record(infinite_game::StatEvent::Died);
+        }
+        let outcome = run.end(&mut self.player_combat);
+        self.rift_ledger.bank(outcome);
+        self.leave_dungeon();
+        self.notification_text = Some(format!(
+            "{} after {} arenas - {} shards banked",
+            if died { "The rift casts you out" } else { "You step out of the rift" },
+            outcome.cleared,
+            outcome.shards,
+        ));
+        self.notification_timer = 3.5;
+        self.autosaver.request(AutosaveTrigger::RiftEnded);
+    }
+
+    /// Tear down the dungeon interior and return the player to its entrance
+    fn leave_dungeon(&mut self) {
+        let Some(dungeon) = self.dungeon.take() else {
+            return;
+        };
+        if let Some(npc_manager) = &mut self.npc_manager {
+            for id in self.dungeon_enemies.drain(..) {
+                npc_manager.despawn(id);
+            }
+        }
+        self.dungeon_enemies.clear();
+        self.dungeon_bosses.clear();
+        self.interaction_system.retain(|i| dungeon.floor_height_at(i.position.x, i.position.z).is_none());
+        self.traps.retain(|t| dungeon.floor_height_at(t.position.x, t.position.z).is_none());
+
+        let entrance = dungeon.entrance.position;
+        if let Some(physics) = &mut self.physics_world {
+            dungeon.remove(physics);
+            physics.update_query_pipeline();
+            if let Some(player) = &mut self.player {
+                player.teleport(physics, entrance + Vec3::new(0.0, 1.0, 3.0));
+            }
+        }
+        self.notification_text = Some("You return to the surface".to_string());
+        self.notification_timer = 2.0;
+    }
+
+    /// Scheduler for a new session: the first auto-save is five minutes in
+    fn initial_scheduler() -> infinite_core::Scheduler<ScheduledEvent> {
+        let mut scheduler = infinite_core::Scheduler::new();
+        scheduler.after(infinite_core::Clock::Real, 300.0, ScheduledEvent::AutoSave);
+        scheduler
+    }
+
+    /// (Re)start the auto-save countdown with the configured interval
+    fn schedule_autosave(&mut self) {
+        self.scheduler.cancel_where(|e| *e == ScheduledEvent::AutoSave);
+        let interval = self.settings.gameplay.auto_save_interval as f32;
+        self.scheduler.after(infinite_core::Clock::Real, interval, ScheduledEvent::AutoSave);
+    }
+
+    /// Snapshot the game and write it to the autosave slot in the
+    /// background. Any autosave restarts the timer.
+    fn start_autosave(&mut self, trigger: AutosaveTrigger) {
+        info!("Auto-saving ({:?})", trigger);
+        let data = self.gather_save_data("Autosave");
+        self.flush_world();
+        self.autosaver.mark_saved(self.play_time);
+        self.save_indicator_timer = 1.0;
+        self.save_worker.save(SaveSlot::Autosave, data, |app, result| {
+            if let Err(e) = result {
+                app.error_dialog.report(&save::save_error(save::SAVE_WRITE_FAILED, &e).context("auto-saving"));
+                app.notification_text = Some("Auto-save failed".to_string());
+                app.notification_timer = 2.0;
+            }
+        });
+        self.schedule_autosave();
+    }
+
+    /// Restore game state from save data
+    /// Restore a loaded save, returning how the active mods differ from
+    /// the ones it was made with
+    /// Recompute the cached stat growth from the player's class (saves from
+    /// before classes were recorded fall back to the character's archetype)
+    fn refresh_class_growth(&mut self) {
+        let class = &self.player_combat.progression.class;
+        let primary = class.primary.as_deref().and_then(Archetype::from_key)
+            .or_else(|| self.current_character.as_ref().and_then(|c| c.archetype));
+        let secondary = class.secondary.as_deref().and_then(Archetype::from_key);
+        self.archetype_growth = primary.map(|archetype| archetype.growth_with(secondary));
+    }
+
+    fn restore_from_save(&mut self, data: SaveData) -> Vec<infinite_game::ModMismatch> {
+        let mod_changes = data.mods.as_deref()
+            .map(|saved| infinite_game::mods::compare_mods(saved, &self.mods.records()))
+            .unwrap_or_default();
+        for change in &mod_changes {
+            tracing::warn!("Save made with different mods: {}", change);
+        }
+
+        // Restore the player's look (older saves keep the character's)
+        if let Some(appearance) = data.appearance {
+            if let Some(character) = &mut self.current_character {
+                character.appearance = appearance.clone();
+            }
+            self.player_appearance = appearance;
+            self.player_mesh_dirty = true;
+        }
+
+        // Restore player position
+        if let (Some(player), Some(physics)) = (&mut self.player, &mut self.physics_world) {
+            let pos = Vec3::new(data.player.position[0], data.player.position[1], data.player.position[2]);
+            player.teleport(physics, pos);
+        }
+
+        // Restore camera rotation
+        if let Some(camera) = &mut self.camera {
+            camera.set_yaw(data.player.rotation_yaw);
+            camera.set_pitch(data.player.rotation_pitch);
+        }
+
+        // Restore the timeline's branches (the rest of the save is the active
+        // branch's world state)
+        let timeline = data.timeline.unwrap_or_default();
+        let branch_changed = timeline.branches.active() != self.timeline.active_branch();
+        self.timeline.branches = timeline.branches;
+        self.parked_branches = timeline.parked;
+        self.pending_branch_switch = None;
+        self.history_forked = false;
+
+        // Restore year (if different), reloading the chunks for the branch too
+        if (data.world.active_year != self.timeline.active_year || branch_changed) && !self.time_transitioning {
+            self.time_transition_source = self.timeline.active_year;
+            self.pending_time_transition = Some(data.world.active_year);
+            self.time_transitioning = true;
+            self.time_transition_alpha = 0.0;
+        }
+
+        // Restore time of day
+        self.time_of_day.set_time(data.world.time_of_day);
+        self.time_of_day.day = data.world.day;
+
+        // Restore collected items and play time
+        self.collected_items = data.collected_items;
+        self.play_time = data.play_time_seconds;
+        self.dismiss_world_boss();
+
+        // Restore interaction states
+        self.interaction_system.load_states(data.interactions);
+        if self.collected_items.iter().any(|i| i == GLIDER_ITEM) {
+            self.interaction_system.retain(|i| {
+                !matches!(&i.kind, infinite_game::InteractableKind::Pickup { item_name } if item_name == GLIDER_ITEM)
+            });
+        }
+        self.key_ring = data.key_ring.unwrap_or_default();
+        self.lockpicking = None;
+        self.gathering = None;
+        self.gathering_skills = data.gathering_skills.unwrap_or_default();
+        self.recipe_book = data.recipe_book.unwrap_or_default();
+        self.alchemy_journal = data.alchemy_journal.unwrap_or_default();
+        self.shrine_ledger = data.shrine_ledger.unwrap_or_default();
+        self.rift_ledger = data.rift_ledger.unwrap_or_default();
+        self.speedrun = data.speedrun.filter(|_| self.settings.speedrun.enabled);
+        self.speedrun_events.clear();
+        self.player_combat.food.clear();
+        self.hotbar = data.hotbar.unwrap_or_default();
+        self.paradox = data.paradox.unwrap_or_default();
+        if let Some(snapshot) = &data.rng {
+            self.rng.restore(snapshot);
+        }
+        self.world_flags = data.world_flags.unwrap_or_default();
+        self.throwables.clear();
+        self.throw_aim = None;
+        self.throw_preview = None;
+        let key_ring = &self.key_ring;
+        self.interaction_system.retain(|i| {
+            !matches!(&i.kind, infinite_game::InteractableKind::Key(key) if key_ring.key_for(key.door).is_some())
+        });
+
+        // Restore NPC relationships and who the player has killed
+        self.relationship_manager = RelationshipManager::from_save_data(&data.npc_relationships);
+        if let Some(npc_manager) = &mut self.npc_manager {
+            npc_manager.load_population(data.population.unwrap_or_default());
+        }
+
+        // Restore pending timers (older saves have none)
+        self.scheduler = data.scheduler.unwrap_or_default();
+        if !self.scheduler.is_scheduled(|e| *e == ScheduledEvent::AutoSave) {
+            self.schedule_autosave();
+        }
+
+        // Restore player combat stats and progression
+        if let Some(stats) = data.player_stats {
+            self.player_combat.stats = stats;
+        }
+        if let Some(progression) = data.player_progression {
+            self.player_combat.progression = progression;
+            self.refresh_class_growth();
+        }
+
+        // Restore inventory
+        if let Some(items) = data.inventory {
+            self.player_combat.inventory.items = items;
+        }
+
+        // Restore gold
+        if let Some(gold) = data.gold {
+            self.player_combat.gold = gold;
+        }
+        self.combat_log.load_save_data(data.combat_stats.unwrap_or_default());
+        self.bestiary = data.bestiary.unwrap_or_default();
+        self.locations = data.locations.unwrap_or_default();
+        self.economy = data.economy.unwrap_or_default();
+        self.tutorials.load_save_data(data.tutorials.unwrap_or_default());
+        self.play_stats = data.play_stats.unwrap_or_default();
+        mod_changes
+    }
+
+    /// Tell the player a save loaded, and whether its mods have changed
+    fn notify_loaded(&mut self, mod_changes: &[infinite_game::ModMismatch]) {
+        if let Some(first) = mod_changes.first() {
+            let more = match mod_changes.len() {
+                1 => String::new(),
+                n => format!(" (+{} more)", n - 1),
+            };
+            self.notification_text = Some(format!("Game Loaded - mods changed: {}{}", first, more));
+            self.notification_timer = 6.0;
+        } else {
+            self.notification_text = Some("Game Loaded".to_string());
+            self.notification_timer = 2.0;
+        }
+    }
+
+    /// Quick load the game (F9)
+    fn do_quickload(&mut self) {
+        self.save_worker.load(SaveSlot::Quicksave, |app, result| match result {
+            Ok(data) => {
+                let mod_changes = app.restore_from_save(data);
+                app.notify_loaded(&mod_changes);
+                info!("Game loaded successfully");
+            }
+            Err(e) => {
+                app.notification_text = Some("Load failed".to_string());
+                app.notification_timer = 3.0;
+                app.error_dialog.report(&save::save_error(save::SAVE_READ_FAILED, &e));
+            }
+        });
+    }
+
+    /// Write pending chunk changes (harvests, terrain edits, ...) to disk
+    fn flush_world(&mut self) {
+        if let Some(chunk_manager) = &mut self.chunk_manager {
+            if let Err(e) = chunk_manager.flush_store() {
+                let err = InfiniteError::from(infinite_world::WorldError::from(e)).context("saving world changes");
+                self.error_dialog.report(&err);
+            }
+        }
+    }
+
+    /// Wait for queued saves to reach the disk before quitting
+    fn flush_saves(&mut self) {
+        self.flush_world();
+        for completion in self.save_worker.flush() {
+            completion.run(self);
+        }
+    }
+
+    /// Keep the compiled pipelines for the next start
+    fn save_pipeline_cache(&self) {
+        let Some(render_ctx) = &self.render_ctx else { return };
+        match render_ctx.pipeline_cache.save() {
+            Ok(bytes) => info!(
+                "Saved pipeline cache ({}) to {}",
+                infinite_core::format_bytes(bytes as u64),
+                render_ctx.pipeline_cache.path().display()
+            ),
+            Err(e) => tracing::warn!("Failed to save pipeline cache: {}", e),
+        }
+    }
+
+    /// Push the current audio settings to the audio engine, reopening it if
+    /// the output device changed
+    fn apply_audio_settings(&mut self) {
+        let config = self.settings.audio.to_audio_config();
+        let device_changed = self.audio.as_ref()
+            .map(|a| a.config().output_device != config.output_device)
+            .unwrap_or(true);
+
+        if device_changed {
+            let device = config.output_device.clone().unwrap_or_else(|| "the default output device".to_string());
+            self.audio = match AudioEngine::new(config) {
+                Ok(engine) => Some(engine),
+                Err(e) => {
+                    let err = InfiniteError::from(e).context(format!("starting audio on {}", device));
+                    self.error_dialog.report(&err);
+                    None
+                }
+            };
+        } else if let Some(audio) = &mut self.audio {
+            audio.update_volumes(config);
+        }
+    }
+
+    /// Update cursor capture state
+    fn update_cursor_capture(&mut self, should_capture: bool) {
+        if self.cursor_captured == should_capture {
+            return;
+        }
+
+        if let Some(window) = &self.window {
+            if should_capture {
+                let _ = window.set_cursor_grab(CursorGrabMode::Locked)
+                    .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined));
+                window.set_cursor_visible(false);
+            } else {
+                let _ = window.set_cursor_grab(CursorGrabMode::None);
+                window.set_cursor_visible(true);
+            }
+            self.cursor_captured = should_capture;
+            self.input_handler.set_cursor_captured(should_capture);
+        }
+    }
+
+    fn create_swapchain_and_framebuffers(
+        device: Arc<Device>,
+        surface: Arc<Surface>,
+        window: Arc<Window>,
+        memory_allocator: Arc<StandardMemoryAllocator>,
+    ) -> Result<SwapchainParts> {
+        let surface_capabilities = device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .context("Failed to get surface capabilities")?;
+
+        let image_format = device
+            .physical_device()
+            .surface_formats(&surface, Default::default())
+            .context("Failed to get surface formats")?[0]
+            .0;
+
+        let window_size = window.inner_size();
+
+        let (swapchain, images) = Swapchain::new(
+            device.clone(),
+            surface.clone(),
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count.max(2),
+                image_format,
+                image_extent: [window_size.width, window_size.height],
+                // The scene is blitted in from the scene target
+                image_usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_DST,
+                composite_alpha: surface_capabilities
+                    .supported_composite_alpha
+                    .into_iter()
+                    .next()
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .context("Failed to create swapchain")?;
+
+        // Scene pass: 3D scene with depth, drawn into the scene target at
+        // the render scale
+        let render_pass = vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: image_format,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+                depth: {
+                    format: Format::D32_SFLOAT,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: DontCare,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {depth},
+            }
+        )
+        .context("Failed to create render pass")?;
+
+        // UI pass: overlay at native resolution over the upscaled scene
+        let ui_render_pass = vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: image_format,
+                    samples: 1,
+                    load_op: Load,
+                    store_op: Store,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {},
+            }
+        )
+        .context("Failed to create UI render pass")?;
+
+        let scene_target = SceneTarget::new(
+            memory_allocator,
+            render_pass.clone(),
+            image_format,
+            [window_size.width, window_size.height],
+        )
+        .context("Failed to create scene target")?;
+
+        let framebuffers = images
+            .iter()
+            .map(|image| {
+                let view = ImageView::new_default(image.clone()).unwrap();
+                Framebuffer::new(
+                    ui_render_pass.clone(),
+                    FramebufferCreateInfo {
+                        attachments: vec![view],
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+            })
+            .collect();
+
+        Ok((swapchain, images, render_pass, ui_render_pass, framebuffers, scene_target))
+    }
+
+    /// Rebuild the swapchain and everything sized to it. On failure the old
+    /// one stays and the next frame tries again.
+    fn recreate_swapchain(&mut self) -> Result<(), InfiniteError> {
+        let Some(window) = &self.window else { return Ok(()) };
+        let Some(_surface) = &self.surface else { return Ok(()) };
+        let Some(render_ctx) = &mut self.render_ctx else {
+            return Ok(());
+        };
+
+        let window_size = window.inner_size();
+        if window_size.width == 0 || window_size.height == 0 {
+            return Ok(());
+        }
+
+        let (new_swapchain, new_images) = render_ctx
+            .swapchain
+            .recreate(SwapchainCreateInfo {
+                image_extent: [window_size.width, window_size.height],
+                ..render_ctx.swapchain.create_info()
+            })
+            .map_err(|e| vulkan_error(RENDER_SWAPCHAIN_FAILED, "Failed to recreate swapchain", e))?;
+
+        // Recreate the scene target with the new size
+        let scene_target = SceneTarget::new(
+            render_ctx.memory_allocator.clone(),
+            render_ctx.render_pass.clone(),
+            render_ctx.swapchain.image_format(),
+            [window_size.width, window_size.height],
+        )
+        .map_err(|e| render_error(RENDER_SWAPCHAIN_FAILED, "Failed to recreate scene target", e))?;
+
+        let framebuffers = new_images
+            .iter()
+            .map(|image| {
+                let view = ImageView::new_default(image.clone())
+                    .map_err(|e| vulkan_error(RENDER_SWAPCHAIN_FAILED, "Failed to create swapchain image view", e))?;
+                Framebuffer::new(
+                    render_ctx.ui_render_pass.clone(),
+                    FramebufferCreateInfo {
+                        attachments: vec![view],
+                        ..Default::default()
+                    },
+                )
+                .map_err(|e| vulkan_error(RENDER_SWAPCHAIN_FAILED, "Failed to create framebuffer", e))
+            })
+            .collect::<Result<Vec<_>, InfiniteError>>()?;
+
+        render_ctx.swapchain = new_swapchain;
+        render_ctx.images = new_images;
+        render_ctx.scene_target = scene_target;
+        render_ctx.framebuffers = framebuffers;
+        render_ctx.recreate_swapchain = false;
+        Ok(())
+    }
+
+    /// A frame couldn't be drawn. Frames are skipped until drawing works
+    /// again; a lost device, or failing for too long, rebuilds the renderer.
+    fn on_render_error(&mut self, err: InfiniteError) {
+        self.failed_frames += 1;
+        let device_lost = err.code() == RENDER_DEVICE_LOST;
+        if (device_lost || self.failed_frames >= MAX_FAILED_FRAMES) && !self.rebuild_renderer {
+            self.rebuild_renderer = true;
+            let err = err.with_hint("The graphics driver stopped responding. The renderer was restarted");
+            self.error_dialog.report(&err);
+        } else if self.failed_frames == 1 {
+            // The first failure of a run, not every frame of it
+            self.error_dialog.report(&err);
+        }
+    }
+
+    /// Throw the renderer away and build a new one for the same window,
+    /// then upload the loaded world again
+    fn rebuild_render_context(&mut self, event_loop: &ActiveEventLoop) -> Result<(), InfiniteError> {
+        info!("Rebuilding the renderer");
+        self.save_pipeline_cache();
+        self.gui = None;
+        self.render_ctx = None;
+        self.create_render_context(event_loop)?;
+
+        if let Some(render_ctx) = &mut self.render_ctx {
+            upload_npc_mesh(render_ctx);
+            if let Some(chunk_manager) = &self.chunk_manager {
+                if let Err(e) = upload_chunk_meshes(render_ctx, chunk_manager.loaded_chunks()) {
+                    self.error_dialog.report(&e);
+                }
+            }
+        }
+        self.failed_frames = 0;
+        Ok(())
+    }
+
+    /// Pick a GPU and build the device, swapchain, pipelines and shared
+    /// meshes for the window
+    fn create_render_context(&mut self, event_loop: &ActiveEventLoop) -> Result<(), InfiniteError> {
+        let (Some(window), Some(surface)) = (self.window.clone(), self.surface.clone()) else {
+            return Err(InfiniteError::new(RENDER_INIT_FAILED, Severity::Fatal, "No window to render to"));
+        };
+
+        // Select physical device
+        let device_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+
+        let (physical_device, queue_family_index) = self
+            .instance
+            .enumerate_physical_devices()
+            .map_err(|e| render_error(RENDER_INIT_FAILED, "Failed to enumerate physical devices", e))?
+            .filter(|p| p.supported_extensions().contains(&device_extensions))
+            .filter_map(|p| {
+                p.queue_family_properties()
+                    .iter()
+                    .enumerate()
+                    .position(|(i, q)| {
+                        q.queue_flags.contains(QueueFlags::GRAPHICS)
+                            && p.surface_support(i as u32, &surface).unwrap_or(false)
+                    })
+                    .map(|i| (p, i as u32))
+            })
+            .min_by_key(|(p, _)| match p.properties().device_type {
+                PhysicalDeviceType::DiscreteGpu => 0,
+                PhysicalDeviceType::IntegratedGpu => 1,
+                PhysicalDeviceType::VirtualGpu => 2,
+                PhysicalDeviceType::Cpu => 3,
+                _ => 4,
+            })
+            .ok_or_else(|| {
+                InfiniteError::new(RENDER_INIT_FAILED, Severity::Fatal, "No suitable GPU found")
+                    .with_hint("Infinite needs a Vulkan capable graphics card with up to date drivers")
+            })?;
+
+        info!(
+            "Using GPU: {} ({:?})",
+            physical_device.properties().device_name,
+            physical_device.properties().device_type
+        );
+
+        // Check for ray tracing support
+        let rt_supported = physical_device
+            .supported_extensions()
+            .khr_ray_tracing_pipeline;
+        if rt_supported {
+            info!("Hardware ray tracing supported");
+        } else {
+            info!("Hardware ray tracing NOT supported - will use compute fallback");
+        }
+
+        // Mesh uploads get their own queue on a dedicated transfer family if there is one
+        let transfer_family_index = infinite_render::transfer_queue_family(&physical_device);
+        let mut queue_create_infos = vec![QueueCreateInfo {
+            queue_family_index,
+            ..Default::default()
+        }];
+        if let Some(transfer_family_index) = transfer_family_index {
+            info!("Using dedicated transfer queue family {}", transfer_family_index);
+            queue_create_infos.push(QueueCreateInfo {
+                queue_family_index: transfer_family_index,
+                ..Default::default()
+            });
+        }
+
+        // Create logical device
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos,
+                enabled_extensions: device_extensions,
+                enabled_features: DeviceFeatures {
+                    fill_mode_non_solid: true,
+                    wide_lines: true,
+                    ..DeviceFeatures::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .map_err(|e| vulkan_error(RENDER_INIT_FAILED, "Failed to create logical device", e))?;
+
+        let queue = queues.next().unwrap();
+        let transfer_queue = match transfer_family_index {
+            Some(_) => queues.next().unwrap(),
+            None => queue.clone(),
+        };
+
+        // Create allocators first (needed for depth buffer creation)
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+        let mut mesh_uploader = MeshUploader::new(memory_allocator.clone(), transfer_queue, queue_family_index);
+
+        // Create swapchain and framebuffers (with depth buffer)
+        let (swapchain, images, render_pass, ui_render_pass, framebuffers, scene_target) =
+            Self::create_swapchain_and_framebuffers(
+                device.clone(),
+                surface.clone(),
+                window.clone(),
+                memory_allocator.clone(),
+            )
+            .map_err(|e| {
+                InfiniteError::new(RENDER_INIT_FAILED, Severity::Error, format!("Failed to create swapchain: {:#}", e))
+            })?;
+
+        // Create 3D pipelines, through the cache saved by the last run
+        let mut pipeline_cache = PersistentPipelineCache::load(device.clone(), pipeline_cache_path())
+            .map_err(|e| vulkan_error(RENDER_INIT_FAILED, "Failed to create pipeline cache", e))?;
+        let basic_pipeline = pipeline_cache.create(|cache| create_basic_pipeline(device.clone(), render_pass.clone(), cache));
+        if basic_pipeline.is_none() {
+            tracing::error!("Failed to create basic 3D pipeline!");
+        } else {
+            info!("Basic 3D pipeline created successfully");
+        }
+
+        let sky_pipeline = pipeline_cache.create(|cache| create_sky_pipeline(device.clone(), render_pass.clone(), cache));
+        if sky_pipeline.is_none() {
+            tracing::error!("Failed to create sky pipeline!");
+        } else {
+            info!("Sky pipeline created successfully");
+        }
+
+        let wireframe_pipeline =
+            pipeline_cache.create(|cache| create_wireframe_pipeline(device.clone(), render_pass.clone(), cache));
+        if wireframe_pipeline.is_some() {
+            info!("Wireframe debug pipeline created successfully");
+        }
+
+        let portal_pipeline = pipeline_cache.create(|cache| create_portal_pipeline(device.clone(), render_pass.clone(), cache));
+        if portal_pipeline.is_none() {
+            tracing::error!("Failed to create portal pipeline!");
+        }
+
+        let cache_stats = pipeline_cache.stats();
+        info!(
+            "Pipeline cache: {}/{} pipelines from cache ({} loaded)",
+            cache_stats.hits,
+            cache_stats.pipelines,
+            infinite_core::format_bytes(cache_stats.loaded_bytes as u64)
+        );
+
+        let portal_mesh_data = Mesh::disc(1.0, 48, [1.0, 1.0, 1.0, 1.0]);
+        let portal_mesh = match create_mesh_buffers(
+            &mut mesh_uploader,
+            &portal_mesh_data.vertices,
+            &portal_mesh_data.indices,
+        ) {
+            Ok(mesh) => Some(mesh),
+            Err(e) => {
+                tracing::error!("Failed to create portal mesh: {}", e);
+                None
+            }
+        };
+
+        let glider_mesh_data = Mesh::plane(1.0, 4, [1.0, 1.0, 1.0, 1.0]);
+        let glider_mesh = match create_mesh_buffers(
+            &mut mesh_uploader,
+            &glider_mesh_data.vertices,
+            &glider_mesh_data.indices,
+        ) {
+            Ok(mesh) => Some(mesh),
+            Err(e) => {
+                tracing::error!("Failed to create glider mesh: {}", e);
+                None
+            }
+        };
+
+        let rope_mesh_data = Mesh::line(1.0, [1.0, 1.0, 1.0, 1.0]);
+        let rope_mesh = match create_mesh_buffers(
+            &mut mesh_uploader,
+            &rope_mesh_data.vertices,
+            &rope_mesh_data.indices,
+        ) {
+            Ok(mesh) => Some(mesh),
+            Err(e) => {
+                tracing::error!("Failed to create rope mesh: {}", e);
+                None
+            }
+        };
+
+        let box_mesh_data = Mesh::cuboid([1.0, 1.0, 1.0, 1.0]);
+        let box_mesh = match create_mesh_buffers(
+            &mut mesh_uploader,
+            &box_mesh_data.vertices,
+            &box_mesh_data.indices,
+        ) {
+            Ok(mesh) => Some(mesh),
+            Err(e) => {
+                tracing::error!("Failed to create box mesh: {}", e);
+                None
+            }
+        };
+
+        // Create capsule mesh for player/preview
+        let capsule_mesh_data = Mesh::capsule(1.8, 0.4, 16, 16, [0.6, 0.7, 0.8, 1.0]);
+        let capsule_mesh = match create_mesh_buffers(
+            &mut mesh_uploader,
+            &capsule_mesh_data.vertices,
+            &capsule_mesh_data.indices,
+        ) {
+            Ok(mesh) => {
+                info!("Capsule mesh created: {} vertices, {} indices",
+                      capsule_mesh_data.vertices.len(), capsule_mesh_data.indices.len());
+                Some(mesh)
+            }
+            Err(e) => {
+                tracing::error!("Failed to create capsule mesh: {}", e);
+                None
+            }
+        };
+
+        // Create sky dome mesh
+        let sky_mesh_data = SkyMesh::dome(32, 16);
+        let sky_mesh = match create_sky_mesh_buffers(
+            &mut mesh_uploader,
+            &sky_mesh_data.vertices,
+            &sky_mesh_data.indices,
+        ) {
+            Ok(mesh) => {
+                info!("Sky mesh created: {} vertices, {} indices",
+                      sky_mesh_data.vertices.len(), sky_mesh_data.indices.len());
+                Some(mesh)
+            }
+            Err(e) => {
+                tracing::error!("Failed to create sky mesh: {}", e);
+                None
+            }
+        };
+
+        // Create egui renderer (UI pass)
+        let gui = Gui::new_with_subpass(
+            event_loop,
+            surface.clone(),
+            queue.clone(),
+            Subpass::from(ui_render_pass.clone(), 0).unwrap(),
+            swapchain.image_format(),
+            GuiConfig::default(),
+        );
+
+        // One ring region per swapchain image that can be in flight, plus the one being written
+        let uniform_ring = UniformRing::new(memory_allocator.clone(), UNIFORM_RING_FRAME_BYTES, images.len() as u32 + 1)
+            .map_err(|e| InfiniteError::from(e).context("creating the uniform ring"))?;
+        let frame_uniforms = FrameUniforms::new(uniform_ring, descriptor_set_allocator);
+
+        let gpu_timer = GpuTimer::new(device.clone(), queue_family_index, GPU_TIMER_FRAMES);
+        if gpu_timer.is_none() {
+            tracing::warn!("GPU timestamps not supported, dynamic resolution disabled");
+        }
+
+        self.render_ctx = Some(RenderContext {
+            device,
+            queue,
+            swapchain,
+            images,
+            render_pass,
+            ui_render_pass,
+            framebuffers,
+            memory_allocator,
+            command_buffer_allocator,
+            mesh_uploader,
+            recreate_swapchain: false,
+            previous_frame_end: None,
+            frame_uniforms,
+            pipeline_cache,
+            scene_target,
+            gpu_timer,
+            basic_pipeline,
+            sky_pipeline,
+            wireframe_pipeline,
+            portal_pipeline,
+            capsule_mesh,
+            terrain_mesh: None,
+            chunk_meshes: chunk_mesh_pool(),
+            npc_capsule_mesh: None,
+            sky_mesh,
+            debug_capsule_mesh: None,
+            portal_mesh,
+            glider_mesh,
+            rope_mesh,
+            box_mesh,
+        });
+        self.gui = Some(gui);
+        Ok(())
+    }
+
+    /// NPC the player is talking to, AI or scripted
+    fn dialogue_partner(&self) -> Option<infinite_game::NpcId> {
+        self.ai_dialogue
+            .active_npc_id()
+            .or_else(|| self.dialogue_system.active().map(|a| a.npc_id))
+    }
+
+    /// Relationship with an NPC, kept under the name they go by now
+    fn relationship_with(&mut self, npc_id: infinite_game::NpcId) -> Option<&mut infinite_game::NpcRelationship> {
+        let npc = self.npc_manager.as_ref()?.get(npc_id)?;
+        let rel = self.relationship_manager.get_or_create(npc.persistent_key);
+        rel.name = npc.name().to_string();
+        Some(rel)
+    }
+
+    /// Write ended scripted conversations, and the quests they touched,
+    /// into each NPC's relationship log
+    fn log_finished_dialogues(&mut self) {
+        let year = self.timeline.active_year;
+        for finished in self.dialogue_system.drain_finished() {
+            if let Some(rel) = self.relationship_with(finished.npc_id) {
+                rel.log_conversation(year, &finished.transcript);
+                for quest in &finished.quests {
+                    rel.note_quest(quest);
+                }
+            }
+        }
+    }
+
+    /// Hand one item of an inventory stack to the NPC being talked to
+    fn give_gift(&mut self, inventory_index: usize) {
+        let Some(npc_id) = self.dialogue_partner() else {
+            return;
+        };
+        let Some(npc_name) = self.npc_manager.as_ref().and_then(|m| m.get(npc_id)).map(|n| n.name().to_string()) else {
+            return;
+        };
+        let Some(item) = self.player_combat.inventory.remove_item_stack(inventory_index, 1) else {
+            return;
+        };
+        if let Some(rel) = self.relationship_with(npc_id) {
+            rel.record_gift(item.name.clone());
+        }
+        self.notification_text = Some(format!("Gave {} to {}", item.name, npc_name));
+        self.notification_timer = 2.0;
+    }
+
+    /// Play newly spoken dialogue lines: a recording if one exists for the
+    /// line, otherwise text-to-speech when enabled. Also tracks the subtitle.
+    fn update_voice_lines(&mut self, delta: f32) {
+        let mut lines = self.dialogue_system.drain_speak_lines();
+        let ai_lines = self.ai_dialogue.drain_speak_lines();
+        // Short AI lines come back later as the NPC's greetings
+        if let Some(npc_manager) = &self.npc_manager {
+            for line in &ai_lines {
+                if let Some(npc) = npc_manager.get(line.npc_id) {
+                    self.barks.library.remember_generated(npc.persistent_key, &line.text);
+                }
+            }
+        }
+        lines.extend(ai_lines);
+        let bark = self.barks.drain_speak_lines().pop();
+
+        // Only the most recent line matters; earlier ones were interrupted
+        if let Some(line) = lines.pop() {
+            self.subtitle_timer = 2.0 + line.text.chars().count() as f32 * 0.06;
+            self.subtitle = Some((line.speaker.clone(), line.text.clone()));
+            self.pending_tts = None;
+
+            let mut recorded = false;
+            if let Some(audio) = &mut self.audio {
+                match line.line_id.as_deref().map(|id| audio.play_voice_line(id)) {
+                    Some(Ok(played)) => recorded = played,
+                    Some(Err(e)) => tracing::warn!("Failed to play voice line: {}", e),
+                    None => {}
+                }
+                if !recorded {
+                    audio.stop_voice(std::time::Duration::from_millis(80));
+                }
+            }
+
+            if !recorded && self.audio.is_some() && self.settings.audio.tts_voice_lines {
+                if let Some(client) = &self.integration_client {
+                    self.pending_tts = Some(client.send_tts(infinite_integration::TtsRequest {
+                        text: line.text,
+                        voice: None,
+                    }));
+                }
+            }
+        }
+
+        if let Some(pending) = &self.pending_tts {
+            if let Some(result) = pending.try_recv() {
+                match result {
+                    Ok(bytes) => {
+                        if let Some(audio) = &mut self.audio {
+                            if let Err(e) = audio.play_voice_data(bytes) {
+                                tracing::warn!("Failed to play TTS audio: {}", e);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("TTS request failed: {}", e),
+                }
+                self.pending_tts = None;
+            }
+        }
+
+        // Barks float as text; they are only voiced over silence
+        let quiet = self.subtitle.is_none()
+            && self.pending_tts.is_none()
+            && !self.dialogue_system.is_active()
+            && !self.ai_dialogue.is_active();
+        if let Some(bark) = bark.filter(|_| quiet && self.settings.audio.voice_barks) {
+            if let Some(audio) = self.audio.as_mut().filter(|a| !a.is_voice_playing()) {
+                let recorded = match bark.line_id.as_deref().map(|id| audio.play_voice_line(id)) {
+                    Some(Ok(played)) => played,
+                    Some(Err(e)) => {
+                        tracing::warn!("Failed to play bark: {}", e);
+                        false
+                    }
+                    None => false,
+                };
+                if !recorded && self.settings.audio.tts_voice_lines {
+                    if let Some(client) = &self.integration_client {
+                        self.pending_tts = Some(client.send_tts(infinite_integration::TtsRequest {
+                            text: bark.text,
+                            voice: None,
+                        }));
+                    }
+                }
+            }
+        }
+
+        self.subtitle_timer = (self.subtitle_timer - delta).max(0.0);
+        let speaking = self.pending_tts.is_some()
+            || self.audio.as_ref().is_some_and(|a| a.is_voice_playing());
+        if self.subtitle_timer <= 0.0 && !speaking {
+            self.subtitle = None;
+        }
+    }
+
+    fn update(&mut self, delta: f32) {
+        self.game_time.set_time_scale(self.settings.gameplay.time_scale);
+        self.game_time.update(delta);
+
+        if let Some(audio) = &mut self.audio {
+            audio.update();
+            // Sound effects follow slow motion, but keep playing while the world is frozen
+            let scale = self.game_time.time_scale();
+            let rate = if self.settings.audio.pitch_follows_time_scale && scale > 0.0 {
+                scale.clamp(0.5, 2.0)
+            } else {
+                1.0
+            };
+            audio.set_playback_rate(rate as f64);
+        }
+
+        // Finished background saves and loads
+        for completion in self.save_worker.poll() {
+            completion.run(self);
+        }
+        self.save_indicator_timer = (self.save_indicator_timer - delta).max(0.0);
+
+        // Character uploads, deletions and syncs
+        self.roster.poll(self.integration_client.as_ref());
+
+        // Poll pending item catalog fetch
+        if let Some(pending) = &self.pending_catalog {
+            if let Some(result) = pending.try_recv() {
+                match result {
+                    Ok(server_items) => {
+                        let mut catalog = infinite_game::combat::ItemCatalog::load_from_server(server_items);
+                        catalog.insert(infinite_game::combat::create_repair_kit(1), infinite_game::combat::REPAIR_KIT_PRICE);
+                        catalog.insert(infinite_game::combat::create_purifying_salts(1), infinite_game::combat::PURIFYING_SALTS_PRICE);
+                        for tool in [infinite_game::GatherTool::Pickaxe, infinite_game::GatherTool::Hatchet] {
+                            catalog.insert(tool.create(), infinite_game::GATHERING_TOOL_PRICE);
+                        }
+                        for ingredient in infinite_game::Ingredient::ALL {
+                            catalog.insert(ingredient.create(1), ingredient.price());
+                        }
+                        for reagent in infinite_game::Reagent::BOUGHT {
+                            catalog.insert(reagent.create(1), reagent.price());
+                        }
+                        for replaced in self.mod_content.apply_items(&mut catalog) {
+                            info!("Mod override: {}", replaced);
+                        }
+                        info!("Item catalog loaded: {} items", catalog.len());
+                        self.item_catalog = Some(catalog);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load item catalog: {}", e);
+                    }
+                }
+                self.pending_catalog = None;
+            }
+        }
+
+        // Survival scores: once one is in, fetch the board it landed on
+        if let Some(result) = self.pending_score.as_ref().and_then(|p| p.try_recv()) {
+            self.pending_score = None;
+            let status = match result {
+                Ok(_) => {
+                    self.pending_leaderboard = self.integration_client.as_ref().map(|client| {
+                        client.fetch_leaderboard(infinite_game::npc::survival::SURVIVAL_BOARD, LEADERBOARD_SIZE)
+                    });
+                    ScoreStatus::Submitted(None)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to submit survival score: {}", e);
+                    ScoreStatus::Failed(e.to_string())
+                }
+            };
+            if let Some(menu) = &mut self.survival_menu {
+                menu.score_status = status;
+            }
+        }
+        if let Some(result) = self.pending_leaderboard.as_ref().and_then(|p| p.try_recv()) {
+            self.pending_leaderboard = None;
+            let entries = result.unwrap_or_else(|e| {
+                tracing::warn!("Failed to fetch the survival leaderboard: {}", e);
+                Vec::new()
+            });
+            if let Some(menu) = &mut self.survival_menu {
+                menu.score_status = ScoreStatus::Submitted(Some(entries));
+            }
+        }
+
+        // Update based on current state
+        match &self.app_state {
+            ApplicationState::Loading(phase) => {
+                self.loading_timer += delta;
+                self.loading_screen.update(delta, phase.progress());
+
+                // Simulate loading phases (advance every 0.5 seconds)
+                if self.loading_timer >= 0.5 {
+                    self.loading_timer = 0.0;
+                    if let Some(next_phase) = phase.next() {
+                        self.app_state = ApplicationState::Loading(next_phase);
+                    } else {
+                        // Loading complete — go to login screen (or main menu if offline)
+                        if self.integration_client.is_some() {
+                            self.app_state = ApplicationState::Login;
+                        } else {
+                            self.app_state = ApplicationState::MainMenu;
+                        }
+                        info!(
+                            "Loading complete - Year: {}",
+                            self.timeline.year_label()
+                        );
+                    }
+                }
+            }
+            ApplicationState::Playing => {
+                // Release cursor when debug overlay or any dialogue is active
+                let dialogue_active = self.dialogue_system.is_active() || self.ai_dialogue.is_active();
+                self.update_cursor_capture(
+                    !self.debug_visible && !dialogue_active && !self.show_shop && self.respec_menu.is_none() && self.cooking_menu.is_none() && self.brewing_menu.is_none() && self.shrine_menu.is_none() && !self.survival_window_open() && !self.rift_window_open() && !self.hud_editor.active && !self.error_dialog.is_open(),
+                );
+                self.update_macros(delta);
+                self.update_speedrun(delta);
+
+                // Conversations hold the world still while the UI keeps running
+                match (dialogue_active, self.dialogue_freeze) {
+                    (true, None) => self.dialogue_freeze = Some(self.game_time.push_time_scale(0.0)),
+                    (false, Some(id)) => {
+                        self.game_time.pop_time_scale(id);
+                        self.dialogue_freeze = None;
+                    }
+                    _ => {}
+                }
+
+                // The world runs on scaled game time; fades, prompts and
+                // notifications on real time
+                let real_delta = delta;
+                let delta = self.game_time.delta_time;
+
+                // Update world systems
+                self.time_of_day.update(delta);
+                self.weather.update(delta);
+                self.wind.update(delta, &self.weather);
+
+                // --- Ambient soundscape ---
+                let biome = self.player_biome();
+                if let Some(audio) = &mut self.audio {
+                    if let Some(camera) = &self.camera {
+                        audio.set_listener(camera.position(), camera.forward(), camera.up());
+                    }
+                    let (wildlife, machinery) = era_ambience(infinite_game::Era::for_year(self.timeline.active_year));
+                    audio.set_ambient_conditions(AmbientConditions {
+                        biome,
+                        period: DayPeriod::from_hours(self.time_of_day.time_hours),
+                        wildlife,
+                        machinery,
+                        precipitation: self.weather.precipitation_intensity(),
+                        wind: self.wind.strength().min(1.0),
+                    });
+                    audio.update_ambience(delta);
+                }
+
+                // --- Time transition fade ---
+                if self.time_transitioning {
+                    if let Some(target_year) = self.pending_time_transition {
+                        let blend = self.settings.gameplay.time_travel_transition == TimeTravelTransition::Blend
+                            && self.chunk_manager.is_some();
+                        if self.time_transition_alpha < 1.0 && !blend {
+                            // Fade to black
+                            self.time_transition_alpha = (self.time_transition_alpha + real_delta * 2.0).min(1.0);
+                        } else {
+                            // At full black (or straight away when blending): switch branch
+                            // and year, regenerate terrain
+                            if let Some(branch) = self.pending_branch_switch.take() {
+                                self.switch_branch(branch);
+                            }
+                            self.history_forked = false;
+                            if let Err(e) = self.timeline.travel_to_year(target_year) {
+                                tracing::error!("Failed to travel to year {}: {}", target_year, e);
+                            } else {
+                                info!("Switched to year: {}", self.timeline.year_label());
+                                // World bosses stay in their own era
+                                self.dismiss_world_boss();
+                            }
+
+                            // Regenerate chunks with new time-period terrain config
+                            let time_config = if self.timeline.is_present() {
+                                None
+                            } else {
+                                Some(TimeTerrainConfig::for_year(target_year, self.timeline.present_year))
+                            };
+
+                            // Chunks are rebuilt a few per frame (see the era regeneration
+                            // below); the fade holds until the player's surroundings are done.
+                            // A blend instead morphs the terrain over in view, step by step.
+                            if let Some(chunk_manager) = &mut self.chunk_manager {
+                                let previous = chunk_manager.time_terrain_config().cloned();
+                                chunk_manager.set_time_terrain_config(time_config);
+                                chunk_manager.set_store_branch(self.timeline.active_branch());
+                                chunk_manager.set_store_year(target_year);
+                                if blend {
+                                    chunk_manager.begin_era_blend(previous);
+                                    self.era_blend_progress = Some(0.0);
+                                } else {
+                                    chunk_manager.begin_regeneration();
+                                }
+                            }
+
+                            self.pending_time_transition = None;
+
+                            self.autosaver.request(AutosaveTrigger::YearChanged);
+                        }
+                    } else if let Some(progress) = self.era_blend_progress {
+                        // Watching the world change: move the blend along, but never
+                        // ahead of the chunks around the player
+                        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                        let mut blend_done = true;
+                        if let Some(chunk_manager) = &mut self.chunk_manager {
+                            blend_done = false;
+                            if chunk_manager.stale_within(player_pos, ERA_READY_RADIUS) == 0 {
+                                let progress = (progress + real_delta / ERA_BLEND_DURATION).min(1.0);
+                                chunk_manager.set_era_blend((progress * ERA_BLEND_STEPS).ceil() / ERA_BLEND_STEPS);
+                                self.era_blend_progress = Some(progress);
+                                if progress >= 1.0 && chunk_manager.stale_count() == 0 {
+                                    chunk_manager.finish_era_blend();
+                                    blend_done = true;
+                                }
+                            }
+                        }
+                        if blend_done {
+                            self.era_blend_progress = None;
+                            self.time_transitioning = false;
+                            self.place_overworld_traps();
+                        }
+                    } else {
+                        // No pending transition: fade back in once the chunks around
+                        // the player show the new era
+                        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                        let surroundings_ready = self.chunk_manager.as_ref()
+                            .is_none_or(|cm| cm.stale_within(player_pos, ERA_READY_RADIUS) == 0);
+                        if surroundings_ready {
+                            self.time_transition_alpha = (self.time_transition_alpha - real_delta * 2.0).max(0.0);
+                            if self.time_transition_alpha <= 0.0 {
+                                self.time_transitioning = false;
+                            }
+                        }
+                    }
+                }
+
+                // --- Era regeneration: rebuild chunks still on the previous era's terrain ---
+                let mut era_rebuilt = false;
+                if let (Some(chunk_manager), Some(physics)) = (&mut self.chunk_manager, &mut self.physics_world) {
+                    if chunk_manager.stale_count() > 0 {
+                        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                        // Faster while the screen is black or the terrain is morphing in
+                        // steps, gently once the player can see the finished era
+                        let budget = if self.time_transition_alpha >= 1.0 {
+                            ERA_REBUILDS_BEHIND_FADE
+                        } else if chunk_manager.era_blend().is_some() {
+                            ERA_BLEND_REBUILDS_PER_FRAME
+                        } else {
+                            ERA_REBUILDS_PER_FRAME
+                        };
+                        chunk_manager.regenerate(player_pos, physics, budget);
+                        physics.update_query_pipeline();
+                        era_rebuilt = chunk_manager.stale_count() == 0 && chunk_manager.era_blend().is_none();
+
+                        // Rising ground must not swallow the player
+                        if let Some(player) = &mut self.player {
+                            let ground = chunk_manager.height_at(player_pos.x, player_pos.z);
+                            if self.dungeon.is_none() && player_pos.y < ground {
+                                player.teleport(physics, Vec3::new(player_pos.x, ground + 1.0, player_pos.z));
+                            }
+                        }
+                    }
+                }
+                if era_rebuilt {
+                    // Trap spots and bridge piers sit on the terrain, so wait
+                    // for the new era's heights
+                    self.place_overworld_traps();
+                    self.place_crafting_stations();
+                    self.sync_bridges();
+                    self.sync_resource_nodes();
+                    self.sync_shrines();
+                }
+
+                // --- Fixed timestep physics update ---
+                let fixed_dt = self.game_time.config.fixed_timestep;
+                let steps = self.game_time.fixed_steps();
+
+                // Update query pipeline so character controller sees all colliders
+                if let Some(physics) = &mut self.physics_world {
+                    physics.update_query_pipeline();
+                }
+
+                if self.player_combat.durability_enabled() != self.settings.gameplay.durability {
+                    self.player_combat.set_durability_enabled(self.settings.gameplay.durability);
+                }
+
+                // Say so when the load gets too heavy to sprint or dodge
+                let encumbrance = self.player_combat.encumbrance();
+                if encumbrance != self.encumbrance {
+                    if encumbrance == infinite_game::Encumbrance::Overloaded {
+                        self.notification_text = Some("Overloaded: too heavy to sprint or dodge".to_string());
+                        self.notification_timer = 3.0;
+                    } else if self.encumbrance == infinite_game::Encumbrance::Overloaded {
+                        self.notification_text = Some("No longer overloaded".to_string());
+                        self.notification_timer = 2.0;
+                    }
+                    self.encumbrance = encumbrance;
+                }
+
+                // Storm wind shoves loose props and the player on foot
+                if let Some(physics) = &mut self.physics_world {
+                    let field = self.wind.force_field();
+                    match self.wind_field.and_then(|id| physics.force_field_mut(id)) {
+                        Some(current) => *current = field,
+                        None => self.wind_field = Some(physics.add_force_field(field)),
+                    }
+                }
+
+                // Glider unlocks once collected and drifts with the weather's wind
+                if let Some(player) = &mut self.player {
+                    player.set_glider_owned(self.collected_items.iter().any(|i| i == GLIDER_ITEM));
+                    player.set_wind(self.wind.velocity());
+                    player.set_speed_scale(self.player_combat.movement_speed_scale());
+                    player.set_can_sprint(
+                        encumbrance.can_sprint() && !self.player_combat.curses.blocks_sprint(self.time_of_day.is_night()),
+                    );
+                    player.set_stamina_regen_scale(self.player_combat.food.stamina_regen_scale());
+                }
+
+                // Grappling hook: fire along the crosshair, or let go
+                if self.input_handler.state.is_just_pressed(InputAction::Grapple) {
+                    if let (Some(physics), Some(player), Some(camera)) =
+                        (&mut self.physics_world, &mut self.player, &self.camera)
+                    {
+                        if player.is_grappling() {
+                            player.release_grapple(physics);
+                        } else if !player.fire_grapple(physics, camera.position(), camera.forward(), &self.grapple_anchors) {
+                            self.notification_text = Some("Nothing to hook onto".to_string());
+                            self.notification_timer = 1.5;
+                        }
+                    }
+                }
+
+                for _ in 0..steps {
+                    if let (Some(physics), Some(player), Some(camera)) =
+                        (&mut self.physics_world, &mut self.player, &self.camera)
+                    {
+                        player.fixed_update(
+                            physics,
+                            &self.input_handler.state,
+                            camera.yaw,
+                            fixed_dt,
+                        );
+                        physics.step();
+                    }
+                }
+
+                if self.player.as_mut().is_some_and(|p| p.take_grapple_snapped()) {
+                    self.notification_text = Some("The rope snapped!".to_string());
+                    self.notification_timer = 2.0;
+                }
+
+                // --- Variable timestep camera update ---
+                // Frame the conversation partner's head, a little below the top of the capsule
+                let dialogue_head = self
+                    .dialogue_partner()
+                    .filter(|_| self.settings.gameplay.dialogue_camera)
+                    .and_then(|id| self.npc_manager.as_ref()?.get(id))
+                    .map(|npc| npc.position + Vec3::Y * 0.7);
+                // Follow the player's eyes blended between physics steps, so
+                // the view doesn't judder at frame rates above the step rate
+                let alpha = self.game_time.fixed_interpolation();
+                if let (Some(physics), Some(player), Some(camera)) =
+                    (&self.physics_world, &self.player, &mut self.camera)
+                {
+                    // Pull back while climbing, gliding or swinging to see more of the
+                    // surroundings, and ease out a little while resting
+                    let pull_back = player.is_climbing() || player.is_gliding() || player.is_grappling();
+                    let offset = if pull_back {
+                        2.0
+                    } else if player.is_resting() {
+                        1.0
+                    } else {
+                        0.0
+                    };
+                    camera.set_distance_offset(offset);
+                    camera.set_dialogue_shot(dialogue_head.map(|npc_head| infinite_game::DialogueShot {
+                        player_eye: player.eye_position(),
+                        npc_head,
+                    }));
+                    // Real time, so the camera can still frame a frozen conversation
+                    camera.update(
+                        &self.input_handler.state,
+                        player.render_eye_position(alpha),
+                        Some(physics),
+                        real_delta,
+                    );
+                }
+
+                // --- Chunk streaming (paused inside a dungeon) ---
+                let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                let mut chunks_changed = false;
+                if let (Some(chunk_manager), Some(physics), None) =
+                    (&mut self.chunk_manager, &mut self.physics_world, &self.dungeon)
+                {
+                    chunk_manager.update(player_pos, physics);
+                    chunks_changed = !chunk_manager.newly_loaded.is_empty() || !chunk_manager.newly_unloaded.is_empty();
+                    let edited = chunk_manager.take_edited();
+
+                    // Remove meshes for unloaded chunks
+                    if let Some(render_ctx) = &mut self.render_ctx {
+                        for coord in &chunk_manager.newly_unloaded {
+                            render_ctx.chunk_meshes.remove(coord);
+                        }
+
+                        // Create meshes for newly loaded and reshaped chunks
+                        let changed = chunk_manager.newly_loaded.iter().chain(&edited);
+                        if let Err(e) = upload_chunk_meshes(render_ctx, changed.filter_map(|coord| chunk_manager.get_chunk(coord))) {
+                            self.error_dialog.report(&e);
+                        }
+                    }
+
+                    physics.update_query_pipeline();
+
+                    // Ground under a GM teleport exists now
+                    if std::mem::take(&mut self.gm_landing) {
+                        if let Some(player) = &mut self.player {
+                            let pos = player.position();
+                            player.teleport(physics, Vec3::new(pos.x, chunk_manager.height_at(pos.x, pos.z) + 1.0, pos.z));
+                        }
+                    }
+                }
+                if chunks_changed {
+                    self.sync_dungeon_entrances();
+                    self.sync_bridges();
+                }
+                // Harvested trees and herbs grow back over time
+                self.resource_regrow_timer -= delta;
+                if chunks_changed || self.resource_regrow_timer <= 0.0 {
+                    self.resource_regrow_timer = RESOURCE_REGROW_CHECK;
+                    self.sync_resource_nodes();
+                }
+                if chunks_changed {
+                    self.sync_shrines();
+                }
+                // Chunks that generated broken terrain were flattened so play can go on
+                if let Some(chunk_manager) = &mut self.chunk_manager {
+                    for err in chunk_manager.take_errors() {
+                        self.error_dialog.report(&InfiniteError::from(err).context("streaming in terrain"));
+                    }
+                }
+                let map_seeds_changed = !self.player_combat.inventory.items.iter()
+                    .filter_map(|item| item.treasure_map.as_ref().map(|map| map.seed))
+                    .eq(self.dig_spot_seeds.iter().copied());
+                if chunks_changed || map_seeds_changed {
+                    self.sync_dig_spots();
+                }
+
+                // --- NPC spawning/despawning with chunks ---
+                if let (Some(npc_manager), Some(chunk_manager), None) =
+                    (&mut self.npc_manager, &self.chunk_manager, &self.dungeon)
+                {
+                    let active_year = self.timeline.active_year;
+
+                    // Population options take effect without reloading
+                    if npc_manager.population_config() != self.settings.gameplay.population {
+                        npc_manager.set_population_config(self.settings.gameplay.population, |x, z| chunk_manager.height_at(x, z));
+                    }
+
+                    // Despawn NPCs from unloaded chunks
+                    for coord in &chunk_manager.newly_unloaded {
+                        npc_manager.on_chunk_unloaded(*coord);
+                    }
+
+                    // Spawn NPCs for newly loaded chunks
+                    for coord in &chunk_manager.newly_loaded {
+                        let cm_ref = chunk_manager;
+                        npc_manager.on_chunk_loaded(*coord, active_year, |x, z| cm_ref.height_at(x, z));
+                    }
+                }
+
+                // --- Stealth: NPC perception of the player ---
+                if let (Some(npc_manager), Some(player), Some(physics)) =
+                    (&mut self.npc_manager, &self.player, &self.physics_world)
+                {
+                    // A held torch lights the player up regardless of time of day
+                    let light_level = if self.player_combat.equipment.holds_torch() {
+                        self.time_of_day.light_intensity().max(0.9)
+                    } else {
+                        self.time_of_day.light_intensity()
+                    };
+                    let stealth = StealthInputs {
+                        position: player.position(),
+                        eye_position: player.eye_position(),
+                        crouching: player.is_crouching(),
+                        speed: player.horizontal_speed(),
+                        walk_speed: player.config.walk_speed,
+                        light_level,
+                    };
+                    let exclude = player.character.collider_handle;
+                    npc_manager.player_hidden = self.gm_tools.invisible
+                        || self.player_combat.status_manager.has_effect(infinite_game::StatusEffectType::Invisible);
+                    // Smoke clouds block sight lines like walls do
+                    let throwables = &self.throwables;
+                    npc_manager.update_perception(delta, &stealth, |from, to| {
+                        !throwables.blocks_sight(from, to) && perception::line_of_sight(physics, from, to, exclude)
+                    });
+                }
+
+                // --- NPC update ---
+                if let Some(npc_manager) = &mut self.npc_manager {
+                    let cm_ref = self.chunk_manager.as_ref();
+                    // Dungeon enemies stand on the dungeon floor, survival waves on the arena's
+                    let dungeon = self.dungeon.as_ref();
+                    let arena = self.survival_arena.as_ref();
+                    npc_manager.update(delta, player_pos, |x, z| {
+                        dungeon.and_then(|d| d.floor_height_at(x, z))
+                            .or_else(|| arena.and_then(|a| a.floor_height_at(x, z)))
+                            .or_else(|| cm_ref.map(|cm| cm.height_at(x, z)))
+                            .unwrap_or(0.0)
+                    });
+
+                    // Sync NPC positions to interaction system:
+                    // Remove old NPC interactables
+                    self.interaction_system.retain(|i| {
+                        !matches!(i.kind, infinite_game::InteractableKind::Npc { .. })
+                    });
+
+                    // Add current NPC interactables (non-hostile and not provoked)
+                    for npc in npc_manager.npcs_iter() {
+                        if npc.data.faction != infinite_game::NpcFaction::Hostile
+                            && !npc_manager.is_provoked(npc.id)
+                        {
+                            self.interaction_system.add(Interactable::npc(
+                                npc.position,
+                                npc.id,
+                                npc.name(),
+                                npc.data.interaction_radius,
+                            ));
+                        }
+                    }
+
+                    // Ambient barks from living NPCs (not dummies or arena waves)
+                    let relationships = &self.relationship_manager;
+                    let bark_npcs: Vec<infinite_game::BarkCandidate> = npc_manager
+                        .npcs_iter()
+                        .filter(|npc| npc.identity.is_some())
+                        .filter(|npc| npc_manager.combat_stats.get(&npc.id).is_none_or(|s| s.is_alive()))
+                        .map(|npc| infinite_game::BarkCandidate {
+                            npc_id: npc.id,
+                            persistent_key: npc.persistent_key,
+                            speaker: npc.name(),
+                            role: npc.data.role,
+                            hostile: npc.data.faction == infinite_game::NpcFaction::Hostile || npc_manager.is_provoked(npc.id),
+                            position: npc.position + Vec3::Y * 2.4,
+                            affection: relationships.get(npc.persistent_key).map_or(0.0, |r| r.affection),
+                        })
+                        .collect();
+                    let bark_world = infinite_game::BarkWorld {
+                        player_pos,
+                        year: self.timeline.active_year,
+                        weather: self.weather.current,
+                    };
+                    self.barks.update(delta, &bark_world, &bark_npcs);
+
+                    // --- NPC combat: enemies and provoked NPCs damage player ---
+                    // Collect attacking NPC IDs and positions first (avoids borrow conflicts)
+                    let attacking_enemies: Vec<(NpcId, Vec3)> = npc_manager.npcs_iter()
+                        .filter(|n| {
+                            n.data.role == infinite_game::NpcRole::Enemy
+                            || npc_manager.is_provoked(n.id)
+                        })
+                        .filter(|n| {
+                            n.brain.as_ref()
+                                .and_then(|b| b.current_action_name())
+                                .map(|name| name == "attack_melee")
+                                .unwrap_or(false)
+                        })
+                        .filter(|n| npc_manager.threat_target(n.id) == Some(ThreatSource::Player))
+                        .map(|n| (n.id, n.position))
+                        .collect();
+
+                    let mut landed_hits: Vec<(NpcId, f32)> = Vec::new();
+                    for (npc_id, npc_pos) in &attacking_enemies {
+                        if let Some(stats) = npc_manager.combat_stats.get_mut(npc_id) {
+                            if stats.is_alive() && stats.update_attack(delta) {
+                                // Check if player is in attack range
+                                let dist = (player_pos - *npc_pos).length();
+                                if dist < stats.attack_radius {
+                                    let dmg = stats.attack;
+                                    let actual_dmg = self.player_combat.take_elemental_damage(dmg, stats.element);
+                                    for name in self.player_combat.take_worn_out() {
+                                        self.notification_text = Some(format!("Your {} is worn out! Repair it at a shop or with a repair kit.", name));
+                                        self.notification_timer = 2.5;
+                                    }
+                                    self.damage_numbers.push(
+                                        infinite_game::PLAYER_TARGET,
+                                        infinite_game::DamageKind::Incoming,
+                                        player_pos + Vec3::Y * 2.0,
+                                        actual_dmg,
+                                        false,
+                                    );
+                                    if self.player_combat.last_hit_parried {
+                                        self.notification_text = Some("Parried!".to_string());
+                                        self.notification_timer = 0.8;
+                                        let slowed = self.parry_slow_motion.is_some_and(|id| self.game_time.has_time_scale(id));
+                                        if !slowed {
+                                            self.parry_slow_motion =
+                                                Some(self.game_time.slow_motion(PARRY_SLOW_MOTION.0, PARRY_SLOW_MOTION.1));
+                                        }
+                                    } else if self.player_combat.last_blocked_amount > 0.0 && actual_dmg == 0.0 {
+                                        self.notification_text = Some("Blocked!".to_string());
+                                        self.notification_timer = 0.6;
+                                    }
+                                    if actual_dmg > 0.0 {
+                                        landed_hits.push((*npc_id, actual_dmg));
+                                        self.combat_log.record_taken(actual_dmg, stats.element);
+                                        // Void enemies sometimes leave a curse behind
+                                        if let Some(curse) = infinite_game::combat::CurseKind::from_enemy_element(stats.element) {
+                                            if self.rng.stream(infinite_core::RngStream::Combat).chance(infinite_game::combat::VOID_CURSE_CHANCE)
+                                                && self.player_combat.curses.afflict(curse)
+                                            {
+                                                self.notification_text = Some(format!("You've been cursed with {}!", curse.name()));
+                                                self.notification_timer = 3.0;
+                                            }
+                                        }
+                                        // Apply knockback
+                                        if let Some(player) = &mut self.player {
+                                            let knockback_dir = (player_pos - *npc_pos).normalize_or_zero();
+                                            player.character.apply_impulse(knockback_dir * balance::combat::KNOCKBACK.get());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    // Vampiric elites heal from the hits they landed
+                    for (npc_id, dmg) in landed_hits {
+                        npc_manager.on_npc_hit_player(npc_id, dmg);
+                    }
+
+                    // Frozen aura elites slow the player while nearby
+                    if npc_manager.in_frozen_aura(player_pos) {
+                        self.player_combat.status_manager.apply(infinite_game::StatusEffect::elemental_proc(
+                            infinite_game::StatusEffectType::Slowed,
+                            0.5,
+                        ));
+                    }
+                }
+
+                // --- NPC status effects (coated weapon hits) ---
+                let status_ticks = self.npc_manager.as_mut().map(|m| m.update_statuses(delta)).unwrap_or_default();
+                for (npc_id, damage, source) in status_ticks {
+                    let (element, cause) = match source {
+                        infinite_game::StatusEffectType::Burning => (infinite_game::Element::Fire, "flames"),
+                        infinite_game::StatusEffectType::Shocked => (infinite_game::Element::Air, "shock"),
+                        _ => (infinite_game::Element::Physical, "poison"),
+                    };
+                    self.damage_npc_indirect(npc_id, damage, element, cause);
+                }
+
+                // --- Traps ---
+                self.update_traps(delta);
+
+                // --- Anachronisms ---
+                self.update_paradox(delta);
+
+                // --- Memory budget ---
+                self.update_memory(delta);
+
+                // --- Balance hot reload ---
+                self.update_balance(delta);
+
+                // --- Named locations ---
+                self.update_locations();
+
+                // --- Markets and caravans ---
+                self.update_economy(delta);
+
+                // --- Hotbar and throwables ---
+                self.update_hotbar();
+                self.update_throwables(delta);
+
+                // --- Crosshair spread and aim assist ---
+                self.update_aim(delta);
+
+                // --- Player attack input (light + heavy) ---
+                if let Some(camera) = &self.camera {
+                    let attack_range = balance::combat::ATTACK_RANGE.get();
+                    let attack_angle = balance::combat::ATTACK_ANGLE.get().to_radians();
+                    // Where kills are logged in the bestiary
+                    let habitat = if self.dungeon.is_some() { "Dungeon" } else { self.player_biome().name() };
+                    let player_forward = camera.forward();
+                    let player_forward_xz = Vec3::new(player_forward.x, 0.0, player_forward.z).normalize_or_zero();
+
+                    // Helper closure: find closest NPC with combat stats in attack cone,
+                    // with no wall in the way of the swing
+                    let physics = self.physics_world.as_ref();
+                    let chest = player_pos + Vec3::Y * 1.2;
+                    let find_target = |npc_manager: &NpcManager, range: f32| -> Option<(NpcId, Vec3, f32)> {
+                        npc_manager.npcs_iter()
+                            .filter(|n| npc_manager.combat_stats.contains_key(&n.id))
+                            .filter_map(|n| {
+                                let to_npc = n.position - player_pos;
+                                let to_npc_xz = Vec3::new(to_npc.x, 0.0, to_npc.z);
+                                let distance = to_npc_xz.length();
+                                if distance < range && distance > 0.01 {
+                                    let angle = player_forward_xz.dot(to_npc_xz.normalize());
+                                    if angle > (attack_angle / 2.0).cos() {
+                                        return Some((n.id, n.position, distance));
+                                    }
+                                }
+                                None
+                            })
+                            .filter(|&(_, position, _)| {
+                                physics.is_none_or(|p| infinite_game::combat::swing_reaches(p, chest, position + Vec3::Y))
+                            })
+                            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+                    };
+
+                    // Holding block (F) raises an off-hand shield
+                    self.player_combat.set_blocking(self.input_handler.state.is_held(InputAction::Block));
+
+                    // Light (left click) and heavy (right click) presses feed the weapon's combo chain
+                    if self.input_handler.state.is_just_pressed(InputAction::Attack) {
+                        self.player_combat.queue_attack(infinite_game::combat::damage::AttackType::Light);
+                    }
+                    if self.input_handler.state.is_just_pressed(InputAction::HeavyAttack) {
+                        self.player_combat.queue_attack(infinite_game::combat::damage::AttackType::Heavy);
+                    }
+
+                    // Combo hits land partway through each step
+                    if let Some(hit) = self.player_combat.take_combo_hit() {
+                        if let Some(weapon) = &self.player_combat.equipment.main_hand {
+                            let year = self.timeline.active_year;
+                            self.paradox.on_item_used(weapon, year, &self.settings.gameplay.paradox);
+                        }
+                        if self.player_combat.equipment.holds_ranged() {
+                            self.aim_spread.fire();
+                        }
+                        let heavy_reach = if hit.attack_type == infinite_game::combat::damage::AttackType::Heavy { 0.5 } else { 0.0 };
+                        let reach = attack_range * hit.range_multiplier + heavy_reach;
+                        if let Some(npc_manager) = &mut self.npc_manager {
+                            if let Some((npc_id, npc_pos, _)) = find_target(npc_manager, reach) {
+                                let npc_defense = npc_manager.combat_stats.get(&npc_id)
+                                    .map(|s| s.defense).unwrap_or(0.0);
+                                let npc_element = npc_manager.combat_stats.get(&npc_id)
+                                    .map(|s| s.element).unwrap_or(infinite_game::combat::element::Element::Physical);
+                                let npc_weakness = npc_manager.combat_stats.get(&npc_id)
+                                    .and_then(|s| s.weapon_weakness);
+                                let npc_armor = npc_manager.combat_stats.get(&npc_id)
+                                    .map(|s| s.armor).unwrap_or(0.0);
+
+                                let crit_roll = self.rng.stream(infinite_core::RngStream::Combat).next_f32();
+                                let mut event = self.player_combat.calculate_hit_damage(
+                                    &hit, npc_defense, npc_armor, npc_element, npc_weakness, crit_roll,
+                                );
+                                let sneak = npc_manager.is_sneak_attack(npc_id);
+                                if sneak {
+                                    event.final_amount *= balance::combat::SNEAK_ATTACK_MULTIPLIER.get();
+                                }
+                                let result = npc_manager.damage_npc(
+                                    npc_id, event.final_amount, event.element, event.attack_type,
+                                );
+                                self.player_combat.wear_weapon(hit.off_hand);
+                                for name in self.player_combat.take_worn_out() {
+                                    self.notification_text = Some(format!("Your {} is worn out! Repair it at a shop or with a repair kit.", name));
+                                    self.notification_timer = 2.5;
+                                }
+                                if let Some(kind) = (!result.defeated && !hit.off_hand).then(|| self.player_combat.take_coating_charge()).flatten() {
+                                    if !npc_manager.apply_status(npc_id, kind.status(), kind.element()) {
+                                        self.notification_text = Some(format!("It shrugs off the {}", kind.name()));
+                                        self.notification_timer = 1.2;
+                                    }
+                                    if self.player_combat.coating.is_none() {
+                                        self.notification_text = Some(format!("Your {} has worn off", kind.name()));
+                                        self.notification_timer = 1.5;
+                                    }
+                                }
+                                if !result.defeated && npc_manager.apply_poise_damage(npc_id, hit.poise_damage) {
+                                    self.notification_text = Some("Staggered!".to_string());
+                                    self.notification_timer = 0.8;
+                                    self.game_time.slow_motion(STAGGER_HIT_STOP.0, STAGGER_HIT_STOP.1);
+                                }
+                                self.combat_log.record_dealt(event.final_amount, event.element, &hit.name, event.is_crit);
+                                if let Some(dummy) = self.training_dummy.as_mut().filter(|d| d.id == npc_id) {
+                                    dummy.record_hit(DummyHit::from_event(&event, hit.name.as_str()));
+                                }
+
+                                self.damage_numbers.push(
+                                    npc_id.0,
+                                    infinite_game::DamageKind::Outgoing,
+                                    npc_pos + Vec3::Y * 1.5,
+                                    event.final_amount,
+                                    event.is_crit || sneak || hit.finisher,
+                                );
+
+                                if result.defeated {
+                                    self.combat_log.record_kill(result.role.name());
+                                    let npc_level = npc_manager.npc_level(npc_id);
+                                    let xp = (infinite_game::player::stats::xp_for_enemy(
+                                        npc_level, infinite_game::player::stats::EnemyType::Normal,
+                                    ) as f32 * result.reward_multiplier) as u64;
+                                    let levels_gained = self.player_combat.add_xp(xp);
+                                    for new_level in levels_gained {
+                                        if let Some(growth) = &self.archetype_growth {
+                                            self.player_combat.apply_level_up(growth);
+                                        }
+                                        self.level_up_notification = Some((new_level, 3.0));
+                                    }
+                                    let gold_reward = match result.role {
+                                        infinite_game::NpcRole::Guard => 25 * npc_level as u64,
+                                        infinite_game::NpcRole::Shopkeeper => 50 * npc_level as u64,
+                                        infinite_game::NpcRole::Villager => 2 * npc_level as u64,
+                                        infinite_game::NpcRole::QuestGiver => 15 * npc_level as u64,
+                                        infinite_game::NpcRole::Enemy => 10 * npc_level as u64,
+                                    };
+                                    let gold_reward = (gold_reward as f32 * result.reward_multiplier) as u64;
+                                    self.player_combat.gold += gold_reward;
+                                    self.play_stats.record(infinite_game::StatEvent::GoldEarned(gold_reward));
+                                    if result.was_friendly {
+                                        self.notification_text = Some(format!("You murdered a {}!  +{} Gold", result.role.name(), gold_reward));
+                                    } else if result.split {
+                                        self.notification_text = Some(format!("The elite splits apart!  +{} XP  +{} Gold", xp, gold_reward));
+                                    } else if result.reward_multiplier > 1.0 {
+                                        self.notification_text = Some(format!("Elite defeated!  +{} XP  +{} Gold", xp, gold_reward));
+                                    } else {
+                                        self.notification_text = Some(format!("+{} XP  +{} Gold", xp, gold_reward));
+                                    }
+                                    self.notification_timer = 1.5;
+                                    if let Some(kill) = &result.kill {
+                                        self.tutorials.handle(infinite_game::TutorialEvent::EnemyDefeated);
+                                        self.play_stats.record(infinite_game::StatEvent::EnemyDefeated);
+                                        self.speedrun_events.push(infinite_game::RunEvent::EnemyDefeated);
+                                        let update = self.bestiary.record_kill(kill, self.timeline.active_year, habitat);
+                                        if let Some(note) = update.message(&kill.name) {
+                                            let text = self.notification_text.take().unwrap_or_default();
+                                            self.notification_text = Some(format!("{}  {}", text, note));
+                                            self.notification_timer = 2.5;
+                                        }
+                                    }
+                                    if result.role == infinite_game::NpcRole::Enemy && !result.was_friendly {
+                                        let drop_seed = self.rng.stream(infinite_core::RngStream::Loot).next_u64();
+                                        if let Some(map) = roll_treasure_map(
+                                            self.chunk_manager.as_ref(), drop_seed, result.reward_multiplier, player_pos, self.timeline.present_year,
+                                        ) {
+                                            let name = map.name.clone();
+                                            if self.player_combat.inventory.add_item(map).is_ok() {
+                                                self.tutorials.handle(infinite_game::TutorialEvent::TreasureMapFound);
+                                                let text = self.notification_text.take().unwrap_or_default();
+                                                self.notification_text = Some(format!("{}  Found a {}!", text, name));
+                                                self.notification_timer = 2.5;
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // --- Skill slots (1-4 keys) ---
+                    for (slot_idx, action) in [
+                        InputAction::Skill1, InputAction::Skill2,
+                        InputAction::Skill3, InputAction::Skill4,
+                    ].iter().enumerate() {
+                        if self.input_handler.state.is_just_pressed(*action) {
+                            // Check if skill exists and get its cost
+                            let skill_info = self.player_combat.skill_slots.get(slot_idx)
+                                .and_then(|slot| {
+                                    if let Some(infinite_game::combat::skill::Skill::Active(ref active)) = slot.skill {
+                                        Some((active.cost, active.base_damage * active.damage_multiplier, active.element, active.name.clone(), active.taunt_threat))
+                                    } else {
+                                        None
+                                    }
+                                });
+
+                            if let Some((mana_cost, skill_damage, skill_element, skill_name, taunt_threat)) = skill_info {
+                                if self.player_combat.stats.current_mana >= mana_cost {
+                                    if self.player_combat.try_use_skill(slot_idx) {
+                                        self.player_combat.stats.use_mana(mana_cost);
+
+                                        // Apply skill damage to nearest enemy in range
+                                        let skill_range = 10.0_f32;
+                                        if let Some(npc_manager) = &mut self.npc_manager {
+                                            if let Some((npc_id, npc_pos, _)) = find_target(npc_manager, skill_range) {
+                                                let npc_defense = npc_manager.combat_stats.get(&npc_id)
+                                                    .map(|s| s.defense).unwrap_or(0.0);
+                                                let sneak = npc_manager.is_sneak_attack(npc_id);
+                                                let mut damage = (skill_damage - npc_defense * 0.5).max(1.0);
+                                                if sneak {
+                                                    damage *= balance::combat::SNEAK_ATTACK_MULTIPLIER.get();
+                                                }
+                                                let result = npc_manager.damage_npc(
                                                     npc_id, damage, skill_element,
                                                     infinite_game::combat::damage::AttackType::Light,
                                                 );
+                                                self.combat_log.record_dealt(damage, skill_element, &skill_name, false);
+                                                if taunt_threat > 0.0 {
+                                                    npc_manager.taunt(npc_id, ThreatSource::Player, taunt_threat);
+                                                }
+                                                if let Some(dummy) = self.training_dummy.as_mut().filter(|d| d.id == npc_id) {
+                                                    dummy.record_hit(DummyHit::new(damage, skill_element, skill_name.clone(), false));
+                                                }
 
-                                                self.damage_numbers.push(DamageNumber {
-                                                    position: npc_pos + Vec3::Y * 1.5,
-                                                    amount: damage,
-                                                    is_crit: false,
-                                                    timer: 1.0,
-                                                });
+                                                self.damage_numbers.push(
+                                                    npc_id.0,
+                                                    infinite_game::DamageKind::Outgoing,
+                                                    npc_pos + Vec3::Y * 1.5,
+                                                    damage,
+                                                    sneak,
+                                                );
 
                                                 if result.defeated {
+                                                    self.combat_log.record_kill(result.role.name());
                                                     let npc_level = npc_manager.npc_level(npc_id);
-                                                    let xp = infinite_game::player::stats::xp_for_enemy(
+                                                    let xp = (infinite_game::player::stats::xp_for_enemy(
                                                         npc_level, infinite_game::player::stats::EnemyType::Normal,
-                                                    );
+                                                    ) as f32 * result.reward_multiplier) as u64;
                                                     let levels_gained = self.player_combat.add_xp(xp);
                                                     for new_level in levels_gained {
                                                         if let Some(growth) = &self.archetype_growth {
@@ -1472,13 +5580,44 @@ impl InfiniteApp {
                                                         infinite_game::NpcRole::QuestGiver => 15 * npc_level as u64,
                                                         infinite_game::NpcRole::Enemy => 10 * npc_level as u64,
                                                     };
+                                                    let gold_reward = (gold_reward as f32 * result.reward_multiplier) as u64;
                                                     self.player_combat.gold += gold_reward;
+                                                    self.play_stats.record(infinite_game::StatEvent::GoldEarned(gold_reward));
                                                     if result.was_friendly {
                                                         self.notification_text = Some(format!("You murdered a {}!  +{} Gold", result.role.name(), gold_reward));
+                                                    } else if result.split {
+                                                        self.notification_text = Some(format!("The elite splits apart!  +{} XP  +{} Gold", xp, gold_reward));
+                                                    } else if result.reward_multiplier > 1.0 {
+                                                        self.notification_text = Some(format!("Elite defeated!  +{} XP  +{} Gold", xp, gold_reward));
                                                     } else {
                                                         self.notification_text = Some(format!("+{} XP  +{} Gold", xp, gold_reward));
                                                     }
                                                     self.notification_timer = 1.5;
+                                                    if let Some(kill) = &result.kill {
+                                                        self.tutorials.handle(infinite_game::TutorialEvent::EnemyDefeated);
+                                                        self.play_stats.record(infinite_game::StatEvent::EnemyDefeated);
+                                                        self.speedrun_events.push(infinite_game::RunEvent::EnemyDefeated);
+                                                        let update = self.bestiary.record_kill(kill, self.timeline.active_year, habitat);
+                                                        if let Some(note) = update.message(&kill.name) {
+                                                            let text = self.notification_text.take().unwrap_or_default();
+                                                            self.notification_text = Some(format!("{}  {}", text, note));
+                                                            self.notification_timer = 2.5;
+                                                        }
+                                                    }
+                                                    if result.role == infinite_game::NpcRole::Enemy && !result.was_friendly {
+                                                        let drop_seed = self.rng.stream(infinite_core::RngStream::Loot).next_u64();
+                                                        if let Some(map) = roll_treasure_map(
+                                                            self.chunk_manager.as_ref(), drop_seed, result.reward_multiplier, player_pos, self.timeline.present_year,
+                                                        ) {
+                                                            let name = map.name.clone();
+                                                            if self.player_combat.inventory.add_item(map).is_ok() {
+                                                                self.tutorials.handle(infinite_game::TutorialEvent::TreasureMapFound);
+                                                                let text = self.notification_text.take().unwrap_or_default();
+                                                                self.notification_text = Some(format!("{}  Found a {}!", text, name));
+                                                                self.notification_timer = 2.5;
+                                                            }
+                                                        }
+                                                    }
                                                 }
                                             }
                                         }
@@ -1498,7 +5637,7 @@ impl InfiniteApp {
                 {
                     // Apply dodge velocity impulse
                     if let Some(player) = &mut self.player {
-                        let dodge_speed = 15.0_f32;
+                        let dodge_speed = balance::player::DODGE_SPEED.get();
                         let move_dir = if let Some(camera) = &self.camera {
                             let fwd = camera.forward();
                             let right = camera.right();
@@ -1521,10 +5660,15 @@ impl InfiniteApp {
                 }
 
                 // --- Player combat update ---
-                let _dot_damage = self.player_combat.update(delta);
+                let dot_damage = self.player_combat.update(delta);
+                self.show_player_number(infinite_game::DamageKind::DamageOverTime, dot_damage);
 
                 // --- Player death/respawn ---
-                if !self.player_combat.is_alive() {
+                if !self.player_combat.is_alive() && self.survival.is_some() {
+                    self.end_survival_run();
+                } else if !self.player_combat.is_alive() && self.rift.is_some() {
+                    self.end_rift_run(true);
+                } else if !self.player_combat.is_alive() {
                     self.player_combat.respawn();
                     // Teleport to spawn point
                     if let (Some(player), Some(physics), Some(chunk_manager)) =
@@ -1533,42 +5677,116 @@ impl InfiniteApp {
                         let spawn_height = chunk_manager.height_at(0.0, 0.0);
                         player.teleport(physics, Vec3::new(0.0, spawn_height + 2.0, 0.0));
                     }
+                    self.play_stats.record(infinite_game::StatEvent::Died);
                     self.notification_text = Some("You died!".to_string());
                     self.notification_timer = 3.0;
                 }
 
                 // --- Update damage numbers ---
-                self.damage_numbers.retain_mut(|dn| {
-                    dn.timer -= delta;
-                    dn.position.y += delta * 2.0; // Float upward
-                    dn.timer > 0.0
-                });
+                self.damage_numbers.update(delta);
 
                 // --- Update level-up notification ---
                 if let Some((_, timer)) = &mut self.level_up_notification {
-                    *timer -= delta;
+                    *timer -= real_delta;
                     if *timer <= 0.0 {
                         self.level_up_notification = None;
                     }
                 }
 
+                // --- Tutorials ---
+                self.update_tutorials(real_delta);
+
+                // --- Play statistics ---
+                self.play_stats.track_position(player_pos);
+                self.play_stats.record(infinite_game::StatEvent::VisitedYear(self.timeline.active_year));
+
+                // --- Torch light follows the player while a torch is held ---
+                if self.player_combat.equipment.holds_torch() {
+                    let torch_pos = player_pos + Vec3::new(0.3, 1.6, 0.0);
+                    match self.torch_light {
+                        Some(id) => self.point_lights.set_position(id, torch_pos),
+                        None => self.torch_light = Some(self.point_lights.add(PointLight::torch(torch_pos))),
+                    }
+                } else if let Some(id) = self.torch_light.take() {
+                    self.point_lights.remove(id);
+                }
+
+                // --- Waypoint: place one 100m ahead, or clear the current one ---
+                if self.input_handler.state.is_just_pressed(InputAction::Waypoint) {
+                    if let Some(id) = self.waypoint.take() {
+                        self.compass_tracker.remove(id);
+                        self.notification_text = Some("Waypoint cleared".to_string());
+                        self.notification_timer = 1.5;
+                    } else if let Some(camera) = &self.camera {
+                        let forward = camera.forward();
+                        let flat = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
+                        let mut target = player_pos + flat * 100.0;
+                        if let Some(chunk_manager) = &self.chunk_manager {
+                            target.y = chunk_manager.height_at(target.x, target.z);
+                        }
+                        self.waypoint = Some(self.compass_tracker.add(CompassMarker::new(
+                            target,
+                            "Waypoint",
+                            MarkerCategory::Waypoint,
+                        )));
+                        self.notification_text = Some("Waypoint placed".to_string());
+                        self.notification_timer = 1.5;
+                    }
+                }
+
                 // --- Interaction system ---
                 if let Some(camera) = &self.camera {
                     let forward = camera.forward();
-                    self.interaction_system.update(player_pos, forward);
+                    match (&mut self.physics_world, &self.player) {
+                        (Some(physics), Some(player)) => {
+                            let exclude = player.character.collider_handle;
+                            self.interaction_system
+                                .update_targeted(physics, player_pos, forward, camera.position(), forward, exclude);
+                        }
+                        _ => self.interaction_system.update(player_pos, forward),
+                    }
                 }
 
                 // Poll AI dialogue for responses
                 self.ai_dialogue.update();
 
+                self.update_voice_lines(real_delta);
+                self.log_finished_dialogues();
+
+                // The conversation partner turns to face the player; NPCs whose
+                // conversation ended go back to what they were doing (a
+                // shopkeeper keeps talking while the shop is open)
+                let partner = self.dialogue_partner();
+                let player_pos = self.player.as_ref().map(|p| p.position());
+                if let Some(npc_manager) = &mut self.npc_manager {
+                    if let (Some(id), Some(player_pos)) = (partner, player_pos) {
+                        npc_manager.turn_to_face(id, player_pos, real_delta);
+                    }
+                    if !self.show_shop {
+                        npc_manager.release_talking(partner);
+                    }
+                }
+                self.update_rest(delta);
+
                 // Poll NPC generator
                 if let Some(npc_manager) = &mut self.npc_manager {
                     npc_manager.npc_generator.poll(&mut npc_manager.character_cache);
                 }
 
+                // --- Lockpicking (takes the Interact key while active) ---
+                self.update_lockpicking(real_delta);
+                self.update_gathering(delta);
+
+                // Cycle between overlapping interactables (T key)
+                if self.lockpicking.is_none() && self.input_handler.state.is_just_pressed(InputAction::CycleInteract) {
+                    self.interaction_system.cycle_focus();
+                }
+
                 // Handle Interact input (E key)
-                if !self.dialogue_system.is_active() && !self.ai_dialogue.is_active() && self.input_handler.state.is_just_pressed(InputAction::Interact) {
+                if !self.dialogue_system.is_active() && !self.ai_dialogue.is_active() && self.lockpicking.is_none() && self.gathering.is_none() && self.input_handler.state.is_just_pressed(InputAction::Interact) {
                     if let Some(result) = self.interaction_system.interact() {
+                        self.tutorials.handle(infinite_game::TutorialEvent::Interacted);
+                        self.world_flags.record_interaction(&result);
                         match result {
                             InteractionResult::ShowText(text) => {
                                 self.interaction_text = Some(text);
@@ -1576,6 +5794,7 @@ impl InfiniteApp {
                             }
                             InteractionResult::ChangeTimePeriod(target_year) => {
                                 if !self.time_transitioning {
+                                    self.tutorials.handle(infinite_game::TutorialEvent::TimeTraveled);
                                     self.time_transition_source = self.timeline.active_year;
                                     self.pending_time_transition = Some(target_year);
                                     self.time_transitioning = true;
@@ -1584,8 +5803,21 @@ impl InfiniteApp {
                                 }
                             }
                             InteractionResult::PickupItem(name) => {
-                                self.notification_text = Some(format!("Picked up: {}", name));
+                                self.notification_text = Some(if name == GLIDER_ITEM {
+                                    "Picked up: Glider (hold Jump while falling to glide)".to_string()
+                                } else {
+                                    format!("Picked up: {}", name)
+                                });
                                 self.notification_timer = 3.0;
+                                // Items a GM dropped are real items
+                                if let Some(index) = self.gm_drops.iter().position(|item| item.name == name) {
+                                    let item = self.gm_drops.remove(index);
+                                    if let Err(item) = self.player_combat.inventory.add_item(item) {
+                                        self.collected_items.push(item.name);
+                                    }
+                                } else {
+                                    self.collected_items.push(name);
+                                }
                             }
                             InteractionResult::TalkToNpc(npc_id) => {
                                 // Extract NPC data first to avoid borrow conflicts
@@ -1595,6 +5827,7 @@ impl InfiniteApp {
                                             npc.name().to_string(),
                                             npc.data.role,
                                             npc.persistent_key,
+                                            npc.identity.as_ref().map(|i| i.traits.clone()).unwrap_or_default(),
                                             npc.chunk,
                                             npc.brain.as_ref()
                                                 .and_then(|b| b.current_action_name())
@@ -1610,11 +5843,14 @@ impl InfiniteApp {
                                     None
                                 };
 
-                                if let Some((npc_name, role, persistent_key, chunk, goap_state)) = npc_info {
+                                if let Some((npc_name, role, persistent_key, traits, chunk, goap_state)) = npc_info {
                                     // Shopkeeper: open shop instead of dialogue
                                     if role == infinite_game::NpcRole::Shopkeeper && self.item_catalog.is_some() {
                                         self.show_shop = true;
                                         self.shop_menu = ShopMenu::new();
+                                        self.shop_market = self.player.as_ref()
+                                            .filter(|_| self.dungeon.is_none())
+                                            .and_then(|p| self.economy.nearest_market(p.position()));
                                         self.update_cursor_capture(false);
                                         // Skip dialogue — continue below is not needed since we early-continue via the if
                                     } else {
@@ -1637,6 +5873,7 @@ impl InfiniteApp {
                                                         time_of_day: self.time_of_day.time_hours,
                                                         weather: format!("{:?}", self.weather),
                                                         player_name,
+                                                        player_appearance: Some(self.player_appearance.describe()),
                                                         npc_goap_state: goap_state,
                                                         npc_location_desc: format!("chunk ({}, {})", chunk.x, chunk.z),
                                                         relationship_level: affection,
@@ -1659,7 +5896,7 @@ impl InfiniteApp {
                                                         npc_manager.character_cache.set_pending(persistent_key);
                                                         npc_manager.npc_generator.generate_for_npc(
                                                             persistent_key, &npc_name, role,
-                                                            self.timeline.active_year, client,
+                                                            self.timeline.active_year, &traits, client,
                                                         );
                                                     }
                                                     false
@@ -1703,12 +5940,69 @@ impl InfiniteApp {
                                 }
                                 self.notification_timer = 3.0;
                             }
-                            InteractionResult::StartClimbing { height, direction } => {
-                                self.climbing = true;
-                                self.climb_direction = direction;
-                                self.climb_remaining = height;
-                                self.notification_text = Some("Climbing...".to_string());
-                                self.notification_timer = 1.5;
+                            InteractionResult::StartClimbing { position } => {
+                                let grabbed = if let (Some(player), Some(physics)) =
+                                    (&mut self.player, &self.physics_world)
+                                {
+                                    player.grab_wall(physics, position - player.position())
+                                } else {
+                                    false
+                                };
+                                if !grabbed {
+                                    self.notification_text = Some("Get closer to climb".to_string());
+                                    self.notification_timer = 1.5;
+                                }
+                            }
+                            InteractionResult::Rest(seat) => {
+                                self.rest_on_seat(seat);
+                            }
+                            InteractionResult::Gather(node) => {
+                                self.start_gathering(node);
+                            }
+                            InteractionResult::Cook(station) => {
+                                self.cooking_menu = Some(CookingMenu::new(station));
+                                self.update_cursor_capture(false);
+                            }
+                            InteractionResult::Brew => {
+                                self.brewing_menu = Some(BrewingMenu::new());
+                                self.update_cursor_capture(false);
+                            }
+                            InteractionResult::Pray(shrine) => {
+                                self.shrine_menu = Some(ShrineMenu::new(shrine));
+                                self.update_cursor_capture(false);
+                            }
+                            InteractionResult::OpenRift { gate } => {
+                                self.rift_menu = Some(RiftMenu::new(gate));
+                                self.update_cursor_capture(false);
+                            }
+                            InteractionResult::SpawnTrainingDummy { position } => {
+                                self.spawn_training_dummy(position);
+                            }
+                            InteractionResult::StartArena { center } => {
+                                self.start_arena(center);
+                            }
+                            InteractionResult::EnterDungeon(entrance) => {
+                                self.enter_dungeon(entrance);
+                            }
+                            InteractionResult::LeaveDungeon if self.rift.is_some() => {
+                                self.end_rift_run(false);
+                            }
+                            InteractionResult::LeaveDungeon => {
+                                self.leave_dungeon();
+                            }
+                            InteractionResult::Dig { map_seed } => {
+                                self.dig_treasure(map_seed);
+                            }
+                            InteractionResult::PickupKey(key) => {
+                                self.notification_text = Some(format!("Picked up: {} (added to key ring)", key.name));
+                                self.notification_timer = 3.0;
+                                self.key_ring.add(key);
+                            }
+                            InteractionResult::DisarmTrap(id) => {
+                                self.disarm_trap(id);
+                            }
+                            InteractionResult::LockedDoor { id, lock } => {
+                                self.try_unlock_door(id, lock);
                             }
                             InteractionResult::Locked => {
                                 self.notification_text = Some("It's locked".to_string());
@@ -1718,10 +6012,81 @@ impl InfiniteApp {
                     }
                 }
 
+                // --- Training dummy and practice arena ---
+                let mut arena_event = None;
+                let mut dungeon_cleared = false;
+                let mut bosses_defeated = false;
+                if let Some(npc_manager) = &self.npc_manager {
+                    if self.training_dummy.as_ref().is_some_and(|d| npc_manager.get(d.id).is_none()) {
+                        // Despawned with its chunk or by time travel
+                        self.training_dummy = None;
+                    }
+                    arena_event = self.arena.as_mut()
+                        .and_then(|arena| arena.update(|id| npc_manager.get(id).is_some()));
+                    // The dungeon is cleared once every enemy in it is defeated
+                    if !self.dungeon_enemies.is_empty() {
+                        self.dungeon_enemies.retain(|id| npc_manager.get(*id).is_some());
+                        dungeon_cleared = self.dungeon_enemies.is_empty();
+                    }
+                    if !self.dungeon_bosses.is_empty() {
+                        self.dungeon_bosses.retain(|id| npc_manager.get(*id).is_some());
+                        bosses_defeated = self.dungeon_bosses.is_empty();
+                    }
+                    // The boss room opens once the rest of the dungeon is cleared
+                    if let (Some(dungeon), Some(physics)) = (&mut self.dungeon, &mut self.physics_world) {
+                        let guards_left = self.dungeon_enemies.iter().any(|id| !self.dungeon_bosses.contains(id));
+                        if !guards_left && dungeon.raise_boss_gate(physics) {
+                            self.notification_text = Some("The portcullis to the boss chamber rises".to_string());
+                            self.notification_timer = 3.0;
+                        }
+                    }
+                }
+                match arena_event {
+                    Some(ArenaEvent::SpawnWave(_)) => self.spawn_arena_wave(),
+                    Some(ArenaEvent::Completed) => {
+                        self.arena = None;
+                        self.notification_text = Some("Arena cleared!".to_string());
+                        self.notification_timer = 3.0;
+                    }
+                    None => {}
+                }
+                if dungeon_cleared {
+                    self.notification_text = Some("Dungeon cleared! Claim the treasure.".to_string());
+                    self.notification_timer = 3.0;
+                }
+                if bosses_defeated {
+                    self.autosaver.request(AutosaveTrigger::BossDefeated);
+                }
+                if let Some(dummy) = &mut self.training_dummy {
+                    dummy.update(delta);
+                }
+                self.update_world_boss();
+                self.update_survival(delta);
+                self.update_rift();
+
+                // --- Combat log ---
+                self.combat_log.update(delta);
+                if self.input_handler.state.is_just_pressed(InputAction::EditHud) {
+                    self.hud_editor.active = !self.hud_editor.active;
+                    if !self.hud_editor.active {
+                        if let Err(e) = self.settings.save() {
+                            tracing::error!("Failed to save settings: {}", e);
+                        }
+                    }
+                }
+                if self.input_handler.state.is_just_pressed(InputAction::CombatStats) {
+                    self.combat_stats_panel.toggle();
+                }
+                if self.input_handler.state.is_just_pressed(InputAction::Timelines) {
+                    self.timeline_browser.toggle();
+                    self.update_cursor_capture(!self.timeline_browser.visible);
+                }
+
                 // --- Inventory toggle ---
                 if self.input_handler.state.is_just_pressed(InputAction::Inventory) {
                     self.show_inventory = !self.show_inventory;
                     if self.show_inventory {
+                        self.tutorials.handle(infinite_game::TutorialEvent::InventoryOpened);
                         self.update_cursor_capture(false);
                         self.inventory_menu = InventoryMenu::new();
                     } else {
@@ -1730,32 +6095,74 @@ impl InfiniteApp {
                 }
 
                 // --- Save/Load ---
-                if self.input_handler.state.is_just_pressed(InputAction::QuickSave) {
+                // Survival and rift runs are never saved
+                if self.input_handler.state.is_just_pressed(InputAction::QuickSave) && !self.in_unsaved_run() {
                     self.do_quicksave();
                 }
-                if self.input_handler.state.is_just_pressed(InputAction::QuickLoad) {
+                if self.input_handler.state.is_just_pressed(InputAction::QuickLoad) && !self.in_unsaved_run() {
                     self.do_quickload();
                 }
 
                 // --- Play time & auto-save ---
-                self.play_time += delta as f64;
-                if self.settings.gameplay.auto_save {
-                    self.auto_save_timer -= delta;
-                    if self.auto_save_timer <= 0.0 {
-                        self.do_autosave();
-                        self.auto_save_timer = self.settings.gameplay.auto_save_interval as f32;
+                self.play_time += real_delta as f64;
+                let mut history_changes = Vec::new();
+                for change in self.world_flags.drain_changes() {
+                    tracing::debug!("Flag {}.{}: {:?} -> {:?}", change.namespace, change.key, change.old, change.new);
+                    if let Some(trigger) = AutosaveTrigger::for_flag_change(&change) {
+                        self.autosaver.request(trigger);
+                    }
+                    // Quest givers offer archetype changes through a one-shot flag
+                    if change.namespace == "services" && change.key == "respec" && change.new.as_ref().is_some_and(|v| v.is_truthy()) {
+                        self.world_flags.remove("services", "respec");
+                        self.respec_menu = Some(RespecMenu::new(&self.player_combat.progression.class));
+                    }
+                    // ...and to lift curses, for a fee
+                    if change.namespace == "services" && change.key == "cleanse" && change.new.as_ref().is_some_and(|v| v.is_truthy()) {
+                        self.world_flags.remove("services", "cleanse");
+                        let cost = infinite_game::combat::CLEANSE_GOLD_COST;
+                        self.notification_text = Some(if self.player_combat.curses.is_empty() {
+                            "You carry no curse".to_string()
+                        } else if self.player_combat.gold < cost {
+                            format!("The rite costs {} gold", cost)
+                        } else {
+                            self.player_combat.gold -= cost;
+                            self.player_combat.curses.cleanse();
+                            "Your curses are lifted".to_string()
+                        });
+                        self.notification_timer = 2.0;
+                    }
+                    // Story progress made in the past rewrites history
+                    if change.namespace == "quest" && self.timeline.is_past() {
+                        history_changes.push(change);
+                    }
+                }
+                if !history_changes.is_empty() && !self.history_forked {
+                    let name = format!("Altered {}", self.timeline.year_label());
+                    self.fork_timeline(name, &history_changes);
+                }
+                for event in self.scheduler.update(&self.game_time, self.time_of_day.time_hours) {
+                    match event {
+                        ScheduledEvent::AutoSave => {
+                            self.autosaver.request(AutosaveTrigger::Timer);
+                            self.schedule_autosave();
+                        }
+                    }
+                }
+                if let Some(trigger) = self.autosaver.take_due(self.play_time) {
+                    if self.settings.gameplay.auto_save && !self.in_unsaved_run() {
+                        self.start_autosave(trigger);
                     }
                 }
 
                 // --- Timers ---
                 if self.interaction_text_timer > 0.0 {
-                    self.interaction_text_timer -= delta;
+                    self.interaction_text_timer -= real_delta;
                     if self.interaction_text_timer <= 0.0 {
                         self.interaction_text = None;
                     }
                 }
                 if self.notification_timer > 0.0 {
-                    self.notification_timer -= delta;
+                    self.notification_timer -= real_delta;
                     if self.notification_timer <= 0.0 {
                         self.notification_text = None;
                     }
@@ -1773,6 +6180,12 @@ impl InfiniteApp {
 
     fn apply_transition(&mut self, transition: StateTransition) {
         let old_state = self.app_state.clone();
+        if self.in_unsaved_run() && matches!(transition, StateTransition::Push(ApplicationState::SaveLoad { .. })) {
+            let run = if self.survival.is_some() { "Survival" } else { "Rift" };
+            self.notification_text = Some(format!("{} runs can't be saved or loaded", run));
+            self.notification_timer = 2.0;
+            return;
+        }
 
         match transition {
             StateTransition::None => return,
@@ -1803,12 +6216,39 @@ impl InfiniteApp {
             ApplicationState::Playing => {
                 // Initialize game systems if coming from character creation
                 if matches!(old_state, ApplicationState::CharacterCreation) {
+                    if let Some(character) = self.character_creator.created.take() {
+                        self.player_appearance = character.appearance.clone();
+                        self.roster.add(character.clone(), self.integration_client.as_ref());
+                        self.current_character = Some(character);
+                    }
                     self.init_game_systems();
                 }
+                // Or with a character picked from the roster, or an imported
+                // build off to the arena
+                if matches!(old_state, ApplicationState::MainMenu) {
+                    if let Some(character) = self.main_menu.arena_character.take() {
+                        self.player_appearance = character.appearance.clone();
+                        self.current_character = Some(character);
+                        self.init_survival_systems();
+                    } else if let Some(character) = self.main_menu.chosen.take().and_then(|id| self.roster.get(&id).cloned()) {
+                        self.player_appearance = character.appearance.clone();
+                        self.current_character = Some(character);
+                        if std::mem::take(&mut self.main_menu.survival) {
+                            self.init_survival_systems();
+                        } else {
+                            self.init_game_systems();
+                            self.resume_character_progress();
+                        }
+                    }
+                }
             }
             ApplicationState::MainMenu => {
                 // Cleanup game systems when returning to main menu
                 if matches!(old_state, ApplicationState::Playing | ApplicationState::Paused | ApplicationState::SaveLoad { .. }) {
+                    // Survival runs start from the starter kit and don't count as progress
+                    if self.survival.is_none() {
+                        self.record_character_session();
+                    }
                     self.cleanup_game_systems();
                     self.current_character = None;
                     self.save_load_menu = None;
@@ -1824,6 +6264,8 @@ impl InfiniteApp {
                             self.pending_catalog = Some(client.list_project_items());
                         }
                     }
+                    // Send changes made offline and fetch characters made elsewhere
+                    self.roster.sync(self.integration_client.as_ref());
                 }
             }
             _ => {}
@@ -1837,10 +6279,17 @@ impl InfiniteApp {
             None => return,
         };
 
+        // Nothing to draw with until the renderer is rebuilt
+        if self.rebuild_renderer {
+            return;
+        }
+
         // Check if we need to recreate swapchain first
         if let Some(render_ctx) = &self.render_ctx {
             if render_ctx.recreate_swapchain {
-                self.recreate_swapchain();
+                if let Err(e) = self.recreate_swapchain() {
+                    self.on_render_error(e);
+                }
                 return;
             }
         } else {
@@ -1855,17 +6304,21 @@ impl InfiniteApp {
         }
 
         // Acquire next swapchain image
-        let (image_index, suboptimal, acquire_future) = {
+        let acquired = {
             let render_ctx = self.render_ctx.as_mut().unwrap();
-            match acquire_next_image(render_ctx.swapchain.clone(), None)
-                .map_err(Validated::unwrap)
-            {
-                Ok(r) => r,
-                Err(VulkanError::OutOfDate) => {
+            acquire_next_image(render_ctx.swapchain.clone(), None)
+        };
+        let (image_index, suboptimal, acquire_future) = match acquired {
+            Ok(r) => r,
+            Err(Validated::Error(VulkanError::OutOfDate)) => {
+                if let Some(render_ctx) = &mut self.render_ctx {
                     render_ctx.recreate_swapchain = true;
-                    return;
                 }
-                Err(e) => panic!("Failed to acquire next image: {e}"),
+                return;
+            }
+            Err(e) => {
+                self.on_render_error(vulkan_error(RENDER_ACQUIRE_FAILED, "Failed to acquire next image", e));
+                return;
             }
         };
 
@@ -1881,7 +6334,21 @@ impl InfiniteApp {
         let mut save_load_pending_action: Option<(StateTransition, SaveLoadAction)> = None;
         let mut inventory_pending_action = InventoryAction::None;
         let mut shop_pending_action = ShopAction::None;
+        let mut respec_pending_action: Option<RespecAction> = None;
+        let mut cooking_pending_action: Option<CookingAction> = None;
+        let mut brewing_pending_action: Option<BrewingAction> = None;
+        let mut shrine_pending_action: Option<ShrineAction> = None;
+        let mut survival_pending_action: Option<SurvivalAction> = None;
+        let mut rift_pending_action: Option<RiftAction> = None;
         let mut close_inventory = false;
+        let mut timeline_action: Option<TimelineAction> = None;
+        let mut error_action: Option<ErrorDialogAction> = None;
+        let timelines_open = self.timeline_browser.visible;
+        let mut spawn_dummy_at: Option<Vec3> = None;
+        let mut start_arena_at: Option<Vec3> = None;
+        let mut spawn_template: Option<(infinite_integration::ServerCharacter, infinite_game::NpcRole)> = None;
+        let mut gm_action: Option<GmAction> = None;
+        let mut gift_item: Option<usize> = None;
 
         if let Some(gui) = &mut self.gui {
             gui.immediate_ui(|gui| {
@@ -1921,7 +6388,38 @@ impl InfiniteApp {
                                     .map(|c| c.is_admin()).unwrap_or(false);
                                 let user_name = self.integration_client.as_ref()
                                     .and_then(|c| c.user_name());
-                                self.main_menu.render(ui, is_admin, user_name.as_deref())
+                                let mut transition = self.main_menu.render(ui, is_admin, user_name.as_deref(), &self.roster);
+                                if let Some(id) = self.main_menu.delete_requested.take() {
+                                    self.roster.delete(&id, self.integration_client.as_ref());
+                                }
+                                if let Some(target) = self.main_menu.import_requested.take() {
+                                    // Gear is checked against the catalog when online, the starter kits otherwise
+                                    let catalog = self.item_catalog.as_ref().map(|c| c.items()).unwrap_or(&[]);
+                                    match CharacterBuild::read(&self.main_menu.import_text).and_then(|build| build.import(catalog)) {
+                                        Ok(ImportedBuild { character, mut notes }) => {
+                                            if self.item_catalog.is_none() && !notes.is_empty() {
+                                                notes.push("Log in to check gear against the full item catalog".to_string());
+                                            }
+                                            let class = character.archetype.map_or("", |a| a.name());
+                                            let mut notice = format!("Imported {}, level {} {}", character.name, character.snapshot.level, class);
+                                            if !notes.is_empty() {
+                                                notice = format!("{}. {}.", notice, notes.join(". "));
+                                            }
+                                            self.main_menu.finish_import(notice);
+                                            match target {
+                                                ImportTarget::Roster => {
+                                                    self.roster.add(character, self.integration_client.as_ref());
+                                                }
+                                                ImportTarget::Arena => {
+                                                    self.main_menu.arena_character = Some(character);
+                                                    transition = StateTransition::Replace(ApplicationState::Playing);
+                                                }
+                                            }
+                                        }
+                                        Err(e) => self.main_menu.import_notice = Some(Err(e.to_string())),
+                                    }
+                                }
+                                transition
                             }
                             ApplicationState::CharacterCreation => {
                                 self.character_creator.render(ui)
@@ -1931,6 +6429,7 @@ impl InfiniteApp {
                                     let (transition, apply) = settings_menu.render(ui);
                                     if apply {
                                         self.settings = settings_menu.working_settings().clone();
+                                        self.memory.budget = self.settings.video.memory_budget.clone();
                                         should_save_settings = true;
                                     }
                                     transition
@@ -1938,7 +6437,45 @@ impl InfiniteApp {
                                     StateTransition::Pop
                                 }
                             }
-                            ApplicationState::Paused => self.pause_menu.render(ui),
+                            ApplicationState::Paused => {
+                                let summary = PauseSummary {
+                                    play_time: self.play_time,
+                                    year: self.timeline.active_year,
+                                    time_of_day: format!(
+                                        "{} ({})",
+                                        self.time_of_day.formatted_time(),
+                                        self.time_of_day.period_name(),
+                                    ),
+                                    weather: self.weather.current.name(),
+                                    level: self.player_combat.progression.level,
+                                    gold: self.player_combat.gold,
+                                    npcs_befriended: self.relationship_manager.befriended_count(),
+                                    bestiary_entries: self.bestiary.len(),
+                                    stats: &self.play_stats,
+                                    relationships: &self.relationship_manager,
+                                    speedrun: self.speedrun.is_some(),
+                                };
+                                let transition = self.pause_menu.render(ui, &summary);
+                                if std::mem::take(&mut self.pause_menu.share_requested) {
+                                    if let Some(character) = &self.current_character {
+                                        self.pause_menu.share(CharacterBuild::capture(character, &self.player_combat));
+                                    }
+                                }
+                                if std::mem::take(&mut self.pause_menu.export_requested) {
+                                    if let Some(shared) = &mut self.pause_menu.shared {
+                                        shared.file = Some(export_build_file(&shared.build).map_err(|e| e.to_string()));
+                                    }
+                                }
+                                if std::mem::take(&mut self.pause_menu.export_splits_requested) {
+                                    if let Some(run) = &self.speedrun {
+                                        self.pause_menu.speedrun_notice = Some(match save::export_splits(run, &self.speedrun_records) {
+                                            Ok(path) => format!("Splits saved to {}", path.display()),
+                                            Err(e) => format!("Couldn't save the splits: {}", e),
+                                        });
+                                    }
+                                }
+                                transition
+                            }
                             ApplicationState::SaveLoad { is_saving } => {
                                 let is_saving = *is_saving;
                                 if self.save_load_menu.is_none() {
@@ -1955,6 +6492,20 @@ impl InfiniteApp {
                                 StateTransition::None
                             }
                             ApplicationState::Playing => {
+                                // Move and scale HUD widgets per the player's layout
+                                let hud = &self.settings.hud;
+                                apply_layout(&ctx, hud, HudWidget::PlayerStats, &["player_stats", "status_effects"]);
+                                apply_layout(&ctx, hud, HudWidget::Compass, &["compass_bar"]);
+                                apply_layout(&ctx, hud, HudWidget::TimeWeather, &["time_weather"]);
+                                apply_layout(&ctx, hud, HudWidget::Minimap, &["minimap"]);
+                                apply_layout(
+                                    &ctx,
+                                    hud,
+                                    HudWidget::SkillBar,
+                                    &["skill_bar", "dodge_indicator", "sneak_indicator", "block_indicator", "weapon_coating"],
+                                );
+                                apply_layout(&ctx, hud, HudWidget::Hotbar, &["consumable_hotbar"]);
+
                                 // Player stats from combat system
                                 let hp = self.player_combat.current_hp();
                                 let max_hp = self.player_combat.max_hp();
@@ -1971,252 +6522,589 @@ impl InfiniteApp {
                                 let weather_name = self.weather.current.name();
 
                                 // Top-left: HP, Level, Mana, XP
-                                egui::Area::new(egui::Id::new("player_stats"))
-                                    .fixed_pos([10.0, 10.0])
-                                    .show(&ctx, |ui| {
-                                        egui::Frame::new()
-                                            .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200))
-                                            .corner_radius(8.0)
-                                            .inner_margin(12.0)
-                                            .show(ui, |ui| {
-                                                ui.set_min_width(180.0);
-
-                                                // Level
-                                                ui.label(
-                                                    egui::RichText::new(format!("Level {}", level))
-                                                        .font(egui::FontId::proportional(18.0))
-                                                        .color(egui::Color32::from_rgb(255, 215, 0))
-                                                );
-
-                                                ui.add_space(8.0);
+                                if self.settings.hud.is_enabled(HudWidget::PlayerStats) {
+                                    egui::Area::new(egui::Id::new("player_stats"))
+                                        .fixed_pos([10.0, 10.0])
+                                        .show(&ctx, |ui| {
+                                            egui::Frame::new()
+                                                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200))
+                                                .corner_radius(8.0)
+                                                .inner_margin(12.0)
+                                                .show(ui, |ui| {
+                                                    ui.set_min_width(180.0);
 
-                                                // HP Bar
-                                                ui.horizontal(|ui| {
+                                                    // Level
                                                     ui.label(
-                                                        egui::RichText::new("HP")
-                                                            .font(egui::FontId::proportional(12.0))
-                                                            .color(egui::Color32::from_rgb(200, 80, 80))
+                                                        egui::RichText::new(format!("Level {}", level))
+                                                            .font(egui::FontId::proportional(18.0))
+                                                            .color(egui::Color32::from_rgb(255, 215, 0))
                                                     );
-                                                    ui.label(
-                                                        egui::RichText::new(format!("{:.0}/{:.0}", hp, max_hp))
-                                                            .font(egui::FontId::proportional(12.0))
-                                                            .color(egui::Color32::from_rgb(180, 180, 180))
+
+                                                    ui.add_space(8.0);
+
+                                                    // HP Bar
+                                                    ui.horizontal(|ui| {
+                                                        ui.label(
+                                                            egui::RichText::new("HP")
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(200, 80, 80))
+                                                        );
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{:.0}/{:.0}", hp, max_hp))
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(180, 180, 180))
+                                                        );
+                                                    });
+                                                    let hp_rect = ui.available_rect_before_wrap();
+                                                    let hp_bar_rect = egui::Rect::from_min_size(
+                                                        hp_rect.min,
+                                                        egui::vec2(160.0, 12.0)
                                                     );
-                                                });
-                                                let hp_rect = ui.available_rect_before_wrap();
-                                                let hp_bar_rect = egui::Rect::from_min_size(
-                                                    hp_rect.min,
-                                                    egui::vec2(160.0, 12.0)
-                                                );
-                                                ui.painter().rect_filled(hp_bar_rect, 3.0, egui::Color32::from_rgb(60, 20, 20));
-                                                let hp_fill = egui::Rect::from_min_size(
-                                                    hp_bar_rect.min,
-                                                    egui::vec2(160.0 * (hp / max_hp), 12.0)
-                                                );
-                                                ui.painter().rect_filled(hp_fill, 3.0, egui::Color32::from_rgb(200, 50, 50));
-                                                ui.allocate_space(egui::vec2(160.0, 12.0));
+                                                    ui.painter().rect_filled(hp_bar_rect, 3.0, egui::Color32::from_rgb(60, 20, 20));
+                                                    let hp_fill = egui::Rect::from_min_size(
+                                                        hp_bar_rect.min,
+                                                        egui::vec2(160.0 * (hp / max_hp), 12.0)
+                                                    );
+                                                    ui.painter().rect_filled(hp_fill, 3.0, egui::Color32::from_rgb(200, 50, 50));
+                                                    ui.allocate_space(egui::vec2(160.0, 12.0));
 
-                                                ui.add_space(6.0);
+                                                    ui.add_space(6.0);
 
-                                                // Mana Bar
-                                                ui.horizontal(|ui| {
-                                                    ui.label(
-                                                        egui::RichText::new("MP")
-                                                            .font(egui::FontId::proportional(12.0))
-                                                            .color(egui::Color32::from_rgb(80, 120, 200))
+                                                    // Mana Bar
+                                                    ui.horizontal(|ui| {
+                                                        ui.label(
+                                                            egui::RichText::new("MP")
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(80, 120, 200))
+                                                        );
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{:.0}/{:.0}", mana, max_mana))
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(180, 180, 180))
+                                                        );
+                                                    });
+                                                    let mana_rect = ui.available_rect_before_wrap();
+                                                    let mana_bar_rect = egui::Rect::from_min_size(
+                                                        mana_rect.min,
+                                                        egui::vec2(160.0, 12.0)
                                                     );
-                                                    ui.label(
-                                                        egui::RichText::new(format!("{:.0}/{:.0}", mana, max_mana))
-                                                            .font(egui::FontId::proportional(12.0))
-                                                            .color(egui::Color32::from_rgb(180, 180, 180))
+                                                    ui.painter().rect_filled(mana_bar_rect, 3.0, egui::Color32::from_rgb(20, 30, 60));
+                                                    let mana_fill = egui::Rect::from_min_size(
+                                                        mana_bar_rect.min,
+                                                        egui::vec2(160.0 * (mana / max_mana), 12.0)
                                                     );
-                                                });
-                                                let mana_rect = ui.available_rect_before_wrap();
-                                                let mana_bar_rect = egui::Rect::from_min_size(
-                                                    mana_rect.min,
-                                                    egui::vec2(160.0, 12.0)
-                                                );
-                                                ui.painter().rect_filled(mana_bar_rect, 3.0, egui::Color32::from_rgb(20, 30, 60));
-                                                let mana_fill = egui::Rect::from_min_size(
-                                                    mana_bar_rect.min,
-                                                    egui::vec2(160.0 * (mana / max_mana), 12.0)
-                                                );
-                                                ui.painter().rect_filled(mana_fill, 3.0, egui::Color32::from_rgb(50, 100, 200));
-                                                ui.allocate_space(egui::vec2(160.0, 12.0));
+                                                    ui.painter().rect_filled(mana_fill, 3.0, egui::Color32::from_rgb(50, 100, 200));
+                                                    ui.allocate_space(egui::vec2(160.0, 12.0));
 
-                                                ui.add_space(6.0);
+                                                    ui.add_space(6.0);
 
-                                                // XP Bar
-                                                ui.horizontal(|ui| {
+                                                    // XP Bar
+                                                    ui.horizontal(|ui| {
+                                                        ui.label(
+                                                            egui::RichText::new("XP")
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(150, 100, 200))
+                                                        );
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{}/{}", current_xp, xp_to_next))
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(180, 180, 180))
+                                                        );
+                                                    });
+                                                    let xp_rect = ui.available_rect_before_wrap();
+                                                    let xp_bar_rect = egui::Rect::from_min_size(
+                                                        xp_rect.min,
+                                                        egui::vec2(160.0, 12.0)
+                                                    );
+                                                    ui.painter().rect_filled(xp_bar_rect, 3.0, egui::Color32::from_rgb(30, 20, 50));
+                                                    let xp_fill = egui::Rect::from_min_size(
+                                                        xp_bar_rect.min,
+                                                        egui::vec2(160.0 * xp_fraction, 12.0)
+                                                    );
+                                                    ui.painter().rect_filled(xp_fill, 3.0, egui::Color32::from_rgb(100, 50, 200));
+                                                    ui.allocate_space(egui::vec2(160.0, 12.0));
+
+                                                    ui.add_space(6.0);
+
+                                                    // Paradox bar, only while the timeline is strained
+                                                    if self.paradox.level() > 0.0 {
+                                                        ui.horizontal(|ui| {
+                                                            ui.label(
+                                                                egui::RichText::new("PX")
+                                                                    .font(egui::FontId::proportional(12.0))
+                                                                    .color(egui::Color32::from_rgb(200, 120, 255))
+                                                            );
+                                                            ui.label(
+                                                                egui::RichText::new(self.paradox.stage().name())
+                                                                    .font(egui::FontId::proportional(12.0))
+                                                                    .color(egui::Color32::from_rgb(180, 180, 180))
+                                                            );
+                                                        });
+                                                        let paradox_rect = ui.available_rect_before_wrap();
+                                                        let paradox_bar_rect = egui::Rect::from_min_size(
+                                                            paradox_rect.min,
+                                                            egui::vec2(160.0, 12.0)
+                                                        );
+                                                        ui.painter().rect_filled(paradox_bar_rect, 3.0, egui::Color32::from_rgb(40, 20, 55));
+                                                        let paradox_fill = egui::Rect::from_min_size(
+                                                            paradox_bar_rect.min,
+                                                            egui::vec2(160.0 * self.paradox.fraction(), 12.0)
+                                                        );
+                                                        ui.painter().rect_filled(paradox_fill, 3.0, egui::Color32::from_rgb(170, 80, 255));
+                                                        ui.allocate_space(egui::vec2(160.0, 12.0));
+
+                                                        ui.add_space(6.0);
+                                                    }
+
+                                                    // Gold
                                                     ui.label(
-                                                        egui::RichText::new("XP")
-                                                            .font(egui::FontId::proportional(12.0))
-                                                            .color(egui::Color32::from_rgb(150, 100, 200))
+                                                        egui::RichText::new(format!("Gold: {}", self.player_combat.gold))
+                                                            .font(egui::FontId::proportional(13.0))
+                                                            .color(egui::Color32::from_rgb(255, 215, 0))
                                                     );
+
+                                                    // Carried weight, tinted by encumbrance
+                                                    let load = self.player_combat.carried_weight();
+                                                    let capacity = self.player_combat.carry_capacity();
+                                                    let tier = infinite_game::Encumbrance::from_load(load, capacity);
+                                                    let [r, g, b] = tier.color();
+                                                    let text = if tier == infinite_game::Encumbrance::Unburdened {
+                                                        format!("Load: {:.0}/{:.0} kg", load, capacity)
+                                                    } else {
+                                                        format!("Load: {:.0}/{:.0} kg ({})", load, capacity, tier.name())
+                                                    };
                                                     ui.label(
-                                                        egui::RichText::new(format!("{}/{}", current_xp, xp_to_next))
+                                                        egui::RichText::new(text)
                                                             .font(egui::FontId::proportional(12.0))
-                                                            .color(egui::Color32::from_rgb(180, 180, 180))
+                                                            .color(egui::Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8))
                                                     );
                                                 });
-                                                let xp_rect = ui.available_rect_before_wrap();
-                                                let xp_bar_rect = egui::Rect::from_min_size(
-                                                    xp_rect.min,
-                                                    egui::vec2(160.0, 12.0)
-                                                );
-                                                ui.painter().rect_filled(xp_bar_rect, 3.0, egui::Color32::from_rgb(30, 20, 50));
-                                                let xp_fill = egui::Rect::from_min_size(
-                                                    xp_bar_rect.min,
-                                                    egui::vec2(160.0 * xp_fraction, 12.0)
-                                                );
-                                                ui.painter().rect_filled(xp_fill, 3.0, egui::Color32::from_rgb(100, 50, 200));
-                                                ui.allocate_space(egui::vec2(160.0, 12.0));
+                                        });
 
-                                                ui.add_space(6.0);
+                                    // Status effects row (below player stats)
+                                    if !self.player_combat.status_manager.effects.is_empty() || !self.player_combat.curses.is_empty() {
+                                        egui::Area::new(egui::Id::new("status_effects"))
+                                            .fixed_pos([10.0, 190.0])
+                                            .show(&ctx, |ui| {
+                                                ui.horizontal(|ui| {
+                                                    for effect in &self.player_combat.status_manager.effects {
+                                                        let box_size = egui::vec2(28.0, 28.0);
+                                                        let (rect, _) = ui.allocate_exact_size(box_size, egui::Sense::hover());
+
+                                                        // Background color from element or hardcoded for non-elemental
+                                                        let bg_color = if let Some(elem) = effect.effect_type.element() {
+                                                            let c = elem.color();
+                                                            egui::Color32::from_rgba_unmultiplied(
+                                                                (c[0] * 180.0) as u8,
+                                                                (c[1] * 180.0) as u8,
+                                                                (c[2] * 180.0) as u8,
+                                                                200,
+                                                            )
+                                                        } else {
+                                                            match effect.effect_type {
+                                                                infinite_game::StatusEffectType::Poisoned => egui::Color32::from_rgba_unmultiplied(80, 160, 40, 200),
+                                                                infinite_game::StatusEffectType::Stunned => egui::Color32::from_rgba_unmultiplied(200, 200, 50, 200),
+                                                                infinite_game::StatusEffectType::Slowed => egui::Color32::from_rgba_unmultiplied(100, 100, 150, 200),
+                                                                infinite_game::StatusEffectType::Weakened => egui::Color32::from_rgba_unmultiplied(150, 80, 80, 200),
+                                                                infinite_game::StatusEffectType::Empowered => egui::Color32::from_rgba_unmultiplied(220, 180, 50, 200),
+                                                                infinite_game::StatusEffectType::Hastened => egui::Color32::from_rgba_unmultiplied(50, 200, 180, 200),
+                                                                infinite_game::StatusEffectType::Shielded => egui::Color32::from_rgba_unmultiplied(180, 180, 220, 200),
+                                                                infinite_game::StatusEffectType::Invisible => egui::Color32::from_rgba_unmultiplied(140, 150, 200, 120),
+                                                                infinite_game::StatusEffectType::FireWarded => egui::Color32::from_rgba_unmultiplied(220, 120, 40, 200),
+                                                                infinite_game::StatusEffectType::Enlightened => egui::Color32::from_rgba_unmultiplied(200, 170, 240, 200),
+                                                                infinite_game::StatusEffectType::Attuned => egui::Color32::from_rgba_unmultiplied(230, 150, 90, 200),
+                                                                infinite_game::StatusEffectType::Swift => egui::Color32::from_rgba_unmultiplied(120, 220, 140, 200),
+                                                                infinite_game::StatusEffectType::RiftTouched => egui::Color32::from_rgba_unmultiplied(230, 90, 60, 200),
+                                                                _ => egui::Color32::from_rgba_unmultiplied(120, 120, 120, 200),
+                                                            }
+                                                        };
 
-                                                // Gold
-                                                ui.label(
-                                                    egui::RichText::new(format!("Gold: {}", self.player_combat.gold))
-                                                        .font(egui::FontId::proportional(13.0))
-                                                        .color(egui::Color32::from_rgb(255, 215, 0))
-                                                );
-                                            });
-                                    });
+                                                        ui.painter().rect_filled(rect, 3.0, bg_color);
+                                                        ui.painter().rect_stroke(
+                                                            rect,
+                                                            3.0,
+                                                            egui::Stroke::new(1.0, egui::Color32::from_rgb(40, 40, 40)),
+                                                            egui::epaint::StrokeKind::Outside,
+                                                        );
 
-                                // Status effects row (below player stats)
-                                if !self.player_combat.status_manager.effects.is_empty() {
-                                    egui::Area::new(egui::Id::new("status_effects"))
-                                        .fixed_pos([10.0, 170.0])
-                                        .show(&ctx, |ui| {
-                                            ui.horizontal(|ui| {
-                                                for effect in &self.player_combat.status_manager.effects {
-                                                    let box_size = egui::vec2(28.0, 28.0);
-                                                    let (rect, _) = ui.allocate_exact_size(box_size, egui::Sense::hover());
-
-                                                    // Background color from element or hardcoded for non-elemental
-                                                    let bg_color = if let Some(elem) = effect.effect_type.element() {
-                                                        let c = elem.color();
-                                                        egui::Color32::from_rgba_unmultiplied(
-                                                            (c[0] * 180.0) as u8,
-                                                            (c[1] * 180.0) as u8,
-                                                            (c[2] * 180.0) as u8,
-                                                            200,
-                                                        )
-                                                    } else {
-                                                        match effect.effect_type {
-                                                            infinite_game::StatusEffectType::Poisoned => egui::Color32::from_rgba_unmultiplied(80, 160, 40, 200),
-                                                            infinite_game::StatusEffectType::Stunned => egui::Color32::from_rgba_unmultiplied(200, 200, 50, 200),
-                                                            infinite_game::StatusEffectType::Slowed => egui::Color32::from_rgba_unmultiplied(100, 100, 150, 200),
-                                                            infinite_game::StatusEffectType::Weakened => egui::Color32::from_rgba_unmultiplied(150, 80, 80, 200),
-                                                            infinite_game::StatusEffectType::Empowered => egui::Color32::from_rgba_unmultiplied(220, 180, 50, 200),
-                                                            infinite_game::StatusEffectType::Hastened => egui::Color32::from_rgba_unmultiplied(50, 200, 180, 200),
-                                                            infinite_game::StatusEffectType::Shielded => egui::Color32::from_rgba_unmultiplied(180, 180, 220, 200),
-                                                            _ => egui::Color32::from_rgba_unmultiplied(120, 120, 120, 200),
-                                                        }
-                                                    };
+                                                        // 3-letter abbreviation
+                                                        let abbrev: String = effect.effect_type.name().chars().take(3).collect::<String>().to_uppercase();
+                                                        ui.painter().text(
+                                                            rect.center() - egui::vec2(0.0, 3.0),
+                                                            egui::Align2::CENTER_CENTER,
+                                                            &abbrev,
+                                                            egui::FontId::proportional(9.0),
+                                                            egui::Color32::WHITE,
+                                                        );
 
-                                                    ui.painter().rect_filled(rect, 3.0, bg_color);
-                                                    ui.painter().rect_stroke(
-                                                        rect,
-                                                        3.0,
-                                                        egui::Stroke::new(1.0, egui::Color32::from_rgb(40, 40, 40)),
-                                                        egui::epaint::StrokeKind::Outside,
-                                                    );
+                                                        // Duration countdown (rift boons last until the run ends)
+                                                        if effect.duration.is_finite() {
+                                                            ui.painter().text(
+                                                                egui::pos2(rect.center().x, rect.max.y - 2.0),
+                                                                egui::Align2::CENTER_BOTTOM,
+                                                                format!("{:.0}", effect.duration),
+                                                                egui::FontId::proportional(8.0),
+                                                                egui::Color32::from_rgb(180, 180, 180),
+                                                            );
+                                                        }
 
-                                                    // 3-letter abbreviation
-                                                    let abbrev: String = effect.effect_type.name().chars().take(3).collect::<String>().to_uppercase();
-                                                    ui.painter().text(
-                                                        rect.center() - egui::vec2(0.0, 3.0),
-                                                        egui::Align2::CENTER_CENTER,
-                                                        &abbrev,
-                                                        egui::FontId::proportional(9.0),
-                                                        egui::Color32::WHITE,
-                                                    );
+                                                        // Shield HP bar for Shielded effect
+                                                        if effect.effect_type == infinite_game::StatusEffectType::Shielded
+                                                            && effect.shield_hp_max > 0.0
+                                                        {
+                                                            let bar_rect = egui::Rect::from_min_size(
+                                                                egui::pos2(rect.min.x, rect.max.y + 1.0),
+                                                                egui::vec2(28.0, 3.0),
+                                                            );
+                                                            ui.painter().rect_filled(bar_rect, 1.0, egui::Color32::from_rgb(40, 40, 60));
+                                                            let fill_rect = egui::Rect::from_min_size(
+                                                                bar_rect.min,
+                                                                egui::vec2(28.0 * (effect.shield_hp / effect.shield_hp_max), 3.0),
+                                                            );
+                                                            ui.painter().rect_filled(fill_rect, 1.0, egui::Color32::from_rgb(100, 160, 240));
+                                                        }
 
-                                                    // Duration countdown
-                                                    ui.painter().text(
-                                                        egui::pos2(rect.center().x, rect.max.y - 2.0),
-                                                        egui::Align2::CENTER_BOTTOM,
-                                                        format!("{:.0}", effect.duration),
-                                                        egui::FontId::proportional(8.0),
-                                                        egui::Color32::from_rgb(180, 180, 180),
-                                                    );
+                                                        ui.add_space(2.0);
+                                                    }
 
-                                                    // Shield HP bar for Shielded effect
-                                                    if effect.effect_type == infinite_game::StatusEffectType::Shielded
-                                                        && effect.shield_hp_max > 0.0
-                                                    {
-                                                        let bar_rect = egui::Rect::from_min_size(
-                                                            egui::pos2(rect.min.x, rect.max.y + 1.0),
-                                                            egui::vec2(28.0, 3.0),
+                                                    // Curses never run out, so they get no countdown
+                                                    for curse in self.player_combat.curses.iter() {
+                                                        let (rect, response) = ui.allocate_exact_size(egui::vec2(28.0, 28.0), egui::Sense::hover());
+                                                        ui.painter().rect_filled(rect, 3.0, egui::Color32::from_rgba_unmultiplied(50, 10, 60, 230));
+                                                        ui.painter().rect_stroke(
+                                                            rect,
+                                                            3.0,
+                                                            egui::Stroke::new(1.5, egui::Color32::from_rgb(220, 60, 240)),
+                                                            egui::epaint::StrokeKind::Outside,
                                                         );
-                                                        ui.painter().rect_filled(bar_rect, 1.0, egui::Color32::from_rgb(40, 40, 60));
-                                                        let fill_rect = egui::Rect::from_min_size(
-                                                            bar_rect.min,
-                                                            egui::vec2(28.0 * (effect.shield_hp / effect.shield_hp_max), 3.0),
+                                                        let abbrev: String = curse.name().chars().take(3).collect::<String>().to_uppercase();
+                                                        ui.painter().text(
+                                                            rect.center(),
+                                                            egui::Align2::CENTER_CENTER,
+                                                            &abbrev,
+                                                            egui::FontId::proportional(9.0),
+                                                            egui::Color32::from_rgb(240, 180, 250),
                                                         );
-                                                        ui.painter().rect_filled(fill_rect, 1.0, egui::Color32::from_rgb(100, 160, 240));
+                                                        response.on_hover_text(format!("Curse: {} ({})", curse.name(), curse.description()));
+                                                        ui.add_space(2.0);
                                                     }
+                                                });
+                                            });
+                                    }
 
-                                                    ui.add_space(2.0);
-                                                }
+                                    // Meal buffs, below the status effects
+                                    if !self.player_combat.food.is_empty() {
+                                        egui::Area::new(egui::Id::new("food_buffs"))
+                                            .fixed_pos([10.0, 226.0])
+                                            .show(&ctx, |ui| {
+                                                ui.horizontal(|ui| {
+                                                    for buff in self.player_combat.food.iter() {
+                                                        let [r, g, b] = buff.kind.color();
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{} {:.0}s", buff.kind.name(), buff.remaining))
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)),
+                                                        )
+                                                        .on_hover_text(buff.source);
+                                                    }
+                                                });
                                             });
-                                        });
+                                    }
+                                }
+
+                                // Crosshair (hidden while the cursor is free for menus)
+                                if self.cursor_captured {
+                                    let spread = self.player_combat.equipment.holds_ranged().then(|| self.aim_spread.current());
+                                    // Vertical FOV matches the scene projection
+                                    draw_crosshair(&ctx, &self.settings.crosshair, spread, 60.0, self.aim_locked);
+                                }
+
+                                // Top-centre: Compass bar, and the minimap below the time panel;
+                                // objectives and waypoints also marked over the view
+                                let show_compass = self.settings.hud.is_enabled(HudWidget::Compass);
+                                let show_minimap = self.settings.hud.is_enabled(HudWidget::Minimap);
+                                let show_markers = self.settings.hud.is_enabled(HudWidget::ScreenMarkers);
+                                if show_compass || show_minimap || show_markers {
+                                    if let Some(camera) = &self.camera {
+                                        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                                        let mut world_markers: Vec<CompassMarker> = self.interaction_system.iter()
+                                            .filter(|i| !matches!(i.kind, infinite_game::InteractableKind::Npc { .. }))
+                                            .map(|i| CompassMarker::new(i.position, i.kind.name(), MarkerCategory::Interactable))
+                                            .collect();
+                                        if let Some(npc_manager) = &self.npc_manager {
+                                            world_markers.extend(npc_manager.npcs_iter().map(|npc| {
+                                                CompassMarker::new(npc.position, npc.data.name.clone(), MarkerCategory::Npc)
+                                            }));
+                                        }
+                                        if self.dungeon.is_none() {
+                                            world_markers.extend(self.locations.markers(self.timeline.active_year));
+                                            world_markers.extend(self.world_bosses.marker());
+                                            world_markers.extend(self.economy.caravans()
+                                                .filter(|caravan| caravan.escorted)
+                                                .filter_map(|caravan| self.economy.market(caravan.to))
+                                                .map(|market| CompassMarker::new(market.position, format!("Caravan to {}", market.name), MarkerCategory::Objective)));
+                                        }
+                                        let heading = infinite_game::compass::heading(camera.forward());
+                                        if show_compass {
+                                            let entries = self.compass_tracker.entries(
+                                                &world_markers,
+                                                player_pos,
+                                                heading,
+                                                self.compass_hud.visible_arc,
+                                                &self.settings.gameplay.compass_filter,
+                                            );
+                                            self.compass_hud.render(&ctx, heading, &entries);
+                                        }
+                                        if show_minimap {
+                                            self.minimap_hud.render(
+                                                &ctx,
+                                                player_pos,
+                                                heading,
+                                                self.compass_tracker.iter().chain(&world_markers),
+                                                &self.settings.gameplay.compass_filter,
+                                            );
+                                        }
+                                        if show_markers {
+                                            ScreenMarkerHud::new(self.settings.hud.screen_markers.scale).render(
+                                                &ctx,
+                                                self.compass_tracker.iter().chain(&world_markers),
+                                                player_pos,
+                                                hud_view_proj(camera, ctx.screen_rect().size()),
+                                                &self.settings.gameplay.compass_filter,
+                                            );
+                                        }
+                                    }
                                 }
 
                                 // Top-right: Time of day + Weather
-                                egui::Area::new(egui::Id::new("time_weather"))
-                                    .anchor(egui::Align2::RIGHT_TOP, [-10.0, 10.0])
-                                    .show(&ctx, |ui| {
-                                        egui::Frame::new()
-                                            .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200))
-                                            .corner_radius(8.0)
-                                            .inner_margin(12.0)
-                                            .show(ui, |ui| {
-                                                ui.with_layout(egui::Layout::top_down(egui::Align::RIGHT), |ui| {
-                                                    // Time
-                                                    ui.label(
-                                                        egui::RichText::new(&time_str)
-                                                            .font(egui::FontId::proportional(24.0))
-                                                            .color(egui::Color32::from_rgb(255, 255, 200))
-                                                    );
-                                                    ui.label(
-                                                        egui::RichText::new(period)
-                                                            .font(egui::FontId::proportional(14.0))
-                                                            .color(egui::Color32::from_rgb(180, 180, 150))
-                                                    );
+                                if self.settings.hud.is_enabled(HudWidget::TimeWeather) {
+                                    egui::Area::new(egui::Id::new("time_weather"))
+                                        .anchor(egui::Align2::RIGHT_TOP, [-10.0, 10.0])
+                                        .show(&ctx, |ui| {
+                                            egui::Frame::new()
+                                                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200))
+                                                .corner_radius(8.0)
+                                                .inner_margin(12.0)
+                                                .show(ui, |ui| {
+                                                    ui.with_layout(egui::Layout::top_down(egui::Align::RIGHT), |ui| {
+                                                        // Time
+                                                        ui.label(
+                                                            egui::RichText::new(&time_str)
+                                                                .font(egui::FontId::proportional(24.0))
+                                                                .color(egui::Color32::from_rgb(255, 255, 200))
+                                                        );
+                                                        ui.label(
+                                                            egui::RichText::new(period)
+                                                                .font(egui::FontId::proportional(14.0))
+                                                                .color(egui::Color32::from_rgb(180, 180, 150))
+                                                        );
 
-                                                    ui.add_space(4.0);
+                                                        ui.add_space(4.0);
 
-                                                    // Weather
-                                                    let weather_color = match self.weather.current {
-                                                        infinite_world::WeatherState::Clear => egui::Color32::from_rgb(135, 206, 250),
-                                                        infinite_world::WeatherState::Cloudy => egui::Color32::from_rgb(180, 180, 190),
-                                                        infinite_world::WeatherState::Rain => egui::Color32::from_rgb(100, 130, 180),
-                                                        infinite_world::WeatherState::Storm => egui::Color32::from_rgb(80, 80, 120),
-                                                    };
-                                                    ui.label(
-                                                        egui::RichText::new(weather_name)
-                                                            .font(egui::FontId::proportional(14.0))
-                                                            .color(weather_color)
-                                                    );
+                                                        // Weather
+                                                        let weather_color = match self.weather.current {
+                                                            infinite_world::WeatherState::Clear => egui::Color32::from_rgb(135, 206, 250),
+                                                            infinite_world::WeatherState::Cloudy => egui::Color32::from_rgb(180, 180, 190),
+                                                            infinite_world::WeatherState::Fog => egui::Color32::from_rgb(200, 200, 205),
+                                                            infinite_world::WeatherState::Rain => egui::Color32::from_rgb(100, 130, 180),
+                                                            infinite_world::WeatherState::Storm => egui::Color32::from_rgb(80, 80, 120),
+                                                        };
+                                                        ui.label(
+                                                            egui::RichText::new(weather_name)
+                                                                .font(egui::FontId::proportional(14.0))
+                                                                .color(weather_color)
+                                                        );
+                                                    });
                                                 });
-                                            });
-                                    });
+                                        });
+                                }
 
-                                // Controls hint at bottom
+                                // Controls hint at bottom (tutorials teach the rest)
+                                let controls_hint = if self.tutorials.enabled {
+                                    "Tab: Inventory | H: Tutorials | ESC: Pause"
+                                } else {
+                                    "WASD: Move | Space: Jump | Shift: Sprint | Scroll: Zoom | E: Interact | F5: Save | F9: Load | ESC: Pause | F3: Debug | F4: Edit HUD"
+                                };
                                 egui::Area::new(egui::Id::new("controls_hint"))
                                     .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -20.0])
                                     .show(&ctx, |ui| {
                                         ui.label(
-                                            egui::RichText::new("WASD: Move | Space: Jump | Shift: Sprint | Scroll: Zoom | E: Interact | F5: Save | F9: Load | ESC: Pause | F3: Debug")
+                                            egui::RichText::new(controls_hint)
                                                 .color(egui::Color32::from_rgba_unmultiplied(150, 150, 170, 200))
                                                 .font(egui::FontId::proportional(12.0)),
                                         );
                                     });
 
+                                // Lockpicking minigame
+                                if let Some(session) = &self.lockpicking {
+                                    egui::Area::new(egui::Id::new("lockpicking"))
+                                        .anchor(egui::Align2::CENTER_CENTER, [0.0, 120.0])
+                                        .show(&ctx, |ui| {
+                                            egui::Frame::new()
+                                                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200))
+                                                .corner_radius(6.0)
+                                                .inner_margin(12.0)
+                                                .show(ui, |ui| {
+                                                    ui.label(
+                                                        egui::RichText::new(format!(
+                                                            "{} Lock  {}/{} pins",
+                                                            session.lock.name(),
+                                                            session.pins_set,
+                                                            session.lock.pins(),
+                                                        ))
+                                                        .font(egui::FontId::proportional(15.0))
+                                                        .color(egui::Color32::from_rgb(230, 200, 110))
+                                                    );
+                                                    ui.add_space(4.0);
+
+                                                    // Lock: sweet spot and the sweeping pick
+                                                    let width = 300.0;
+                                                    let (lock_rect, _) = ui.allocate_exact_size(egui::vec2(width, 24.0), egui::Sense::hover());
+                                                    ui.painter().rect_filled(lock_rect, 3.0, egui::Color32::from_rgb(40, 40, 50));
+                                                    let spot_rect = egui::Rect::from_center_size(
+                                                        egui::pos2(lock_rect.min.x + width * session.sweet_spot, lock_rect.center().y),
+                                                        egui::vec2(width * session.sweet_width, 24.0),
+                                                    );
+                                                    let spot_color = if session.in_sweet_spot() {
+                                                        egui::Color32::from_rgb(120, 220, 120)
+                                                    } else {
+                                                        egui::Color32::from_rgb(70, 130, 70)
+                                                    };
+                                                    ui.painter().rect_filled(spot_rect, 2.0, spot_color);
+                                                    let pick_x = lock_rect.min.x + width * session.pick;
+                                                    ui.painter().line_segment(
+                                                        [egui::pos2(pick_x, lock_rect.min.y - 3.0), egui::pos2(pick_x, lock_rect.max.y + 3.0)],
+                                                        egui::Stroke::new(3.0, egui::Color32::WHITE),
+                                                    );
+                                                    ui.add_space(6.0);
+
+                                                    // Tension
+                                                    let (tension_rect, _) = ui.allocate_exact_size(egui::vec2(width, 8.0), egui::Sense::hover());
+                                                    ui.painter().rect_filled(tension_rect, 3.0, egui::Color32::from_rgb(50, 30, 30));
+                                                    let tension_fill = egui::Rect::from_min_size(
+                                                        tension_rect.min,
+                                                        egui::vec2(width * session.tension, 8.0),
+                                                    );
+                                                    ui.painter().rect_filled(tension_fill, 3.0, egui::Color32::from_rgb(220, 90, 60));
+                                                    ui.label(
+                                                        egui::RichText::new("E: Set pin | ESC: Stop")
+                                                            .font(egui::FontId::proportional(11.0))
+                                                            .color(egui::Color32::from_rgb(150, 150, 170))
+                                                    );
+                                                });
+                                        });
+                                }
+
+                                // Tutorial prompt
+                                if let Some(prompt) = self.tutorials.prompt() {
+                                    egui::Area::new(egui::Id::new("tutorial_prompt"))
+                                        .anchor(egui::Align2::CENTER_TOP, [0.0, 90.0])
+                                        .show(&ctx, |ui| {
+                                            egui::Frame::new()
+                                                .fill(egui::Color32::from_rgba_unmultiplied(20, 30, 50, 220))
+                                                .corner_radius(6.0)
+                                                .inner_margin(12.0)
+                                                .show(ui, |ui| {
+                                                    ui.set_max_width(420.0);
+                                                    ui.label(
+                                                        egui::RichText::new(prompt.topic.title())
+                                                            .font(egui::FontId::proportional(16.0))
+                                                            .color(egui::Color32::from_rgb(255, 220, 120))
+                                                    );
+                                                    ui.label(
+                                                        egui::RichText::new(prompt.topic.text())
+                                                            .font(egui::FontId::proportional(13.0))
+                                                            .color(egui::Color32::WHITE)
+                                                    );
+                                                    ui.label(
+                                                        egui::RichText::new("H: Dismiss")
+                                                            .font(egui::FontId::proportional(11.0))
+                                                            .color(egui::Color32::from_rgb(150, 150, 170))
+                                                    );
+                                                });
+                                        });
+                                }
+
+                                // Tutorial checklist (H)
+                                if self.show_tutorial_checklist {
+                                    egui::Area::new(egui::Id::new("tutorial_checklist"))
+                                        .anchor(egui::Align2::LEFT_CENTER, [20.0, 0.0])
+                                        .show(&ctx, |ui| {
+                                            egui::Frame::new()
+                                                .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200))
+                                                .corner_radius(6.0)
+                                                .inner_margin(10.0)
+                                                .show(ui, |ui| {
+                                                    ui.label(
+                                                        egui::RichText::new(format!(
+                                                            "Tutorials  {}/{}",
+                                                            self.tutorials.completed_count(),
+                                                            infinite_game::TutorialTopic::ALL.len(),
+                                                        ))
+                                                        .font(egui::FontId::proportional(15.0))
+                                                        .color(egui::Color32::from_rgb(200, 200, 255))
+                                                    );
+                                                    for (topic, done) in self.tutorials.checklist() {
+                                                        let (mark, color) = if done {
+                                                            ("\u{2714}", egui::Color32::from_rgb(120, 220, 120))
+                                                        } else {
+                                                            ("\u{25CB}", egui::Color32::from_rgb(180, 180, 190))
+                                                        };
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{} {}", mark, topic.title()))
+                                                                .font(egui::FontId::proportional(13.0))
+                                                                .color(color)
+                                                        );
+                                                    }
+                                                });
+                                        });
+                                }
+
+                                // How to get up (and pass time) while sitting or leaning
+                                let resting = self.player.as_ref().is_some_and(|p| p.is_resting());
+                                if resting {
+                                    let hint = if self.settings.gameplay.rest_fast_forward {
+                                        "Move or jump to stand up  ·  Hold Shift to pass time"
+                                    } else {
+                                        "Move or jump to stand up"
+                                    };
+                                    egui::Area::new(egui::Id::new("rest_hint"))
+                                        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -140.0])
+                                        .show(&ctx, |ui| {
+                                            ui.label(
+                                                egui::RichText::new(hint)
+                                                    .font(egui::FontId::proportional(14.0))
+                                                    .color(egui::Color32::from_rgb(220, 220, 230)),
+                                            );
+                                        });
+                                }
+
+                                // Gathering progress bar
+                                if let Some(session) = &self.gathering {
+                                    let skill = self.gathering_skills.skill(session.node.kind);
+                                    egui::Area::new(egui::Id::new("gather_progress"))
+                                        .anchor(egui::Align2::CENTER_CENTER, [0.0, 60.0])
+                                        .show(&ctx, |ui| {
+                                            ui.set_width(220.0);
+                                            ui.label(
+                                                egui::RichText::new(format!(
+                                                    "{} ({} {})",
+                                                    session.node.kind.prompt(),
+                                                    session.node.kind.skill_name(),
+                                                    skill.level()
+                                                ))
+                                                .font(egui::FontId::proportional(14.0))
+                                                .color(egui::Color32::from_rgb(220, 220, 230)),
+                                            );
+                                            ui.add(
+                                                egui::ProgressBar::new(session.progress())
+                                                    .fill(egui::Color32::from_rgb(120, 180, 90)),
+                                            );
+                                        });
+                                }
+
                                 // Interaction prompt (when focused on an interactable)
-                                if let Some(focused) = self.interaction_system.focused() {
+                                if let Some(focused) = self.interaction_system.focused().filter(|_| !resting && self.gathering.is_none()) {
                                     egui::Area::new(egui::Id::new("interaction_prompt"))
                                         .anchor(egui::Align2::CENTER_CENTER, [0.0, 50.0])
                                         .show(&ctx, |ui| {
@@ -2230,6 +7118,13 @@ impl InfiniteApp {
                                                             .font(egui::FontId::proportional(16.0))
                                                             .color(egui::Color32::from_rgb(255, 220, 100)),
                                                     );
+                                                    if let Some((rank, total)) = self.interaction_system.focus_cycle() {
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{} ({}/{}) - T: next", focused.kind.name(), rank, total))
+                                                                .font(egui::FontId::proportional(12.0))
+                                                                .color(egui::Color32::from_rgb(180, 180, 180)),
+                                                        );
+                                                    }
                                                 });
                                         });
                                 }
@@ -2254,6 +7149,27 @@ impl InfiniteApp {
                                         });
                                 }
 
+                                // Survival wave, score and combo
+                                if let Some(run) = &self.survival {
+                                    draw_survival_hud(&ctx, run);
+                                }
+
+                                // Rift arena, depth and shards
+                                if let Some(run) = &self.rift {
+                                    draw_rift_hud(&ctx, run);
+                                }
+
+                                // Speedrun timer and splits
+                                if let Some(run) = &self.speedrun {
+                                    draw_speedrun_overlay(&ctx, run, self.speedrun_records.best(run));
+                                }
+
+                                // World boss countdown, then its health
+                                if self.dungeon.is_none() {
+                                    let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                                    draw_world_boss_banner(&ctx, &self.world_bosses, self.play_time, player_pos);
+                                }
+
                                 // Notification (save/load/pickup)
                                 if let Some(notif) = &self.notification_text {
                                     egui::Area::new(egui::Id::new("notification"))
@@ -2273,6 +7189,40 @@ impl InfiniteApp {
                                         });
                                 }
 
+                                // Save/load spinner (both run in the background)
+                                if self.save_worker.is_busy() || self.save_indicator_timer > 0.0 {
+                                    let label = if self.save_worker.is_busy() && !self.save_worker.is_saving() { "Loading" } else { "Saving" };
+                                    egui::Area::new(egui::Id::new("autosave_indicator"))
+                                        .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -16.0])
+                                        .interactable(false)
+                                        .show(&ctx, |ui| {
+                                            ui.horizontal(|ui| {
+                                                ui.add(egui::Spinner::new().size(14.0).color(egui::Color32::from_rgb(200, 200, 220)));
+                                                ui.label(
+                                                    egui::RichText::new(label)
+                                                        .font(egui::FontId::proportional(13.0))
+                                                        .color(egui::Color32::from_rgb(200, 200, 220)),
+                                                );
+                                            });
+                                        });
+                                }
+
+                                // Who the player is talking to, and how they measure up to locked responses
+                                let dialogue_partner = self.ai_dialogue.active_npc_id()
+                                    .or_else(|| self.dialogue_system.active().map(|a| a.npc_id))
+                                    .and_then(|id| self.npc_manager.as_ref().and_then(|m| m.get(id)))
+                                    .map(|npc| (npc.name().to_string(), npc.persistent_key));
+                                let partner_tier = dialogue_partner.as_ref()
+                                    .and_then(|(_, key)| self.relationship_manager.get(*key))
+                                    .map(|rel| rel.tier())
+                                    .unwrap_or(infinite_game::RelationshipTier::Stranger);
+                                let dialogue_player = infinite_game::DialoguePlayer::new(
+                                    partner_tier,
+                                    self.player_combat.progression.level,
+                                    &self.player_combat.stats,
+                                    &self.player_combat.inventory.items,
+                                );
+
                                 // --- AI Dialogue UI ---
                                 if self.ai_dialogue.is_active() {
                                     let mut should_close = false;
@@ -2289,11 +7239,17 @@ impl InfiniteApp {
 
                                                     // NPC name header
                                                     if let Some(name) = self.ai_dialogue.active_npc_name() {
-                                                        ui.label(
-                                                            egui::RichText::new(name)
-                                                                .font(egui::FontId::proportional(14.0))
-                                                                .color(egui::Color32::from_rgb(180, 180, 200))
-                                                        );
+                                                        ui.horizontal(|ui| {
+                                                            ui.label(
+                                                                egui::RichText::new(name)
+                                                                    .font(egui::FontId::proportional(14.0))
+                                                                    .color(egui::Color32::from_rgb(180, 180, 200))
+                                                            );
+                                                            let items = &self.player_combat.inventory.items;
+                                                            if let Some(index) = dialogue_buttons(ui, &mut self.dialogue_history_open, items) {
+                                                                gift_item = Some(index);
+                                                            }
+                                                        });
                                                         ui.separator();
                                                     }
 
@@ -2338,22 +7294,33 @@ impl InfiniteApp {
 
                                                             if !is_waiting {
                                                                 // Quick response buttons
-                                                                ui.horizontal(|ui| {
-                                                                    let quick_responses = ["Hello", "Tell me about this place", "What year is it?", "Goodbye"];
-                                                                    for resp in &quick_responses {
-                                                                        if ui.button(
-                                                                            egui::RichText::new(*resp)
-                                                                                .font(egui::FontId::proportional(12.0))
-                                                                        ).clicked() {
-                                                                            if *resp == "Goodbye" {
-                                                                                should_close = true;
-                                                                            } else if let Some(client) = &self.integration_client {
-                                                                                self.ai_dialogue.send_player_message(
-                                                                                    resp.to_string(), client,
+                                                                let quick_responses: Vec<(usize, infinite_game::npc::dialogue::DialogueResponse, bool)> = self.ai_dialogue
+                                                                    .quick_responses
+                                                                    .iter()
+                                                                    .enumerate()
+                                                                    .filter(|(_, r)| r.is_available(&self.world_flags))
+                                                                    .map(|(i, r)| (i, r.clone(), r.is_unlocked(&dialogue_player, &self.world_flags)))
+                                                                    .collect();
+                                                                ui.horizontal_wrapped(|ui| {
+                                                                    for (i, response, unlocked) in &quick_responses {
+                                                                        if response_button(ui, response, *unlocked, 12.0) {
+                                                                            if let Some(client) = &self.integration_client {
+                                                                                let check = self.ai_dialogue.send_quick_response(
+                                                                                    *i, &mut self.world_flags, &dialogue_player, client,
                                                                                 );
+                                                                                if let Some(passed) = check {
+                                                                                    self.notification_text = Some(check_outcome(response, passed));
+                                                                                    self.notification_timer = 2.0;
+                                                                                }
                                                                             }
                                                                         }
                                                                     }
+                                                                    if ui.button(
+                                                                        egui::RichText::new("Goodbye")
+                                                                            .font(egui::FontId::proportional(12.0))
+                                                                    ).clicked() {
+                                                                        should_close = true;
+                                                                    }
                                                                 });
 
                                                                 // Text input
@@ -2392,11 +7359,11 @@ impl InfiniteApp {
                                         // Record relationship data before closing
                                         if let Some(AiDialogueState::WaitingForInput { messages }) = self.ai_dialogue.active_state() {
                                             if let Some(npc_id) = self.ai_dialogue.active_npc_id() {
-                                                let persistent_key = self.npc_manager.as_ref()
+                                                let partner = self.npc_manager.as_ref()
                                                     .and_then(|m| m.get(npc_id))
-                                                    .map(|n| n.persistent_key);
+                                                    .map(|n| (n.persistent_key, n.name().to_string()));
 
-                                                if let Some(key) = persistent_key {
+                                                if let Some((key, name)) = partner {
                                                     let rel_messages: Vec<RelationshipMessage> = messages.iter()
                                                         .map(|m| RelationshipMessage {
                                                             speaker: m.speaker.clone(),
@@ -2404,8 +7371,11 @@ impl InfiniteApp {
                                                             is_player: m.is_player,
                                                         })
                                                         .collect();
+                                                    let year = self.timeline.active_year;
                                                     let rel = self.relationship_manager.get_or_create(key);
+                                                    rel.name = name;
                                                     rel.record_conversation(&rel_messages);
+                                                    rel.log_conversation(year, &rel_messages);
                                                 }
                                             }
                                         }
@@ -2440,11 +7410,17 @@ impl InfiniteApp {
                                                         ui.set_max_width(500.0);
 
                                                         if let Some(active) = self.dialogue_system.active() {
-                                                            ui.label(
-                                                                egui::RichText::new(&active.npc_name)
-                                                                    .font(egui::FontId::proportional(14.0))
-                                                                    .color(egui::Color32::from_rgb(180, 180, 200))
-                                                            );
+                                                            ui.horizontal(|ui| {
+                                                                ui.label(
+                                                                    egui::RichText::new(&active.npc_name)
+                                                                        .font(egui::FontId::proportional(14.0))
+                                                                        .color(egui::Color32::from_rgb(180, 180, 200))
+                                                                );
+                                                                let items = &self.player_combat.inventory.items;
+                                                                if let Some(index) = dialogue_buttons(ui, &mut self.dialogue_history_open, items) {
+                                                                    gift_item = Some(index);
+                                                                }
+                                                            });
                                                             ui.separator();
                                                         }
 
@@ -2456,17 +7432,19 @@ impl InfiniteApp {
                                                             );
                                                             ui.add_space(10.0);
 
-                                                            let responses: Vec<(usize, String)> = node.responses.iter()
-                                                                .enumerate()
-                                                                .map(|(i, r)| (i, r.text.clone()))
+                                                            let responses: Vec<(usize, infinite_game::npc::dialogue::DialogueResponse, bool)> = self.dialogue_system
+                                                                .available_responses(&self.world_flags)
+                                                                .into_iter()
+                                                                .map(|(i, r)| (i, r.clone(), r.is_unlocked(&dialogue_player, &self.world_flags)))
                                                                 .collect();
-                                                            for (i, text) in responses {
-                                                                if ui.button(
-                                                                    egui::RichText::new(format!("  {}  ", text))
-                                                                        .font(egui::FontId::proportional(14.0))
-                                                                ).clicked() {
+                                                            for (i, response, unlocked) in responses {
+                                                                if response_button(ui, &response, unlocked, 14.0) {
                                                                     close = false;
-                                                                    self.dialogue_system.choose_response(i);
+                                                                    let check = self.dialogue_system.choose_response(i, &mut self.world_flags, &dialogue_player);
+                                                                    if let Some(passed) = check {
+                                                                        self.notification_text = Some(check_outcome(&response, passed));
+                                                                        self.notification_timer = 2.0;
+                                                                    }
                                                                 }
                                                             }
                                                         } else {
@@ -2481,6 +7459,47 @@ impl InfiniteApp {
                                     }
                                 }
 
+                                // --- Conversation history with whoever is being talked to ---
+                                if self.dialogue_history_open && (self.ai_dialogue.is_active() || self.dialogue_system.is_active()) {
+                                    if let Some((name, key)) = dialogue_partner {
+                                        let first_meeting = infinite_game::NpcRelationship::new();
+                                        let relationship = self.relationship_manager.get(key).unwrap_or(&first_meeting);
+                                        egui::Window::new(format!("History with {}", name))
+                                            .id(egui::Id::new("dialogue_history"))
+                                            .open(&mut self.dialogue_history_open)
+                                            .anchor(egui::Align2::RIGHT_CENTER, [-20.0, 0.0])
+                                            .resizable(false)
+                                            .collapsible(false)
+                                            .default_width(380.0)
+                                            .show(&ctx, |ui| relationship_details(ui, relationship));
+                                    }
+                                }
+
+                                // --- Subtitles (lines still being spoken after the dialogue panel closes) ---
+                                if self.settings.audio.subtitles
+                                    && !self.ai_dialogue.is_active()
+                                    && !self.dialogue_system.is_active()
+                                {
+                                    if let Some((speaker, text)) = &self.subtitle {
+                                        egui::Area::new(egui::Id::new("subtitles"))
+                                            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -90.0])
+                                            .show(&ctx, |ui| {
+                                                egui::Frame::new()
+                                                    .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 170))
+                                                    .corner_radius(6.0)
+                                                    .inner_margin(8.0)
+                                                    .show(ui, |ui| {
+                                                        ui.set_max_width(600.0);
+                                                        ui.label(
+                                                            egui::RichText::new(format!("{}: {}", speaker, text))
+                                                                .font(egui::FontId::proportional(16.0))
+                                                                .color(egui::Color32::WHITE),
+                                                        );
+                                                    });
+                                            });
+                                    }
+                                }
+
                                 // --- Player Health Bar (compact, shows when damaged) ---
                                 if self.player_combat.current_hp() < self.player_combat.max_hp() {
                                     egui::Area::new(egui::Id::new("player_health"))
@@ -2502,14 +7521,31 @@ impl InfiniteApp {
                                         });
                                 }
 
+                                // --- Climbing stamina (shows while climbing or recovering) ---
+                                if let Some(player) = self.player.as_ref().filter(|p| {
+                                    p.is_climbing() || p.is_gliding() || !p.stamina().is_full()
+                                }) {
+                                    let stamina = player.stamina().fraction();
+                                    egui::Area::new(egui::Id::new("player_stamina"))
+                                        .anchor(egui::Align2::LEFT_BOTTOM, [10.0, -58.0])
+                                        .interactable(false)
+                                        .show(&ctx, |ui| {
+                                            let color = if stamina < 0.25 {
+                                                egui::Color32::from_rgb(220, 80, 60)
+                                            } else {
+                                                egui::Color32::from_rgb(230, 200, 70)
+                                            };
+                                            ui.add_sized(
+                                                [150.0, 8.0],
+                                                egui::ProgressBar::new(stamina).fill(color),
+                                            );
+                                        });
+                                }
+
                                 // --- Enemy Health Bars (floating above NPCs) ---
                                 if let (Some(npc_manager), Some(camera)) = (&self.npc_manager, &self.camera) {
                                     let screen_size = ctx.screen_rect().size();
-                                    let aspect_ratio = screen_size.x / screen_size.y;
-                                    let view_matrix = camera.view_matrix();
-                                    let mut projection_matrix = camera.projection_matrix(aspect_ratio, 60.0);
-                                    projection_matrix.y_axis.y *= -1.0;
-                                    let view_proj = projection_matrix * view_matrix;
+                                    let view_proj = hud_view_proj(camera, screen_size);
                                     let max_display_dist = 20.0_f32;
                                     let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
 
@@ -2527,8 +7563,11 @@ impl InfiniteApp {
                                         let in_aggro = is_hostile && dist_to_player < npc_manager.combat_stats.get(&npc.id)
                                             .map(|s| s.aggro_radius).unwrap_or(12.0);
 
-                                        // Show bar if damaged, provoked, or hostile in aggro range
-                                        if !is_damaged && !is_provoked && !in_aggro {
+                                        let noticed = npc_manager.detection_state(npc.id) != DetectionState::Unaware;
+                                        let elite = npc_manager.elite(npc.id);
+
+                                        // Show bar if damaged, provoked, noticing the player, hostile in aggro range, or elite
+                                        if !is_damaged && !is_provoked && !noticed && !in_aggro && elite.is_none() {
                                             continue;
                                         }
 
@@ -2553,12 +7592,37 @@ impl InfiniteApp {
                                                 egui::Area::new(egui::Id::new(("enemy_hp", npc.id.0)))
                                                     .fixed_pos([screen_pos.x - bar_width / 2.0, screen_pos.y - 14.0])
                                                     .show(&ctx, |ui| {
-                                                        // NPC name
-                                                        ui.label(
-                                                            egui::RichText::new(&npc.data.name)
-                                                                .font(egui::FontId::proportional(10.0))
-                                                                .color(name_color),
-                                                        );
+                                                        // NPC name with detection marker (? suspicious, ! alerted)
+                                                        let detection = npc_manager.detection_state(npc.id);
+                                                        ui.horizontal(|ui| {
+                                                            if elite.is_some() {
+                                                                ui.label(
+                                                                    egui::RichText::new(format!("\u{2605} {}", npc.data.name))
+                                                                        .font(egui::FontId::proportional(10.0))
+                                                                        .strong()
+                                                                        .color(egui::Color32::from_rgb(255, 200, 60)),
+                                                                );
+                                                            } else {
+                                                                ui.label(
+                                                                    egui::RichText::new(&npc.data.name)
+                                                                        .font(egui::FontId::proportional(10.0))
+                                                                        .color(name_color),
+                                                                );
+                                                            }
+                                                            if detection != DetectionState::Unaware {
+                                                                let marker_color = if detection == DetectionState::Alerted {
+                                                                    egui::Color32::from_rgb(255, 70, 50)
+                                                                } else {
+                                                                    egui::Color32::from_rgb(255, 210, 60)
+                                                                };
+                                                                ui.label(
+                                                                    egui::RichText::new(detection.marker())
+                                                                        .font(egui::FontId::proportional(12.0))
+                                                                        .strong()
+                                                                        .color(marker_color),
+                                                                );
+                                                            }
+                                                        });
                                                         // HP bar color: red for hostile, orange for provoked friendly
                                                         let bar_color = if is_provoked && !is_hostile {
                                                             egui::Color32::from_rgb(230, 160, 50)
@@ -2577,6 +7641,41 @@ impl InfiniteApp {
                                                         ui.painter().rect_filled(hp_rect, 2.0, bar_color);
                                                         ui.allocate_space(egui::vec2(bar_width, bar_height));
 
+                                                        if let Some(elite) = elite {
+                                                            // Shield bar under the HP bar
+                                                            if elite.shield_max > 0.0 {
+                                                                let shield_rect = egui::Rect::from_min_size(
+                                                                    ui.cursor().min,
+                                                                    egui::vec2(bar_width, 4.0)
+                                                                );
+                                                                ui.painter().rect_filled(shield_rect, 1.0, egui::Color32::from_rgb(40, 40, 40));
+                                                                ui.painter().rect_filled(
+                                                                    egui::Rect::from_min_size(
+                                                                        shield_rect.min,
+                                                                        egui::vec2(bar_width * elite.shield / elite.shield_max, 4.0)
+                                                                    ),
+                                                                    1.0,
+                                                                    egui::Color32::from_rgb(220, 200, 90),
+                                                                );
+                                                                ui.allocate_space(egui::vec2(bar_width, 4.0));
+                                                            }
+                                                            // Affix labels in their colors
+                                                            ui.horizontal(|ui| {
+                                                                for affix in &elite.affixes {
+                                                                    let c = affix.color();
+                                                                    ui.label(
+                                                                        egui::RichText::new(affix.name())
+                                                                            .font(egui::FontId::proportional(9.0))
+                                                                            .color(egui::Color32::from_rgb(
+                                                                                (c[0] * 255.0) as u8,
+                                                                                (c[1] * 255.0) as u8,
+                                                                                (c[2] * 255.0) as u8,
+                                                                            )),
+                                                                    );
+                                                                }
+                                                            });
+                                                        }
+
                                                         // Level and element info
                                                         let npc_level = npc_manager.npc_level(npc.id);
                                                         if stats.element != infinite_game::Element::Physical {
@@ -2613,41 +7712,50 @@ impl InfiniteApp {
                                     }
                                 }
 
-                                // --- Damage Numbers (floating combat text) ---
+                                // --- Conversation letterbox ---
+                                if let Some(camera) = &self.camera {
+                                    draw_letterbox(&ctx, camera.dialogue_blend());
+                                }
+
+                                // --- NPC barks (floating over their heads) ---
                                 if let Some(camera) = &self.camera {
                                     let screen_size = ctx.screen_rect().size();
-                                    let aspect_ratio = screen_size.x / screen_size.y;
-                                    let view_matrix = camera.view_matrix();
-                                    let mut projection_matrix = camera.projection_matrix(aspect_ratio, 60.0);
-                                    projection_matrix.y_axis.y *= -1.0;
-                                    let view_proj = projection_matrix * view_matrix;
-
-                                    for (i, dn) in self.damage_numbers.iter().enumerate() {
-                                        if let Some(screen_pos) = world_to_screen(dn.position, view_proj, screen_size) {
-                                            let alpha = (dn.timer * 255.0) as u8;
-                                            let color = if dn.is_crit {
-                                                egui::Color32::from_rgba_unmultiplied(255, 215, 0, alpha) // Gold for crits
-                                            } else {
-                                                egui::Color32::from_rgba_unmultiplied(255, 255, 255, alpha) // White for normal
-                                            };
-                                            let text = if dn.is_crit {
-                                                format!("{:.0}!", dn.amount)
-                                            } else {
-                                                format!("{:.0}", dn.amount)
-                                            };
-                                            let font_size = if dn.is_crit { 20.0 } else { 16.0 };
+                                    let view_proj = hud_view_proj(camera, screen_size);
+                                    draw_barks(&ctx, &self.barks, |position| world_to_screen(position, view_proj, screen_size));
+                                }
 
-                                            egui::Area::new(egui::Id::new(("dmg_num", i)))
-                                                .fixed_pos([screen_pos.x - 20.0, screen_pos.y - 10.0])
-                                                .order(egui::Order::Foreground)
-                                                .show(&ctx, |ui| {
-                                                    ui.label(
-                                                        egui::RichText::new(text)
-                                                            .font(egui::FontId::proportional(font_size))
-                                                            .color(color)
-                                                            .strong()
-                                                    );
-                                                });
+                                // --- Damage Numbers (floating combat text) ---
+                                let damage_number_scale = self.settings.hud.damage_numbers.scale;
+                                if let Some(camera) = self.camera.as_ref().filter(|_| self.settings.hud.is_enabled(HudWidget::DamageNumbers)) {
+                                    let screen_size = ctx.screen_rect().size();
+                                    let view_proj = hud_view_proj(camera, screen_size);
+
+                                    DamageNumberHud::new(damage_number_scale).render(&ctx, &self.damage_numbers, |position| {
+                                        world_to_screen(position, view_proj, screen_size)
+                                    });
+                                }
+
+                                // --- Occlusion debug: outline last frame's culled objects ---
+                                if let Some(camera) = self.camera.as_ref().filter(|_| self.debug_show_culled) {
+                                    let screen_size = ctx.screen_rect().size();
+                                    let view_proj = hud_view_proj(camera, screen_size);
+
+                                    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Background, egui::Id::new("culled_bounds")));
+                                    let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 60, 200));
+                                    for (min, max) in self.occlusion.culled() {
+                                        let corner = |i: usize| Vec3::new(
+                                            if i & 1 == 0 { min.x } else { max.x },
+                                            if i & 2 == 0 { min.y } else { max.y },
+                                            if i & 4 == 0 { min.z } else { max.z },
+                                        );
+                                        for i in 0..8 {
+                                            for axis in [1, 2, 4].into_iter().filter(|axis| i & axis == 0) {
+                                                let from = world_to_screen(corner(i), view_proj, screen_size);
+                                                let to = world_to_screen(corner(i | axis), view_proj, screen_size);
+                                                if let (Some(from), Some(to)) = (from, to) {
+                                                    painter.line_segment([from, to], stroke);
+                                                }
+                                            }
                                         }
                                     }
                                 }
@@ -2730,6 +7838,122 @@ impl InfiniteApp {
                                         });
                                 }
 
+                                // Combat stats panel (L)
+                                self.combat_stats_panel.render(&ctx, &self.combat_log);
+
+                                // Archetype change, offered by quest givers
+                                if let Some(menu) = &mut self.respec_menu {
+                                    respec_pending_action = menu.render(
+                                        &ctx,
+                                        &self.player_combat.progression.class,
+                                        self.player_combat.progression.level,
+                                        self.player_combat.gold,
+                                        self.player_combat.progression.respec_cost(),
+                                    );
+                                }
+
+                                // Cooking, at a campfire or stove
+                                if let Some(menu) = &mut self.cooking_menu {
+                                    cooking_pending_action = menu.render(
+                                        &ctx,
+                                        &self.player_combat.inventory,
+                                        &self.recipe_book,
+                                        infinite_game::Era::for_year(self.timeline.active_year),
+                                    );
+                                }
+
+                                // Brewing, at an alchemy table
+                                if let Some(menu) = &mut self.brewing_menu {
+                                    brewing_pending_action = menu.render(
+                                        &ctx,
+                                        &self.player_combat.inventory,
+                                        &self.alchemy_journal,
+                                        infinite_game::alchemy::potency_for(&self.player_combat.effective_stats()),
+                                    );
+                                }
+
+                                // Praying, at a shrine
+                                if let Some(menu) = &self.shrine_menu {
+                                    shrine_pending_action = menu.render(
+                                        &ctx,
+                                        &self.player_combat.inventory,
+                                        self.player_combat.gold,
+                                        self.player_combat.progression.level,
+                                        &self.player_combat.curses,
+                                        self.shrine_ledger.cooldown_left(menu.shrine.id, self.play_time),
+                                    );
+                                }
+
+                                // Arena shop between survival waves, results once the run is over
+                                if let (Some(run), Some(menu)) = (&self.survival, &self.survival_menu) {
+                                    survival_pending_action = menu.render(&ctx, run);
+                                }
+
+                                // Time rift gate, and the boon choice after each cleared arena
+                                if let Some(menu) = &self.rift_menu {
+                                    rift_pending_action = menu.render(&ctx, &self.rift_ledger);
+                                }
+                                if let Some(run) = &self.rift {
+                                    rift_pending_action = rift_pending_action.or(render_boon_choice(&ctx, run));
+                                }
+
+                                // Timeline browser (B)
+                                timeline_action = self.timeline_browser.render(
+                                    &ctx,
+                                    &self.timeline.branches,
+                                    self.timeline.active_year,
+                                    self.time_transitioning,
+                                );
+
+                                // Entity inspector (click with the debug overlay up)
+                                if self.debug_visible {
+                                    self.inspector.render(&ctx, self.npc_manager.as_mut(), &mut self.interaction_system);
+                                    self.balance_panel.render(&ctx, &mut self.tunables);
+                                    if let Some(client) = self.integration_client.as_ref().filter(|c| c.is_admin()) {
+                                        spawn_template = self.template_spawner.render(&ctx, client);
+                                        let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                                        let waypoint = self.waypoint.and_then(|id| self.compass_tracker.get(id)).map(|m| m.position);
+                                        gm_action = self.gm_tools.render(
+                                            &ctx,
+                                            player_pos,
+                                            waypoint,
+                                            self.compass_tracker.iter(),
+                                            self.item_catalog.as_ref(),
+                                        );
+                                    }
+                                }
+
+                                // Training dummy readout (while nearby)
+                                if let Some(dummy) = &self.training_dummy {
+                                    let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                                    if (dummy.position - player_pos).length() < 25.0 {
+                                        egui::Area::new(egui::Id::new("training_dummy"))
+                                            .anchor(egui::Align2::LEFT_CENTER, [10.0, 0.0])
+                                            .show(&ctx, |ui| {
+                                                egui::Frame::new()
+                                                    .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 160))
+                                                    .inner_margin(8.0)
+                                                    .corner_radius(4.0)
+                                                    .show(ui, |ui| {
+                                                        ui.label(egui::RichText::new("Training Dummy").strong().color(egui::Color32::from_rgb(220, 180, 110)));
+                                                        ui.label(format!("DPS: {:.1}", dummy.dps()));
+                                                        ui.label(format!("Total: {:.0} ({} hits)", dummy.total(), dummy.hits()));
+                                                        if let Some(hit) = dummy.last_hit() {
+                                                            ui.separator();
+                                                            ui.label(format!(
+                                                                "Last: {:.0} {}{}",
+                                                                hit.amount,
+                                                                hit.source,
+                                                                if hit.is_crit { " (CRIT)" } else { "" },
+                                                            ));
+                                                            ui.label(format!("Element: {} x{:.2}", hit.element.name(), hit.element_multiplier));
+                                                            ui.label(format!("Weapon: x{:.2}  Base: {:.0}", hit.weapon_multiplier, hit.base_amount));
+                                                        }
+                                                    });
+                                            });
+                                    }
+                                }
+
                                 // Debug overlay (F3)
                                 if self.debug_visible {
                                     let player_pos = self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
@@ -2740,6 +7964,12 @@ impl InfiniteApp {
                                     let chunks_loaded = self.chunk_manager.as_ref()
                                         .map(|cm| cm.loaded_count())
                                         .unwrap_or(0);
+                                    let mesh_pool = self.render_ctx.as_ref().map(|r| r.chunk_meshes.stats()).unwrap_or_default();
+                                    let queue_families = self.render_ctx.as_ref().map(|r| {
+                                        [&r.queue, r.mesh_uploader.queue()].map(|queue| queue.queue_family_index())
+                                    });
+                                    let pipeline_cache = self.render_ctx.as_ref().map(|r| r.pipeline_cache.stats());
+                                    let uniform_bytes = self.render_ctx.as_ref().map(|r| r.frame_uniforms.ring().used());
 
                                     egui::Window::new("Debug")
                                         .anchor(egui::Align2::LEFT_BOTTOM, [10.0, -40.0])
@@ -2747,6 +7977,8 @@ impl InfiniteApp {
                                         .collapsible(true)
                                         .default_width(280.0)
                                         .show(&ctx, |ui| {
+                                            ui.label(egui::RichText::new("Click an NPC or object to inspect it").weak());
+                                            ui.checkbox(&mut self.balance_panel.open, "Balance panel");
                                             ui.heading("Player");
                                             ui.label(format!("Position: ({:.1}, {:.1}, {:.1})", player_pos.x, player_pos.y, player_pos.z));
                                             ui.label(format!("Grounded: {}", player_grounded));
@@ -2756,6 +7988,13 @@ impl InfiniteApp {
                                             ui.separator();
                                             ui.heading("Chunks");
                                             ui.label(format!("Loaded: {}", chunks_loaded));
+                                            ui.label(format!(
+                                                "Mesh pool: {} meshes in {} blocks, {}k / {}k vertices",
+                                                mesh_pool.meshes,
+                                                mesh_pool.blocks,
+                                                mesh_pool.used_vertices / 1000,
+                                                mesh_pool.vertex_capacity / 1000
+                                            ));
                                             if let Some(cm) = &self.chunk_manager {
                                                 let pc = cm.player_chunk(player_pos);
                                                 ui.label(format!("Player chunk: ({}, {})", pc.x, pc.z));
@@ -2765,12 +8004,99 @@ impl InfiniteApp {
                                             ui.heading("Rendering");
                                             ui.checkbox(&mut self.debug_wireframe, "Wireframe terrain");
                                             ui.checkbox(&mut self.debug_colliders, "Show colliders");
+                                            ui.checkbox(&mut self.occlusion_culling, "Occlusion culling");
+                                            ui.checkbox(&mut self.debug_show_culled, "Show culled objects");
+                                            if let Some([graphics, transfer]) = queue_families {
+                                                ui.label(format!("Queue families: graphics {}, transfer {}", graphics, transfer));
+                                            }
+                                            if let Some(cache) = pipeline_cache {
+                                                ui.label(format!(
+                                                    "Pipeline cache: {} / {} hits ({} loaded)",
+                                                    cache.hits,
+                                                    cache.pipelines,
+                                                    infinite_core::format_bytes(cache.loaded_bytes as u64)
+                                                ));
+                                            }
+                                            if let Some(bytes) = uniform_bytes {
+                                                ui.label(format!(
+                                                    "Frame uniforms: {} / {}",
+                                                    infinite_core::format_bytes(bytes),
+                                                    infinite_core::format_bytes(UNIFORM_RING_FRAME_BYTES)
+                                                ));
+                                            }
+                                            let occlusion = self.occlusion.stats();
+                                            ui.label(format!(
+                                                "Occluded: {} / {} ({} occluder tris)",
+                                                occlusion.culled, occlusion.tested, occlusion.occluder_triangles
+                                            ));
+                                            if self.settings.video.dynamic_resolution {
+                                                let resolution = &self.dynamic_resolution;
+                                                let [width, height] =
+                                                    scaled_extent([window_size.width, window_size.height], resolution.scale());
+                                                ui.label(format!(
+                                                    "Render scale: {:.0}% ({}x{})",
+                                                    resolution.scale() * 100.0,
+                                                    width,
+                                                    height
+                                                ));
+                                                ui.label(match resolution.gpu_ms() {
+                                                    Some(gpu_ms) => format!(
+                                                        "GPU: {:.1} ms / {:.1} ms budget",
+                                                        gpu_ms,
+                                                        resolution.config().frame_budget_ms()
+                                                    ),
+                                                    None => "GPU: not measured".to_string(),
+                                                });
+                                            } else {
+                                                ui.label("Render scale: 100% (dynamic resolution off)");
+                                            }
+
+                                            ui.separator();
+                                            ui.heading("Memory");
+                                            let memory = &self.memory;
+                                            ui.label(format!(
+                                                "Pressure: {} (downgrade {}/{})",
+                                                memory.pressure().name(),
+                                                memory.level(),
+                                                infinite_core::MAX_DOWNGRADE
+                                            ));
+                                            ui.add(egui::ProgressBar::new(memory.device_fraction().min(1.0)).text(format!(
+                                                "VRAM {} / {}",
+                                                infinite_core::format_bytes(memory.device_total()),
+                                                infinite_core::format_bytes(memory.budget.device_bytes())
+                                            )));
+                                            ui.add(egui::ProgressBar::new(memory.host_fraction().min(1.0)).text(format!(
+                                                "RAM {} / {}",
+                                                infinite_core::format_bytes(memory.host_total()),
+                                                infinite_core::format_bytes(memory.budget.host_bytes())
+                                            )));
+                                            egui::Grid::new("debug_memory").striped(true).show(ui, |ui| {
+                                                for category in infinite_core::MemoryCategory::ALL {
+                                                    ui.label(category.name());
+                                                    ui.label(infinite_core::format_bytes(memory.usage(category)));
+                                                    ui.label(egui::RichText::new(format!("peak {}", infinite_core::format_bytes(memory.peak(category)))).weak());
+                                                    ui.end_row();
+                                                }
+                                            });
 
                                             ui.separator();
                                             ui.heading("World");
                                             ui.label(format!("Year: {}", self.timeline.year_label()));
                                             ui.label(format!("Time: {} ({})", self.time_of_day.formatted_time(), self.time_of_day.period_name()));
                                             ui.label(format!("Weather: {}", self.weather.current.name()));
+                                            ui.label(format!("Wind: {:.2} (gust {:.2})", self.wind.strength(), self.wind.gust()));
+
+                                            ui.separator();
+                                            ui.heading(format!("Flags ({})", self.world_flags.len()));
+                                            for namespace in self.world_flags.namespaces() {
+                                                egui::CollapsingHeader::new(namespace).show(ui, |ui| {
+                                                    for key in self.world_flags.keys(namespace) {
+                                                        if let Some(value) = self.world_flags.get(namespace, key) {
+                                                            ui.label(format!("{} = {}", key, value));
+                                                        }
+                                                    }
+                                                });
+                                            }
 
                                             // NPC info
                                             ui.separator();
@@ -2784,6 +8110,40 @@ impl InfiniteApp {
                                             ui.label(format!("Player HP: {:.0}/{:.0}", self.player_combat.current_hp(), self.player_combat.max_hp()));
                                             ui.label(format!("Player Level: {} (XP: {}/{})", self.player_combat.level(), self.player_combat.current_xp(), self.player_combat.xp_to_next_level()));
 
+                                            ui.separator();
+                                            ui.heading("Combat");
+                                            let session = self.combat_log.session();
+                                            ui.label(format!("DPS ({:.0}s): {:.1}", self.combat_log.dps_window, self.combat_log.dps()));
+                                            ui.label(format!("Dealt: {:.0}  Taken: {:.0}", session.damage_dealt, session.damage_taken));
+                                            ui.label(format!("Kills: {} (all time {})", session.total_kills(), self.combat_log.lifetime().total_kills()));
+
+                                            // Training commands
+                                            ui.separator();
+                                            ui.heading("Training");
+                                            if ui.button("Spawn training dummy").clicked() {
+                                                spawn_dummy_at = Some(player_pos);
+                                            }
+                                            ui.add(egui::Slider::new(&mut self.arena_config.waves, 1..=10).text("Waves"));
+                                            ui.add(egui::Slider::new(&mut self.arena_config.enemies_per_wave, 1..=10).text("Enemies"));
+                                            ui.add(egui::Slider::new(&mut self.arena_config.wave_growth, 0..=5).text("Growth"));
+                                            ui.checkbox(&mut self.arena_config.boss_final_wave, "Boss final wave");
+                                            if let Some(arena) = &self.arena {
+                                                ui.label(format!("Arena: wave {}/{} ({} left)", arena.wave(), arena.config.waves, arena.remaining()));
+                                            }
+                                            if ui.button("Start arena here").clicked() {
+                                                start_arena_at = Some(player_pos);
+                                            }
+                                            if self.integration_client.as_ref().is_some_and(|c| c.is_admin()) {
+                                                ui.horizontal(|ui| {
+                                                    if ui.button("Spawn NPC template...").clicked() {
+                                                        self.template_spawner.visible = !self.template_spawner.visible;
+                                                    }
+                                                    if ui.button("GM tools...").clicked() {
+                                                        self.gm_tools.visible = !self.gm_tools.visible;
+                                                    }
+                                                });
+                                            }
+
                                             // Time travel debug buttons
                                             ui.separator();
                                             ui.heading("Time Travel");
@@ -2816,179 +8176,323 @@ impl InfiniteApp {
                                     let bar_x = (screen_rect.width() - total_width) / 2.0;
                                     let bar_y = screen_rect.height() - slot_size - 20.0;
 
-                                    egui::Area::new(egui::Id::new("skill_bar"))
-                                        .fixed_pos([bar_x, bar_y])
-                                        .show(&ctx, |ui| {
-                                            ui.horizontal(|ui| {
-                                                let keybinds = ["1", "2", "3", "4"];
-                                                for (i, slot) in self.player_combat.skill_slots.iter().enumerate() {
-                                                    let slot_rect = ui.allocate_space(egui::vec2(slot_size, slot_size)).1;
-
-                                                    // Background
-                                                    ui.painter().rect_filled(
-                                                        slot_rect,
-                                                        4.0,
-                                                        egui::Color32::from_rgba_unmultiplied(20, 20, 35, 220),
-                                                    );
-                                                    ui.painter().rect_stroke(
-                                                        slot_rect,
-                                                        4.0,
-                                                        egui::Stroke::new(1.0, egui::Color32::from_rgb(60, 60, 90)),
-                                                        egui::epaint::StrokeKind::Outside,
-                                                    );
-
-                                                    if let Some(infinite_game::combat::skill::Skill::Active(ref active)) = slot.skill {
-                                                        // Skill name (abbreviated)
-                                                        let abbrev: String = active.name.chars().take(6).collect();
-                                                        ui.painter().text(
-                                                            slot_rect.center(),
-                                                            egui::Align2::CENTER_CENTER,
-                                                            &abbrev,
-                                                            egui::FontId::proportional(11.0),
-                                                            egui::Color32::from_rgb(220, 220, 240),
+                                    if self.settings.hud.is_enabled(HudWidget::SkillBar) {
+                                        egui::Area::new(egui::Id::new("skill_bar"))
+                                            .fixed_pos([bar_x, bar_y])
+                                            .show(&ctx, |ui| {
+                                                ui.horizontal(|ui| {
+                                                    let keybinds = ["1", "2", "3", "4"];
+                                                    for (i, slot) in self.player_combat.skill_slots.iter().enumerate() {
+                                                        let slot_rect = ui.allocate_space(egui::vec2(slot_size, slot_size)).1;
+
+                                                        // Background
+                                                        ui.painter().rect_filled(
+                                                            slot_rect,
+                                                            4.0,
+                                                            egui::Color32::from_rgba_unmultiplied(20, 20, 35, 220),
+                                                        );
+                                                        ui.painter().rect_stroke(
+                                                            slot_rect,
+                                                            4.0,
+                                                            egui::Stroke::new(1.0, egui::Color32::from_rgb(60, 60, 90)),
+                                                            egui::epaint::StrokeKind::Outside,
                                                         );
 
-                                                        // Cooldown overlay
-                                                        if slot.is_on_cooldown() {
-                                                            let cd_frac = 1.0 - slot.cooldown_fraction();
-                                                            let cd_rect = egui::Rect::from_min_size(
-                                                                slot_rect.min,
-                                                                egui::vec2(slot_size, slot_size * cd_frac),
+                                                        if let Some(infinite_game::combat::skill::Skill::Active(ref active)) = slot.skill {
+                                                            // Skill name (abbreviated)
+                                                            let abbrev: String = active.name.chars().take(6).collect();
+                                                            ui.painter().text(
+                                                                slot_rect.center(),
+                                                                egui::Align2::CENTER_CENTER,
+                                                                &abbrev,
+                                                                egui::FontId::proportional(11.0),
+                                                                egui::Color32::from_rgb(220, 220, 240),
                                                             );
-                                                            ui.painter().rect_filled(
-                                                                cd_rect,
-                                                                4.0,
-                                                                egui::Color32::from_rgba_unmultiplied(0, 0, 0, 150),
+
+                                                            // Cooldown overlay
+                                                            if slot.is_on_cooldown() {
+                                                                let cd_frac = 1.0 - slot.cooldown_fraction();
+                                                                let cd_rect = egui::Rect::from_min_size(
+                                                                    slot_rect.min,
+                                                                    egui::vec2(slot_size, slot_size * cd_frac),
+                                                                );
+                                                                ui.painter().rect_filled(
+                                                                    cd_rect,
+                                                                    4.0,
+                                                                    egui::Color32::from_rgba_unmultiplied(0, 0, 0, 150),
+                                                                );
+                                                                // Show remaining seconds
+                                                                ui.painter().text(
+                                                                    slot_rect.center() + egui::vec2(0.0, 12.0),
+                                                                    egui::Align2::CENTER_CENTER,
+                                                                    format!("{:.1}s", slot.cooldown_remaining),
+                                                                    egui::FontId::proportional(10.0),
+                                                                    egui::Color32::from_rgb(255, 200, 100),
+                                                                );
+                                                            }
+
+                                                            // Mana cost below
+                                                            ui.painter().text(
+                                                                egui::pos2(slot_rect.center().x, slot_rect.max.y - 3.0),
+                                                                egui::Align2::CENTER_BOTTOM,
+                                                                format!("{:.0}", active.cost),
+                                                                egui::FontId::proportional(9.0),
+                                                                egui::Color32::from_rgb(80, 140, 220),
                                                             );
-                                                            // Show remaining seconds
+                                                        } else {
+                                                            // Empty slot
                                                             ui.painter().text(
-                                                                slot_rect.center() + egui::vec2(0.0, 12.0),
+                                                                slot_rect.center(),
                                                                 egui::Align2::CENTER_CENTER,
-                                                                format!("{:.1}s", slot.cooldown_remaining),
+                                                                "[Empty]",
                                                                 egui::FontId::proportional(10.0),
-                                                                egui::Color32::from_rgb(255, 200, 100),
+                                                                egui::Color32::from_rgb(80, 80, 100),
                                                             );
                                                         }
 
-                                                        // Mana cost below
-                                                        ui.painter().text(
-                                                            egui::pos2(slot_rect.center().x, slot_rect.max.y - 3.0),
-                                                            egui::Align2::CENTER_BOTTOM,
-                                                            format!("{:.0}", active.cost),
-                                                            egui::FontId::proportional(9.0),
-                                                            egui::Color32::from_rgb(80, 140, 220),
+                                                        // Keybind label in top-left corner
+                                                        if i < keybinds.len() {
+                                                            ui.painter().text(
+                                                                slot_rect.min + egui::vec2(4.0, 2.0),
+                                                                egui::Align2::LEFT_TOP,
+                                                                keybinds[i],
+                                                                egui::FontId::proportional(10.0),
+                                                                egui::Color32::from_rgb(160, 160, 180),
+                                                            );
+                                                        }
+
+                                                        // Gap between slots
+                                                        if i < num_slots - 1 {
+                                                            ui.add_space(slot_gap);
+                                                        }
+                                                    }
+                                                });
+                                            });
+                                    }
+
+                                    // Consumable hotbar (left of the skill bar)
+                                    if self.settings.hud.is_enabled(HudWidget::Hotbar) {
+                                        let hotbar_slot = 48.0_f32;
+                                        let hotbar_width = hotbar_slot * infinite_game::HOTBAR_SLOTS as f32
+                                            + slot_gap * (infinite_game::HOTBAR_SLOTS - 1) as f32;
+                                        egui::Area::new(egui::Id::new("consumable_hotbar"))
+                                            .fixed_pos([bar_x - hotbar_width - 24.0, bar_y + slot_size - hotbar_slot])
+                                            .show(&ctx, |ui| {
+                                                ui.horizontal(|ui| {
+                                                    for slot in 0..infinite_game::HOTBAR_SLOTS {
+                                                        let slot_rect = ui.allocate_space(egui::vec2(hotbar_slot, hotbar_slot)).1;
+                                                        let aiming = self.throw_aim.filter(|(s, _)| *s == slot);
+
+                                                        ui.painter().rect_filled(
+                                                            slot_rect,
+                                                            4.0,
+                                                            egui::Color32::from_rgba_unmultiplied(20, 20, 35, 220),
                                                         );
-                                                    } else {
-                                                        // Empty slot
-                                                        ui.painter().text(
-                                                            slot_rect.center(),
-                                                            egui::Align2::CENTER_CENTER,
-                                                            "[Empty]",
-                                                            egui::FontId::proportional(10.0),
-                                                            egui::Color32::from_rgb(80, 80, 100),
+                                                        let border = if aiming.is_some() {
+                                                            egui::Color32::from_rgb(255, 200, 80)
+                                                        } else {
+                                                            egui::Color32::from_rgb(60, 60, 90)
+                                                        };
+                                                        ui.painter().rect_stroke(
+                                                            slot_rect,
+                                                            4.0,
+                                                            egui::Stroke::new(1.0, border),
+                                                            egui::epaint::StrokeKind::Outside,
                                                         );
-                                                    }
 
-                                                    // Keybind label in top-left corner
-                                                    if i < keybinds.len() {
+                                                        let inventory = &self.player_combat.inventory;
+                                                        let bound = self.hotbar.find(slot, inventory).map(|index| &inventory.items[index]);
+                                                        if let Some(item) = bound {
+                                                            let abbrev: String = item.name.chars().take(6).collect();
+                                                            ui.painter().text(
+                                                                slot_rect.center(),
+                                                                egui::Align2::CENTER_CENTER,
+                                                                &abbrev,
+                                                                egui::FontId::proportional(10.0),
+                                                                egui::Color32::from_rgb(220, 220, 240),
+                                                            );
+                                                            ui.painter().text(
+                                                                slot_rect.max - egui::vec2(3.0, 2.0),
+                                                                egui::Align2::RIGHT_BOTTOM,
+                                                                format!("{}", self.hotbar.count(slot, inventory)),
+                                                                egui::FontId::proportional(10.0),
+                                                                egui::Color32::from_rgb(200, 200, 140),
+                                                            );
+                                                        } else {
+                                                            ui.painter().text(
+                                                                slot_rect.center(),
+                                                                egui::Align2::CENTER_CENTER,
+                                                                if self.hotbar.get(slot).is_some() { "[None]" } else { "[Empty]" },
+                                                                egui::FontId::proportional(9.0),
+                                                                egui::Color32::from_rgb(80, 80, 100),
+                                                            );
+                                                        }
+
+                                                        // Wind-up meter while aiming a throw
+                                                        if let Some((_, held)) = aiming {
+                                                            let charge = (held / infinite_game::FULL_CHARGE_TIME).min(1.0);
+                                                            let meter = egui::Rect::from_min_size(
+                                                                egui::pos2(slot_rect.min.x, slot_rect.max.y + 3.0),
+                                                                egui::vec2(hotbar_slot * charge, 4.0),
+                                                            );
+                                                            ui.painter().rect_filled(meter, 2.0, egui::Color32::from_rgb(255, 200, 80));
+                                                        }
+
                                                         ui.painter().text(
                                                             slot_rect.min + egui::vec2(4.0, 2.0),
                                                             egui::Align2::LEFT_TOP,
-                                                            keybinds[i],
+                                                            format!("{}", slot + 5),
                                                             egui::FontId::proportional(10.0),
                                                             egui::Color32::from_rgb(160, 160, 180),
                                                         );
-                                                    }
 
-                                                    // Gap between slots
-                                                    if i < num_slots - 1 {
-                                                        ui.add_space(slot_gap);
+                                                        if slot < infinite_game::HOTBAR_SLOTS - 1 {
+                                                            ui.add_space(slot_gap);
+                                                        }
                                                     }
-                                                }
+                                                });
                                             });
-                                        });
+                                    }
 
                                     // Dodge cooldown indicator (to the right of skill bar)
-                                    egui::Area::new(egui::Id::new("dodge_indicator"))
-                                        .fixed_pos([bar_x + total_width + 10.0, bar_y + 10.0])
-                                        .show(&ctx, |ui| {
-                                            let dodge_rect = ui.allocate_space(egui::vec2(70.0, 40.0)).1;
+                                    if self.settings.hud.is_enabled(HudWidget::SkillBar) {
+                                        egui::Area::new(egui::Id::new("dodge_indicator"))
+                                            .fixed_pos([bar_x + total_width + 10.0, bar_y + 10.0])
+                                            .show(&ctx, |ui| {
+                                                let dodge_rect = ui.allocate_space(egui::vec2(70.0, 40.0)).1;
+
+                                                // Dark background
+                                                ui.painter().rect_filled(
+                                                    dodge_rect,
+                                                    4.0,
+                                                    egui::Color32::from_rgba_unmultiplied(20, 20, 35, 220),
+                                                );
+                                                ui.painter().rect_stroke(
+                                                    dodge_rect,
+                                                    4.0,
+                                                    egui::Stroke::new(1.0, egui::Color32::from_rgb(60, 60, 90)),
+                                                    egui::epaint::StrokeKind::Outside,
+                                                );
+
+                                                if self.player_combat.is_dodging {
+                                                    // Active: cyan highlight
+                                                    ui.painter().text(
+                                                        dodge_rect.center(),
+                                                        egui::Align2::CENTER_CENTER,
+                                                        "DODGE",
+                                                        egui::FontId::proportional(13.0),
+                                                        egui::Color32::from_rgb(0, 220, 220),
+                                                    );
+                                                } else if self.player_combat.dodge_cooldown_timer > 0.0 {
+                                                    // On cooldown: dark overlay + countdown
+                                                    let cd_frac = (self.player_combat.dodge_cooldown_timer / balance::player::DODGE_COOLDOWN.get()).min(1.0);
+                                                    let overlay_rect = egui::Rect::from_min_size(
+                                                        dodge_rect.min,
+                                                        egui::vec2(70.0, 40.0 * cd_frac),
+                                                    );
+                                                    ui.painter().rect_filled(
+                                                        overlay_rect,
+                                                        4.0,
+                                                        egui::Color32::from_rgba_unmultiplied(0, 0, 0, 150),
+                                                    );
+                                                    ui.painter().text(
+                                                        dodge_rect.center() - egui::vec2(0.0, 4.0),
+                                                        egui::Align2::CENTER_CENTER,
+                                                        format!("{:.1}", self.player_combat.dodge_cooldown_timer),
+                                                        egui::FontId::proportional(13.0),
+                                                        egui::Color32::from_rgb(255, 180, 80),
+                                                    );
+                                                    ui.painter().text(
+                                                        egui::pos2(dodge_rect.center().x, dodge_rect.max.y - 4.0),
+                                                        egui::Align2::CENTER_BOTTOM,
+                                                        "Ctrl",
+                                                        egui::FontId::proportional(9.0),
+                                                        egui::Color32::from_rgb(120, 120, 140),
+                                                    );
+                                                } else {
+                                                    // Ready: green text
+                                                    ui.painter().text(
+                                                        dodge_rect.center() - egui::vec2(0.0, 4.0),
+                                                        egui::Align2::CENTER_CENTER,
+                                                        "DODGE",
+                                                        egui::FontId::proportional(13.0),
+                                                        egui::Color32::from_rgb(80, 220, 80),
+                                                    );
+                                                    ui.painter().text(
+                                                        egui::pos2(dodge_rect.center().x, dodge_rect.max.y - 4.0),
+                                                        egui::Align2::CENTER_BOTTOM,
+                                                        "Ctrl",
+                                                        egui::FontId::proportional(9.0),
+                                                        egui::Color32::from_rgb(120, 120, 140),
+                                                    );
+                                                }
+                                            });
+
+                                        // Sneaking indicator above the dodge box
+                                        if self.player.as_ref().is_some_and(|p| p.is_crouching()) {
+                                            egui::Area::new(egui::Id::new("sneak_indicator"))
+                                                .fixed_pos([bar_x + total_width + 10.0, bar_y - 14.0])
+                                                .interactable(false)
+                                                .show(&ctx, |ui| {
+                                                    ui.label(
+                                                        egui::RichText::new("SNEAKING")
+                                                            .font(egui::FontId::proportional(12.0))
+                                                            .color(egui::Color32::from_rgb(170, 150, 230)),
+                                                    );
+                                                });
+                                        }
+
+                                        // Blocking indicator
+                                        if self.player_combat.is_blocking {
+                                            egui::Area::new(egui::Id::new("block_indicator"))
+                                                .fixed_pos([bar_x + total_width + 10.0, bar_y - 30.0])
+                                                .interactable(false)
+                                                .show(&ctx, |ui| {
+                                                    ui.label(
+                                                        egui::RichText::new("BLOCKING")
+                                                            .font(egui::FontId::proportional(12.0))
+                                                            .color(egui::Color32::from_rgb(200, 190, 140)),
+                                                    );
+                                                });
+                                        }
 
-                                            // Dark background
-                                            ui.painter().rect_filled(
-                                                dodge_rect,
-                                                4.0,
-                                                egui::Color32::from_rgba_unmultiplied(20, 20, 35, 220),
-                                            );
-                                            ui.painter().rect_stroke(
-                                                dodge_rect,
-                                                4.0,
-                                                egui::Stroke::new(1.0, egui::Color32::from_rgb(60, 60, 90)),
-                                                egui::epaint::StrokeKind::Outside,
-                                            );
+                                        // Weapon coating charges (above the skill bar)
+                                        if let Some(coating) = self.player_combat.coating {
+                                            let [r, g, b] = coating.kind.effect().color();
+                                            egui::Area::new(egui::Id::new("weapon_coating"))
+                                                .fixed_pos([bar_x, bar_y - 22.0])
+                                                .interactable(false)
+                                                .show(&ctx, |ui| {
+                                                    ui.label(
+                                                        egui::RichText::new(format!("{} x{}", coating.kind.name(), coating.charges))
+                                                            .font(egui::FontId::proportional(12.0))
+                                                            .color(egui::Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)),
+                                                    );
+                                                });
+                                        }
+                                    }
+                                }
 
-                                            if self.player_combat.is_dodging {
-                                                // Active: cyan highlight
-                                                ui.painter().text(
-                                                    dodge_rect.center(),
-                                                    egui::Align2::CENTER_CENTER,
-                                                    "DODGE",
-                                                    egui::FontId::proportional(13.0),
-                                                    egui::Color32::from_rgb(0, 220, 220),
-                                                );
-                                            } else if self.player_combat.dodge_cooldown_timer > 0.0 {
-                                                // On cooldown: dark overlay + countdown
-                                                let cd_frac = self.player_combat.dodge_cooldown_timer / self.player_combat.dodge_cooldown;
-                                                let overlay_rect = egui::Rect::from_min_size(
-                                                    dodge_rect.min,
-                                                    egui::vec2(70.0, 40.0 * cd_frac),
-                                                );
-                                                ui.painter().rect_filled(
-                                                    overlay_rect,
-                                                    4.0,
-                                                    egui::Color32::from_rgba_unmultiplied(0, 0, 0, 150),
-                                                );
-                                                ui.painter().text(
-                                                    dodge_rect.center() - egui::vec2(0.0, 4.0),
-                                                    egui::Align2::CENTER_CENTER,
-                                                    format!("{:.1}", self.player_combat.dodge_cooldown_timer),
-                                                    egui::FontId::proportional(13.0),
-                                                    egui::Color32::from_rgb(255, 180, 80),
-                                                );
-                                                ui.painter().text(
-                                                    egui::pos2(dodge_rect.center().x, dodge_rect.max.y - 4.0),
-                                                    egui::Align2::CENTER_BOTTOM,
-                                                    "Ctrl",
-                                                    egui::FontId::proportional(9.0),
-                                                    egui::Color32::from_rgb(120, 120, 140),
-                                                );
-                                            } else {
-                                                // Ready: green text
-                                                ui.painter().text(
-                                                    dodge_rect.center() - egui::vec2(0.0, 4.0),
-                                                    egui::Align2::CENTER_CENTER,
-                                                    "DODGE",
-                                                    egui::FontId::proportional(13.0),
-                                                    egui::Color32::from_rgb(80, 220, 80),
-                                                );
-                                                ui.painter().text(
-                                                    egui::pos2(dodge_rect.center().x, dodge_rect.max.y - 4.0),
-                                                    egui::Align2::CENTER_BOTTOM,
-                                                    "Ctrl",
-                                                    egui::FontId::proportional(9.0),
-                                                    egui::Color32::from_rgb(120, 120, 140),
-                                                );
-                                            }
-                                        });
+                                // --- HUD layout edit mode (F4) ---
+                                if self.hud_editor.active && self.hud_editor.render(&ctx, &mut self.settings.hud) {
+                                    should_save_settings = true;
                                 }
 
                                 // --- Inventory overlay ---
                                 if self.show_inventory {
+                                    self.inventory_menu.player_position =
+                                        self.player.as_ref().map(|p| p.position()).unwrap_or(Vec3::ZERO);
+                                    self.inventory_menu.active_year = self.timeline.active_year;
+                                    self.inventory_menu.paradox = self.settings.gameplay.paradox.clone();
                                     let (inv_transition, inv_action) = self.inventory_menu.render(
                                         ui,
                                         &self.player_combat.equipment,
                                         &self.player_combat.inventory,
                                         &self.player_combat.stats,
+                                        &self.bestiary,
+                                        &self.key_ring,
+                                        &self.hotbar,
+                                        &self.gathering_skills,
                                     );
                                     inventory_pending_action = inv_action;
                                     if matches!(inv_transition, StateTransition::Pop) {
@@ -3003,7 +8507,9 @@ impl InfiniteApp {
                                             ui,
                                             catalog,
                                             &self.player_combat.inventory,
+                                            &self.player_combat.equipment,
                                             self.player_combat.gold,
+                                            self.shop_market.and_then(|id| self.economy.market(id)),
                                         );
                                         shop_pending_action = action;
                                     }
@@ -3016,7 +8522,7 @@ impl InfiniteApp {
                                     self.admin_panel = Some(AdminPanel::new());
                                 }
                                 if let Some(panel) = &mut self.admin_panel {
-                                    panel.render(ui, self.integration_client.as_ref())
+                                    panel.render(ui, self.integration_client.as_ref(), &mut self.admin_audit)
                                 } else {
                                     StateTransition::None
                                 }
@@ -3026,43 +8532,92 @@ impl InfiniteApp {
 
                         pending_transition = transition;
                     });
+
+                // Errors show over everything else
+                error_action = self.error_dialog.render(&ctx);
             });
         }
 
+        // A fatal error closes the game once the player has read it
+        if error_action == Some(ErrorDialogAction::Quit) {
+            pending_transition = StateTransition::Replace(ApplicationState::Exiting);
+        }
+
+        // Process timeline browser choices
+        match timeline_action {
+            Some(TimelineAction::Jump(branch)) => self.start_branch_switch(branch),
+            Some(TimelineAction::Fork) => {
+                let name = format!("Branch {}", self.timeline.branches.branches().len());
+                self.fork_timeline(name, &[]);
+            }
+            None => {}
+        }
+        if timelines_open && !self.timeline_browser.visible {
+            self.update_cursor_capture(true);
+        }
+
+        // Process training commands from the debug overlay
+        if let Some(position) = spawn_dummy_at {
+            self.spawn_training_dummy(position);
+        }
+        if let Some((character, role)) = spawn_template {
+            self.spawn_npc_template(character, role);
+        }
+        if let Some(action) = gm_action {
+            self.apply_gm_action(action);
+        }
+        if let Some(index) = gift_item {
+            self.give_gift(index);
+        }
+        if let Some(center) = start_arena_at {
+            self.start_arena(center);
+        }
+
+        // Practice: start the speedrun over from where the player stands
+        if std::mem::take(&mut self.pause_menu.restart_run_requested) {
+            if let Some(seed) = self.speedrun.as_ref().map(|run| run.seed()) {
+                self.start_speedrun(seed);
+                self.pause_menu.speedrun_notice = Some("The timer starts again when you resume".to_string());
+            }
+        }
+
         // Process save/load actions (deferred to avoid borrow conflicts in the UI closure)
         if let Some((menu_transition, action)) = save_load_pending_action {
-            let mut result_transition = menu_transition;
             match action {
                 SaveLoadAction::SaveNew(name) => {
                     let data = self.gather_save_data(&name);
-                    match save::save_to_slot(&name, &data) {
-                        Ok(()) => {
-                            self.notification_text = Some(format!("Saved: {}", name));
-                            self.notification_timer = 2.0;
+                    self.flush_world();
+                    self.save_indicator_timer = 1.0;
+                    self.save_worker.save(SaveSlot::named(&name), data, move |app, result| {
+                        match result {
+                            Ok(()) => {
+                                app.tutorials.handle(infinite_game::TutorialEvent::Saved);
+                                app.notification_text = Some(format!("Saved: {}", name));
+                                app.notification_timer = 2.0;
+                            }
+                            Err(e) => {
+                                app.notification_text = Some(format!("Save failed: {}", e));
+                                app.notification_timer = 3.0;
+                            }
                         }
-                        Err(e) => {
-                            self.notification_text = Some(format!("Save failed: {}", e));
-                            self.notification_timer = 3.0;
+                        if let Some(menu) = &mut app.save_load_menu {
+                            menu.mark_needs_refresh();
                         }
-                    }
-                    if let Some(menu) = &mut self.save_load_menu {
-                        menu.mark_needs_refresh();
-                    }
+                    });
                 }
                 SaveLoadAction::Load(filename) => {
-                    match save::load_from_slot(&filename) {
+                    self.save_worker.load(SaveSlot::Named(filename), |app, result| match result {
                         Ok(data) => {
-                            self.restore_from_save(data);
-                            self.notification_text = Some("Game Loaded".to_string());
-                            self.notification_timer = 2.0;
-                            self.save_load_menu = None;
-                            result_transition = StateTransition::Replace(ApplicationState::Playing);
+                            let mod_changes = app.restore_from_save(data);
+                            app.notify_loaded(&mod_changes);
+                            app.save_load_menu = None;
+                            app.apply_transition(StateTransition::Replace(ApplicationState::Playing));
                         }
                         Err(e) => {
-                            self.notification_text = Some(format!("Load failed: {}", e));
-                            self.notification_timer = 3.0;
+                            app.notification_text = Some(format!("Load failed: {}", e));
+                            app.notification_timer = 3.0;
                         }
-                    }
+                    });
                 }
                 SaveLoadAction::Delete(filename) => {
                     if let Err(e) = save::delete_slot(&filename) {
@@ -3075,11 +8630,11 @@ impl InfiniteApp {
                 }
                 SaveLoadAction::None => {}
             }
-            if matches!(result_transition, StateTransition::Pop) {
+            if matches!(menu_transition, StateTransition::Pop) {
                 self.save_load_menu = None;
             }
-            if !matches!(result_transition, StateTransition::None) {
-                pending_transition = result_transition;
+            if !matches!(menu_transition, StateTransition::None) {
+                pending_transition = menu_transition;
             }
         }
 
@@ -3094,7 +8649,7 @@ impl InfiniteApp {
             InventoryAction::EquipItem { inventory_index, slot } => {
                 // Validate category compatibility before removing from inventory
                 let can_equip = self.player_combat.inventory.get(inventory_index)
-                    .map(|item| item.category == slot.valid_category())
+                    .map(|item| slot.accepts(item))
                     .unwrap_or(false);
                 if can_equip {
                     if let Some(item) = self.player_combat.inventory.remove_item(inventory_index) {
@@ -3125,21 +8680,13 @@ impl InfiniteApp {
                 }
             }
             InventoryAction::UseItem { inventory_index } => {
+                self.use_consumable(inventory_index);
+            }
+            InventoryAction::BindHotbar { inventory_index, slot } => {
                 if let Some(item) = self.player_combat.inventory.get(inventory_index) {
-                    let item_name = item.name.clone();
-                    let is_health_potion = item_name.to_lowercase().contains("health");
-
-                    if is_health_potion {
-                        self.player_combat.stats.heal(30.0);
-                        self.player_combat.inventory.remove_item_stack(inventory_index, 1);
-                        self.notification_text = Some(format!("Used {}", item_name));
-                        self.notification_timer = 1.5;
-                        // Green healing flash (re-use damage flash with positive indicator)
-                        self.player_combat.damage_flash_timer = 0.3;
-                    } else {
-                        self.notification_text = Some("Cannot use this item.".to_string());
-                        self.notification_timer = 1.5;
-                    }
+                    self.hotbar.bind(slot, item.id);
+                    self.notification_text = Some(format!("{} bound to {}", item.name, slot + 5));
+                    self.notification_timer = 1.5;
                 }
             }
             InventoryAction::None => {}
@@ -3149,11 +8696,19 @@ impl InfiniteApp {
         match shop_pending_action {
             ShopAction::Buy { catalog_index } => {
                 if let Some(catalog) = &self.item_catalog {
-                    let price = catalog.price(catalog_index);
+                    let market = self.shop_market.and_then(|id| self.economy.market_mut(id));
+                    let price = buy_price_for(catalog_index, catalog, market.as_deref());
                     if self.player_combat.gold >= price {
                         if let Some(item) = catalog.items().get(catalog_index).cloned() {
-                            if self.player_combat.inventory.add_item(item.clone()).is_ok() {
+                            if market.as_ref().is_some_and(|m| !m.in_stock(item.category)) {
+                                self.notification_text = Some("Out of stock!".to_string());
+                                self.notification_timer = 2.0;
+                            } else if self.player_combat.inventory.add_item(item.clone()).is_ok() {
+                                if let Some(market) = market {
+                                    market.record_purchase(item.category);
+                                }
                                 self.player_combat.gold -= price;
+                                self.play_stats.record(infinite_game::StatEvent::GoldSpent(price));
                                 self.notification_text = Some(format!("Bought {}", item.name));
                                 self.notification_timer = 1.5;
                             } else {
@@ -3169,20 +8724,38 @@ impl InfiniteApp {
             }
             ShopAction::Sell { inventory_index } => {
                 if let Some(item) = self.player_combat.inventory.get(inventory_index) {
+                    let market = self.shop_market.and_then(|id| self.economy.market_mut(id));
                     let sell_price = if let Some(catalog) = &self.item_catalog {
-                        sell_price_for(item, catalog)
+                        market_sell_price(item, catalog, market.as_deref())
                     } else {
                         1
                     };
+                    if let Some(market) = market {
+                        market.record_sale(item.category);
+                    }
                     let item_name = item.name.clone();
                     self.player_combat.inventory.remove_item(inventory_index);
                     self.player_combat.gold += sell_price;
+                    self.play_stats.record(infinite_game::StatEvent::GoldEarned(sell_price));
                     self.notification_text = Some(format!("Sold {} for {} gold", item_name, sell_price));
                     self.notification_timer = 1.5;
                     // Reset selection after selling
                     self.shop_menu.selected_sell_item_reset();
                 }
             }
+            ShopAction::RepairAll => {
+                let cost = self.player_combat.equipment.repair_cost();
+                if self.player_combat.gold >= cost {
+                    self.player_combat.gold -= cost;
+                    self.play_stats.record(infinite_game::StatEvent::GoldSpent(cost));
+                    self.player_combat.equipment.repair_all();
+                    self.notification_text = Some(format!("Gear repaired for {} gold", cost));
+                    self.notification_timer = 1.5;
+                } else {
+                    self.notification_text = Some("Not enough gold!".to_string());
+                    self.notification_timer = 2.0;
+                }
+            }
             ShopAction::Close => {
                 self.show_shop = false;
                 self.update_cursor_capture(true);
@@ -3190,6 +8763,131 @@ impl InfiniteApp {
             ShopAction::None => {}
         }
 
+        // Process archetype changes
+        match respec_pending_action {
+            Some(RespecAction::Confirm { primary, secondary }) => {
+                let growth = primary.growth_with(secondary);
+                let secondary_key = secondary.map(|a| a.key());
+                match self.player_combat.respec(&primary.key(), secondary_key.as_deref(), &primary.base_stats(), &growth) {
+                    Some(cost) => {
+                        if cost > 0 {
+                            self.play_stats.record(infinite_game::StatEvent::GoldSpent(cost));
+                        }
+                        if let Some(character) = &mut self.current_character {
+                            character.set_archetype(primary);
+                        }
+                        self.archetype_growth = Some(growth);
+                        self.notification_text = Some(match secondary {
+                            Some(other) => format!("You now walk the paths of {} and {}", primary.name(), other.name()),
+                            None => format!("You now walk the path of the {}", primary.name()),
+                        });
+                        self.notification_timer = 2.5;
+                        self.respec_menu = None;
+                        self.update_cursor_capture(true);
+                    }
+                    None => {
+                        self.notification_text = Some("Not enough gold!".to_string());
+                        self.notification_timer = 2.0;
+                    }
+                }
+            }
+            Some(RespecAction::Close) => {
+                self.respec_menu = None;
+                self.update_cursor_capture(true);
+            }
+            None => {}
+        }
+
+        // Process cooking
+        match cooking_pending_action {
+            Some(CookingAction::Cook(pot)) => self.cook(&pot),
+            Some(CookingAction::Close) => {
+                self.cooking_menu = None;
+                self.update_cursor_capture(true);
+            }
+            None => {}
+        }
+
+        // Process brewing
+        match brewing_pending_action {
+            Some(BrewingAction::Brew { reagents, coating }) => self.brew(&reagents, coating),
+            Some(BrewingAction::Taste(reagent)) => self.taste_reagent(reagent),
+            Some(BrewingAction::Close) => {
+                self.brewing_menu = None;
+                self.update_cursor_capture(true);
+            }
+            None => {}
+        }
+
+        // Process prayers
+        match shrine_pending_action {
+            Some(ShrineAction::Pray(boon, tribute)) => self.pray(boon, tribute),
+            Some(ShrineAction::Cleanse) => self.cleanse_at_shrine(),
+            Some(ShrineAction::Close) => {
+                self.shrine_menu = None;
+                self.update_cursor_capture(true);
+            }
+            None => {}
+        }
+
+        // Process the survival arena shop and results
+        match survival_pending_action {
+            Some(SurvivalAction::Buy(upgrade)) => {
+                if let Some(run) = &mut self.survival {
+                    self.notification_text = Some(match run.buy(upgrade, &mut self.player_combat) {
+                        Ok(()) => format!("Bought {}", upgrade.name()),
+                        Err(infinite_game::SurvivalError::WaveUnderway) => "The shop is closed while a wave is on".to_string(),
+                        Err(infinite_game::SurvivalError::NotEnoughPoints) => "Not enough points".to_string(),
+                        Err(infinite_game::SurvivalError::InventoryFull) => "Inventory full".to_string(),
+                    });
+                    self.notification_timer = 1.5;
+                }
+            }
+            Some(SurvivalAction::NextWave) => {
+                if let Some(run) = &mut self.survival {
+                    run.skip_intermission();
+                }
+            }
+            Some(SurvivalAction::Retry) => {
+                self.cleanup_game_systems();
+                self.init_survival_systems();
+            }
+            Some(SurvivalAction::Leave) => {
+                pending_transition = StateTransition::Replace(ApplicationState::MainMenu);
+            }
+            Some(SurvivalAction::Close) => {
+                if let Some(menu) = &mut self.survival_menu {
+                    menu.shop_closed = true;
+                }
+                self.update_cursor_capture(true);
+            }
+            None => {}
+        }
+
+        // Process the time rift gate and boon choices
+        match rift_pending_action {
+            Some(RiftAction::Enter) => {
+                if let Some(menu) = self.rift_menu.take() {
+                    self.update_cursor_capture(true);
+                    self.start_rift_run(menu.gate);
+                }
+            }
+            Some(RiftAction::Attune(attunement)) => {
+                self.notification_text = Some(match self.rift_ledger.attune(attunement) {
+                    Ok(()) => format!("{} attuned to level {}", attunement.name(), self.rift_ledger.level(attunement)),
+                    Err(infinite_game::RiftError::Maxed) => format!("{} is mastered", attunement.name()),
+                    Err(_) => "Not enough rift shards".to_string(),
+                });
+                self.notification_timer = 1.5;
+            }
+            Some(RiftAction::Choose(index)) => self.choose_rift_boon(index),
+            Some(RiftAction::Close) => {
+                self.rift_menu = None;
+                self.update_cursor_capture(true);
+            }
+            None => {}
+        }
+
         // Apply state transition after UI is done
         if !matches!(pending_transition, StateTransition::None) {
             self.apply_transition(pending_transition);
@@ -3197,6 +8895,7 @@ impl InfiniteApp {
 
         // Save settings if needed
         if should_save_settings {
+            self.apply_audio_settings();
             if let Err(e) = self.settings.save() {
                 tracing::error!("Failed to save settings: {}", e);
             }
@@ -3206,12 +8905,37 @@ impl InfiniteApp {
         let render_ctx = self.render_ctx.as_mut().unwrap();
         let gui = self.gui.as_mut().unwrap();
 
-        let mut builder = AutoCommandBufferBuilder::primary(
+        let mut builder = match AutoCommandBufferBuilder::primary(
             render_ctx.command_buffer_allocator.clone(),
             render_ctx.queue.queue_family_index(),
             CommandBufferUsage::OneTimeSubmit,
-        )
-        .unwrap();
+        ) {
+            Ok(builder) => builder,
+            Err(e) => {
+                self.on_render_error(vulkan_error(RENDER_SUBMIT_FAILED, "Failed to allocate a command buffer", e));
+                return;
+            }
+        };
+
+        // Time the frame on the GPU, and size the scene by the frames
+        // measured so far when dynamic resolution is on
+        let video = &self.settings.video;
+        let dynamic_resolution = video.dynamic_resolution && matches!(self.app_state, ApplicationState::Playing);
+        self.dynamic_resolution.set_config(ResolutionConfig {
+            target_fps: video.target_fps as f32,
+            min_scale: video.min_render_scale,
+            max_scale: video.max_render_scale,
+        });
+        if let Some(timer) = render_ctx.gpu_timer.as_mut() {
+            if let Err(e) = timer.begin(&mut builder) {
+                tracing::warn!("Failed to start the GPU frame timer: {}", e);
+            }
+            if let Some(gpu_ms) = timer.take_frame_time().filter(|_| dynamic_resolution) {
+                self.dynamic_resolution.record(gpu_ms);
+            }
+        }
+        let render_scale = if dynamic_resolution { self.dynamic_resolution.scale() } else { 1.0 };
+        let scene_extent = scaled_extent(render_ctx.scene_target.extent(), render_scale);
 
         // Get sky colors from time of day, modified by weather
         let sky_colors = self.time_of_day.sky_colors();
@@ -3229,9 +8953,7 @@ impl InfiniteApp {
                         ].into()),
                         Some(1.0f32.into()), // Depth clear value
                     ],
-                    ..RenderPassBeginInfo::framebuffer(
-                        render_ctx.framebuffers[image_index as usize].clone(),
-                    )
+                    ..RenderPassBeginInfo::framebuffer(render_ctx.scene_target.framebuffer.clone())
                 },
                 SubpassBeginInfo {
                     contents: SubpassContents::Inline,
@@ -3240,10 +8962,11 @@ impl InfiniteApp {
             )
             .unwrap();
 
-        // === SUBPASS 0: 3D Scene Rendering ===
+        // === SCENE PASS: 3D Scene Rendering ===
+        // Drawn into the top-left `scene_extent` of the scene target
         let viewport = Viewport {
             offset: [0.0, 0.0],
-            extent: [window_size.width as f32, window_size.height as f32],
+            extent: [scene_extent[0] as f32, scene_extent[1] as f32],
             depth_range: 0.0..=1.0,
         };
 
@@ -3261,21 +8984,112 @@ impl InfiniteApp {
         // Vulkan Y-axis is inverted compared to OpenGL, flip it in projection
         projection_matrix.y_axis.y *= -1.0;
 
+        // Rasterize the terrain into the occlusion buffer so chunks, props
+        // and NPCs hidden behind hills can be skipped below
+        self.occlusion.begin(projection_matrix * view_matrix);
+        if let Some(chunk_manager) = self.chunk_manager.as_ref().filter(|_| {
+            self.occlusion_culling && matches!(self.app_state, ApplicationState::Playing)
+        }) {
+            let chunk_size = chunk_manager.config.chunk_size;
+            for chunk in chunk_manager.loaded_chunks() {
+                let terrain = &chunk.terrain;
+                self.occlusion.add_heightfield(
+                    chunk.coord.world_center(chunk_size),
+                    terrain.config.size,
+                    terrain.config.subdivisions,
+                    &terrain.heights,
+                    OCCLUDER_BLOCK,
+                );
+            }
+        }
+        self.occlusion.finish();
+
         // Get lighting from time of day and weather
         let sun_direction = self.time_of_day.light_direction();
         let sun_intensity = self.time_of_day.light_intensity() * self.weather.sun_modifier();
         let ambient_intensity = 0.3 * self.weather.ambient_modifier();
 
-        // Set viewport and scissor for all 3D rendering in subpass 0
+        // Point lights nearest the camera, shared by every basic/wireframe draw
+        let light_uniforms = match (&self.app_state, &self.camera) {
+            (ApplicationState::Playing, Some(camera)) => PointLightUniforms::from_lights(
+                &self.point_lights.prioritized(
+                    camera.position(),
+                    self.game_time.total_time as f32,
+                    MAX_POINT_LIGHTS,
+                ),
+            ),
+            _ => PointLightUniforms::default(),
+        };
+        // Height fog in the sky's horizon color, closing in to hide the edge
+        // of the loaded chunks
+        let view_distance = match &self.chunk_manager {
+            Some(chunk_manager) => chunk_manager.config.load_radius as f32 * chunk_manager.config.chunk_size,
+            None => 200.0,
+        };
+        let fog = Fog::atmospheric(&sky_colors, &self.weather, view_distance);
+        let fog_uniforms = FogUniforms::new(
+            self.camera.as_ref().map_or(Vec3::ZERO, |camera| camera.position()),
+            fog.color,
+            fog.density,
+            fog.height_falloff,
+            fog.base_height,
+            fog.haze_start,
+            fog.haze_end,
+        );
+
+        render_ctx.frame_uniforms.begin_frame();
+        let light_set = render_ctx.basic_pipeline.as_ref().and_then(|pipeline| {
+            render_ctx
+                .frame_uniforms
+                .frame_set_with(pipeline.layout(), light_uniforms, fog_uniforms)
+                .map_err(|e| tracing::error!("Failed to create lighting descriptor set: {}", e))
+                .ok()
+        });
+
+        // Stars, moon and clouds over the sky gradient, styled by era
+        let era_sky = EraSky::for_era(&TimeTerrainConfig::for_year(self.timeline.active_year, self.timeline.present_year));
+        let cloud_cover = self.weather.cloud_cover();
+        let sky_uniforms = SkyUniforms::new(
+            self.time_of_day.moon_direction(),
+            self.time_of_day.moon_phase(),
+            &SkyStyle {
+                sun_tint: era_sky.sun_tint,
+                sun_scale: era_sky.sun_scale,
+                moon_tint: era_sky.moon_tint,
+                moon_size: era_sky.moon_size,
+                star_tint: era_sky.star_tint,
+                star_density: era_sky.star_density,
+                cloud_tint: era_sky.cloud_tint,
+                second_moon: era_sky.second_moon,
+                ring: era_sky.ring,
+            },
+            &CloudLayer {
+                coverage: cloud_cover.coverage,
+                density: cloud_cover.density,
+                darkness: cloud_cover.darkness,
+                drift: self.wind.vector() * CLOUD_DRIFT,
+            },
+            self.game_time.total_time as f32,
+        )
+        .with_haze(self.weather.fog_density);
+        let sky_set = render_ctx.sky_pipeline.as_ref().and_then(|pipeline| {
+            render_ctx
+                .frame_uniforms
+                .frame_set(pipeline.layout(), sky_uniforms)
+                .map_err(|e| tracing::error!("Failed to create sky descriptor set: {}", e))
+                .ok()
+        });
+
+        // Set viewport and scissor for all 3D rendering in the scene pass
         // Both must be set when using dynamic state
         let scissor = vulkano::pipeline::graphics::viewport::Scissor {
             offset: [0, 0],
-            extent: [window_size.width, window_size.height],
+            extent: scene_extent,
         };
         builder
             .set_viewport(0, [viewport.clone()].into_iter().collect())
             .unwrap()
-            .set_scissor(0, [scissor.clone()].into_iter().collect())
+            .set_scissor(0, [scissor].into_iter().collect())
             .unwrap();
 
         // Render 3D scene if playing
@@ -3291,7 +9105,7 @@ impl InfiniteApp {
             }
 
             // Render sky dome
-            if let (Some(sky_pipeline), Some(sky_mesh)) = (&render_ctx.sky_pipeline, &render_ctx.sky_mesh) {
+            if let (Some(sky_pipeline), Some(sky_mesh), Some(sky_set)) = (&render_ctx.sky_pipeline, &render_ctx.sky_mesh, &sky_set) {
                 let sky_push = SkyPushConstants::new(
                     view_matrix,
                     projection_matrix,
@@ -3308,133 +9122,674 @@ impl InfiniteApp {
 
                 unsafe {
                     builder
-                        .bind_pipeline_graphics(sky_pipeline.clone())
+                        .bind_pipeline_graphics(sky_pipeline.clone())
+                        .unwrap()
+                        .push_constants(sky_pipeline.layout().clone(), 0, sky_push)
+                        .unwrap()
+                        .bind_descriptor_sets(PipelineBindPoint::Graphics, sky_pipeline.layout().clone(), 0, sky_set.clone())
+                        .unwrap()
+                        .bind_vertex_buffers(0, sky_mesh.vertex_buffer.clone())
+                        .unwrap()
+                        .bind_index_buffer(sky_mesh.index_buffer.clone())
+                        .unwrap()
+                        .draw_indexed(sky_mesh.index_count, 1, 0, 0, 0)
+                        .unwrap();
+                }
+            }
+
+            // Render chunk terrain meshes
+            {
+                let terrain_pipeline = if self.debug_wireframe {
+                    render_ctx.wireframe_pipeline.as_ref().or(render_ctx.basic_pipeline.as_ref())
+                } else {
+                    render_ctx.basic_pipeline.as_ref()
+                };
+
+                if let (Some(pipeline), Some(chunk_manager), Some(light_set)) =
+                    (terrain_pipeline, &self.chunk_manager, &light_set)
+                {
+                    let chunk_size = chunk_manager.config.chunk_size;
+
+                    for chunk in chunk_manager.loaded_chunks() {
+                        if let Some(mesh) = render_ctx.chunk_meshes.get(&chunk.coord) {
+                            let origin = chunk.coord.world_center(chunk_size);
+                            let half = Vec3::new(chunk_size / 2.0, 0.0, chunk_size / 2.0);
+                            let (low, high) = (Vec3::Y * chunk.terrain.min_height, Vec3::Y * chunk.terrain.max_height);
+                            if self.occlusion.cull(origin - half + low, origin + half + high) {
+                                continue;
+                            }
+                            let model = Mat4::from_translation(origin);
+
+                            let push = BasicPushConstants::new(
+                                model,
+                                view_matrix,
+                                projection_matrix,
+                                sun_direction,
+                                sun_intensity,
+                                Vec3::new(1.0, 0.95, 0.85),
+                                ambient_intensity,
+                            );
+
+                            unsafe {
+                                builder
+                                    .bind_pipeline_graphics(pipeline.clone())
+                                    .unwrap()
+                                    .bind_descriptor_sets(PipelineBindPoint::Graphics, pipeline.layout().clone(), 0, light_set.clone())
+                                    .unwrap()
+                                    .push_constants(pipeline.layout().clone(), 0, push)
+                                    .unwrap()
+                                    .bind_vertex_buffers(0, mesh.vertex_buffer.clone())
+                                    .unwrap()
+                                    .bind_index_buffer(mesh.index_buffer.clone())
+                                    .unwrap()
+                                    .draw_indexed(mesh.index_count, 1, 0, 0, 0)
+                                    .unwrap();
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Also render legacy single terrain if present (fallback)
+            if let Some(terrain_mesh) = &render_ctx.terrain_mesh {
+                if let (Some(pipeline), Some(light_set)) = (render_ctx.basic_pipeline.as_ref(), &light_set) {
+                    let push = BasicPushConstants::new(
+                        Mat4::IDENTITY,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::new(1.0, 0.95, 0.85),
+                        ambient_intensity,
+                    );
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, terrain_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(terrain_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(terrain_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
+
+            // Render player capsule (debug visualization)
+            if let (Some(basic_pipeline), Some(capsule_mesh), Some(player), Some(light_set)) =
+                (&render_ctx.basic_pipeline, &render_ctx.capsule_mesh, &self.player, &light_set)
+            {
+                let player_pos = player.render_center_position(self.game_time.fixed_interpolation());
+                // Seated players sink onto the seat; leaning ones tip back against the wall
+                let model = match (player.rest_pose(), player.rest_facing()) {
+                    (Some(pose), Some(facing)) => {
+                        let up = (Vec3::Y - facing * pose.tilt()).normalize();
+                        Mat4::from_rotation_translation(
+                            glam::Quat::from_rotation_arc(Vec3::Y, up),
+                            player_pos - Vec3::Y * pose.body_drop(),
+                        )
+                    }
+                    _ => Mat4::from_translation(player_pos),
+                };
+
+                let push = BasicPushConstants::new(
+                    model,
+                    view_matrix,
+                    projection_matrix,
+                    sun_direction,
+                    sun_intensity,
+                    Vec3::new(1.0, 0.95, 0.85),
+                    ambient_intensity,
+                );
+
+                unsafe {
+                    builder
+                        .bind_pipeline_graphics(basic_pipeline.clone())
                         .unwrap()
-                        .push_constants(sky_pipeline.layout().clone(), 0, sky_push)
+                        .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
                         .unwrap()
-                        .bind_vertex_buffers(0, sky_mesh.vertex_buffer.clone())
+                        .push_constants(basic_pipeline.layout().clone(), 0, push)
                         .unwrap()
-                        .bind_index_buffer(sky_mesh.index_buffer.clone())
+                        .bind_vertex_buffers(0, capsule_mesh.vertex_buffer.clone())
                         .unwrap()
-                        .draw_indexed(sky_mesh.index_count, 1, 0, 0, 0)
+                        .bind_index_buffer(capsule_mesh.index_buffer.clone())
+                        .unwrap()
+                        .draw_indexed(capsule_mesh.index_count, 1, 0, 0, 0)
                         .unwrap();
                 }
             }
 
-            // Render chunk terrain meshes
+            // Render glider canopy above the player, unfolding with its openness
+            if let (Some(basic_pipeline), Some(glider_mesh), Some(player), Some(camera), Some(light_set)) =
+                (&render_ctx.basic_pipeline, &render_ctx.glider_mesh, &self.player, &self.camera, &light_set)
             {
-                let terrain_pipeline = if self.debug_wireframe {
-                    render_ctx.wireframe_pipeline.as_ref().or(render_ctx.basic_pipeline.as_ref())
-                } else {
-                    render_ctx.basic_pipeline.as_ref()
-                };
+                let openness = player.glider().openness();
+                if openness > 0.0 {
+                    let model = Mat4::from_translation(player.position() + Vec3::Y * (2.0 + 0.4 * openness))
+                        * Mat4::from_rotation_y(-camera.yaw)
+                        * Mat4::from_scale(Vec3::new(3.2 * openness, 1.0, 1.2));
 
-                if let (Some(pipeline), Some(chunk_manager)) = (terrain_pipeline, &self.chunk_manager) {
-                    let chunk_size = chunk_manager.config.chunk_size;
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::new(0.85, 0.3, 0.25),
+                        ambient_intensity,
+                    );
 
-                    for chunk in chunk_manager.loaded_chunks() {
-                        if let Some(mesh) = render_ctx.chunk_meshes.get(&chunk.coord) {
-                            let origin = chunk.coord.world_center(chunk_size);
-                            let model = Mat4::from_translation(origin);
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, glider_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(glider_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(glider_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
 
-                            let push = BasicPushConstants::new(
-                                model,
-                                view_matrix,
-                                projection_matrix,
-                                sun_direction,
-                                sun_intensity,
-                                Vec3::new(1.0, 0.95, 0.85),
-                                ambient_intensity,
-                            );
+            // Render grapple posts, and the rope from the player's hand to its anchor
+            if let (Some(basic_pipeline), Some(wireframe_pipeline), Some(rope_mesh), Some(light_set)) = (
+                &render_ctx.basic_pipeline,
+                &render_ctx.wireframe_pipeline,
+                &render_ctx.rope_mesh,
+                &light_set,
+            ) {
+                let mut draws: Vec<(&Arc<GraphicsPipeline>, Mat4, Vec3, f32)> = self
+                    .grapple_anchors
+                    .iter()
+                    .map(|&top| {
+                        let base = top - Vec3::Y * GRAPPLE_POST_HEIGHT;
+                        let model = Mat4::from_translation(base)
+                            * Mat4::from_scale(Vec3::new(0.7, GRAPPLE_POST_HEIGHT, 0.7));
+                        (basic_pipeline, model, Vec3::new(0.45, 0.35, 0.25), ambient_intensity)
+                    })
+                    .collect();
+                if let Some((player, anchor)) = self.player.as_ref().and_then(|p| p.grapple_anchor().map(|a| (p, a))) {
+                    let hand = player.character.center_position() + Vec3::Y * 0.4;
+                    let span = anchor - hand;
+                    let (side, forward) = span.normalize_or_zero().any_orthonormal_pair();
+                    let model = Mat4::from_cols(
+                        (side * 0.05).extend(0.0),
+                        span.extend(0.0),
+                        (forward * 0.05).extend(0.0),
+                        hand.extend(1.0),
+                    );
+                    draws.push((wireframe_pipeline, model, Vec3::new(0.55, 0.4, 0.2), 1.0));
+                }
 
-                            unsafe {
-                                builder
-                                    .bind_pipeline_graphics(pipeline.clone())
-                                    .unwrap()
-                                    .push_constants(pipeline.layout().clone(), 0, push)
-                                    .unwrap()
-                                    .bind_vertex_buffers(0, mesh.vertex_buffer.clone())
-                                    .unwrap()
-                                    .bind_index_buffer(mesh.index_buffer.clone())
-                                    .unwrap()
-                                    .draw_indexed(mesh.index_count, 1, 0, 0, 0)
-                                    .unwrap();
-                            }
+                for (pipeline, model, color, ambient) in draws {
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        color,
+                        ambient,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, rope_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(rope_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(rope_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
+
+            // Render the dungeon interior's (or survival arena's) floors, walls and gate
+            if let (Some(basic_pipeline), Some(box_mesh), Some(light_set)) =
+                (&render_ctx.basic_pipeline, &render_ctx.box_mesh, &light_set)
+            {
+                let dungeon_blocks = self.dungeon.iter().flat_map(|d| d.blocks());
+                let arena_blocks = self.survival_arena.iter().flat_map(|a| a.blocks());
+                let gate = self.dungeon.as_ref().zip(self.physics_world.as_ref()).and_then(|(d, p)| d.gate_block(p));
+                for block in dungeon_blocks.chain(arena_blocks).chain(gate.iter()) {
+                    let model = Mat4::from_translation(block.center) * Mat4::from_scale(block.half_extents * 2.0);
+                    let color = match block.kind {
+                        infinite_world::dungeon::BlockKind::Floor => Vec3::new(0.3, 0.28, 0.26),
+                        infinite_world::dungeon::BlockKind::Wall => Vec3::new(0.45, 0.42, 0.38),
+                        infinite_world::dungeon::BlockKind::Gate => Vec3::new(0.22, 0.22, 0.25),
+                    };
+
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        color,
+                        ambient_intensity,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
+
+            // Render door leaves, swung as far as their hinges have turned
+            if let (Some(basic_pipeline), Some(box_mesh), Some(light_set), Some(physics)) =
+                (&render_ctx.basic_pipeline, &render_ctx.box_mesh, &light_set, &self.physics_world)
+            {
+                for (center, rotation, half_extents) in self.interaction_system.door_leaves(physics) {
+                    let model = Mat4::from_scale_rotation_translation(half_extents * 2.0, rotation, center);
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::new(0.42, 0.3, 0.18),
+                        ambient_intensity,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
+
+            // Render bridges over the loaded chunks
+            if let (Some(basic_pipeline), Some(box_mesh), Some(light_set), None) =
+                (&render_ctx.basic_pipeline, &render_ctx.box_mesh, &light_set, &self.dungeon)
+            {
+                for instance in self.bridges.values() {
+                    for block in instance.blocks() {
+                        let model = Mat4::from_scale_rotation_translation(block.half_extents * 2.0, block.rotation, block.center);
+                        let color = Vec3::from(instance.bridge.style.color(block.part));
+
+                        let push = BasicPushConstants::new(
+                            model,
+                            view_matrix,
+                            projection_matrix,
+                            sun_direction,
+                            sun_intensity,
+                            color,
+                            ambient_intensity,
+                        );
+
+                        unsafe {
+                            builder
+                                .bind_pipeline_graphics(basic_pipeline.clone())
+                                .unwrap()
+                                .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                                .unwrap()
+                                .push_constants(basic_pipeline.layout().clone(), 0, push)
+                                .unwrap()
+                                .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                                .unwrap()
+                                .bind_index_buffer(box_mesh.index_buffer.clone())
+                                .unwrap()
+                                .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                                .unwrap();
+                        }
+                    }
+                }
+            }
+
+            // Render resource nodes
+            if let (Some(basic_pipeline), Some(box_mesh), Some(light_set), None) =
+                (&render_ctx.basic_pipeline, &render_ctx.box_mesh, &light_set, &self.dungeon)
+            {
+                for node in self.interaction_system.resource_nodes() {
+                    let size = node.kind.size();
+                    let model = Mat4::from_translation(node.position + Vec3::Y * size.y * 0.5) * Mat4::from_scale(size);
+                    if self.occlusion.cull_model(model, Vec3::splat(0.5)) {
+                        continue;
+                    }
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::from(node.kind.color()),
+                        ambient_intensity,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+
+                // Alchemy tables
+                let table_size = infinite_game::InteractableKind::BrewingStation.outline_size();
+                for position in self.interaction_system.brewing_stations() {
+                    let model = Mat4::from_translation(position) * Mat4::from_scale(table_size);
+                    if self.occlusion.cull_model(model, Vec3::splat(0.5)) {
+                        continue;
+                    }
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::new(0.35, 0.22, 0.12),
+                        ambient_intensity,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+
+                // Shrines
+                for shrine in self.interaction_system.shrines() {
+                    let size = shrine.size();
+                    let model = Mat4::from_translation(shrine.position + Vec3::Y * size.y * 0.5) * Mat4::from_scale(size);
+                    if self.occlusion.cull_model(model, Vec3::splat(0.5)) {
+                        continue;
+                    }
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::from(shrine.color()),
+                        ambient_intensity,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
+
+            // Render benches, chairs and lean walls
+            if let (Some(basic_pipeline), Some(box_mesh), Some(light_set), Some(npc_manager), None) =
+                (&render_ctx.basic_pipeline, &render_ctx.box_mesh, &light_set, &self.npc_manager, &self.dungeon)
+            {
+                for seat in npc_manager.seats.iter() {
+                    for block in seat.blocks() {
+                        let model = Mat4::from_scale_rotation_translation(block.half_extents * 2.0, block.rotation, block.center);
+                        if self.occlusion.cull_model(model, Vec3::splat(0.5)) {
+                            continue;
+                        }
+                        let push = BasicPushConstants::new(
+                            model,
+                            view_matrix,
+                            projection_matrix,
+                            sun_direction,
+                            sun_intensity,
+                            Vec3::from(seat.kind.color()),
+                            ambient_intensity,
+                        );
+
+                        unsafe {
+                            builder
+                                .bind_pipeline_graphics(basic_pipeline.clone())
+                                .unwrap()
+                                .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                                .unwrap()
+                                .push_constants(basic_pipeline.layout().clone(), 0, push)
+                                .unwrap()
+                                .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                                .unwrap()
+                                .bind_index_buffer(box_mesh.index_buffer.clone())
+                                .unwrap()
+                                .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                                .unwrap();
                         }
                     }
                 }
-            }
+            }
+
+            // Render loose-dirt decals over treasure map dig spots
+            if let (Some(basic_pipeline), Some(box_mesh), Some(light_set)) =
+                (&render_ctx.basic_pipeline, &render_ctx.box_mesh, &light_set)
+            {
+                for spot in self.interaction_system.dig_spots() {
+                    let model = Mat4::from_translation(spot - Vec3::Y * 0.3)
+                        * Mat4::from_rotation_y(std::f32::consts::FRAC_PI_4)
+                        * Mat4::from_scale(Vec3::new(1.6, 0.12, 1.6));
+                    if self.occlusion.cull_model(model, Vec3::splat(0.5)) {
+                        continue;
+                    }
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        Vec3::new(0.36, 0.25, 0.14),
+                        ambient_intensity,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
 
-            // Also render legacy single terrain if present (fallback)
-            if let Some(terrain_mesh) = &render_ctx.terrain_mesh {
-                if let Some(pipeline) = render_ctx.basic_pipeline.as_ref() {
+                // Spotted traps (disarmed ones dimmed)
+                for trap in self.traps.iter().filter(|t| t.detected) {
+                    let radius = trap.kind.radius();
+                    let model = Mat4::from_translation(trap.position + Vec3::Y * 0.05)
+                        * Mat4::from_scale(Vec3::new(radius * 2.0, 0.1, radius * 2.0));
+                    if self.occlusion.cull_model(model, Vec3::splat(0.5)) {
+                        continue;
+                    }
+                    let dim = if trap.armed { 1.0 } else { 0.4 };
                     let push = BasicPushConstants::new(
-                        Mat4::IDENTITY,
+                        model,
                         view_matrix,
                         projection_matrix,
                         sun_direction,
                         sun_intensity,
-                        Vec3::new(1.0, 0.95, 0.85),
+                        Vec3::from(trap.kind.color()) * dim,
                         ambient_intensity,
                     );
+
                     unsafe {
                         builder
-                            .bind_pipeline_graphics(pipeline.clone())
+                            .bind_pipeline_graphics(basic_pipeline.clone())
                             .unwrap()
-                            .push_constants(pipeline.layout().clone(), 0, push)
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
                             .unwrap()
-                            .bind_vertex_buffers(0, terrain_mesh.vertex_buffer.clone())
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
                             .unwrap()
-                            .bind_index_buffer(terrain_mesh.index_buffer.clone())
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
                             .unwrap()
-                            .draw_indexed(terrain_mesh.index_count, 1, 0, 0, 0)
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
                             .unwrap();
                     }
                 }
-            }
-
-            // Render player capsule (debug visualization)
-            if let (Some(basic_pipeline), Some(capsule_mesh), Some(player)) =
-                (&render_ctx.basic_pipeline, &render_ctx.capsule_mesh, &self.player)
-            {
-                let player_pos = player.character.center_position();
-                let model = Mat4::from_translation(player_pos);
 
-                let push = BasicPushConstants::new(
-                    model,
-                    view_matrix,
-                    projection_matrix,
-                    sun_direction,
-                    sun_intensity,
-                    Vec3::new(1.0, 0.95, 0.85),
-                    ambient_intensity,
-                );
+                // Throwables in flight, lingering smoke, and the arc of the throw being aimed
+                let mut throwable_boxes: Vec<(Mat4, Vec3)> = Vec::new();
+                for projectile in self.throwables.projectiles() {
+                    throwable_boxes.push((
+                        Mat4::from_translation(projectile.position) * Mat4::from_scale(Vec3::splat(0.3)),
+                        Vec3::from(projectile.kind.color()),
+                    ));
+                }
+                for cloud in self.throwables.smoke_clouds() {
+                    // Shrinks away over its last two seconds
+                    let size = cloud.radius * 1.6 * (cloud.remaining / 2.0).min(1.0);
+                    throwable_boxes.push((
+                        Mat4::from_translation(cloud.position + Vec3::Y * size * 0.35)
+                            * Mat4::from_scale(Vec3::new(size, size * 0.7, size)),
+                        Vec3::splat(0.55),
+                    ));
+                }
+                if let Some(preview) = &self.throw_preview {
+                    for point in preview.points.iter().step_by(4) {
+                        throwable_boxes.push((
+                            Mat4::from_translation(*point) * Mat4::from_scale(Vec3::splat(0.08)),
+                            Vec3::new(1.0, 0.95, 0.7),
+                        ));
+                    }
+                    let aimed = self.throw_aim
+                        .and_then(|(slot, _)| self.hotbar.get(slot))
+                        .and_then(infinite_game::ThrowableKind::from_item_id);
+                    if let (Some(impact), Some(kind)) = (preview.impact, aimed) {
+                        let radius = kind.radius();
+                        throwable_boxes.push((
+                            Mat4::from_translation(impact + Vec3::Y * 0.03)
+                                * Mat4::from_scale(Vec3::new(radius * 2.0, 0.04, radius * 2.0)),
+                            Vec3::from(kind.color()).lerp(Vec3::ONE, 0.4),
+                        ));
+                    }
+                }
+                for (model, color) in throwable_boxes {
+                    let push = BasicPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        sun_direction,
+                        sun_intensity,
+                        color,
+                        ambient_intensity,
+                    );
 
-                unsafe {
-                    builder
-                        .bind_pipeline_graphics(basic_pipeline.clone())
-                        .unwrap()
-                        .push_constants(basic_pipeline.layout().clone(), 0, push)
-                        .unwrap()
-                        .bind_vertex_buffers(0, capsule_mesh.vertex_buffer.clone())
-                        .unwrap()
-                        .bind_index_buffer(capsule_mesh.index_buffer.clone())
-                        .unwrap()
-                        .draw_indexed(capsule_mesh.index_count, 1, 0, 0, 0)
-                        .unwrap();
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(basic_pipeline.clone())
+                            .unwrap()
+                            .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                            .unwrap()
+                            .push_constants(basic_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(box_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
                 }
             }
 
+            // The focused interactable pulses with a rim light (NPCs) or a
+            // wireframe outline box (everything else)
+            let focus_pulse = 0.75 + 0.25 * (self.game_time.total_time as f32 * 4.0).sin();
+            let focused_npc = match self.interaction_system.focused().map(|f| &f.kind) {
+                Some(infinite_game::InteractableKind::Npc { npc_id }) => Some(*npc_id),
+                _ => None,
+            };
+
             // Render NPC capsules
-            if let (Some(basic_pipeline), Some(npc_mesh)) =
-                (&render_ctx.basic_pipeline, &render_ctx.npc_capsule_mesh)
+            if let (Some(basic_pipeline), Some(npc_mesh), Some(light_set)) =
+                (&render_ctx.basic_pipeline, &render_ctx.npc_capsule_mesh, &light_set)
             {
                 if let Some(npc_manager) = &self.npc_manager {
-                    for npc in npc_manager.npcs_iter() {
-                        let model = Mat4::from_translation(npc.position);
-                        let color = npc.data.color;
+                    // Distant (ambient) NPCs are drawn as one widened crowd marker per chunk
+                    let mut draws: Vec<(Option<NpcId>, Mat4, [f32; 4])> = npc_manager
+                        .npcs_iter()
+                        .filter(|npc| npc_manager.lod(npc.id) != infinite_game::NpcLod::Ambient)
+                        .map(|npc| (Some(npc.id), Mat4::from_translation(npc_manager.render_transform(npc).position), npc.data.color))
+                        .collect();
+                    for crowd in npc_manager.ambient_crowds() {
+                        let spread = 1.0 + 0.25 * (crowd.count - 1) as f32;
+                        let model = Mat4::from_translation(crowd.center) * Mat4::from_scale(Vec3::new(spread, 1.0, spread));
+                        draws.push((None, model, [0.55, 0.55, 0.5, 1.0]));
+                    }
 
-                        let push = BasicPushConstants::new(
+                    for (npc_id, model, color) in draws {
+                        if self.occlusion.cull_model(model, NPC_HALF_EXTENT) {
+                            continue;
+                        }
+                        let mut push = BasicPushConstants::new(
                             model,
                             view_matrix,
                             projection_matrix,
@@ -3443,11 +9798,16 @@ impl InfiniteApp {
                             Vec3::new(color[0], color[1], color[2]),
                             ambient_intensity,
                         );
+                        if npc_id.is_some() && focused_npc == npc_id {
+                            push = push.with_highlight(FOCUS_HIGHLIGHT_COLOR, focus_pulse * 1.5);
+                        }
 
                         unsafe {
                             builder
                                 .bind_pipeline_graphics(basic_pipeline.clone())
                                 .unwrap()
+                                .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                                .unwrap()
                                 .push_constants(basic_pipeline.layout().clone(), 0, push)
                                 .unwrap()
                                 .bind_vertex_buffers(0, npc_mesh.vertex_buffer.clone())
@@ -3461,9 +9821,108 @@ impl InfiniteApp {
                 }
             }
 
+            // Outline the focused (non-NPC) interactable
+            if let (Some(wireframe_pipeline), Some(box_mesh), Some(light_set), Some(focused)) = (
+                &render_ctx.wireframe_pipeline,
+                &render_ctx.box_mesh,
+                &light_set,
+                self.interaction_system.focused().filter(|_| focused_npc.is_none()),
+            ) {
+                let model = Mat4::from_translation(focused.position) * Mat4::from_scale(focused.kind.outline_size());
+                let push = BasicPushConstants::new(
+                    model,
+                    view_matrix,
+                    projection_matrix,
+                    sun_direction,
+                    0.0,
+                    FOCUS_HIGHLIGHT_COLOR * focus_pulse,
+                    1.0,
+                );
+
+                unsafe {
+                    builder
+                        .bind_pipeline_graphics(wireframe_pipeline.clone())
+                        .unwrap()
+                        .bind_descriptor_sets(PipelineBindPoint::Graphics, wireframe_pipeline.layout().clone(), 0, light_set.clone())
+                        .unwrap()
+                        .push_constants(wireframe_pipeline.layout().clone(), 0, push)
+                        .unwrap()
+                        .bind_vertex_buffers(0, box_mesh.vertex_buffer.clone())
+                        .unwrap()
+                        .bind_index_buffer(box_mesh.index_buffer.clone())
+                        .unwrap()
+                        .draw_indexed(box_mesh.index_count, 1, 0, 0, 0)
+                        .unwrap();
+                }
+            }
+
+            // Render time portals (emissive, alpha blended, drawn after opaque geometry)
+            if let (Some(portal_pipeline), Some(portal_mesh), Some(camera)) =
+                (&render_ctx.portal_pipeline, &render_ctx.portal_mesh, &self.camera)
+            {
+                let camera_pos = camera.position();
+                let time = self.game_time.total_time as f32;
+                let present_year = self.timeline.present_year;
+                let previews = self.settings.video.portal_previews;
+
+                let time_portals = self.interaction_system.time_portals().map(|(position, target_year)| {
+                    let palette = TimeTerrainConfig::for_year(target_year, present_year).preview_palette();
+                    (
+                        position,
+                        portal_tint(target_year, present_year),
+                        [
+                            Vec3::from_array(palette.sky_zenith),
+                            Vec3::from_array(palette.sky_horizon),
+                            Vec3::from_array(palette.ground),
+                        ],
+                        if previews { 1.0 } else { 0.0 },
+                    )
+                });
+                // Dungeon portals swirl without a preview of the other side
+                let dungeon_portals = self.interaction_system.dungeon_portals().map(|(position, exit)| {
+                    let tint = if exit { Vec3::new(0.5, 1.0, 0.6) } else { Vec3::new(0.55, 0.25, 0.85) };
+                    (position, tint, [Vec3::ZERO; 3], 0.0)
+                });
+                let time_rifts = self.interaction_system.time_rifts().map(|position| (position, RIFT_TINT, [Vec3::ZERO; 3], 0.0));
+
+                for (position, tint, palette, preview) in time_portals.chain(dungeon_portals).chain(time_rifts) {
+                    // Billboard around Y so the disc always faces the camera
+                    let center = position + Vec3::new(0.0, 0.5, 0.0);
+                    let to_camera = camera_pos - center;
+                    let yaw = to_camera.x.atan2(to_camera.z);
+                    let model = Mat4::from_translation(center)
+                        * Mat4::from_rotation_y(yaw)
+                        * Mat4::from_scale(Vec3::new(1.2, 1.8, 1.0));
+
+                    let push = PortalPushConstants::new(
+                        model,
+                        view_matrix,
+                        projection_matrix,
+                        tint,
+                        time,
+                        palette,
+                        preview,
+                    );
+
+                    unsafe {
+                        builder
+                            .bind_pipeline_graphics(portal_pipeline.clone())
+                            .unwrap()
+                            .push_constants(portal_pipeline.layout().clone(), 0, push)
+                            .unwrap()
+                            .bind_vertex_buffers(0, portal_mesh.vertex_buffer.clone())
+                            .unwrap()
+                            .bind_index_buffer(portal_mesh.index_buffer.clone())
+                            .unwrap()
+                            .draw_indexed(portal_mesh.index_count, 1, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+            }
+
             // Debug: render collider wireframes
             if self.debug_colliders {
-                if let Some(wireframe_pipeline) = &render_ctx.wireframe_pipeline {
+                if let (Some(wireframe_pipeline), Some(light_set)) = (&render_ctx.wireframe_pipeline, &light_set) {
                     // Render player collider capsule as wireframe
                     if let (Some(capsule_mesh), Some(player)) = (&render_ctx.capsule_mesh, &self.player) {
                         let player_pos = player.character.center_position();
@@ -3483,6 +9942,8 @@ impl InfiniteApp {
                             builder
                                 .bind_pipeline_graphics(wireframe_pipeline.clone())
                                 .unwrap()
+                                .bind_descriptor_sets(PipelineBindPoint::Graphics, wireframe_pipeline.layout().clone(), 0, light_set.clone())
+                                .unwrap()
                                 .push_constants(wireframe_pipeline.layout().clone(), 0, push)
                                 .unwrap()
                                 .bind_vertex_buffers(0, capsule_mesh.vertex_buffer.clone())
@@ -3509,7 +9970,7 @@ impl InfiniteApp {
                                 [0.0, 1.0, 0.0, 0.3],
                             );
                             if let Ok(buffers) = create_mesh_buffers(
-                                render_ctx.memory_allocator.clone(),
+                                &mut render_ctx.mesh_uploader,
                                 &box_mesh.vertices,
                                 &box_mesh.indices,
                             ) {
@@ -3521,33 +9982,20 @@ impl InfiniteApp {
             }
         }
 
+        // The in-game player shares the preview's mesh, rebuilt from the
+        // player's own appearance once the creator is left
+        if self.player_mesh_dirty && !matches!(self.app_state, ApplicationState::CharacterCreation) {
+            upload_character_mesh(render_ctx, &self.player_appearance);
+            self.player_mesh_dirty = false;
+        }
+
         // Character creator 3D preview
         if matches!(self.app_state, ApplicationState::CharacterCreation) {
             // Regenerate capsule mesh if appearance changed
             if self.character_creator.appearance_dirty {
-                let appearance = &self.character_creator.appearance;
-                let mesh_data = infinite_render::Mesh::character_capsule(
-                    appearance.body.height,
-                    appearance.body.build,
-                    appearance.body.shoulder_width,
-                    appearance.body.hip_width,
-                    appearance.skin.tone,
-                    appearance.skin.undertone,
-                );
-
-                match create_mesh_buffers(
-                    render_ctx.memory_allocator.clone(),
-                    &mesh_data.vertices,
-                    &mesh_data.indices,
-                ) {
-                    Ok(buffers) => {
-                        render_ctx.capsule_mesh = Some(buffers);
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to rebuild character mesh: {}", e);
-                    }
-                }
+                upload_character_mesh(render_ctx, &self.character_creator.appearance);
                 self.character_creator.appearance_dirty = false;
+                self.player_mesh_dirty = true;
             }
 
             // Check if we have a preview rect from egui
@@ -3599,8 +10047,8 @@ impl InfiniteApp {
                     preview_proj.y_axis.y *= -1.0;
 
                     // Render capsule with fixed lighting
-                    if let (Some(basic_pipeline), Some(capsule_mesh)) =
-                        (&render_ctx.basic_pipeline, &render_ctx.capsule_mesh)
+                    if let (Some(basic_pipeline), Some(capsule_mesh), Some(light_set)) =
+                        (&render_ctx.basic_pipeline, &render_ctx.capsule_mesh, &light_set)
                     {
                         // Log first draw call
                         static LOGGED_DRAW: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
@@ -3622,6 +10070,8 @@ impl InfiniteApp {
                             builder
                                 .bind_pipeline_graphics(basic_pipeline.clone())
                                 .unwrap()
+                                .bind_descriptor_sets(PipelineBindPoint::Graphics, basic_pipeline.layout().clone(), 0, light_set.clone())
+                                .unwrap()
                                 .push_constants(basic_pipeline.layout().clone(), 0, push)
                                 .unwrap()
                                 .bind_vertex_buffers(0, capsule_mesh.vertex_buffer.clone())
@@ -3643,10 +10093,20 @@ impl InfiniteApp {
             }
         }
 
-        // === SUBPASS 1: UI Overlay ===
+        builder.end_render_pass(Default::default()).unwrap();
+
+        // Scale the scene up onto the swapchain image
         builder
-            .next_subpass(
-                SubpassEndInfo::default(),
+            .blit_image(render_ctx.scene_target.blit_to(render_ctx.images[image_index as usize].clone(), render_scale))
+            .unwrap();
+
+        // === UI PASS: UI Overlay at native resolution ===
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(render_ctx.framebuffers[image_index as usize].clone())
+                },
                 SubpassBeginInfo {
                     contents: SubpassContents::SecondaryCommandBuffers,
                     ..Default::default()
@@ -3660,241 +10120,98 @@ impl InfiniteApp {
 
         builder.end_render_pass(Default::default()).unwrap();
 
-        let command_buffer = builder.build().unwrap();
+        if let Some(timer) = render_ctx.gpu_timer.as_mut() {
+            if let Err(e) = timer.end(&mut builder) {
+                tracing::warn!("Failed to stop the GPU frame timer: {}", e);
+            }
+        }
+
+        let command_buffer = match builder.build() {
+            Ok(command_buffer) => command_buffer,
+            Err(e) => {
+                self.on_render_error(vulkan_error(RENDER_SUBMIT_FAILED, "Failed to build the frame's commands", e));
+                return;
+            }
+        };
 
-        // Submit
-        let future = render_ctx
-            .previous_frame_end
-            .take()
-            .unwrap_or_else(|| sync::now(render_ctx.device.clone()).boxed())
+        // Submit, waiting on the GPU for this frame's mesh uploads
+        let executed = take_frame_dependencies(render_ctx)
             .join(acquire_future)
-            .then_execute(render_ctx.queue.clone(), command_buffer)
-            .unwrap()
+            .then_execute(render_ctx.queue.clone(), command_buffer);
+        let executed = match executed {
+            Ok(executed) => executed,
+            Err(e) => {
+                render_ctx.previous_frame_end = Some(sync::now(render_ctx.device.clone()).boxed());
+                self.on_render_error(render_error(RENDER_SUBMIT_FAILED, "Failed to submit the frame", e));
+                return;
+            }
+        };
+        let future = executed
             .then_swapchain_present(
                 render_ctx.queue.clone(),
                 SwapchainPresentInfo::swapchain_image_index(
                     render_ctx.swapchain.clone(),
                     image_index,
-                ),
-            )
-            .then_signal_fence_and_flush();
-
-        match future.map_err(Validated::unwrap) {
-            Ok(future) => {
-                render_ctx.previous_frame_end = Some(future.boxed());
-            }
-            Err(VulkanError::OutOfDate) => {
-                render_ctx.recreate_swapchain = true;
-                render_ctx.previous_frame_end = Some(sync::now(render_ctx.device.clone()).boxed());
-            }
-            Err(e) => {
-                tracing::error!("Failed to flush future: {e}");
-                render_ctx.previous_frame_end = Some(sync::now(render_ctx.device.clone()).boxed());
-            }
-        }
-    }
-}
-
-impl ApplicationHandler for InfiniteApp {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        info!("Application resumed, creating window...");
-
-        let window_attrs = WindowAttributes::default()
-            .with_title("Infinite")
-            .with_inner_size(winit::dpi::LogicalSize::new(
-                self.settings.video.width,
-                self.settings.video.height,
-            ));
-
-        let window = Arc::new(
-            event_loop
-                .create_window(window_attrs)
-                .expect("Failed to create window"),
-        );
-
-        let surface =
-            Surface::from_window(self.instance.clone(), window.clone()).expect("Failed to create surface");
-
-        // Select physical device
-        let device_extensions = DeviceExtensions {
-            khr_swapchain: true,
-            ..DeviceExtensions::empty()
-        };
-
-        let (physical_device, queue_family_index) = self
-            .instance
-            .enumerate_physical_devices()
-            .expect("Failed to enumerate physical devices")
-            .filter(|p| p.supported_extensions().contains(&device_extensions))
-            .filter_map(|p| {
-                p.queue_family_properties()
-                    .iter()
-                    .enumerate()
-                    .position(|(i, q)| {
-                        q.queue_flags.contains(QueueFlags::GRAPHICS)
-                            && p.surface_support(i as u32, &surface).unwrap_or(false)
-                    })
-                    .map(|i| (p, i as u32))
-            })
-            .min_by_key(|(p, _)| match p.properties().device_type {
-                PhysicalDeviceType::DiscreteGpu => 0,
-                PhysicalDeviceType::IntegratedGpu => 1,
-                PhysicalDeviceType::VirtualGpu => 2,
-                PhysicalDeviceType::Cpu => 3,
-                _ => 4,
-            })
-            .expect("No suitable GPU found");
-
-        info!(
-            "Using GPU: {} ({:?})",
-            physical_device.properties().device_name,
-            physical_device.properties().device_type
-        );
-
-        // Check for ray tracing support
-        let rt_supported = physical_device
-            .supported_extensions()
-            .khr_ray_tracing_pipeline;
-        if rt_supported {
-            info!("Hardware ray tracing supported");
-        } else {
-            info!("Hardware ray tracing NOT supported - will use compute fallback");
-        }
-
-        // Create logical device
-        let (device, mut queues) = Device::new(
-            physical_device,
-            DeviceCreateInfo {
-                queue_create_infos: vec![QueueCreateInfo {
-                    queue_family_index,
-                    ..Default::default()
-                }],
-                enabled_extensions: device_extensions,
-                enabled_features: DeviceFeatures {
-                    fill_mode_non_solid: true,
-                    wide_lines: true,
-                    ..DeviceFeatures::empty()
-                },
-                ..Default::default()
-            },
-        )
-        .expect("Failed to create logical device");
-
-        let queue = queues.next().unwrap();
-
-        // Create allocators first (needed for depth buffer creation)
-        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
-        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
-            device.clone(),
-            Default::default(),
-        ));
-        let descriptor_set_allocator = Arc::new(StandardDescriptorSetAllocator::new(
-            device.clone(),
-            Default::default(),
-        ));
-
-        // Create swapchain and framebuffers (with depth buffer)
-        let (swapchain, images, render_pass, framebuffers, depth_buffer) =
-            Self::create_swapchain_and_framebuffers(
-                device.clone(),
-                surface.clone(),
-                window.clone(),
-                memory_allocator.clone(),
+                ),
             )
-            .expect("Failed to create swapchain");
+            .then_signal_fence_and_flush();
 
-        // Create 3D pipelines
-        let basic_pipeline = create_basic_pipeline(device.clone(), render_pass.clone());
-        if basic_pipeline.is_none() {
-            tracing::error!("Failed to create basic 3D pipeline!");
-        } else {
-            info!("Basic 3D pipeline created successfully");
+        match future {
+            Ok(future) => {
+                render_ctx.previous_frame_end = Some(future.boxed());
+                self.failed_frames = 0;
+            }
+            Err(Validated::Error(VulkanError::OutOfDate)) => {
+                render_ctx.recreate_swapchain = true;
+                render_ctx.previous_frame_end = Some(sync::now(render_ctx.device.clone()).boxed());
+            }
+            Err(e) => {
+                render_ctx.previous_frame_end = Some(sync::now(render_ctx.device.clone()).boxed());
+                self.on_render_error(vulkan_error(RENDER_SUBMIT_FAILED, "Failed to present the frame", e));
+            }
         }
+    }
+}
 
-        let sky_pipeline = create_sky_pipeline(device.clone(), render_pass.clone());
-        if sky_pipeline.is_none() {
-            tracing::error!("Failed to create sky pipeline!");
-        } else {
-            info!("Sky pipeline created successfully");
-        }
+impl ApplicationHandler for InfiniteApp {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        info!("Application resumed, creating window...");
 
-        let wireframe_pipeline = create_wireframe_pipeline(device.clone(), render_pass.clone());
-        if wireframe_pipeline.is_some() {
-            info!("Wireframe debug pipeline created successfully");
-        }
+        let window_attrs = WindowAttributes::default()
+            .with_title("Infinite")
+            .with_inner_size(winit::dpi::LogicalSize::new(
+                self.settings.video.width,
+                self.settings.video.height,
+            ));
 
-        // Create capsule mesh for player/preview
-        let capsule_mesh_data = Mesh::capsule(1.8, 0.4, 16, 16, [0.6, 0.7, 0.8, 1.0]);
-        let capsule_mesh = match create_mesh_buffers(
-            memory_allocator.clone(),
-            &capsule_mesh_data.vertices,
-            &capsule_mesh_data.indices,
-        ) {
-            Ok(mesh) => {
-                info!("Capsule mesh created: {} vertices, {} indices",
-                      capsule_mesh_data.vertices.len(), capsule_mesh_data.indices.len());
-                Some(mesh)
-            }
+        let window = match event_loop.create_window(window_attrs) {
+            Ok(window) => Arc::new(window),
             Err(e) => {
-                tracing::error!("Failed to create capsule mesh: {}", e);
-                None
+                let err = InfiniteError::new(RENDER_INIT_FAILED, Severity::Fatal, format!("Failed to create window: {}", e));
+                self.error_dialog.report(&err);
+                event_loop.exit();
+                return;
             }
         };
 
-        // Create sky dome mesh
-        let sky_mesh_data = SkyMesh::dome(32, 16);
-        let sky_mesh = match create_sky_mesh_buffers(
-            memory_allocator.clone(),
-            &sky_mesh_data.vertices,
-            &sky_mesh_data.indices,
-        ) {
-            Ok(mesh) => {
-                info!("Sky mesh created: {} vertices, {} indices",
-                      sky_mesh_data.vertices.len(), sky_mesh_data.indices.len());
-                Some(mesh)
-            }
+        let surface = match Surface::from_window(self.instance.clone(), window.clone()) {
+            Ok(surface) => surface,
             Err(e) => {
-                tracing::error!("Failed to create sky mesh: {}", e);
-                None
+                let err = InfiniteError::new(RENDER_INIT_FAILED, Severity::Fatal, format!("Failed to create surface: {}", e));
+                self.error_dialog.report(&err);
+                event_loop.exit();
+                return;
             }
         };
 
-        // Create egui renderer (subpass 1 - UI overlay)
-        let gui = Gui::new_with_subpass(
-            event_loop,
-            surface.clone(),
-            queue.clone(),
-            Subpass::from(render_pass.clone(), 1).unwrap(),
-            swapchain.image_format(),
-            GuiConfig::default(),
-        );
-
         self.window = Some(window);
         self.surface = Some(surface);
-        self.render_ctx = Some(RenderContext {
-            device,
-            queue,
-            swapchain,
-            images,
-            render_pass,
-            framebuffers,
-            memory_allocator,
-            command_buffer_allocator,
-            _descriptor_set_allocator: descriptor_set_allocator,
-            recreate_swapchain: false,
-            previous_frame_end: None,
-            depth_buffer,
-            basic_pipeline,
-            sky_pipeline,
-            wireframe_pipeline,
-            capsule_mesh,
-            terrain_mesh: None,
-            chunk_meshes: HashMap::new(),
-            npc_capsule_mesh: None,
-            sky_mesh,
-            debug_capsule_mesh: None,
-        });
-        self.gui = Some(gui);
+        if let Err(err) = self.create_render_context(event_loop) {
+            // Without a renderer the error can't be shown in game
+            self.error_dialog.report(&err.context("starting the renderer").with_severity(Severity::Fatal));
+            event_loop.exit();
+            return;
+        }
         self.last_frame = Instant::now();
 
         info!("Window and Vulkan context created successfully with 3D rendering");
@@ -3916,6 +10233,7 @@ impl ApplicationHandler for InfiniteApp {
         match event {
             WindowEvent::CloseRequested => {
                 info!("Window close requested");
+                self.flush_saves();
                 event_loop.exit();
             }
             WindowEvent::Resized(_size) => {
@@ -3923,12 +10241,18 @@ impl ApplicationHandler for InfiniteApp {
                     render_ctx.recreate_swapchain = true;
                 }
             }
+            WindowEvent::Focused(focused) => {
+                if let Some(audio) = &mut self.audio {
+                    audio.set_focused(focused);
+                }
+            }
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
                         physical_key,
                         state,
                         logical_key,
+                        repeat,
                         ..
                     },
                 ..
@@ -3940,15 +10264,52 @@ impl ApplicationHandler for InfiniteApp {
                             if self.show_shop {
                                 self.show_shop = false;
                                 self.update_cursor_capture(true);
+                            } else if self.respec_menu.is_some() {
+                                self.respec_menu = None;
+                                self.update_cursor_capture(true);
+                            } else if self.cooking_menu.is_some() {
+                                self.cooking_menu = None;
+                                self.update_cursor_capture(true);
+                            } else if self.brewing_menu.is_some() {
+                                self.brewing_menu = None;
+                                self.update_cursor_capture(true);
+                            } else if self.shrine_menu.is_some() {
+                                self.shrine_menu = None;
+                                self.update_cursor_capture(true);
+                            } else if self.rift_menu.is_some() {
+                                self.rift_menu = None;
+                                self.update_cursor_capture(true);
+                            } else if self.survival_window_open() && self.survival.as_ref().is_some_and(|run| !run.is_over()) {
+                                if let Some(menu) = &mut self.survival_menu {
+                                    menu.shop_closed = true;
+                                }
+                                self.update_cursor_capture(true);
+                            } else if self.macro_pending.is_some() {
+                                self.macro_pending = None;
+                                self.notification_text = Some("Macro discarded".to_string());
+                                self.notification_timer = 2.0;
+                            } else if self.hud_editor.active {
+                                self.hud_editor.active = false;
+                                if let Err(e) = self.settings.save() {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            } else if self.lockpicking.is_some() {
+                                self.lockpicking = None;
                             } else if self.show_inventory {
                                 self.show_inventory = false;
                                 self.update_cursor_capture(true);
                             } else {
+                                self.pause_menu.page = PausePage::Main;
+                                self.pause_menu.speedrun_notice = None;
                                 self.apply_transition(StateTransition::Push(ApplicationState::Paused));
                             }
                         }
                         ApplicationState::Paused => {
-                            self.apply_transition(StateTransition::Pop);
+                            if self.pause_menu.page != PausePage::Main {
+                                self.pause_menu.page = PausePage::Main;
+                            } else {
+                                self.apply_transition(StateTransition::Pop);
+                            }
                         }
                         ApplicationState::Settings { .. } => {
                             self.apply_transition(StateTransition::Pop);
@@ -3976,7 +10337,13 @@ impl ApplicationHandler for InfiniteApp {
 
                 // Pass to input handler for game controls
                 if matches!(self.app_state, ApplicationState::Playing) {
-                    self.input_handler.handle_keyboard(physical_key, state);
+                    let macro_key = match physical_key {
+                        PhysicalKey::Code(key_code) if state == ElementState::Pressed && !repeat => self.handle_macro_key(key_code),
+                        _ => false,
+                    };
+                    if !macro_key {
+                        self.input_handler.handle_keyboard(physical_key, state);
+                    }
 
                     // Debug keys for weather/time
                     if state == ElementState::Pressed {
@@ -4011,7 +10378,14 @@ impl ApplicationHandler for InfiniteApp {
             }
             WindowEvent::MouseInput { button, state, .. } => {
                 if matches!(self.app_state, ApplicationState::Playing) {
-                    self.input_handler.handle_mouse_button(button, state);
+                    // With the debug overlay up, clicking the world inspects
+                    // instead of attacking
+                    let inspecting = self.debug_visible && !self.cursor_captured && button == MouseButton::Left;
+                    if !inspecting {
+                        self.input_handler.handle_mouse_button(button, state);
+                    } else if state == ElementState::Pressed {
+                        self.inspect_under_cursor();
+                    }
                 }
             }
             WindowEvent::MouseWheel { delta, .. } => {
@@ -4030,6 +10404,8 @@ impl ApplicationHandler for InfiniteApp {
 
                 // Check for exit state
                 if matches!(self.app_state, ApplicationState::Exiting) {
+                    self.record_character_session();
+                    self.flush_saves();
                     event_loop.exit();
                     return;
                 }
@@ -4055,11 +10431,25 @@ impl ApplicationHandler for InfiniteApp {
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if self.rebuild_renderer {
+            self.rebuild_renderer = false;
+            if let Err(err) = self.rebuild_render_context(event_loop) {
+                // Nothing can be drawn any more: keep what we can and leave
+                self.error_dialog.report(&err.context("restarting the renderer").with_severity(Severity::Fatal));
+                self.flush_saves();
+                event_loop.exit();
+                return;
+            }
+        }
         if let Some(window) = &self.window {
             window.request_redraw();
         }
     }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        self.save_pipeline_cache();
+    }
 }
 
 fn main() -> Result<()> {
@@ -4166,6 +10556,7 @@ fn main() -> Result<()> {
 fn create_basic_pipeline(
     device: Arc<Device>,
     render_pass: Arc<RenderPass>,
+    cache: Arc<PipelineCache>,
 ) -> Option<Arc<GraphicsPipeline>> {
     // Compile shaders using vulkano_shaders
     mod basic_vs {
@@ -4207,7 +10598,7 @@ fn create_basic_pipeline(
 
     GraphicsPipeline::new(
         device.clone(),
-        None,
+        Some(cache),
         GraphicsPipelineCreateInfo {
             stages: stages.into_iter().collect(),
             vertex_input_state: Some(vertex_input_state),
@@ -4239,6 +10630,7 @@ fn create_basic_pipeline(
 fn create_wireframe_pipeline(
     device: Arc<Device>,
     render_pass: Arc<RenderPass>,
+    cache: Arc<PipelineCache>,
 ) -> Option<Arc<GraphicsPipeline>> {
     mod wireframe_vs {
         vulkano_shaders::shader! {
@@ -4279,7 +10671,7 @@ fn create_wireframe_pipeline(
 
     GraphicsPipeline::new(
         device.clone(),
-        None,
+        Some(cache),
         GraphicsPipelineCreateInfo {
             stages: stages.into_iter().collect(),
             vertex_input_state: Some(vertex_input_state),
@@ -4309,10 +10701,124 @@ fn create_wireframe_pipeline(
     .ok()
 }
 
+/// Roll a treasure map drop for an enemy defeated near `position`
+fn roll_treasure_map(
+    chunk_manager: Option<&ChunkManager>,
+    seed: u64,
+    reward_multiplier: f32,
+    position: Vec3,
+    present_year: i64,
+) -> Option<infinite_game::Item> {
+    let tier = infinite_game::combat::treasure::roll_map_drop(seed, reward_multiplier)?;
+    let terrain = &chunk_manager?.terrain_config;
+    let map = infinite_game::combat::TreasureMapData::generate(seed, position, tier, terrain, present_year);
+    Some(infinite_game::combat::treasure::create_treasure_map(map))
+}
+
+/// Portal swirl/rim color: amber for the past, cyan for the future, pale
+/// violet for the present
+fn portal_tint(target_year: i64, present_year: i64) -> Vec3 {
+    match target_year.cmp(&present_year) {
+        std::cmp::Ordering::Less => Vec3::new(1.0, 0.7, 0.3),
+        std::cmp::Ordering::Greater => Vec3::new(0.3, 0.8, 1.0),
+        std::cmp::Ordering::Equal => Vec3::new(0.8, 0.7, 1.0),
+    }
+}
+
+/// Ambient wildlife and machinery levels for `era`'s soundscape, as
+/// (wildlife, machinery)
+fn era_ambience(era: infinite_game::Era) -> (f32, f32) {
+    match era {
+        infinite_game::Era::Ancient => (1.2, 0.0),
+        infinite_game::Era::Medieval | infinite_game::Era::Modern => (1.0, 0.0),
+        infinite_game::Era::Future => (0.25, 1.0),
+    }
+}
+
+/// Create the emissive time-portal pipeline (alpha blended, no depth writes)
+fn create_portal_pipeline(
+    device: Arc<Device>,
+    render_pass: Arc<RenderPass>,
+    cache: Arc<PipelineCache>,
+) -> Option<Arc<GraphicsPipeline>> {
+    mod portal_vs {
+        vulkano_shaders::shader! {
+            ty: "vertex",
+            path: "assets/shaders/portal.vert",
+        }
+    }
+
+    mod portal_fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            path: "assets/shaders/portal.frag",
+        }
+    }
+
+    let vs = portal_vs::load(device.clone()).ok()?;
+    let fs = portal_fs::load(device.clone()).ok()?;
+
+    let vs_entry = vs.entry_point("main")?;
+    let fs_entry = fs.entry_point("main")?;
+
+    let vertex_input_state = [Vertex3D::per_vertex()]
+        .definition(&vs_entry)
+        .ok()?;
+
+    let stages = [
+        PipelineShaderStageCreateInfo::new(vs_entry),
+        PipelineShaderStageCreateInfo::new(fs_entry),
+    ];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .ok()?,
+    )
+    .ok()?;
+
+    GraphicsPipeline::new(
+        device.clone(),
+        Some(cache),
+        GraphicsPipelineCreateInfo {
+            stages: stages.into_iter().collect(),
+            vertex_input_state: Some(vertex_input_state),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState::default()),
+            rasterization_state: Some(RasterizationState {
+                cull_mode: CullMode::None, // Visible from both sides
+                front_face: FrontFace::CounterClockwise,
+                ..Default::default()
+            }),
+            multisample_state: Some(MultisampleState::default()),
+            depth_stencil_state: Some(DepthStencilState {
+                depth: Some(DepthState {
+                    write_enable: false, // Transparent: test but don't occlude
+                    compare_op: vulkano::pipeline::graphics::depth_stencil::CompareOp::Less,
+                }),
+                ..Default::default()
+            }),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                1,
+                ColorBlendAttachmentState {
+                    blend: Some(AttachmentBlend::alpha()),
+                    ..Default::default()
+                },
+            )),
+            dynamic_state: [DynamicState::Viewport, DynamicState::Scissor].into_iter().collect(),
+            subpass: Some(Subpass::from(render_pass, 0).unwrap().into()),
+            ..GraphicsPipelineCreateInfo::layout(layout)
+        },
+    )
+    .ok()
+}
+
 /// Create the sky dome rendering pipeline
 fn create_sky_pipeline(
     device: Arc<Device>,
     render_pass: Arc<RenderPass>,
+    cache: Arc<PipelineCache>,
 ) -> Option<Arc<GraphicsPipeline>> {
     mod sky_vs {
         vulkano_shaders::shader! {
@@ -4353,7 +10859,7 @@ fn create_sky_pipeline(
 
     GraphicsPipeline::new(
         device.clone(),
-        None,
+        Some(cache),
         GraphicsPipelineCreateInfo {
             stages: stages.into_iter().collect(),
             vertex_input_state: Some(vertex_input_state),
@@ -4384,29 +10890,23 @@ fn create_sky_pipeline(
     .ok()
 }
 
+/// View-projection the HUD projects world positions with, matching the 3D view
+fn hud_view_proj(camera: &CameraController, screen_size: egui::Vec2) -> Mat4 {
+    let mut projection_matrix = camera.projection_matrix(screen_size.x / screen_size.y, 60.0);
+    projection_matrix.y_axis.y *= -1.0;
+    projection_matrix * camera.view_matrix()
+}
+
 /// Project a world position to screen coordinates
-/// Returns None if the point is behind the camera
+/// Returns None if the point is behind the camera or outside the view
+/// (see `ScreenMarkerHud` for markers pinned to the screen's edge instead)
 fn world_to_screen(world_pos: Vec3, view_proj: Mat4, screen_size: egui::Vec2) -> Option<egui::Pos2> {
-    let clip = view_proj * world_pos.extend(1.0);
-
-    // Check if behind camera
-    if clip.w <= 0.0 {
-        return None;
-    }
-
-    // Perspective divide
-    let ndc = glam::Vec3::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w);
-
-    // Check if outside frustum
-    if ndc.x < -1.0 || ndc.x > 1.0 || ndc.y < -1.0 || ndc.y > 1.0 {
+    let ndc = infinite_game::screen_markers::project(world_pos, view_proj)?;
+    if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
         return None;
     }
-
-    // Convert NDC to screen coordinates
-    let screen_x = (ndc.x + 1.0) * 0.5 * screen_size.x;
-    let screen_y = (1.0 - ndc.y) * 0.5 * screen_size.y; // Y is flipped
-
-    Some(egui::Pos2::new(screen_x, screen_y))
+    let screen = infinite_game::screen_markers::ndc_to_screen(ndc, glam::Vec2::new(screen_size.x, screen_size.y));
+    Some(egui::Pos2::new(screen.x, screen.y))
 }
 
 /// Get tint color for time transitions based on how far from the present
@@ -4435,90 +10935,162 @@ fn time_tint_color(year: i64, present_year: i64) -> (u8, u8, u8) {
     }
 }
 
-/// Create GPU buffers for a mesh
+/// Upload a mesh into device-local buffers
 fn create_mesh_buffers(
-    memory_allocator: Arc<StandardMemoryAllocator>,
+    uploader: &mut MeshUploader,
     vertices: &[Vertex3D],
     indices: &[u32],
 ) -> Result<MeshBuffers> {
-    let vertex_buffer = Buffer::from_iter(
-        memory_allocator.clone(),
-        BufferCreateInfo {
-            usage: BufferUsage::VERTEX_BUFFER,
-            ..Default::default()
-        },
-        AllocationCreateInfo {
-            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
-                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-            ..Default::default()
-        },
-        vertices.iter().copied(),
-    )
-    .context("Failed to create vertex buffer")?;
-
-    let index_buffer = Buffer::from_iter(
-        memory_allocator,
-        BufferCreateInfo {
-            usage: BufferUsage::INDEX_BUFFER,
-            ..Default::default()
-        },
-        AllocationCreateInfo {
-            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
-                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-            ..Default::default()
-        },
-        indices.iter().copied(),
-    )
-    .context("Failed to create index buffer")?;
-
-    Ok(MeshBuffers {
-        vertex_buffer,
-        index_buffer,
-        index_count: indices.len() as u32,
-    })
+    uploader.upload(vertices, indices).context("Failed to upload mesh")
 }
 
-/// Create GPU buffers for a sky mesh
+/// Upload a sky mesh into device-local buffers
 fn create_sky_mesh_buffers(
-    memory_allocator: Arc<StandardMemoryAllocator>,
+    uploader: &mut MeshUploader,
     vertices: &[SkyVertex],
     indices: &[u32],
 ) -> Result<SkyMeshBuffers> {
-    let vertex_buffer = Buffer::from_iter(
-        memory_allocator.clone(),
-        BufferCreateInfo {
-            usage: BufferUsage::VERTEX_BUFFER,
-            ..Default::default()
-        },
-        AllocationCreateInfo {
-            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
-                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-            ..Default::default()
-        },
-        vertices.iter().copied(),
-    )
-    .context("Failed to create sky vertex buffer")?;
+    uploader.upload(vertices, indices).context("Failed to upload sky mesh")
+}
 
-    let index_buffer = Buffer::from_iter(
-        memory_allocator,
-        BufferCreateInfo {
-            usage: BufferUsage::INDEX_BUFFER,
-            ..Default::default()
-        },
-        AllocationCreateInfo {
-            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
-                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-            ..Default::default()
-        },
-        indices.iter().copied(),
+/// Empty chunk mesh pool with blocks sized for the default chunk resolution
+fn chunk_mesh_pool() -> MeshPool<ChunkCoord, Vertex3D> {
+    let cells = ChunkConfig::default().subdivisions as u64;
+    MeshPool::new(
+        CHUNK_MESHES_PER_BLOCK * (cells + 1) * (cells + 1),
+        CHUNK_MESHES_PER_BLOCK * cells * cells * 6,
     )
-    .context("Failed to create sky index buffer")?;
+}
+
+/// Block until the frames in flight are done, so buffers they read can be rewritten
+fn wait_for_frames_in_flight(render_ctx: &mut RenderContext) {
+    let frames = take_frame_dependencies(render_ctx);
+    if let Err(e) = frames.then_signal_fence_and_flush().and_then(|fence| fence.wait(None)) {
+        tracing::error!("Failed to wait for frames in flight: {e}");
+    }
+    render_ctx.previous_frame_end = Some(sync::now(render_ctx.device.clone()).boxed());
+}
+
+/// The frames in flight joined with the mesh uploads submitted since, for
+/// the next submission to wait on. The uploads signal a semaphore, so the
+/// graphics queue waits for them without the CPU blocking.
+fn take_frame_dependencies(render_ctx: &mut RenderContext) -> Box<dyn GpuFuture> {
+    let frames = render_ctx
+        .previous_frame_end
+        .take()
+        .unwrap_or_else(|| sync::now(render_ctx.device.clone()).boxed());
+    match render_ctx.mesh_uploader.take_pending() {
+        Ok(Some(uploads)) => frames.join(uploads).boxed(),
+        Ok(None) => frames,
+        Err(e) => {
+            tracing::error!("Failed to hand mesh uploads to the frame: {e}");
+            frames
+        }
+    }
+}
+
+/// Build the player mesh shaped by `appearance` into `capsule_mesh`. For
+/// now a capsule sized by the body sliders and tinted by the skin; rigged
+/// glTF bodies would take the same values as morph target weights.
+fn upload_character_mesh(render_ctx: &mut RenderContext, appearance: &CharacterAppearance) {
+    let mesh_data = Mesh::character_capsule(
+        appearance.body.height,
+        appearance.body.build,
+        appearance.body.shoulder_width,
+        appearance.body.hip_width,
+        appearance.skin.tone,
+        appearance.skin.undertone,
+    );
+    match create_mesh_buffers(&mut render_ctx.mesh_uploader, &mesh_data.vertices, &mesh_data.indices) {
+        Ok(buffers) => render_ctx.capsule_mesh = Some(buffers),
+        Err(e) => tracing::error!("Failed to rebuild character mesh: {}", e),
+    }
+}
+
+/// Create the NPC capsule mesh (smaller than the player's) if it isn't there yet
+fn upload_npc_mesh(render_ctx: &mut RenderContext) {
+    if render_ctx.npc_capsule_mesh.is_none() {
+        let npc_mesh_data = Mesh::capsule(1.6, 0.35, 12, 8, [1.0, 1.0, 1.0, 1.0]);
+        if let Ok(buffers) = create_mesh_buffers(
+            &mut render_ctx.mesh_uploader,
+            &npc_mesh_data.vertices,
+            &npc_mesh_data.indices,
+        ) {
+            render_ctx.npc_capsule_mesh = Some(buffers);
+        }
+    }
+}
+
+/// A render loop failure from Vulkan. A lost device gets its own code, so
+/// the loop knows to rebuild the renderer.
+fn vulkan_error(code: ErrorCode, message: &str, err: Validated<VulkanError>) -> InfiniteError {
+    let code = if matches!(err, Validated::Error(VulkanError::DeviceLost)) {
+        RENDER_DEVICE_LOST
+    } else {
+        code
+    };
+    render_error(code, message, err)
+}
+
+/// World-space ray (origin, direction) from the camera through a point on
+/// screen; the inverse of `world_to_screen`
+fn screen_ray(camera: &CameraController, screen_pos: egui::Pos2, screen_size: egui::Vec2) -> (Vec3, Vec3) {
+    let inverse = hud_view_proj(camera, screen_size).inverse();
+    let ndc_x = screen_pos.x / screen_size.x * 2.0 - 1.0;
+    let ndc_y = 1.0 - screen_pos.y / screen_size.y * 2.0;
+    let near = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 0.0));
+    let far = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 1.0));
+    (near, (far - near).normalize())
+}
+
+/// Device memory behind an image, ignoring mip levels
+fn image_bytes(image: &Image) -> u64 {
+    let [width, height, depth] = image.extent();
+    width as u64 * height as u64 * depth as u64 * image.array_layers() as u64 * image.format().block_size()
+}
+
+fn render_error(code: ErrorCode, message: &str, err: impl std::error::Error + Send + Sync + 'static) -> InfiniteError {
+    InfiniteError::new(code, Severity::Error, format!("{}: {}", message, err)).with_source(err)
+}
+
+/// Build terrain meshes for `chunks` and upload them as one batch into the chunk mesh pool.
+/// On failure the chunks go undrawn (but stay walkable) until they reload.
+fn upload_chunk_meshes<'a>(
+    render_ctx: &mut RenderContext,
+    chunks: impl IntoIterator<Item = &'a infinite_world::Chunk>,
+) -> Result<(), InfiniteError> {
+    let meshes: Vec<(ChunkCoord, Mesh)> = chunks
+        .into_iter()
+        .map(|chunk| {
+            let terrain = &chunk.terrain;
+            let mesh = Mesh::terrain(
+                terrain.config.size,
+                terrain.config.subdivisions,
+                &terrain.heights,
+                |x, h, z| terrain.color_at(x, h, z),
+            );
+            (chunk.coord, mesh)
+        })
+        .collect();
+    if meshes.is_empty() {
+        return Ok(());
+    }
+
+    // Reused ranges may still be read by the last frames
+    wait_for_frames_in_flight(render_ctx);
+    let batch = meshes.iter().map(|(coord, mesh)| (*coord, mesh.vertices.as_slice(), mesh.indices.as_slice()));
+    render_ctx
+        .chunk_meshes
+        .insert_all(&mut render_ctx.mesh_uploader, batch)
+        .map_err(|e| InfiniteError::from(e).context(format!("uploading {} chunk meshes", meshes.len())))
+}
 
-    Ok(SkyMeshBuffers {
-        vertex_buffer,
-        index_buffer,
-        index_count: indices.len() as u32,
-    })
+/// Where compiled pipelines are kept between runs
+fn pipeline_cache_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("infinite")
+        .join("pipeline_cache.bin")
 }
 
 /// Return default view and projection matrices
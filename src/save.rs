@@ -1,19 +1,43 @@
 //! Save/load system with named save slots, quicksave, and auto-save
 //!
 //! Persists player position, rotation, active year, time of day, collected items,
-//! interaction states, and player combat stats to JSON files.
+//! interaction states, player combat stats and the timeline's branches to JSON files.
 
 use anyhow::{Context, Result};
+use infinite_core::{BranchGraph, BranchId, ErrorCode, ErrorDomain, InfiniteError, RngSnapshot, Scheduler, Severity};
+use infinite_game::combat::anachronism::ParadoxMeter;
 use infinite_game::combat::equipment::EquipmentSet;
+use infinite_game::combat::hotbar::ConsumableHotbar;
 use infinite_game::combat::item::Item;
+use infinite_game::combat::log::CombatTotals;
 use infinite_game::combat::rune::Rune;
 use infinite_game::combat::skill::SkillSlot;
+use infinite_game::economy::Economy;
+use infinite_game::locations::LocationRegistry;
+use infinite_game::lockpick::KeyRing;
+use infinite_game::mods::ModRecord;
+use infinite_game::npc::bestiary::Bestiary;
+use infinite_game::player::statistics::PlayStatistics;
 use infinite_game::player::stats::{CharacterStats, PlayerProgression};
+use infinite_game::tutorial::TutorialProgress;
+use infinite_game::GatheringSkills;
+use infinite_game::RecipeBook;
+use infinite_game::AlchemyJournal;
+use infinite_game::ShrineLedger;
+use infinite_game::RiftLedger;
+use infinite_game::{SpeedrunRecords, SpeedrunRun};
 use infinite_game::InteractionSaveData;
 use infinite_game::RelationshipSaveData;
+use infinite_game::{FlagChange, WorldFlags};
+use infinite_world::PopulationSaveData;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use crate::character::CharacterAppearance;
 
 /// Top-level save data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +86,79 @@ pub struct SaveData {
     /// Player gold
     #[serde(default)]
     pub gold: Option<u64>,
+    /// Combat statistics accumulated over this save
+    #[serde(default)]
+    pub combat_stats: Option<CombatTotals>,
+    /// Discovered enemy archetypes
+    #[serde(default)]
+    pub bestiary: Option<Bestiary>,
+    /// Tutorial prompts seen and completed
+    #[serde(default)]
+    pub tutorials: Option<TutorialProgress>,
+    /// Lifetime play statistics
+    #[serde(default)]
+    pub play_stats: Option<PlayStatistics>,
+    /// Door keys carried
+    #[serde(default)]
+    pub key_ring: Option<KeyRing>,
+    /// Mining, woodcutting and herbalism XP
+    #[serde(default)]
+    pub gathering_skills: Option<GatheringSkills>,
+    /// Recipes discovered by cooking
+    #[serde(default)]
+    pub recipe_book: Option<RecipeBook>,
+    /// Reagent effects and brews discovered
+    #[serde(default)]
+    pub alchemy_journal: Option<AlchemyJournal>,
+    /// When each shrine answers again
+    #[serde(default)]
+    pub shrine_ledger: Option<ShrineLedger>,
+    /// Rift shards, records and attunements carried between runs
+    #[serde(default)]
+    pub rift_ledger: Option<RiftLedger>,
+    /// Consumables bound to the hotbar
+    #[serde(default)]
+    pub hotbar: Option<ConsumableHotbar>,
+    /// Residents the player killed (the rest of the population is re-derived)
+    #[serde(default)]
+    pub population: Option<PopulationSaveData>,
+    /// Quest, dialogue and interaction flags
+    #[serde(default)]
+    pub world_flags: Option<WorldFlags>,
+    /// Timers still pending when the game was saved
+    #[serde(default)]
+    pub scheduler: Option<Scheduler<ScheduledEvent>>,
+    /// Branch graph and the world state of the branches the player isn't on
+    #[serde(default)]
+    pub timeline: Option<TimelineSaveData>,
+    /// Paradox built up from anachronistic items
+    #[serde(default)]
+    pub paradox: Option<ParadoxMeter>,
+    /// Random stream states, so a reload rolls what the original would have
+    #[serde(default)]
+    pub rng: Option<RngSnapshot>,
+    /// Named places registered so far, and which have been discovered
+    #[serde(default)]
+    pub locations: Option<LocationRegistry>,
+    /// Settlement markets and caravans on the road
+    #[serde(default)]
+    pub economy: Option<Economy>,
+    /// Mods active when the game was saved, to warn when they've changed
+    #[serde(default)]
+    pub mods: Option<Vec<ModRecord>>,
+    /// The player's look, so the body is rebuilt to match on load
+    #[serde(default)]
+    pub appearance: Option<CharacterAppearance>,
+    /// Speedrun timer and splits, when the world is being run
+    #[serde(default)]
+    pub speedrun: Option<SpeedrunRun>,
+}
+
+/// Gameplay events fired by the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduledEvent {
+    /// Periodic auto-save (re-armed with the current interval each time)
+    AutoSave,
 }
 
 /// Saved player state
@@ -84,6 +181,28 @@ pub struct WorldSaveData {
     pub active_year: i64,
     /// Time of day in hours (0.0 - 24.0)
     pub time_of_day: f32,
+    /// Days passed in the world (drives the moon's phases)
+    #[serde(default)]
+    pub day: u32,
+}
+
+/// Every branch of the timeline, with the world state left behind on each
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimelineSaveData {
+    pub branches: BranchGraph,
+    /// State of every branch but the active one (whose state is the save's own)
+    #[serde(default)]
+    pub parked: Vec<BranchWorldState>,
+}
+
+/// World state that differs between timeline branches. The player (stats,
+/// inventory, ...) travels between branches unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchWorldState {
+    pub branch: BranchId,
+    pub world_flags: WorldFlags,
+    pub interactions: InteractionSaveData,
+    pub population: Option<PopulationSaveData>,
 }
 
 /// Summary info for a save slot (for listing in UI)
@@ -104,6 +223,25 @@ pub struct SaveSlotInfo {
     pub play_time_seconds: f64,
 }
 
+/// Writing a save failed
+pub const SAVE_WRITE_FAILED: ErrorCode = ErrorCode::new(ErrorDomain::Save, 1);
+/// Reading a save failed
+pub const SAVE_READ_FAILED: ErrorCode = ErrorCode::new(ErrorDomain::Save, 2);
+
+/// A save error for the error dialog, keeping its context chain
+pub fn save_error(code: ErrorCode, err: &anyhow::Error) -> InfiniteError {
+    let mut chain: Vec<String> = err.chain().map(|cause| cause.to_string()).collect();
+    let root = chain.pop().unwrap_or_default();
+    let hint = match code {
+        SAVE_READ_FAILED => "The save may be from a newer version of the game, or damaged",
+        _ => "Check there is free disk space and the save folder can be written to",
+    };
+    chain
+        .into_iter()
+        .rev()
+        .fold(InfiniteError::new(code, Severity::Error, root).with_hint(hint), |err, context| err.context(context))
+}
+
 /// Get the save directory path, creating it if it doesn't exist
 fn save_dir() -> Result<PathBuf> {
     let dir = dirs::data_local_dir()
@@ -114,6 +252,45 @@ fn save_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
+/// Directory of the persistent world changes (chunk region files)
+pub fn world_dir() -> Result<PathBuf> {
+    Ok(save_dir()?.join("world"))
+}
+
+/// Speedrun personal bests, shared by every save
+fn speedrun_records_path() -> Result<PathBuf> {
+    Ok(save_dir()?.join("speedruns.json"))
+}
+
+/// Read the speedrun personal bests, starting afresh if there are none yet
+pub fn load_speedrun_records() -> SpeedrunRecords {
+    let Ok(path) = speedrun_records_path() else {
+        return SpeedrunRecords::new();
+    };
+    let Ok(json) = fs::read_to_string(&path) else {
+        return SpeedrunRecords::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable speedrun records {:?}: {}", path, e);
+        SpeedrunRecords::new()
+    })
+}
+
+/// Write the speedrun personal bests
+pub fn write_speedrun_records(records: &SpeedrunRecords) -> Result<()> {
+    let json = serde_json::to_string_pretty(records).context("Failed to serialize speedrun records")?;
+    fs::write(speedrun_records_path()?, json).context("Failed to write speedrun records")
+}
+
+/// Write a run's splits as a LiveSplit file in the splits directory
+pub fn export_splits(run: &SpeedrunRun, records: &SpeedrunRecords) -> Result<PathBuf> {
+    let dir = save_dir()?.join("splits");
+    fs::create_dir_all(&dir).context("Failed to create splits directory")?;
+    let path = dir.join(format!("seed_{}.lss", run.seed()));
+    fs::write(&path, run.to_lss(records)).context("Failed to write splits file")?;
+    Ok(path)
+}
+
 /// Get the quicksave file path
 fn quicksave_path() -> Result<PathBuf> {
     Ok(save_dir()?.join("quicksave.json"))
@@ -137,41 +314,293 @@ fn sanitize_filename(name: &str) -> String {
         .to_lowercase()
 }
 
-/// Save the game to the quicksave slot
-pub fn save_game(data: &SaveData) -> Result<()> {
-    let path = quicksave_path()?;
-    write_save(&path, data)
-}
-
-/// Load the game from the quicksave slot
-pub fn load_game() -> Result<SaveData> {
-    let path = quicksave_path()?;
-    read_save(&path)
-}
-
 /// Check if a quicksave file exists
 #[allow(dead_code)]
 pub fn has_quicksave() -> bool {
     quicksave_path().map(|p| p.exists()).unwrap_or(false)
 }
 
-/// Save to the autosave slot
-pub fn autosave(data: &SaveData) -> Result<()> {
-    let path = autosave_path()?;
-    write_save(&path, data)
+/// Minimum play-time seconds between two autosaves
+pub const MIN_AUTOSAVE_INTERVAL: f64 = 30.0;
+
+/// Why an autosave was requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutosaveTrigger {
+    /// The periodic auto-save timer ran out
+    Timer,
+    QuestCompleted,
+    BossDefeated,
+    /// Travelled to another year
+    YearChanged,
+    /// Changed history, splitting off a new timeline branch
+    TimelineBranched,
+    WaypointUnlocked,
+    /// A rift run ended, banking its shards
+    RiftEnded,
 }
 
-/// Save to a named slot
-pub fn save_to_slot(slot_name: &str, data: &SaveData) -> Result<()> {
-    let filename = sanitize_filename(slot_name);
-    let path = slot_path(&filename)?;
-    write_save(&path, data)
+impl AutosaveTrigger {
+    /// Progress recorded in the world flags that is worth a save:
+    /// `quest.<name>_completed` and any `waypoint.<name>` turning on
+    pub fn for_flag_change(change: &FlagChange) -> Option<Self> {
+        let turned_on = change.new.as_ref().is_some_and(|v| v.is_truthy())
+            && !change.old.as_ref().is_some_and(|v| v.is_truthy());
+        if !turned_on {
+            return None;
+        }
+        match change.namespace.as_str() {
+            "quest" if change.key.ends_with("_completed") => Some(Self::QuestCompleted),
+            "waypoint" => Some(Self::WaypointUnlocked),
+            _ => None,
+        }
+    }
 }
 
-/// Load from a named slot (by filename, not display name)
-pub fn load_from_slot(filename: &str) -> Result<SaveData> {
-    let path = slot_path(filename)?;
-    read_save(&path)
+/// Holds event-driven autosave requests until the interval guard allows one
+#[derive(Debug, Default)]
+pub struct Autosaver {
+    /// Trigger waiting for the interval guard
+    pending: Option<AutosaveTrigger>,
+    /// Play time of the last autosave started
+    last_save: Option<f64>,
+}
+
+impl Autosaver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask for an autosave; several requests before it runs collapse into one
+    pub fn request(&mut self, trigger: AutosaveTrigger) {
+        self.pending.get_or_insert(trigger);
+    }
+
+    /// The pending trigger, once the last autosave is at least
+    /// `MIN_AUTOSAVE_INTERVAL` ago. Requests arriving too early wait rather
+    /// than being dropped.
+    pub fn take_due(&mut self, play_time: f64) -> Option<AutosaveTrigger> {
+        if self.last_save.is_some_and(|last| play_time - last < MIN_AUTOSAVE_INTERVAL) {
+            return None;
+        }
+        self.pending.take()
+    }
+
+    /// Note that an autosave was just started
+    pub fn mark_saved(&mut self, play_time: f64) {
+        self.last_save = Some(play_time);
+    }
+}
+
+/// A save file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveSlot {
+    Quicksave,
+    Autosave,
+    /// Named slot, by filename (see `SaveSlot::named`)
+    Named(String),
+}
+
+impl SaveSlot {
+    /// Slot for a display name typed by the player
+    pub fn named(slot_name: &str) -> Self {
+        Self::Named(sanitize_filename(slot_name))
+    }
+
+    fn path(&self) -> Result<PathBuf> {
+        match self {
+            Self::Quicksave => quicksave_path(),
+            Self::Autosave => autosave_path(),
+            Self::Named(filename) => slot_path(filename),
+        }
+    }
+
+    /// Serialize and write `data` to this slot (blocking)
+    pub fn write(&self, data: &SaveData) -> Result<()> {
+        write_save(&self.path()?, data)
+    }
+
+    /// Read and deserialize this slot (blocking)
+    pub fn read(&self) -> Result<SaveData> {
+        read_save(&self.path()?)
+    }
+}
+
+type SaveCallback<C> = Box<dyn FnOnce(&mut C, Result<()>)>;
+type LoadCallback<C> = Box<dyn FnOnce(&mut C, Result<SaveData>)>;
+
+enum Job {
+    Save(u64, SaveSlot, Box<SaveData>),
+    Load(u64, SaveSlot),
+}
+
+enum JobResult {
+    Saved(u64, Result<()>),
+    Loaded(u64, Result<Box<SaveData>>),
+}
+
+fn run_job(job: Job) -> JobResult {
+    match job {
+        Job::Save(id, slot, data) => JobResult::Saved(id, slot.write(&data)),
+        Job::Load(id, slot) => JobResult::Loaded(id, slot.read().map(Box::new)),
+    }
+}
+
+enum Callback<C> {
+    Save(SaveCallback<C>),
+    Load(LoadCallback<C>),
+}
+
+/// Runs saves and loads on a background thread so serialization and disk
+/// IO never stall a frame. Jobs run one at a time in the order they were
+/// queued; each finishes by handing its callback to the main thread through
+/// `poll`, where it gets mutable access to `C` (the app).
+pub struct SaveWorker<C> {
+    jobs: Option<mpsc::Sender<Job>>,
+    results: mpsc::Receiver<JobResult>,
+    thread: Option<JoinHandle<()>>,
+    /// Results of jobs that had to run inline because the thread is gone
+    ready: Vec<JobResult>,
+    callbacks: HashMap<u64, Callback<C>>,
+    saves_in_flight: usize,
+    next_id: u64,
+}
+
+impl<C> SaveWorker<C> {
+    pub fn new() -> Self {
+        let (jobs, job_rx) = mpsc::channel();
+        let (result_tx, results) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("save-worker".to_string())
+            .spawn(move || {
+                for job in job_rx {
+                    if result_tx.send(run_job(job)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .ok();
+        Self {
+            jobs: Some(jobs),
+            results,
+            thread,
+            ready: Vec::new(),
+            callbacks: HashMap::new(),
+            saves_in_flight: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Queue `data` to be written to `slot`; `on_done` runs on the main
+    /// thread once it's on disk (or failed)
+    pub fn save(&mut self, slot: SaveSlot, data: SaveData, on_done: impl FnOnce(&mut C, Result<()>) + 'static) {
+        let id = self.next_job_id();
+        self.saves_in_flight += 1;
+        self.callbacks.insert(id, Callback::Save(Box::new(on_done)));
+        self.send(Job::Save(id, slot, Box::new(data)));
+    }
+
+    /// Queue a read of `slot`; `on_done` receives the loaded data on the
+    /// main thread
+    pub fn load(&mut self, slot: SaveSlot, on_done: impl FnOnce(&mut C, Result<SaveData>) + 'static) {
+        let id = self.next_job_id();
+        self.callbacks.insert(id, Callback::Load(Box::new(on_done)));
+        self.send(Job::Load(id, slot));
+    }
+
+    /// Whether a save is queued or being written
+    pub fn is_saving(&self) -> bool {
+        self.saves_in_flight > 0
+    }
+
+    /// Whether anything (save or load) is still pending
+    pub fn is_busy(&self) -> bool {
+        !self.callbacks.is_empty()
+    }
+
+    /// Callbacks of every job that finished since the last poll. Run each
+    /// with `completion(app)`.
+    pub fn poll(&mut self) -> Vec<Completion<C>> {
+        let mut results = std::mem::take(&mut self.ready);
+        results.extend(self.results.try_iter());
+        results.into_iter().filter_map(|result| self.complete(result)).collect()
+    }
+
+    /// Block until every queued job has finished (before quitting)
+    pub fn flush(&mut self) -> Vec<Completion<C>> {
+        let mut done = self.poll();
+        while self.is_busy() {
+            let Ok(result) = self.results.recv() else {
+                break;
+            };
+            done.extend(self.complete(result));
+        }
+        done
+    }
+
+    fn next_job_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Hand a job to the thread, or run it inline if the thread is gone
+    fn send(&mut self, job: Job) {
+        let job = match &self.jobs {
+            Some(jobs) => match jobs.send(job) {
+                Ok(()) => return,
+                Err(mpsc::SendError(job)) => job,
+            },
+            None => job,
+        };
+        self.ready.push(run_job(job));
+    }
+
+    fn complete(&mut self, result: JobResult) -> Option<Completion<C>> {
+        let completion = match result {
+            JobResult::Saved(id, result) => {
+                self.saves_in_flight = self.saves_in_flight.saturating_sub(1);
+                match self.callbacks.remove(&id)? {
+                    Callback::Save(callback) => Completion::Save(callback, result),
+                    Callback::Load(_) => return None,
+                }
+            }
+            JobResult::Loaded(id, result) => match self.callbacks.remove(&id)? {
+                Callback::Load(callback) => Completion::Load(callback, result),
+                Callback::Save(_) => return None,
+            },
+        };
+        Some(completion)
+    }
+}
+
+impl<C> Default for SaveWorker<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Drop for SaveWorker<C> {
+    fn drop(&mut self) {
+        // Closing the queue lets the thread finish what it has and exit
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A finished job's callback, ready to run on the main thread
+pub enum Completion<C> {
+    Save(SaveCallback<C>, Result<()>),
+    Load(LoadCallback<C>, Result<Box<SaveData>>),
+}
+
+impl<C> Completion<C> {
+    pub fn run(self, context: &mut C) {
+        match self {
+            Self::Save(callback, result) => callback(context, result),
+            Self::Load(callback, result) => callback(context, result.map(|data| *data)),
+        }
+    }
 }
 
 /// Delete a save slot
@@ -227,7 +656,11 @@ pub fn list_save_slots() -> Result<Vec<SaveSlotInfo>> {
 
 fn write_save(path: &PathBuf, data: &SaveData) -> Result<()> {
     let json = serde_json::to_string_pretty(data).context("Failed to serialize save data")?;
-    fs::write(path, json).context("Failed to write save file")?;
+    // Write beside the old save and swap it in, so quitting mid-write never
+    // leaves a truncated file
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, json).context("Failed to write save file")?;
+    fs::rename(&temp, path).context("Failed to replace save file")?;
     Ok(())
 }
 
@@ -267,6 +700,7 @@ mod tests {
             world: WorldSaveData {
                 active_year: 2025,
                 time_of_day: 14.5,
+                day: 3,
             },
             timestamp: "2025-01-01 12:00:00".to_string(),
             slot_name: String::new(),
@@ -281,6 +715,28 @@ mod tests {
             known_runes: None,
             inventory: None,
             gold: None,
+            combat_stats: None,
+            bestiary: None,
+            tutorials: None,
+            play_stats: None,
+            key_ring: None,
+            gathering_skills: None,
+            recipe_book: None,
+            alchemy_journal: None,
+            shrine_ledger: None,
+            rift_ledger: None,
+            hotbar: None,
+            population: None,
+            world_flags: None,
+            scheduler: None,
+            timeline: None,
+            paradox: None,
+            rng: None,
+            locations: None,
+            economy: None,
+            mods: None,
+            appearance: None,
+            speedrun: None,
         }
     }
 
@@ -296,26 +752,105 @@ mod tests {
         assert_eq!(loaded.player.character_name, "TestPlayer");
         assert_eq!(loaded.world.active_year, 2025);
         assert_eq!(loaded.world.time_of_day, 14.5);
+        assert_eq!(loaded.world.day, 3);
         assert_eq!(loaded.collected_items, vec!["Gem"]);
         assert_eq!(loaded.play_time_seconds, 3661.0);
     }
 
+    #[test]
+    fn test_branch_graph_round_trip() {
+        let mut branches = BranchGraph::default();
+        let branch = branches.fork("Spared the bandit", 1200);
+        branches.switch(BranchId::MAIN).unwrap();
+        let mut world_flags = WorldFlags::new();
+        world_flags.set("quest", "bandit_spared", true);
+
+        let mut data = test_save_data();
+        data.timeline = Some(TimelineSaveData {
+            branches: branches.clone(),
+            parked: vec![BranchWorldState {
+                branch,
+                world_flags,
+                interactions: InteractionSaveData::default(),
+                population: None,
+            }],
+        });
+        let json = serde_json::to_string(&data).unwrap();
+        let loaded: SaveData = serde_json::from_str(&json).unwrap();
+        let timeline = loaded.timeline.unwrap();
+        assert_eq!(timeline.branches, branches);
+        assert_eq!(timeline.parked[0].branch, branch);
+        assert!(timeline.parked[0].world_flags.get_bool("quest", "bandit_spared"));
+    }
+
     #[test]
     fn test_save_and_load() {
         let data = test_save_data();
 
         // Save
-        save_game(&data).unwrap();
+        SaveSlot::Quicksave.write(&data).unwrap();
 
         // Verify file exists
         assert!(has_quicksave());
 
         // Load
-        let loaded = load_game().unwrap();
+        let loaded = SaveSlot::Quicksave.read().unwrap();
         assert_eq!(loaded.player.position, data.player.position);
         assert_eq!(loaded.world.active_year, data.world.active_year);
     }
 
+    #[test]
+    fn test_autosave_interval_guard() {
+        let mut autosaver = Autosaver::new();
+        assert_eq!(autosaver.take_due(0.0), None);
+
+        autosaver.request(AutosaveTrigger::BossDefeated);
+        autosaver.request(AutosaveTrigger::QuestCompleted);
+        assert_eq!(autosaver.take_due(100.0), Some(AutosaveTrigger::BossDefeated));
+        autosaver.mark_saved(100.0);
+
+        // Too soon after the last save: kept until the interval has passed
+        autosaver.request(AutosaveTrigger::YearChanged);
+        assert_eq!(autosaver.take_due(110.0), None);
+        assert_eq!(autosaver.take_due(100.0 + MIN_AUTOSAVE_INTERVAL), Some(AutosaveTrigger::YearChanged));
+    }
+
+    #[test]
+    fn test_worker_runs_callbacks_in_order() {
+        let mut worker: SaveWorker<Vec<String>> = SaveWorker::new();
+        let slot = SaveSlot::named("Worker Test");
+        worker.save(slot.clone(), test_save_data(), |log, result| {
+            log.push(format!("saved {}", result.is_ok()));
+        });
+        assert!(worker.is_saving());
+        worker.load(slot.clone(), |log, result| {
+            log.push(format!("loaded {}", result.unwrap().player.character_name));
+        });
+        worker.load(SaveSlot::named("Missing Worker Test"), |log, result| {
+            log.push(format!("missing {}", result.is_err()));
+        });
+
+        let mut log = Vec::new();
+        for completion in worker.flush() {
+            completion.run(&mut log);
+        }
+        assert_eq!(log, vec!["saved true", "loaded TestPlayer", "missing true"]);
+        assert!(!worker.is_busy());
+        let SaveSlot::Named(filename) = slot else { unreachable!() };
+        delete_slot(&filename).unwrap();
+    }
+
+    #[test]
+    fn test_autosave_flag_triggers() {
+        let mut flags = WorldFlags::new();
+        flags.set("quest", "heard_of_rifts", true);
+        flags.set("quest", "rifts_completed", true);
+        flags.set("waypoint", "old_mill", true);
+        flags.set("waypoint", "old_mill", true);
+        let triggers: Vec<_> = flags.drain_changes().iter().map(AutosaveTrigger::for_flag_change).collect();
+        assert_eq!(triggers, vec![None, Some(AutosaveTrigger::QuestCompleted), Some(AutosaveTrigger::WaypointUnlocked)]);
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("My Save!"), "my_save_");
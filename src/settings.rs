@@ -2,28 +2,30 @@
 //!
 //! Settings are saved to `~/.config/infinite/settings.toml`
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+use infinite_game::speedrun::default_route;
+use infinite_game::{MacroSet, Split};
+
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 /// All game settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GameSettings {
     pub video: VideoSettings,
     pub audio: AudioSettings,
     pub gameplay: GameplaySettings,
-}
-
-impl Default for GameSettings {
-    fn default() -> Self {
-        Self {
-            video: VideoSettings::default(),
-            audio: AudioSettings::default(),
-            gameplay: GameplaySettings::default(),
-        }
-    }
+    #[serde(default)]
+    pub hud: HudSettings,
+    #[serde(default)]
+    pub crosshair: CrosshairSettings,
+    #[serde(default)]
+    pub macros: MacroSettings,
+    #[serde(default)]
+    pub speedrun: SpeedrunSettings,
 }
 
 impl GameSettings {
@@ -102,6 +104,36 @@ pub struct VideoSettings {
     pub ray_tracing_quality: u8,
     /// Field of view in degrees
     pub fov: f32,
+    /// Show the destination era inside time portals
+    #[serde(default = "default_true")]
+    pub portal_previews: bool,
+    /// Memory the game tries to stay under before shedding detail
+    #[serde(default)]
+    pub memory_budget: infinite_core::MemoryBudget,
+    /// Lower the 3D scene's render resolution to hold the target frame rate
+    #[serde(default)]
+    pub dynamic_resolution: bool,
+    /// Frame rate dynamic resolution aims for
+    #[serde(default = "default_target_fps")]
+    pub target_fps: u32,
+    /// Smallest render scale dynamic resolution may drop to (share of native)
+    #[serde(default = "default_min_render_scale")]
+    pub min_render_scale: f32,
+    /// Largest render scale dynamic resolution may use (share of native)
+    #[serde(default = "default_max_render_scale")]
+    pub max_render_scale: f32,
+}
+
+fn default_target_fps() -> u32 {
+    60
+}
+
+fn default_min_render_scale() -> f32 {
+    0.5
+}
+
+fn default_max_render_scale() -> f32 {
+    1.0
 }
 
 impl Default for VideoSettings {
@@ -113,6 +145,12 @@ impl Default for VideoSettings {
             vsync: true,
             ray_tracing_quality: 2,
             fov: 90.0,
+            portal_previews: true,
+            memory_budget: infinite_core::MemoryBudget::default(),
+            dynamic_resolution: false,
+            target_fps: default_target_fps(),
+            min_render_scale: default_min_render_scale(),
+            max_render_scale: default_max_render_scale(),
         }
     }
 }
@@ -147,6 +185,35 @@ pub struct AudioSettings {
     pub sfx: f32,
     /// Voice/dialogue volume (0.0 to 1.0)
     pub voice: f32,
+    /// Ambient soundscape volume (0.0 to 1.0)
+    #[serde(default = "default_ambience_volume")]
+    pub ambience: f32,
+    /// Mute all audio while the window is unfocused
+    #[serde(default = "default_true")]
+    pub mute_on_unfocus: bool,
+    /// Output device name (None = system default)
+    #[serde(default)]
+    pub output_device: Option<String>,
+    /// Show captions for spoken dialogue lines
+    #[serde(default = "default_true")]
+    pub subtitles: bool,
+    /// Synthesize speech for lines without a recording
+    #[serde(default = "default_true")]
+    pub tts_voice_lines: bool,
+    /// Voice NPCs' ambient barks, not just show them
+    #[serde(default = "default_true")]
+    pub voice_barks: bool,
+    /// Slow motion and sped-up time shift sound effects' speed and pitch
+    #[serde(default = "default_true")]
+    pub pitch_follows_time_scale: bool,
+}
+
+fn default_ambience_volume() -> f32 {
+    0.8
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for AudioSettings {
@@ -156,6 +223,28 @@ impl Default for AudioSettings {
             music: 0.8,
             sfx: 1.0,
             voice: 1.0,
+            ambience: default_ambience_volume(),
+            mute_on_unfocus: true,
+            output_device: None,
+            subtitles: true,
+            tts_voice_lines: true,
+            voice_barks: true,
+            pitch_follows_time_scale: true,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Convert to the audio engine's config
+    pub fn to_audio_config(&self) -> infinite_audio::AudioConfig {
+        infinite_audio::AudioConfig {
+            master_volume: self.master as f64,
+            music_volume: self.music as f64,
+            sfx_volume: self.sfx as f64,
+            voice_volume: self.voice as f64,
+            ambience_volume: self.ambience as f64,
+            mute_on_unfocus: self.mute_on_unfocus,
+            output_device: self.output_device.clone(),
         }
     }
 }
@@ -169,6 +258,33 @@ pub struct GameplaySettings {
     pub auto_save: bool,
     /// Auto-save interval in seconds
     pub auto_save_interval: u32,
+    /// Marker categories shown on the compass
+    #[serde(default)]
+    pub compass_filter: infinite_game::CompassFilter,
+    /// Show contextual tutorial prompts
+    #[serde(default = "default_true")]
+    pub show_tutorials: bool,
+    /// How the world changes over when traveling to another year
+    #[serde(default)]
+    pub time_travel_transition: TimeTravelTransition,
+    /// Anachronism rules for items used outside their era
+    #[serde(default)]
+    pub paradox: infinite_game::ParadoxConfig,
+    /// Gently pull the aim onto targets near the crosshair (accessibility)
+    #[serde(default)]
+    pub aim_assist: bool,
+    /// Frame conversations with a letterboxed over-the-shoulder shot
+    #[serde(default = "default_true")]
+    pub dialogue_camera: bool,
+    /// Holding Sprint while seated makes time pass faster
+    #[serde(default = "default_true")]
+    pub rest_fast_forward: bool,
+    /// Weapons, shields and armor wear with use and need repairs
+    #[serde(default = "default_true")]
+    pub durability: bool,
+    /// NPC density, hostile cap and ambient life
+    #[serde(default)]
+    pub population: infinite_game::PopulationConfig,
 }
 
 impl Default for GameplaySettings {
@@ -177,6 +293,483 @@ impl Default for GameplaySettings {
             time_scale: 1.0,
             auto_save: true,
             auto_save_interval: 300, // 5 minutes
+            compass_filter: infinite_game::CompassFilter::default(),
+            show_tutorials: true,
+            time_travel_transition: TimeTravelTransition::default(),
+            paradox: infinite_game::ParadoxConfig::default(),
+            aim_assist: false,
+            dialogue_camera: true,
+            rest_fast_forward: true,
+            durability: true,
+            population: infinite_game::PopulationConfig::default(),
+        }
+    }
+}
+
+/// Time travel presentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeTravelTransition {
+    /// Fade to black and swap the terrain out of sight
+    #[default]
+    Fade,
+    /// Stay in view and watch the terrain morph into the new era
+    Blend,
+}
+
+impl TimeTravelTransition {
+    pub const ALL: [Self; 2] = [Self::Fade, Self::Blend];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fade => "Fade",
+            Self::Blend => "Watch the world change",
+        }
+    }
+}
+
+/// Shape of the crosshair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrosshairStyle {
+    /// Four lines around a gap
+    #[default]
+    Cross,
+    Dot,
+    Circle,
+    /// Four lines around a centre dot
+    CrossDot,
+}
+
+impl CrosshairStyle {
+    pub const ALL: [Self; 4] = [Self::Cross, Self::Dot, Self::Circle, Self::CrossDot];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cross => "Cross",
+            Self::Dot => "Dot",
+            Self::Circle => "Circle",
+            Self::CrossDot => "Cross and dot",
+        }
+    }
+}
+
+/// Crosshair look
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrosshairSettings {
+    pub enabled: bool,
+    pub style: CrosshairStyle,
+    /// RGB
+    pub color: [u8; 3],
+    /// Length of the lines (or radius of the circle) in points
+    pub size: f32,
+    /// Widen with ranged weapon spread
+    pub dynamic_spread: bool,
+}
+
+impl CrosshairSettings {
+    pub const MIN_SIZE: f32 = 2.0;
+    pub const MAX_SIZE: f32 = 30.0;
+}
+
+impl Default for CrosshairSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            style: CrosshairStyle::Cross,
+            color: [255, 255, 255],
+            size: 8.0,
+            dynamic_spread: true,
+        }
+    }
+}
+
+/// Input macros (accessibility), kept per player profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MacroSettings {
+    /// Recording and playing macros is allowed
+    pub enabled: bool,
+    /// Each profile's macros, by account name
+    pub profiles: BTreeMap<String, MacroSet>,
+}
+
+impl MacroSettings {
+    /// Profile in use when nobody is logged in
+    pub const LOCAL_PROFILE: &'static str = "Local";
+
+    pub fn profile(&self, name: &str) -> Option<&MacroSet> {
+        self.profiles.get(name)
+    }
+
+    pub fn profile_mut(&mut self, name: &str) -> &mut MacroSet {
+        self.profiles.entry(name.to_string()).or_default()
+    }
+}
+
+impl Default for MacroSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+/// Speedrun and practice timing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeedrunSettings {
+    /// Time new worlds and show the splits overlay
+    pub enabled: bool,
+    /// The route, split by split
+    pub splits: Vec<Split>,
+}
+
+impl Default for SpeedrunSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            splits: default_route(),
+        }
+    }
+}
+
+/// HUD widgets that can be toggled and laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HudWidget {
+    PlayerStats,
+    Compass,
+    TimeWeather,
+    Minimap,
+    SkillBar,
+    Hotbar,
+    DamageNumbers,
+    ScreenMarkers,
+}
+
+impl HudWidget {
+    pub const ALL: [Self; 8] = [
+        Self::PlayerStats,
+        Self::Compass,
+        Self::TimeWeather,
+        Self::Minimap,
+        Self::SkillBar,
+        Self::Hotbar,
+        Self::DamageNumbers,
+        Self::ScreenMarkers,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PlayerStats => "Player stats",
+            Self::Compass => "Compass",
+            Self::TimeWeather => "Time & weather",
+            Self::Minimap => "Minimap",
+            Self::SkillBar => "Skill bar",
+            Self::Hotbar => "Consumable hotbar",
+            Self::DamageNumbers => "Damage numbers",
+            Self::ScreenMarkers => "Objective markers",
+        }
+    }
+
+    /// Whether the widget sits at a screen position (damage numbers and
+    /// objective markers float over their targets instead)
+    pub fn is_movable(self) -> bool {
+        !matches!(self, Self::DamageNumbers | Self::ScreenMarkers)
+    }
+
+    /// Where the widget sits in the stock HUD. The offset goes from the
+    /// anchor to the widget's pivot, the point it is scaled around.
+    pub fn default_layout(self) -> WidgetLayout {
+        let (anchor, offset) = match self {
+            Self::PlayerStats => (HudAnchor::TopLeft, [10.0, 10.0]),
+            Self::Compass => (HudAnchor::TopCenter, [0.0, 8.0]),
+            Self::TimeWeather => (HudAnchor::TopRight, [-10.0, 10.0]),
+            Self::Minimap => (HudAnchor::TopRight, [-10.0, 90.0]),
+            Self::SkillBar => (HudAnchor::BottomCenter, [0.0, -20.0]),
+            // Right edge of the hotbar, left of the skill bar
+            Self::Hotbar => (HudAnchor::BottomCenter, [-156.0, -20.0]),
+            Self::DamageNumbers | Self::ScreenMarkers => (HudAnchor::TopLeft, [0.0, 0.0]),
+        };
+        WidgetLayout {
+            enabled: true,
+            anchor,
+            offset,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Screen point a HUD widget is positioned relative to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl HudAnchor {
+    pub const ALL: [Self; 6] = [
+        Self::TopLeft,
+        Self::TopCenter,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomCenter,
+        Self::BottomRight,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TopLeft => "Top left",
+            Self::TopCenter => "Top center",
+            Self::TopRight => "Top right",
+            Self::BottomLeft => "Bottom left",
+            Self::BottomCenter => "Bottom center",
+            Self::BottomRight => "Bottom right",
+        }
+    }
+
+    /// Anchor position on a screen of `screen` size
+    pub fn point(self, screen: [f32; 2]) -> [f32; 2] {
+        let x = match self {
+            Self::TopLeft | Self::BottomLeft => 0.0,
+            Self::TopCenter | Self::BottomCenter => screen[0] * 0.5,
+            Self::TopRight | Self::BottomRight => screen[0],
+        };
+        let y = match self {
+            Self::TopLeft | Self::TopCenter | Self::TopRight => 0.0,
+            _ => screen[1],
+        };
+        [x, y]
+    }
+}
+
+/// Placement of one HUD widget
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WidgetLayout {
+    pub enabled: bool,
+    pub anchor: HudAnchor,
+    /// Offset from the anchor to the widget's pivot, in points
+    pub offset: [f32; 2],
+    pub scale: f32,
+}
+
+impl WidgetLayout {
+    pub const MIN_SCALE: f32 = 0.5;
+    pub const MAX_SCALE: f32 = 2.0;
+
+    /// Screen position of the widget's pivot
+    pub fn pivot(&self, screen: [f32; 2]) -> [f32; 2] {
+        let anchor = self.anchor.point(screen);
+        [anchor[0] + self.offset[0], anchor[1] + self.offset[1]]
+    }
+
+    /// Move the pivot to `point`, anchoring to whichever anchor is closest
+    /// so the widget stays in place when the window is resized
+    pub fn move_pivot_to(&mut self, point: [f32; 2], screen: [f32; 2]) {
+        let point = [point[0].clamp(0.0, screen[0]), point[1].clamp(0.0, screen[1])];
+        let distance = |anchor: HudAnchor| {
+            let p = anchor.point(screen);
+            (p[0] - point[0]).powi(2) + (p[1] - point[1]).powi(2)
+        };
+        self.anchor = HudAnchor::ALL
+            .into_iter()
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            .unwrap_or(self.anchor);
+        let anchor = self.anchor.point(screen);
+        self.offset = [point[0] - anchor[0], point[1] - anchor[1]];
+    }
+}
+
+/// Predefined HUD setups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HudPreset {
+    /// Every widget in its stock position
+    Full,
+    /// Only the player's stats and action bars
+    Minimal,
+}
+
+impl HudPreset {
+    pub const ALL: [Self; 2] = [Self::Full, Self::Minimal];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Full => "Full",
+            Self::Minimal => "Minimal",
+        }
+    }
+}
+
+/// Per-widget HUD layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudSettings {
+    pub player_stats: WidgetLayout,
+    pub compass: WidgetLayout,
+    pub time_weather: WidgetLayout,
+    pub minimap: WidgetLayout,
+    pub skill_bar: WidgetLayout,
+    pub hotbar: WidgetLayout,
+    pub damage_numbers: WidgetLayout,
+    pub screen_markers: WidgetLayout,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self::preset(HudPreset::Full)
+    }
+}
+
+impl HudSettings {
+    pub fn preset(preset: HudPreset) -> Self {
+        let mut hud = Self {
+            player_stats: HudWidget::PlayerStats.default_layout(),
+            compass: HudWidget::Compass.default_layout(),
+            time_weather: HudWidget::TimeWeather.default_layout(),
+            minimap: HudWidget::Minimap.default_layout(),
+            skill_bar: HudWidget::SkillBar.default_layout(),
+            hotbar: HudWidget::Hotbar.default_layout(),
+            damage_numbers: HudWidget::DamageNumbers.default_layout(),
+            screen_markers: HudWidget::ScreenMarkers.default_layout(),
+        };
+        if preset == HudPreset::Minimal {
+            for widget in [
+                HudWidget::Compass,
+                HudWidget::TimeWeather,
+                HudWidget::Minimap,
+                HudWidget::DamageNumbers,
+                HudWidget::ScreenMarkers,
+            ] {
+                hud.get_mut(widget).enabled = false;
+            }
         }
+        hud
+    }
+
+    pub fn get(&self, widget: HudWidget) -> &WidgetLayout {
+        match widget {
+            HudWidget::PlayerStats => &self.player_stats,
+            HudWidget::Compass => &self.compass,
+            HudWidget::TimeWeather => &self.time_weather,
+            HudWidget::Minimap => &self.minimap,
+            HudWidget::SkillBar => &self.skill_bar,
+            HudWidget::Hotbar => &self.hotbar,
+            HudWidget::DamageNumbers => &self.damage_numbers,
+            HudWidget::ScreenMarkers => &self.screen_markers,
+        }
+    }
+
+    pub fn get_mut(&mut self, widget: HudWidget) -> &mut WidgetLayout {
+        match widget {
+            HudWidget::PlayerStats => &mut self.player_stats,
+            HudWidget::Compass => &mut self.compass,
+            HudWidget::TimeWeather => &mut self.time_weather,
+            HudWidget::Minimap => &mut self.minimap,
+            HudWidget::SkillBar => &mut self.skill_bar,
+            HudWidget::Hotbar => &mut self.hotbar,
+            HudWidget::DamageNumbers => &mut self.damage_numbers,
+            HudWidget::ScreenMarkers => &mut self.screen_markers,
+        }
+    }
+
+    pub fn is_enabled(&self, widget: HudWidget) -> bool {
+        self.get(widget).enabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: [f32; 2] = [1920.0, 1080.0];
+
+    #[test]
+    fn test_presets() {
+        let full = HudSettings::preset(HudPreset::Full);
+        assert!(HudWidget::ALL.iter().all(|w| full.is_enabled(*w)));
+        let minimal = HudSettings::preset(HudPreset::Minimal);
+        assert!(minimal.is_enabled(HudWidget::SkillBar));
+        assert!(!minimal.is_enabled(HudWidget::Minimap));
+        assert_eq!(minimal.skill_bar, full.skill_bar);
+    }
+
+    #[test]
+    fn test_moving_reanchors_to_nearest_corner() {
+        let mut layout = HudWidget::SkillBar.default_layout();
+        assert_eq!(layout.pivot(SCREEN), [960.0, 1060.0]);
+
+        layout.move_pivot_to([1800.0, 100.0], SCREEN);
+        assert_eq!(layout.anchor, HudAnchor::TopRight);
+        assert_eq!(layout.offset, [-120.0, 100.0]);
+        // Stays the same distance from its corner on a smaller screen
+        assert_eq!(layout.pivot([1280.0, 720.0]), [1160.0, 100.0]);
+    }
+
+    #[test]
+    fn test_old_settings_get_default_hud() {
+        let mut value = toml::Value::try_from(GameSettings::default()).unwrap();
+        value.as_table_mut().unwrap().remove("hud");
+        let settings: GameSettings = value.try_into().unwrap();
+        assert_eq!(settings.hud, HudSettings::default());
+
+        let toml = toml::to_string(&HudSettings::preset(HudPreset::Minimal)).unwrap();
+        let restored: HudSettings = toml::from_str(&toml).unwrap();
+        assert_eq!(restored, HudSettings::preset(HudPreset::Minimal));
+    }
+
+    #[test]
+    fn test_old_settings_get_default_crosshair() {
+        let mut value = toml::Value::try_from(GameSettings::default()).unwrap();
+        let table = value.as_table_mut().unwrap();
+        table.remove("crosshair");
+        table["gameplay"].as_table_mut().unwrap().remove("aim_assist");
+        let settings: GameSettings = value.try_into().unwrap();
+        assert_eq!(settings.crosshair, CrosshairSettings::default());
+        assert!(!settings.gameplay.aim_assist);
+
+        let crosshair = CrosshairSettings { style: CrosshairStyle::Circle, color: [0, 255, 0], ..Default::default() };
+        let restored: CrosshairSettings = toml::from_str(&toml::to_string(&crosshair).unwrap()).unwrap();
+        assert_eq!(restored, crosshair);
+    }
+
+    #[test]
+    fn test_macros_kept_per_profile() {
+        let mut value = toml::Value::try_from(GameSettings::default()).unwrap();
+        value.as_table_mut().unwrap().remove("macros");
+        let settings: GameSettings = value.try_into().unwrap();
+        assert_eq!(settings.macros, MacroSettings::default());
+        assert!(settings.macros.enabled);
+
+        let mut settings = GameSettings::default();
+        let opener = infinite_game::InputMacro {
+            name: "Opener".to_string(),
+            key: "KeyZ".to_string(),
+            steps: vec![
+                infinite_game::MacroStep { action: infinite_game::InputAction::Skill1, at: 0.0, hold: 0.1 },
+                infinite_game::MacroStep { action: infinite_game::InputAction::Attack, at: 0.25, hold: 0.5 },
+            ],
+        };
+        settings.macros.profile_mut("ada").add(opener.clone()).unwrap();
+        let restored: GameSettings = toml::from_str(&toml::to_string_pretty(&settings).unwrap()).unwrap();
+        assert_eq!(restored.macros, settings.macros);
+        assert_eq!(restored.macros.profile("ada").and_then(|set| set.find("KeyZ")), Some(&opener));
+        assert!(restored.macros.profile(MacroSettings::LOCAL_PROFILE).is_none());
+    }
+
+    #[test]
+    fn test_speedrun_route_round_trips() {
+        let mut settings = GameSettings::default();
+        assert!(!settings.speedrun.enabled);
+        settings.speedrun.enabled = true;
+        settings.speedrun.splits.push(Split {
+            name: "Home again".to_string(),
+            trigger: infinite_game::SplitTrigger::ReachYear(2025),
+        });
+        let restored: GameSettings = toml::from_str(&toml::to_string_pretty(&settings).unwrap()).unwrap();
+        assert_eq!(restored.speedrun, settings.speedrun);
     }
 }
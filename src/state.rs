@@ -89,9 +89,10 @@ impl LoadingPhase {
 }
 
 /// State transition commands
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum StateTransition {
     /// No transition
+    #[default]
     None,
     /// Push a new state onto the stack (for menus that return)
     Push(ApplicationState),
@@ -100,9 +101,3 @@ pub enum StateTransition {
     /// Replace the current state entirely
     Replace(ApplicationState),
 }
-
-impl Default for StateTransition {
-    fn default() -> Self {
-        Self::None
-    }
-}
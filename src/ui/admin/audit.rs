@@ -0,0 +1,110 @@
+//! Audit log — every server content change made this session
+
+use chrono::{DateTime, Local};
+use egui::{Color32, FontId, RichText, ScrollArea, Ui};
+
+use infinite_integration::IntegrationError;
+
+/// What kind of content a change touched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditKind {
+    Item,
+    Npc,
+}
+
+/// What was done to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Created,
+    Updated,
+    Deleted,
+    /// Spawned into the running game from a template
+    Spawned,
+}
+
+/// One change, and whether the server accepted it
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub at: DateTime<Local>,
+    pub kind: AuditKind,
+    pub action: AuditAction,
+    pub name: String,
+    /// The server's reasons, if it rejected the change
+    pub error: Option<String>,
+}
+
+/// Changes made through the admin tools, newest last. Kept for the whole
+/// session, across visits to the admin panel.
+#[derive(Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, kind: AuditKind, action: AuditAction, name: &str, error: Option<String>) {
+        match &error {
+            Some(e) => tracing::warn!("Admin {:?} {:?} '{}' failed: {}", kind, action, name, e),
+            None => tracing::info!("Admin {:?} {:?} '{}'", kind, action, name),
+        }
+        self.entries.push(AuditEntry {
+            at: Local::now(),
+            kind,
+            action,
+            name: name.to_string(),
+            error,
+        });
+    }
+
+    pub fn render(&self, ui: &mut Ui) {
+        ui.label(
+            RichText::new(format!("Changes this session ({})", self.entries.len()))
+                .font(FontId::proportional(18.0))
+                .color(Color32::from_rgb(180, 180, 220)),
+        );
+        ui.add_space(5.0);
+        if self.entries.is_empty() {
+            ui.label(RichText::new("Nothing changed yet").color(Color32::from_rgb(120, 120, 140)));
+            return;
+        }
+
+        ScrollArea::vertical().show(ui, |ui| {
+            for entry in self.entries.iter().rev() {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(entry.at.format("%H:%M:%S").to_string())
+                            .font(FontId::monospace(12.0))
+                            .color(Color32::from_rgb(120, 120, 140)),
+                    );
+                    let (status, color) = match &entry.error {
+                        None => ("ok", Color32::from_rgb(100, 220, 100)),
+                        Some(_) => ("rejected", Color32::from_rgb(255, 100, 100)),
+                    };
+                    ui.label(RichText::new(status).font(FontId::monospace(12.0)).color(color));
+                    ui.label(format!("{:?} {:?}: {}", entry.kind, entry.action, entry.name));
+                });
+                if let Some(error) = &entry.error {
+                    ui.label(
+                        RichText::new(format!("    {}", error))
+                            .font(FontId::proportional(12.0))
+                            .color(Color32::from_rgb(200, 130, 130)),
+                    );
+                }
+            }
+        });
+    }
+}
+
+/// A failed request as shown to admins: the server's validation messages
+/// when it gave any, otherwise the error itself
+pub fn failure_text(error: &IntegrationError) -> String {
+    let messages = error.validation_messages();
+    if messages.is_empty() {
+        error.to_string()
+    } else {
+        messages.join("; ")
+    }
+}
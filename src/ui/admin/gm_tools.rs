@@ -0,0 +1,243 @@
+//! GM tools — in-game window for admins: free flight, invisibility,
+//! teleporting around a map, and spawning things at the crosshair
+
+use egui::{Align2, Color32, FontId, RichText, Sense, Stroke, Vec2};
+use glam::Vec3;
+
+use infinite_game::combat::ItemCatalog;
+use infinite_game::{CompassMarker, NpcRole};
+
+use crate::ui::compass::marker_color;
+
+/// Half the side of the map square in points
+const MAP_HALF: f32 = 120.0;
+
+const NPC_ROLES: [NpcRole; 5] = [
+    NpcRole::Villager,
+    NpcRole::Guard,
+    NpcRole::Shopkeeper,
+    NpcRole::QuestGiver,
+    NpcRole::Enemy,
+];
+
+/// World objects a GM can place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GmObject {
+    Sign,
+    Container,
+    Door,
+    Button,
+    TrainingDummy,
+}
+
+impl GmObject {
+    pub const ALL: [GmObject; 5] = [Self::Sign, Self::Container, Self::Door, Self::Button, Self::TrainingDummy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sign => "Sign",
+            Self::Container => "Container",
+            Self::Door => "Door",
+            Self::Button => "Button",
+            Self::TrainingDummy => "Training Dummy",
+        }
+    }
+}
+
+/// Something to place at the crosshair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GmSpawn {
+    /// Catalog item at this index, dropped as a pickup
+    Item(usize),
+    Npc(NpcRole),
+    Object(GmObject),
+}
+
+/// Choice made in the GM tools window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GmAction {
+    /// Free flight was switched on or off
+    SetNoclip(bool),
+    /// Move the player to this spot (dropped onto the ground)
+    Teleport(Vec3),
+    Spawn(GmSpawn),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GmTab {
+    Map,
+    Spawn,
+}
+
+/// Toggleable window of GM tools. The noclip and invisibility switches
+/// stay on after the window closes.
+pub struct GmTools {
+    /// Whether the window is open
+    pub visible: bool,
+    /// Whether free flight is on
+    pub noclip: bool,
+    /// Whether NPCs are blind to the player
+    pub invisible: bool,
+    /// World distance (meters) from the map centre to its edge
+    map_range: f32,
+    tab: GmTab,
+}
+
+impl GmTools {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            noclip: false,
+            invisible: false,
+            map_range: 200.0,
+            tab: GmTab::Map,
+        }
+    }
+
+    /// Draw the window if visible. The map is north-up around `player_pos`;
+    /// clicking it teleports there.
+    pub fn render<'a>(
+        &mut self,
+        ctx: &egui::Context,
+        player_pos: Vec3,
+        waypoint: Option<Vec3>,
+        markers: impl IntoIterator<Item = &'a CompassMarker>,
+        catalog: Option<&ItemCatalog>,
+    ) -> Option<GmAction> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = self.visible;
+        egui::Window::new("GM Tools")
+            .open(&mut open)
+            .anchor(Align2::LEFT_TOP, [10.0, 60.0])
+            .resizable(false)
+            .collapsible(true)
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut self.noclip, "Noclip").changed() {
+                        action = Some(GmAction::SetNoclip(self.noclip));
+                    }
+                    ui.checkbox(&mut self.invisible, "Invisible to NPCs");
+                });
+                ui.label(
+                    RichText::new("Noclip: Jump rises, Crouch sinks, Sprint speeds up")
+                        .size(11.0)
+                        .color(Color32::from_rgb(120, 120, 140)),
+                );
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.tab, GmTab::Map, "Map");
+                    ui.selectable_value(&mut self.tab, GmTab::Spawn, "Spawn");
+                });
+                ui.separator();
+
+                match self.tab {
+                    GmTab::Map => {
+                        if let Some(target) = self.render_map(ui, player_pos, markers) {
+                            action = Some(GmAction::Teleport(target));
+                        }
+                        if ui.add_enabled(waypoint.is_some(), egui::Button::new("Teleport to waypoint")).clicked() {
+                            action = waypoint.map(GmAction::Teleport);
+                        }
+                    }
+                    GmTab::Spawn => {
+                        if let Some(spawn) = render_spawn(ui, catalog) {
+                            action = Some(GmAction::Spawn(spawn));
+                        }
+                    }
+                }
+            });
+        self.visible = open;
+        action
+    }
+
+    /// Returns the ground position clicked, if any
+    fn render_map<'a>(
+        &mut self,
+        ui: &mut egui::Ui,
+        player_pos: Vec3,
+        markers: impl IntoIterator<Item = &'a CompassMarker>,
+    ) -> Option<Vec3> {
+        ui.add(egui::Slider::new(&mut self.map_range, 50.0..=1000.0).text("Range (m)"));
+        let (rect, response) = ui.allocate_exact_size(Vec2::splat(MAP_HALF * 2.0), Sense::click());
+        let painter = ui.painter_at(rect);
+        let center = rect.center();
+        let scale = MAP_HALF / self.map_range;
+        painter.rect_filled(rect, 2.0, Color32::from_rgba_unmultiplied(0, 0, 0, 180));
+        painter.rect_stroke(rect, 2.0, Stroke::new(1.0, Color32::from_gray(120)), egui::StrokeKind::Inside);
+        painter.text(
+            rect.center_top() + Vec2::new(0.0, 8.0),
+            Align2::CENTER_CENTER,
+            "N",
+            FontId::proportional(11.0),
+            Color32::from_rgb(255, 120, 100),
+        );
+
+        // North-up: world +X is right, world -Z is up
+        for marker in markers {
+            let offset = marker.position - player_pos;
+            let pos = center + Vec2::new(offset.x, offset.z) * scale;
+            if rect.contains(pos) {
+                painter.circle_filled(pos, 3.0, marker_color(marker.category));
+            }
+        }
+        painter.circle_filled(center, 4.0, Color32::from_rgb(255, 220, 120));
+
+        let hover = response.hover_pos().map(|pos| (pos - center) / scale);
+        if let Some(offset) = hover {
+            response.clone().on_hover_text(format!("Teleport {:.0} m here", offset.length()));
+        }
+        if response.clicked() {
+            hover.map(|offset| player_pos + Vec3::new(offset.x, 0.0, offset.y))
+        } else {
+            None
+        }
+    }
+}
+
+fn render_spawn(ui: &mut egui::Ui, catalog: Option<&ItemCatalog>) -> Option<GmSpawn> {
+    let mut spawn = None;
+    ui.label(RichText::new("Placed where the crosshair points").size(11.0).color(Color32::from_rgb(120, 120, 140)));
+
+    ui.collapsing("NPCs", |ui| {
+        ui.horizontal_wrapped(|ui| {
+            for role in NPC_ROLES {
+                if ui.button(role.name()).clicked() {
+                    spawn = Some(GmSpawn::Npc(role));
+                }
+            }
+        });
+    });
+
+    ui.collapsing("Objects", |ui| {
+        ui.horizontal_wrapped(|ui| {
+            for object in GmObject::ALL {
+                if ui.button(object.name()).clicked() {
+                    spawn = Some(GmSpawn::Object(object));
+                }
+            }
+        });
+    });
+
+    ui.collapsing("Items", |ui| match catalog.filter(|c| !c.is_empty()) {
+        Some(catalog) => {
+            egui::ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+                for (index, item) in catalog.items().iter().enumerate() {
+                    if ui.selectable_label(false, &item.name).clicked() {
+                        spawn = Some(GmSpawn::Item(index));
+                    }
+                }
+            });
+        }
+        None => {
+            ui.label(RichText::new("No item catalog loaded").color(Color32::from_rgb(120, 120, 140)));
+        }
+    });
+
+    spawn
+}
@@ -6,6 +6,8 @@ use infinite_integration::IntegrationClient;
 use infinite_integration::types::{ServerCharacterItem, ServerItemStats, GameItemCustomStats};
 use infinite_integration::PendingRequest;
 
+use super::audit::{failure_text, AuditAction, AuditKind, AuditLog};
+
 /// Item editor state
 pub struct ItemEditor {
     /// List of items fetched from server
@@ -22,6 +24,8 @@ pub struct ItemEditor {
     pending_save: Option<PendingRequest<ServerCharacterItem>>,
     /// Pending delete
     pending_delete: Option<PendingRequest<serde_json::Value>>,
+    /// What the pending save or delete does, for the audit log
+    pending_change: Option<(AuditAction, String)>,
     /// Status message
     status: Option<(String, bool)>, // (message, is_error)
 }
@@ -53,6 +57,7 @@ struct ItemForm {
     // Meta
     required_level: u32,
     item_level: u32,
+    origin_year: Option<i64>,
     is_available: bool,
     tags: String,
     // Server ID for updates
@@ -85,6 +90,7 @@ impl Default for ItemForm {
             tags: String::new(),
             required_level: 1,
             item_level: 1,
+            origin_year: None,
             item_id: String::new(),
         }
     }
@@ -123,6 +129,7 @@ impl ItemForm {
             tags: item.tags.join(", "),
             required_level: custom.required_level.unwrap_or(1),
             item_level: custom.item_level.unwrap_or(1),
+            origin_year: custom.origin_year,
             item_id: item.item_id.clone(),
         }
     }
@@ -162,6 +169,7 @@ impl ItemForm {
             item_level: Some(self.item_level),
             required_level: Some(self.required_level),
             game_category: Some(game_category.to_string()),
+            origin_year: self.origin_year,
         };
 
         let tags: Vec<String> = self.tags
@@ -219,6 +227,7 @@ impl ItemEditor {
             pending_list: None,
             pending_save: None,
             pending_delete: None,
+            pending_change: None,
             status: None,
         }
     }
@@ -229,9 +238,9 @@ impl ItemEditor {
     }
 
     /// Main render
-    pub fn render(&mut self, ui: &mut Ui, integration_client: Option<&IntegrationClient>) {
+    pub fn render(&mut self, ui: &mut Ui, integration_client: Option<&IntegrationClient>, audit: &mut AuditLog) {
         // Poll pending operations
-        self.poll_pending(integration_client);
+        self.poll_pending(integration_client, audit);
 
         ui.horizontal(|ui| {
             // Left panel: item list
@@ -449,6 +458,18 @@ impl ItemEditor {
                     ui.add(egui::DragValue::new(&mut il).range(1..=100));
                     self.form.item_level = il.max(1) as u32;
                 });
+                ui.horizontal(|ui| {
+                    let mut dated = self.form.origin_year.is_some();
+                    ui.checkbox(&mut dated, "Origin Year:");
+                    match (dated, self.form.origin_year) {
+                        (true, Some(mut year)) => {
+                            ui.add(egui::DragValue::new(&mut year).range(-10000..=10000));
+                            self.form.origin_year = Some(year);
+                        }
+                        (true, None) => self.form.origin_year = Some(0),
+                        (false, _) => self.form.origin_year = None,
+                    }
+                });
                 form_field(ui, "Tags (comma-separated)", &mut self.form.tags);
                 ui.checkbox(&mut self.form.is_available, "Published");
             });
@@ -472,6 +493,8 @@ impl ItemEditor {
             if ui.add(save_btn).clicked() && !is_busy {
                 if let Some(client) = integration_client {
                     let item = self.form.to_server_item("6981e8eda259e89734bd007a");
+                    let action = if self.creating_new { AuditAction::Created } else { AuditAction::Updated };
+                    self.pending_change = Some((action, self.form.name.clone()));
                     if self.creating_new {
                         self.pending_save = Some(client.create_item(item));
                     } else {
@@ -504,6 +527,7 @@ impl ItemEditor {
 
                 if ui.add(del_btn).clicked() && !is_busy {
                     if let Some(client) = integration_client {
+                        self.pending_change = Some((AuditAction::Deleted, self.form.name.clone()));
                         self.pending_delete = Some(client.delete_item(&self.form.item_id));
                     }
                 }
@@ -511,7 +535,7 @@ impl ItemEditor {
         });
     }
 
-    fn poll_pending(&mut self, integration_client: Option<&IntegrationClient>) {
+    fn poll_pending(&mut self, integration_client: Option<&IntegrationClient>, audit: &mut AuditLog) {
         // Poll list
         if let Some(pending) = &self.pending_list {
             if let Some(result) = pending.try_recv() {
@@ -531,6 +555,9 @@ impl ItemEditor {
         // Poll save
         if let Some(pending) = &self.pending_save {
             if let Some(result) = pending.try_recv() {
+                if let Some((action, name)) = self.pending_change.take() {
+                    audit.record(AuditKind::Item, action, &name, result.as_ref().err().map(failure_text));
+                }
                 match result {
                     Ok(_item) => {
                         self.status = Some(("Item saved!".to_string(), false));
@@ -542,7 +569,7 @@ impl ItemEditor {
                         }
                     }
                     Err(e) => {
-                        self.status = Some((format!("Save failed: {}", e), true));
+                        self.status = Some((format!("Save failed: {}", failure_text(&e)), true));
                     }
                 }
                 self.pending_save = None;
@@ -552,6 +579,9 @@ impl ItemEditor {
         // Poll delete
         if let Some(pending) = &self.pending_delete {
             if let Some(result) = pending.try_recv() {
+                if let Some((action, name)) = self.pending_change.take() {
+                    audit.record(AuditKind::Item, action, &name, result.as_ref().err().map(failure_text));
+                }
                 match result {
                     Ok(_) => {
                         self.status = Some(("Item deleted".to_string(), false));
@@ -562,7 +592,7 @@ impl ItemEditor {
                         }
                     }
                     Err(e) => {
-                        self.status = Some((format!("Delete failed: {}", e), true));
+                        self.status = Some((format!("Delete failed: {}", failure_text(&e)), true));
                     }
                 }
                 self.pending_delete = None;
@@ -1,8 +1,17 @@
-//! Admin panel — tabs for Items and Stories (admin-only)
+//! Admin panel — tabs for Items, NPCs, Stories and the session's audit
+//! log (admin-only)
 
+mod audit;
+mod gm_tools;
 mod item_editor;
+mod npc_editor;
+mod spawner;
 mod story_editor;
 
+pub use audit::{AuditAction, AuditKind, AuditLog};
+pub use gm_tools::{GmAction, GmObject, GmSpawn, GmTools};
+pub use spawner::TemplateSpawner;
+
 use egui::{Align, Color32, FontId, Layout, RichText, Ui, Vec2};
 
 use infinite_integration::IntegrationClient;
@@ -10,19 +19,23 @@ use infinite_integration::IntegrationClient;
 use crate::state::{ApplicationState, StateTransition};
 
 use item_editor::ItemEditor;
+use npc_editor::NpcEditor;
 use story_editor::StoryEditor;
 
 /// Which admin tab is selected
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AdminTab {
     Items,
+    Npcs,
     Stories,
+    Audit,
 }
 
 /// Admin panel with tabbed sub-editors
 pub struct AdminPanel {
     tab: AdminTab,
     item_editor: ItemEditor,
+    npc_editor: NpcEditor,
     story_editor: StoryEditor,
     initialized: bool,
 }
@@ -32,16 +45,19 @@ impl AdminPanel {
         Self {
             tab: AdminTab::Items,
             item_editor: ItemEditor::new(),
+            npc_editor: NpcEditor::new(),
             story_editor: StoryEditor::new(),
             initialized: false,
         }
     }
 
-    /// Render the admin panel and return any state transition
+    /// Render the admin panel and return any state transition. Changes
+    /// sent to the server are recorded in `audit`.
     pub fn render(
         &mut self,
         ui: &mut Ui,
         integration_client: Option<&IntegrationClient>,
+        audit: &mut AuditLog,
     ) -> StateTransition {
         let mut transition = StateTransition::None;
 
@@ -49,6 +65,7 @@ impl AdminPanel {
         if !self.initialized {
             if let Some(client) = integration_client {
                 self.item_editor.refresh_items(client);
+                self.npc_editor.refresh_templates(client);
                 self.story_editor.refresh_stories(client);
             }
             self.initialized = true;
@@ -89,7 +106,9 @@ impl AdminPanel {
             ui.horizontal(|ui| {
                 let tabs = [
                     (AdminTab::Items, "Items"),
+                    (AdminTab::Npcs, "NPCs"),
                     (AdminTab::Stories, "Stories"),
+                    (AdminTab::Audit, "Audit Log"),
                 ];
                 for (tab, label) in &tabs {
                     let selected = self.tab == *tab;
@@ -121,8 +140,10 @@ impl AdminPanel {
             let remaining = ui.available_size();
             ui.allocate_ui(remaining, |ui| {
                 match self.tab {
-                    AdminTab::Items => self.item_editor.render(ui, integration_client),
+                    AdminTab::Items => self.item_editor.render(ui, integration_client, audit),
+                    AdminTab::Npcs => self.npc_editor.render(ui, integration_client, audit),
                     AdminTab::Stories => self.story_editor.render(ui, integration_client),
+                    AdminTab::Audit => audit.render(ui),
                 }
             });
         });
@@ -0,0 +1,342 @@
+//! NPC editor — list + create/edit form for server-backed NPC character
+//! templates (the characters AI dialogue speaks as)
+
+use egui::{Color32, FontId, RichText, ScrollArea, Ui, Vec2};
+
+use infinite_integration::types::{CharacterLore, CreateCharacterRequest, ServerCharacter};
+use infinite_integration::{IntegrationClient, PendingRequest};
+
+use super::audit::{failure_text, AuditAction, AuditKind, AuditLog};
+
+/// NPC templates among the account's characters (player characters carry a
+/// progress snapshot; templates don't)
+pub fn npc_templates(characters: Vec<ServerCharacter>) -> Vec<ServerCharacter> {
+    characters.into_iter().filter(|c| c.snapshot.is_none()).collect()
+}
+
+/// NPC template editor state
+pub struct NpcEditor {
+    /// Templates fetched from the server
+    templates: Vec<ServerCharacter>,
+    /// Currently selected template index (None = creating new)
+    selected_index: Option<usize>,
+    /// Editable form fields
+    form: NpcForm,
+    /// Whether we're in "create new" mode
+    creating_new: bool,
+    pending_list: Option<PendingRequest<Vec<ServerCharacter>>>,
+    pending_save: Option<PendingRequest<ServerCharacter>>,
+    pending_delete: Option<PendingRequest<serde_json::Value>>,
+    /// What the pending save or delete does, for the audit log
+    pending_change: Option<(AuditAction, String)>,
+    /// Status message
+    status: Option<(String, bool)>, // (message, is_error)
+}
+
+/// Editable form for a template
+#[derive(Default)]
+struct NpcForm {
+    name: String,
+    system_prompt: String,
+    backstory: String,
+    personality: String,
+    occupation: String,
+    era: String,
+    // Server ID for updates
+    character_id: String,
+}
+
+impl NpcForm {
+    fn from_server_character(character: &ServerCharacter) -> Self {
+        let lore = character.lore.clone();
+        Self {
+            name: character.name.clone(),
+            system_prompt: character.system_prompt.clone(),
+            backstory: lore.as_ref().map(|l| l.backstory.clone()).unwrap_or_default(),
+            personality: lore.as_ref().map(|l| l.personality.clone()).unwrap_or_default(),
+            occupation: lore.as_ref().map(|l| l.occupation.clone()).unwrap_or_default(),
+            era: lore.map(|l| l.era).unwrap_or_default(),
+            character_id: character.id.clone(),
+        }
+    }
+
+    fn to_request(&self) -> CreateCharacterRequest {
+        let has_lore = [&self.backstory, &self.personality, &self.occupation, &self.era]
+            .iter()
+            .any(|s| !s.trim().is_empty());
+        CreateCharacterRequest {
+            name: self.name.trim().to_string(),
+            system_prompt: self.system_prompt.clone(),
+            lore: has_lore.then(|| CharacterLore {
+                backstory: self.backstory.clone(),
+                personality: self.personality.clone(),
+                occupation: self.occupation.clone(),
+                era: self.era.clone(),
+            }),
+            client_id: None,
+            snapshot: None,
+        }
+    }
+}
+
+impl NpcEditor {
+    pub fn new() -> Self {
+        Self {
+            templates: Vec::new(),
+            selected_index: None,
+            form: NpcForm::default(),
+            creating_new: false,
+            pending_list: None,
+            pending_save: None,
+            pending_delete: None,
+            pending_change: None,
+            status: None,
+        }
+    }
+
+    /// Trigger a refresh of the template list from the server
+    pub fn refresh_templates(&mut self, client: &IntegrationClient) {
+        self.pending_list = Some(client.list_characters());
+    }
+
+    /// Main render
+    pub fn render(&mut self, ui: &mut Ui, integration_client: Option<&IntegrationClient>, audit: &mut AuditLog) {
+        self.poll_pending(integration_client, audit);
+
+        ui.horizontal(|ui| {
+            // Left panel: template list
+            let list_width = 250.0;
+            ui.vertical(|ui| {
+                ui.set_width(list_width);
+                ui.set_min_height(ui.available_height());
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new("NPC Templates")
+                            .font(FontId::proportional(18.0))
+                            .color(Color32::from_rgb(180, 180, 220)),
+                    );
+
+                    if let Some(client) = integration_client {
+                        if ui.small_button("Refresh").clicked() {
+                            self.refresh_templates(client);
+                        }
+                    }
+                });
+
+                ui.add_space(5.0);
+
+                let new_btn = egui::Button::new(
+                    RichText::new("+ New NPC")
+                        .font(FontId::proportional(14.0))
+                        .color(Color32::from_rgb(100, 255, 100)),
+                )
+                .min_size(Vec2::new(list_width - 10.0, 28.0))
+                .fill(Color32::from_rgb(40, 60, 40))
+                .stroke(egui::Stroke::new(1.0, Color32::from_rgb(60, 100, 60)));
+
+                if ui.add(new_btn).clicked() {
+                    self.form = NpcForm::default();
+                    self.selected_index = None;
+                    self.creating_new = true;
+                }
+
+                ui.add_space(5.0);
+                ui.separator();
+
+                ScrollArea::vertical()
+                    .max_height(ui.available_height() - 10.0)
+                    .show(ui, |ui| {
+                        let mut clicked_index = None;
+                        for (i, template) in self.templates.iter().enumerate() {
+                            let selected = self.selected_index == Some(i);
+                            let occupation = template.lore.as_ref().map_or("", |l| l.occupation.as_str());
+                            let text = if occupation.is_empty() {
+                                template.name.clone()
+                            } else {
+                                format!("{} ({})", template.name, occupation)
+                            };
+                            if ui.selectable_label(selected, text).clicked() {
+                                clicked_index = Some(i);
+                            }
+                        }
+
+                        if let Some(i) = clicked_index {
+                            self.selected_index = Some(i);
+                            self.creating_new = false;
+                            self.form = NpcForm::from_server_character(&self.templates[i]);
+                        }
+                    });
+            });
+
+            ui.separator();
+
+            // Right panel: edit form
+            ui.vertical(|ui| {
+                if self.creating_new || self.selected_index.is_some() {
+                    self.render_form(ui, integration_client);
+                } else {
+                    ui.add_space(100.0);
+                    ui.vertical_centered(|ui| {
+                        ui.label(
+                            RichText::new("Select an NPC or create a new one")
+                                .font(FontId::proportional(16.0))
+                                .color(Color32::from_rgb(120, 120, 140)),
+                        );
+                    });
+                }
+            });
+        });
+    }
+
+    fn render_form(&mut self, ui: &mut Ui, integration_client: Option<&IntegrationClient>) {
+        let title = if self.creating_new { "New NPC" } else { "Edit NPC" };
+        ui.label(
+            RichText::new(title)
+                .font(FontId::proportional(20.0))
+                .color(Color32::from_rgb(200, 200, 255)),
+        );
+
+        if let Some((msg, is_err)) = &self.status {
+            let color = if *is_err {
+                Color32::from_rgb(255, 100, 100)
+            } else {
+                Color32::from_rgb(100, 255, 100)
+            };
+            ui.label(RichText::new(msg).font(FontId::proportional(13.0)).color(color));
+        }
+
+        ui.add_space(10.0);
+
+        ScrollArea::vertical()
+            .max_height(ui.available_height() - 50.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.form.name);
+                });
+                ui.label("System prompt:");
+                ui.add(egui::TextEdit::multiline(&mut self.form.system_prompt).desired_rows(5).desired_width(f32::INFINITY));
+
+                ui.add_space(10.0);
+                ui.label(
+                    RichText::new("Lore")
+                        .font(FontId::proportional(16.0))
+                        .color(Color32::from_rgb(180, 180, 220)),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Occupation:");
+                    ui.text_edit_singleline(&mut self.form.occupation);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Era:");
+                    ui.text_edit_singleline(&mut self.form.era);
+                });
+                ui.label("Personality:");
+                ui.add(egui::TextEdit::multiline(&mut self.form.personality).desired_rows(2).desired_width(f32::INFINITY));
+                ui.label("Backstory:");
+                ui.add(egui::TextEdit::multiline(&mut self.form.backstory).desired_rows(4).desired_width(f32::INFINITY));
+            });
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            let is_busy = self.pending_save.is_some() || self.pending_delete.is_some();
+
+            let save_label = if is_busy { "Saving..." } else if self.creating_new { "Create" } else { "Save" };
+            if ui.add(egui::Button::new(save_label).min_size(Vec2::new(100.0, 32.0))).clicked() && !is_busy {
+                if self.form.name.trim().is_empty() {
+                    self.status = Some(("An NPC needs a name".to_string(), true));
+                } else if let Some(client) = integration_client {
+                    let request = self.form.to_request();
+                    let action = if self.creating_new { AuditAction::Created } else { AuditAction::Updated };
+                    self.pending_change = Some((action, request.name.clone()));
+                    self.pending_save = Some(if self.creating_new {
+                        client.create_character(request)
+                    } else {
+                        client.update_character(&self.form.character_id, request)
+                    });
+                }
+            }
+
+            if ui.button("Cancel").clicked() {
+                self.selected_index = None;
+                self.creating_new = false;
+                self.status = None;
+            }
+
+            if !self.creating_new && self.selected_index.is_some() {
+                ui.add_space(20.0);
+                let del_btn = egui::Button::new(RichText::new("Delete").color(Color32::from_rgb(255, 150, 150)))
+                    .min_size(Vec2::new(80.0, 32.0))
+                    .fill(Color32::from_rgb(80, 30, 30));
+                if ui.add(del_btn).clicked() && !is_busy {
+                    if let Some(client) = integration_client {
+                        self.pending_change = Some((AuditAction::Deleted, self.form.name.clone()));
+                        self.pending_delete = Some(client.delete_character(&self.form.character_id));
+                    }
+                }
+            }
+        });
+    }
+
+    fn poll_pending(&mut self, integration_client: Option<&IntegrationClient>, audit: &mut AuditLog) {
+        if let Some(pending) = &self.pending_list {
+            if let Some(result) = pending.try_recv() {
+                match result {
+                    Ok(characters) => {
+                        self.templates = npc_templates(characters);
+                        self.status = Some((format!("Loaded {} NPC templates", self.templates.len()), false));
+                    }
+                    Err(e) => {
+                        self.status = Some((format!("Failed to load NPCs: {}", e), true));
+                    }
+                }
+                self.pending_list = None;
+            }
+        }
+
+        if let Some(pending) = &self.pending_save {
+            if let Some(result) = pending.try_recv() {
+                if let Some((action, name)) = self.pending_change.take() {
+                    audit.record(AuditKind::Npc, action, &name, result.as_ref().err().map(failure_text));
+                }
+                match result {
+                    Ok(_) => {
+                        self.status = Some(("NPC saved!".to_string(), false));
+                        self.creating_new = false;
+                        self.selected_index = None;
+                        if let Some(client) = integration_client {
+                            self.refresh_templates(client);
+                        }
+                    }
+                    Err(e) => {
+                        self.status = Some((format!("Save failed: {}", failure_text(&e)), true));
+                    }
+                }
+                self.pending_save = None;
+            }
+        }
+
+        if let Some(pending) = &self.pending_delete {
+            if let Some(result) = pending.try_recv() {
+                if let Some((action, name)) = self.pending_change.take() {
+                    audit.record(AuditKind::Npc, action, &name, result.as_ref().err().map(failure_text));
+                }
+                match result {
+                    Ok(_) => {
+                        self.status = Some(("NPC deleted".to_string(), false));
+                        self.selected_index = None;
+                        self.creating_new = false;
+                        if let Some(client) = integration_client {
+                            self.refresh_templates(client);
+                        }
+                    }
+                    Err(e) => {
+                        self.status = Some((format!("Delete failed: {}", failure_text(&e)), true));
+                    }
+                }
+                self.pending_delete = None;
+            }
+        }
+    }
+}
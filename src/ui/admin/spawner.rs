@@ -0,0 +1,130 @@
+//! Template spawner — in-game window for admins to place NPCs made from
+//! server templates next to the player
+
+use egui::{Color32, RichText};
+
+use infinite_game::NpcRole;
+use infinite_integration::types::ServerCharacter;
+use infinite_integration::{IntegrationClient, PendingRequest};
+
+use super::npc_editor::npc_templates;
+
+const ROLES: [NpcRole; 5] = [
+    NpcRole::Villager,
+    NpcRole::Guard,
+    NpcRole::Shopkeeper,
+    NpcRole::QuestGiver,
+    NpcRole::Enemy,
+];
+
+/// Role a template most likely plays, from its occupation
+fn guess_role(template: &ServerCharacter) -> NpcRole {
+    let occupation = template.lore.as_ref().map(|l| l.occupation.to_lowercase()).unwrap_or_default();
+    let has = |words: &[&str]| words.iter().any(|w| occupation.contains(w));
+    if has(&["guard", "soldier", "watch", "knight"]) {
+        NpcRole::Guard
+    } else if has(&["merchant", "shop", "trader", "vendor"]) {
+        NpcRole::Shopkeeper
+    } else if has(&["elder", "sage", "oracle", "seer", "quest"]) {
+        NpcRole::QuestGiver
+    } else if has(&["bandit", "raider", "monster", "enemy"]) {
+        NpcRole::Enemy
+    } else {
+        NpcRole::Villager
+    }
+}
+
+/// Toggleable window listing NPC templates with a Spawn button each
+pub struct TemplateSpawner {
+    /// Whether the window is open
+    pub visible: bool,
+    templates: Vec<(ServerCharacter, NpcRole)>,
+    pending_list: Option<PendingRequest<Vec<ServerCharacter>>>,
+    status: Option<String>,
+}
+
+impl TemplateSpawner {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            templates: Vec::new(),
+            pending_list: None,
+            status: None,
+        }
+    }
+
+    /// Draw the window if visible; returns a template to spawn and its role
+    pub fn render(&mut self, ctx: &egui::Context, client: &IntegrationClient) -> Option<(ServerCharacter, NpcRole)> {
+        if !self.visible {
+            return None;
+        }
+
+        if let Some(pending) = &self.pending_list {
+            if let Some(result) = pending.try_recv() {
+                match result {
+                    Ok(characters) => {
+                        self.templates = npc_templates(characters)
+                            .into_iter()
+                            .map(|t| {
+                                let role = guess_role(&t);
+                                (t, role)
+                            })
+                            .collect();
+                        self.status = None;
+                    }
+                    Err(e) => self.status = Some(format!("Failed to load NPCs: {}", e)),
+                }
+                self.pending_list = None;
+            }
+        } else if self.templates.is_empty() && self.status.is_none() {
+            self.pending_list = Some(client.list_characters());
+        }
+
+        let mut spawn = None;
+        let mut open = self.visible;
+        egui::Window::new("Spawn NPC")
+            .open(&mut open)
+            .anchor(egui::Align2::RIGHT_TOP, [-10.0, 60.0])
+            .resizable(false)
+            .collapsible(true)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.small_button("Refresh").clicked() && self.pending_list.is_none() {
+                        self.pending_list = Some(client.list_characters());
+                    }
+                    if self.pending_list.is_some() {
+                        ui.label("Loading...");
+                    }
+                });
+                if let Some(status) = &self.status {
+                    ui.label(RichText::new(status).color(Color32::from_rgb(255, 100, 100)));
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for (i, (template, role)) in self.templates.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(&template.name);
+                            egui::ComboBox::from_id_salt(("spawn_role", i))
+                                .selected_text(role.name())
+                                .width(90.0)
+                                .show_ui(ui, |ui| {
+                                    for option in ROLES {
+                                        ui.selectable_value(role, option, option.name());
+                                    }
+                                });
+                            if ui.button("Spawn").clicked() {
+                                spawn = Some((template.clone(), *role));
+                            }
+                        });
+                    }
+                    if self.templates.is_empty() && self.pending_list.is_none() {
+                        ui.label(RichText::new("No NPC templates yet").color(Color32::from_rgb(120, 120, 140)));
+                    }
+                });
+            });
+        self.visible = open;
+        spawn
+    }
+}
@@ -0,0 +1,119 @@
+//! Balance panel: sliders for every tunable, opened from the debug overlay
+
+use egui::{Color32, RichText};
+use infinite_core::{LoadReport, Tunable, TunableError, TunableRegistry};
+
+/// Window for retuning game balance while playing
+pub struct BalancePanel {
+    pub open: bool,
+    /// Outcome of the last save or reload, and whether it failed
+    status: Option<(String, bool)>,
+}
+
+impl BalancePanel {
+    pub fn new() -> Self {
+        Self { open: false, status: None }
+    }
+
+    /// Note a reload that happened outside the panel (the file was saved)
+    pub fn reloaded(&mut self, result: &Result<LoadReport, TunableError>) {
+        self.status = Some(match result {
+            Ok(report) => (describe(report), false),
+            Err(e) => (e.to_string(), true),
+        });
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, registry: &mut TunableRegistry) {
+        if !self.open {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Balance")
+            .id(egui::Id::new("balance_panel"))
+            .open(&mut open)
+            .default_pos([320.0, 80.0])
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                // Tunables come sorted by name, so each group is a run
+                let mut groups: Vec<(&str, Vec<&'static Tunable>)> = Vec::new();
+                for tunable in registry.iter() {
+                    let group = tunable.name().split_once('.').map_or("", |(group, _)| group);
+                    match groups.last_mut() {
+                        Some((name, members)) if *name == group => members.push(tunable),
+                        _ => groups.push((group, vec![tunable])),
+                    }
+                }
+
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    for (group, members) in groups {
+                        egui::CollapsingHeader::new(group).default_open(true).show(ui, |ui| {
+                            egui::Grid::new(("balance", group)).num_columns(3).show(ui, |ui| {
+                                for tunable in members {
+                                    tunable_row(ui, tunable);
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let file = registry.path().map(|path| path.display().to_string()).unwrap_or_default();
+                    if ui.button("Save").on_hover_text(format!("Write these values to {}", file)).clicked() {
+                        self.status = Some(match registry.save() {
+                            Ok(()) => (format!("Saved to {}", file), false),
+                            Err(e) => (e.to_string(), true),
+                        });
+                    }
+                    if ui.button("Reload").on_hover_text("Discard changes since the last save").clicked() {
+                        let result = registry.reload();
+                        self.reloaded(&result);
+                    }
+                    if ui.button("Defaults").clicked() {
+                        registry.reset_all();
+                        self.status = None;
+                    }
+                    ui.checkbox(&mut registry.hot_reload, "Hot reload")
+                        .on_hover_text("Pick up changes when the file is saved in an editor");
+                });
+                if let Some((status, failed)) = &self.status {
+                    let color = if *failed { Color32::from_rgb(230, 110, 90) } else { Color32::GRAY };
+                    ui.label(RichText::new(status).color(color));
+                }
+            });
+        if !open {
+            self.open = false;
+        }
+    }
+}
+
+fn tunable_row(ui: &mut egui::Ui, tunable: &Tunable) {
+    let key = tunable.name().rsplit('.').next().unwrap_or(tunable.name());
+    let label = if tunable.is_default() { RichText::new(key) } else { RichText::new(key).strong() };
+    ui.label(label).on_hover_text(tunable.description());
+
+    let (min, max) = tunable.range();
+    let mut value = tunable.get();
+    let response = ui
+        .add(egui::Slider::new(&mut value, min..=max))
+        .on_hover_text(format!("{}\nDefault: {}", tunable.description(), tunable.default_value()));
+    if response.changed() {
+        tunable.set(value);
+    }
+
+    if tunable.is_default() {
+        ui.label("");
+    } else if ui.small_button("Reset").on_hover_text("Back to the default").clicked() {
+        tunable.reset();
+    }
+}
+
+fn describe(report: &LoadReport) -> String {
+    if report.unknown.is_empty() {
+        format!("Loaded {} values", report.applied)
+    } else {
+        format!("Loaded {} values; unknown: {}", report.applied, report.unknown.join(", "))
+    }
+}
@@ -0,0 +1,26 @@
+//! Ambient NPC barks floating over their heads
+
+use egui::{Align2, Color32, FontId, Id, LayerId, Order, Pos2, Vec2};
+use glam::Vec3;
+use infinite_game::{BarkSystem, BarkTrigger};
+
+const FONT_SIZE: f32 = 14.0;
+
+/// Draw every floating bark; `project` maps a world position to the screen
+pub fn draw_barks(ctx: &egui::Context, barks: &BarkSystem, project: impl Fn(Vec3) -> Option<Pos2>) {
+    let painter = ctx.layer_painter(LayerId::new(Order::Background, Id::new("barks")));
+    for bark in barks.barks() {
+        let Some(pos) = project(bark.position) else {
+            continue;
+        };
+        let alpha = bark.alpha();
+        let color = match bark.trigger {
+            BarkTrigger::Warning => Color32::from_rgb(255, 130, 90),
+            _ => Color32::from_rgb(240, 236, 220),
+        };
+        let galley = painter.layout_no_wrap(bark.text.clone(), FontId::proportional(FONT_SIZE), color.gamma_multiply(alpha));
+        let rect = Align2::CENTER_BOTTOM.anchor_size(pos, galley.size()).expand2(Vec2::new(6.0, 3.0));
+        painter.rect_filled(rect, 4.0, Color32::from_black_alpha((alpha * 150.0) as u8));
+        painter.galley(rect.min + Vec2::new(6.0, 3.0), galley, Color32::WHITE);
+    }
+}
@@ -0,0 +1,153 @@
+//! Brewing menu: pick reagents at an alchemy table and brew a potion
+//!
+//! Reagents show the effects found so far, so the player can aim for a
+//! shared one; unknown effects show as "?". Brews written in the journal
+//! can be made again in one click. Harmful brews can be bottled as weapon
+//! coatings instead of potions.
+
+use egui::{Color32, RichText};
+
+use infinite_game::alchemy::{brew_effects, coatings_from, count_reagent, MAX_BREW_REAGENTS};
+use infinite_game::{AlchemyEffect, AlchemyJournal, Inventory, Reagent};
+
+/// Choice made in the brewing menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrewingAction {
+    /// Brew these reagents, as weapon coatings if `coating`
+    Brew { reagents: Vec<Reagent>, coating: bool },
+    /// Eat one to learn an effect
+    Taste(Reagent),
+    Close,
+}
+
+/// Window for brewing, opened at an alchemy table
+#[derive(Default)]
+pub struct BrewingMenu {
+    selected: Vec<Reagent>,
+    /// Bottle harmful effects as weapon coatings
+    coating: bool,
+}
+
+fn effect_color(effect: AlchemyEffect) -> Color32 {
+    let [r, g, b] = effect.color();
+    Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
+impl BrewingMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clear the selection once it has been brewed
+    pub fn clear_selection(&mut self) {
+        self.selected.clear();
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, inventory: &Inventory, journal: &AlchemyJournal, potency: f32) -> Option<BrewingAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Alchemy Table")
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!("Potency {:.2} (grows with maximum mana)", potency))
+                        .color(Color32::from_rgb(180, 180, 200)),
+                );
+                ui.separator();
+
+                ui.label(RichText::new("Reagents").strong());
+                let mut any = false;
+                for reagent in Reagent::ALL {
+                    let count = count_reagent(inventory, reagent);
+                    if count == 0 {
+                        self.selected.retain(|r| *r != reagent);
+                        continue;
+                    }
+                    any = true;
+                    ui.horizontal(|ui| {
+                        let mut picked = self.selected.contains(&reagent);
+                        let full = self.selected.len() >= MAX_BREW_REAGENTS;
+                        let toggle = ui.add_enabled(picked || !full, egui::Checkbox::new(&mut picked, format!("{} ({})", reagent.name(), count)));
+                        if toggle.changed() {
+                            if picked {
+                                self.selected.push(reagent);
+                            } else {
+                                self.selected.retain(|r| *r != reagent);
+                            }
+                        }
+                        for effect in journal.reagent_effects(reagent) {
+                            match effect {
+                                Some(effect) => ui.label(RichText::new(effect.name()).size(12.0).color(effect_color(effect))),
+                                None => ui.label(RichText::new("?").size(12.0).color(Color32::from_rgb(120, 120, 120))),
+                            };
+                        }
+                        if ui.small_button("Taste").on_hover_text("Eat one to learn an effect").clicked() {
+                            action = Some(BrewingAction::Taste(reagent));
+                        }
+                    });
+                }
+                if !any {
+                    ui.label(
+                        RichText::new("You have no reagents. Gather herbs or buy them from merchants.")
+                            .color(Color32::from_rgb(180, 180, 200)),
+                    );
+                }
+
+                ui.separator();
+                let names: Vec<&str> = self.selected.iter().map(|r| r.name()).collect();
+                ui.label(format!(
+                    "Brewing ({}/{}): {}",
+                    self.selected.len(),
+                    MAX_BREW_REAGENTS,
+                    if names.is_empty() { "nothing yet".to_string() } else { names.join(" + ") }
+                ));
+                ui.checkbox(&mut self.coating, "Bottle as weapon coating")
+                    .on_hover_text("Harmful effects coat your weapon instead of filling a potion. Anything else is lost.");
+                // Only known recipes can be checked for harmful effects ahead of time
+                let mut key = self.selected.clone();
+                key.sort();
+                key.dedup();
+                let known = journal.recipes().iter().any(|r| r.reagents == key);
+                let coatable = !known || !coatings_from(&brew_effects(&self.selected)).is_empty();
+                if ui.add_enabled(self.selected.len() >= 2 && (!self.coating || coatable), egui::Button::new("Brew")).clicked() {
+                    action = Some(BrewingAction::Brew { reagents: self.selected.clone(), coating: self.coating });
+                }
+
+                ui.separator();
+                ui.label(RichText::new("Journal").strong());
+                if journal.recipes().is_empty() {
+                    ui.label(
+                        RichText::new("No brews yet. Reagents that share an effect brew into a potion.")
+                            .color(Color32::from_rgb(180, 180, 200)),
+                    );
+                }
+                for recipe in journal.recipes() {
+                    ui.horizontal(|ui| {
+                        let names: Vec<&str> = recipe.reagents.iter().map(|r| r.name()).collect();
+                        ui.label(recipe.name()).on_hover_text(names.join(" + "));
+                        let ready = recipe.reagents.iter().all(|r| count_reagent(inventory, *r) > 0);
+                        if ui.add_enabled(ready, egui::Button::new("Brew")).clicked() {
+                            action = Some(BrewingAction::Brew { reagents: recipe.reagents.clone(), coating: false });
+                        }
+                        if !coatings_from(&recipe.effects).is_empty()
+                            && ui.add_enabled(ready, egui::Button::new("Coating")).on_hover_text("Bottle as weapon coating").clicked()
+                        {
+                            action = Some(BrewingAction::Brew { reagents: recipe.reagents.clone(), coating: true });
+                        }
+                    });
+                }
+
+                if ui.button("Leave").clicked() {
+                    action = Some(BrewingAction::Close);
+                }
+            });
+        if !open {
+            action = Some(BrewingAction::Close);
+        }
+        action
+    }
+}
@@ -105,6 +105,8 @@ pub struct CharacterCreator {
     pub name_error: Option<String>,
     /// Whether appearance has changed and preview mesh needs rebuilding
     pub appearance_dirty: bool,
+    /// The character just created, for the game to take when it starts
+    pub created: Option<CharacterData>,
 }
 
 impl Default for CharacterCreator {
@@ -127,6 +129,7 @@ impl CharacterCreator {
             show_wireframe: false,
             name_error: None,
             appearance_dirty: true,
+            created: None,
         }
     }
 
@@ -299,16 +302,15 @@ impl CharacterCreator {
                             "Create Character",
                             Vec2::new(150.0, 40.0),
                             Color32::from_rgb(60, 80, 120),
-                        ) {
-                            if self.validate_name() {
-                                let character = self.create_character();
-                                if let Err(e) = crate::character::save_character(&character) {
-                                    tracing::error!("Failed to save character: {}", e);
-                                    self.name_error = Some("Failed to save character".to_string());
-                                } else {
-                                    transition =
-                                        StateTransition::Replace(ApplicationState::Playing);
-                                }
+                        ) && self.validate_name()
+                        {
+                            let character = self.create_character();
+                            if let Err(e) = crate::character::save_character(&character) {
+                                tracing::error!("Failed to save character: {}", e);
+                                self.name_error = Some("Failed to save character".to_string());
+                            } else {
+                                self.created = Some(character);
+                                transition = StateTransition::Replace(ApplicationState::Playing);
                             }
                         }
                     });
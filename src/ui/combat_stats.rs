@@ -0,0 +1,103 @@
+//! Combat statistics panel (damage log, DPS, kills)
+
+use egui::{Color32, RichText};
+use infinite_game::combat::log::sorted_breakdown;
+use infinite_game::{CombatLog, CombatTotals};
+
+/// Toggleable window summarising the combat log
+pub struct CombatStatsPanel {
+    /// Whether the panel is open
+    pub visible: bool,
+    /// Show whole-save totals instead of this session
+    show_lifetime: bool,
+}
+
+impl CombatStatsPanel {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            show_lifetime: false,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Draw the panel if visible
+    pub fn render(&mut self, ctx: &egui::Context, log: &CombatLog) {
+        if !self.visible {
+            return;
+        }
+
+        let lifetime = log.lifetime();
+        let mut open = self.visible;
+        egui::Window::new("Combat Stats")
+            .open(&mut open)
+            .anchor(egui::Align2::RIGHT_CENTER, [-10.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!("DPS ({:.0}s): {:.1}", log.dps_window, log.dps()))
+                        .size(16.0)
+                        .color(Color32::from_rgb(255, 200, 80)),
+                );
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.show_lifetime, false, "Session");
+                    ui.selectable_value(&mut self.show_lifetime, true, "All time");
+                });
+                ui.separator();
+
+                let totals = if self.show_lifetime { &lifetime } else { log.session() };
+                render_totals(ui, totals);
+            });
+        self.visible = open;
+    }
+}
+
+fn render_totals(ui: &mut egui::Ui, totals: &CombatTotals) {
+    ui.label(format!("Damage dealt: {:.0}", totals.damage_dealt));
+    ui.label(format!("Damage taken: {:.0}", totals.damage_taken));
+    ui.label(format!(
+        "Hits: {}  Crits: {} ({:.0}%)",
+        totals.hits,
+        totals.crits,
+        totals.crit_rate() * 100.0
+    ));
+    ui.label(format!("Highest hit: {:.0}", totals.highest_hit));
+
+    if !totals.dealt_by_source.is_empty() {
+        ui.separator();
+        ui.label(RichText::new("Dealt by source").strong());
+        for (source, amount) in sorted_breakdown(&totals.dealt_by_source) {
+            ui.label(format!("  {}: {:.0}", source, amount));
+        }
+    }
+
+    if !totals.dealt_by_element.is_empty() {
+        ui.separator();
+        ui.label(RichText::new("Dealt by element").strong());
+        for (element, amount) in sorted_breakdown(&totals.dealt_by_element) {
+            ui.label(format!("  {}: {:.0}", element.name(), amount));
+        }
+    }
+
+    if !totals.taken_by_element.is_empty() {
+        ui.separator();
+        ui.label(RichText::new("Taken by element").strong());
+        for (element, amount) in sorted_breakdown(&totals.taken_by_element) {
+            ui.label(format!("  {}: {:.0}", element.name(), amount));
+        }
+    }
+
+    ui.separator();
+    ui.label(RichText::new(format!("Kills: {}", totals.total_kills())).strong());
+    let mut kills: Vec<(&String, &u32)> = totals.kills.iter().collect();
+    kills.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    for (enemy, count) in kills {
+        ui.label(format!("  {}: {}", enemy, count));
+    }
+}
@@ -0,0 +1,104 @@
+//! Compass bar HUD
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, Stroke, Vec2};
+use infinite_game::compass::{bar_offset, relative_bearing, CARDINALS};
+use infinite_game::{CompassEntry, MarkerCategory};
+
+/// Width of the compass bar in points
+const BAR_WIDTH: f32 = 520.0;
+/// Height of the compass bar in points
+const BAR_HEIGHT: f32 = 34.0;
+
+/// Compass bar shown across the top of the HUD
+pub struct CompassHud {
+    /// Degrees of heading visible across the full bar width
+    pub visible_arc: f32,
+}
+
+impl CompassHud {
+    pub fn new() -> Self {
+        Self { visible_arc: 180.0 }
+    }
+
+    /// Draw the compass for the given heading and markers
+    pub fn render(&self, ctx: &egui::Context, heading: f32, entries: &[CompassEntry]) {
+        egui::Area::new(egui::Id::new("compass_bar"))
+            .anchor(Align2::CENTER_TOP, [0.0, 8.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                let (rect, _) = ui.allocate_exact_size(Vec2::new(BAR_WIDTH, BAR_HEIGHT), egui::Sense::hover());
+                let painter = ui.painter();
+                painter.rect_filled(rect, 4.0, Color32::from_rgba_unmultiplied(0, 0, 0, 150));
+
+                let x_at = |offset: f32| rect.center().x + offset * (BAR_WIDTH * 0.5 - 12.0);
+
+                // Tick marks every 15 degrees
+                let mut tick = 0.0;
+                while tick < 360.0 {
+                    if let Some(offset) = bar_offset(relative_bearing(heading, tick), self.visible_arc) {
+                        let x = x_at(offset);
+                        let len = if tick % 45.0 == 0.0 { 6.0 } else { 3.0 };
+                        painter.line_segment(
+                            [Pos2::new(x, rect.bottom() - len), Pos2::new(x, rect.bottom())],
+                            Stroke::new(1.0, Color32::from_gray(140)),
+                        );
+                    }
+                    tick += 15.0;
+                }
+
+                // Cardinal labels
+                for (label, bearing) in CARDINALS {
+                    if let Some(offset) = bar_offset(relative_bearing(heading, bearing), self.visible_arc) {
+                        let major = label.len() == 1;
+                        painter.text(
+                            Pos2::new(x_at(offset), rect.top() + 10.0),
+                            Align2::CENTER_CENTER,
+                            label,
+                            FontId::proportional(if major { 15.0 } else { 11.0 }),
+                            if label == "N" {
+                                Color32::from_rgb(255, 120, 100)
+                            } else if major {
+                                Color32::WHITE
+                            } else {
+                                Color32::from_gray(170)
+                            },
+                        );
+                    }
+                }
+
+                // Markers with distance labels
+                for entry in entries {
+                    let x = x_at(entry.offset);
+                    let color = marker_color(entry.category);
+                    let pip = Rect::from_center_size(Pos2::new(x, rect.top() + 22.0), Vec2::splat(7.0));
+                    painter.rect_filled(pip, 2.0, color);
+
+                    // Only label markers near the centre to avoid clutter
+                    if entry.offset.abs() < 0.35 || entry.category == MarkerCategory::Objective {
+                        painter.text(
+                            Pos2::new(x, rect.bottom() + 8.0),
+                            Align2::CENTER_CENTER,
+                            format!("{} {:.0}m", entry.label, entry.distance),
+                            FontId::proportional(11.0),
+                            color,
+                        );
+                    }
+                }
+
+                // Centre line
+                painter.line_segment(
+                    [Pos2::new(rect.center().x, rect.top()), Pos2::new(rect.center().x, rect.top() + 5.0)],
+                    Stroke::new(2.0, Color32::from_rgb(255, 220, 120)),
+                );
+            });
+    }
+}
+
+pub(super) fn marker_color(category: MarkerCategory) -> Color32 {
+    match category {
+        MarkerCategory::Interactable => Color32::from_rgb(150, 200, 255),
+        MarkerCategory::Npc => Color32::from_rgb(140, 230, 140),
+        MarkerCategory::Objective => Color32::from_rgb(255, 210, 80),
+        MarkerCategory::Waypoint => Color32::from_rgb(230, 120, 255),
+    }
+}
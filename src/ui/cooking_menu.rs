@@ -0,0 +1,128 @@
+//! Cooking menu: fill the pot at a campfire or stove and cook
+//!
+//! Anything from the pack can go in the pot, so new recipes are found by
+//! trying combinations. Recipes cooked before are listed and can be cooked
+//! again in one click.
+
+use egui::{Color32, RichText};
+
+use infinite_game::cooking::{count_ingredient, has_ingredients, MAX_POT_INGREDIENTS};
+use infinite_game::{Era, Ingredient, Inventory, RecipeBook, StationKind};
+
+/// Choice made in the cooking menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CookingAction {
+    /// Cook these ingredients
+    Cook(Vec<Ingredient>),
+    Close,
+}
+
+/// Window for cooking, opened at a campfire or stove
+pub struct CookingMenu {
+    station: StationKind,
+    pot: Vec<Ingredient>,
+}
+
+impl CookingMenu {
+    pub fn new(station: StationKind) -> Self {
+        Self { station, pot: Vec::new() }
+    }
+
+    /// Empty the pot once it has been cooked
+    pub fn clear_pot(&mut self) {
+        self.pot.clear();
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, inventory: &Inventory, book: &RecipeBook, era: Era) -> Option<CookingAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(self.station.name())
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.label(RichText::new("Ingredients").strong());
+                let mut any = false;
+                for ingredient in Ingredient::ALL {
+                    let held = count_ingredient(inventory, ingredient);
+                    if held == 0 {
+                        continue;
+                    }
+                    any = true;
+                    let in_pot = self.pot.iter().filter(|i| **i == ingredient).count() as u32;
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} ({})", ingredient.name(), held - in_pot.min(held)));
+                        let room = self.pot.len() < MAX_POT_INGREDIENTS && in_pot < held;
+                        if ui.add_enabled(room, egui::Button::new("Add")).clicked() {
+                            self.pot.push(ingredient);
+                        }
+                    });
+                }
+                if !any {
+                    ui.label(
+                        RichText::new("You have nothing to cook. Buy ingredients or gather herbs.")
+                            .color(Color32::from_rgb(180, 180, 200)),
+                    );
+                }
+
+                ui.separator();
+                ui.label(RichText::new(format!("Pot ({}/{})", self.pot.len(), MAX_POT_INGREDIENTS)).strong());
+                let mut remove = None;
+                ui.horizontal_wrapped(|ui| {
+                    for (index, ingredient) in self.pot.iter().enumerate() {
+                        if ui.button(ingredient.name()).on_hover_text("Take out").clicked() {
+                            remove = Some(index);
+                        }
+                    }
+                });
+                if let Some(index) = remove {
+                    self.pot.remove(index);
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.pot.is_empty(), egui::Button::new("Cook")).clicked() {
+                        action = Some(CookingAction::Cook(self.pot.clone()));
+                    }
+                    if ui.add_enabled(!self.pot.is_empty(), egui::Button::new("Empty")).clicked() {
+                        self.pot.clear();
+                    }
+                });
+
+                ui.separator();
+                ui.label(RichText::new("Known recipes").strong());
+                let mut known = false;
+                for recipe in book.known() {
+                    known = true;
+                    let names: Vec<&str> = recipe.ingredients.iter().map(|i| i.name()).collect();
+                    ui.horizontal(|ui| {
+                        let available = recipe.available_in(era);
+                        let color = if available {
+                            Color32::from_rgb(230, 230, 230)
+                        } else {
+                            Color32::from_rgb(120, 120, 120)
+                        };
+                        ui.label(RichText::new(recipe.name).color(color)).on_hover_text(names.join(" + "));
+                        let ready = available && has_ingredients(inventory, recipe.ingredients);
+                        if ui.add_enabled(ready, egui::Button::new("Cook")).clicked() {
+                            action = Some(CookingAction::Cook(recipe.ingredients.to_vec()));
+                        }
+                    });
+                }
+                if !known {
+                    ui.label(
+                        RichText::new("None yet. Try putting things together and see what comes out.")
+                            .color(Color32::from_rgb(180, 180, 200)),
+                    );
+                }
+
+                if ui.button("Leave").clicked() {
+                    action = Some(CookingAction::Close);
+                }
+            });
+        if !open {
+            action = Some(CookingAction::Close);
+        }
+        action
+    }
+}
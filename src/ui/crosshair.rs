@@ -0,0 +1,58 @@
+//! Crosshair at the screen centre, opening up with ranged weapon spread
+
+use egui::{Color32, Id, LayerId, Order, Pos2, Stroke, Vec2};
+
+use crate::settings::{CrosshairSettings, CrosshairStyle};
+
+/// Gap between the centre and the lines with no spread, in points
+const BASE_GAP: f32 = 3.0;
+/// Colour while aim assist holds a target
+const LOCKED_COLOR: Color32 = Color32::from_rgb(255, 120, 90);
+
+/// Draw the crosshair. `spread` is the current ranged spread in degrees
+/// (None without a ranged weapon); `vertical_fov` converts it to points.
+pub fn draw_crosshair(ctx: &egui::Context, settings: &CrosshairSettings, spread: Option<f32>, vertical_fov: f32, locked: bool) {
+    if !settings.enabled {
+        return;
+    }
+    let screen = ctx.screen_rect();
+    let center = screen.center();
+    let spread_gap = match spread {
+        Some(spread) if settings.dynamic_spread => {
+            let half_fov = (vertical_fov * 0.5).to_radians().tan();
+            (spread * 0.5).to_radians().tan() / half_fov * screen.height() * 0.5
+        }
+        _ => 0.0,
+    };
+    let gap = BASE_GAP + spread_gap;
+    let size = settings.size.clamp(CrosshairSettings::MIN_SIZE, CrosshairSettings::MAX_SIZE);
+    let [r, g, b] = settings.color;
+    let color = if locked { LOCKED_COLOR } else { Color32::from_rgb(r, g, b) };
+    let stroke = Stroke::new(2.0, color);
+    let outline = Stroke::new(4.0, Color32::from_black_alpha(140));
+
+    let painter = ctx.layer_painter(LayerId::new(Order::Background, Id::new("crosshair")));
+    let lines = |painter: &egui::Painter| {
+        for dir in [Vec2::X, -Vec2::X, Vec2::Y, -Vec2::Y] {
+            let segment = [center + dir * gap, center + dir * (gap + size)];
+            painter.line_segment(segment, outline);
+            painter.line_segment(segment, stroke);
+        }
+    };
+    let dot = |painter: &egui::Painter, at: Pos2| {
+        painter.circle_filled(at, 2.5, Color32::from_black_alpha(140));
+        painter.circle_filled(at, 1.5, color);
+    };
+    match settings.style {
+        CrosshairStyle::Cross => lines(&painter),
+        CrosshairStyle::Dot => dot(&painter, center),
+        CrosshairStyle::Circle => {
+            painter.circle_stroke(center, gap + size * 0.5, outline);
+            painter.circle_stroke(center, gap + size * 0.5, stroke);
+        }
+        CrosshairStyle::CrossDot => {
+            lines(&painter);
+            dot(&painter, center);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Floating damage number rendering
+
+use egui::{Align2, Color32, FontId, Id, LayerId, Order, Pos2, Vec2};
+use glam::Vec3;
+use infinite_game::combat::DamageNumber;
+use infinite_game::{DamageKind, DamageNumbers};
+
+/// Base font size in points before the widget scale and pop
+const BASE_FONT_SIZE: f32 = 16.0;
+
+/// Draws every floating number on one foreground layer
+pub struct DamageNumberHud {
+    /// Widget scale from the HUD settings
+    pub scale: f32,
+}
+
+impl DamageNumberHud {
+    pub fn new(scale: f32) -> Self {
+        Self { scale }
+    }
+
+    /// Draw the numbers; `project` maps a world position to the screen
+    pub fn render(&self, ctx: &egui::Context, numbers: &DamageNumbers, project: impl Fn(Vec3) -> Option<Pos2>) {
+        let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("damage_numbers")));
+        for number in numbers.iter() {
+            let Some(pos) = project(number.position) else {
+                continue;
+            };
+            let alpha = number.alpha();
+            let font = FontId::proportional(BASE_FONT_SIZE * size_factor(number) * number.pop_scale() * self.scale);
+            let text = number.label();
+            // Dark drop shadow keeps numbers readable against bright terrain
+            painter.text(
+                pos + Vec2::splat(1.5),
+                Align2::CENTER_CENTER,
+                &text,
+                font.clone(),
+                Color32::from_black_alpha((alpha * 180.0) as u8),
+            );
+            painter.text(pos, Align2::CENTER_CENTER, text, font, color(number).gamma_multiply(alpha));
+        }
+    }
+}
+
+/// Outgoing white, crits gold, incoming red, healing green, DoT orange
+fn color(number: &DamageNumber) -> Color32 {
+    match number.kind {
+        DamageKind::Outgoing if number.is_crit => Color32::from_rgb(255, 215, 0),
+        DamageKind::Outgoing => Color32::WHITE,
+        DamageKind::Incoming => Color32::from_rgb(255, 70, 60),
+        DamageKind::Healing => Color32::from_rgb(90, 230, 110),
+        DamageKind::DamageOverTime => Color32::from_rgb(230, 150, 60),
+    }
+}
+
+/// Crits stand out, DoT ticks stay in the background
+fn size_factor(number: &DamageNumber) -> f32 {
+    match number.kind {
+        _ if number.is_crit => 1.3,
+        DamageKind::DamageOverTime => 0.8,
+        _ => 1.0,
+    }
+}
@@ -0,0 +1,31 @@
+//! Dialogue response buttons, tagged with what the response needs
+
+use egui::{Color32, FontId, RichText, Ui};
+
+use infinite_game::npc::dialogue::DialogueResponse;
+
+/// A response button. Requirements and stat checks lead the text in
+/// brackets; locked responses are greyed out and say why on hover.
+pub fn response_button(ui: &mut Ui, response: &DialogueResponse, unlocked: bool, size: f32) -> bool {
+    let tag = response.tag();
+    let (text, color) = match &tag {
+        None => (response.text.clone(), Color32::from_rgb(220, 220, 240)),
+        Some(tag) if !unlocked => (format!("[{}] {}", tag, response.text), Color32::from_rgb(130, 130, 145)),
+        Some(tag) if response.check.is_some() => (format!("[{}] {}", tag, response.text), Color32::from_rgb(255, 210, 110)),
+        Some(tag) => (format!("[{}] {}", tag, response.text), Color32::from_rgb(140, 210, 140)),
+    };
+    let button = ui.add_enabled(
+        unlocked,
+        egui::Button::new(RichText::new(format!("  {}  ", text)).font(FontId::proportional(size)).color(color)),
+    );
+    match tag {
+        Some(tag) if !unlocked => button.on_disabled_hover_text(format!("Requires {}", tag)).clicked(),
+        _ => button.clicked(),
+    }
+}
+
+/// Notification for a stat check's outcome
+pub fn check_outcome(response: &DialogueResponse, passed: bool) -> String {
+    let label = response.check.as_ref().map(|c| c.label()).unwrap_or_default();
+    format!("{} check {}", label, if passed { "passed" } else { "failed" })
+}
@@ -0,0 +1,154 @@
+//! Error dialog: failures the player should know about, one at a time
+
+use std::collections::VecDeque;
+
+use egui::{Color32, RichText};
+use infinite_core::{ErrorDomain, InfiniteError, Severity};
+
+/// Most errors kept waiting behind the one on screen
+const MAX_QUEUED: usize = 8;
+
+/// Choice made in the error dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorDialogAction {
+    /// The player read it; carry on
+    Dismiss,
+    /// A fatal error; the game should save what it can and exit
+    Quit,
+}
+
+/// What the dialog shows for one error
+struct ShownError {
+    code: String,
+    severity: Severity,
+    title: &'static str,
+    summary: String,
+    hint: Option<&'static str>,
+    report: String,
+    /// Times this error came up while it was waiting
+    repeats: u32,
+}
+
+/// Modal window for errors worth interrupting the player for
+pub struct ErrorDialog {
+    queue: VecDeque<ShownError>,
+    show_details: bool,
+}
+
+impl ErrorDialog {
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            show_details: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    /// Log an error with its full report, and queue it for the player
+    /// unless it's only a warning. One already waiting with the same code
+    /// just counts the repeat, so a failure every frame doesn't bury the
+    /// player.
+    pub fn report(&mut self, err: &InfiniteError) {
+        match err.severity() {
+            Severity::Warning => {
+                tracing::warn!("{}", err.report());
+                return;
+            }
+            Severity::Error | Severity::Fatal => tracing::error!("{}", err.report()),
+        }
+
+        let code = err.code().to_string();
+        if let Some(shown) = self.queue.iter_mut().find(|shown| shown.code == code) {
+            shown.repeats += 1;
+            shown.severity = shown.severity.max(err.severity());
+            return;
+        }
+        if self.queue.len() >= MAX_QUEUED && err.severity() < Severity::Fatal {
+            return;
+        }
+        self.queue.push_back(ShownError {
+            code,
+            severity: err.severity(),
+            title: title_for(err.code().domain),
+            summary: err.to_string(),
+            hint: err.hint(),
+            report: err.report(),
+            repeats: 0,
+        });
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<ErrorDialogAction> {
+        let shown = self.queue.front()?;
+        let fatal = shown.severity == Severity::Fatal;
+
+        let mut action = None;
+        egui::Window::new(RichText::new(shown.title).strong())
+            .id(egui::Id::new("error_dialog"))
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                let color = if fatal {
+                    Color32::from_rgb(255, 90, 90)
+                } else {
+                    Color32::from_rgb(255, 200, 80)
+                };
+                ui.label(RichText::new(&shown.summary).color(color));
+                if let Some(hint) = shown.hint {
+                    ui.add_space(4.0);
+                    ui.label(hint);
+                }
+                if shown.repeats > 0 {
+                    ui.label(RichText::new(format!("Happened {} more times", shown.repeats)).weak());
+                }
+
+                ui.add_space(4.0);
+                ui.checkbox(&mut self.show_details, "Details");
+                if self.show_details {
+                    egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                        ui.label(RichText::new(&shown.report).monospace().size(11.0));
+                    });
+                    if ui.button("Copy details").clicked() {
+                        ui.ctx().copy_text(shown.report.clone());
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if fatal {
+                        if ui.button("Quit").clicked() {
+                            action = Some(ErrorDialogAction::Quit);
+                        }
+                    } else if ui.button("OK").clicked() {
+                        action = Some(ErrorDialogAction::Dismiss);
+                    }
+                    if self.queue.len() > 1 {
+                        ui.label(RichText::new(format!("{} more", self.queue.len() - 1)).weak());
+                    }
+                });
+            });
+
+        if action == Some(ErrorDialogAction::Dismiss) {
+            self.queue.pop_front();
+            self.show_details = false;
+        }
+        action
+    }
+}
+
+fn title_for(domain: ErrorDomain) -> &'static str {
+    match domain {
+        ErrorDomain::Render => "Graphics problem",
+        ErrorDomain::Audio => "Sound problem",
+        ErrorDomain::Assets => "Missing game files",
+        ErrorDomain::World => "World problem",
+        ErrorDomain::Net | ErrorDomain::Integration => "Connection problem",
+        ErrorDomain::Save => "Save problem",
+        ErrorDomain::Mods => "Mod problem",
+        ErrorDomain::Core => "Something went wrong",
+    }
+}
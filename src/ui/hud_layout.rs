@@ -0,0 +1,143 @@
+//! HUD layout: places widgets per the HUD settings, and the edit mode for
+//! dragging them into position
+//!
+//! Widgets keep drawing at their stock positions; the layout is applied as
+//! a transform on their egui layers that moves the widget's pivot to where
+//! the player put it and scales around it.
+
+use egui::emath::TSTransform;
+use egui::{Align2, Color32, FontId, Id, LayerId, Order, RichText, Sense, Slider, Stroke, Vec2};
+
+use crate::settings::{HudPreset, HudSettings, HudWidget, WidgetLayout};
+
+/// Size of the drag handles shown in edit mode
+const HANDLE_SIZE: Vec2 = Vec2::new(130.0, 24.0);
+
+/// Move and scale the areas making up `widget` according to its layout
+pub fn apply_layout(ctx: &egui::Context, hud: &HudSettings, widget: HudWidget, area_ids: &[&str]) {
+    let screen = ctx.screen_rect().size();
+    let screen = [screen.x, screen.y];
+    let layout = hud.get(widget);
+    let scale = layout.scale.clamp(WidgetLayout::MIN_SCALE, WidgetLayout::MAX_SCALE);
+    let from = widget.default_layout().pivot(screen);
+    let to = layout.pivot(screen);
+    let transform = TSTransform::new(Vec2::new(to[0] - scale * from[0], to[1] - scale * from[1]), scale);
+    for id in area_ids {
+        ctx.set_transform_layer(LayerId::new(Order::Middle, Id::new(*id)), transform);
+    }
+}
+
+/// Per-widget enable and scale controls, with the anchor each widget is
+/// attached to. Shared by the settings menu and the edit mode panel.
+pub fn widget_controls(ui: &mut egui::Ui, hud: &mut HudSettings) {
+    ui.horizontal(|ui| {
+        ui.label("Preset:");
+        for preset in HudPreset::ALL {
+            if ui.button(preset.name()).clicked() {
+                *hud = HudSettings::preset(preset);
+            }
+        }
+    });
+    ui.add_space(8.0);
+
+    egui::Grid::new("hud_widgets").num_columns(3).spacing([12.0, 6.0]).show(ui, |ui| {
+        for widget in HudWidget::ALL {
+            let layout = hud.get_mut(widget);
+            ui.checkbox(&mut layout.enabled, widget.name());
+            ui.add_enabled(
+                layout.enabled,
+                Slider::new(&mut layout.scale, WidgetLayout::MIN_SCALE..=WidgetLayout::MAX_SCALE)
+                    .fixed_decimals(2)
+                    .suffix("x"),
+            );
+            if widget.is_movable() {
+                ui.label(RichText::new(layout.anchor.name()).color(Color32::from_rgb(150, 150, 170)));
+            } else {
+                ui.label("");
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// Drag-to-position HUD edit mode
+pub struct HudEditor {
+    pub active: bool,
+}
+
+impl HudEditor {
+    pub fn new() -> Self {
+        Self { active: false }
+    }
+
+    /// Draw drag handles on every placed widget and the layout panel.
+    /// Returns true when the player is done editing.
+    pub fn render(&mut self, ctx: &egui::Context, hud: &mut HudSettings) -> bool {
+        let screen = ctx.screen_rect().size();
+        let screen = [screen.x, screen.y];
+
+        for widget in HudWidget::ALL {
+            let layout = hud.get_mut(widget);
+            if !widget.is_movable() || !layout.enabled {
+                continue;
+            }
+            let pivot = layout.pivot(screen);
+            let response = egui::Area::new(Id::new(("hud_handle", widget)))
+                .order(Order::Foreground)
+                .fixed_pos([pivot[0] - HANDLE_SIZE.x * 0.5, pivot[1] - HANDLE_SIZE.y * 0.5])
+                .constrain(true)
+                .show(ctx, |ui| {
+                    let (rect, response) = ui.allocate_exact_size(HANDLE_SIZE, Sense::drag());
+                    let active = response.dragged() || response.hovered();
+                    let fill = if active {
+                        Color32::from_rgba_unmultiplied(90, 110, 200, 230)
+                    } else {
+                        Color32::from_rgba_unmultiplied(40, 50, 90, 200)
+                    };
+                    ui.painter().rect_filled(rect, 4.0, fill);
+                    ui.painter().rect_stroke(
+                        rect,
+                        4.0,
+                        Stroke::new(1.0, Color32::from_rgb(160, 180, 255)),
+                        egui::epaint::StrokeKind::Outside,
+                    );
+                    ui.painter().text(
+                        rect.center(),
+                        Align2::CENTER_CENTER,
+                        widget.name(),
+                        FontId::proportional(12.0),
+                        Color32::WHITE,
+                    );
+                    response
+                })
+                .inner;
+            if response.dragged() {
+                let delta = response.drag_delta();
+                layout.move_pivot_to([pivot[0] + delta.x, pivot[1] + delta.y], screen);
+            }
+        }
+
+        let mut done = false;
+        egui::Window::new("HUD Layout")
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new("Drag the handles to move widgets. Each stays attached to the nearest corner or edge.")
+                        .color(Color32::from_rgb(180, 180, 200)),
+                );
+                ui.add_space(8.0);
+                widget_controls(ui, hud);
+                ui.add_space(8.0);
+                if ui.button("Done").clicked() {
+                    done = true;
+                }
+            });
+
+        if done {
+            self.active = false;
+        }
+        done
+    }
+}
@@ -0,0 +1,310 @@
+//! Entity inspector: click an NPC or interactable while the debug overlay is
+//! up to see its fields, and edit them live
+
+use egui::{DragValue, RichText};
+use glam::Vec3;
+use infinite_game::npc::manager::NpcManager;
+use infinite_game::{InteractableKind, InteractableState, InteractionSystem, NpcFaction, NpcId, NpcRole};
+
+const ROLES: [NpcRole; 5] = [
+    NpcRole::Villager,
+    NpcRole::Guard,
+    NpcRole::Shopkeeper,
+    NpcRole::QuestGiver,
+    NpcRole::Enemy,
+];
+
+const FACTIONS: [NpcFaction; 3] = [NpcFaction::Friendly, NpcFaction::Neutral, NpcFaction::Hostile];
+
+/// What the inspector is looking at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectTarget {
+    Npc(NpcId),
+    /// By position in the interaction system; `kind` notices when the list
+    /// has changed under it (chunks streaming in and out)
+    Interactable { index: usize, kind: &'static str },
+}
+
+/// Window showing the picked entity's fields
+pub struct EntityInspector {
+    pub target: Option<InspectTarget>,
+}
+
+impl EntityInspector {
+    pub fn new() -> Self {
+        Self { target: None }
+    }
+
+    /// Draw the inspector if something is picked. Edits go straight into
+    /// the live entity; the window closes when it despawns.
+    pub fn render(&mut self, ctx: &egui::Context, npcs: Option<&mut NpcManager>, interactions: &mut InteractionSystem) {
+        let Some(target) = self.target else {
+            return;
+        };
+
+        let mut open = true;
+        let mut exists = true;
+        egui::Window::new("Inspector")
+            .id(egui::Id::new("entity_inspector"))
+            .open(&mut open)
+            .anchor(egui::Align2::RIGHT_CENTER, [-10.0, 0.0])
+            .resizable(false)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    exists = match target {
+                        InspectTarget::Npc(id) => npcs.is_some_and(|npcs| npc_fields(ui, npcs, id)),
+                        InspectTarget::Interactable { index, kind } => interactable_fields(ui, interactions, index, kind),
+                    };
+                });
+            });
+        if !open || !exists {
+            self.target = None;
+        }
+    }
+}
+
+/// Nearest of `candidates` (target, box centre, half extent) along the ray
+/// from `origin`, within `max_distance`
+pub fn pick(
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+    candidates: impl IntoIterator<Item = (InspectTarget, Vec3, Vec3)>,
+) -> Option<InspectTarget> {
+    candidates
+        .into_iter()
+        .filter_map(|(target, center, half_extent)| {
+            ray_box(origin, direction, center, half_extent)
+                .filter(|&distance| distance <= max_distance)
+                .map(|distance| (target, distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(target, _)| target)
+}
+
+/// Distance along the ray to an axis-aligned box, if it hits
+fn ray_box(origin: Vec3, direction: Vec3, center: Vec3, half_extent: Vec3) -> Option<f32> {
+    let inv = direction.recip();
+    let t1 = (center - half_extent - origin) * inv;
+    let t2 = (center + half_extent - origin) * inv;
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element();
+    (far >= near).then_some(near)
+}
+
+/// Returns false once the NPC is gone
+fn npc_fields(ui: &mut egui::Ui, npcs: &mut NpcManager, id: NpcId) -> bool {
+    let lod = npcs.lod(id);
+    let detection = npcs.detection_state(id);
+    let Some(npc) = npcs.get_mut(id) else {
+        return false;
+    };
+
+    ui.label(RichText::new(&npc.data.name).strong().size(16.0));
+    ui.label(RichText::new(format!("NPC {} (key {:016x})", id.0, npc.persistent_key)).weak());
+    egui::Grid::new("inspect_npc").num_columns(2).show(ui, |ui| {
+        ui.label("Position");
+        vec3_field(ui, &mut npc.position);
+        ui.end_row();
+        ui.label("Yaw");
+        ui.add(DragValue::new(&mut npc.yaw).speed(0.05));
+        ui.end_row();
+        ui.label("Home");
+        vec3_field(ui, &mut npc.data.home_position);
+        ui.end_row();
+        ui.label("Wander radius");
+        ui.add(DragValue::new(&mut npc.data.wander_radius).speed(0.1).range(0.0..=100.0));
+        ui.end_row();
+        ui.label("Role");
+        egui::ComboBox::from_id_salt("inspect_role")
+            .selected_text(format!("{:?}", npc.data.role))
+            .show_ui(ui, |ui| {
+                for role in ROLES {
+                    ui.selectable_value(&mut npc.data.role, role, format!("{:?}", role));
+                }
+            });
+        ui.end_row();
+        ui.label("Faction");
+        egui::ComboBox::from_id_salt("inspect_faction")
+            .selected_text(format!("{:?}", npc.data.faction))
+            .show_ui(ui, |ui| {
+                for faction in FACTIONS {
+                    ui.selectable_value(&mut npc.data.faction, faction, format!("{:?}", faction));
+                }
+            });
+        ui.end_row();
+        ui.label("Colour");
+        ui.color_edit_button_rgba_unmultiplied(&mut npc.data.color);
+        ui.end_row();
+        ui.label("State");
+        ui.label(format!("{:?}", npc.state));
+        ui.end_row();
+        ui.label("LOD");
+        ui.label(format!("{:?}", lod));
+        ui.end_row();
+        ui.label("Awareness");
+        ui.label(format!("{:?}", detection));
+        ui.end_row();
+    });
+
+    if let Some(brain) = &mut npc.brain {
+        ui.separator();
+        ui.heading("Brain");
+        ui.label(format!("Action: {}", brain.current_action_name().unwrap_or("none")));
+        if let Some(plan) = &brain.current_plan {
+            let steps: Vec<&str> = plan
+                .iter()
+                .filter_map(|&index| brain.actions.get(index))
+                .map(|action| action.name.as_str())
+                .collect();
+            ui.label(format!("Plan: {}", steps.join(" > ")));
+        }
+        egui::Grid::new("inspect_goals").num_columns(2).show(ui, |ui| {
+            for goal in &mut brain.goals {
+                ui.label(&goal.name);
+                ui.add(DragValue::new(&mut goal.priority).speed(0.05).prefix("priority "));
+                ui.end_row();
+            }
+        });
+        if ui.button("Replan").clicked() {
+            brain.replan();
+        }
+    }
+
+    if let Some(stats) = npcs.combat_stats.get_mut(&id) {
+        ui.separator();
+        ui.heading("Combat");
+        egui::Grid::new("inspect_combat").num_columns(2).show(ui, |ui| {
+            ui.label("HP");
+            ui.add(DragValue::new(&mut stats.current_hp).range(0.0..=stats.max_hp));
+            ui.end_row();
+            let fields = [
+                ("Max HP", &mut stats.max_hp),
+                ("Attack", &mut stats.attack),
+                ("Defense", &mut stats.defense),
+                ("Armor", &mut stats.armor),
+                ("Speed", &mut stats.speed),
+                ("Aggro radius", &mut stats.aggro_radius),
+                ("Attack radius", &mut stats.attack_radius),
+                ("Attack cooldown", &mut stats.attack_cooldown),
+            ];
+            for (name, value) in fields {
+                ui.label(name);
+                ui.add(DragValue::new(value).speed(0.1).range(0.0..=f32::MAX));
+                ui.end_row();
+            }
+            ui.label("Element");
+            ui.label(stats.element.name());
+            ui.end_row();
+        });
+    }
+
+    ui.separator();
+    if ui.button("Despawn").clicked() {
+        npcs.despawn(id);
+        return false;
+    }
+    true
+}
+
+/// Returns false once the interactable is gone
+fn interactable_fields(ui: &mut egui::Ui, interactions: &mut InteractionSystem, index: usize, kind: &'static str) -> bool {
+    let Some(interactable) = interactions.get_mut(index).filter(|interactable| interactable.kind.name() == kind) else {
+        return false;
+    };
+
+    ui.label(RichText::new(kind).strong().size(16.0));
+    let mut state_id = None;
+    egui::Grid::new("inspect_interactable").num_columns(2).show(ui, |ui| {
+        ui.label("Position");
+        vec3_field(ui, &mut interactable.position);
+        ui.end_row();
+        ui.label("Reach");
+        ui.add(DragValue::new(&mut interactable.interaction_radius).speed(0.05).range(0.0..=50.0));
+        ui.end_row();
+        ui.label("Prompt");
+        ui.text_edit_singleline(&mut interactable.prompt);
+        ui.end_row();
+
+        match &mut interactable.kind {
+            InteractableKind::Sign { text } => {
+                ui.label("Text");
+                ui.text_edit_multiline(text);
+            }
+            InteractableKind::TimePortal { target_year } => {
+                ui.label("Target year");
+                ui.add(DragValue::new(target_year).speed(10.0));
+            }
+            InteractableKind::Pickup { item_name } => {
+                ui.label("Item");
+                ui.text_edit_singleline(item_name);
+            }
+            InteractableKind::Ladder { height, direction } => {
+                ui.label("Height");
+                ui.add(DragValue::new(height).speed(0.1).range(0.5..=100.0));
+                ui.end_row();
+                ui.label("Direction");
+                vec3_field(ui, direction);
+            }
+            InteractableKind::Door { id }
+            | InteractableKind::Lever { id }
+            | InteractableKind::Button { id }
+            | InteractableKind::Container { id } => {
+                state_id = Some(*id);
+                ui.label("Id");
+                ui.label(id.0.to_string());
+            }
+            other => {
+                ui.label("Data");
+                ui.label(RichText::new(format!("{:?}", other)).weak());
+            }
+        }
+        ui.end_row();
+    });
+
+    if let Some(state) = state_id.and_then(|id| interactions.state_mut(id)) {
+        ui.separator();
+        ui.heading("State");
+        match state {
+            InteractableState::Door { is_open, is_locked, lock } => {
+                ui.checkbox(is_open, "Open");
+                ui.checkbox(is_locked, "Locked");
+                if let Some(lock) = lock {
+                    ui.label(format!("Lock: {:?}", lock));
+                }
+            }
+            InteractableState::Lever { is_on, linked_ids } => {
+                ui.checkbox(is_on, "On");
+                ui.label(format!("Linked to {} objects", linked_ids.len()));
+            }
+            InteractableState::Button { is_pressed } => {
+                ui.checkbox(is_pressed, "Pressed");
+            }
+            InteractableState::Container { is_open, items } => {
+                ui.checkbox(is_open, "Open");
+                let mut remove = None;
+                for (slot, item) in items.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(item);
+                        if ui.small_button("x").clicked() {
+                            remove = Some(slot);
+                        }
+                    });
+                }
+                if let Some(slot) = remove {
+                    items.remove(slot);
+                }
+            }
+        }
+    }
+    true
+}
+
+fn vec3_field(ui: &mut egui::Ui, value: &mut Vec3) {
+    ui.horizontal(|ui| {
+        ui.add(DragValue::new(&mut value.x).speed(0.1).prefix("x "));
+        ui.add(DragValue::new(&mut value.y).speed(0.1).prefix("y "));
+        ui.add(DragValue::new(&mut value.z).speed(0.1).prefix("z "));
+    });
+}
@@ -1,10 +1,23 @@
 //! Inventory and equipment UI
 
-use egui::{Color32, FontId, RichText, ScrollArea, Ui, Vec2};
+use egui::{Color32, FontId, Pos2, Rect, RichText, ScrollArea, Sense, Stroke, Ui, Vec2};
+use glam::Vec3;
 
-use infinite_game::combat::equipment::{EquipmentSet, EquipmentSlot};
+use infinite_core::time::format_year;
+
+use infinite_game::combat::anachronism::{anachronism, ParadoxConfig};
+use infinite_game::combat::armor::{armor_reduction, ELEMENTAL_ARMOR_FACTOR};
+use infinite_game::combat::durability::{Durable, WORN_OUT_EFFECTIVENESS};
+use infinite_game::combat::equipment::{EquipmentSet, EquipmentSlot, OFF_HAND_STAT_SCALE};
+use infinite_game::combat::hotbar::{ConsumableHotbar, HOTBAR_SLOTS};
 use infinite_game::combat::inventory::Inventory;
 use infinite_game::combat::item::{Item, ItemCategory, ItemRarity};
+use infinite_game::combat::weapon::WeaponGrip;
+use infinite_game::combat::treasure::{TreasureMapData, SNIPPET_RESOLUTION};
+use infinite_game::combat::TORCH_ITEM_ID;
+use infinite_game::gathering::{GatheringSkills, ResourceKind};
+use infinite_game::lockpick::{KeyRing, LockTier, LockpickSession, LOCKPICK_ITEM_ID};
+use infinite_game::npc::bestiary::{Bestiary, BestiaryEntry};
 use infinite_game::Element;
 use infinite_game::player::stats::CharacterStats;
 
@@ -16,6 +29,8 @@ pub enum InventoryTab {
     Equipment,
     Inventory,
     Stats,
+    Bestiary,
+    Keys,
 }
 
 /// Action to perform after rendering the inventory
@@ -25,6 +40,7 @@ pub enum InventoryAction {
     EquipItem { inventory_index: usize, slot: EquipmentSlot },
     UnequipItem { slot: EquipmentSlot },
     UseItem { inventory_index: usize },
+    BindHotbar { inventory_index: usize, slot: usize },
 }
 
 /// Inventory menu state
@@ -32,6 +48,14 @@ pub struct InventoryMenu {
     pub active_tab: InventoryTab,
     pub selected_item: Option<usize>,
     pub selected_slot: Option<EquipmentSlot>,
+    /// Where the player stands, for treasure map directions
+    pub player_position: Vec3,
+    /// Year the player is in, for item anachronism warnings
+    pub active_year: i64,
+    /// Anachronism rules the warnings follow
+    pub paradox: ParadoxConfig,
+    /// Bestiary entry shown in the Bestiary tab
+    pub selected_entry: Option<String>,
 }
 
 impl Default for InventoryMenu {
@@ -46,15 +70,24 @@ impl InventoryMenu {
             active_tab: InventoryTab::Equipment,
             selected_item: None,
             selected_slot: None,
+            player_position: Vec3::ZERO,
+            active_year: 0,
+            paradox: ParadoxConfig::default(),
+            selected_entry: None,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         ui: &mut Ui,
         equipment: &EquipmentSet,
         inventory: &Inventory,
         stats: &CharacterStats,
+        bestiary: &Bestiary,
+        key_ring: &KeyRing,
+        hotbar: &ConsumableHotbar,
+        gathering: &GatheringSkills,
     ) -> (StateTransition, InventoryAction) {
         let mut transition = StateTransition::None;
         let mut action = InventoryAction::None;
@@ -101,6 +134,18 @@ impl InventoryMenu {
                     self.selected_item = None;
                     self.selected_slot = None;
                 }
+                ui.add_space(10.0);
+                if tab_button(ui, "Bestiary", self.active_tab == InventoryTab::Bestiary) {
+                    self.active_tab = InventoryTab::Bestiary;
+                    self.selected_item = None;
+                    self.selected_slot = None;
+                }
+                ui.add_space(10.0);
+                if tab_button(ui, "Keys", self.active_tab == InventoryTab::Keys) {
+                    self.active_tab = InventoryTab::Keys;
+                    self.selected_item = None;
+                    self.selected_slot = None;
+                }
             });
 
             ui.add_space(15.0);
@@ -115,10 +160,16 @@ impl InventoryMenu {
                         action = self.render_equipment_tab(ui, equipment, inventory, stats);
                     }
                     InventoryTab::Inventory => {
-                        action = self.render_inventory_tab(ui, equipment, inventory, stats);
+                        action = self.render_inventory_tab(ui, equipment, inventory, stats, hotbar);
                     }
                     InventoryTab::Stats => {
-                        self.render_stats_tab(ui, equipment, stats);
+                        self.render_stats_tab(ui, equipment, stats, gathering);
+                    }
+                    InventoryTab::Bestiary => {
+                        self.render_bestiary_tab(ui, bestiary);
+                    }
+                    InventoryTab::Keys => {
+                        render_key_ring_tab(ui, key_ring, equipment, inventory, stats);
                     }
                 }
             });
@@ -225,13 +276,35 @@ impl InventoryMenu {
                     }
                 }
 
+                // What the off hand is doing
+                let off_hand_info = if let Some(shield) = equipment.shield() {
+                    Some(format!("Shield: blocks {:.0} damage per hit (hold F)", shield.block_value))
+                } else if equipment.is_dual_wielding() {
+                    Some(format!(
+                        "Dual wielding: light combos alternate hands ({:.0}% off-hand stats)",
+                        OFF_HAND_STAT_SCALE * 100.0
+                    ))
+                } else if equipment.off_hand.as_ref().is_some_and(|item| item.id == TORCH_ITEM_ID) {
+                    Some("Torch: lights the way, off-hand strikes burn".to_string())
+                } else {
+                    None
+                };
+                if let Some(info) = off_hand_info {
+                    ui.add_space(4.0);
+                    ui.label(
+                        RichText::new(info)
+                            .font(FontId::proportional(11.0))
+                            .color(Color32::from_rgb(180, 170, 130)),
+                    );
+                }
+
                 // Detail panel for selected slot
                 if let Some(slot) = self.selected_slot {
                     ui.add_space(15.0);
                     ui.separator();
                     ui.add_space(5.0);
                     if let Some(item) = equipment.get(slot) {
-                        render_item_detail(ui, item);
+                        render_item_detail(ui, item, self.active_year, &self.paradox);
                         ui.add_space(5.0);
                         if inv_button(ui, "Unequip", Vec2::new(100.0, 28.0)) {
                             action = InventoryAction::UnequipItem { slot };
@@ -255,6 +328,7 @@ impl InventoryMenu {
         ui: &mut Ui,
         equipment: &EquipmentSet,
         stats: &CharacterStats,
+        gathering: &GatheringSkills,
     ) {
         let equip_mods = equipment.total_modifiers();
         let effective = stats.effective_stats(&equip_mods);
@@ -331,6 +405,144 @@ impl InventoryMenu {
                 stat_display_line(ui, "Max Mana", effective.max_mana, Color32::from_rgb(255, 215, 0));
                 stat_display_line(ui, "Mana Regen", effective.mana_regen, Color32::from_rgb(255, 215, 0));
             });
+
+            ui.add_space(20.0);
+
+            // Column 4: Defense summary
+            ui.vertical(|ui| {
+                ui.set_min_width(180.0);
+                ui.label(
+                    RichText::new("Defense")
+                        .font(FontId::proportional(16.0))
+                        .color(Color32::from_rgb(170, 190, 210)),
+                );
+                ui.add_space(8.0);
+                let armor = equipment.total_armor();
+                stat_display_line(ui, "Armor", armor, Color32::from_rgb(170, 190, 210));
+                stat_display_line(
+                    ui,
+                    "Physical %",
+                    armor_reduction(armor) * 100.0,
+                    Color32::from_rgb(170, 190, 210),
+                );
+                stat_display_line(
+                    ui,
+                    "Elemental %",
+                    armor_reduction(armor * ELEMENTAL_ARMOR_FACTOR) * 100.0,
+                    Color32::from_rgb(170, 190, 210),
+                );
+                for element in Element::all() {
+                    let resist = equip_mods.elemental_resistance[element.index()];
+                    if resist != 0.0 {
+                        stat_display_line(
+                            ui,
+                            &format!("{} Resist", element.name()),
+                            resist,
+                            Color32::from_rgb(160, 180, 220),
+                        );
+                    }
+                }
+
+                // Condition of everything that wears
+                ui.add_space(6.0);
+                for (slot, item) in EquipmentSlot::all()
+                    .iter()
+                    .filter_map(|&slot| equipment.get(slot).as_ref().map(|item| (slot, item)))
+                {
+                    let Some(durable) = item.durable() else { continue };
+                    let text = match item.armor_data {
+                        Some(armor) => format!(
+                            "{}: {:.0} armor ({:.0}%)",
+                            slot.name(),
+                            armor.effective_armor(),
+                            durable.durability_fraction() * 100.0
+                        ),
+                        None => format!("{}: {:.0}%", slot.name(), durable.durability_fraction() * 100.0),
+                    };
+                    ui.label(
+                        RichText::new(text)
+                            .font(FontId::proportional(11.0))
+                            .color(durability_color(durable)),
+                    );
+                }
+            });
+
+            ui.add_space(20.0);
+
+            // Column 5: Gathering skills
+            ui.vertical(|ui| {
+                ui.set_min_width(150.0);
+                ui.label(
+                    RichText::new("Gathering")
+                        .font(FontId::proportional(16.0))
+                        .color(Color32::from_rgb(140, 200, 120)),
+                );
+                ui.add_space(8.0);
+                for kind in ResourceKind::ALL {
+                    let skill = gathering.skill(kind);
+                    ui.label(
+                        RichText::new(format!("{}: {}", kind.skill_name(), skill.level()))
+                            .font(FontId::proportional(13.0))
+                            .color(Color32::from_rgb(200, 200, 220)),
+                    );
+                    ui.add(
+                        egui::ProgressBar::new(skill.xp_fraction())
+                            .desired_width(140.0)
+                            .fill(Color32::from_rgb(120, 180, 90)),
+                    );
+                }
+            });
+        });
+    }
+
+    fn render_bestiary_tab(&mut self, ui: &mut Ui, bestiary: &Bestiary) {
+        if bestiary.is_empty() {
+            ui.label(
+                RichText::new("Defeat an enemy to start your bestiary")
+                    .color(Color32::from_rgb(140, 140, 160)),
+            );
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            // Left: discovered archetypes
+            ui.vertical(|ui| {
+                ui.set_min_width(180.0);
+                ui.label(
+                    RichText::new(format!("Discovered ({})", bestiary.len()))
+                        .font(FontId::proportional(14.0))
+                        .color(Color32::from_rgb(200, 200, 255)),
+                );
+                ui.add_space(5.0);
+                ScrollArea::vertical()
+                    .id_salt("bestiary_list")
+                    .max_height(ui.available_height())
+                    .show(ui, |ui| {
+                        for entry in bestiary.entries() {
+                            let selected = self.selected_entry.as_deref() == Some(entry.name.as_str());
+                            let text = format!("{}  x{}", entry.name, entry.kills);
+                            if tab_button(ui, &text, selected) {
+                                self.selected_entry = Some(entry.name.clone());
+                            }
+                        }
+                    });
+            });
+
+            ui.add_space(20.0);
+
+            // Right: entry details
+            ui.vertical(|ui| {
+                ui.set_min_width(240.0);
+                match self.selected_entry.as_deref().and_then(|name| bestiary.entry(name)) {
+                    Some(entry) => render_bestiary_entry(ui, entry),
+                    None => {
+                        ui.label(
+                            RichText::new("Select an entry to view details")
+                                .color(Color32::from_rgb(140, 140, 160)),
+                        );
+                    }
+                }
+            });
         });
     }
 
@@ -340,6 +552,7 @@ impl InventoryMenu {
         equipment: &EquipmentSet,
         inventory: &Inventory,
         _stats: &CharacterStats,
+        hotbar: &ConsumableHotbar,
     ) -> InventoryAction {
         let mut action = InventoryAction::None;
 
@@ -347,7 +560,12 @@ impl InventoryMenu {
             // Left: Item grid
             ui.vertical(|ui| {
                 ui.label(
-                    RichText::new(format!("Items ({}/{})", inventory.len(), inventory.capacity))
+                    RichText::new(format!(
+                        "Items ({}/{})  ·  {:.1} kg carried",
+                        inventory.len(),
+                        inventory.capacity,
+                        inventory.total_weight() + equipment.total_weight(),
+                    ))
                         .font(FontId::proportional(14.0))
                         .color(Color32::from_rgb(200, 200, 255)),
                 );
@@ -386,7 +604,10 @@ impl InventoryMenu {
                 ui.set_min_width(200.0);
                 if let Some(idx) = self.selected_item {
                     if let Some(item) = inventory.get(idx) {
-                        render_item_detail(ui, item);
+                        render_item_detail(ui, item, self.active_year, &self.paradox);
+                        if let Some(map) = &item.treasure_map {
+                            render_treasure_map(ui, map, self.player_position);
+                        }
                         ui.add_space(10.0);
 
                         // Equip button for equippable items
@@ -404,6 +625,18 @@ impl InventoryMenu {
                                     self.selected_item = None;
                                 }
 
+                                // One-handed weapons and torches can also go in the off hand
+                                if slot == EquipmentSlot::MainHand
+                                    && fits_off_hand(item)
+                                    && inv_button(ui, "Equip (Off Hand)", Vec2::new(160.0, 28.0))
+                                {
+                                    action = InventoryAction::EquipItem {
+                                        inventory_index: idx,
+                                        slot: EquipmentSlot::OffHand,
+                                    };
+                                    self.selected_item = None;
+                                }
+
                                 // Stat comparison vs equipped item
                                 if let Some(equipped) = equipment.get(slot) {
                                     ui.add_space(8.0);
@@ -427,6 +660,27 @@ impl InventoryMenu {
                             };
                             self.selected_item = None;
                         }
+
+                        // Consumables can be bound to the hotbar (5-8)
+                        if item.category == ItemCategory::Consumable {
+                            ui.add_space(6.0);
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new("Hotbar:")
+                                        .font(FontId::proportional(12.0))
+                                        .color(Color32::from_rgb(160, 160, 180)),
+                                );
+                                let bound = hotbar.slot_of(item.id);
+                                for slot in 0..HOTBAR_SLOTS {
+                                    if ui.selectable_label(bound == Some(slot), (slot + 5).to_string()).clicked() {
+                                        action = InventoryAction::BindHotbar {
+                                            inventory_index: idx,
+                                            slot,
+                                        };
+                                    }
+                                }
+                            });
+                        }
                     }
                 } else {
                     ui.label(
@@ -443,6 +697,9 @@ impl InventoryMenu {
 
 /// Suggest the best equipment slot for an item
 fn suggested_slot(item: &Item) -> Option<EquipmentSlot> {
+    if item.is_shield() {
+        return Some(EquipmentSlot::OffHand);
+    }
     match item.category {
         ItemCategory::Weapon => Some(EquipmentSlot::MainHand),
         ItemCategory::Armor => Some(EquipmentSlot::Chest), // default to chest
@@ -451,6 +708,14 @@ fn suggested_slot(item: &Item) -> Option<EquipmentSlot> {
     }
 }
 
+/// Whether a weapon-category item can be held in the off hand
+fn fits_off_hand(item: &Item) -> bool {
+    match &item.weapon_data {
+        Some(wd) => wd.weapon_type.grip() == WeaponGrip::OneHanded,
+        None => item.id == TORCH_ITEM_ID,
+    }
+}
+
 fn rarity_color(rarity: ItemRarity) -> Color32 {
     let c = rarity.color();
     Color32::from_rgb(
@@ -516,10 +781,13 @@ fn equipment_slot_button(
         Color32::from_rgb(70, 70, 90)
     };
 
-    let label = if let Some(item) = item {
-        format!("{}: {}", slot.name(), item.name)
-    } else {
-        format!("{}: Empty", slot.name())
+    let label = match item {
+        Some(item) => match item.durable().filter(|d| d.is_damaged()) {
+            Some(durable) if durable.is_worn_out() => format!("{}: {} (worn out)", slot.name(), item.name),
+            Some(durable) => format!("{}: {} ({:.0}%)", slot.name(), item.name, durable.durability_fraction() * 100.0),
+            None => format!("{}: {}", slot.name(), item.name),
+        },
+        None => format!("{}: Empty", slot.name()),
     };
 
     let text_color = if let Some(item) = item {
@@ -541,6 +809,35 @@ fn equipment_slot_button(
     .clicked()
 }
 
+/// Red once worn out, amber when nearly there
+fn durability_color(durable: &dyn Durable) -> Color32 {
+    if durable.is_worn_out() {
+        Color32::from_rgb(220, 80, 80)
+    } else if durable.durability_fraction() < 0.25 {
+        Color32::from_rgb(220, 180, 80)
+    } else {
+        Color32::from_rgb(160, 160, 180)
+    }
+}
+
+/// "Durability: 40/100", and what wearing out costs
+fn durability_line(ui: &mut Ui, durable: &dyn Durable) {
+    let text = if durable.is_worn_out() {
+        format!(
+            "Durability: 0/{:.0}  Worn out: {:.0}% effect until repaired",
+            durable.max_durability(),
+            WORN_OUT_EFFECTIVENESS * 100.0
+        )
+    } else {
+        format!("Durability: {:.0}/{:.0}", durable.durability(), durable.max_durability())
+    };
+    ui.label(
+        RichText::new(text)
+            .font(FontId::proportional(12.0))
+            .color(durability_color(durable)),
+    );
+}
+
 fn inventory_item_button(ui: &mut Ui, item: &Item, selected: bool) -> bool {
     let fill = if selected {
         Color32::from_rgba_unmultiplied(70, 70, 100, 220)
@@ -572,7 +869,7 @@ fn inventory_item_button(ui: &mut Ui, item: &Item, selected: bool) -> bool {
     .clicked()
 }
 
-fn render_item_detail(ui: &mut Ui, item: &Item) {
+fn render_item_detail(ui: &mut Ui, item: &Item, active_year: i64, paradox: &ParadoxConfig) {
     ui.label(
         RichText::new(&item.name)
             .font(FontId::proportional(18.0))
@@ -590,6 +887,21 @@ fn render_item_detail(ui: &mut Ui, item: &Item) {
                 .color(Color32::from_rgb(160, 180, 220)),
         );
     }
+    if let Some(origin) = item.origin_year {
+        ui.label(
+            RichText::new(format!("Origin: {}", format_year(origin)))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(200, 180, 140)),
+        );
+        let severity = anachronism(Some(origin), active_year, paradox);
+        if severity > 0.0 {
+            ui.label(
+                RichText::new(format!("Anachronism {:.0}%: using it here builds paradox", severity * 100.0))
+                    .font(FontId::proportional(12.0))
+                    .color(Color32::from_rgb(220, 90, 90)),
+            );
+        }
+    }
     ui.add_space(4.0);
     ui.label(
         RichText::new(&item.description)
@@ -629,6 +941,30 @@ fn render_item_detail(ui: &mut Ui, item: &Item) {
         );
     }
 
+    // Armor data
+    if let Some(armor) = &item.armor_data {
+        ui.add_space(4.0);
+        ui.label(
+            RichText::new(format!("Armor: {:.0}", armor.armor))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(170, 190, 210)),
+        );
+    }
+
+    if let Some(durable) = item.durable() {
+        durability_line(ui, durable);
+    }
+
+    // Shield data
+    if let Some(shield) = &item.shield_data {
+        ui.add_space(4.0);
+        ui.label(
+            RichText::new(format!("Block: {:.0}  (Off Hand)", shield.block_value))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(200, 180, 140)),
+        );
+    }
+
     if item.stack_count > 1 {
         ui.label(
             RichText::new(format!("Stack: {}/{}", item.stack_count, item.max_stack))
@@ -636,6 +972,220 @@ fn render_item_detail(ui: &mut Ui, item: &Item) {
                 .color(Color32::from_rgb(160, 160, 180)),
         );
     }
+
+    let weight = if item.stack_count > 1 {
+        format!("Weight: {:.1} kg ({:.1} each)", item.total_weight(), item.weight())
+    } else {
+        format!("Weight: {:.1} kg", item.weight())
+    };
+    ui.label(
+        RichText::new(weight)
+            .font(FontId::proportional(11.0))
+            .color(Color32::from_rgb(160, 160, 180)),
+    );
+}
+
+/// Draw a treasure map: its terrain snippet shaded by height with the dig
+/// spot marked in the middle (north is up), plus directions and era
+fn render_treasure_map(ui: &mut Ui, map: &TreasureMapData, player_position: Vec3) {
+    ui.add_space(6.0);
+    let size = 160.0;
+    let (rect, _) = ui.allocate_exact_size(Vec2::splat(size), Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, Color32::from_rgb(210, 186, 138));
+
+    let n = SNIPPET_RESOLUTION as usize;
+    let cell = size / n as f32;
+    let (lo, hi) = map.snippet_range();
+    let span = (hi - lo).max(0.01);
+    for (i, &height) in map.snippet.iter().enumerate() {
+        let (row, col) = (i / n, i % n);
+        // Low ground is dark ink, high ground pale parchment
+        let t = (height - lo) / span;
+        let shade = |dark: f32, light: f32| (dark + (light - dark) * t) as u8;
+        let min = rect.min + Vec2::new(col as f32 * cell, row as f32 * cell);
+        painter.rect_filled(
+            Rect::from_min_size(min, Vec2::splat(cell)),
+            0.0,
+            Color32::from_rgba_unmultiplied(shade(110.0, 235.0), shade(80.0, 215.0), shade(50.0, 170.0), 200),
+        );
+    }
+
+    // X marks the spot
+    let center = rect.center();
+    let arm = cell * 1.2;
+    let ink = Stroke::new(3.0, Color32::from_rgb(160, 30, 30));
+    painter.line_segment([center - Vec2::splat(arm), center + Vec2::splat(arm)], ink);
+    painter.line_segment([center + Vec2::new(-arm, arm), center + Vec2::new(arm, -arm)], ink);
+    painter.text(
+        Pos2::new(rect.center().x, rect.min.y + 10.0),
+        egui::Align2::CENTER_CENTER,
+        "N",
+        FontId::proportional(12.0),
+        Color32::from_rgb(60, 40, 20),
+    );
+    painter.rect_stroke(rect, 4.0, Stroke::new(2.0, Color32::from_rgb(90, 60, 30)), egui::StrokeKind::Inside);
+
+    ui.add_space(4.0);
+    ui.label(
+        RichText::new(map.hint(player_position))
+            .font(FontId::proportional(12.0))
+            .color(Color32::from_rgb(220, 200, 150)),
+    );
+    ui.label(
+        RichText::new(format!("Marked at ({:.0}, {:.0})", map.target[0], map.target[1]))
+            .font(FontId::proportional(11.0))
+            .color(Color32::from_rgb(160, 160, 180)),
+    );
+    if let Some(year) = map.required_year {
+        ui.label(
+            RichText::new(format!("Drawn in {} - dig there", format_year(year)))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(180, 160, 230)),
+        );
+    }
+}
+
+/// Carried keys, lockpicks, and which lock tiers the player can pick
+fn render_key_ring_tab(
+    ui: &mut Ui,
+    key_ring: &KeyRing,
+    equipment: &EquipmentSet,
+    inventory: &Inventory,
+    stats: &CharacterStats,
+) {
+    ui.horizontal(|ui| {
+        // Left: the key ring
+        ui.vertical(|ui| {
+            ui.set_min_width(220.0);
+            ui.label(
+                RichText::new(format!("Key Ring ({})", key_ring.len()))
+                    .font(FontId::proportional(14.0))
+                    .color(Color32::from_rgb(200, 200, 255)),
+            );
+            ui.add_space(5.0);
+            if key_ring.is_empty() {
+                ui.label(
+                    RichText::new("No keys yet")
+                        .color(Color32::from_rgb(140, 140, 160)),
+                );
+            }
+            for key in key_ring.keys() {
+                ui.label(
+                    RichText::new(format!("\u{1F511} {}", key.name))
+                        .color(Color32::from_rgb(230, 200, 110)),
+                );
+            }
+        });
+
+        ui.add_space(20.0);
+
+        // Right: lockpicking
+        ui.vertical(|ui| {
+            ui.set_min_width(240.0);
+            let lockpicks: u32 = inventory.items.iter()
+                .filter(|item| item.id == LOCKPICK_ITEM_ID)
+                .map(|item| item.stack_count)
+                .sum();
+            let dexterity = stats.effective_stats(&equipment.total_modifiers()).dexterity();
+            ui.label(
+                RichText::new("Lockpicking")
+                    .font(FontId::proportional(14.0))
+                    .color(Color32::from_rgb(200, 200, 255)),
+            );
+            ui.add_space(5.0);
+            ui.label(format!("Lockpicks: {}", lockpicks));
+            ui.label(format!("Dexterity: {:.0}", dexterity));
+            ui.add_space(5.0);
+            for tier in [LockTier::Simple, LockTier::Sturdy, LockTier::Masterwork] {
+                let (text, color) = if LockpickSession::can_attempt(tier, dexterity) {
+                    (format!("{} locks: {} pins", tier.name(), tier.pins()), Color32::from_rgb(120, 220, 120))
+                } else {
+                    (
+                        format!("{} locks: need {:.0} dexterity", tier.name(), tier.required_dexterity()),
+                        Color32::from_rgb(200, 120, 120),
+                    )
+                };
+                ui.label(RichText::new(text).color(color));
+            }
+        });
+    });
+}
+
+/// Stats, affinities, drops and habitat of a bestiary entry
+fn render_bestiary_entry(ui: &mut Ui, entry: &BestiaryEntry) {
+    let profile = &entry.profile;
+    ui.label(
+        RichText::new(&entry.name)
+            .font(FontId::proportional(18.0))
+            .color(Color32::from_rgb(220, 120, 110)),
+    );
+    ui.label(
+        RichText::new(format!("Defeated {} times ({} elite)", entry.kills, entry.elite_kills))
+            .font(FontId::proportional(12.0))
+            .color(Color32::from_rgb(180, 180, 200)),
+    );
+
+    ui.add_space(6.0);
+    let white = Color32::from_rgb(220, 220, 230);
+    stat_display_line(ui, "Max HP", profile.max_hp, white);
+    stat_display_line(ui, "Attack", profile.attack, white);
+    stat_display_line(ui, "Defense", profile.defense, white);
+    stat_display_line(ui, "Armor", profile.armor, white);
+
+    ui.add_space(6.0);
+    let element_names = |elements: Vec<Element>| {
+        if elements.is_empty() {
+            "None".to_string()
+        } else {
+            elements.iter().map(|e| e.name()).collect::<Vec<_>>().join(", ")
+        }
+    };
+    let affinity = Color32::from_rgb(160, 180, 220);
+    for line in [
+        format!("Element: {}", profile.element.name()),
+        format!("Weak to: {}", element_names(profile.weak_to())),
+        format!("Resists: {}", element_names(profile.resists())),
+        format!("Weapon weakness: {}", profile.weapon_weakness.map(|w| w.name()).unwrap_or("None")),
+    ] {
+        ui.label(RichText::new(line).font(FontId::proportional(12.0)).color(affinity));
+    }
+
+    ui.add_space(6.0);
+    ui.label(
+        RichText::new("Drops")
+            .font(FontId::proportional(14.0))
+            .color(Color32::from_rgb(200, 200, 255)),
+    );
+    for drop in entry.revealed_drops() {
+        ui.label(
+            RichText::new(format!("{}  ({})", drop.name, drop.chance))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(200, 180, 140)),
+        );
+    }
+    if let Some(at) = entry.next_reveal() {
+        ui.label(
+            RichText::new(format!("??? ({} more kills to reveal)", at - entry.kills))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(120, 120, 140)),
+        );
+    }
+
+    ui.add_space(6.0);
+    let years: Vec<String> = entry.years.iter().map(|&y| format_year(y)).collect();
+    let biomes: Vec<&str> = entry.biomes.iter().map(String::as_str).collect();
+    let habitat = Color32::from_rgb(150, 200, 150);
+    ui.label(
+        RichText::new(format!("Eras: {}", years.join(", ")))
+            .font(FontId::proportional(12.0))
+            .color(habitat),
+    );
+    ui.label(
+        RichText::new(format!("Habitat: {}", biomes.join(", ")))
+            .font(FontId::proportional(12.0))
+            .color(habitat),
+    );
 }
 
 fn stat_line(ui: &mut Ui, name: &str, value: f32) {
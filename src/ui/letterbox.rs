@@ -0,0 +1,43 @@
+//! Letterbox bars and vignette for framed conversations
+
+use egui::epaint::{Mesh, Vertex, WHITE_UV};
+use egui::{Color32, Id, LayerId, Order, Pos2, Rect, Shape};
+
+/// Bar height as a fraction of the screen height, fully in
+const BAR_FRACTION: f32 = 0.1;
+/// How far the vignette reaches in from each edge, as a fraction of the screen
+const VIGNETTE_REACH: f32 = 0.3;
+/// Vignette darkness at the screen edge
+const VIGNETTE_ALPHA: f32 = 170.0;
+
+/// Darken the edges and slide in black bars; `amount` runs from 0 (off)
+/// to 1 (fully framed)
+pub fn draw_letterbox(ctx: &egui::Context, amount: f32) {
+    if amount <= 0.0 {
+        return;
+    }
+    let screen = ctx.screen_rect();
+    let painter = ctx.layer_painter(LayerId::new(Order::Background, Id::new("letterbox")));
+
+    // Clear in the middle, dark at the edges: an outer ring of dark vertices
+    // around an inner ring of transparent ones
+    let inner = screen.shrink2(screen.size() * VIGNETTE_REACH);
+    let edge = Color32::from_black_alpha((VIGNETTE_ALPHA * amount) as u8);
+    let corners = |rect: Rect| [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()];
+    let mut mesh = Mesh::default();
+    for (rect, color) in [(screen, edge), (inner, Color32::TRANSPARENT)] {
+        for pos in corners(rect) {
+            mesh.vertices.push(Vertex { pos, uv: WHITE_UV, color });
+        }
+    }
+    for side in 0..4u32 {
+        let next = (side + 1) % 4;
+        mesh.add_triangle(side, next, 4 + side);
+        mesh.add_triangle(next, 4 + next, 4 + side);
+    }
+    painter.add(Shape::mesh(mesh));
+
+    let bar = screen.height() * BAR_FRACTION * amount;
+    painter.rect_filled(Rect::from_min_max(screen.min, Pos2::new(screen.max.x, screen.min.y + bar)), 0.0, Color32::BLACK);
+    painter.rect_filled(Rect::from_min_max(Pos2::new(screen.min.x, screen.max.y - bar), screen.max), 0.0, Color32::BLACK);
+}
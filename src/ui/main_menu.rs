@@ -1,18 +1,58 @@
 //! Main menu UI
 
-use egui::{Align, Color32, FontId, Layout, RichText, Ui, Vec2};
+use egui::{Align, Color32, FontId, Layout, RichText, ScrollArea, Ui, Vec2};
 
+use crate::character::{CharacterData, CharacterRoster, RosterStatus, SyncState};
 use crate::state::{ApplicationState, StateTransition};
 
+/// Where an imported build goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportTarget {
+    /// Added to the roster as a new character
+    Roster,
+    /// A one-off character for the survival arena
+    Arena,
+}
+
 /// Main menu renderer
 pub struct MainMenu {
     /// Whether a save file exists (for Continue button)
     has_save: bool,
+    /// Character picked to play, taken when the menu hands over to Playing
+    pub chosen: Option<String>,
+    /// The chosen character is off to the survival arena rather than the world
+    pub survival: bool,
+    /// Character the player asked to delete, taken by the caller
+    pub delete_requested: Option<String>,
+    /// Character waiting for the player to confirm its deletion
+    confirm_delete: Option<String>,
+    /// The build import panel is showing
+    import_open: bool,
+    /// Build code, JSON or file path typed into the import panel
+    pub import_text: String,
+    /// The player asked to import [`Self::import_text`], taken by the caller
+    pub import_requested: Option<ImportTarget>,
+    /// How the last import went, set by the caller
+    pub import_notice: Option<Result<String, String>>,
+    /// Imported character off to the arena, taken when the menu hands over
+    /// to Playing
+    pub arena_character: Option<CharacterData>,
 }
 
 impl MainMenu {
     pub fn new() -> Self {
-        Self { has_save: false }
+        Self {
+            has_save: false,
+            chosen: None,
+            survival: false,
+            delete_requested: None,
+            confirm_delete: None,
+            import_open: false,
+            import_text: String::new(),
+            import_requested: None,
+            import_notice: None,
+            arena_character: None,
+        }
     }
 
     /// Set whether a save file exists
@@ -24,7 +64,7 @@ impl MainMenu {
     /// Render the main menu and return any state transition.
     /// `is_admin` controls whether the Admin Tools button is shown.
     /// `user_name` is shown as a greeting if logged in.
-    pub fn render(&self, ui: &mut Ui, is_admin: bool, user_name: Option<&str>) -> StateTransition {
+    pub fn render(&mut self, ui: &mut Ui, is_admin: bool, user_name: Option<&str>, roster: &CharacterRoster) -> StateTransition {
         let mut transition = StateTransition::None;
         let available = ui.available_size();
 
@@ -37,7 +77,19 @@ impl MainMenu {
                     .color(Color32::from_rgb(200, 200, 255)),
             );
 
-            ui.add_space(80.0);
+            ui.add_space(40.0);
+
+            if roster.characters().next().is_some() {
+                self.render_roster(ui, roster, &mut transition);
+                ui.add_space(20.0);
+            } else {
+                ui.add_space(40.0);
+            }
+
+            if self.import_open {
+                self.render_import(ui);
+                ui.add_space(20.0);
+            }
 
             // Menu buttons
             let button_width = 200.0;
@@ -52,6 +104,14 @@ impl MainMenu {
 
                 ui.add_space(10.0);
 
+                // Import Build
+                if menu_button(ui, "Import Build", button_size) {
+                    self.import_open = !self.import_open;
+                    self.import_notice = None;
+                }
+
+                ui.add_space(10.0);
+
                 // Continue (only if save exists)
                 if self.has_save {
                     if menu_button(ui, "Continue", button_size) {
@@ -104,6 +164,120 @@ impl MainMenu {
 
         transition
     }
+
+    /// The account's characters, each with Play, Survival and Delete
+    fn render_roster(&mut self, ui: &mut Ui, roster: &CharacterRoster, transition: &mut StateTransition) {
+        ui.label(
+            RichText::new("Characters")
+                .font(FontId::proportional(20.0))
+                .color(Color32::from_rgb(200, 200, 240)),
+        );
+        let (status, color) = match roster.status() {
+            RosterStatus::Synced => ("Synced with the server".to_string(), Color32::from_rgb(120, 180, 120)),
+            RosterStatus::Syncing => ("Syncing...".to_string(), Color32::from_rgb(180, 180, 120)),
+            RosterStatus::Offline => match roster.unsynced() {
+                0 => ("Offline".to_string(), Color32::from_rgb(150, 150, 150)),
+                n => (format!("Offline - {} change(s) will sync when you're back online", n), Color32::from_rgb(200, 160, 100)),
+            },
+        };
+        ui.label(RichText::new(status).font(FontId::proportional(12.0)).color(color));
+        ui.add_space(6.0);
+
+        ScrollArea::vertical().max_height(220.0).show(ui, |ui| {
+            for character in roster.characters() {
+                ui.horizontal(|ui| {
+                    ui.add_space((ui.available_width() - 500.0).max(0.0) / 2.0);
+                    let class = character.archetype.map_or("No archetype", |a| a.name());
+                    let marker = if character.sync == SyncState::Synced { "" } else { " *" };
+                    ui.label(
+                        RichText::new(format!("{}{}", character.name, marker))
+                            .font(FontId::proportional(16.0))
+                            .color(Color32::from_rgb(220, 220, 240)),
+                    );
+                    ui.label(
+                        RichText::new(format!("Level {} {}", character.snapshot.level.max(1), class))
+                            .font(FontId::proportional(13.0))
+                            .color(Color32::from_rgb(150, 150, 180)),
+                    );
+
+                    if self.confirm_delete.as_deref() == Some(character.id.as_str()) {
+                        if ui.button("Really delete").clicked() {
+                            self.delete_requested = Some(character.id.clone());
+                            self.confirm_delete = None;
+                        }
+                        if ui.button("Keep").clicked() {
+                            self.confirm_delete = None;
+                        }
+                    } else {
+                        if ui.button("Play").clicked() {
+                            self.chosen = Some(character.id.clone());
+                            self.survival = false;
+                            *transition = StateTransition::Replace(ApplicationState::Playing);
+                        }
+                        let survival = ui.button("Survival")
+                            .on_hover_text("Fight endless waves in the arena for a place on the leaderboard");
+                        if survival.clicked() {
+                            self.chosen = Some(character.id.clone());
+                            self.survival = true;
+                            *transition = StateTransition::Replace(ApplicationState::Playing);
+                        }
+                        if ui.button("Delete").clicked() {
+                            self.confirm_delete = Some(character.id.clone());
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Box for a shared build, with where to take it
+    fn render_import(&mut self, ui: &mut Ui) {
+        ui.label(
+            RichText::new("Import Build")
+                .font(FontId::proportional(20.0))
+                .color(Color32::from_rgb(200, 200, 240)),
+        );
+        ui.label(
+            RichText::new("Paste a build code or JSON, or the path of a build file")
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(150, 150, 180)),
+        );
+        ui.add_space(6.0);
+        ui.add(egui::TextEdit::multiline(&mut self.import_text).desired_width(480.0).desired_rows(3));
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - 320.0).max(0.0) / 2.0);
+            let ready = !self.import_text.trim().is_empty();
+            if ui.add_enabled(ready, egui::Button::new("Add as new character")).clicked() {
+                self.import_requested = Some(ImportTarget::Roster);
+            }
+            let arena = ui
+                .add_enabled(ready, egui::Button::new("Fight in the arena"))
+                .on_hover_text("Take the build into the survival arena without adding it to your characters");
+            if arena.clicked() {
+                self.import_requested = Some(ImportTarget::Arena);
+            }
+            if ui.button("Close").clicked() {
+                self.import_open = false;
+                self.import_notice = None;
+            }
+        });
+        match &self.import_notice {
+            Some(Ok(notice)) => {
+                ui.label(RichText::new(notice).font(FontId::proportional(13.0)).color(Color32::from_rgb(120, 180, 120)));
+            }
+            Some(Err(reason)) => {
+                ui.label(RichText::new(reason).font(FontId::proportional(13.0)).color(Color32::from_rgb(220, 120, 100)));
+            }
+            None => {}
+        }
+    }
+
+    /// Clear the import box once a build has made it in
+    pub fn finish_import(&mut self, notice: String) {
+        self.import_text.clear();
+        self.import_notice = Some(Ok(notice));
+    }
 }
 
 impl Default for MainMenu {
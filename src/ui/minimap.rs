@@ -0,0 +1,76 @@
+//! Minimap HUD: a player-centred radar of nearby markers
+
+use egui::{Align2, Color32, FontId, Stroke, Vec2};
+use glam::Vec3;
+use infinite_game::compass::{bearing, relative_bearing};
+use infinite_game::{CompassFilter, CompassMarker, MarkerCategory};
+
+use super::compass::marker_color;
+
+/// Radius of the minimap disc in points
+const RADIUS: f32 = 64.0;
+
+/// Minimap rotating with the camera, drawn below the time/weather panel
+pub struct MinimapHud {
+    /// World distance (meters) from the centre to the edge
+    pub range: f32,
+}
+
+impl MinimapHud {
+    pub fn new() -> Self {
+        Self { range: 80.0 }
+    }
+
+    /// Draw the minimap around `player_pos`, forward (`heading`) pointing up
+    pub fn render<'a>(
+        &self,
+        ctx: &egui::Context,
+        player_pos: Vec3,
+        heading: f32,
+        markers: impl IntoIterator<Item = &'a CompassMarker>,
+        filter: &CompassFilter,
+    ) {
+        egui::Area::new(egui::Id::new("minimap"))
+            .anchor(Align2::RIGHT_TOP, [-10.0, 90.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                let (rect, _) = ui.allocate_exact_size(Vec2::splat(RADIUS * 2.0), egui::Sense::hover());
+                let painter = ui.painter();
+                let center = rect.center();
+                painter.circle_filled(center, RADIUS, Color32::from_rgba_unmultiplied(0, 0, 0, 150));
+                painter.circle_stroke(center, RADIUS, Stroke::new(1.0, Color32::from_gray(120)));
+
+                // North indicator on the rim
+                let north = relative_bearing(heading, 0.0).to_radians();
+                let rim = center + Vec2::new(north.sin(), -north.cos()) * (RADIUS - 8.0);
+                painter.text(rim, Align2::CENTER_CENTER, "N", FontId::proportional(11.0), Color32::from_rgb(255, 120, 100));
+
+                for marker in markers {
+                    if !filter.allows(marker.category) {
+                        continue;
+                    }
+                    let offset = (marker.position - player_pos).with_y(0.0);
+                    let distance = offset.length();
+                    let tracked = matches!(marker.category, MarkerCategory::Objective | MarkerCategory::Waypoint);
+                    if distance > self.range && !tracked {
+                        continue;
+                    }
+                    // Tracked markers out of range sit on the rim
+                    let radial = (distance / self.range).min(1.0) * (RADIUS - 4.0);
+                    let angle = relative_bearing(heading, bearing(player_pos, marker.position)).to_radians();
+                    let pos = center + Vec2::new(angle.sin(), -angle.cos()) * radial;
+                    let color = marker_color(marker.category);
+                    if tracked {
+                        painter.rect_filled(egui::Rect::from_center_size(pos, Vec2::splat(7.0)), 1.0, color);
+                    } else {
+                        painter.circle_filled(pos, 2.5, color);
+                    }
+                }
+
+                // Player arrow, always pointing up
+                let tip = center + Vec2::new(0.0, -7.0);
+                let arrow = vec![tip, center + Vec2::new(5.0, 5.0), center + Vec2::new(-5.0, 5.0)];
+                painter.add(egui::Shape::convex_polygon(arrow, Color32::from_rgb(255, 220, 120), Stroke::NONE));
+            });
+    }
+}
@@ -3,23 +3,69 @@
 //! Contains all egui-based UI screens and menus.
 
 pub mod admin;
+mod balance_panel;
+mod barks;
+mod brewing_menu;
 mod character_creator;
+mod combat_stats;
+mod compass;
+mod cooking_menu;
+mod crosshair;
+mod damage_numbers;
+mod dialogue_options;
+mod error_dialog;
+mod hud_layout;
+mod inspector;
 mod inventory_menu;
+mod letterbox;
 mod loading_screen;
 mod login_menu;
 mod main_menu;
+mod minimap;
 mod pause_menu;
+mod relationships_menu;
+mod respec_menu;
+mod rift_menu;
 mod save_load_menu;
+mod screen_markers;
 mod settings_menu;
 mod shop_menu;
+mod shrine_menu;
+mod speedrun_overlay;
+mod survival_menu;
+mod timeline_browser;
+mod world_boss_banner;
 
-pub use admin::AdminPanel;
+pub use admin::{AdminPanel, AuditAction, AuditKind, AuditLog, GmAction, GmObject, GmSpawn, GmTools, TemplateSpawner};
+pub use balance_panel::BalancePanel;
+pub use barks::draw_barks;
+pub use brewing_menu::{BrewingAction, BrewingMenu};
 pub use character_creator::CharacterCreator;
+pub use combat_stats::CombatStatsPanel;
+pub use compass::CompassHud;
+pub use cooking_menu::{CookingAction, CookingMenu};
+pub use crosshair::draw_crosshair;
+pub use damage_numbers::DamageNumberHud;
+pub use dialogue_options::{check_outcome, response_button};
+pub use error_dialog::{ErrorDialog, ErrorDialogAction};
+pub use hud_layout::{apply_layout, widget_controls, HudEditor};
+pub use inspector::{pick, EntityInspector, InspectTarget};
 pub use inventory_menu::{InventoryAction, InventoryMenu};
+pub use letterbox::draw_letterbox;
 pub use loading_screen::LoadingScreen;
 pub use login_menu::LoginMenu;
-pub use main_menu::MainMenu;
-pub use pause_menu::PauseMenu;
+pub use main_menu::{ImportTarget, MainMenu};
+pub use minimap::MinimapHud;
+pub use pause_menu::{PauseMenu, PausePage, PauseSummary};
+pub use relationships_menu::{dialogue_buttons, relationship_details, RelationshipsView};
+pub use respec_menu::{RespecAction, RespecMenu};
+pub use rift_menu::{draw_rift_hud, render_boon_choice, RiftAction, RiftMenu};
 pub use save_load_menu::{SaveLoadAction, SaveLoadMenu};
+pub use screen_markers::ScreenMarkerHud;
 pub use settings_menu::SettingsMenu;
-pub use shop_menu::{ShopAction, ShopMenu, sell_price_for};
+pub use shop_menu::{ShopAction, ShopMenu, buy_price_for, market_sell_price};
+pub use shrine_menu::{ShrineAction, ShrineMenu};
+pub use speedrun_overlay::draw_speedrun_overlay;
+pub use survival_menu::{draw_survival_hud, ScoreStatus, SurvivalAction, SurvivalMenu};
+pub use timeline_browser::{TimelineAction, TimelineBrowser};
+pub use world_boss_banner::draw_world_boss_banner;
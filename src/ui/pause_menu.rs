@@ -1,19 +1,110 @@
 //! Pause menu UI
 
+use std::path::PathBuf;
+
 use egui::{Align, Color32, FontId, Layout, RichText, Ui, Vec2};
 
+use infinite_core::time::format_year;
+use infinite_game::{PlayStatistics, RelationshipManager};
+
+use crate::character::CharacterBuild;
+use crate::save::format_play_time;
 use crate::state::{ApplicationState, StateTransition};
+use crate::ui::RelationshipsView;
+
+/// Which page of the pause menu is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausePage {
+    Main,
+    Statistics,
+    Relationships,
+    ShareBuild,
+}
+
+/// World state and statistics shown on the statistics page
+pub struct PauseSummary<'a> {
+    pub play_time: f64,
+    pub year: i64,
+    pub time_of_day: String,
+    pub weather: &'static str,
+    pub level: u32,
+    pub gold: u64,
+    pub npcs_befriended: usize,
+    pub bestiary_entries: usize,
+    pub stats: &'a PlayStatistics,
+    pub relationships: &'a RelationshipManager,
+    /// A speedrun is being timed, so its buttons show
+    pub speedrun: bool,
+}
+
+/// The player's build as shown on the share page
+pub struct SharedBuild {
+    pub build: CharacterBuild,
+    /// The code, or why one couldn't be made
+    pub code: Result<String, String>,
+    /// Where the build file was written, or why it couldn't be
+    pub file: Option<Result<PathBuf, String>>,
+}
 
 /// Pause menu renderer
-pub struct PauseMenu;
+pub struct PauseMenu {
+    pub page: PausePage,
+    relationships: RelationshipsView,
+    /// The build on the share page, filled in by the caller
+    pub shared: Option<SharedBuild>,
+    /// The share page was opened and wants the player's build, taken by the caller
+    pub share_requested: bool,
+    /// The player asked for the build as a file, taken by the caller
+    pub export_requested: bool,
+    /// The player wants the speedrun timer started over, taken by the caller
+    pub restart_run_requested: bool,
+    /// The player asked for the splits as a file, taken by the caller
+    pub export_splits_requested: bool,
+    /// Result of the last speedrun button, shown under the buttons
+    pub speedrun_notice: Option<String>,
+}
 
 impl PauseMenu {
     pub fn new() -> Self {
-        Self
+        Self {
+            page: PausePage::Main,
+            relationships: RelationshipsView::new(),
+            shared: None,
+            share_requested: false,
+            export_requested: false,
+            restart_run_requested: false,
+            export_splits_requested: false,
+            speedrun_notice: None,
+        }
+    }
+
+    /// Show `build` on the share page
+    pub fn share(&mut self, build: CharacterBuild) {
+        self.shared = Some(SharedBuild {
+            code: build.to_code().map_err(|e| e.to_string()),
+            build,
+            file: None,
+        });
     }
 
     /// Render the pause menu and return any state transition
-    pub fn render(&self, ui: &mut Ui) -> StateTransition {
+    pub fn render(&mut self, ui: &mut Ui, summary: &PauseSummary) -> StateTransition {
+        match self.page {
+            PausePage::Main => {}
+            PausePage::Statistics => {
+                self.render_statistics(ui, summary);
+                return StateTransition::None;
+            }
+            PausePage::Relationships => {
+                self.render_relationships(ui, summary.relationships);
+                return StateTransition::None;
+            }
+            PausePage::ShareBuild => {
+                self.render_share(ui);
+                return StateTransition::None;
+            }
+        }
+
         let mut transition = StateTransition::None;
         let available = ui.available_size();
 
@@ -61,6 +152,47 @@ impl PauseMenu {
 
                 ui.add_space(10.0);
 
+                // Statistics
+                if pause_button(ui, "Statistics", button_size) {
+                    self.page = PausePage::Statistics;
+                }
+
+                ui.add_space(10.0);
+
+                // Relationships
+                if pause_button(ui, "Relationships", button_size) {
+                    self.page = PausePage::Relationships;
+                }
+
+                ui.add_space(10.0);
+
+                // Share Build
+                if pause_button(ui, "Share Build", button_size) {
+                    self.page = PausePage::ShareBuild;
+                    self.shared = None;
+                    self.share_requested = true;
+                }
+
+                ui.add_space(10.0);
+
+                // Speedrun
+                if summary.speedrun {
+                    if pause_button(ui, "Restart Run", button_size) {
+                        self.restart_run_requested = true;
+                    }
+
+                    ui.add_space(10.0);
+
+                    if pause_button(ui, "Export Splits", button_size) {
+                        self.export_splits_requested = true;
+                    }
+                    if let Some(notice) = &self.speedrun_notice {
+                        ui.label(RichText::new(notice).size(13.0).color(Color32::from_rgb(180, 180, 200)));
+                    }
+
+                    ui.add_space(10.0);
+                }
+
                 // Settings
                 if pause_button(ui, "Settings", button_size) {
                     transition = StateTransition::Push(ApplicationState::Settings {
@@ -86,6 +218,219 @@ impl PauseMenu {
 
         transition
     }
+
+    /// World summary and lifetime statistics, with a Back button
+    fn render_statistics(&mut self, ui: &mut Ui, summary: &PauseSummary) {
+        let available = ui.available_size();
+        ui.painter().rect_filled(
+            ui.max_rect(),
+            0.0,
+            Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+        );
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(available.y * 0.12);
+            ui.label(
+                RichText::new("STATISTICS")
+                    .font(FontId::proportional(40.0))
+                    .color(Color32::from_rgb(200, 200, 255)),
+            );
+            ui.add_space(25.0);
+
+            let stats = summary.stats;
+            let years: Vec<String> = stats.years_visited.iter().map(|&y| format_year(y)).collect();
+            let sections = [
+                (
+                    "World",
+                    vec![
+                        ("Year", format_year(summary.year)),
+                        ("Time of day", summary.time_of_day.clone()),
+                        ("Weather", summary.weather.to_string()),
+                        ("Level", summary.level.to_string()),
+                        ("Gold", summary.gold.to_string()),
+                    ],
+                ),
+                (
+                    "Journey",
+                    vec![
+                        ("Play time", format_play_time(summary.play_time)),
+                        ("Distance traveled", format_distance(stats.distance_traveled)),
+                        ("Years visited", years.len().to_string()),
+                        ("NPCs befriended", summary.npcs_befriended.to_string()),
+                    ],
+                ),
+                (
+                    "Combat & Trade",
+                    vec![
+                        ("Enemies defeated", stats.enemies_defeated.to_string()),
+                        ("World bosses defeated", stats.world_bosses_defeated.to_string()),
+                        ("Bestiary entries", summary.bestiary_entries.to_string()),
+                        ("Deaths", stats.deaths.to_string()),
+                        ("Gold earned", stats.gold_earned.to_string()),
+                        ("Gold spent", stats.gold_spent.to_string()),
+                    ],
+                ),
+            ];
+
+            ui.horizontal(|ui| {
+                ui.add_space(available.x * 0.18);
+                for (title, rows) in &sections {
+                    ui.vertical(|ui| {
+                        ui.set_min_width(available.x * 0.2);
+                        ui.label(
+                            RichText::new(*title)
+                                .font(FontId::proportional(18.0))
+                                .color(Color32::from_rgb(255, 220, 120)),
+                        );
+                        ui.add_space(6.0);
+                        for (name, value) in rows {
+                            ui.label(
+                                RichText::new(format!("{}: {}", name, value))
+                                    .font(FontId::proportional(14.0))
+                                    .color(Color32::from_rgb(220, 220, 240)),
+                            );
+                        }
+                    });
+                }
+            });
+
+            if !years.is_empty() {
+                ui.add_space(15.0);
+                ui.label(
+                    RichText::new(format!("Visited: {}", years.join(", ")))
+                        .font(FontId::proportional(13.0))
+                        .color(Color32::from_rgb(180, 160, 230)),
+                );
+            }
+
+            ui.add_space(30.0);
+            if pause_button(ui, "Back", Vec2::new(180.0, 40.0)) {
+                self.page = PausePage::Main;
+            }
+        });
+    }
+
+    /// Everyone the player knows, with a Back button
+    fn render_relationships(&mut self, ui: &mut Ui, relationships: &RelationshipManager) {
+        let available = ui.available_size();
+        ui.painter().rect_filled(
+            ui.max_rect(),
+            0.0,
+            Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+        );
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(available.y * 0.1);
+            ui.label(
+                RichText::new("RELATIONSHIPS")
+                    .font(FontId::proportional(40.0))
+                    .color(Color32::from_rgb(200, 200, 255)),
+            );
+            ui.add_space(20.0);
+        });
+        ui.horizontal(|ui| {
+            ui.add_space((available.x - 640.0).max(0.0) * 0.5);
+            self.relationships.render(ui, relationships);
+        });
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            if pause_button(ui, "Back", Vec2::new(180.0, 40.0)) {
+                self.page = PausePage::Main;
+            }
+        });
+    }
+
+    /// The player's build as a code to copy or a file to save, with a Back button
+    fn render_share(&mut self, ui: &mut Ui) {
+        let available = ui.available_size();
+        ui.painter().rect_filled(
+            ui.max_rect(),
+            0.0,
+            Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+        );
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(available.y * 0.15);
+            ui.label(
+                RichText::new("SHARE BUILD")
+                    .font(FontId::proportional(40.0))
+                    .color(Color32::from_rgb(200, 200, 255)),
+            );
+            ui.add_space(20.0);
+
+            let muted = Color32::from_rgb(180, 180, 200);
+            match &self.shared {
+                None => {
+                    ui.label(RichText::new("There's no build to share.").color(muted));
+                }
+                Some(shared) => {
+                    let build = &shared.build;
+                    let class = match &build.secondary {
+                        Some(secondary) => format!("{} / {}", build.archetype, secondary),
+                        None => build.archetype.clone(),
+                    };
+                    ui.label(
+                        RichText::new(format!("{} - level {} {}", build.name, build.level, class))
+                            .font(FontId::proportional(18.0))
+                            .color(Color32::from_rgb(255, 220, 120)),
+                    );
+                    let gear: Vec<&str> = build.equipment.iter().map(|item| item.name.as_str()).collect();
+                    if !gear.is_empty() {
+                        ui.label(RichText::new(gear.join(", ")).font(FontId::proportional(13.0)).color(muted));
+                    }
+                    ui.add_space(12.0);
+
+                    match &shared.code {
+                        Ok(code) => {
+                            let mut shown = code.as_str();
+                            ui.add(egui::TextEdit::multiline(&mut shown).desired_width(520.0).desired_rows(4));
+                            ui.add_space(8.0);
+                            ui.horizontal(|ui| {
+                                ui.add_space((ui.available_width() - 380.0).max(0.0) / 2.0);
+                                if pause_button(ui, "Copy Code", Vec2::new(180.0, 40.0)) {
+                                    ui.ctx().copy_text(code.clone());
+                                }
+                                if pause_button(ui, "Save as File", Vec2::new(180.0, 40.0)) {
+                                    self.export_requested = true;
+                                }
+                            });
+                        }
+                        Err(reason) => {
+                            ui.label(RichText::new(reason).color(Color32::from_rgb(220, 120, 100)));
+                        }
+                    }
+                    match &shared.file {
+                        Some(Ok(path)) => {
+                            ui.label(RichText::new(format!("Saved to {}", path.display())).font(FontId::proportional(13.0)).color(muted));
+                        }
+                        Some(Err(reason)) => {
+                            ui.label(RichText::new(format!("Couldn't save: {}", reason)).color(Color32::from_rgb(220, 120, 100)));
+                        }
+                        None => {}
+                    }
+                    ui.label(
+                        RichText::new("Import it from the main menu, as a new character or to fight in the arena.")
+                            .font(FontId::proportional(12.0))
+                            .color(muted),
+                    );
+                }
+            }
+
+            ui.add_space(30.0);
+            if pause_button(ui, "Back", Vec2::new(180.0, 40.0)) {
+                self.page = PausePage::Main;
+            }
+        });
+    }
+}
+
+/// Meters below a kilometer, kilometers above
+fn format_distance(meters: f32) -> String {
+    if meters < 1000.0 {
+        format!("{:.0} m", meters)
+    } else {
+        format!("{:.1} km", meters / 1000.0)
+    }
 }
 
 impl Default for PauseMenu {
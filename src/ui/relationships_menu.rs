@@ -0,0 +1,198 @@
+//! Relationships — everyone the player has talked to, how close they are,
+//! gifts given, quests shared and what was said
+
+use egui::{Color32, FontId, RichText, Ui};
+
+use infinite_core::time::format_year;
+use infinite_game::combat::Item;
+use infinite_game::{NpcRelationship, RelationshipManager, RelationshipTier};
+
+/// Known NPCs on the left, the selected one's details on the right
+pub struct RelationshipsView {
+    selected: Option<u64>,
+}
+
+impl RelationshipsView {
+    pub fn new() -> Self {
+        Self { selected: None }
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, relationships: &RelationshipManager) {
+        let mut known: Vec<(u64, &NpcRelationship)> = relationships.iter().collect();
+        if known.is_empty() {
+            ui.label(
+                RichText::new("You haven't talked to anyone yet")
+                    .font(FontId::proportional(14.0))
+                    .color(Color32::from_rgb(150, 150, 170)),
+            );
+            return;
+        }
+        known.sort_by(|a, b| b.1.affection.total_cmp(&a.1.affection).then_with(|| display_name(a.1).cmp(display_name(b.1))));
+        if self.selected.and_then(|key| relationships.get(key)).is_none() {
+            self.selected = known.first().map(|(key, _)| *key);
+        }
+
+        ui.horizontal_top(|ui| {
+            ui.vertical(|ui| {
+                ui.set_width(200.0);
+                egui::ScrollArea::vertical().id_salt("relationships_list").max_height(420.0).show(ui, |ui| {
+                    for (key, relationship) in &known {
+                        let tier = relationship.tier();
+                        let text = RichText::new(format!("{}  ({})", display_name(relationship), tier.name()))
+                            .font(FontId::proportional(14.0))
+                            .color(tier_color(tier));
+                        if ui.selectable_label(self.selected == Some(*key), text).clicked() {
+                            self.selected = Some(*key);
+                        }
+                    }
+                });
+            });
+            ui.separator();
+            ui.vertical(|ui| {
+                ui.set_width(420.0);
+                if let Some(relationship) = self.selected.and_then(|key| relationships.get(key)) {
+                    ui.label(
+                        RichText::new(display_name(relationship))
+                            .font(FontId::proportional(20.0))
+                            .color(Color32::from_rgb(255, 220, 120)),
+                    );
+                    relationship_details(ui, relationship);
+                }
+            });
+        });
+    }
+}
+
+impl Default for RelationshipsView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// History toggle and gift menu beside the NPC's name in a dialogue
+/// window. Returns the inventory index of an item picked as a gift.
+pub fn dialogue_buttons(ui: &mut Ui, history_open: &mut bool, items: &[Item]) -> Option<usize> {
+    let mut gift = None;
+    ui.toggle_value(history_open, "History");
+    ui.add_enabled_ui(!items.is_empty(), |ui| {
+        ui.menu_button("Give gift", |ui| {
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                for (index, item) in items.iter().enumerate() {
+                    let label = if item.stack_count > 1 {
+                        format!("{} (x{})", item.name, item.stack_count)
+                    } else {
+                        item.name.clone()
+                    };
+                    if ui.button(label).clicked() {
+                        gift = Some(index);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+    });
+    gift
+}
+
+/// Tier, gifts, quests and the conversation log of one NPC
+pub fn relationship_details(ui: &mut Ui, relationship: &NpcRelationship) {
+    let tier = relationship.tier();
+    ui.label(
+        RichText::new(format!("{} — affection {:.0}/100", tier.name(), relationship.affection))
+            .font(FontId::proportional(15.0))
+            .color(tier_color(tier)),
+    );
+    ui.add(egui::ProgressBar::new(relationship.affection / 100.0).desired_width(240.0));
+    ui.label(muted(format!("Spoken {} times", relationship.times_spoken)));
+    ui.add_space(6.0);
+
+    heading(ui, "Gifts");
+    if relationship.gifts.is_empty() {
+        ui.label(muted("None yet".to_string()));
+    } else {
+        ui.label(RichText::new(relationship.gifts.join(", ")).font(FontId::proportional(13.0)));
+    }
+
+    heading(ui, "Quests");
+    if relationship.quests.is_empty() {
+        ui.label(muted("None yet".to_string()));
+    } else {
+        for quest in &relationship.quests {
+            ui.label(RichText::new(format!("• {}", humanize_key(quest))).font(FontId::proportional(13.0)));
+        }
+    }
+
+    heading(ui, "Conversations");
+    if relationship.conversations.is_empty() {
+        ui.label(muted("Nothing said yet".to_string()));
+        return;
+    }
+    egui::ScrollArea::vertical().id_salt("relationship_log").max_height(260.0).show(ui, |ui| {
+        // Newest first, the latest one unfolded
+        for (i, record) in relationship.conversations.iter().enumerate().rev() {
+            let title = format!("{} — {} lines", format_year(record.year), record.messages.len());
+            egui::CollapsingHeader::new(title)
+                .id_salt(("conversation", i))
+                .default_open(i + 1 == relationship.conversations.len())
+                .show(ui, |ui| {
+                    for msg in &record.messages {
+                        let color = if msg.is_player {
+                            Color32::from_rgb(150, 200, 255)
+                        } else {
+                            Color32::from_rgb(230, 230, 240)
+                        };
+                        ui.label(
+                            RichText::new(format!("{}: {}", msg.speaker, msg.text))
+                                .font(FontId::proportional(13.0))
+                                .color(color),
+                        );
+                    }
+                });
+        }
+    });
+}
+
+/// Older saves didn't keep names
+fn display_name(relationship: &NpcRelationship) -> &str {
+    if relationship.name.is_empty() {
+        "Someone"
+    } else {
+        &relationship.name
+    }
+}
+
+fn tier_color(tier: RelationshipTier) -> Color32 {
+    match tier {
+        RelationshipTier::Stranger => Color32::from_rgb(170, 170, 180),
+        RelationshipTier::Acquaintance => Color32::from_rgb(200, 200, 220),
+        RelationshipTier::Friend => Color32::from_rgb(140, 210, 140),
+        RelationshipTier::CloseFriend => Color32::from_rgb(120, 220, 200),
+        RelationshipTier::Trusted => Color32::from_rgb(150, 180, 255),
+        RelationshipTier::Bonded => Color32::from_rgb(255, 170, 220),
+    }
+}
+
+/// "find_the_relic" -> "Find the relic"
+fn humanize_key(key: &str) -> String {
+    let spaced = key.replace(['_', '-'], " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
+
+fn heading(ui: &mut Ui, text: &str) {
+    ui.add_space(4.0);
+    ui.label(
+        RichText::new(text)
+            .font(FontId::proportional(15.0))
+            .color(Color32::from_rgb(255, 220, 120)),
+    );
+}
+
+fn muted(text: String) -> RichText {
+    RichText::new(text)
+        .font(FontId::proportional(13.0))
+        .color(Color32::from_rgb(150, 150, 170))
+}
@@ -0,0 +1,134 @@
+//! Respec menu: change archetype, or multiclass into a second one
+//!
+//! Level and XP are kept; stats are rebuilt from the new archetype's base
+//! and growth. A multiclass character grows by a cut-down blend of both
+//! archetypes' growth tables.
+
+use egui::{Color32, RichText};
+
+use infinite_game::ClassSetup;
+
+use crate::character::Archetype;
+
+/// Choice made in the respec menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RespecAction {
+    /// Switch to `primary`, multiclassing into `secondary` if set
+    Confirm {
+        primary: Archetype,
+        secondary: Option<Archetype>,
+    },
+    Close,
+}
+
+/// Window for choosing archetypes, opened by talking to a quest giver
+pub struct RespecMenu {
+    primary: Archetype,
+    secondary: Option<Archetype>,
+}
+
+impl RespecMenu {
+    /// Open with the character's current class selected
+    pub fn new(class: &ClassSetup) -> Self {
+        let primary = class
+            .primary
+            .as_deref()
+            .and_then(Archetype::from_key)
+            .unwrap_or_default();
+        let secondary = class.secondary.as_deref().and_then(Archetype::from_key);
+        Self { primary, secondary }
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, class: &ClassSetup, level: u32, gold: u64, cost: u64) -> Option<RespecAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Change Path")
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!("Level {} - your level and experience are kept", level))
+                        .color(Color32::from_rgb(180, 180, 200)),
+                );
+                ui.separator();
+
+                ui.label(RichText::new("Archetype").strong());
+                for archetype in Archetype::all() {
+                    ui.radio_value(&mut self.primary, *archetype, archetype.name())
+                        .on_hover_text(archetype.description());
+                }
+                if self.secondary == Some(self.primary) {
+                    self.secondary = None;
+                }
+
+                ui.add_space(6.0);
+                let mut multiclass = self.secondary.is_some();
+                if ui.checkbox(&mut multiclass, "Multiclass").changed() {
+                    self.secondary = if multiclass {
+                        Archetype::all().iter().copied().find(|a| *a != self.primary)
+                    } else {
+                        None
+                    };
+                }
+                if let Some(secondary) = self.secondary.as_mut() {
+                    ui.horizontal(|ui| {
+                        ui.label("Second archetype:");
+                        egui::ComboBox::from_id_salt("respec_secondary")
+                            .selected_text(secondary.name())
+                            .show_ui(ui, |ui| {
+                                for archetype in Archetype::all().iter().filter(|a| **a != self.primary) {
+                                    ui.selectable_value(secondary, *archetype, archetype.name());
+                                }
+                            });
+                    });
+                    ui.label(
+                        RichText::new("Multiclass characters grow more slowly than either archetype alone")
+                            .size(12.0)
+                            .color(Color32::from_rgb(200, 160, 100)),
+                    );
+                }
+
+                ui.separator();
+                let stats = self.primary.base_stats().at_level(&self.primary.growth_with(self.secondary), level);
+                ui.label(format!(
+                    "At level {}: {:.0} HP, {:.0} attack, {:.0} defense",
+                    level, stats.max_hp, stats.attack, stats.defense
+                ));
+
+                let unchanged = class.primary.as_deref() == Some(self.primary.key().as_str())
+                    && class.secondary == self.secondary.map(|a| a.key());
+                let affordable = gold >= cost;
+                let price = if cost == 0 {
+                    "Free".to_string()
+                } else {
+                    format!("{} gold", cost)
+                };
+                ui.label(
+                    RichText::new(format!("Cost: {} (you have {})", price, gold)).color(if affordable {
+                        Color32::from_rgb(255, 215, 0)
+                    } else {
+                        Color32::from_rgb(255, 100, 100)
+                    }),
+                );
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(affordable && !unchanged, egui::Button::new("Confirm")).clicked() {
+                        action = Some(RespecAction::Confirm {
+                            primary: self.primary,
+                            secondary: self.secondary,
+                        });
+                    }
+                    if ui.button("Leave").clicked() {
+                        action = Some(RespecAction::Close);
+                    }
+                });
+            });
+        if !open {
+            action = Some(RespecAction::Close);
+        }
+        action
+    }
+}
@@ -0,0 +1,140 @@
+//! Time rift UI: the gate with its attunements, the boon choice after each
+//! cleared arena, and the depth readout while a run is on
+
+use egui::{Align2, Color32, FontId, RichText};
+use glam::Vec3;
+use infinite_game::{RiftAttunement, RiftLedger, RiftPhase, RiftRun};
+
+/// Choice made in a rift window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiftAction {
+    /// Start a run from the gate
+    Enter,
+    Attune(RiftAttunement),
+    /// Take the boon at this index of the offer
+    Choose(usize),
+    Close,
+}
+
+/// Window at the rift's gate, for starting runs and spending shards
+pub struct RiftMenu {
+    /// Where the gate stands; runs return the player here
+    pub gate: Vec3,
+}
+
+impl RiftMenu {
+    pub fn new(gate: Vec3) -> Self {
+        Self { gate }
+    }
+
+    pub fn render(&self, ctx: &egui::Context, ledger: &RiftLedger) -> Option<RiftAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Time Rift")
+            .open(&mut open)
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                let muted = Color32::from_rgb(180, 180, 200);
+                ui.label(
+                    RichText::new("Arenas torn from every era, one after another. Fall and the run is over.")
+                        .color(muted),
+                );
+                ui.label(format!(
+                    "{} shards - deepest arena {} - {} runs",
+                    ledger.shards, ledger.best_depth, ledger.runs
+                ));
+                ui.separator();
+
+                ui.label(RichText::new("Attunements").strong());
+                for attunement in RiftAttunement::ALL {
+                    let level = ledger.level(attunement);
+                    ui.horizontal(|ui| {
+                        let button = match ledger.cost(attunement) {
+                            Some(cost) => ui.add_enabled(ledger.shards >= cost, egui::Button::new(format!("{} shards", cost))),
+                            None => ui.add_enabled(false, egui::Button::new("Mastered")),
+                        };
+                        if button.on_hover_text(attunement.description()).clicked() {
+                            action = Some(RiftAction::Attune(attunement));
+                        }
+                        ui.label(RichText::new(format!("{} {}/{}", attunement.name(), level, attunement.max_level())).strong());
+                        ui.label(RichText::new(attunement.description()).size(12.0).color(muted));
+                    });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Step into the rift").clicked() {
+                        action = Some(RiftAction::Enter);
+                    }
+                    if ui.button("Leave").clicked() {
+                        action = Some(RiftAction::Close);
+                    }
+                });
+            });
+        if !open {
+            action = Some(RiftAction::Close);
+        }
+        action
+    }
+}
+
+/// The boons on offer once an arena is cleared. It can't be closed: the
+/// run goes on once one is taken.
+pub fn render_boon_choice(ctx: &egui::Context, run: &RiftRun) -> Option<RiftAction> {
+    if run.phase() != RiftPhase::Choosing {
+        return None;
+    }
+    let mut action = None;
+    egui::Window::new("Choose a boon")
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .resizable(false)
+        .collapsible(false)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.label(format!("Arena {} cleared - {} shards so far", run.cleared(), run.shards()));
+            ui.separator();
+            for (index, boon) in run.offer().iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.button(RichText::new(boon.name()).strong()).clicked() {
+                        action = Some(RiftAction::Choose(index));
+                    }
+                    ui.label(RichText::new(boon.description()).size(12.0).color(Color32::from_rgb(180, 180, 200)));
+                });
+            }
+            if !run.boons().is_empty() {
+                ui.separator();
+                let taken: Vec<&str> = run.boons().iter().map(|b| b.name()).collect();
+                ui.label(RichText::new(format!("Carrying: {}", taken.join(", "))).size(12.0).color(Color32::from_rgb(230, 140, 110)));
+            }
+        });
+    action
+}
+
+/// Draw the arena, depth and shard readout just below the compass
+pub fn draw_rift_hud(ctx: &egui::Context, run: &RiftRun) {
+    egui::Area::new(egui::Id::new("rift_hud"))
+        .anchor(Align2::CENTER_TOP, [0.0, 46.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(Color32::from_rgba_unmultiplied(40, 15, 20, 200))
+                .corner_radius(6.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    let arena = run.arena();
+                    let status = match run.phase() {
+                        RiftPhase::Fighting => format!("{} left", run.remaining()),
+                        RiftPhase::Choosing => "cleared".to_string(),
+                        RiftPhase::Over => "fallen".to_string(),
+                    };
+                    ui.label(
+                        RichText::new(format!("{} - depth {} - {}   {} shards", arena.name(), arena.depth, status, run.shards()))
+                            .font(FontId::proportional(15.0))
+                            .color(Color32::from_rgb(240, 170, 130)),
+                    );
+                });
+        });
+}
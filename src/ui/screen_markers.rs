@@ -0,0 +1,86 @@
+//! Objective and waypoint markers over the 3D view
+
+use egui::{Align2, Color32, FontId, Id, LayerId, Order, Painter, Pos2, Shape, Stroke, Vec2};
+use glam::{Mat4, Vec3};
+use infinite_game::screen_markers::{ndc_to_screen, screen_markers};
+use infinite_game::{CompassFilter, CompassMarker, ScreenMarker, ScreenPlacement};
+
+use super::compass::marker_color;
+
+/// Gap between an edge marker and the screen's edge, in points
+const EDGE_MARGIN: f32 = 36.0;
+/// Half the width of a marker's diamond, in points
+const DIAMOND_SIZE: f32 = 7.0;
+
+/// Markers over tracked objectives and waypoints, pinned to the screen's
+/// edge with their distance when out of view
+pub struct ScreenMarkerHud {
+    /// Widget scale from the HUD settings
+    pub scale: f32,
+}
+
+impl ScreenMarkerHud {
+    pub fn new(scale: f32) -> Self {
+        Self { scale }
+    }
+
+    /// Draw the tracked ones of `markers` as seen through `view_proj` by a
+    /// player at `player_pos`
+    pub fn render<'a>(
+        &self,
+        ctx: &egui::Context,
+        markers: impl IntoIterator<Item = &'a CompassMarker>,
+        player_pos: Vec3,
+        view_proj: Mat4,
+        filter: &CompassFilter,
+    ) {
+        let screen = ctx.screen_rect();
+        let size = glam::Vec2::new(screen.width(), screen.height());
+        // A point is 2 / size normalized device units
+        let margin = glam::Vec2::splat(EDGE_MARGIN * self.scale) * 2.0 / size;
+        let tracked = markers.into_iter().filter(|m| m.category.is_tracked());
+        let painter = ctx.layer_painter(LayerId::new(Order::Background, Id::new("screen_markers")));
+        for marker in screen_markers(tracked, player_pos, view_proj, margin, filter) {
+            let pos = ndc_to_screen(marker.placement.position(), size);
+            self.draw(&painter, &marker, screen.min + Vec2::new(pos.x, pos.y));
+        }
+    }
+
+    fn draw(&self, painter: &Painter, marker: &ScreenMarker, pos: Pos2) {
+        let color = marker_color(marker.category);
+        let font = FontId::proportional(12.0 * self.scale);
+        let shadow = Color32::from_black_alpha(180);
+        match marker.placement {
+            ScreenPlacement::OnScreen(_) => {
+                // Diamond just above the target, label above that
+                let center = pos - Vec2::new(0.0, 14.0 * self.scale);
+                let r = DIAMOND_SIZE * self.scale;
+                let diamond = vec![
+                    center + Vec2::new(0.0, -r),
+                    center + Vec2::new(r, 0.0),
+                    center + Vec2::new(0.0, r),
+                    center + Vec2::new(-r, 0.0),
+                ];
+                painter.add(Shape::convex_polygon(diamond, color, Stroke::new(1.5, shadow)));
+                let text = format!("{} {:.0}m", marker.label, marker.distance);
+                let text_pos = center - Vec2::new(0.0, r + 8.0 * self.scale);
+                painter.text(text_pos + Vec2::splat(1.0), Align2::CENTER_CENTER, &text, font.clone(), shadow);
+                painter.text(text_pos, Align2::CENTER_CENTER, text, font, color);
+            }
+            ScreenPlacement::Edge { direction, .. } => {
+                // Arrow pointing off screen, distance just inside it. Screen
+                // y runs down, normalized device y up.
+                let towards = Vec2::new(direction.x, -direction.y);
+                let side = Vec2::new(-towards.y, towards.x);
+                let r = DIAMOND_SIZE * self.scale * 1.4;
+                let back = pos - towards * r * 0.5;
+                let arrow = vec![pos + towards * r, back + side * r * 0.8, back - side * r * 0.8];
+                painter.add(Shape::convex_polygon(arrow, color, Stroke::new(1.5, shadow)));
+                let text_pos = pos - towards * (r + 14.0 * self.scale);
+                let text = format!("{:.0}m", marker.distance);
+                painter.text(text_pos + Vec2::splat(1.0), Align2::CENTER_CENTER, &text, font.clone(), shadow);
+                painter.text(text_pos, Align2::CENTER_CENTER, text, font, color);
+            }
+        }
+    }
+}
@@ -1,16 +1,25 @@
 //! Settings menu UI
 
-use egui::{Color32, FontId, RichText, Slider, Ui, Vec2};
+use egui::{Color32, FontId, RichText, ScrollArea, Slider, Ui, Vec2};
+use infinite_game::input_macro::macro_key_label;
+use infinite_game::speedrun::default_route;
+use infinite_game::{Split, SplitTrigger, WorldBossKind};
 
-use crate::settings::GameSettings;
+use crate::settings::{CrosshairSettings, CrosshairStyle, GameSettings, TimeTravelTransition};
 use crate::state::StateTransition;
 
+/// Hostile limit set when the limit is first turned on
+const DEFAULT_HOSTILE_CAP: usize = 20;
+
 /// Settings tab selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SettingsTab {
     Video,
     Audio,
     Gameplay,
+    Hud,
+    Macros,
+    Speedrun,
 }
 
 /// Settings menu renderer
@@ -21,6 +30,8 @@ pub struct SettingsMenu {
     working_settings: GameSettings,
     /// Original settings (for Reset)
     original_settings: GameSettings,
+    /// Available audio output device names
+    output_devices: Vec<String>,
 }
 
 impl SettingsMenu {
@@ -29,6 +40,7 @@ impl SettingsMenu {
             current_tab: SettingsTab::Video,
             working_settings: settings.clone(),
             original_settings: settings,
+            output_devices: infinite_audio::AudioEngine::output_device_names(),
         }
     }
 
@@ -61,7 +73,7 @@ impl SettingsMenu {
 
             // Tab bar
             ui.horizontal(|ui| {
-                ui.add_space((available.x - 300.0) / 2.0);
+                ui.add_space((available.x - 580.0) / 2.0);
                 if tab_button(ui, "Video", self.current_tab == SettingsTab::Video) {
                     self.current_tab = SettingsTab::Video;
                 }
@@ -73,17 +85,32 @@ impl SettingsMenu {
                 if tab_button(ui, "Gameplay", self.current_tab == SettingsTab::Gameplay) {
                     self.current_tab = SettingsTab::Gameplay;
                 }
+                ui.add_space(10.0);
+                if tab_button(ui, "HUD", self.current_tab == SettingsTab::Hud) {
+                    self.current_tab = SettingsTab::Hud;
+                }
+                ui.add_space(10.0);
+                if tab_button(ui, "Macros", self.current_tab == SettingsTab::Macros) {
+                    self.current_tab = SettingsTab::Macros;
+                }
+                ui.add_space(10.0);
+                if tab_button(ui, "Speedrun", self.current_tab == SettingsTab::Speedrun) {
+                    self.current_tab = SettingsTab::Speedrun;
+                }
             });
 
             ui.add_space(30.0);
 
             // Settings panel
             let panel_width = (available.x * 0.6).min(500.0);
-            ui.allocate_ui(Vec2::new(panel_width, 300.0), |ui| {
+            ui.allocate_ui(Vec2::new(panel_width, 440.0), |ui| {
                 match self.current_tab {
                     SettingsTab::Video => self.render_video_settings(ui),
                     SettingsTab::Audio => self.render_audio_settings(ui),
                     SettingsTab::Gameplay => self.render_gameplay_settings(ui),
+                    SettingsTab::Hud => self.render_hud_settings(ui),
+                    SettingsTab::Macros => self.render_macro_settings(ui),
+                    SettingsTab::Speedrun => self.render_speedrun_settings(ui),
                 }
             });
 
@@ -141,6 +168,9 @@ impl SettingsMenu {
         ui.add_space(15.0);
         ui.checkbox(&mut video.vsync, "VSync");
 
+        ui.add_space(15.0);
+        ui.checkbox(&mut video.portal_previews, "Portal Destination Previews");
+
         ui.add_space(15.0);
         ui.horizontal(|ui| {
             ui.label("Ray Tracing:");
@@ -164,6 +194,32 @@ impl SettingsMenu {
             ui.label("Field of View:");
             ui.add(Slider::new(&mut video.fov, 60.0..=120.0).suffix(""));
         });
+
+        ui.add_space(15.0);
+        ui.horizontal(|ui| {
+            ui.label("VRAM Budget:");
+            ui.add(Slider::new(&mut video.memory_budget.device_mib, 256..=16384).logarithmic(true).suffix(" MiB"));
+        });
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut video.dynamic_resolution, "Dynamic Resolution");
+        ui.add_enabled_ui(video.dynamic_resolution, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Target FPS:");
+                ui.add(Slider::new(&mut video.target_fps, 30..=240));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Minimum Scale:");
+                ui.add(Slider::new(&mut video.min_render_scale, 0.25..=1.0).show_value(false));
+                ui.label(format!("{:.0}%", video.min_render_scale * 100.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Maximum Scale:");
+                ui.add(Slider::new(&mut video.max_render_scale, 0.25..=1.0).show_value(false));
+                ui.label(format!("{:.0}%", video.max_render_scale * 100.0));
+            });
+        });
+        video.min_render_scale = video.min_render_scale.min(video.max_render_scale);
     }
 
     fn render_audio_settings(&mut self, ui: &mut Ui) {
@@ -191,10 +247,43 @@ impl SettingsMenu {
 
         ui.add_space(15.0);
         ui.horizontal(|ui| {
-            ui.label("Voice Volume:");
+            ui.label("Ambience Volume:");
+            ui.add(Slider::new(&mut audio.ambience, 0.0..=1.0).show_value(false));
+            ui.label(format!("{:.0}%", audio.ambience * 100.0));
+        });
+
+        ui.add_space(15.0);
+        ui.horizontal(|ui| {
+            ui.label("Dialogue Volume:");
             ui.add(Slider::new(&mut audio.voice, 0.0..=1.0).show_value(false));
             ui.label(format!("{:.0}%", audio.voice * 100.0));
         });
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut audio.mute_on_unfocus, "Mute when window is unfocused");
+        ui.checkbox(&mut audio.subtitles, "Show subtitles");
+        ui.checkbox(&mut audio.tts_voice_lines, "Voice unrecorded lines (text-to-speech)");
+        ui.checkbox(&mut audio.voice_barks, "Voice NPC barks");
+        ui.checkbox(&mut audio.pitch_follows_time_scale, "Sound follows slow motion");
+
+        ui.add_space(15.0);
+        ui.horizontal(|ui| {
+            ui.label("Output Device:");
+            ui.add_space(20.0);
+            egui::ComboBox::from_id_salt("output_device")
+                .selected_text(audio.output_device.as_deref().unwrap_or("System Default"))
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(audio.output_device.is_none(), "System Default").clicked() {
+                        audio.output_device = None;
+                    }
+                    for name in &self.output_devices {
+                        let selected = audio.output_device.as_deref() == Some(name.as_str());
+                        if ui.selectable_label(selected, name).clicked() {
+                            audio.output_device = Some(name.clone());
+                        }
+                    }
+                });
+        });
     }
 
     fn render_gameplay_settings(&mut self, ui: &mut Ui) {
@@ -219,6 +308,264 @@ impl SettingsMenu {
                 }
             });
         }
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut gameplay.show_tutorials, "Show tutorial prompts");
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut gameplay.aim_assist, "Aim assist")
+            .on_hover_text("Gently pulls your aim onto enemies near the crosshair while holding a ranged weapon");
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut gameplay.dialogue_camera, "Cinematic dialogue camera")
+            .on_hover_text("Frames conversations over your shoulder with letterbox bars");
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut gameplay.rest_fast_forward, "Pass time while resting")
+            .on_hover_text("Hold Sprint while sitting or leaning to speed up time");
+
+        ui.add_space(15.0);
+        ui.checkbox(&mut gameplay.durability, "Gear durability")
+            .on_hover_text("Weapons, shields and armor wear with use and work less well until repaired. Turning this off mends everything you carry.");
+
+        ui.add_space(15.0);
+        let population = &mut gameplay.population;
+        ui.horizontal(|ui| {
+            ui.label("NPC density:");
+            ui.add(Slider::new(&mut population.density, 0.0..=infinite_game::PopulationConfig::MAX_DENSITY).show_value(false));
+            ui.label(format!("{:.0}%", population.density * 100.0));
+        })
+        .response
+        .on_hover_text("Fewer NPCs run better on slower machines; above 100% brings visitors and more monsters");
+        let mut capped = population.max_hostiles.is_some();
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut capped, "Limit hostiles").changed() {
+                population.max_hostiles = capped.then_some(DEFAULT_HOSTILE_CAP);
+            }
+            if let Some(max) = &mut population.max_hostiles {
+                ui.add(Slider::new(max, 1..=60).suffix(" at once"));
+            }
+        });
+        ui.checkbox(&mut population.ambient_life, "Ambient life")
+            .on_hover_text("Villagers and guards going about their day. Shopkeepers and quest givers always stay.");
+
+        ui.add_space(15.0);
+        ui.horizontal(|ui| {
+            ui.label("Time travel:");
+            ui.add_space(20.0);
+            egui::ComboBox::from_id_salt("time_travel_transition")
+                .selected_text(gameplay.time_travel_transition.name())
+                .show_ui(ui, |ui| {
+                    for transition in TimeTravelTransition::ALL {
+                        ui.selectable_value(&mut gameplay.time_travel_transition, transition, transition.name());
+                    }
+                });
+        });
+
+        ui.add_space(15.0);
+        let paradox = &mut gameplay.paradox;
+        ui.checkbox(&mut paradox.enabled, "Paradox from anachronistic items");
+        if paradox.enabled {
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.label("Tolerance:");
+                ui.add(Slider::new(&mut paradox.tolerance_years, 0..=2000).suffix(" years"));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Buildup:");
+                ui.add(Slider::new(&mut paradox.buildup_scale, 0.25..=3.0).show_value(false));
+                ui.label(format!("{:.2}x", paradox.buildup_scale));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Recovery:");
+                ui.add(Slider::new(&mut paradox.decay_per_second, 0.5..=10.0).suffix("/s"));
+            });
+        }
+    }
+
+    fn render_hud_settings(&mut self, ui: &mut Ui) {
+        crate::ui::widget_controls(ui, &mut self.working_settings.hud);
+
+        ui.add_space(15.0);
+        ui.horizontal(|ui| {
+            ui.label("Compass & minimap markers:");
+            for category in infinite_game::MarkerCategory::ALL {
+                ui.checkbox(self.working_settings.gameplay.compass_filter.toggle_mut(category), category.name());
+            }
+        });
+
+        ui.add_space(15.0);
+        let crosshair = &mut self.working_settings.crosshair;
+        ui.checkbox(&mut crosshair.enabled, "Crosshair");
+        if crosshair.enabled {
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.label("Style:");
+                egui::ComboBox::from_id_salt("crosshair_style")
+                    .selected_text(crosshair.style.name())
+                    .show_ui(ui, |ui| {
+                        for style in CrosshairStyle::ALL {
+                            ui.selectable_value(&mut crosshair.style, style, style.name());
+                        }
+                    });
+                ui.add_space(20.0);
+                ui.label("Colour:");
+                ui.color_edit_button_srgb(&mut crosshair.color);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Size:");
+                ui.add(Slider::new(&mut crosshair.size, CrosshairSettings::MIN_SIZE..=CrosshairSettings::MAX_SIZE).fixed_decimals(0));
+            });
+            ui.checkbox(&mut crosshair.dynamic_spread, "Widen with ranged weapon spread");
+        }
+
+        ui.add_space(15.0);
+        ui.label(
+            RichText::new("Press F4 while playing to drag widgets into position.")
+                .color(Color32::from_rgb(150, 150, 170)),
+        );
+    }
+
+    fn render_macro_settings(&mut self, ui: &mut Ui) {
+        let muted = Color32::from_rgb(150, 150, 170);
+        let macros = &mut self.working_settings.macros;
+        ui.checkbox(&mut macros.enabled, "Input macros")
+            .on_hover_text("Record a short run of actions, like a combo, and play it back from a single key");
+
+        ui.add_space(10.0);
+        ui.label(
+            RichText::new(
+                "Press F7 while playing to start recording and F7 again to stop, then press the key to bind the \
+                 macro to. Macros can't open menus or save, and never run against other players.",
+            )
+            .color(muted),
+        );
+
+        ui.add_space(15.0);
+        if macros.profiles.values().all(|set| set.macros.is_empty()) {
+            ui.label(RichText::new("No macros recorded yet.").color(muted));
+            return;
+        }
+        ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for (profile, set) in macros.profiles.iter_mut().filter(|(_, set)| !set.macros.is_empty()) {
+                ui.label(RichText::new(profile).strong());
+                let mut removed = None;
+                for (index, input_macro) in set.macros.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(macro_key_label(&input_macro.key)).monospace().strong());
+                        ui.add(egui::TextEdit::singleline(&mut input_macro.name).desired_width(120.0));
+                        let actions: Vec<String> = input_macro.steps.iter().map(|s| format!("{:?}", s.action)).collect();
+                        ui.label(
+                            RichText::new(format!("{} ({:.1}s)", actions.join(" > "), input_macro.length()))
+                                .size(12.0)
+                                .color(muted),
+                        );
+                        if ui.button("Delete").clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    set.macros.remove(index);
+                }
+                ui.add_space(8.0);
+            }
+        });
+    }
+
+    fn render_speedrun_settings(&mut self, ui: &mut Ui) {
+        let muted = Color32::from_rgb(150, 150, 170);
+        let speedrun = &mut self.working_settings.speedrun;
+        ui.checkbox(&mut speedrun.enabled, "Speedrun timer")
+            .on_hover_text("Time new worlds against your route and show the splits on screen");
+
+        ui.add_space(10.0);
+        ui.label(
+            RichText::new(
+                "The timer starts with a new world and splits by itself as each step is done, in order. \
+                 Personal bests are kept for each world seed. Restart the run or export the splits from the pause menu.",
+            )
+            .color(muted),
+        );
+
+        ui.add_space(15.0);
+        let mut removed = None;
+        let mut raised = None;
+        ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+            for (index, split) in speedrun.splits.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(format!("{}.", index + 1)).monospace());
+                    ui.add(egui::TextEdit::singleline(&mut split.name).desired_width(150.0));
+                    trigger_editor(ui, index, &mut split.trigger);
+                    if ui.add_enabled(index > 0, egui::Button::new("Up")).clicked() {
+                        raised = Some(index);
+                    }
+                    if ui.button("Remove").clicked() {
+                        removed = Some(index);
+                    }
+                });
+            }
+        });
+        if let Some(index) = raised {
+            speedrun.splits.swap(index - 1, index);
+        }
+        if let Some(index) = removed {
+            speedrun.splits.remove(index);
+        }
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            if ui.button("Add split").clicked() {
+                speedrun.splits.push(Split::new(SplitTrigger::ReachLevel(10)));
+            }
+            if ui.button("Default route").clicked() {
+                speedrun.splits = default_route();
+            }
+        });
+    }
+}
+
+/// Kind and target of a split's trigger
+fn trigger_editor(ui: &mut Ui, index: usize, trigger: &mut SplitTrigger) {
+    let kinds = [
+        ("Reach year", SplitTrigger::ReachYear(2025)),
+        ("Defeat boss", SplitTrigger::DefeatWorldBoss(WorldBossKind::Colossus)),
+        ("Reach level", SplitTrigger::ReachLevel(10)),
+        ("Defeat enemies", SplitTrigger::DefeatEnemies(10)),
+    ];
+    let current = kinds
+        .iter()
+        .position(|(_, kind)| std::mem::discriminant(kind) == std::mem::discriminant(trigger))
+        .unwrap_or(0);
+    egui::ComboBox::from_id_salt(("split_trigger", index))
+        .selected_text(kinds[current].0)
+        .width(110.0)
+        .show_ui(ui, |ui| {
+            for (i, (label, kind)) in kinds.iter().enumerate() {
+                if ui.selectable_label(i == current, *label).clicked() && i != current {
+                    *trigger = *kind;
+                }
+            }
+        });
+    match trigger {
+        SplitTrigger::ReachYear(year) => {
+            ui.add(egui::DragValue::new(year).range(-10_000..=10_000));
+        }
+        SplitTrigger::DefeatWorldBoss(kind) => {
+            egui::ComboBox::from_id_salt(("split_boss", index))
+                .selected_text(kind.name())
+                .show_ui(ui, |ui| {
+                    for boss in WorldBossKind::ALL {
+                        ui.selectable_value(kind, boss, boss.name());
+                    }
+                });
+        }
+        SplitTrigger::ReachLevel(level) => {
+            ui.add(egui::DragValue::new(level).range(1..=100));
+        }
+        SplitTrigger::DefeatEnemies(count) => {
+            ui.add(egui::DragValue::new(count).range(1..=1000));
+        }
     }
 }
 
@@ -1,17 +1,24 @@
-//! Shop UI — buy and sell items from a catalog
+//! Shop UI — buy and sell items from a catalog, and repair gear
+//!
+//! Prices follow the local market's supply when the shop belongs to a
+//! settlement; goods the market has run out of can't be bought.
 
 use egui::{Color32, FontId, RichText, ScrollArea, Ui, Vec2};
 
+use infinite_core::time::format_year;
+
 use infinite_game::combat::catalog::ItemCatalog;
+use infinite_game::combat::equipment::EquipmentSet;
 use infinite_game::combat::inventory::Inventory;
 use infinite_game::combat::item::{Item, ItemCategory, ItemRarity};
-use infinite_game::Element;
+use infinite_game::{Element, Market};
 
 /// Active tab in the shop
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShopTab {
     Buy,
     Sell,
+    Repair,
 }
 
 /// Category filter for the buy tab
@@ -27,6 +34,8 @@ pub enum ShopAction {
     None,
     Buy { catalog_index: usize },
     Sell { inventory_index: usize },
+    /// Repair all equipped armor
+    RepairAll,
     Close,
 }
 
@@ -64,7 +73,9 @@ impl ShopMenu {
         ui: &mut Ui,
         catalog: &ItemCatalog,
         inventory: &Inventory,
+        equipment: &EquipmentSet,
         gold: u64,
+        market: Option<&Market>,
     ) -> ShopAction {
         let mut action = ShopAction::None;
 
@@ -97,6 +108,17 @@ impl ShopMenu {
                     .color(Color32::from_rgb(255, 215, 0)),
             );
 
+            if let Some(market) = market {
+                ui.label(
+                    RichText::new(format!(
+                        "{} market  -  plenty of {:?}, short of {:?}",
+                        market.name, market.specialty, market.need
+                    ))
+                    .font(FontId::proportional(13.0))
+                    .color(Color32::from_rgb(180, 180, 200)),
+                );
+            }
+
             ui.add_space(10.0);
 
             // Tab buttons
@@ -113,6 +135,12 @@ impl ShopMenu {
                     self.selected_buy_item = None;
                     self.selected_sell_item = None;
                 }
+                ui.add_space(10.0);
+                if tab_button(ui, "Repair", self.active_tab == ShopTab::Repair) {
+                    self.active_tab = ShopTab::Repair;
+                    self.selected_buy_item = None;
+                    self.selected_sell_item = None;
+                }
             });
 
             ui.add_space(10.0);
@@ -124,10 +152,13 @@ impl ShopMenu {
             ui.allocate_ui(Vec2::new(content_width, content_height), |ui| {
                 match self.active_tab {
                     ShopTab::Buy => {
-                        action = self.render_buy_tab(ui, catalog, gold, inventory);
+                        action = self.render_buy_tab(ui, catalog, gold, inventory, market);
                     }
                     ShopTab::Sell => {
-                        action = self.render_sell_tab(ui, catalog, inventory);
+                        action = self.render_sell_tab(ui, catalog, inventory, market);
+                    }
+                    ShopTab::Repair => {
+                        action = render_repair_tab(ui, equipment, gold);
                     }
                 }
             });
@@ -149,6 +180,7 @@ impl ShopMenu {
         catalog: &ItemCatalog,
         gold: u64,
         inventory: &Inventory,
+        market: Option<&Market>,
     ) -> ShopAction {
         let mut action = ShopAction::None;
 
@@ -204,9 +236,9 @@ impl ShopMenu {
                         };
 
                         for (catalog_idx, item) in &filtered {
-                            let price = catalog.price(*catalog_idx);
+                            let price = buy_price_for(*catalog_idx, catalog, market);
                             let is_selected = self.selected_buy_item == Some(*catalog_idx);
-                            let can_afford = gold >= price;
+                            let can_afford = gold >= price && in_stock(item, market);
                             if catalog_item_button(ui, item, price, is_selected, can_afford) {
                                 if self.selected_buy_item == Some(*catalog_idx) {
                                     self.selected_buy_item = None;
@@ -225,7 +257,7 @@ impl ShopMenu {
                 ui.set_min_width(200.0);
                 if let Some(idx) = self.selected_buy_item {
                     if let Some(item) = catalog.items().get(idx) {
-                        let price = catalog.price(idx);
+                        let price = buy_price_for(idx, catalog, market);
                         render_item_detail(ui, item);
 
                         ui.add_space(8.0);
@@ -239,8 +271,15 @@ impl ShopMenu {
 
                         let can_afford = gold >= price;
                         let inv_full = inventory.is_full();
+                        let available = in_stock(item, market);
 
-                        if !can_afford {
+                        if !available {
+                            ui.label(
+                                RichText::new("Out of stock")
+                                    .font(FontId::proportional(12.0))
+                                    .color(Color32::from_rgb(220, 100, 100)),
+                            );
+                        } else if !can_afford {
                             ui.label(
                                 RichText::new("Not enough gold")
                                     .font(FontId::proportional(12.0))
@@ -254,7 +293,7 @@ impl ShopMenu {
                             );
                         }
 
-                        let enabled = can_afford && !inv_full;
+                        let enabled = available && can_afford && !inv_full;
                         if buy_sell_button(ui, "Buy", enabled) {
                             action = ShopAction::Buy { catalog_index: idx };
                         }
@@ -276,6 +315,7 @@ impl ShopMenu {
         ui: &mut Ui,
         catalog: &ItemCatalog,
         inventory: &Inventory,
+        market: Option<&Market>,
     ) -> ShopAction {
         let mut action = ShopAction::None;
 
@@ -294,7 +334,7 @@ impl ShopMenu {
                     .max_height(ui.available_height())
                     .show(ui, |ui| {
                         for (idx, item) in inventory.items.iter().enumerate() {
-                            let sell_price = sell_price_for(item, catalog);
+                            let sell_price = market_sell_price(item, catalog, market);
                             let is_selected = self.selected_sell_item == Some(idx);
                             if sell_item_button(ui, item, sell_price, is_selected) {
                                 if self.selected_sell_item == Some(idx) {
@@ -321,7 +361,7 @@ impl ShopMenu {
                 ui.set_min_width(200.0);
                 if let Some(idx) = self.selected_sell_item {
                     if let Some(item) = inventory.get(idx) {
-                        let sell = sell_price_for(item, catalog);
+                        let sell = market_sell_price(item, catalog, market);
                         render_item_detail(ui, item);
 
                         ui.add_space(8.0);
@@ -349,6 +389,85 @@ impl ShopMenu {
     }
 }
 
+/// Blacksmith services: list worn equipped weapons, shields and armor and
+/// repair them all at once
+fn render_repair_tab(ui: &mut Ui, equipment: &EquipmentSet, gold: u64) -> ShopAction {
+    let mut action = ShopAction::None;
+    let damaged = equipment.damaged_gear();
+
+    ui.vertical(|ui| {
+        ui.label(
+            RichText::new("Worn Gear")
+                .font(FontId::proportional(14.0))
+                .color(Color32::from_rgb(200, 200, 255)),
+        );
+        ui.add_space(5.0);
+
+        if damaged.is_empty() {
+            ui.label(
+                RichText::new("All equipped gear is in good condition")
+                    .color(Color32::from_rgb(140, 140, 160)),
+            );
+            return;
+        }
+
+        for (slot, item) in &damaged {
+            let Some(durable) = item.durable() else { continue };
+            let color = if durable.is_worn_out() {
+                Color32::from_rgb(220, 80, 80)
+            } else {
+                Color32::from_rgb(200, 200, 220)
+            };
+            ui.label(
+                RichText::new(format!(
+                    "{}: {}  {:.0}/{:.0}{}  -  {} gold",
+                    slot.name(),
+                    item.name,
+                    durable.durability(),
+                    durable.max_durability(),
+                    if durable.is_worn_out() { " (worn out)" } else { "" },
+                    durable.repair_cost(item.rarity),
+                ))
+                .font(FontId::proportional(12.0))
+                .color(color),
+            );
+        }
+
+        let cost = equipment.repair_cost();
+        ui.add_space(8.0);
+        ui.label(
+            RichText::new(format!("Repair all: {} gold", format_gold(cost)))
+                .font(FontId::proportional(14.0))
+                .color(Color32::from_rgb(255, 215, 0)),
+        );
+        ui.add_space(8.0);
+        if buy_sell_button(ui, "Repair All", gold >= cost) {
+            action = ShopAction::RepairAll;
+        }
+    });
+
+    action
+}
+
+/// What the shop charges for a catalog item, adjusted for local supply
+pub fn buy_price_for(catalog_index: usize, catalog: &ItemCatalog, market: Option<&Market>) -> u64 {
+    let base = catalog.price(catalog_index);
+    match (market, catalog.items().get(catalog_index)) {
+        (Some(market), Some(item)) => market.buy_price(item.category, base),
+        _ => base,
+    }
+}
+
+/// What the shop pays for an item, adjusted for local supply
+pub fn market_sell_price(item: &Item, catalog: &ItemCatalog, market: Option<&Market>) -> u64 {
+    let base = sell_price_for(item, catalog);
+    market.map_or(base, |market| market.sell_price(item.category, base))
+}
+
+fn in_stock(item: &Item, market: Option<&Market>) -> bool {
+    market.is_none_or(|market| market.in_stock(item.category))
+}
+
 /// Calculate sell price: 50% of catalog price if found, else rarity-based fallback, min 1
 pub fn sell_price_for(item: &Item, catalog: &ItemCatalog) -> u64 {
     // Try to find this item in the catalog by name
@@ -358,14 +477,13 @@ pub fn sell_price_for(item: &Item, catalog: &ItemCatalog) -> u64 {
         }
     }
     // Fallback: rarity-based
-    let base = match item.rarity {
+    match item.rarity {
         ItemRarity::Common => 2,
         ItemRarity::Uncommon => 5,
         ItemRarity::Rare => 15,
         ItemRarity::Epic => 50,
         ItemRarity::Legendary => 200,
-    };
-    base
+    }
 }
 
 fn format_gold(amount: u64) -> String {
@@ -601,6 +719,13 @@ fn render_item_detail(ui: &mut Ui, item: &Item) {
                 .color(Color32::from_rgb(160, 180, 220)),
         );
     }
+    if let Some(origin) = item.origin_year {
+        ui.label(
+            RichText::new(format!("Origin: {}", format_year(origin)))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(200, 180, 140)),
+        );
+    }
     ui.add_space(4.0);
     ui.label(
         RichText::new(&item.description)
@@ -628,6 +753,28 @@ fn render_item_detail(ui: &mut Ui, item: &Item) {
         );
     }
 
+    // Armor data
+    if let Some(armor) = &item.armor_data {
+        ui.add_space(4.0);
+        ui.label(
+            RichText::new(format!("Armor: {:.0}", armor.armor))
+                .font(FontId::proportional(12.0))
+                .color(Color32::from_rgb(170, 190, 210)),
+        );
+    }
+
+    if let Some(durable) = item.durable() {
+        ui.label(
+            RichText::new(format!("Durability: {:.0}/{:.0}", durable.durability(), durable.max_durability()))
+                .font(FontId::proportional(12.0))
+                .color(if durable.is_worn_out() {
+                    Color32::from_rgb(220, 80, 80)
+                } else {
+                    Color32::from_rgb(170, 190, 210)
+                }),
+        );
+    }
+
     if item.required_level > 1 {
         ui.label(
             RichText::new(format!("Requires Level {}", item.required_level))
@@ -0,0 +1,106 @@
+//! Shrine menu: pick a boon at a shrine and how to pay for it
+//!
+//! Every boon can be paid for in gold or with an offering of the shrine
+//! era's herbs. Old shrines can also lift the player's curses for gold. A
+//! resting shrine shows how long until it answers again.
+
+use egui::{Color32, RichText};
+
+use infinite_game::gathering::ResourceKind;
+use infinite_game::shrine::{count_offering, OFFERING_COUNT};
+use infinite_game::combat::{Curses, CLEANSE_GOLD_COST};
+use infinite_game::{Boon, Inventory, Shrine, Tribute};
+
+/// Choice made in the shrine menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShrineAction {
+    Pray(Boon, Tribute),
+    /// Lift every curse
+    Cleanse,
+    Close,
+}
+
+/// Window for praying, opened at a shrine
+pub struct ShrineMenu {
+    pub shrine: Shrine,
+}
+
+impl ShrineMenu {
+    pub fn new(shrine: Shrine) -> Self {
+        Self { shrine }
+    }
+
+    /// `cooldown` is the seconds until the shrine answers again, if resting
+    pub fn render(
+        &self,
+        ctx: &egui::Context,
+        inventory: &Inventory,
+        gold: u64,
+        level: u32,
+        curses: &Curses,
+        cooldown: Option<f64>,
+    ) -> Option<ShrineAction> {
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(self.shrine.name())
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                if let Some(left) = cooldown {
+                    let minutes = (left / 60.0).ceil() as u32;
+                    ui.label(
+                        RichText::new(format!("The shrine is silent. It will answer again in {} min.", minutes))
+                            .color(Color32::from_rgb(180, 180, 200)),
+                    );
+                    ui.separator();
+                }
+
+                let offering = self.shrine.offering_id();
+                let herbs = count_offering(inventory, offering);
+                let herb_name = ResourceKind::Herb.material_name(self.shrine.era);
+                for boon in Boon::ALL {
+                    let cost = boon.gold_cost(level);
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(boon.name()).strong());
+                        ui.label(RichText::new(boon.description()).size(12.0).color(Color32::from_rgb(180, 180, 200)));
+                    });
+                    ui.horizontal(|ui| {
+                        let ready = cooldown.is_none();
+                        if ui.add_enabled(ready && gold >= cost, egui::Button::new(format!("{} gold", cost))).clicked() {
+                            action = Some(ShrineAction::Pray(boon, Tribute::Gold));
+                        }
+                        let offer = ui
+                            .add_enabled(ready && herbs >= OFFERING_COUNT, egui::Button::new(format!("Offer {} herbs", OFFERING_COUNT)))
+                            .on_hover_text(format!("{} ({} carried)", herb_name, herbs))
+                            .on_disabled_hover_text(format!("{} ({} carried)", herb_name, herbs));
+                        if offer.clicked() {
+                            action = Some(ShrineAction::Pray(boon, Tribute::Offering));
+                        }
+                    });
+                    ui.add_space(4.0);
+                }
+
+                if self.shrine.cleanses() && !curses.is_empty() {
+                    ui.separator();
+                    let names: Vec<&str> = curses.iter().map(|c| c.name()).collect();
+                    ui.label(RichText::new(format!("Cursed: {}", names.join(", "))).color(Color32::from_rgb(200, 90, 220)));
+                    let ready = cooldown.is_none() && gold >= CLEANSE_GOLD_COST;
+                    if ui.add_enabled(ready, egui::Button::new(format!("Lift curses ({} gold)", CLEANSE_GOLD_COST))).clicked() {
+                        action = Some(ShrineAction::Cleanse);
+                    }
+                }
+
+                ui.separator();
+                if ui.button("Leave").clicked() {
+                    action = Some(ShrineAction::Close);
+                }
+            });
+        if !open {
+            action = Some(ShrineAction::Close);
+        }
+        action
+    }
+}
@@ -0,0 +1,63 @@
+//! Speedrun overlay: the run timer, the route's splits and how each compares
+//! with the personal best
+
+use egui::{Align2, Color32, FontId, RichText};
+use infinite_game::speedrun::{format_delta, format_run_time};
+use infinite_game::{PersonalBest, SpeedrunRun};
+
+const AHEAD: Color32 = Color32::from_rgb(110, 220, 120);
+const BEHIND: Color32 = Color32::from_rgb(230, 110, 100);
+const MUTED: Color32 = Color32::from_rgb(170, 170, 190);
+
+/// Draw the splits and timer at the right edge of the screen
+pub fn draw_speedrun_overlay(ctx: &egui::Context, run: &SpeedrunRun, best: Option<&PersonalBest>) {
+    egui::Area::new(egui::Id::new("speedrun_overlay"))
+        .anchor(Align2::RIGHT_CENTER, [-12.0, 0.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(Color32::from_rgba_unmultiplied(15, 15, 25, 200))
+                .corner_radius(6.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    ui.set_min_width(220.0);
+                    let current = run.current();
+                    egui::Grid::new("speedrun_splits").num_columns(3).spacing([10.0, 2.0]).show(ui, |ui| {
+                        for (index, split) in run.splits().iter().enumerate() {
+                            let name_color = if current == Some(index) { Color32::WHITE } else { MUTED };
+                            ui.label(RichText::new(&split.name).size(13.0).color(name_color));
+                            match (run.split_time(index), best.and_then(|b| run.delta(index, b))) {
+                                (Some(_), Some(delta)) => {
+                                    let color = if delta < 0.0 { AHEAD } else { BEHIND };
+                                    ui.label(RichText::new(format_delta(delta)).size(13.0).color(color));
+                                }
+                                _ => {
+                                    ui.label("");
+                                }
+                            }
+                            let time = run
+                                .split_time(index)
+                                .or_else(|| best.and_then(|b| b.splits.get(index).copied()));
+                            let text = time.map(format_run_time).unwrap_or_else(|| "-".to_string());
+                            let color = if run.split_time(index).is_some() { Color32::WHITE } else { MUTED };
+                            ui.label(RichText::new(text).size(13.0).monospace().color(color));
+                            ui.end_row();
+                        }
+                    });
+                    ui.separator();
+                    let color = match (current, best) {
+                        (None, Some(b)) if run.time() < b.total() => AHEAD,
+                        (None, _) => Color32::from_rgb(240, 210, 110),
+                        _ => Color32::WHITE,
+                    };
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(RichText::new(format_run_time(run.time())).font(FontId::monospace(24.0)).color(color));
+                    });
+                    if let Some(best) = best {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(RichText::new(format!("PB {}", format_run_time(best.total()))).size(12.0).color(MUTED));
+                        });
+                    }
+                });
+        });
+}
@@ -0,0 +1,212 @@
+//! Survival mode UI: the wave and score readout, the arena shop between
+//! waves, and the results with the leaderboard once the run is over
+
+use egui::{Align2, Color32, FontId, RichText, Vec2};
+use infinite_game::npc::survival::COMBO_WINDOW;
+use infinite_game::{SurvivalPhase, SurvivalRun, SurvivalUpgrade};
+use infinite_integration::LeaderboardEntry;
+
+/// Width of the combo timer bar in points
+const COMBO_BAR_WIDTH: f32 = 160.0;
+
+/// Choice made in the survival window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurvivalAction {
+    Buy(SurvivalUpgrade),
+    /// Skip the rest of the intermission
+    NextWave,
+    /// Fight in the arena again from wave one
+    Retry,
+    /// Back to the main menu
+    Leave,
+    Close,
+}
+
+/// Where the finished run's score stands with the leaderboard
+#[derive(Debug, Clone)]
+pub enum ScoreStatus {
+    Submitting,
+    /// Submitted; the board is there once it has been fetched
+    Submitted(Option<Vec<LeaderboardEntry>>),
+    /// Offline or the server turned it down
+    Failed(String),
+}
+
+/// The arena shop, and the results screen once the run is over
+pub struct SurvivalMenu {
+    pub score_status: ScoreStatus,
+    /// The player closed the shop for the rest of this intermission
+    pub shop_closed: bool,
+}
+
+impl SurvivalMenu {
+    pub fn new() -> Self {
+        Self {
+            score_status: ScoreStatus::Submitting,
+            shop_closed: false,
+        }
+    }
+
+    pub fn render(&self, ctx: &egui::Context, run: &SurvivalRun) -> Option<SurvivalAction> {
+        if run.is_over() {
+            self.render_results(ctx, run)
+        } else if self.shop_closed {
+            None
+        } else {
+            render_shop(ctx, run)
+        }
+    }
+
+    /// Whether a window is up and wants the cursor
+    pub fn is_open(&self, run: &SurvivalRun) -> bool {
+        run.is_over() || (!self.shop_closed && matches!(run.phase(), SurvivalPhase::Intermission { .. }))
+    }
+
+    fn render_results(&self, ctx: &egui::Context, run: &SurvivalRun) -> Option<SurvivalAction> {
+        let mut action = None;
+        egui::Window::new("You have fallen")
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.label(RichText::new(format!("Score {}", run.score())).font(FontId::proportional(22.0)).strong());
+                ui.label(format!(
+                    "Wave {} - {} kills - best combo {}",
+                    run.wave(),
+                    run.kills(),
+                    run.best_combo(),
+                ));
+                ui.separator();
+
+                let muted = Color32::from_rgb(180, 180, 200);
+                match &self.score_status {
+                    ScoreStatus::Submitting => {
+                        ui.label(RichText::new("Submitting score...").color(muted));
+                    }
+                    ScoreStatus::Failed(reason) => {
+                        ui.label(RichText::new(format!("Score not submitted: {}", reason)).color(Color32::from_rgb(220, 120, 100)));
+                    }
+                    ScoreStatus::Submitted(None) => {
+                        ui.label(RichText::new("Score submitted. Fetching the leaderboard...").color(muted));
+                    }
+                    ScoreStatus::Submitted(Some(entries)) if entries.is_empty() => {
+                        ui.label(RichText::new("Score submitted. The leaderboard couldn't be loaded.").color(muted));
+                    }
+                    ScoreStatus::Submitted(Some(entries)) => {
+                        ui.label(RichText::new("Leaderboard").strong());
+                        egui::Grid::new("survival_leaderboard").striped(true).show(ui, |ui| {
+                            for (rank, entry) in entries.iter().enumerate() {
+                                let name = entry.user_name.as_deref().unwrap_or(&entry.character_name);
+                                ui.label(format!("{}.", rank + 1));
+                                ui.label(name);
+                                ui.label(format!("wave {}", entry.wave));
+                                ui.label(RichText::new(entry.score.to_string()).strong());
+                                ui.end_row();
+                            }
+                        });
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Fight again").clicked() {
+                        action = Some(SurvivalAction::Retry);
+                    }
+                    if ui.button("Leave the arena").clicked() {
+                        action = Some(SurvivalAction::Leave);
+                    }
+                });
+            });
+        action
+    }
+}
+
+impl Default for SurvivalMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The arena shop, open between waves
+fn render_shop(ctx: &egui::Context, run: &SurvivalRun) -> Option<SurvivalAction> {
+    let SurvivalPhase::Intermission { remaining } = run.phase() else {
+        return None;
+    };
+    let mut action = None;
+    let mut open = true;
+    egui::Window::new("Arena Shop")
+        .open(&mut open)
+        .anchor(Align2::RIGHT_CENTER, [-20.0, 0.0])
+        .resizable(false)
+        .collapsible(false)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            ui.label(RichText::new(format!("{} points to spend", run.points())).strong());
+            ui.label(
+                RichText::new(format!("Wave {} begins in {:.0}s", run.wave() + 1, remaining.ceil()))
+                    .color(Color32::from_rgb(180, 180, 200)),
+            );
+            ui.separator();
+            for upgrade in SurvivalUpgrade::ALL {
+                let cost = run.cost(upgrade);
+                ui.horizontal(|ui| {
+                    let buy = ui
+                        .add_enabled(run.points() >= cost, egui::Button::new(format!("{} pts", cost)))
+                        .on_hover_text(upgrade.description());
+                    if buy.clicked() {
+                        action = Some(SurvivalAction::Buy(upgrade));
+                    }
+                    ui.label(RichText::new(upgrade.name()).strong());
+                    ui.label(RichText::new(upgrade.description()).size(12.0).color(Color32::from_rgb(180, 180, 200)));
+                });
+            }
+            ui.separator();
+            if ui.button("Bring on the next wave").clicked() {
+                action = Some(SurvivalAction::NextWave);
+            }
+        });
+    if !open {
+        action = Some(SurvivalAction::Close);
+    }
+    action
+}
+
+/// Draw the wave, score and combo readout just below the compass
+pub fn draw_survival_hud(ctx: &egui::Context, run: &SurvivalRun) {
+    egui::Area::new(egui::Id::new("survival_hud"))
+        .anchor(Align2::CENTER_TOP, [0.0, 46.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(Color32::from_rgba_unmultiplied(20, 20, 30, 200))
+                .corner_radius(6.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    let status = match run.phase() {
+                        SurvivalPhase::Fighting => format!("Wave {} - {} left", run.wave(), run.remaining()),
+                        SurvivalPhase::Intermission { remaining } => {
+                            format!("Wave {} in {:.0}s", run.wave() + 1, remaining.ceil())
+                        }
+                        SurvivalPhase::Over => format!("Fell on wave {}", run.wave()),
+                    };
+                    ui.label(
+                        RichText::new(format!("{}   Score {}", status, run.score()))
+                            .font(FontId::proportional(15.0))
+                            .color(Color32::from_rgb(230, 220, 180)),
+                    );
+                    if run.combo() > 0 {
+                        ui.label(
+                            RichText::new(format!("Combo {}  x{:.2}", run.combo(), run.combo_multiplier()))
+                                .font(FontId::proportional(13.0))
+                                .color(Color32::from_rgb(255, 170, 60)),
+                        );
+                        let (rect, _) = ui.allocate_exact_size(Vec2::new(COMBO_BAR_WIDTH, 4.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, Color32::from_rgb(50, 40, 30));
+                        let left = (run.combo_time_left() / COMBO_WINDOW).clamp(0.0, 1.0);
+                        let fill = egui::Rect::from_min_size(rect.min, Vec2::new(COMBO_BAR_WIDTH * left, 4.0));
+                        ui.painter().rect_filled(fill, 2.0, Color32::from_rgb(255, 170, 60));
+                    }
+                });
+        });
+}
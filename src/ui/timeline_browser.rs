@@ -0,0 +1,104 @@
+//! Timeline browser: the tree of alternate histories, and jumping between them
+
+use egui::{Color32, RichText};
+use infinite_core::time::format_year;
+use infinite_core::{BranchGraph, BranchId, TimelineBranch};
+
+/// Choice made in the timeline browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineAction {
+    /// Move onto another branch, in the same year
+    Jump(BranchId),
+    /// Split a new branch off the current one, here and now
+    Fork,
+}
+
+/// Toggleable window listing every timeline branch
+pub struct TimelineBrowser {
+    /// Whether the browser is open
+    pub visible: bool,
+}
+
+impl TimelineBrowser {
+    pub fn new() -> Self {
+        Self { visible: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Draw the browser if visible. `busy` disables jumping and forking
+    /// (e.g. mid time travel).
+    pub fn render(&mut self, ctx: &egui::Context, branches: &BranchGraph, active_year: i64, busy: bool) -> Option<TimelineAction> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = self.visible;
+        egui::Window::new("Timelines")
+            .open(&mut open)
+            .anchor(egui::Align2::LEFT_CENTER, [10.0, 0.0])
+            .resizable(false)
+            .collapsible(false)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!("Now: {}", format_year(active_year)))
+                        .size(16.0)
+                        .color(Color32::from_rgb(255, 200, 80)),
+                );
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    if let Some(main) = branches.get(BranchId::MAIN) {
+                        render_branch(ui, branches, main, 0, busy, &mut action);
+                    }
+                });
+
+                ui.separator();
+                if ui.add_enabled(!busy, egui::Button::new("Branch off here")).clicked() {
+                    action = Some(TimelineAction::Fork);
+                }
+            });
+        self.visible = open;
+        action
+    }
+}
+
+/// One row per branch, children indented under their parent
+fn render_branch(
+    ui: &mut egui::Ui,
+    branches: &BranchGraph,
+    branch: &TimelineBranch,
+    depth: usize,
+    busy: bool,
+    action: &mut Option<TimelineAction>,
+) {
+    // A malformed graph could loop; no real tree is deeper than its size
+    if depth > branches.branches().len() {
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.add_space(depth as f32 * 16.0);
+        let label = match branch.parent {
+            Some(_) => format!("{} (from {})", branch.name, format_year(branch.fork_year)),
+            None => branch.name.clone(),
+        };
+        if branch.id == branches.active() {
+            ui.label(
+                RichText::new(format!("> {}", label))
+                    .strong()
+                    .color(Color32::from_rgb(120, 220, 255)),
+            );
+        } else if ui.add_enabled(!busy, egui::Button::new(label)).clicked() {
+            *action = Some(TimelineAction::Jump(branch.id));
+        }
+    });
+
+    for child in branches.children(branch.id) {
+        render_branch(ui, branches, child, depth + 1, busy, action);
+    }
+}
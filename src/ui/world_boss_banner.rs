@@ -0,0 +1,53 @@
+//! World boss banner: the countdown to an announced boss, then its health
+//! and phase while it's up, just below the compass
+
+use egui::{Align2, Color32, FontId, RichText, Vec2};
+use glam::Vec3;
+use infinite_game::WorldBossTracker;
+
+/// Width of the boss health bar in points
+const BAR_WIDTH: f32 = 360.0;
+
+/// Draw the banner for the announced or active world boss, if there is one.
+/// `now` is the play time.
+pub fn draw_world_boss_banner(ctx: &egui::Context, bosses: &WorldBossTracker, now: f64, player_pos: Vec3) {
+    if bosses.announced().is_none() && bosses.active().is_none() {
+        return;
+    }
+    egui::Area::new(egui::Id::new("world_boss_banner"))
+        .anchor(Align2::CENTER_TOP, [0.0, 46.0])
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::new()
+                .fill(Color32::from_rgba_unmultiplied(60, 10, 10, 210))
+                .corner_radius(6.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    if let Some(boss) = bosses.active() {
+                        ui.label(
+                            RichText::new(format!("{} - {}", boss.kind.name(), boss.phase().name))
+                                .font(FontId::proportional(15.0))
+                                .color(Color32::from_rgb(255, 200, 120)),
+                        );
+                        let (rect, _) = ui.allocate_exact_size(Vec2::new(BAR_WIDTH, 8.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 3.0, Color32::from_rgb(40, 20, 20));
+                        let fill = egui::Rect::from_min_size(rect.min, Vec2::new(BAR_WIDTH * boss.hp_fraction().clamp(0.0, 1.0), 8.0));
+                        ui.painter().rect_filled(fill, 3.0, Color32::from_rgb(200, 40, 40));
+                    } else if let Some(announced) = bosses.announced() {
+                        let left = announced.time_left(now).ceil() as u32;
+                        let distance = announced.position.with_y(0.0).distance(player_pos.with_y(0.0));
+                        ui.label(
+                            RichText::new(format!(
+                                "The {} rises in {}:{:02} - {:.0} m away",
+                                announced.kind.name(),
+                                left / 60,
+                                left % 60,
+                                distance,
+                            ))
+                            .font(FontId::proportional(15.0))
+                            .color(Color32::from_rgb(255, 200, 120)),
+                        );
+                    }
+                });
+        });
+}